@@ -2086,7 +2086,7 @@ impl DynamicPdfiumBindings {
             #[cfg(feature = "pdfium_enable_xfa")]
             extern_FPDF_GetXFAPacketName: *(Self::bind(&library, "FPDF_GetXFAPacketName")?),
             #[cfg(feature = "pdfium_enable_xfa")]
-            extern_FPDF_GetXFAPacketContent: *(Self::bind(&library, "FPDF_GetXFAPacketContet")?),
+            extern_FPDF_GetXFAPacketContent: *(Self::bind(&library, "FPDF_GetXFAPacketContent")?),
             #[cfg(feature = "pdfium_enable_v8")]
             extern_FPDF_GetRecommendedV8Flags: *(Self::bind(
                 &library,
@@ -88,6 +88,7 @@ pub enum PdfiumError {
     NoCharsInPageObject,
     NoCharsInAnnotation,
     NoCharsInRect,
+    NoCharsInRange,
     ImageObjectFilterIndexOutOfBounds,
     ImageObjectFilterIndexInBoundsButFilterUndefined,
     UnknownPdfColorSpace,
@@ -139,6 +140,166 @@ pub enum PdfiumError {
     /// The setting cannot be returned because this `PdfPageGroupObject` is empty.
     EmptyPageObjectGroup,
 
+    /// An annotation layer can only be rendered into a bitmap using the `PdfBitmapFormat::BGRA`
+    /// pixel format, since all pixels outside the annotation layer must be made transparent.
+    AnnotationLayerRequiresBitmapWithAlphaChannel,
+
+    /// A page can only be rendered onto a transparent background in a bitmap using the
+    /// `PdfBitmapFormat::BGRA` pixel format, since transparency requires an alpha channel.
+    TransparentBackgroundRequiresBitmapWithAlphaChannel,
+
+    /// The bitmap required to render a page would exceed the memory budget set by
+    /// `PdfRenderConfig::set_maximum_bitmap_bytes()`, and automatic downscaling was disabled.
+    RenderBudgetExceeded,
+
+    /// A page cannot be rendered because the pixel dimensions configured in the
+    /// `PdfRenderConfig` give the rendered page a width or height of zero.
+    ZeroSizedRenderTarget,
+
+    /// Rendering was cancelled by the callback registered using
+    /// `PdfRenderConfig::set_progress_callback()`.
+    RenderCancelled,
+
+    /// The bytes passed to `PdfTextIndex::from_bytes()` are not a valid encoding
+    /// of a `PdfTextIndex`.
+    InvalidTextIndexData,
+
+    /// The worker thread owning a `PdfDocumentAsync` stopped before completing
+    /// a requested operation.
+    AsyncWorkerUnavailable,
+
+    /// The given `PdfBitmapRect` does not lie entirely within the bounds of the `PdfBitmap`.
+    BitmapRectOutOfBounds,
+
+    /// Pixels can only be copied between two `PdfBitmap` objects that use the same pixel format
+    /// and the same byte order.
+    BitmapFormatMismatch,
+
+    /// The `PdfAction` has no file path; only actions that launch an application or open
+    /// a remote document have file paths.
+    NoFilePathForAction,
+
+    /// An annotation builder was asked to create an annotation without bounds, and the
+    /// bounds of that kind of annotation cannot be derived from its other properties.
+    NoBoundsForAnnotation,
+
+    /// A polygon annotation was given fewer than three vertices, or a polyline annotation
+    /// fewer than two.
+    NotEnoughVerticesForAnnotation,
+
+    /// The string passed to `PdfDocument::import_annotations_xfdf()` is not a well-formed
+    /// XFDF document, or an annotation in it is missing a required attribute.
+    InvalidXfdfData,
+
+    /// A file attachment annotation does not embed a file, or an annotation builder was asked
+    /// to create a file attachment annotation without a file.
+    NoAttachmentForAnnotation,
+
+    /// The annotation type is not supported by the requested operation; for example,
+    /// `PdfPageAnnotations::create_markup_over_chars()` only supports text markup
+    /// annotation types.
+    UnsupportedPdfAnnotationType,
+
+    /// A form field value does not match the type of the field, or is not one of the values
+    /// the field accepts.
+    InvalidFormFieldValue,
+
+    /// No field with the given fully qualified name exists in the form.
+    FormFieldNotFound,
+
+    /// The bytes passed to `PdfForm::import_fdf()` are not a well-formed FDF document.
+    InvalidFdfData,
+
+    /// The string passed to `PdfForm::from_json()` is not a JSON object of form field values.
+    InvalidJsonData,
+
+    /// A form field builder was asked to create a field without a name or bounds, with a name
+    /// that is already used by another field, or with settings that contradict one another.
+    InvalidFormFieldDefinition,
+
+    /// New form fields cannot be added to the document, nor existing fields edited, because
+    /// the document is encrypted or Pdfium saved it in a form that could not be updated.
+    FormFieldCreationUnsupported,
+
+    /// The document cannot be updated because it is encrypted or Pdfium saved it in a form
+    /// that could not be updated; for example, `PdfDocument::remove_all_javascript()`
+    /// cannot remove scripts from an encrypted document.
+    DocumentUpdateUnsupported,
+
+    /// The contents of a digital signature are not a well-formed PKCS#7 `signedData`
+    /// structure.
+    InvalidSignatureContents,
+
+    /// A digital signature does not record a message digest of the signed content of the
+    /// document, or computes it using an unrecognized digest algorithm, so the integrity of
+    /// the signed content cannot be checked.
+    SignatureDigestUnavailable,
+
+    /// The bytes of a document were not retained when it was loaded, so the content covered
+    /// by its signatures cannot be checked unless the complete file is given; see
+    /// `PdfSignature::verify_document_integrity()`.
+    SignedFileUnavailable,
+
+    /// A signature is longer than the space reserved for it in a prepared document; see
+    /// `PdfSignatureFieldBuilder::contents_capacity()`.
+    SignatureContentsTooLarge,
+
+    /// The index of an XFA packet was out of range of the packets in a document's XFA form.
+    XfaPacketIndexOutOfBounds,
+
+    /// The document does not contain an XFA form stored as separate packets, so its
+    /// `datasets` packet cannot be replaced.
+    XfaPacketsUnavailable,
+
+    /// The document's XMP metadata stream is compressed or encrypted, is not valid UTF-8,
+    /// or could not be located, so its packet cannot be read.
+    XmpMetadataUnreadable,
+
+    /// The byte range passed to `PdfDocumentLoader::add_data()` extends past the end of the
+    /// document's content.
+    LoaderDataOutOfBounds,
+
+    /// A `PdfDocumentLoader` has not yet received the data needed to open the document,
+    /// or, when converting it into a `PdfDocument`, has not yet received all the document's data.
+    LoaderDocumentNotReady,
+
+    /// The requested `PdfDocumentLoader` function can only be used after the document has been
+    /// opened using `PdfDocumentLoader::open_document()`.
+    LoaderDocumentNotOpened,
+
+    /// The description or MIME type of an attachment cannot be read because the document
+    /// is encrypted or Pdfium saved it in a form that could not be parsed.
+    AttachmentDetailsUnreadable,
+
+    /// The given `PdfBookmarkId` or `PdfBookmark` does not identify a bookmark in the
+    /// edited outline of a `PdfBookmarks` collection, for instance because it was removed.
+    BookmarkNotFound,
+
+    /// A bookmark cannot be inserted or moved to the requested position, either because the
+    /// index is past the end of the parent's children or because a bookmark cannot be moved
+    /// beneath itself or one of its own descendants.
+    BookmarkPositionInvalid,
+
+    /// An imposition layout has no rows or columns, or its margins and gutter leave no room
+    /// on the sheet for the imposed pages.
+    InvalidImpositionLayout,
+
+    /// The page order passed to `PdfPages::reorder()` does not list every page in the
+    /// document exactly once.
+    InvalidPageOrder,
+
+    /// The document's page labels cannot be read because the document is encrypted or
+    /// Pdfium saved it in a form that could not be parsed.
+    PageLabelsUnreadable,
+
+    /// The page label ranges passed to `PdfDocument::set_page_label_ranges()` do not start
+    /// at the first page, or are not in strictly increasing order of start index.
+    InvalidPageLabelRanges,
+
+    /// The pages passed to `PdfPages::delete_range()` cannot be deleted because a `PdfPage`
+    /// referring to one of them is still open.
+    PageInUse,
+
     /// A call to a internal Pdfium `FPDF_*` function returned a value indicating failure.
     ///
     /// For Pdfium functions that return enumerations, this means the function returned
@@ -87,22 +87,45 @@ pub mod prelude {
         pdf::color::*,
         pdf::color_space::*,
         pdf::destination::*,
+        pdf::document::annotation_summary::*,
+        pdf::document::attachment::classification::*,
         pdf::document::attachment::*,
         pdf::document::attachments::*,
         pdf::document::bookmark::*,
         pdf::document::bookmarks::*,
+        pdf::document::compare::*,
+        pdf::document::extracted_link::*,
+        pdf::document::font_coverage::*,
         pdf::document::fonts::*,
+        pdf::document::form::builder::*,
+        pdf::document::form::diff::*,
+        pdf::document::form::events::*,
+        pdf::document::form::fields::*,
+        pdf::document::form::scripts::*,
         pdf::document::form::*,
+        pdf::document::imposition::*,
+        pdf::document::javascript::*,
+        pdf::document::link_hits::*,
+        pdf::document::link_rewrite::*,
         pdf::document::metadata::*,
+        pdf::document::outline::*,
+        pdf::document::page::additional_actions::*,
         pdf::document::page::annotation::attachment_points::*,
+        pdf::document::page::annotation::border::*,
+        pdf::document::page::annotation::builders::*,
         pdf::document::page::annotation::circle::*,
+        pdf::document::page::annotation::file_attachment::*,
         pdf::document::page::annotation::free_text::*,
         pdf::document::page::annotation::highlight::*,
         pdf::document::page::annotation::ink::*,
+        pdf::document::page::annotation::line_ending::*,
         pdf::document::page::annotation::link::*,
+        pdf::document::page::annotation::measure::*,
+        pdf::document::page::annotation::multimedia::*,
         pdf::document::page::annotation::objects::*,
         pdf::document::page::annotation::popup::*,
         pdf::document::page::annotation::redacted::*,
+        pdf::document::page::annotation::review::*,
         pdf::document::page::annotation::square::*,
         pdf::document::page::annotation::squiggly::*,
         pdf::document::page::annotation::stamp::*,
@@ -117,6 +140,8 @@ pub mod prelude {
         },
         pdf::document::page::annotations::*,
         pdf::document::page::boundaries::*,
+        pdf::document::page::coordinate_mapper::*,
+        pdf::document::page::field::barcode::*,
         pdf::document::page::field::button::*,
         pdf::document::page::field::checkbox::*,
         pdf::document::page::field::combo::*,
@@ -138,26 +163,44 @@ pub mod prelude {
         pdf::document::page::object::x_object_form::*,
         pdf::document::page::object::{
             PdfPageObject, PdfPageObjectBlendMode, PdfPageObjectCommon, PdfPageObjectLineCap,
-            PdfPageObjectLineJoin, PdfPageObjectType,
+            PdfPageObjectLineJoin, PdfPageObjectStructureTag, PdfPageObjectType,
         },
         pdf::document::page::objects::common::*,
         pdf::document::page::objects::*,
+        pdf::document::page::page_number::*,
         pdf::document::page::render_config::*,
+        pdf::document::page::render_stats::*,
         pdf::document::page::size::*,
+        pdf::document::page::tagged_text::*,
         pdf::document::page::text::char::*,
         pdf::document::page::text::chars::*,
+        pdf::document::page::text::extraction::*,
+        pdf::document::page::text::index_map::*,
+        pdf::document::page::text::layout::*,
+        pdf::document::page::text::region::*,
         pdf::document::page::text::search::*,
         pdf::document::page::text::segment::*,
         pdf::document::page::text::segments::*,
+        pdf::document::page::text::selection::*,
+        pdf::document::page::text::style_runs::*,
         pdf::document::page::text::*,
         pdf::document::page::{
             PdfBitmapRotation, PdfPage, PdfPageContentRegenerationStrategy, PdfPageOrientation,
             PdfPageRenderRotation,
         },
+        pdf::document::page_labels::*,
         pdf::document::pages::*,
         pdf::document::permissions::*,
+        pdf::document::render_cache::*,
+        pdf::document::render_iterator::*,
+        pdf::document::render_sequence::*,
+        pdf::document::search::*,
         pdf::document::signature::*,
         pdf::document::signatures::*,
+        pdf::document::signing::*,
+        pdf::document::text_flow::*,
+        pdf::document::text_index::*,
+        pdf::document::xmp::*,
         pdf::document::{PdfDocument, PdfDocumentVersion},
         pdf::font::glyph::*,
         pdf::font::glyphs::*,
@@ -171,6 +214,35 @@ pub mod prelude {
         pdf::rect::*,
         pdfium::*,
     };
+
+    #[cfg(feature = "pdfium_use_win32")]
+    pub use crate::pdf::document::print_job::*;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use crate::pdf::document::append_only_writer::*;
+
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use crate::pdf::document::loader::*;
+
+    #[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+    pub use crate::pdf::document::asynchronous::*;
+
+    #[cfg(feature = "serde")]
+    pub use crate::pdf::document::page::annotation::dto::*;
+
+    #[cfg(feature = "pdfium_enable_xfa")]
+    pub use crate::pdf::document::xfa::*;
+
+    #[cfg(feature = "pkcs7")]
+    pub use crate::pdf::document::signature::pkcs7::*;
+
+    #[cfg(feature = "tables")]
+    pub use crate::pdf::tables::*;
+}
+
+/// Functions for comparing the content of two `PdfDocument` objects.
+pub mod compare {
+    pub use crate::pdf::document::compare::text_diff;
 }
 
 #[cfg(test)]
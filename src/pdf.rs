@@ -12,4 +12,8 @@ pub mod path;
 pub mod points;
 pub mod quad_points;
 pub mod rect;
+
+#[cfg(feature = "tables")]
+pub mod tables;
+
 mod transform; // Keep private so that internal macros are not exposed.
@@ -3,6 +3,7 @@
 
 use crate::bindgen::FPDF_ACTION;
 use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
 use crate::pdf::action::private::internal::PdfActionPrivate;
 
 pub struct PdfActionLaunch<'a> {
@@ -18,6 +19,14 @@ impl<'a> PdfActionLaunch<'a> {
     ) -> Self {
         PdfActionLaunch { handle, bindings }
     }
+
+    /// Returns the file path of the application or document launched by this [PdfActionLaunch], if any.
+    /// The path is returned exactly as stored in the document; it may be relative to the
+    /// location of the document.
+    #[inline]
+    pub fn file_path(&self) -> Result<String, PdfiumError> {
+        self.file_path_impl()
+    }
 }
 
 impl<'a> PdfActionPrivate<'a> for PdfActionLaunch<'a> {
@@ -9,15 +9,60 @@ pub(crate) mod internal {
 
     use crate::bindgen::FPDF_ACTION;
     use crate::bindings::PdfiumLibraryBindings;
+    use crate::error::PdfiumError;
     use crate::pdf::action::PdfActionCommon;
+    use crate::utils::mem::create_byte_buffer;
+    use std::ffi::{c_void, CString};
+    use std::os::raw::c_ulong;
 
     /// Internal crate-specific functionality common to all [PdfAction] actions.
     pub(crate) trait PdfActionPrivate<'a>: PdfActionCommon<'a> {
         /// Returns the internal `FPDF_ACTION` handle for this [PdfAction].
-        #[allow(dead_code)] // TODO: AJRC - 13/6/24 - remove once handle() function is in use.
         fn handle(&self) -> &FPDF_ACTION;
 
         /// Returns the [PdfiumLibraryBindings] used by this [PdfAction].
         fn bindings(&self) -> &dyn PdfiumLibraryBindings;
+
+        /// Returns the file path associated with this [PdfAction], if any. Only actions of type
+        /// `PdfActionType::Launch` and `PdfActionType::GoToDestinationInRemoteDocument`
+        /// have file paths.
+        fn file_path_impl(&self) -> Result<String, PdfiumError> {
+            // Retrieving the file path from Pdfium is a two-step operation. First, we call
+            // FPDFAction_GetFilePath() with a null buffer; this will retrieve the length of
+            // the path in bytes, including a trailing null. If the length is zero, then there
+            // is no path associated with this action.
+
+            // If the length is non-zero, then we reserve a byte buffer of the given
+            // length and call FPDFAction_GetFilePath() again with a pointer to the buffer;
+            // this will write the path to the buffer in UTF-8 format.
+
+            let buffer_length =
+                self.bindings()
+                    .FPDFAction_GetFilePath(*self.handle(), std::ptr::null_mut(), 0);
+
+            if buffer_length == 0 {
+                // There is no file path for this action.
+
+                return Err(PdfiumError::NoFilePathForAction);
+            }
+
+            let mut buffer = create_byte_buffer(buffer_length as usize);
+
+            let result = self.bindings().FPDFAction_GetFilePath(
+                *self.handle(),
+                buffer.as_mut_ptr() as *mut c_void,
+                buffer_length as c_ulong,
+            );
+
+            assert_eq!(result, buffer_length);
+
+            if let Ok(result) = CString::from_vec_with_nul(buffer) {
+                result
+                    .into_string()
+                    .map_err(PdfiumError::CStringConversionError)
+            } else {
+                Err(PdfiumError::NoFilePathForAction)
+            }
+        }
     }
 }
@@ -3,6 +3,7 @@
 
 use crate::bindgen::FPDF_ACTION;
 use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
 use crate::pdf::action::private::internal::PdfActionPrivate;
 
 pub struct PdfActionRemoteDestination<'a> {
@@ -18,6 +19,14 @@ impl<'a> PdfActionRemoteDestination<'a> {
     ) -> Self {
         PdfActionRemoteDestination { handle, bindings }
     }
+
+    /// Returns the file path of the remote document opened by this [PdfActionRemoteDestination], if any.
+    /// The path is returned exactly as stored in the document; it may be relative to the
+    /// location of the document.
+    #[inline]
+    pub fn file_path(&self) -> Result<String, PdfiumError> {
+        self.file_path_impl()
+    }
 }
 
 impl<'a> PdfActionPrivate<'a> for PdfActionRemoteDestination<'a> {
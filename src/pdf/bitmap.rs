@@ -6,12 +6,24 @@ use crate::bindgen::{
 };
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::color::PdfColor;
 use crate::pdf::document::page::render_config::PdfRenderSettings;
+use crate::pdf::document::page::render_stats::PdfRenderStats;
+use crate::utils::hash::Fnv1a64;
 use crate::utils::pixels::{aligned_bgr_to_rgba, aligned_rgb_to_rgba, bgra_to_rgba};
 use std::os::raw::c_int;
 
 #[cfg(feature = "image")]
-use image::{DynamicImage, GrayImage, RgbaImage};
+use image::{DynamicImage, GrayImage, ImageFormat, RgbaImage};
+
+#[cfg(feature = "image")]
+use image::codecs::jpeg::{JpegEncoder, PixelDensity, PixelDensityUnit};
+
+#[cfg(feature = "image")]
+use crate::utils::hash::crc32;
+
+#[cfg(feature = "image")]
+use std::io::Cursor;
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::os::raw::c_void;
@@ -99,10 +111,87 @@ impl Default for PdfBitmapFormat {
     }
 }
 
+/// The order of the rows of pixel data copied out of a [PdfBitmap].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfBitmapRowOrder {
+    /// The first row of pixel data is the top row of the image. This is the order used by
+    /// Pdfium and by most image encoders. This is the default.
+    TopDown,
+
+    /// The first row of pixel data is the bottom row of the image. This is the order expected
+    /// by Windows device-independent bitmaps with a positive height and by OpenGL textures.
+    BottomUp,
+}
+
+impl Default for PdfBitmapRowOrder {
+    #[inline]
+    fn default() -> Self {
+        PdfBitmapRowOrder::TopDown
+    }
+}
+
+/// The padding applied to the end of each row of pixel data copied out of a [PdfBitmap].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfBitmapStride {
+    /// Rows are tightly packed, with no padding. The stride is the width of the image
+    /// multiplied by the number of bytes per pixel. This is the default.
+    Tight,
+
+    /// Each row is padded with zero bytes to a multiple of four bytes, as expected by Windows
+    /// device-independent bitmaps and by OpenGL's default unpack alignment.
+    FourByteAligned,
+}
+
+impl Default for PdfBitmapStride {
+    #[inline]
+    fn default() -> Self {
+        PdfBitmapStride::Tight
+    }
+}
+
+/// A rectangular region of a [PdfBitmap], measured in [Pixels] from the top left corner
+/// of the bitmap.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PdfBitmapRect {
+    pub left: Pixels,
+    pub top: Pixels,
+    pub width: Pixels,
+    pub height: Pixels,
+}
+
+impl PdfBitmapRect {
+    /// Creates a new [PdfBitmapRect] with the given top left corner and dimensions.
+    #[inline]
+    pub const fn new(left: Pixels, top: Pixels, width: Pixels, height: Pixels) -> Self {
+        PdfBitmapRect {
+            left,
+            top,
+            width,
+            height,
+        }
+    }
+
+    /// Returns the part of this [PdfBitmapRect] that lies within a bitmap of the given
+    /// dimensions, or `None` if no part of this [PdfBitmapRect] lies within the bitmap.
+    pub(crate) fn clipped_to(&self, width: Pixels, height: Pixels) -> Option<PdfBitmapRect> {
+        let left = self.left.max(0);
+        let top = self.top.max(0);
+        let right = self.left.saturating_add(self.width).min(width);
+        let bottom = self.top.saturating_add(self.height).min(height);
+
+        if right > left && bottom > top {
+            Some(PdfBitmapRect::new(left, top, right - left, bottom - top))
+        } else {
+            None
+        }
+    }
+}
+
 /// A bitmap image with a specific width and height.
 pub struct PdfBitmap<'a> {
     handle: FPDF_BITMAP,
     was_byte_order_reversed_during_rendering: bool,
+    render_stats: Option<PdfRenderStats>,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
 
@@ -115,6 +204,7 @@ impl<'a> PdfBitmap<'a> {
         PdfBitmap {
             handle,
             was_byte_order_reversed_during_rendering: false,
+            render_stats: None,
             bindings,
         }
     }
@@ -194,6 +284,31 @@ impl<'a> PdfBitmap<'a> {
         self.was_byte_order_reversed_during_rendering = settings.is_reversed_byte_order_flag_set
     }
 
+    /// Returns a breakdown of the work done while most recently rendering a page into this
+    /// [PdfBitmap], if the page was rendered with `PdfRenderConfig::collect_render_stats(true)`.
+    /// Returns `None` if render statistics were not collected.
+    #[inline]
+    pub fn render_stats(&self) -> Option<&PdfRenderStats> {
+        self.render_stats.as_ref()
+    }
+
+    #[inline]
+    pub(crate) fn set_render_stats(&mut self, stats: Option<PdfRenderStats>) {
+        self.render_stats = stats;
+    }
+
+    /// Adds the size of the buffer backing this [PdfBitmap] to the bytes allocated during
+    /// rendering, if render statistics were collected. Used when the bitmap was newly
+    /// created in order to be rendered into.
+    #[inline]
+    pub(crate) fn record_buffer_allocation_in_render_stats(&mut self) {
+        let bytes = self.as_raw_bytes().len();
+
+        if let Some(stats) = self.render_stats.as_mut() {
+            stats.bytes_allocated += bytes;
+        }
+    }
+
     /// Returns the [PdfiumLibraryBindings] used by this [PdfBitmap].
     #[inline]
     pub fn bindings(&self) -> &dyn PdfiumLibraryBindings {
@@ -283,6 +398,46 @@ impl<'a> PdfBitmap<'a> {
         }
     }
 
+    /// Returns an owned copy of the bitmap buffer backing this [PdfBitmap], in its original
+    /// pixel format, with the rows of pixel data arranged in the given order and padded to
+    /// the given stride.
+    ///
+    /// Like [PdfBitmap::as_raw_bytes()], this function does not attempt any color channel
+    /// normalization. Pdfium itself always returns rows in top-down order, padded to a multiple
+    /// of four bytes.
+    pub fn copy_raw_bytes(&self, row_order: PdfBitmapRowOrder, stride: PdfBitmapStride) -> Vec<u8> {
+        let row_length = self.width() as usize * bytes_per_pixel(self.format().unwrap_or_default());
+
+        repack_rows(
+            self.as_raw_bytes(),
+            self.height() as usize,
+            row_length,
+            row_order,
+            stride,
+        )
+    }
+
+    /// Returns an owned copy of the bitmap buffer backing this [PdfBitmap], normalizing all
+    /// color channels as for [PdfBitmap::as_rgba_bytes()], with the rows of pixel data arranged
+    /// in the given order. Each row of four-channel pixel data is always a multiple of four
+    /// bytes long, so no stride padding is needed.
+    pub fn copy_rgba_bytes(&self, row_order: PdfBitmapRowOrder) -> Vec<u8> {
+        let bytes = self.as_rgba_bytes();
+
+        let row_length = match self.format().unwrap_or_default() {
+            PdfBitmapFormat::Gray => self.width() as usize,
+            _ => self.width() as usize * 4,
+        };
+
+        repack_rows(
+            bytes.as_slice(),
+            self.height() as usize,
+            row_length,
+            row_order,
+            PdfBitmapStride::Tight,
+        )
+    }
+
     /// Returns a new `Image::DynamicImage` created from the bitmap buffer backing this [PdfBitmap].
     ///
     /// This function is only available when this crate's `image` feature is enabled.
@@ -368,6 +523,400 @@ impl<'a> PdfBitmap<'a> {
         )
     }
 
+    /// Returns a deterministic hash of the pixel content of this [PdfBitmap], suitable for
+    /// detecting visual regressions by comparing renderings across runs.
+    ///
+    /// The hash takes into account the bitmap's dimensions, pixel format, and pixel data.
+    /// Any alignment padding at the end of each scanline, and the unused fourth byte of each
+    /// pixel in [PdfBitmapFormat::BGRx] bitmaps, are excluded, since their values are undefined.
+    /// The hash is stable across program runs, platforms, and `pdfium-render` releases, although
+    /// it may change if a different Pdfium release renders the same content differently.
+    pub fn content_hash(&self) -> u64 {
+        let format = self.format().unwrap_or_default();
+
+        let height = self.height() as usize;
+
+        let bytes = self.as_raw_bytes();
+
+        let stride = bytes.len().checked_div(height).unwrap_or(0);
+
+        hash_bitmap_content(
+            bytes,
+            self.width() as usize,
+            height,
+            stride,
+            format,
+            self.was_byte_order_reversed_during_rendering,
+        )
+    }
+
+    /// Composites the pixels in this [PdfBitmap] over the given background color, in place,
+    /// using standard "source over" alpha blending. This is typically used to place a page
+    /// rendered with `PdfRenderConfig::render_transparent_background(true)` over a custom
+    /// background color. If the given color is fully opaque, every pixel in this bitmap
+    /// will be fully opaque afterwards.
+    ///
+    /// Bitmaps in pixel formats other than [PdfBitmapFormat::BGRA] have no alpha channel,
+    /// so they are already opaque and are left unchanged.
+    pub fn composite_over(&mut self, color: PdfColor) -> Result<(), PdfiumError> {
+        if self.format()? != PdfBitmapFormat::BGRA {
+            return Ok(());
+        }
+
+        // The red and blue channels are swapped if the byte order was reversed during rendering.
+
+        let background = if self.was_byte_order_reversed_during_rendering {
+            [color.red(), color.green(), color.blue(), color.alpha()]
+        } else {
+            [color.blue(), color.green(), color.red(), color.alpha()]
+        };
+
+        let mut bytes = self.as_raw_bytes().to_vec();
+
+        let row_length = self.width() as usize * 4;
+
+        let stride = bytes.len().checked_div(self.height() as usize).unwrap_or(0);
+
+        for row in bytes.chunks_mut(stride.max(1)) {
+            let row_length = row_length.min(row.len());
+
+            for pixel in row[..row_length].chunks_exact_mut(4) {
+                composite_pixel_over(pixel, &background);
+            }
+        }
+
+        self.set_raw_bytes(bytes.as_slice())
+    }
+
+    /// Converts every pixel in this [PdfBitmap] to either pure black or pure white, in place,
+    /// by comparing the luminance of the pixel against the given threshold. Pixels darker than
+    /// the threshold become black; all other pixels become white. The pixel format of the bitmap
+    /// and the alpha channel of each pixel, if any, are unchanged.
+    ///
+    /// This is useful when preparing rendered pages for one-bit output devices such as fax
+    /// machines, thermal receipt printers, and label printers. A threshold of 128 is a
+    /// reasonable starting point.
+    pub fn apply_threshold(&mut self, threshold: u8) -> Result<(), PdfiumError> {
+        let bytes_per_pixel = bytes_per_pixel(self.format()?);
+
+        let mut bytes = self.as_raw_bytes().to_vec();
+
+        let row_length = self.width() as usize * bytes_per_pixel;
+
+        let stride = bytes.len().checked_div(self.height() as usize).unwrap_or(0);
+
+        for row in bytes.chunks_mut(stride.max(1)) {
+            let row_length = row_length.min(row.len());
+
+            for pixel in row[..row_length].chunks_exact_mut(bytes_per_pixel) {
+                threshold_pixel(
+                    pixel,
+                    threshold,
+                    self.was_byte_order_reversed_during_rendering,
+                );
+            }
+        }
+
+        self.set_raw_bytes(bytes.as_slice())
+    }
+
+    /// Converts every pixel in this [PdfBitmap] to either pure black or pure white, in place,
+    /// using Floyd-Steinberg error diffusion. Each pixel is compared against the given
+    /// threshold as for [PdfBitmap::apply_threshold()], but the difference between the pixel's
+    /// luminance and the black or white value it is set to is carried over to its unprocessed
+    /// neighbours, so that areas of gray are reproduced as patterns of black and white pixels
+    /// of the same average brightness rather than being flattened to a single value.
+    /// The pixel format of the bitmap and the alpha channel of each pixel, if any, are unchanged.
+    ///
+    /// This is useful when preparing rendered pages containing photographs, shading, or
+    /// other grayscale content for one-bit output devices.
+    pub fn apply_dithering(&mut self, threshold: u8) -> Result<(), PdfiumError> {
+        let bytes_per_pixel = bytes_per_pixel(self.format()?);
+
+        let width = self.width() as usize;
+
+        let mut bytes = self.as_raw_bytes().to_vec();
+
+        let row_length = width * bytes_per_pixel;
+
+        let stride = bytes.len().checked_div(self.height() as usize).unwrap_or(0);
+
+        let pixels = |row: &[u8]| {
+            row[..row_length.min(row.len())]
+                .chunks_exact(bytes_per_pixel)
+                .map(|pixel| pixel_luminance(pixel, self.was_byte_order_reversed_during_rendering))
+                .collect::<Vec<_>>()
+        };
+
+        let mut luminances = bytes
+            .chunks(stride.max(1))
+            .flat_map(pixels)
+            .map(|luminance| luminance as i32)
+            .collect::<Vec<_>>();
+
+        dither_luminances(&mut luminances, width, threshold);
+
+        let mut values = luminances.into_iter();
+
+        for row in bytes.chunks_mut(stride.max(1)) {
+            let row_length = row_length.min(row.len());
+
+            for (pixel, value) in row[..row_length]
+                .chunks_exact_mut(bytes_per_pixel)
+                .zip(&mut values)
+            {
+                for channel in pixel.iter_mut().take(3) {
+                    *channel = value as u8;
+                }
+            }
+        }
+
+        self.set_raw_bytes(bytes.as_slice())
+    }
+
+    /// Swaps the red and blue subpixel contributions of the text anti-aliasing in this
+    /// [PdfBitmap], which must hold a page rendered with Pdfium's `FPDF_LCD_TEXT` render flag,
+    /// relative to the given bitmap, which must hold the same page rendered identically but
+    /// without that flag. The result is suitable for displays with blue, green, red subpixels.
+    pub(crate) fn reverse_subpixel_order(
+        &mut self,
+        grayscale: &PdfBitmap,
+    ) -> Result<(), PdfiumError> {
+        let bytes_per_pixel = bytes_per_pixel(self.format()?);
+
+        if bytes_per_pixel < 3 {
+            return Ok(());
+        }
+
+        let mut bytes = self.as_raw_bytes().to_vec();
+
+        let grayscale_bytes = grayscale.as_raw_bytes();
+
+        if grayscale_bytes.len() != bytes.len() {
+            return Ok(());
+        }
+
+        let row_length = self.width() as usize * bytes_per_pixel;
+
+        let stride = bytes.len().checked_div(self.height() as usize).unwrap_or(0);
+
+        for (row, grayscale_row) in bytes
+            .chunks_mut(stride.max(1))
+            .zip(grayscale_bytes.chunks(stride.max(1)))
+        {
+            let row_length = row_length.min(row.len());
+
+            for (pixel, grayscale_pixel) in row[..row_length]
+                .chunks_exact_mut(bytes_per_pixel)
+                .zip(grayscale_row.chunks_exact(bytes_per_pixel))
+            {
+                reverse_subpixel_pixel(pixel, grayscale_pixel);
+            }
+        }
+
+        self.set_raw_bytes(bytes.as_slice())
+    }
+
+    /// Fills the given region of this [PdfBitmap] with the given color. Any part of the region
+    /// that lies outside the bounds of this [PdfBitmap] is ignored.
+    ///
+    /// Unlike a page rendered onto a background color, the filled pixels are not blended with
+    /// the existing content of the bitmap; the color, including its alpha channel if this
+    /// bitmap uses the [PdfBitmapFormat::BGRA] pixel format, replaces them.
+    pub fn fill_rect(&mut self, rect: PdfBitmapRect, color: PdfColor) -> Result<(), PdfiumError> {
+        let format = self.format()?;
+
+        let rect = match rect.clipped_to(self.width(), self.height()) {
+            Some(rect) => rect,
+            None => return Ok(()),
+        };
+
+        let pixel = self.pixel_from_color(color, format);
+
+        let mut bytes = self.as_raw_bytes().to_vec();
+
+        let stride = bytes.len().checked_div(self.height() as usize).unwrap_or(0);
+
+        for row in bytes
+            .chunks_mut(stride.max(1))
+            .skip(rect.top as usize)
+            .take(rect.height as usize)
+        {
+            let start = rect.left as usize * pixel.len();
+
+            let end = start + rect.width as usize * pixel.len();
+
+            for target in row[start..end].chunks_exact_mut(pixel.len()) {
+                target.copy_from_slice(pixel.as_slice());
+            }
+        }
+
+        self.set_raw_bytes(bytes.as_slice())
+    }
+
+    /// Fills every pixel in this [PdfBitmap] for which the given predicate, called with the
+    /// pixel's column and row, returns `true` with the given color. Returns `true` if any
+    /// pixel was filled.
+    pub(crate) fn fill_pixels_where(
+        &mut self,
+        color: PdfColor,
+        predicate: impl Fn(Pixels, Pixels) -> bool,
+    ) -> Result<bool, PdfiumError> {
+        let format = self.format()?;
+
+        let pixel = self.pixel_from_color(color, format);
+
+        let mut bytes = self.as_raw_bytes().to_vec();
+
+        let row_length = self.width() as usize * pixel.len();
+
+        let stride = bytes.len().checked_div(self.height() as usize).unwrap_or(0);
+
+        let mut is_filled = false;
+
+        for (y, row) in bytes.chunks_mut(stride.max(1)).enumerate() {
+            let row_length = row_length.min(row.len());
+
+            for (x, target) in row[..row_length].chunks_exact_mut(pixel.len()).enumerate() {
+                if predicate(x as Pixels, y as Pixels) {
+                    target.copy_from_slice(pixel.as_slice());
+
+                    is_filled = true;
+                }
+            }
+        }
+
+        if is_filled {
+            self.set_raw_bytes(bytes.as_slice())?;
+        }
+
+        Ok(is_filled)
+    }
+
+    /// Copies the given region of this [PdfBitmap] into a new [PdfBitmap] with the same
+    /// pixel format. The region must lie entirely within the bounds of this [PdfBitmap].
+    pub fn crop(&self, rect: PdfBitmapRect) -> Result<PdfBitmap<'a>, PdfiumError> {
+        if rect.width <= 0
+            || rect.height <= 0
+            || rect.clipped_to(self.width(), self.height()) != Some(rect)
+        {
+            return Err(PdfiumError::BitmapRectOutOfBounds);
+        }
+
+        let format = self.format()?;
+
+        let mut result = PdfBitmap::empty(rect.width, rect.height, format, self.bindings)?;
+
+        result.was_byte_order_reversed_during_rendering =
+            self.was_byte_order_reversed_during_rendering;
+
+        result.blit_from(self, rect, (0, 0))?;
+
+        Ok(result)
+    }
+
+    /// Copies the given region of the given [PdfBitmap] into this [PdfBitmap], placing the
+    /// top left corner of the region at the given pixel position. Any part of the region that
+    /// lies outside the bounds of either bitmap is ignored.
+    ///
+    /// Both bitmaps must use the same pixel format and the same byte order. Pixels are copied
+    /// exactly, without blending; use [PdfBitmap::composite_over()] beforehand if the source
+    /// bitmap contains transparent pixels that should be blended over a background color.
+    pub fn blit_from(
+        &mut self,
+        source: &PdfBitmap,
+        source_rect: PdfBitmapRect,
+        destination: (Pixels, Pixels),
+    ) -> Result<(), PdfiumError> {
+        let format = self.format()?;
+
+        if source.format()? != format
+            || source.was_byte_order_reversed_during_rendering
+                != self.was_byte_order_reversed_during_rendering
+        {
+            return Err(PdfiumError::BitmapFormatMismatch);
+        }
+
+        let (source_rect, destination_rect) = match clip_blit(
+            source_rect,
+            (source.width(), source.height()),
+            destination,
+            (self.width(), self.height()),
+        ) {
+            Some(rects) => rects,
+            None => return Ok(()),
+        };
+
+        let bytes_per_pixel = bytes_per_pixel(format);
+
+        let source_bytes = source.as_raw_bytes();
+
+        let source_stride = source_bytes
+            .len()
+            .checked_div(source.height() as usize)
+            .unwrap_or(0);
+
+        let mut bytes = self.as_raw_bytes().to_vec();
+
+        let stride = bytes.len().checked_div(self.height() as usize).unwrap_or(0);
+
+        let row_length = source_rect.width as usize * bytes_per_pixel;
+
+        for row in 0..source_rect.height as usize {
+            let source_start = (source_rect.top as usize + row) * source_stride
+                + source_rect.left as usize * bytes_per_pixel;
+
+            let start = (destination_rect.top as usize + row) * stride
+                + destination_rect.left as usize * bytes_per_pixel;
+
+            bytes[start..start + row_length]
+                .copy_from_slice(&source_bytes[source_start..source_start + row_length]);
+        }
+
+        self.set_raw_bytes(bytes.as_slice())
+    }
+
+    /// Returns the bytes of a single pixel of the given color in the given pixel format,
+    /// taking the byte order of this [PdfBitmap] into account.
+    fn pixel_from_color(&self, color: PdfColor, format: PdfBitmapFormat) -> Vec<u8> {
+        let (first, last) = if self.was_byte_order_reversed_during_rendering {
+            (color.red(), color.blue())
+        } else {
+            (color.blue(), color.red())
+        };
+
+        #[allow(deprecated)]
+        match format {
+            PdfBitmapFormat::Gray => {
+                // ITU-R BT.601 luma weights, scaled by 1000.
+
+                vec![
+                    ((color.red() as u32 * 299
+                        + color.green() as u32 * 587
+                        + color.blue() as u32 * 114
+                        + 500)
+                        / 1000) as u8,
+                ]
+            }
+            PdfBitmapFormat::BGR => vec![first, color.green(), last],
+            PdfBitmapFormat::BGRx | PdfBitmapFormat::BRGx => {
+                vec![first, color.green(), last, 255]
+            }
+            PdfBitmapFormat::BGRA => vec![first, color.green(), last, color.alpha()],
+        }
+    }
+
+    /// Replaces the bitmap buffer backing this [PdfBitmap] with the given bytes.
+    fn set_raw_bytes(&mut self, bytes: &[u8]) -> Result<(), PdfiumError> {
+        if self.bindings.FPDFBitmap_SetBuffer(self.handle, bytes) {
+            Ok(())
+        } else {
+            Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ))
+        }
+    }
+
     /// Estimates the maximum memory buffer size required for a [PdfBitmap] of the given dimensions.
     ///
     /// Certain platforms, architectures, and operating systems may limit the maximum size of a
@@ -380,6 +929,331 @@ impl<'a> PdfBitmap<'a> {
     }
 }
 
+/// Hashes the given bitmap buffer, one scanline at a time, ignoring any bytes that do not
+/// contribute to the visible content of the bitmap.
+pub(crate) fn hash_bitmap_content(
+    bytes: &[u8],
+    width: usize,
+    height: usize,
+    stride: usize,
+    format: PdfBitmapFormat,
+    was_byte_order_reversed: bool,
+) -> u64 {
+    #[allow(deprecated)]
+    let (bytes_per_pixel, significant_bytes_per_pixel) = match format {
+        PdfBitmapFormat::Gray => (1, 1),
+        PdfBitmapFormat::BGR => (3, 3),
+        PdfBitmapFormat::BGRx | PdfBitmapFormat::BRGx => (4, 3),
+        PdfBitmapFormat::BGRA => (4, 4),
+    };
+
+    let mut hasher = Fnv1a64::new();
+
+    hasher.write(&(width as u64).to_le_bytes());
+    hasher.write(&(height as u64).to_le_bytes());
+    hasher.write(&format.as_pdfium().to_le_bytes());
+    hasher.write(&[was_byte_order_reversed as u8]);
+
+    for row in bytes.chunks(stride.max(1)).take(height) {
+        let row = &row[..(width * bytes_per_pixel).min(row.len())];
+
+        if significant_bytes_per_pixel == bytes_per_pixel {
+            hasher.write(row);
+        } else {
+            for pixel in row.chunks(bytes_per_pixel) {
+                hasher.write(&pixel[..significant_bytes_per_pixel.min(pixel.len())]);
+            }
+        }
+    }
+
+    hasher.finish()
+}
+
+/// Blends the given four-channel pixel over the given four-channel background color, in place.
+/// Both must use the same channel order, with the alpha channel last. Color channels are not
+/// premultiplied by alpha.
+pub(crate) fn composite_pixel_over(pixel: &mut [u8], background: &[u8; 4]) {
+    let source_alpha = pixel[3] as u32;
+
+    if source_alpha == 255 {
+        return;
+    }
+
+    // The contribution of the background to the result, scaled to 0..=255 * 255.
+
+    let background_weight = background[3] as u32 * (255 - source_alpha);
+
+    let result_alpha = source_alpha * 255 + background_weight;
+
+    if result_alpha == 0 {
+        pixel.copy_from_slice(&[0, 0, 0, 0]);
+
+        return;
+    }
+
+    for channel in 0..3 {
+        let blended = pixel[channel] as u32 * source_alpha * 255
+            + background[channel] as u32 * background_weight;
+
+        pixel[channel] = ((blended + result_alpha / 2) / result_alpha) as u8;
+    }
+
+    pixel[3] = ((result_alpha + 127) / 255) as u8;
+}
+
+/// Returns the number of bytes used to store a single pixel in the given pixel format.
+#[allow(deprecated)]
+fn bytes_per_pixel(format: PdfBitmapFormat) -> usize {
+    match format {
+        PdfBitmapFormat::Gray => 1,
+        PdfBitmapFormat::BGR => 3,
+        PdfBitmapFormat::BGRx | PdfBitmapFormat::BRGx | PdfBitmapFormat::BGRA => 4,
+    }
+}
+
+/// Copies the given number of rows of pixel data, each of the given length in bytes, out of
+/// the given buffer, arranging them in the given order and padding them to the given stride.
+/// The stride of the given buffer is calculated from its length.
+pub(crate) fn repack_rows(
+    bytes: &[u8],
+    height: usize,
+    row_length: usize,
+    row_order: PdfBitmapRowOrder,
+    stride: PdfBitmapStride,
+) -> Vec<u8> {
+    let source_stride = bytes.len().checked_div(height).unwrap_or(0).max(1);
+
+    let row_length = row_length.min(source_stride);
+
+    let padding = match stride {
+        PdfBitmapStride::Tight => 0,
+        PdfBitmapStride::FourByteAligned => (4 - row_length % 4) % 4,
+    };
+
+    let mut result = Vec::with_capacity((row_length + padding) * height);
+
+    let mut append_row = |row: &[u8]| {
+        result.extend_from_slice(&row[..row_length]);
+        result.resize(result.len() + padding, 0);
+    };
+
+    let rows = bytes.chunks(source_stride).take(height);
+
+    match row_order {
+        PdfBitmapRowOrder::TopDown => rows.for_each(&mut append_row),
+        PdfBitmapRowOrder::BottomUp => rows.rev().for_each(&mut append_row),
+    }
+
+    result
+}
+
+/// Clips a copy of the given source region, from a source bitmap of the given dimensions,
+/// to the given destination position in a destination bitmap of the given dimensions.
+/// Returns the clipped source and destination regions, which always have the same
+/// dimensions, or `None` if nothing would be copied.
+pub(crate) fn clip_blit(
+    source_rect: PdfBitmapRect,
+    source_size: (Pixels, Pixels),
+    destination: (Pixels, Pixels),
+    destination_size: (Pixels, Pixels),
+) -> Option<(PdfBitmapRect, PdfBitmapRect)> {
+    let clipped_source = source_rect.clipped_to(source_size.0, source_size.1)?;
+
+    // Move the destination by the same amount the source was clipped at its top left.
+
+    let requested_destination = PdfBitmapRect::new(
+        destination
+            .0
+            .saturating_add(clipped_source.left - source_rect.left),
+        destination
+            .1
+            .saturating_add(clipped_source.top - source_rect.top),
+        clipped_source.width,
+        clipped_source.height,
+    );
+
+    let clipped_destination =
+        requested_destination.clipped_to(destination_size.0, destination_size.1)?;
+
+    Some((
+        PdfBitmapRect::new(
+            clipped_source.left + (clipped_destination.left - requested_destination.left),
+            clipped_source.top + (clipped_destination.top - requested_destination.top),
+            clipped_destination.width,
+            clipped_destination.height,
+        ),
+        clipped_destination,
+    ))
+}
+
+/// Sets the color channels of the given one, three, or four-channel pixel to either pure black
+/// or pure white, in place, depending on whether its luminance is below the given threshold.
+/// The color channels of three and four-channel pixels are in BGR order, or in RGB order if the
+/// given flag is set; the fourth channel, if any, is left unchanged.
+pub(crate) fn threshold_pixel(pixel: &mut [u8], threshold: u8, is_rgb: bool) {
+    let value = if pixel_luminance(pixel, is_rgb) < threshold as u32 {
+        0
+    } else {
+        255
+    };
+
+    for channel in pixel.iter_mut().take(3) {
+        *channel = value;
+    }
+}
+
+/// Swaps the difference between the first and third channels of the given subpixel
+/// anti-aliased pixel and the same channels of the given grayscale anti-aliased pixel,
+/// in place. Since the two channels are swapped symmetrically, the byte order of the
+/// pixels is irrelevant. Any other channels are left unchanged.
+pub(crate) fn reverse_subpixel_pixel(pixel: &mut [u8], grayscale: &[u8]) {
+    let first = grayscale[0] as i32 + pixel[2] as i32 - grayscale[2] as i32;
+
+    let third = grayscale[2] as i32 + pixel[0] as i32 - grayscale[0] as i32;
+
+    pixel[0] = first.clamp(0, 255) as u8;
+    pixel[2] = third.clamp(0, 255) as u8;
+}
+
+/// Returns the luminance of the given one, three, or four-channel pixel, in the range 0..=255.
+/// The color channels of three and four-channel pixels are in BGR order, or in RGB order if the
+/// given flag is set.
+fn pixel_luminance(pixel: &[u8], is_rgb: bool) -> u32 {
+    if pixel.len() < 3 {
+        pixel[0] as u32
+    } else {
+        let (red, blue) = if is_rgb {
+            (pixel[0], pixel[2])
+        } else {
+            (pixel[2], pixel[0])
+        };
+
+        // ITU-R BT.601 luma weights, scaled by 1000.
+
+        (red as u32 * 299 + pixel[1] as u32 * 587 + blue as u32 * 114 + 500) / 1000
+    }
+}
+
+/// Replaces each of the given luminances, arranged in rows of the given width, with either
+/// 0 or 255 using Floyd-Steinberg error diffusion around the given threshold. The error of
+/// each pixel is distributed to the pixel to its right and to the three pixels below it,
+/// in the proportions 7/16, 3/16, 5/16, and 1/16.
+pub(crate) fn dither_luminances(luminances: &mut [i32], width: usize, threshold: u8) {
+    if width == 0 {
+        return;
+    }
+
+    for index in 0..luminances.len() {
+        let old = luminances[index];
+
+        let new = if old < threshold as i32 { 0 } else { 255 };
+
+        luminances[index] = new;
+
+        let error = old - new;
+
+        let column = index % width;
+
+        let mut diffuse = |target: usize, weight: i32| {
+            if let Some(luminance) = luminances.get_mut(target) {
+                *luminance += error * weight / 16;
+            }
+        };
+
+        if column + 1 < width {
+            diffuse(index + 1, 7);
+        }
+
+        if column > 0 {
+            diffuse(index + width - 1, 3);
+        }
+
+        diffuse(index + width, 5);
+
+        if column + 1 < width {
+            diffuse(index + width + 1, 1);
+        }
+    }
+}
+
+/// Encodes the given image in the given file format, recording the given horizontal and
+/// vertical resolutions, in dots per inch, in the metadata of PNG and JPEG files.
+/// Resolutions are not recorded in files of other formats.
+#[cfg(feature = "image")]
+pub(crate) fn encode_image_with_dpi(
+    image: &DynamicImage,
+    format: ImageFormat,
+    dpi: (f32, f32),
+) -> Result<Vec<u8>, PdfiumError> {
+    let mut bytes = Vec::new();
+
+    match format {
+        ImageFormat::Jpeg => {
+            let mut encoder = JpegEncoder::new(&mut bytes);
+
+            encoder.set_pixel_density(PixelDensity {
+                density: (dots_per_inch_as_u16(dpi.0), dots_per_inch_as_u16(dpi.1)),
+                unit: PixelDensityUnit::Inches,
+            });
+
+            // JPEG does not support an alpha channel.
+
+            match image {
+                DynamicImage::ImageLuma8(image) => encoder.encode_image(image),
+                _ => encoder.encode_image(&image.to_rgb8()),
+            }
+            .map_err(|_| PdfiumError::ImageError)?;
+        }
+        _ => {
+            image
+                .write_to(&mut Cursor::new(&mut bytes), format)
+                .map_err(|_| PdfiumError::ImageError)?;
+
+            if format == ImageFormat::Png {
+                insert_png_physical_dimensions(&mut bytes, dpi);
+            }
+        }
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(feature = "image")]
+#[inline]
+fn dots_per_inch_as_u16(dpi: f32) -> u16 {
+    dpi.round().clamp(1.0, u16::MAX as f32) as u16
+}
+
+/// Inserts a `pHYs` chunk recording the given horizontal and vertical resolutions, in dots
+/// per inch, immediately after the `IHDR` chunk of the given encoded PNG file.
+#[cfg(feature = "image")]
+pub(crate) fn insert_png_physical_dimensions(png: &mut Vec<u8>, dpi: (f32, f32)) {
+    // The eight-byte PNG signature is always followed by the 25-byte IHDR chunk.
+
+    const IHDR_END: usize = 8 + 25;
+
+    if png.len() < IHDR_END || &png[12..16] != b"IHDR" {
+        return;
+    }
+
+    let pixels_per_meter = |dpi: f32| ((dpi / 0.0254).round().max(1.0) as u32).to_be_bytes();
+
+    let mut data = Vec::with_capacity(13);
+
+    data.extend_from_slice(b"pHYs");
+    data.extend_from_slice(&pixels_per_meter(dpi.0));
+    data.extend_from_slice(&pixels_per_meter(dpi.1));
+    data.push(1); // The unit is the meter.
+
+    let mut chunk = Vec::with_capacity(21);
+
+    chunk.extend_from_slice(&9u32.to_be_bytes());
+    chunk.extend_from_slice(&data);
+    chunk.extend_from_slice(&crc32(&data).to_be_bytes());
+
+    png.splice(IHDR_END..IHDR_END, chunk);
+}
+
 impl<'a> Drop for PdfBitmap<'a> {
     /// Closes this [PdfBitmap], releasing the memory held by the bitmap buffer.
     #[inline]
@@ -390,6 +1264,10 @@ impl<'a> Drop for PdfBitmap<'a> {
 
 #[cfg(test)]
 mod tests {
+    use crate::pdf::bitmap::{
+        clip_blit, composite_pixel_over, dither_luminances, hash_bitmap_content, repack_rows,
+        reverse_subpixel_pixel, threshold_pixel,
+    };
     use crate::prelude::*;
     use crate::utils::mem::create_sized_buffer;
     use crate::utils::test::test_bind_to_pdfium;
@@ -436,4 +1314,353 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_content_hash_ignores_padding() {
+        // Two 2x2 pixel BGR bitmaps with identical content but different scanline padding.
+
+        let unpadded = [1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12];
+
+        let padded = [1, 2, 3, 4, 5, 6, 0, 0, 7, 8, 9, 10, 11, 12, 99, 99];
+
+        assert_eq!(
+            hash_bitmap_content(&unpadded, 2, 2, 6, PdfBitmapFormat::BGR, false),
+            hash_bitmap_content(&padded, 2, 2, 8, PdfBitmapFormat::BGR, false)
+        );
+
+        // The unused fourth byte in BGRx pixels should be ignored.
+
+        assert_eq!(
+            hash_bitmap_content(
+                &[1, 2, 3, 0, 4, 5, 6, 0],
+                2,
+                1,
+                8,
+                PdfBitmapFormat::BGRx,
+                false
+            ),
+            hash_bitmap_content(
+                &[1, 2, 3, 255, 4, 5, 6, 7],
+                2,
+                1,
+                8,
+                PdfBitmapFormat::BGRx,
+                false
+            )
+        );
+
+        // Changes to visible pixels should change the hash.
+
+        assert_ne!(
+            hash_bitmap_content(&unpadded, 2, 2, 6, PdfBitmapFormat::BGR, false),
+            hash_bitmap_content(
+                &[1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 13],
+                2,
+                2,
+                6,
+                PdfBitmapFormat::BGR,
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn test_composite_pixel_over() {
+        let background = [255, 0, 0, 255];
+
+        let mut transparent = [10, 20, 30, 0];
+
+        composite_pixel_over(&mut transparent, &background);
+
+        assert_eq!(transparent, [255, 0, 0, 255]);
+
+        let mut opaque = [10, 20, 30, 255];
+
+        composite_pixel_over(&mut opaque, &background);
+
+        assert_eq!(opaque, [10, 20, 30, 255]);
+
+        let mut half = [0, 255, 0, 128];
+
+        composite_pixel_over(&mut half, &background);
+
+        assert_eq!(half, [127, 128, 0, 255]);
+    }
+
+    #[test]
+    fn test_repack_rows() {
+        // Two rows of three single-byte pixels, each padded by Pdfium to four bytes.
+
+        let bytes = [1, 2, 3, 0, 4, 5, 6, 0];
+
+        assert_eq!(
+            repack_rows(
+                &bytes,
+                2,
+                3,
+                PdfBitmapRowOrder::TopDown,
+                PdfBitmapStride::Tight
+            ),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+
+        assert_eq!(
+            repack_rows(
+                &bytes,
+                2,
+                3,
+                PdfBitmapRowOrder::BottomUp,
+                PdfBitmapStride::Tight
+            ),
+            vec![4, 5, 6, 1, 2, 3]
+        );
+
+        assert_eq!(
+            repack_rows(
+                &bytes,
+                2,
+                3,
+                PdfBitmapRowOrder::BottomUp,
+                PdfBitmapStride::FourByteAligned
+            ),
+            vec![4, 5, 6, 0, 1, 2, 3, 0]
+        );
+
+        // Rows that are already a multiple of four bytes long are not padded.
+
+        assert_eq!(
+            repack_rows(
+                &bytes,
+                1,
+                8,
+                PdfBitmapRowOrder::TopDown,
+                PdfBitmapStride::FourByteAligned
+            ),
+            bytes.to_vec()
+        );
+    }
+
+    #[test]
+    fn test_clip_blit() {
+        // Entirely within bounds.
+
+        assert_eq!(
+            clip_blit(PdfBitmapRect::new(1, 2, 3, 4), (10, 10), (5, 5), (10, 10)),
+            Some((
+                PdfBitmapRect::new(1, 2, 3, 4),
+                PdfBitmapRect::new(5, 5, 3, 4)
+            ))
+        );
+
+        // Source region overhangs the top left of the source bitmap.
+
+        assert_eq!(
+            clip_blit(PdfBitmapRect::new(-2, -1, 5, 5), (10, 10), (0, 0), (10, 10)),
+            Some((
+                PdfBitmapRect::new(0, 0, 3, 4),
+                PdfBitmapRect::new(2, 1, 3, 4)
+            ))
+        );
+
+        // Destination overhangs the bottom right of the destination bitmap.
+
+        assert_eq!(
+            clip_blit(PdfBitmapRect::new(0, 0, 5, 5), (10, 10), (8, 7), (10, 10)),
+            Some((
+                PdfBitmapRect::new(0, 0, 2, 3),
+                PdfBitmapRect::new(8, 7, 2, 3)
+            ))
+        );
+
+        // Destination overhangs the top left of the destination bitmap.
+
+        assert_eq!(
+            clip_blit(PdfBitmapRect::new(2, 2, 4, 4), (10, 10), (-1, -3), (10, 10)),
+            Some((
+                PdfBitmapRect::new(3, 5, 3, 1),
+                PdfBitmapRect::new(0, 0, 3, 1)
+            ))
+        );
+
+        // Nothing to copy.
+
+        assert_eq!(
+            clip_blit(PdfBitmapRect::new(0, 0, 5, 5), (10, 10), (10, 0), (10, 10)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_crop_blit_and_fill() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut bitmap = PdfBitmap::empty(20, 10, PdfBitmapFormat::BGRA, pdfium.bindings())?;
+
+        bitmap.fill_rect(PdfBitmapRect::new(0, 0, 20, 10), PdfColor::WHITE)?;
+        bitmap.fill_rect(
+            PdfBitmapRect::new(15, 5, 10, 10),
+            PdfColor::new(255, 0, 0, 255),
+        )?;
+
+        let cropped = bitmap.crop(PdfBitmapRect::new(14, 4, 6, 6))?;
+
+        assert_eq!(cropped.width(), 6);
+        assert_eq!(cropped.height(), 6);
+
+        let rgba = cropped.as_rgba_bytes();
+
+        assert_eq!(&rgba[0..4], &[255, 255, 255, 255]);
+        assert_eq!(&rgba[(6 + 1) * 4..(6 + 2) * 4], &[255, 0, 0, 255]);
+
+        assert!(bitmap.crop(PdfBitmapRect::new(15, 5, 10, 10)).is_err());
+
+        let mut target = PdfBitmap::empty(6, 6, PdfBitmapFormat::BGRA, pdfium.bindings())?;
+
+        target.fill_rect(PdfBitmapRect::new(0, 0, 6, 6), PdfColor::BLACK)?;
+        target.blit_from(&cropped, PdfBitmapRect::new(0, 0, 6, 6), (0, 0))?;
+
+        assert_eq!(target.as_rgba_bytes(), cropped.as_rgba_bytes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_threshold_pixel() {
+        let mut gray = [127];
+
+        threshold_pixel(&mut gray, 128, false);
+
+        assert_eq!(gray, [0]);
+
+        let mut gray = [128];
+
+        threshold_pixel(&mut gray, 128, false);
+
+        assert_eq!(gray, [255]);
+
+        // Pure blue is dark; pure green is light. The alpha channel is preserved.
+
+        let mut blue = [255, 0, 0, 100];
+
+        threshold_pixel(&mut blue, 128, false);
+
+        assert_eq!(blue, [0, 0, 0, 100]);
+
+        let mut green = [0, 255, 0];
+
+        threshold_pixel(&mut green, 128, true);
+
+        assert_eq!(green, [255, 255, 255]);
+
+        // The same bytes are pure red in BGR order but pure blue, which is darker, in RGB order.
+
+        let mut red_bgr = [0, 0, 255];
+
+        threshold_pixel(&mut red_bgr, 64, false);
+
+        assert_eq!(red_bgr, [255, 255, 255]);
+
+        let mut blue_rgb = [0, 0, 255];
+
+        threshold_pixel(&mut blue_rgb, 64, true);
+
+        assert_eq!(blue_rgb, [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_reverse_subpixel_pixel() {
+        // A fringe of black text on a white background: the subpixel rendering darkens
+        // the red channel more than the grayscale rendering does, so the reversed pixel
+        // darkens the blue channel instead.
+
+        let mut pixel = [200, 120, 40, 255];
+
+        reverse_subpixel_pixel(&mut pixel, &[120, 120, 120, 255]);
+
+        assert_eq!(pixel, [40, 120, 200, 255]);
+
+        // Where the two renderings agree, as in areas without text, the pixel keeps its color.
+
+        let mut red = [0, 0, 255];
+
+        reverse_subpixel_pixel(&mut red, &[0, 0, 255]);
+
+        assert_eq!(red, [0, 0, 255]);
+
+        // Red text on a blue background keeps its colors; only its fringes are swapped.
+
+        let mut fringe = [230, 0, 60];
+
+        reverse_subpixel_pixel(&mut fringe, &[200, 0, 100]);
+
+        assert_eq!(fringe, [160, 0, 130]);
+    }
+
+    #[test]
+    fn test_dither_luminances() {
+        // A uniform mid-gray area is reproduced as a mix of black and white pixels
+        // of roughly the same average brightness, rather than flattened to a single value.
+
+        let mut gray = vec![128; 16 * 16];
+
+        dither_luminances(&mut gray, 16, 128);
+
+        assert!(gray.iter().all(|value| *value == 0 || *value == 255));
+
+        let white = gray.iter().filter(|value| **value == 255).count();
+
+        assert!((112..=144).contains(&white));
+
+        // Pure black and pure white are unchanged.
+
+        let mut extremes = vec![0, 255, 0, 255, 255, 0];
+
+        dither_luminances(&mut extremes, 3, 128);
+
+        assert_eq!(extremes, vec![0, 255, 0, 255, 255, 0]);
+
+        // A light gray area contains more white pixels than a dark gray area.
+
+        let mut light = vec![192; 64];
+
+        let mut dark = vec![64; 64];
+
+        dither_luminances(&mut light, 8, 128);
+        dither_luminances(&mut dark, 8, 128);
+
+        let count = |values: &[i32]| values.iter().filter(|value| **value == 255).count();
+
+        assert!(count(&light) > count(&dark));
+    }
+
+    #[test]
+    #[cfg(feature = "image")]
+    fn test_encode_image_with_dpi() -> Result<(), PdfiumError> {
+        use crate::pdf::bitmap::encode_image_with_dpi;
+        use image::{DynamicImage, ImageFormat, RgbaImage};
+
+        let image = DynamicImage::ImageRgba8(RgbaImage::new(2, 2));
+
+        // 300 dpi is 11811 pixels per meter.
+
+        let png = encode_image_with_dpi(&image, ImageFormat::Png, (300.0, 300.0))?;
+
+        assert_eq!(&png[33..41], &[0, 0, 0, 9, b'p', b'H', b'Y', b's']);
+        assert_eq!(&png[41..50], &[0, 0, 46, 35, 0, 0, 46, 35, 1]);
+
+        // The inserted chunk must not corrupt the file.
+
+        assert!(image::load_from_memory_with_format(&png, ImageFormat::Png).is_ok());
+
+        // The JFIF header records the density in dots per inch.
+
+        let jpeg = encode_image_with_dpi(&image, ImageFormat::Jpeg, (150.0, 300.0))?;
+
+        assert_eq!(&jpeg[6..11], b"JFIF\0");
+        assert_eq!(&jpeg[13..18], &[1, 0, 150, 1, 44]);
+
+        assert!(!encode_image_with_dpi(&image, ImageFormat::WebP, (300.0, 300.0))?.is_empty());
+
+        Ok(())
+    }
 }
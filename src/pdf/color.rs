@@ -10,7 +10,7 @@ use crate::error::PdfiumError;
 /// Note that when used as a form field highlight color, a solid color with no opacity
 /// will overprint any user data in the field. Use the [PdfColor::with_alpha()] function
 /// to apply an alpha channel value to an existing [PdfColor].
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct PdfColor {
     r: u8,
     g: u8,
@@ -339,6 +339,19 @@ impl PdfColor {
         )
     }
 
+    /// Returns the hexadecimal color specification of this color, with a leading hash symbol,
+    /// in the form accepted by [PdfColor::from_hex()]. The alpha channel value is included
+    /// only if the given flag is set. Used when serializing colors to XFDF and to annotation
+    /// DTOs, so that both always describe colors in the same way.
+    #[inline]
+    pub(crate) fn to_hex_specification(self, include_alpha: bool) -> String {
+        if include_alpha {
+            format!("#{}", self.to_hex_with_alpha())
+        } else {
+            format!("#{}", self.to_hex())
+        }
+    }
+
     /// Returns this color encoded as a 32-bit hexadecimal 0xAARRGGBB value,
     /// suitable for passing to Pdfium.
     #[inline]
@@ -401,5 +414,10 @@ mod tests {
             PdfColor::PURPLE.with_alpha(64).to_hex_with_alpha(),
             "40800080"
         );
+        assert_eq!(PdfColor::YELLOW.to_hex_specification(false), "#FFFF00");
+        assert_eq!(
+            PdfColor::PURPLE.with_alpha(64).to_hex_specification(true),
+            "#40800080"
+        );
     }
 }
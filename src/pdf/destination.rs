@@ -15,7 +15,7 @@ use crate::utils::mem::create_sized_buffer;
 
 /// The view settings that a PDF viewer should apply when displaying the target
 /// `PdfPage` nominated by a [PdfDestination] in its display window.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum PdfDestinationViewSettings {
     /// The view settings are unknown.
     Unknown,
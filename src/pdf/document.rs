@@ -1,36 +1,132 @@
 //! Defines the [PdfDocument] struct, the entry point to all Pdfium functionality
 //! related to a single PDF file.
 
+pub mod annotation_summary;
 pub mod attachment;
 pub mod attachments;
 pub mod bookmark;
 pub mod bookmarks;
+pub mod compare;
+pub mod extracted_link;
+pub mod font_coverage;
 pub mod fonts;
 pub mod form;
+pub mod imposition;
+pub mod javascript;
+pub mod link_hits;
+pub mod link_rewrite;
+pub(crate) mod merge;
 pub mod metadata;
+pub mod outline;
 pub mod page;
+pub mod page_labels;
 pub mod pages;
 pub mod permissions;
+pub mod render_cache;
+pub mod render_iterator;
+pub mod render_sequence;
+pub mod search;
 pub mod signature;
 pub mod signatures;
+pub mod signing;
+pub(crate) mod structure_tree;
+pub(crate) mod syntax;
+pub mod text_flow;
+pub mod text_index;
+pub(crate) mod xfdf;
+pub(crate) mod xml;
+pub mod xmp;
+
+#[cfg(feature = "pdfium_use_win32")]
+pub mod print_job;
 
-use crate::bindgen::FPDF_DOCUMENT;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod append_only_writer;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod loader;
+
+#[cfg(all(feature = "async", not(target_arch = "wasm32")))]
+pub mod asynchronous;
+
+#[cfg(feature = "pdfium_enable_xfa")]
+pub mod xfa;
+
+use crate::bindgen::{FPDF_DOCUMENT, FPDF_DWORD, FPDF_INCREMENTAL};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::PdfiumError;
 use crate::error::PdfiumInternalError;
-use crate::pdf::document::attachments::PdfAttachments;
+use crate::pdf::bitmap::{PdfBitmap, PdfBitmapFormat, Pixels};
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::annotation_summary::PdfAnnotationSummary;
+use crate::pdf::document::attachment::details::{
+    write_attachment_description, write_attachment_mime_type,
+};
+use crate::pdf::document::attachments::{PdfAttachmentIndex, PdfAttachments};
 use crate::pdf::document::bookmarks::PdfBookmarks;
+use crate::pdf::document::extracted_link::{PdfExtractedLink, PdfExtractedLinks};
+use crate::pdf::document::font_coverage::{distinct_chars, PdfFontCoverage, PdfUsedFonts};
 use crate::pdf::document::fonts::PdfFonts;
+use crate::pdf::document::form::appearances::{read_need_appearances, write_need_appearances};
+use crate::pdf::document::form::builder::{append_form_fields, PdfFormFieldBuilder};
+use crate::pdf::document::form::fields::PdfFormNamedField;
+use crate::pdf::document::form::flatten::flatten_widgets;
+use crate::pdf::document::form::options::{edit_form_field_options, PdfFormFieldOptionsEdit};
 use crate::pdf::document::form::PdfForm;
+use crate::pdf::document::imposition::{booklet_document, n_up_document, PdfBookletConfig};
+use crate::pdf::document::javascript::{
+    collect_document_javascript, remove_javascript_actions, PdfJavaScriptAction,
+};
+use crate::pdf::document::link_hits::PdfDocumentLinks;
+use crate::pdf::document::link_rewrite::{rewrite_links_on_page, PdfLinkRewrite, PdfLinkTarget};
+use crate::pdf::document::merge::split_document;
 use crate::pdf::document::metadata::PdfMetadata;
-use crate::pdf::document::pages::PdfPages;
+use crate::pdf::document::page::additional_actions::PdfPageAdditionalActionsEdits;
+use crate::pdf::document::page::annotation::append::append_annotation;
+use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
+use crate::pdf::document::page::annotations::PdfPageAnnotationIndex;
+use crate::pdf::document::page::generation::PdfContentGenerations;
+use crate::pdf::document::page::layers::collect_page_layer_names;
+use crate::pdf::document::page::render_config::PdfRenderConfig;
+use crate::pdf::document::page::size::PdfPagePaperSize;
+use crate::pdf::document::page::text::search::PdfSearchOptions;
+use crate::pdf::document::page_labels::{
+    read_page_labels, validate_page_label_ranges, write_page_labels, PdfPageLabelRange,
+};
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
 use crate::pdf::document::permissions::PdfPermissions;
-use crate::pdf::document::signatures::PdfSignatures;
+use crate::pdf::document::render_iterator::PdfRenderIterator;
+use crate::pdf::document::render_sequence::PdfRenderSequenceConfig;
+use crate::pdf::document::search::PdfDocumentSearch;
+use crate::pdf::document::search::PdfNormalizedTextCache;
+use crate::pdf::document::signatures::{PdfSignatureSourceFile, PdfSignatures};
+use crate::pdf::document::signing::{
+    append_signature_field, PdfPreparedSignature, PdfSignatureFieldBuilder,
+};
+use crate::pdf::document::structure_tree::PdfStructureTreeEdits;
+use crate::pdf::document::syntax::PdfSyntaxObject;
+use crate::pdf::document::text_flow::{PdfTextFlow, PdfTextFlowPage};
+use crate::pdf::document::text_index::{PdfPageTextIndex, PdfTextIndex};
+use crate::pdf::document::xfdf::{export_annotations, import_annotations};
+use crate::pdf::document::xmp::{read_xmp, write_xmp, PdfXmpMetadata};
+use crate::pdf::rect::PdfRect;
+use crate::pdfium::Pdfium;
 use crate::utils::files::get_pdfium_file_writer_from_writer;
 use crate::utils::files::FpdfFileAccessExt;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::pdf::document::loader::PdfDocumentLoaderSource;
 use std::fmt::{Debug, Formatter};
 use std::io::Cursor;
 use std::io::Write;
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+#[cfg(feature = "pdfium_enable_xfa")]
+use crate::pdf::document::xfa::{replace_xfa_datasets, PdfXfaPackets};
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
@@ -47,6 +143,9 @@ use wasm_bindgen::JsValue;
 #[cfg(target_arch = "wasm32")]
 use web_sys::Blob;
 
+#[cfg(doc)]
+use crate::pdf::document::search::PdfSearchResult;
+
 // The following dummy declaration is used only when running cargo doc.
 // It allows documentation of WASM-specific functionality to be included
 // in documentation generated on non-WASM targets.
@@ -143,10 +242,12 @@ impl PdfDocumentVersion {
 /// * [PdfDocument::attachments()], an immutable collection of all the [PdfAttachments] in the document.
 /// * [PdfDocument::attachments_mut()], a mutable collection of all the [PdfAttachments] in the document.
 /// * [PdfDocument::bookmarks()], an immutable collection of all the [PdfBookmarks] in the document.
+/// * [PdfDocument::bookmarks_mut()], a mutable collection of all the [PdfBookmarks] in the document.
 /// * [PdfDocument::fonts()], an immutable collection of all the [PdfFonts] in the document.
 /// * [PdfDocument::fonts_mut()], a mutable collection of all the [PdfFonts] in the document.
 /// * [PdfDocument::form()], an immutable reference to the [PdfForm] embedded in the document, if any.
 /// * [PdfDocument::metadata()], an immutable collection of all the [PdfMetadata] tags in the document.
+/// * [PdfDocument::metadata_mut()], a mutable collection of all the [PdfMetadata] tags in the document.
 /// * [PdfDocument::pages()], an immutable collection of all the [PdfPages] in the document.
 /// * [PdfDocument::pages_mut()], a mutable collection of all the [PdfPages] in the document.
 /// * [PdfDocument::permissions()], settings relating to security handlers and document permissions
@@ -163,12 +264,16 @@ pub struct PdfDocument<'a> {
     pages: PdfPages<'a>,
     permissions: PdfPermissions<'a>,
     signatures: PdfSignatures<'a>,
+    structure_tree: PdfStructureTreeEdits,
     bindings: &'a dyn PdfiumLibraryBindings,
-    source_byte_buffer: Option<Vec<u8>>,
+    source_byte_buffer: Option<Arc<Vec<u8>>>,
 
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     // This field is never used when compiling to WASM.
     file_access_reader: Option<Box<FpdfFileAccessExt<'a>>>,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    loader_source: Option<PdfDocumentLoaderSource<'a>>,
 }
 
 impl<'a> PdfDocument<'a> {
@@ -177,6 +282,8 @@ impl<'a> PdfDocument<'a> {
         handle: FPDF_DOCUMENT,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
+        PdfContentGenerations::open_document(handle);
+
         let form = PdfForm::from_pdfium(handle, bindings);
 
         let pages =
@@ -193,9 +300,12 @@ impl<'a> PdfDocument<'a> {
             pages,
             permissions: PdfPermissions::from_pdfium(handle, bindings),
             signatures: PdfSignatures::from_pdfium(handle, bindings),
+            structure_tree: PdfStructureTreeEdits::new(),
             bindings,
             source_byte_buffer: None,
             file_access_reader: None,
+            #[cfg(not(target_arch = "wasm32"))]
+            loader_source: None,
         }
     }
 
@@ -211,13 +321,32 @@ impl<'a> PdfDocument<'a> {
         self.bindings
     }
 
+    /// Returns the structure elements added to this [PdfDocument] since it was loaded,
+    /// which are written into the document's structure tree when it is saved.
+    #[inline]
+    pub(crate) fn structure_tree_mut(&mut self) -> &mut PdfStructureTreeEdits {
+        &mut self.structure_tree
+    }
+
     /// Transfers ownership of the byte buffer containing the binary data of this [PdfDocument],
     /// so that it will always be available for Pdfium to read data from as needed.
     #[inline]
     pub(crate) fn set_source_byte_buffer(&mut self, bytes: Vec<u8>) {
+        let bytes = Arc::new(bytes);
+
+        self.signatures
+            .set_source_file(PdfSignatureSourceFile::Owned(bytes.clone()));
         self.source_byte_buffer = Some(bytes);
     }
 
+    /// Records the byte slice containing the binary data of this [PdfDocument], so that
+    /// the content covered by the document's signatures can be checked.
+    #[inline]
+    pub(crate) fn set_source_byte_slice(&mut self, bytes: &'a [u8]) {
+        self.signatures
+            .set_source_file(PdfSignatureSourceFile::Borrowed(bytes));
+    }
+
     /// Binds an `FPDF_FILEACCESS` reader to the lifetime of this [PdfDocument], so that
     /// it will always be available for Pdfium to read data from as needed.
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
@@ -227,6 +356,15 @@ impl<'a> PdfDocument<'a> {
         self.file_access_reader = Some(reader);
     }
 
+    /// Binds the availability provider and data received by a `PdfDocumentLoader` to the
+    /// lifetime of this [PdfDocument], so that they will always be available for Pdfium
+    /// to read data from as needed.
+    #[cfg(not(target_arch = "wasm32"))]
+    #[inline]
+    pub(crate) fn set_loader_source(&mut self, source: PdfDocumentLoaderSource<'a>) {
+        self.loader_source = Some(source);
+    }
+
     /// Returns the file version of this [PdfDocument].
     pub fn version(&self) -> PdfDocumentVersion {
         let mut version = 0;
@@ -255,18 +393,119 @@ impl<'a> PdfDocument<'a> {
         &mut self.attachments
     }
 
+    /// Sets the description of the attachment at the given index in this document's
+    /// [PdfAttachments] collection, or removes it if `None` is given.
+    ///
+    /// Pdfium cannot write the file specification dictionary holding the description, so
+    /// this function updates the document in the same way as [PdfDocument::set_xmp()],
+    /// preserving any existing signatures. Objects in compressed object streams are not read.
+    /// Returns [PdfiumError::AttachmentIndexOutOfBounds] if there is no attachment at the
+    /// given index, or [PdfiumError::DocumentUpdateUnsupported] if the document is encrypted
+    /// or its file specification cannot be read.
+    pub fn set_attachment_description(
+        &mut self,
+        index: PdfAttachmentIndex,
+        description: Option<&str>,
+    ) -> Result<(), PdfiumError> {
+        let bytes =
+            write_attachment_description(self.save_for_update()?, index as usize, description)?;
+
+        self.reload_from_bytes(bytes)
+    }
+
+    /// Sets the MIME type of the file data embedded in the attachment at the given index
+    /// in this document's [PdfAttachments] collection, or removes it if `None` is given.
+    ///
+    /// The MIME type is written in the same way as [PdfDocument::set_attachment_description()],
+    /// preserving any existing signatures, and subject to the same limitation on reading
+    /// objects in compressed object streams.
+    /// Since replacing the data of an attachment discards its MIME type, the MIME type
+    /// should be set after calling `PdfAttachment::set_bytes()`. Returns
+    /// [PdfiumError::NoDataInAttachment] if the attachment has no embedded file data.
+    pub fn set_attachment_mime_type(
+        &mut self,
+        index: PdfAttachmentIndex,
+        mime_type: Option<&str>,
+    ) -> Result<(), PdfiumError> {
+        let bytes = write_attachment_mime_type(self.save_for_update()?, index as usize, mime_type)?;
+
+        self.reload_from_bytes(bytes)
+    }
+
     /// Returns an immutable collection of all the [PdfBookmarks] in this [PdfDocument].
     #[inline]
     pub fn bookmarks(&self) -> &PdfBookmarks {
         &self.bookmarks
     }
 
+    /// Returns a mutable collection of all the [PdfBookmarks] in this [PdfDocument].
+    /// Changes to the bookmarks are applied when the document is saved.
+    #[inline]
+    pub fn bookmarks_mut(&mut self) -> &mut PdfBookmarks<'a> {
+        &mut self.bookmarks
+    }
+
     /// Returns an immutable reference to the [PdfForm] embedded in this [PdfDocument], if any.
     #[inline]
     pub fn form(&self) -> Option<&PdfForm> {
         self.form.as_ref()
     }
 
+    /// Returns the collection of [PdfXfaPackets] making up the XFA form embedded in this
+    /// [PdfDocument]. The collection is empty if the document does not contain an XFA form.
+    ///
+    /// This function is only available when the `pdfium_enable_xfa` crate feature is enabled.
+    #[cfg(feature = "pdfium_enable_xfa")]
+    #[inline]
+    pub fn xfa_packets(&self) -> PdfXfaPackets {
+        PdfXfaPackets::from_pdfium(self.handle, self.bindings)
+    }
+
+    /// Asks Pdfium to load the XFA form embedded in this [PdfDocument], so that the form's
+    /// fields are laid out and rendered from its XFA template rather than from any
+    /// AcroForm fields provided as a fallback.
+    ///
+    /// Returns [PdfiumError::PdfiumLibraryInternalError] if the document does not contain an
+    /// XFA form, or if the bound Pdfium library was built without XFA support.
+    ///
+    /// This function is only available when the `pdfium_enable_xfa` crate feature is enabled.
+    #[cfg(feature = "pdfium_enable_xfa")]
+    pub fn load_xfa(&mut self) -> Result<(), PdfiumError> {
+        if self
+            .bindings
+            .is_true(self.bindings.FPDF_LoadXFA(self.handle))
+        {
+            Ok(())
+        } else {
+            Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ))
+        }
+    }
+
+    /// Replaces the `datasets` packet of the XFA form embedded in this [PdfDocument], holding
+    /// the data entered into the form, with the given XML. The XML should be a complete
+    /// `<xfa:datasets>` element, such as the content of the packet returned by
+    /// `PdfXfaPackets::datasets()` after modification. A `datasets` packet is added if the
+    /// form does not already have one.
+    ///
+    /// Pdfium cannot write XFA packets itself, so this function updates the document in the
+    /// same way as [PdfDocument::create_form_fields()], preserving any existing signatures.
+    /// An XFA form stored in a compressed object stream cannot be found. If XFA support was loaded using
+    /// [PdfDocument::load_xfa()], it must be loaded again after the document is reloaded.
+    ///
+    /// Returns [PdfiumError::XfaPacketsUnavailable] if the document does not contain an XFA
+    /// form, or if the form is stored as a single XML document rather than as separate
+    /// packets, or [PdfiumError::DocumentUpdateUnsupported] if the document is encrypted.
+    ///
+    /// This function is only available when the `pdfium_enable_xfa` crate feature is enabled.
+    #[cfg(feature = "pdfium_enable_xfa")]
+    pub fn set_xfa_datasets(&mut self, datasets: &str) -> Result<(), PdfiumError> {
+        let bytes = replace_xfa_datasets(self.save_for_update()?, datasets.as_bytes())?;
+
+        self.reload_from_bytes(bytes)
+    }
+
     /// Returns an immutable collection of all the [PdfFonts] in this [PdfDocument].
     #[inline]
     pub fn fonts(&self) -> &PdfFonts {
@@ -285,6 +524,55 @@ impl<'a> PdfDocument<'a> {
         &self.metadata
     }
 
+    /// Returns a mutable collection of all the [PdfMetadata] tags in this [PdfDocument].
+    /// Changes to the metadata are applied when the document is saved.
+    #[inline]
+    pub fn metadata_mut(&mut self) -> &mut PdfMetadata<'a> {
+        &mut self.metadata
+    }
+
+    /// Returns the XMP metadata packet referenced by the catalog of this [PdfDocument],
+    /// or `None` if the document has no XMP metadata.
+    ///
+    /// Pdfium does not expose the document catalog, so this function saves the document to
+    /// memory and reads the packet from the saved copy. Returns
+    /// [PdfiumError::XmpMetadataUnreadable] if the metadata stream is compressed or encrypted.
+    #[inline]
+    pub fn xmp(&self) -> Result<Option<String>, PdfiumError> {
+        read_xmp(&self.save_to_bytes()?)
+    }
+
+    /// Replaces the XMP metadata packet referenced by the catalog of this [PdfDocument]
+    /// with the given packet, which should be a complete `x:xmpmeta` document, optionally
+    /// wrapped in `xpacket` processing instructions.
+    ///
+    /// Pdfium cannot write the document catalog itself, so this function updates the document
+    /// in the same way as [PdfDocument::create_form_fields()], preserving any existing
+    /// signatures. The information dictionary tags returned by [PdfDocument::metadata()] are
+    /// not updated to match; PDF/A requires them to be kept consistent with the packet. Returns
+    /// [PdfiumError::DocumentUpdateUnsupported] if the document is encrypted, or if its catalog
+    /// is stored in a compressed object stream or it has no readable trailer.
+    pub fn set_xmp(&mut self, xml: &str) -> Result<(), PdfiumError> {
+        let bytes = write_xmp(self.save_for_update()?, xml)?;
+
+        self.reload_from_bytes(bytes)
+    }
+
+    /// Returns a typed view of the common Dublin Core and PDF/A identification properties
+    /// in the XMP metadata packet of this [PdfDocument], or `None` if the document has
+    /// no XMP metadata.
+    #[inline]
+    pub fn xmp_metadata(&self) -> Result<Option<PdfXmpMetadata>, PdfiumError> {
+        Ok(self.xmp()?.map(|xml| PdfXmpMetadata::from_xml(&xml)))
+    }
+
+    /// Replaces the XMP metadata packet of this [PdfDocument] with a packet containing
+    /// only the properties in the given [PdfXmpMetadata]. See [PdfDocument::set_xmp()].
+    #[inline]
+    pub fn set_xmp_metadata(&mut self, metadata: &PdfXmpMetadata) -> Result<(), PdfiumError> {
+        self.set_xmp(&metadata.to_xml())
+    }
+
     /// Returns an immutable collection of all the [PdfPages] in this [PdfDocument].
     #[inline]
     pub fn pages(&self) -> &PdfPages<'a> {
@@ -309,15 +597,822 @@ impl<'a> PdfDocument<'a> {
         &self.signatures
     }
 
+    /// Returns a [PdfAnnotationSummary] describing the annotations on every page in this
+    /// [PdfDocument], including per-page counts by annotation type, annotation authors,
+    /// the range of annotation creation and modification dates, and the number of replies.
+    ///
+    /// Every page in the document will be loaded in order to build the summary.
+    #[inline]
+    pub fn annotation_summary(&self) -> PdfAnnotationSummary {
+        PdfAnnotationSummary::from_pages(self.pages())
+    }
+
+    /// Returns the markup annotations on every page in this [PdfDocument] as an XFDF document,
+    /// suitable for exchanging annotations with other PDF viewers and libraries such as PDF.js.
+    ///
+    /// Text, free text, highlight, underline, strikeout, squiggly, square, circle, and ink
+    /// annotations are exported, together with their bounds, attachment points, colors,
+    /// opacity, border width, ink strokes, contents, author, subject, name, and creation
+    /// and modification dates. Annotations of other types are omitted.
+    ///
+    /// Every page in the document will be loaded in order to export its annotations.
+    #[inline]
+    pub fn export_annotations_xfdf(&self) -> String {
+        export_annotations(self.pages())
+    }
+
+    /// Creates the markup annotations described by the given XFDF document on the pages of
+    /// this [PdfDocument], returning the number of annotations created. Annotations are
+    /// added to any existing annotations; nothing is removed.
+    ///
+    /// The same annotation types and properties are imported as are exported by
+    /// [PdfDocument::export_annotations_xfdf()]; elements describing other annotation types
+    /// are skipped. An appearance stream is generated for each imported annotation where
+    /// possible; see `PdfPageAnnotationCommon::generate_appearance_stream()`.
+    ///
+    /// Returns [PdfiumError::InvalidXfdfData] if the given string is not a well-formed XFDF
+    /// document, or [PdfiumError::PageIndexOutOfBounds] if an annotation refers to a page
+    /// that does not exist. Annotations imported before the error was encountered are kept.
+    pub fn import_annotations_xfdf(&mut self, xfdf: &str) -> Result<usize, PdfiumError> {
+        import_annotations(self.pages_mut(), xfdf)
+    }
+
+    /// Creates the new form fields described by the given [PdfFormFieldBuilder] objects in
+    /// this [PdfDocument], adding a [PdfForm] to the document if it does not already have one.
+    ///
+    /// Pdfium cannot create form fields itself, so this function saves the document to memory,
+    /// appends an incremental update adding the new fields to the saved copy, and reloads this
+    /// [PdfDocument] from the updated copy. Unsaved changes are carried over to the reloaded
+    /// document, but any form fill state held by Pdfium, such as the focused field, is reset.
+    /// If the document contains signatures, the copy is saved incrementally so that the existing
+    /// signatures remain valid, both in the reloaded document and when it is later saved using
+    /// [PdfDocument::save_incremental_to_bytes()].
+    ///
+    /// The saved copy is read object by object, without consulting its cross-reference
+    /// information, so objects stored in compressed object streams are not read, and a copy
+    /// whose cross-reference information is held only in cross-reference streams has no
+    /// trailer. Pdfium writes neither when saving a whole document, but the original bytes
+    /// of a signed document are preserved as they are, so updating a signed document that
+    /// uses them fails with an error.
+    ///
+    /// Returns [PdfiumError::InvalidFormFieldDefinition] if a builder does not describe a valid
+    /// field or uses the name of an existing field, [PdfiumError::PageIndexOutOfBounds] if a
+    /// field refers to a page that does not exist, or
+    /// [PdfiumError::FormFieldCreationUnsupported] if the document is encrypted. No fields are
+    /// created if an error is returned.
+    pub fn create_form_fields(
+        &mut self,
+        fields: &[PdfFormFieldBuilder],
+    ) -> Result<(), PdfiumError> {
+        let existing_field_names = match self.form() {
+            Some(form) => form
+                .fields(self.pages())
+                .iter()
+                .map(|field| field.name().to_string())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let bytes = append_form_fields(
+            self.save_for_update()?,
+            fields,
+            self.pages().len(),
+            &existing_field_names,
+        )?;
+
+        self.reload_from_bytes(bytes)
+    }
+
+    /// Replaces the options of the combo box or list box field with the given fully qualified
+    /// name in the [PdfForm] embedded in this [PdfDocument] with the given
+    /// (label, export value) pairs. The label of each option is displayed to the user;
+    /// the export value is the value taken by the field when the option is selected.
+    ///
+    /// Pdfium cannot write the options of a field itself, so this function updates the document
+    /// in the same way as [PdfDocument::create_form_fields()], preserving any existing
+    /// signatures. Fields stored in compressed object streams cannot be edited. Any selected
+    /// value that is no longer one of the field's options is cleared, and the appearance streams of the field's
+    /// widgets are regenerated by Pdfium when the document is reloaded.
+    ///
+    /// Returns [PdfiumError::FormFieldNotFound] if the form has no field with the given name,
+    /// [PdfiumError::InvalidFormFieldDefinition] if the field is not a combo box or list box,
+    /// or [PdfiumError::FormFieldCreationUnsupported] if the document is encrypted.
+    #[inline]
+    pub fn set_form_field_options(
+        &mut self,
+        name: &str,
+        options: Vec<(String, String)>,
+    ) -> Result<(), PdfiumError> {
+        self.edit_form_field_options(name, PdfFormFieldOptionsEdit::Set(options))
+    }
+
+    /// Inserts a new option with the given label and export value at the given index in the
+    /// options of the combo box or list box field with the given fully qualified name in the
+    /// [PdfForm] embedded in this [PdfDocument]. The document is updated and errors are
+    /// reported as described for [PdfDocument::set_form_field_options()];
+    /// [PdfiumError::FormFieldOptionIndexOutOfBounds] is returned if the index is greater
+    /// than the number of options.
+    #[inline]
+    pub fn insert_form_field_option(
+        &mut self,
+        name: &str,
+        index: usize,
+        label: &str,
+        export_value: &str,
+    ) -> Result<(), PdfiumError> {
+        self.edit_form_field_options(
+            name,
+            PdfFormFieldOptionsEdit::Insert(index, label.to_string(), export_value.to_string()),
+        )
+    }
+
+    /// Removes the option at the given index from the options of the combo box or list box
+    /// field with the given fully qualified name in the [PdfForm] embedded in this [PdfDocument].
+    /// The document is updated and errors are reported as described for
+    /// [PdfDocument::set_form_field_options()]; [PdfiumError::FormFieldOptionIndexOutOfBounds]
+    /// is returned if the index does not refer to an option.
+    #[inline]
+    pub fn remove_form_field_option(
+        &mut self,
+        name: &str,
+        index: usize,
+    ) -> Result<(), PdfiumError> {
+        self.edit_form_field_options(name, PdfFormFieldOptionsEdit::Remove(index))
+    }
+
+    /// Allows or prevents the selection of more than one option of the list box field with
+    /// the given fully qualified name in the [PdfForm] embedded in this [PdfDocument]. If
+    /// multiple selection is turned off, only the first selected option remains selected.
+    /// The document is updated and errors are reported as described for
+    /// [PdfDocument::set_form_field_options()].
+    ///
+    /// Options can be selected and deselected using `PdfFormNamedListBoxField::set_option_selected()`.
+    #[inline]
+    pub fn set_form_field_multi_select(
+        &mut self,
+        name: &str,
+        is_multi_select: bool,
+    ) -> Result<(), PdfiumError> {
+        self.edit_form_field_options(
+            name,
+            PdfFormFieldOptionsEdit::SetMultiSelect(is_multi_select),
+        )
+    }
+
+    /// Returns `true` if the [PdfForm] embedded in this [PdfDocument] asks PDF viewers to
+    /// regenerate the appearance streams of its fields when the document is opened, as set
+    /// by the `/NeedAppearances` entry of the form dictionary. Returns `false` if the document
+    /// has no form.
+    ///
+    /// Pdfium cannot read the form dictionary itself, so this function saves the document to
+    /// memory and reads the entry from the saved copy. Returns
+    /// [PdfiumError::FormFieldCreationUnsupported] if the saved copy cannot be read.
+    #[inline]
+    pub fn form_needs_appearances(&self) -> Result<bool, PdfiumError> {
+        read_need_appearances(&self.save_to_bytes()?)
+    }
+
+    /// Controls whether the [PdfForm] embedded in this [PdfDocument] asks PDF viewers to
+    /// regenerate the appearance streams of its fields when the document is opened.
+    ///
+    /// After setting field values programmatically, either regenerate each field's appearance
+    /// using `PdfFormFieldCommon::regenerate_appearance()`, so that every viewer displays the
+    /// appearance drawn by Pdfium, or set this flag so that viewers such as Acrobat draw the
+    /// new values themselves. Viewers that ignore the flag display whatever appearance
+    /// streams the fields have.
+    ///
+    /// Pdfium cannot write the form dictionary itself, so this function updates the document
+    /// in the same way as [PdfDocument::create_form_fields()], preserving any existing
+    /// signatures. The document is left unchanged if it has no form or the flag already has
+    /// the given value. Returns [PdfiumError::FormFieldCreationUnsupported] if the document is
+    /// encrypted, or if its form dictionary is stored in a compressed object stream or it has
+    /// no readable trailer.
+    pub fn set_form_needs_appearances(&mut self, value: bool) -> Result<(), PdfiumError> {
+        match write_need_appearances(self.save_for_update()?, value)? {
+            Some(bytes) => self.reload_from_bytes(bytes),
+            None => Ok(()),
+        }
+    }
+
+    /// Applies the given edit to the options or selection settings of the combo box or list box
+    /// field with the given fully qualified name, reloading this [PdfDocument].
+    fn edit_form_field_options(
+        &mut self,
+        name: &str,
+        edit: PdfFormFieldOptionsEdit,
+    ) -> Result<(), PdfiumError> {
+        let bytes = edit_form_field_options(self.save_for_update()?, name, edit)?;
+
+        self.reload_from_bytes(bytes)
+    }
+
+    /// Adds the given annotation dictionary, with a normal appearance stream drawn using the
+    /// given content, to the annotations of the page at the given index, reloading this
+    /// [PdfDocument]. Returns the index of the new annotation within the page's annotations.
+    pub(crate) fn append_annotation(
+        &mut self,
+        page_index: PdfPageIndex,
+        annotation: PdfSyntaxObject,
+        bounds: PdfRect,
+        color: PdfColor,
+        appearance: &str,
+    ) -> Result<PdfPageAnnotationIndex, PdfiumError> {
+        let (bytes, index) = append_annotation(
+            self.save_for_update()?,
+            page_index,
+            self.pages().len(),
+            annotation,
+            bounds,
+            color,
+            appearance,
+        )?;
+
+        self.reload_from_bytes(bytes)?;
+
+        Ok(index)
+    }
+
+    /// Saves this [PdfDocument] to memory so that an incremental update can be appended to the
+    /// saved copy. If the document contains signatures, it is saved incrementally, so that the
+    /// bytes covered by the existing signatures are preserved.
+    fn save_for_update(&self) -> Result<Vec<u8>, PdfiumError> {
+        if self.signatures().is_empty() {
+            self.save_to_bytes()
+        } else {
+            self.save_incremental_to_bytes()
+        }
+    }
+
+    /// Replaces this [PdfDocument] with a new document loaded from the given bytes, carrying
+    /// over the output version of this [PdfDocument]. The bytes are retained as the source
+    /// of the reloaded document, so later incremental saves append to them.
+    fn reload_from_bytes(&mut self, bytes: Vec<u8>) -> Result<(), PdfiumError> {
+        let mut document = Pdfium::pdfium_document_handle_to_result(
+            self.bindings.FPDF_LoadMemDocument64(bytes.as_slice(), None),
+            self.bindings,
+        )?;
+
+        document.set_source_byte_buffer(bytes);
+        document.output_version = self.output_version;
+
+        *self = document;
+
+        Ok(())
+    }
+
+    /// Returns the ranges of page labels assigned to the pages of this [PdfDocument], in order
+    /// of start index, or an empty list if the document does not label its pages. Use
+    /// `PdfPages::label()` to retrieve the label of a single page.
+    ///
+    /// Pdfium does not expose the page label ranges themselves, so this function saves the
+    /// document to memory and reads them from the saved copy. Returns
+    /// [PdfiumError::PageLabelsUnreadable] if the document is encrypted.
+    #[inline]
+    pub fn page_label_ranges(&self) -> Result<Vec<PdfPageLabelRange>, PdfiumError> {
+        read_page_labels(&self.save_to_bytes()?)
+    }
+
+    /// Replaces the page labels of this [PdfDocument] with the given ranges, or removes all
+    /// page labels if no ranges are given. The first range must start at the first page,
+    /// and each subsequent range must start after the one before it.
+    ///
+    /// Pdfium cannot write page labels, so this function updates the document in the same
+    /// way as [PdfDocument::set_xmp()], preserving any existing signatures. A catalog stored
+    /// in a compressed object stream cannot be updated. Returns
+    /// [PdfiumError::InvalidPageLabelRanges] if
+    /// the ranges are out of order or do not start at the first page,
+    /// [PdfiumError::PageIndexOutOfBounds] if a range starts past the last page, or
+    /// [PdfiumError::DocumentUpdateUnsupported] if the document is encrypted.
+    pub fn set_page_label_ranges(
+        &mut self,
+        ranges: &[PdfPageLabelRange],
+    ) -> Result<(), PdfiumError> {
+        validate_page_label_ranges(ranges, self.pages().len())?;
+
+        let bytes = write_page_labels(self.save_for_update()?, ranges)?;
+
+        self.reload_from_bytes(bytes)
+    }
+
+    /// Creates a new [PdfDocument] for each of the given ranges of page indices, containing
+    /// copies of the pages in that range. Ranges may overlap, and need not be given in order.
+    ///
+    /// Each new document also receives copies of this document's viewer preferences and of
+    /// all the attachments in its [PdfAttachments] collection. Bookmarks are copied into each
+    /// new document if they refer to a page in its range, retargeted to the copied page.
+    /// Bookmarks that refer to other pages are copied without a destination if any of their
+    /// descendants are copied, so that the structure of the bookmark tree is retained.
+    /// Page labels are carried across, so each copied page keeps its label.
+    ///
+    /// Returns [PdfiumError::PageIndexOutOfBounds] if any range is empty or extends past
+    /// the last page of this document.
+    #[inline]
+    pub fn split(
+        &self,
+        ranges: &[RangeInclusive<PdfPageIndex>],
+    ) -> Result<Vec<PdfDocument<'a>>, PdfiumError> {
+        split_document(self, ranges)
+    }
+
+    /// Creates a new [PdfDocument] by tiling the pages of this [PdfDocument] into grids of
+    /// the given number of columns and rows, in reading order, on pages of the given
+    /// [PdfPagePaperSize]. Each source page is scaled to fit its tile.
+    ///
+    /// For example, to print four pages on each side of an A4 sheet:
+    ///
+    /// ```
+    /// document.n_up(2, 2, PdfPagePaperSize::a4())
+    /// ```
+    ///
+    /// Returns [PdfiumError::InvalidImpositionLayout] if either the number of columns or
+    /// the number of rows is zero.
+    #[inline]
+    pub fn n_up(
+        &self,
+        columns: u8,
+        rows: u8,
+        page_size: PdfPagePaperSize,
+    ) -> Result<PdfDocument<'a>, PdfiumError> {
+        n_up_document(self, columns, rows, page_size)
+    }
+
+    /// Creates a new [PdfDocument] laying out the pages of this [PdfDocument] for printing
+    /// as a saddle-stitched booklet, as configured by the given [PdfBookletConfig].
+    ///
+    /// Each page of the new document is one side of a printer sheet holding two source pages
+    /// side by side. Printing the new document double-sided, flipping on the short edge,
+    /// then folding the stacked sheets in half produces a booklet with the source pages in
+    /// order. Blank pages are added to the end of the booklet if the number of source pages
+    /// is not a multiple of four.
+    ///
+    /// Source pages are drawn onto each sheet as form objects, so their content is not
+    /// rasterized. Crop marks, if enabled, are drawn as path objects.
+    ///
+    /// Returns [PdfiumError::NoPagesInDocument] if this document has no pages, or
+    /// [PdfiumError::InvalidImpositionLayout] if the configured margin and gutter leave
+    /// no room for pages on the sheet.
+    #[inline]
+    pub fn booklet(&self, config: &PdfBookletConfig) -> Result<PdfDocument<'a>, PdfiumError> {
+        booklet_document(self, config)
+    }
+
+    /// Flattens every field of the [PdfForm] embedded in this [PdfDocument], if any, drawing
+    /// the current appearance of each field's widgets into the content of the pages that
+    /// display them and removing the widgets, producing a non-editable copy of the
+    /// filled form. Annotations that are not form fields are left untouched.
+    ///
+    /// Use [PdfDocument::flatten_form_fields()] to flatten only some fields, or
+    /// `PdfPage::flatten()` to flatten every annotation on a single page.
+    #[inline]
+    pub fn flatten_form(&mut self) -> Result<(), PdfiumError> {
+        self.flatten_form_fields(|_| true)
+    }
+
+    /// Flattens the fields of the [PdfForm] embedded in this [PdfDocument], if any, for which
+    /// the given predicate returns `true`, drawing the current appearance of each field's
+    /// widgets into the content of the pages that display them and removing the widgets.
+    /// Other fields remain interactive.
+    ///
+    /// Appearance streams are generated for any flattened widgets that lack them, so values
+    /// set using `PdfFormNamedField::set_value()` are drawn correctly. Hidden widgets are
+    /// removed without being drawn.
+    ///
+    /// Pdfium provides no way to remove field definitions from a document's form dictionary,
+    /// so the definitions of flattened fields remain in the document without any widgets.
+    /// They can no longer be seen or edited, but their values can still be read by tools
+    /// that inspect the form dictionary directly.
+    pub fn flatten_form_fields(
+        &mut self,
+        mut predicate: impl FnMut(&PdfFormNamedField) -> bool,
+    ) -> Result<(), PdfiumError> {
+        let form = match self.form() {
+            Some(form) => form,
+            None => return Ok(()),
+        };
+
+        let widgets = form
+            .fields(self.pages())
+            .iter()
+            .filter(|field| predicate(field))
+            .flat_map(|field| field.widgets().iter().copied())
+            .collect::<Vec<_>>();
+
+        flatten_widgets(self, form.handle(), &widgets)
+    }
+
+    /// Returns every document-level JavaScript action in this [PdfDocument], in the order
+    /// in which they are listed in the document. Document-level actions are run by a viewer
+    /// when the document is opened.
+    ///
+    /// JavaScript actions attached to pages, annotations, bookmarks, and form fields are not
+    /// included. The scripts run by form fields can be retrieved using
+    /// `PdfFormNamedField::scripts()`.
+    #[inline]
+    pub fn javascript(&self) -> Vec<PdfJavaScriptAction> {
+        collect_document_javascript(self.handle, self.bindings)
+    }
+
+    /// Removes every JavaScript action from this [PdfDocument], including document-level
+    /// actions, the action run when the document is opened, and the actions attached to
+    /// pages, annotations, bookmarks, and form fields. Actions of other types are kept.
+    /// This is useful for sanitization pipelines that must deliver documents containing
+    /// no scripts.
+    ///
+    /// Pdfium cannot remove actions itself, so this function updates the document in the same
+    /// way as [PdfDocument::create_form_fields()], preserving any existing signatures. Actions
+    /// held in compressed object streams are not read, and so are not removed. The document is
+    /// left unchanged if it contains no JavaScript actions. The removed scripts remain in the reloaded document's
+    /// source bytes, but are not written when the document is next saved.
+    ///
+    /// Returns [PdfiumError::DocumentUpdateUnsupported] if the document is encrypted.
+    pub fn remove_all_javascript(&mut self) -> Result<(), PdfiumError> {
+        match remove_javascript_actions(self.save_for_update()?)? {
+            Some(bytes) => self.reload_from_bytes(bytes),
+            None => Ok(()),
+        }
+    }
+
+    /// Prepares this [PdfDocument] for signing by an external signing service, such as
+    /// a hardware security module or a cloud key management service, returning a copy of
+    /// the document containing the new, unsigned signature field described by the given
+    /// [PdfSignatureFieldBuilder]. This [PdfDocument] is not changed.
+    ///
+    /// Space for the signature is reserved in the `/Contents` entry of the new field's
+    /// signature dictionary, and its `/ByteRange` entry is set to cover the rest of the file.
+    /// Compute the signature over the bytes given by `PdfPreparedSignature::signed_content()`,
+    /// then write it into the copy using `PdfPreparedSignature::into_signed_bytes()`. The
+    /// signed copy must not be changed or saved again using Pdfium, except by appending
+    /// further incremental updates.
+    ///
+    /// Pdfium cannot create signature fields itself, so the field is added in an incremental
+    /// update appended to a saved copy of this [PdfDocument]. If the document already contains
+    /// signatures, the copy is saved incrementally so that the existing signatures remain valid.
+    ///
+    /// Returns [PdfiumError::InvalidFormFieldDefinition] if the builder's name is not valid or
+    /// is the name of an existing field, [PdfiumError::PageIndexOutOfBounds] if the builder
+    /// refers to a page that does not exist, or [PdfiumError::DocumentUpdateUnsupported] if
+    /// the document is encrypted.
+    pub fn prepare_signature(
+        &self,
+        field: &PdfSignatureFieldBuilder,
+    ) -> Result<PdfPreparedSignature, PdfiumError> {
+        let existing_field_names = match self.form() {
+            Some(form) => form
+                .fields(self.pages())
+                .iter()
+                .map(|field| field.name().to_string())
+                .collect(),
+            None => Vec::new(),
+        };
+
+        append_signature_field(
+            self.save_for_update()?,
+            field,
+            self.pages().len(),
+            &existing_field_names,
+        )
+    }
+
+    /// Returns a thumbnail image for every page in this [PdfDocument], in page order, each no
+    /// larger than the given maximum pixel dimension in either direction.
+    ///
+    /// Embedded page thumbnails will be used where available; for pages without a suitable
+    /// embedded thumbnail, a thumbnail will be rendered. See `PdfPage::thumbnail()` for details.
+    pub fn generate_thumbnails(
+        &self,
+        max_dimension: Pixels,
+    ) -> Result<Vec<PdfBitmap<'_>>, PdfiumError> {
+        self.pages()
+            .iter()
+            .map(|page| page.thumbnail(max_dimension))
+            .collect()
+    }
+
+    /// Visits the target of every link annotation in this [PdfDocument] that opens a URI or
+    /// navigates to a page in the same document, passing each target to the given callback.
+    /// The [PdfLinkRewrite] returned by the callback determines whether the link is kept,
+    /// changed to open a different URI, or removed. This is useful for publishing pipelines
+    /// that need to, for example, force all links to use HTTPS or strip tracking parameters.
+    ///
+    /// Returns the total number of links that were changed or removed. Changes are
+    /// persisted when this [PdfDocument] is saved.
+    ///
+    /// Pdfium does not provide any way to set the destination page of a link, so links
+    /// retargeted to a page using [PdfLinkRewrite::SetPage] are written directly into the
+    /// document's file when this [PdfDocument] is saved. Until then, Pdfium continues to
+    /// report each link's original target, although subsequent calls to this function pass
+    /// the new target to the callback. Returns [PdfiumError::PageIndexOutOfBounds] if the
+    /// callback retargets a link to a page that does not exist.
+    pub fn rewrite_links<F>(&mut self, mut callback: F) -> Result<usize, PdfiumError>
+    where
+        F: FnMut(&PdfLinkTarget) -> PdfLinkRewrite,
+    {
+        let mut changes = 0;
+
+        let page_count = self.pages().len();
+
+        for mut page in self.pages().iter() {
+            changes += rewrite_links_on_page(&mut page, page_count, &mut callback)?;
+        }
+
+        Ok(changes)
+    }
+
+    /// Returns a [PdfDocumentLinks] iterator over every clickable link in this [PdfDocument],
+    /// in page order, combining link annotations with URLs and email addresses detected by
+    /// Pdfium in the text of each page. Each `PdfLinkHit` gives the link's page, clickable area,
+    /// and target, with local and named destinations resolved to page indices.
+    ///
+    /// Duplicate links are skipped, as are web links whose area is already covered by a link
+    /// annotation with the same URI. A web link that wraps across several lines of text is
+    /// returned once for each line. Link annotations whose target cannot be resolved,
+    /// for example those that run JavaScript, are skipped.
+    ///
+    /// To list each URI referenced by this [PdfDocument] only once, including URIs
+    /// referenced by bookmarks, use the [PdfDocument::extract_links()] function instead.
+    #[inline]
+    pub fn links<'b>(&'b self) -> PdfDocumentLinks<'a, 'b> {
+        PdfDocumentLinks::new(self.pages())
+    }
+
+    /// Returns every URI and email address referenced by this [PdfDocument], combining
+    /// the URI actions of link annotations, URLs and email addresses detected by Pdfium in the
+    /// text of each page, and the URI actions of bookmarks. This is useful for compliance
+    /// scanning and link analytics.
+    ///
+    /// Links are returned in page order, followed by any links found only in bookmarks.
+    /// A URI referenced several times on the same page is returned once;
+    /// see [PdfExtractedLink] for details.
+    pub fn extract_links(&self) -> Result<Vec<PdfExtractedLink>, PdfiumError> {
+        let mut links = PdfExtractedLinks::new();
+
+        for (index, page) in self.pages().iter().enumerate() {
+            links.push_page(index as PdfPageIndex, &page)?;
+        }
+
+        links.push_bookmarks(self.bookmarks());
+
+        Ok(links.into_vec())
+    }
+
+    /// Reports, for every font used by the text on any page in this [PdfDocument], which
+    /// characters in the given strings that font has no glyph for. This is useful for choosing
+    /// a font that can safely display user-entered text, such as names written in arbitrary
+    /// scripts, before writing that text into the document.
+    ///
+    /// Fonts are returned in the order in which they are first used. Whitespace and control
+    /// characters are not checked. Pdfium can draw glyphs missing from a font using a
+    /// fallback font; such characters are not reported as missing, so the returned
+    /// coverage should be treated as a best-case estimate.
+    ///
+    /// Pdfium releases a font once no open page uses it, so every page in the document is
+    /// kept open until the coverage of every font has been measured.
+    pub fn font_coverage(&self, strings: &[&str]) -> Result<Vec<PdfFontCoverage>, PdfiumError> {
+        let chars = distinct_chars(strings);
+
+        Ok(self
+            .used_fonts()
+            .iter()
+            .map(|font| PdfFontCoverage::from_pdf_font(font, &chars, self.bindings()))
+            .collect())
+    }
+
+    /// Returns every font used by the text on any page in this [PdfDocument], including text
+    /// inside form XObjects, in the order in which each font is first used. Each font is
+    /// returned once, no matter how many text objects use it.
+    ///
+    /// Unlike the [PdfFonts] collection returned by [PdfDocument::fonts()], which holds only
+    /// fonts added to this [PdfDocument] by this library, the returned fonts are those the
+    /// document's pages actually reference. This is useful for preflight checks: use
+    /// `PdfFont::is_embedded()` to verify that every font is embedded, and `PdfFont::data()`
+    /// to extract each embedded font program.
+    ///
+    /// Pdfium releases a font once no open page uses it, so the returned [PdfUsedFonts]
+    /// collection keeps every page in this [PdfDocument] open for as long as it exists.
+    pub fn used_fonts(&self) -> PdfUsedFonts<'_> {
+        PdfUsedFonts::from_pages(self.pages().iter().collect(), self.bindings())
+    }
+
+    /// Returns the names of the optional content groups, or layers, used by the page objects
+    /// on every page in this [PdfDocument], in the order in which they are first used.
+    ///
+    /// Pdfium does not expose a document's list of optional content groups directly, so layers
+    /// are discovered from the marked content on each page; a layer defined in the document
+    /// but not used on any page is not returned. Layers hidden by the document's default layer
+    /// configuration are returned. Pass any of the returned names to the
+    /// [PdfRenderConfig::set_layer_visibility()] function to show or hide that layer during
+    /// rendering.
+    pub fn layers(&self) -> Vec<String> {
+        let mut names = Vec::new();
+
+        for page in self.pages().iter() {
+            collect_page_layer_names(page.page_handle(), &mut names, self.bindings());
+        }
+
+        names
+    }
+
+    /// Returns a [PdfTextIndex] containing the full text of every page in this [PdfDocument],
+    /// together with the bounding box of every character. A search backend can store the
+    /// compact encoding of the returned index alongside its own index and use it to return
+    /// highlight coordinates for search hits without reopening this [PdfDocument].
+    pub fn build_text_index(&self) -> Result<PdfTextIndex, PdfiumError> {
+        let mut pages = Vec::new();
+
+        for (index, page) in self.pages().iter().enumerate() {
+            pages.push(PdfPageTextIndex::from_page_text(
+                index as PdfPageIndex,
+                &page.text()?,
+            ));
+        }
+
+        Ok(PdfTextIndex::from_pages(pages))
+    }
+
+    /// Lays out the given [PdfTextFlow] inside the given frame rectangle, starting on the page
+    /// at the given zero-based index. When that page is full, the flow continues onto new pages
+    /// of the same size, inserted directly after it, with the same frame rectangle applied to
+    /// each page. See [PdfTextFlow] for details of how page breaks are chosen.
+    ///
+    /// Returns the text objects created on each page, in page order.
+    pub fn flow_text(
+        &mut self,
+        flow: &PdfTextFlow,
+        first_page_index: PdfPageIndex,
+        frame: PdfRect,
+    ) -> Result<Vec<PdfTextFlowPage>, PdfiumError> {
+        flow.flow(self, first_page_index, frame)
+    }
+
+    /// Returns a [PdfRenderIterator] that renders every page in this [PdfDocument], in page
+    /// order, using the given [PdfRenderConfig]. Pages are rendered lazily, one at a time, and
+    /// a single bitmap is reused between pages wherever possible, keeping memory usage bounded
+    /// irrespective of the number of pages in the document.
+    #[inline]
+    pub fn render_iter<'b>(&'b self, config: &'b PdfRenderConfig) -> PdfRenderIterator<'a, 'b> {
+        PdfRenderIterator::new(self.pages(), config)
+    }
+
+    /// Returns a [PdfDocumentSearch] iterator over every occurrence of the given text in every
+    /// page in this [PdfDocument], in page order, using the given [PdfSearchOptions].
+    ///
+    /// Pages are searched lazily as results are requested, so stopping iteration early avoids
+    /// searching the remaining pages. Each [PdfSearchResult] owns its data and outlives the
+    /// page it was found on. To step backwards and forwards through the results on a single
+    /// page, use the `PdfPageText::search()` function instead.
+    #[inline]
+    pub fn search<'b>(
+        &'b self,
+        text: &str,
+        options: &PdfSearchOptions,
+    ) -> PdfDocumentSearch<'a, 'b> {
+        PdfDocumentSearch::new(self.pages(), text, options)
+    }
+
+    /// Returns a [PdfDocumentSearch] iterator over every match of the given regular expression
+    /// in every page in this [PdfDocument], in page order.
+    ///
+    /// The pattern is matched against a normalized copy of each page's text, in which runs of
+    /// whitespace, including line breaks, are collapsed into single spaces, and a hyphen
+    /// followed by a line break is removed if it has letters on both sides, so that words
+    /// broken across lines can be matched as a whole. Other hyphens, such as those in numeric
+    /// ranges like "3-4", are kept. Each [PdfSearchResult] reports the range of page characters
+    /// spanned by the match, including any removed hyphens and line breaks, along with the
+    /// match's bounds on the page. Empty matches are ignored.
+    ///
+    /// The normalized text of each page is cached the first time the page is searched, and
+    /// reused by later calls to this function and to the `PdfDocument::search_fuzzy()` function
+    /// until the page's content changes or this [PdfDocument] is dropped.
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub fn search_regex<'b>(&'b self, pattern: &Regex) -> PdfDocumentSearch<'a, 'b> {
+        PdfDocumentSearch::new_regex(self.pages(), pattern)
+    }
+
+    /// Returns a [PdfDocumentSearch] iterator over every approximate, case-insensitive match
+    /// of the given text in every page in this [PdfDocument], in page order. A match is any
+    /// run of page text that can be turned into the given text with at most `max_edits`
+    /// single-character insertions, deletions, or substitutions. Where several candidate
+    /// matches overlap, only the candidate needing the fewest edits is returned. This is
+    /// useful for finding text in documents with imperfect OCR text layers.
+    ///
+    /// Page text is normalized in the same way as for the `PdfDocument::search_regex()`
+    /// function. No results are returned if `max_edits` is not smaller than the length
+    /// of the given text, since such a search would match everywhere.
+    #[inline]
+    pub fn search_fuzzy<'b>(&'b self, text: &str, max_edits: usize) -> PdfDocumentSearch<'a, 'b> {
+        PdfDocumentSearch::new_fuzzy(self.pages(), text, max_edits)
+    }
+
+    /// Renders every page in this [PdfDocument], in page order, into a sequence of identically
+    /// sized frames of raw RGBA pixel data, writing the frames to the given sink. This is useful
+    /// for generating page-turn videos or flipbooks, for example by piping the output into
+    /// an `ffmpeg` process. See [PdfRenderSequenceConfig] for details of the frame format
+    /// and the available sizing and letterboxing options.
+    ///
+    /// Returns the total number of frames written.
+    pub fn render_sequence<W: Write>(
+        &self,
+        config: &PdfRenderSequenceConfig,
+        frame_sink: W,
+    ) -> Result<usize, PdfiumError> {
+        config.render(self.pages(), frame_sink)
+    }
+
+    /// Renders every page in this [PdfDocument] at the given resolution, in dots per inch,
+    /// and returns a deterministic hash of each rendered page, in page order.
+    ///
+    /// Comparing the returned digests against digests recorded from a known-good rendering is
+    /// a lightweight way of detecting visual regressions. Pages are rendered with annotations
+    /// and form data into [PdfBitmapFormat::BGRA] bitmaps; see [PdfBitmap::content_hash()]
+    /// for details of how each digest is calculated.
+    pub fn render_digests(&self, dpi: f32) -> Result<Vec<u64>, PdfiumError> {
+        let config = PdfRenderConfig::new()
+            .scale_page_by_factor(dpi / 72.0)
+            .set_format(PdfBitmapFormat::BGRA)
+            .render_annotations(true)
+            .render_form_data(true);
+
+        self.pages()
+            .iter()
+            .map(|page| {
+                page.render_with_config(&config)
+                    .map(|bitmap| bitmap.content_hash())
+            })
+            .collect()
+    }
+
     /// Writes this [PdfDocument] to the given writer.
-    pub fn save_to_writer<W: Write + 'static>(&self, writer: &mut W) -> Result<(), PdfiumError> {
-        // TODO: AJRC - 25/5/22 - investigate supporting the FPDF_INCREMENTAL, FPDF_NO_INCREMENTAL,
-        // and FPDF_REMOVE_SECURITY flags defined in fpdf_save.h. There's not a lot of information
+    ///
+    /// Pdfium streams the document into the writer as it is serialized, so the document
+    /// is never held in memory in its entirety. If the writer returns an error, Pdfium
+    /// abandons the save and the writer's error is returned as [PdfiumError::IoError].
+    ///
+    /// If the document's [PdfMetadata] or [PdfBookmarks] have been changed, or page objects
+    /// have been bound to new structure elements, the document is serialized into memory
+    /// first, so that the changes can be appended to it.
+    #[inline]
+    pub fn save_to_writer<W: Write>(&self, writer: &mut W) -> Result<(), PdfiumError> {
+        // TODO: AJRC - 25/5/22 - investigate supporting the FPDF_NO_INCREMENTAL and
+        // FPDF_REMOVE_SECURITY flags defined in fpdf_save.h. There's not a lot of information
         // on what they actually do, however.
         // Some small info at https://forum.patagames.com/posts/t155-PDF-SaveFlags.
 
-        let flags = 0;
+        self.save_to_writer_with_flags(writer, 0)
+    }
+
+    /// Writes this [PdfDocument] to the given writer, passing the given `FPDF_*` save flags
+    /// to Pdfium. When the `FPDF_INCREMENTAL` flag is set, Pdfium writes the document's
+    /// original file content unchanged, followed by an incremental update.
+    pub(crate) fn save_to_writer_with_flags<W: Write>(
+        &self,
+        writer: &mut W,
+        flags: FPDF_DWORD,
+    ) -> Result<(), PdfiumError> {
+        if !self.metadata.is_modified()
+            && !self.bookmarks.is_modified()
+            && !self.structure_tree.is_modified()
+            && !PdfAnnotationDictionaryEdits::is_modified(self.handle)
+            && !PdfPageAdditionalActionsEdits::is_modified(self.handle)
+        {
+            return self.save_to_writer_with_pdfium(writer, flags);
+        }
+
+        // Pdfium cannot write the document's metadata, outline, or structure tree, some
+        // annotation properties, or the removal of page additional actions, so apply the changes to the serialized document before
+        // passing it to the writer.
+
+        let mut bytes = Vec::new();
+
+        self.save_to_writer_with_pdfium(&mut bytes, flags)?;
+
+        if self.metadata.is_modified() {
+            bytes = self.metadata.write_to_file(bytes)?;
+        }
+
+        if self.bookmarks.is_modified() {
+            bytes = self.bookmarks.write_to_file(bytes)?;
+        }
+
+        bytes = self.structure_tree.write_to_file(bytes)?;
+
+        bytes = PdfAnnotationDictionaryEdits::write_to_file(self.handle, bytes)?;
+
+        bytes = PdfPageAdditionalActionsEdits::write_to_file(self.handle, bytes)?;
 
+        writer
+            .write_all(&bytes)
+            .and_then(|_| writer.flush())
+            .map_err(PdfiumError::IoError)
+    }
+
+    /// Writes this [PdfDocument] to the given writer exactly as Pdfium serializes it,
+    /// passing the given `FPDF_*` save flags to Pdfium.
+    fn save_to_writer_with_pdfium<W: Write>(
+        &self,
+        writer: &mut W,
+        flags: FPDF_DWORD,
+    ) -> Result<(), PdfiumError> {
         let mut pdfium_file_writer = get_pdfium_file_writer_from_writer(writer);
 
         let result = match self.output_version {
@@ -343,15 +1438,61 @@ impl<'a> PdfDocument<'a> {
                 pdfium_file_writer.flush().map_err(PdfiumError::IoError)
             }
             false => {
-                // Pdfium's return value indicated failure.
-
-                Err(PdfiumError::PdfiumLibraryInternalError(
-                    PdfiumInternalError::Unknown,
-                ))
+                // Pdfium's return value indicated failure. If the failure was caused by
+                // the writer, return the writer's error.
+
+                match pdfium_file_writer.take_error() {
+                    Some(err) => Err(PdfiumError::IoError(err)),
+                    None => Err(PdfiumError::PdfiumLibraryInternalError(
+                        PdfiumInternalError::Unknown,
+                    )),
+                }
             }
         }
     }
 
+    /// Writes this [PdfDocument] to the given writer as an incremental update: the bytes of
+    /// the file from which the document was loaded are written unchanged, followed by an
+    /// update section containing only the objects that have changed since the document
+    /// was loaded.
+    ///
+    /// Digital signatures cover a fixed range of the bytes of a file, so any change saved
+    /// to a signed document must be saved incrementally, or the existing signatures will
+    /// no longer verify. Note that some changes, such as editing a signed page's content,
+    /// may still be rejected by a viewer enforcing the signature's modification permissions.
+    ///
+    /// A document that was created using `Pdfium::create_new_pdf()` has no original bytes
+    /// to preserve, so it is written in full.
+    #[inline]
+    pub fn save_incremental_to_writer<W: Write>(&self, writer: &mut W) -> Result<(), PdfiumError> {
+        self.save_to_writer_with_flags(writer, FPDF_INCREMENTAL as FPDF_DWORD)
+    }
+
+    /// Writes this [PdfDocument] to the file at the given path as an incremental update.
+    /// See [PdfDocument::save_incremental_to_writer()] for details.
+    ///
+    /// The path must not be the path of the file from which this document was loaded, since
+    /// Pdfium may still need to read the original file content while it is being written.
+    ///
+    /// This function is not available when compiling to WASM.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn save_incremental_to_file(
+        &self,
+        path: &(impl AsRef<Path> + ?Sized),
+    ) -> Result<(), PdfiumError> {
+        self.save_incremental_to_writer(&mut File::create(path).map_err(PdfiumError::IoError)?)
+    }
+
+    /// Writes this [PdfDocument] to a new byte buffer as an incremental update, returning
+    /// the byte buffer. See [PdfDocument::save_incremental_to_writer()] for details.
+    pub fn save_incremental_to_bytes(&self) -> Result<Vec<u8>, PdfiumError> {
+        let mut cursor = Cursor::new(Vec::new());
+
+        self.save_incremental_to_writer(&mut cursor)?;
+
+        Ok(cursor.into_inner())
+    }
+
     /// Writes this [PdfDocument] to the file at the given path.
     ///
     /// This function is not available when compiling to WASM. You have several options for
@@ -374,6 +1515,32 @@ impl<'a> PdfDocument<'a> {
         Ok(cursor.into_inner())
     }
 
+    /// Serializes the current in-memory state of this [PdfDocument] to a new byte buffer,
+    /// returning the byte buffer. The buffer can be opened as a separate, independent
+    /// [PdfDocument], for instance using the `Pdfium::load_pdf_from_byte_vec()` function or
+    /// the `PdfDocumentAsync::load_from_byte_vec()` function.
+    ///
+    /// Pdfium is single-threaded, so a single [PdfDocument] cannot be rendered on one thread
+    /// while it is being edited on another. A snapshot lets a background worker render or
+    /// extract text from a consistent, read-only copy of the document while the original
+    /// continues to be edited. Changes made to either document after the snapshot is taken
+    /// are not reflected in the other; take a new snapshot whenever the worker should see
+    /// the latest edits. Note that individual calls into Pdfium are still serialized when
+    /// the `thread_safe` crate feature is enabled.
+    ///
+    /// The snapshot includes all changes made to this document so far, with one exception:
+    /// changes to the page objects of a `PdfPage` using the
+    /// `PdfPageContentRegenerationStrategy::Manual` content regeneration strategy are only
+    /// included once the page's content has been regenerated by calling
+    /// `PdfPage::regenerate_content()`. If this document is encrypted, the snapshot is
+    /// encrypted too, and must be opened using the same password.
+    ///
+    /// This is equivalent to calling [PdfDocument::save_to_bytes()].
+    #[inline]
+    pub fn snapshot_bytes(&self) -> Result<Vec<u8>, PdfiumError> {
+        self.save_to_bytes()
+    }
+
     /// Writes this [PdfDocument] to a new `Blob`, returning the `Blob`.
     ///
     /// This function is only available when compiling to WASM.
@@ -393,6 +1560,33 @@ impl<'a> PdfDocument<'a> {
     }
 }
 
+/// Saves a copy of the document with the given handle to a new byte buffer, exactly as
+/// Pdfium serializes it. This is used by collections that hold only a document handle but
+/// need to read parts of the document that Pdfium does not expose.
+pub(crate) fn save_document_to_bytes(
+    document_handle: FPDF_DOCUMENT,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<Vec<u8>, PdfiumError> {
+    let mut bytes = Vec::new();
+
+    let mut writer = get_pdfium_file_writer_from_writer(&mut bytes);
+
+    if !bindings.is_true(bindings.FPDF_SaveAsCopy(
+        document_handle,
+        writer.as_fpdf_file_write_mut_ptr(),
+        0,
+    )) {
+        return Err(match writer.take_error() {
+            Some(err) => PdfiumError::IoError(err),
+            None => PdfiumError::PdfiumLibraryInternalError(PdfiumInternalError::Unknown),
+        });
+    }
+
+    drop(writer);
+
+    Ok(bytes)
+}
+
 impl<'a> Drop for PdfDocument<'a> {
     /// Closes this [PdfDocument], releasing held memory and, if the document was loaded
     /// from a file, the file handle on the document.
@@ -404,6 +1598,11 @@ impl<'a> Drop for PdfDocument<'a> {
 
         self.form = None;
         self.bindings.FPDF_CloseDocument(self.handle);
+
+        PdfContentGenerations::close_document(self.handle);
+        PdfAnnotationDictionaryEdits::close_document(self.handle);
+        PdfPageAdditionalActionsEdits::close_document(self.handle);
+        PdfNormalizedTextCache::close_document(self.handle);
     }
 }
 
@@ -421,3 +1620,216 @@ unsafe impl<'a> Sync for PdfDocument<'a> {}
 
 #[cfg(feature = "sync")]
 unsafe impl<'a> Send for PdfDocument<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_save_incremental_to_bytes() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let original = std::fs::read("./test/signatures-test.pdf").unwrap();
+
+        let mut document = pdfium.load_pdf_from_byte_vec(original.clone(), None)?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let bytes = document.save_incremental_to_bytes()?;
+
+        // The original bytes are preserved unchanged, followed by the update.
+
+        assert!(bytes.len() > original.len());
+        assert_eq!(&bytes[..original.len()], original.as_slice());
+
+        let reloaded = pdfium.load_pdf_from_byte_vec(bytes, None)?;
+
+        assert_eq!(reloaded.pages().len(), document.pages().len());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot_bytes() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let snapshot = pdfium.load_pdf_from_byte_vec(document.snapshot_bytes()?, None)?;
+
+        // Edits made to the original document after the snapshot was taken
+        // are not reflected in the snapshot.
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        assert_eq!(snapshot.pages().len(), 1);
+        assert_eq!(document.pages().len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let font = document.fonts_mut().helvetica();
+
+        for text in ["A needle in a haystack", "No match here", "Needle, needle"] {
+            let mut page = document
+                .pages_mut()
+                .create_page_at_end(PdfPagePaperSize::a4())?;
+
+            page.objects_mut().create_text_object(
+                PdfPoints::new(50.0),
+                PdfPoints::new(700.0),
+                text,
+                font,
+                PdfPoints::new(12.0),
+            )?;
+        }
+
+        let results = document
+            .search("needle", &PdfSearchOptions::new())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|result| result.page_index())
+                .collect::<Vec<_>>(),
+            vec![0, 2, 2]
+        );
+
+        assert_eq!(results[0].char_range(), 2..8);
+        assert_eq!(results[0].bounding_rects().len(), 1);
+
+        let results = document.search("needle", &PdfSearchOptions::new().match_case(true));
+
+        assert_eq!(results.count(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_search_fuzzy() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let font = document.fonts_mut().helvetica();
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        page.objects_mut().create_text_object(
+            PdfPoints::new(50.0),
+            PdfPoints::new(700.0),
+            "The qu1ck brown fox",
+            font,
+            PdfPoints::new(12.0),
+        )?;
+
+        let results = document.search_fuzzy("Quick", 1).collect::<Vec<_>>();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].char_range(), 4..9);
+
+        assert_eq!(document.search_fuzzy("Quick", 0).count(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_generate_thumbnails() -> Result<(), PdfiumError> {
+        // The first page of the test file has an embedded 20 x 10 pixel thumbnail;
+        // the second page has none, so its thumbnail is rendered.
+
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/thumbnail-test.pdf", None)?;
+
+        let thumbnails = document.generate_thumbnails(64)?;
+
+        assert_eq!(thumbnails.len(), 2);
+        assert_eq!((thumbnails[0].width(), thumbnails[0].height()), (20, 10));
+        assert_ne!((thumbnails[1].width(), thumbnails[1].height()), (20, 10));
+        assert!(thumbnails[1].width() <= 64 && thumbnails[1].height() <= 64);
+
+        // No embedded thumbnail fits within a smaller maximum dimension, so every
+        // thumbnail is rendered.
+
+        for thumbnail in document.generate_thumbnails(16)? {
+            assert!(thumbnail.width() <= 16 && thumbnail.height() <= 16);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_used_fonts() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let fonts = document.used_fonts();
+
+        assert!(!fonts.is_empty());
+
+        assert_eq!(
+            fonts.len(),
+            document.font_coverage(&[])?.len(),
+            "each used font should be listed once"
+        );
+
+        // The fonts remain valid while the collection keeps the document's pages open.
+
+        for font in fonts.iter() {
+            assert!(!font.family().is_empty());
+
+            if font.is_embedded()? {
+                assert!(!font.data()?.is_empty());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_update_preserves_signatures() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let original = std::fs::read("./test/signatures-test.pdf").unwrap();
+
+        let mut document = pdfium.load_pdf_from_byte_vec(original.clone(), None)?;
+
+        let signatures = document.signatures().len();
+
+        assert!(signatures > 0);
+
+        document.set_xmp("<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"></x:xmpmeta>")?;
+
+        // The signed bytes are carried through the reload, and through a later incremental save.
+
+        assert_eq!(document.signatures().len(), signatures);
+
+        let saved = document.save_incremental_to_bytes()?;
+
+        assert!(saved.starts_with(&original));
+
+        assert!(document.xmp()?.unwrap().contains("x:xmpmeta"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,381 @@
+//! Defines the [PdfAnnotationSummary] struct, a snapshot of the review status of all
+//! the annotations in a `PdfDocument`.
+
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::{PdfPageAnnotationCommon, PdfPageAnnotationType};
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use crate::utils::dates::date_time_from_pdf_string;
+use crate::utils::json::json_string;
+use chrono::prelude::*;
+
+/// A summary of the annotations attached to a single page in a `PdfDocument`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfPageAnnotationSummary {
+    page_index: PdfPageIndex,
+    total: usize,
+    counts_by_type: Vec<(PdfPageAnnotationType, usize)>,
+    authors: Vec<String>,
+    earliest_date: Option<DateTime<Utc>>,
+    latest_date: Option<DateTime<Utc>>,
+    replies: usize,
+}
+
+impl PdfPageAnnotationSummary {
+    pub(crate) fn from_page(
+        page_index: PdfPageIndex,
+        page: &PdfPage,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Self {
+        let mut summary = PdfPageAnnotationSummary {
+            page_index,
+            total: 0,
+            counts_by_type: Vec::new(),
+            authors: Vec::new(),
+            earliest_date: None,
+            latest_date: None,
+            replies: 0,
+        };
+
+        for annotation in page.annotations().iter() {
+            summary.total += 1;
+
+            let annotation_type = annotation.annotation_type();
+
+            match summary
+                .counts_by_type
+                .iter_mut()
+                .find(|(existing, _)| *existing == annotation_type)
+            {
+                Some((_, count)) => *count += 1,
+                None => summary.counts_by_type.push((annotation_type, 1)),
+            }
+
+            if let Some(author) = annotation.creator() {
+                if !author.is_empty() && !summary.authors.contains(&author) {
+                    summary.authors.push(author);
+                }
+            }
+
+            for date in [annotation.creation_date(), annotation.modification_date()]
+                .iter()
+                .flatten()
+                .filter_map(|date| date_time_from_pdf_string(date))
+            {
+                summary.include_date(date);
+            }
+
+            // An annotation that is a reply to another annotation carries an "IRT"
+            // ("in reply to") entry in its annotation dictionary.
+
+            if bindings.is_true(bindings.FPDFAnnot_HasKey(annotation.handle(), "IRT")) {
+                summary.replies += 1;
+            }
+        }
+
+        summary
+    }
+
+    #[inline]
+    fn include_date(&mut self, date: DateTime<Utc>) {
+        if self
+            .earliest_date
+            .map(|earliest| date < earliest)
+            .unwrap_or(true)
+        {
+            self.earliest_date = Some(date);
+        }
+
+        if self.latest_date.map(|latest| date > latest).unwrap_or(true) {
+            self.latest_date = Some(date);
+        }
+    }
+
+    /// Returns the zero-based index of the page summarized by this [PdfPageAnnotationSummary].
+    #[inline]
+    pub fn page_index(&self) -> PdfPageIndex {
+        self.page_index
+    }
+
+    /// Returns the total number of annotations on the page, including replies.
+    #[inline]
+    pub fn total(&self) -> usize {
+        self.total
+    }
+
+    /// Returns the number of annotations on the page of each [PdfPageAnnotationType],
+    /// in the order in which each type was first encountered on the page.
+    #[inline]
+    pub fn counts_by_type(&self) -> &[(PdfPageAnnotationType, usize)] {
+        self.counts_by_type.as_slice()
+    }
+
+    /// Returns the number of annotations on the page of the given [PdfPageAnnotationType].
+    pub fn count_of_type(&self, annotation_type: PdfPageAnnotationType) -> usize {
+        self.counts_by_type
+            .iter()
+            .find(|(existing, _)| *existing == annotation_type)
+            .map(|(_, count)| *count)
+            .unwrap_or(0)
+    }
+
+    /// Returns the distinct authors of the annotations on the page, in the order in which
+    /// each author was first encountered on the page.
+    #[inline]
+    pub fn authors(&self) -> &[String] {
+        self.authors.as_slice()
+    }
+
+    /// Returns the earliest creation or modification date of any annotation on the page, if any.
+    #[inline]
+    pub fn earliest_date(&self) -> Option<DateTime<Utc>> {
+        self.earliest_date
+    }
+
+    /// Returns the latest creation or modification date of any annotation on the page, if any.
+    #[inline]
+    pub fn latest_date(&self) -> Option<DateTime<Utc>> {
+        self.latest_date
+    }
+
+    /// Returns the number of annotations on the page that are replies to other annotations.
+    #[inline]
+    pub fn replies(&self) -> usize {
+        self.replies
+    }
+
+    /// Returns a JSON representation of this [PdfPageAnnotationSummary].
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"page_index\":{},\"total\":{},\"counts_by_type\":{{{}}},\"authors\":[{}],\"earliest_date\":{},\"latest_date\":{},\"replies\":{}}}",
+            self.page_index,
+            self.total,
+            self.counts_by_type
+                .iter()
+                .map(|(annotation_type, count)| format!(
+                    "{}:{}",
+                    json_string(&format!("{:?}", annotation_type)),
+                    count
+                ))
+                .collect::<Vec<_>>()
+                .join(","),
+            self.authors
+                .iter()
+                .map(|author| json_string(author))
+                .collect::<Vec<_>>()
+                .join(","),
+            json_date(self.earliest_date),
+            json_date(self.latest_date),
+            self.replies,
+        )
+    }
+}
+
+/// A summary of all the annotations in a `PdfDocument`, broken down by page.
+/// A [PdfAnnotationSummary] is a snapshot; it will not reflect any changes made to the
+/// document's annotations after it was created.
+///
+/// Use the [PdfAnnotationSummary::to_json()] function to serialize the summary for
+/// consumption by reporting or review tracking tools.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfAnnotationSummary {
+    pages: Vec<PdfPageAnnotationSummary>,
+}
+
+impl PdfAnnotationSummary {
+    pub(crate) fn from_pages(pages: &PdfPages) -> Self {
+        PdfAnnotationSummary {
+            pages: pages
+                .iter()
+                .enumerate()
+                .map(|(index, page)| {
+                    PdfPageAnnotationSummary::from_page(
+                        index as PdfPageIndex,
+                        &page,
+                        pages.bindings(),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the per-page summaries in this [PdfAnnotationSummary], one for each page
+    /// in the document, in page order.
+    #[inline]
+    pub fn pages(&self) -> &[PdfPageAnnotationSummary] {
+        self.pages.as_slice()
+    }
+
+    /// Returns the total number of annotations in the document, including replies.
+    pub fn total(&self) -> usize {
+        self.pages.iter().map(|page| page.total()).sum()
+    }
+
+    /// Returns the number of annotations in the document of each [PdfPageAnnotationType],
+    /// in the order in which each type was first encountered in the document.
+    pub fn counts_by_type(&self) -> Vec<(PdfPageAnnotationType, usize)> {
+        let mut result: Vec<(PdfPageAnnotationType, usize)> = Vec::new();
+
+        for (annotation_type, count) in self.pages.iter().flat_map(|page| page.counts_by_type()) {
+            match result
+                .iter_mut()
+                .find(|(existing, _)| existing == annotation_type)
+            {
+                Some((_, total)) => *total += count,
+                None => result.push((*annotation_type, *count)),
+            }
+        }
+
+        result
+    }
+
+    /// Returns the distinct authors of all annotations in the document, in the order in which
+    /// each author was first encountered in the document.
+    pub fn authors(&self) -> Vec<String> {
+        let mut result: Vec<String> = Vec::new();
+
+        for author in self.pages.iter().flat_map(|page| page.authors()) {
+            if !result.contains(author) {
+                result.push(author.clone());
+            }
+        }
+
+        result
+    }
+
+    /// Returns the earliest creation or modification date of any annotation in the document, if any.
+    pub fn earliest_date(&self) -> Option<DateTime<Utc>> {
+        self.pages
+            .iter()
+            .filter_map(|page| page.earliest_date())
+            .min()
+    }
+
+    /// Returns the latest creation or modification date of any annotation in the document, if any.
+    pub fn latest_date(&self) -> Option<DateTime<Utc>> {
+        self.pages
+            .iter()
+            .filter_map(|page| page.latest_date())
+            .max()
+    }
+
+    /// Returns the number of annotations in the document that are replies to other annotations.
+    pub fn replies(&self) -> usize {
+        self.pages.iter().map(|page| page.replies()).sum()
+    }
+
+    /// Returns a JSON representation of this [PdfAnnotationSummary], containing both
+    /// document-wide totals and the individual summary for each page.
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"total\":{},\"counts_by_type\":{{{}}},\"authors\":[{}],\"earliest_date\":{},\"latest_date\":{},\"replies\":{},\"pages\":[{}]}}",
+            self.total(),
+            self.counts_by_type()
+                .iter()
+                .map(|(annotation_type, count)| format!(
+                    "{}:{}",
+                    json_string(&format!("{:?}", annotation_type)),
+                    count
+                ))
+                .collect::<Vec<_>>()
+                .join(","),
+            self.authors()
+                .iter()
+                .map(|author| json_string(author))
+                .collect::<Vec<_>>()
+                .join(","),
+            json_date(self.earliest_date()),
+            json_date(self.latest_date()),
+            self.replies(),
+            self.pages
+                .iter()
+                .map(|page| page.to_json())
+                .collect::<Vec<_>>()
+                .join(","),
+        )
+    }
+}
+
+#[inline]
+fn json_date(date: Option<DateTime<Utc>>) -> String {
+    match date {
+        Some(date) => json_string(&date.to_rfc3339()),
+        None => "null".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+    use chrono::prelude::*;
+
+    #[test]
+    fn test_annotation_summary() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        // The test document contains a single page with a square annotation and a circle
+        // annotation by "Author", and three text annotations by "Reviewer" replying to
+        // the square annotation. Only modification dates are set.
+
+        let document = pdfium.load_pdf_from_file("./test/replies-test.pdf", None)?;
+
+        let summary = document.annotation_summary();
+
+        assert_eq!(summary.pages().len(), 1);
+        assert_eq!(summary.total(), 5);
+        assert_eq!(
+            summary.counts_by_type(),
+            vec![
+                (PdfPageAnnotationType::Square, 1),
+                (PdfPageAnnotationType::Text, 3),
+                (PdfPageAnnotationType::Circle, 1),
+            ]
+        );
+        assert_eq!(
+            summary.authors(),
+            vec!["Author".to_string(), "Reviewer".to_string()]
+        );
+        assert_eq!(
+            summary.earliest_date(),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 9, 0, 0).unwrap())
+        );
+        assert_eq!(
+            summary.latest_date(),
+            Some(Utc.with_ymd_and_hms(2024, 1, 3, 9, 0, 0).unwrap())
+        );
+        assert_eq!(summary.replies(), 3);
+
+        let page = &summary.pages()[0];
+
+        assert_eq!(page.page_index(), 0);
+        assert_eq!(page.count_of_type(PdfPageAnnotationType::Text), 3);
+        assert_eq!(page.count_of_type(PdfPageAnnotationType::Highlight), 0);
+
+        let page_json = "{\"page_index\":0,\"total\":5,\
+            \"counts_by_type\":{\"Square\":1,\"Text\":3,\"Circle\":1},\
+            \"authors\":[\"Author\",\"Reviewer\"],\
+            \"earliest_date\":\"2024-01-01T09:00:00+00:00\",\
+            \"latest_date\":\"2024-01-03T09:00:00+00:00\",\
+            \"replies\":3}";
+
+        assert_eq!(page.to_json(), page_json);
+
+        assert_eq!(
+            summary.to_json(),
+            format!(
+                "{{\"total\":5,\
+                \"counts_by_type\":{{\"Square\":1,\"Text\":3,\"Circle\":1}},\
+                \"authors\":[\"Author\",\"Reviewer\"],\
+                \"earliest_date\":\"2024-01-01T09:00:00+00:00\",\
+                \"latest_date\":\"2024-01-03T09:00:00+00:00\",\
+                \"replies\":3,\
+                \"pages\":[{}]}}",
+                page_json
+            )
+        );
+
+        Ok(())
+    }
+}
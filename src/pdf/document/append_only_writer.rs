@@ -0,0 +1,738 @@
+//! Defines the [PdfAppendOnlyWriter] struct, used to append pages of timestamped text
+//! entries to an existing PDF file using incremental saves, without rewriting the file.
+
+use crate::bindgen::FPDF_INCREMENTAL;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::form::builder::{append_to_array, number};
+use crate::pdf::document::page::annotation::appearance::format_number;
+use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
+use crate::pdf::document::page::size::PdfPagePaperSize;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{
+    format_pdf_string, PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject,
+};
+use crate::pdf::document::PdfDocument;
+use crate::pdf::points::PdfPoints;
+use crate::pdfium::Pdfium;
+use crate::utils::dates::date_time_to_rfc3339_string;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// Appends pages of timestamped text entries to an existing PDF file, saving each batch of
+/// new pages as an incremental update appended to the end of the file.
+///
+/// The existing content of the file is never rewritten. The file is read once, when it is
+/// opened; thereafter each call to [PdfAppendOnlyWriter::save()] writes only the objects making
+/// up the new pages, so its cost depends on the number of entries being saved, not on the
+/// size of the file. This makes [PdfAppendOnlyWriter] suitable for generating audit logs and
+/// receipt trails that grow over a long period of time. Because earlier revisions of the file
+/// are left intact, any digital signatures covering them remain valid.
+///
+/// Files that are encrypted, or whose cross-reference information is stored in
+/// cross-reference streams, are instead saved using Pdfium's own incremental save. Pdfium
+/// copies the entire original file before writing each update, so in this case the cost
+/// of each save grows with the size of the file.
+///
+/// Entries are buffered in memory until [PdfAppendOnlyWriter::save()] is called. Each save
+/// lays out the buffered entries, one line per entry, onto one or more new pages added to
+/// the end of the document. Lines that are too long to fit across the page are not wrapped.
+/// Characters outside the Latin-1 character set are written as question marks.
+///
+/// This struct is not available when compiling to WASM.
+pub struct PdfAppendOnlyWriter<'a> {
+    pdfium: &'a Pdfium,
+    path: PathBuf,
+    password: Option<&'a str>,
+    document: PdfDocument<'a>,
+    saved_length: u64,
+    update: Option<PdfAppendOnlyUpdate>,
+    paper_size: PdfPagePaperSize,
+    margin: PdfPoints,
+    font_size: PdfPoints,
+    entries: Vec<String>,
+}
+
+impl<'a> PdfAppendOnlyWriter<'a> {
+    /// Opens the existing PDF file at the given path for appending.
+    ///
+    /// If the document is password protected, the given password will be used to unlock it.
+    pub fn open(
+        pdfium: &'a Pdfium,
+        path: &(impl AsRef<Path> + ?Sized),
+        password: Option<&'a str>,
+    ) -> Result<Self, PdfiumError> {
+        let path = path.as_ref().to_path_buf();
+
+        let document = pdfium.load_pdf_from_file(&path, password)?;
+
+        let file = std::fs::read(&path).map_err(PdfiumError::IoError)?;
+
+        let update = PdfAppendOnlyUpdate::parse(&file, document.pages().len());
+
+        Ok(PdfAppendOnlyWriter {
+            pdfium,
+            path,
+            password,
+            document,
+            saved_length: file.len() as u64,
+            update,
+            paper_size: PdfPagePaperSize::a4(),
+            margin: PdfPoints::from_mm(15.0),
+            font_size: PdfPoints::new(10.0),
+            entries: Vec::new(),
+        })
+    }
+
+    /// Sets the size of the pages appended to the document. The default is A4 portrait.
+    #[inline]
+    pub fn set_paper_size(mut self, paper_size: PdfPagePaperSize) -> Self {
+        self.paper_size = paper_size;
+
+        self
+    }
+
+    /// Sets the margin between the edges of each appended page and its entries.
+    /// The default is 15 mm.
+    #[inline]
+    pub fn set_margin(mut self, margin: PdfPoints) -> Self {
+        self.margin = margin;
+
+        self
+    }
+
+    /// Sets the font size of the entries on each appended page. Entries are set in
+    /// the built-in Courier font. The default is 10 points.
+    #[inline]
+    pub fn set_font_size(mut self, font_size: PdfPoints) -> Self {
+        self.font_size = font_size;
+
+        self
+    }
+
+    /// Returns an immutable reference to the [PdfDocument] being appended to, reflecting
+    /// the state of the file as of the most recent call to [PdfAppendOnlyWriter::save()].
+    #[inline]
+    pub fn document(&self) -> &PdfDocument<'a> {
+        &self.document
+    }
+
+    /// Buffers a new entry containing the given text, timestamped with the current time
+    /// in UTC. Any line breaks in the given text start new lines in the same entry.
+    #[inline]
+    pub fn append_entry(&mut self, text: &str) {
+        self.append_entry_at(SystemTime::now(), text);
+    }
+
+    /// Buffers a new entry containing the given text, timestamped with the given time in UTC.
+    /// Any line breaks in the given text start new lines in the same entry.
+    pub fn append_entry_at(&mut self, time: SystemTime, text: &str) {
+        let timestamp = date_time_to_rfc3339_string(DateTime::<Utc>::from(time));
+
+        for (index, line) in text.lines().enumerate() {
+            if index == 0 {
+                self.entries.push(format!("{} {}", timestamp, line));
+            } else {
+                self.entries
+                    .push(format!("{} {}", " ".repeat(timestamp.len()), line));
+            }
+        }
+
+        if text.is_empty() {
+            self.entries.push(timestamp);
+        }
+    }
+
+    /// Returns the number of buffered lines that will be written by the next call to
+    /// [PdfAppendOnlyWriter::save()].
+    #[inline]
+    pub fn pending_lines(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Lays out all buffered entries onto new pages at the end of the document, then appends
+    /// the new pages to the file as an incremental update. Does nothing if no entries
+    /// are buffered.
+    ///
+    /// If the update cannot be written in full, the file is truncated back to its length
+    /// before the update and the entries remain buffered.
+    ///
+    /// Returns the number of pages appended.
+    pub fn save(&mut self) -> Result<usize, PdfiumError> {
+        if self.entries.is_empty() {
+            return Ok(0);
+        }
+
+        let page_count = if self.update.is_some() {
+            self.append_pages()?
+        } else {
+            self.append_pages_with_pdfium()?
+        };
+
+        self.entries.clear();
+
+        self.document = self.pdfium.load_pdf_from_file(&self.path, self.password)?;
+
+        Ok(page_count)
+    }
+
+    /// Returns the number of entry lines that fit on each appended page.
+    fn lines_per_page(&self) -> usize {
+        let available = self.paper_size.height().value - self.margin.value * 2.0;
+
+        ((available / (self.font_size.value * LINE_SPACING)).floor() as usize).max(1)
+    }
+
+    /// Returns the vertical position of the baseline of the first line on each appended page.
+    fn first_baseline(&self) -> PdfPoints {
+        PdfPoints::new(self.paper_size.height().value - self.margin.value - self.font_size.value)
+    }
+
+    /// Appends the buffered entries to the file by writing the objects making up the new
+    /// pages, together with the updated root of the page tree, as an incremental update.
+    fn append_pages(&mut self) -> Result<usize, PdfiumError> {
+        let update = self
+            .update
+            .as_ref()
+            .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let contents = self
+            .entries
+            .chunks(self.lines_per_page())
+            .map(|lines| self.content_stream(lines))
+            .collect::<Vec<_>>();
+
+        let (bytes, next) = update
+            .append(self.saved_length as usize, &contents, &self.paper_size)
+            .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let mut file = self.open_for_append()?;
+
+        if let Err(error) = file.write_all(&bytes).and_then(|_| file.sync_data()) {
+            return Err(self.truncate(&file, PdfiumError::IoError(error)));
+        }
+
+        self.saved_length += bytes.len() as u64;
+
+        self.update = Some(next);
+
+        Ok(contents.len())
+    }
+
+    /// Returns a content stream drawing the given lines of text in the built-in Courier font,
+    /// which the page's resources must name `/F1`.
+    fn content_stream(&self, lines: &[String]) -> Vec<u8> {
+        let mut content = format!(
+            "BT\n/F1 {} Tf\n{} TL\n{} {} Td\n",
+            format_number(self.font_size.value),
+            format_number(self.font_size.value * LINE_SPACING),
+            format_number(self.margin.value),
+            format_number(self.first_baseline().value),
+        );
+
+        for line in lines {
+            let text = line
+                .chars()
+                .map(|c| match c as u32 {
+                    // WinAnsiEncoding matches Latin-1 for these characters.
+                    code @ (0x20..=0x7E | 0xA0..=0xFF) => code as u8,
+                    _ => b'?',
+                })
+                .collect::<Vec<_>>();
+
+            content.push_str(&format!("{} Tj\nT*\n", format_pdf_string(&text)));
+        }
+
+        content.push_str("ET\n");
+
+        content.into_bytes()
+    }
+
+    /// Appends the buffered entries to the file using Pdfium's incremental save.
+    fn append_pages_with_pdfium(&mut self) -> Result<usize, PdfiumError> {
+        let width = self.paper_size.width();
+
+        let height = self.paper_size.height();
+
+        let line_height = self.font_size.value * LINE_SPACING;
+
+        let first_baseline = self.first_baseline();
+
+        let font = self.document.fonts_mut().courier();
+
+        let mut page_count = 0;
+
+        for lines in self.entries.chunks(self.lines_per_page()) {
+            let mut page = self
+                .document
+                .pages_mut()
+                .create_page_at_end(PdfPagePaperSize::Custom(width, height))?;
+
+            for (index, line) in lines.iter().enumerate() {
+                page.objects_mut().create_text_object(
+                    self.margin,
+                    PdfPoints::new(first_baseline.value - line_height * index as f32),
+                    line,
+                    font,
+                    self.font_size,
+                )?;
+            }
+
+            page_count += 1;
+        }
+
+        let result = self.append_update_with_pdfium();
+
+        if result.is_err() {
+            // Discard the new pages, so the document matches the file again.
+
+            self.document = self.pdfium.load_pdf_from_file(&self.path, self.password)?;
+        }
+
+        result.map(|_| page_count)
+    }
+
+    /// Saves the document incrementally, appending the incremental update to the file.
+    fn append_update_with_pdfium(&mut self) -> Result<(), PdfiumError> {
+        // When saving incrementally, Pdfium first copies the original file unchanged, then
+        // writes the update. Only the update needs to be appended to the file.
+
+        let mut writer = PdfAppendingFileWriter {
+            file: self.open_for_append()?,
+            skip: self.saved_length,
+            position: 0,
+        };
+
+        let result = self
+            .document
+            .save_to_writer_with_flags(&mut writer, FPDF_INCREMENTAL as _)
+            .and_then(|_| {
+                if writer.position <= writer.skip {
+                    // Pdfium did not write an incremental update.
+
+                    Err(PdfiumError::PdfiumLibraryInternalError(
+                        PdfiumInternalError::Unknown,
+                    ))
+                } else {
+                    writer.file.sync_data().map_err(PdfiumError::IoError)
+                }
+            });
+
+        if let Err(error) = result {
+            return Err(self.truncate(&writer.file, error));
+        }
+
+        self.saved_length = writer.position;
+
+        Ok(())
+    }
+
+    #[inline]
+    fn open_for_append(&self) -> Result<File, PdfiumError> {
+        OpenOptions::new()
+            .append(true)
+            .open(&self.path)
+            .map_err(PdfiumError::IoError)
+    }
+
+    /// Truncates the given file back to its length after the last successful save, removing
+    /// any partially written update, then returns the given error. If the file cannot be
+    /// truncated, the truncation error is returned instead.
+    fn truncate(&self, file: &File, error: PdfiumError) -> PdfiumError {
+        match file
+            .set_len(self.saved_length)
+            .and_then(|_| file.sync_data())
+        {
+            Ok(()) => error,
+            Err(error) => PdfiumError::IoError(error),
+        }
+    }
+}
+
+/// The spacing between the baselines of successive lines, as a multiple of the font size.
+const LINE_SPACING: f32 = 1.2;
+
+/// The parts of a file needed to append new pages to it as an incremental update: the
+/// trailer and cross-reference offset of its latest revision, and the latest values of
+/// the objects an update changes. Nothing else from the file is retained.
+#[derive(Debug, Clone)]
+struct PdfAppendOnlyUpdate {
+    /// The latest revision's trailer and cross-reference offset, together with the root of
+    /// the page tree and its `/Kids` and `/Count` entries, if they are indirect objects.
+    file: PdfSyntaxFile,
+
+    /// The object number of the root of the page tree.
+    root: u32,
+
+    /// A reference to the Courier font added by an earlier update, if any.
+    font: Option<PdfSyntaxObject>,
+
+    /// `true` if the file ends with a line break.
+    ends_with_line_break: bool,
+}
+
+impl PdfAppendOnlyUpdate {
+    /// Parses the given file, which Pdfium reports as containing the given number of pages.
+    /// Returns `None` if the file is encrypted, if its cross-reference information is not
+    /// stored in cross-reference tables, or if its page tree cannot be followed.
+    fn parse(file: &[u8], page_count: PdfPageIndex) -> Option<Self> {
+        let parsed = PdfSyntaxFile::parse(file)?;
+
+        let mut trailer = parsed.trailer.clone()?;
+
+        if trailer.get("Encrypt").is_some() {
+            return None;
+        }
+
+        if parsed.page_references()?.len() != page_count as usize {
+            return None;
+        }
+
+        let root = match parsed.resolve(trailer.get("Root")?).get("Pages")? {
+            PdfSyntaxObject::Reference(number, _) => *number,
+            _ => return None,
+        };
+
+        let root_object = &parsed.objects.get(&root)?.object;
+
+        if !matches!(
+            parsed.resolve(root_object.get("Kids")?),
+            PdfSyntaxObject::Array(_)
+        ) || !matches!(
+            parsed.resolve(root_object.get("Count")?),
+            PdfSyntaxObject::Number(_)
+        ) {
+            return None;
+        }
+
+        let mut numbers = vec![root];
+
+        for key in ["Kids", "Count"] {
+            if let Some(PdfSyntaxObject::Reference(number, _)) = root_object.get(key) {
+                numbers.push(*number);
+            }
+        }
+
+        let objects = numbers
+            .into_iter()
+            .map(|number| {
+                parsed
+                    .objects
+                    .get(&number)
+                    .filter(|indirect| !indirect.is_stream)
+                    .map(|indirect| (number, indirect.clone()))
+            })
+            .collect::<Option<HashMap<_, _>>>()?;
+
+        // Object numbers used anywhere in the file must not be reused, even though
+        // the objects themselves are not retained.
+
+        let size = match trailer.get("Size") {
+            Some(PdfSyntaxObject::Number(size)) => *size as u32,
+            _ => return None,
+        }
+        .max(parsed.objects.keys().max().map_or(1, |number| number + 1));
+
+        trailer.set("Size", PdfSyntaxObject::Number(size as f64));
+
+        Some(PdfAppendOnlyUpdate {
+            file: PdfSyntaxFile {
+                objects,
+                trailer: Some(trailer),
+                start_xref: parsed.start_xref,
+            },
+            root,
+            font: None,
+            ends_with_line_break: file.ends_with(b"\n") || file.ends_with(b"\r"),
+        })
+    }
+
+    /// Returns the bytes of an incremental update adding a new page for each of the given
+    /// content streams to the end of the document, ready to be appended to the file, which
+    /// must be the given number of bytes long. The new pages share a single intermediate
+    /// node in the page tree. Also returns the state of the file once the update has been
+    /// appended.
+    fn append(
+        &self,
+        length: usize,
+        contents: &[Vec<u8>],
+        paper_size: &PdfPagePaperSize,
+    ) -> Option<(Vec<u8>, Self)> {
+        let mut update = PdfIncrementalUpdate::new(&self.file)?;
+
+        let font = match self.font.as_ref() {
+            Some(font) => font.clone(),
+            None => update.add(PdfSyntaxObject::Dictionary(vec![
+                ("Type".to_string(), PdfSyntaxObject::name("Font")),
+                ("Subtype".to_string(), PdfSyntaxObject::name("Type1")),
+                ("BaseFont".to_string(), PdfSyntaxObject::name("Courier")),
+                (
+                    "Encoding".to_string(),
+                    PdfSyntaxObject::name("WinAnsiEncoding"),
+                ),
+            ])),
+        };
+
+        let node = update.reserve();
+
+        let media_box = PdfSyntaxObject::Array(vec![
+            number(0.0),
+            number(0.0),
+            number(paper_size.width().value),
+            number(paper_size.height().value),
+        ]);
+
+        let resources = PdfSyntaxObject::Dictionary(vec![(
+            "Font".to_string(),
+            PdfSyntaxObject::Dictionary(vec![("F1".to_string(), font.clone())]),
+        )]);
+
+        let kids = contents
+            .iter()
+            .map(|content| {
+                let content =
+                    update.add_stream(PdfSyntaxObject::Dictionary(Vec::new()), content.clone());
+
+                update.add(PdfSyntaxObject::Dictionary(vec![
+                    ("Type".to_string(), PdfSyntaxObject::name("Page")),
+                    ("Parent".to_string(), node.clone()),
+                    ("MediaBox".to_string(), media_box.clone()),
+                    ("Resources".to_string(), resources.clone()),
+                    ("Contents".to_string(), content),
+                ]))
+            })
+            .collect::<Vec<_>>();
+
+        if let PdfSyntaxObject::Reference(number, _) = node {
+            update.set(
+                number,
+                PdfSyntaxObject::Dictionary(vec![
+                    ("Type".to_string(), PdfSyntaxObject::name("Pages")),
+                    (
+                        "Parent".to_string(),
+                        PdfSyntaxObject::Reference(self.root, 0),
+                    ),
+                    ("Kids".to_string(), PdfSyntaxObject::Array(kids)),
+                    (
+                        "Count".to_string(),
+                        PdfSyntaxObject::Number(contents.len() as f64),
+                    ),
+                ]),
+            );
+        }
+
+        update.modify(self.root, &["Kids"], &mut |kids| {
+            append_to_array(kids, std::slice::from_ref(&node))
+        })?;
+
+        update.modify(self.root, &["Count"], &mut |count| match count {
+            PdfSyntaxObject::Number(count) => {
+                *count += contents.len() as f64;
+
+                Some(())
+            }
+            _ => None,
+        })?;
+
+        let mut objects = self.file.objects.clone();
+
+        for (number, indirect) in objects.iter_mut() {
+            indirect.object = update.get(*number)?;
+        }
+
+        let mut bytes = Vec::new();
+
+        if !self.ends_with_line_break {
+            bytes.push(b'\n');
+        }
+
+        bytes.extend_from_slice(&update.write_at(length + bytes.len()));
+
+        // The update is small, so parsing it is the simplest way to obtain the new trailer
+        // and cross-reference offset.
+
+        let written = PdfSyntaxFile::parse(&bytes)?;
+
+        Some((
+            bytes,
+            PdfAppendOnlyUpdate {
+                file: PdfSyntaxFile {
+                    objects,
+                    trailer: written.trailer,
+                    start_xref: written.start_xref,
+                },
+                root: self.root,
+                font: Some(font),
+                ends_with_line_break: true,
+            },
+        ))
+    }
+}
+
+/// A writer that discards the first `skip` bytes written to it, appending all subsequent
+/// bytes to the end of a file.
+struct PdfAppendingFileWriter {
+    file: File,
+    skip: u64,
+    position: u64,
+}
+
+impl Write for PdfAppendingFileWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let start = self.position;
+
+        let end = start + buf.len() as u64;
+
+        if end > self.skip {
+            let offset = self.skip.saturating_sub(start) as usize;
+
+            self.file.write_all(&buf[offset..])?;
+        }
+
+        self.position = end;
+
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::append_only_writer::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    const MINIMAL_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] >>\nendobj\n\
+        xref\n0 4\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 4 /Root 1 0 R >>\nstartxref\n200\n%%EOF";
+
+    #[test]
+    fn test_append_update() {
+        let update = PdfAppendOnlyUpdate::parse(MINIMAL_PDF, 1).unwrap();
+
+        assert!(PdfAppendOnlyUpdate::parse(MINIMAL_PDF, 2).is_none());
+
+        let mut file = MINIMAL_PDF.to_vec();
+
+        let (bytes, update) = update
+            .append(file.len(), &[b"BT ET\n".to_vec()], &PdfPagePaperSize::a4())
+            .unwrap();
+
+        // The file does not end with a line break, so the update must start with one.
+
+        assert_eq!(bytes[0], b'\n');
+
+        file.extend_from_slice(&bytes);
+
+        let first_xref = PdfSyntaxFile::parse(&file).unwrap().start_xref.unwrap();
+
+        let (bytes, _) = update
+            .append(
+                file.len(),
+                &[b"BT ET\n".to_vec(), b"BT ET\n".to_vec()],
+                &PdfPagePaperSize::a4(),
+            )
+            .unwrap();
+
+        file.extend_from_slice(&bytes);
+
+        assert!(file.starts_with(MINIMAL_PDF));
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let start_xref = parsed.start_xref.unwrap();
+
+        assert!(file[start_xref..].starts_with(b"xref"));
+
+        let trailer = parsed.trailer.as_ref().unwrap();
+
+        assert_eq!(
+            trailer.get("Prev"),
+            Some(&PdfSyntaxObject::Number(first_xref as f64))
+        );
+
+        let pages = parsed.page_references().unwrap();
+
+        assert_eq!(pages.len(), 4);
+        assert_eq!(
+            parsed
+                .resolve(&PdfSyntaxObject::Reference(2, 0))
+                .get("Count"),
+            Some(&PdfSyntaxObject::Number(4.0))
+        );
+
+        // Both updates share a single font.
+
+        let font = |page: &PdfSyntaxObject| {
+            parsed
+                .resolve(page)
+                .get("Resources")
+                .and_then(|resources| resources.get("Font"))
+                .and_then(|fonts| fonts.get("F1"))
+                .cloned()
+        };
+
+        assert!(font(&pages[1]).is_some());
+        assert_eq!(font(&pages[1]), font(&pages[3]));
+    }
+
+    #[test]
+    fn test_append_only_writer() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let path = std::env::temp_dir().join("pdfium-render-test-append-only-writer.pdf");
+
+        std::fs::copy("./test/export-test.pdf", &path).map_err(PdfiumError::IoError)?;
+
+        let original = std::fs::read(&path).map_err(PdfiumError::IoError)?;
+
+        let original_page_count = pdfium
+            .load_pdf_from_byte_slice(&original, None)?
+            .pages()
+            .len();
+
+        {
+            let mut writer = PdfAppendOnlyWriter::open(&pdfium, &path, None)?;
+
+            writer.append_entry_at(SystemTime::now(), "First entry");
+            writer.append_entry_at(SystemTime::now(), "Second entry\nwith a second line");
+
+            assert_eq!(writer.pending_lines(), 3);
+            assert_eq!(writer.save()?, 1);
+
+            writer.append_entry("Third entry");
+
+            assert_eq!(writer.save()?, 1);
+            assert_eq!(writer.save()?, 0);
+            assert_eq!(writer.document().pages().len(), original_page_count + 2);
+        }
+
+        let appended = std::fs::read(&path).map_err(PdfiumError::IoError)?;
+
+        // The original file content must be left untouched.
+
+        assert!(appended.starts_with(&original));
+
+        assert_eq!(
+            pdfium
+                .load_pdf_from_byte_slice(&appended, None)?
+                .pages()
+                .len(),
+            original_page_count + 2
+        );
+
+        std::fs::remove_file(&path).map_err(PdfiumError::IoError)?;
+
+        Ok(())
+    }
+}
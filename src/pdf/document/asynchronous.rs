@@ -0,0 +1,764 @@
+//! Defines the [PdfDocumentAsync] struct, a handle to a `PdfDocument` owned by a dedicated
+//! Pdfium worker thread, allowing async code to render pages and run other operations on the
+//! document without blocking executor threads.
+
+use crate::error::PdfiumError;
+use crate::pdf::bitmap::{PdfBitmap, PdfBitmapFormat, Pixels};
+use crate::pdf::document::page::render_config::PdfRenderConfig;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::PdfDocument;
+use crate::pdfium::Pdfium;
+use std::future::Future;
+use std::ops::Deref;
+use std::pin::Pin;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+
+#[cfg(feature = "tokio")]
+use {
+    std::collections::VecDeque,
+    std::io::{ErrorKind, Write},
+    std::sync::Condvar,
+    tokio::io::AsyncWrite,
+};
+
+#[cfg(feature = "image")]
+use image::{DynamicImage, GrayImage, RgbaImage};
+
+/// A unit of work sent to the worker thread owning a `PdfDocument`.
+type PdfDocumentJob = Box<dyn FnOnce(&PdfDocument) + Send + 'static>;
+
+/// A page rendered by the [PdfDocumentAsync::render_page()] function.
+///
+/// Unlike a [PdfBitmap], which is backed by a buffer owned by Pdfium and so cannot leave
+/// the thread on which it was rendered, a [PdfRenderedPage] owns a copy of the rendered pixel
+/// data and can be freely sent between threads.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfRenderedPage {
+    width: Pixels,
+    height: Pixels,
+    format: PdfBitmapFormat,
+    bytes: Vec<u8>,
+}
+
+impl PdfRenderedPage {
+    /// Creates a new [PdfRenderedPage] from a copy of the pixel data in the given [PdfBitmap].
+    pub(crate) fn from_bitmap(bitmap: &PdfBitmap) -> Self {
+        PdfRenderedPage {
+            width: bitmap.width(),
+            height: bitmap.height(),
+            format: bitmap.format().unwrap_or_default(),
+            bytes: bitmap.as_rgba_bytes(),
+        }
+    }
+
+    /// Returns the pixel width of this [PdfRenderedPage].
+    #[inline]
+    pub fn width(&self) -> Pixels {
+        self.width
+    }
+
+    /// Returns the pixel height of this [PdfRenderedPage].
+    #[inline]
+    pub fn height(&self) -> Pixels {
+        self.height
+    }
+
+    /// Returns the pixel format of the [PdfBitmap] into which this [PdfRenderedPage]
+    /// was originally rendered.
+    #[inline]
+    pub fn format(&self) -> PdfBitmapFormat {
+        self.format
+    }
+
+    /// Returns the pixel data of this [PdfRenderedPage], normalized into RGBA as described
+    /// by the [PdfBitmap::as_rgba_bytes()] function.
+    #[inline]
+    pub fn as_rgba_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Consumes this [PdfRenderedPage], returning its pixel data.
+    #[inline]
+    pub fn into_rgba_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns a new `Image::DynamicImage` created from the pixel data of this [PdfRenderedPage].
+    ///
+    /// This function is only available when this crate's `image` feature is enabled.
+    #[cfg(feature = "image")]
+    pub fn as_image(&self) -> Result<DynamicImage, PdfiumError> {
+        let width = self.width as u32;
+
+        let height = self.height as u32;
+
+        match self.format {
+            PdfBitmapFormat::Gray => {
+                GrayImage::from_raw(width, height, self.bytes.clone()).map(DynamicImage::ImageLuma8)
+            }
+            _ => {
+                RgbaImage::from_raw(width, height, self.bytes.clone()).map(DynamicImage::ImageRgba8)
+            }
+        }
+        .ok_or(PdfiumError::DataBufferLengthMismatch)
+    }
+}
+
+/// A handle to a `PdfDocument` owned by a dedicated Pdfium worker thread.
+///
+/// Neither `Pdfium` nor `PdfDocument` can be sent between threads, so async code using them
+/// directly must either block an executor thread while Pdfium works or wrap every call in its
+/// own `spawn_blocking()` plumbing. A [PdfDocumentAsync] instead opens the document on a worker
+/// thread of its own; every operation is sent to that thread over a channel and is returned
+/// as a [PdfFuture] that completes once the worker has finished. Operations are carried out
+/// one at a time, in the order in which they were requested.
+///
+/// The returned futures do not depend on any particular async runtime, and can be awaited
+/// from `tokio`, `async-std`, or any other executor.
+///
+/// Dropping the [PdfDocumentAsync] closes the document and stops the worker thread once all
+/// outstanding operations have completed.
+///
+/// The worker thread either binds to Pdfium itself, using [PdfDocumentAsync::load_from_byte_vec()],
+/// or shares the caller's [Pdfium] instance, using
+/// [PdfDocumentAsync::load_from_byte_vec_with_pdfium()] when this crate's `sync` feature is
+/// enabled. Pdfium's library state is shared by the whole process, and is only released once
+/// every [Pdfium] instance has been dropped, so closing a [PdfDocumentAsync] never releases
+/// a library still in use elsewhere. Pdfium itself is not thread safe, however: if Pdfium is
+/// also used elsewhere in the same process, this crate's `thread_safe` feature must be
+/// enabled so that all access to Pdfium is serialized.
+///
+/// This struct is only available when this crate's `async` feature is enabled.
+pub struct PdfDocumentAsync {
+    jobs: Sender<PdfDocumentJob>,
+}
+
+impl PdfDocumentAsync {
+    /// Starts a new worker thread, binds to Pdfium on that thread using the given function,
+    /// and opens a document from the given byte buffer. If the document is password protected,
+    /// the given password will be used to unlock it.
+    ///
+    /// The given function is called on the worker thread, and typically binds to Pdfium using
+    /// one of the [Pdfium::bind_to_library()], [Pdfium::bind_to_system_library()], or
+    /// [Pdfium::bind_to_statically_linked_library()] functions:
+    ///
+    /// ```ignore
+    /// let document = PdfDocumentAsync::load_from_byte_vec(
+    ///     || Ok(Pdfium::new(Pdfium::bind_to_system_library()?)),
+    ///     bytes,
+    ///     None,
+    /// )
+    /// .await?;
+    /// ```
+    pub fn load_from_byte_vec<F>(
+        bind: F,
+        bytes: Vec<u8>,
+        password: Option<String>,
+    ) -> PdfFuture<PdfDocumentAsync>
+    where
+        F: FnOnce() -> Result<Pdfium, PdfiumError> + Send + 'static,
+    {
+        Self::spawn(move || bind().map(Box::new), bytes, password)
+    }
+
+    /// Starts a new worker thread and opens a document from the given byte buffer using
+    /// the given shared [Pdfium] instance, rather than binding to Pdfium separately. If the
+    /// document is password protected, the given password will be used to unlock it.
+    ///
+    /// The worker thread holds a reference to the given [Pdfium] instance until the
+    /// [PdfDocumentAsync] is dropped and all outstanding operations have completed.
+    ///
+    /// This function is only available when this crate's `sync` feature is enabled.
+    #[cfg(feature = "sync")]
+    pub fn load_from_byte_vec_with_pdfium(
+        pdfium: Arc<Pdfium>,
+        bytes: Vec<u8>,
+        password: Option<String>,
+    ) -> PdfFuture<PdfDocumentAsync> {
+        Self::spawn(move || Ok(pdfium), bytes, password)
+    }
+
+    /// Starts a new worker thread, obtains a [Pdfium] instance on that thread using the given
+    /// function, and opens a document from the given byte buffer. The worker thread keeps the
+    /// [Pdfium] instance until it stops.
+    fn spawn<F, P>(bind: F, bytes: Vec<u8>, password: Option<String>) -> PdfFuture<PdfDocumentAsync>
+    where
+        F: FnOnce() -> Result<P, PdfiumError> + Send + 'static,
+        P: Deref<Target = Pdfium>,
+    {
+        let (future, completer) = PdfFuture::new();
+
+        let (jobs, receiver) = channel::<PdfDocumentJob>();
+
+        let spawned = thread::Builder::new()
+            .name("pdfium-render".to_string())
+            .spawn(move || {
+                let pdfium = match bind() {
+                    Ok(pdfium) => pdfium,
+                    Err(err) => return completer.complete(Err(err)),
+                };
+
+                let document = match pdfium.load_pdf_from_byte_vec(bytes, password.as_deref()) {
+                    Ok(document) => document,
+                    Err(err) => return completer.complete(Err(err)),
+                };
+
+                completer.complete(Ok(PdfDocumentAsync { jobs }));
+
+                // Carry out each job in turn until every PdfDocumentAsync handle
+                // (and so every sender) has been dropped.
+
+                while let Ok(job) = receiver.recv() {
+                    job(&document);
+                }
+
+                // Close the document before releasing the worker's Pdfium instance.
+
+                drop(document);
+                drop(pdfium);
+            });
+
+        match spawned {
+            Ok(_) => future,
+            Err(err) => PdfFuture::ready(Err(PdfiumError::IoError(err))),
+        }
+    }
+
+    /// Runs the given function against the `PdfDocument` on the worker thread, returning
+    /// a [PdfFuture] that completes with the function's result.
+    ///
+    /// This allows any functionality of `PdfDocument` not covered by the convenience functions
+    /// of [PdfDocumentAsync] to be used from async code. The function's result must be able to
+    /// be sent between threads, so it cannot borrow from the document.
+    pub fn run<F, T>(&self, f: F) -> PdfFuture<T>
+    where
+        F: FnOnce(&PdfDocument) -> Result<T, PdfiumError> + Send + 'static,
+        T: Send + 'static,
+    {
+        let (future, completer) = PdfFuture::new();
+
+        match self
+            .jobs
+            .send(Box::new(move |document| completer.complete(f(document))))
+        {
+            Ok(()) => future,
+            Err(_) => PdfFuture::ready(Err(PdfiumError::AsyncWorkerUnavailable)),
+        }
+    }
+
+    /// Returns a [PdfFuture] that completes with the number of pages in the document.
+    #[inline]
+    pub fn page_count(&self) -> PdfFuture<PdfPageIndex> {
+        self.run(|document| Ok(document.pages().len()))
+    }
+
+    /// Renders the page at the given zero-based index using the given [PdfRenderConfig],
+    /// returning a [PdfFuture] that completes with the rendered page.
+    pub fn render_page(
+        &self,
+        index: PdfPageIndex,
+        config: &PdfRenderConfig,
+    ) -> PdfFuture<PdfRenderedPage> {
+        let config = config.clone();
+
+        self.run(move |document| {
+            let bitmap = document.pages().get(index)?.render_with_config(&config)?;
+
+            Ok(PdfRenderedPage::from_bitmap(&bitmap))
+        })
+    }
+
+    /// Saves the document, streaming it from the worker thread into the given async writer
+    /// as Pdfium serializes it, returning a [PdfSaveFuture] that completes once the whole
+    /// document has been written and the writer has been flushed.
+    ///
+    /// The document is passed to the writer in chunks as it is saved, and the worker thread
+    /// waits for the writer to catch up whenever more than a few chunks are pending, so the
+    /// document is never held in memory in its entirety. If the writer returns an error,
+    /// the save is abandoned and the writer's error is returned as [PdfiumError::IoError].
+    /// Dropping the [PdfSaveFuture] before it completes also abandons the save.
+    ///
+    /// This function is only available when this crate's `tokio` feature is enabled.
+    #[cfg(feature = "tokio")]
+    pub fn save_to_async_writer<'w, W: AsyncWrite + Unpin>(
+        &self,
+        writer: &'w mut W,
+    ) -> PdfSaveFuture<'w, W> {
+        let channel = Arc::new(PdfSaveChannel::new());
+
+        let mut sender = PdfSaveChannelWriter {
+            channel: channel.clone(),
+        };
+
+        let job: PdfDocumentJob = Box::new(move |document| {
+            let result = document.save_to_writer(&mut sender);
+
+            sender.finish(result);
+        });
+
+        if self.jobs.send(job).is_err() {
+            channel.finish(Err(PdfiumError::AsyncWorkerUnavailable));
+        }
+
+        PdfSaveFuture {
+            channel,
+            writer,
+            chunk: None,
+            is_saved: false,
+        }
+    }
+}
+
+/// The state shared between a [PdfSaveFuture] and the worker thread saving the document.
+#[cfg(feature = "tokio")]
+struct PdfSaveChannelState {
+    chunks: VecDeque<Vec<u8>>,
+    result: Option<Result<(), PdfiumError>>,
+    is_cancelled: bool,
+    waker: Option<Waker>,
+}
+
+/// Passes chunks of a saved document from the worker thread to a [PdfSaveFuture].
+#[cfg(feature = "tokio")]
+struct PdfSaveChannel {
+    state: Mutex<PdfSaveChannelState>,
+    space_available: Condvar,
+}
+
+#[cfg(feature = "tokio")]
+impl PdfSaveChannel {
+    /// The number of chunks the worker thread may write ahead of the async writer.
+    const MAXIMUM_PENDING_CHUNKS: usize = 16;
+
+    fn new() -> Self {
+        PdfSaveChannel {
+            state: Mutex::new(PdfSaveChannelState {
+                chunks: VecDeque::new(),
+                result: None,
+                is_cancelled: false,
+                waker: None,
+            }),
+            space_available: Condvar::new(),
+        }
+    }
+
+    /// Records the result of the save, if no result has yet been recorded, and wakes
+    /// the [PdfSaveFuture].
+    fn finish(&self, result: Result<(), PdfiumError>) {
+        let waker = match self.state.lock() {
+            Ok(mut state) => {
+                if state.result.is_none() {
+                    state.result = Some(result);
+                }
+
+                state.waker.take()
+            }
+            Err(_) => None,
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+
+    /// Abandons the save, releasing the worker thread if it is waiting for the
+    /// async writer to catch up.
+    fn cancel(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.is_cancelled = true;
+            state.chunks.clear();
+        }
+
+        self.space_available.notify_all();
+    }
+}
+
+/// The worker thread's half of a [PdfSaveChannel], receiving the document from Pdfium.
+#[cfg(feature = "tokio")]
+struct PdfSaveChannelWriter {
+    channel: Arc<PdfSaveChannel>,
+}
+
+#[cfg(feature = "tokio")]
+impl PdfSaveChannelWriter {
+    #[inline]
+    fn finish(&self, result: Result<(), PdfiumError>) {
+        self.channel.finish(result);
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Write for PdfSaveChannelWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let cancelled = || std::io::Error::new(ErrorKind::Other, "save abandoned");
+
+        let mut state = self.channel.state.lock().map_err(|_| cancelled())?;
+
+        while state.chunks.len() >= PdfSaveChannel::MAXIMUM_PENDING_CHUNKS && !state.is_cancelled {
+            state = self
+                .channel
+                .space_available
+                .wait(state)
+                .map_err(|_| cancelled())?;
+        }
+
+        if state.is_cancelled {
+            return Err(cancelled());
+        }
+
+        state.chunks.push_back(buf.to_vec());
+
+        let waker = state.waker.take();
+
+        drop(state);
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl Drop for PdfSaveChannelWriter {
+    fn drop(&mut self) {
+        // If the job is dropped without finishing, for instance because it panicked,
+        // complete the future with an error rather than leaving it waiting forever.
+
+        self.channel
+            .finish(Err(PdfiumError::AsyncWorkerUnavailable));
+    }
+}
+
+/// A future returned by [PdfDocumentAsync::save_to_async_writer()], completing once the
+/// saved document has been written to the async writer and the writer has been flushed.
+///
+/// This struct is only available when this crate's `tokio` feature is enabled.
+#[cfg(feature = "tokio")]
+pub struct PdfSaveFuture<'w, W: AsyncWrite + Unpin> {
+    channel: Arc<PdfSaveChannel>,
+    writer: &'w mut W,
+    chunk: Option<(Vec<u8>, usize)>,
+    is_saved: bool,
+}
+
+#[cfg(feature = "tokio")]
+impl<'w, W: AsyncWrite + Unpin> PdfSaveFuture<'w, W> {
+    /// Abandons the save, returning the given error.
+    fn fail(&mut self, err: std::io::Error) -> Poll<Result<(), PdfiumError>> {
+        self.channel.cancel();
+
+        Poll::Ready(Err(PdfiumError::IoError(err)))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<'w, W: AsyncWrite + Unpin> Future for PdfSaveFuture<'w, W> {
+    type Output = Result<(), PdfiumError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        loop {
+            // Write out the current chunk, if any.
+
+            if let Some((chunk, offset)) = this.chunk.as_mut() {
+                match Pin::new(&mut *this.writer).poll_write(cx, &chunk[*offset..]) {
+                    Poll::Ready(Ok(0)) => {
+                        return this.fail(std::io::Error::new(
+                            ErrorKind::WriteZero,
+                            "failed to write whole buffer",
+                        ))
+                    }
+                    Poll::Ready(Ok(written)) => {
+                        *offset += written;
+
+                        if *offset >= chunk.len() {
+                            this.chunk = None;
+                        }
+
+                        continue;
+                    }
+                    Poll::Ready(Err(err)) => return this.fail(err),
+                    Poll::Pending => return Poll::Pending,
+                }
+            }
+
+            if this.is_saved {
+                // Every chunk has been written.
+
+                return match Pin::new(&mut *this.writer).poll_flush(cx) {
+                    Poll::Ready(Ok(())) => Poll::Ready(Ok(())),
+                    Poll::Ready(Err(err)) => Poll::Ready(Err(PdfiumError::IoError(err))),
+                    Poll::Pending => Poll::Pending,
+                };
+            }
+
+            // Take the next chunk from the worker thread, if one is available.
+
+            let mut state = match this.channel.state.lock() {
+                Ok(state) => state,
+                Err(_) => return Poll::Ready(Err(PdfiumError::AsyncWorkerUnavailable)),
+            };
+
+            if let Some(chunk) = state.chunks.pop_front() {
+                drop(state);
+
+                this.channel.space_available.notify_all();
+                this.chunk = Some((chunk, 0));
+            } else {
+                match state.result.take() {
+                    Some(Ok(())) => this.is_saved = true,
+                    Some(Err(err)) => return Poll::Ready(Err(err)),
+                    None => {
+                        state.waker = Some(cx.waker().clone());
+
+                        return Poll::Pending;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl<'w, W: AsyncWrite + Unpin> Drop for PdfSaveFuture<'w, W> {
+    fn drop(&mut self) {
+        self.channel.cancel();
+    }
+}
+
+/// The state shared between a [PdfFuture] and the worker thread that completes it.
+struct PdfFutureState<T> {
+    result: Option<Result<T, PdfiumError>>,
+    waker: Option<Waker>,
+}
+
+/// A future returned by the functions of [PdfDocumentAsync], completing once the worker
+/// thread owning the document has finished the requested operation.
+///
+/// This struct is only available when this crate's `async` feature is enabled.
+pub struct PdfFuture<T> {
+    state: Arc<Mutex<PdfFutureState<T>>>,
+}
+
+impl<T> PdfFuture<T> {
+    fn new() -> (Self, PdfFutureCompleter<T>) {
+        let state = Arc::new(Mutex::new(PdfFutureState {
+            result: None,
+            waker: None,
+        }));
+
+        (
+            PdfFuture {
+                state: state.clone(),
+            },
+            PdfFutureCompleter { state: Some(state) },
+        )
+    }
+
+    fn ready(result: Result<T, PdfiumError>) -> Self {
+        let (future, completer) = Self::new();
+
+        completer.complete(result);
+
+        future
+    }
+}
+
+impl<T> Future for PdfFuture<T> {
+    type Output = Result<T, PdfiumError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(_) => return Poll::Ready(Err(PdfiumError::AsyncWorkerUnavailable)),
+        };
+
+        match state.result.take() {
+            Some(result) => Poll::Ready(result),
+            None => {
+                state.waker = Some(cx.waker().clone());
+
+                Poll::Pending
+            }
+        }
+    }
+}
+
+/// The worker thread's half of a [PdfFuture]. If the completer is dropped without being
+/// completed, for instance because a job panicked, the future completes with an error
+/// rather than waiting forever.
+struct PdfFutureCompleter<T> {
+    state: Option<Arc<Mutex<PdfFutureState<T>>>>,
+}
+
+impl<T> PdfFutureCompleter<T> {
+    fn complete(mut self, result: Result<T, PdfiumError>) {
+        if let Some(state) = self.state.take() {
+            Self::set_result(&state, result);
+        }
+    }
+
+    fn set_result(state: &Mutex<PdfFutureState<T>>, result: Result<T, PdfiumError>) {
+        let waker = match state.lock() {
+            Ok(mut state) => {
+                state.result = Some(result);
+
+                state.waker.take()
+            }
+            Err(_) => None,
+        };
+
+        if let Some(waker) = waker {
+            waker.wake();
+        }
+    }
+}
+
+impl<T> Drop for PdfFutureCompleter<T> {
+    fn drop(&mut self) {
+        if let Some(state) = self.state.take() {
+            Self::set_result(&state, Err(PdfiumError::AsyncWorkerUnavailable));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+    use std::future::Future;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::thread::{self, Thread};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    // A minimal executor, so the test does not depend on any particular async runtime.
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+
+        let mut context = Context::from_waker(&waker);
+
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_page_async() -> Result<(), PdfiumError> {
+        let bytes = std::fs::read("./test/export-test.pdf").map_err(PdfiumError::IoError)?;
+
+        let document = block_on(PdfDocumentAsync::load_from_byte_vec(
+            || Ok(test_bind_to_pdfium()),
+            bytes,
+            None,
+        ))?;
+
+        assert!(block_on(document.page_count())? > 0);
+
+        let page =
+            block_on(document.render_page(0, &PdfRenderConfig::new().set_target_width(400)))?;
+
+        assert_eq!(page.width(), 400);
+        assert_eq!(
+            page.as_rgba_bytes().len(),
+            (page.width() * page.height() * 4) as usize
+        );
+
+        assert!(block_on(document.render_page(9999, &PdfRenderConfig::new())).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_worker_shares_pdfium_library() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        // Dropping a second Pdfium instance, as a worker thread does when its PdfDocumentAsync
+        // is dropped, must not release the library while the first instance is still in use.
+
+        drop(test_bind_to_pdfium());
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        assert!(!document.pages().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "sync")]
+    fn test_render_page_async_with_shared_pdfium() -> Result<(), PdfiumError> {
+        let pdfium = Arc::new(test_bind_to_pdfium());
+
+        let bytes = std::fs::read("./test/export-test.pdf").map_err(PdfiumError::IoError)?;
+
+        let document = block_on(PdfDocumentAsync::load_from_byte_vec_with_pdfium(
+            pdfium.clone(),
+            bytes,
+            None,
+        ))?;
+
+        let page =
+            block_on(document.render_page(0, &PdfRenderConfig::new().set_target_width(200)))?;
+
+        assert_eq!(page.width(), 200);
+
+        drop(document);
+
+        // The caller's Pdfium instance remains usable.
+
+        assert!(pdfium
+            .load_pdf_from_file("./test/export-test.pdf", None)
+            .is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "tokio")]
+    fn test_save_to_async_writer() -> Result<(), PdfiumError> {
+        let bytes = std::fs::read("./test/export-test.pdf").map_err(PdfiumError::IoError)?;
+
+        let document = block_on(PdfDocumentAsync::load_from_byte_vec(
+            || Ok(test_bind_to_pdfium()),
+            bytes,
+            None,
+        ))?;
+
+        let mut saved = Vec::new();
+
+        block_on(document.save_to_async_writer(&mut saved))?;
+
+        assert!(saved.starts_with(b"%PDF-"));
+
+        let expected = block_on(document.run(|document| document.save_to_bytes()))?;
+
+        assert_eq!(saved.len(), expected.len());
+
+        Ok(())
+    }
+}
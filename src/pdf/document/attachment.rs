@@ -1,13 +1,26 @@
 //! Defines the [PdfAttachment] struct, exposing functionality related to a single
 //! attachment in a `PdfAttachments` collection.
 
-use crate::bindgen::{FPDF_ATTACHMENT, FPDF_WCHAR};
+pub mod classification;
+pub(crate) mod details;
+
+use crate::bindgen::{FPDF_ATTACHMENT, FPDF_DOCUMENT, FPDF_WCHAR};
 use crate::bindings::PdfiumLibraryBindings;
-use crate::error::PdfiumError;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::attachment::classification::{
+    PdfAttachmentClassification, PdfAttachmentFileType,
+};
+use crate::pdf::document::attachment::details::{
+    read_attachment_description, read_attachment_mime_type,
+};
+use crate::pdf::document::save_document_to_bytes;
+use crate::utils::dates::{date_time_from_pdf_string, date_time_to_pdf_string};
 use crate::utils::mem::create_byte_buffer;
 use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use chrono::prelude::*;
+use std::fmt::Display;
 use std::io::Write;
-use std::os::raw::{c_ulong, c_void};
+use std::os::raw::{c_int, c_ulong, c_void};
 
 #[cfg(not(target_arch = "wasm32"))]
 use std::fs::File;
@@ -34,6 +47,7 @@ struct Blob;
 /// A single attached data file embedded in a `PdfDocument`.
 pub struct PdfAttachment<'a> {
     handle: FPDF_ATTACHMENT,
+    document_handle: FPDF_DOCUMENT,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
 
@@ -41,9 +55,14 @@ impl<'a> PdfAttachment<'a> {
     #[inline]
     pub(crate) fn from_pdfium(
         handle: FPDF_ATTACHMENT,
+        document_handle: FPDF_DOCUMENT,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
-        PdfAttachment { handle, bindings }
+        PdfAttachment {
+            handle,
+            document_handle,
+            bindings,
+        }
     }
 
     /// Returns the [PdfiumLibraryBindings] used by this [PdfAttachment].
@@ -114,6 +133,205 @@ impl<'a> PdfAttachment<'a> {
         self.len() == 0
     }
 
+    /// Returns the description of this [PdfAttachment], if any.
+    ///
+    /// Pdfium does not expose the file specification dictionary holding the description,
+    /// so this function saves a copy of the containing document to memory and reads the
+    /// description from the copy. Returns `None` if this attachment belongs to a file
+    /// attachment annotation rather than the document's `PdfAttachments` collection, or
+    /// [PdfiumError::AttachmentDetailsUnreadable] if the document is encrypted.
+    pub fn description(&self) -> Result<Option<String>, PdfiumError> {
+        match self.index() {
+            Some(index) => read_attachment_description(
+                &save_document_to_bytes(self.document_handle, self.bindings)?,
+                index,
+            ),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the MIME type of the file data embedded in this [PdfAttachment], if any.
+    ///
+    /// Pdfium does not expose the embedded file stream dictionary holding the MIME type,
+    /// so the MIME type is read in the same way as [PdfAttachment::description()].
+    pub fn mime_type(&self) -> Result<Option<String>, PdfiumError> {
+        match self.index() {
+            Some(index) => read_attachment_mime_type(
+                &save_document_to_bytes(self.document_handle, self.bindings)?,
+                index,
+            ),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the checksum of the file data embedded in this [PdfAttachment], if any.
+    /// The checksum is the MD5 digest of the uncompressed file data, given as a string
+    /// of hexadecimal digits.
+    #[inline]
+    pub fn checksum(&self) -> Option<String> {
+        self.get_string_value("CheckSum")
+    }
+
+    /// Returns the date and time at which the file embedded in this [PdfAttachment]
+    /// was created, if any.
+    #[inline]
+    pub fn creation_date(&self) -> Option<DateTime<Utc>> {
+        self.get_string_value("CreationDate")
+            .and_then(|date| date_time_from_pdf_string(&date))
+    }
+
+    /// Sets the date and time at which the file embedded in this [PdfAttachment]
+    /// was created.
+    #[inline]
+    pub fn set_creation_date<T, O>(&mut self, date: DateTime<T>) -> Result<(), PdfiumError>
+    where
+        T: TimeZone<Offset = O>,
+        O: Display,
+    {
+        self.set_string_value("CreationDate", &date_time_to_pdf_string(date))
+    }
+
+    /// Returns the date and time at which the file embedded in this [PdfAttachment]
+    /// was last modified, if any.
+    #[inline]
+    pub fn modification_date(&self) -> Option<DateTime<Utc>> {
+        self.get_string_value("ModDate")
+            .and_then(|date| date_time_from_pdf_string(&date))
+    }
+
+    /// Sets the date and time at which the file embedded in this [PdfAttachment]
+    /// was last modified.
+    #[inline]
+    pub fn set_modification_date<T, O>(&mut self, date: DateTime<T>) -> Result<(), PdfiumError>
+    where
+        T: TimeZone<Offset = O>,
+        O: Display,
+    {
+        self.set_string_value("ModDate", &date_time_to_pdf_string(date))
+    }
+
+    /// Replaces the file data embedded in this [PdfAttachment] with the given bytes.
+    ///
+    /// Pdfium creates a new embedded file stream for the data, recording its size,
+    /// checksum, and creation date. Any modification date and MIME type set for the previous
+    /// data are discarded, but the name and description of the attachment are retained.
+    pub fn set_bytes(&mut self, bytes: &[u8]) -> Result<(), PdfiumError> {
+        if self.bindings.is_true(self.bindings.FPDFAttachment_SetFile(
+            self.handle,
+            self.document_handle,
+            bytes.as_ptr() as *const c_void,
+            bytes.len() as c_ulong,
+        )) {
+            Ok(())
+        } else {
+            Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ))
+        }
+    }
+
+    /// Returns the string value of the given key in the parameters dictionary of the file
+    /// embedded in this [PdfAttachment], if any.
+    fn get_string_value(&self, key: &str) -> Option<String> {
+        // Retrieving the value from Pdfium is a two-step operation. First, we call
+        // FPDFAttachment_GetStringValue() with a null buffer; this will retrieve the length of
+        // the value in bytes. A length of 2 or less indicates an empty value, since the
+        // length always includes a trailing null terminator.
+
+        // If the length is greater than 2, then we reserve a byte buffer of the given
+        // length and call FPDFAttachment_GetStringValue() again with a pointer to the buffer;
+        // this will write the value to the buffer in UTF16-LE format.
+
+        if !self
+            .bindings
+            .is_true(self.bindings.FPDFAttachment_HasKey(self.handle, key))
+        {
+            return None;
+        }
+
+        let buffer_length =
+            self.bindings
+                .FPDFAttachment_GetStringValue(self.handle, key, std::ptr::null_mut(), 0);
+
+        if buffer_length <= 2 {
+            return None;
+        }
+
+        let mut buffer = create_byte_buffer(buffer_length as usize);
+
+        let result = self.bindings.FPDFAttachment_GetStringValue(
+            self.handle,
+            key,
+            buffer.as_mut_ptr() as *mut FPDF_WCHAR,
+            buffer_length,
+        );
+
+        assert_eq!(result, buffer_length);
+
+        get_string_from_pdfium_utf16le_bytes(buffer)
+    }
+
+    /// Sets the string value of the given key in the parameters dictionary of the file
+    /// embedded in this [PdfAttachment].
+    fn set_string_value(&mut self, key: &str, value: &str) -> Result<(), PdfiumError> {
+        if self
+            .bindings
+            .is_true(
+                self.bindings
+                    .FPDFAttachment_SetStringValue_str(self.handle, key, value),
+            )
+        {
+            Ok(())
+        } else {
+            Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ))
+        }
+    }
+
+    /// Returns the index of this [PdfAttachment] in the containing document's collection of
+    /// attachments, or `None` if it belongs to a file attachment annotation instead.
+    fn index(&self) -> Option<usize> {
+        // Pdfium returns the same handle for the same attachment each time it is retrieved,
+        // so the index can be found by comparing handles.
+
+        let count = self
+            .bindings
+            .FPDFDoc_GetAttachmentCount(self.document_handle);
+
+        (0..count.max(0)).map(|index| index as usize).find(|index| {
+            self.bindings
+                .FPDFDoc_GetAttachment(self.document_handle, *index as c_int)
+                == self.handle
+        })
+    }
+
+    /// Returns the [PdfAttachmentFileType] declared by the file extension in the name of
+    /// this [PdfAttachment], if any.
+    #[inline]
+    pub fn declared_file_type(&self) -> Option<PdfAttachmentFileType> {
+        PdfAttachmentFileType::from_file_name(&self.name())
+    }
+
+    /// Returns the [PdfAttachmentFileType] detected by inspecting the leading bytes of the
+    /// file data embedded in this [PdfAttachment], if any. The declared name of the
+    /// attachment is not taken into account.
+    pub fn detected_file_type(&self) -> Option<PdfAttachmentFileType> {
+        self.save_to_bytes()
+            .ok()
+            .and_then(|bytes| PdfAttachmentFileType::from_bytes(bytes.as_slice()))
+    }
+
+    /// Returns a [PdfAttachmentClassification] comparing the file type declared by the name
+    /// of this [PdfAttachment] with the file type detected from its embedded file data.
+    /// The classification flags executables, scripts, and archives, along with any mismatch
+    /// between the declared and detected file types.
+    pub fn classification(&self) -> PdfAttachmentClassification {
+        let bytes = self.save_to_bytes().unwrap_or_default();
+
+        PdfAttachmentClassification::classify(&self.name(), bytes.as_slice())
+    }
+
     /// Writes this [PdfAttachment] to a new byte buffer, returning the byte buffer.
     pub fn save_to_bytes(&self) -> Result<Vec<u8>, PdfiumError> {
         // Retrieving the attachment data from Pdfium is a two-step operation. First, we call
@@ -0,0 +1,484 @@
+//! Defines the [PdfAttachmentClassification] struct, exposing functionality related to
+//! detecting the type of the file data embedded in a single `PdfAttachment`.
+
+/// A broad category of file content, used to assess the risk posed by a `PdfAttachment`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfAttachmentCategory {
+    /// A document format, such as PDF, RTF, or a word processing or spreadsheet file.
+    Document,
+
+    /// A raster image format.
+    Image,
+
+    /// An audio format.
+    Audio,
+
+    /// A video format.
+    Video,
+
+    /// Plain text or structured text such as XML or JSON.
+    Text,
+
+    /// A compressed archive or container format that may hold other files.
+    Archive,
+
+    /// Native executable code, such as a Windows portable executable or an ELF binary.
+    Executable,
+
+    /// A script or other text-based format that can be executed or interpreted.
+    Script,
+
+    /// The category could not be determined.
+    Unknown,
+}
+
+impl PdfAttachmentCategory {
+    /// Returns `true` if file data in this [PdfAttachmentCategory] can contain executable
+    /// content or can hide other files, and so should be treated with caution.
+    #[inline]
+    pub fn is_risky(&self) -> bool {
+        matches!(
+            self,
+            PdfAttachmentCategory::Archive
+                | PdfAttachmentCategory::Executable
+                | PdfAttachmentCategory::Script
+        )
+    }
+}
+
+/// A MIME type and [PdfAttachmentCategory] for the file data embedded in a `PdfAttachment`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PdfAttachmentFileType {
+    mime_type: &'static str,
+    category: PdfAttachmentCategory,
+}
+
+impl PdfAttachmentFileType {
+    #[inline]
+    const fn new(mime_type: &'static str, category: PdfAttachmentCategory) -> Self {
+        PdfAttachmentFileType {
+            mime_type,
+            category,
+        }
+    }
+
+    /// Returns the MIME type of this [PdfAttachmentFileType], e.g. `application/pdf`.
+    #[inline]
+    pub fn mime_type(&self) -> &'static str {
+        self.mime_type
+    }
+
+    /// Returns the [PdfAttachmentCategory] of this [PdfAttachmentFileType].
+    #[inline]
+    pub fn category(&self) -> PdfAttachmentCategory {
+        self.category
+    }
+
+    /// Returns `true` if the [PdfAttachmentCategory] of this [PdfAttachmentFileType] is risky.
+    #[inline]
+    pub fn is_risky(&self) -> bool {
+        self.category.is_risky()
+    }
+
+    /// Returns the [PdfAttachmentFileType] declared by the extension of the given file name, if any.
+    pub fn from_file_name(name: &str) -> Option<Self> {
+        let extension = name.rsplit_once('.')?.1.to_ascii_lowercase();
+
+        let file_type = match extension.as_str() {
+            "pdf" => PDF,
+            "rtf" => RTF,
+            "doc" | "xls" | "ppt" | "msg" => OLE,
+            "docx" => DOCX,
+            "xlsx" => XLSX,
+            "pptx" => PPTX,
+            "odt" | "ods" | "odp" => OPEN_DOCUMENT,
+            "epub" => EPUB,
+            "png" => PNG,
+            "jpg" | "jpeg" => JPEG,
+            "gif" => GIF,
+            "tif" | "tiff" => TIFF,
+            "bmp" => BMP,
+            "webp" => WEBP,
+            "wav" => WAV,
+            "mp3" => MP3,
+            "ogg" | "oga" => OGG,
+            "flac" => FLAC,
+            "avi" => AVI,
+            "mp4" | "m4v" | "mov" => MP4,
+            "txt" | "log" | "md" => PLAIN_TEXT,
+            "csv" => CSV,
+            "json" => JSON,
+            "xml" => XML,
+            "html" | "htm" | "xhtml" | "svg" => HTML,
+            "zip" => ZIP,
+            "rar" => RAR,
+            "7z" => SEVEN_ZIP,
+            "gz" | "tgz" => GZIP,
+            "bz2" => BZIP2,
+            "xz" => XZ,
+            "tar" => TAR,
+            "exe" | "dll" | "scr" | "com" | "sys" | "cpl" | "msi" | "lnk" => PORTABLE_EXECUTABLE,
+            "elf" | "so" | "bin" => ELF,
+            "dylib" => MACH_O,
+            "jar" | "apk" => JAR,
+            "sh" | "bash" | "zsh" | "command" => SHELL_SCRIPT,
+            "js" | "mjs" | "jse" => JAVASCRIPT,
+            "vbs" | "vbe" | "wsf" | "hta" => VBSCRIPT,
+            "ps1" | "psm1" => POWERSHELL,
+            "bat" | "cmd" => BATCH,
+            "py" => PYTHON,
+            "pl" => PERL,
+            "rb" => RUBY,
+            "php" => PHP,
+            _ => return None,
+        };
+
+        Some(file_type)
+    }
+
+    /// Returns the [PdfAttachmentFileType] detected by inspecting the leading "magic" bytes
+    /// of the given file data, if any.
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let starts_with = |prefix: &[u8]| bytes.starts_with(prefix);
+
+        let at = |offset: usize, value: &[u8]| {
+            bytes
+                .get(offset..offset + value.len())
+                .map(|slice| slice == value)
+                .unwrap_or(false)
+        };
+
+        let file_type = if starts_with(b"%PDF-") {
+            PDF
+        } else if starts_with(b"{\\rtf") {
+            RTF
+        } else if starts_with(&[0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1]) {
+            OLE
+        } else if starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+            PNG
+        } else if starts_with(&[0xFF, 0xD8, 0xFF]) {
+            JPEG
+        } else if starts_with(b"GIF87a") || starts_with(b"GIF89a") {
+            GIF
+        } else if starts_with(b"II*\0") || starts_with(b"MM\0*") {
+            TIFF
+        } else if starts_with(b"RIFF") && at(8, b"WEBP") {
+            WEBP
+        } else if starts_with(b"RIFF") && at(8, b"WAVE") {
+            WAV
+        } else if starts_with(b"RIFF") && at(8, b"AVI ") {
+            AVI
+        } else if at(4, b"ftyp") {
+            MP4
+        } else if starts_with(b"ID3") || starts_with(&[0xFF, 0xFB]) {
+            MP3
+        } else if starts_with(b"OggS") {
+            OGG
+        } else if starts_with(b"fLaC") {
+            FLAC
+        } else if starts_with(b"MZ") {
+            PORTABLE_EXECUTABLE
+        } else if starts_with(&[0x7F, b'E', b'L', b'F']) {
+            ELF
+        } else if starts_with(&[0xFE, 0xED, 0xFA, 0xCE])
+            || starts_with(&[0xFE, 0xED, 0xFA, 0xCF])
+            || starts_with(&[0xCE, 0xFA, 0xED, 0xFE])
+            || starts_with(&[0xCF, 0xFA, 0xED, 0xFE])
+        {
+            MACH_O
+        } else if starts_with(&[0xCA, 0xFE, 0xBA, 0xBE]) {
+            // Both Java class files and universal Mach-O binaries share this signature.
+            // Either way, the content is executable.
+
+            JAVA_CLASS
+        } else if starts_with(b"PK\x03\x04") || starts_with(b"PK\x05\x06") {
+            ZIP
+        } else if starts_with(b"Rar!\x1A\x07") {
+            RAR
+        } else if starts_with(&[b'7', b'z', 0xBC, 0xAF, 0x27, 0x1C]) {
+            SEVEN_ZIP
+        } else if starts_with(&[0x1F, 0x8B]) {
+            GZIP
+        } else if starts_with(b"BZh") {
+            BZIP2
+        } else if starts_with(&[0xFD, b'7', b'z', b'X', b'Z', 0x00]) {
+            XZ
+        } else if at(257, b"ustar") {
+            TAR
+        } else if starts_with(b"#!") {
+            SHELL_SCRIPT
+        } else if let Some(text) = as_text(bytes) {
+            let lowercase = text.trim_start().to_ascii_lowercase();
+
+            if lowercase.starts_with("<!doctype html")
+                || lowercase.starts_with("<html")
+                || lowercase.starts_with("<svg")
+            {
+                HTML
+            } else if lowercase.starts_with("<?xml") {
+                XML
+            } else {
+                PLAIN_TEXT
+            }
+        } else {
+            return None;
+        };
+
+        Some(file_type)
+    }
+
+    /// Returns `true` if file data detected as this [PdfAttachmentFileType] is consistent
+    /// with the given declared [PdfAttachmentFileType].
+    fn is_consistent_with_declared(&self, declared: &PdfAttachmentFileType) -> bool {
+        if self == declared {
+            return true;
+        }
+
+        match *self {
+            // Many modern formats are ZIP containers under the hood.
+            ZIP => matches!(*declared, DOCX | XLSX | PPTX | OPEN_DOCUMENT | EPUB | JAR),
+
+            // Structured storage is used by legacy Microsoft Office formats and installers.
+            OLE => declared.mime_type == PORTABLE_EXECUTABLE.mime_type,
+
+            // Anything declared as a text-based format is consistent with detected text.
+            PLAIN_TEXT => matches!(
+                declared.category,
+                PdfAttachmentCategory::Text | PdfAttachmentCategory::Script
+            ),
+            XML => declared.category == PdfAttachmentCategory::Text || *declared == HTML,
+            HTML => *declared == XML,
+            SHELL_SCRIPT => declared.category == PdfAttachmentCategory::Script,
+            MACH_O | JAVA_CLASS => matches!(*declared, MACH_O | JAVA_CLASS | JAR),
+            _ => false,
+        }
+    }
+}
+
+/// The result of comparing the file type declared by the name of a `PdfAttachment`
+/// with the file type detected by inspecting its embedded file data.
+///
+/// Pdfium does not expose the MIME type optionally recorded in an embedded file stream,
+/// so the declared file type is taken from the extension of the attachment's name.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PdfAttachmentClassification {
+    declared: Option<PdfAttachmentFileType>,
+    detected: Option<PdfAttachmentFileType>,
+}
+
+impl PdfAttachmentClassification {
+    /// Classifies the given file data, embedded under the given attachment name.
+    pub fn classify(name: &str, bytes: &[u8]) -> Self {
+        PdfAttachmentClassification {
+            declared: PdfAttachmentFileType::from_file_name(name),
+            detected: PdfAttachmentFileType::from_bytes(bytes),
+        }
+    }
+
+    /// Returns the [PdfAttachmentFileType] declared by the attachment's name, if any.
+    #[inline]
+    pub fn declared(&self) -> Option<PdfAttachmentFileType> {
+        self.declared
+    }
+
+    /// Returns the [PdfAttachmentFileType] detected from the attachment's file data, if any.
+    #[inline]
+    pub fn detected(&self) -> Option<PdfAttachmentFileType> {
+        self.detected
+    }
+
+    /// Returns the best available [PdfAttachmentCategory] for the attachment. The detected
+    /// file type takes precedence over the declared file type.
+    #[inline]
+    pub fn category(&self) -> PdfAttachmentCategory {
+        self.detected
+            .or(self.declared)
+            .map(|file_type| file_type.category())
+            .unwrap_or(PdfAttachmentCategory::Unknown)
+    }
+
+    /// Returns `true` if both a declared and a detected file type are available and
+    /// they disagree with one another. A mismatch is a common sign that a file has been
+    /// disguised to evade filtering.
+    pub fn is_mismatched(&self) -> bool {
+        match (self.declared, self.detected) {
+            (Some(declared), Some(detected)) => !detected.is_consistent_with_declared(&declared),
+            _ => false,
+        }
+    }
+
+    /// Returns `true` if either the declared or the detected file type is risky, or if
+    /// the declared and detected file types are mismatched. Attachments for which this
+    /// function returns `true` are candidates for quarantine.
+    pub fn is_risky(&self) -> bool {
+        self.declared
+            .map(|file_type| file_type.is_risky())
+            .unwrap_or(false)
+            || self
+                .detected
+                .map(|file_type| file_type.is_risky())
+                .unwrap_or(false)
+            || self.is_mismatched()
+    }
+}
+
+// Returns the given bytes as text, if they appear to contain only printable UTF-8 text.
+fn as_text(bytes: &[u8]) -> Option<&str> {
+    if bytes.is_empty() {
+        return None;
+    }
+
+    // Only the leading portion of the data is inspected; a multi-byte character may
+    // straddle the end of the sample, so allow for an incomplete trailing sequence.
+
+    let sample = &bytes[..bytes.len().min(1024)];
+
+    let text = match std::str::from_utf8(sample) {
+        Ok(text) => text,
+        Err(error) if error.error_len().is_none() => {
+            std::str::from_utf8(&sample[..error.valid_up_to()]).ok()?
+        }
+        Err(_) => return None,
+    };
+
+    if text
+        .chars()
+        .all(|c| !c.is_control() || c.is_ascii_whitespace())
+    {
+        Some(text)
+    } else {
+        None
+    }
+}
+
+use PdfAttachmentCategory::*;
+
+const PDF: PdfAttachmentFileType = PdfAttachmentFileType::new("application/pdf", Document);
+const RTF: PdfAttachmentFileType = PdfAttachmentFileType::new("application/rtf", Document);
+const OLE: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("application/x-ole-storage", Document);
+const DOCX: PdfAttachmentFileType = PdfAttachmentFileType::new(
+    "application/vnd.openxmlformats-officedocument.wordprocessingml.document",
+    Document,
+);
+const XLSX: PdfAttachmentFileType = PdfAttachmentFileType::new(
+    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet",
+    Document,
+);
+const PPTX: PdfAttachmentFileType = PdfAttachmentFileType::new(
+    "application/vnd.openxmlformats-officedocument.presentationml.presentation",
+    Document,
+);
+const OPEN_DOCUMENT: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("application/vnd.oasis.opendocument", Document);
+const EPUB: PdfAttachmentFileType = PdfAttachmentFileType::new("application/epub+zip", Document);
+const PNG: PdfAttachmentFileType = PdfAttachmentFileType::new("image/png", Image);
+const JPEG: PdfAttachmentFileType = PdfAttachmentFileType::new("image/jpeg", Image);
+const GIF: PdfAttachmentFileType = PdfAttachmentFileType::new("image/gif", Image);
+const TIFF: PdfAttachmentFileType = PdfAttachmentFileType::new("image/tiff", Image);
+const BMP: PdfAttachmentFileType = PdfAttachmentFileType::new("image/bmp", Image);
+const WEBP: PdfAttachmentFileType = PdfAttachmentFileType::new("image/webp", Image);
+const WAV: PdfAttachmentFileType = PdfAttachmentFileType::new("audio/wav", Audio);
+const MP3: PdfAttachmentFileType = PdfAttachmentFileType::new("audio/mpeg", Audio);
+const OGG: PdfAttachmentFileType = PdfAttachmentFileType::new("audio/ogg", Audio);
+const FLAC: PdfAttachmentFileType = PdfAttachmentFileType::new("audio/flac", Audio);
+const AVI: PdfAttachmentFileType = PdfAttachmentFileType::new("video/x-msvideo", Video);
+const MP4: PdfAttachmentFileType = PdfAttachmentFileType::new("video/mp4", Video);
+const PLAIN_TEXT: PdfAttachmentFileType = PdfAttachmentFileType::new("text/plain", Text);
+const CSV: PdfAttachmentFileType = PdfAttachmentFileType::new("text/csv", Text);
+const JSON: PdfAttachmentFileType = PdfAttachmentFileType::new("application/json", Text);
+const XML: PdfAttachmentFileType = PdfAttachmentFileType::new("application/xml", Text);
+const HTML: PdfAttachmentFileType = PdfAttachmentFileType::new("text/html", Script);
+const ZIP: PdfAttachmentFileType = PdfAttachmentFileType::new("application/zip", Archive);
+const RAR: PdfAttachmentFileType = PdfAttachmentFileType::new("application/vnd.rar", Archive);
+const SEVEN_ZIP: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("application/x-7z-compressed", Archive);
+const GZIP: PdfAttachmentFileType = PdfAttachmentFileType::new("application/gzip", Archive);
+const BZIP2: PdfAttachmentFileType = PdfAttachmentFileType::new("application/x-bzip2", Archive);
+const XZ: PdfAttachmentFileType = PdfAttachmentFileType::new("application/x-xz", Archive);
+const TAR: PdfAttachmentFileType = PdfAttachmentFileType::new("application/x-tar", Archive);
+const PORTABLE_EXECUTABLE: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("application/vnd.microsoft.portable-executable", Executable);
+const ELF: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("application/x-executable", Executable);
+const MACH_O: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("application/x-mach-binary", Executable);
+const JAVA_CLASS: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("application/java-vm", Executable);
+const JAR: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("application/java-archive", Executable);
+const SHELL_SCRIPT: PdfAttachmentFileType =
+    PdfAttachmentFileType::new("text/x-shellscript", Script);
+const JAVASCRIPT: PdfAttachmentFileType = PdfAttachmentFileType::new("text/javascript", Script);
+const VBSCRIPT: PdfAttachmentFileType = PdfAttachmentFileType::new("text/vbscript", Script);
+const POWERSHELL: PdfAttachmentFileType = PdfAttachmentFileType::new("text/x-powershell", Script);
+const BATCH: PdfAttachmentFileType = PdfAttachmentFileType::new("application/x-bat", Script);
+const PYTHON: PdfAttachmentFileType = PdfAttachmentFileType::new("text/x-python", Script);
+const PERL: PdfAttachmentFileType = PdfAttachmentFileType::new("text/x-perl", Script);
+const RUBY: PdfAttachmentFileType = PdfAttachmentFileType::new("text/x-ruby", Script);
+const PHP: PdfAttachmentFileType = PdfAttachmentFileType::new("application/x-httpd-php", Script);
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_detects_file_types_from_magic_bytes() {
+        assert_eq!(
+            PdfAttachmentFileType::from_bytes(b"%PDF-1.7\n...")
+                .unwrap()
+                .mime_type(),
+            "application/pdf"
+        );
+
+        assert_eq!(
+            PdfAttachmentFileType::from_bytes(b"MZ\x90\x00\x03")
+                .unwrap()
+                .category(),
+            PdfAttachmentCategory::Executable
+        );
+
+        assert_eq!(
+            PdfAttachmentFileType::from_bytes(b"PK\x03\x04\x14\x00")
+                .unwrap()
+                .category(),
+            PdfAttachmentCategory::Archive
+        );
+
+        assert_eq!(
+            PdfAttachmentFileType::from_bytes(b"Quarterly results\r\n")
+                .unwrap()
+                .mime_type(),
+            "text/plain"
+        );
+
+        assert_eq!(PdfAttachmentFileType::from_bytes(&[0x00, 0x01, 0x02]), None);
+    }
+
+    #[test]
+    fn test_classification_flags_mismatches_and_risky_content() {
+        let disguised = PdfAttachmentClassification::classify("invoice.pdf", b"MZ\x90\x00");
+
+        assert!(disguised.is_mismatched());
+        assert!(disguised.is_risky());
+        assert_eq!(disguised.category(), PdfAttachmentCategory::Executable);
+
+        let genuine = PdfAttachmentClassification::classify("invoice.pdf", b"%PDF-1.4\n");
+
+        assert!(!genuine.is_mismatched());
+        assert!(!genuine.is_risky());
+
+        let spreadsheet = PdfAttachmentClassification::classify("data.XLSX", b"PK\x03\x04");
+
+        assert!(!spreadsheet.is_mismatched());
+        assert_eq!(
+            spreadsheet.declared().unwrap().category(),
+            PdfAttachmentCategory::Document
+        );
+
+        let script = PdfAttachmentClassification::classify("setup.sh", b"#!/bin/sh\n");
+
+        assert!(!script.is_mismatched());
+        assert!(script.is_risky());
+    }
+}
@@ -0,0 +1,287 @@
+//! Reads and writes the description and MIME type of the attachments in a document's
+//! name tree of embedded files. Pdfium exposes neither the file specification dictionary
+//! holding an attachment's description nor the embedded file stream dictionary holding
+//! its MIME type, so both are accessed at the syntax level.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::syntax::{
+    decode_pdf_string, PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject,
+};
+
+/// The maximum depth of the name tree of embedded files followed when locating an attachment.
+const MAXIMUM_NAME_TREE_DEPTH: usize = 32;
+
+/// The keys of the embedded file stream in a file specification's `/EF` dictionary,
+/// in the order in which Pdfium consults them.
+const EMBEDDED_FILE_KEYS: &[&str] = &["UF", "F", "DOS", "Mac", "Unix"];
+
+/// Returns the file specification of the attachment at the given index in the name tree of
+/// embedded files of the given file, counting attachments in the same order as Pdfium.
+fn find_file_specification(
+    file: &PdfSyntaxFile,
+    index: usize,
+) -> Result<PdfSyntaxObject, PdfiumError> {
+    let catalog = file.resolve(
+        file.trailer
+            .as_ref()
+            .and_then(|trailer| trailer.get("Root"))
+            .ok_or(PdfiumError::AttachmentIndexOutOfBounds)?,
+    );
+
+    let names = catalog
+        .get("Names")
+        .map(|names| file.resolve(names))
+        .ok_or(PdfiumError::AttachmentIndexOutOfBounds)?;
+
+    let tree = names
+        .get("EmbeddedFiles")
+        .map(|tree| file.resolve(tree))
+        .ok_or(PdfiumError::AttachmentIndexOutOfBounds)?;
+
+    let mut remaining = index;
+
+    find_in_name_tree(file, &tree, &mut remaining, 0).ok_or(PdfiumError::AttachmentIndexOutOfBounds)
+}
+
+/// Searches the given name tree node for the value at the given index, decrementing the
+/// index past each value that is skipped.
+fn find_in_name_tree(
+    file: &PdfSyntaxFile,
+    node: &PdfSyntaxObject,
+    remaining: &mut usize,
+    depth: usize,
+) -> Option<PdfSyntaxObject> {
+    if depth > MAXIMUM_NAME_TREE_DEPTH {
+        return None;
+    }
+
+    if let Some(PdfSyntaxObject::Array(names)) = node.get("Names").map(|names| file.resolve(names))
+    {
+        let count = names.len() / 2;
+
+        if *remaining < count {
+            return names.get(*remaining * 2 + 1).cloned();
+        }
+
+        *remaining -= count;
+
+        return None;
+    }
+
+    if let Some(PdfSyntaxObject::Array(kids)) = node.get("Kids").map(|kids| file.resolve(kids)) {
+        for kid in kids.iter() {
+            if let Some(value) = find_in_name_tree(file, &file.resolve(kid), remaining, depth + 1) {
+                return Some(value);
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns the embedded file stream referenced by the given file specification, if any.
+pub(crate) fn find_embedded_file(
+    file: &PdfSyntaxFile,
+    specification: &PdfSyntaxObject,
+) -> Option<u32> {
+    let embedded_files = file.resolve(specification.get("EF")?);
+
+    EMBEDDED_FILE_KEYS
+        .iter()
+        .find_map(|key| match embedded_files.get(key) {
+            Some(PdfSyntaxObject::Reference(number, _)) => Some(*number),
+            _ => None,
+        })
+}
+
+/// Returns the parsed form of the given file, or [PdfiumError::AttachmentDetailsUnreadable]
+/// if it cannot be parsed or is encrypted.
+fn parse_unencrypted(file: &[u8]) -> Result<PdfSyntaxFile, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(file).ok_or(PdfiumError::AttachmentDetailsUnreadable)?;
+
+    match parsed.trailer.as_ref() {
+        Some(trailer) if trailer.get("Encrypt").is_none() => Ok(parsed),
+        _ => Err(PdfiumError::AttachmentDetailsUnreadable),
+    }
+}
+
+/// Returns the description of the attachment at the given index in the given file.
+pub(crate) fn read_attachment_description(
+    file: &[u8],
+    index: usize,
+) -> Result<Option<String>, PdfiumError> {
+    let parsed = parse_unencrypted(file)?;
+
+    let specification = parsed.resolve(&find_file_specification(&parsed, index)?);
+
+    match specification.get("Desc").map(|desc| parsed.resolve(desc)) {
+        Some(PdfSyntaxObject::String(bytes)) => Ok(Some(decode_pdf_string(&bytes))),
+        _ => Ok(None),
+    }
+}
+
+/// Returns the MIME type of the attachment at the given index in the given file.
+pub(crate) fn read_attachment_mime_type(
+    file: &[u8],
+    index: usize,
+) -> Result<Option<String>, PdfiumError> {
+    let parsed = parse_unencrypted(file)?;
+
+    let specification = parsed.resolve(&find_file_specification(&parsed, index)?);
+
+    let subtype = find_embedded_file(&parsed, &specification)
+        .and_then(|number| parsed.objects.get(&number))
+        .and_then(|stream| stream.object.get("Subtype").cloned());
+
+    match subtype {
+        Some(PdfSyntaxObject::Name(name)) => Ok(Some(name)),
+        _ => Ok(None),
+    }
+}
+
+/// Parses the given file and locates the file specification of the attachment at the given
+/// index, returning the parsed file and the object number of the file specification.
+fn parse_for_update(file: &[u8], index: usize) -> Result<(PdfSyntaxFile, u32), PdfiumError> {
+    let parsed = parse_unencrypted(file).map_err(|_| PdfiumError::DocumentUpdateUnsupported)?;
+
+    match find_file_specification(&parsed, index)? {
+        PdfSyntaxObject::Reference(number, _) => Ok((parsed, number)),
+
+        // A file specification written directly into the name tree cannot be replaced
+        // without rewriting the name tree node containing it.
+        _ => Err(PdfiumError::DocumentUpdateUnsupported),
+    }
+}
+
+/// Appends an incremental update to the given file setting the description of the
+/// attachment at the given index, or removing it if `None` is given.
+pub(crate) fn write_attachment_description(
+    file: Vec<u8>,
+    index: usize,
+    description: Option<&str>,
+) -> Result<Vec<u8>, PdfiumError> {
+    let (parsed, number) = parse_for_update(&file, index)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut specification = update
+        .get(number)
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    match description {
+        Some(description) => specification.set("Desc", PdfSyntaxObject::text(description)),
+        None => specification.remove("Desc"),
+    }
+
+    update.set(number, specification);
+
+    Ok(update.write(file))
+}
+
+/// Appends an incremental update to the given file setting the MIME type of the attachment
+/// at the given index, or removing it if `None` is given. The embedded file stream is
+/// rewritten with its existing, possibly compressed, data.
+pub(crate) fn write_attachment_mime_type(
+    file: Vec<u8>,
+    index: usize,
+    mime_type: Option<&str>,
+) -> Result<Vec<u8>, PdfiumError> {
+    let (parsed, number) = parse_for_update(&file, index)?;
+
+    let specification = parsed.resolve(&PdfSyntaxObject::Reference(number, 0));
+
+    let stream_number =
+        find_embedded_file(&parsed, &specification).ok_or(PdfiumError::NoDataInAttachment)?;
+
+    let stream = parsed
+        .objects
+        .get(&stream_number)
+        .ok_or(PdfiumError::NoDataInAttachment)?;
+
+    let data = stream
+        .stream_range
+        .clone()
+        .and_then(|range| file.get(range))
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?
+        .to_vec();
+
+    let mut dictionary = stream.object.clone();
+
+    match mime_type {
+        Some(mime_type) => dictionary.set("Subtype", PdfSyntaxObject::name(mime_type)),
+        None => dictionary.remove("Subtype"),
+    }
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    update.set_stream(stream_number, dictionary, data);
+
+    Ok(update.write(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::attachment::details::*;
+
+    const ATTACHMENTS_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R /Names << /EmbeddedFiles 3 0 R >> >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n\
+        3 0 obj\n<< /Kids [4 0 R 5 0 R] >>\nendobj\n\
+        4 0 obj\n<< /Names [(a.txt) 6 0 R] /Limits [(a.txt) (a.txt)] >>\nendobj\n\
+        5 0 obj\n<< /Names [(b.txt) 7 0 R] /Limits [(b.txt) (b.txt)] >>\nendobj\n\
+        6 0 obj\n<< /Type /Filespec /F (a.txt) /Desc (First) /EF << /F 8 0 R >> >>\nendobj\n\
+        7 0 obj\n<< /Type /Filespec /F (b.txt) /EF << /F 9 0 R >> >>\nendobj\n\
+        8 0 obj\n<< /Type /EmbeddedFile /Subtype /text#2Fplain /Length 5 >>\nstream\nHello\nendstream\nendobj\n\
+        9 0 obj\n<< /Type /EmbeddedFile /Length 5 >>\nstream\nWorld\nendstream\nendobj\n\
+        xref\n0 10\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 10 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    #[test]
+    fn test_read_attachment_details() {
+        assert_eq!(
+            read_attachment_description(ATTACHMENTS_PDF, 0).unwrap(),
+            Some("First".to_owned())
+        );
+        assert_eq!(
+            read_attachment_description(ATTACHMENTS_PDF, 1).unwrap(),
+            None
+        );
+        assert_eq!(
+            read_attachment_mime_type(ATTACHMENTS_PDF, 0).unwrap(),
+            Some("text/plain".to_owned())
+        );
+        assert_eq!(read_attachment_mime_type(ATTACHMENTS_PDF, 1).unwrap(), None);
+        assert!(matches!(
+            read_attachment_description(ATTACHMENTS_PDF, 2),
+            Err(PdfiumError::AttachmentIndexOutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn test_write_attachment_details() {
+        let file =
+            write_attachment_description(ATTACHMENTS_PDF.to_vec(), 1, Some("Second")).unwrap();
+
+        let file = write_attachment_description(file, 0, None).unwrap();
+
+        let file = write_attachment_mime_type(file, 1, Some("application/pdf")).unwrap();
+
+        assert_eq!(read_attachment_description(&file, 0).unwrap(), None);
+        assert_eq!(
+            read_attachment_description(&file, 1).unwrap(),
+            Some("Second".to_owned())
+        );
+        assert_eq!(
+            read_attachment_mime_type(&file, 1).unwrap(),
+            Some("application/pdf".to_owned())
+        );
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let stream = parsed.objects.get(&9).unwrap();
+
+        assert_eq!(&file[stream.stream_range.clone().unwrap()], b"World");
+    }
+}
@@ -97,7 +97,11 @@ impl<'a> PdfAttachments<'a> {
                 PdfiumInternalError::Unknown,
             ))
         } else {
-            Ok(PdfAttachment::from_pdfium(handle, self.bindings()))
+            Ok(PdfAttachment::from_pdfium(
+                handle,
+                self.document_handle,
+                self.bindings(),
+            ))
         }
     }
 
@@ -132,7 +136,11 @@ impl<'a> PdfAttachments<'a> {
                     bytes.len() as c_ulong,
                 ))
             {
-                Ok(PdfAttachment::from_pdfium(handle, self.bindings))
+                Ok(PdfAttachment::from_pdfium(
+                    handle,
+                    self.document_handle,
+                    self.bindings,
+                ))
             } else {
                 // The return value from FPDFAttachment_SetFile() indicates failure.
 
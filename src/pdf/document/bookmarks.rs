@@ -5,6 +5,8 @@ use crate::bindgen::{FPDF_BOOKMARK, FPDF_DOCUMENT};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::{PdfiumError, PdfiumInternalError};
 use crate::pdf::document::bookmark::PdfBookmark;
+use crate::pdf::document::outline::{PdfBookmarkDestination, PdfBookmarkId, PdfOutline};
+use crate::pdf::document::save_document_to_bytes;
 use std::collections::{HashMap, VecDeque};
 use std::ptr::null_mut;
 
@@ -18,8 +20,19 @@ use std::ptr::null_mut;
 /// To search the tree for a bookmark with a specific title, use the [PdfBookmarks::find_first_by_title()]
 /// and [PdfBookmarks::find_all_by_title()] functions. To traverse the tree breadth-first, visiting
 /// every bookmark in the tree, create an iterator using the [PdfBookmarks::iter()] function.
+///
+/// Pdfium cannot create or edit bookmarks, so edits are made to a copy of the bookmark tree
+/// that is read from the document when it is first edited, and written to the document,
+/// replacing its existing bookmarks, when the document is saved. Bookmarks in the edited tree
+/// are identified by [PdfBookmarkId] values: those returned when adding bookmarks using the
+/// [PdfBookmarks::add_root()], [PdfBookmarks::add_child()], and [PdfBookmarks::insert()]
+/// functions, and those returned for existing bookmarks by the [PdfBookmarks::id_of()] and
+/// [PdfBookmarks::child_ids()] functions. The [PdfBookmark] objects returned by
+/// [PdfBookmarks::root()] and [PdfBookmarks::iter()] continue to describe the document's
+/// bookmarks as they were before editing until the document is saved and reloaded.
 pub struct PdfBookmarks<'a> {
     document_handle: FPDF_DOCUMENT,
+    outline: Option<PdfOutline>,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
 
@@ -31,6 +44,7 @@ impl<'a> PdfBookmarks<'a> {
     ) -> Self {
         Self {
             document_handle,
+            outline: None,
             bindings,
         }
     }
@@ -107,6 +121,195 @@ impl<'a> PdfBookmarks<'a> {
             .collect()
     }
 
+    /// Appends a new top-level bookmark with the given title and destination to the end
+    /// of the bookmark tree, returning its [PdfBookmarkId].
+    #[inline]
+    pub fn add_root(
+        &mut self,
+        title: &str,
+        destination: PdfBookmarkDestination,
+    ) -> Result<PdfBookmarkId, PdfiumError> {
+        let index = self.child_ids(None)?.len();
+
+        self.insert(None, index, title, destination)
+    }
+
+    /// Appends a new bookmark with the given title and destination to the end of the
+    /// children of the given parent bookmark, returning its [PdfBookmarkId].
+    #[inline]
+    pub fn add_child(
+        &mut self,
+        parent: PdfBookmarkId,
+        title: &str,
+        destination: PdfBookmarkDestination,
+    ) -> Result<PdfBookmarkId, PdfiumError> {
+        let index = self.child_ids(Some(parent))?.len();
+
+        self.insert(Some(parent), index, title, destination)
+    }
+
+    /// Inserts a new bookmark with the given title and destination at the given index among
+    /// the children of the given parent bookmark, or among the top-level bookmarks if `None`
+    /// is given, returning its [PdfBookmarkId].
+    ///
+    /// Returns [PdfiumError::PageIndexOutOfBounds] if the destination's page does not exist,
+    /// or [PdfiumError::BookmarkPositionInvalid] if the index is past the end of the parent's
+    /// children. The destination's page index is resolved to a page when the document is
+    /// saved, so it should be updated if pages are later inserted or removed before it.
+    pub fn insert(
+        &mut self,
+        parent: Option<PdfBookmarkId>,
+        index: usize,
+        title: &str,
+        destination: PdfBookmarkDestination,
+    ) -> Result<PdfBookmarkId, PdfiumError> {
+        self.check_destination(&destination)?;
+
+        self.outline_mut()?
+            .insert(parent, index, title, Some(destination))
+    }
+
+    /// Moves the given bookmark, along with its descendants, to the given index among the
+    /// children of the given parent bookmark, or among the top-level bookmarks if `None` is
+    /// given. When moving a bookmark within its current parent, the index gives its position
+    /// after it has been removed from its current position.
+    ///
+    /// Returns [PdfiumError::BookmarkPositionInvalid] if the index is out of range, or if
+    /// the parent is the bookmark itself or one of its descendants.
+    #[inline]
+    pub fn move_to(
+        &mut self,
+        bookmark: PdfBookmarkId,
+        parent: Option<PdfBookmarkId>,
+        index: usize,
+    ) -> Result<(), PdfiumError> {
+        self.outline_mut()?.move_to(bookmark, parent, index)
+    }
+
+    /// Sets the title of the given bookmark.
+    #[inline]
+    pub fn set_title(&mut self, bookmark: PdfBookmarkId, title: &str) -> Result<(), PdfiumError> {
+        self.outline_mut()?.set_title(bookmark, title)
+    }
+
+    /// Sets the destination of the given bookmark, replacing any existing destination
+    /// or action.
+    pub fn set_destination(
+        &mut self,
+        bookmark: PdfBookmarkId,
+        destination: PdfBookmarkDestination,
+    ) -> Result<(), PdfiumError> {
+        self.check_destination(&destination)?;
+
+        self.outline_mut()?.set_destination(bookmark, destination)
+    }
+
+    /// Removes the given bookmark, along with all its descendants.
+    #[inline]
+    pub fn remove(&mut self, bookmark: PdfBookmarkId) -> Result<(), PdfiumError> {
+        self.outline_mut()?.remove(bookmark)
+    }
+
+    /// Returns the [PdfBookmarkId] identifying the given existing [PdfBookmark] in the
+    /// edited bookmark tree. Returns [PdfiumError::BookmarkNotFound] if the bookmark
+    /// has been removed.
+    pub fn id_of(&mut self, bookmark: &PdfBookmark) -> Result<PdfBookmarkId, PdfiumError> {
+        self.outline_mut()?
+            .find_handle(bookmark.bookmark_handle())
+            .ok_or(PdfiumError::BookmarkNotFound)
+    }
+
+    /// Returns the [PdfBookmarkId] values of the children of the given bookmark in the edited
+    /// bookmark tree, or of the top-level bookmarks if `None` is given, in order.
+    #[inline]
+    pub fn child_ids(
+        &mut self,
+        parent: Option<PdfBookmarkId>,
+    ) -> Result<Vec<PdfBookmarkId>, PdfiumError> {
+        Ok(self.outline_mut()?.children(parent)?.to_vec())
+    }
+
+    /// Returns the title of the given bookmark in the edited bookmark tree.
+    #[inline]
+    pub fn title_of(&mut self, bookmark: PdfBookmarkId) -> Result<String, PdfiumError> {
+        Ok(self.outline_mut()?.title(bookmark)?.to_owned())
+    }
+
+    /// Returns the parent of the given bookmark in the edited bookmark tree, or `None`
+    /// if it is a top-level bookmark.
+    #[inline]
+    pub fn parent_of(
+        &mut self,
+        bookmark: PdfBookmarkId,
+    ) -> Result<Option<PdfBookmarkId>, PdfiumError> {
+        self.outline_mut()?.parent(bookmark)
+    }
+
+    /// Returns `true` if the bookmark tree has been edited since the document was loaded.
+    #[inline]
+    pub(crate) fn is_modified(&self) -> bool {
+        self.outline.is_some()
+    }
+
+    /// Writes the edited bookmark tree to the given PDF file, returning the updated file.
+    /// The file is returned unchanged if the bookmark tree has not been edited.
+    pub(crate) fn write_to_file(&self, file: Vec<u8>) -> Result<Vec<u8>, PdfiumError> {
+        match self.outline.as_ref() {
+            Some(outline) => outline.write(file),
+            None => Ok(file),
+        }
+    }
+
+    /// Returns the edited bookmark tree, reading it from the document if it has not yet
+    /// been edited. Returns [PdfiumError::DocumentUpdateUnsupported] if the document
+    /// is encrypted.
+    pub(crate) fn outline_mut(&mut self) -> Result<&mut PdfOutline, PdfiumError> {
+        if self.outline.is_none() {
+            let mut outline = PdfOutline::read(&save_document_to_bytes(
+                self.document_handle,
+                self.bindings,
+            )?)?;
+
+            let document_handle = self.document_handle;
+
+            let bindings = self.bindings;
+
+            let non_null = |handle: FPDF_BOOKMARK| (!handle.is_null()).then(|| handle);
+
+            outline.attach_handles(
+                &|parent| {
+                    non_null(
+                        bindings.FPDFBookmark_GetFirstChild(
+                            document_handle,
+                            parent.unwrap_or(null_mut()),
+                        ),
+                    )
+                },
+                &|bookmark| {
+                    non_null(bindings.FPDFBookmark_GetNextSibling(document_handle, bookmark))
+                },
+            );
+
+            self.outline = Some(outline);
+        }
+
+        self.outline
+            .as_mut()
+            .ok_or(PdfiumError::DocumentUpdateUnsupported)
+    }
+
+    /// Returns [PdfiumError::PageIndexOutOfBounds] if the page targeted by the given
+    /// [PdfBookmarkDestination] does not exist.
+    fn check_destination(&self, destination: &PdfBookmarkDestination) -> Result<(), PdfiumError> {
+        let page_count = self.bindings.FPDF_GetPageCount(self.document_handle);
+
+        if (destination.page_index() as i32) < page_count {
+            Ok(())
+        } else {
+            Err(PdfiumError::PageIndexOutOfBounds)
+        }
+    }
+
     /// Returns a breadth-first iterator over all the [PdfBookmark] objects in the containing
     /// `PdfDocument`, starting from the top-level root bookmark.
     #[inline]
@@ -308,3 +511,76 @@ impl<'a> Iterator for PdfBookmarksIterator<'a> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_bookmarks_persisted_on_save() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        for _ in 0..3 {
+            document
+                .pages_mut()
+                .create_page_at_end(PdfPagePaperSize::a4())?;
+        }
+
+        let bookmarks = document.bookmarks_mut();
+
+        let chapter = bookmarks.add_root("Chapter 1", PdfBookmarkDestination::page(0))?;
+
+        let section =
+            bookmarks.add_child(chapter, "Section 1.1", PdfBookmarkDestination::page(1))?;
+
+        bookmarks.add_root("Chapter 2", PdfBookmarkDestination::page(2))?;
+        bookmarks.set_title(section, "Introduction")?;
+
+        assert!(matches!(
+            bookmarks.add_root("Missing", PdfBookmarkDestination::page(3)),
+            Err(PdfiumError::PageIndexOutOfBounds)
+        ));
+
+        let bytes = document.save_to_bytes()?;
+
+        let mut document = pdfium.load_pdf_from_byte_vec(bytes, None)?;
+
+        let titles = document
+            .bookmarks()
+            .iter()
+            .filter_map(|bookmark| bookmark.title())
+            .collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Chapter 1", "Introduction", "Chapter 2"]);
+
+        let introduction = document.bookmarks().find_first_by_title("Introduction")?;
+
+        assert_eq!(introduction.destination().unwrap().page_index()?, 1);
+
+        // Existing bookmarks can be identified, reordered, and removed.
+
+        let bookmarks = document.bookmarks_mut();
+
+        let roots = bookmarks.child_ids(None)?;
+
+        bookmarks.move_to(roots[1], None, 0)?;
+        bookmarks.remove(roots[0])?;
+
+        let bytes = document.save_to_bytes()?;
+
+        let document = pdfium.load_pdf_from_byte_vec(bytes, None)?;
+
+        let titles = document
+            .bookmarks()
+            .iter()
+            .filter_map(|bookmark| bookmark.title())
+            .collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Chapter 2"]);
+
+        Ok(())
+    }
+}
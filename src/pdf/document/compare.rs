@@ -0,0 +1,387 @@
+//! Defines the [PdfTextDiff] struct, a word-level comparison of the text in two
+//! `PdfDocument` objects, as returned by the [text_diff()] function.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::page::text::layout::PdfTextWord;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::PdfDocument;
+use std::ops::Range;
+
+/// The maximum number of inserted and deleted words on a single page for which the smallest
+/// set of changes is calculated. Pages that differ by more words than this are reported as a
+/// single changed hunk covering everything between their common leading and trailing words.
+const MAXIMUM_EDIT_DISTANCE: usize = 2000;
+
+/// The kind of change described by a [PdfTextDiffHunk].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfTextDiffHunkKind {
+    /// Words present only in the second document were inserted.
+    Insert,
+
+    /// Words present only in the first document were deleted.
+    Delete,
+
+    /// Words in the first document were replaced by different words in the second document.
+    Change,
+}
+
+/// A single run of consecutive changed words on a page, as part of a [PdfPageTextDiff].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextDiffHunk {
+    deleted_words: Vec<PdfTextWord>,
+    inserted_words: Vec<PdfTextWord>,
+}
+
+impl PdfTextDiffHunk {
+    /// Returns the kind of change described by this [PdfTextDiffHunk].
+    #[inline]
+    pub fn kind(&self) -> PdfTextDiffHunkKind {
+        if self.deleted_words.is_empty() {
+            PdfTextDiffHunkKind::Insert
+        } else if self.inserted_words.is_empty() {
+            PdfTextDiffHunkKind::Delete
+        } else {
+            PdfTextDiffHunkKind::Change
+        }
+    }
+
+    /// Returns the words removed by this [PdfTextDiffHunk], as they appear on the page in
+    /// the first document, with their positions on that page.
+    #[inline]
+    pub fn deleted_words(&self) -> &[PdfTextWord] {
+        self.deleted_words.as_slice()
+    }
+
+    /// Returns the words added by this [PdfTextDiffHunk], as they appear on the page in
+    /// the second document, with their positions on that page.
+    #[inline]
+    pub fn inserted_words(&self) -> &[PdfTextWord] {
+        self.inserted_words.as_slice()
+    }
+
+    /// Returns the text of the words removed by this [PdfTextDiffHunk], separated by spaces.
+    #[inline]
+    pub fn deleted_text(&self) -> String {
+        join_words(&self.deleted_words)
+    }
+
+    /// Returns the text of the words added by this [PdfTextDiffHunk], separated by spaces.
+    #[inline]
+    pub fn inserted_text(&self) -> String {
+        join_words(&self.inserted_words)
+    }
+}
+
+/// The differences between the text on the page at a single index in two documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfPageTextDiff {
+    page_index: PdfPageIndex,
+    hunks: Vec<PdfTextDiffHunk>,
+}
+
+impl PdfPageTextDiff {
+    /// Returns the zero-based index of the page compared in this [PdfPageTextDiff].
+    #[inline]
+    pub fn page_index(&self) -> PdfPageIndex {
+        self.page_index
+    }
+
+    /// Returns the runs of changed words on the page, in reading order.
+    #[inline]
+    pub fn hunks(&self) -> &[PdfTextDiffHunk] {
+        self.hunks.as_slice()
+    }
+}
+
+/// A word-level comparison of the text in two documents, as returned by the [text_diff()]
+/// function.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PdfTextDiff {
+    pages: Vec<PdfPageTextDiff>,
+}
+
+impl PdfTextDiff {
+    /// Returns the differences for every page whose text differs between the two documents,
+    /// in page order. Pages with identical text are omitted.
+    #[inline]
+    pub fn pages(&self) -> &[PdfPageTextDiff] {
+        self.pages.as_slice()
+    }
+
+    /// Returns `true` if the text of both documents is identical.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.pages.is_empty()
+    }
+}
+
+/// Compares the text in the two given documents word by word, page by page, returning
+/// the runs of words that were inserted, deleted, or changed on each page.
+///
+/// The page at each index in the first document is compared with the page at the same
+/// index in the second document. Words on pages present in only one document are reported
+/// as entirely deleted or entirely inserted. Words are compared by their text alone;
+/// changes to a word's font, size, or position are not reported.
+///
+/// Each word in a hunk carries its bounding box on its page, suitable for highlighting the
+/// change when rendering either document.
+pub fn text_diff(a: &PdfDocument, b: &PdfDocument) -> Result<PdfTextDiff, PdfiumError> {
+    let page_count = a.pages().len().max(b.pages().len());
+
+    let mut pages = Vec::new();
+
+    for page_index in 0..page_count {
+        let words_a = page_words(a, page_index)?;
+
+        let words_b = page_words(b, page_index)?;
+
+        let hunks = diff_hunks(&words_a, &words_b)
+            .into_iter()
+            .map(|(deleted, inserted)| PdfTextDiffHunk {
+                deleted_words: words_a[deleted].to_vec(),
+                inserted_words: words_b[inserted].to_vec(),
+            })
+            .collect::<Vec<_>>();
+
+        if !hunks.is_empty() {
+            pages.push(PdfPageTextDiff { page_index, hunks });
+        }
+    }
+
+    Ok(PdfTextDiff { pages })
+}
+
+/// Returns the words on the page at the given index in the given document, in reading
+/// order, or an empty list if the document has no page at that index.
+fn page_words(
+    document: &PdfDocument,
+    page_index: PdfPageIndex,
+) -> Result<Vec<PdfTextWord>, PdfiumError> {
+    if page_index >= document.pages().len() {
+        return Ok(Vec::new());
+    }
+
+    Ok(document
+        .pages()
+        .get(page_index)?
+        .text()?
+        .layout()
+        .words()
+        .cloned()
+        .collect())
+}
+
+#[inline]
+fn join_words(words: &[PdfTextWord]) -> String {
+    words
+        .iter()
+        .map(|word| word.text())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Compares the text of the given words, returning the ranges of words deleted from the
+/// first list and inserted into the second list by each run of consecutive changes.
+/// Either range in a run may be empty.
+pub(crate) fn diff_hunks(
+    a: &[PdfTextWord],
+    b: &[PdfTextWord],
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let a = a.iter().map(|word| word.text()).collect::<Vec<_>>();
+
+    let b = b.iter().map(|word| word.text()).collect::<Vec<_>>();
+
+    diff_sequences(&a, &b)
+}
+
+/// Compares the given sequences, returning the ranges of items deleted from the first
+/// sequence and inserted into the second sequence by each run of consecutive changes,
+/// using Myers' algorithm to find the smallest set of changes.
+pub(crate) fn diff_sequences<T: PartialEq>(a: &[T], b: &[T]) -> Vec<(Range<usize>, Range<usize>)> {
+    // Items common to the start and end of both sequences are never part of a change.
+
+    let prefix = a.iter().zip(b.iter()).take_while(|(a, b)| a == b).count();
+
+    let suffix = a[prefix..]
+        .iter()
+        .rev()
+        .zip(b[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let middle_a = &a[prefix..a.len() - suffix];
+
+    let middle_b = &b[prefix..b.len() - suffix];
+
+    if middle_a.is_empty() && middle_b.is_empty() {
+        return Vec::new();
+    }
+
+    let matches = match shortest_edit_matches(middle_a, middle_b) {
+        Some(matches) => matches,
+        None => {
+            return vec![(
+                prefix..prefix + middle_a.len(),
+                prefix..prefix + middle_b.len(),
+            )]
+        }
+    };
+
+    // Each run of changes lies between two consecutive matching items.
+
+    let mut hunks = Vec::new();
+
+    let mut next = (0, 0);
+
+    for (x, y) in matches
+        .into_iter()
+        .chain(std::iter::once((middle_a.len(), middle_b.len())))
+    {
+        if x > next.0 || y > next.1 {
+            hunks.push((prefix + next.0..prefix + x, prefix + next.1..prefix + y));
+        }
+
+        next = (x + 1, y + 1);
+    }
+
+    hunks
+}
+
+/// Returns the positions of the matching items in both sequences that remain after the
+/// smallest set of insertions and deletions transforming the first sequence into the second,
+/// in ascending order. Returns `None` if more than [MAXIMUM_EDIT_DISTANCE] insertions and
+/// deletions are required.
+fn shortest_edit_matches<T: PartialEq>(a: &[T], b: &[T]) -> Option<Vec<(usize, usize)>> {
+    let n = a.len() as isize;
+
+    let m = b.len() as isize;
+
+    let limit = (a.len() + b.len()).min(MAXIMUM_EDIT_DISTANCE) as isize;
+
+    let offset = limit + 1;
+
+    // v[k + offset] holds the furthest x position reached on diagonal k = x - y. The state
+    // of v before each round is kept so that the path can be traced back afterwards.
+
+    let mut v = vec![0_isize; 2 * offset as usize + 1];
+
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+
+    for d in 0..=limit {
+        trace.push(v.clone());
+
+        for k in (-d..=d).step_by(2) {
+            let mut x = if k == -d
+                || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize])
+            {
+                v[(k + 1 + offset) as usize]
+            } else {
+                v[(k - 1 + offset) as usize] + 1
+            };
+
+            let mut y = x - k;
+
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+
+            v[(k + offset) as usize] = x;
+
+            if x >= n && y >= m {
+                return Some(trace_matches(&trace, offset, n, m));
+            }
+        }
+    }
+
+    None
+}
+
+/// Traces the path found by [shortest_edit_matches()] back from the end of both sequences,
+/// returning the positions of the matching items along it in ascending order.
+fn trace_matches(trace: &[Vec<isize>], offset: isize, n: isize, m: isize) -> Vec<(usize, usize)> {
+    let mut matches = Vec::new();
+
+    let (mut x, mut y) = (n, m);
+
+    for (d, v) in trace.iter().enumerate().rev() {
+        let d = d as isize;
+
+        let k = x - y;
+
+        let previous_k =
+            if k == -d || (k != d && v[(k - 1 + offset) as usize] < v[(k + 1 + offset) as usize]) {
+                k + 1
+            } else {
+                k - 1
+            };
+
+        let previous_x = v[(previous_k + offset) as usize];
+
+        let previous_y = previous_x - previous_k;
+
+        while x > previous_x && y > previous_y {
+            x -= 1;
+            y -= 1;
+
+            matches.push((x as usize, y as usize));
+        }
+
+        x = previous_x;
+        y = previous_y;
+    }
+
+    matches.reverse();
+
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::compare::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_diff_sequences() {
+        let a = "the quick brown fox jumps over the lazy dog"
+            .split(' ')
+            .collect::<Vec<_>>();
+
+        let b = "the quick red fox jumps over the very lazy dog today"
+            .split(' ')
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            diff_sequences(&a, &b),
+            vec![(2..3, 2..3), (7..7, 7..8), (9..9, 10..11)]
+        );
+
+        assert_eq!(diff_sequences(&a, &a), vec![]);
+        assert_eq!(diff_sequences(&a, &[]), vec![(0..9, 0..0)]);
+        assert_eq!(diff_sequences(&[], &b), vec![(0..0, 0..11)]);
+    }
+
+    #[test]
+    fn test_text_diff_of_identical_documents() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let a = pdfium.load_pdf_from_file("./test/text-test.pdf", None)?;
+
+        let b = pdfium.load_pdf_from_file("./test/text-test.pdf", None)?;
+
+        assert!(text_diff(&a, &b)?.is_empty());
+
+        let empty = pdfium.create_new_pdf()?;
+
+        let diff = text_diff(&a, &empty)?;
+
+        assert!(diff
+            .pages()
+            .iter()
+            .flat_map(|page| page.hunks())
+            .all(|hunk| hunk.kind() == PdfTextDiffHunkKind::Delete));
+
+        Ok(())
+    }
+}
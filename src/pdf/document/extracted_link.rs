@@ -0,0 +1,349 @@
+//! Defines the [PdfExtractedLink] struct, a single deduplicated URI or email address
+//! referenced by a `PdfDocument`, as returned by the `PdfDocument::extract_links()` function.
+
+use crate::bindgen::FPDF_PAGELINK;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::action::PdfAction;
+use crate::pdf::document::bookmarks::PdfBookmarks;
+use crate::pdf::document::page::text::PdfPageText;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::rect::PdfRect;
+use crate::utils::mem::create_sized_buffer;
+use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use bytemuck::cast_slice;
+use std::os::raw::c_int;
+use std::ptr::null_mut;
+
+/// The location in a `PdfDocument` at which a [PdfExtractedLink] was found.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfExtractedLinkSource {
+    /// The link was found in the URI action of a link annotation.
+    LinkAnnotation,
+
+    /// The link was detected by Pdfium in the text of a page, for example a URL or an
+    /// email address that is not covered by a link annotation.
+    WebLink,
+
+    /// The link was found in the URI action of a bookmark.
+    Bookmark,
+}
+
+/// A single URI or email address referenced by a `PdfDocument`.
+///
+/// Links are deduplicated by URI and page: a URI referenced several times on the same page,
+/// for example by both a link annotation and the text underneath it, results in a single
+/// [PdfExtractedLink] that lists every source in which the URI was found.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfExtractedLink {
+    uri: String,
+    page_index: Option<PdfPageIndex>,
+    anchor_text: Option<String>,
+    sources: Vec<PdfExtractedLinkSource>,
+}
+
+impl PdfExtractedLink {
+    /// Returns the URI of this [PdfExtractedLink]. Email addresses detected in page text
+    /// are returned as `mailto:` URIs.
+    #[inline]
+    pub fn uri(&self) -> &str {
+        self.uri.as_str()
+    }
+
+    /// Returns `true` if this [PdfExtractedLink] is a `mailto:` URI.
+    #[inline]
+    pub fn is_email(&self) -> bool {
+        self.uri
+            .get(..7)
+            .map(|scheme| scheme.eq_ignore_ascii_case("mailto:"))
+            .unwrap_or(false)
+    }
+
+    /// Returns the zero-based index of the page on which this [PdfExtractedLink] was found,
+    /// or `None` if the link was not found on any page, for example if it was
+    /// found only in a bookmark.
+    #[inline]
+    pub fn page_index(&self) -> Option<PdfPageIndex> {
+        self.page_index
+    }
+
+    /// Returns the text associated with this [PdfExtractedLink], if any. For links found on a page,
+    /// this is the page text covered by the link; for links found in a bookmark,
+    /// this is the title of the bookmark.
+    #[inline]
+    pub fn anchor_text(&self) -> Option<&str> {
+        self.anchor_text.as_deref()
+    }
+
+    /// Returns every location in the containing `PdfDocument` at which this
+    /// [PdfExtractedLink] was found.
+    #[inline]
+    pub fn sources(&self) -> &[PdfExtractedLinkSource] {
+        self.sources.as_slice()
+    }
+}
+
+/// Accumulates [PdfExtractedLink] objects, merging links that share the same URI and page.
+#[derive(Default)]
+pub(crate) struct PdfExtractedLinks {
+    links: Vec<PdfExtractedLink>,
+}
+
+impl PdfExtractedLinks {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        PdfExtractedLinks::default()
+    }
+
+    /// Adds the given link, merging it into any previously added link with the same URI and page.
+    pub(crate) fn push(
+        &mut self,
+        uri: String,
+        page_index: Option<PdfPageIndex>,
+        anchor_text: Option<String>,
+        source: PdfExtractedLinkSource,
+    ) {
+        let uri = uri.trim().to_string();
+
+        if uri.is_empty() {
+            return;
+        }
+
+        let anchor_text = anchor_text
+            .map(|text| text.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|text| !text.is_empty());
+
+        match self
+            .links
+            .iter_mut()
+            .find(|link| link.uri == uri && link.page_index == page_index)
+        {
+            Some(link) => {
+                if link.anchor_text.is_none() {
+                    link.anchor_text = anchor_text;
+                }
+
+                if !link.sources.contains(&source) {
+                    link.sources.push(source);
+                }
+            }
+            None => self.links.push(PdfExtractedLink {
+                uri,
+                page_index,
+                anchor_text,
+                sources: vec![source],
+            }),
+        }
+    }
+
+    /// Adds the URI of every link annotation and every web link detected in the text
+    /// of the given page.
+    pub(crate) fn push_page(
+        &mut self,
+        page_index: PdfPageIndex,
+        page: &PdfPage,
+    ) -> Result<(), PdfiumError> {
+        let text = page.text()?;
+
+        for annotation in page.annotations().iter() {
+            let link = match annotation
+                .as_link_annotation()
+                .and_then(|annotation| annotation.link().ok())
+            {
+                Some(link) => link,
+                None => continue,
+            };
+
+            if let Some(PdfAction::Uri(action)) = link.action() {
+                if let Ok(uri) = action.uri() {
+                    self.push(
+                        uri,
+                        Some(page_index),
+                        text.for_annotation(&annotation).ok(),
+                        PdfExtractedLinkSource::LinkAnnotation,
+                    );
+                }
+            }
+        }
+
+        for link in web_links(&text) {
+            self.push(
+                link.url,
+                Some(page_index),
+                Some(link.text),
+                PdfExtractedLinkSource::WebLink,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Adds the URI of every bookmark with a URI action.
+    pub(crate) fn push_bookmarks(&mut self, bookmarks: &PdfBookmarks) {
+        for bookmark in bookmarks.iter() {
+            if let Some(PdfAction::Uri(action)) = bookmark.action() {
+                if let Ok(uri) = action.uri() {
+                    self.push(
+                        uri,
+                        None,
+                        bookmark.title(),
+                        PdfExtractedLinkSource::Bookmark,
+                    );
+                }
+            }
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_vec(self) -> Vec<PdfExtractedLink> {
+        self.links
+    }
+}
+
+/// A single URL or email address detected by Pdfium in the text of a page.
+pub(crate) struct PdfPageWebLink {
+    pub(crate) url: String,
+    pub(crate) text: String,
+    pub(crate) rects: Vec<PdfRect>,
+}
+
+/// Returns every web link Pdfium detects in the given page text.
+pub(crate) fn web_links(text: &PdfPageText) -> Vec<PdfPageWebLink> {
+    let bindings = text.bindings();
+
+    let link_page = bindings.FPDFLink_LoadWebLinks(*text.handle());
+
+    if link_page.is_null() {
+        return Vec::new();
+    }
+
+    let mut result = Vec::new();
+
+    for index in 0..bindings.FPDFLink_CountWebLinks(link_page) {
+        // Retrieving the URL from Pdfium is a two-step operation. First, we call
+        // FPDFLink_GetURL() with a null buffer; this will retrieve the length of
+        // the URL in UTF-16 code units, including a trailing terminator. If the length
+        // is one or less, then there is no URL.
+
+        // If the length is greater than one, then we reserve a buffer sized in code units
+        // and call FPDFLink_GetURL() again with a pointer to the buffer; this will write
+        // the URL to the buffer in UTF16-LE format.
+
+        let url_length = bindings.FPDFLink_GetURL(link_page, index, null_mut(), 0);
+
+        if url_length <= 1 {
+            continue;
+        }
+
+        let mut buffer = create_sized_buffer::<u16>(url_length as usize);
+
+        bindings.FPDFLink_GetURL(link_page, index, buffer.as_mut_ptr(), url_length);
+
+        let url = match get_string_from_pdfium_utf16le_bytes(cast_slice(buffer.as_slice()).to_vec())
+        {
+            Some(url) => url,
+            None => continue,
+        };
+
+        result.push(PdfPageWebLink {
+            url,
+            text: web_link_text(text, link_page, index, bindings),
+            rects: web_link_rects(link_page, index, bindings),
+        });
+    }
+
+    bindings.FPDFLink_CloseWebLinks(link_page);
+
+    result
+}
+
+/// Returns the rectangles covered by the web link at the given index. A link that wraps
+/// across several lines of text covers several rectangles.
+fn web_link_rects(
+    link_page: FPDF_PAGELINK,
+    index: c_int,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Vec<PdfRect> {
+    (0..bindings.FPDFLink_CountRects(link_page, index))
+        .filter_map(|rect_index| {
+            let mut left = 0.0;
+            let mut top = 0.0;
+            let mut right = 0.0;
+            let mut bottom = 0.0;
+
+            bindings
+                .is_true(bindings.FPDFLink_GetRect(
+                    link_page,
+                    index,
+                    rect_index,
+                    &mut left,
+                    &mut top,
+                    &mut right,
+                    &mut bottom,
+                ))
+                .then(|| {
+                    PdfRect::new_from_values(bottom as f32, left as f32, top as f32, right as f32)
+                })
+        })
+        .collect()
+}
+
+/// Returns the page text covered by the web link at the given index.
+fn web_link_text(
+    text: &PdfPageText,
+    link_page: FPDF_PAGELINK,
+    index: c_int,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> String {
+    let mut start: c_int = 0;
+
+    let mut count: c_int = 0;
+
+    if !bindings.is_true(bindings.FPDFLink_GetTextRange(link_page, index, &mut start, &mut count))
+        || count <= 0
+    {
+        return String::new();
+    }
+
+    // FPDFText_GetText() requires space for a trailing terminator.
+
+    let mut buffer = create_sized_buffer::<u16>(count as usize + 1);
+
+    let written = bindings.FPDFText_GetText(*text.handle(), start, count, buffer.as_mut_ptr());
+
+    if written <= 0 {
+        return String::new();
+    }
+
+    buffer.truncate(written as usize);
+
+    get_string_from_pdfium_utf16le_bytes(cast_slice(buffer.as_slice()).to_vec()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_extract_links() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/links-test.pdf", None)?;
+
+        let links = document.extract_links()?;
+
+        assert!(!links.is_empty());
+
+        // Every link should be unique by URI and page.
+
+        for (index, link) in links.iter().enumerate() {
+            assert!(!links[index + 1..]
+                .iter()
+                .any(|other| other.uri() == link.uri() && other.page_index() == link.page_index()));
+        }
+
+        Ok(())
+    }
+}
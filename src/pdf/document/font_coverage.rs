@@ -0,0 +1,291 @@
+//! Defines the [PdfFontCoverage] struct, reporting which characters in a set of strings
+//! cannot be drawn by a font used in a `PdfDocument`, as returned by the
+//! `PdfDocument::font_coverage()` function, and the [PdfUsedFonts] struct, holding the fonts
+//! used by a `PdfDocument` as returned by the `PdfDocument::used_fonts()` function.
+
+use crate::bindgen::{FPDF_FONT, FPDF_PAGE, FPDF_PAGEOBJECT, FPDF_PAGEOBJ_FORM, FPDF_PAGEOBJ_TEXT};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::font::PdfFont;
+use std::os::raw::{c_uint, c_ulong};
+use std::slice::Iter;
+
+/// The fonts used by the text on the pages of a `PdfDocument`, as returned by the
+/// `PdfDocument::used_fonts()` function.
+///
+/// Pdfium releases a font once no open page uses it, so a [PdfUsedFonts] collection keeps
+/// every page of its document open for as long as it exists. Drop it once the fonts are
+/// no longer needed.
+pub struct PdfUsedFonts<'a> {
+    // Fields are dropped in declaration order, so the fonts are dropped before the pages
+    // that keep them alive.
+    fonts: Vec<PdfFont<'a>>,
+    #[allow(dead_code)]
+    pages: Vec<PdfPage<'a>>,
+}
+
+impl<'a> PdfUsedFonts<'a> {
+    /// Collects the fonts used by the given pages, which are kept open by the returned
+    /// [PdfUsedFonts] collection.
+    pub(crate) fn from_pages(
+        pages: Vec<PdfPage<'a>>,
+        bindings: &'a dyn PdfiumLibraryBindings,
+    ) -> Self {
+        let mut handles = Vec::new();
+
+        for page in pages.iter() {
+            collect_page_fonts(page.page_handle(), &mut handles, bindings);
+        }
+
+        PdfUsedFonts {
+            fonts: handles
+                .into_iter()
+                .map(|handle| PdfFont::from_pdfium(handle, bindings, None, false))
+                .collect(),
+            pages,
+        }
+    }
+
+    /// Returns the number of fonts in this [PdfUsedFonts] collection.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fonts.len()
+    }
+
+    /// Returns `true` if this [PdfUsedFonts] collection is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fonts.is_empty()
+    }
+
+    /// Returns the font at the given index in this [PdfUsedFonts] collection, if any.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<&PdfFont<'a>> {
+        self.fonts.get(index)
+    }
+
+    /// Returns an iterator over all the fonts in this [PdfUsedFonts] collection.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, PdfFont<'a>> {
+        self.fonts.iter()
+    }
+}
+
+/// The coverage of a single font used in a `PdfDocument` against the characters
+/// in a set of strings, as returned by the `PdfDocument::font_coverage()` function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfFontCoverage {
+    family: String,
+    is_embedded: bool,
+    missing_chars: Vec<char>,
+}
+
+impl PdfFontCoverage {
+    /// Checks the given font against the given characters, creating a new [PdfFontCoverage].
+    pub(crate) fn from_pdf_font(
+        font: &PdfFont,
+        chars: &[char],
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Self {
+        let handle = font.handle();
+
+        PdfFontCoverage {
+            family: font.family(),
+            is_embedded: font.is_embedded().unwrap_or(false),
+            missing_chars: chars
+                .iter()
+                .copied()
+                .filter(|char| !has_glyph(handle, *char, bindings))
+                .collect(),
+        }
+    }
+
+    /// Returns the family name of the font described by this [PdfFontCoverage].
+    #[inline]
+    pub fn family(&self) -> &str {
+        self.family.as_str()
+    }
+
+    /// Returns `true` if the font described by this [PdfFontCoverage] is embedded in the
+    /// document. If the font is not embedded, its coverage was measured against the
+    /// substitution font chosen by Pdfium, which may differ from the font used by other viewers.
+    #[inline]
+    pub fn is_embedded(&self) -> bool {
+        self.is_embedded
+    }
+
+    /// Returns the distinct characters in the checked strings for which the font described
+    /// by this [PdfFontCoverage] has no glyph, in the order in which they first appear.
+    #[inline]
+    pub fn missing_chars(&self) -> &[char] {
+        self.missing_chars.as_slice()
+    }
+
+    /// Returns `true` if the font described by this [PdfFontCoverage] has a glyph for
+    /// every character in the checked strings.
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.missing_chars.is_empty()
+    }
+}
+
+/// Returns the distinct characters in the given strings, in the order in which they first
+/// appear. Whitespace and control characters, which need no glyph, are skipped.
+pub(crate) fn distinct_chars(strings: &[&str]) -> Vec<char> {
+    let mut result: Vec<char> = Vec::new();
+
+    for char in strings.iter().flat_map(|string| string.chars()) {
+        if !char.is_whitespace() && !char.is_control() && !result.contains(&char) {
+            result.push(char);
+        }
+    }
+
+    result
+}
+
+/// Appends the handle of every font used by a text object on the given page to the given
+/// list, including text objects nested inside form XObjects. Each font is listed once.
+pub(crate) fn collect_page_fonts(
+    page: FPDF_PAGE,
+    fonts: &mut Vec<FPDF_FONT>,
+    bindings: &dyn PdfiumLibraryBindings,
+) {
+    for index in 0..bindings.FPDFPage_CountObjects(page) {
+        collect_object_fonts(bindings.FPDFPage_GetObject(page, index), fonts, bindings);
+    }
+}
+
+fn collect_object_fonts(
+    object: FPDF_PAGEOBJECT,
+    fonts: &mut Vec<FPDF_FONT>,
+    bindings: &dyn PdfiumLibraryBindings,
+) {
+    if object.is_null() {
+        return;
+    }
+
+    match bindings.FPDFPageObj_GetType(object) as u32 {
+        FPDF_PAGEOBJ_TEXT => {
+            let font = bindings.FPDFTextObj_GetFont(object);
+
+            if !font.is_null() && !fonts.contains(&font) {
+                fonts.push(font);
+            }
+        }
+        FPDF_PAGEOBJ_FORM => {
+            for index in 0..bindings.FPDFFormObj_CountObjects(object).max(0) {
+                collect_object_fonts(
+                    bindings.FPDFFormObj_GetObject(object, index as c_ulong),
+                    fonts,
+                    bindings,
+                );
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Returns `true` if the given font can draw the given character.
+///
+/// Pdfium returns no glyph path for characters the font cannot map to a glyph. Characters
+/// the font does map may still be drawn using glyph 0, the font's `.notdef` glyph, which is
+/// often a visible box; Pdfium does not expose glyph ids, so a character is treated as drawn
+/// using `.notdef` if its glyph path is identical to the path drawn for a noncharacter that
+/// no font maps. A glyph that has neither a path nor an advance width is also missing.
+fn has_glyph(font: FPDF_FONT, char: char, bindings: &dyn PdfiumLibraryBindings) -> bool {
+    let path = match glyph_path_points(font, char, bindings) {
+        Some(path) => path,
+        None => return false,
+    };
+
+    if path.is_empty() {
+        let mut width = 0.0;
+
+        let has_width = bindings.is_true(bindings.FPDFFont_GetGlyphWidth(
+            font,
+            char as c_uint,
+            1.0,
+            &mut width,
+        )) && width > 0.0;
+
+        return has_width;
+    }
+
+    match glyph_path_points(font, NOTDEF_PROBE, bindings) {
+        Some(notdef) if !notdef.is_empty() => path != notdef,
+        _ => true,
+    }
+}
+
+/// A Unicode noncharacter, which no font maps to a glyph, used to obtain the path of
+/// a font's `.notdef` glyph.
+const NOTDEF_PROBE: char = '\u{FFFF}';
+
+/// Returns the points of every segment in the glyph path Pdfium draws for the given
+/// character in the given font, or `None` if Pdfium returns no glyph path.
+fn glyph_path_points(
+    font: FPDF_FONT,
+    char: char,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Option<Vec<(f32, f32)>> {
+    let path = bindings.FPDFFont_GetGlyphPath(font, char as c_uint, 1.0);
+
+    if path.is_null() {
+        return None;
+    }
+
+    let count = bindings.FPDFGlyphPath_CountGlyphSegments(path).max(0);
+
+    let mut points = Vec::with_capacity(count as usize);
+
+    for index in 0..count {
+        let segment = bindings.FPDFGlyphPath_GetGlyphPathSegment(path, index);
+
+        let mut x = 0.0;
+
+        let mut y = 0.0;
+
+        if !segment.is_null()
+            && bindings.is_true(bindings.FPDFPathSegment_GetPoint(segment, &mut x, &mut y))
+        {
+            points.push((x, y));
+        }
+    }
+
+    Some(points)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::font_coverage::distinct_chars;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_distinct_chars() {
+        assert_eq!(
+            distinct_chars(&["Zoë Ødegård", "Zoë\t李"]),
+            vec!['Z', 'o', 'ë', 'Ø', 'd', 'e', 'g', 'å', 'r', '李']
+        );
+    }
+
+    #[test]
+    fn test_font_coverage() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let coverage = document.font_coverage(&["Hello", "\u{e000}"])?;
+
+        assert!(!coverage.is_empty());
+
+        for font in coverage.iter() {
+            // U+E000 lies in the Unicode private use area, so no font in the test document
+            // should have a glyph for it.
+
+            assert!(font.missing_chars().contains(&'\u{e000}'));
+        }
+
+        Ok(())
+    }
+}
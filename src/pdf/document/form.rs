@@ -1,20 +1,45 @@
 //! Defines the [PdfForm] struct, exposing functionality related to a form
 //! embedded in a `PdfDocument`.
 
+pub(crate) mod appearances;
+pub mod builder;
+pub mod diff;
+pub mod events;
+pub(crate) mod exchange;
+pub mod fields;
+pub(crate) mod flatten;
+pub(crate) mod options;
+pub mod scripts;
+pub(crate) mod tab_order;
+
 use crate::bindgen::{
     FORMTYPE_ACRO_FORM, FORMTYPE_NONE, FORMTYPE_XFA_FOREGROUND, FORMTYPE_XFA_FULL, FPDF_DOCUMENT,
-    FPDF_FORMFILLINFO, FPDF_FORMHANDLE,
+    FPDF_FORMFILLINFO, FPDF_FORMHANDLE, FPDF_PAGE,
 };
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::PdfiumError;
+use crate::pdf::document::form::diff::PdfFormDiff;
+use crate::pdf::document::form::events::PdfFormEventHandler;
+use crate::pdf::document::form::exchange::{
+    export_fdf, export_json, parse_fdf_values, parse_json_values, ExchangedValue,
+};
+use crate::pdf::document::form::fields::{PdfFormFieldValue, PdfFormFields, PdfFormWidgetLocation};
+use crate::pdf::document::form::tab_order::{page_tab_orders, widgets_in_tab_order};
 use crate::pdf::document::page::field::PdfFormFieldCommon;
 use crate::pdf::document::page::field::PdfFormFieldType;
+use crate::pdf::document::page::index_cache::PdfPageIndexCache;
+use crate::pdf::document::page::PdfPage;
 use crate::pdf::document::pages::PdfPages;
+use crate::pdf::rect::PdfRect;
+use std::cell::RefCell;
 use std::collections::HashMap;
 use std::ops::DerefMut;
 use std::pin::Pin;
 use std::ptr::null_mut;
 
+#[cfg(not(target_arch = "wasm32"))]
+use crate::bindgen::_FPDF_FORMFILLINFO;
+
 /// The internal definition type of a [PdfForm] embedded in a `PdfDocument`.
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum PdfFormType {
@@ -51,6 +76,43 @@ impl PdfFormType {
     }
 }
 
+/// The `FPDF_FORMFILLINFO` struct passed to Pdfium when initializing the form fill environment,
+/// followed by the state recorded by the callback functions it contains. Pdfium passes a pointer
+/// to the `FPDF_FORMFILLINFO` struct to each callback function; since the struct is the first
+/// field of this `#[repr(C)]` struct, the callbacks can cast that pointer back to this struct.
+#[repr(C)]
+struct PdfFormFillInfo {
+    info: FPDF_FORMFILLINFO,
+
+    /// The areas of pages that Pdfium has asked to be redrawn since this list was last taken.
+    invalidated_rects: RefCell<Vec<(FPDF_PAGE, PdfRect)>>,
+}
+
+/// Records an area of a page that Pdfium has asked to be redrawn.
+#[cfg(not(target_arch = "wasm32"))]
+unsafe extern "C" fn invalidate(
+    this: *mut _FPDF_FORMFILLINFO,
+    page: FPDF_PAGE,
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+) {
+    let form_fill_info = &*(this as *const PdfFormFillInfo);
+
+    if let Ok(mut invalidated_rects) = form_fill_info.invalidated_rects.try_borrow_mut() {
+        invalidated_rects.push((
+            page,
+            PdfRect::new_from_values(
+                bottom.min(top) as f32,
+                left.min(right) as f32,
+                bottom.max(top) as f32,
+                left.max(right) as f32,
+            ),
+        ));
+    }
+}
+
 /// The [PdfForm] embedded inside a `PdfDocument`.
 ///
 /// Form fields in Pdfium are exposed as page annotations of type `PdfPageAnnotationType::Widget`
@@ -70,14 +132,13 @@ impl PdfFormType {
 /// ```
 ///
 /// Alternatively, use the [PdfForm::field_values()] function to eagerly retrieve the values of all
-/// fields in the document as a map of (field name, field value) pairs.
+/// fields in the document as a map of (field name, field value) pairs, or the [PdfForm::fields()]
+/// function to read and write typed field values by fully qualified field name.
 pub struct PdfForm<'a> {
     form_handle: FPDF_FORMHANDLE,
     document_handle: FPDF_DOCUMENT,
 
-    #[allow(dead_code)]
-    // The form_fill_info field is not currently used, but we expect it to be in future
-    form_fill_info: Pin<Box<FPDF_FORMFILLINFO>>,
+    form_fill_info: Pin<Box<PdfFormFillInfo>>,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
 
@@ -94,8 +155,12 @@ impl<'a> PdfForm<'a> {
         // FPDFDOC_InitFormFillEnvironment() function. This function takes a large
         // struct, FPDF_FORMFILLINFO, which Pdfium uses to store a variety of form
         // configuration information - mostly callback functions that should be called
-        // when the user interacts with a form field widget. Since pdfium-render has
-        // no concept of interactivity, we can leave all these set to None.
+        // when the user interacts with a form field widget. We only need to know which
+        // areas of a page Pdfium asks to be redrawn, so that PdfFormEventHandler can report
+        // them; we can leave all other callbacks set to None.
+
+        // Callback functions cannot be passed to Pdfium when compiling to WASM, since
+        // Pdfium runs in a separate WASM module that cannot call functions in this one.
 
         // We allocate the FPDF_FORMFILLINFO struct on the heap and pin its pointer location
         // so Rust will not move it around. Pdfium retains the pointer location
@@ -104,46 +169,55 @@ impl<'a> PdfForm<'a> {
         // during drop(); if we don't pin the struct's location it may move, and the
         // call to FPDFDOC_ExitFormFillEnvironment() will segfault.
 
-        let mut form_fill_info = Box::pin(FPDF_FORMFILLINFO {
-            version: 2,
-            Release: None,
-            FFI_Invalidate: None,
-            FFI_OutputSelectedRect: None,
-            FFI_SetCursor: None,
-            FFI_SetTimer: None,
-            FFI_KillTimer: None,
-            FFI_GetLocalTime: None,
-            FFI_OnChange: None,
-            FFI_GetPage: None,
-            FFI_GetCurrentPage: None,
-            FFI_GetRotation: None,
-            FFI_ExecuteNamedAction: None,
-            FFI_SetTextFieldFocus: None,
-            FFI_DoURIAction: None,
-            FFI_DoGoToAction: None,
-            m_pJsPlatform: null_mut(),
-            xfa_disabled: 0,
-            FFI_DisplayCaret: None,
-            FFI_GetCurrentPageIndex: None,
-            FFI_SetCurrentPage: None,
-            FFI_GotoURL: None,
-            FFI_GetPageViewRect: None,
-            FFI_PageEvent: None,
-            FFI_PopupMenu: None,
-            FFI_OpenFile: None,
-            FFI_EmailTo: None,
-            FFI_UploadTo: None,
-            FFI_GetPlatform: None,
-            FFI_GetLanguage: None,
-            FFI_DownloadFromURL: None,
-            FFI_PostRequestURL: None,
-            FFI_PutRequestURL: None,
-            FFI_OnFocusChange: None,
-            FFI_DoURIActionWithKeyboardModifier: None,
+        #[cfg(not(target_arch = "wasm32"))]
+        let invalidate_callback = Some(invalidate as _);
+
+        #[cfg(target_arch = "wasm32")]
+        let invalidate_callback = None;
+
+        let mut form_fill_info = Box::pin(PdfFormFillInfo {
+            info: FPDF_FORMFILLINFO {
+                version: 2,
+                Release: None,
+                FFI_Invalidate: invalidate_callback,
+                FFI_OutputSelectedRect: None,
+                FFI_SetCursor: None,
+                FFI_SetTimer: None,
+                FFI_KillTimer: None,
+                FFI_GetLocalTime: None,
+                FFI_OnChange: None,
+                FFI_GetPage: None,
+                FFI_GetCurrentPage: None,
+                FFI_GetRotation: None,
+                FFI_ExecuteNamedAction: None,
+                FFI_SetTextFieldFocus: None,
+                FFI_DoURIAction: None,
+                FFI_DoGoToAction: None,
+                m_pJsPlatform: null_mut(),
+                xfa_disabled: 0,
+                FFI_DisplayCaret: None,
+                FFI_GetCurrentPageIndex: None,
+                FFI_SetCurrentPage: None,
+                FFI_GotoURL: None,
+                FFI_GetPageViewRect: None,
+                FFI_PageEvent: None,
+                FFI_PopupMenu: None,
+                FFI_OpenFile: None,
+                FFI_EmailTo: None,
+                FFI_UploadTo: None,
+                FFI_GetPlatform: None,
+                FFI_GetLanguage: None,
+                FFI_DownloadFromURL: None,
+                FFI_PostRequestURL: None,
+                FFI_PutRequestURL: None,
+                FFI_OnFocusChange: None,
+                FFI_DoURIActionWithKeyboardModifier: None,
+            },
+            invalidated_rects: RefCell::new(Vec::new()),
         });
 
-        let form_handle =
-            bindings.FPDFDOC_InitFormFillEnvironment(document_handle, form_fill_info.deref_mut());
+        let form_handle = bindings
+            .FPDFDOC_InitFormFillEnvironment(document_handle, &mut form_fill_info.deref_mut().info);
 
         if !form_handle.is_null() {
             // There is a form embedded in this document, and we retrieved a valid handle to it.
@@ -177,6 +251,19 @@ impl<'a> PdfForm<'a> {
         self.form_handle
     }
 
+    /// Returns the internal `FPDF_DOCUMENT` handle of the document containing this [PdfForm].
+    #[inline]
+    pub(crate) fn document_handle(&self) -> FPDF_DOCUMENT {
+        self.document_handle
+    }
+
+    /// Returns the areas of pages that Pdfium has asked to be redrawn since this function was
+    /// last called, clearing the list.
+    #[inline]
+    pub(crate) fn take_invalidated_rects(&self) -> Vec<(FPDF_PAGE, PdfRect)> {
+        self.form_fill_info.invalidated_rects.take()
+    }
+
     /// Returns the [PdfiumLibraryBindings] used by this [PdfForm].
     #[inline]
     pub fn bindings(&self) -> &'a dyn PdfiumLibraryBindings {
@@ -190,6 +277,176 @@ impl<'a> PdfForm<'a> {
             .unwrap()
     }
 
+    /// Returns the logical fields of this [PdfForm] on every page of the given [PdfPages]
+    /// collection, grouping the widgets of each field by fully qualified field name.
+    #[inline]
+    pub fn fields(&self, pages: &'a PdfPages<'a>) -> PdfFormFields<'a> {
+        PdfFormFields::from_pages(pages)
+    }
+
+    /// Compares the fields of this [PdfForm] on every page of the given [PdfPages] collection,
+    /// taken as the original form, with the fields of the given revised [PdfForm] on every
+    /// page of its own [PdfPages] collection, returning the fields added, removed, or
+    /// changed in the revised form.
+    ///
+    /// Fields are matched by fully qualified name, and their values are compared as
+    /// returned by `PdfFormNamedField::value()`. This is useful for approval workflows
+    /// in which a revised copy of a filled form must be checked against the original.
+    #[inline]
+    pub fn diff<'b>(
+        &self,
+        pages: &'a PdfPages<'a>,
+        other: &PdfForm<'b>,
+        other_pages: &'b PdfPages<'b>,
+    ) -> PdfFormDiff {
+        PdfFormDiff::from_fields(&self.fields(pages), &other.fields(other_pages))
+    }
+
+    /// Returns the locations of the form field widgets on the given page in the order the
+    /// keyboard focus moves between them, as set by the page's `/Tabs` entry: in rows,
+    /// in columns, or in the order of the page's annotations.
+    ///
+    /// Pdfium does not expose the `/Tabs` entry, so this function saves a copy of the
+    /// document in order to read it.
+    pub fn tab_order(&self, page: &PdfPage) -> Result<Vec<PdfFormWidgetLocation>, PdfiumError> {
+        let page_index =
+            PdfPageIndexCache::get_index_for_page(self.document_handle, page.page_handle())
+                .ok_or(PdfiumError::SourcePageIndexNotInCache)?;
+
+        let order = page_tab_orders(self.document_handle, self.bindings)?
+            .get(page_index as usize)
+            .copied()
+            .ok_or(PdfiumError::PageIndexOutOfBounds)?;
+
+        widgets_in_tab_order(page, page_index, order)
+    }
+
+    /// Returns a new [PdfFormEventHandler] that passes user input events, such as mouse clicks
+    /// and key presses, to the fields of this [PdfForm] on the pages of the given [PdfPages]
+    /// collection, allowing an interactive viewer to let users fill in the form.
+    #[inline]
+    pub fn event_handler(&'a self, pages: &'a PdfPages<'a>) -> PdfFormEventHandler<'a> {
+        PdfFormEventHandler::new(self, pages)
+    }
+
+    /// Fills the fields of this [PdfForm] on every page of the given [PdfPages] collection
+    /// with the given map of (fully qualified field name, field value) pairs.
+    ///
+    /// Values are entered using Pdfium's form filling event functions, as if a user had
+    /// focused each field, entered its value, then moved focus away, so that Pdfium
+    /// validates each value and regenerates the appearance streams of the filled widgets.
+    /// Values that cannot be entered this way, such as the values of read-only fields,
+    /// are written directly to the field dictionaries instead. Either way, filled values
+    /// are retained when the document is saved using `PdfDocument::save_to_bytes()`
+    /// or a similar function.
+    ///
+    /// Every field name and value is checked before any field is filled. If a field name
+    /// does not exist in the form, `PdfiumError::FormFieldNotFound` is returned; if a value
+    /// does not match the type of its field, or is not one of the values the field accepts,
+    /// `PdfiumError::InvalidFormFieldValue` is returned.
+    pub fn fill_from(
+        &self,
+        pages: &'a PdfPages<'a>,
+        values: HashMap<String, PdfFormFieldValue>,
+    ) -> Result<(), PdfiumError> {
+        let mut fields = self.fields(pages);
+
+        for (name, value) in values.iter() {
+            fields
+                .get(name)
+                .ok_or(PdfiumError::FormFieldNotFound)?
+                .check_value(value)?;
+        }
+
+        for (name, value) in values.iter() {
+            if let Some(field) = fields.get_mut(name) {
+                field.fill(self.form_handle, value)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the values of the fields of this [PdfForm] on every page of the given [PdfPages]
+    /// collection as an FDF document, the Forms Data Format used by many form processing
+    /// pipelines to exchange form data.
+    ///
+    /// Each field is written with its fully qualified name and its value: text fields,
+    /// combo boxes, and list boxes as strings or arrays of strings, checkboxes and radio
+    /// button groups as the name of their selected state. Fields without values, such as
+    /// push buttons and signatures, are omitted.
+    #[inline]
+    pub fn export_fdf(&self, pages: &'a PdfPages<'a>) -> Vec<u8> {
+        export_fdf(&self.fields(pages))
+    }
+
+    /// Fills the fields of this [PdfForm] on every page of the given [PdfPages] collection
+    /// with the values in the given FDF document, returning the number of fields filled.
+    /// Fields are filled as described for [PdfForm::fill_from()].
+    ///
+    /// Fields may be identified either by their fully qualified names or by a hierarchy of
+    /// partial names. Values of fields that do not exist in this form are ignored.
+    ///
+    /// Returns [PdfiumError::InvalidFdfData] if the given bytes are not a well-formed FDF
+    /// document, or [PdfiumError::InvalidFormFieldValue] if a value does not match the type
+    /// of its field. No fields are filled if an error is returned.
+    pub fn import_fdf(&self, pages: &'a PdfPages<'a>, fdf: &[u8]) -> Result<usize, PdfiumError> {
+        let values = parse_fdf_values(fdf).ok_or(PdfiumError::InvalidFdfData)?;
+
+        self.fill_from_exchanged_values(pages, values)
+    }
+
+    /// Returns the values of the fields of this [PdfForm] on every page of the given [PdfPages]
+    /// collection as a JSON object of (fully qualified field name, field value) pairs.
+    ///
+    /// Text fields are written as strings, checkboxes as booleans, radio button groups and
+    /// combo boxes as strings, or `null` if nothing is selected, and list boxes as arrays of
+    /// the labels of their selected options. Fields without values, such as push buttons and
+    /// signatures, are omitted.
+    #[inline]
+    pub fn to_json(&self, pages: &'a PdfPages<'a>) -> String {
+        export_json(&self.fields(pages))
+    }
+
+    /// Fills the fields of this [PdfForm] on every page of the given [PdfPages] collection
+    /// with the values in the given JSON object, in the format returned by
+    /// [PdfForm::to_json()], returning the number of fields filled. Fields are filled as
+    /// described for [PdfForm::fill_from()]. Values of fields that do not exist in this form
+    /// are ignored.
+    ///
+    /// Returns [PdfiumError::InvalidJsonData] if the given string is not a JSON object of
+    /// field values, or [PdfiumError::InvalidFormFieldValue] if a value does not match the
+    /// type of its field. No fields are filled if an error is returned.
+    pub fn from_json(&self, pages: &'a PdfPages<'a>, json: &str) -> Result<usize, PdfiumError> {
+        let values = parse_json_values(json).ok_or(PdfiumError::InvalidJsonData)?;
+
+        self.fill_from_exchanged_values(pages, values)
+    }
+
+    /// Fills the fields of this [PdfForm] with the given values read from an FDF or JSON
+    /// document, returning the number of fields filled.
+    fn fill_from_exchanged_values(
+        &self,
+        pages: &'a PdfPages<'a>,
+        values: Vec<(String, ExchangedValue)>,
+    ) -> Result<usize, PdfiumError> {
+        let fields = self.fields(pages);
+
+        let mut field_values = HashMap::new();
+
+        for (name, value) in values.iter() {
+            if let Some(field) = fields.get(name) {
+                field_values.insert(name.clone(), value.to_field_value(field)?);
+            }
+        }
+
+        let count = field_values.len();
+
+        self.fill_from(pages, field_values)?;
+
+        Ok(count)
+    }
+
     /// Captures a string representation of the value of every form field on every page of
     /// the given [PdfPages] collection, returning a map of (field name, field value) pairs.
     ///
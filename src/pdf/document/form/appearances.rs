@@ -0,0 +1,116 @@
+//! Reads and writes the `/NeedAppearances` entry of the form dictionary in a `PdfDocument`,
+//! via the `PdfDocument::form_needs_appearances()` and
+//! `PdfDocument::set_form_needs_appearances()` functions.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+
+/// Returns the value of the `/NeedAppearances` entry of the form dictionary in the given
+/// PDF file. The entry defaults to `false` if it is missing, or if the file has no form.
+pub(crate) fn read_need_appearances(file: &[u8]) -> Result<bool, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(file).ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    let catalog = parsed.resolve(
+        parsed
+            .trailer
+            .as_ref()
+            .and_then(|trailer| trailer.get("Root"))
+            .ok_or(PdfiumError::FormFieldCreationUnsupported)?,
+    );
+
+    let form = parsed.resolve(catalog.get("AcroForm").unwrap_or(&PdfSyntaxObject::Null));
+
+    Ok(parsed.resolve(
+        form.get("NeedAppearances")
+            .unwrap_or(&PdfSyntaxObject::Null),
+    ) == PdfSyntaxObject::Bool(true))
+}
+
+/// Sets the `/NeedAppearances` entry of the form dictionary in the given PDF file to the
+/// given value, returning the updated file, or `None` if the file has no form or the entry
+/// already has the given value. The change is applied in an incremental update appended
+/// to the end of the file.
+pub(crate) fn write_need_appearances(
+    file: Vec<u8>,
+    value: bool,
+) -> Result<Option<Vec<u8>>, PdfiumError> {
+    if read_need_appearances(&file)? == value {
+        return Ok(None);
+    }
+
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    let root = match update.trailer().get("Root") {
+        Some(PdfSyntaxObject::Reference(number, _))
+            if update.trailer().get("Encrypt").is_none() =>
+        {
+            *number
+        }
+        _ => return Err(PdfiumError::FormFieldCreationUnsupported),
+    };
+
+    if update
+        .get(root)
+        .and_then(|catalog| catalog.get("AcroForm").cloned())
+        .is_none()
+    {
+        return Ok(None);
+    }
+
+    update
+        .modify(root, &["AcroForm"], &mut |form| {
+            // The entry defaults to false, so it is removed rather than set to false.
+
+            if value {
+                form.set("NeedAppearances", PdfSyntaxObject::Bool(true));
+            } else {
+                form.remove("NeedAppearances");
+            }
+
+            Some(())
+        })
+        .ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    Ok(Some(update.write(file)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::form::appearances::*;
+
+    const FORM_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R /AcroForm 3 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n\
+        3 0 obj\n<< /Fields [] >>\nendobj\n\
+        xref\n0 4\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 4 /Root 1 0 R >>\nstartxref\n150\n%%EOF\n";
+
+    #[test]
+    fn test_write_need_appearances() {
+        assert!(!read_need_appearances(FORM_PDF).unwrap());
+        assert_eq!(
+            write_need_appearances(FORM_PDF.to_vec(), false).unwrap(),
+            None
+        );
+
+        let file = write_need_appearances(FORM_PDF.to_vec(), true)
+            .unwrap()
+            .unwrap();
+
+        assert!(read_need_appearances(&file).unwrap());
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        assert_eq!(
+            parsed.objects[&3].object.get("NeedAppearances"),
+            Some(&PdfSyntaxObject::Bool(true))
+        );
+
+        let file = write_need_appearances(file, false).unwrap().unwrap();
+
+        assert!(!read_need_appearances(&file).unwrap());
+    }
+}
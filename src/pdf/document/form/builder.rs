@@ -0,0 +1,1074 @@
+//! Defines the [PdfFormFieldBuilder] struct, used to create new interactive form fields,
+//! such as text fields, checkboxes, radio button groups, and combo boxes, in a `PdfDocument`.
+
+use crate::bindgen::{
+    FPDF_ANNOT_FLAG_PRINT, FPDF_FORMFLAG_CHOICE_COMBO, FPDF_FORMFLAG_CHOICE_EDIT,
+    FPDF_FORMFLAG_READONLY, FPDF_FORMFLAG_REQUIRED, FPDF_FORMFLAG_TEXT_MULTILINE,
+    FPDF_FORMFLAG_TEXT_PASSWORD,
+};
+use crate::error::PdfiumError;
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::fonts::PdfFontBuiltin;
+use crate::pdf::document::page::annotation::appearance::{color_operands, format_number};
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use crate::pdf::document::PdfDocument;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+
+/// The flag marking a button field as a group of radio buttons. Pdfium does not define
+/// a constant for this flag.
+const FORM_FLAG_RADIO: u32 = 1 << 15;
+
+/// The flag preventing a user from turning off every button in a group of radio buttons.
+/// Pdfium does not define a constant for this flag.
+const FORM_FLAG_NO_TOGGLE_TO_OFF: u32 = 1 << 14;
+
+/// The kind of form field created by a [PdfFormFieldBuilder], along with any settings
+/// specific to that kind of field.
+#[derive(Debug, Clone)]
+enum PdfFormFieldBuilderKind {
+    Text {
+        is_multiline: bool,
+        is_password: bool,
+    },
+    Checkbox {
+        export_value: String,
+        is_checked: bool,
+    },
+    RadioGroup {
+        buttons: Vec<(String, PdfRect)>,
+    },
+    ComboBox {
+        options: Vec<String>,
+        is_editable: bool,
+    },
+}
+
+/// Creates a new interactive form field in a `PdfDocument`: a text field, a checkbox,
+/// a group of radio buttons, or a combo box.
+///
+/// Start with [PdfFormFieldBuilder::text()], [PdfFormFieldBuilder::checkbox()],
+/// [PdfFormFieldBuilder::radio_group()], or [PdfFormFieldBuilder::combo_box()], then set the
+/// page and bounds of the new field and any other settings before calling
+/// [PdfFormFieldBuilder::create()]. To create several fields at once, pass a list of builders
+/// to the `PdfDocument::create_form_fields()` function. An interactive form is added to the
+/// document if it does not already have one.
+///
+/// Field names are partial names, and may not contain periods. Text fields and combo boxes
+/// are drawn by PDF viewers in the given font and font size, which should be set with
+/// [PdfFormFieldBuilder::font()] and [PdfFormFieldBuilder::font_size()]; a font size of zero,
+/// the default, sizes text automatically to fit the field. Appearance streams are generated for
+/// checkboxes and radio buttons; PDF viewers, including Pdfium, generate the appearances of
+/// text fields and combo boxes from their values.
+#[derive(Debug, Clone)]
+pub struct PdfFormFieldBuilder {
+    name: String,
+    kind: PdfFormFieldBuilderKind,
+    page_index: PdfPageIndex,
+    bounds: Option<PdfRect>,
+    default_value: Option<String>,
+    font: PdfFontBuiltin,
+    font_size: PdfPoints,
+    text_color: PdfColor,
+    is_read_only: bool,
+    is_required: bool,
+}
+
+impl PdfFormFieldBuilder {
+    #[inline]
+    fn new(name: &str, kind: PdfFormFieldBuilderKind) -> Self {
+        PdfFormFieldBuilder {
+            name: name.to_string(),
+            kind,
+            page_index: 0,
+            bounds: None,
+            default_value: None,
+            font: PdfFontBuiltin::Helvetica,
+            font_size: PdfPoints::ZERO,
+            text_color: PdfColor::BLACK,
+            is_read_only: false,
+            is_required: false,
+        }
+    }
+
+    /// Creates a new [PdfFormFieldBuilder] for a single line text field with the given name.
+    #[inline]
+    pub fn text(name: &str) -> Self {
+        Self::new(
+            name,
+            PdfFormFieldBuilderKind::Text {
+                is_multiline: false,
+                is_password: false,
+            },
+        )
+    }
+
+    /// Creates a new [PdfFormFieldBuilder] for an unchecked checkbox with the given name.
+    /// The checkbox's export value defaults to `Yes`.
+    #[inline]
+    pub fn checkbox(name: &str) -> Self {
+        Self::new(
+            name,
+            PdfFormFieldBuilderKind::Checkbox {
+                export_value: "Yes".to_string(),
+                is_checked: false,
+            },
+        )
+    }
+
+    /// Creates a new [PdfFormFieldBuilder] for a group of radio buttons with the given name.
+    /// Add buttons to the group using [PdfFormFieldBuilder::add_radio_button()].
+    #[inline]
+    pub fn radio_group(name: &str) -> Self {
+        Self::new(
+            name,
+            PdfFormFieldBuilderKind::RadioGroup {
+                buttons: Vec::new(),
+            },
+        )
+    }
+
+    /// Creates a new [PdfFormFieldBuilder] for a combo box with the given name.
+    /// Add options to the combo box using [PdfFormFieldBuilder::add_option()].
+    #[inline]
+    pub fn combo_box(name: &str) -> Self {
+        Self::new(
+            name,
+            PdfFormFieldBuilderKind::ComboBox {
+                options: Vec::new(),
+                is_editable: false,
+            },
+        )
+    }
+
+    /// Sets the zero-based index of the page displaying the new field. The default is
+    /// the first page in the document.
+    #[inline]
+    pub fn page_index(mut self, index: PdfPageIndex) -> Self {
+        self.page_index = index;
+
+        self
+    }
+
+    /// Sets the bounding box of the new field on its page. The bounds of radio buttons
+    /// are instead set for each button by [PdfFormFieldBuilder::add_radio_button()].
+    #[inline]
+    pub fn bounds(mut self, bounds: PdfRect) -> Self {
+        self.bounds = Some(bounds);
+
+        self
+    }
+
+    /// Sets the initial value of the new field, which is also the value the field returns to
+    /// when the form is reset. For a group of radio buttons, this is the export value of the
+    /// selected button. Has no effect on checkboxes; use [PdfFormFieldBuilder::checked()]
+    /// instead.
+    #[inline]
+    pub fn default_value(mut self, value: &str) -> Self {
+        self.default_value = Some(value.to_string());
+
+        self
+    }
+
+    /// Sets the built-in font used to display the value of the new field. The default is
+    /// Helvetica. Checkboxes and radio buttons always use the ZapfDingbats font.
+    #[inline]
+    pub fn font(mut self, font: PdfFontBuiltin) -> Self {
+        self.font = font;
+
+        self
+    }
+
+    /// Sets the font size used to display the value of the new field. A size of zero,
+    /// the default, sizes the value automatically to fit the field.
+    #[inline]
+    pub fn font_size(mut self, size: PdfPoints) -> Self {
+        self.font_size = size;
+
+        self
+    }
+
+    /// Sets the color used to display the value of the new field. The default is black.
+    #[inline]
+    pub fn text_color(mut self, color: PdfColor) -> Self {
+        self.text_color = color;
+
+        self
+    }
+
+    /// Controls whether the user may change the value of the new field.
+    #[inline]
+    pub fn read_only(mut self, is_read_only: bool) -> Self {
+        self.is_read_only = is_read_only;
+
+        self
+    }
+
+    /// Controls whether the new field must have a value when the form is submitted.
+    #[inline]
+    pub fn required(mut self, is_required: bool) -> Self {
+        self.is_required = is_required;
+
+        self
+    }
+
+    /// Controls whether a new text field accepts multiple lines of text.
+    /// Has no effect on other kinds of field.
+    #[inline]
+    pub fn multiline(mut self, value: bool) -> Self {
+        if let PdfFormFieldBuilderKind::Text { is_multiline, .. } = &mut self.kind {
+            *is_multiline = value;
+        }
+
+        self
+    }
+
+    /// Controls whether a new text field obscures its value, as for a password.
+    /// Has no effect on other kinds of field.
+    #[inline]
+    pub fn password(mut self, value: bool) -> Self {
+        if let PdfFormFieldBuilderKind::Text { is_password, .. } = &mut self.kind {
+            *is_password = value;
+        }
+
+        self
+    }
+
+    /// Controls whether a new checkbox is initially checked.
+    /// Has no effect on other kinds of field.
+    #[inline]
+    pub fn checked(mut self, value: bool) -> Self {
+        if let PdfFormFieldBuilderKind::Checkbox { is_checked, .. } = &mut self.kind {
+            *is_checked = value;
+        }
+
+        self
+    }
+
+    /// Sets the export value of a new checkbox, the value it has when checked.
+    /// Has no effect on other kinds of field.
+    #[inline]
+    pub fn export_value(mut self, value: &str) -> Self {
+        if let PdfFormFieldBuilderKind::Checkbox { export_value, .. } = &mut self.kind {
+            *export_value = value.to_string();
+        }
+
+        self
+    }
+
+    /// Adds a button with the given export value and bounding box to a new group of
+    /// radio buttons. Has no effect on other kinds of field.
+    #[inline]
+    pub fn add_radio_button(mut self, export_value: &str, bounds: PdfRect) -> Self {
+        if let PdfFormFieldBuilderKind::RadioGroup { buttons } = &mut self.kind {
+            buttons.push((export_value.to_string(), bounds));
+        }
+
+        self
+    }
+
+    /// Adds the given option to the list of choices offered by a new combo box.
+    /// Has no effect on other kinds of field.
+    #[inline]
+    pub fn add_option(mut self, option: &str) -> Self {
+        if let PdfFormFieldBuilderKind::ComboBox { options, .. } = &mut self.kind {
+            options.push(option.to_string());
+        }
+
+        self
+    }
+
+    /// Controls whether the user may enter a value into a new combo box that is not one of
+    /// its options. Has no effect on other kinds of field.
+    #[inline]
+    pub fn editable(mut self, value: bool) -> Self {
+        if let PdfFormFieldBuilderKind::ComboBox { is_editable, .. } = &mut self.kind {
+            *is_editable = value;
+        }
+
+        self
+    }
+
+    /// Creates the new field in the given `PdfDocument`, as configured by this builder.
+    ///
+    /// See `PdfDocument::create_form_fields()` for details of how the field is created.
+    #[inline]
+    pub fn create(&self, document: &mut PdfDocument) -> Result<(), PdfiumError> {
+        document.create_form_fields(std::slice::from_ref(self))
+    }
+
+    /// Returns an error if this builder's settings do not describe a valid form field.
+    fn validate(&self) -> Result<(), PdfiumError> {
+        if self.name.is_empty() || self.name.contains('.') {
+            return Err(PdfiumError::InvalidFormFieldDefinition);
+        }
+
+        let has_valid_bounds =
+            |bounds: &PdfRect| bounds.width().value > 0.0 && bounds.height().value > 0.0;
+
+        match &self.kind {
+            PdfFormFieldBuilderKind::RadioGroup { buttons } => {
+                if buttons.is_empty()
+                    || buttons.iter().enumerate().any(|(index, (value, bounds))| {
+                        !is_valid_state_name(value)
+                            || !has_valid_bounds(bounds)
+                            || buttons[..index].iter().any(|(other, _)| other == value)
+                    })
+                {
+                    return Err(PdfiumError::InvalidFormFieldDefinition);
+                }
+
+                if let Some(value) = self.default_value.as_ref() {
+                    if !buttons
+                        .iter()
+                        .any(|(export_value, _)| export_value == value)
+                    {
+                        return Err(PdfiumError::InvalidFormFieldValue);
+                    }
+                }
+            }
+            kind => {
+                if !self.bounds.as_ref().map_or(false, has_valid_bounds) {
+                    return Err(PdfiumError::InvalidFormFieldDefinition);
+                }
+
+                match kind {
+                    PdfFormFieldBuilderKind::Checkbox { export_value, .. }
+                        if !is_valid_state_name(export_value) =>
+                    {
+                        return Err(PdfiumError::InvalidFormFieldDefinition);
+                    }
+                    PdfFormFieldBuilderKind::ComboBox {
+                        options,
+                        is_editable: false,
+                    } if self
+                        .default_value
+                        .as_ref()
+                        .map_or(false, |value| !options.contains(value)) =>
+                    {
+                        return Err(PdfiumError::InvalidFormFieldValue);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the built-in fonts referenced by the field created by this builder.
+    fn fonts(&self) -> Vec<PdfFontBuiltin> {
+        match self.kind {
+            PdfFormFieldBuilderKind::Checkbox { .. }
+            | PdfFormFieldBuilderKind::RadioGroup { .. } => {
+                vec![PdfFontBuiltin::ZapfDingbats]
+            }
+            _ => vec![self.font],
+        }
+    }
+
+    /// Returns the flags of the field created by this builder.
+    fn flags(&self) -> u32 {
+        let mut flags = 0;
+
+        if self.is_read_only {
+            flags |= FPDF_FORMFLAG_READONLY;
+        }
+
+        if self.is_required {
+            flags |= FPDF_FORMFLAG_REQUIRED;
+        }
+
+        match &self.kind {
+            PdfFormFieldBuilderKind::Text {
+                is_multiline,
+                is_password,
+            } => {
+                if *is_multiline {
+                    flags |= FPDF_FORMFLAG_TEXT_MULTILINE;
+                }
+
+                if *is_password {
+                    flags |= FPDF_FORMFLAG_TEXT_PASSWORD;
+                }
+            }
+            PdfFormFieldBuilderKind::Checkbox { .. } => {}
+            PdfFormFieldBuilderKind::RadioGroup { .. } => {
+                flags |= FORM_FLAG_RADIO | FORM_FLAG_NO_TOGGLE_TO_OFF;
+            }
+            PdfFormFieldBuilderKind::ComboBox { is_editable, .. } => {
+                flags |= FPDF_FORMFLAG_CHOICE_COMBO;
+
+                if *is_editable {
+                    flags |= FPDF_FORMFLAG_CHOICE_EDIT;
+                }
+            }
+        }
+
+        flags
+    }
+
+    /// Returns the default appearance string of the field created by this builder, which
+    /// selects the font, font size, and color used to display the field's value.
+    fn default_appearance(&self) -> String {
+        let font = self.fonts()[0];
+
+        format!(
+            "/{} {} Tf {} rg",
+            font_resource_name(font),
+            format_number(self.font_size.value),
+            color_operands(self.text_color)
+        )
+    }
+
+    /// Adds the objects making up the field created by this builder to the given update,
+    /// returning a reference to the field's dictionary along with references to the
+    /// widget annotations that must be added to the field's page.
+    fn add_to(
+        &self,
+        update: &mut PdfIncrementalUpdate,
+        page: &PdfSyntaxObject,
+        zapf_dingbats: &PdfSyntaxObject,
+    ) -> (PdfSyntaxObject, Vec<PdfSyntaxObject>) {
+        let mut field = PdfSyntaxObject::Dictionary(Vec::new());
+
+        field.set("T", PdfSyntaxObject::text(&self.name));
+        field.set("Ff", PdfSyntaxObject::Number(self.flags() as f64));
+        field.set("DA", PdfSyntaxObject::text(&self.default_appearance()));
+
+        match &self.kind {
+            PdfFormFieldBuilderKind::RadioGroup { buttons } => {
+                let state = PdfSyntaxObject::name(self.default_value.as_deref().unwrap_or("Off"));
+
+                field.set("FT", PdfSyntaxObject::name("Btn"));
+                field.set("V", state.clone());
+                field.set("DV", state);
+
+                let parent = update.reserve();
+
+                let widgets = buttons
+                    .iter()
+                    .map(|(export_value, bounds)| {
+                        let mut widget = new_widget(page, bounds);
+
+                        widget.set("Parent", parent.clone());
+
+                        self.set_button_appearance(
+                            update,
+                            &mut widget,
+                            export_value,
+                            self.default_value.as_ref() == Some(export_value),
+                            bounds,
+                            zapf_dingbats,
+                        );
+
+                        update.add(widget)
+                    })
+                    .collect::<Vec<_>>();
+
+                field.set("Kids", PdfSyntaxObject::Array(widgets.clone()));
+
+                if let PdfSyntaxObject::Reference(number, _) = parent {
+                    update.set(number, field);
+                }
+
+                (parent, widgets)
+            }
+            kind => {
+                let bounds = self.bounds.unwrap_or(PdfRect::ZERO);
+
+                let mut widget = new_widget(page, &bounds);
+
+                if let PdfSyntaxObject::Dictionary(entries) = field {
+                    for (key, value) in entries {
+                        widget.set(&key, value);
+                    }
+                }
+
+                match kind {
+                    PdfFormFieldBuilderKind::Text { .. } => {
+                        widget.set("FT", PdfSyntaxObject::name("Tx"));
+
+                        if let Some(value) = self.default_value.as_deref() {
+                            widget.set("V", PdfSyntaxObject::text(value));
+                            widget.set("DV", PdfSyntaxObject::text(value));
+                        }
+                    }
+                    PdfFormFieldBuilderKind::Checkbox {
+                        export_value,
+                        is_checked,
+                    } => {
+                        let state =
+                            PdfSyntaxObject::name(if *is_checked { export_value } else { "Off" });
+
+                        widget.set("FT", PdfSyntaxObject::name("Btn"));
+                        widget.set("V", state.clone());
+                        widget.set("DV", state);
+
+                        self.set_button_appearance(
+                            update,
+                            &mut widget,
+                            export_value,
+                            *is_checked,
+                            &bounds,
+                            zapf_dingbats,
+                        );
+                    }
+                    PdfFormFieldBuilderKind::ComboBox { options, .. } => {
+                        widget.set("FT", PdfSyntaxObject::name("Ch"));
+                        widget.set(
+                            "Opt",
+                            PdfSyntaxObject::Array(
+                                options
+                                    .iter()
+                                    .map(|option| PdfSyntaxObject::text(option))
+                                    .collect(),
+                            ),
+                        );
+
+                        if let Some(value) = self.default_value.as_deref() {
+                            widget.set("V", PdfSyntaxObject::text(value));
+                            widget.set("DV", PdfSyntaxObject::text(value));
+                        }
+                    }
+                    PdfFormFieldBuilderKind::RadioGroup { .. } => {}
+                }
+
+                let widget = update.add(widget);
+
+                (widget.clone(), vec![widget])
+            }
+        }
+    }
+
+    /// Sets the appearance state, appearance characteristics, and on and off appearance
+    /// streams of the given checkbox or radio button widget.
+    fn set_button_appearance(
+        &self,
+        update: &mut PdfIncrementalUpdate,
+        widget: &mut PdfSyntaxObject,
+        export_value: &str,
+        is_on: bool,
+        bounds: &PdfRect,
+        zapf_dingbats: &PdfSyntaxObject,
+    ) {
+        let is_radio = matches!(self.kind, PdfFormFieldBuilderKind::RadioGroup { .. });
+
+        // The ZapfDingbats font draws a check mark for the character 4 and a filled
+        // circle for the character l.
+
+        let (symbol, symbol_width) = if is_radio { ("l", 0.791) } else { ("4", 0.846) };
+
+        let width = bounds.width().value;
+
+        let height = bounds.height().value;
+
+        let border = if is_radio {
+            circle_path(width / 2.0, height / 2.0, width.min(height) / 2.0 - 0.5)
+        } else {
+            format!(
+                "0.5 0.5 {} {} re",
+                format_number(width - 1.0),
+                format_number(height - 1.0)
+            )
+        };
+
+        let border = format!("q 0 G 1 w {} S Q\n", border);
+
+        let font_size = if self.font_size.value > 0.0 {
+            self.font_size.value
+        } else {
+            width.min(height) * if is_radio { 0.5 } else { 0.8 }
+        };
+
+        let symbol = format!(
+            "q BT {} rg /ZaDb {} Tf {} {} Td ({}) Tj ET Q\n",
+            color_operands(self.text_color),
+            format_number(font_size),
+            format_number((width - font_size * symbol_width) / 2.0),
+            format_number((height - font_size * 0.7) / 2.0),
+            symbol
+        );
+
+        let mut stream = |content: String, fonts: PdfSyntaxObject| {
+            let mut dictionary = PdfSyntaxObject::Dictionary(Vec::new());
+
+            dictionary.set("Type", PdfSyntaxObject::name("XObject"));
+            dictionary.set("Subtype", PdfSyntaxObject::name("Form"));
+            dictionary.set(
+                "BBox",
+                PdfSyntaxObject::Array(vec![
+                    number(0.0),
+                    number(0.0),
+                    number(width),
+                    number(height),
+                ]),
+            );
+            dictionary.set(
+                "Resources",
+                PdfSyntaxObject::Dictionary(vec![("Font".to_string(), fonts)]),
+            );
+
+            update.add_stream(dictionary, content.into_bytes())
+        };
+
+        let on = stream(
+            format!("{}{}", border, symbol),
+            PdfSyntaxObject::Dictionary(vec![("ZaDb".to_string(), zapf_dingbats.clone())]),
+        );
+
+        let off = stream(border, PdfSyntaxObject::Dictionary(Vec::new()));
+
+        widget.set(
+            "AS",
+            PdfSyntaxObject::name(if is_on { export_value } else { "Off" }),
+        );
+        widget.set(
+            "MK",
+            PdfSyntaxObject::Dictionary(vec![
+                ("BC".to_string(), PdfSyntaxObject::Array(vec![number(0.0)])),
+                (
+                    "CA".to_string(),
+                    PdfSyntaxObject::text(symbol_character(is_radio)),
+                ),
+            ]),
+        );
+        widget.set(
+            "AP",
+            PdfSyntaxObject::Dictionary(vec![(
+                "N".to_string(),
+                PdfSyntaxObject::Dictionary(vec![
+                    (export_value.to_string(), on),
+                    ("Off".to_string(), off),
+                ]),
+            )]),
+        );
+    }
+}
+
+/// Returns the ZapfDingbats character drawn by a checked checkbox or a selected radio button.
+#[inline]
+fn symbol_character(is_radio: bool) -> &'static str {
+    if is_radio {
+        "l"
+    } else {
+        "4"
+    }
+}
+
+/// Returns `true` if the given name can be used as the on state of a checkbox or radio button.
+#[inline]
+fn is_valid_state_name(name: &str) -> bool {
+    !name.is_empty() && name != "Off"
+}
+
+/// Returns a new number object for the given value, rounded to the precision used in
+/// content streams.
+#[inline]
+pub(crate) fn number(value: f32) -> PdfSyntaxObject {
+    PdfSyntaxObject::Number(format_number(value).parse().unwrap_or(0.0))
+}
+
+/// Returns a new widget annotation dictionary with the given bounds on the given page.
+pub(crate) fn new_widget(page: &PdfSyntaxObject, bounds: &PdfRect) -> PdfSyntaxObject {
+    PdfSyntaxObject::Dictionary(vec![
+        ("Type".to_string(), PdfSyntaxObject::name("Annot")),
+        ("Subtype".to_string(), PdfSyntaxObject::name("Widget")),
+        (
+            "Rect".to_string(),
+            PdfSyntaxObject::Array(vec![
+                number(bounds.left.value),
+                number(bounds.bottom.value),
+                number(bounds.right.value),
+                number(bounds.top.value),
+            ]),
+        ),
+        ("P".to_string(), page.clone()),
+        (
+            "F".to_string(),
+            PdfSyntaxObject::Number(FPDF_ANNOT_FLAG_PRINT as f64),
+        ),
+    ])
+}
+
+/// Returns the content stream operators tracing a circle with the given center and radius,
+/// approximated using four Bézier curves.
+fn circle_path(x: f32, y: f32, radius: f32) -> String {
+    let k = radius * 0.5523;
+
+    let points = [
+        (x + radius, y + k, x + k, y + radius, x, y + radius),
+        (x - k, y + radius, x - radius, y + k, x - radius, y),
+        (x - radius, y - k, x - k, y - radius, x, y - radius),
+        (x + k, y - radius, x + radius, y - k, x + radius, y),
+    ];
+
+    let mut path = format!("{} {} m", format_number(x + radius), format_number(y));
+
+    for (x1, y1, x2, y2, x3, y3) in points.iter() {
+        path.push_str(&format!(
+            " {} {} {} {} {} {} c",
+            format_number(*x1),
+            format_number(*y1),
+            format_number(*x2),
+            format_number(*y2),
+            format_number(*x3),
+            format_number(*y3)
+        ));
+    }
+
+    path
+}
+
+/// Returns the conventional resource name used for the given built-in font in the default
+/// resources of an interactive form.
+fn font_resource_name(font: PdfFontBuiltin) -> &'static str {
+    match font {
+        PdfFontBuiltin::TimesRoman => "TiRo",
+        PdfFontBuiltin::TimesBold => "TiBo",
+        PdfFontBuiltin::TimesItalic => "TiIt",
+        PdfFontBuiltin::TimesBoldItalic => "TiBI",
+        PdfFontBuiltin::Helvetica => "Helv",
+        PdfFontBuiltin::HelveticaBold => "HeBo",
+        PdfFontBuiltin::HelveticaOblique => "HeOb",
+        PdfFontBuiltin::HelveticaBoldOblique => "HeBO",
+        PdfFontBuiltin::Courier => "Cour",
+        PdfFontBuiltin::CourierBold => "CoBo",
+        PdfFontBuiltin::CourierOblique => "CoOb",
+        PdfFontBuiltin::CourierBoldOblique => "CoBO",
+        PdfFontBuiltin::Symbol => "Symb",
+        PdfFontBuiltin::ZapfDingbats => "ZaDb",
+    }
+}
+
+/// Returns a new font dictionary for the given built-in font.
+fn font_dictionary(font: PdfFontBuiltin) -> PdfSyntaxObject {
+    let mut dictionary = PdfSyntaxObject::Dictionary(vec![
+        ("Type".to_string(), PdfSyntaxObject::name("Font")),
+        ("Subtype".to_string(), PdfSyntaxObject::name("Type1")),
+        (
+            "BaseFont".to_string(),
+            PdfSyntaxObject::name(font.to_pdf_font_name()),
+        ),
+    ]);
+
+    // The Symbol and ZapfDingbats fonts use their own built-in encodings.
+
+    if font != PdfFontBuiltin::Symbol && font != PdfFontBuiltin::ZapfDingbats {
+        dictionary.set("Encoding", PdfSyntaxObject::name("WinAnsiEncoding"));
+    }
+
+    dictionary
+}
+
+/// Adds the form fields described by the given builders to the given PDF file, which must
+/// contain the given number of pages and the fields with the given fully qualified names,
+/// returning the updated file. The fields are added in an incremental update appended to
+/// the end of the file.
+pub(crate) fn append_form_fields(
+    file: Vec<u8>,
+    fields: &[PdfFormFieldBuilder],
+    page_count: PdfPageIndex,
+    existing_field_names: &[String],
+) -> Result<Vec<u8>, PdfiumError> {
+    for (index, field) in fields.iter().enumerate() {
+        field.validate()?;
+
+        if field.page_index >= page_count {
+            return Err(PdfiumError::PageIndexOutOfBounds);
+        }
+
+        // A new field may not share its name with an existing field, or with the
+        // first component of the fully qualified name of an existing field.
+
+        if fields[..index].iter().any(|other| other.name == field.name)
+            || existing_field_names
+                .iter()
+                .any(|name| name.split('.').next() == Some(field.name.as_str()))
+        {
+            return Err(PdfiumError::InvalidFormFieldDefinition);
+        }
+    }
+
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    let root = match update.trailer().get("Root") {
+        Some(PdfSyntaxObject::Reference(number, _))
+            if update.trailer().get("Encrypt").is_none() =>
+        {
+            *number
+        }
+        _ => return Err(PdfiumError::FormFieldCreationUnsupported),
+    };
+
+    let pages = parsed
+        .page_references()
+        .ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    if pages.len() != page_count as usize {
+        return Err(PdfiumError::FormFieldCreationUnsupported);
+    }
+
+    // Add any fonts used by the new fields that are missing from the form's default resources.
+
+    let existing_fonts = ["AcroForm", "DR", "Font"].iter().fold(
+        PdfSyntaxObject::Reference(root, 0),
+        |object, key| {
+            update
+                .resolve(&object)
+                .get(key)
+                .cloned()
+                .unwrap_or(PdfSyntaxObject::Null)
+        },
+    );
+
+    let existing_fonts = update.resolve(&existing_fonts);
+
+    let mut fonts = vec![PdfFontBuiltin::Helvetica, PdfFontBuiltin::ZapfDingbats];
+
+    for font in fields.iter().flat_map(|field| field.fonts()) {
+        if !fonts.contains(&font) {
+            fonts.push(font);
+        }
+    }
+
+    let mut added_fonts = Vec::new();
+
+    let mut zapf_dingbats = PdfSyntaxObject::Null;
+
+    for font in fonts {
+        let name = font_resource_name(font);
+
+        let reference = match existing_fonts.get(name) {
+            Some(reference) => reference.clone(),
+            None => {
+                let reference = update.add(font_dictionary(font));
+
+                added_fonts.push((name.to_string(), reference.clone()));
+
+                reference
+            }
+        };
+
+        if font == PdfFontBuiltin::ZapfDingbats {
+            zapf_dingbats = reference;
+        }
+    }
+
+    // Create the new fields and their widgets.
+
+    let mut field_references = Vec::with_capacity(fields.len());
+
+    let mut page_widgets = vec![Vec::new(); pages.len()];
+
+    for field in fields.iter() {
+        let page = &pages[field.page_index as usize];
+
+        let (reference, widgets) = field.add_to(&mut update, page, &zapf_dingbats);
+
+        field_references.push(reference);
+
+        page_widgets[field.page_index as usize].extend(widgets);
+    }
+
+    // Add the widgets to their pages, and the fields and fonts to the form, creating
+    // the form if necessary.
+
+    for (page, widgets) in pages.iter().zip(page_widgets) {
+        if let (PdfSyntaxObject::Reference(number, _), false) = (page, widgets.is_empty()) {
+            update
+                .modify(*number, &["Annots"], &mut |annotations| {
+                    append_to_array(annotations, &widgets)
+                })
+                .ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+        }
+    }
+
+    update
+        .modify(root, &["AcroForm", "Fields"], &mut |existing| {
+            append_to_array(existing, &field_references)
+        })
+        .and_then(|_| {
+            update.modify(root, &["AcroForm", "DR", "Font"], &mut |existing| {
+                if *existing == PdfSyntaxObject::Null {
+                    *existing = PdfSyntaxObject::Dictionary(Vec::new());
+                }
+
+                for (name, reference) in added_fonts.iter() {
+                    existing.set(name, reference.clone());
+                }
+
+                Some(())
+            })
+        })
+        .and_then(|_| {
+            // Asking PDF viewers to regenerate the appearances of the form's fields ensures
+            // that text fields and combo boxes, for which no appearance streams are created,
+            // are displayed correctly.
+
+            update.modify(root, &["AcroForm"], &mut |form| {
+                form.set("NeedAppearances", PdfSyntaxObject::Bool(true));
+
+                if form.get("DA").is_none() {
+                    form.set("DA", PdfSyntaxObject::text("/Helv 0 Tf 0 g"));
+                }
+
+                Some(())
+            })
+        })
+        .ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    Ok(update.write(file))
+}
+
+/// Appends the given items to the given array, replacing a null value with a new array.
+pub(crate) fn append_to_array(
+    array: &mut PdfSyntaxObject,
+    items: &[PdfSyntaxObject],
+) -> Option<()> {
+    if *array == PdfSyntaxObject::Null {
+        *array = PdfSyntaxObject::Array(Vec::new());
+    }
+
+    match array {
+        PdfSyntaxObject::Array(array) => {
+            array.extend_from_slice(items);
+
+            Some(())
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::form::builder::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    const MINIMAL_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] >>\nendobj\n\
+        xref\n0 4\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 4 /Root 1 0 R >>\nstartxref\n200\n%%EOF\n";
+
+    #[test]
+    fn test_append_form_fields() {
+        let fields = [
+            PdfFormFieldBuilder::text("Name")
+                .bounds(PdfRect::new_from_values(700.0, 50.0, 720.0, 250.0))
+                .default_value("Jane"),
+            PdfFormFieldBuilder::radio_group("Size")
+                .add_radio_button("Small", PdfRect::new_from_values(650.0, 50.0, 662.0, 62.0))
+                .add_radio_button("Large", PdfRect::new_from_values(650.0, 80.0, 662.0, 92.0))
+                .default_value("Large"),
+        ];
+
+        let output = append_form_fields(MINIMAL_PDF.to_vec(), &fields, 1, &[]).unwrap();
+
+        let file = PdfSyntaxFile::parse(&output).unwrap();
+
+        let catalog = file.resolve(&PdfSyntaxObject::Reference(1, 0));
+
+        let form = file.resolve(catalog.get("AcroForm").unwrap());
+
+        assert_eq!(
+            form.get("NeedAppearances"),
+            Some(&PdfSyntaxObject::Bool(true))
+        );
+
+        let field_references = match form.get("Fields") {
+            Some(PdfSyntaxObject::Array(fields)) => fields.clone(),
+            _ => panic!("missing form fields"),
+        };
+
+        assert_eq!(field_references.len(), 2);
+
+        let text = file.resolve(&field_references[0]);
+
+        assert_eq!(text.get("FT"), Some(&PdfSyntaxObject::name("Tx")));
+        assert_eq!(text.get("V"), Some(&PdfSyntaxObject::text("Jane")));
+
+        let radio = file.resolve(&field_references[1]);
+
+        assert_eq!(radio.get("V"), Some(&PdfSyntaxObject::name("Large")));
+
+        // The text field's widget and both radio buttons are added to the page.
+
+        match file
+            .resolve(&PdfSyntaxObject::Reference(3, 0))
+            .get("Annots")
+        {
+            Some(PdfSyntaxObject::Array(annotations)) => assert_eq!(annotations.len(), 3),
+            _ => panic!("missing page annotations"),
+        }
+
+        assert!(matches!(
+            append_form_fields(MINIMAL_PDF.to_vec(), &fields, 1, &["Name".to_string()]),
+            Err(PdfiumError::InvalidFormFieldDefinition)
+        ));
+        assert!(matches!(
+            append_form_fields(
+                MINIMAL_PDF.to_vec(),
+                &[PdfFormFieldBuilder::checkbox("Agree")],
+                1,
+                &[]
+            ),
+            Err(PdfiumError::InvalidFormFieldDefinition)
+        ));
+    }
+
+    #[test]
+    fn test_create_form_fields() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        document.create_form_fields(&[
+            PdfFormFieldBuilder::text("Name")
+                .bounds(PdfRect::new_from_values(700.0, 50.0, 720.0, 250.0))
+                .default_value("Jane")
+                .required(true),
+            PdfFormFieldBuilder::checkbox("Agree")
+                .bounds(PdfRect::new_from_values(670.0, 50.0, 682.0, 62.0))
+                .checked(true),
+            PdfFormFieldBuilder::radio_group("Size")
+                .add_radio_button("Small", PdfRect::new_from_values(640.0, 50.0, 652.0, 62.0))
+                .add_radio_button("Large", PdfRect::new_from_values(640.0, 80.0, 652.0, 92.0)),
+            PdfFormFieldBuilder::combo_box("Color")
+                .bounds(PdfRect::new_from_values(600.0, 50.0, 620.0, 250.0))
+                .add_option("Red")
+                .add_option("Blue")
+                .default_value("Blue"),
+        ])?;
+
+        let fields = document.form().unwrap().fields(document.pages());
+
+        assert_eq!(fields.len(), 4);
+        assert_eq!(
+            fields.get("Name").unwrap().value(),
+            Some(PdfFormFieldValue::Text("Jane".to_string()))
+        );
+        assert!(fields.get("Name").unwrap().is_required());
+        assert_eq!(
+            fields.get("Agree").unwrap().value(),
+            Some(PdfFormFieldValue::Checkbox(true))
+        );
+        assert_eq!(
+            fields.get("Color").unwrap().value(),
+            Some(PdfFormFieldValue::ComboBox(Some("Blue".to_string())))
+        );
+        assert_eq!(fields.get("Size").unwrap().widgets().len(), 2);
+
+        Ok(())
+    }
+}
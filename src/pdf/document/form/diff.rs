@@ -0,0 +1,291 @@
+//! Defines the [PdfFormDiff] struct, describing the differences between the fields of
+//! two forms as returned by the `PdfForm::diff()` function.
+
+use crate::pdf::document::form::fields::{PdfFormFieldValue, PdfFormFields};
+use crate::pdf::document::page::field::PdfFormFieldType;
+use std::slice::Iter;
+
+#[cfg(doc)]
+use crate::pdf::document::form::PdfForm;
+
+/// A single difference between a logical field in one form and the field with the same
+/// fully qualified name in another form.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfFormFieldDifference {
+    /// A field exists only in the revised form.
+    Added {
+        name: String,
+        field_type: PdfFormFieldType,
+        value: Option<PdfFormFieldValue>,
+    },
+
+    /// A field exists only in the original form.
+    Removed {
+        name: String,
+        field_type: PdfFormFieldType,
+        value: Option<PdfFormFieldValue>,
+    },
+
+    /// A field exists in both forms, but with different field types. The values of
+    /// fields of different types cannot be compared, so no value change is reported
+    /// for such fields.
+    TypeChanged {
+        name: String,
+        old: PdfFormFieldType,
+        new: PdfFormFieldType,
+    },
+
+    /// The value of a field differs between the two forms.
+    ValueChanged {
+        name: String,
+        old: Option<PdfFormFieldValue>,
+        new: Option<PdfFormFieldValue>,
+    },
+
+    /// The options of a combo box or list box field, or the export values of the widgets
+    /// of a checkbox or radio button field, differ between the two forms.
+    OptionsChanged {
+        name: String,
+        old: Vec<String>,
+        new: Vec<String>,
+    },
+}
+
+impl PdfFormFieldDifference {
+    /// Returns the fully qualified name of the field to which this [PdfFormFieldDifference]
+    /// applies.
+    pub fn name(&self) -> &str {
+        match self {
+            PdfFormFieldDifference::Added { name, .. }
+            | PdfFormFieldDifference::Removed { name, .. }
+            | PdfFormFieldDifference::TypeChanged { name, .. }
+            | PdfFormFieldDifference::ValueChanged { name, .. }
+            | PdfFormFieldDifference::OptionsChanged { name, .. } => name.as_str(),
+        }
+    }
+}
+
+/// The differences between the fields of an original form and a revised form,
+/// as returned by [PdfForm::diff()].
+///
+/// Fields are matched by fully qualified name. Differences are listed in the document
+/// order of the fields in the original form, followed by fields added in the revised form
+/// in their document order. A field whose options and value both changed is listed with
+/// both differences, options first.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PdfFormDiff {
+    differences: Vec<PdfFormFieldDifference>,
+}
+
+impl PdfFormDiff {
+    /// Compares the fields in the given original and revised [PdfFormFields] collections.
+    pub(crate) fn from_fields(old: &PdfFormFields, new: &PdfFormFields) -> Self {
+        let snapshot = |fields: &PdfFormFields| {
+            fields
+                .iter()
+                .map(|field| PdfFormFieldSnapshot {
+                    name: field.name().to_owned(),
+                    field_type: field.field_type(),
+                    value: field.value(),
+                    options: match field.field_type() {
+                        PdfFormFieldType::Checkbox | PdfFormFieldType::RadioButton => {
+                            field.export_values()
+                        }
+                        _ => field.options(),
+                    },
+                })
+                .collect::<Vec<_>>()
+        };
+
+        Self::from_snapshots(snapshot(old), snapshot(new))
+    }
+
+    /// Compares the given snapshots of the fields in an original and a revised form.
+    fn from_snapshots(old: Vec<PdfFormFieldSnapshot>, new: Vec<PdfFormFieldSnapshot>) -> Self {
+        let mut differences = Vec::new();
+
+        for old_field in old.iter() {
+            let new_field = match new.iter().find(|field| field.name == old_field.name) {
+                Some(field) => field,
+                None => {
+                    differences.push(PdfFormFieldDifference::Removed {
+                        name: old_field.name.clone(),
+                        field_type: old_field.field_type,
+                        value: old_field.value.clone(),
+                    });
+
+                    continue;
+                }
+            };
+
+            if old_field.field_type != new_field.field_type {
+                differences.push(PdfFormFieldDifference::TypeChanged {
+                    name: old_field.name.clone(),
+                    old: old_field.field_type,
+                    new: new_field.field_type,
+                });
+
+                continue;
+            }
+
+            if old_field.options != new_field.options {
+                differences.push(PdfFormFieldDifference::OptionsChanged {
+                    name: old_field.name.clone(),
+                    old: old_field.options.clone(),
+                    new: new_field.options.clone(),
+                });
+            }
+
+            if old_field.value != new_field.value {
+                differences.push(PdfFormFieldDifference::ValueChanged {
+                    name: old_field.name.clone(),
+                    old: old_field.value.clone(),
+                    new: new_field.value.clone(),
+                });
+            }
+        }
+
+        for new_field in new.into_iter() {
+            if !old.iter().any(|field| field.name == new_field.name) {
+                differences.push(PdfFormFieldDifference::Added {
+                    name: new_field.name,
+                    field_type: new_field.field_type,
+                    value: new_field.value,
+                });
+            }
+        }
+
+        PdfFormDiff { differences }
+    }
+
+    /// Returns the number of differences in this [PdfFormDiff].
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.differences.len()
+    }
+
+    /// Returns `true` if the two compared forms have identical fields, options, and values.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.differences.is_empty()
+    }
+
+    /// Returns all the differences in this [PdfFormDiff].
+    #[inline]
+    pub fn differences(&self) -> &[PdfFormFieldDifference] {
+        self.differences.as_slice()
+    }
+
+    /// Returns the differences that apply to the field with the given fully qualified name.
+    #[inline]
+    pub fn for_field<'a>(
+        &'a self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'a PdfFormFieldDifference> + 'a {
+        self.differences
+            .iter()
+            .filter(move |difference| difference.name() == name)
+    }
+
+    /// Returns an iterator over all the differences in this [PdfFormDiff].
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, PdfFormFieldDifference> {
+        self.differences.iter()
+    }
+}
+
+/// The name, type, value, and options of a single logical field, captured for comparison.
+struct PdfFormFieldSnapshot {
+    name: String,
+    field_type: PdfFormFieldType,
+    value: Option<PdfFormFieldValue>,
+    options: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::form::diff::*;
+
+    fn snapshot(
+        name: &str,
+        field_type: PdfFormFieldType,
+        value: Option<PdfFormFieldValue>,
+        options: &[&str],
+    ) -> PdfFormFieldSnapshot {
+        PdfFormFieldSnapshot {
+            name: name.to_owned(),
+            field_type,
+            value,
+            options: options.iter().map(|option| option.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff_snapshots() {
+        let text = |value: &str| Some(PdfFormFieldValue::Text(value.to_owned()));
+
+        let combo = |value: &str| Some(PdfFormFieldValue::ComboBox(Some(value.to_owned())));
+
+        let old = vec![
+            snapshot("name", PdfFormFieldType::Text, text("Alice"), &[]),
+            snapshot("unchanged", PdfFormFieldType::Text, text("same"), &[]),
+            snapshot("removed", PdfFormFieldType::Text, text("gone"), &[]),
+            snapshot("retyped", PdfFormFieldType::Text, text("1"), &[]),
+            snapshot("colour", PdfFormFieldType::ComboBox, combo("Red"), &["Red"]),
+        ];
+
+        let new = vec![
+            snapshot("added", PdfFormFieldType::Checkbox, None, &["Yes"]),
+            snapshot(
+                "colour",
+                PdfFormFieldType::ComboBox,
+                combo("Blue"),
+                &["Red", "Blue"],
+            ),
+            snapshot("retyped", PdfFormFieldType::Checkbox, None, &["Yes"]),
+            snapshot("unchanged", PdfFormFieldType::Text, text("same"), &[]),
+            snapshot("name", PdfFormFieldType::Text, text("Bob"), &[]),
+        ];
+
+        let diff = PdfFormDiff::from_snapshots(old, new);
+
+        assert_eq!(
+            diff.differences(),
+            &[
+                PdfFormFieldDifference::ValueChanged {
+                    name: "name".to_owned(),
+                    old: text("Alice"),
+                    new: text("Bob"),
+                },
+                PdfFormFieldDifference::Removed {
+                    name: "removed".to_owned(),
+                    field_type: PdfFormFieldType::Text,
+                    value: text("gone"),
+                },
+                PdfFormFieldDifference::TypeChanged {
+                    name: "retyped".to_owned(),
+                    old: PdfFormFieldType::Text,
+                    new: PdfFormFieldType::Checkbox,
+                },
+                PdfFormFieldDifference::OptionsChanged {
+                    name: "colour".to_owned(),
+                    old: vec!["Red".to_owned()],
+                    new: vec!["Red".to_owned(), "Blue".to_owned()],
+                },
+                PdfFormFieldDifference::ValueChanged {
+                    name: "colour".to_owned(),
+                    old: combo("Red"),
+                    new: combo("Blue"),
+                },
+                PdfFormFieldDifference::Added {
+                    name: "added".to_owned(),
+                    field_type: PdfFormFieldType::Checkbox,
+                    value: None,
+                },
+            ]
+        );
+
+        assert_eq!(diff.for_field("colour").count(), 2);
+        assert_eq!(diff.for_field("unchanged").count(), 0);
+    }
+}
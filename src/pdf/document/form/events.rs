@@ -0,0 +1,760 @@
+//! Defines the [PdfFormEventHandler] struct, passing user input events from an interactive
+//! viewer to the fields of a `PdfForm`.
+
+use crate::bindgen::{
+    FWL_EVENTFLAG_FWL_EVENTFLAG_AltKey, FWL_EVENTFLAG_FWL_EVENTFLAG_ControlKey,
+    FWL_EVENTFLAG_FWL_EVENTFLAG_MetaKey, FWL_EVENTFLAG_FWL_EVENTFLAG_ShiftKey,
+    FWL_VKEYCODE_FWL_VKEY_Back, FWL_VKEYCODE_FWL_VKEY_Delete, FWL_VKEYCODE_FWL_VKEY_Down,
+    FWL_VKEYCODE_FWL_VKEY_End, FWL_VKEYCODE_FWL_VKEY_Escape, FWL_VKEYCODE_FWL_VKEY_Home,
+    FWL_VKEYCODE_FWL_VKEY_Insert, FWL_VKEYCODE_FWL_VKEY_Left, FWL_VKEYCODE_FWL_VKEY_Next,
+    FWL_VKEYCODE_FWL_VKEY_Prior, FWL_VKEYCODE_FWL_VKEY_Return, FWL_VKEYCODE_FWL_VKEY_Right,
+    FWL_VKEYCODE_FWL_VKEY_Tab, FWL_VKEYCODE_FWL_VKEY_Up, FPDFPAGE_AACTION_CLOSE,
+    FPDFPAGE_AACTION_OPEN, FPDF_ANNOTATION, FPDF_BOOL, FPDF_FORMHANDLE, FPDF_PAGE, FS_POINTF,
+};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::document::form::fields::PdfFormWidgetLocation;
+use crate::pdf::document::form::tab_order::{
+    page_tab_orders, widgets_in_tab_order, PdfFormTabOrder,
+};
+use crate::pdf::document::form::PdfForm;
+use crate::pdf::document::page::annotations::PdfPageAnnotationIndex;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+use std::os::raw::c_int;
+use std::ptr::null_mut;
+
+/// A mouse button pressed or released over a page displaying a `PdfForm`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PdfFormMouseButton {
+    Left,
+    Right,
+}
+
+/// A non-character key pressed or released while a field in a `PdfForm` has the keyboard focus.
+/// Keys that produce text should also be passed to [PdfFormEventHandler::on_char()].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PdfFormKey {
+    Backspace,
+    Tab,
+    Enter,
+    Escape,
+    PageUp,
+    PageDown,
+    End,
+    Home,
+    Left,
+    Up,
+    Right,
+    Down,
+    Insert,
+    Delete,
+
+    /// Any other key, identified by its Windows virtual key code.
+    Other(i32),
+}
+
+impl PdfFormKey {
+    #[inline]
+    pub(crate) fn as_pdfium(&self) -> c_int {
+        (match self {
+            PdfFormKey::Backspace => FWL_VKEYCODE_FWL_VKEY_Back,
+            PdfFormKey::Tab => FWL_VKEYCODE_FWL_VKEY_Tab,
+            PdfFormKey::Enter => FWL_VKEYCODE_FWL_VKEY_Return,
+            PdfFormKey::Escape => FWL_VKEYCODE_FWL_VKEY_Escape,
+            PdfFormKey::PageUp => FWL_VKEYCODE_FWL_VKEY_Prior,
+            PdfFormKey::PageDown => FWL_VKEYCODE_FWL_VKEY_Next,
+            PdfFormKey::End => FWL_VKEYCODE_FWL_VKEY_End,
+            PdfFormKey::Home => FWL_VKEYCODE_FWL_VKEY_Home,
+            PdfFormKey::Left => FWL_VKEYCODE_FWL_VKEY_Left,
+            PdfFormKey::Up => FWL_VKEYCODE_FWL_VKEY_Up,
+            PdfFormKey::Right => FWL_VKEYCODE_FWL_VKEY_Right,
+            PdfFormKey::Down => FWL_VKEYCODE_FWL_VKEY_Down,
+            PdfFormKey::Insert => FWL_VKEYCODE_FWL_VKEY_Insert,
+            PdfFormKey::Delete => FWL_VKEYCODE_FWL_VKEY_Delete,
+            PdfFormKey::Other(code) => return *code as c_int,
+        }) as c_int
+    }
+}
+
+/// The modifier keys held down when a user input event occurred.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PdfFormEventModifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+impl PdfFormEventModifiers {
+    #[inline]
+    pub(crate) fn as_pdfium(&self) -> c_int {
+        let mut result = 0;
+
+        if self.shift {
+            result |= FWL_EVENTFLAG_FWL_EVENTFLAG_ShiftKey;
+        }
+
+        if self.control {
+            result |= FWL_EVENTFLAG_FWL_EVENTFLAG_ControlKey;
+        }
+
+        if self.alt {
+            result |= FWL_EVENTFLAG_FWL_EVENTFLAG_AltKey;
+        }
+
+        if self.meta {
+            result |= FWL_EVENTFLAG_FWL_EVENTFLAG_MetaKey;
+        }
+
+        result as c_int
+    }
+}
+
+/// The result of passing a single user input event to a [PdfFormEventHandler].
+#[derive(Clone, Debug, PartialEq)]
+pub struct PdfFormEventResponse {
+    is_handled: bool,
+    invalidated_rects: Vec<(PdfPageIndex, PdfRect)>,
+}
+
+impl PdfFormEventResponse {
+    /// Returns `true` if a form field widget consumed the event.
+    #[inline]
+    pub fn is_handled(&self) -> bool {
+        self.is_handled
+    }
+
+    /// Returns the areas of pages, in page coordinates, that changed appearance as a result
+    /// of the event and should be re-rendered.
+    ///
+    /// Invalidated areas are not reported when compiling to WASM.
+    #[inline]
+    pub fn invalidated_rects(&self) -> &[(PdfPageIndex, PdfRect)] {
+        self.invalidated_rects.as_slice()
+    }
+}
+
+/// Passes user input events, such as mouse clicks and key presses, from an interactive viewer
+/// to the fields of a `PdfForm`.
+///
+/// Pdfium only delivers events to pages that have been loaded into the form fill environment.
+/// The handler loads each page the first time an event is passed to it, keeping it loaded
+/// (and running its page open and close actions) until [PdfFormEventHandler::close_page()]
+/// is called or the handler is dropped. Any field edit still in progress when the handler
+/// is dropped is committed to the field's value.
+///
+/// Coordinates are given in page coordinates, with the origin at the bottom left of the page;
+/// use `PdfPage::pixels_to_points()` to convert from window coordinates.
+pub struct PdfFormEventHandler<'a> {
+    form: &'a PdfForm<'a>,
+    pages: &'a PdfPages<'a>,
+    loaded_pages: Vec<(PdfPageIndex, PdfPage<'a>)>,
+    tab_orders: Option<Vec<PdfFormTabOrder>>,
+}
+
+impl<'a> PdfFormEventHandler<'a> {
+    #[inline]
+    pub(crate) fn new(form: &'a PdfForm<'a>, pages: &'a PdfPages<'a>) -> Self {
+        PdfFormEventHandler {
+            form,
+            pages,
+            loaded_pages: Vec::new(),
+            tab_orders: None,
+        }
+    }
+
+    /// Returns the [PdfiumLibraryBindings] used by this [PdfFormEventHandler].
+    #[inline]
+    pub fn bindings(&self) -> &'a dyn PdfiumLibraryBindings {
+        self.form.bindings()
+    }
+
+    /// Returns the page at the given index, loading it into the form fill environment if
+    /// it is not already loaded. Pages returned by this function should be used for rendering,
+    /// so that the current state of any field being edited is drawn.
+    pub fn page(&mut self, index: PdfPageIndex) -> Result<&PdfPage<'a>, PdfiumError> {
+        let position = match self.position_of(index) {
+            Some(position) => position,
+            None => {
+                let page = self.pages.get(index)?;
+
+                let bindings = self.bindings();
+
+                let form_handle = self.form.handle();
+
+                bindings.FORM_OnAfterLoadPage(page.page_handle(), form_handle);
+                bindings.FORM_DoPageAAction(
+                    page.page_handle(),
+                    form_handle,
+                    FPDFPAGE_AACTION_OPEN as c_int,
+                );
+
+                self.loaded_pages.push((index, page));
+
+                self.loaded_pages.len() - 1
+            }
+        };
+
+        Ok(&self.loaded_pages[position].1)
+    }
+
+    /// Removes the page at the given index from the form fill environment, running its page
+    /// close action. Does nothing if the page is not loaded.
+    pub fn close_page(&mut self, index: PdfPageIndex) {
+        if let Some(position) = self.position_of(index) {
+            let (_, page) = self.loaded_pages.remove(position);
+
+            Self::unload(self.bindings(), self.form.handle(), &page);
+        }
+    }
+
+    /// Passes a mouse movement over the page at the given index to the form.
+    pub fn on_mouse_move(
+        &mut self,
+        page_index: PdfPageIndex,
+        x: PdfPoints,
+        y: PdfPoints,
+        modifiers: PdfFormEventModifiers,
+    ) -> Result<PdfFormEventResponse, PdfiumError> {
+        let page = self.page_handle(page_index)?;
+
+        Ok(self.dispatch(|bindings, form| {
+            bindings.FORM_OnMouseMove(
+                form,
+                page,
+                modifiers.as_pdfium(),
+                x.value as f64,
+                y.value as f64,
+            )
+        }))
+    }
+
+    /// Passes a mouse button press over the page at the given index to the form.
+    pub fn on_mouse_down(
+        &mut self,
+        page_index: PdfPageIndex,
+        button: PdfFormMouseButton,
+        x: PdfPoints,
+        y: PdfPoints,
+        modifiers: PdfFormEventModifiers,
+    ) -> Result<PdfFormEventResponse, PdfiumError> {
+        let page = self.page_handle(page_index)?;
+
+        Ok(self.dispatch(|bindings, form| {
+            let (modifier, x, y) = (modifiers.as_pdfium(), x.value as f64, y.value as f64);
+
+            match button {
+                PdfFormMouseButton::Left => bindings.FORM_OnLButtonDown(form, page, modifier, x, y),
+                PdfFormMouseButton::Right => {
+                    bindings.FORM_OnRButtonDown(form, page, modifier, x, y)
+                }
+            }
+        }))
+    }
+
+    /// Passes a mouse button release over the page at the given index to the form.
+    pub fn on_mouse_up(
+        &mut self,
+        page_index: PdfPageIndex,
+        button: PdfFormMouseButton,
+        x: PdfPoints,
+        y: PdfPoints,
+        modifiers: PdfFormEventModifiers,
+    ) -> Result<PdfFormEventResponse, PdfiumError> {
+        let page = self.page_handle(page_index)?;
+
+        Ok(self.dispatch(|bindings, form| {
+            let (modifier, x, y) = (modifiers.as_pdfium(), x.value as f64, y.value as f64);
+
+            match button {
+                PdfFormMouseButton::Left => bindings.FORM_OnLButtonUp(form, page, modifier, x, y),
+                PdfFormMouseButton::Right => bindings.FORM_OnRButtonUp(form, page, modifier, x, y),
+            }
+        }))
+    }
+
+    /// Passes a left mouse button double click over the page at the given index to the form.
+    pub fn on_double_click(
+        &mut self,
+        page_index: PdfPageIndex,
+        x: PdfPoints,
+        y: PdfPoints,
+        modifiers: PdfFormEventModifiers,
+    ) -> Result<PdfFormEventResponse, PdfiumError> {
+        let page = self.page_handle(page_index)?;
+
+        Ok(self.dispatch(|bindings, form| {
+            bindings.FORM_OnLButtonDoubleClick(
+                form,
+                page,
+                modifiers.as_pdfium(),
+                x.value as f64,
+                y.value as f64,
+            )
+        }))
+    }
+
+    /// Passes a mouse wheel movement over the page at the given index to the form.
+    /// The given deltas are measured in wheel notches, positive values scrolling up and right.
+    pub fn on_mouse_wheel(
+        &mut self,
+        page_index: PdfPageIndex,
+        x: PdfPoints,
+        y: PdfPoints,
+        delta_x: i32,
+        delta_y: i32,
+        modifiers: PdfFormEventModifiers,
+    ) -> Result<PdfFormEventResponse, PdfiumError> {
+        let page = self.page_handle(page_index)?;
+
+        let coordinates = FS_POINTF {
+            x: x.value,
+            y: y.value,
+        };
+
+        Ok(self.dispatch(|bindings, form| {
+            bindings.FORM_OnMouseWheel(
+                form,
+                page,
+                modifiers.as_pdfium(),
+                &coordinates,
+                delta_x as c_int,
+                delta_y as c_int,
+            )
+        }))
+    }
+
+    /// Moves the keyboard focus to the form field widget at the given position on the page
+    /// at the given index, without clicking it. If there is no widget at the given position,
+    /// the keyboard focus is removed from any focused widget.
+    pub fn on_focus(
+        &mut self,
+        page_index: PdfPageIndex,
+        x: PdfPoints,
+        y: PdfPoints,
+        modifiers: PdfFormEventModifiers,
+    ) -> Result<PdfFormEventResponse, PdfiumError> {
+        let page = self.page_handle(page_index)?;
+
+        Ok(self.dispatch(|bindings, form| {
+            bindings.FORM_OnFocus(
+                form,
+                page,
+                modifiers.as_pdfium(),
+                x.value as f64,
+                y.value as f64,
+            )
+        }))
+    }
+
+    /// Passes a key press to the form field widget with the keyboard focus, if any.
+    pub fn on_key_down(
+        &mut self,
+        key: PdfFormKey,
+        modifiers: PdfFormEventModifiers,
+    ) -> PdfFormEventResponse {
+        self.dispatch_to_focused_page(|bindings, form, page| {
+            bindings.FORM_OnKeyDown(form, page, key.as_pdfium(), modifiers.as_pdfium())
+        })
+    }
+
+    /// Passes a key release to the form field widget with the keyboard focus, if any.
+    pub fn on_key_up(
+        &mut self,
+        key: PdfFormKey,
+        modifiers: PdfFormEventModifiers,
+    ) -> PdfFormEventResponse {
+        self.dispatch_to_focused_page(|bindings, form, page| {
+            bindings.FORM_OnKeyUp(form, page, key.as_pdfium(), modifiers.as_pdfium())
+        })
+    }
+
+    /// Passes a typed character to the form field widget with the keyboard focus, if any.
+    pub fn on_char(
+        &mut self,
+        character: char,
+        modifiers: PdfFormEventModifiers,
+    ) -> PdfFormEventResponse {
+        let mut buffer = [0; 2];
+
+        let units = character.encode_utf16(&mut buffer);
+
+        let mut is_handled = false;
+
+        let mut invalidated_rects = Vec::new();
+
+        for unit in units.iter() {
+            let response = self.dispatch_to_focused_page(|bindings, form, page| {
+                bindings.FORM_OnChar(form, page, *unit as c_int, modifiers.as_pdfium())
+            });
+
+            is_handled |= response.is_handled;
+            invalidated_rects.extend(response.invalidated_rects);
+        }
+
+        PdfFormEventResponse {
+            is_handled,
+            invalidated_rects,
+        }
+    }
+
+    /// Removes the keyboard focus from the focused form field widget, if any, committing
+    /// any edit in progress to the field's value.
+    pub fn kill_focus(&mut self) -> PdfFormEventResponse {
+        self.dispatch(|bindings, form| bindings.FORM_ForceToKillFocus(form))
+    }
+
+    /// Moves the keyboard focus to the next form field widget in tab order, as returned by
+    /// `PdfForm::tab_order()`, skipping any widgets that cannot take the focus. The focus
+    /// moves on to the widgets of the following pages once the last widget on a page is
+    /// reached, wrapping around from the last page to the first. If no widget has the
+    /// focus, the first widget that can take the focus is focused.
+    ///
+    /// The tab order of each page is read the first time this function or
+    /// [PdfFormEventHandler::focus_previous()] is called, and is not updated afterwards.
+    #[inline]
+    pub fn focus_next(&mut self) -> Result<PdfFormEventResponse, PdfiumError> {
+        self.move_focus(true)
+    }
+
+    /// Moves the keyboard focus to the previous form field widget in tab order, as returned
+    /// by `PdfForm::tab_order()`, skipping any widgets that cannot take the focus. The focus
+    /// moves back to the widgets of the preceding pages once the first widget on a page
+    /// is reached, wrapping around from the first page to the last. If no widget has the
+    /// focus, the last widget that can take the focus is focused.
+    #[inline]
+    pub fn focus_previous(&mut self) -> Result<PdfFormEventResponse, PdfiumError> {
+        self.move_focus(false)
+    }
+
+    /// Moves the keyboard focus forwards or backwards through the tab order of the form's
+    /// widgets, wrapping around at the end of the document.
+    fn move_focus(&mut self, is_forwards: bool) -> Result<PdfFormEventResponse, PdfiumError> {
+        let page_count = self.pages.len() as usize;
+
+        let focused = self.focused_annotation();
+
+        let first_page_index = match focused {
+            Some(location) => location.page_index() as usize,
+            None if is_forwards => 0,
+            None => page_count.saturating_sub(1),
+        };
+
+        let mut candidates = Vec::new();
+
+        // Widgets on the first page that precede the focused widget are only reached
+        // after wrapping around the end of the document.
+
+        let mut wrapped_candidates = Vec::new();
+
+        for step in 0..page_count {
+            let page_index = if is_forwards {
+                (first_page_index + step) % page_count
+            } else {
+                (first_page_index + page_count - step) % page_count
+            };
+
+            let mut widgets = self.widgets_in_tab_order(page_index as PdfPageIndex)?;
+
+            if !is_forwards {
+                widgets.reverse();
+            }
+
+            if step == 0 {
+                if let Some(position) =
+                    focused.and_then(|focused| widgets.iter().position(|widget| *widget == focused))
+                {
+                    wrapped_candidates = widgets.drain(..=position).collect();
+
+                    // Remove the focused widget itself.
+
+                    wrapped_candidates.pop();
+                }
+            }
+
+            candidates.extend(widgets);
+        }
+
+        candidates.extend(wrapped_candidates);
+
+        let bindings = self.bindings();
+
+        for candidate in candidates {
+            let page_handle = self.page_handle(candidate.page_index())?;
+
+            let annotation_handle =
+                bindings.FPDFPage_GetAnnot(page_handle, candidate.annotation_index() as c_int);
+
+            if annotation_handle.is_null() {
+                continue;
+            }
+
+            let response = self
+                .dispatch(|bindings, form| bindings.FORM_SetFocusedAnnot(form, annotation_handle));
+
+            bindings.FPDFPage_CloseAnnot(annotation_handle);
+
+            if response.is_handled() {
+                return Ok(response);
+            }
+        }
+
+        Ok(PdfFormEventResponse {
+            is_handled: false,
+            invalidated_rects: Vec::new(),
+        })
+    }
+
+    /// Returns the locations of the form field widgets on the page at the given index,
+    /// in tab order.
+    fn widgets_in_tab_order(
+        &mut self,
+        page_index: PdfPageIndex,
+    ) -> Result<Vec<PdfFormWidgetLocation>, PdfiumError> {
+        if self.tab_orders.is_none() {
+            self.tab_orders = Some(page_tab_orders(
+                self.form.document_handle(),
+                self.bindings(),
+            )?);
+        }
+
+        let order = self
+            .tab_orders
+            .as_ref()
+            .and_then(|orders| orders.get(page_index as usize))
+            .copied()
+            .unwrap_or(PdfFormTabOrder::Annotation);
+
+        widgets_in_tab_order(&self.pages.get(page_index)?, page_index, order)
+    }
+
+    /// Returns the location of the form field widget with the keyboard focus, if any.
+    pub fn focused_annotation(&self) -> Option<PdfFormWidgetLocation> {
+        let bindings = self.bindings();
+
+        let (page_index, annotation_handle) = self.focused_annotation_handle()?;
+
+        let result = self
+            .position_of(page_index)
+            .map(|position| {
+                bindings.FPDFPage_GetAnnotIndex(
+                    self.loaded_pages[position].1.page_handle(),
+                    annotation_handle,
+                )
+            })
+            .filter(|index| *index >= 0)
+            .map(|index| PdfFormWidgetLocation::new(page_index, index as PdfPageAnnotationIndex));
+
+        bindings.FPDFPage_CloseAnnot(annotation_handle);
+
+        result
+    }
+
+    /// Returns the index of the page containing the form field widget with the keyboard focus,
+    /// along with a handle to the widget that must be closed by the caller.
+    fn focused_annotation_handle(&self) -> Option<(PdfPageIndex, FPDF_ANNOTATION)> {
+        let mut page_index: c_int = -1;
+
+        let mut annotation_handle: FPDF_ANNOTATION = null_mut();
+
+        let bindings = self.bindings();
+
+        if !bindings.is_true(bindings.FORM_GetFocusedAnnot(
+            self.form.handle(),
+            &mut page_index,
+            &mut annotation_handle,
+        )) || annotation_handle.is_null()
+        {
+            return None;
+        }
+
+        if page_index < 0 {
+            bindings.FPDFPage_CloseAnnot(annotation_handle);
+
+            return None;
+        }
+
+        Some((page_index as PdfPageIndex, annotation_handle))
+    }
+
+    /// Returns the position of the page at the given index in the list of loaded pages.
+    #[inline]
+    fn position_of(&self, index: PdfPageIndex) -> Option<usize> {
+        self.loaded_pages
+            .iter()
+            .position(|(page_index, _)| *page_index == index)
+    }
+
+    /// Returns the handle of the page at the given index, loading the page if necessary.
+    #[inline]
+    fn page_handle(&mut self, index: PdfPageIndex) -> Result<FPDF_PAGE, PdfiumError> {
+        self.page(index).map(|page| page.page_handle())
+    }
+
+    /// Calls the given Pdfium function on the page containing the form field widget
+    /// with the keyboard focus. The event is not handled if no widget has the focus.
+    fn dispatch_to_focused_page(
+        &mut self,
+        f: impl FnOnce(&dyn PdfiumLibraryBindings, FPDF_FORMHANDLE, FPDF_PAGE) -> FPDF_BOOL,
+    ) -> PdfFormEventResponse {
+        let page = match self.focused_annotation_handle() {
+            Some((page_index, annotation_handle)) => {
+                self.bindings().FPDFPage_CloseAnnot(annotation_handle);
+
+                self.page_handle(page_index).ok()
+            }
+            None => None,
+        };
+
+        match page {
+            Some(page) => self.dispatch(|bindings, form| f(bindings, form, page)),
+            None => PdfFormEventResponse {
+                is_handled: false,
+                invalidated_rects: Vec::new(),
+            },
+        }
+    }
+
+    /// Calls the given Pdfium function, collecting the areas of loaded pages that Pdfium
+    /// asks to be redrawn as a result.
+    fn dispatch(
+        &self,
+        f: impl FnOnce(&dyn PdfiumLibraryBindings, FPDF_FORMHANDLE) -> FPDF_BOOL,
+    ) -> PdfFormEventResponse {
+        let bindings = self.bindings();
+
+        // Discard any areas recorded outside of this handler, for instance while rendering.
+
+        self.form.take_invalidated_rects();
+
+        let is_handled = bindings.is_true(f(bindings, self.form.handle()));
+
+        let invalidated_rects = self
+            .form
+            .take_invalidated_rects()
+            .into_iter()
+            .filter_map(|(page_handle, rect)| {
+                self.loaded_pages
+                    .iter()
+                    .find(|(_, page)| page.page_handle() == page_handle)
+                    .map(|(index, _)| (*index, rect))
+            })
+            .collect();
+
+        PdfFormEventResponse {
+            is_handled,
+            invalidated_rects,
+        }
+    }
+
+    /// Runs the page close action of the given page and removes it from the form fill
+    /// environment.
+    #[inline]
+    fn unload(bindings: &dyn PdfiumLibraryBindings, form_handle: FPDF_FORMHANDLE, page: &PdfPage) {
+        bindings.FORM_DoPageAAction(
+            page.page_handle(),
+            form_handle,
+            FPDFPAGE_AACTION_CLOSE as c_int,
+        );
+        bindings.FORM_OnBeforeClosePage(page.page_handle(), form_handle);
+    }
+}
+
+impl<'a> Drop for PdfFormEventHandler<'a> {
+    /// Commits any edit in progress, then removes every loaded page from the
+    /// form fill environment.
+    fn drop(&mut self) {
+        let bindings = self.bindings();
+
+        let form_handle = self.form.handle();
+
+        bindings.FORM_ForceToKillFocus(form_handle);
+
+        for (_, page) in self.loaded_pages.drain(..) {
+            Self::unload(bindings, form_handle, &page);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_form_event_handler_types_text() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("test/form-test.pdf", None)?;
+
+        let form = document.form().unwrap();
+
+        let fields = form.fields(document.pages());
+
+        let field = fields
+            .iter()
+            .find(|field| field.field_type() == PdfFormFieldType::Text)
+            .unwrap();
+
+        let name = field.name().to_string();
+
+        let widget = field.widgets()[0];
+
+        let bounds = document
+            .pages()
+            .get(widget.page_index())?
+            .annotations()
+            .get(widget.annotation_index() as usize)?
+            .bounds()?;
+
+        let x = (bounds.left + bounds.right) / 2.0;
+
+        let y = (bounds.top + bounds.bottom) / 2.0;
+
+        {
+            let mut handler = form.event_handler(document.pages());
+
+            let modifiers = PdfFormEventModifiers::default();
+
+            let page_index = widget.page_index();
+
+            handler.on_mouse_down(page_index, PdfFormMouseButton::Left, x, y, modifiers)?;
+            handler.on_mouse_up(page_index, PdfFormMouseButton::Left, x, y, modifiers)?;
+
+            assert_eq!(handler.focused_annotation(), Some(widget));
+
+            handler.on_key_down(PdfFormKey::End, modifiers);
+
+            for character in "xyz".chars() {
+                assert!(handler.on_char(character, modifiers).is_handled());
+            }
+
+            handler.kill_focus();
+
+            assert_eq!(handler.focused_annotation(), None);
+
+            assert!(handler.focus_next()?.is_handled());
+
+            let focused = handler.focused_annotation().unwrap();
+
+            let tab_order = form.tab_order(&document.pages().get(focused.page_index())?)?;
+
+            assert!(tab_order.contains(&focused));
+
+            handler.kill_focus();
+        }
+
+        let values = form.field_values(document.pages());
+
+        assert!(values
+            .get(&name)
+            .and_then(|value| value.as_deref())
+            .unwrap()
+            .ends_with("xyz"));
+
+        Ok(())
+    }
+}
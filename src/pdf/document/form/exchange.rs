@@ -0,0 +1,652 @@
+//! Imports and exports the values of the fields in a `PdfForm` as FDF, the Forms Data Format
+//! described in section 8.6.6 of The PDF Reference Manual, version 1.7, and as a simple JSON
+//! object, via the `PdfForm::export_fdf()`, `PdfForm::import_fdf()`, `PdfForm::to_json()`,
+//! and `PdfForm::from_json()` functions.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::form::fields::{PdfFormFieldValue, PdfFormFields, PdfFormNamedField};
+use crate::pdf::document::page::field::PdfFormFieldType;
+use crate::pdf::document::syntax::{
+    decode_pdf_string, encode_pdf_text, format_pdf_name, format_pdf_string, PdfSyntaxFile,
+    PdfSyntaxObject,
+};
+use std::str::CharIndices;
+
+/// The maximum depth of nested objects accepted when parsing an FDF or JSON document.
+/// Form data is never deeply nested; the limit guards against exhausting the stack
+/// when parsing malicious input.
+const MAXIMUM_NESTING_DEPTH: usize = 64;
+
+/// The value of a single form field read from an FDF or JSON document, before it is
+/// interpreted according to the type of the field it belongs to.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum ExchangedValue {
+    Null,
+    Bool(bool),
+    Text(String),
+    Name(String),
+    List(Vec<String>),
+}
+
+impl ExchangedValue {
+    /// Interprets this [ExchangedValue] according to the type of the given field.
+    pub(crate) fn to_field_value(
+        &self,
+        field: &PdfFormNamedField,
+    ) -> Result<PdfFormFieldValue, PdfiumError> {
+        let value = match (field.field_type(), self) {
+            (PdfFormFieldType::Text, ExchangedValue::Text(text) | ExchangedValue::Name(text)) => {
+                Some(PdfFormFieldValue::Text(text.clone()))
+            }
+            (PdfFormFieldType::Text, ExchangedValue::Null) => {
+                Some(PdfFormFieldValue::Text(String::new()))
+            }
+            (PdfFormFieldType::Checkbox, ExchangedValue::Bool(is_checked)) => {
+                Some(PdfFormFieldValue::Checkbox(*is_checked))
+            }
+            (
+                PdfFormFieldType::Checkbox,
+                ExchangedValue::Text(state) | ExchangedValue::Name(state),
+            ) => Some(PdfFormFieldValue::Checkbox(!is_off_state(state))),
+            (PdfFormFieldType::Checkbox, ExchangedValue::Null) => {
+                Some(PdfFormFieldValue::Checkbox(false))
+            }
+            (
+                PdfFormFieldType::RadioButton,
+                ExchangedValue::Text(state) | ExchangedValue::Name(state),
+            ) => Some(PdfFormFieldValue::RadioGroup(if is_off_state(state) {
+                None
+            } else {
+                Some(state.clone())
+            })),
+            (PdfFormFieldType::RadioButton, ExchangedValue::Null) => {
+                Some(PdfFormFieldValue::RadioGroup(None))
+            }
+            (
+                PdfFormFieldType::ComboBox,
+                ExchangedValue::Text(value) | ExchangedValue::Name(value),
+            ) => Some(PdfFormFieldValue::ComboBox(if value.is_empty() {
+                None
+            } else {
+                Some(value.clone())
+            })),
+            (PdfFormFieldType::ComboBox, ExchangedValue::Null) => {
+                Some(PdfFormFieldValue::ComboBox(None))
+            }
+            (
+                PdfFormFieldType::ListBox,
+                ExchangedValue::Text(value) | ExchangedValue::Name(value),
+            ) => Some(PdfFormFieldValue::ListBox(vec![value.clone()])),
+            (PdfFormFieldType::ListBox, ExchangedValue::List(values)) => {
+                Some(PdfFormFieldValue::ListBox(values.clone()))
+            }
+            (PdfFormFieldType::ListBox, ExchangedValue::Null) => {
+                Some(PdfFormFieldValue::ListBox(Vec::new()))
+            }
+            _ => None,
+        };
+
+        value.ok_or(PdfiumError::InvalidFormFieldValue)
+    }
+}
+
+/// Returns `true` if the given checkbox or radio button state is the off state.
+#[inline]
+fn is_off_state(state: &str) -> bool {
+    state.is_empty() || state == "Off"
+}
+
+/// Returns the values of the given form fields as an FDF document. Fields are identified
+/// by their fully qualified names; fields without values, such as push buttons, are omitted.
+pub(crate) fn export_fdf(fields: &PdfFormFields) -> Vec<u8> {
+    // The comment on the second line contains bytes outside the ASCII range, marking the
+    // document as binary data as recommended by the PDF Reference.
+
+    let mut fdf = b"%FDF-1.2\n%\xE2\xE3\xCF\xD3\n".to_vec();
+
+    fdf.extend_from_slice(b"1 0 obj\n<< /FDF << /Fields [\n");
+
+    for field in fields.iter() {
+        let value = match field.value() {
+            Some(PdfFormFieldValue::Text(text)) => format_fdf_string(&text),
+            Some(PdfFormFieldValue::Checkbox(is_checked)) => format_pdf_name(&if is_checked {
+                field
+                    .export_values()
+                    .into_iter()
+                    .next()
+                    .unwrap_or_else(|| "Yes".to_string())
+            } else {
+                "Off".to_string()
+            }),
+            Some(PdfFormFieldValue::RadioGroup(state)) => {
+                format_pdf_name(&state.unwrap_or_else(|| "Off".to_string()))
+            }
+            Some(PdfFormFieldValue::ComboBox(Some(value))) => format_fdf_string(&value),
+            Some(PdfFormFieldValue::ListBox(values)) if values.len() == 1 => {
+                format_fdf_string(&values[0])
+            }
+            Some(PdfFormFieldValue::ListBox(values)) if !values.is_empty() => format!(
+                "[{}]",
+                values
+                    .iter()
+                    .map(|value| format_fdf_string(value))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            _ => continue,
+        };
+
+        fdf.extend_from_slice(
+            format!(
+                "<< /T {} /V {} >>\n",
+                format_fdf_string(field.name()),
+                value
+            )
+            .as_bytes(),
+        );
+    }
+
+    fdf.extend_from_slice(b"] >> >>\nendobj\ntrailer\n<< /Root 1 0 R >>\n%%EOF\n");
+
+    fdf
+}
+
+/// Returns the values of the given form fields as a JSON object of
+/// (fully qualified field name, field value) pairs. Fields without values, such as
+/// push buttons, are omitted.
+pub(crate) fn export_json(fields: &PdfFormFields) -> String {
+    let entries = fields
+        .iter()
+        .filter_map(|field| {
+            let value = match field.value()? {
+                PdfFormFieldValue::Text(text) => format_json_string(&text),
+                PdfFormFieldValue::Checkbox(is_checked) => is_checked.to_string(),
+                PdfFormFieldValue::RadioGroup(value) | PdfFormFieldValue::ComboBox(value) => value
+                    .map(|value| format_json_string(&value))
+                    .unwrap_or_else(|| "null".to_string()),
+                PdfFormFieldValue::ListBox(values) => format!(
+                    "[{}]",
+                    values
+                        .iter()
+                        .map(|value| format_json_string(value))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            };
+
+            Some(format!("  {}: {}", format_json_string(field.name()), value))
+        })
+        .collect::<Vec<_>>();
+
+    if entries.is_empty() {
+        "{}".to_string()
+    } else {
+        format!("{{\n{}\n}}", entries.join(",\n"))
+    }
+}
+
+/// Formats the given text as a PDF string.
+#[inline]
+fn format_fdf_string(text: &str) -> String {
+    format_pdf_string(&encode_pdf_text(text))
+}
+
+/// Escapes the given text for use as a quoted JSON string.
+fn format_json_string(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 2);
+
+    result.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+            c => result.push(c),
+        }
+    }
+
+    result.push('"');
+
+    result
+}
+
+/// Reads the field values in the given FDF document as a list of
+/// (fully qualified field name, field value) pairs, in document order.
+pub(crate) fn parse_fdf_values(fdf: &[u8]) -> Option<Vec<(String, ExchangedValue)>> {
+    if !fdf.starts_with(b"%FDF-") {
+        return None;
+    }
+
+    let file = PdfSyntaxFile::parse(fdf)?;
+
+    let resolve = |object: &PdfSyntaxObject| file.resolve(object);
+
+    let root = resolve(file.trailer.as_ref()?.get("Root")?);
+
+    let fdf = resolve(root.get("FDF")?);
+
+    let mut result = Vec::new();
+
+    if let Some(fields) = fdf.get("Fields") {
+        collect_fdf_fields(&resolve(fields), "", 0, &resolve, &mut result)?;
+    }
+
+    Some(result)
+}
+
+/// Appends the values of the given array of FDF field dictionaries, and of their descendants,
+/// to the given list. Partial field names are joined to form fully qualified names.
+fn collect_fdf_fields(
+    fields: &PdfSyntaxObject,
+    parent_name: &str,
+    depth: usize,
+    resolve: &dyn Fn(&PdfSyntaxObject) -> PdfSyntaxObject,
+    result: &mut Vec<(String, ExchangedValue)>,
+) -> Option<()> {
+    if depth > MAXIMUM_NESTING_DEPTH {
+        return None;
+    }
+
+    let fields = match fields {
+        PdfSyntaxObject::Array(fields) => fields,
+        _ => return None,
+    };
+
+    for field in fields.iter() {
+        let field = resolve(field);
+
+        let name = match field.get("T").map(resolve) {
+            Some(PdfSyntaxObject::String(partial_name)) if parent_name.is_empty() => {
+                decode_pdf_string(&partial_name)
+            }
+            Some(PdfSyntaxObject::String(partial_name)) => {
+                format!("{}.{}", parent_name, decode_pdf_string(&partial_name))
+            }
+            _ => parent_name.to_string(),
+        };
+
+        if let Some(value) = field.get("V") {
+            let value = match resolve(value) {
+                PdfSyntaxObject::Null => ExchangedValue::Null,
+                PdfSyntaxObject::Bool(value) => ExchangedValue::Bool(value),
+                PdfSyntaxObject::Number(value) => ExchangedValue::Text(value.to_string()),
+                PdfSyntaxObject::String(value) => ExchangedValue::Text(decode_pdf_string(&value)),
+                PdfSyntaxObject::Name(value) => ExchangedValue::Name(value),
+                PdfSyntaxObject::Array(values) => ExchangedValue::List(
+                    values
+                        .iter()
+                        .map(|value| match resolve(value) {
+                            PdfSyntaxObject::String(value) => Some(decode_pdf_string(&value)),
+                            PdfSyntaxObject::Name(value) => Some(value),
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>()?,
+                ),
+                _ => return None,
+            };
+
+            result.push((name.clone(), value));
+        }
+
+        if let Some(kids) = field.get("Kids") {
+            collect_fdf_fields(&resolve(kids), &name, depth + 1, resolve, result)?;
+        }
+    }
+
+    Some(())
+}
+
+/// Reads the field values in the given JSON object of (fully qualified field name,
+/// field value) pairs, in document order.
+pub(crate) fn parse_json_values(json: &str) -> Option<Vec<(String, ExchangedValue)>> {
+    let mut parser = JsonParser {
+        input: json,
+        position: 0,
+    };
+
+    let root = parser.parse_value(0)?;
+
+    parser.skip_whitespace();
+
+    if parser.position != json.len() {
+        return None;
+    }
+
+    let entries = match root {
+        JsonValue::Object(entries) => entries,
+        _ => return None,
+    };
+
+    entries
+        .into_iter()
+        .map(|(name, value)| {
+            let value = match value {
+                JsonValue::Null => ExchangedValue::Null,
+                JsonValue::Bool(value) => ExchangedValue::Bool(value),
+                JsonValue::Number(value) | JsonValue::String(value) => ExchangedValue::Text(value),
+                JsonValue::Array(values) => ExchangedValue::List(
+                    values
+                        .into_iter()
+                        .map(|value| match value {
+                            JsonValue::String(value) => Some(value),
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>()?,
+                ),
+                JsonValue::Object(_) => return None,
+            };
+
+            Some((name, value))
+        })
+        .collect()
+}
+
+/// A single value in a parsed JSON document. Numbers retain their original text.
+#[derive(Debug, Clone, PartialEq)]
+enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+/// A minimal JSON parser, sufficient for reading form field values.
+struct JsonParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    #[inline]
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+
+        self.position += rest.len() - rest.trim_start_matches([' ', '\t', '\n', '\r']).len();
+    }
+
+    /// Moves past the given text, if the remaining input starts with it.
+    fn consume(&mut self, text: &str) -> bool {
+        if self.rest().starts_with(text) {
+            self.position += text.len();
+
+            true
+        } else {
+            false
+        }
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Option<JsonValue> {
+        if depth > MAXIMUM_NESTING_DEPTH {
+            return None;
+        }
+
+        self.skip_whitespace();
+
+        if self.consume("null") {
+            Some(JsonValue::Null)
+        } else if self.consume("true") {
+            Some(JsonValue::Bool(true))
+        } else if self.consume("false") {
+            Some(JsonValue::Bool(false))
+        } else if self.rest().starts_with('"') {
+            self.parse_string().map(JsonValue::String)
+        } else if self.consume("[") {
+            let mut items = Vec::new();
+
+            self.skip_whitespace();
+
+            if self.consume("]") {
+                return Some(JsonValue::Array(items));
+            }
+
+            loop {
+                items.push(self.parse_value(depth + 1)?);
+
+                self.skip_whitespace();
+
+                if self.consume("]") {
+                    return Some(JsonValue::Array(items));
+                }
+
+                if !self.consume(",") {
+                    return None;
+                }
+            }
+        } else if self.consume("{") {
+            let mut entries = Vec::new();
+
+            self.skip_whitespace();
+
+            if self.consume("}") {
+                return Some(JsonValue::Object(entries));
+            }
+
+            loop {
+                self.skip_whitespace();
+
+                let key = self.parse_string()?;
+
+                self.skip_whitespace();
+
+                if !self.consume(":") {
+                    return None;
+                }
+
+                entries.push((key, self.parse_value(depth + 1)?));
+
+                self.skip_whitespace();
+
+                if self.consume("}") {
+                    return Some(JsonValue::Object(entries));
+                }
+
+                if !self.consume(",") {
+                    return None;
+                }
+            }
+        } else {
+            let rest = self.rest();
+
+            let length = rest
+                .find(|c: char| !(c.is_ascii_digit() || "+-.eE".contains(c)))
+                .unwrap_or(rest.len());
+
+            let number = &rest[..length];
+
+            number.parse::<f64>().ok()?;
+
+            self.position += length;
+
+            Some(JsonValue::Number(number.to_string()))
+        }
+    }
+
+    fn parse_string(&mut self) -> Option<String> {
+        if !self.consume("\"") {
+            return None;
+        }
+
+        let mut result = String::new();
+
+        let mut chars = self.rest().char_indices();
+
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += offset + 1;
+
+                    return Some(result);
+                }
+                '\\' => match chars.next()?.1 {
+                    '"' => result.push('"'),
+                    '\\' => result.push('\\'),
+                    '/' => result.push('/'),
+                    'b' => result.push('\u{8}'),
+                    'f' => result.push('\u{c}'),
+                    'n' => result.push('\n'),
+                    'r' => result.push('\r'),
+                    't' => result.push('\t'),
+                    'u' => {
+                        let unit = parse_json_unicode_escape(&mut chars)?;
+
+                        let c = if (0xD800..0xDC00).contains(&unit) {
+                            // A high surrogate must be followed by an escaped low surrogate.
+
+                            if chars.next()?.1 != '\\' || chars.next()?.1 != 'u' {
+                                return None;
+                            }
+
+                            let low = parse_json_unicode_escape(&mut chars)?;
+
+                            if !(0xDC00..0xE000).contains(&low) {
+                                return None;
+                            }
+
+                            char::from_u32(0x10000 + ((unit - 0xD800) << 10) + (low - 0xDC00))?
+                        } else {
+                            char::from_u32(unit)?
+                        };
+
+                        result.push(c);
+                    }
+                    _ => return None,
+                },
+                c if (c as u32) < 0x20 => return None,
+                c => result.push(c),
+            }
+        }
+
+        None
+    }
+}
+
+/// Parses the four hexadecimal digits following a `\u` escape sequence in a JSON string.
+fn parse_json_unicode_escape(chars: &mut CharIndices) -> Option<u32> {
+    let digits = (0..4)
+        .map(|_| chars.next().map(|(_, c)| c))
+        .collect::<Option<String>>()?;
+
+    u32::from_str_radix(&digits, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::form::exchange::*;
+    use crate::pdf::document::syntax::PdfSyntaxParser;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_parse_fdf_values() {
+        let fdf = b"%FDF-1.2\n%\xE2\xE3\xCF\xD3\n1 0 obj\n<< /FDF << /Fields [\
+            << /T (Name) /V (A \\(quoted\\) value) >>\
+            << /T (Agree) /V /Yes >>\
+            << /T <FEFF0043006F006C006F0072> /V [(Red) (Blue)] >>\
+            << /T (Address) /Kids [<< /T (City) /V 2 0 R >>] >>\
+            ] >> >>\nendobj\n2 0 obj\n(Paris)\nendobj\ntrailer\n<< /Root 1 0 R >>\n%%EOF\n";
+
+        assert_eq!(
+            parse_fdf_values(fdf),
+            Some(vec![
+                (
+                    "Name".to_string(),
+                    ExchangedValue::Text("A (quoted) value".to_string())
+                ),
+                ("Agree".to_string(), ExchangedValue::Name("Yes".to_string())),
+                (
+                    "Color".to_string(),
+                    ExchangedValue::List(vec!["Red".to_string(), "Blue".to_string()])
+                ),
+                (
+                    "Address.City".to_string(),
+                    ExchangedValue::Text("Paris".to_string())
+                ),
+            ])
+        );
+
+        assert_eq!(parse_fdf_values(b"%PDF-1.7\n"), None);
+        assert_eq!(parse_fdf_values(b"%FDF-1.2\n1 0 obj\n<< /FDF"), None);
+    }
+
+    #[test]
+    fn test_format_fdf() {
+        assert_eq!(format_fdf_string("a(b)\\c\n"), "(a\\(b\\)\\\\c\\n)");
+        assert_eq!(format_fdf_string("é"), "<FEFF00E9>");
+        assert_eq!(format_pdf_name("Choice 1"), "/Choice#201");
+
+        let mut parser = PdfSyntaxParser::new(b"/Choice#201");
+
+        assert_eq!(parser.parse_name(), Some("Choice 1".to_string()));
+    }
+
+    #[test]
+    fn test_parse_json_values() {
+        assert_eq!(
+            parse_json_values(
+                "{\"Name\": \"Jos\\u00e9 \\ud83d\\ude00\", \"Agree\": true, \"Age\": 42, \
+                \"Colors\": [\"Red\"], \"Choice\": null}"
+            ),
+            Some(vec![
+                (
+                    "Name".to_string(),
+                    ExchangedValue::Text("José 😀".to_string())
+                ),
+                ("Agree".to_string(), ExchangedValue::Bool(true)),
+                ("Age".to_string(), ExchangedValue::Text("42".to_string())),
+                (
+                    "Colors".to_string(),
+                    ExchangedValue::List(vec!["Red".to_string()])
+                ),
+                ("Choice".to_string(), ExchangedValue::Null),
+            ])
+        );
+
+        assert_eq!(parse_json_values("[\"Name\"]"), None);
+        assert_eq!(parse_json_values("{\"Name\": {}}"), None);
+        assert_eq!(parse_json_values("{\"Name\": \"x\"} trailing"), None);
+        assert_eq!(
+            format_json_string("say \"hi\"\n\u{1}"),
+            "\"say \\\"hi\\\"\\n\\u0001\""
+        );
+    }
+
+    #[test]
+    fn test_fdf_and_json_round_trip() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("test/form-test.pdf", None)?;
+
+        let form = document.form().unwrap();
+
+        let fdf = form.export_fdf(document.pages());
+
+        let json = form.to_json(document.pages());
+
+        let target = pdfium.load_pdf_from_file("test/form-test.pdf", None)?;
+
+        let target_form = target.form().unwrap();
+
+        let imported = target_form.import_fdf(target.pages(), &fdf)?;
+
+        assert!(imported > 0);
+        assert_eq!(target_form.to_json(target.pages()), json);
+        assert_eq!(target_form.from_json(target.pages(), &json)?, imported);
+
+        assert!(matches!(
+            target_form.import_fdf(target.pages(), b"not fdf"),
+            Err(PdfiumError::InvalidFdfData)
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1313 @@
+//! Defines the [PdfFormFields] collection and the [PdfFormNamedField] struct, a document-wide
+//! view of the fields in a `PdfForm` that groups the widgets of each field by the field's
+//! fully qualified name, together with a typed wrapper around [PdfFormNamedField] for each
+//! field type.
+
+use crate::bindgen::{
+    FPDF_FORMFLAG_CHOICE_EDIT, FPDF_FORMFLAG_CHOICE_MULTI_SELECT, FPDF_FORMFLAG_NOEXPORT,
+    FPDF_FORMFLAG_READONLY, FPDF_FORMFLAG_REQUIRED, FPDF_FORMFLAG_TEXT_MULTILINE,
+    FPDF_FORMFLAG_TEXT_PASSWORD, FPDF_FORMHANDLE, FPDF_PAGE, FPDF_WIDESTRING,
+};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::document::form::scripts::PdfFormFieldScripts;
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotations::PdfPageAnnotationIndex;
+use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
+use crate::pdf::document::page::field::text::FORM_FIELD_FLAG_TEXT_RICH_TEXT;
+use crate::pdf::document::page::field::{PdfFormField, PdfFormFieldCommon, PdfFormFieldType};
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use crate::utils::utf16le::get_pdfium_utf16le_bytes_from_str;
+use std::ops::{Deref, DerefMut};
+use std::os::raw::c_int;
+use std::slice::{Iter, IterMut};
+
+#[cfg(doc)]
+use crate::pdf::document::form::PdfForm;
+
+/// The typed value of a single [PdfFormNamedField].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfFormFieldValue {
+    /// The text entered into a text field.
+    Text(String),
+
+    /// Whether or not a checkbox field is checked.
+    Checkbox(bool),
+
+    /// The export value of the selected radio button in a radio button group, or `None`
+    /// if no radio button in the group is selected.
+    RadioGroup(Option<String>),
+
+    /// The selected or entered value of a combo box field, or `None` if no value is set.
+    ComboBox(Option<String>),
+
+    /// The labels of the selected options of a list box field.
+    ListBox(Vec<String>),
+}
+
+/// The location of a single widget annotation belonging to a [PdfFormNamedField].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PdfFormWidgetLocation {
+    page_index: PdfPageIndex,
+    annotation_index: PdfPageAnnotationIndex,
+}
+
+impl PdfFormWidgetLocation {
+    #[inline]
+    pub(crate) fn new(page_index: PdfPageIndex, annotation_index: PdfPageAnnotationIndex) -> Self {
+        PdfFormWidgetLocation {
+            page_index,
+            annotation_index,
+        }
+    }
+
+    /// Returns the index of the page containing this widget.
+    #[inline]
+    pub fn page_index(&self) -> PdfPageIndex {
+        self.page_index
+    }
+
+    /// Returns the index of this widget in the annotations of its containing page.
+    #[inline]
+    pub fn annotation_index(&self) -> PdfPageAnnotationIndex {
+        self.annotation_index
+    }
+}
+
+// Creates a typed wrapper around a PdfFormNamedField whose field type is known. The wrapper
+// dereferences to the wrapped PdfFormNamedField, so the functions common to all field types
+// remain available.
+macro_rules! create_named_field_wrapper {
+    ($wrapper:ident, $field_type:path) => {
+        #[doc = concat!(
+            "A [PdfFormNamedField] with a field type of [",
+            stringify!($field_type),
+            "]. All the functions of [PdfFormNamedField] are available through dereferencing."
+        )]
+        #[repr(transparent)]
+        pub struct $wrapper<'a>(PdfFormNamedField<'a>);
+
+        impl<'a> Deref for $wrapper<'a> {
+            type Target = PdfFormNamedField<'a>;
+
+            #[inline]
+            fn deref(&self) -> &Self::Target {
+                &self.0
+            }
+        }
+
+        impl<'a> DerefMut for $wrapper<'a> {
+            #[inline]
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
+            }
+        }
+    };
+}
+
+// Creates the functions returning a PdfFormNamedField as one of its typed wrappers. The
+// containing impl block must be for PdfFormNamedField.
+macro_rules! create_named_field_accessors {
+    ($as_ref:ident, $as_mut:ident, $wrapper:ident, $field_type:path) => {
+        #[doc = concat!(
+                            "Returns an immutable reference to this [PdfFormNamedField] as a [",
+                            stringify!($wrapper),
+                            "], or `None` if its field type is not [",
+                            stringify!($field_type),
+                            "]."
+                        )]
+        #[inline]
+        pub fn $as_ref(&self) -> Option<&$wrapper<'a>> {
+            if self.field_type == $field_type {
+                // SAFETY: the wrapper is a transparent wrapper around PdfFormNamedField,
+                // so the two types have the same layout.
+
+                Some(unsafe { &*(self as *const PdfFormNamedField<'a> as *const $wrapper<'a>) })
+            } else {
+                None
+            }
+        }
+
+        #[doc = concat!(
+                            "Returns a mutable reference to this [PdfFormNamedField] as a [",
+                            stringify!($wrapper),
+                            "], or `None` if its field type is not [",
+                            stringify!($field_type),
+                            "]."
+                        )]
+        #[inline]
+        pub fn $as_mut(&mut self) -> Option<&mut $wrapper<'a>> {
+            if self.field_type == $field_type {
+                // SAFETY: as above.
+
+                Some(unsafe { &mut *(self as *mut PdfFormNamedField<'a> as *mut $wrapper<'a>) })
+            } else {
+                None
+            }
+        }
+    };
+}
+
+/// A single logical field in a [PdfForm], identified by its fully qualified name.
+///
+/// A logical field may be displayed by more than one widget annotation; for example,
+/// every radio button in a radio button group is a separate widget of the same field,
+/// and a text field may be repeated on several pages. A [PdfFormNamedField] reads its
+/// value from, and writes its value to, all of its widgets, so callers do not need to
+/// locate individual widget annotations themselves.
+///
+/// Functions specific to a single field type, such as checking a checkbox or selecting
+/// a radio button, are provided by the typed wrapper for that field type, returned by
+/// [PdfFormNamedField::as_checkbox_field_mut()] and its siblings.
+pub struct PdfFormNamedField<'a> {
+    name: String,
+    field_type: PdfFormFieldType,
+    widgets: Vec<PdfFormWidgetLocation>,
+    pages: &'a PdfPages<'a>,
+}
+
+impl<'a> PdfFormNamedField<'a> {
+    #[inline]
+    pub(crate) fn new(name: String, field_type: PdfFormFieldType, pages: &'a PdfPages<'a>) -> Self {
+        PdfFormNamedField {
+            name,
+            field_type,
+            widgets: Vec::new(),
+            pages,
+        }
+    }
+
+    /// Returns the fully qualified name of this [PdfFormNamedField].
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the [PdfFormFieldType] of this [PdfFormNamedField].
+    #[inline]
+    pub fn field_type(&self) -> PdfFormFieldType {
+        self.field_type
+    }
+
+    /// Returns the locations of the widget annotations that display this [PdfFormNamedField],
+    /// in document order.
+    #[inline]
+    pub fn widgets(&self) -> &[PdfFormWidgetLocation] {
+        self.widgets.as_slice()
+    }
+
+    create_named_field_accessors!(
+        as_text_field,
+        as_text_field_mut,
+        PdfFormNamedTextField,
+        PdfFormFieldType::Text
+    );
+
+    create_named_field_accessors!(
+        as_checkbox_field,
+        as_checkbox_field_mut,
+        PdfFormNamedCheckboxField,
+        PdfFormFieldType::Checkbox
+    );
+
+    create_named_field_accessors!(
+        as_radio_group,
+        as_radio_group_mut,
+        PdfFormNamedRadioGroup,
+        PdfFormFieldType::RadioButton
+    );
+
+    create_named_field_accessors!(
+        as_combo_box_field,
+        as_combo_box_field_mut,
+        PdfFormNamedComboBoxField,
+        PdfFormFieldType::ComboBox
+    );
+
+    create_named_field_accessors!(
+        as_list_box_field,
+        as_list_box_field_mut,
+        PdfFormNamedListBoxField,
+        PdfFormFieldType::ListBox
+    );
+
+    create_named_field_accessors!(
+        as_button_field,
+        as_button_field_mut,
+        PdfFormNamedButtonField,
+        PdfFormFieldType::PushButton
+    );
+
+    create_named_field_accessors!(
+        as_signature_field,
+        as_signature_field_mut,
+        PdfFormNamedSignatureField,
+        PdfFormFieldType::Signature
+    );
+
+    /// Returns the current typed value of this [PdfFormNamedField].
+    ///
+    /// Push button, signature, and unknown fields carry no value, so `None` is always
+    /// returned for them.
+    pub fn value(&self) -> Option<PdfFormFieldValue> {
+        match self.field_type {
+            PdfFormFieldType::Text => self
+                .find_map_widget(|field| Ok(Some(field.value_impl().unwrap_or_default())))
+                .ok()
+                .flatten()
+                .map(PdfFormFieldValue::Text),
+            PdfFormFieldType::Checkbox => self
+                .find_map_widget(|field| {
+                    Ok(if field.is_checked_impl()? {
+                        Some(())
+                    } else {
+                        None
+                    })
+                })
+                .ok()
+                .map(|checked| PdfFormFieldValue::Checkbox(checked.is_some())),
+            PdfFormFieldType::RadioButton => self
+                .find_map_widget(|field| {
+                    let field = match field.as_radio_button_field() {
+                        Some(field) => field,
+                        None => return Ok(None),
+                    };
+
+                    Ok(if field.is_checked().unwrap_or(false) {
+                        Some(field.export_value_impl().or_else(|| field.group_value()))
+                    } else {
+                        None
+                    })
+                })
+                .ok()
+                .map(|value| PdfFormFieldValue::RadioGroup(value.flatten())),
+            PdfFormFieldType::ComboBox => self
+                .find_map_widget(|field| {
+                    Ok(Some(
+                        field
+                            .as_combo_box_field()
+                            .and_then(|field| field.value())
+                            .or_else(|| field.value_impl()),
+                    ))
+                })
+                .ok()
+                .flatten()
+                .map(PdfFormFieldValue::ComboBox),
+            PdfFormFieldType::ListBox => self
+                .find_map_widget(|field| {
+                    Ok(field.as_list_box_field().map(|field| {
+                        field
+                            .options()
+                            .iter()
+                            .filter(|option| option.is_set())
+                            .filter_map(|option| option.label().cloned())
+                            .collect::<Vec<_>>()
+                    }))
+                })
+                .ok()
+                .flatten()
+                .map(PdfFormFieldValue::ListBox),
+            PdfFormFieldType::PushButton
+            | PdfFormFieldType::Signature
+            | PdfFormFieldType::Unknown => None,
+        }
+    }
+
+    /// Sets the value of this [PdfFormNamedField] to the given [PdfFormFieldValue],
+    /// updating every widget that displays this field.
+    ///
+    /// The given value must match the type of this field; for example, a
+    /// [PdfFormFieldValue::Text] value can only be applied to a text field. Values of radio
+    /// button groups must be one of this field's export values, and values of combo boxes
+    /// and list boxes must be one of this field's options unless the combo box is editable.
+    /// Since Pdfium cannot write array values, at most one option of a list box can be
+    /// selected. `PdfiumError::InvalidFormFieldValue` is returned if the value cannot
+    /// be applied.
+    ///
+    /// Values are written directly to the field's dictionaries. Checkbox and radio button
+    /// widgets keep their appearance streams, switching to the appearance of their new state;
+    /// the existing appearance streams of other widgets are removed so that conforming PDF
+    /// viewers regenerate them from the new value.
+    pub fn set_value(&mut self, value: &PdfFormFieldValue) -> Result<(), PdfiumError> {
+        match (self.field_type, value) {
+            (PdfFormFieldType::Text, PdfFormFieldValue::Text(text)) => {
+                self.for_each_widget(|field| field.set_value_impl(text))
+            }
+            (PdfFormFieldType::Checkbox, PdfFormFieldValue::Checkbox(is_checked)) => self
+                .for_each_widget(|field| {
+                    let state = if *is_checked {
+                        field
+                            .export_value_impl()
+                            .unwrap_or_else(|| "Yes".to_string())
+                    } else {
+                        "Off".to_string()
+                    };
+
+                    field.set_checked_state_impl(state.as_str(), state.as_str())
+                }),
+            (PdfFormFieldType::RadioButton, PdfFormFieldValue::RadioGroup(Some(export_value))) => {
+                if !self.export_values().contains(export_value) {
+                    return Err(PdfiumError::InvalidFormFieldValue);
+                }
+
+                self.for_each_widget(|field| {
+                    let state = match field.export_value_impl() {
+                        Some(on_state_name) if on_state_name == *export_value => on_state_name,
+                        _ => "Off".to_string(),
+                    };
+
+                    field.set_checked_state_impl(export_value, state.as_str())
+                })
+            }
+            (PdfFormFieldType::RadioButton, PdfFormFieldValue::RadioGroup(None)) => {
+                self.for_each_widget(|field| field.set_checked_state_impl("Off", "Off"))
+            }
+            (PdfFormFieldType::ComboBox, PdfFormFieldValue::ComboBox(value)) => {
+                let value = value.as_deref().unwrap_or("");
+
+                if !value.is_empty()
+                    && !self.is_editable()
+                    && !self.options().iter().any(|option| option == value)
+                {
+                    return Err(PdfiumError::InvalidFormFieldValue);
+                }
+
+                self.for_each_widget(|field| field.set_value_impl(value))
+            }
+            (PdfFormFieldType::ListBox, PdfFormFieldValue::ListBox(values)) => {
+                if values.len() > 1 {
+                    return Err(PdfiumError::InvalidFormFieldValue);
+                }
+
+                let value = values.first().map(|value| value.as_str()).unwrap_or("");
+
+                if !value.is_empty() && !self.options().iter().any(|option| option == value) {
+                    return Err(PdfiumError::InvalidFormFieldValue);
+                }
+
+                self.for_each_widget(|field| field.set_value_impl(value))
+            }
+            _ => Err(PdfiumError::InvalidFormFieldValue),
+        }
+    }
+
+    /// Replaces the normal appearance stream of every widget of this [PdfFormNamedField] with
+    /// one generated by Pdfium from the field's current value. See
+    /// [PdfFormFieldCommon::regenerate_appearance()] for details.
+    #[inline]
+    pub fn regenerate_appearances(&mut self) -> Result<(), PdfiumError> {
+        self.for_each_widget(|field| field.regenerate_appearance())
+    }
+
+    /// Returns the labels of the options of this [PdfFormNamedField], if it is a combo box
+    /// or list box field. An empty list is returned for all other field types.
+    pub(crate) fn options(&self) -> Vec<String> {
+        self.find_map_widget(|field| {
+            let options = match field {
+                PdfFormField::ComboBox(field) => field.options(),
+                PdfFormField::ListBox(field) => field.options(),
+                _ => return Ok(None),
+            };
+
+            Ok(Some(
+                options
+                    .iter()
+                    .filter_map(|option| option.label().cloned())
+                    .collect::<Vec<_>>(),
+            ))
+        })
+        .ok()
+        .flatten()
+        .unwrap_or_default()
+    }
+
+    /// Returns the export values of the widgets of this [PdfFormNamedField], if it is a
+    /// checkbox or radio button field, in document order and without duplicates. Each export
+    /// value is the value taken by the field when the corresponding widget is checked.
+    /// An empty list is returned for all other field types.
+    pub(crate) fn export_values(&self) -> Vec<String> {
+        let mut result: Vec<String> = Vec::new();
+
+        if self.field_type == PdfFormFieldType::Checkbox
+            || self.field_type == PdfFormFieldType::RadioButton
+        {
+            let _ = self.for_each_widget(|field| {
+                if let Some(export_value) = field.export_value_impl() {
+                    if !result.contains(&export_value) {
+                        result.push(export_value);
+                    }
+                }
+
+                Ok(())
+            });
+        }
+
+        result
+    }
+
+    /// Returns the JavaScript actions run by a viewer when the value of this
+    /// [PdfFormNamedField] is typed, formatted, validated, or calculated. These actions are
+    /// shared by every widget of the field.
+    pub fn scripts(&self) -> PdfFormFieldScripts {
+        self.find_map_widget(|field| Ok(Some(field.scripts())))
+            .ok()
+            .flatten()
+            .unwrap_or_default()
+    }
+
+    /// Returns the name of the appearance state each widget of this [PdfFormNamedField] takes
+    /// when it is checked, if it is a checkbox or radio button field, in the same order as
+    /// [PdfFormNamedField::widgets()]. This is also the value taken by the field when the
+    /// corresponding widget is checked. `None` is returned for widgets of all other field types,
+    /// and for widgets that define no on state.
+    pub(crate) fn on_state_names(&self) -> Vec<Option<String>> {
+        let mut result = Vec::with_capacity(self.widgets.len());
+
+        let _ = self.for_each_widget(|field| {
+            result.push(match field.field_type() {
+                PdfFormFieldType::Checkbox | PdfFormFieldType::RadioButton => {
+                    field.export_value_impl()
+                }
+                _ => None,
+            });
+
+            Ok(())
+        });
+
+        result
+    }
+
+    /// Returns `true` if the value of this [PdfFormNamedField] cannot be changed by the user.
+    #[inline]
+    pub fn is_read_only(&self) -> bool {
+        self.has_flag(FPDF_FORMFLAG_READONLY)
+    }
+
+    /// Returns `true` if this [PdfFormNamedField] must have a value when the form is submitted.
+    #[inline]
+    pub fn is_required(&self) -> bool {
+        self.has_flag(FPDF_FORMFLAG_REQUIRED)
+    }
+
+    /// Returns `true` if the value of this [PdfFormNamedField] is included when the form
+    /// is submitted.
+    #[inline]
+    pub fn is_exported(&self) -> bool {
+        !self.has_flag(FPDF_FORMFLAG_NOEXPORT)
+    }
+
+    /// Returns `true` if this [PdfFormNamedField] is a combo box field that accepts values
+    /// other than its options.
+    #[inline]
+    pub(crate) fn is_editable(&self) -> bool {
+        self.field_type == PdfFormFieldType::ComboBox && self.has_flag(FPDF_FORMFLAG_CHOICE_EDIT)
+    }
+
+    /// Returns `true` if this [PdfFormNamedField] is a list box field that allows more than
+    /// one option to be selected.
+    #[inline]
+    pub(crate) fn is_multi_select(&self) -> bool {
+        self.field_type == PdfFormFieldType::ListBox
+            && self.has_flag(FPDF_FORMFLAG_CHOICE_MULTI_SELECT)
+    }
+
+    /// Fills this [PdfFormNamedField] with the given [PdfFormFieldValue] by driving Pdfium's
+    /// form filling event functions against the given form handle, as if a user had entered
+    /// the value, so that Pdfium updates the field's value and regenerates the appearance
+    /// streams of all its widgets. Falls back to [PdfFormNamedField::set_value()] if the
+    /// value could not be entered this way; for example, read-only fields cannot be focused.
+    pub(crate) fn fill(
+        &mut self,
+        form_handle: FPDF_FORMHANDLE,
+        value: &PdfFormFieldValue,
+    ) -> Result<(), PdfiumError> {
+        self.check_value(value)?;
+
+        if self.fill_with_events(form_handle, value) && self.has_value(value) {
+            Ok(())
+        } else {
+            self.set_value(value)
+        }
+    }
+
+    /// Selects or deselects the option at the given index of this [PdfFormNamedField], which
+    /// must be a combo box or list box field, as described for
+    /// [PdfFormNamedListBoxField::set_option_selected()].
+    fn set_option_selected(&mut self, index: usize, is_selected: bool) -> Result<(), PdfiumError> {
+        let options = self.options();
+
+        let label = options
+            .get(index)
+            .ok_or(PdfiumError::FormFieldOptionIndexOutOfBounds)?;
+
+        let value = match (self.field_type, self.value()) {
+            (PdfFormFieldType::ComboBox, Some(PdfFormFieldValue::ComboBox(current))) => {
+                PdfFormFieldValue::ComboBox(match (is_selected, current) {
+                    (true, _) => Some(label.clone()),
+                    (false, Some(current)) if current != *label => Some(current),
+                    (false, _) => None,
+                })
+            }
+            (PdfFormFieldType::ListBox, Some(PdfFormFieldValue::ListBox(mut current))) => {
+                current.retain(|value| value != label);
+
+                if is_selected {
+                    if !self.is_multi_select() {
+                        current.clear();
+                    }
+
+                    current.push(label.clone());
+                }
+
+                PdfFormFieldValue::ListBox(current)
+            }
+            _ => return Err(PdfiumError::InvalidFormFieldValue),
+        };
+
+        self.fill_with_own_form(&value)
+    }
+
+    /// Fills this [PdfFormNamedField] with the given [PdfFormFieldValue] using the form
+    /// handle of the form containing its widgets.
+    fn fill_with_own_form(&mut self, value: &PdfFormFieldValue) -> Result<(), PdfiumError> {
+        self.check_value(value)?;
+
+        let form_handle = self
+            .find_map_widget(|field| Ok(Some(*field.form_handle())))?
+            .ok_or(PdfiumError::InvalidFormFieldValue)?;
+
+        self.fill(form_handle, value)
+    }
+
+    /// Returns an error if the given [PdfFormFieldValue] cannot be entered into
+    /// this [PdfFormNamedField].
+    pub(crate) fn check_value(&self, value: &PdfFormFieldValue) -> Result<(), PdfiumError> {
+        let is_valid = match (self.field_type, value) {
+            (PdfFormFieldType::Text, PdfFormFieldValue::Text(_))
+            | (PdfFormFieldType::Checkbox, PdfFormFieldValue::Checkbox(_))
+            | (PdfFormFieldType::RadioButton, PdfFormFieldValue::RadioGroup(None))
+            | (PdfFormFieldType::ComboBox, PdfFormFieldValue::ComboBox(None)) => true,
+            (PdfFormFieldType::RadioButton, PdfFormFieldValue::RadioGroup(Some(export_value))) => {
+                self.export_values().contains(export_value)
+            }
+            (PdfFormFieldType::ComboBox, PdfFormFieldValue::ComboBox(Some(value))) => {
+                self.is_editable() || self.options().contains(value)
+            }
+            (PdfFormFieldType::ListBox, PdfFormFieldValue::ListBox(values)) => {
+                let options = self.options();
+
+                (values.len() <= 1 || self.is_multi_select())
+                    && values.iter().all(|value| options.contains(value))
+            }
+            _ => false,
+        };
+
+        if is_valid {
+            Ok(())
+        } else {
+            Err(PdfiumError::InvalidFormFieldValue)
+        }
+    }
+
+    /// Returns `true` if the current value of this [PdfFormNamedField] matches the
+    /// given [PdfFormFieldValue].
+    fn has_value(&self, value: &PdfFormFieldValue) -> bool {
+        match (self.value(), value) {
+            (Some(PdfFormFieldValue::ListBox(current)), PdfFormFieldValue::ListBox(expected)) => {
+                current.len() == expected.len()
+                    && expected.iter().all(|value| current.contains(value))
+            }
+            (current, expected) => current.as_ref() == Some(expected),
+        }
+    }
+
+    /// Enters the given [PdfFormFieldValue] into this [PdfFormNamedField] using Pdfium's
+    /// form filling event functions, returning `true` if the value was entered.
+    fn fill_with_events(&self, form_handle: FPDF_FORMHANDLE, value: &PdfFormFieldValue) -> bool {
+        let first_widget = match self.widgets.first() {
+            Some(location) => *location,
+            None => return false,
+        };
+
+        match value {
+            PdfFormFieldValue::Text(text) => {
+                self.with_focused_widget(first_widget, form_handle, |bindings, page| {
+                    replace_text(bindings, form_handle, page, text)
+                })
+            }
+            PdfFormFieldValue::ComboBox(Some(value)) => {
+                match self.options().iter().position(|option| option == value) {
+                    Some(index) => {
+                        self.with_focused_widget(first_widget, form_handle, |bindings, page| {
+                            bindings.is_true(bindings.FORM_SetIndexSelected(
+                                form_handle,
+                                page,
+                                index as c_int,
+                                bindings.TRUE(),
+                            ))
+                        })
+                    }
+                    None => {
+                        self.with_focused_widget(first_widget, form_handle, |bindings, page| {
+                            replace_text(bindings, form_handle, page, value)
+                        })
+                    }
+                }
+            }
+            PdfFormFieldValue::ListBox(values) => {
+                let options = self.options();
+
+                self.with_focused_widget(first_widget, form_handle, |bindings, page| {
+                    // Deselect options before selecting new ones, so that selecting an option
+                    // in a single selection list box is not undone by a later deselection.
+
+                    let mut result = true;
+
+                    for (index, option) in options.iter().enumerate() {
+                        if !values.contains(option) {
+                            result &= bindings.is_true(bindings.FORM_SetIndexSelected(
+                                form_handle,
+                                page,
+                                index as c_int,
+                                bindings.FALSE(),
+                            ));
+                        }
+                    }
+
+                    for (index, option) in options.iter().enumerate() {
+                        if values.contains(option) {
+                            result &= bindings.is_true(bindings.FORM_SetIndexSelected(
+                                form_handle,
+                                page,
+                                index as c_int,
+                                bindings.TRUE(),
+                            ));
+                        }
+                    }
+
+                    result
+                })
+            }
+            PdfFormFieldValue::Checkbox(is_checked) => {
+                if self.value() == Some(PdfFormFieldValue::Checkbox(*is_checked)) {
+                    return true;
+                }
+
+                // Pressing the space bar on a focused checkbox toggles it.
+
+                self.with_focused_widget(first_widget, form_handle, |bindings, page| {
+                    press_space(bindings, form_handle, page)
+                })
+            }
+            PdfFormFieldValue::RadioGroup(Some(export_value)) => {
+                if self.value() == Some(PdfFormFieldValue::RadioGroup(Some(export_value.clone()))) {
+                    return true;
+                }
+
+                // Pressing the space bar on a focused radio button selects it.
+
+                let widget = self
+                    .widgets
+                    .iter()
+                    .zip(self.on_state_names())
+                    .find(|(_, value)| value.as_ref() == Some(export_value))
+                    .map(|(location, _)| *location);
+
+                match widget {
+                    Some(location) => {
+                        self.with_focused_widget(location, form_handle, |bindings, page| {
+                            press_space(bindings, form_handle, page)
+                        })
+                    }
+                    None => false,
+                }
+            }
+            PdfFormFieldValue::ComboBox(None) | PdfFormFieldValue::RadioGroup(None) => {
+                // A user cannot clear a selection, so there is no event we can drive.
+
+                false
+            }
+        }
+    }
+
+    /// Focuses the widget at the given location, calls the given function with the page
+    /// containing the widget, then removes focus from the widget, committing any changes
+    /// made to it. Returns `false` if the widget could not be focused.
+    fn with_focused_widget(
+        &self,
+        location: PdfFormWidgetLocation,
+        form_handle: FPDF_FORMHANDLE,
+        f: impl FnOnce(&dyn PdfiumLibraryBindings, FPDF_PAGE) -> bool,
+    ) -> bool {
+        let page = match self.pages.get(location.page_index) {
+            Ok(page) => page,
+            Err(_) => return false,
+        };
+
+        let annotation = match page.annotations().get(location.annotation_index) {
+            Ok(annotation) => annotation,
+            Err(_) => return false,
+        };
+
+        let bindings = page.bindings();
+
+        let page_handle = page.page_handle();
+
+        bindings.FORM_OnAfterLoadPage(page_handle, form_handle);
+
+        let result = bindings
+            .is_true(bindings.FORM_SetFocusedAnnot(form_handle, annotation.handle()))
+            && f(bindings, page_handle);
+
+        // Killing focus commits the entered value to the field and regenerates the
+        // appearance streams of all the field's widgets.
+
+        bindings.FORM_ForceToKillFocus(form_handle);
+        bindings.FORM_OnBeforeClosePage(page_handle, form_handle);
+
+        result
+    }
+
+    #[inline]
+    pub(crate) fn add_widget(&mut self, location: PdfFormWidgetLocation) {
+        self.widgets.push(location);
+    }
+
+    /// Returns `true` if the given field flag is set for this [PdfFormNamedField].
+    fn has_flag(&self, flag: u32) -> bool {
+        self.find_map_widget(|field| {
+            Ok(Some(field.bindings().FPDFAnnot_GetFormFieldFlags(
+                *field.form_handle(),
+                *field.annotation_handle(),
+            )))
+        })
+        .ok()
+        .flatten()
+        .map(|flags: c_int| flags as u32 & flag != 0)
+        .unwrap_or(false)
+    }
+
+    /// Calls the given function with each widget of this [PdfFormNamedField] in turn,
+    /// stopping at the first widget for which the function returns a value.
+    fn find_map_widget<T>(
+        &self,
+        mut f: impl FnMut(&mut PdfFormField<'a>) -> Result<Option<T>, PdfiumError>,
+    ) -> Result<Option<T>, PdfiumError> {
+        for location in self.widgets.iter() {
+            let page = self.pages.get(location.page_index)?;
+
+            let mut annotation = page.annotations().get(location.annotation_index)?;
+
+            if let Some(field) = annotation.as_form_field_mut() {
+                if let Some(result) = f(field)? {
+                    return Ok(Some(result));
+                }
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Calls the given function with every widget of this [PdfFormNamedField].
+    #[inline]
+    fn for_each_widget(
+        &self,
+        mut f: impl FnMut(&mut PdfFormField<'a>) -> Result<(), PdfiumError>,
+    ) -> Result<(), PdfiumError> {
+        self.find_map_widget(|field| f(field).map(|_| None::<()>))
+            .map(|_| ())
+    }
+}
+
+create_named_field_wrapper!(PdfFormNamedTextField, PdfFormFieldType::Text);
+
+impl<'a> PdfFormNamedTextField<'a> {
+    /// Returns the text entered into this [PdfFormNamedTextField].
+    #[inline]
+    pub fn text(&self) -> String {
+        match self.value() {
+            Some(PdfFormFieldValue::Text(text)) => text,
+            _ => String::new(),
+        }
+    }
+
+    /// Replaces the text of this [PdfFormNamedTextField], updating every widget that displays
+    /// it. The text is entered using Pdfium's form filling event functions, as if a user had
+    /// typed it, so that Pdfium writes the field's value and regenerates the appearance of
+    /// every widget exactly as a viewer would. If the text cannot be entered this way, for
+    /// instance because the field is read-only, the value is written as described for
+    /// [PdfFormNamedField::set_value()].
+    #[inline]
+    pub fn set_text(&mut self, text: &str) -> Result<(), PdfiumError> {
+        self.0
+            .fill_with_own_form(&PdfFormFieldValue::Text(text.to_string()))
+    }
+
+    /// Returns `true` if this [PdfFormNamedTextField] accepts multiple lines of text.
+    #[inline]
+    pub fn is_multiline(&self) -> bool {
+        self.has_flag(FPDF_FORMFLAG_TEXT_MULTILINE)
+    }
+
+    /// Returns `true` if the value of this [PdfFormNamedTextField] should be obscured
+    /// when displayed.
+    #[inline]
+    pub fn is_password(&self) -> bool {
+        self.has_flag(FPDF_FORMFLAG_TEXT_PASSWORD)
+    }
+
+    /// Returns `true` if the value of this [PdfFormNamedTextField] is formatted as rich text.
+    #[inline]
+    pub fn is_rich_text(&self) -> bool {
+        self.has_flag(FORM_FIELD_FLAG_TEXT_RICH_TEXT)
+    }
+}
+
+create_named_field_wrapper!(PdfFormNamedCheckboxField, PdfFormFieldType::Checkbox);
+
+impl<'a> PdfFormNamedCheckboxField<'a> {
+    /// Returns `true` if this [PdfFormNamedCheckboxField] is checked.
+    #[inline]
+    pub fn is_checked(&self) -> bool {
+        self.value() == Some(PdfFormFieldValue::Checkbox(true))
+    }
+
+    /// Checks this [PdfFormNamedCheckboxField], updating every widget that displays it.
+    ///
+    /// The checkbox is toggled using Pdfium's form filling event functions, as if a user had
+    /// clicked it, so that Pdfium writes the field's value and the appearance state of every
+    /// widget exactly as a viewer would. If the checkbox cannot be toggled this way, for
+    /// instance because it is read-only, the value is written as described for
+    /// [PdfFormNamedField::set_value()].
+    #[inline]
+    pub fn check(&mut self) -> Result<(), PdfiumError> {
+        self.0
+            .fill_with_own_form(&PdfFormFieldValue::Checkbox(true))
+    }
+
+    /// Clears this [PdfFormNamedCheckboxField], updating every widget that displays it.
+    /// The checkbox is cleared in the same way as [PdfFormNamedCheckboxField::check()].
+    #[inline]
+    pub fn uncheck(&mut self) -> Result<(), PdfiumError> {
+        self.0
+            .fill_with_own_form(&PdfFormFieldValue::Checkbox(false))
+    }
+
+    /// Returns the export values of the widgets of this [PdfFormNamedCheckboxField], in
+    /// document order and without duplicates. Each export value is the value taken by the
+    /// field when the corresponding widget is checked.
+    #[inline]
+    pub fn export_values(&self) -> Vec<String> {
+        self.0.export_values()
+    }
+
+    /// Returns the name of the appearance state each widget of this
+    /// [PdfFormNamedCheckboxField] takes when it is checked, in the same order as
+    /// [PdfFormNamedField::widgets()], or `None` for widgets that define no on state.
+    #[inline]
+    pub fn on_state_names(&self) -> Vec<Option<String>> {
+        self.0.on_state_names()
+    }
+}
+
+create_named_field_wrapper!(PdfFormNamedRadioGroup, PdfFormFieldType::RadioButton);
+
+impl<'a> PdfFormNamedRadioGroup<'a> {
+    /// Returns the export value of the selected radio button in this [PdfFormNamedRadioGroup],
+    /// or `None` if no radio button in the group is selected.
+    #[inline]
+    pub fn selected(&self) -> Option<String> {
+        match self.value() {
+            Some(PdfFormFieldValue::RadioGroup(selected)) => selected,
+            _ => None,
+        }
+    }
+
+    /// Selects the radio button with the given export value in this [PdfFormNamedRadioGroup],
+    /// clearing every other radio button in the group. `PdfiumError::InvalidFormFieldValue`
+    /// is returned if the given export value is not one of the group's
+    /// [PdfFormNamedRadioGroup::export_values()]. The radio button is selected in the same
+    /// way as [PdfFormNamedCheckboxField::check()].
+    #[inline]
+    pub fn select(&mut self, export_value: &str) -> Result<(), PdfiumError> {
+        self.0
+            .fill_with_own_form(&PdfFormFieldValue::RadioGroup(Some(
+                export_value.to_string(),
+            )))
+    }
+
+    /// Returns the export values of the radio buttons in this [PdfFormNamedRadioGroup], in
+    /// document order and without duplicates. Each export value is the value taken by the
+    /// group when the corresponding radio button is selected.
+    #[inline]
+    pub fn export_values(&self) -> Vec<String> {
+        self.0.export_values()
+    }
+
+    /// Returns the name of the appearance state each radio button in this
+    /// [PdfFormNamedRadioGroup] takes when it is selected, in the same order as
+    /// [PdfFormNamedField::widgets()], or `None` for radio buttons that define no on state.
+    #[inline]
+    pub fn on_state_names(&self) -> Vec<Option<String>> {
+        self.0.on_state_names()
+    }
+}
+
+create_named_field_wrapper!(PdfFormNamedComboBoxField, PdfFormFieldType::ComboBox);
+
+impl<'a> PdfFormNamedComboBoxField<'a> {
+    /// Returns the selected or entered value of this [PdfFormNamedComboBoxField], or `None`
+    /// if no value is set.
+    #[inline]
+    pub fn selected(&self) -> Option<String> {
+        match self.value() {
+            Some(PdfFormFieldValue::ComboBox(selected)) => selected,
+            _ => None,
+        }
+    }
+
+    /// Returns the labels of the options of this [PdfFormNamedComboBoxField].
+    #[inline]
+    pub fn options(&self) -> Vec<String> {
+        self.0.options()
+    }
+
+    /// Returns `true` if this [PdfFormNamedComboBoxField] accepts values other than
+    /// its options.
+    #[inline]
+    pub fn is_editable(&self) -> bool {
+        self.0.is_editable()
+    }
+
+    /// Selects or deselects the option at the given index of this [PdfFormNamedComboBoxField].
+    /// Selecting an option deselects any other option. Options are indexed in the same order
+    /// as [PdfFormNamedComboBoxField::options()].
+    ///
+    /// `PdfiumError::FormFieldOptionIndexOutOfBounds` is returned if the index does not refer
+    /// to an option. The option is selected in the same way as
+    /// [PdfFormNamedCheckboxField::check()].
+    #[inline]
+    pub fn set_option_selected(
+        &mut self,
+        index: usize,
+        is_selected: bool,
+    ) -> Result<(), PdfiumError> {
+        self.0.set_option_selected(index, is_selected)
+    }
+}
+
+create_named_field_wrapper!(PdfFormNamedListBoxField, PdfFormFieldType::ListBox);
+
+impl<'a> PdfFormNamedListBoxField<'a> {
+    /// Returns the labels of the selected options of this [PdfFormNamedListBoxField].
+    #[inline]
+    pub fn selected(&self) -> Vec<String> {
+        match self.value() {
+            Some(PdfFormFieldValue::ListBox(selected)) => selected,
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns the labels of the options of this [PdfFormNamedListBoxField].
+    #[inline]
+    pub fn options(&self) -> Vec<String> {
+        self.0.options()
+    }
+
+    /// Returns `true` if this [PdfFormNamedListBoxField] allows more than one option
+    /// to be selected.
+    #[inline]
+    pub fn is_multi_select(&self) -> bool {
+        self.0.is_multi_select()
+    }
+
+    /// Selects or deselects the option at the given index of this [PdfFormNamedListBoxField],
+    /// leaving the selection of other options unchanged if the list box allows more than one
+    /// option to be selected. Otherwise, selecting an option deselects any other option.
+    /// Options are indexed in the same order as [PdfFormNamedListBoxField::options()].
+    ///
+    /// `PdfiumError::FormFieldOptionIndexOutOfBounds` is returned if the index does not refer
+    /// to an option. The option is selected in the same way as
+    /// [PdfFormNamedCheckboxField::check()].
+    #[inline]
+    pub fn set_option_selected(
+        &mut self,
+        index: usize,
+        is_selected: bool,
+    ) -> Result<(), PdfiumError> {
+        self.0.set_option_selected(index, is_selected)
+    }
+}
+
+create_named_field_wrapper!(PdfFormNamedButtonField, PdfFormFieldType::PushButton);
+
+create_named_field_wrapper!(PdfFormNamedSignatureField, PdfFormFieldType::Signature);
+
+/// Replaces the text of the focused text field or editable combo box with the given text.
+fn replace_text(
+    bindings: &dyn PdfiumLibraryBindings,
+    form_handle: FPDF_FORMHANDLE,
+    page: FPDF_PAGE,
+    text: &str,
+) -> bool {
+    // FORM_SelectAllText() fails if the field is empty, in which case there is nothing
+    // to select and the replacement text is simply inserted.
+
+    bindings.FORM_SelectAllText(form_handle, page);
+
+    let text = get_pdfium_utf16le_bytes_from_str(text);
+
+    bindings.FORM_ReplaceSelection(form_handle, page, text.as_ptr() as FPDF_WIDESTRING);
+
+    true
+}
+
+/// Sends a press of the space bar to the focused widget.
+#[inline]
+fn press_space(
+    bindings: &dyn PdfiumLibraryBindings,
+    form_handle: FPDF_FORMHANDLE,
+    page: FPDF_PAGE,
+) -> bool {
+    bindings.is_true(bindings.FORM_OnChar(form_handle, page, ' ' as c_int, 0))
+}
+
+/// The collection of logical fields in a [PdfForm], each identified by its fully
+/// qualified name, in the document order of each field's first widget.
+pub struct PdfFormFields<'a> {
+    fields: Vec<PdfFormNamedField<'a>>,
+}
+
+impl<'a> PdfFormFields<'a> {
+    /// Groups the form field widgets on every page of the given [PdfPages] collection
+    /// by fully qualified field name.
+    pub(crate) fn from_pages(pages: &'a PdfPages<'a>) -> Self {
+        let mut fields: Vec<PdfFormNamedField<'a>> = Vec::new();
+
+        for (page_index, page) in pages.iter().enumerate() {
+            for (annotation_index, annotation) in page.annotations().iter().enumerate() {
+                if let Some(field) = annotation.as_form_field() {
+                    let name = field.name().unwrap_or_default();
+
+                    let location = PdfFormWidgetLocation::new(
+                        page_index as PdfPageIndex,
+                        annotation_index as PdfPageAnnotationIndex,
+                    );
+
+                    match fields.iter_mut().find(|existing| existing.name == name) {
+                        Some(existing) => existing.add_widget(location),
+                        None => {
+                            let mut named = PdfFormNamedField::new(name, field.field_type(), pages);
+
+                            named.add_widget(location);
+                            fields.push(named);
+                        }
+                    }
+                }
+            }
+        }
+
+        PdfFormFields { fields }
+    }
+
+    /// Returns the number of logical fields in this [PdfFormFields] collection.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// Returns `true` if this [PdfFormFields] collection is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// Returns the field with the given fully qualified name, if any.
+    #[inline]
+    pub fn get(&self, name: &str) -> Option<&PdfFormNamedField<'a>> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+
+    /// Returns a mutable reference to the field with the given fully qualified name, if any.
+    #[inline]
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut PdfFormNamedField<'a>> {
+        self.fields.iter_mut().find(|field| field.name == name)
+    }
+
+    /// Returns an iterator over all the fields in this [PdfFormFields] collection.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, PdfFormNamedField<'a>> {
+        self.fields.iter()
+    }
+
+    /// Returns a mutable iterator over all the fields in this [PdfFormFields] collection.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, PdfFormNamedField<'a>> {
+        self.fields.iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_form_fields() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("test/form-test.pdf", None)?;
+
+        let form = document.form().unwrap();
+
+        let mut fields = form.fields(document.pages());
+
+        assert!(!fields.is_empty());
+
+        let text_field_name = fields
+            .iter()
+            .find(|field| field.field_type() == PdfFormFieldType::Text)
+            .map(|field| field.name().to_string())
+            .unwrap();
+
+        let field = fields.get_mut(&text_field_name).unwrap();
+
+        field.set_value(&PdfFormFieldValue::Text("Hello".to_string()))?;
+
+        assert_eq!(
+            field.value(),
+            Some(PdfFormFieldValue::Text("Hello".to_string()))
+        );
+
+        assert!(matches!(
+            field.set_value(&PdfFormFieldValue::Checkbox(true)),
+            Err(PdfiumError::InvalidFormFieldValue)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_check_and_select() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        document.create_form_fields(&[
+            PdfFormFieldBuilder::checkbox("Agree")
+                .bounds(PdfRect::new_from_values(670.0, 50.0, 682.0, 62.0))
+                .export_value("Agreed"),
+            PdfFormFieldBuilder::radio_group("Size")
+                .add_radio_button("Small", PdfRect::new_from_values(640.0, 50.0, 652.0, 62.0))
+                .add_radio_button("Large", PdfRect::new_from_values(640.0, 80.0, 652.0, 92.0)),
+        ])?;
+
+        let form = document.form().unwrap();
+
+        let mut fields = form.fields(document.pages());
+
+        let agree = fields.get_mut("Agree").unwrap();
+
+        assert!(agree.as_radio_group().is_none());
+
+        let agree = agree.as_checkbox_field_mut().unwrap();
+
+        assert_eq!(agree.on_state_names(), vec![Some("Agreed".to_string())]);
+
+        agree.check()?;
+
+        assert!(agree.is_checked());
+        assert_eq!(agree.value(), Some(PdfFormFieldValue::Checkbox(true)));
+
+        agree.uncheck()?;
+
+        assert!(!agree.is_checked());
+
+        let size = fields.get_mut("Size").unwrap();
+
+        assert!(size.as_checkbox_field().is_none());
+
+        let size = size.as_radio_group_mut().unwrap();
+
+        assert_eq!(
+            size.on_state_names(),
+            vec![Some("Small".to_string()), Some("Large".to_string())]
+        );
+
+        size.select("Large")?;
+
+        assert_eq!(
+            size.value(),
+            Some(PdfFormFieldValue::RadioGroup(Some("Large".to_string())))
+        );
+
+        size.select("Small")?;
+
+        assert_eq!(size.selected(), Some("Small".to_string()));
+
+        assert!(matches!(
+            size.select("Medium"),
+            Err(PdfiumError::InvalidFormFieldValue)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_fill_from_persists_after_save() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("test/form-test.pdf", None)?;
+
+        let form = document.form().unwrap();
+
+        let text_field_name = form
+            .fields(document.pages())
+            .iter()
+            .find(|field| field.field_type() == PdfFormFieldType::Text && !field.is_read_only())
+            .map(|field| field.name().to_string())
+            .unwrap();
+
+        let mut values = HashMap::new();
+
+        values.insert(
+            text_field_name.clone(),
+            PdfFormFieldValue::Text("Filled".to_string()),
+        );
+
+        form.fill_from(document.pages(), values)?;
+
+        let bytes = document.save_to_bytes()?;
+
+        let document = pdfium.load_pdf_from_byte_vec(bytes, None)?;
+
+        let form = document.form().unwrap();
+
+        assert_eq!(
+            form.fields(document.pages())
+                .get(&text_field_name)
+                .unwrap()
+                .value(),
+            Some(PdfFormFieldValue::Text("Filled".to_string()))
+        );
+
+        let mut unknown = HashMap::new();
+
+        unknown.insert(
+            "No such field".to_string(),
+            PdfFormFieldValue::Text("Filled".to_string()),
+        );
+
+        assert!(matches!(
+            form.fill_from(document.pages(), unknown),
+            Err(PdfiumError::FormFieldNotFound)
+        ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,209 @@
+//! Flattens selected form fields in a `PdfDocument` into the content of the pages that
+//! display them, via the `PdfDocument::flatten_form()` and `PdfDocument::flatten_form_fields()`
+//! functions.
+
+use crate::bindgen::{
+    FLATTEN_NOTHINGTODO, FLATTEN_SUCCESS, FLAT_NORMALDISPLAY, FPDF_FORMHANDLE, FPDF_PAGEOBJECT,
+};
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::form::fields::PdfFormWidgetLocation;
+use crate::pdf::document::page::annotations::PdfPageAnnotationIndex;
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use crate::pdf::document::PdfDocument;
+use crate::pdfium::Pdfium;
+use std::os::raw::c_int;
+
+/// Draws the current normal appearance of each of the given widgets into the content of the
+/// page displaying it, then removes the widgets from their pages.
+pub(crate) fn flatten_widgets(
+    document: &PdfDocument,
+    form_handle: FPDF_FORMHANDLE,
+    widgets: &[PdfFormWidgetLocation],
+) -> Result<(), PdfiumError> {
+    let bindings = document.bindings();
+
+    let mut page_indices = widgets
+        .iter()
+        .map(|widget| widget.page_index())
+        .collect::<Vec<_>>();
+
+    page_indices.sort_unstable();
+    page_indices.dedup();
+
+    for page_index in page_indices {
+        let mut annotation_indices = widgets
+            .iter()
+            .filter(|widget| widget.page_index() == page_index)
+            .map(|widget| widget.annotation_index())
+            .collect::<Vec<_>>();
+
+        annotation_indices.sort_unstable();
+        annotation_indices.dedup();
+
+        let mut page = document.pages().get(page_index)?;
+
+        let page_handle = page.page_handle();
+
+        // Pdfium generates appearance streams for any widgets on a page that lack them, for
+        // example because their values were set without regenerating their appearances,
+        // when the page is loaded into the form fill environment.
+
+        bindings.FORM_OnAfterLoadPage(page_handle, form_handle);
+        bindings.FORM_OnBeforeClosePage(page_handle, form_handle);
+
+        if let Some(object) = flattened_widgets(document, page_index, &annotation_indices)? {
+            bindings.FPDFPage_InsertObject(page_handle, object);
+        }
+
+        // Remove widgets in reverse order, so that the indices of the remaining widgets
+        // are not affected.
+
+        for annotation_index in annotation_indices.iter().rev() {
+            if !bindings
+                .is_true(bindings.FPDFPage_RemoveAnnot(page_handle, *annotation_index as c_int))
+            {
+                return Err(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ));
+            }
+        }
+
+        page.regenerate_content()?;
+    }
+
+    Ok(())
+}
+
+/// Returns a new form object in the given document that draws the normal appearances of the
+/// given widgets on the page at the given index, or `None` if none of the widgets are visible.
+fn flattened_widgets(
+    document: &PdfDocument,
+    page_index: PdfPageIndex,
+    annotation_indices: &[PdfPageAnnotationIndex],
+) -> Result<Option<FPDF_PAGEOBJECT>, PdfiumError> {
+    // Pdfium can only flatten every annotation on a page at once, and only into the content
+    // of that page. We therefore copy the page into a scratch document, remove the copy's
+    // content and every annotation other than the given widgets, flatten the copy, then
+    // wrap the flattened copy in a form XObject that can be drawn on the original page.
+
+    let bindings = document.bindings();
+
+    let scratch =
+        Pdfium::pdfium_document_handle_to_result(bindings.FPDF_CreateNewDocument(), bindings)?;
+
+    PdfPages::copy_page_range_between_documents(
+        document.handle(),
+        page_index..=page_index,
+        scratch.handle(),
+        0,
+        bindings,
+    )?;
+
+    let page = scratch.pages().get(0)?;
+
+    let page_handle = page.page_handle();
+
+    while bindings.FPDFPage_CountObjects(page_handle) > 0 {
+        let object = bindings.FPDFPage_GetObject(page_handle, 0);
+
+        if !bindings.is_true(bindings.FPDFPage_RemoveObject(page_handle, object)) {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        bindings.FPDFPageObj_Destroy(object);
+    }
+
+    for index in (0..bindings.FPDFPage_GetAnnotCount(page_handle)).rev() {
+        if !annotation_indices.contains(&(index as PdfPageAnnotationIndex)) {
+            bindings.FPDFPage_RemoveAnnot(page_handle, index);
+        }
+    }
+
+    // A form XObject created from a page includes the page's rotation and maps the corner
+    // of the page's bounding box to the origin. We remove the rotation, and move the
+    // form object back into place on the original page once it is created.
+
+    bindings.FPDFPage_SetRotation(page_handle, 0);
+
+    let origin = page.boundaries().bounding()?.bounds;
+
+    if !bindings.is_true(bindings.FPDFPage_GenerateContent(page_handle)) {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    match bindings.FPDFPage_Flatten(page_handle, FLAT_NORMALDISPLAY as c_int) as u32 {
+        FLATTEN_SUCCESS => {}
+        FLATTEN_NOTHINGTODO => return Ok(None),
+        _ => return Err(PdfiumError::PageFlattenFailure),
+    }
+
+    drop(page);
+
+    let xobject = bindings.FPDF_NewXObjectFromPage(document.handle(), scratch.handle(), 0);
+
+    if xobject.is_null() {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    let object = bindings.FPDF_NewFormObjectFromXObject(xobject);
+
+    bindings.FPDF_CloseXObject(xobject);
+
+    if object.is_null() {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    bindings.FPDFPageObj_Transform(
+        object,
+        1.0,
+        0.0,
+        0.0,
+        1.0,
+        origin.left.value as f64,
+        origin.bottom.value as f64,
+    );
+
+    Ok(Some(object))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_flatten_form_fields() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.load_pdf_from_file("test/form-test.pdf", None)?;
+
+        let field_count = document.form().unwrap().fields(document.pages()).len();
+
+        let kept_field_name = document
+            .form()
+            .unwrap()
+            .fields(document.pages())
+            .iter()
+            .next()
+            .map(|field| field.name().to_string())
+            .unwrap();
+
+        document.flatten_form_fields(|field| field.name() != kept_field_name)?;
+
+        let fields = document.form().unwrap().fields(document.pages());
+
+        assert_eq!(fields.len(), 1);
+        assert!(fields.get(&kept_field_name).is_some());
+        assert!(field_count > 1);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,425 @@
+//! Edits the options and selection settings of combo box and list box fields in a
+//! `PdfDocument`, via the `PdfDocument::set_form_field_options()` function and related
+//! functions.
+
+use crate::bindgen::{FPDF_FORMFLAG_CHOICE_EDIT, FPDF_FORMFLAG_CHOICE_MULTI_SELECT};
+use crate::error::PdfiumError;
+use crate::pdf::document::syntax::{
+    decode_pdf_string, PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject,
+};
+
+/// The maximum depth of the field tree followed when locating a field.
+const MAXIMUM_FIELD_TREE_DEPTH: usize = 64;
+
+/// A single change to the options or selection settings of a combo box or list box field.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PdfFormFieldOptionsEdit {
+    /// Replaces every option of the field with the given (label, export value) pairs.
+    Set(Vec<(String, String)>),
+
+    /// Inserts an option with the given label and export value at the given index.
+    Insert(usize, String, String),
+
+    /// Removes the option at the given index.
+    Remove(usize),
+
+    /// Allows or prevents the selection of more than one option of a list box.
+    SetMultiSelect(bool),
+}
+
+/// Applies the given edit to the combo box or list box field with the given fully qualified
+/// name in the given PDF file, returning the updated file. The edit is applied in an
+/// incremental update appended to the end of the file.
+///
+/// The field's selected option indices are removed, as is any selected value that is no
+/// longer one of the field's options. The appearance streams of the field's widgets are
+/// removed and the form is marked as needing new appearances, so that Pdfium and other
+/// viewers draw the field with its new options.
+pub(crate) fn edit_form_field_options(
+    file: Vec<u8>,
+    name: &str,
+    edit: PdfFormFieldOptionsEdit,
+) -> Result<Vec<u8>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    let root = match update.trailer().get("Root") {
+        Some(PdfSyntaxObject::Reference(number, _))
+            if update.trailer().get("Encrypt").is_none() =>
+        {
+            *number
+        }
+        _ => return Err(PdfiumError::FormFieldCreationUnsupported),
+    };
+
+    let number = update
+        .get(root)
+        .and_then(|catalog| catalog.get("AcroForm").map(|form| update.resolve(form)))
+        .and_then(|form| form.get("Fields").cloned())
+        .and_then(|fields| find_field(&update, &fields, None, name, 0))
+        .ok_or(PdfiumError::FormFieldNotFound)?;
+
+    let mut field = update.get(number).ok_or(PdfiumError::FormFieldNotFound)?;
+
+    if inherited(&update, &field, "FT") != Some(PdfSyntaxObject::name("Ch")) {
+        return Err(PdfiumError::InvalidFormFieldDefinition);
+    }
+
+    let original_flags = match inherited(&update, &field, "Ff") {
+        Some(PdfSyntaxObject::Number(flags)) => flags as u32,
+        _ => 0,
+    };
+
+    let mut flags = original_flags;
+
+    let mut options = inherited(&update, &field, "Opt")
+        .map(|options| read_options(&update, &options))
+        .unwrap_or_default();
+
+    match edit {
+        PdfFormFieldOptionsEdit::Set(new_options) => options = new_options,
+        PdfFormFieldOptionsEdit::Insert(index, label, export_value) => {
+            if index > options.len() {
+                return Err(PdfiumError::FormFieldOptionIndexOutOfBounds);
+            }
+
+            options.insert(index, (label, export_value));
+        }
+        PdfFormFieldOptionsEdit::Remove(index) => {
+            if index >= options.len() {
+                return Err(PdfiumError::FormFieldOptionIndexOutOfBounds);
+            }
+
+            options.remove(index);
+        }
+        PdfFormFieldOptionsEdit::SetMultiSelect(is_multi_select) => {
+            if is_multi_select {
+                flags |= FPDF_FORMFLAG_CHOICE_MULTI_SELECT;
+            } else {
+                flags &= !FPDF_FORMFLAG_CHOICE_MULTI_SELECT;
+            }
+        }
+    }
+
+    field.set("Opt", write_options(&options));
+
+    if flags != original_flags {
+        field.set("Ff", PdfSyntaxObject::Number(flags as f64));
+    }
+
+    // Selected option indices may no longer refer to the same options, and Pdfium
+    // prefers the selected value in any case.
+
+    field.remove("I");
+
+    let export_values = options
+        .iter()
+        .map(|(_, export_value)| export_value.as_str())
+        .collect::<Vec<_>>();
+
+    let is_editable = flags & FPDF_FORMFLAG_CHOICE_EDIT != 0;
+
+    let is_multi_select = flags & FPDF_FORMFLAG_CHOICE_MULTI_SELECT != 0;
+
+    match field.get("V").map(|value| update.resolve(value)) {
+        Some(PdfSyntaxObject::String(value))
+            if !is_editable && !export_values.contains(&decode_pdf_string(&value).as_str()) =>
+        {
+            field.remove("V");
+        }
+        Some(PdfSyntaxObject::Array(values)) => {
+            let mut values = values
+                .into_iter()
+                .filter(|value| match update.resolve(value) {
+                    PdfSyntaxObject::String(value) => {
+                        export_values.contains(&decode_pdf_string(&value).as_str())
+                    }
+                    _ => false,
+                })
+                .collect::<Vec<_>>();
+
+            if !is_multi_select {
+                values.truncate(1);
+            }
+
+            match values.len() {
+                0 => field.remove("V"),
+                1 if !is_multi_select => field.set("V", values.remove(0)),
+                _ => field.set("V", PdfSyntaxObject::Array(values)),
+            }
+        }
+        _ => {}
+    }
+
+    // A field with no child fields or widgets is merged with its only widget.
+
+    let widgets = match field.get("Kids").map(|kids| update.resolve(kids)) {
+        Some(PdfSyntaxObject::Array(kids)) => kids
+            .iter()
+            .filter_map(|kid| match kid {
+                PdfSyntaxObject::Reference(number, _) if update.resolve(kid).get("T").is_none() => {
+                    Some(*number)
+                }
+                _ => None,
+            })
+            .collect(),
+        _ => {
+            field.remove("AP");
+
+            Vec::new()
+        }
+    };
+
+    update.set(number, field);
+
+    for widget_number in widgets {
+        if let Some(mut widget) = update.get(widget_number) {
+            widget.remove("AP");
+
+            update.set(widget_number, widget);
+        }
+    }
+
+    update
+        .modify(root, &["AcroForm"], &mut |form| {
+            form.set("NeedAppearances", PdfSyntaxObject::Bool(true));
+
+            Some(())
+        })
+        .ok_or(PdfiumError::FormFieldCreationUnsupported)?;
+
+    Ok(update.write(file))
+}
+
+/// Returns the object number of the field with the given fully qualified name among the
+/// given array of fields, whose parent field has the given fully qualified name.
+fn find_field(
+    update: &PdfIncrementalUpdate,
+    fields: &PdfSyntaxObject,
+    parent_name: Option<&str>,
+    name: &str,
+    depth: usize,
+) -> Option<u32> {
+    if depth > MAXIMUM_FIELD_TREE_DEPTH {
+        return None;
+    }
+
+    let fields = match update.resolve(fields) {
+        PdfSyntaxObject::Array(fields) => fields,
+        _ => return None,
+    };
+
+    for reference in fields.iter() {
+        let number = match reference {
+            PdfSyntaxObject::Reference(number, _) => *number,
+            _ => continue,
+        };
+
+        let field = update.resolve(reference);
+
+        let field_name = match (field.get("T").map(|name| update.resolve(name)), parent_name) {
+            (Some(PdfSyntaxObject::String(partial_name)), Some(parent_name)) => {
+                format!("{}.{}", parent_name, decode_pdf_string(&partial_name))
+            }
+            (Some(PdfSyntaxObject::String(partial_name)), None) => decode_pdf_string(&partial_name),
+
+            // A widget has no partial name of its own.
+            _ => continue,
+        };
+
+        if field_name == name {
+            return Some(number);
+        }
+
+        if name.starts_with(&format!("{}.", field_name)) {
+            if let Some(number) = field.get("Kids").and_then(|kids| {
+                find_field(update, kids, Some(field_name.as_str()), name, depth + 1)
+            }) {
+                return Some(number);
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns the value of the given inheritable field attribute, looking first in the given
+/// field dictionary and then in the dictionaries of its ancestors.
+fn inherited(
+    update: &PdfIncrementalUpdate,
+    field: &PdfSyntaxObject,
+    key: &str,
+) -> Option<PdfSyntaxObject> {
+    let mut field = field.clone();
+
+    for _ in 0..MAXIMUM_FIELD_TREE_DEPTH {
+        if let Some(value) = field.get(key) {
+            return Some(update.resolve(value));
+        }
+
+        field = update.resolve(field.get("Parent")?);
+    }
+
+    None
+}
+
+/// Returns the (label, export value) pairs described by the given `/Opt` array.
+fn read_options(update: &PdfIncrementalUpdate, options: &PdfSyntaxObject) -> Vec<(String, String)> {
+    let options = match update.resolve(options) {
+        PdfSyntaxObject::Array(options) => options,
+        _ => return Vec::new(),
+    };
+
+    options
+        .iter()
+        .filter_map(|option| match update.resolve(option) {
+            PdfSyntaxObject::String(value) => {
+                let value = decode_pdf_string(&value);
+
+                Some((value.clone(), value))
+            }
+            PdfSyntaxObject::Array(pair) if pair.len() == 2 => {
+                match (update.resolve(&pair[0]), update.resolve(&pair[1])) {
+                    (PdfSyntaxObject::String(export_value), PdfSyntaxObject::String(label)) => {
+                        Some((decode_pdf_string(&label), decode_pdf_string(&export_value)))
+                    }
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Returns an `/Opt` array describing the given (label, export value) pairs.
+fn write_options(options: &[(String, String)]) -> PdfSyntaxObject {
+    PdfSyntaxObject::Array(
+        options
+            .iter()
+            .map(|(label, export_value)| {
+                if label == export_value {
+                    PdfSyntaxObject::text(label)
+                } else {
+                    PdfSyntaxObject::Array(vec![
+                        PdfSyntaxObject::text(export_value),
+                        PdfSyntaxObject::text(label),
+                    ])
+                }
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_file() -> Vec<u8> {
+        let mut file = b"%PDF-1.7\n".to_vec();
+
+        let mut offsets = Vec::new();
+
+        for (index, object) in [
+            "<< /Type /Catalog /Pages 2 0 R /AcroForm << /Fields [4 0 R] >> >>",
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>",
+            "<< /Type /Page /Parent 2 0 R /Annots [5 0 R] >>",
+            "<< /T (Choices) /Kids [5 0 R] >>",
+            "<< /Type /Annot /Subtype /Widget /Parent 4 0 R /T (Color) /FT /Ch /Ff 0 \
+               /Opt [(Red) [(B) (Blue)]] /V (Red) /I [0] /AP << /N << >> >> >>",
+        ]
+        .iter()
+        .enumerate()
+        {
+            offsets.push(file.len());
+            file.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).as_bytes());
+        }
+
+        let start_xref = file.len();
+
+        file.extend_from_slice(b"xref\n0 6\n0000000000 65535 f \n");
+
+        for offset in offsets {
+            file.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+
+        file.extend_from_slice(
+            format!(
+                "trailer\n<< /Size 6 /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                start_xref
+            )
+            .as_bytes(),
+        );
+
+        file
+    }
+
+    fn updated_field(file: &[u8]) -> PdfSyntaxObject {
+        PdfSyntaxFile::parse(file).unwrap().objects[&5]
+            .object
+            .clone()
+    }
+
+    #[test]
+    fn test_edit_form_field_options() {
+        let file = edit_form_field_options(
+            test_file(),
+            "Choices.Color",
+            PdfFormFieldOptionsEdit::Insert(1, "Green".to_string(), "G".to_string()),
+        )
+        .unwrap();
+
+        let field = updated_field(&file);
+
+        assert_eq!(
+            read_options(
+                &PdfIncrementalUpdate::new(&PdfSyntaxFile::parse(&file).unwrap()).unwrap(),
+                field.get("Opt").unwrap()
+            ),
+            vec![
+                ("Red".to_string(), "Red".to_string()),
+                ("Green".to_string(), "G".to_string()),
+                ("Blue".to_string(), "B".to_string()),
+            ]
+        );
+        assert_eq!(field.get("V"), Some(&PdfSyntaxObject::text("Red")));
+        assert_eq!(field.get("I"), None);
+        assert_eq!(field.get("AP"), None);
+
+        let file =
+            edit_form_field_options(file, "Choices.Color", PdfFormFieldOptionsEdit::Remove(0))
+                .unwrap();
+
+        let field = updated_field(&file);
+
+        assert_eq!(field.get("V"), None);
+
+        let file = edit_form_field_options(
+            file,
+            "Choices.Color",
+            PdfFormFieldOptionsEdit::SetMultiSelect(true),
+        )
+        .unwrap();
+
+        assert_eq!(
+            updated_field(&file).get("Ff"),
+            Some(&PdfSyntaxObject::Number(
+                FPDF_FORMFLAG_CHOICE_MULTI_SELECT as f64
+            ))
+        );
+
+        assert!(matches!(
+            edit_form_field_options(
+                file.clone(),
+                "Choices.Color",
+                PdfFormFieldOptionsEdit::Remove(5)
+            ),
+            Err(PdfiumError::FormFieldOptionIndexOutOfBounds)
+        ));
+        assert!(matches!(
+            edit_form_field_options(file, "Choices", PdfFormFieldOptionsEdit::Remove(0)),
+            Err(PdfiumError::InvalidFormFieldDefinition)
+        ));
+    }
+}
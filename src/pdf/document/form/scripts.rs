@@ -0,0 +1,590 @@
+//! Defines the [PdfFormFieldScripts] struct, exposing the JavaScript actions run by a viewer
+//! when the value of a form field is typed, formatted, validated, or calculated, and the
+//! [PdfFormFieldFormat] enum, describing the standard formats applied by those actions.
+
+use crate::bindgen::{
+    FPDF_ANNOTATION, FPDF_ANNOT_AACTION_CALCULATE, FPDF_ANNOT_AACTION_FORMAT,
+    FPDF_ANNOT_AACTION_KEY_STROKE, FPDF_ANNOT_AACTION_VALIDATE, FPDF_FORMHANDLE, FPDF_WCHAR,
+};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::utils::mem::create_byte_buffer;
+use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use std::os::raw::c_int;
+
+/// The date formats selected by index by the `AFDate_Format()` function of the
+/// Acrobat JavaScript API.
+const DATE_FORMATS: [&str; 14] = [
+    "m/d",
+    "m/d/yy",
+    "mm/dd/yy",
+    "mm/yy",
+    "d-mmm",
+    "d-mmm-yy",
+    "dd-mmm-yy",
+    "yy-mm-dd",
+    "mmm-yy",
+    "mmmm-yy",
+    "mmm d, yyyy",
+    "mmmm d, yyyy",
+    "m/d/yy h:MM tt",
+    "m/d/yy HH:MM",
+];
+
+/// The time formats selected by index by the `AFTime_Format()` function of the
+/// Acrobat JavaScript API.
+const TIME_FORMATS: [&str; 4] = ["HH:MM", "h:MM tt", "HH:MM:ss", "h:MM:ss tt"];
+
+/// The digit grouping and decimal separators used by a [PdfFormFieldFormat::Number] or
+/// [PdfFormFieldFormat::Percent] format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfFormNumberSeparatorStyle {
+    /// Digits are grouped with commas and decimals separated by a period, as in `1,234.56`.
+    CommaPeriod,
+
+    /// Digits are not grouped and decimals are separated by a period, as in `1234.56`.
+    Period,
+
+    /// Digits are grouped with periods and decimals separated by a comma, as in `1.234,56`.
+    PeriodComma,
+
+    /// Digits are not grouped and decimals are separated by a comma, as in `1234,56`.
+    Comma,
+
+    /// Digits are grouped with apostrophes and decimals separated by a period,
+    /// as in `1'234.56`.
+    ApostrophePeriod,
+}
+
+impl PdfFormNumberSeparatorStyle {
+    #[inline]
+    fn from_index(index: f64) -> Option<Self> {
+        match index as i32 {
+            0 => Some(PdfFormNumberSeparatorStyle::CommaPeriod),
+            1 => Some(PdfFormNumberSeparatorStyle::Period),
+            2 => Some(PdfFormNumberSeparatorStyle::PeriodComma),
+            3 => Some(PdfFormNumberSeparatorStyle::Comma),
+            4 => Some(PdfFormNumberSeparatorStyle::ApostrophePeriod),
+            _ => None,
+        }
+    }
+
+    /// Returns the separator placed between groups of three digits, if digits are grouped.
+    #[inline]
+    pub fn grouping_separator(&self) -> Option<char> {
+        match self {
+            PdfFormNumberSeparatorStyle::CommaPeriod => Some(','),
+            PdfFormNumberSeparatorStyle::PeriodComma => Some('.'),
+            PdfFormNumberSeparatorStyle::ApostrophePeriod => Some('\''),
+            PdfFormNumberSeparatorStyle::Period | PdfFormNumberSeparatorStyle::Comma => None,
+        }
+    }
+
+    /// Returns the separator placed between the integer and decimal parts of a number.
+    #[inline]
+    pub fn decimal_separator(&self) -> char {
+        match self {
+            PdfFormNumberSeparatorStyle::PeriodComma | PdfFormNumberSeparatorStyle::Comma => ',',
+            _ => '.',
+        }
+    }
+}
+
+/// The way negative numbers are shown by a [PdfFormFieldFormat::Number] format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfFormNegativeNumberStyle {
+    /// Negative numbers are shown with a leading minus sign.
+    MinusSign,
+
+    /// Negative numbers are shown without a sign, in red text.
+    Red,
+
+    /// Negative numbers are shown without a sign, in parentheses.
+    Parentheses,
+
+    /// Negative numbers are shown without a sign, in parentheses and in red text.
+    RedParentheses,
+}
+
+impl PdfFormNegativeNumberStyle {
+    #[inline]
+    fn from_index(index: f64) -> Option<Self> {
+        match index as i32 {
+            0 => Some(PdfFormNegativeNumberStyle::MinusSign),
+            1 => Some(PdfFormNegativeNumberStyle::Red),
+            2 => Some(PdfFormNegativeNumberStyle::Parentheses),
+            3 => Some(PdfFormNegativeNumberStyle::RedParentheses),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of value formatted by a [PdfFormFieldFormat::Special] format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfFormSpecialFormat {
+    /// A five digit US ZIP code, as in `12345`.
+    ZipCode,
+
+    /// A nine digit US ZIP+4 code, as in `12345-6789`.
+    ZipCodePlus4,
+
+    /// A ten digit US phone number, as in `(123) 456-7890`.
+    PhoneNumber,
+
+    /// A nine digit US social security number, as in `123-45-6789`.
+    SocialSecurityNumber,
+}
+
+impl PdfFormSpecialFormat {
+    #[inline]
+    fn from_index(index: f64) -> Option<Self> {
+        match index as i32 {
+            0 => Some(PdfFormSpecialFormat::ZipCode),
+            1 => Some(PdfFormSpecialFormat::ZipCodePlus4),
+            2 => Some(PdfFormSpecialFormat::PhoneNumber),
+            3 => Some(PdfFormSpecialFormat::SocialSecurityNumber),
+            _ => None,
+        }
+    }
+}
+
+/// A standard format applied to the value of a form field by one of the formatting functions
+/// of the Acrobat JavaScript API, such as `AFNumber_Format()` or `AFDate_FormatEx()`.
+///
+/// Viewers that do not run JavaScript can use a [PdfFormFieldFormat] to display and
+/// validate field values in the same way as a viewer that does.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfFormFieldFormat {
+    /// The value is a number, set by `AFNumber_Format()`.
+    Number {
+        /// The number of decimal places shown.
+        decimal_places: u32,
+
+        /// The digit grouping and decimal separators used.
+        separator_style: PdfFormNumberSeparatorStyle,
+
+        /// The way negative numbers are shown.
+        negative_style: PdfFormNegativeNumberStyle,
+
+        /// The currency symbol shown alongside the number, if any.
+        currency_symbol: Option<String>,
+
+        /// `true` if the currency symbol is shown before the number rather than after it.
+        is_currency_prepended: bool,
+    },
+
+    /// The value is a percentage, set by `AFPercent_Format()`. The value is multiplied
+    /// by 100 when shown.
+    Percent {
+        /// The number of decimal places shown.
+        decimal_places: u32,
+
+        /// The digit grouping and decimal separators used.
+        separator_style: PdfFormNumberSeparatorStyle,
+    },
+
+    /// The value is a date, shown using the given Acrobat date pattern, such as `mm/dd/yyyy`.
+    /// Set by `AFDate_Format()` or `AFDate_FormatEx()`.
+    Date(String),
+
+    /// The value is a time, shown using the given Acrobat time pattern, such as `HH:MM`.
+    /// Set by `AFTime_Format()` or `AFTime_FormatEx()`.
+    Time(String),
+
+    /// The value is one of a number of common kinds of value, set by `AFSpecial_Format()`.
+    Special(PdfFormSpecialFormat),
+
+    /// The value is shown using the given arbitrary mask, in which `9` stands for a digit,
+    /// `A` for a letter, `O` for a letter or digit, and `X` for any character.
+    /// Set by `AFSpecial_KeystrokeEx()`.
+    Mask(String),
+}
+
+impl PdfFormFieldFormat {
+    /// Returns the first standard format applied by the given JavaScript, if any.
+    pub(crate) fn from_script(script: &str) -> Option<Self> {
+        let (function, arguments) = find_format_function_call(script)?;
+
+        let number = |index: usize| match arguments.get(index) {
+            Some(PdfScriptArgument::Number(value)) => Some(*value),
+            _ => None,
+        };
+
+        let text = |index: usize| match arguments.get(index) {
+            Some(PdfScriptArgument::String(value)) => Some(value.clone()),
+            _ => None,
+        };
+
+        let flag = |index: usize| match arguments.get(index) {
+            Some(PdfScriptArgument::Bool(value)) => Some(*value),
+            Some(PdfScriptArgument::Number(value)) => Some(*value != 0.0),
+            _ => None,
+        };
+
+        match function.as_str() {
+            "AFNumber_Format" | "AFNumber_Keystroke" => Some(PdfFormFieldFormat::Number {
+                decimal_places: number(0)?.max(0.0) as u32,
+                separator_style: PdfFormNumberSeparatorStyle::from_index(number(1)?)?,
+                negative_style: PdfFormNegativeNumberStyle::from_index(number(2)?)?,
+                currency_symbol: text(4).filter(|symbol| !symbol.is_empty()),
+                is_currency_prepended: flag(5).unwrap_or(true),
+            }),
+            "AFPercent_Format" | "AFPercent_Keystroke" => Some(PdfFormFieldFormat::Percent {
+                decimal_places: number(0)?.max(0.0) as u32,
+                separator_style: PdfFormNumberSeparatorStyle::from_index(number(1)?)?,
+            }),
+            "AFDate_Format" | "AFDate_Keystroke" => DATE_FORMATS
+                .get(number(0)? as usize)
+                .map(|format| PdfFormFieldFormat::Date(format.to_string())),
+            "AFDate_FormatEx" | "AFDate_KeystrokeEx" => text(0).map(PdfFormFieldFormat::Date),
+            "AFTime_Format" | "AFTime_Keystroke" => TIME_FORMATS
+                .get(number(0)? as usize)
+                .map(|format| PdfFormFieldFormat::Time(format.to_string())),
+            "AFTime_FormatEx" | "AFTime_KeystrokeEx" => text(0).map(PdfFormFieldFormat::Time),
+            "AFSpecial_Format" | "AFSpecial_Keystroke" => {
+                PdfFormSpecialFormat::from_index(number(0)?).map(PdfFormFieldFormat::Special)
+            }
+            "AFSpecial_KeystrokeEx" => text(0).map(PdfFormFieldFormat::Mask),
+            _ => None,
+        }
+    }
+}
+
+/// The JavaScript actions of a single form field, run by a viewer when the value of the
+/// field is typed, formatted, validated, or calculated. The actions are defined by the
+/// `/AA` entry in the field dictionary.
+///
+/// Pdfium can run these actions only when it is built with V8 support; `pdfium-render`
+/// never runs them. Viewers that do not run JavaScript can use
+/// [PdfFormFieldScripts::format_pattern()] to emulate the most common formatting actions.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PdfFormFieldScripts {
+    keystroke: Option<String>,
+    format: Option<String>,
+    validate: Option<String>,
+    calculate: Option<String>,
+}
+
+impl PdfFormFieldScripts {
+    /// Returns the [PdfFormFieldScripts] of the form field widget with the given handle.
+    pub(crate) fn from_pdfium(
+        form_handle: FPDF_FORMHANDLE,
+        annotation_handle: FPDF_ANNOTATION,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Self {
+        let script = |event: u32| {
+            // Retrieving the script from Pdfium is a two-step operation. First, we call
+            // FPDFAnnot_GetFormAdditionalActionJavaScript() with a null buffer; this will
+            // retrieve the length of the script in bytes. A length of 2 indicates an empty
+            // script, consisting only of the null terminator.
+
+            // If the length is greater than 2, then we reserve a byte buffer of the given
+            // length and call FPDFAnnot_GetFormAdditionalActionJavaScript() again with
+            // a pointer to the buffer; this will write the script to the buffer
+            // in UTF16LE format.
+
+            let buffer_length = bindings.FPDFAnnot_GetFormAdditionalActionJavaScript(
+                form_handle,
+                annotation_handle,
+                event as c_int,
+                std::ptr::null_mut(),
+                0,
+            );
+
+            if buffer_length <= 2 {
+                return None;
+            }
+
+            let mut buffer = create_byte_buffer(buffer_length as usize);
+
+            let result = bindings.FPDFAnnot_GetFormAdditionalActionJavaScript(
+                form_handle,
+                annotation_handle,
+                event as c_int,
+                buffer.as_mut_ptr() as *mut FPDF_WCHAR,
+                buffer_length,
+            );
+
+            debug_assert_eq!(result, buffer_length);
+
+            get_string_from_pdfium_utf16le_bytes(buffer).filter(|script| !script.is_empty())
+        };
+
+        PdfFormFieldScripts {
+            keystroke: script(FPDF_ANNOT_AACTION_KEY_STROKE),
+            format: script(FPDF_ANNOT_AACTION_FORMAT),
+            validate: script(FPDF_ANNOT_AACTION_VALIDATE),
+            calculate: script(FPDF_ANNOT_AACTION_CALCULATE),
+        }
+    }
+
+    /// Returns the JavaScript run as the user types into the field, or as the user changes
+    /// the selection of a combo box or list box, if any. Usually used to reject invalid input.
+    #[inline]
+    pub fn keystroke(&self) -> Option<&str> {
+        self.keystroke.as_deref()
+    }
+
+    /// Returns the JavaScript run before the field's value is displayed, if any. Usually
+    /// used to format the value, for instance as a currency amount.
+    #[inline]
+    pub fn format(&self) -> Option<&str> {
+        self.format.as_deref()
+    }
+
+    /// Returns the JavaScript run when the field's value changes, if any. Usually used to
+    /// check that the value is acceptable, for instance that a number lies within a range.
+    #[inline]
+    pub fn validate(&self) -> Option<&str> {
+        self.validate.as_deref()
+    }
+
+    /// Returns the JavaScript run to recalculate the field's value when the value of
+    /// another field changes, if any.
+    #[inline]
+    pub fn calculate(&self) -> Option<&str> {
+        self.calculate.as_deref()
+    }
+
+    /// Returns `true` if the field has none of these JavaScript actions.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keystroke.is_none()
+            && self.format.is_none()
+            && self.validate.is_none()
+            && self.calculate.is_none()
+    }
+
+    /// Returns the standard format applied to the field's value by its format action, or
+    /// failing that by its keystroke action, if the action calls one of the standard
+    /// formatting functions of the Acrobat JavaScript API.
+    #[inline]
+    pub fn format_pattern(&self) -> Option<PdfFormFieldFormat> {
+        self.format
+            .as_deref()
+            .and_then(PdfFormFieldFormat::from_script)
+            .or_else(|| {
+                self.keystroke
+                    .as_deref()
+                    .and_then(PdfFormFieldFormat::from_script)
+            })
+    }
+}
+
+/// A literal argument passed to a function in a JavaScript action.
+#[derive(Debug, Clone, PartialEq)]
+enum PdfScriptArgument {
+    Number(f64),
+    String(String),
+    Bool(bool),
+}
+
+/// Returns the name and literal arguments of the first call in the given JavaScript to a
+/// standard formatting function of the Acrobat JavaScript API, if any. Arguments that are
+/// not literals end the argument list.
+fn find_format_function_call(script: &str) -> Option<(String, Vec<PdfScriptArgument>)> {
+    let mut search_from = 0;
+
+    while let Some(offset) = script[search_from..].find("AF") {
+        let start = search_from + offset;
+
+        search_from = start + 2;
+
+        if script[..start]
+            .chars()
+            .next_back()
+            .map_or(false, |c| c.is_alphanumeric() || c == '_' || c == '.')
+        {
+            // The match is part of a longer identifier.
+
+            continue;
+        }
+
+        let name_length = script[start..]
+            .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+            .unwrap_or(script.len() - start);
+
+        let name = &script[start..start + name_length];
+
+        let rest = script[start + name_length..].trim_start();
+
+        if let Some(arguments) = rest.strip_prefix('(') {
+            return Some((name.to_string(), parse_arguments(arguments)));
+        }
+    }
+
+    None
+}
+
+/// Parses literal arguments from the given JavaScript, which follows the opening
+/// parenthesis of a function call.
+fn parse_arguments(script: &str) -> Vec<PdfScriptArgument> {
+    let mut result = Vec::new();
+
+    let mut chars = script.chars().peekable();
+
+    loop {
+        while chars.peek().map_or(false, |c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let argument = match chars.peek() {
+            Some(&quote) if quote == '"' || quote == '\'' => {
+                chars.next();
+
+                let mut value = String::new();
+
+                loop {
+                    match chars.next() {
+                        None => return result,
+                        Some(c) if c == quote => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some(c) => value.push(c),
+                            None => return result,
+                        },
+                        Some(c) => value.push(c),
+                    }
+                }
+
+                PdfScriptArgument::String(value)
+            }
+            Some(c) if c.is_ascii_digit() || *c == '-' || *c == '+' || *c == '.' => {
+                let mut literal = String::new();
+
+                while let Some(&c) = chars.peek() {
+                    if c.is_ascii_digit() || c == '-' || c == '+' || c == '.' {
+                        literal.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                match literal.parse() {
+                    Ok(value) => PdfScriptArgument::Number(value),
+                    Err(_) => return result,
+                }
+            }
+            Some(c) if c.is_alphabetic() => {
+                let mut word = String::new();
+
+                while let Some(&c) = chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        word.push(c);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+
+                match word.as_str() {
+                    "true" => PdfScriptArgument::Bool(true),
+                    "false" => PdfScriptArgument::Bool(false),
+                    _ => return result,
+                }
+            }
+            _ => return result,
+        };
+
+        result.push(argument);
+
+        while chars.peek().map_or(false, |c| c.is_whitespace()) {
+            chars.next();
+        }
+
+        match chars.next() {
+            Some(',') => continue,
+            _ => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_from_script() {
+        assert_eq!(
+            PdfFormFieldFormat::from_script("AFNumber_Format(2, 0, 2, 0, \"$\", true);"),
+            Some(PdfFormFieldFormat::Number {
+                decimal_places: 2,
+                separator_style: PdfFormNumberSeparatorStyle::CommaPeriod,
+                negative_style: PdfFormNegativeNumberStyle::Parentheses,
+                currency_symbol: Some("$".to_string()),
+                is_currency_prepended: true,
+            })
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("AFNumber_Format(0,2,0,0,\" \u{20ac}\",false)"),
+            Some(PdfFormFieldFormat::Number {
+                decimal_places: 0,
+                separator_style: PdfFormNumberSeparatorStyle::PeriodComma,
+                negative_style: PdfFormNegativeNumberStyle::MinusSign,
+                currency_symbol: Some(" \u{20ac}".to_string()),
+                is_currency_prepended: false,
+            })
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("AFPercent_Format(1, 1);"),
+            Some(PdfFormFieldFormat::Percent {
+                decimal_places: 1,
+                separator_style: PdfFormNumberSeparatorStyle::Period,
+            })
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("AFDate_FormatEx('dd.mm.yyyy');"),
+            Some(PdfFormFieldFormat::Date("dd.mm.yyyy".to_string()))
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("AFDate_Format(2);"),
+            Some(PdfFormFieldFormat::Date("mm/dd/yy".to_string()))
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("AFTime_Format(1);"),
+            Some(PdfFormFieldFormat::Time("h:MM tt".to_string()))
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("AFSpecial_Format(3);"),
+            Some(PdfFormFieldFormat::Special(
+                PdfFormSpecialFormat::SocialSecurityNumber
+            ))
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("AFSpecial_KeystrokeEx(\"AA-9999\");"),
+            Some(PdfFormFieldFormat::Mask("AA-9999".to_string()))
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("event.value = util.printd(\"mm\", new Date());"),
+            None
+        );
+        assert_eq!(
+            PdfFormFieldFormat::from_script("MyAFNumber_Format(2, 0, 0, 0, \"\", true);"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_format_pattern_falls_back_to_keystroke() {
+        let scripts = PdfFormFieldScripts {
+            keystroke: Some("AFNumber_Keystroke(2, 0, 0, 0, \"\", true);".to_string()),
+            format: Some("event.value = event.value.toUpperCase();".to_string()),
+            validate: None,
+            calculate: None,
+        };
+
+        assert_eq!(
+            scripts.format_pattern(),
+            Some(PdfFormFieldFormat::Number {
+                decimal_places: 2,
+                separator_style: PdfFormNumberSeparatorStyle::CommaPeriod,
+                negative_style: PdfFormNegativeNumberStyle::MinusSign,
+                currency_symbol: None,
+                is_currency_prepended: true,
+            })
+        );
+        assert!(!scripts.is_empty());
+        assert!(PdfFormFieldScripts::default().is_empty());
+    }
+}
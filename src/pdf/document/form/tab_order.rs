@@ -0,0 +1,228 @@
+//! Defines the [PdfFormTabOrder] enum, describing the order in which the keyboard focus
+//! moves between the form field widgets on a page, along with functions that arrange
+//! widgets in that order.
+
+use crate::bindgen::FPDF_DOCUMENT;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::form::fields::PdfFormWidgetLocation;
+use crate::pdf::document::page::annotation::{PdfPageAnnotation, PdfPageAnnotationCommon};
+use crate::pdf::document::page::annotations::PdfPageAnnotationIndex;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{PdfSyntaxFile, PdfSyntaxObject};
+use crate::pdf::rect::PdfRect;
+use crate::utils::files::get_pdfium_file_writer_from_writer;
+use std::cmp::Reverse;
+use std::io::Cursor;
+
+/// The order in which the keyboard focus moves between the form field widgets on a page,
+/// as set by the `/Tabs` entry of the page's dictionary.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum PdfFormTabOrder {
+    /// Widgets are visited in rows from the top of the page to the bottom, and from left
+    /// to right within each row.
+    Row,
+
+    /// Widgets are visited in columns from the left of the page to the right, and from top
+    /// to bottom within each column.
+    Column,
+
+    /// Widgets are visited in the order of the page's structure tree. Like Pdfium, we treat
+    /// this as [PdfFormTabOrder::Annotation].
+    Structure,
+
+    /// Widgets are visited in the order they appear in the page's annotations array.
+    Annotation,
+}
+
+impl PdfFormTabOrder {
+    #[inline]
+    pub(crate) fn from_pdf_name(name: &str) -> Self {
+        match name {
+            "R" => PdfFormTabOrder::Row,
+            "C" => PdfFormTabOrder::Column,
+            "S" => PdfFormTabOrder::Structure,
+            _ => PdfFormTabOrder::Annotation,
+        }
+    }
+}
+
+/// Returns the tab order of every page in the document with the given handle, in page order.
+///
+/// Pdfium does not expose the `/Tabs` entry of a page's dictionary, so we read it from a copy
+/// of the document saved by Pdfium. Pages whose dictionaries cannot be located in the copy
+/// use [PdfFormTabOrder::Annotation], Pdfium's default.
+pub(crate) fn page_tab_orders(
+    document_handle: FPDF_DOCUMENT,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<Vec<PdfFormTabOrder>, PdfiumError> {
+    let mut cursor = Cursor::new(Vec::new());
+
+    {
+        let mut pdfium_file_writer = get_pdfium_file_writer_from_writer(&mut cursor);
+
+        if !bindings.is_true(bindings.FPDF_SaveAsCopy(
+            document_handle,
+            pdfium_file_writer.as_fpdf_file_write_mut_ptr(),
+            0,
+        )) {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        pdfium_file_writer.flush().map_err(PdfiumError::IoError)?;
+    }
+
+    let page_count = bindings.FPDF_GetPageCount(document_handle).max(0) as usize;
+
+    Ok(tab_orders_from_file(&cursor.into_inner(), page_count))
+}
+
+/// Returns the tab order of each of the given number of pages in the given PDF file.
+fn tab_orders_from_file(file: &[u8], page_count: usize) -> Vec<PdfFormTabOrder> {
+    let mut result = vec![PdfFormTabOrder::Annotation; page_count];
+
+    if let Some(parsed) = PdfSyntaxFile::parse(file) {
+        if let Some(pages) = parsed.page_references() {
+            for (order, page) in result.iter_mut().zip(pages.iter()) {
+                if let Some(PdfSyntaxObject::Name(name)) = parsed.resolve(page).get("Tabs") {
+                    *order = PdfFormTabOrder::from_pdf_name(name);
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Returns the locations of the form field widgets on the given page, which has the given
+/// index, in the given tab order.
+pub(crate) fn widgets_in_tab_order(
+    page: &PdfPage,
+    page_index: PdfPageIndex,
+    order: PdfFormTabOrder,
+) -> Result<Vec<PdfFormWidgetLocation>, PdfiumError> {
+    let mut widgets = Vec::new();
+
+    for (index, annotation) in page.annotations().iter().enumerate() {
+        if let PdfPageAnnotation::Widget(widget) = &annotation {
+            widgets.push((index as PdfPageAnnotationIndex, widget.bounds()?));
+        }
+    }
+
+    Ok(sort_widgets(widgets, order)
+        .into_iter()
+        .map(|index| PdfFormWidgetLocation::new(page_index, index))
+        .collect())
+}
+
+/// Arranges the given widgets, identified by annotation index, in the given tab order.
+///
+/// Row and column orders follow Pdfium's own focus traversal: the topmost (or leftmost)
+/// remaining widget starts a new row (or column), which also takes in every other remaining
+/// widget whose center lies within the extent of that widget.
+fn sort_widgets(
+    mut widgets: Vec<(PdfPageAnnotationIndex, PdfRect)>,
+    order: PdfFormTabOrder,
+) -> Vec<PdfPageAnnotationIndex> {
+    match order {
+        PdfFormTabOrder::Annotation | PdfFormTabOrder::Structure => {
+            return widgets.into_iter().map(|(index, _)| index).collect();
+        }
+        PdfFormTabOrder::Row => widgets.sort_by_key(|(_, bounds)| bounds.left),
+        PdfFormTabOrder::Column => widgets.sort_by_key(|(_, bounds)| Reverse(bounds.top)),
+    }
+
+    let mut result = Vec::with_capacity(widgets.len());
+
+    while !widgets.is_empty() {
+        let first = match order {
+            PdfFormTabOrder::Row => widgets.iter().max_by(|(_, a), (_, b)| a.top.cmp(&b.top)),
+            _ => widgets.iter().min_by(|(_, a), (_, b)| a.left.cmp(&b.left)),
+        }
+        .map(|(_, bounds)| *bounds)
+        .unwrap();
+
+        let (group, remaining): (Vec<_>, Vec<_>) =
+            widgets.into_iter().partition(|(_, bounds)| match order {
+                PdfFormTabOrder::Row => {
+                    let center = (bounds.top + bounds.bottom) / 2.0;
+
+                    center >= first.bottom && center <= first.top
+                }
+                _ => {
+                    let center = (bounds.left + bounds.right) / 2.0;
+
+                    center >= first.left && center <= first.right
+                }
+            });
+
+        result.extend(group.into_iter().map(|(index, _)| index));
+
+        widgets = remaining;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pdf::points::PdfPoints;
+
+    fn rect(left: f32, bottom: f32, right: f32, top: f32) -> PdfRect {
+        PdfRect::new(
+            PdfPoints::new(bottom),
+            PdfPoints::new(left),
+            PdfPoints::new(top),
+            PdfPoints::new(right),
+        )
+    }
+
+    #[test]
+    fn test_sort_widgets() {
+        // Two rows of two widgets, given in an order matching neither rows nor columns.
+
+        let widgets = vec![
+            (0, rect(300.0, 500.0, 400.0, 520.0)),
+            (1, rect(100.0, 600.0, 200.0, 620.0)),
+            (2, rect(100.0, 498.0, 200.0, 518.0)),
+            (3, rect(300.0, 602.0, 400.0, 622.0)),
+        ];
+
+        assert_eq!(
+            sort_widgets(widgets.clone(), PdfFormTabOrder::Annotation),
+            vec![0, 1, 2, 3]
+        );
+        assert_eq!(
+            sort_widgets(widgets.clone(), PdfFormTabOrder::Row),
+            vec![1, 3, 2, 0]
+        );
+        assert_eq!(
+            sort_widgets(widgets, PdfFormTabOrder::Column),
+            vec![1, 2, 3, 0]
+        );
+    }
+
+    #[test]
+    fn test_tab_orders_from_file() {
+        let file = b"%PDF-1.7\n\
+            1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+            2 0 obj\n<< /Type /Pages /Kids [3 0 R 4 0 R 5 0 R] /Count 3 >>\nendobj\n\
+            3 0 obj\n<< /Type /Page /Parent 2 0 R /Tabs /R >>\nendobj\n\
+            4 0 obj\n<< /Type /Page /Parent 2 0 R >>\nendobj\n\
+            5 0 obj\n<< /Type /Page /Parent 2 0 R /Tabs /C >>\nendobj\n\
+            trailer\n<< /Size 6 /Root 1 0 R >>\n%%EOF\n";
+
+        assert_eq!(
+            tab_orders_from_file(file, 3),
+            vec![
+                PdfFormTabOrder::Row,
+                PdfFormTabOrder::Annotation,
+                PdfFormTabOrder::Column
+            ]
+        );
+    }
+}
@@ -0,0 +1,411 @@
+//! Defines the [PdfBookletConfig] struct, which controls how the pages of a `PdfDocument`
+//! are imposed onto printer sheets by the `PdfDocument::booklet()` function, along with
+//! the imposition functions backing `PdfDocument::n_up()` and `PdfDocument::booklet()`.
+
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::merge::create_document;
+use crate::pdf::document::page::object::PdfPageObject;
+use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
+use crate::pdf::document::page::size::PdfPagePaperSize;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::PdfDocument;
+use crate::pdf::points::PdfPoints;
+use std::os::raw::c_int;
+
+/// The stroke width of crop marks drawn by `PdfDocument::booklet()`.
+const CROP_MARK_STROKE_WIDTH: PdfPoints = PdfPoints::new(0.25);
+
+/// Configures how the pages of a `PdfDocument` are imposed onto printer sheets by the
+/// `PdfDocument::booklet()` function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfBookletConfig {
+    sheet_size: Option<PdfPagePaperSize>,
+    gutter: PdfPoints,
+    margin: PdfPoints,
+    crop_marks: bool,
+    crop_mark_length: PdfPoints,
+    crop_mark_offset: PdfPoints,
+}
+
+impl PdfBookletConfig {
+    /// Creates a new [PdfBookletConfig] with default settings: each sheet is exactly wide
+    /// enough to hold two of the largest pages in the document side by side at their original
+    /// size, with no gutter or margin, and no crop marks are drawn.
+    #[inline]
+    pub fn new() -> Self {
+        PdfBookletConfig {
+            sheet_size: None,
+            gutter: PdfPoints::ZERO,
+            margin: PdfPoints::ZERO,
+            crop_marks: false,
+            crop_mark_length: PdfPoints::new(18.0),
+            crop_mark_offset: PdfPoints::new(6.0),
+        }
+    }
+
+    /// Sets the size of each printer sheet. Pages are scaled to fit the space available
+    /// on each half of the sheet once the gutter and margin have been taken into account.
+    /// By default, the sheet size is calculated from the size of the largest page in the
+    /// document so that no scaling is necessary.
+    #[inline]
+    pub fn set_sheet_size(mut self, size: PdfPagePaperSize) -> Self {
+        self.sheet_size = Some(size);
+
+        self
+    }
+
+    /// Sets the width of the gap left between the two pages on each side of a sheet,
+    /// either side of the fold. The default is no gap.
+    #[inline]
+    pub fn set_gutter(mut self, gutter: PdfPoints) -> Self {
+        self.gutter = gutter.max(PdfPoints::ZERO);
+
+        self
+    }
+
+    /// Sets the width of the margin left around the edges of each sheet. Crop marks are
+    /// drawn in this margin, so it should be at least as wide as the sum of the crop mark
+    /// offset and length if crop marks are enabled. The default is no margin.
+    #[inline]
+    pub fn set_margin(mut self, margin: PdfPoints) -> Self {
+        self.margin = margin.max(PdfPoints::ZERO);
+
+        self
+    }
+
+    /// Controls whether crop marks should be drawn at the corners of each imposed page,
+    /// indicating where the printed sheets should be trimmed. The default is `false`.
+    #[inline]
+    pub fn draw_crop_marks(mut self, draw: bool) -> Self {
+        self.crop_marks = draw;
+
+        self
+    }
+
+    /// Sets the length of each crop mark. The default is 18 points.
+    #[inline]
+    pub fn set_crop_mark_length(mut self, length: PdfPoints) -> Self {
+        self.crop_mark_length = length.max(PdfPoints::ZERO);
+
+        self
+    }
+
+    /// Sets the distance between each crop mark and the corner of the page it marks.
+    /// The default is 6 points.
+    #[inline]
+    pub fn set_crop_mark_offset(mut self, offset: PdfPoints) -> Self {
+        self.crop_mark_offset = offset.max(PdfPoints::ZERO);
+
+        self
+    }
+}
+
+impl Default for PdfBookletConfig {
+    #[inline]
+    fn default() -> Self {
+        PdfBookletConfig::new()
+    }
+}
+
+/// Creates a new [PdfDocument] by tiling the pages of the given document into grids of the
+/// given number of columns and rows on pages of the given size. See `PdfDocument::n_up()`.
+pub(crate) fn n_up_document<'a>(
+    document: &PdfDocument<'a>,
+    columns: u8,
+    rows: u8,
+    size: PdfPagePaperSize,
+) -> Result<PdfDocument<'a>, PdfiumError> {
+    if columns == 0 || rows == 0 {
+        return Err(PdfiumError::InvalidImpositionLayout);
+    }
+
+    document.pages().tile_into_new_document(rows, columns, size)
+}
+
+/// Creates a new [PdfDocument] containing the pages of the given document imposed two to
+/// a side onto printer sheets in saddle-stitch order. See `PdfDocument::booklet()`.
+pub(crate) fn booklet_document<'a>(
+    document: &PdfDocument<'a>,
+    config: &PdfBookletConfig,
+) -> Result<PdfDocument<'a>, PdfiumError> {
+    let bindings = document.bindings();
+
+    let sizes = document.pages().page_sizes()?;
+
+    if sizes.is_empty() {
+        return Err(PdfiumError::NoPagesInDocument);
+    }
+
+    // Blank pages padding the booklet are treated as being the size of the largest page.
+
+    let largest_width = sizes
+        .iter()
+        .map(|size| size.width())
+        .max()
+        .unwrap_or(PdfPoints::ZERO);
+
+    let largest_height = sizes
+        .iter()
+        .map(|size| size.height())
+        .max()
+        .unwrap_or(PdfPoints::ZERO);
+
+    let (sheet_width, sheet_height) = match config.sheet_size {
+        Some(size) => (size.width(), size.height()),
+        None => (
+            largest_width * 2.0 + config.gutter + config.margin * 2.0,
+            largest_height + config.margin * 2.0,
+        ),
+    };
+
+    let cell_width = (sheet_width - config.gutter - config.margin * 2.0) / 2.0;
+
+    let cell_height = sheet_height - config.margin * 2.0;
+
+    if cell_width.value <= 0.0 || cell_height.value <= 0.0 {
+        return Err(PdfiumError::InvalidImpositionLayout);
+    }
+
+    let mut booklet = create_document(bindings)?;
+
+    for side in booklet_page_order(sizes.len()) {
+        let mut sheet = booklet
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::Custom(sheet_width, sheet_height))?;
+
+        for (position, index) in side.iter().enumerate() {
+            let (width, height) = match index {
+                Some(index) => (
+                    sizes[*index as usize].width(),
+                    sizes[*index as usize].height(),
+                ),
+                None => (largest_width, largest_height),
+            };
+
+            let scale = (cell_width.value / width.value).min(cell_height.value / height.value);
+
+            let width = width * scale;
+
+            let height = height * scale;
+
+            // Pages are placed against the fold, so that the distance between facing pages
+            // in the bound booklet is exactly the gutter.
+
+            let is_left = position == 0;
+
+            let left = if is_left {
+                config.margin + cell_width - width
+            } else {
+                config.margin + cell_width + config.gutter
+            };
+
+            let bottom = config.margin + (cell_height - height) / 2.0;
+
+            if let Some(index) = index {
+                place_page(
+                    bindings, document, *index, &booklet, &mut sheet, scale, left, bottom,
+                )?;
+            }
+
+            if config.crop_marks {
+                draw_crop_marks(&mut sheet, config, is_left, left, bottom, width, height)?;
+            }
+        }
+    }
+
+    Ok(booklet)
+}
+
+/// Returns the indices of the pages placed on the left and right halves of each side of each
+/// printer sheet in a saddle-stitched booklet of the given number of pages, in printing order:
+/// the front of the first sheet, then its back, then the front of the second sheet, and so on.
+/// The page count is padded to a multiple of four; padding pages are returned as `None`.
+fn booklet_page_order(page_count: usize) -> Vec<[Option<PdfPageIndex>; 2]> {
+    let padded = (page_count + 3) / 4 * 4;
+
+    let page = |index: usize| {
+        if index < page_count {
+            Some(index as PdfPageIndex)
+        } else {
+            None
+        }
+    };
+
+    let mut sides = Vec::with_capacity(padded / 2);
+
+    for sheet in 0..padded / 4 {
+        sides.push([page(padded - 1 - sheet * 2), page(sheet * 2)]);
+        sides.push([page(sheet * 2 + 1), page(padded - 2 - sheet * 2)]);
+    }
+
+    sides
+}
+
+/// Draws the page at the given index in the given source document onto the given sheet as
+/// a form object, scaled by the given factor and with its origin at the given position.
+#[allow(clippy::too_many_arguments)]
+fn place_page<'a>(
+    bindings: &'a dyn PdfiumLibraryBindings,
+    source: &PdfDocument,
+    index: PdfPageIndex,
+    destination: &PdfDocument,
+    sheet: &mut PdfPage<'a>,
+    scale: f32,
+    left: PdfPoints,
+    bottom: PdfPoints,
+) -> Result<(), PdfiumError> {
+    let x_object =
+        bindings.FPDF_NewXObjectFromPage(destination.handle(), source.handle(), index as c_int);
+
+    if x_object.is_null() {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    let handle = bindings.FPDF_NewFormObjectFromXObject(x_object);
+
+    // Form objects created from the x-object are not affected by closing it.
+
+    bindings.FPDF_CloseXObject(x_object);
+
+    if handle.is_null() {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    let mut object = PdfPageObject::from_pdfium(handle, None, None, bindings);
+
+    object.transform(scale, 0.0, 0.0, scale, left.value, bottom.value)?;
+
+    sheet.objects_mut().add_object(object)?;
+
+    Ok(())
+}
+
+/// Draws crop marks at the corners of the page with the given bounds on the given sheet.
+/// Horizontal marks are only drawn on the outer side of the page, so that they do not
+/// intrude into the gutter or onto the facing page.
+fn draw_crop_marks(
+    sheet: &mut PdfPage,
+    config: &PdfBookletConfig,
+    is_left: bool,
+    left: PdfPoints,
+    bottom: PdfPoints,
+    width: PdfPoints,
+    height: PdfPoints,
+) -> Result<(), PdfiumError> {
+    let offset = config.crop_mark_offset;
+
+    let length = config.crop_mark_length;
+
+    let top = bottom + height;
+
+    let right = left + width;
+
+    let outer = if is_left { left } else { right };
+
+    let outward = if is_left { -1.0 } else { 1.0 };
+
+    let objects = sheet.objects_mut();
+
+    for x in [left, right] {
+        objects.create_path_object_line(
+            x,
+            bottom - offset,
+            x,
+            bottom - offset - length,
+            PdfColor::BLACK,
+            CROP_MARK_STROKE_WIDTH,
+        )?;
+
+        objects.create_path_object_line(
+            x,
+            top + offset,
+            x,
+            top + offset + length,
+            PdfColor::BLACK,
+            CROP_MARK_STROKE_WIDTH,
+        )?;
+    }
+
+    for y in [bottom, top] {
+        objects.create_path_object_line(
+            outer + offset * outward,
+            y,
+            outer + (offset + length) * outward,
+            y,
+            PdfColor::BLACK,
+            CROP_MARK_STROKE_WIDTH,
+        )?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::imposition::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_n_up() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page_count = document.pages().len();
+
+        let size = PdfPagePaperSize::a4().landscape();
+
+        let n_up = document.n_up(3, 2, size)?;
+
+        assert_eq!(n_up.pages().len(), (page_count + 5) / 6);
+
+        for page in n_up.pages().iter() {
+            assert_eq!(page.width(), size.width());
+            assert_eq!(page.height(), size.height());
+        }
+
+        assert!(matches!(
+            document.n_up(0, 2, size),
+            Err(PdfiumError::InvalidImpositionLayout)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_booklet_page_order() {
+        assert_eq!(
+            booklet_page_order(8),
+            vec![
+                [Some(7), Some(0)],
+                [Some(1), Some(6)],
+                [Some(5), Some(2)],
+                [Some(3), Some(4)],
+            ]
+        );
+
+        // Page counts that are not a multiple of four are padded with blank pages,
+        // which fall at the end of the booklet.
+
+        assert_eq!(
+            booklet_page_order(6),
+            vec![
+                [None, Some(0)],
+                [Some(1), None],
+                [Some(5), Some(2)],
+                [Some(3), Some(4)],
+            ]
+        );
+
+        assert_eq!(booklet_page_order(1), vec![[None, Some(0)], [None, None]]);
+
+        assert!(booklet_page_order(0).is_empty());
+    }
+}
@@ -0,0 +1,307 @@
+//! Defines the [PdfJavaScriptAction] struct, a single document-level JavaScript action in a
+//! `PdfDocument`, as returned by the `PdfDocument::javascript()` function, and supports
+//! the removal of every JavaScript action from a document via the
+//! `PdfDocument::remove_all_javascript()` function.
+
+use crate::bindgen::{FPDF_DOCUMENT, FPDF_JAVASCRIPT_ACTION, FPDF_WCHAR};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use crate::utils::mem::create_byte_buffer;
+use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use std::collections::HashSet;
+use std::os::raw::c_ulong;
+
+/// A single document-level JavaScript action in a `PdfDocument`. Document-level actions
+/// are listed in the `/JavaScript` name tree of the document catalog, and are run by
+/// a viewer when the document is opened.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfJavaScriptAction {
+    name: String,
+    script: String,
+}
+
+impl PdfJavaScriptAction {
+    /// Returns the name under which this [PdfJavaScriptAction] is listed in the document.
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the JavaScript source code run by this [PdfJavaScriptAction].
+    #[inline]
+    pub fn script(&self) -> &str {
+        self.script.as_str()
+    }
+}
+
+/// Returns every document-level JavaScript action in the document with the given handle,
+/// in the order in which they are listed in the document.
+pub(crate) fn collect_document_javascript(
+    document_handle: FPDF_DOCUMENT,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Vec<PdfJavaScriptAction> {
+    let count = bindings.FPDFDoc_GetJavaScriptActionCount(document_handle);
+
+    let mut result = Vec::with_capacity(count.max(0) as usize);
+
+    for index in 0..count {
+        let handle = bindings.FPDFDoc_GetJavaScriptAction(document_handle, index);
+
+        if handle.is_null() {
+            continue;
+        }
+
+        let name = get_javascript_action_string(handle, |handle, buffer, length| {
+            bindings.FPDFJavaScriptAction_GetName(handle, buffer, length)
+        });
+
+        let script = get_javascript_action_string(handle, |handle, buffer, length| {
+            bindings.FPDFJavaScriptAction_GetScript(handle, buffer, length)
+        });
+
+        bindings.FPDFDoc_CloseJavaScriptAction(handle);
+
+        result.push(PdfJavaScriptAction {
+            name: name.unwrap_or_default(),
+            script: script.unwrap_or_default(),
+        });
+    }
+
+    result
+}
+
+/// Retrieves a string from the JavaScript action with the given handle using the given
+/// Pdfium function, which is either `FPDFJavaScriptAction_GetName()` or
+/// `FPDFJavaScriptAction_GetScript()`.
+fn get_javascript_action_string(
+    handle: FPDF_JAVASCRIPT_ACTION,
+    get: impl Fn(FPDF_JAVASCRIPT_ACTION, *mut FPDF_WCHAR, c_ulong) -> c_ulong,
+) -> Option<String> {
+    // Retrieving the string from Pdfium is a two-step operation. First, we call the
+    // Pdfium function with a null buffer; this will retrieve the length of the string
+    // in bytes. A length of 2 indicates an empty string, consisting only of the
+    // null terminator.
+
+    // If the length is greater than 2, then we reserve a byte buffer of the given
+    // length and call the Pdfium function again with a pointer to the buffer;
+    // this will write the string to the buffer in UTF16LE format.
+
+    let buffer_length = get(handle, std::ptr::null_mut(), 0);
+
+    if buffer_length <= 2 {
+        return None;
+    }
+
+    let mut buffer = create_byte_buffer(buffer_length as usize);
+
+    let result = get(
+        handle,
+        buffer.as_mut_ptr() as *mut FPDF_WCHAR,
+        buffer_length,
+    );
+
+    debug_assert_eq!(result, buffer_length);
+
+    get_string_from_pdfium_utf16le_bytes(buffer)
+}
+
+/// Removes every JavaScript action from the given PDF file, returning the updated file,
+/// or `None` if the file contains no JavaScript actions. The changes are applied in an
+/// incremental update appended to the end of the file.
+///
+/// The `/JavaScript` name tree of the document catalog is removed, as is every entry in an
+/// action dictionary, additional-actions dictionary, or array of actions that refers to
+/// a JavaScript action. The JavaScript action objects themselves, and any streams holding
+/// their scripts, are replaced with null objects, so that they are not written when
+/// Pdfium next saves the file.
+pub(crate) fn remove_javascript_actions(file: Vec<u8>) -> Result<Option<Vec<u8>>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if update.trailer().get("Encrypt").is_some() {
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    // Object numbers are visited in order so that the update is the same each time
+    // the same file is processed.
+
+    let mut numbers = parsed
+        .objects
+        .iter()
+        .filter(|(_, indirect)| !indirect.is_stream)
+        .map(|(number, _)| *number)
+        .collect::<Vec<_>>();
+
+    numbers.sort_unstable();
+
+    let actions = numbers
+        .iter()
+        .copied()
+        .filter(|number| is_javascript_action(&parsed.objects[number].object))
+        .collect::<HashSet<_>>();
+
+    let mut is_changed = false;
+
+    for number in numbers.iter() {
+        if actions.contains(number) {
+            // Scripts longer than a few lines are usually held in a separate stream.
+
+            if let Some(PdfSyntaxObject::Reference(script, _)) =
+                parsed.objects[number].object.get("JS")
+            {
+                update.set(*script, PdfSyntaxObject::Null);
+            }
+
+            update.set(*number, PdfSyntaxObject::Null);
+
+            is_changed = true;
+        } else {
+            let mut object = parsed.objects[number].object.clone();
+
+            if strip_javascript(&mut object, &actions) {
+                update.set(*number, object);
+
+                is_changed = true;
+            }
+        }
+    }
+
+    Ok(if is_changed {
+        Some(update.write(file))
+    } else {
+        None
+    })
+}
+
+/// Returns `true` if the given object is a JavaScript action dictionary.
+#[inline]
+fn is_javascript_action(object: &PdfSyntaxObject) -> bool {
+    object.get("S") == Some(&PdfSyntaxObject::name("JavaScript"))
+}
+
+/// Returns `true` if the given object is a JavaScript action dictionary, or a reference to
+/// one of the given indirect JavaScript action objects.
+#[inline]
+fn refers_to_javascript_action(object: &PdfSyntaxObject, actions: &HashSet<u32>) -> bool {
+    match object {
+        PdfSyntaxObject::Reference(number, _) => actions.contains(number),
+        _ => is_javascript_action(object),
+    }
+}
+
+/// Removes every reference to a JavaScript action from the given object and the objects
+/// nested inside it, along with any `/JavaScript` name tree. Returns `true` if the object
+/// was changed.
+fn strip_javascript(object: &mut PdfSyntaxObject, actions: &HashSet<u32>) -> bool {
+    match object {
+        PdfSyntaxObject::Dictionary(entries) => {
+            let length = entries.len();
+
+            entries.retain(|(key, value)| {
+                key != "JavaScript" && !refers_to_javascript_action(value, actions)
+            });
+
+            let mut is_changed = entries.len() != length;
+
+            for (_, value) in entries.iter_mut() {
+                is_changed |= strip_javascript(value, actions);
+            }
+
+            is_changed
+        }
+        PdfSyntaxObject::Array(values) => {
+            let length = values.len();
+
+            values.retain(|value| !refers_to_javascript_action(value, actions));
+
+            let mut is_changed = values.len() != length;
+
+            for value in values.iter_mut() {
+                is_changed |= strip_javascript(value, actions);
+            }
+
+            is_changed
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_file() -> Vec<u8> {
+        let mut file = b"%PDF-1.7\n".to_vec();
+
+        let mut offsets = Vec::new();
+
+        for (index, object) in [
+            "<< /Type /Catalog /Pages 2 0 R /Names << /JavaScript 4 0 R >> \
+               /OpenAction << /S /JavaScript /JS (app.alert\\('Hi'\\);) >> >>",
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>",
+            "<< /Type /Page /Parent 2 0 R /AA << /O 5 0 R /C << /S /GoTo /D [3 0 R /Fit] >> >> >>",
+            "<< /Names [(Init) 5 0 R] >>",
+            "<< /S /JavaScript /JS 6 0 R /Next [7 0 R] >>",
+            "(this.print\\(\\);)",
+            "<< /S /GoTo /D [3 0 R /Fit] >>",
+        ]
+        .iter()
+        .enumerate()
+        {
+            offsets.push(file.len());
+            file.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).as_bytes());
+        }
+
+        let start_xref = file.len();
+
+        file.extend_from_slice(b"xref\n0 8\n0000000000 65535 f \n");
+
+        for offset in offsets {
+            file.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+
+        file.extend_from_slice(
+            format!(
+                "trailer\n<< /Size 8 /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                start_xref
+            )
+            .as_bytes(),
+        );
+
+        file
+    }
+
+    #[test]
+    fn test_remove_javascript_actions() {
+        let file = remove_javascript_actions(test_file()).unwrap().unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let catalog = &parsed.objects[&1].object;
+
+        assert_eq!(catalog.get("OpenAction"), None);
+        assert_eq!(
+            catalog.get("Names"),
+            Some(&PdfSyntaxObject::Dictionary(Vec::new()))
+        );
+
+        let page_actions = parsed.objects[&3].object.get("AA").unwrap();
+
+        assert_eq!(page_actions.get("O"), None);
+        assert!(page_actions.get("C").is_some());
+
+        assert_eq!(parsed.objects[&5].object, PdfSyntaxObject::Null);
+        assert_eq!(parsed.objects[&6].object, PdfSyntaxObject::Null);
+        assert_eq!(
+            parsed.objects[&7].object.get("S"),
+            Some(&PdfSyntaxObject::name("GoTo"))
+        );
+
+        // The updated file contains no further JavaScript actions.
+
+        assert_eq!(remove_javascript_actions(file).unwrap(), None);
+    }
+}
@@ -0,0 +1,308 @@
+//! Defines the [PdfDocumentLinks] struct, an iterator over every clickable link in
+//! a `PdfDocument`, as returned by the `PdfDocument::links()` function.
+
+use crate::pdf::action::PdfAction;
+use crate::pdf::destination::PdfDestinationViewSettings;
+use crate::pdf::document::extracted_link::web_links;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use crate::pdf::link::PdfLink;
+use crate::pdf::rect::PdfRect;
+use std::collections::VecDeque;
+
+/// The target of a single [PdfLinkHit].
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfLinkHitTarget {
+    /// The link opens the given URI. Email addresses detected in page text are
+    /// given as `mailto:` URIs.
+    Uri(String),
+
+    /// The link navigates to the page at the given zero-based index in the same document,
+    /// applying the given view settings if the destination specifies any. Named destinations
+    /// are resolved to their target pages.
+    PageDestination(PdfPageIndex, Option<PdfDestinationViewSettings>),
+
+    /// The link opens a different document at the given file path.
+    RemoteDocument(String),
+
+    /// The link launches the application or opens the file at the given file path.
+    FileLaunch(String),
+}
+
+/// The location in a `PdfPage` at which a [PdfLinkHit] was found.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfLinkHitSource {
+    /// The link is a link annotation.
+    LinkAnnotation,
+
+    /// The link was detected by Pdfium in the text of the page, for example a URL or an
+    /// email address that is not covered by a link annotation.
+    WebLink,
+}
+
+/// A single clickable area in a `PdfDocument`, as returned by the `PdfDocument::links()`
+/// function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfLinkHit {
+    page_index: PdfPageIndex,
+    rect: PdfRect,
+    target: PdfLinkHitTarget,
+    source: PdfLinkHitSource,
+}
+
+impl PdfLinkHit {
+    /// Returns the zero-based index of the page containing this [PdfLinkHit].
+    #[inline]
+    pub fn page_index(&self) -> PdfPageIndex {
+        self.page_index
+    }
+
+    /// Returns the clickable area of this [PdfLinkHit] on its page.
+    #[inline]
+    pub fn rect(&self) -> PdfRect {
+        self.rect
+    }
+
+    /// Returns the target of this [PdfLinkHit].
+    #[inline]
+    pub fn target(&self) -> &PdfLinkHitTarget {
+        &self.target
+    }
+
+    /// Returns where on its page this [PdfLinkHit] was found.
+    #[inline]
+    pub fn source(&self) -> PdfLinkHitSource {
+        self.source
+    }
+}
+
+/// An iterator over every clickable link in a `PdfDocument`, in page order, as returned by the
+/// `PdfDocument::links()` function.
+///
+/// Pages are scanned lazily, one page at a time, as links are requested. Pages that cannot be
+/// loaded are skipped.
+pub struct PdfDocumentLinks<'a, 'b> {
+    pages: &'b PdfPages<'a>,
+    next_page_index: PdfPageIndex,
+    hits: VecDeque<PdfLinkHit>,
+}
+
+impl<'a, 'b> PdfDocumentLinks<'a, 'b> {
+    #[inline]
+    pub(crate) fn new(pages: &'b PdfPages<'a>) -> Self {
+        PdfDocumentLinks {
+            pages,
+            next_page_index: 0,
+            hits: VecDeque::new(),
+        }
+    }
+
+    /// Collects all links on the page at the given index, queueing them in the order in which
+    /// they are found: link annotations first, followed by web links.
+    fn scan_page(&mut self, index: PdfPageIndex) {
+        let page = match self.pages.get(index) {
+            Ok(page) => page,
+            Err(_) => return,
+        };
+
+        let mut hits: Vec<PdfLinkHit> = Vec::new();
+
+        for link in page.links().iter() {
+            let (rect, target) = match (link.rect(), link_target(&link)) {
+                (Ok(rect), Some(target)) => (rect, target),
+                _ => continue,
+            };
+
+            push_hit(
+                &mut hits,
+                PdfLinkHit {
+                    page_index: index,
+                    rect,
+                    target,
+                    source: PdfLinkHitSource::LinkAnnotation,
+                },
+            );
+        }
+
+        push_web_links(&mut hits, index, &page);
+
+        self.hits.extend(hits);
+    }
+}
+
+impl<'a, 'b> Iterator for PdfDocumentLinks<'a, 'b> {
+    type Item = PdfLinkHit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.hits.is_empty() {
+            if self.next_page_index >= self.pages.len() {
+                return None;
+            }
+
+            let index = self.next_page_index;
+
+            self.next_page_index += 1;
+
+            self.scan_page(index);
+        }
+
+        self.hits.pop_front()
+    }
+}
+
+/// Returns the resolved target of the given link, if it has a target that can be followed.
+fn link_target(link: &PdfLink) -> Option<PdfLinkHitTarget> {
+    match link.action() {
+        Some(PdfAction::Uri(action)) => action.uri().ok().map(PdfLinkHitTarget::Uri),
+        Some(PdfAction::LocalDestination(action)) => {
+            let destination = action.destination().ok()?;
+
+            Some(PdfLinkHitTarget::PageDestination(
+                destination.page_index().ok()?,
+                destination.view_settings().ok(),
+            ))
+        }
+        Some(PdfAction::RemoteDestination(action)) => action
+            .file_path()
+            .ok()
+            .map(PdfLinkHitTarget::RemoteDocument),
+        Some(PdfAction::Launch(action)) => {
+            action.file_path().ok().map(PdfLinkHitTarget::FileLaunch)
+        }
+        Some(_) => None,
+        None => {
+            let destination = link.destination()?;
+
+            Some(PdfLinkHitTarget::PageDestination(
+                destination.page_index().ok()?,
+                destination.view_settings().ok(),
+            ))
+        }
+    }
+}
+
+/// Adds a hit for every area covered by a web link Pdfium detects in the text of the given
+/// page, except where a link annotation with the same URI already covers the area.
+fn push_web_links(hits: &mut Vec<PdfLinkHit>, page_index: PdfPageIndex, page: &PdfPage) {
+    let text = match page.text() {
+        Ok(text) => text,
+        Err(_) => return,
+    };
+
+    for link in web_links(&text) {
+        let target = PdfLinkHitTarget::Uri(link.url);
+
+        for rect in link.rects {
+            let is_covered_by_annotation = hits.iter().any(|hit| {
+                hit.source == PdfLinkHitSource::LinkAnnotation
+                    && hit.target == target
+                    && hit.rect.does_overlap(&rect)
+            });
+
+            if !is_covered_by_annotation {
+                push_hit(
+                    hits,
+                    PdfLinkHit {
+                        page_index,
+                        rect,
+                        target: target.clone(),
+                        source: PdfLinkHitSource::WebLink,
+                    },
+                );
+            }
+        }
+    }
+}
+
+/// Adds the given hit, unless an identical hit has already been added.
+#[inline]
+fn push_hit(hits: &mut Vec<PdfLinkHit>, hit: PdfLinkHit) {
+    if !hits.contains(&hit) {
+        hits.push(hit);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_links() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/links-test.pdf", None)?;
+
+        let hits = document.links().collect::<Vec<_>>();
+
+        assert!(!hits.is_empty());
+
+        // Every hit should be unique, and should lie on an existing page.
+
+        for (index, hit) in hits.iter().enumerate() {
+            assert!(!hits[index + 1..].contains(hit));
+            assert!(hit.page_index() < document.pages().len());
+        }
+
+        // The test file's only link annotation opens the project's repository.
+
+        let is_known_link = |hit: &PdfLinkHit| {
+            let rect = hit.rect();
+
+            hit.page_index() == 0
+                && hit.source() == PdfLinkHitSource::LinkAnnotation
+                && (rect.left.value - 207.854).abs() < 0.01
+                && (rect.bottom.value - 733.363).abs() < 0.01
+                && (rect.right.value - 333.146).abs() < 0.01
+                && (rect.top.value - 757.613).abs() < 0.01
+        };
+
+        let known = hits
+            .iter()
+            .filter(|hit| is_known_link(hit))
+            .collect::<Vec<_>>();
+
+        assert_eq!(known.len(), 1);
+        assert_eq!(
+            known[0].target(),
+            &PdfLinkHitTarget::Uri("https://github.com/ajrcarey/pdfium-render".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_links_to_page() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.load_pdf_from_file("./test/links-test.pdf", None)?;
+
+        // Send every URI link to a new second page, then reload the document so that
+        // Pdfium can see the new destinations.
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        document.rewrite_links(|target| match target {
+            PdfLinkTarget::Uri(_) => PdfLinkRewrite::SetPage(1),
+            _ => PdfLinkRewrite::Keep,
+        })?;
+
+        let saved = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        let hits = saved
+            .links()
+            .filter(|hit| hit.source() == PdfLinkHitSource::LinkAnnotation)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].page_index(), 0);
+        assert!(matches!(
+            hits[0].target(),
+            PdfLinkHitTarget::PageDestination(1, _)
+        ));
+
+        Ok(())
+    }
+}
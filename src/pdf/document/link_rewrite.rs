@@ -0,0 +1,289 @@
+//! Defines the [PdfLinkTarget] and [PdfLinkRewrite] enums, used when rewriting the targets
+//! of link annotations across a `PdfDocument` using the `PdfDocument::rewrite_links()` function.
+
+use crate::error::PdfiumError;
+use crate::pdf::action::PdfAction;
+use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
+use crate::pdf::document::page::annotation::PdfPageAnnotation;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{decode_pdf_string, PdfSyntaxObject};
+
+/// The target of a single link annotation in a `PdfDocument`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PdfLinkTarget {
+    /// The link opens the given URI.
+    Uri(String),
+
+    /// The link navigates to the page at the given zero-based index in the same document.
+    Page(PdfPageIndex),
+}
+
+/// The change to make to a single link annotation, as returned from the callback
+/// passed to `PdfDocument::rewrite_links()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PdfLinkRewrite {
+    /// The link is left unchanged.
+    Keep,
+
+    /// The link is changed to open the given URI.
+    SetUri(String),
+
+    /// The link is changed to navigate to the page at the given zero-based index in the
+    /// same document, displaying the whole page.
+    SetPage(PdfPageIndex),
+
+    /// The link annotation is removed from its page.
+    Remove,
+}
+
+/// Returns the target of the given annotation, if it is a link annotation with
+/// a URI or local destination target. Targets set since the annotation's document was
+/// loaded, which Pdfium cannot see until the document is saved, take precedence.
+fn link_target(annotation: &PdfPageAnnotation) -> Option<PdfLinkTarget> {
+    let link = annotation.as_link_annotation()?;
+
+    if let Some(Some(PdfSyntaxObject::Array(destination))) =
+        PdfAnnotationDictionaryEdits::get(link, "Dest")
+    {
+        if let Some(PdfSyntaxObject::Number(index)) = destination.first() {
+            return Some(PdfLinkTarget::Page(*index as PdfPageIndex));
+        }
+    }
+
+    if let Some(Some(action)) = PdfAnnotationDictionaryEdits::get(link, "A") {
+        if let Some(PdfSyntaxObject::String(uri)) = action.get("URI") {
+            return Some(PdfLinkTarget::Uri(decode_pdf_string(uri)));
+        }
+    }
+
+    let link = link.link().ok()?;
+
+    match link.action() {
+        Some(PdfAction::Uri(action)) => action.uri().ok().map(PdfLinkTarget::Uri),
+        Some(PdfAction::LocalDestination(action)) => action
+            .destination()
+            .ok()?
+            .page_index()
+            .ok()
+            .map(PdfLinkTarget::Page),
+        Some(_) => None,
+        None => link
+            .destination()?
+            .page_index()
+            .ok()
+            .map(PdfLinkTarget::Page),
+    }
+}
+
+/// Passes the target of every link annotation on the given page to the given callback,
+/// applying the [PdfLinkRewrite] returned by the callback. The page's document must contain
+/// the given number of pages. Returns the number of link annotations that were changed
+/// or removed.
+///
+/// Pdfium can set the URI opened by a link, but cannot set a link's destination page, so
+/// destination pages are recorded and written when the document is saved.
+pub(crate) fn rewrite_links_on_page<F>(
+    page: &mut PdfPage,
+    page_count: PdfPageIndex,
+    callback: &mut F,
+) -> Result<usize, PdfiumError>
+where
+    F: FnMut(&PdfLinkTarget) -> PdfLinkRewrite,
+{
+    let mut removals = Vec::new();
+
+    let mut changes = 0;
+
+    for index in 0..page.annotations().len() {
+        let mut annotation = page.annotations().get(index)?;
+
+        let target = match link_target(&annotation) {
+            Some(target) => target,
+            None => continue,
+        };
+
+        match callback(&target) {
+            PdfLinkRewrite::Keep => {}
+            PdfLinkRewrite::SetUri(uri) => {
+                if target != PdfLinkTarget::Uri(uri.clone()) {
+                    if let Some(link) = annotation.as_link_annotation_mut() {
+                        link.set_link(&uri)?;
+
+                        if let PdfLinkTarget::Page(_) = target {
+                            // Pdfium sets the link's action, but leaves its destination
+                            // in place; the destination must be removed, and any destination
+                            // page recorded earlier replaced, when the document is saved.
+
+                            PdfAnnotationDictionaryEdits::set(
+                                link,
+                                "A",
+                                Some(PdfSyntaxObject::Dictionary(vec![
+                                    ("Type".to_string(), PdfSyntaxObject::name("Action")),
+                                    ("S".to_string(), PdfSyntaxObject::name("URI")),
+                                    (
+                                        "URI".to_string(),
+                                        PdfSyntaxObject::String(uri.as_bytes().to_vec()),
+                                    ),
+                                ])),
+                            )?;
+
+                            PdfAnnotationDictionaryEdits::set(link, "Dest", None)?;
+                        }
+
+                        changes += 1;
+                    }
+                }
+            }
+            PdfLinkRewrite::SetPage(page_index) => {
+                if page_index >= page_count {
+                    return Err(PdfiumError::PageIndexOutOfBounds);
+                }
+
+                if target != PdfLinkTarget::Page(page_index) {
+                    if let Some(link) = annotation.as_link_annotation_mut() {
+                        PdfAnnotationDictionaryEdits::set_page_destination(
+                            link, "Dest", page_index,
+                        )?;
+
+                        PdfAnnotationDictionaryEdits::set(link, "A", None)?;
+
+                        changes += 1;
+                    }
+                }
+            }
+            PdfLinkRewrite::Remove => removals.push(index),
+        }
+    }
+
+    // Remove annotations in reverse order, so that removing one annotation does not change
+    // the index of any annotation still to be removed.
+
+    for index in removals.into_iter().rev() {
+        let annotation = page.annotations().get(index)?;
+
+        page.annotations_mut().delete_annotation(annotation)?;
+
+        changes += 1;
+    }
+
+    Ok(changes)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_rewrite_links() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.load_pdf_from_file("./test/links-test.pdf", None)?;
+
+        // Force every URI link to use HTTPS, leaving page links untouched.
+
+        document.rewrite_links(|target| match target {
+            PdfLinkTarget::Uri(uri) if uri.starts_with("http://") => {
+                PdfLinkRewrite::SetUri(uri.replacen("http://", "https://", 1))
+            }
+            _ => PdfLinkRewrite::Keep,
+        })?;
+
+        let mut insecure = 0;
+
+        document.rewrite_links(|target| {
+            if let PdfLinkTarget::Uri(uri) = target {
+                if uri.starts_with("http://") {
+                    insecure += 1;
+                }
+            }
+
+            PdfLinkRewrite::Keep
+        })?;
+
+        assert_eq!(insecure, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_retarget_links_to_page() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.load_pdf_from_file("./test/links-test.pdf", None)?;
+
+        let targets = |document: &mut PdfDocument| -> Result<Vec<PdfLinkTarget>, PdfiumError> {
+            let mut targets = Vec::new();
+
+            document.rewrite_links(|target| {
+                targets.push(target.clone());
+
+                PdfLinkRewrite::Keep
+            })?;
+
+            Ok(targets)
+        };
+
+        let original = targets(&mut document)?;
+
+        let uris = original
+            .iter()
+            .filter(|target| matches!(target, PdfLinkTarget::Uri(_)))
+            .count();
+
+        assert!(uris > 0);
+
+        assert!(matches!(
+            document.rewrite_links(|_| PdfLinkRewrite::SetPage(9999)),
+            Err(PdfiumError::PageIndexOutOfBounds)
+        ));
+
+        // Send every URI link to the first page instead.
+
+        let changes = document.rewrite_links(|target| match target {
+            PdfLinkTarget::Uri(_) => PdfLinkRewrite::SetPage(0),
+            _ => PdfLinkRewrite::Keep,
+        })?;
+
+        assert_eq!(changes, uris);
+
+        let expected = original
+            .iter()
+            .map(|target| match target {
+                PdfLinkTarget::Uri(_) => PdfLinkTarget::Page(0),
+                target => target.clone(),
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(targets(&mut document)?, expected);
+
+        // The new destinations are written when the document is saved.
+
+        let mut saved = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        assert_eq!(targets(&mut saved)?, expected);
+
+        // Links retargeted to a page can be changed back to open a URI.
+
+        saved.rewrite_links(|target| match target {
+            PdfLinkTarget::Page(0) => PdfLinkRewrite::SetUri("https://example.com/".to_string()),
+            _ => PdfLinkRewrite::Keep,
+        })?;
+
+        let mut saved = pdfium.load_pdf_from_byte_vec(saved.save_to_bytes()?, None)?;
+
+        let targets = targets(&mut saved)?;
+
+        assert!(!targets.contains(&PdfLinkTarget::Page(0)));
+        assert!(
+            targets
+                .iter()
+                .filter(|target| **target == PdfLinkTarget::Uri("https://example.com/".to_string()))
+                .count()
+                >= uris
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,594 @@
+//! Defines the [PdfDocumentLoader] struct, used to open a [PdfDocument] progressively
+//! while its data is still being downloaded.
+
+use crate::bindgen::{
+    FPDF_AVAIL, FPDF_FILEACCESS, FX_DOWNLOADHINTS, FX_FILEAVAIL, PDF_DATA_AVAIL, PDF_DATA_NOTAVAIL,
+    PDF_FORM_AVAIL, PDF_FORM_NOTAVAIL, PDF_FORM_NOTEXIST, PDF_LINEARIZED, PDF_NOT_LINEARIZED,
+};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::PdfDocument;
+use crate::pdfium::Pdfium;
+use std::convert::TryFrom;
+use std::ops::Range;
+use std::os::raw::{c_int, c_uchar, c_ulong, c_void};
+use std::ptr::null_mut;
+use std::slice;
+
+/// Opens a [PdfDocument] progressively, while its data is still arriving over the network.
+///
+/// Pdfium can open a linearized ("fast web view") document, and render its first page, once
+/// only a small portion of the file has been received. Data is passed to the loader as it
+/// arrives using the [PdfDocumentLoader::add_data()] function, in any order. After each new
+/// block of data arrives, an application typically:
+/// * calls [PdfDocumentLoader::is_document_ready()] until it returns `true`, then opens the
+///   document using [PdfDocumentLoader::open_document()];
+/// * calls [PdfDocumentLoader::is_page_ready()] for the pages it wants to display,
+///   loading each page from [PdfDocumentLoader::document()] once it is ready;
+/// * calls [PdfDocumentLoader::take_download_hints()] to find out which byte ranges
+///   Pdfium needs next, and prioritizes downloading those ranges.
+///
+/// Documents that are not linearized can still be loaded this way, but Pdfium will not
+/// consider the document ready until all of it has been received.
+///
+/// Once all the document's data has been received, the loader can be converted into a
+/// standalone [PdfDocument] using the [PdfDocumentLoader::into_document()] function.
+///
+/// This struct is not available when compiling to WASM.
+pub struct PdfDocumentLoader<'a> {
+    // The document is declared before the source so that it is closed before the source's
+    // availability provider and data are released.
+    document: Option<PdfDocument<'a>>,
+    source: PdfDocumentLoaderSource<'a>,
+    hints: Vec<Range<usize>>,
+}
+
+impl<'a> PdfDocumentLoader<'a> {
+    /// Creates a new [PdfDocumentLoader] for a document with the given total length in bytes.
+    /// The total length of a document being downloaded over HTTP is usually available from
+    /// the `Content-Length` header of the response.
+    pub fn new(pdfium: &'a Pdfium, content_length: usize) -> Result<Self, PdfiumError> {
+        let bindings = pdfium.bindings();
+
+        let mut data = Box::new(PdfDocumentLoaderData {
+            file_avail: FX_FILEAVAIL {
+                version: 1,
+                IsDataAvail: Some(is_data_available_callback),
+            },
+            file_access: FPDF_FILEACCESS {
+                m_FileLen: c_ulong::try_from(content_length)
+                    .map_err(|_| PdfiumError::LoaderDataOutOfBounds)?,
+                m_GetBlock: Some(get_block_callback),
+                m_Param: null_mut(), // We'll set this value in just a moment.
+            },
+            bytes: vec![0; content_length],
+            available: PdfByteRanges::default(),
+        });
+
+        let data_ptr: *mut PdfDocumentLoaderData = data.as_mut();
+
+        data.file_access.m_Param = data_ptr as *mut c_void;
+
+        let avail = bindings.FPDFAvail_Create(&mut data.file_avail, &mut data.file_access);
+
+        if avail.is_null() {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        Ok(PdfDocumentLoader {
+            document: None,
+            source: PdfDocumentLoaderSource {
+                avail,
+                data,
+                bindings,
+            },
+            hints: Vec::new(),
+        })
+    }
+
+    /// Returns the [PdfiumLibraryBindings] used by this [PdfDocumentLoader].
+    #[inline]
+    pub fn bindings(&self) -> &'a dyn PdfiumLibraryBindings {
+        self.source.bindings
+    }
+
+    /// Returns the total length of the document, in bytes.
+    #[inline]
+    pub fn content_length(&self) -> usize {
+        self.source.data.bytes.len()
+    }
+
+    /// Passes the given block of data, starting at the given byte offset into the document,
+    /// to this [PdfDocumentLoader]. Blocks may be added in any order, and may overlap blocks
+    /// that were added previously.
+    ///
+    /// Returns [PdfiumError::LoaderDataOutOfBounds] if the block extends past the end of the
+    /// document.
+    pub fn add_data(&mut self, offset: usize, data: &[u8]) -> Result<(), PdfiumError> {
+        let end = offset
+            .checked_add(data.len())
+            .filter(|end| *end <= self.content_length())
+            .ok_or(PdfiumError::LoaderDataOutOfBounds)?;
+
+        self.source.data.bytes[offset..end].copy_from_slice(data);
+        self.source.data.available.insert(offset..end);
+
+        Ok(())
+    }
+
+    /// Returns `true` if every byte in the given range has been passed to this
+    /// [PdfDocumentLoader].
+    #[inline]
+    pub fn is_data_available(&self, range: Range<usize>) -> bool {
+        self.source.data.is_available(range)
+    }
+
+    /// Returns `true` if the entire document has been passed to this [PdfDocumentLoader].
+    #[inline]
+    pub fn is_complete(&self) -> bool {
+        self.is_data_available(0..self.content_length())
+    }
+
+    /// Returns `Some(true)` if the document is linearized, `Some(false)` if it is not, or `None`
+    /// if not enough data has been received to tell. Pdfium can usually tell once the first
+    /// kilobyte of the document has been received.
+    pub fn is_linearized(&self) -> Option<bool> {
+        match self.bindings().FPDFAvail_IsLinearized(self.source.avail) as u32 {
+            PDF_LINEARIZED => Some(true),
+            PDF_NOT_LINEARIZED => Some(false),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if enough data has been received to open the document using
+    /// [PdfDocumentLoader::open_document()]. If not, the byte ranges Pdfium needs next
+    /// are added to the ranges returned by [PdfDocumentLoader::take_download_hints()].
+    pub fn is_document_ready(&mut self) -> Result<bool, PdfiumError> {
+        let avail = self.source.avail;
+
+        let bindings = self.bindings();
+
+        let result = self.with_hints(|hints| bindings.FPDFAvail_IsDocAvail(avail, hints));
+
+        Self::data_result_to_bool(result)
+    }
+
+    /// Opens the document, returning a mutable reference to it. The document can only be
+    /// opened once [PdfDocumentLoader::is_document_ready()] returns `true`; otherwise,
+    /// [PdfiumError::LoaderDocumentNotReady] is returned. If the document has already been
+    /// opened, the previously opened document is returned.
+    ///
+    /// If the document is password protected, the given password will be used to unlock it.
+    ///
+    /// The document's form, if any, is initialized when the document is opened. For linearized
+    /// documents containing forms, Pdfium may not yet have received all the form's data at this
+    /// point, so form fields on pages that have not yet been received may be unavailable.
+    pub fn open_document(
+        &mut self,
+        password: Option<&str>,
+    ) -> Result<&mut PdfDocument<'a>, PdfiumError> {
+        if self.document.is_none() {
+            let bindings = self.bindings();
+
+            if bindings.FPDFAvail_IsDocAvail(self.source.avail, null_mut())
+                != PDF_DATA_AVAIL as c_int
+            {
+                return Err(PdfiumError::LoaderDocumentNotReady);
+            }
+
+            self.document = Some(Pdfium::pdfium_document_handle_to_result(
+                bindings.FPDFAvail_GetDocument(self.source.avail, password),
+                bindings,
+            )?);
+        }
+
+        self.document
+            .as_mut()
+            .ok_or(PdfiumError::LoaderDocumentNotOpened)
+    }
+
+    /// Returns the document opened using [PdfDocumentLoader::open_document()], if any.
+    #[inline]
+    pub fn document(&self) -> Option<&PdfDocument<'a>> {
+        self.document.as_ref()
+    }
+
+    /// Returns a mutable reference to the document opened using
+    /// [PdfDocumentLoader::open_document()], if any.
+    #[inline]
+    pub fn document_mut(&mut self) -> Option<&mut PdfDocument<'a>> {
+        self.document.as_mut()
+    }
+
+    /// Returns the index of the first page Pdfium can display before the rest of the
+    /// document has been received. This is usually, but not always, the first page of a
+    /// linearized document, and is always the first page of a document that is not linearized.
+    ///
+    /// Returns [PdfiumError::LoaderDocumentNotOpened] if the document has not yet been opened.
+    pub fn first_available_page_index(&self) -> Result<PdfPageIndex, PdfiumError> {
+        let document = self
+            .document
+            .as_ref()
+            .ok_or(PdfiumError::LoaderDocumentNotOpened)?;
+
+        let index = self.bindings().FPDFAvail_GetFirstPageNum(document.handle());
+
+        PdfPageIndex::try_from(index).map_err(|_| PdfiumError::PageIndexOutOfBounds)
+    }
+
+    /// Returns `true` if enough data has been received to load the page at the given index
+    /// from the opened document. If not, the byte ranges Pdfium needs next are added to the
+    /// ranges returned by [PdfDocumentLoader::take_download_hints()].
+    ///
+    /// Returns [PdfiumError::LoaderDocumentNotOpened] if the document has not yet been opened.
+    pub fn is_page_ready(&mut self, index: PdfPageIndex) -> Result<bool, PdfiumError> {
+        if self.document.is_none() {
+            return Err(PdfiumError::LoaderDocumentNotOpened);
+        }
+
+        let avail = self.source.avail;
+
+        let bindings = self.bindings();
+
+        let result =
+            self.with_hints(|hints| bindings.FPDFAvail_IsPageAvail(avail, index as c_int, hints));
+
+        Self::data_result_to_bool(result)
+    }
+
+    /// Returns `true` if enough data has been received for the document's form to be used,
+    /// or if the document does not contain a form. If not, the byte ranges Pdfium needs next
+    /// are added to the ranges returned by [PdfDocumentLoader::take_download_hints()].
+    ///
+    /// Returns [PdfiumError::LoaderDocumentNotOpened] if the document has not yet been opened.
+    pub fn is_form_ready(&mut self) -> Result<bool, PdfiumError> {
+        if self.document.is_none() {
+            return Err(PdfiumError::LoaderDocumentNotOpened);
+        }
+
+        let avail = self.source.avail;
+
+        let bindings = self.bindings();
+
+        match self.with_hints(|hints| bindings.FPDFAvail_IsFormAvail(avail, hints)) as u32 {
+            PDF_FORM_AVAIL | PDF_FORM_NOTEXIST => Ok(true),
+            PDF_FORM_NOTAVAIL => Ok(false),
+            _ => Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure),
+        }
+    }
+
+    /// Returns the byte ranges Pdfium has asked for since this function was last called,
+    /// in ascending order, omitting any data that has already been received. Applications
+    /// should download these ranges before any others.
+    pub fn take_download_hints(&mut self) -> Vec<Range<usize>> {
+        let mut missing = PdfByteRanges::default();
+
+        let content_length = self.content_length();
+
+        for hint in self.hints.drain(..) {
+            let hint = hint.start.min(content_length)..hint.end.min(content_length);
+
+            for range in self.source.data.available.missing(hint) {
+                missing.insert(range);
+            }
+        }
+
+        missing.ranges
+    }
+
+    /// Converts this [PdfDocumentLoader] into the [PdfDocument] it opened. The document
+    /// takes ownership of the received data, so Pdfium can continue to read from it as needed
+    /// throughout the lifetime of the document.
+    ///
+    /// Returns [PdfiumError::LoaderDocumentNotOpened] if the document has not yet been opened,
+    /// or [PdfiumError::LoaderDocumentNotReady] if not all the document's data has been received.
+    pub fn into_document(self) -> Result<PdfDocument<'a>, PdfiumError> {
+        if self.document.is_none() {
+            return Err(PdfiumError::LoaderDocumentNotOpened);
+        }
+
+        if !self.is_complete() {
+            return Err(PdfiumError::LoaderDocumentNotReady);
+        }
+
+        let PdfDocumentLoader {
+            document, source, ..
+        } = self;
+
+        let mut document = document.ok_or(PdfiumError::LoaderDocumentNotOpened)?;
+
+        document.set_loader_source(source);
+
+        Ok(document)
+    }
+
+    /// Calls the given function with a download hints interface, retaining any byte ranges
+    /// Pdfium adds to it, and returns the function's result.
+    fn with_hints(&mut self, f: impl FnOnce(*mut FX_DOWNLOADHINTS) -> c_int) -> c_int {
+        let mut hints = PdfDownloadHintsExt {
+            hints: FX_DOWNLOADHINTS {
+                version: 1,
+                AddSegment: Some(add_segment_callback),
+            },
+            segments: Vec::new(),
+        };
+
+        let result = f(&mut hints.hints);
+
+        self.hints.append(&mut hints.segments);
+
+        result
+    }
+
+    #[inline]
+    fn data_result_to_bool(result: c_int) -> Result<bool, PdfiumError> {
+        match result {
+            result if result == PDF_DATA_AVAIL as c_int => Ok(true),
+            result if result == PDF_DATA_NOTAVAIL as c_int => Ok(false),
+            _ => Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure),
+        }
+    }
+}
+
+/// The availability provider and received data backing a document opened by a
+/// [PdfDocumentLoader]. Both must outlive the document.
+pub(crate) struct PdfDocumentLoaderSource<'a> {
+    avail: FPDF_AVAIL,
+    data: Box<PdfDocumentLoaderData>,
+    bindings: &'a dyn PdfiumLibraryBindings,
+}
+
+impl<'a> Drop for PdfDocumentLoaderSource<'a> {
+    /// Destroys the availability provider. The received data is released afterwards.
+    #[inline]
+    fn drop(&mut self) {
+        self.bindings.FPDFAvail_Destroy(self.avail);
+    }
+}
+
+// Pdfium passes a pointer to the FX_FILEAVAIL struct to the IsDataAvail() callback.
+// As with the FpdfFileAccessExt struct in crate::utils::files, we embed that struct
+// as the first field of our own struct so the callback can recover the received data.
+
+#[repr(C)]
+struct PdfDocumentLoaderData {
+    file_avail: FX_FILEAVAIL, // Must be the first field
+    file_access: FPDF_FILEACCESS,
+    bytes: Vec<u8>,
+    available: PdfByteRanges,
+}
+
+impl PdfDocumentLoaderData {
+    #[inline]
+    fn is_available(&self, range: Range<usize>) -> bool {
+        range.end <= self.bytes.len() && self.available.contains(range)
+    }
+}
+
+#[repr(C)]
+struct PdfDownloadHintsExt {
+    hints: FX_DOWNLOADHINTS, // Must be the first field
+    segments: Vec<Range<usize>>,
+}
+
+// The callback functions invoked by Pdfium.
+
+unsafe extern "C" fn is_data_available_callback(
+    file_avail: *mut FX_FILEAVAIL,
+    offset: usize,
+    size: usize,
+) -> c_int {
+    let data = &*(file_avail as *const PdfDocumentLoaderData);
+
+    match offset.checked_add(size) {
+        Some(end) if data.is_available(offset..end) => 1,
+        _ => 0,
+    }
+}
+
+unsafe extern "C" fn get_block_callback(
+    param: *mut c_void,
+    position: c_ulong,
+    buf: *mut c_uchar,
+    size: c_ulong,
+) -> c_int {
+    let data = &*(param as *const PdfDocumentLoaderData);
+
+    let start = position as usize;
+
+    match start.checked_add(size as usize) {
+        Some(end) if data.is_available(start..end) => {
+            slice::from_raw_parts_mut(buf, size as usize).copy_from_slice(&data.bytes[start..end]);
+
+            1
+        }
+        _ => 0,
+    }
+}
+
+unsafe extern "C" fn add_segment_callback(
+    hints: *mut FX_DOWNLOADHINTS,
+    offset: usize,
+    size: usize,
+) {
+    let hints = &mut *(hints as *mut PdfDownloadHintsExt);
+
+    hints.segments.push(offset..offset.saturating_add(size));
+}
+
+/// A set of byte ranges, stored in ascending order with overlapping and adjacent
+/// ranges merged together.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct PdfByteRanges {
+    ranges: Vec<Range<usize>>,
+}
+
+impl PdfByteRanges {
+    /// Adds the given range to this set.
+    fn insert(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+
+        // Find the ranges that overlap or touch the new range, and replace them
+        // with a single range covering all of them.
+
+        let first = self
+            .ranges
+            .partition_point(|existing| existing.end < range.start);
+
+        let last = self
+            .ranges
+            .partition_point(|existing| existing.start <= range.end);
+
+        let merged = if first < last {
+            self.ranges[first].start.min(range.start)..self.ranges[last - 1].end.max(range.end)
+        } else {
+            range
+        };
+
+        self.ranges.splice(first..last, std::iter::once(merged));
+    }
+
+    /// Returns `true` if every byte in the given range is in this set.
+    fn contains(&self, range: Range<usize>) -> bool {
+        if range.is_empty() {
+            return true;
+        }
+
+        let index = self
+            .ranges
+            .partition_point(|existing| existing.end <= range.start);
+
+        self.ranges.get(index).map_or(false, |existing| {
+            existing.start <= range.start && existing.end >= range.end
+        })
+    }
+
+    /// Returns the parts of the given range that are not in this set, in ascending order.
+    fn missing(&self, range: Range<usize>) -> Vec<Range<usize>> {
+        let mut result = Vec::new();
+
+        let mut start = range.start;
+
+        for existing in self.ranges.iter() {
+            if existing.end <= start {
+                continue;
+            }
+
+            if existing.start >= range.end {
+                break;
+            }
+
+            if existing.start > start {
+                result.push(start..existing.start);
+            }
+
+            start = existing.end;
+        }
+
+        if start < range.end {
+            result.push(start..range.end);
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::loader::PdfByteRanges;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_byte_ranges() {
+        let mut ranges = PdfByteRanges::default();
+
+        ranges.insert(10..20);
+        ranges.insert(30..40);
+        ranges.insert(0..0);
+
+        assert_eq!(ranges.ranges, vec![10..20, 30..40]);
+        assert!(ranges.contains(12..18));
+        assert!(!ranges.contains(15..35));
+        assert_eq!(ranges.missing(0..50), vec![0..10, 20..30, 40..50]);
+        assert!(ranges.missing(12..18).is_empty());
+
+        ranges.insert(20..30);
+
+        assert_eq!(ranges.ranges, vec![10..40]);
+        assert!(ranges.contains(15..35));
+
+        ranges.insert(5..45);
+
+        assert_eq!(ranges.ranges, vec![5..45]);
+        assert_eq!(ranges.missing(0..50), vec![0..5, 45..50]);
+    }
+
+    #[test]
+    fn test_document_loader() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let bytes = std::fs::read("./test/form-test.pdf").map_err(PdfiumError::IoError)?;
+
+        let mut loader = PdfDocumentLoader::new(&pdfium, bytes.len())?;
+
+        assert!(matches!(
+            loader.add_data(bytes.len(), &[0]),
+            Err(PdfiumError::LoaderDataOutOfBounds)
+        ));
+        assert!(matches!(
+            loader.open_document(None),
+            Err(PdfiumError::LoaderDocumentNotReady)
+        ));
+
+        // Feed the document to the loader in small blocks, fetching the ranges Pdfium asks for
+        // first, until it can be opened.
+
+        const BLOCK_SIZE: usize = 1024;
+
+        let mut next = 0;
+
+        while !loader.is_document_ready()? {
+            assert!(!loader.is_complete());
+
+            let range = loader
+                .take_download_hints()
+                .into_iter()
+                .next()
+                .unwrap_or(next..(next + BLOCK_SIZE).min(bytes.len()));
+
+            let range = range.start..range.end.min(range.start + BLOCK_SIZE);
+
+            loader.add_data(range.start, &bytes[range.clone()])?;
+
+            if range.start == next {
+                next = range.end;
+            }
+        }
+
+        let page_count = loader.open_document(None)?.pages().len();
+
+        assert_eq!(
+            page_count,
+            pdfium.load_pdf_from_byte_slice(&bytes, None)?.pages().len()
+        );
+
+        loader.add_data(0, &bytes)?;
+
+        assert!(loader.is_complete());
+        assert!(loader.is_page_ready(0)?);
+        assert!(loader.is_form_ready()?);
+
+        let document = loader.into_document()?;
+
+        assert_eq!(document.pages().len(), page_count);
+        assert!(!document.pages().get(0)?.text()?.all().is_empty());
+
+        Ok(())
+    }
+}
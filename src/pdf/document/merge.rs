@@ -0,0 +1,514 @@
+//! Merges several `PdfDocument` objects into one, and splits a single `PdfDocument` into
+//! several, carrying each document's bookmarks, attachments, page labels, and viewer
+//! preferences across alongside its pages.
+
+use crate::bindgen::FPDF_BOOKMARK;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::action::PdfAction;
+use crate::pdf::destination::PdfDestinationViewSettings;
+use crate::pdf::document::attachment::details::{
+    read_attachment_description, read_attachment_mime_type, write_attachment_description,
+    write_attachment_mime_type,
+};
+use crate::pdf::document::bookmark::PdfBookmark;
+use crate::pdf::document::outline::{PdfBookmarkDestination, PdfBookmarkId, PdfOutline};
+use crate::pdf::document::page_labels::{
+    offset_page_labels, read_page_labels, slice_page_labels, write_page_labels, PdfPageLabelRange,
+    PdfPageLabelStyle,
+};
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use crate::pdf::document::syntax::PdfSyntaxObject;
+use crate::pdf::document::{PdfDocument, PdfDocumentVersion};
+use crate::pdfium::Pdfium;
+use std::collections::{HashMap, HashSet};
+use std::ops::RangeInclusive;
+
+#[cfg(doc)]
+use crate::pdf::document::attachments::PdfAttachments;
+
+/// The maximum depth of nested bookmarks copied from a source document. The limit guards
+/// against exhausting the stack when copying from malicious input.
+const MAXIMUM_BOOKMARK_DEPTH: usize = 64;
+
+/// The description and MIME type of an attachment copied into a destination document,
+/// keyed by the attachment's name in the destination document. Pdfium cannot write either,
+/// so they are applied once all attachments have been copied.
+type PdfAttachmentDetails = HashMap<String, (Option<String>, Option<String>)>;
+
+/// Creates a new [PdfDocument] containing the pages of the given documents, in order,
+/// along with their bookmarks and attachments. See `Pdfium::merge()`.
+pub(crate) fn merge_documents<'a>(
+    bindings: &'a dyn PdfiumLibraryBindings,
+    documents: &[&PdfDocument],
+) -> Result<PdfDocument<'a>, PdfiumError> {
+    let mut merged = create_document(bindings)?;
+
+    if let Some(first) = documents.first() {
+        bindings.FPDF_CopyViewerPreferences(merged.handle(), first.handle());
+    }
+
+    let mut details = PdfAttachmentDetails::new();
+
+    let mut labels = Vec::new();
+
+    let mut has_labels = false;
+
+    for document in documents.iter() {
+        if document.pages().is_empty() {
+            copy_attachments(document, &mut merged, &mut details)?;
+
+            continue;
+        }
+
+        let offset = merged.pages().len();
+
+        let range = document.pages().as_range_inclusive();
+
+        PdfPages::copy_page_range_between_documents(
+            document.handle(),
+            range.clone(),
+            merged.handle(),
+            offset,
+            bindings,
+        )?;
+
+        copy_bookmarks(document, &mut merged, range, offset)?;
+        copy_attachments(document, &mut merged, &mut details)?;
+
+        // Pages from documents without page labels are labelled with their page numbers
+        // in the merged document, as they would be displayed if it had no page labels.
+
+        let ranges = read_label_ranges(document);
+
+        if ranges.is_empty() {
+            labels.push(
+                PdfPageLabelRange::new(offset, PdfPageLabelStyle::Decimal)
+                    .set_first_number(offset as u32 + 1),
+            );
+        } else {
+            has_labels = true;
+
+            labels.extend(offset_page_labels(&ranges, offset));
+        }
+    }
+
+    if !has_labels {
+        labels.clear();
+    }
+
+    finish_document(&mut merged, &details, &labels)?;
+
+    Ok(merged)
+}
+
+/// Creates a new [PdfDocument] for each of the given ranges of page indices in the given
+/// document, containing the pages in the range along with the bookmarks that refer to them
+/// and all the document's attachments. See `PdfDocument::split()`.
+pub(crate) fn split_document<'a>(
+    document: &PdfDocument<'a>,
+    ranges: &[RangeInclusive<PdfPageIndex>],
+) -> Result<Vec<PdfDocument<'a>>, PdfiumError> {
+    let page_count = document.pages().len();
+
+    if ranges
+        .iter()
+        .any(|range| range.is_empty() || *range.end() >= page_count)
+    {
+        return Err(PdfiumError::PageIndexOutOfBounds);
+    }
+
+    let labels = read_label_ranges(document);
+
+    let mut parts = Vec::with_capacity(ranges.len());
+
+    for range in ranges.iter() {
+        let mut part = create_document(document.bindings())?;
+
+        document
+            .bindings()
+            .FPDF_CopyViewerPreferences(part.handle(), document.handle());
+
+        PdfPages::copy_page_range_between_documents(
+            document.handle(),
+            range.clone(),
+            part.handle(),
+            0,
+            document.bindings(),
+        )?;
+
+        let mut details = PdfAttachmentDetails::new();
+
+        copy_bookmarks(document, &mut part, range.clone(), 0)?;
+        copy_attachments(document, &mut part, &mut details)?;
+        finish_document(
+            &mut part,
+            &details,
+            &slice_page_labels(&labels, range.clone()),
+        )?;
+
+        parts.push(part);
+    }
+
+    Ok(parts)
+}
+
+/// Creates a new, empty [PdfDocument].
+pub(crate) fn create_document(
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<PdfDocument, PdfiumError> {
+    let mut document =
+        Pdfium::pdfium_document_handle_to_result(bindings.FPDF_CreateNewDocument(), bindings)?;
+
+    document.set_version(PdfDocumentVersion::DEFAULT_VERSION);
+
+    Ok(document)
+}
+
+/// Returns the page label ranges of the given document, or an empty list if it does not
+/// label its pages or its page labels cannot be read.
+fn read_label_ranges(document: &PdfDocument) -> Vec<PdfPageLabelRange> {
+    // Reading the ranges requires saving the document, which can be avoided if Pdfium
+    // reports that no page has a label.
+
+    let pages = document.pages();
+
+    if !pages.as_range().any(|index| pages.label(index).is_some()) {
+        return Vec::new();
+    }
+
+    document
+        .save_to_bytes()
+        .ok()
+        .and_then(|bytes| read_page_labels(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Writes the bookmarks, attachment details, and page labels copied into the given document,
+/// then reloads the document so that they are visible through Pdfium.
+fn finish_document(
+    document: &mut PdfDocument,
+    details: &PdfAttachmentDetails,
+    labels: &[PdfPageLabelRange],
+) -> Result<(), PdfiumError> {
+    let has_details = details
+        .values()
+        .any(|(description, mime_type)| description.is_some() || mime_type.is_some());
+
+    if !has_details && labels.is_empty() && !document.bookmarks().is_modified() {
+        return Ok(());
+    }
+
+    let mut bytes = document.save_to_bytes()?;
+
+    if has_details {
+        let names = document
+            .attachments()
+            .iter()
+            .map(|attachment| attachment.name())
+            .collect::<Vec<_>>();
+
+        for (index, name) in names.iter().enumerate() {
+            if let Some((description, mime_type)) = details.get(name) {
+                if description.is_some() {
+                    bytes = write_attachment_description(bytes, index, description.as_deref())?;
+                }
+
+                if mime_type.is_some() {
+                    bytes = write_attachment_mime_type(bytes, index, mime_type.as_deref())?;
+                }
+            }
+        }
+    }
+
+    if !labels.is_empty() {
+        bytes = write_page_labels(bytes, labels)?;
+    }
+
+    document.reload_from_bytes(bytes)
+}
+
+/// Copies the bookmarks of the source document that refer to pages in the given range into
+/// the destination document, where the first page in the range has the given index.
+/// Bookmarks referring to other pages are copied without a destination if any of their
+/// descendants are copied, so that the structure of the bookmark tree is retained; otherwise
+/// they are skipped. Bookmarks with URI actions are always copied.
+fn copy_bookmarks(
+    source: &PdfDocument,
+    destination: &mut PdfDocument,
+    range: RangeInclusive<PdfPageIndex>,
+    offset: PdfPageIndex,
+) -> Result<(), PdfiumError> {
+    let root = match source.bookmarks().root() {
+        Some(root) => root,
+        None => return Ok(()),
+    };
+
+    let outline = destination.bookmarks_mut().outline_mut()?;
+
+    let mut visited = HashSet::new();
+
+    copy_bookmark_siblings(
+        Some(root),
+        None,
+        outline,
+        &|index| {
+            if range.contains(&index) {
+                Some(index - range.start() + offset)
+            } else {
+                None
+            }
+        },
+        &mut visited,
+        0,
+    )?;
+
+    Ok(())
+}
+
+/// Copies the given bookmark and its following siblings, along with their descendants,
+/// into the given outline, returning `true` if any bookmarks were copied.
+fn copy_bookmark_siblings(
+    mut next: Option<PdfBookmark>,
+    parent: Option<PdfBookmarkId>,
+    outline: &mut PdfOutline,
+    map_page_index: &dyn Fn(PdfPageIndex) -> Option<PdfPageIndex>,
+    visited: &mut HashSet<FPDF_BOOKMARK>,
+    depth: usize,
+) -> Result<bool, PdfiumError> {
+    let mut copied = false;
+
+    if depth > MAXIMUM_BOOKMARK_DEPTH {
+        return Ok(copied);
+    }
+
+    while let Some(bookmark) = next {
+        if !visited.insert(bookmark.bookmark_handle()) {
+            // The bookmark tree contains a cycle.
+
+            break;
+        }
+
+        let target = bookmark.destination().and_then(|destination| {
+            let page_index = map_page_index(destination.page_index().ok()?)?;
+
+            let view_settings = destination
+                .view_settings()
+                .unwrap_or(PdfDestinationViewSettings::Unknown);
+
+            Some(PdfBookmarkDestination::new(page_index, view_settings))
+        });
+
+        let uri = match bookmark.action() {
+            Some(PdfAction::Uri(action)) => action.uri().ok(),
+            _ => None,
+        };
+
+        let index = outline.children(parent)?.len();
+
+        let id = outline.insert(parent, index, &bookmark.title().unwrap_or_default(), target)?;
+
+        if let Some(uri) = uri.as_ref() {
+            outline.set_entry(
+                id,
+                "A",
+                PdfSyntaxObject::Dictionary(vec![
+                    ("S".to_owned(), PdfSyntaxObject::name("URI")),
+                    (
+                        "URI".to_owned(),
+                        PdfSyntaxObject::String(uri.as_bytes().to_vec()),
+                    ),
+                ]),
+            )?;
+        }
+
+        let has_children = copy_bookmark_siblings(
+            bookmark.first_child(),
+            Some(id),
+            outline,
+            map_page_index,
+            visited,
+            depth + 1,
+        )?;
+
+        if target.is_some() || uri.is_some() || has_children {
+            copied = true;
+        } else {
+            outline.remove(id)?;
+        }
+
+        next = bookmark.next_sibling();
+    }
+
+    Ok(copied)
+}
+
+/// Copies the attachments in the [PdfAttachments] collection of the source document into
+/// the destination document, recording the description and MIME type of each copied
+/// attachment in the given map. Attachments whose names are already used in the destination
+/// document are renamed.
+fn copy_attachments(
+    source: &PdfDocument,
+    destination: &mut PdfDocument,
+    details: &mut PdfAttachmentDetails,
+) -> Result<(), PdfiumError> {
+    if source.attachments().is_empty() {
+        return Ok(());
+    }
+
+    // The description and MIME type of each attachment can only be read from a saved copy
+    // of the source document. They are not copied if they cannot be read.
+
+    let bytes = source.save_to_bytes().ok();
+
+    let mut names = destination
+        .attachments()
+        .iter()
+        .map(|attachment| attachment.name())
+        .collect::<HashSet<_>>();
+
+    for (index, attachment) in source.attachments().iter().enumerate() {
+        let name = unique_attachment_name(&attachment.name(), &names);
+
+        let data = attachment.save_to_bytes().unwrap_or_default();
+
+        let mut copy = destination
+            .attachments_mut()
+            .create_attachment_from_bytes(&name, &data)?;
+
+        if let Some(date) = attachment.creation_date() {
+            copy.set_creation_date(date)?;
+        }
+
+        if let Some(date) = attachment.modification_date() {
+            copy.set_modification_date(date)?;
+        }
+
+        if let Some(bytes) = bytes.as_ref() {
+            details.insert(
+                name.clone(),
+                (
+                    read_attachment_description(bytes, index).ok().flatten(),
+                    read_attachment_mime_type(bytes, index).ok().flatten(),
+                ),
+            );
+        }
+
+        names.insert(name);
+    }
+
+    Ok(())
+}
+
+/// Returns the given attachment name if it is not among the given names, or otherwise
+/// the name with the lowest numeric suffix, placed before any file extension, that is not.
+fn unique_attachment_name(name: &str, names: &HashSet<String>) -> String {
+    if !names.contains(name) {
+        return name.to_owned();
+    }
+
+    let (stem, extension) = match name.rfind('.') {
+        Some(position) if position > 0 => name.split_at(position),
+        _ => (name, ""),
+    };
+
+    (2..)
+        .map(|suffix| format!("{} ({}){}", stem, suffix, extension))
+        .find(|candidate| !names.contains(candidate))
+        .unwrap_or_else(|| name.to_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::merge::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_unique_attachment_name() {
+        let names = ["report.pdf", "report (2).pdf", "notes"]
+            .iter()
+            .map(|name| name.to_string())
+            .collect::<HashSet<_>>();
+
+        assert_eq!(unique_attachment_name("data.csv", &names), "data.csv");
+        assert_eq!(
+            unique_attachment_name("report.pdf", &names),
+            "report (3).pdf"
+        );
+        assert_eq!(unique_attachment_name("notes", &names), "notes (2)");
+    }
+
+    #[test]
+    fn test_merge_and_split() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut first = pdfium.create_new_pdf()?;
+
+        let mut second = pdfium.create_new_pdf()?;
+
+        for document in [&mut first, &mut second] {
+            for _ in 0..2 {
+                document
+                    .pages_mut()
+                    .create_page_at_end(PdfPagePaperSize::a4())?;
+            }
+
+            document
+                .attachments_mut()
+                .create_attachment_from_bytes("data.txt", b"data")?;
+        }
+
+        first
+            .bookmarks_mut()
+            .add_root("First", PdfBookmarkDestination::page(1))?;
+        second
+            .bookmarks_mut()
+            .add_root("Second", PdfBookmarkDestination::page(0))?;
+
+        first.set_page_label_ranges(&[PdfPageLabelRange::new(
+            0,
+            PdfPageLabelStyle::LowercaseRoman,
+        )])?;
+
+        let first = pdfium.load_pdf_from_byte_vec(first.save_to_bytes()?, None)?;
+
+        let second = pdfium.load_pdf_from_byte_vec(second.save_to_bytes()?, None)?;
+
+        let merged = pdfium.merge(&[&first, &second])?;
+
+        assert_eq!(merged.pages().len(), 4);
+        assert_eq!(merged.attachments().len(), 2);
+
+        let second_bookmark = merged.bookmarks().find_first_by_title("Second")?;
+
+        assert_eq!(second_bookmark.destination().unwrap().page_index()?, 2);
+
+        // Pages from the second document, which has no page labels, are labelled with
+        // their page numbers in the merged document.
+
+        assert_eq!(merged.pages().label(1), Some("ii".to_owned()));
+        assert_eq!(merged.pages().label(2), Some("3".to_owned()));
+
+        let parts = merged.split(&[0..=1, 2..=3])?;
+
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[1].pages().len(), 2);
+
+        let titles = parts[1]
+            .bookmarks()
+            .iter()
+            .filter_map(|bookmark| bookmark.title())
+            .collect::<Vec<_>>();
+
+        assert_eq!(titles, vec!["Second"]);
+        assert_eq!(parts[0].pages().label(1), Some("ii".to_owned()));
+        assert_eq!(parts[1].pages().label(0), Some("3".to_owned()));
+        assert!(matches!(
+            merged.split(&[3..=4]),
+            Err(PdfiumError::PageIndexOutOfBounds)
+        ));
+
+        Ok(())
+    }
+}
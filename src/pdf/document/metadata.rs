@@ -2,8 +2,13 @@
 
 use crate::bindgen::FPDF_DOCUMENT;
 use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use crate::utils::dates::{date_time_from_pdf_string, date_time_to_pdf_string};
 use crate::utils::mem::create_byte_buffer;
 use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use chrono::prelude::*;
+use std::fmt::Display;
 use std::os::raw::c_void;
 use std::slice::Iter;
 
@@ -20,6 +25,35 @@ pub enum PdfDocumentMetadataTagType {
     ModificationDate,
 }
 
+impl PdfDocumentMetadataTagType {
+    /// All metadata tag types, in the order they are listed in a [PdfMetadata] collection.
+    const ALL: [PdfDocumentMetadataTagType; 8] = [
+        PdfDocumentMetadataTagType::Title,
+        PdfDocumentMetadataTagType::Author,
+        PdfDocumentMetadataTagType::Subject,
+        PdfDocumentMetadataTagType::Keywords,
+        PdfDocumentMetadataTagType::Creator,
+        PdfDocumentMetadataTagType::Producer,
+        PdfDocumentMetadataTagType::CreationDate,
+        PdfDocumentMetadataTagType::ModificationDate,
+    ];
+
+    /// Returns the key of this metadata tag type in a document information dictionary.
+    #[inline]
+    pub(crate) fn as_pdf_key(&self) -> &'static str {
+        match self {
+            PdfDocumentMetadataTagType::Title => "Title",
+            PdfDocumentMetadataTagType::Author => "Author",
+            PdfDocumentMetadataTagType::Subject => "Subject",
+            PdfDocumentMetadataTagType::Keywords => "Keywords",
+            PdfDocumentMetadataTagType::Creator => "Creator",
+            PdfDocumentMetadataTagType::Producer => "Producer",
+            PdfDocumentMetadataTagType::CreationDate => "CreationDate",
+            PdfDocumentMetadataTagType::ModificationDate => "ModDate",
+        }
+    }
+}
+
 /// A single metadata tag in a `PdfDocument`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct PdfDocumentMetadataTag {
@@ -44,12 +78,22 @@ impl PdfDocumentMetadataTag {
     pub fn value(&self) -> &str {
         self.value.as_str()
     }
+
+    /// Returns the value of this metadata tag parsed as a PDF date, as defined in
+    /// The PDF Reference Manual, sixth edition, section 3.8.3, on page 160. Returns `None`
+    /// if the value is not a valid PDF date. The `CreationDate` and `ModificationDate`
+    /// tags hold dates.
+    #[inline]
+    pub fn date(&self) -> Option<DateTime<Utc>> {
+        date_time_from_pdf_string(&self.value)
+    }
 }
 
 pub struct PdfMetadata<'a> {
     document_handle: FPDF_DOCUMENT,
     bindings: &'a dyn PdfiumLibraryBindings,
     tags: Vec<PdfDocumentMetadataTag>,
+    is_modified: bool,
 }
 
 impl<'a> PdfMetadata<'a> {
@@ -61,38 +105,13 @@ impl<'a> PdfMetadata<'a> {
             document_handle,
             bindings,
             tags: vec![],
+            is_modified: false,
         };
 
-        if let Some(tag) = result.get(PdfDocumentMetadataTagType::Title) {
-            result.tags.push(tag);
-        }
-
-        if let Some(tag) = result.get(PdfDocumentMetadataTagType::Author) {
-            result.tags.push(tag);
-        }
-
-        if let Some(tag) = result.get(PdfDocumentMetadataTagType::Subject) {
-            result.tags.push(tag);
-        }
-
-        if let Some(tag) = result.get(PdfDocumentMetadataTagType::Keywords) {
-            result.tags.push(tag);
-        }
-
-        if let Some(tag) = result.get(PdfDocumentMetadataTagType::Creator) {
-            result.tags.push(tag);
-        }
-
-        if let Some(tag) = result.get(PdfDocumentMetadataTagType::Producer) {
-            result.tags.push(tag);
-        }
-
-        if let Some(tag) = result.get(PdfDocumentMetadataTagType::CreationDate) {
-            result.tags.push(tag);
-        }
-
-        if let Some(tag) = result.get(PdfDocumentMetadataTagType::ModificationDate) {
-            result.tags.push(tag);
+        for tag in PdfDocumentMetadataTagType::ALL {
+            if let Some(value) = result.get_raw_metadata_tag(tag.as_pdf_key()) {
+                result.tags.push(PdfDocumentMetadataTag::new(tag, value));
+            }
         }
 
         result
@@ -118,20 +137,75 @@ impl<'a> PdfMetadata<'a> {
 
     /// Returns one metadata tag from this [PdfMetadata] collection, if it is defined.
     pub fn get(&self, tag: PdfDocumentMetadataTagType) -> Option<PdfDocumentMetadataTag> {
-        let result = match tag {
-            PdfDocumentMetadataTagType::Title => self.get_raw_metadata_tag("Title"),
-            PdfDocumentMetadataTagType::Author => self.get_raw_metadata_tag("Author"),
-            PdfDocumentMetadataTagType::Subject => self.get_raw_metadata_tag("Subject"),
-            PdfDocumentMetadataTagType::Keywords => self.get_raw_metadata_tag("Keywords"),
-            PdfDocumentMetadataTagType::Creator => self.get_raw_metadata_tag("Creator"),
-            PdfDocumentMetadataTagType::Producer => self.get_raw_metadata_tag("Producer"),
-            PdfDocumentMetadataTagType::CreationDate => self.get_raw_metadata_tag("CreationDate"),
-            PdfDocumentMetadataTagType::ModificationDate => {
-                self.get_raw_metadata_tag("ModificationDate")
-            }
-        };
+        self.tags
+            .iter()
+            .find(|existing| existing.tag_type() == tag)
+            .cloned()
+    }
+
+    /// Sets the value of the given metadata tag in this [PdfMetadata] collection, replacing
+    /// its existing value if it is already defined.
+    ///
+    /// Pdfium cannot write a document's information dictionary itself, so changes to the
+    /// metadata are applied when the document is saved, in an incremental update appended
+    /// to the file Pdfium writes. Saving returns [PdfiumError::DocumentUpdateUnsupported]
+    /// if the metadata has changed and the document is encrypted.
+    pub fn set(&mut self, tag: PdfDocumentMetadataTagType, value: &str) {
+        match self
+            .tags
+            .iter_mut()
+            .find(|existing| existing.tag_type() == tag)
+        {
+            Some(existing) => existing.value = value.to_owned(),
+            None => self
+                .tags
+                .push(PdfDocumentMetadataTag::new(tag, value.to_owned())),
+        }
+
+        self.is_modified = true;
+    }
+
+    /// Sets the value of the given metadata tag in this [PdfMetadata] collection to the given
+    /// date, formatted as a PDF date string. This is typically used to set the
+    /// `CreationDate` and `ModificationDate` tags.
+    ///
+    /// Changes to the metadata are applied when the document is saved; see
+    /// [PdfMetadata::set()].
+    #[inline]
+    pub fn set_date<T, O>(&mut self, tag: PdfDocumentMetadataTagType, date: DateTime<T>)
+    where
+        T: TimeZone<Offset = O>,
+        O: Display,
+    {
+        self.set(tag, &date_time_to_pdf_string(date));
+    }
+
+    /// Removes the given metadata tag from this [PdfMetadata] collection, if it is defined.
+    ///
+    /// Changes to the metadata are applied when the document is saved; see
+    /// [PdfMetadata::set()].
+    pub fn remove(&mut self, tag: PdfDocumentMetadataTagType) {
+        let len = self.tags.len();
+
+        self.tags.retain(|existing| existing.tag_type() != tag);
+
+        if self.tags.len() != len {
+            self.is_modified = true;
+        }
+    }
+
+    /// Returns `true` if any metadata tags have been set or removed since the document
+    /// was loaded.
+    #[inline]
+    pub(crate) fn is_modified(&self) -> bool {
+        self.is_modified
+    }
 
-        result.map(|value| PdfDocumentMetadataTag::new(tag, value))
+    /// Writes the metadata tags in this [PdfMetadata] collection to the information dictionary
+    /// of the given PDF file, returning the updated file.
+    #[inline]
+    pub(crate) fn write_to_file(&self, file: Vec<u8>) -> Result<Vec<u8>, PdfiumError> {
+        write_info_dictionary(file, &self.tags)
     }
 
     #[inline]
@@ -174,3 +248,145 @@ impl<'a> PdfMetadata<'a> {
         self.tags.iter()
     }
 }
+
+/// Replaces the metadata tags in the information dictionary of the given PDF file with the
+/// given tags, returning the updated file. Entries in the dictionary other than the standard
+/// metadata tags are preserved. The change is applied in an incremental update appended to
+/// the end of the file.
+pub(crate) fn write_info_dictionary(
+    file: Vec<u8>,
+    tags: &[PdfDocumentMetadataTag],
+) -> Result<Vec<u8>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if update.trailer().get("Encrypt").is_some() {
+        // The strings in the information dictionary of an encrypted document
+        // must themselves be encrypted.
+
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    let existing = update.trailer().get("Info").cloned();
+
+    let mut info = match existing.as_ref().map(|info| update.resolve(info)) {
+        Some(info @ PdfSyntaxObject::Dictionary(_)) => info,
+        _ => PdfSyntaxObject::Dictionary(Vec::new()),
+    };
+
+    for tag_type in PdfDocumentMetadataTagType::ALL {
+        match tags.iter().find(|tag| tag.tag_type() == tag_type) {
+            Some(tag) => info.set(tag_type.as_pdf_key(), PdfSyntaxObject::text(tag.value())),
+            None => info.remove(tag_type.as_pdf_key()),
+        }
+    }
+
+    match existing {
+        Some(PdfSyntaxObject::Reference(number, _)) => update.set(number, info),
+        _ => {
+            let reference = update.add(info);
+
+            update.set_trailer_entry("Info", reference);
+        }
+    }
+
+    Ok(update.write(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::metadata::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    const INFO_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n\
+        3 0 obj\n<< /Title (Old) /Author (Someone) /Custom (Kept) >>\nendobj\n\
+        xref\n0 4\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 4 /Root 1 0 R /Info 3 0 R >>\nstartxref\n150\n%%EOF\n";
+
+    const NO_INFO_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n\
+        xref\n0 3\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 3 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    #[test]
+    fn test_write_info_dictionary() {
+        let date = Utc.with_ymd_and_hms(2024, 2, 29, 12, 30, 0).unwrap();
+
+        let tags = vec![
+            PdfDocumentMetadataTag::new(PdfDocumentMetadataTagType::Title, "Résumé".to_owned()),
+            PdfDocumentMetadataTag::new(
+                PdfDocumentMetadataTagType::ModificationDate,
+                date_time_to_pdf_string(date),
+            ),
+        ];
+
+        assert_eq!(tags[1].date(), Some(date));
+
+        let file = write_info_dictionary(INFO_PDF.to_vec(), &tags).unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let info = &parsed.objects[&3].object;
+
+        assert_eq!(info.get("Title"), Some(&PdfSyntaxObject::text("Résumé")));
+        assert_eq!(
+            info.get("ModDate"),
+            Some(&PdfSyntaxObject::text("D:20240229123000Z00'00'"))
+        );
+        assert_eq!(info.get("Author"), None);
+        assert_eq!(info.get("Custom"), Some(&PdfSyntaxObject::text("Kept")));
+
+        // A file without an information dictionary gains one.
+
+        let file = write_info_dictionary(NO_INFO_PDF.to_vec(), &tags).unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let info = parsed.resolve(parsed.trailer.as_ref().unwrap().get("Info").unwrap());
+
+        assert_eq!(info.get("Title"), Some(&PdfSyntaxObject::text("Résumé")));
+    }
+
+    #[test]
+    fn test_set_metadata_persisted_on_save() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let date = Utc.with_ymd_and_hms(2024, 2, 29, 12, 30, 0).unwrap();
+
+        document
+            .metadata_mut()
+            .set(PdfDocumentMetadataTagType::Title, "Quarterly report");
+        document
+            .metadata_mut()
+            .set_date(PdfDocumentMetadataTagType::CreationDate, date);
+
+        let bytes = document.save_to_bytes()?;
+
+        let document = pdfium.load_pdf_from_byte_vec(bytes, None)?;
+
+        assert_eq!(
+            document
+                .metadata()
+                .get(PdfDocumentMetadataTagType::Title)
+                .map(|tag| tag.value().to_owned()),
+            Some("Quarterly report".to_owned())
+        );
+        assert_eq!(
+            document
+                .metadata()
+                .get(PdfDocumentMetadataTagType::CreationDate)
+                .and_then(|tag| tag.date()),
+            Some(date)
+        );
+
+        Ok(())
+    }
+}
@@ -0,0 +1,834 @@
+//! Defines the [PdfBookmarkId] and [PdfBookmarkDestination] types, used when creating and
+//! editing the bookmarks in a `PdfBookmarks` collection, along with the editable copy of
+//! a document's outline that is written to the document when it is saved.
+
+use crate::bindgen::FPDF_BOOKMARK;
+use crate::error::PdfiumError;
+use crate::pdf::destination::PdfDestinationViewSettings;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{
+    decode_pdf_string, PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject,
+};
+use crate::pdf::points::PdfPoints;
+use std::collections::HashSet;
+
+#[cfg(doc)]
+use crate::pdf::document::bookmarks::PdfBookmarks;
+
+/// The maximum depth of nested outline items followed when reading or writing an outline.
+/// The limit guards against exhausting the stack when reading malicious input.
+const MAXIMUM_OUTLINE_DEPTH: usize = 64;
+
+/// The keys of an outline item dictionary that describe the item's title and its position
+/// in the outline tree. These are replaced whenever the outline is written; all other keys,
+/// such as the item's action, color, and style, are retained.
+const OUTLINE_TREE_KEYS: &[&str] = &["Title", "Parent", "Prev", "Next", "First", "Last", "Count"];
+
+/// Identifies a single bookmark in the editable outline of a [PdfBookmarks] collection.
+///
+/// Identifiers remain valid as bookmarks are moved, retitled, or added around them, but
+/// not after the identified bookmark has been removed.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PdfBookmarkId(usize);
+
+/// The page, and the view of that page, that a PDF viewer should display when the user
+/// activates a bookmark created or edited using a [PdfBookmarks] collection.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct PdfBookmarkDestination {
+    page_index: PdfPageIndex,
+    view_settings: PdfDestinationViewSettings,
+}
+
+impl PdfBookmarkDestination {
+    /// Creates a new [PdfBookmarkDestination] targeting the page at the given index,
+    /// displayed using the given view settings.
+    #[inline]
+    pub fn new(page_index: PdfPageIndex, view_settings: PdfDestinationViewSettings) -> Self {
+        PdfBookmarkDestination {
+            page_index,
+            view_settings,
+        }
+    }
+
+    /// Creates a new [PdfBookmarkDestination] targeting the top of the page at the given index,
+    /// leaving the viewer's current zoom factor unchanged.
+    #[inline]
+    pub fn page(page_index: PdfPageIndex) -> Self {
+        Self::new(
+            page_index,
+            PdfDestinationViewSettings::SpecificCoordinatesAndZoom(None, None, None),
+        )
+    }
+
+    /// Returns the index of the page targeted by this [PdfBookmarkDestination].
+    #[inline]
+    pub fn page_index(&self) -> PdfPageIndex {
+        self.page_index
+    }
+
+    /// Returns the view settings a PDF viewer should apply when displaying the target page.
+    #[inline]
+    pub fn view_settings(&self) -> PdfDestinationViewSettings {
+        self.view_settings
+    }
+
+    /// Returns an explicit destination array targeting the given page reference.
+    fn to_pdf_array(self, page: PdfSyntaxObject) -> PdfSyntaxObject {
+        let number = |value: Option<PdfPoints>| match value {
+            Some(value) => PdfSyntaxObject::Number(value.value as f64),
+            None => PdfSyntaxObject::Null,
+        };
+
+        let mut array = vec![page];
+
+        match self.view_settings {
+            PdfDestinationViewSettings::Unknown => array.extend([
+                PdfSyntaxObject::name("XYZ"),
+                PdfSyntaxObject::Null,
+                PdfSyntaxObject::Null,
+                PdfSyntaxObject::Null,
+            ]),
+            PdfDestinationViewSettings::SpecificCoordinatesAndZoom(x, y, zoom) => array.extend([
+                PdfSyntaxObject::name("XYZ"),
+                number(x),
+                number(y),
+                match zoom {
+                    Some(zoom) => PdfSyntaxObject::Number(zoom as f64),
+                    None => PdfSyntaxObject::Null,
+                },
+            ]),
+            PdfDestinationViewSettings::FitPageToWindow => array.push(PdfSyntaxObject::name("Fit")),
+            PdfDestinationViewSettings::FitPageHorizontallyToWindow(y) => {
+                array.extend([PdfSyntaxObject::name("FitH"), number(y)])
+            }
+            PdfDestinationViewSettings::FitPageVerticallyToWindow(x) => {
+                array.extend([PdfSyntaxObject::name("FitV"), number(x)])
+            }
+            PdfDestinationViewSettings::FitPageToRectangle(rect) => array.extend([
+                PdfSyntaxObject::name("FitR"),
+                number(Some(rect.left)),
+                number(Some(rect.bottom)),
+                number(Some(rect.right)),
+                number(Some(rect.top)),
+            ]),
+            PdfDestinationViewSettings::FitBoundsToWindow => {
+                array.push(PdfSyntaxObject::name("FitB"))
+            }
+            PdfDestinationViewSettings::FitBoundsHorizontallyToWindow(y) => {
+                array.extend([PdfSyntaxObject::name("FitBH"), number(y)])
+            }
+            PdfDestinationViewSettings::FitBoundsVerticallyToWindow(x) => {
+                array.extend([PdfSyntaxObject::name("FitBV"), number(x)])
+            }
+        }
+
+        PdfSyntaxObject::Array(array)
+    }
+}
+
+/// A single item in a [PdfOutline].
+struct PdfOutlineItem {
+    title: String,
+
+    /// The entries of the item's dictionary other than those describing its title and
+    /// its position in the outline tree.
+    entries: Vec<(String, PdfSyntaxObject)>,
+
+    /// The destination set for the item, replacing any destination or action given
+    /// in its entries.
+    destination: Option<PdfBookmarkDestination>,
+
+    is_open: bool,
+    object_number: Option<u32>,
+    handle: Option<FPDF_BOOKMARK>,
+    parent: Option<PdfBookmarkId>,
+    children: Vec<PdfBookmarkId>,
+    is_removed: bool,
+}
+
+/// An editable copy of the outline, or bookmark tree, of a document.
+///
+/// The outline is read from the document when it is first edited. Changes are held in memory
+/// and written to the document, replacing its existing outline, when the document is saved.
+pub(crate) struct PdfOutline {
+    items: Vec<PdfOutlineItem>,
+    roots: Vec<PdfBookmarkId>,
+    object_number: Option<u32>,
+}
+
+impl PdfOutline {
+    /// Reads the outline of the given PDF file. Returns
+    /// [PdfiumError::DocumentUpdateUnsupported] if the file cannot be parsed or is encrypted.
+    pub(crate) fn read(file: &[u8]) -> Result<Self, PdfiumError> {
+        let parsed = PdfSyntaxFile::parse(file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let trailer = parsed
+            .trailer
+            .as_ref()
+            .filter(|trailer| trailer.get("Encrypt").is_none())
+            .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let mut outline = PdfOutline {
+            items: Vec::new(),
+            roots: Vec::new(),
+            object_number: None,
+        };
+
+        let catalog = parsed.resolve(
+            trailer
+                .get("Root")
+                .ok_or(PdfiumError::DocumentUpdateUnsupported)?,
+        );
+
+        if let Some(outlines) = catalog.get("Outlines") {
+            if let PdfSyntaxObject::Reference(number, _) = outlines {
+                outline.object_number = Some(*number);
+            }
+
+            let first = parsed.resolve(outlines).get("First").cloned();
+
+            let mut visited = HashSet::new();
+
+            outline.roots = outline.read_items(&parsed, first, None, &mut visited, 0);
+        }
+
+        Ok(outline)
+    }
+
+    /// Reads the chain of sibling outline items starting with the given item, returning
+    /// the identifiers of the items read.
+    fn read_items(
+        &mut self,
+        file: &PdfSyntaxFile,
+        mut next: Option<PdfSyntaxObject>,
+        parent: Option<PdfBookmarkId>,
+        visited: &mut HashSet<u32>,
+        depth: usize,
+    ) -> Vec<PdfBookmarkId> {
+        let mut ids = Vec::new();
+
+        if depth > MAXIMUM_OUTLINE_DEPTH {
+            return ids;
+        }
+
+        while let Some(PdfSyntaxObject::Reference(number, _)) = next {
+            if !visited.insert(number) {
+                // The outline contains a cycle.
+
+                break;
+            }
+
+            let dictionary = file.resolve(&PdfSyntaxObject::Reference(number, 0));
+
+            let title = match dictionary.get("Title").map(|title| file.resolve(title)) {
+                Some(PdfSyntaxObject::String(bytes)) => decode_pdf_string(&bytes),
+                _ => String::new(),
+            };
+
+            let is_open = !matches!(dictionary.get("Count"), Some(PdfSyntaxObject::Number(count)) if *count < 0.0);
+
+            let entries = match &dictionary {
+                PdfSyntaxObject::Dictionary(entries) => entries
+                    .iter()
+                    .filter(|(key, _)| !OUTLINE_TREE_KEYS.contains(&key.as_str()))
+                    .cloned()
+                    .collect(),
+                _ => Vec::new(),
+            };
+
+            let id = PdfBookmarkId(self.items.len());
+
+            self.items.push(PdfOutlineItem {
+                title,
+                entries,
+                destination: None,
+                is_open,
+                object_number: Some(number),
+                handle: None,
+                parent,
+                children: Vec::new(),
+                is_removed: false,
+            });
+
+            self.items[id.0].children = self.read_items(
+                file,
+                dictionary.get("First").cloned(),
+                Some(id),
+                visited,
+                depth + 1,
+            );
+
+            ids.push(id);
+
+            next = dictionary.get("Next").cloned();
+        }
+
+        ids
+    }
+
+    /// Associates the items in this [PdfOutline] with the handles Pdfium uses for the same
+    /// items, using the given functions to traverse Pdfium's copy of the outline. Pdfium
+    /// follows the same chains of items as [PdfOutline::read()], so the two copies can be
+    /// traversed side by side.
+    pub(crate) fn attach_handles(
+        &mut self,
+        first_child: &dyn Fn(Option<FPDF_BOOKMARK>) -> Option<FPDF_BOOKMARK>,
+        next_sibling: &dyn Fn(FPDF_BOOKMARK) -> Option<FPDF_BOOKMARK>,
+    ) {
+        let roots = self.roots.clone();
+
+        self.attach_handles_to(&roots, first_child(None), first_child, next_sibling, 0);
+    }
+
+    fn attach_handles_to(
+        &mut self,
+        ids: &[PdfBookmarkId],
+        mut handle: Option<FPDF_BOOKMARK>,
+        first_child: &dyn Fn(Option<FPDF_BOOKMARK>) -> Option<FPDF_BOOKMARK>,
+        next_sibling: &dyn Fn(FPDF_BOOKMARK) -> Option<FPDF_BOOKMARK>,
+        depth: usize,
+    ) {
+        if depth > MAXIMUM_OUTLINE_DEPTH {
+            return;
+        }
+
+        for id in ids.iter() {
+            let current = match handle {
+                Some(current) => current,
+                None => return,
+            };
+
+            self.items[id.0].handle = Some(current);
+
+            let children = self.items[id.0].children.clone();
+
+            self.attach_handles_to(
+                &children,
+                first_child(Some(current)),
+                first_child,
+                next_sibling,
+                depth + 1,
+            );
+
+            handle = next_sibling(current);
+        }
+    }
+
+    /// Returns the identifier of the item Pdfium identifies using the given handle, if any.
+    pub(crate) fn find_handle(&self, handle: FPDF_BOOKMARK) -> Option<PdfBookmarkId> {
+        self.items
+            .iter()
+            .position(|item| !item.is_removed && item.handle == Some(handle))
+            .map(PdfBookmarkId)
+    }
+
+    /// Returns the item with the given identifier, if it has not been removed.
+    fn item(&self, id: PdfBookmarkId) -> Result<&PdfOutlineItem, PdfiumError> {
+        self.items
+            .get(id.0)
+            .filter(|item| !item.is_removed)
+            .ok_or(PdfiumError::BookmarkNotFound)
+    }
+
+    /// Returns the item with the given identifier, if it has not been removed.
+    fn item_mut(&mut self, id: PdfBookmarkId) -> Result<&mut PdfOutlineItem, PdfiumError> {
+        self.items
+            .get_mut(id.0)
+            .filter(|item| !item.is_removed)
+            .ok_or(PdfiumError::BookmarkNotFound)
+    }
+
+    /// Returns the children of the given item, or the top-level items if `None` is given.
+    pub(crate) fn children(
+        &self,
+        parent: Option<PdfBookmarkId>,
+    ) -> Result<&[PdfBookmarkId], PdfiumError> {
+        match parent {
+            Some(parent) => Ok(self.item(parent)?.children.as_slice()),
+            None => Ok(self.roots.as_slice()),
+        }
+    }
+
+    fn children_mut(
+        &mut self,
+        parent: Option<PdfBookmarkId>,
+    ) -> Result<&mut Vec<PdfBookmarkId>, PdfiumError> {
+        match parent {
+            Some(parent) => Ok(&mut self.item_mut(parent)?.children),
+            None => Ok(&mut self.roots),
+        }
+    }
+
+    /// Returns the title of the given item.
+    #[inline]
+    pub(crate) fn title(&self, id: PdfBookmarkId) -> Result<&str, PdfiumError> {
+        Ok(self.item(id)?.title.as_str())
+    }
+
+    /// Returns the parent of the given item, or `None` if it is a top-level item.
+    #[inline]
+    pub(crate) fn parent(&self, id: PdfBookmarkId) -> Result<Option<PdfBookmarkId>, PdfiumError> {
+        Ok(self.item(id)?.parent)
+    }
+
+    /// Inserts a new item with the given title and destination at the given position among
+    /// the children of the given parent, or among the top-level items if `None` is given.
+    /// An item without a destination does nothing when activated, but can still be used
+    /// to group its children.
+    pub(crate) fn insert(
+        &mut self,
+        parent: Option<PdfBookmarkId>,
+        index: usize,
+        title: &str,
+        destination: Option<PdfBookmarkDestination>,
+    ) -> Result<PdfBookmarkId, PdfiumError> {
+        if index > self.children(parent)?.len() {
+            return Err(PdfiumError::BookmarkPositionInvalid);
+        }
+
+        let id = PdfBookmarkId(self.items.len());
+
+        self.items.push(PdfOutlineItem {
+            title: title.to_owned(),
+            entries: Vec::new(),
+            destination,
+            is_open: true,
+            object_number: None,
+            handle: None,
+            parent,
+            children: Vec::new(),
+            is_removed: false,
+        });
+
+        self.children_mut(parent)?.insert(index, id);
+
+        Ok(id)
+    }
+
+    /// Moves the given item, along with its descendants, to the given position among the
+    /// children of the given parent, or among the top-level items if `None` is given.
+    /// The position is interpreted after the item has been removed from its current parent.
+    pub(crate) fn move_to(
+        &mut self,
+        id: PdfBookmarkId,
+        parent: Option<PdfBookmarkId>,
+        index: usize,
+    ) -> Result<(), PdfiumError> {
+        let current_parent = self.item(id)?.parent;
+
+        // An item cannot be moved beneath itself or one of its own descendants.
+
+        let mut ancestor = parent;
+
+        while let Some(candidate) = ancestor {
+            if candidate == id {
+                return Err(PdfiumError::BookmarkPositionInvalid);
+            }
+
+            ancestor = self.item(candidate)?.parent;
+        }
+
+        let siblings = self.children(parent)?.len();
+
+        let limit = if parent == current_parent {
+            siblings - 1
+        } else {
+            siblings
+        };
+
+        if index > limit {
+            return Err(PdfiumError::BookmarkPositionInvalid);
+        }
+
+        self.children_mut(current_parent)?
+            .retain(|child| *child != id);
+
+        self.children_mut(parent)?.insert(index, id);
+
+        self.item_mut(id)?.parent = parent;
+
+        Ok(())
+    }
+
+    /// Sets the title of the given item.
+    #[inline]
+    pub(crate) fn set_title(&mut self, id: PdfBookmarkId, title: &str) -> Result<(), PdfiumError> {
+        self.item_mut(id)?.title = title.to_owned();
+
+        Ok(())
+    }
+
+    /// Sets the destination of the given item, replacing any existing destination or action.
+    #[inline]
+    pub(crate) fn set_destination(
+        &mut self,
+        id: PdfBookmarkId,
+        destination: PdfBookmarkDestination,
+    ) -> Result<(), PdfiumError> {
+        self.item_mut(id)?.destination = Some(destination);
+
+        Ok(())
+    }
+
+    /// Sets the given entry in the dictionary of the given item, for instance to give the
+    /// item an action. The entry is overridden by any destination set for the item.
+    pub(crate) fn set_entry(
+        &mut self,
+        id: PdfBookmarkId,
+        key: &str,
+        value: PdfSyntaxObject,
+    ) -> Result<(), PdfiumError> {
+        let item = self.item_mut(id)?;
+
+        item.entries.retain(|(name, _)| name != key);
+        item.entries.push((key.to_owned(), value));
+
+        Ok(())
+    }
+
+    /// Removes the given item, along with its descendants.
+    pub(crate) fn remove(&mut self, id: PdfBookmarkId) -> Result<(), PdfiumError> {
+        let parent = self.item(id)?.parent;
+
+        self.children_mut(parent)?.retain(|child| *child != id);
+
+        let mut pending = vec![id];
+
+        while let Some(next) = pending.pop() {
+            let item = &mut self.items[next.0];
+
+            item.is_removed = true;
+
+            pending.extend(item.children.iter().copied());
+        }
+
+        Ok(())
+    }
+
+    /// Appends an incremental update to the given PDF file replacing its outline with
+    /// this [PdfOutline], returning the updated file. Items read from the file keep their
+    /// object numbers; items that were removed are left unreferenced.
+    pub(crate) fn write(&self, file: Vec<u8>) -> Result<Vec<u8>, PdfiumError> {
+        let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let pages = parsed
+            .page_references()
+            .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let mut update =
+            PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let root = match update.trailer().get("Root") {
+            Some(PdfSyntaxObject::Reference(number, _))
+                if update.trailer().get("Encrypt").is_none() =>
+            {
+                *number
+            }
+            _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+        };
+
+        let mut catalog = update
+            .get(root)
+            .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        if self.roots.is_empty() {
+            catalog.remove("Outlines");
+
+            update.set(root, catalog);
+
+            return Ok(update.write(file));
+        }
+
+        // Items keep their existing object numbers where possible, so that any references
+        // to them from elsewhere in the document remain valid.
+
+        let mut numbers = vec![None; self.items.len()];
+
+        let mut pending = self.roots.clone();
+
+        while let Some(id) = pending.pop() {
+            let item = &self.items[id.0];
+
+            numbers[id.0] = match item.object_number {
+                Some(number) if update.get(number).is_some() => Some(number),
+                _ => match update.reserve() {
+                    PdfSyntaxObject::Reference(number, _) => Some(number),
+                    _ => None,
+                },
+            };
+
+            pending.extend(item.children.iter().copied());
+        }
+
+        let outlines = match self.object_number {
+            Some(number) if update.get(number).is_some() => number,
+            _ => match update.reserve() {
+                PdfSyntaxObject::Reference(number, _) => number,
+                _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+            },
+        };
+
+        let count = self.write_items(&mut update, &self.roots, outlines, &numbers, &pages, 0)?;
+
+        let mut dictionary = match update.get(outlines) {
+            Some(dictionary @ PdfSyntaxObject::Dictionary(_)) => dictionary,
+            _ => PdfSyntaxObject::Dictionary(Vec::new()),
+        };
+
+        for key in OUTLINE_TREE_KEYS.iter() {
+            dictionary.remove(key);
+        }
+
+        dictionary.set("Type", PdfSyntaxObject::name("Outlines"));
+        dictionary.set("First", reference(&numbers, self.roots[0]));
+        dictionary.set(
+            "Last",
+            reference(&numbers, self.roots[self.roots.len() - 1]),
+        );
+        dictionary.set("Count", PdfSyntaxObject::Number(count as f64));
+
+        update.set(outlines, dictionary);
+
+        catalog.set("Outlines", PdfSyntaxObject::Reference(outlines, 0));
+
+        update.set(root, catalog);
+
+        Ok(update.write(file))
+    }
+
+    /// Writes the given chain of sibling items, returning the number of items that are
+    /// visible when the outline is first displayed.
+    fn write_items(
+        &self,
+        update: &mut PdfIncrementalUpdate,
+        ids: &[PdfBookmarkId],
+        parent: u32,
+        numbers: &[Option<u32>],
+        pages: &[PdfSyntaxObject],
+        depth: usize,
+    ) -> Result<usize, PdfiumError> {
+        if depth > MAXIMUM_OUTLINE_DEPTH {
+            return Err(PdfiumError::DocumentUpdateUnsupported);
+        }
+
+        let mut visible = 0;
+
+        for (index, id) in ids.iter().enumerate() {
+            let item = &self.items[id.0];
+
+            let number = numbers[id.0].ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+            let mut dictionary = PdfSyntaxObject::Dictionary(item.entries.clone());
+
+            dictionary.set("Title", PdfSyntaxObject::text(&item.title));
+            dictionary.set("Parent", PdfSyntaxObject::Reference(parent, 0));
+
+            if index > 0 {
+                dictionary.set("Prev", reference(numbers, ids[index - 1]));
+            }
+
+            if let Some(next) = ids.get(index + 1) {
+                dictionary.set("Next", reference(numbers, *next));
+            }
+
+            if let Some(destination) = item.destination {
+                let page = pages
+                    .get(destination.page_index() as usize)
+                    .cloned()
+                    .ok_or(PdfiumError::PageIndexOutOfBounds)?;
+
+                dictionary.remove("A");
+                dictionary.set("Dest", destination.to_pdf_array(page));
+            }
+
+            visible += 1;
+
+            if let (Some(first), Some(last)) = (item.children.first(), item.children.last()) {
+                let descendants =
+                    self.write_items(update, &item.children, number, numbers, pages, depth + 1)?;
+
+                dictionary.set("First", reference(numbers, *first));
+                dictionary.set("Last", reference(numbers, *last));
+
+                // A positive count gives the number of visible descendants of an open item;
+                // a negative count gives the number that would be visible if a closed item
+                // were opened.
+
+                if item.is_open {
+                    dictionary.set("Count", PdfSyntaxObject::Number(descendants as f64));
+
+                    visible += descendants;
+                } else {
+                    dictionary.set("Count", PdfSyntaxObject::Number(-(descendants as f64)));
+                }
+            }
+
+            update.set(number, dictionary);
+        }
+
+        Ok(visible)
+    }
+}
+
+/// Returns a reference to the object number assigned to the given item.
+#[inline]
+fn reference(numbers: &[Option<u32>], id: PdfBookmarkId) -> PdfSyntaxObject {
+    PdfSyntaxObject::Reference(numbers[id.0].unwrap_or(0), 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::outline::*;
+
+    const OUTLINE_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R /Outlines 5 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R >>\nendobj\n\
+        4 0 obj\n<< /Type /Page /Parent 2 0 R >>\nendobj\n\
+        5 0 obj\n<< /Type /Outlines /First 6 0 R /Last 7 0 R /Count 2 >>\nendobj\n\
+        6 0 obj\n<< /Title (One) /Parent 5 0 R /Next 7 0 R /Dest [3 0 R /Fit] /C [1 0 0] >>\nendobj\n\
+        7 0 obj\n<< /Title (Two) /Parent 5 0 R /Prev 6 0 R /First 8 0 R /Last 8 0 R /Count -1 >>\nendobj\n\
+        8 0 obj\n<< /Title (Two point one) /Parent 7 0 R /A << /S /URI /URI (https://example.com) >> >>\nendobj\n\
+        xref\n0 9\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 9 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    fn titles(outline: &PdfOutline, parent: Option<PdfBookmarkId>) -> Vec<&str> {
+        outline
+            .children(parent)
+            .unwrap()
+            .iter()
+            .map(|id| outline.title(*id).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_read_outline() {
+        let outline = PdfOutline::read(OUTLINE_PDF).unwrap();
+
+        assert_eq!(titles(&outline, None), vec!["One", "Two"]);
+
+        let two = outline.children(None).unwrap()[1];
+
+        assert_eq!(titles(&outline, Some(two)), vec!["Two point one"]);
+        assert!(!outline.items[two.0].is_open);
+    }
+
+    #[test]
+    fn test_edit_outline() {
+        let mut outline = PdfOutline::read(OUTLINE_PDF).unwrap();
+
+        let one = outline.children(None).unwrap()[0];
+        let two = outline.children(None).unwrap()[1];
+
+        let zero = outline
+            .insert(None, 0, "Zero", Some(PdfBookmarkDestination::page(1)))
+            .unwrap();
+
+        outline
+            .insert(
+                Some(zero),
+                0,
+                "Zero point one",
+                Some(PdfBookmarkDestination::page(0)),
+            )
+            .unwrap();
+
+        outline.set_title(one, "First").unwrap();
+        outline.move_to(one, Some(two), 1).unwrap();
+
+        assert_eq!(titles(&outline, None), vec!["Zero", "Two"]);
+        assert_eq!(titles(&outline, Some(two)), vec!["Two point one", "First"]);
+        assert_eq!(outline.parent(one).unwrap(), Some(two));
+
+        assert!(matches!(
+            outline.move_to(two, Some(one), 0),
+            Err(PdfiumError::BookmarkPositionInvalid)
+        ));
+        assert!(matches!(
+            outline.insert(None, 3, "Four", None),
+            Err(PdfiumError::BookmarkPositionInvalid)
+        ));
+
+        outline.remove(two).unwrap();
+
+        assert_eq!(titles(&outline, None), vec!["Zero"]);
+        assert!(matches!(
+            outline.title(one),
+            Err(PdfiumError::BookmarkNotFound)
+        ));
+    }
+
+    #[test]
+    fn test_write_outline() {
+        let mut outline = PdfOutline::read(OUTLINE_PDF).unwrap();
+
+        let one = outline.children(None).unwrap()[0];
+        let two = outline.children(None).unwrap()[1];
+
+        outline.move_to(two, None, 0).unwrap();
+
+        let three = outline
+            .insert(
+                Some(one),
+                0,
+                "One point one",
+                Some(PdfBookmarkDestination::page(1)),
+            )
+            .unwrap();
+
+        outline.set_title(three, "One \u{2014} first").unwrap();
+
+        let file = outline.write(OUTLINE_PDF.to_vec()).unwrap();
+
+        let written = PdfOutline::read(&file).unwrap();
+
+        assert_eq!(titles(&written, None), vec!["Two", "One"]);
+
+        let one = written.children(None).unwrap()[1];
+
+        assert_eq!(titles(&written, Some(one)), vec!["One \u{2014} first"]);
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let item = |number: u32| parsed.objects.get(&number).unwrap().object.clone();
+
+        // Existing items keep their object numbers, actions, and colors.
+
+        assert_eq!(
+            item(6).get("C"),
+            Some(&PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Number(1.0),
+                PdfSyntaxObject::Number(0.0),
+                PdfSyntaxObject::Number(0.0),
+            ]))
+        );
+        assert_eq!(item(6).get("Count"), Some(&PdfSyntaxObject::Number(1.0)));
+        assert_eq!(item(7).get("Count"), Some(&PdfSyntaxObject::Number(-1.0)));
+        assert!(item(8).get("A").is_some());
+
+        // The outline root counts the two top-level items and the open item's child.
+
+        assert_eq!(item(5).get("Count"), Some(&PdfSyntaxObject::Number(3.0)));
+        assert_eq!(
+            item(5).get("First"),
+            Some(&PdfSyntaxObject::Reference(7, 0))
+        );
+
+        let new_item = written.items[written.children(Some(one)).unwrap()[0].0]
+            .entries
+            .iter()
+            .find(|(key, _)| key == "Dest")
+            .map(|(_, value)| value.clone());
+
+        assert_eq!(
+            new_item,
+            Some(PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Reference(4, 0),
+                PdfSyntaxObject::name("XYZ"),
+                PdfSyntaxObject::Null,
+                PdfSyntaxObject::Null,
+                PdfSyntaxObject::Null,
+            ]))
+        );
+    }
+}
@@ -1,16 +1,31 @@
 //! Defines the [PdfPage] struct, exposing functionality related to a single page in a
 //! `PdfPages` collection.
 
+pub mod additional_actions;
 pub mod annotation;
 pub mod annotations;
 pub mod boundaries;
+pub mod coordinate_mapper;
 pub mod field;
+pub(crate) mod generation;
 pub(crate) mod index_cache;
+pub(crate) mod layers;
 pub mod links;
 pub mod object;
 pub mod objects;
+pub mod page_number;
+
+#[cfg(any(
+    feature = "pdfium_6611",
+    feature = "pdfium_6666",
+    feature = "pdfium_future"
+))]
+pub(crate) mod redaction;
+
 pub mod render_config;
+pub mod render_stats;
 pub mod size;
+pub mod tagged_text;
 pub mod text;
 
 #[cfg(feature = "paragraph")]
@@ -20,33 +35,83 @@ pub mod paragraph;
 mod flatten; // Keep internal flatten operation private.
 
 use crate::bindgen::{
-    FLATTEN_FAIL, FLATTEN_NOTHINGTODO, FLATTEN_SUCCESS, FLAT_PRINT, FPDF_DOCUMENT, FPDF_FORMHANDLE,
-    FPDF_PAGE,
+    FLATTEN_FAIL, FLATTEN_NOTHINGTODO, FLATTEN_SUCCESS, FLAT_PRINT, FPDF_BITMAP, FPDF_BOOL,
+    FPDF_DOCUMENT, FPDF_FORMHANDLE, FPDF_PAGE, FPDF_RENDER_FAILED, FPDF_RENDER_TOBECONTINUED,
+    FS_RECTF, IFSDK_PAUSE,
 };
 use crate::bindings::PdfiumLibraryBindings;
 use crate::create_transform_setters;
 use crate::error::{PdfiumError, PdfiumInternalError};
 use crate::pdf::bitmap::{PdfBitmap, PdfBitmapFormat, Pixels};
+use crate::pdf::document::page::additional_actions::{
+    PdfPageAdditionalActions, PdfPageAdditionalActionsEdits,
+};
+use crate::pdf::document::page::annotation::PdfPageAnnotationType;
 use crate::pdf::document::page::annotations::PdfPageAnnotations;
 use crate::pdf::document::page::boundaries::PdfPageBoundaries;
+use crate::pdf::document::page::coordinate_mapper::PdfPageCoordinateMapper;
+use crate::pdf::document::page::generation::PdfContentGenerations;
 use crate::pdf::document::page::index_cache::PdfPageIndexCache;
+use crate::pdf::document::page::layers::apply_layer_visibility;
 use crate::pdf::document::page::links::PdfPageLinks;
 use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
 use crate::pdf::document::page::objects::PdfPageObjects;
-use crate::pdf::document::page::render_config::{PdfRenderConfig, PdfRenderSettings};
+use crate::pdf::document::page::page_number::{detect_printed_page_number, PdfPrintedPageNumber};
+use crate::pdf::document::page::render_config::{
+    PdfRenderConfig, PdfRenderProgressCallback, PdfRenderSettings,
+};
+use crate::pdf::document::page::render_stats::{PdfRenderStats, PdfRenderTimer};
 use crate::pdf::document::page::size::PdfPagePaperSize;
+use crate::pdf::document::page::tagged_text::PdfTaggedTextNode;
 use crate::pdf::document::page::text::PdfPageText;
+use crate::pdf::document::save_document_to_bytes;
 use crate::pdf::font::PdfFont;
 use crate::pdf::matrix::{PdfMatrix, PdfMatrixValue};
 use crate::pdf::points::PdfPoints;
 use crate::pdf::rect::PdfRect;
+use crate::pdfium::Pdfium;
+use crate::utils::mem::create_byte_buffer;
 use std::collections::{hash_map::Entry, HashMap};
 use std::f32::consts::{FRAC_PI_2, PI};
-use std::os::raw::{c_double, c_int};
+use std::ops::ControlFlow;
+use std::os::raw::{c_double, c_int, c_ulong, c_void};
+
+#[cfg(all(feature = "skia", not(target_arch = "wasm32")))]
+use crate::bindgen::FPDF_SKIA_CANVAS;
+
+#[cfg(feature = "image")]
+use crate::pdf::bitmap::encode_image_with_dpi;
+
+#[cfg(feature = "image")]
+use image::ImageFormat;
+
+#[cfg(feature = "tables")]
+use crate::pdf::tables::{tables_in_page, PdfTable, PdfTableConfig};
+
+#[cfg(any(
+    feature = "pdfium_6611",
+    feature = "pdfium_6666",
+    feature = "pdfium_future"
+))]
+use crate::pdf::document::page::redaction::{add_overlay_text, redact_page};
+
+#[cfg(any(
+    feature = "pdfium_6611",
+    feature = "pdfium_6666",
+    feature = "pdfium_future"
+))]
+use crate::pdf::color::PdfColor;
 
 #[cfg(doc)]
 use crate::pdf::document::PdfDocument;
 
+#[cfg(doc)]
+use crate::pdf::document::render_cache::PdfRenderCache;
+
+/// The number of page objects Pdfium renders between each opportunity to pause its
+/// progressive rendering loop, used to estimate rendering progress.
+const OBJECTS_PER_PROGRESSIVE_RENDER_STEP: f32 = 100.0;
+
 /// The orientation of a [PdfPage].
 #[derive(Copy, Clone, Debug, PartialEq)]
 pub enum PdfPageOrientation {
@@ -311,6 +376,8 @@ impl<'a> PdfPage<'a> {
     pub fn set_rotation(&mut self, rotation: PdfPageRenderRotation) {
         self.bindings
             .FPDFPage_SetRotation(self.page_handle, rotation.as_pdfium());
+
+        self.mark_content_changed();
     }
 
     /// Returns `true` if any object on the page contains transparency.
@@ -364,7 +431,7 @@ impl<'a> PdfPage<'a> {
     ///         &PdfRenderConfig::thumbnail(thumbnail_desired_pixel_size)
     ///     )?; // Renders a 128 x 128 thumbnail of the page
     /// ```
-    pub fn embedded_thumbnail(&self) -> Result<PdfBitmap, PdfiumError> {
+    pub fn embedded_thumbnail(&self) -> Result<PdfBitmap<'a>, PdfiumError> {
         let thumbnail_handle = self
             .bindings()
             .FPDFPage_GetThumbnailAsBitmap(self.page_handle);
@@ -378,6 +445,79 @@ impl<'a> PdfPage<'a> {
         }
     }
 
+    /// Returns the raw, undecoded byte data of the embedded thumbnail for this [PdfPage], if any.
+    /// The data is returned exactly as it is stored in the thumbnail's image stream; any stream
+    /// filters (for instance, `DCTDecode` for JPEG-compressed thumbnails) will not be applied.
+    ///
+    /// Use [PdfPage::embedded_thumbnail_decoded_data()] to retrieve the thumbnail data with
+    /// all stream filters applied, or [PdfPage::embedded_thumbnail()] to retrieve the thumbnail
+    /// as a [PdfBitmap].
+    pub fn embedded_thumbnail_raw_data(&self) -> Result<Vec<u8>, PdfiumError> {
+        self.get_thumbnail_data(|page, buffer, buflen| {
+            self.bindings()
+                .FPDFPage_GetRawThumbnailData(page, buffer, buflen)
+        })
+    }
+
+    /// Returns the decoded byte data of the embedded thumbnail for this [PdfPage], if any.
+    /// All stream filters applied to the thumbnail's image stream will have been decoded.
+    pub fn embedded_thumbnail_decoded_data(&self) -> Result<Vec<u8>, PdfiumError> {
+        self.get_thumbnail_data(|page, buffer, buflen| {
+            self.bindings()
+                .FPDFPage_GetDecodedThumbnailData(page, buffer, buflen)
+        })
+    }
+
+    // Retrieves thumbnail data for this page using the given Pdfium thumbnail data function.
+    fn get_thumbnail_data(
+        &self,
+        get_data: impl Fn(FPDF_PAGE, *mut c_void, c_ulong) -> c_ulong,
+    ) -> Result<Vec<u8>, PdfiumError> {
+        // Retrieving thumbnail data from Pdfium is a two-step operation. First, we call
+        // the data function with a null buffer; this will retrieve the length of
+        // the data in bytes. If the length is zero, then there is no thumbnail.
+
+        // If the length is non-zero, then we reserve a byte buffer of the given
+        // length and call the data function again with a pointer to the buffer;
+        // this will write the thumbnail data into the buffer.
+
+        let buffer_length = get_data(self.page_handle, std::ptr::null_mut(), 0);
+
+        if buffer_length == 0 {
+            // No thumbnail is available for this page.
+
+            return Err(PdfiumError::PageMissingEmbeddedThumbnail);
+        }
+
+        let mut buffer = create_byte_buffer(buffer_length as usize);
+
+        let result = get_data(
+            self.page_handle,
+            buffer.as_mut_ptr() as *mut c_void,
+            buffer_length,
+        );
+
+        assert_eq!(result, buffer_length);
+
+        Ok(buffer)
+    }
+
+    /// Returns a thumbnail image of this [PdfPage] no larger than the given maximum
+    /// pixel dimension in either direction.
+    ///
+    /// If this page contains an embedded thumbnail that fits within the given maximum dimension,
+    /// the embedded thumbnail will be returned. Otherwise, a thumbnail will be rendered from the
+    /// page using the settings applied by [PdfRenderConfig::thumbnail()].
+    pub fn thumbnail(&self, max_dimension: Pixels) -> Result<PdfBitmap<'a>, PdfiumError> {
+        if let Ok(thumbnail) = self.embedded_thumbnail() {
+            if thumbnail.width() <= max_dimension && thumbnail.height() <= max_dimension {
+                return Ok(thumbnail);
+            }
+        }
+
+        self.render_with_config(&PdfRenderConfig::new().thumbnail(max_dimension))
+    }
+
     /// Returns the collection of text boxes contained within this [PdfPage].
     pub fn text(&self) -> Result<PdfPageText, PdfiumError> {
         if self.regeneration_strategy == PdfPageContentRegenerationStrategy::AutomaticOnEveryChange
@@ -397,6 +537,77 @@ impl<'a> PdfPage<'a> {
         }
     }
 
+    /// Returns the text in this [PdfPage] structured according to the page's structure tree,
+    /// as a tree of [PdfTaggedTextNode] objects, one for each top-level structure element.
+    ///
+    /// Each node gives the structure type of its element, such as a heading, a paragraph,
+    /// or a table cell, and the text marked by that element. Nodes are in the logical
+    /// reading order defined by the document's author, rather than the order in which
+    /// text is defined in the page's content stream. Text marked as an artifact, such as
+    /// running headers and footers, is not included in any node.
+    ///
+    /// Returns an empty list if this [PdfPage] is not tagged.
+    pub fn tagged_text(&self) -> Result<Vec<PdfTaggedTextNode>, PdfiumError> {
+        let text = self.text()?;
+
+        Ok(PdfTaggedTextNode::from_pdfium(
+            self.page_handle,
+            *text.handle(),
+            self.bindings(),
+        ))
+    }
+
+    /// Detects the tables in this [PdfPage] using the default [PdfTableConfig], returning
+    /// the text of each table's cells. Tables are returned from the top of the page to the
+    /// bottom.
+    ///
+    /// Table detection is heuristic; see the `pdf::tables` module for the kinds of tables
+    /// that can be detected. Use [PdfPage::tables_with_config()] to adjust how tables
+    /// are detected.
+    #[cfg(feature = "tables")]
+    #[inline]
+    pub fn tables(&self) -> Vec<PdfTable> {
+        self.tables_with_config(&PdfTableConfig::new())
+    }
+
+    /// Detects the tables in this [PdfPage] using the given [PdfTableConfig], returning
+    /// the text of each table's cells. Tables are returned from the top of the page to the
+    /// bottom.
+    #[cfg(feature = "tables")]
+    #[inline]
+    pub fn tables_with_config(&self, config: &PdfTableConfig) -> Vec<PdfTable> {
+        tables_in_page(self, config)
+    }
+
+    /// Attempts to locate the page number printed in the content of this [PdfPage], if any.
+    ///
+    /// Text near the top and bottom margins of the page is examined for arabic or roman
+    /// numerals, either standing alone or with common decoration such as `Page 12`, `- 12 -`,
+    /// or `12 of 300`, or at the start or end of a running header or footer. If several
+    /// candidates are found, text closer to the edge of the page, in the footer, and either
+    /// centered or aligned to an outside edge is preferred.
+    ///
+    /// This is a heuristic; it is intended to help re-synchronize a document's logical page
+    /// numbering with its printed page numbering, for instance in scanned books, and may
+    /// return `None` or an incorrect result for pages with unusual layouts. Pages without a
+    /// text layer, such as scanned pages that have not been through OCR, will always
+    /// return `None`.
+    pub fn detect_printed_page_number(&self) -> Result<Option<PdfPrintedPageNumber>, PdfiumError> {
+        let text = self.text()?;
+
+        let candidates = text
+            .segments()
+            .iter()
+            .map(|segment| (segment.text(), segment.bounds()))
+            .collect::<Vec<_>>();
+
+        Ok(detect_printed_page_number(
+            self.width(),
+            self.height(),
+            candidates.into_iter(),
+        ))
+    }
+
     /// Returns an immutable collection of the annotations that have been added to this [PdfPage].
     pub fn annotations(&self) -> &PdfPageAnnotations<'a> {
         if self.regeneration_strategy == PdfPageContentRegenerationStrategy::AutomaticOnEveryChange
@@ -447,6 +658,30 @@ impl<'a> PdfPage<'a> {
         &self.links
     }
 
+    /// Returns the additional actions of this [PdfPage]: actions performed automatically
+    /// by a viewer when this page is opened or closed.
+    #[inline]
+    pub fn additional_actions(&self) -> PdfPageAdditionalActions<'a> {
+        PdfPageAdditionalActions::from_pdfium(self.document_handle, self.page_handle, self.bindings)
+    }
+
+    /// Removes the additional actions of this [PdfPage], so that no actions are performed
+    /// automatically by a viewer when this page is opened or closed.
+    ///
+    /// Pdfium cannot edit a page's `/AA` entry itself, so the entry is removed from the page
+    /// dictionary when the containing `PdfDocument` is next saved. The cleared actions are
+    /// no longer returned by [PdfPage::additional_actions()] in the meantime. The page is
+    /// identified by its index, so pages should not be inserted, deleted, or reordered
+    /// between clearing the additional actions and saving the document.
+    pub fn clear_additional_actions(&mut self) -> Result<(), PdfiumError> {
+        let index = PdfPageIndexCache::get_index_for_page(self.document_handle, self.page_handle)
+            .ok_or(PdfiumError::SourcePageIndexNotInCache)?;
+
+        PdfPageAdditionalActionsEdits::clear(self.document_handle, index);
+
+        Ok(())
+    }
+
     /// Returns a mutable collection of the links on this [PdfPage].
     #[inline]
     pub fn links_mut(&mut self) -> &mut PdfPageLinks<'a> {
@@ -572,6 +807,16 @@ impl<'a> PdfPage<'a> {
         }
     }
 
+    /// Returns a [PdfPageCoordinateMapper] that converts positions between the coordinate system
+    /// of this [PdfPage] and the coordinate system of a bitmap into which this page has been
+    /// rendered using the given [PdfRenderConfig]. Prefer this to repeated calls to
+    /// [PdfPage::pixels_to_points()] or [PdfPage::points_to_pixels()] when converting
+    /// many positions, such as when tracking mouse movements in a viewer.
+    #[inline]
+    pub fn coordinate_mapper(&self, config: &PdfRenderConfig) -> PdfPageCoordinateMapper<'_> {
+        PdfPageCoordinateMapper::new(self, config)
+    }
+
     /// Renders this [PdfPage] into a [PdfBitmap] with the given pixel dimensions and page rotation.
     ///
     /// It is the responsibility of the caller to ensure the given pixel width and height
@@ -612,9 +857,16 @@ impl<'a> PdfPage<'a> {
     /// allocates memory for it. To avoid repeated allocations, create a single [PdfBitmap] object
     /// using [PdfBitmap::empty()] and reuse it across multiple calls to
     /// [PdfPage::render_into_bitmap_with_config()].
-    pub fn render_with_config(&self, config: &PdfRenderConfig) -> Result<PdfBitmap, PdfiumError> {
+    pub fn render_with_config(
+        &self,
+        config: &PdfRenderConfig,
+    ) -> Result<PdfBitmap<'a>, PdfiumError> {
         let settings = config.apply_to_page(self);
 
+        if settings.is_maximum_bitmap_bytes_exceeded {
+            return Err(PdfiumError::RenderBudgetExceeded);
+        }
+
         let mut bitmap = PdfBitmap::empty(
             settings.width as Pixels,
             settings.height as Pixels,
@@ -625,9 +877,53 @@ impl<'a> PdfPage<'a> {
 
         self.render_into_bitmap_with_settings(&mut bitmap, settings)?;
 
+        bitmap.record_buffer_allocation_in_render_stats();
+
         Ok(bitmap)
     }
 
+    /// Renders this [PdfPage] using pixel dimensions, page rotation settings, and rendering
+    /// options configured in the given [PdfRenderConfig], then encodes the rendered image
+    /// in the given file format, returning the encoded bytes.
+    ///
+    /// The resolution of the rendered image, in dots per inch, is recorded in the `pHYs` chunk
+    /// of PNG files and in the JFIF header of JPEG files, so that the image prints at the
+    /// same physical size as the page. The resolution is derived from the ratio of the rendered
+    /// image's pixel dimensions to the page's dimensions in points, so it will not reflect the
+    /// page's physical size if the configuration applies a clipping rectangle or a custom
+    /// transformation matrix. Resolutions are not recorded in files of other formats,
+    /// including WebP.
+    ///
+    /// JPEG files do not support an alpha channel, so any transparency in the rendered image
+    /// is discarded when encoding to JPEG.
+    ///
+    /// This function is only available when this crate's `image` feature is enabled.
+    #[cfg(feature = "image")]
+    pub fn render_to_encoded(
+        &self,
+        config: &PdfRenderConfig,
+        format: ImageFormat,
+    ) -> Result<Vec<u8>, PdfiumError> {
+        let settings = config.apply_to_page(self);
+
+        let bitmap = self.render_with_config(config)?;
+
+        // Rotating the page by a quarter turn swaps its horizontal and vertical dimensions.
+
+        let (page_width, page_height) = if settings.rotate % 2 == 1 {
+            (self.height(), self.width())
+        } else {
+            (self.width(), self.height())
+        };
+
+        let dpi = (
+            bitmap.width() as f32 / page_width.to_inches(),
+            bitmap.height() as f32 / page_height.to_inches(),
+        );
+
+        encode_image_with_dpi(&bitmap.as_image(), format, dpi)
+    }
+
     /// Renders this [PdfPage] into the given [PdfBitmap] using the given pixel dimensions
     /// and page rotation.
     ///
@@ -669,6 +965,160 @@ impl<'a> PdfPage<'a> {
         self.render_into_bitmap_with_settings(bitmap, config.apply_to_page(self))
     }
 
+    /// Renders this [PdfPage] into a new [PdfBitmap] with the given pixel dimensions, applying
+    /// the given transformation matrix and clipping output to the given rectangle.
+    ///
+    /// This is an alternative to the size and rotation settings of [PdfRenderConfig] for callers
+    /// that need full control over the transformation from page space to bitmap space,
+    /// for example when implementing custom pan, zoom, or skew effects. The given matrix is
+    /// applied after the page has been mapped onto the bitmap at a scale of one pixel per point,
+    /// with the origin at the top left corner of the bitmap.
+    ///
+    /// The clipping rectangle is expressed in pixels, relative to the top left corner of the
+    /// bitmap. Pdfium will not render outside the clipping rectangle.
+    ///
+    /// Rendering flags, the bitmap format, and the background color are taken from the given
+    /// [PdfRenderConfig]; its size, rotation, transformation, and clipping settings are ignored.
+    /// Form data cannot be rendered when a transformation matrix is applied, so the
+    /// [PdfRenderConfig::render_form_data()] setting is also ignored.
+    pub fn render_with_matrix(
+        &self,
+        width: Pixels,
+        height: Pixels,
+        matrix: PdfMatrix,
+        clip: PdfRect,
+        config: &PdfRenderConfig,
+    ) -> Result<PdfBitmap<'a>, PdfiumError> {
+        let settings = config.apply_to_page(self);
+
+        let mut bitmap = PdfBitmap::empty(
+            width,
+            height,
+            PdfBitmapFormat::from_pdfium(settings.format as u32)
+                .unwrap_or_else(|_| PdfBitmapFormat::default()),
+            self.bindings,
+        )?;
+
+        self.render_into_bitmap_with_matrix(&mut bitmap, matrix, clip, config)?;
+
+        Ok(bitmap)
+    }
+
+    /// Renders this [PdfPage] into the given [PdfBitmap], applying the given transformation
+    /// matrix and clipping output to the given rectangle. The bitmap's existing pixel
+    /// dimensions are used.
+    ///
+    /// See [PdfPage::render_with_matrix()] for details of how the matrix, clipping rectangle,
+    /// and [PdfRenderConfig] are applied.
+    pub fn render_into_bitmap_with_matrix(
+        &self,
+        bitmap: &mut PdfBitmap,
+        matrix: PdfMatrix,
+        clip: PdfRect,
+        config: &PdfRenderConfig,
+    ) -> Result<(), PdfiumError> {
+        let mut settings = config.apply_to_page(self);
+
+        settings.width = bitmap.width() as c_int;
+        settings.height = bitmap.height() as c_int;
+        settings.do_render_form_data = false;
+        settings.form_field_highlight = None;
+        settings.matrix = matrix.as_pdfium();
+
+        // Bitmap coordinates increase downwards, so accept the clipping rectangle's
+        // vertical bounds in either order.
+
+        settings.clipping = FS_RECTF {
+            left: clip.left.value.min(clip.right.value),
+            top: clip.top.value.min(clip.bottom.value),
+            right: clip.left.value.max(clip.right.value),
+            bottom: clip.top.value.max(clip.bottom.value),
+        };
+
+        self.render_into_bitmap_with_settings(bitmap, settings)
+    }
+
+    /// Renders this [PdfPage] directly onto the given Skia canvas using pixel dimensions,
+    /// page rotation settings, and rendering options configured in the given [PdfRenderConfig].
+    ///
+    /// The page is drawn at the canvas's current origin, using the canvas's current
+    /// transformation matrix and clip. User-supplied form data is drawn as an overlay on top
+    /// of the page if the [PdfRenderConfig::render_form_data()] setting is enabled.
+    /// Custom transformation matrices and clipping rectangles set on the [PdfRenderConfig]
+    /// are ignored; apply them to the canvas instead. Returns [PdfiumError::ZeroSizedRenderTarget]
+    /// if the configured pixel dimensions give the page a width or height of zero.
+    ///
+    /// This function is only available when the `skia` crate feature is enabled. You must bind
+    /// to a build of Pdfium that was compiled with the `PDF_USE_SKIA` flag, or rendering will fail.
+    #[cfg(all(feature = "skia", not(target_arch = "wasm32")))]
+    pub fn render_to_skia_canvas(
+        &self,
+        canvas: &skia_safe::Canvas,
+        config: &PdfRenderConfig,
+    ) -> Result<(), PdfiumError> {
+        let settings = config.apply_to_page(self).without_subpixel_reversal();
+
+        if settings.width <= 0 || settings.height <= 0 {
+            return Err(PdfiumError::ZeroSizedRenderTarget);
+        }
+
+        // skia_safe::Canvas is a transparent wrapper around Skia's native SkCanvas,
+        // so a pointer to it can be passed directly to Pdfium.
+
+        let canvas_handle = canvas as *const skia_safe::Canvas as FPDF_SKIA_CANVAS;
+
+        canvas.save();
+
+        if settings.do_clear_bitmap_before_rendering {
+            canvas.clip_rect(
+                skia_safe::Rect::from_iwh(settings.width, settings.height),
+                None,
+                None,
+            );
+            canvas.clear(skia_safe::Color::new(settings.clear_color as u32));
+        }
+
+        self.bindings.FPDF_RenderPageSkia(
+            canvas_handle,
+            self.page_handle,
+            settings.width,
+            settings.height,
+        );
+
+        if let (true, Some(form_handle)) = (settings.do_render_form_data, self.form_handle) {
+            // Render user-supplied form data, if any, as an overlay on top of the page.
+
+            if let Some(form_field_highlight) = settings.form_field_highlight.as_ref() {
+                for (form_field_type, (color, alpha)) in form_field_highlight.iter() {
+                    self.bindings.FPDF_SetFormFieldHighlightColor(
+                        form_handle,
+                        *form_field_type,
+                        *color,
+                    );
+
+                    self.bindings
+                        .FPDF_SetFormFieldHighlightAlpha(form_handle, *alpha);
+                }
+            }
+
+            self.bindings.FPDF_FFLDrawSkia(
+                form_handle,
+                canvas_handle,
+                self.page_handle,
+                0,
+                0,
+                settings.width,
+                settings.height,
+                settings.rotate,
+                settings.render_flags,
+            );
+        }
+
+        canvas.restore();
+
+        Ok(())
+    }
+
     /// Renders this [PdfPage] into the given [PdfBitmap] using the given [PdfRenderSettings].
     /// The size of the buffer backing the given bitmap must be sufficiently large to hold
     /// the rendered image or an error will be returned.
@@ -677,9 +1127,146 @@ impl<'a> PdfPage<'a> {
         bitmap: &mut PdfBitmap,
         settings: PdfRenderSettings,
     ) -> Result<(), PdfiumError> {
-        let bitmap_handle = *bitmap.handle();
+        if settings.do_clear_to_transparent && bitmap.format()? != PdfBitmapFormat::BGRA {
+            return Err(PdfiumError::TransparentBackgroundRequiresBitmapWithAlphaChannel);
+        }
 
-        if settings.do_clear_bitmap_before_rendering {
+        if !settings.layer_visibility.is_empty() {
+            return self.render_into_bitmap_with_layer_visibility(bitmap, settings);
+        }
+
+        let timer = PdfRenderTimer::start();
+
+        let mut stats = PdfRenderStats::default();
+
+        self.render_bitmap_layers(bitmap, &settings, &mut stats)?;
+
+        if settings.do_reverse_subpixel_order {
+            // Pdfium only renders subpixel anti-aliased text for displays with red, green, blue
+            // subpixels. Render the page again with text anti-aliased in shades of gray, so that
+            // the subpixel contributions of the first rendering can be isolated and reversed.
+
+            let grayscale_settings = settings.clone().without_subpixel_reversal();
+
+            let mut grayscale = PdfBitmap::empty(
+                bitmap.width(),
+                bitmap.height(),
+                bitmap.format()?,
+                self.bindings,
+            )?;
+
+            self.render_bitmap_layers(&mut grayscale, &grayscale_settings, &mut stats)?;
+
+            bitmap.reverse_subpixel_order(&grayscale)?;
+        }
+
+        bitmap.set_byte_order_from_render_settings(&settings);
+
+        if let Some(threshold) = settings.monochrome_threshold {
+            if settings.do_dither_monochrome {
+                bitmap.apply_dithering(threshold)?;
+            } else {
+                bitmap.apply_threshold(threshold)?;
+            }
+        }
+
+        if settings.do_collect_render_stats {
+            stats.conversion_duration = timer.elapsed().saturating_sub(stats.pdfium_duration);
+
+            bitmap.set_render_stats(Some(stats));
+        } else {
+            bitmap.set_render_stats(None);
+        }
+
+        Ok(())
+    }
+
+    /// Renders either every layer of this [PdfPage], or only its annotation layer, into the
+    /// given [PdfBitmap] using the given [PdfRenderSettings].
+    fn render_bitmap_layers(
+        &self,
+        bitmap: &mut PdfBitmap,
+        settings: &PdfRenderSettings,
+        stats: &mut PdfRenderStats,
+    ) -> Result<(), PdfiumError> {
+        if settings.do_render_annotation_layer_only {
+            self.render_annotation_layer_into_bitmap(bitmap, settings, stats)
+        } else {
+            self.render_layers_into_bitmap(*bitmap.handle(), settings, true, stats)
+        }
+    }
+
+    /// Renders this [PdfPage] into the given [PdfBitmap] using the given [PdfRenderSettings],
+    /// showing and hiding the optional content groups, or layers, named in the settings.
+    ///
+    /// Pdfium cannot change the visibility of layers directly, so the page is rendered from
+    /// a temporary copy of its document whose default layer configuration has been changed.
+    fn render_into_bitmap_with_layer_visibility(
+        &self,
+        bitmap: &mut PdfBitmap,
+        mut settings: PdfRenderSettings,
+    ) -> Result<(), PdfiumError> {
+        let index = PdfPageIndexCache::get_index_for_page(self.document_handle, self.page_handle)
+            .ok_or(PdfiumError::SourcePageIndexNotInCache)?;
+
+        let file = apply_layer_visibility(
+            save_document_to_bytes(self.document_handle, self.bindings)?,
+            &settings.layer_visibility,
+        )?;
+
+        // Pdfium requires the file to remain valid for as long as the copy is open.
+
+        let document_handle = self.bindings.FPDF_LoadMemDocument64(file.as_slice(), None);
+
+        if document_handle.is_null() {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let page_handle = self.bindings.FPDF_LoadPage(document_handle, index as c_int);
+
+        let result = if page_handle.is_null() {
+            Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ))
+        } else {
+            settings.layer_visibility = Vec::new();
+
+            PdfPage::from_pdfium(document_handle, page_handle, None, None, self.bindings)
+                .render_into_bitmap_with_settings(bitmap, settings)
+        };
+
+        // The temporary page has been dropped, so the copy can now be closed.
+
+        self.bindings.FPDF_CloseDocument(document_handle);
+
+        result
+    }
+
+    /// Renders this [PdfPage] into the given bitmap handle using the given [PdfRenderSettings].
+    /// User-supplied form data will be rendered as an overlay on top of the page only if
+    /// the settings request it and the given flag is `true`. The time spent rendering and
+    /// the number of rendering segments are added to the given [PdfRenderStats].
+    fn render_layers_into_bitmap(
+        &self,
+        bitmap_handle: FPDF_BITMAP,
+        settings: &PdfRenderSettings,
+        do_render_form_layer: bool,
+        stats: &mut PdfRenderStats,
+    ) -> Result<(), PdfiumError> {
+        let timer = PdfRenderTimer::start();
+
+        if settings.do_clear_to_transparent {
+            // Clear the bitmap buffer by zeroing it directly, since the alpha handling of
+            // FPDFBitmap_FillRect() is not consistent across Pdfium versions.
+
+            let buffer_length = self.bindings.FPDFBitmap_GetStride(bitmap_handle)
+                * self.bindings.FPDFBitmap_GetHeight(bitmap_handle);
+
+            self.bindings
+                .FPDFBitmap_SetBuffer(bitmap_handle, &vec![0; buffer_length.max(0) as usize]);
+        } else if settings.do_clear_bitmap_before_rendering {
             // Clear the bitmap buffer by setting every pixel to a known color.
 
             self.bindings().FPDFBitmap_FillRect(
@@ -696,18 +1283,27 @@ impl<'a> PdfPage<'a> {
             // Render the PDF page into the bitmap buffer, ignoring any custom transformation matrix.
             // (Custom transforms cannot be applied to the rendering of form fields.)
 
-            self.bindings.FPDF_RenderPageBitmap(
-                bitmap_handle,
-                self.page_handle,
-                0,
-                0,
-                settings.width,
-                settings.height,
-                settings.rotate,
-                settings.render_flags,
-            );
+            stats.progressive_segments += match settings.progress_callback.as_ref() {
+                Some(callback) => {
+                    self.render_page_bitmap_progressively(bitmap_handle, settings, callback)?
+                }
+                None => {
+                    self.bindings.FPDF_RenderPageBitmap(
+                        bitmap_handle,
+                        self.page_handle,
+                        0,
+                        0,
+                        settings.width,
+                        settings.height,
+                        settings.rotate,
+                        settings.render_flags,
+                    );
+
+                    1
+                }
+            };
 
-            if let Some(form_handle) = self.form_handle {
+            if let (true, Some(form_handle)) = (do_render_form_layer, self.form_handle) {
                 // Render user-supplied form data, if any, as an overlay on top of the page.
 
                 if let Some(form_field_highlight) = settings.form_field_highlight.as_ref() {
@@ -745,24 +1341,172 @@ impl<'a> PdfPage<'a> {
                 &settings.clipping,
                 settings.render_flags,
             );
+
+            stats.progressive_segments += 1;
+
+            if let Some(callback) = settings.progress_callback.as_ref() {
+                // Progressive rendering does not support custom transformation matrices,
+                // so we can only report completion.
+
+                let _ = callback.report(1.0);
+            }
         }
 
-        bitmap.set_byte_order_from_render_settings(&settings);
+        stats.pdfium_duration += timer.elapsed();
 
         Ok(())
     }
 
-    // TODO: AJRC - 29/7/22 - remove deprecated PdfPage::get_bitmap_*() functions in 0.9.0
-    // as part of tracking issue https://github.com/ajrcarey/pdfium-render/issues/36
-    /// Renders this [PdfPage] into a new [PdfBitmap] using pixel dimensions, rotation settings,
-    /// and rendering options configured in the given [PdfRenderConfig].
-    #[deprecated(
-        since = "0.7.12",
-        note = "This function has been renamed to better reflect its purpose. Use the PdfPage::render_with_config() function instead."
-    )]
-    #[doc(hidden)]
-    #[inline]
-    pub fn get_bitmap_with_config(
+    /// Renders this [PdfPage] into the given bitmap handle using Pdfium's progressive rendering
+    /// loop, reporting estimated progress to the given callback after each rendering step and
+    /// cancelling rendering if the callback returns `ControlFlow::Break`.
+    ///
+    /// Returns the number of segments in which the page was rendered.
+    fn render_page_bitmap_progressively(
+        &self,
+        bitmap_handle: FPDF_BITMAP,
+        settings: &PdfRenderSettings,
+        callback: &PdfRenderProgressCallback,
+    ) -> Result<usize, PdfiumError> {
+        // Pdfium checks whether it should pause after rendering each batch of page objects.
+        // Asking it to pause at every opportunity returns control to us after each batch,
+        // letting us estimate progress from the number of batches rendered so far.
+
+        unsafe extern "C" fn need_to_pause_now(_: *mut IFSDK_PAUSE) -> FPDF_BOOL {
+            1
+        }
+
+        let mut pause = IFSDK_PAUSE {
+            version: 1,
+            NeedToPauseNow: Some(need_to_pause_now),
+            user: std::ptr::null_mut(),
+        };
+
+        // Pdfium cannot call back into Rust code when compiled to WASM, so we render
+        // without pausing.
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let pause_ptr: *mut IFSDK_PAUSE = &mut pause;
+
+        #[cfg(target_arch = "wasm32")]
+        let pause_ptr: *mut IFSDK_PAUSE = {
+            let _ = &mut pause;
+
+            std::ptr::null_mut()
+        };
+
+        let objects = self.bindings.FPDFPage_CountObjects(self.page_handle).max(1) as f32;
+
+        let mut status = self.bindings.FPDF_RenderPageBitmap_Start(
+            bitmap_handle,
+            self.page_handle,
+            0,
+            0,
+            settings.width,
+            settings.height,
+            settings.rotate,
+            settings.render_flags,
+            pause_ptr,
+        );
+
+        let mut steps = 0;
+
+        while status == FPDF_RENDER_TOBECONTINUED as c_int {
+            steps += 1;
+
+            let progress = (steps as f32 * OBJECTS_PER_PROGRESSIVE_RENDER_STEP / objects).min(0.99);
+
+            if let ControlFlow::Break(()) = callback.report(progress) {
+                self.bindings.FPDF_RenderPage_Close(self.page_handle);
+
+                return Err(PdfiumError::RenderCancelled);
+            }
+
+            status = self
+                .bindings
+                .FPDF_RenderPage_Continue(self.page_handle, pause_ptr);
+        }
+
+        self.bindings.FPDF_RenderPage_Close(self.page_handle);
+
+        if status == FPDF_RENDER_FAILED as c_int {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let _ = callback.report(1.0);
+
+        // The initial call to FPDF_RenderPageBitmap_Start() renders the first segment.
+
+        Ok(steps + 1)
+    }
+
+    /// Renders only the annotation and form field layer of this [PdfPage] into the given
+    /// [PdfBitmap], leaving all other pixels fully transparent.
+    ///
+    /// Pdfium cannot render annotations without also rendering the page content beneath them,
+    /// so the annotations are rendered from a page in a temporary copy of its document from
+    /// which every page object has been removed.
+    fn render_annotation_layer_into_bitmap(
+        &self,
+        bitmap: &mut PdfBitmap,
+        settings: &PdfRenderSettings,
+        stats: &mut PdfRenderStats,
+    ) -> Result<(), PdfiumError> {
+        if bitmap.format()? != PdfBitmapFormat::BGRA {
+            return Err(PdfiumError::AnnotationLayerRequiresBitmapWithAlphaChannel);
+        }
+
+        let index = PdfPageIndexCache::get_index_for_page(self.document_handle, self.page_handle)
+            .ok_or(PdfiumError::SourcePageIndexNotInCache)?;
+
+        let file = save_document_to_bytes(self.document_handle, self.bindings)?;
+
+        let mut copy = Pdfium::pdfium_document_handle_to_result(
+            self.bindings.FPDF_LoadMemDocument64(file.as_slice(), None),
+            self.bindings,
+        )?;
+
+        // Pdfium requires the file to remain valid for as long as the copy is open.
+
+        copy.set_source_byte_buffer(file);
+
+        let page = copy.pages().get(index)?;
+
+        while self.bindings.FPDFPage_CountObjects(page.page_handle) > 0 {
+            let object = self.bindings.FPDFPage_GetObject(page.page_handle, 0);
+
+            if !self.bindings.is_true(
+                self.bindings
+                    .FPDFPage_RemoveObject(page.page_handle, object),
+            ) {
+                return Err(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ));
+            }
+
+            self.bindings.FPDFPageObj_Destroy(object);
+        }
+
+        let mut layer_settings = settings.clone();
+
+        layer_settings.do_clear_to_transparent = true;
+
+        page.render_layers_into_bitmap(*bitmap.handle(), &layer_settings, true, stats)
+    }
+
+    // TODO: AJRC - 29/7/22 - remove deprecated PdfPage::get_bitmap_*() functions in 0.9.0
+    // as part of tracking issue https://github.com/ajrcarey/pdfium-render/issues/36
+    /// Renders this [PdfPage] into a new [PdfBitmap] using pixel dimensions, rotation settings,
+    /// and rendering options configured in the given [PdfRenderConfig].
+    #[deprecated(
+        since = "0.7.12",
+        note = "This function has been renamed to better reflect its purpose. Use the PdfPage::render_with_config() function instead."
+    )]
+    #[doc(hidden)]
+    #[inline]
+    pub fn get_bitmap_with_config(
         &self,
         config: &PdfRenderConfig,
     ) -> Result<PdfBitmap, PdfiumError> {
@@ -913,6 +1657,8 @@ impl<'a> PdfPage<'a> {
     // Use a custom-written flatten operation, rather than Pdfium's built-in flatten. See:
     // https://github.com/ajrcarey/pdfium-render/issues/140
     pub fn flatten(&mut self) -> Result<(), PdfiumError> {
+        self.mark_content_changed();
+
         flatten_page(self.handle())
     }
 
@@ -946,6 +1692,133 @@ impl<'a> PdfPage<'a> {
         }
     }
 
+    /// Permanently removes all text and image content within the given regions of this
+    /// [PdfPage], then covers each region with a black rectangle.
+    ///
+    /// Unlike drawing a filled rectangle over sensitive content, redaction removes the
+    /// content itself, so that it cannot be recovered from the saved document by copying
+    /// text, by text extraction, or by removing the rectangle:
+    ///
+    /// * Text objects containing characters that lie partly or wholly within a region are
+    ///   replaced by new text objects holding only the characters outside the regions, in
+    ///   the same font, size, and color. Text objects entirely within the regions are removed.
+    /// * Pixels of image objects that lie within a region are overwritten with black.
+    /// * Form XObjects containing redacted text, or images overlapping a region, are removed
+    ///   in their entirety, since Pdfium cannot edit their content in place.
+    /// * Annotations overlapping a region, including links, are removed.
+    ///
+    /// Vector graphics within the regions are covered but not removed. Replacement text
+    /// objects do not preserve any character or word spacing applied to the original text,
+    /// so the remaining text in a partially redacted line may shift slightly.
+    ///
+    /// The content of this [PdfPage] is regenerated once redaction is complete.
+    #[cfg(any(
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    #[inline]
+    pub fn redact(&mut self, regions: &[PdfRect]) -> Result<(), PdfiumError> {
+        self.redact_with_fill_color(regions, Some(PdfColor::BLACK))
+    }
+
+    /// Permanently removes all text and image content within the given regions of this
+    /// [PdfPage], as described for [PdfPage::redact()], then covers each region with a
+    /// rectangle filled with the given color. If no color is given, the redacted regions
+    /// are left blank.
+    #[cfg(any(
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    pub fn redact_with_fill_color(
+        &mut self,
+        regions: &[PdfRect],
+        fill_color: Option<PdfColor>,
+    ) -> Result<(), PdfiumError> {
+        redact_page(
+            self.document_handle,
+            self.page_handle,
+            regions,
+            fill_color,
+            self.bindings(),
+        )?;
+
+        self.regenerate_content()
+    }
+
+    /// Applies every redaction annotation on this [PdfPage], returning the number of
+    /// redaction annotations applied.
+    ///
+    /// The content within the regions marked by each redaction annotation is permanently
+    /// removed, as described for [PdfPage::redact()]. Each region is then filled with the
+    /// annotation's fill color, if it has one, and labelled with the annotation's overlay
+    /// text, if it has any. The redaction annotations themselves are removed from the page.
+    ///
+    /// The content of this [PdfPage] is regenerated once redaction is complete.
+    #[cfg(any(
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    pub fn apply_redactions(&mut self) -> Result<usize, PdfiumError> {
+        // Applying one redaction removes any other annotations overlapping its regions,
+        // so all redactions are collected before any are applied.
+
+        let mut redactions = Vec::new();
+
+        for annotation in self.annotations().iter() {
+            if let Some(annotation) = annotation.as_redacted_annotation() {
+                redactions.push((
+                    annotation.regions()?,
+                    annotation.redaction_fill_color(),
+                    annotation.overlay_text(),
+                ));
+            }
+        }
+
+        for (regions, fill_color, overlay_text) in redactions.iter() {
+            redact_page(
+                self.document_handle,
+                self.page_handle,
+                regions,
+                *fill_color,
+                self.bindings(),
+            )?;
+
+            if let Some(text) = overlay_text.as_deref().filter(|text| !text.is_empty()) {
+                for region in regions.iter() {
+                    add_overlay_text(
+                        self.document_handle,
+                        self.page_handle,
+                        region,
+                        text,
+                        *fill_color,
+                        self.bindings(),
+                    )?;
+                }
+            }
+        }
+
+        // Remove any redaction annotations whose bounds did not overlap their own regions.
+
+        while let Some(index) = self
+            .annotations()
+            .iter()
+            .position(|annotation| annotation.annotation_type() == PdfPageAnnotationType::Redacted)
+        {
+            let annotation = self.annotations().get(index)?;
+
+            self.annotations_mut().delete_annotation(annotation)?;
+        }
+
+        if !redactions.is_empty() {
+            self.regenerate_content()?;
+        }
+
+        Ok(redactions.len())
+    }
+
     /// Deletes this [PdfPage] from its containing `PdfPages` collection, consuming this [PdfPage].
     pub fn delete(self) -> Result<(), PdfiumError> {
         let index = PdfPageIndexCache::get_index_for_page(self.document_handle, self.page_handle)
@@ -954,6 +1827,8 @@ impl<'a> PdfPage<'a> {
         self.bindings
             .FPDFPage_Delete(self.document_handle, index as c_int);
 
+        PdfContentGenerations::increment_for_document(self.document_handle);
+
         PdfPageIndexCache::delete_pages_at_index(self.document_handle, index, 1);
 
         Ok(())
@@ -1023,9 +1898,35 @@ impl<'a> PdfPage<'a> {
         // This is a publicly-visible wrapper for the private regenerate_content_immut() function.
         // It is only available to callers who hold a mutable reference to the page.
 
+        self.mark_content_changed();
+
         self.regenerate_content_immut()
     }
 
+    /// Returns the content generation of this [PdfPage]. The content generation increases
+    /// whenever `pdfium-render` adds, removes, or edits the page's objects or annotations,
+    /// changes its boundary boxes, rotates or flattens it, or regenerates its content, and
+    /// when pages are added to, removed from, or rearranged within the containing
+    /// [PdfDocument]. It is never reused, even across different documents.
+    ///
+    /// Comparing content generations is a cheap way of telling whether a page may have changed
+    /// since it was last rendered, for instance when caching rendered pages; see
+    /// [PdfRenderCache]. Changes made directly through the raw Pdfium bindings are not tracked.
+    pub fn content_generation(&self) -> u64 {
+        match PdfPageIndexCache::get_index_for_page(self.document_handle, self.page_handle) {
+            Some(index) => PdfContentGenerations::get_for_page(self.document_handle, index),
+            None => 0,
+        }
+    }
+
+    /// Records that this [PdfPage] may have changed, advancing its content generation.
+    fn mark_content_changed(&self) {
+        match PdfPageIndexCache::get_index_for_page(self.document_handle, self.page_handle) {
+            Some(index) => PdfContentGenerations::increment_for_page(self.document_handle, index),
+            None => PdfContentGenerations::increment_for_document(self.document_handle),
+        }
+    }
+
     /// Commits any staged but unsaved changes to this [PdfPage] to the underlying [PdfDocument].
     #[inline]
     pub(crate) fn regenerate_content_immut(&self) -> Result<(), PdfiumError> {
@@ -1153,4 +2054,680 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_render_with_matrix() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_start(PdfPagePaperSize::new_custom(
+                PdfPoints::new(100.0),
+                PdfPoints::new(100.0),
+            ))?;
+
+        // A black square filling the top left quarter of the page.
+
+        page.objects_mut().create_path_object_rect(
+            PdfRect::new_from_values(50.0, 0.0, 100.0, 50.0),
+            None,
+            None,
+            Some(PdfColor::BLACK),
+        )?;
+
+        // Scaling by two and moving 50 pixels to the right places the square at pixels
+        // 50..150 horizontally and 0..100 vertically; clipping to the left half of the
+        // bitmap leaves only pixels 50..100 horizontally.
+
+        let bitmap = page.render_with_matrix(
+            200,
+            200,
+            PdfMatrix::new(2.0, 0.0, 0.0, 2.0, 50.0, 0.0),
+            PdfRect::new_from_values(0.0, 0.0, 200.0, 100.0),
+            &PdfRenderConfig::new(),
+        )?;
+
+        assert_eq!(bitmap.width(), 200);
+        assert_eq!(bitmap.height(), 200);
+
+        let bytes = bitmap.as_rgba_bytes();
+
+        let is_black = |x: usize, y: usize| bytes[(y * 200 + x) * 4..][..3] == [0, 0, 0];
+
+        let is_white = |x: usize, y: usize| bytes[(y * 200 + x) * 4..][..3] == [255, 255, 255];
+
+        // Inside both the transformed square and the clipping rectangle.
+
+        assert!(is_black(75, 50));
+        assert!(is_black(95, 5));
+
+        // Where the square would lie without the translation.
+
+        assert!(is_white(25, 50));
+
+        // Below the transformed square, where the bottom of the page lies once scaled.
+
+        assert!(is_white(75, 150));
+
+        // Inside the transformed square, but outside the clipping rectangle.
+
+        assert!(!is_black(125, 50));
+        assert!(!is_black(105, 50));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_maximum_bitmap_bytes() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let maximum = 1024 * 1024;
+
+        // By default, the rendered page is scaled down to fit within the budget.
+
+        let bitmap = page.render_with_config(
+            &PdfRenderConfig::new()
+                .scale_page_by_factor(10.0)
+                .set_maximum_bitmap_bytes(maximum),
+        )?;
+
+        assert!(bitmap.as_raw_bytes().len() <= maximum);
+
+        // Rendering fails if automatic downscaling is disabled.
+
+        let result = page.render_with_config(
+            &PdfRenderConfig::new()
+                .scale_page_by_factor(10.0)
+                .set_maximum_bitmap_bytes(maximum)
+                .downscale_to_maximum_bitmap_bytes(false),
+        );
+
+        assert!(matches!(result, Err(PdfiumError::RenderBudgetExceeded)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_additional_actions() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/page-actions-test.pdf", None)?;
+
+        let mut page = document.pages().first()?;
+
+        let actions = page.additional_actions();
+
+        // Pdfium reports JavaScript actions as unsupported.
+
+        assert!(matches!(actions.open(), Some(PdfAction::Unsupported(_))));
+
+        match actions.close() {
+            Some(PdfAction::Uri(action)) => {
+                assert_eq!(action.uri()?, "https://example.com/closed")
+            }
+            _ => panic!("expected a URI action when the page is closed"),
+        }
+
+        assert_eq!(
+            actions
+                .all()
+                .iter()
+                .map(|(trigger, _)| *trigger)
+                .collect::<Vec<_>>(),
+            vec![
+                PdfPageAdditionalActionTrigger::Open,
+                PdfPageAdditionalActionTrigger::Close
+            ]
+        );
+
+        assert!(document.pages().get(1)?.additional_actions().is_empty());
+
+        page.clear_additional_actions()?;
+
+        assert!(page.additional_actions().is_empty());
+
+        drop(page);
+
+        let document = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        assert!(document.pages().first()?.additional_actions().is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_for_monochrome_printer() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let bitmap = document
+            .pages()
+            .first()?
+            .render_with_config(&PdfRenderConfig::for_monochrome_printer(96.0))?;
+
+        assert!(bitmap
+            .as_rgba_bytes()
+            .chunks_exact(4)
+            .all(|pixel| pixel[..3] == [0, 0, 0] || pixel[..3] == [255, 255, 255]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_subpixel_order() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let render = |order: PdfSubpixelOrder| {
+            page.render_with_config(
+                &PdfRenderConfig::new()
+                    .set_target_width(400)
+                    .set_subpixel_order(order),
+            )
+        };
+
+        let rgb = render(PdfSubpixelOrder::Rgb)?;
+
+        let bgr = render(PdfSubpixelOrder::Bgr)?;
+
+        let none = render(PdfSubpixelOrder::None)?;
+
+        // The colored fringes of the subpixel anti-aliased text are mirrored for BGR displays,
+        // but pixels away from the text are unaffected.
+
+        assert_ne!(bgr.content_hash(), rgb.content_hash());
+        assert_ne!(bgr.content_hash(), none.content_hash());
+
+        for ((rgb, bgr), none) in rgb
+            .as_rgba_bytes()
+            .chunks_exact(4)
+            .zip(bgr.as_rgba_bytes().chunks_exact(4))
+            .zip(none.as_rgba_bytes().chunks_exact(4))
+        {
+            if rgb == none {
+                assert_eq!(bgr, none);
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_minimum_smooth_text_size() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let render = |config: PdfRenderConfig| {
+            page.render_with_config(&config.set_target_width(400))
+                .map(|bitmap| bitmap.content_hash())
+        };
+
+        // Text on the test page is never 1000 pixels high, so anti-aliasing of text
+        // should be disabled entirely.
+
+        assert_eq!(
+            render(PdfRenderConfig::new().set_minimum_smooth_text_size(Some(1000.0)))?,
+            render(PdfRenderConfig::new().set_text_smoothing(false))?
+        );
+
+        assert_eq!(
+            render(PdfRenderConfig::new().set_minimum_smooth_text_size(Some(0.0)))?,
+            render(PdfRenderConfig::new())?
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_to_encoded() -> Result<(), PdfiumError> {
+        use image::ImageFormat;
+
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let config = PdfRenderConfig::new().scale_page_by_factor(2.0);
+
+        let png = page.render_to_encoded(&config, ImageFormat::Png)?;
+
+        let image = image::load_from_memory_with_format(&png, ImageFormat::Png)
+            .map_err(|_| PdfiumError::ImageError)?;
+
+        assert_eq!(
+            image.dimensions(),
+            page.render_with_config(&config)?.as_image().dimensions()
+        );
+
+        // Rendering at twice the page's size in points gives a resolution of 144 dpi,
+        // or 5669 pixels per meter.
+
+        assert_eq!(&png[37..41], b"pHYs");
+        assert_eq!(&png[41..45], &5669u32.to_be_bytes());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_stats() -> Result<(), PdfiumError> {
+        use std::ops::ControlFlow;
+
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let config = PdfRenderConfig::new().set_target_width(400);
+
+        assert!(page.render_with_config(&config)?.render_stats().is_none());
+
+        let bitmap = page.render_with_config(&config.clone().collect_render_stats(true))?;
+
+        let stats = bitmap.render_stats().unwrap();
+
+        assert_eq!(stats.progressive_segments(), 1);
+        assert_eq!(stats.bytes_allocated(), bitmap.as_raw_bytes().len());
+
+        // Progressive rendering renders the page in at least one segment.
+
+        let bitmap = page.render_with_config(
+            &config
+                .collect_render_stats(true)
+                .set_progress_callback(|_| ControlFlow::Continue(())),
+        )?;
+
+        assert!(bitmap.render_stats().unwrap().progressive_segments() >= 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_layer_visibility() -> Result<(), PdfiumError> {
+        // The test file has three layers: Red, drawn on the left half of the page; Blue,
+        // drawn on the right half of the page but hidden by default; and Green, drawn in
+        // the bottom left corner of the page from inside a form XObject.
+
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/layers-test.pdf", None)?;
+
+        assert_eq!(
+            document.layers(),
+            vec!["Red".to_string(), "Blue".to_string(), "Green".to_string()]
+        );
+
+        let page = document.pages().first()?;
+
+        let objects = page.objects().len();
+
+        let render = |visibility: &[(&str, bool)]| -> Result<Vec<[u8; 4]>, PdfiumError> {
+            let image = page
+                .render_with_config(
+                    &PdfRenderConfig::new()
+                        .set_target_width(200)
+                        .set_layer_visibility(
+                            visibility
+                                .iter()
+                                .map(|(name, is_visible)| (name.to_string(), *is_visible))
+                                .collect(),
+                        ),
+                )?
+                .as_image();
+
+            // Sample the left half, the right half, and the bottom left corner of the page.
+
+            Ok([(50, 100), (150, 100), (25, 175)]
+                .iter()
+                .map(|(x, y)| image.get_pixel(*x, *y).0)
+                .collect())
+        };
+
+        let red = [255, 0, 0, 255];
+        let green = [0, 255, 0, 255];
+        let blue = [0, 0, 255, 255];
+        let white = [255, 255, 255, 255];
+
+        assert_eq!(render(&[])?, vec![red, white, green]);
+
+        // Hiding a layer that does not exist changes nothing.
+
+        assert_eq!(
+            render(&[("No such layer", false)])?,
+            vec![red, white, green]
+        );
+
+        // Layers hidden by default can be shown, and content nested inside form XObjects
+        // is hidden along with the rest of its layer.
+
+        assert_eq!(
+            render(&[("Red", false), ("Blue", true), ("Green", false)])?,
+            vec![white, blue, white]
+        );
+
+        // The document itself is unchanged.
+
+        assert_eq!(render(&[])?, vec![red, white, green]);
+        assert_eq!(page.objects().len(), objects);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_thumbnail() -> Result<(), PdfiumError> {
+        // The first page of the test file has an embedded 20 x 10 pixel red thumbnail;
+        // the second page has none. Both pages draw a blue rectangle in their center.
+
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/thumbnail-test.pdf", None)?;
+
+        let pages = document.pages();
+
+        let first = pages.get(0)?;
+
+        let second = pages.get(1)?;
+
+        let center = |thumbnail: &PdfBitmap| {
+            thumbnail
+                .as_image()
+                .to_rgb8()
+                .get_pixel(thumbnail.width() as u32 / 2, thumbnail.height() as u32 / 2)
+                .0
+        };
+
+        // An embedded thumbnail that fits within the maximum dimension is returned as-is.
+
+        let thumbnail = first.thumbnail(64)?;
+
+        assert_eq!((thumbnail.width(), thumbnail.height()), (20, 10));
+        assert_eq!(center(&thumbnail), [255, 0, 0]);
+
+        // An embedded thumbnail that is too large is replaced by a rendered thumbnail.
+
+        let thumbnail = first.thumbnail(16)?;
+
+        assert!(thumbnail.width() <= 16 && thumbnail.height() <= 16);
+        assert_eq!(center(&thumbnail), [0, 0, 255]);
+
+        // A page without an embedded thumbnail is rendered.
+
+        assert!(matches!(
+            second.embedded_thumbnail(),
+            Err(PdfiumError::PageMissingEmbeddedThumbnail)
+        ));
+
+        let thumbnail = second.thumbnail(64)?;
+
+        assert!(thumbnail.width() <= 64 && thumbnail.height() <= 64);
+        assert_eq!(center(&thumbnail), [0, 0, 255]);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "skia", not(target_arch = "wasm32")))]
+    fn test_render_to_skia_canvas() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let mut surface = skia_safe::surfaces::raster_n32_premul((200, 400)).unwrap();
+
+        page.render_to_skia_canvas(
+            surface.canvas(),
+            &PdfRenderConfig::new().set_target_width(200),
+        )?;
+
+        let pixmap = surface.peek_pixels().unwrap();
+
+        // The page is cleared to white before its content is drawn.
+
+        assert_eq!(pixmap.get_color((0, 0)), skia_safe::Color::WHITE);
+        assert!((0..200)
+            .flat_map(|x| (0..200).map(move |y| (x, y)))
+            .any(|point| pixmap.get_color(point) != skia_safe::Color::WHITE));
+
+        assert!(matches!(
+            page.render_to_skia_canvas(
+                surface.canvas(),
+                &PdfRenderConfig::new().set_target_width(0)
+            ),
+            Err(PdfiumError::ZeroSizedRenderTarget)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_annotation_layer_only() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/form-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let render = |config: PdfRenderConfig| -> Result<Vec<[u8; 4]>, PdfiumError> {
+            Ok(page
+                .render_with_config(&config.set_target_width(400))?
+                .as_image()
+                .into_rgba8()
+                .pixels()
+                .map(|pixel| pixel.0)
+                .collect())
+        };
+
+        let content = render(
+            PdfRenderConfig::new()
+                .render_annotations(false)
+                .render_form_data(false),
+        )?;
+
+        let layer = render(PdfRenderConfig::new().render_annotation_layer_only(true))?;
+
+        assert_eq!(layer.len(), content.len());
+
+        // The form field widgets are drawn into the layer...
+
+        assert!(layer.iter().any(|pixel| pixel[3] > 0));
+
+        // ... but the page content beneath them is not.
+
+        let white = [255, 255, 255, 255];
+
+        assert!(content
+            .iter()
+            .zip(layer.iter())
+            .any(|(content, layer)| *content != white && layer[3] == 0));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_with_progress_callback() -> Result<(), PdfiumError> {
+        use std::ops::ControlFlow;
+        use std::sync::{Arc, Mutex};
+
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let reported = Arc::new(Mutex::new(Vec::new()));
+
+        let config = PdfRenderConfig::new().set_progress_callback({
+            let reported = reported.clone();
+
+            move |progress| {
+                reported.lock().unwrap().push(progress);
+
+                ControlFlow::Continue(())
+            }
+        });
+
+        page.render_with_config(&config)?;
+
+        let reported = reported.lock().unwrap();
+
+        assert_eq!(reported.last(), Some(&1.0));
+        assert!(reported.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    fn test_redact() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/text-test.pdf", None)?;
+
+        let mut page = document.pages().first()?;
+
+        // Redact the chapter heading, which appears only once in the document.
+
+        let redacted = "Introduction";
+
+        let segment_bounds = page
+            .text()?
+            .segments()
+            .iter()
+            .find(|segment| segment.text().contains(redacted))
+            .unwrap()
+            .bounds();
+
+        page.redact(&[segment_bounds])?;
+
+        // No text should remain within the redacted region, and the region should be
+        // covered by a newly added rectangle.
+
+        assert!(page.text()?.inside_rect(segment_bounds).trim().is_empty());
+        assert!(page.objects().iter().any(|object| object.object_type()
+            == PdfPageObjectType::Path
+            && object
+                .bounds()
+                .map(|bounds| bounds == segment_bounds)
+                .unwrap_or(false)));
+
+        // The redacted text should not survive saving the document, either in the text
+        // extracted from the saved document or anywhere in the saved bytes.
+
+        let bytes = document.save_to_bytes()?;
+
+        let saved = pdfium.load_pdf_from_byte_slice(&bytes, None)?;
+
+        for page in saved.pages().iter() {
+            assert!(!page.text()?.all().contains(redacted));
+        }
+
+        assert!(!bytes
+            .windows(redacted.len())
+            .any(|window| window == redacted.as_bytes()));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(any(
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    fn test_create_and_apply_redactions() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.load_pdf_from_file("./test/text-test.pdf", None)?;
+
+        let (regions, redacted) = {
+            let page = document.pages().first()?;
+
+            let text = page.text()?;
+
+            let segments = text.segments();
+
+            let segments = [segments.get(0)?, segments.get(1)?];
+
+            (
+                segments
+                    .iter()
+                    .map(|segment| segment.bounds())
+                    .collect::<Vec<_>>(),
+                segments
+                    .iter()
+                    .map(|segment| segment.text())
+                    .collect::<Vec<_>>(),
+            )
+        };
+
+        let index = PdfRedactAnnotationBuilder::new()
+            .add_region(regions[0])
+            .add_region(regions[1])
+            .fill_color(PdfColor::BLACK)
+            .overlay_text("Withheld")
+            .create(&mut document, 0)?;
+
+        {
+            let page = document.pages().first()?;
+
+            let annotation = page.annotations().get(index)?;
+
+            let redaction = annotation.as_redacted_annotation().unwrap();
+
+            let read = redaction.regions()?;
+
+            assert_eq!(read.len(), 2);
+
+            for (read, region) in read.iter().zip(regions.iter()) {
+                assert!((read.left.value - region.left.value).abs() < 0.01);
+                assert!((read.bottom.value - region.bottom.value).abs() < 0.01);
+                assert!((read.right.value - region.right.value).abs() < 0.01);
+                assert!((read.top.value - region.top.value).abs() < 0.01);
+            }
+
+            assert_eq!(redaction.overlay_text().as_deref(), Some("Withheld"));
+        }
+
+        assert_eq!(document.pages().first()?.apply_redactions()?, 1);
+
+        let document = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        let page = document.pages().first()?;
+
+        assert!(page
+            .annotations()
+            .iter()
+            .all(|annotation| annotation.annotation_type() != PdfPageAnnotationType::Redacted));
+
+        let text = page.text()?;
+
+        for (region, redacted) in regions.iter().zip(redacted.iter()) {
+            let remaining = text.inside_rect(*region);
+
+            assert!(!remaining.contains(redacted.trim()));
+            assert!(remaining.contains("Withheld"));
+        }
+
+        Ok(())
+    }
 }
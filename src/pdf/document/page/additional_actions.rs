@@ -0,0 +1,294 @@
+//! Defines the [PdfPageAdditionalActions] struct, exposing functionality related to the
+//! additional actions of a single `PdfPage`, performed automatically by a viewer when
+//! the page is opened or closed.
+
+use crate::bindgen::{FPDFPAGE_AACTION_CLOSE, FPDFPAGE_AACTION_OPEN, FPDF_DOCUMENT, FPDF_PAGE};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::action::PdfAction;
+use crate::pdf::document::page::index_cache::PdfPageIndexCache;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use once_cell::sync::Lazy;
+use std::collections::{BTreeSet, HashMap};
+use std::os::raw::c_int;
+use std::sync::{Mutex, MutexGuard};
+
+/// The pages whose additional actions have been cleared in all open documents.
+static ADDITIONAL_ACTIONS_EDITS: Lazy<Mutex<PdfPageAdditionalActionsEdits>> =
+    Lazy::new(|| Mutex::new(PdfPageAdditionalActionsEdits::new()));
+
+/// The event that triggers an additional action of a `PdfPage`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfPageAdditionalActionTrigger {
+    /// The action is performed when the page is opened, for instance when the user
+    /// navigates to it from another page.
+    Open,
+
+    /// The action is performed when the page is closed, for instance when the user
+    /// navigates away from it to another page.
+    Close,
+}
+
+impl PdfPageAdditionalActionTrigger {
+    #[inline]
+    pub(crate) fn as_pdfium(&self) -> u32 {
+        match self {
+            PdfPageAdditionalActionTrigger::Open => FPDFPAGE_AACTION_OPEN,
+            PdfPageAdditionalActionTrigger::Close => FPDFPAGE_AACTION_CLOSE,
+        }
+    }
+}
+
+/// The additional actions of a single `PdfPage`: actions performed automatically by a
+/// viewer when the page is opened or closed, defined by the `/AA` entry in the page dictionary.
+///
+/// Page-level actions are a common way for a document to run behaviour without any user
+/// interaction, so sanitization tools should audit them. Pdfium reports JavaScript actions,
+/// which are not otherwise supported by Pdfium, as `PdfAction::Unsupported`.
+/// `pdfium-render` itself never performs page actions.
+///
+/// Use the `PdfPage::clear_additional_actions()` function to remove the additional actions
+/// of a page. Pdfium cannot edit a page's `/AA` entry itself, so the entry is removed from
+/// the page dictionary when the document is next saved; until then, the cleared actions
+/// are no longer returned by this collection.
+pub struct PdfPageAdditionalActions<'a> {
+    document_handle: FPDF_DOCUMENT,
+    page_handle: FPDF_PAGE,
+    bindings: &'a dyn PdfiumLibraryBindings,
+}
+
+impl<'a> PdfPageAdditionalActions<'a> {
+    #[inline]
+    pub(crate) fn from_pdfium(
+        document_handle: FPDF_DOCUMENT,
+        page_handle: FPDF_PAGE,
+        bindings: &'a dyn PdfiumLibraryBindings,
+    ) -> Self {
+        PdfPageAdditionalActions {
+            document_handle,
+            page_handle,
+            bindings,
+        }
+    }
+
+    /// Returns the [PdfiumLibraryBindings] used by this [PdfPageAdditionalActions] collection.
+    #[inline]
+    pub fn bindings(&self) -> &'a dyn PdfiumLibraryBindings {
+        self.bindings
+    }
+
+    /// Returns the action performed when the given event occurs, if any.
+    pub fn get(&self, trigger: PdfPageAdditionalActionTrigger) -> Option<PdfAction<'a>> {
+        if PdfPageIndexCache::get_index_for_page(self.document_handle, self.page_handle)
+            .map_or(false, |index| {
+                PdfPageAdditionalActionsEdits::is_cleared(self.document_handle, index)
+            })
+        {
+            return None;
+        }
+
+        let handle = self
+            .bindings
+            .FPDF_GetPageAAction(self.page_handle, trigger.as_pdfium() as c_int);
+
+        if handle.is_null() {
+            None
+        } else {
+            Some(PdfAction::from_pdfium(
+                handle,
+                self.document_handle,
+                self.bindings,
+            ))
+        }
+    }
+
+    /// Returns the action performed when the containing `PdfPage` is opened, if any.
+    #[inline]
+    pub fn open(&self) -> Option<PdfAction<'a>> {
+        self.get(PdfPageAdditionalActionTrigger::Open)
+    }
+
+    /// Returns the action performed when the containing `PdfPage` is closed, if any.
+    #[inline]
+    pub fn close(&self) -> Option<PdfAction<'a>> {
+        self.get(PdfPageAdditionalActionTrigger::Close)
+    }
+
+    /// Returns every additional action of the containing `PdfPage`, together with the event
+    /// that triggers it.
+    pub fn all(&self) -> Vec<(PdfPageAdditionalActionTrigger, PdfAction<'a>)> {
+        [
+            PdfPageAdditionalActionTrigger::Open,
+            PdfPageAdditionalActionTrigger::Close,
+        ]
+        .iter()
+        .filter_map(|trigger| self.get(*trigger).map(|action| (*trigger, action)))
+        .collect()
+    }
+
+    /// Returns `true` if the containing `PdfPage` has no additional actions.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.open().is_none() && self.close().is_none()
+    }
+}
+
+/// The indices of the pages whose `/AA` entries should be removed from each open document
+/// when it is next saved.
+///
+/// Pages are recorded by index, so clearing the additional actions of a page and then
+/// inserting, deleting, or reordering pages before the document is saved removes the
+/// additional actions of whichever page is at the recorded index at that time.
+pub(crate) struct PdfPageAdditionalActionsEdits {
+    cleared_pages_by_document: HashMap<FPDF_DOCUMENT, BTreeSet<PdfPageIndex>>,
+}
+
+impl PdfPageAdditionalActionsEdits {
+    #[inline]
+    fn new() -> Self {
+        PdfPageAdditionalActionsEdits {
+            cleared_pages_by_document: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    fn lock() -> MutexGuard<'static, PdfPageAdditionalActionsEdits> {
+        ADDITIONAL_ACTIONS_EDITS.lock().unwrap()
+    }
+
+    /// Records that the additional actions of the page at the given index in the given
+    /// document should be removed when the document is next saved.
+    #[inline]
+    pub(crate) fn clear(document: FPDF_DOCUMENT, page_index: PdfPageIndex) {
+        Self::lock()
+            .cleared_pages_by_document
+            .entry(document)
+            .or_default()
+            .insert(page_index);
+    }
+
+    /// Returns `true` if the additional actions of the page at the given index in the given
+    /// document have been cleared.
+    #[inline]
+    pub(crate) fn is_cleared(document: FPDF_DOCUMENT, page_index: PdfPageIndex) -> bool {
+        Self::lock()
+            .cleared_pages_by_document
+            .get(&document)
+            .map_or(false, |pages| pages.contains(&page_index))
+    }
+
+    /// Returns `true` if the additional actions of any page in the given document have
+    /// been cleared.
+    #[inline]
+    pub(crate) fn is_modified(document: FPDF_DOCUMENT) -> bool {
+        Self::lock()
+            .cleared_pages_by_document
+            .get(&document)
+            .map_or(false, |pages| !pages.is_empty())
+    }
+
+    /// Removes the `/AA` entries of the cleared pages in the given document from the given
+    /// saved copy of that document, returning the updated file. Returns
+    /// [PdfiumError::DocumentUpdateUnsupported] if the file is encrypted or its page tree
+    /// cannot be read.
+    pub(crate) fn write_to_file(
+        document: FPDF_DOCUMENT,
+        file: Vec<u8>,
+    ) -> Result<Vec<u8>, PdfiumError> {
+        let pages = match Self::lock().cleared_pages_by_document.get(&document) {
+            Some(pages) if !pages.is_empty() => pages.clone(),
+            _ => return Ok(file),
+        };
+
+        remove_additional_actions(file, &pages)
+    }
+
+    /// Discards the cleared pages recorded for the given document, which is being closed.
+    #[inline]
+    pub(crate) fn close_document(document: FPDF_DOCUMENT) {
+        Self::lock().cleared_pages_by_document.remove(&document);
+    }
+}
+
+unsafe impl Send for PdfPageAdditionalActionsEdits {}
+
+unsafe impl Sync for PdfPageAdditionalActionsEdits {}
+
+/// Appends an incremental update to the given file removing the `/AA` entries of the pages
+/// at the given indices. Pages without an `/AA` entry, and indices past the end of the
+/// document, are ignored.
+fn remove_additional_actions(
+    file: Vec<u8>,
+    page_indices: &BTreeSet<PdfPageIndex>,
+) -> Result<Vec<u8>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let pages = parsed
+        .page_references()
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if update.trailer().get("Encrypt").is_some() {
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    let mut is_changed = false;
+
+    for index in page_indices.iter() {
+        let number = match pages.get(*index as usize) {
+            Some(PdfSyntaxObject::Reference(number, _)) => *number,
+            _ => continue,
+        };
+
+        if let Some(mut page) = update.get(number) {
+            if page.get("AA").is_some() {
+                page.remove("AA");
+
+                update.set(number, page);
+
+                is_changed = true;
+            }
+        }
+    }
+
+    if is_changed {
+        Ok(update.write(file))
+    } else {
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::additional_actions::*;
+
+    const PAGE_ACTIONS_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /AA << /O << /S /JavaScript /JS (app.alert(1)) >> >> >>\nendobj\n\
+        4 0 obj\n<< /Type /Page /Parent 2 0 R /AA << /C << /S /URI /URI (https://example.com) >> >> >>\nendobj\n\
+        xref\n0 5\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 5 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    #[test]
+    fn test_remove_additional_actions() {
+        let file =
+            remove_additional_actions(PAGE_ACTIONS_PDF.to_vec(), &vec![1, 5].into_iter().collect())
+                .unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        assert!(parsed
+            .resolve(&PdfSyntaxObject::Reference(3, 0))
+            .get("AA")
+            .is_some());
+
+        assert!(parsed
+            .resolve(&PdfSyntaxObject::Reference(4, 0))
+            .get("AA")
+            .is_none());
+    }
+}
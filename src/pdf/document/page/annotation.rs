@@ -1,15 +1,27 @@
 //! Defines the [PdfPageAnnotation] struct, exposing functionality related to a single annotation.
 
+pub(crate) mod appearance;
+pub(crate) mod append;
 pub mod attachment_points;
+pub mod border;
+pub mod builders;
 pub mod circle;
+pub(crate) mod dictionary_edits;
+#[cfg(feature = "serde")]
+pub mod dto;
+pub mod file_attachment;
 pub mod free_text;
 pub mod highlight;
 pub mod ink;
+pub mod line_ending;
 pub mod link;
+pub mod measure;
+pub mod multimedia;
 pub mod objects;
 pub mod popup;
 pub(crate) mod private; // Keep private so that the PdfPageAnnotationPrivate trait is not exposed.
 pub mod redacted;
+pub mod review;
 pub mod square;
 pub mod squiggly;
 pub mod stamp;
@@ -22,27 +34,36 @@ pub mod xfa_widget;
 
 use crate::bindgen::{
     FPDF_ANNOTATION, FPDF_ANNOTATION_SUBTYPE, FPDF_ANNOT_CARET, FPDF_ANNOT_CIRCLE,
-    FPDF_ANNOT_FILEATTACHMENT, FPDF_ANNOT_FREETEXT, FPDF_ANNOT_HIGHLIGHT, FPDF_ANNOT_INK,
-    FPDF_ANNOT_LINE, FPDF_ANNOT_LINK, FPDF_ANNOT_MOVIE, FPDF_ANNOT_POLYGON, FPDF_ANNOT_POLYLINE,
-    FPDF_ANNOT_POPUP, FPDF_ANNOT_PRINTERMARK, FPDF_ANNOT_REDACT, FPDF_ANNOT_RICHMEDIA,
-    FPDF_ANNOT_SCREEN, FPDF_ANNOT_SOUND, FPDF_ANNOT_SQUARE, FPDF_ANNOT_SQUIGGLY, FPDF_ANNOT_STAMP,
-    FPDF_ANNOT_STRIKEOUT, FPDF_ANNOT_TEXT, FPDF_ANNOT_THREED, FPDF_ANNOT_TRAPNET,
-    FPDF_ANNOT_UNDERLINE, FPDF_ANNOT_UNKNOWN, FPDF_ANNOT_WATERMARK, FPDF_ANNOT_WIDGET,
-    FPDF_ANNOT_XFAWIDGET, FPDF_DOCUMENT, FPDF_FORMHANDLE, FPDF_PAGE,
+    FPDF_ANNOT_FILEATTACHMENT, FPDF_ANNOT_FLAG_HIDDEN, FPDF_ANNOT_FLAG_PRINT, FPDF_ANNOT_FREETEXT,
+    FPDF_ANNOT_HIGHLIGHT, FPDF_ANNOT_INK, FPDF_ANNOT_LINE, FPDF_ANNOT_LINK, FPDF_ANNOT_MOVIE,
+    FPDF_ANNOT_POLYGON, FPDF_ANNOT_POLYLINE, FPDF_ANNOT_POPUP, FPDF_ANNOT_PRINTERMARK,
+    FPDF_ANNOT_REDACT, FPDF_ANNOT_RICHMEDIA, FPDF_ANNOT_SCREEN, FPDF_ANNOT_SOUND,
+    FPDF_ANNOT_SQUARE, FPDF_ANNOT_SQUIGGLY, FPDF_ANNOT_STAMP, FPDF_ANNOT_STRIKEOUT,
+    FPDF_ANNOT_TEXT, FPDF_ANNOT_THREED, FPDF_ANNOT_TRAPNET, FPDF_ANNOT_UNDERLINE,
+    FPDF_ANNOT_UNKNOWN, FPDF_ANNOT_WATERMARK, FPDF_ANNOT_WIDGET, FPDF_ANNOT_XFAWIDGET,
+    FPDF_DOCUMENT, FPDF_FORMHANDLE, FPDF_PAGE,
 };
 use crate::bindings::PdfiumLibraryBindings;
-use crate::error::PdfiumError;
+use crate::error::{PdfiumError, PdfiumInternalError};
 use crate::pdf::color::PdfColor;
+use crate::pdf::document::page::annotation::appearance::generate_normal_appearance;
 use crate::pdf::document::page::annotation::attachment_points::PdfPageAnnotationAttachmentPoints;
+use crate::pdf::document::page::annotation::border::PdfAnnotationBorderStyle;
 use crate::pdf::document::page::annotation::circle::PdfPageCircleAnnotation;
+use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
+use crate::pdf::document::page::annotation::file_attachment::PdfPageFileAttachmentAnnotation;
 use crate::pdf::document::page::annotation::free_text::PdfPageFreeTextAnnotation;
 use crate::pdf::document::page::annotation::highlight::PdfPageHighlightAnnotation;
 use crate::pdf::document::page::annotation::ink::PdfPageInkAnnotation;
 use crate::pdf::document::page::annotation::link::PdfPageLinkAnnotation;
+use crate::pdf::document::page::annotation::multimedia::PdfPageMultimediaAnnotation;
 use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
 use crate::pdf::document::page::annotation::popup::PdfPagePopupAnnotation;
 use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
 use crate::pdf::document::page::annotation::redacted::PdfPageRedactedAnnotation;
+use crate::pdf::document::page::annotation::review::{
+    review_status_from_replies, PdfAnnotationReviewStatus,
+};
 use crate::pdf::document::page::annotation::square::PdfPageSquareAnnotation;
 use crate::pdf::document::page::annotation::squiggly::PdfPageSquigglyAnnotation;
 use crate::pdf::document::page::annotation::stamp::PdfPageStampAnnotation;
@@ -52,21 +73,28 @@ use crate::pdf::document::page::annotation::underline::PdfPageUnderlineAnnotatio
 use crate::pdf::document::page::annotation::unsupported::PdfPageUnsupportedAnnotation;
 use crate::pdf::document::page::annotation::widget::PdfPageWidgetAnnotation;
 use crate::pdf::document::page::annotation::xfa_widget::PdfPageXfaWidgetAnnotation;
+use crate::pdf::document::page::annotations::PdfPageAnnotationIndex;
 use crate::pdf::document::page::field::PdfFormField;
+use crate::pdf::document::page::objects::private::internal::PdfPageObjectsPrivate;
 use crate::pdf::points::PdfPoints;
 use crate::pdf::rect::PdfRect;
+use crate::utils::dates::date_time_from_pdf_string;
 use chrono::prelude::*;
+use std::os::raw::c_int;
+
+#[cfg(feature = "serde")]
+use crate::pdf::document::page::annotation::dto::PdfPageAnnotationDto;
 
 /// The type of a single [PdfPageAnnotation], as defined in table 8.20 of the PDF Reference,
 /// version 1.7, on page 615.
 ///
 /// Not all PDF annotation types are supported by Pdfium. For example, Pdfium does not
-/// currently support embedded sound or movie file annotations, embedded 3D animations, or
-/// annotations containing embedded file attachments.
+/// currently support embedded sound or movie file annotations or embedded 3D animations.
 ///
 /// Pdfium currently supports creating, editing, and rendering the following types of annotations:
 ///
 /// * [PdfPageAnnotationType::Circle]
+/// * [PdfPageAnnotationType::FileAttachment]
 /// * [PdfPageAnnotationType::FreeText]
 /// * [PdfPageAnnotationType::Highlight]
 /// * [PdfPageAnnotationType::Ink]
@@ -86,6 +114,7 @@ use chrono::prelude::*;
 /// floats over the page inside its own enclosed area. Adobe often uses the term "sticky note"
 /// in reference to `Text` annotations to distinguish them from `FreeText` annotations.
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PdfPageAnnotationType {
     Unknown = FPDF_ANNOT_UNKNOWN as isize,
     Text = FPDF_ANNOT_TEXT as isize,
@@ -194,12 +223,18 @@ impl PdfPageAnnotationType {
 }
 
 /// A single user annotation on a `PdfPage`.
+///
+/// New annotation types may be added to this enum in future releases without a major
+/// version change, so matches against it must include a wildcard arm.
+#[non_exhaustive]
 pub enum PdfPageAnnotation<'a> {
     Circle(PdfPageCircleAnnotation<'a>),
+    FileAttachment(PdfPageFileAttachmentAnnotation<'a>),
     FreeText(PdfPageFreeTextAnnotation<'a>),
     Highlight(PdfPageHighlightAnnotation<'a>),
     Ink(PdfPageInkAnnotation<'a>),
     Link(PdfPageLinkAnnotation<'a>),
+    Multimedia(PdfPageMultimediaAnnotation<'a>),
     Popup(PdfPagePopupAnnotation<'a>),
     Square(PdfPageSquareAnnotation<'a>),
     Squiggly(PdfPageSquigglyAnnotation<'a>),
@@ -238,6 +273,14 @@ impl<'a> PdfPageAnnotation<'a> {
                     bindings,
                 ))
             }
+            PdfPageAnnotationType::FileAttachment => {
+                PdfPageAnnotation::FileAttachment(PdfPageFileAttachmentAnnotation::from_pdfium(
+                    document_handle,
+                    page_handle,
+                    annotation_handle,
+                    bindings,
+                ))
+            }
             PdfPageAnnotationType::FreeText => {
                 PdfPageAnnotation::FreeText(PdfPageFreeTextAnnotation::from_pdfium(
                     document_handle,
@@ -352,6 +395,18 @@ impl<'a> PdfPageAnnotation<'a> {
                     bindings,
                 ))
             }
+            PdfPageAnnotationType::Sound
+            | PdfPageAnnotationType::Movie
+            | PdfPageAnnotationType::Screen
+            | PdfPageAnnotationType::RichMedia => {
+                PdfPageAnnotation::Multimedia(PdfPageMultimediaAnnotation::from_pdfium(
+                    document_handle,
+                    page_handle,
+                    annotation_handle,
+                    annotation_type,
+                    bindings,
+                ))
+            }
             _ => PdfPageAnnotation::Unsupported(PdfPageUnsupportedAnnotation::from_pdfium(
                 document_handle,
                 page_handle,
@@ -366,10 +421,12 @@ impl<'a> PdfPageAnnotation<'a> {
     pub(crate) fn unwrap_as_trait(&self) -> &dyn PdfPageAnnotationPrivate<'a> {
         match self {
             PdfPageAnnotation::Circle(annotation) => annotation,
+            PdfPageAnnotation::FileAttachment(annotation) => annotation,
             PdfPageAnnotation::FreeText(annotation) => annotation,
             PdfPageAnnotation::Highlight(annotation) => annotation,
             PdfPageAnnotation::Ink(annotation) => annotation,
             PdfPageAnnotation::Link(annotation) => annotation,
+            PdfPageAnnotation::Multimedia(annotation) => annotation,
             PdfPageAnnotation::Popup(annotation) => annotation,
             PdfPageAnnotation::Square(annotation) => annotation,
             PdfPageAnnotation::Squiggly(annotation) => annotation,
@@ -388,10 +445,12 @@ impl<'a> PdfPageAnnotation<'a> {
     pub(crate) fn unwrap_as_trait_mut(&mut self) -> &mut dyn PdfPageAnnotationPrivate<'a> {
         match self {
             PdfPageAnnotation::Circle(annotation) => annotation,
+            PdfPageAnnotation::FileAttachment(annotation) => annotation,
             PdfPageAnnotation::FreeText(annotation) => annotation,
             PdfPageAnnotation::Highlight(annotation) => annotation,
             PdfPageAnnotation::Ink(annotation) => annotation,
             PdfPageAnnotation::Link(annotation) => annotation,
+            PdfPageAnnotation::Multimedia(annotation) => annotation,
             PdfPageAnnotation::Popup(annotation) => annotation,
             PdfPageAnnotation::Square(annotation) => annotation,
             PdfPageAnnotation::Squiggly(annotation) => annotation,
@@ -409,12 +468,12 @@ impl<'a> PdfPageAnnotation<'a> {
     /// The type of this [PdfPageAnnotation].
     ///
     /// Not all PDF annotation types are supported by Pdfium. For example, Pdfium does not
-    /// currently support embedded sound or movie file annotations, embedded 3D animations, or
-    /// annotations containing embedded file attachments.
+    /// currently support embedded sound or movie file annotations or embedded 3D animations.
     ///
     /// Pdfium currently supports creating, editing, and rendering the following types of annotations:
     ///
     /// * [PdfPageAnnotationType::Circle]
+    /// * [PdfPageAnnotationType::FileAttachment]
     /// * [PdfPageAnnotationType::FreeText]
     /// * [PdfPageAnnotationType::Highlight]
     /// * [PdfPageAnnotationType::Ink]
@@ -433,10 +492,12 @@ impl<'a> PdfPageAnnotation<'a> {
     pub fn annotation_type(&self) -> PdfPageAnnotationType {
         match self {
             PdfPageAnnotation::Circle(_) => PdfPageAnnotationType::Circle,
+            PdfPageAnnotation::FileAttachment(_) => PdfPageAnnotationType::FileAttachment,
             PdfPageAnnotation::FreeText(_) => PdfPageAnnotationType::FreeText,
             PdfPageAnnotation::Highlight(_) => PdfPageAnnotationType::Highlight,
             PdfPageAnnotation::Ink(_) => PdfPageAnnotationType::Ink,
             PdfPageAnnotation::Link(_) => PdfPageAnnotationType::Link,
+            PdfPageAnnotation::Multimedia(annotation) => annotation.get_type(),
             PdfPageAnnotation::Popup(_) => PdfPageAnnotationType::Popup,
             PdfPageAnnotation::Square(_) => PdfPageAnnotationType::Square,
             PdfPageAnnotation::Squiggly(_) => PdfPageAnnotationType::Squiggly,
@@ -455,12 +516,12 @@ impl<'a> PdfPageAnnotation<'a> {
     /// [PdfPageAnnotation].
     ///
     /// Not all PDF annotation types are supported by Pdfium. For example, Pdfium does not
-    /// currently support embedded sound or movie file annotations, embedded 3D animations, or
-    /// annotations containing embedded file attachments.
+    /// currently support embedded sound or movie file annotations or embedded 3D animations.
     ///
     /// Pdfium currently supports creating, editing, and rendering the following types of annotations:
     ///
     /// * [PdfPageAnnotationType::Circle]
+    /// * [PdfPageAnnotationType::FileAttachment]
     /// * [PdfPageAnnotationType::FreeText]
     /// * [PdfPageAnnotationType::Highlight]
     /// * [PdfPageAnnotationType::Ink]
@@ -484,12 +545,12 @@ impl<'a> PdfPageAnnotation<'a> {
     /// [PdfPageAnnotation].
     ///
     /// Not all PDF annotation types are supported by Pdfium. For example, Pdfium does not
-    /// currently support embedded sound or movie file annotations, embedded 3D animations, or
-    /// annotations containing embedded file attachments.
+    /// currently support embedded sound or movie file annotations or embedded 3D animations.
     ///
     /// Pdfium currently supports creating, editing, and rendering the following types of annotations:
     ///
     /// * [PdfPageAnnotationType::Circle]
+    /// * [PdfPageAnnotationType::FileAttachment]
     /// * [PdfPageAnnotationType::FreeText]
     /// * [PdfPageAnnotationType::Highlight]
     /// * [PdfPageAnnotationType::Ink]
@@ -506,7 +567,247 @@ impl<'a> PdfPageAnnotation<'a> {
     /// * [PdfPageAnnotationType::XfaWidget]
     #[inline]
     pub fn is_unsupported(&self) -> bool {
-        matches!(self, PdfPageAnnotation::Unsupported(_))
+        matches!(
+            self,
+            PdfPageAnnotation::Multimedia(_) | PdfPageAnnotation::Unsupported(_)
+        )
+    }
+
+    /// Returns the z-order of this [PdfPageAnnotation] on its containing `PdfPage`, or `None`
+    /// if the annotation has been removed from the page.
+    ///
+    /// Annotations are drawn in the order in which they are listed on the page, so the z-order
+    /// of an annotation is its index in the page's `PdfPageAnnotations` collection; annotations
+    /// with higher z-orders are drawn over those with lower z-orders.
+    pub fn z_order(&self) -> Option<PdfPageAnnotationIndex> {
+        let index = self
+            .bindings()
+            .FPDFPage_GetAnnotIndex(*self.objects().get_page_handle(), self.handle());
+
+        if index < 0 {
+            None
+        } else {
+            Some(index as PdfPageAnnotationIndex)
+        }
+    }
+
+    /// Returns `true` if this [PdfPageAnnotation] was written in reply to another annotation,
+    /// as part of a comment thread.
+    #[inline]
+    pub fn is_reply(&self) -> bool {
+        match PdfAnnotationDictionaryEdits::get_annotation_reference(self, "IRT") {
+            Some(parent) => parent.is_some(),
+            None => self
+                .bindings()
+                .is_true(self.bindings().FPDFAnnot_HasKey(self.handle(), "IRT")),
+        }
+    }
+
+    /// Returns the annotation to which this [PdfPageAnnotation] was written in reply,
+    /// if any.
+    #[inline]
+    pub fn in_reply_to(&self) -> Option<PdfPageAnnotation<'_>> {
+        linked_annotation(self, "IRT")
+    }
+
+    /// Creates a new text annotation on the page containing this [PdfPageAnnotation],
+    /// written by the given author in reply to this annotation, with the given text as its
+    /// contents. The reply is positioned at the top left corner of this annotation, returning
+    /// the newly created reply.
+    ///
+    /// Pdfium cannot write the reference from the reply to this annotation, so it is written
+    /// when the containing `PdfDocument` is saved. Until then, the reply is returned from
+    /// [PdfPageAnnotation::replies()], but conforming PDF viewers will not show it as part
+    /// of this annotation's comment thread.
+    pub fn add_reply(
+        &mut self,
+        author: &str,
+        text: &str,
+    ) -> Result<PdfPageTextAnnotation<'a>, PdfiumError> {
+        let bindings = self.objects_mut_impl().bindings();
+
+        let document_handle = self.objects().document_handle();
+
+        let page_handle = *self.objects().get_page_handle();
+
+        let handle =
+            bindings.FPDFPage_CreateAnnot(page_handle, PdfPageAnnotationType::Text.as_pdfium());
+
+        if handle.is_null() {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let mut reply =
+            PdfPageTextAnnotation::from_pdfium(document_handle, page_handle, handle, bindings);
+
+        const REPLY_SIZE: f32 = 20.0;
+
+        let bounds = self.bounds()?;
+
+        reply.set_bounds(PdfRect::new_from_values(
+            bounds.top.value - REPLY_SIZE,
+            bounds.left.value,
+            bounds.top.value,
+            bounds.left.value + REPLY_SIZE,
+        ))?;
+        reply.set_creation_date(Utc::now())?;
+        reply.set_author(author)?;
+        reply.set_contents(text)?;
+
+        PdfAnnotationDictionaryEdits::set_annotation_reference(&mut reply, "IRT", self)?;
+
+        Ok(reply)
+    }
+
+    /// Sets the review status of this [PdfPageAnnotation] on behalf of the given reviewer,
+    /// by adding a reply that carries the new status, as conforming PDF viewers do. Returns
+    /// the newly created reply, which is hidden so that it does not clutter the page.
+    ///
+    /// As with [PdfPageAnnotation::add_reply()], the reply is returned from
+    /// [PdfPageAnnotation::replies()] and its status from [PdfPageAnnotation::review_status()]
+    /// straight away, but is only linked to this annotation in the file when the containing
+    /// `PdfDocument` is saved.
+    pub fn set_review_status(
+        &mut self,
+        reviewer: &str,
+        status: PdfAnnotationReviewStatus,
+    ) -> Result<PdfPageTextAnnotation<'a>, PdfiumError> {
+        let state = status.as_state();
+
+        let mut reply = self.add_reply(reviewer, &format!("{} set by {}", state, reviewer))?;
+
+        reply.set_string_value("StateModel", "Review")?;
+        reply.set_string_value("State", state)?;
+
+        if !reply
+            .bindings()
+            .is_true(reply.bindings().FPDFAnnot_SetFlags(
+                reply.handle(),
+                (FPDF_ANNOT_FLAG_HIDDEN | FPDF_ANNOT_FLAG_PRINT) as c_int,
+            ))
+        {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        reply.mark_page_content_changed();
+
+        Ok(reply)
+    }
+
+    /// Returns the popup annotation in which conforming PDF viewers display the text of this
+    /// [PdfPageAnnotation], if any. The returned annotation has an annotation type of
+    /// [PdfPageAnnotationType::Popup].
+    ///
+    /// Use [PdfPageAnnotation::create_popup()] to attach a new popup annotation.
+    #[inline]
+    pub fn popup(&self) -> Option<PdfPageAnnotation<'_>> {
+        linked_annotation(self, "Popup")
+    }
+
+    /// Creates a new popup annotation on the page containing this [PdfPageAnnotation],
+    /// displaying the text of this annotation, returning the newly created popup. The popup
+    /// replaces any popup previously attached to this annotation; it is positioned to the
+    /// right of this annotation, and is initially closed.
+    ///
+    /// Pdfium cannot write the references between this annotation and its popup, so they are
+    /// written when the containing `PdfDocument` is saved. Until then, the popup is returned
+    /// from [PdfPageAnnotation::popup()], but conforming PDF viewers will not display it.
+    pub fn create_popup(&mut self) -> Result<PdfPagePopupAnnotation<'a>, PdfiumError> {
+        let bindings = self.objects_mut_impl().bindings();
+
+        let document_handle = self.objects().document_handle();
+
+        let page_handle = *self.objects().get_page_handle();
+
+        let handle =
+            bindings.FPDFPage_CreateAnnot(page_handle, PdfPageAnnotationType::Popup.as_pdfium());
+
+        if handle.is_null() {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let mut popup =
+            PdfPagePopupAnnotation::from_pdfium(document_handle, page_handle, handle, bindings);
+
+        const POPUP_WIDTH: f32 = 180.0;
+
+        const POPUP_HEIGHT: f32 = 120.0;
+
+        let bounds = self.bounds()?;
+
+        popup.set_bounds(PdfRect::new_from_values(
+            bounds.top.value - POPUP_HEIGHT,
+            bounds.right.value,
+            bounds.top.value,
+            bounds.right.value + POPUP_WIDTH,
+        ))?;
+
+        PdfAnnotationDictionaryEdits::set_annotation_reference(&mut popup, "Parent", self)?;
+        PdfAnnotationDictionaryEdits::set_annotation_reference(self, "Popup", &mut popup)?;
+
+        popup.set_open(false)?;
+
+        Ok(popup)
+    }
+
+    /// Sets whether the popup annotation displaying the text of this [PdfPageAnnotation]
+    /// is initially open in conforming PDF viewers, creating a popup annotation using
+    /// [PdfPageAnnotation::create_popup()] if this annotation does not already have one.
+    pub fn set_popup_open(&mut self, is_open: bool) -> Result<(), PdfiumError> {
+        if let Some(mut popup) = self.popup() {
+            if let Some(popup) = popup.as_popup_annotation_mut() {
+                return popup.set_open(is_open);
+            }
+        }
+
+        self.create_popup()?.set_open(is_open)
+    }
+
+    /// Returns a [PdfPageAnnotationDto] describing this [PdfPageAnnotation], suitable for
+    /// serializing using `serde`. Pass the returned value to
+    /// `PdfPageAnnotations::create_from_dto()` to recreate the annotation.
+    ///
+    /// This function is only available when this crate's `serde` feature is enabled.
+    #[cfg(feature = "serde")]
+    #[inline]
+    pub fn to_dto(&self) -> Result<PdfPageAnnotationDto, PdfiumError> {
+        PdfPageAnnotationDto::from_annotation(self)
+    }
+
+    /// Returns the annotations on the containing `PdfPage` that were written in reply to
+    /// this [PdfPageAnnotation], in page order. Replies to those replies are not included;
+    /// call this function on each reply to walk the rest of the comment thread.
+    ///
+    /// Replies include both comments and the status changes made by reviewers; use
+    /// [PdfPageAnnotation::review_status()] to retrieve the current review status.
+    ///
+    /// Use [PdfPageAnnotation::add_reply()] to add a reply, and
+    /// [PdfPageAnnotation::set_review_status()] to change the review status.
+    pub fn replies(&self) -> Vec<PdfPageAnnotation<'_>> {
+        let index = match self.z_order() {
+            Some(index) => index,
+            None => return Vec::new(),
+        };
+
+        page_annotations(self)
+            .filter(|annotation| {
+                annotation.in_reply_to().and_then(|parent| parent.z_order()) == Some(index)
+            })
+            .collect()
+    }
+
+    /// Returns the current review status of this [PdfPageAnnotation], as set by the most
+    /// recent of its replies that carries a review status, or [PdfAnnotationReviewStatus::None]
+    /// if no reviewer has set a status.
+    #[inline]
+    pub fn review_status(&self) -> PdfAnnotationReviewStatus {
+        review_status_from_replies(&self.replies())
     }
 
     /// Returns an immutable reference to the underlying [PdfPageCircleAnnotation]
@@ -531,6 +832,30 @@ impl<'a> PdfPageAnnotation<'a> {
         }
     }
 
+    /// Returns an immutable reference to the underlying [PdfPageFileAttachmentAnnotation]
+    /// for this [PdfPageAnnotation], if this annotation has an annotation type of
+    /// [PdfPageAnnotationType::FileAttachment].
+    #[inline]
+    pub fn as_file_attachment_annotation(&self) -> Option<&PdfPageFileAttachmentAnnotation<'_>> {
+        match self {
+            PdfPageAnnotation::FileAttachment(annotation) => Some(annotation),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the underlying [PdfPageFileAttachmentAnnotation]
+    /// for this [PdfPageAnnotation], if this annotation has an annotation type of
+    /// [PdfPageAnnotationType::FileAttachment].
+    #[inline]
+    pub fn as_file_attachment_annotation_mut(
+        &mut self,
+    ) -> Option<&mut PdfPageFileAttachmentAnnotation<'a>> {
+        match self {
+            PdfPageAnnotation::FileAttachment(annotation) => Some(annotation),
+            _ => None,
+        }
+    }
+
     /// Returns an immutable reference to the underlying [PdfPageFreeTextAnnotation]
     /// for this [PdfPageAnnotation], if this annotation has an annotation type of
     /// [PdfPageAnnotationType::FreeText].
@@ -619,6 +944,30 @@ impl<'a> PdfPageAnnotation<'a> {
         }
     }
 
+    /// Returns an immutable reference to the underlying [PdfPageMultimediaAnnotation]
+    /// for this [PdfPageAnnotation], if this annotation has an annotation type of
+    /// [PdfPageAnnotationType::Sound], [PdfPageAnnotationType::Movie],
+    /// [PdfPageAnnotationType::Screen], or [PdfPageAnnotationType::RichMedia].
+    #[inline]
+    pub fn as_multimedia_annotation(&self) -> Option<&PdfPageMultimediaAnnotation<'_>> {
+        match self {
+            PdfPageAnnotation::Multimedia(annotation) => Some(annotation),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the underlying [PdfPageMultimediaAnnotation]
+    /// for this [PdfPageAnnotation], if this annotation has an annotation type of
+    /// [PdfPageAnnotationType::Sound], [PdfPageAnnotationType::Movie],
+    /// [PdfPageAnnotationType::Screen], or [PdfPageAnnotationType::RichMedia].
+    #[inline]
+    pub fn as_multimedia_annotation_mut(&mut self) -> Option<&mut PdfPageMultimediaAnnotation<'a>> {
+        match self {
+            PdfPageAnnotation::Multimedia(annotation) => Some(annotation),
+            _ => None,
+        }
+    }
+
     /// Returns an immutable reference to the underlying [PdfPagePopupAnnotation]
     /// for this [PdfPageAnnotation], if this annotation has an annotation type of
     /// [PdfPageAnnotationType::Popup].
@@ -839,6 +1188,30 @@ impl<'a> PdfPageAnnotation<'a> {
         }
     }
 
+    /// Returns an immutable reference to the underlying [PdfPageUnsupportedAnnotation]
+    /// for this [PdfPageAnnotation], if this annotation has an annotation type not
+    /// supported by Pdfium, such as [PdfPageAnnotationType::Polygon].
+    #[inline]
+    pub fn as_unsupported_annotation(&self) -> Option<&PdfPageUnsupportedAnnotation<'a>> {
+        match self {
+            PdfPageAnnotation::Unsupported(annotation) => Some(annotation),
+            _ => None,
+        }
+    }
+
+    /// Returns a mutable reference to the underlying [PdfPageUnsupportedAnnotation]
+    /// for this [PdfPageAnnotation], if this annotation has an annotation type not
+    /// supported by Pdfium, such as [PdfPageAnnotationType::Polygon].
+    #[inline]
+    pub fn as_unsupported_annotation_mut(
+        &mut self,
+    ) -> Option<&mut PdfPageUnsupportedAnnotation<'a>> {
+        match self {
+            PdfPageAnnotation::Unsupported(annotation) => Some(annotation),
+            _ => None,
+        }
+    }
+
     /// Returns an immutable reference to the [PdfFormField] wrapped by this [PdfPageAnnotation],
     /// if any.
     ///
@@ -925,18 +1298,91 @@ pub trait PdfPageAnnotationCommon {
     /// Returns the name of the creator of this [PdfPageAnnotation], if any.
     fn creator(&self) -> Option<String>;
 
+    /// Returns the name of the author of this [PdfPageAnnotation], if any. Conforming PDF viewers
+    /// display the author in the title bar of the annotation's popup window.
+    ///
+    /// This is the same value returned by the [PdfPageAnnotationCommon::creator()] function.
+    fn author(&self) -> Option<String>;
+
+    /// Sets the name of the author of this [PdfPageAnnotation].
+    fn set_author(&mut self, author: &str) -> Result<(), PdfiumError>;
+
+    /// Returns the subject of this [PdfPageAnnotation], if any. Conforming PDF viewers
+    /// display the subject in comment lists, often defaulting it to the annotation's type,
+    /// such as "Highlight" or "Sticky Note".
+    fn subject(&self) -> Option<String>;
+
+    /// Sets the subject of this [PdfPageAnnotation].
+    fn set_subject(&mut self, subject: &str) -> Result<(), PdfiumError>;
+
     /// Returns the date and time when this [PdfPageAnnotation] was originally created, if any.
     fn creation_date(&self) -> Option<String>;
 
+    /// Returns the date and time when this [PdfPageAnnotation] was originally created,
+    /// parsed from the PDF date string returned by [PdfPageAnnotationCommon::creation_date()].
+    /// Returns `None` if no creation date is set or the date string is malformed.
+    fn creation_date_time(&self) -> Option<DateTime<Utc>>;
+
     /// Sets the date and time when this [PdfPageAnnotation] was originally created.
     fn set_creation_date(&mut self, date: DateTime<Utc>) -> Result<(), PdfiumError>;
 
     /// Returns the date and time when this [PdfPageAnnotation] was last modified, if any.
     fn modification_date(&self) -> Option<String>;
 
+    /// Returns the date and time when this [PdfPageAnnotation] was last modified, parsed
+    /// from the PDF date string returned by [PdfPageAnnotationCommon::modification_date()].
+    /// Returns `None` if no modification date is set or the date string is malformed.
+    fn modification_date_time(&self) -> Option<DateTime<Utc>>;
+
     /// Sets the date and time when this [PdfPageAnnotation] was last modified.
     fn set_modification_date(&mut self, date: DateTime<Utc>) -> Result<(), PdfiumError>;
 
+    /// Returns the opacity of this [PdfPageAnnotation], from 0.0 (fully transparent)
+    /// to 1.0 (fully opaque). Annotations are fully opaque unless an opacity is set.
+    fn opacity(&self) -> f32;
+
+    /// Sets the opacity of this [PdfPageAnnotation], from 0.0 (fully transparent)
+    /// to 1.0 (fully opaque).
+    ///
+    /// Pdfium only allows changing the opacity of an annotation that has no appearance stream.
+    /// If this annotation has an appearance stream and is of a type supported by
+    /// [PdfPageAnnotationCommon::generate_appearance_stream()], its appearance stream
+    /// will be regenerated to apply the new opacity; otherwise, an error is returned.
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), PdfiumError>;
+
+    /// Returns the width of the border drawn around this [PdfPageAnnotation], or `None` if
+    /// no border width is set.
+    ///
+    /// Pdfium only reports border widths set using the legacy `/Border` array in the
+    /// annotation's dictionary; border widths set only in a `/BS` border style dictionary
+    /// are not reported.
+    fn border_width(&self) -> Option<PdfPoints>;
+
+    /// Returns the style in which the border of this [PdfPageAnnotation] is drawn, as recorded
+    /// in its `/BS` border style dictionary, or `None` if it has no border style dictionary.
+    ///
+    /// Pdfium cannot read `/BS` border style dictionaries, so unless the border style was
+    /// set using [PdfPageAnnotationCommon::set_border()] since the document was opened,
+    /// a copy of the document is saved to memory and the border style is read from the copy.
+    fn border_style(&self) -> Result<Option<PdfAnnotationBorderStyle>, PdfiumError>;
+
+    /// Sets the width of the border drawn around this [PdfPageAnnotation], and draws the
+    /// border in the given style.
+    ///
+    /// Pdfium can set the border width in the annotation's dictionary, but cannot write
+    /// `/BS` border style dictionaries, so the border width and style are written to the
+    /// annotation's `/BS` dictionary when the document is next saved. For annotation types
+    /// supported by [PdfPageAnnotationCommon::generate_appearance_stream()], and for link
+    /// annotations, a border style other than [PdfAnnotationBorderStyle::Solid] is also
+    /// applied immediately by generating the annotation's appearance stream; appearance
+    /// streams generated later draw the border in the same style. Other annotation types are
+    /// drawn in the new style by PDF viewers that generate their own appearance streams.
+    fn set_border(
+        &mut self,
+        width: PdfPoints,
+        style: PdfAnnotationBorderStyle,
+    ) -> Result<(), PdfiumError>;
+
     /// Returns the color of any filled paths in this [PdfPageAnnotation].
     fn fill_color(&self) -> Result<PdfColor, PdfiumError>;
 
@@ -978,6 +1424,26 @@ pub trait PdfPageAnnotationCommon {
     /// annotation.as_link_annotation_mut().unwrap().attachment_points_mut();
     /// ```
     fn attachment_points(&self) -> &PdfPageAnnotationAttachmentPoints;
+
+    /// Generates a normal appearance stream for this [PdfPageAnnotation] from its current
+    /// bounds, colors, border width, attachment points, ink strokes, vertices, and line
+    /// coordinates, replacing any existing normal appearance stream, so that the annotation
+    /// displays consistently in all PDF viewers.
+    ///
+    /// Appearance streams can be generated for annotations of types
+    /// [PdfPageAnnotationType::Highlight], [PdfPageAnnotationType::Underline],
+    /// [PdfPageAnnotationType::Strikeout], [PdfPageAnnotationType::Squiggly],
+    /// [PdfPageAnnotationType::Square], [PdfPageAnnotationType::Circle],
+    /// [PdfPageAnnotationType::Line], [PdfPageAnnotationType::Polygon],
+    /// [PdfPageAnnotationType::Polyline], and [PdfPageAnnotationType::Ink]. Returns `false`,
+    /// leaving this [PdfPageAnnotation] unchanged, for all other annotation types.
+    ///
+    /// If no color is set, highlights are drawn in yellow and all other annotations in black.
+    /// Line endings such as arrowheads are not drawn.
+    ///
+    /// Call this function again after changing any of these properties, since Pdfium
+    /// does not update an existing appearance stream when an annotation is changed.
+    fn generate_appearance_stream(&mut self) -> Result<bool, PdfiumError>;
 }
 
 // Blanket implementation for all PdfPageAnnotation types.
@@ -1041,11 +1507,37 @@ where
         self.creator_impl()
     }
 
+    #[inline]
+    fn author(&self) -> Option<String> {
+        self.creator_impl()
+    }
+
+    #[inline]
+    fn set_author(&mut self, author: &str) -> Result<(), PdfiumError> {
+        self.set_creator(author)
+    }
+
+    #[inline]
+    fn subject(&self) -> Option<String> {
+        self.subject_impl()
+    }
+
+    #[inline]
+    fn set_subject(&mut self, subject: &str) -> Result<(), PdfiumError> {
+        self.set_subject_impl(subject)
+    }
+
     #[inline]
     fn creation_date(&self) -> Option<String> {
         self.creation_date_impl()
     }
 
+    #[inline]
+    fn creation_date_time(&self) -> Option<DateTime<Utc>> {
+        self.creation_date_impl()
+            .and_then(|date| date_time_from_pdf_string(&date))
+    }
+
     #[inline]
     fn set_creation_date(&mut self, date: DateTime<Utc>) -> Result<(), PdfiumError> {
         self.set_creation_date_impl(date)
@@ -1056,11 +1548,46 @@ where
         self.modification_date_impl()
     }
 
+    #[inline]
+    fn modification_date_time(&self) -> Option<DateTime<Utc>> {
+        self.modification_date_impl()
+            .and_then(|date| date_time_from_pdf_string(&date))
+    }
+
     #[inline]
     fn set_modification_date(&mut self, date: DateTime<Utc>) -> Result<(), PdfiumError> {
         self.set_modification_date_impl(date)
     }
 
+    #[inline]
+    fn opacity(&self) -> f32 {
+        self.opacity_impl()
+    }
+
+    #[inline]
+    fn set_opacity(&mut self, opacity: f32) -> Result<(), PdfiumError> {
+        self.set_opacity_impl(opacity)
+    }
+
+    #[inline]
+    fn border_width(&self) -> Option<PdfPoints> {
+        self.border_width_impl()
+    }
+
+    #[inline]
+    fn border_style(&self) -> Result<Option<PdfAnnotationBorderStyle>, PdfiumError> {
+        self.border_style_impl()
+    }
+
+    #[inline]
+    fn set_border(
+        &mut self,
+        width: PdfPoints,
+        style: PdfAnnotationBorderStyle,
+    ) -> Result<(), PdfiumError> {
+        self.set_border_impl(width, &style)
+    }
+
     #[inline]
     fn fill_color(&self) -> Result<PdfColor, PdfiumError> {
         self.fill_color_impl()
@@ -1090,6 +1617,11 @@ where
     fn attachment_points(&self) -> &PdfPageAnnotationAttachmentPoints {
         self.attachment_points_impl()
     }
+
+    #[inline]
+    fn generate_appearance_stream(&mut self) -> Result<bool, PdfiumError> {
+        generate_normal_appearance(self)
+    }
 }
 
 impl<'a> PdfPageAnnotationPrivate<'a> for PdfPageAnnotation<'a> {
@@ -1124,6 +1656,70 @@ impl<'a> PdfPageAnnotationPrivate<'a> for PdfPageAnnotation<'a> {
     }
 }
 
+/// Returns an iterator over all the annotations on the page containing the given annotation,
+/// in page order.
+fn page_annotations<'b, 'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &'b T,
+) -> impl Iterator<Item = PdfPageAnnotation<'b>> {
+    let bindings = annotation.bindings();
+
+    let document_handle = annotation.document_handle();
+
+    let page_handle = *annotation.objects_impl().get_page_handle();
+
+    (0..bindings.FPDFPage_GetAnnotCount(page_handle)).filter_map(move |index| {
+        let handle = bindings.FPDFPage_GetAnnot(page_handle, index);
+
+        if handle.is_null() {
+            None
+        } else {
+            Some(PdfPageAnnotation::from_pdfium(
+                document_handle,
+                page_handle,
+                handle,
+                None,
+                bindings,
+            ))
+        }
+    })
+}
+
+/// Returns the annotation referenced by the entry with the given key in the dictionary of
+/// the given annotation, if any.
+pub(crate) fn linked_annotation<'b, 'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &'b T,
+    key: &str,
+) -> Option<PdfPageAnnotation<'b>> {
+    // Pdfium cannot see references between annotations recorded since the document was
+    // loaded until the document is saved, so we look for the referenced annotations
+    // by unique name.
+
+    match PdfAnnotationDictionaryEdits::get_annotation_reference(annotation, key) {
+        Some(Some(name)) => {
+            return page_annotations(annotation)
+                .find(|linked| linked.name().as_deref() == Some(name.as_str()))
+        }
+        Some(None) => return None,
+        None => {}
+    }
+
+    let bindings = annotation.bindings();
+
+    let handle = bindings.FPDFAnnot_GetLinkedAnnot(annotation.handle(), key);
+
+    if handle.is_null() {
+        None
+    } else {
+        Some(PdfPageAnnotation::from_pdfium(
+            annotation.document_handle(),
+            *annotation.objects_impl().get_page_handle(),
+            handle,
+            None,
+            bindings,
+        ))
+    }
+}
+
 impl<'a> Drop for PdfPageAnnotation<'a> {
     /// Closes this [PdfPageAnnotation], releasing held memory.
     #[inline]
@@ -1131,3 +1727,99 @@ impl<'a> Drop for PdfPageAnnotation<'a> {
         self.bindings().FPDFPage_CloseAnnot(self.handle());
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_annotation_metadata_accessors() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let mut annotation = page.annotations_mut().create_square_annotation()?;
+
+        annotation.set_bounds(PdfRect::new_from_values(100.0, 100.0, 200.0, 200.0))?;
+
+        annotation.set_author("Reviewer")?;
+        annotation.set_subject("Rectangle")?;
+
+        assert_eq!(annotation.author(), Some("Reviewer".to_string()));
+        assert_eq!(annotation.subject(), Some("Rectangle".to_string()));
+
+        let date = Utc.with_ymd_and_hms(2024, 3, 1, 12, 30, 0).unwrap();
+
+        annotation.set_creation_date(date)?;
+        annotation.set_modification_date(date)?;
+
+        assert_eq!(annotation.creation_date_time(), Some(date));
+        assert_eq!(annotation.modification_date_time(), Some(date));
+
+        assert_eq!(annotation.opacity(), 1.0);
+
+        annotation.set_opacity(0.5)?;
+
+        assert!((annotation.opacity() - 0.5).abs() < 0.01);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_border_styles() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let dashed =
+            PdfAnnotationBorderStyle::Dashed(vec![PdfPoints::new(4.0), PdfPoints::new(2.0)]);
+
+        let mut square = page.annotations_mut().create_square_annotation()?;
+
+        square.set_bounds(PdfRect::new_from_values(100.0, 100.0, 200.0, 200.0))?;
+
+        assert_eq!(square.border_style()?, None);
+
+        square.set_border(PdfPoints::new(2.0), dashed.clone())?;
+
+        assert_eq!(square.border_style()?, Some(dashed.clone()));
+        assert_eq!(square.border_width(), Some(PdfPoints::new(2.0)));
+
+        // Appearance streams generated later keep the recorded border style.
+
+        square.set_opacity(0.5)?;
+
+        assert_eq!(square.border_style()?, Some(dashed.clone()));
+
+        // Border styles are recorded for annotation types whose appearance streams cannot
+        // be generated.
+
+        let mut note = page.annotations_mut().create_text_annotation("Note")?;
+
+        note.set_border(PdfPoints::new(1.0), PdfAnnotationBorderStyle::Beveled)?;
+
+        drop(page);
+
+        let document = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        let page = document.pages().get(0)?;
+
+        assert_eq!(page.annotations().get(0)?.border_style()?, Some(dashed));
+        assert_eq!(
+            page.annotations().get(1)?.border_style()?,
+            Some(PdfAnnotationBorderStyle::Beveled)
+        );
+
+        Ok(())
+    }
+}
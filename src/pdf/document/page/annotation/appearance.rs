@@ -0,0 +1,2222 @@
+//! Generates normal appearance streams for annotations from their properties, so that
+//! annotations created or edited using `pdfium-render` display consistently in all PDF
+//! viewers, including viewers that do not generate missing appearance streams themselves.
+
+use crate::bindgen::{
+    FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color,
+    FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor, FPDFANNOT_COLORTYPE, FPDF_FONT,
+    FPDF_WCHAR, FS_POINTF,
+};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::appearance_mode::PdfAppearanceMode;
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::page::annotation::border::PdfAnnotationBorderStyle;
+use crate::pdf::document::page::annotation::builders::{
+    PdfFileAttachmentIcon, PdfFreeTextAlignment,
+};
+use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
+use crate::pdf::document::page::annotation::line_ending::PdfAnnotationLineEnding;
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::PdfPageAnnotationType;
+use crate::pdf::document::syntax::PdfSyntaxObject;
+use crate::pdf::font::glyph::PdfFontGlyph;
+use crate::pdf::path::segment::PdfPathSegmentType;
+use crate::pdf::path::segments::PdfPathSegments;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::quad_points::PdfQuadPoints;
+use crate::pdf::rect::PdfRect;
+use crate::utils::mem::create_byte_buffer;
+use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use std::convert::TryFrom;
+use std::os::raw::{c_float, c_uint, c_ulong};
+use std::ptr::{null, null_mut};
+
+/// The control point distance, as a fraction of the radius, used to approximate a quarter
+/// ellipse with a single cubic Bézier curve.
+const BEZIER_ELLIPSE_CONTROL_POINT: f32 = 0.552_284_8;
+
+/// The border width used to draw annotations that do not set a border width.
+const DEFAULT_BORDER_WIDTH: PdfPoints = PdfPoints::new(1.0);
+
+/// The leader lines drawn by a line annotation, as described in section 8.4.5 of
+/// The PDF Reference Manual, sixth edition, on page 630.
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub(crate) struct PdfLineLeader {
+    /// The length of the leader lines extending perpendicularly from each end point of
+    /// the line to the line itself. Positive lengths extend clockwise, when traversing the
+    /// line from its start point to its end point; negative lengths extend anticlockwise.
+    pub(crate) length: f32,
+
+    /// The length by which the leader lines extend beyond the line.
+    pub(crate) extension: f32,
+
+    /// The length of the gap between each end point and the start of its leader line.
+    pub(crate) offset: f32,
+}
+
+impl PdfLineLeader {
+    /// Returns the points at which the line drawn between the given end points starts and ends,
+    /// taking these leader lines into account, together with the unit vector pointing from each
+    /// end point along its leader line.
+    pub(crate) fn line_ends(
+        &self,
+        start: (f32, f32),
+        end: (f32, f32),
+    ) -> ((f32, f32), (f32, f32), (f32, f32)) {
+        let length = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
+
+        if self.length == 0.0 || length == 0.0 {
+            return (start, end, (0.0, 0.0));
+        }
+
+        // The unit vector perpendicular to the line, pointing clockwise when traversing
+        // the line from its start point to its end point, in the direction of the leader lines.
+
+        let sign = self.length.signum();
+
+        let normal = (
+            (end.1 - start.1) / length * sign,
+            -(end.0 - start.0) / length * sign,
+        );
+
+        let distance = self.offset.abs() + self.length.abs();
+
+        (
+            (start.0 + normal.0 * distance, start.1 + normal.1 * distance),
+            (end.0 + normal.0 * distance, end.1 + normal.1 * distance),
+            normal,
+        )
+    }
+
+    /// Returns the points spanned by the line drawn between the given end points and
+    /// by these leader lines.
+    pub(crate) fn extent(
+        &self,
+        start: (PdfPoints, PdfPoints),
+        end: (PdfPoints, PdfPoints),
+    ) -> Vec<(PdfPoints, PdfPoints)> {
+        let (_, _, (normal_x, normal_y)) =
+            self.line_ends((start.0.value, start.1.value), (end.0.value, end.1.value));
+
+        let distance = self.offset.abs() + self.length.abs() + self.extension.abs();
+
+        let mut points = vec![start, end];
+
+        if normal_x != 0.0 || normal_y != 0.0 {
+            for (x, y) in [start, end] {
+                points.push((
+                    PdfPoints::new(x.value + normal_x * distance),
+                    PdfPoints::new(y.value + normal_y * distance),
+                ));
+            }
+        }
+
+        points
+    }
+}
+
+/// Returns the distance by which the given line endings, drawn on a line of the given width,
+/// may extend beyond the line's end points. Line endings are six times as wide as the line,
+/// and at least six points wide.
+pub(crate) fn line_ending_margin(
+    line_width: PdfPoints,
+    (start, end): (PdfAnnotationLineEnding, PdfAnnotationLineEnding),
+) -> PdfPoints {
+    if start == PdfAnnotationLineEnding::None && end == PdfAnnotationLineEnding::None {
+        PdfPoints::ZERO
+    } else {
+        PdfPoints::new(6.0 * line_width.value.max(1.0))
+    }
+}
+
+/// The caption drawn along a line annotation.
+pub(crate) struct PdfLineCaption<'a> {
+    pub(crate) bindings: &'a dyn PdfiumLibraryBindings,
+    pub(crate) text: &'a str,
+    pub(crate) font: FPDF_FONT,
+    pub(crate) font_size: PdfPoints,
+
+    /// `true` if the caption is centered on the line, which is broken around it;
+    /// `false` if the caption is drawn above the line.
+    pub(crate) is_inline: bool,
+}
+
+/// The style of line drawn by a text markup annotation.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum PdfTextMarkupStyle {
+    Highlight,
+    Underline,
+    Strikeout,
+    Squiggly,
+}
+
+/// Returns `true` if normal appearance streams can be generated for annotations of the
+/// given type.
+pub(crate) fn is_appearance_generation_supported(annotation_type: PdfPageAnnotationType) -> bool {
+    matches!(
+        annotation_type,
+        PdfPageAnnotationType::Highlight
+            | PdfPageAnnotationType::Underline
+            | PdfPageAnnotationType::Strikeout
+            | PdfPageAnnotationType::Squiggly
+            | PdfPageAnnotationType::Square
+            | PdfPageAnnotationType::Circle
+            | PdfPageAnnotationType::Line
+            | PdfPageAnnotationType::Polygon
+            | PdfPageAnnotationType::Polyline
+            | PdfPageAnnotationType::Ink
+    )
+}
+
+/// Returns `true` if the given annotation type is a text markup annotation type, whose
+/// appearance has no border.
+#[inline]
+fn is_text_markup(annotation_type: PdfPageAnnotationType) -> bool {
+    matches!(
+        annotation_type,
+        PdfPageAnnotationType::Highlight
+            | PdfPageAnnotationType::Underline
+            | PdfPageAnnotationType::Strikeout
+            | PdfPageAnnotationType::Squiggly
+    )
+}
+
+/// Returns `true` if the given annotation has a normal appearance stream.
+pub(crate) fn has_normal_appearance<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+) -> bool {
+    // FPDFAnnot_GetAP() returns the length of the appearance stream in bytes, including
+    // a two-byte UTF-16LE terminator, or 2 if the annotation has no appearance stream.
+
+    annotation.bindings().FPDFAnnot_GetAP(
+        annotation.handle(),
+        PdfAppearanceMode::Normal.as_pdfium(),
+        null_mut(),
+        0,
+    ) > 2
+}
+
+/// Generates a normal appearance stream for the given annotation from its current subtype,
+/// bounds, colors, border width, attachment points, ink strokes, vertices, and line coordinates,
+/// replacing any existing normal appearance stream.
+///
+/// The annotation's properties are read before its existing appearance stream is removed, so
+/// an error leaves the existing appearance stream in place.
+///
+/// The border is drawn in the style recorded for the annotation's `/BS` border style
+/// dictionary by `PdfPageAnnotationCommon::set_border()` since the document was opened, or as
+/// a solid line if no style has been recorded.
+///
+/// Returns `Ok(false)`, leaving the annotation unchanged, if appearance streams cannot be
+/// generated for the annotation's type.
+#[inline]
+pub(crate) fn generate_normal_appearance<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+) -> Result<bool, PdfiumError> {
+    let style = match PdfAnnotationDictionaryEdits::get(annotation, "BS") {
+        Some(Some(dictionary)) => PdfAnnotationBorderStyle::from_dictionary(&dictionary),
+        _ => PdfAnnotationBorderStyle::Solid,
+    };
+
+    generate_normal_appearance_with_border_style(annotation, &style)
+}
+
+/// Generates a normal appearance stream for the given annotation as described for
+/// [generate_normal_appearance()], drawing its border in the given style. Appearance streams
+/// can also be generated for link annotations, which are drawn as a border around their bounds.
+///
+/// Dashed borders are drawn for all annotation types other than text markup annotations.
+/// Beveled, inset, and underlined borders are only drawn for square and link annotations;
+/// other annotations are drawn with solid borders instead.
+pub(crate) fn generate_normal_appearance_with_border_style<
+    'a,
+    T: PdfPageAnnotationPrivate<'a> + ?Sized,
+>(
+    annotation: &T,
+    style: &PdfAnnotationBorderStyle,
+) -> Result<bool, PdfiumError> {
+    let annotation_type = annotation.get_annotation_type();
+
+    if !is_appearance_generation_supported(annotation_type)
+        && annotation_type != PdfPageAnnotationType::Link
+    {
+        return Ok(false);
+    }
+
+    let handle = annotation.handle();
+
+    let bindings = annotation.bindings();
+
+    // Read every property that can fail to be read before touching the existing appearance
+    // stream. Only line annotations need their end points.
+
+    let bounds = annotation.bounds_impl()?;
+
+    let line = if annotation_type == PdfPageAnnotationType::Line {
+        Some(
+            line_points(annotation).ok_or(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ))?,
+        )
+    } else {
+        None
+    };
+
+    // Pdfium only reports the colors set in an annotation's dictionary if the annotation
+    // has no appearance stream, so any existing normal appearance stream must be removed
+    // before the colors can be read. The existing stream's content is kept so that it can
+    // be restored if the new stream cannot be set.
+
+    let previous = normal_appearance_content(annotation);
+
+    if !bindings.is_true(bindings.FPDFAnnot_SetAP(
+        handle,
+        PdfAppearanceMode::Normal.as_pdfium(),
+        null(),
+    )) {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    let color = annotation_color(annotation, FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color)
+        .unwrap_or(if annotation_type == PdfPageAnnotationType::Highlight {
+            PdfColor::YELLOW
+        } else {
+            PdfColor::BLACK
+        });
+
+    let content = match annotation_type {
+        PdfPageAnnotationType::Highlight
+        | PdfPageAnnotationType::Underline
+        | PdfPageAnnotationType::Strikeout
+        | PdfPageAnnotationType::Squiggly => {
+            let style = match annotation_type {
+                PdfPageAnnotationType::Highlight => PdfTextMarkupStyle::Highlight,
+                PdfPageAnnotationType::Underline => PdfTextMarkupStyle::Underline,
+                PdfPageAnnotationType::Strikeout => PdfTextMarkupStyle::Strikeout,
+                _ => PdfTextMarkupStyle::Squiggly,
+            };
+
+            let mut quad_points = annotation
+                .attachment_points_impl()
+                .iter()
+                .collect::<Vec<_>>();
+
+            if quad_points.is_empty() {
+                quad_points.push(PdfQuadPoints::from_rect(bounds));
+            }
+
+            text_markup_appearance(&quad_points, style, color)
+        }
+        PdfPageAnnotationType::Square | PdfPageAnnotationType::Link
+            if matches!(
+                style,
+                PdfAnnotationBorderStyle::Beveled
+                    | PdfAnnotationBorderStyle::Inset
+                    | PdfAnnotationBorderStyle::Underline
+            ) =>
+        {
+            styled_rect_appearance(
+                bounds,
+                color,
+                if annotation_type == PdfPageAnnotationType::Square {
+                    interior_color(annotation, color)
+                } else {
+                    None
+                },
+                border_width(annotation).unwrap_or(DEFAULT_BORDER_WIDTH),
+                style,
+            )
+        }
+        PdfPageAnnotationType::Link => shape_appearance(
+            bounds,
+            color,
+            None,
+            border_width(annotation).unwrap_or(DEFAULT_BORDER_WIDTH),
+            false,
+        ),
+        PdfPageAnnotationType::Square | PdfPageAnnotationType::Circle => shape_appearance(
+            bounds,
+            color,
+            interior_color(annotation, color),
+            border_width(annotation).unwrap_or(DEFAULT_BORDER_WIDTH),
+            annotation_type == PdfPageAnnotationType::Circle,
+        ),
+        PdfPageAnnotationType::Polygon => polygon_appearance(
+            &vertices(annotation),
+            color,
+            interior_color(annotation, color),
+            border_width(annotation).unwrap_or(DEFAULT_BORDER_WIDTH),
+            true,
+        ),
+        PdfPageAnnotationType::Polyline => open_path_appearance(
+            &vertices(annotation),
+            color,
+            interior_color(annotation, color),
+            border_width(annotation).unwrap_or(DEFAULT_BORDER_WIDTH),
+            line_endings(annotation),
+        ),
+        PdfPageAnnotationType::Ink => ink_appearance(
+            &ink_strokes(annotation),
+            color,
+            border_width(annotation).unwrap_or(DEFAULT_BORDER_WIDTH),
+        ),
+        _ => {
+            // Only line annotations remain, whose end points have already been read.
+
+            let origin = (PdfPoints::ZERO, PdfPoints::ZERO);
+
+            let (start, end) = line.unwrap_or((origin, origin));
+
+            line_annotation_appearance(
+                start,
+                end,
+                color,
+                interior_color(annotation, color),
+                border_width(annotation).unwrap_or(DEFAULT_BORDER_WIDTH),
+                PdfLineLeader {
+                    length: number_value(annotation, "LL").unwrap_or(0.0),
+                    extension: number_value(annotation, "LLE").unwrap_or(0.0),
+                    offset: number_value(annotation, "LLO").unwrap_or(0.0),
+                },
+                line_endings(annotation),
+                None,
+            )
+        }
+    };
+
+    // Every appearance stream begins by saving the graphics state, after which the
+    // dash pattern of a dashed border can be set.
+
+    let content = match style.dash_operator() {
+        Some(dash) if !is_text_markup(annotation_type) => {
+            content.replacen("q\n", &format!("q\n{}", dash), 1)
+        }
+        _ => content,
+    };
+
+    if bindings.is_true(bindings.FPDFAnnot_SetAP_str(
+        handle,
+        PdfAppearanceMode::Normal.as_pdfium(),
+        &content,
+    )) {
+        annotation.mark_page_content_changed();
+
+        Ok(true)
+    } else {
+        if let Some(previous) = previous {
+            bindings.FPDFAnnot_SetAP_str(handle, PdfAppearanceMode::Normal.as_pdfium(), &previous);
+        }
+
+        Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ))
+    }
+}
+
+/// Returns the content of the given annotation's normal appearance stream, if it has one.
+fn normal_appearance_content<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+) -> Option<String> {
+    // Retrieving the appearance stream from Pdfium is a two-step operation. First, we call
+    // FPDFAnnot_GetAP() with a null buffer; this will retrieve the length of the stream's
+    // content in bytes, including a two-byte UTF-16LE terminator. A length of 2 means the
+    // annotation has no appearance stream.
+
+    // If the length is greater than 2, then we reserve a byte buffer of the given length and
+    // call FPDFAnnot_GetAP() again with a pointer to the buffer; this will write the stream's
+    // content into the buffer as UTF-16LE.
+
+    let bindings = annotation.bindings();
+
+    let buffer_length = bindings.FPDFAnnot_GetAP(
+        annotation.handle(),
+        PdfAppearanceMode::Normal.as_pdfium(),
+        null_mut(),
+        0,
+    );
+
+    if buffer_length <= 2 {
+        return None;
+    }
+
+    let mut buffer = create_byte_buffer(buffer_length as usize);
+
+    let result = bindings.FPDFAnnot_GetAP(
+        annotation.handle(),
+        PdfAppearanceMode::Normal.as_pdfium(),
+        buffer.as_mut_ptr() as *mut FPDF_WCHAR,
+        buffer_length,
+    );
+
+    assert_eq!(result, buffer_length);
+
+    get_string_from_pdfium_utf16le_bytes(buffer)
+}
+
+/// Returns the color of the given type set in the given annotation's dictionary, with its
+/// alpha channel set to the annotation's opacity. Pdfium reports a default color if none is
+/// set, and no color at all if the annotation has a normal appearance stream.
+pub(crate) fn annotation_color<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+    color_type: FPDFANNOT_COLORTYPE,
+) -> Option<PdfColor> {
+    let mut r: c_uint = 0;
+    let mut g: c_uint = 0;
+    let mut b: c_uint = 0;
+    let mut a: c_uint = 0;
+
+    if annotation
+        .bindings()
+        .is_true(annotation.bindings().FPDFAnnot_GetColor(
+            annotation.handle(),
+            color_type,
+            &mut r,
+            &mut g,
+            &mut b,
+            &mut a,
+        ))
+    {
+        Some(PdfColor::new(r as u8, g as u8, b as u8, a as u8))
+    } else {
+        None
+    }
+}
+
+/// Returns the interior color set in the given annotation's dictionary, if any, with its
+/// alpha channel set to that of the given stroke color.
+fn interior_color<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+    color: PdfColor,
+) -> Option<PdfColor> {
+    // Pdfium reports a default interior color of black if none is set, so the
+    // interior color is only used if the annotation's dictionary contains one.
+
+    if annotation.bindings().is_true(
+        annotation
+            .bindings()
+            .FPDFAnnot_HasKey(annotation.handle(), "IC"),
+    ) {
+        annotation_color(
+            annotation,
+            FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor,
+        )
+        .map(|fill_color| fill_color.with_alpha(color.alpha()))
+    } else {
+        None
+    }
+}
+
+/// Returns the border width of the given annotation, or `None` if no border is set.
+pub(crate) fn border_width<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+) -> Option<PdfPoints> {
+    let mut horizontal_radius: c_float = 0.0;
+    let mut vertical_radius: c_float = 0.0;
+    let mut width: c_float = 0.0;
+
+    if annotation
+        .bindings()
+        .is_true(annotation.bindings().FPDFAnnot_GetBorder(
+            annotation.handle(),
+            &mut horizontal_radius,
+            &mut vertical_radius,
+            &mut width,
+        ))
+    {
+        Some(PdfPoints::new(width))
+    } else {
+        None
+    }
+}
+
+/// Returns the points of every stroke in the given ink annotation.
+pub(crate) fn ink_strokes<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+) -> Vec<Vec<(PdfPoints, PdfPoints)>> {
+    let bindings = annotation.bindings();
+
+    let handle = annotation.handle();
+
+    (0..bindings.FPDFAnnot_GetInkListCount(handle))
+        .map(|index| {
+            // Retrieving the length of the path first lets us allocate a buffer of the correct size.
+
+            let length = bindings.FPDFAnnot_GetInkListPath(handle, index, null_mut(), 0);
+
+            let mut buffer = vec![FS_POINTF { x: 0.0, y: 0.0 }; length as usize];
+
+            bindings.FPDFAnnot_GetInkListPath(
+                handle,
+                index,
+                buffer.as_mut_ptr(),
+                length as c_ulong,
+            );
+
+            buffer
+                .into_iter()
+                .map(|point| (PdfPoints::new(point.x), PdfPoints::new(point.y)))
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the start and end points of the given line annotation, or `None` if the
+/// annotation is of any other type.
+pub(crate) fn line_points<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+) -> Option<((PdfPoints, PdfPoints), (PdfPoints, PdfPoints))> {
+    // End points set since the annotation's document was loaded take precedence.
+
+    if let Some(edited) = PdfAnnotationDictionaryEdits::get(annotation, "L") {
+        return match edited {
+            Some(PdfSyntaxObject::Array(coordinates)) => match coordinates.as_slice() {
+                [PdfSyntaxObject::Number(x1), PdfSyntaxObject::Number(y1), PdfSyntaxObject::Number(x2), PdfSyntaxObject::Number(y2)] => {
+                    Some((
+                        (PdfPoints::new(*x1 as f32), PdfPoints::new(*y1 as f32)),
+                        (PdfPoints::new(*x2 as f32), PdfPoints::new(*y2 as f32)),
+                    ))
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+    }
+
+    let mut start = FS_POINTF { x: 0.0, y: 0.0 };
+
+    let mut end = FS_POINTF { x: 0.0, y: 0.0 };
+
+    if annotation
+        .bindings()
+        .is_true(
+            annotation
+                .bindings()
+                .FPDFAnnot_GetLine(annotation.handle(), &mut start, &mut end),
+        )
+    {
+        Some((
+            (PdfPoints::new(start.x), PdfPoints::new(start.y)),
+            (PdfPoints::new(end.x), PdfPoints::new(end.y)),
+        ))
+    } else {
+        None
+    }
+}
+
+/// Returns the number stored under the given key in the given annotation's dictionary, if any.
+/// Numbers set since the annotation's document was loaded, which Pdfium cannot see until
+/// the document is saved, take precedence.
+pub(crate) fn number_value<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+    key: &str,
+) -> Option<f32> {
+    if let Some(edited) = PdfAnnotationDictionaryEdits::get(annotation, key) {
+        return match edited {
+            Some(PdfSyntaxObject::Number(value)) => Some(value as f32),
+            _ => None,
+        };
+    }
+
+    let mut value: c_float = 0.0;
+
+    if annotation
+        .bindings()
+        .is_true(annotation.bindings().FPDFAnnot_GetNumberValue(
+            annotation.handle(),
+            key,
+            &mut value,
+        ))
+    {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+/// Returns the vertices of the given polygon or polyline annotation, or an empty list
+/// if the annotation is of any other type. Vertices set since the annotation's document
+/// was loaded, which Pdfium cannot see until the document is saved, take precedence.
+pub(crate) fn vertices<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+) -> Vec<(PdfPoints, PdfPoints)> {
+    if let Some(edited) = PdfAnnotationDictionaryEdits::get(annotation, "Vertices") {
+        return match edited {
+            Some(PdfSyntaxObject::Array(coordinates)) => coordinates
+                .chunks_exact(2)
+                .filter_map(|pair| match pair {
+                    [PdfSyntaxObject::Number(x), PdfSyntaxObject::Number(y)] => {
+                        Some((PdfPoints::new(*x as f32), PdfPoints::new(*y as f32)))
+                    }
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    let bindings = annotation.bindings();
+
+    let handle = annotation.handle();
+
+    // Retrieving the number of vertices first lets us allocate a buffer of the correct size.
+
+    let length = bindings.FPDFAnnot_GetVertices(handle, null_mut(), 0);
+
+    if length == 0 {
+        return Vec::new();
+    }
+
+    let mut buffer = vec![FS_POINTF { x: 0.0, y: 0.0 }; length as usize];
+
+    bindings.FPDFAnnot_GetVertices(handle, buffer.as_mut_ptr(), length);
+
+    buffer
+        .into_iter()
+        .map(|point| (PdfPoints::new(point.x), PdfPoints::new(point.y)))
+        .collect()
+}
+
+/// Returns the line endings drawn at the start and end of the given line or polyline
+/// annotation. Pdfium cannot read the `/LE` array holding an annotation's line endings,
+/// so only line endings set since the annotation's document was loaded are returned.
+pub(crate) fn line_endings<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &T,
+) -> (PdfAnnotationLineEnding, PdfAnnotationLineEnding) {
+    PdfAnnotationDictionaryEdits::get(annotation, "LE")
+        .flatten()
+        .and_then(|array| PdfAnnotationLineEnding::from_array(&array))
+        .unwrap_or_default()
+}
+
+/// Formats the given value as a number in a PDF content stream, with at most three
+/// decimal places and no trailing zeros.
+pub(crate) fn format_number(value: f32) -> String {
+    let formatted = format!("{:.3}", value);
+
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+
+    if formatted == "-0" {
+        "0".to_string()
+    } else {
+        formatted.to_string()
+    }
+}
+
+/// Returns the red, green, and blue components of the given color as content stream operands.
+pub(crate) fn color_operands(color: PdfColor) -> String {
+    format!(
+        "{} {} {}",
+        format_number(color.red() as f32 / 255.0),
+        format_number(color.green() as f32 / 255.0),
+        format_number(color.blue() as f32 / 255.0)
+    )
+}
+
+/// Returns the content stream operators that begin an appearance stream drawn in the given
+/// color, applying the annotation's opacity if the color is partially transparent.
+pub(crate) fn begin_appearance(color: PdfColor) -> String {
+    if color.alpha() < 255 {
+        String::from("q\n/GS gs\n")
+    } else {
+        String::from("q\n")
+    }
+}
+
+/// Returns the point at the given fractions of the width and height of the given quadrilateral,
+/// measured from its bottom left corner.
+fn point_in_quad(quad: &PdfQuadPoints, along: f32, up: f32) -> (f32, f32) {
+    // Quad points are ordered top left, top right, bottom left, bottom right.
+
+    let bottom_x = quad.x3.value + (quad.x4.value - quad.x3.value) * along;
+    let bottom_y = quad.y3.value + (quad.y4.value - quad.y3.value) * along;
+    let top_x = quad.x1.value + (quad.x2.value - quad.x1.value) * along;
+    let top_y = quad.y1.value + (quad.y2.value - quad.y1.value) * along;
+
+    (
+        bottom_x + (top_x - bottom_x) * up,
+        bottom_y + (top_y - bottom_y) * up,
+    )
+}
+
+/// Returns the height and width of the given quadrilateral.
+fn quad_size(quad: &PdfQuadPoints) -> (f32, f32) {
+    let height = (quad.x1.value - quad.x3.value).hypot(quad.y1.value - quad.y3.value);
+
+    let width = (quad.x4.value - quad.x3.value).hypot(quad.y4.value - quad.y3.value);
+
+    (height, width)
+}
+
+/// Returns a content stream drawing the given text markup style over the given areas.
+fn text_markup_appearance(
+    quad_points: &[PdfQuadPoints],
+    style: PdfTextMarkupStyle,
+    color: PdfColor,
+) -> String {
+    let mut content = begin_appearance(color);
+
+    let color = color_operands(color);
+
+    let point = |(x, y): (f32, f32)| format!("{} {}", format_number(x), format_number(y));
+
+    match style {
+        PdfTextMarkupStyle::Highlight => content.push_str(&format!("{} rg\n", color)),
+        _ => content.push_str(&format!("{} RG\n", color)),
+    }
+
+    for quad in quad_points.iter() {
+        let (height, width) = quad_size(quad);
+
+        let line_width = (height / 14.0).max(1.0);
+
+        match style {
+            PdfTextMarkupStyle::Highlight => content.push_str(&format!(
+                "{} m\n{} l\n{} l\n{} l\nh\nf\n",
+                point(point_in_quad(quad, 0.0, 1.0)),
+                point(point_in_quad(quad, 1.0, 1.0)),
+                point(point_in_quad(quad, 1.0, 0.0)),
+                point(point_in_quad(quad, 0.0, 0.0)),
+            )),
+            PdfTextMarkupStyle::Underline | PdfTextMarkupStyle::Strikeout => {
+                let up = if style == PdfTextMarkupStyle::Underline {
+                    line_width / height.max(1.0)
+                } else {
+                    0.45
+                };
+
+                content.push_str(&format!(
+                    "{} w\n{} m\n{} l\nS\n",
+                    format_number(line_width),
+                    point(point_in_quad(quad, 0.0, up)),
+                    point(point_in_quad(quad, 1.0, up)),
+                ));
+            }
+            PdfTextMarkupStyle::Squiggly => {
+                // A zigzag line along the bottom of the area, with each wave as wide
+                // as a quarter of the area's height.
+
+                let waves = ((width / (height / 4.0).max(1.0)).ceil() as usize).max(2);
+
+                let low = line_width / height.max(1.0);
+
+                let high = low + 0.1;
+
+                content.push_str(&format!("{} w\n", format_number(line_width)));
+
+                for index in 0..=waves {
+                    let up = if index % 2 == 0 { low } else { high };
+
+                    content.push_str(&format!(
+                        "{} {}\n",
+                        point(point_in_quad(quad, index as f32 / waves as f32, up)),
+                        if index == 0 { "m" } else { "l" }
+                    ));
+                }
+
+                content.push_str("S\n");
+            }
+        }
+    }
+
+    content.push_str("Q\n");
+
+    content
+}
+
+/// Returns a content stream drawing the given strokes.
+fn ink_appearance(
+    strokes: &[Vec<(PdfPoints, PdfPoints)>],
+    color: PdfColor,
+    line_width: PdfPoints,
+) -> String {
+    let mut content = begin_appearance(color);
+
+    content.push_str(&format!(
+        "{} RG\n{} w\n1 J\n1 j\n",
+        color_operands(color),
+        format_number(line_width.value)
+    ));
+
+    for stroke in strokes.iter().filter(|stroke| !stroke.is_empty()) {
+        for (index, (x, y)) in stroke.iter().enumerate() {
+            content.push_str(&format!(
+                "{} {} {}\n",
+                format_number(x.value),
+                format_number(y.value),
+                if index == 0 { "m" } else { "l" }
+            ));
+        }
+
+        if stroke.len() == 1 {
+            // Draw a single point as a dot, using the round line cap.
+
+            content.push_str(&format!(
+                "{} {} l\n",
+                format_number(stroke[0].0.value),
+                format_number(stroke[0].1.value)
+            ));
+        }
+
+        content.push_str("S\n");
+    }
+
+    content.push_str("Q\n");
+
+    content
+}
+
+/// Returns a content stream drawing a rectangle or ellipse filling the given bounds.
+fn shape_appearance(
+    bounds: PdfRect,
+    color: PdfColor,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+    is_ellipse: bool,
+) -> String {
+    let mut content = begin_appearance(color);
+
+    content.push_str(&format!(
+        "{} RG\n{} w\n",
+        color_operands(color),
+        format_number(line_width.value)
+    ));
+
+    if let Some(fill_color) = fill_color {
+        content.push_str(&format!("{} rg\n", color_operands(fill_color)));
+    }
+
+    // The border is drawn inside the bounds.
+
+    let inset = line_width.value / 2.0;
+
+    let left = bounds.left.value + inset;
+    let bottom = bounds.bottom.value + inset;
+    let right = bounds.right.value - inset;
+    let top = bounds.top.value - inset;
+
+    if is_ellipse {
+        let center_x = (left + right) / 2.0;
+        let center_y = (bottom + top) / 2.0;
+        let control_x = (right - left) / 2.0 * BEZIER_ELLIPSE_CONTROL_POINT;
+        let control_y = (top - bottom) / 2.0 * BEZIER_ELLIPSE_CONTROL_POINT;
+
+        let n = format_number;
+
+        content.push_str(&format!(
+            "{} {} m\n{} {} {} {} {} {} c\n{} {} {} {} {} {} c\n{} {} {} {} {} {} c\n{} {} {} {} {} {} c\n",
+            n(left), n(center_y),
+            n(left), n(center_y + control_y), n(center_x - control_x), n(top), n(center_x), n(top),
+            n(center_x + control_x), n(top), n(right), n(center_y + control_y), n(right), n(center_y),
+            n(right), n(center_y - control_y), n(center_x + control_x), n(bottom), n(center_x), n(bottom),
+            n(center_x - control_x), n(bottom), n(left), n(center_y - control_y), n(left), n(center_y),
+        ));
+    } else {
+        content.push_str(&format!(
+            "{} {} {} {} re\n",
+            format_number(left),
+            format_number(bottom),
+            format_number(right - left),
+            format_number(top - bottom)
+        ));
+    }
+
+    content.push_str(match (fill_color.is_some(), line_width.value > 0.0) {
+        (true, true) => "B\n",
+        (true, false) => "f\n",
+        (false, _) => "S\n",
+    });
+
+    content.push_str("Q\n");
+
+    content
+}
+
+/// Returns a content stream drawing a beveled, inset, or underlined border inside the given
+/// bounds, as described in section 8.4.3 of The PDF Reference Manual, version 1.7, filling
+/// the bounds with the given fill color first, if any.
+///
+/// Beveled and inset borders are drawn as a solid border enclosing two bands of the same
+/// width: a lighter band along the top and left edges and a darker band along the bottom
+/// and right edges for a beveled border, and the reverse for an inset border.
+fn styled_rect_appearance(
+    bounds: PdfRect,
+    color: PdfColor,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+    style: &PdfAnnotationBorderStyle,
+) -> String {
+    let n = format_number;
+
+    let width = line_width.value;
+
+    let left = bounds.left.value;
+    let bottom = bounds.bottom.value;
+    let right = bounds.right.value;
+    let top = bounds.top.value;
+
+    let mut content = begin_appearance(color);
+
+    if let Some(fill_color) = fill_color {
+        content.push_str(&format!(
+            "{} rg\n{} {} {} {} re\nf\n",
+            color_operands(fill_color),
+            n(left),
+            n(bottom),
+            n(right - left),
+            n(top - bottom)
+        ));
+    }
+
+    if width > 0.0 {
+        content.push_str(&format!("{} RG\n{} w\n", color_operands(color), n(width)));
+
+        if *style == PdfAnnotationBorderStyle::Underline {
+            content.push_str(&format!(
+                "{} {} m\n{} {} l\nS\n",
+                n(left),
+                n(bottom + width / 2.0),
+                n(right),
+                n(bottom + width / 2.0)
+            ));
+        } else {
+            content.push_str(&format!(
+                "{} {} {} {} re\nS\n",
+                n(left + width / 2.0),
+                n(bottom + width / 2.0),
+                n(right - left - width),
+                n(top - bottom - width)
+            ));
+
+            let (light, dark) = if *style == PdfAnnotationBorderStyle::Beveled {
+                (1.0, 0.5)
+            } else {
+                (0.5, 0.75)
+            };
+
+            // The bands lie immediately inside the solid border.
+
+            let (outer_left, outer_bottom) = (left + width, bottom + width);
+            let (outer_right, outer_top) = (right - width, top - width);
+            let (inner_left, inner_bottom) = (outer_left + width, outer_bottom + width);
+            let (inner_right, inner_top) = (outer_right - width, outer_top - width);
+
+            content.push_str(&format!(
+                "{} g\n{} {} m\n{} {} l\n{} {} l\n{} {} l\n{} {} l\n{} {} l\nh\nf\n",
+                n(light),
+                n(outer_left),
+                n(outer_bottom),
+                n(outer_left),
+                n(outer_top),
+                n(outer_right),
+                n(outer_top),
+                n(inner_right),
+                n(inner_top),
+                n(inner_left),
+                n(inner_top),
+                n(inner_left),
+                n(inner_bottom),
+            ));
+
+            content.push_str(&format!(
+                "{} g\n{} {} m\n{} {} l\n{} {} l\n{} {} l\n{} {} l\n{} {} l\nh\nf\n",
+                n(dark),
+                n(outer_right),
+                n(outer_top),
+                n(outer_right),
+                n(outer_bottom),
+                n(outer_left),
+                n(outer_bottom),
+                n(inner_left),
+                n(inner_bottom),
+                n(inner_right),
+                n(inner_bottom),
+                n(inner_right),
+                n(inner_top),
+            ));
+        }
+    }
+
+    content.push_str("Q\n");
+
+    content
+}
+
+/// The layout of the text displayed by a free text annotation.
+pub(crate) struct PdfFreeTextLayout {
+    pub(crate) font: FPDF_FONT,
+    pub(crate) font_size: PdfPoints,
+    pub(crate) text_color: PdfColor,
+    pub(crate) background_color: Option<PdfColor>,
+    pub(crate) border_width: PdfPoints,
+    pub(crate) alignment: PdfFreeTextAlignment,
+    pub(crate) padding: PdfPoints,
+}
+
+/// Returns a content stream drawing the given text inside the given text box, wrapped to fit
+/// the box's width, together with the box's background and border and an optional callout
+/// line pointing from the box to the given point.
+///
+/// Each character is drawn as a filled path tracing the outline of its glyph in the layout's
+/// font, so the content stream does not depend on any font resources. Pdfium provides no way
+/// of adding the font resources needed to draw text to an annotation's appearance stream.
+pub(crate) fn free_text_appearance(
+    bindings: &dyn PdfiumLibraryBindings,
+    text: &str,
+    text_box: PdfRect,
+    callout: Option<(PdfPoints, PdfPoints)>,
+    opacity_color: PdfColor,
+    layout: &PdfFreeTextLayout,
+) -> String {
+    let n = format_number;
+
+    let mut content = begin_appearance(opacity_color);
+
+    if let Some(background_color) = layout.background_color {
+        content.push_str(&format!(
+            "{} rg\n{} {} {} {} re\nf\n",
+            color_operands(background_color),
+            n(text_box.left.value),
+            n(text_box.bottom.value),
+            n(text_box.width().value),
+            n(text_box.height().value)
+        ));
+    }
+
+    let border_width = layout.border_width.value;
+
+    if border_width > 0.0 {
+        content.push_str(&format!(
+            "{} RG\n{} w\n{} {} {} {} re\nS\n",
+            color_operands(layout.text_color),
+            n(border_width),
+            n(text_box.left.value + border_width / 2.0),
+            n(text_box.bottom.value + border_width / 2.0),
+            n(text_box.width().value - border_width),
+            n(text_box.height().value - border_width)
+        ));
+    }
+
+    if let Some((x, y)) = callout {
+        content.push_str(&callout_line(
+            text_box,
+            (x.value, y.value),
+            layout.text_color,
+            border_width.max(DEFAULT_BORDER_WIDTH.value),
+        ));
+    }
+
+    // The text is clipped to the interior of the text box.
+
+    let inset = border_width + layout.padding.value;
+
+    let left = text_box.left.value + inset;
+    let bottom = text_box.bottom.value + inset;
+    let right = text_box.right.value - inset;
+    let top = text_box.top.value - inset;
+
+    if right <= left || top <= bottom {
+        content.push_str("Q\n");
+
+        return content;
+    }
+
+    let size = layout.font_size.value;
+
+    let glyph = |char: char| {
+        u16::try_from(char as u32)
+            .ok()
+            .map(|index| PdfFontGlyph::from_pdfium(layout.font, index, bindings))
+    };
+
+    let char_width = |char: char| {
+        glyph(char)
+            .map(|glyph| glyph.width_at_font_size(layout.font_size).value)
+            .unwrap_or(0.0)
+    };
+
+    let mut ascent: c_float = size;
+    let mut descent: c_float = 0.0;
+
+    bindings.FPDFFont_GetAscent(layout.font, size, &mut ascent);
+    bindings.FPDFFont_GetDescent(layout.font, size, &mut descent);
+
+    let line_height = ascent + descent.abs();
+
+    content.push_str(&format!(
+        "q\n{} {} {} {} re\nW\nn\n{} rg\n",
+        n(left),
+        n(bottom),
+        n(right - left),
+        n(top - bottom),
+        color_operands(layout.text_color)
+    ));
+
+    let mut baseline = top - ascent;
+
+    for (line, width) in wrap_text(text, right - left, char_width) {
+        if baseline + ascent < bottom {
+            break;
+        }
+
+        let mut x = match layout.alignment {
+            PdfFreeTextAlignment::Left => left,
+            PdfFreeTextAlignment::Center => left + (right - left - width) / 2.0,
+            PdfFreeTextAlignment::Right => right - width,
+        };
+
+        for char in line.chars() {
+            if let Some(glyph) = glyph(char) {
+                if !char.is_whitespace() {
+                    if let Ok(path) = glyph.segments_at_font_size(layout.font_size) {
+                        content.push_str(&glyph_path_operators(&path, x, baseline, size));
+                    }
+                }
+
+                x += glyph.width_at_font_size(layout.font_size).value;
+            }
+        }
+
+        baseline -= line_height;
+    }
+
+    content.push_str("f\nQ\nQ\n");
+
+    content
+}
+
+/// Returns path construction operators tracing the given glyph outline, scaled to the given
+/// font size and drawn with its origin at the given position.
+fn glyph_path_operators<'a>(
+    path: &'a impl PdfPathSegments<'a>,
+    origin_x: f32,
+    origin_y: f32,
+    font_size: f32,
+) -> String {
+    // Pdfium returns glyph outlines in a coordinate space where one unit is the font's em size,
+    // irrespective of the requested font size.
+
+    let mut content = String::new();
+
+    let mut control_points = Vec::with_capacity(2);
+
+    for segment in path.iter() {
+        let (x, y) = segment.point();
+
+        let point = format!(
+            "{} {}",
+            format_number(origin_x + x.value * font_size),
+            format_number(origin_y + y.value * font_size)
+        );
+
+        match segment.segment_type() {
+            PdfPathSegmentType::MoveTo => content.push_str(&format!("{} m\n", point)),
+            PdfPathSegmentType::LineTo => content.push_str(&format!("{} l\n", point)),
+            PdfPathSegmentType::BezierTo => {
+                // Each cubic Bézier curve is described by three consecutive segments:
+                // two control points followed by the curve's end point.
+
+                if control_points.len() < 2 {
+                    control_points.push(point);
+
+                    continue;
+                }
+
+                content.push_str(&format!(
+                    "{} {} {} c\n",
+                    control_points[0], control_points[1], point
+                ));
+
+                control_points.clear();
+            }
+            PdfPathSegmentType::Unknown => {}
+        }
+
+        if segment.is_close() {
+            content.push_str("h\n");
+        }
+    }
+
+    content
+}
+
+/// Breaks the given text into lines no wider than the given width, using the given function
+/// to measure the width of each character. Lines are broken at line breaks in the text and
+/// between words; words wider than the given width are broken between characters.
+/// Returns each line together with its width.
+pub(crate) fn wrap_text(
+    text: &str,
+    max_width: f32,
+    char_width: impl Fn(char) -> f32,
+) -> Vec<(String, f32)> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        let mut line_width = 0.0;
+
+        for word in paragraph.split(' ') {
+            let word_width: f32 = word.chars().map(&char_width).sum();
+
+            let space_width = if line.is_empty() {
+                0.0
+            } else {
+                char_width(' ')
+            };
+
+            if !line.is_empty() && line_width + space_width + word_width > max_width {
+                lines.push((std::mem::take(&mut line), line_width));
+                line_width = 0.0;
+            } else if !line.is_empty() {
+                line.push(' ');
+                line_width += space_width;
+            }
+
+            if word_width > max_width {
+                for char in word.chars() {
+                    let width = char_width(char);
+
+                    if !line.is_empty() && line_width + width > max_width {
+                        lines.push((std::mem::take(&mut line), line_width));
+                        line_width = 0.0;
+                    }
+
+                    line.push(char);
+                    line_width += width;
+                }
+            } else {
+                line.push_str(word);
+                line_width += word_width;
+            }
+        }
+
+        lines.push((line, line_width));
+    }
+
+    lines
+}
+
+/// Returns a content stream fragment drawing a callout line with an arrowhead from the
+/// nearest edge of the given text box to the given point.
+fn callout_line(text_box: PdfRect, point: (f32, f32), color: PdfColor, line_width: f32) -> String {
+    let (x, y) = point;
+
+    let center_x = (text_box.left.value + text_box.right.value) / 2.0;
+    let center_y = (text_box.bottom.value + text_box.top.value) / 2.0;
+
+    let start = if x < text_box.left.value {
+        (text_box.left.value, center_y)
+    } else if x > text_box.right.value {
+        (text_box.right.value, center_y)
+    } else if y < text_box.bottom.value {
+        (center_x, text_box.bottom.value)
+    } else {
+        (center_x, text_box.top.value)
+    };
+
+    let length = ((x - start.0).powi(2) + (y - start.1).powi(2)).sqrt();
+
+    let n = format_number;
+
+    let mut content = format!(
+        "{} RG\n{} w\n{} {} m\n{} {} l\n",
+        color_operands(color),
+        n(line_width),
+        n(start.0),
+        n(start.1),
+        n(x),
+        n(y)
+    );
+
+    if length > 0.0 {
+        // An open arrowhead, with arms at 30 degrees to the line.
+
+        let arm = line_width * 6.0;
+
+        let (direction_x, direction_y) = ((start.0 - x) / length, (start.1 - y) / length);
+
+        let (sin, cos) = std::f32::consts::FRAC_PI_6.sin_cos();
+
+        for sign in [1.0, -1.0] {
+            content.push_str(&format!(
+                "{} {} m\n{} {} l\n",
+                n(x + arm * (direction_x * cos - sign * direction_y * sin)),
+                n(y + arm * (sign * direction_x * sin + direction_y * cos)),
+                n(x),
+                n(y)
+            ));
+        }
+    }
+
+    content.push_str("S\n");
+
+    content
+}
+
+/// Returns a content stream drawing the given icon, centered within the given bounds
+/// and scaled to fill them while preserving its aspect ratio.
+pub(crate) fn file_attachment_appearance(
+    bounds: PdfRect,
+    color: PdfColor,
+    icon: PdfFileAttachmentIcon,
+) -> String {
+    // Icons are drawn on a 20 x 20 unit grid, scaled to fit the bounds.
+
+    let scale = bounds.width().value.min(bounds.height().value) / 20.0;
+
+    let n = format_number;
+
+    let mut content = begin_appearance(color);
+
+    content.push_str(&format!(
+        "{} 0 0 {} {} {} cm\n{} RG\n{} rg\n1.5 w\n1 J\n1 j\n",
+        n(scale),
+        n(scale),
+        n((bounds.left.value + bounds.right.value) / 2.0 - 10.0 * scale),
+        n((bounds.bottom.value + bounds.top.value) / 2.0 - 10.0 * scale),
+        color_operands(color),
+        color_operands(color)
+    ));
+
+    let k = BEZIER_ELLIPSE_CONTROL_POINT;
+
+    // Returns operators drawing a semicircle from the left of the given center to its right,
+    // over the top of the center, or the reverse if the semicircle is below the center.
+
+    let semicircle = |center_x: f32, center_y: f32, radius: f32, is_above: bool| {
+        let (from, to, y) = if is_above {
+            (-radius, radius, radius)
+        } else {
+            (radius, -radius, -radius)
+        };
+
+        format!(
+            "{} {} {} {} {} {} c\n{} {} {} {} {} {} c\n",
+            n(center_x + from),
+            n(center_y + y * k),
+            n(center_x + from * k),
+            n(center_y + y),
+            n(center_x),
+            n(center_y + y),
+            n(center_x + to * k),
+            n(center_y + y),
+            n(center_x + to),
+            n(center_y + y * k),
+            n(center_x + to),
+            n(center_y)
+        )
+    };
+
+    match icon {
+        PdfFileAttachmentIcon::Paperclip => {
+            content.push_str("9 7 m\n9 15 l\n");
+            content.push_str(&semicircle(11.0, 15.0, 2.0, true));
+            content.push_str("13 4 l\n");
+            content.push_str(&semicircle(10.0, 4.0, 3.0, false));
+            content.push_str("7 16 l\nS\n");
+        }
+        PdfFileAttachmentIcon::PushPin => {
+            // A needle beneath a round head.
+
+            content.push_str("4 2 m\n9.5 9.5 l\nS\n");
+            content.push_str("8 12 m\n");
+            content.push_str(&semicircle(12.5, 12.0, 4.5, true));
+            content.push_str(&semicircle(12.5, 12.0, 4.5, false));
+            content.push_str("f\n");
+        }
+    }
+
+    content.push_str("Q\n");
+
+    content
+}
+
+/// Returns the content of a normal appearance stream for a redaction annotation, outlining
+/// each of the given regions in the given color, as conforming PDF viewers display
+/// redaction annotations that have not yet been applied.
+pub(crate) fn redact_appearance(regions: &[PdfRect], color: PdfColor) -> String {
+    let mut content = begin_appearance(color);
+
+    content.push_str(&format!("{} RG\n1 w\n", color_operands(color)));
+
+    for region in regions.iter() {
+        content.push_str(&format!(
+            "{} {} {} {} re\n",
+            format_number(region.left.value + 0.5),
+            format_number(region.bottom.value + 0.5),
+            format_number(region.width().value - 1.0),
+            format_number(region.height().value - 1.0)
+        ));
+    }
+
+    content.push_str("S\nQ\n");
+
+    content
+}
+
+/// Returns a content stream drawing straight lines joining the given vertices, closing
+/// and optionally filling the resulting shape if it is a polygon.
+pub(crate) fn polygon_appearance(
+    vertices: &[(PdfPoints, PdfPoints)],
+    color: PdfColor,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+    is_closed: bool,
+) -> String {
+    let mut content = begin_appearance(color);
+
+    content.push_str(&format!(
+        "{} RG\n{} w\n1 j\n",
+        color_operands(color),
+        format_number(line_width.value)
+    ));
+
+    if let Some(fill_color) = fill_color {
+        content.push_str(&format!("{} rg\n", color_operands(fill_color)));
+    }
+
+    if vertices.len() > 1 {
+        for (index, (x, y)) in vertices.iter().enumerate() {
+            content.push_str(&format!(
+                "{} {} {}\n",
+                format_number(x.value),
+                format_number(y.value),
+                if index == 0 { "m" } else { "l" }
+            ));
+        }
+
+        content.push_str(if !is_closed {
+            "S\n"
+        } else {
+            match (fill_color.is_some(), line_width.value > 0.0) {
+                (true, true) => "b\n",
+                (true, false) => "h\nf\n",
+                (false, _) => "s\n",
+            }
+        });
+    }
+
+    content.push_str("Q\n");
+
+    content
+}
+
+/// Returns a content stream drawing straight lines joining the given vertices, with the
+/// given line endings drawn at the start and end of the path. Closed line endings are
+/// filled with the given fill color, if any.
+pub(crate) fn open_path_appearance(
+    vertices: &[(PdfPoints, PdfPoints)],
+    color: PdfColor,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+    (start, end): (PdfAnnotationLineEnding, PdfAnnotationLineEnding),
+) -> String {
+    let content = polygon_appearance(vertices, color, fill_color, line_width, false);
+
+    match (vertices.first(), vertices.get(1)) {
+        (Some(first), Some(second)) => {
+            let last = vertices[vertices.len() - 1];
+
+            let before_last = vertices[vertices.len() - 2];
+
+            let is_filled = fill_color.is_some();
+
+            let point = |(x, y): (PdfPoints, PdfPoints)| (x.value, y.value);
+
+            append_operators(
+                content,
+                &format!(
+                    "{}{}",
+                    line_ending_operators(
+                        start,
+                        point(*first),
+                        point(*second),
+                        line_width.value,
+                        is_filled
+                    ),
+                    line_ending_operators(
+                        end,
+                        point(last),
+                        point(before_last),
+                        line_width.value,
+                        is_filled
+                    )
+                ),
+            )
+        }
+        _ => content,
+    }
+}
+
+/// Inserts the given operators at the end of the given content stream, before the
+/// operator restoring the graphics state saved at its start.
+fn append_operators(mut content: String, operators: &str) -> String {
+    if content.ends_with("Q\n") {
+        content.truncate(content.len() - 2);
+        content.push_str(operators);
+        content.push_str("Q\n");
+    }
+
+    content
+}
+
+/// Returns the content stream operators drawing the given line ending at the given tip of
+/// an open path, whose last segment reaches the tip from the given point. Line endings are
+/// six times as wide as the line, and at least six points wide. Closed line endings are
+/// filled with the current fill color if requested.
+pub(crate) fn line_ending_operators(
+    ending: PdfAnnotationLineEnding,
+    tip: (f32, f32),
+    from: (f32, f32),
+    line_width: f32,
+    is_filled: bool,
+) -> String {
+    let length = ((tip.0 - from.0).powi(2) + (tip.1 - from.1).powi(2)).sqrt();
+
+    if length == 0.0 {
+        return String::new();
+    }
+
+    let size = 6.0 * line_width.max(1.0);
+
+    let half = size / 2.0;
+
+    // The unit vector pointing along the segment towards the tip, and the unit vector
+    // perpendicular to it.
+
+    let (along_x, along_y) = ((tip.0 - from.0) / length, (tip.1 - from.1) / length);
+
+    let (across_x, across_y) = (-along_y, along_x);
+
+    let n = format_number;
+
+    let point = |along: f32, across: f32| {
+        format!(
+            "{} {}",
+            n(tip.0 + along_x * along + across_x * across),
+            n(tip.1 + along_y * along + across_y * across)
+        )
+    };
+
+    let close = if is_filled { "b\n" } else { "s\n" };
+
+    match ending {
+        PdfAnnotationLineEnding::None => String::new(),
+        PdfAnnotationLineEnding::Square => format!(
+            "{} m\n{} l\n{} l\n{} l\n{}",
+            point(-half, -half),
+            point(half, -half),
+            point(half, half),
+            point(-half, half),
+            close
+        ),
+        PdfAnnotationLineEnding::Circle => {
+            let control = half * BEZIER_ELLIPSE_CONTROL_POINT;
+
+            let (x, y) = tip;
+
+            format!(
+                "{} {} m\n{} {} {} {} {} {} c\n{} {} {} {} {} {} c\n{} {} {} {} {} {} c\n{} {} {} {} {} {} c\n{}",
+                n(x - half), n(y),
+                n(x - half), n(y + control), n(x - control), n(y + half), n(x), n(y + half),
+                n(x + control), n(y + half), n(x + half), n(y + control), n(x + half), n(y),
+                n(x + half), n(y - control), n(x + control), n(y - half), n(x), n(y - half),
+                n(x - control), n(y - half), n(x - half), n(y - control), n(x - half), n(y),
+                close
+            )
+        }
+        PdfAnnotationLineEnding::Diamond => format!(
+            "{} m\n{} l\n{} l\n{} l\n{}",
+            point(-half, 0.0),
+            point(0.0, -half),
+            point(half, 0.0),
+            point(0.0, half),
+            close
+        ),
+        PdfAnnotationLineEnding::OpenArrow => format!(
+            "{} m\n{} l\n{} l\nS\n",
+            point(-size, half),
+            point(0.0, 0.0),
+            point(-size, -half)
+        ),
+        PdfAnnotationLineEnding::ClosedArrow => format!(
+            "{} m\n{} l\n{} l\n{}",
+            point(-size, half),
+            point(0.0, 0.0),
+            point(-size, -half),
+            close
+        ),
+        PdfAnnotationLineEnding::ReverseOpenArrow => format!(
+            "{} m\n{} l\n{} l\nS\n",
+            point(size, half),
+            point(0.0, 0.0),
+            point(size, -half)
+        ),
+        PdfAnnotationLineEnding::ReverseClosedArrow => format!(
+            "{} m\n{} l\n{} l\n{}",
+            point(size, half),
+            point(0.0, 0.0),
+            point(size, -half),
+            close
+        ),
+        PdfAnnotationLineEnding::Butt => {
+            format!("{} m\n{} l\nS\n", point(0.0, half), point(0.0, -half))
+        }
+        PdfAnnotationLineEnding::Slash => {
+            // The slash is rotated 30 degrees from the perpendicular.
+
+            let along = half * 0.5;
+
+            let across = half * 0.866_025_4;
+
+            format!(
+                "{} m\n{} l\nS\n",
+                point(along, across),
+                point(-along, -across)
+            )
+        }
+    }
+}
+
+/// Returns a content stream drawing a straight line between the given points, together
+/// with any leader lines.
+fn line_appearance(
+    start: (PdfPoints, PdfPoints),
+    end: (PdfPoints, PdfPoints),
+    color: PdfColor,
+    line_width: PdfPoints,
+    leader: PdfLineLeader,
+    gap: f32,
+) -> String {
+    let mut content = begin_appearance(color);
+
+    content.push_str(&format!(
+        "{} RG\n{} w\n",
+        color_operands(color),
+        format_number(line_width.value)
+    ));
+
+    let (start_x, start_y) = (start.0.value, start.1.value);
+    let (end_x, end_y) = (end.0.value, end.1.value);
+
+    let n = format_number;
+
+    let point = |(x, y): (f32, f32)| format!("{} {}", n(x), n(y));
+
+    let ((line_start_x, line_start_y), (line_end_x, line_end_y), (normal_x, normal_y)) =
+        leader.line_ends((start_x, start_y), (end_x, end_y));
+
+    if normal_x != 0.0 || normal_y != 0.0 {
+        let offset = leader.offset.abs();
+        let leader_end = offset + leader.length.abs() + leader.extension.abs();
+
+        for (x, y) in [(start_x, start_y), (end_x, end_y)] {
+            content.push_str(&format!(
+                "{} m\n{} l\n",
+                point((x + normal_x * offset, y + normal_y * offset)),
+                point((x + normal_x * leader_end, y + normal_y * leader_end))
+            ));
+        }
+    }
+
+    let length = ((line_end_x - line_start_x).powi(2) + (line_end_y - line_start_y).powi(2)).sqrt();
+
+    if gap > 0.0 && gap < length {
+        // The line is broken around its midpoint, leaving room for an inline caption.
+
+        let (middle_x, middle_y) = (
+            (line_start_x + line_end_x) / 2.0,
+            (line_start_y + line_end_y) / 2.0,
+        );
+
+        let (half_gap_x, half_gap_y) = (
+            (line_end_x - line_start_x) / length * gap / 2.0,
+            (line_end_y - line_start_y) / length * gap / 2.0,
+        );
+
+        content.push_str(&format!(
+            "{} m\n{} l\n{} m\n{} l\nS\n",
+            point((line_start_x, line_start_y)),
+            point((middle_x - half_gap_x, middle_y - half_gap_y)),
+            point((middle_x + half_gap_x, middle_y + half_gap_y)),
+            point((line_end_x, line_end_y))
+        ));
+    } else {
+        content.push_str(&format!(
+            "{} m\n{} l\nS\n",
+            point((line_start_x, line_start_y)),
+            point((line_end_x, line_end_y))
+        ));
+    }
+
+    content.push_str("Q\n");
+
+    content
+}
+
+/// Returns a content stream drawing a line annotation between the given points, together
+/// with any leader lines, the given line endings, and the given caption. Closed line endings
+/// are filled with the given fill color, if any. The caption's glyphs are drawn as filled
+/// paths, as for [free_text_appearance()].
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn line_annotation_appearance(
+    start: (PdfPoints, PdfPoints),
+    end: (PdfPoints, PdfPoints),
+    color: PdfColor,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+    leader: PdfLineLeader,
+    (start_ending, end_ending): (PdfAnnotationLineEnding, PdfAnnotationLineEnding),
+    caption: Option<&PdfLineCaption>,
+) -> String {
+    let (line_start, line_end, _) =
+        leader.line_ends((start.0.value, start.1.value), (end.0.value, end.1.value));
+
+    let caption =
+        caption.map(|caption| caption_operators(caption, line_start, line_end, color, line_width));
+
+    let gap = match caption.as_ref() {
+        Some((_, width, true)) => width + 4.0,
+        _ => 0.0,
+    };
+
+    let content = line_appearance(start, end, color, line_width, leader, gap);
+
+    let mut operators = String::new();
+
+    if let Some(fill_color) = fill_color {
+        operators.push_str(&format!("{} rg\n", color_operands(fill_color)));
+    }
+
+    operators.push_str(&line_ending_operators(
+        start_ending,
+        line_start,
+        line_end,
+        line_width.value,
+        fill_color.is_some(),
+    ));
+
+    operators.push_str(&line_ending_operators(
+        end_ending,
+        line_end,
+        line_start,
+        line_width.value,
+        fill_color.is_some(),
+    ));
+
+    if let Some((caption, _, _)) = caption {
+        operators.push_str(&caption);
+    }
+
+    append_operators(content, &operators)
+}
+
+/// Returns the content stream operators drawing the given caption centered on, or above,
+/// the line between the given points, together with the width of the caption and whether
+/// it is drawn inline. The caption is rotated to follow the line, and always reads from
+/// left to right.
+fn caption_operators(
+    caption: &PdfLineCaption,
+    start: (f32, f32),
+    end: (f32, f32),
+    color: PdfColor,
+    line_width: PdfPoints,
+) -> (String, f32, bool) {
+    let size = caption.font_size.value;
+
+    let glyphs = caption
+        .text
+        .chars()
+        .filter_map(|char| {
+            u16::try_from(char as u32).ok().map(|index| {
+                (
+                    char,
+                    PdfFontGlyph::from_pdfium(caption.font, index, caption.bindings),
+                )
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let width = glyphs
+        .iter()
+        .map(|(_, glyph)| glyph.width_at_font_size(caption.font_size).value)
+        .sum::<f32>();
+
+    let length = ((end.0 - start.0).powi(2) + (end.1 - start.1).powi(2)).sqrt();
+
+    if length == 0.0 || glyphs.is_empty() {
+        return (String::new(), 0.0, caption.is_inline);
+    }
+
+    let (mut cos, mut sin) = ((end.0 - start.0) / length, (end.1 - start.1) / length);
+
+    if cos < 0.0 || (cos == 0.0 && sin < 0.0) {
+        cos = -cos;
+        sin = -sin;
+    }
+
+    let mut ascent: c_float = size;
+
+    caption
+        .bindings
+        .FPDFFont_GetAscent(caption.font, size, &mut ascent);
+
+    let baseline = if caption.is_inline {
+        -ascent / 2.0
+    } else {
+        line_width.value / 2.0 + 2.0
+    };
+
+    let n = format_number;
+
+    let mut content = format!(
+        "q\n{} {} {} {} {} {} cm\n{} rg\n",
+        n(cos),
+        n(sin),
+        n(-sin),
+        n(cos),
+        n((start.0 + end.0) / 2.0),
+        n((start.1 + end.1) / 2.0),
+        color_operands(color)
+    );
+
+    let mut x = -width / 2.0;
+
+    for (char, glyph) in glyphs.iter() {
+        if !char.is_whitespace() {
+            if let Ok(path) = glyph.segments_at_font_size(caption.font_size) {
+                content.push_str(&glyph_path_operators(&path, x, baseline, size));
+            }
+        }
+
+        x += glyph.width_at_font_size(caption.font_size).value;
+    }
+
+    content.push_str("f\nQ\n");
+
+    (content, width, caption.is_inline)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::annotation::appearance::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_appearance_streams() {
+        assert_eq!(format_number(1.0), "1");
+        assert_eq!(format_number(0.5), "0.5");
+        assert_eq!(format_number(-0.0001), "0");
+
+        let quad = PdfQuadPoints::from_rect(PdfRect::new_from_values(100.0, 50.0, 114.0, 150.0));
+
+        assert_eq!(
+            text_markup_appearance(&[quad], PdfTextMarkupStyle::Highlight, PdfColor::YELLOW),
+            "q\n1 1 0 rg\n50 114 m\n150 114 l\n150 100 l\n50 100 l\nh\nf\nQ\n"
+        );
+
+        assert_eq!(
+            text_markup_appearance(
+                &[quad],
+                PdfTextMarkupStyle::Strikeout,
+                PdfColor::RED.with_alpha(128)
+            ),
+            "q\n/GS gs\n1 0 0 RG\n1 w\n50 106.3 m\n150 106.3 l\nS\nQ\n"
+        );
+
+        assert_eq!(
+            shape_appearance(
+                PdfRect::new_from_values(0.0, 0.0, 10.0, 20.0),
+                PdfColor::BLACK,
+                Some(PdfColor::WHITE),
+                PdfPoints::new(2.0),
+                false
+            ),
+            "q\n0 0 0 RG\n2 w\n1 1 1 rg\n1 1 18 8 re\nB\nQ\n"
+        );
+
+        assert_eq!(
+            ink_appearance(
+                &[vec![
+                    (PdfPoints::new(1.0), PdfPoints::new(2.0)),
+                    (PdfPoints::new(3.0), PdfPoints::new(4.0))
+                ]],
+                PdfColor::BLUE,
+                PdfPoints::new(1.5)
+            ),
+            "q\n0 0 1 RG\n1.5 w\n1 J\n1 j\n1 2 m\n3 4 l\nS\nQ\n"
+        );
+
+        assert_eq!(
+            line_appearance(
+                (PdfPoints::new(0.0), PdfPoints::new(0.0)),
+                (PdfPoints::new(10.5), PdfPoints::new(20.0)),
+                PdfColor::GREEN,
+                PdfPoints::new(1.0),
+                PdfLineLeader::default(),
+                0.0
+            ),
+            "q\n0 0.502 0 RG\n1 w\n0 0 m\n10.5 20 l\nS\nQ\n"
+        );
+
+        // A horizontal line traversed left to right has clockwise leader lines pointing down.
+
+        assert_eq!(
+            line_appearance(
+                (PdfPoints::new(0.0), PdfPoints::new(50.0)),
+                (PdfPoints::new(100.0), PdfPoints::new(50.0)),
+                PdfColor::BLACK,
+                PdfPoints::new(1.0),
+                PdfLineLeader {
+                    length: 10.0,
+                    extension: 2.0,
+                    offset: 1.0,
+                },
+                0.0
+            ),
+            "q\n0 0 0 RG\n1 w\n0 49 m\n0 37 l\n100 49 m\n100 37 l\n0 39 m\n100 39 l\nS\nQ\n"
+        );
+
+        // An inline caption breaks the line around its midpoint.
+
+        assert_eq!(
+            line_appearance(
+                (PdfPoints::new(0.0), PdfPoints::new(0.0)),
+                (PdfPoints::new(100.0), PdfPoints::new(0.0)),
+                PdfColor::BLACK,
+                PdfPoints::new(1.0),
+                PdfLineLeader::default(),
+                20.0
+            ),
+            "q\n0 0 0 RG\n1 w\n0 0 m\n40 0 l\n60 0 m\n100 0 l\nS\nQ\n"
+        );
+
+        // Line endings are drawn at the ends of the line itself, not at the ends of its
+        // leader lines, and closed line endings are filled with the interior color.
+
+        assert_eq!(
+            line_annotation_appearance(
+                (PdfPoints::new(0.0), PdfPoints::new(50.0)),
+                (PdfPoints::new(20.0), PdfPoints::new(50.0)),
+                PdfColor::BLACK,
+                Some(PdfColor::RED),
+                PdfPoints::new(1.0),
+                PdfLineLeader {
+                    length: 10.0,
+                    extension: 0.0,
+                    offset: 0.0,
+                },
+                (
+                    PdfAnnotationLineEnding::None,
+                    PdfAnnotationLineEnding::ClosedArrow
+                ),
+                None
+            ),
+            "q\n0 0 0 RG\n1 w\n0 50 m\n0 40 l\n20 50 m\n20 40 l\n0 40 m\n20 40 l\nS\n\
+             1 0 0 rg\n14 43 m\n20 40 l\n14 37 l\nb\nQ\n"
+        );
+
+        assert_eq!(
+            wrap_text("aa bb cc\ndddddd", 5.0, |_| 1.0),
+            vec![
+                ("aa bb".to_string(), 5.0),
+                ("cc".to_string(), 2.0),
+                ("ddddd".to_string(), 5.0),
+                ("d".to_string(), 1.0)
+            ]
+        );
+
+        assert_eq!(
+            callout_line(
+                PdfRect::new_from_values(0.0, 0.0, 10.0, 10.0),
+                (20.0, 5.0),
+                PdfColor::BLACK,
+                1.0
+            ),
+            "0 0 0 RG\n1 w\n10 5 m\n20 5 l\n14.804 2 m\n20 5 l\n14.804 8 m\n20 5 l\nS\n"
+        );
+
+        assert_eq!(
+            file_attachment_appearance(
+                PdfRect::new_from_values(0.0, 0.0, 20.0, 40.0),
+                PdfColor::BLACK,
+                PdfFileAttachmentIcon::Paperclip
+            ),
+            "q\n1 0 0 1 10 0 cm\n0 0 0 RG\n0 0 0 rg\n1.5 w\n1 J\n1 j\n9 7 m\n9 15 l\n\
+             9 16.105 9.895 17 11 17 c\n12.105 17 13 16.105 13 15 c\n13 4 l\n\
+             13 2.343 11.657 1 10 1 c\n8.343 1 7 2.343 7 4 c\n7 16 l\nS\nQ\n"
+        );
+
+        let triangle = [
+            (PdfPoints::new(0.0), PdfPoints::new(0.0)),
+            (PdfPoints::new(10.0), PdfPoints::new(0.0)),
+            (PdfPoints::new(5.0), PdfPoints::new(8.0)),
+        ];
+
+        assert_eq!(
+            polygon_appearance(
+                &triangle,
+                PdfColor::BLACK,
+                Some(PdfColor::WHITE),
+                PdfPoints::new(1.0),
+                true
+            ),
+            "q\n0 0 0 RG\n1 w\n1 j\n1 1 1 rg\n0 0 m\n10 0 l\n5 8 l\nb\nQ\n"
+        );
+
+        assert_eq!(
+            polygon_appearance(&triangle, PdfColor::BLACK, None, PdfPoints::new(1.0), false),
+            "q\n0 0 0 RG\n1 w\n1 j\n0 0 m\n10 0 l\n5 8 l\nS\nQ\n"
+        );
+
+        // A closed arrowhead at the end of a horizontal polyline points along the line.
+
+        let polyline = [
+            (PdfPoints::new(0.0), PdfPoints::new(0.0)),
+            (PdfPoints::new(20.0), PdfPoints::new(0.0)),
+        ];
+
+        assert_eq!(
+            open_path_appearance(
+                &polyline,
+                PdfColor::BLACK,
+                None,
+                PdfPoints::new(1.0),
+                (
+                    PdfAnnotationLineEnding::Butt,
+                    PdfAnnotationLineEnding::ClosedArrow
+                )
+            ),
+            "q\n0 0 0 RG\n1 w\n1 j\n0 0 m\n20 0 l\nS\n\
+             0 -3 m\n0 3 l\nS\n14 3 m\n20 0 l\n14 -3 l\ns\nQ\n"
+        );
+
+        assert_eq!(
+            line_ending_operators(
+                PdfAnnotationLineEnding::ReverseOpenArrow,
+                (0.0, 0.0),
+                (0.0, -10.0),
+                2.0,
+                true
+            ),
+            "-6 12 m\n0 0 l\n6 12 l\nS\n"
+        );
+
+        assert_eq!(
+            line_ending_operators(
+                PdfAnnotationLineEnding::Square,
+                (0.0, 0.0),
+                (0.0, 0.0),
+                1.0,
+                false
+            ),
+            ""
+        );
+
+        let bounds = PdfRect::new_from_values(0.0, 0.0, 10.0, 20.0);
+
+        assert_eq!(
+            styled_rect_appearance(
+                bounds,
+                PdfColor::BLACK,
+                None,
+                PdfPoints::new(1.0),
+                &PdfAnnotationBorderStyle::Underline
+            ),
+            "q\n0 0 0 RG\n1 w\n0 0.5 m\n20 0.5 l\nS\nQ\n"
+        );
+
+        assert_eq!(
+            styled_rect_appearance(
+                bounds,
+                PdfColor::BLACK,
+                None,
+                PdfPoints::new(1.0),
+                &PdfAnnotationBorderStyle::Inset
+            ),
+            "q\n0 0 0 RG\n1 w\n0.5 0.5 19 9 re\nS\n\
+             0.5 g\n1 1 m\n1 9 l\n19 9 l\n18 8 l\n2 8 l\n2 2 l\nh\nf\n\
+             0.75 g\n19 9 m\n19 1 l\n1 1 l\n2 2 l\n18 2 l\n18 8 l\nh\nf\nQ\n"
+        );
+
+        assert_eq!(
+            redact_appearance(
+                &[bounds, PdfRect::new_from_values(30.0, 0.0, 40.0, 50.0)],
+                PdfColor::RED
+            ),
+            "q\n1 0 0 RG\n1 w\n0.5 0.5 19 9 re\n0.5 30.5 49 9 re\nS\nQ\n"
+        );
+    }
+
+    #[test]
+    fn test_generate_appearance_stream() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let mut square = page.annotations_mut().create_square_annotation()?;
+
+        square.set_bounds(PdfRect::new_from_values(100.0, 100.0, 150.0, 300.0))?;
+        square.set_stroke_color(PdfColor::BLUE)?;
+
+        assert!(!has_normal_appearance(&square));
+        assert!(square.generate_appearance_stream()?);
+        assert!(has_normal_appearance(&square));
+
+        // Regenerating the appearance stream replaces the existing stream.
+
+        assert!(square.generate_appearance_stream()?);
+
+        let mut text = page.annotations_mut().create_text_annotation("Note")?;
+
+        assert!(!text.generate_appearance_stream()?);
+
+        assert_eq!(
+            page.annotations_mut()
+                .generate_missing_appearance_streams()?,
+            0
+        );
+
+        Ok(())
+    }
+    #[test]
+    fn test_failed_generation_keeps_appearance_stream() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let index = PdfLineAnnotationBuilder::new()
+            .start(PdfPoints::new(100.0), PdfPoints::new(100.0))
+            .end(PdfPoints::new(200.0), PdfPoints::new(100.0))
+            .create(&mut document, 0)?;
+
+        let mut page = document.pages().get(0)?;
+
+        let mut line = page.annotations_mut().get(index)?;
+
+        let previous = normal_appearance_content(&line);
+
+        assert!(previous.is_some());
+
+        // A line annotation whose end points cannot be read cannot be drawn, so its existing
+        // appearance stream is left unchanged.
+
+        PdfAnnotationDictionaryEdits::set(&mut line, "L", None)?;
+
+        assert!(generate_normal_appearance(&line).is_err());
+        assert_eq!(normal_appearance_content(&line), previous);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,198 @@
+//! Appends annotations of types Pdfium cannot create, such as polygon and polyline
+//! annotations, to a saved copy of a document.
+
+use crate::error::PdfiumError;
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::form::builder::{append_to_array, number};
+use crate::pdf::document::page::annotations::PdfPageAnnotationIndex;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use crate::pdf::rect::PdfRect;
+
+/// Appends an incremental update to the given file, which must contain the given number of
+/// pages, adding the given annotation dictionary to the annotations of the page at the given
+/// index. The annotation is given a normal appearance stream with the given content, drawn
+/// in page coordinates within the given bounds, which must be the annotation's `/Rect`.
+/// The stream's `/GS` graphics state applies the alpha channel of the given color.
+///
+/// Returns the updated file, together with the index of the new annotation in the page's
+/// annotations.
+pub(crate) fn append_annotation(
+    file: Vec<u8>,
+    page_index: PdfPageIndex,
+    page_count: PdfPageIndex,
+    mut annotation: PdfSyntaxObject,
+    bounds: PdfRect,
+    color: PdfColor,
+    appearance: &str,
+) -> Result<(Vec<u8>, PdfPageAnnotationIndex), PdfiumError> {
+    if page_index >= page_count {
+        return Err(PdfiumError::PageIndexOutOfBounds);
+    }
+
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if update.trailer().get("Encrypt").is_some() {
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    let pages = parsed
+        .page_references()
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if pages.len() != page_count as usize {
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    let page = &pages[page_index as usize];
+
+    let page_number = match page {
+        PdfSyntaxObject::Reference(number, _) => *number,
+        _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+    };
+
+    let mut resources = PdfSyntaxObject::Dictionary(Vec::new());
+
+    if color.alpha() < 255 {
+        let alpha = number(color.alpha() as f32 / 255.0);
+
+        resources.set(
+            "ExtGState",
+            PdfSyntaxObject::Dictionary(vec![(
+                "GS".to_string(),
+                PdfSyntaxObject::Dictionary(vec![
+                    ("CA".to_string(), alpha.clone()),
+                    ("ca".to_string(), alpha),
+                ]),
+            )]),
+        );
+    }
+
+    let appearance = update.add_stream(
+        PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("XObject")),
+            ("Subtype".to_string(), PdfSyntaxObject::name("Form")),
+            (
+                "BBox".to_string(),
+                PdfSyntaxObject::Array(vec![
+                    number(bounds.left.value),
+                    number(bounds.bottom.value),
+                    number(bounds.right.value),
+                    number(bounds.top.value),
+                ]),
+            ),
+            ("Resources".to_string(), resources),
+        ]),
+        appearance.as_bytes().to_vec(),
+    );
+
+    annotation.set("P", page.clone());
+    annotation.set(
+        "AP",
+        PdfSyntaxObject::Dictionary(vec![("N".to_string(), appearance)]),
+    );
+
+    let annotation = update.add(annotation);
+
+    let mut index = 0;
+
+    update
+        .modify(page_number, &["Annots"], &mut |annotations| {
+            append_to_array(annotations, std::slice::from_ref(&annotation))?;
+
+            if let PdfSyntaxObject::Array(annotations) = annotations {
+                index = annotations.len() - 1;
+            }
+
+            Some(())
+        })
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    Ok((update.write(file), index))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::annotation::append::*;
+
+    const MINIMAL_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /Annots [4 0 R] >>\nendobj\n\
+        4 0 obj\n<< /Type /Annot /Subtype /Square /Rect [0 0 10 10] >>\nendobj\n\
+        xref\n0 5\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 5 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    #[test]
+    fn test_append_annotation() {
+        let annotation = PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("Annot")),
+            ("Subtype".to_string(), PdfSyntaxObject::name("Polygon")),
+        ]);
+
+        let bounds = PdfRect::new_from_values(0.0, 0.0, 20.0, 10.0);
+
+        assert!(matches!(
+            append_annotation(
+                MINIMAL_PDF.to_vec(),
+                1,
+                1,
+                annotation.clone(),
+                bounds,
+                PdfColor::RED,
+                "q\nQ\n"
+            ),
+            Err(PdfiumError::PageIndexOutOfBounds)
+        ));
+
+        let (file, index) = append_annotation(
+            MINIMAL_PDF.to_vec(),
+            0,
+            1,
+            annotation,
+            bounds,
+            PdfColor::RED.with_alpha(128),
+            "q\n/GS gs\nQ\n",
+        )
+        .unwrap();
+
+        assert_eq!(index, 1);
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let page = parsed.resolve(&PdfSyntaxObject::Reference(3, 0));
+
+        let annotations = match page.get("Annots") {
+            Some(PdfSyntaxObject::Array(annotations)) => annotations.clone(),
+            _ => panic!("page has no annotations"),
+        };
+
+        assert_eq!(annotations.len(), 2);
+
+        let polygon = parsed.resolve(&annotations[1]);
+
+        assert_eq!(
+            polygon.get("Subtype"),
+            Some(&PdfSyntaxObject::name("Polygon"))
+        );
+        assert_eq!(polygon.get("P"), Some(&PdfSyntaxObject::Reference(3, 0)));
+
+        let appearance = match polygon.get("AP").and_then(|ap| ap.get("N")) {
+            Some(PdfSyntaxObject::Reference(number, _)) => *number,
+            _ => panic!("annotation has no normal appearance stream"),
+        };
+
+        let stream = &parsed.objects[&appearance];
+
+        assert!(stream.is_stream);
+        assert!(stream
+            .object
+            .get("Resources")
+            .and_then(|resources| resources.get("ExtGState"))
+            .and_then(|states| states.get("GS"))
+            .is_some());
+    }
+}
@@ -2,9 +2,10 @@
 //! attachment points that visually associate a `PdfPageAnnotation` object with one or more
 //! `PdfPageObject` objects on a `PdfPage`.
 
-use crate::bindgen::FPDF_ANNOTATION;
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::page::generation::PdfContentGenerations;
 use crate::pdf::quad_points::PdfQuadPoints;
 use crate::pdf::rect::PdfRect;
 use std::ops::{Range, RangeInclusive};
@@ -16,6 +17,7 @@ pub type PdfPageAnnotationAttachmentPointIndex = usize;
 /// A set of all the attachment points that visually connect a `PdfPageAnnotation` object
 /// to one or more `PdfPageObject` objects on a `PdfPage`.
 pub struct PdfPageAnnotationAttachmentPoints<'a> {
+    page_handle: FPDF_PAGE,
     annotation_handle: FPDF_ANNOTATION,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
@@ -23,10 +25,12 @@ pub struct PdfPageAnnotationAttachmentPoints<'a> {
 impl<'a> PdfPageAnnotationAttachmentPoints<'a> {
     #[inline]
     pub(crate) fn from_pdfium(
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
         PdfPageAnnotationAttachmentPoints {
+            page_handle,
             annotation_handle,
             bindings,
         }
@@ -136,6 +140,8 @@ impl<'a> PdfPageAnnotationAttachmentPoints<'a> {
                 &attachment_point.as_pdfium(),
             ))
         {
+            PdfContentGenerations::increment_for_page_handle(self.page_handle);
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -159,6 +165,8 @@ impl<'a> PdfPageAnnotationAttachmentPoints<'a> {
                 &attachment_point.as_pdfium(),
             ))
         {
+            PdfContentGenerations::increment_for_page_handle(self.page_handle);
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
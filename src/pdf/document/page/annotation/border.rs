@@ -0,0 +1,185 @@
+//! Defines the [PdfAnnotationBorderStyle] enum, the style in which the border of a
+//! `PdfPageAnnotation` is drawn.
+
+use crate::pdf::document::form::builder::number;
+use crate::pdf::document::page::annotation::appearance::format_number;
+use crate::pdf::document::syntax::PdfSyntaxObject;
+use crate::pdf::points::PdfPoints;
+
+/// The style in which the border of a `PdfPageAnnotation` is drawn, as described in
+/// section 8.4.3 of The PDF Reference Manual, version 1.7, on page 611.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PdfAnnotationBorderStyle {
+    /// A solid line.
+    Solid,
+
+    /// A dashed line, alternating between dashes and gaps of the given lengths. An empty
+    /// dash pattern draws dashes and gaps of 3 points each.
+    Dashed(Vec<PdfPoints>),
+
+    /// An embossed rectangle that appears to be raised above the surface of the page.
+    Beveled,
+
+    /// An engraved rectangle that appears to be recessed below the surface of the page.
+    Inset,
+
+    /// A single line along the bottom of the annotation.
+    Underline,
+}
+
+impl PdfAnnotationBorderStyle {
+    /// Returns the content stream operator that sets the dash pattern of this
+    /// [PdfAnnotationBorderStyle], if it is dashed.
+    pub(crate) fn dash_operator(&self) -> Option<String> {
+        match self {
+            PdfAnnotationBorderStyle::Dashed(pattern) => {
+                let pattern = if pattern.is_empty() {
+                    String::from("3")
+                } else {
+                    pattern
+                        .iter()
+                        .map(|length| format_number(length.value))
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+
+                Some(format!("[{}] 0 d\n", pattern))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the PDF name identifying this [PdfAnnotationBorderStyle] in the `/S` entry
+    /// of a border style dictionary.
+    pub(crate) fn as_pdf_name(&self) -> &'static str {
+        match self {
+            PdfAnnotationBorderStyle::Solid => "S",
+            PdfAnnotationBorderStyle::Dashed(_) => "D",
+            PdfAnnotationBorderStyle::Beveled => "B",
+            PdfAnnotationBorderStyle::Inset => "I",
+            PdfAnnotationBorderStyle::Underline => "U",
+        }
+    }
+
+    /// Returns a `/BS` border style dictionary describing a border of the given width
+    /// drawn in this [PdfAnnotationBorderStyle].
+    pub(crate) fn to_dictionary(&self, width: PdfPoints) -> PdfSyntaxObject {
+        let mut dictionary = PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("Border")),
+            ("W".to_string(), number(width.value)),
+            ("S".to_string(), PdfSyntaxObject::name(self.as_pdf_name())),
+        ]);
+
+        if let PdfAnnotationBorderStyle::Dashed(pattern) = self {
+            if !pattern.is_empty() {
+                dictionary.set(
+                    "D",
+                    PdfSyntaxObject::Array(
+                        pattern.iter().map(|length| number(length.value)).collect(),
+                    ),
+                );
+            }
+        }
+
+        dictionary
+    }
+
+    /// Returns the [PdfAnnotationBorderStyle] described by the given `/BS` border style
+    /// dictionary. Unrecognized or missing styles are treated as
+    /// [PdfAnnotationBorderStyle::Solid], as the PDF specification requires.
+    pub(crate) fn from_dictionary(dictionary: &PdfSyntaxObject) -> Self {
+        match dictionary.get("S") {
+            Some(PdfSyntaxObject::Name(name)) => match name.as_str() {
+                "D" => PdfAnnotationBorderStyle::Dashed(match dictionary.get("D") {
+                    Some(PdfSyntaxObject::Array(pattern)) => pattern
+                        .iter()
+                        .filter_map(|length| match length {
+                            PdfSyntaxObject::Number(length) => Some(PdfPoints::new(*length as f32)),
+                            _ => None,
+                        })
+                        .collect(),
+                    _ => Vec::new(),
+                }),
+                "B" => PdfAnnotationBorderStyle::Beveled,
+                "I" => PdfAnnotationBorderStyle::Inset,
+                "U" => PdfAnnotationBorderStyle::Underline,
+                _ => PdfAnnotationBorderStyle::Solid,
+            },
+            _ => PdfAnnotationBorderStyle::Solid,
+        }
+    }
+}
+
+impl Default for PdfAnnotationBorderStyle {
+    #[inline]
+    fn default() -> Self {
+        PdfAnnotationBorderStyle::Solid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::annotation::border::*;
+
+    #[test]
+    fn test_dash_operator() {
+        assert_eq!(PdfAnnotationBorderStyle::Solid.dash_operator(), None);
+
+        assert_eq!(
+            PdfAnnotationBorderStyle::Dashed(vec![]).dash_operator(),
+            Some("[3] 0 d\n".to_string())
+        );
+
+        assert_eq!(
+            PdfAnnotationBorderStyle::Dashed(vec![PdfPoints::new(4.0), PdfPoints::new(1.5)])
+                .dash_operator(),
+            Some("[4 1.5] 0 d\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_border_style_dictionary() {
+        let mut output = Vec::new();
+
+        PdfAnnotationBorderStyle::Dashed(vec![PdfPoints::new(4.0), PdfPoints::new(1.5)])
+            .to_dictionary(PdfPoints::new(2.0))
+            .write_to(&mut output);
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "<< /Type /Border /W 2 /S /D /D [4 1.5] >>"
+        );
+
+        let mut output = Vec::new();
+
+        PdfAnnotationBorderStyle::Beveled
+            .to_dictionary(PdfPoints::new(1.0))
+            .write_to(&mut output);
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "<< /Type /Border /W 1 /S /B >>"
+        );
+
+        for style in [
+            PdfAnnotationBorderStyle::Solid,
+            PdfAnnotationBorderStyle::Dashed(vec![]),
+            PdfAnnotationBorderStyle::Dashed(vec![PdfPoints::new(4.0), PdfPoints::new(1.5)]),
+            PdfAnnotationBorderStyle::Beveled,
+            PdfAnnotationBorderStyle::Inset,
+            PdfAnnotationBorderStyle::Underline,
+        ] {
+            assert_eq!(
+                PdfAnnotationBorderStyle::from_dictionary(
+                    &style.to_dictionary(PdfPoints::new(1.0))
+                ),
+                style
+            );
+        }
+
+        assert_eq!(
+            PdfAnnotationBorderStyle::from_dictionary(&PdfSyntaxObject::Dictionary(vec![])),
+            PdfAnnotationBorderStyle::Solid
+        );
+    }
+}
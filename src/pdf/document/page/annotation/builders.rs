@@ -0,0 +1,2609 @@
+//! Defines builders for creating fully configured annotations in a single fluent call,
+//! such as the [PdfHighlightAnnotationBuilder], [PdfInkAnnotationBuilder], and
+//! [PdfSquareAnnotationBuilder] structs.
+//!
+//! Each builder sets the bounds, color, opacity, contents, author, and modification date
+//! of the new annotation, along with any properties specific to its subtype, and generates
+//! an appearance stream so that the annotation displays consistently in all PDF viewers.
+
+use crate::bindgen::{FPDF_ANNOT_FLAG_PRINT, FS_POINTF};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::appearance_mode::PdfAppearanceMode;
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::fonts::{PdfFontToken, ToPdfFontToken};
+use crate::pdf::document::form::builder::number;
+use crate::pdf::document::page::annotation::appearance::{
+    color_operands, file_attachment_appearance, format_number, free_text_appearance,
+    generate_normal_appearance, line_annotation_appearance, line_ending_margin,
+    open_path_appearance, polygon_appearance, redact_appearance, PdfFreeTextLayout, PdfLineCaption,
+    PdfLineLeader,
+};
+use crate::pdf::document::page::annotation::border::PdfAnnotationBorderStyle;
+use crate::pdf::document::page::annotation::circle::PdfPageCircleAnnotation;
+use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
+use crate::pdf::document::page::annotation::file_attachment::PdfPageFileAttachmentAnnotation;
+use crate::pdf::document::page::annotation::free_text::PdfPageFreeTextAnnotation;
+use crate::pdf::document::page::annotation::highlight::PdfPageHighlightAnnotation;
+use crate::pdf::document::page::annotation::ink::PdfPageInkAnnotation;
+use crate::pdf::document::page::annotation::line_ending::PdfAnnotationLineEnding;
+use crate::pdf::document::page::annotation::measure::PdfLineMeasure;
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::square::PdfPageSquareAnnotation;
+use crate::pdf::document::page::annotation::squiggly::PdfPageSquigglyAnnotation;
+use crate::pdf::document::page::annotation::stamp::PdfPageStampAnnotation;
+use crate::pdf::document::page::annotation::strikeout::PdfPageStrikeoutAnnotation;
+use crate::pdf::document::page::annotation::underline::PdfPageUnderlineAnnotation;
+use crate::pdf::document::page::annotation::PdfPageAnnotationCommon;
+use crate::pdf::document::page::annotations::{PdfPageAnnotationIndex, PdfPageAnnotations};
+use crate::pdf::document::page::object::text::PdfPageTextObject;
+use crate::pdf::document::page::object::PdfPageObjectCommon;
+use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::PdfSyntaxObject;
+use crate::pdf::document::PdfDocument;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::quad_points::PdfQuadPoints;
+use crate::pdf::rect::PdfRect;
+use crate::utils::dates::date_time_to_pdf_string;
+use chrono::{DateTime, Utc};
+
+#[cfg(feature = "image")]
+use image::DynamicImage;
+
+// Creates the setters shared by every annotation builder. The containing struct must have
+// a field named common of type PdfAnnotationBuilderCommon.
+macro_rules! create_annotation_builder_setters {
+    () => {
+        /// Sets the bounding box of the new annotation on the page.
+        #[inline]
+        pub fn bounds(mut self, bounds: PdfRect) -> Self {
+            self.common.bounds = Some(bounds);
+
+            self
+        }
+
+        /// Sets the color of the new annotation.
+        #[inline]
+        pub fn color(mut self, color: PdfColor) -> Self {
+            self.common.color = Some(color);
+
+            self
+        }
+
+        /// Sets the opacity of the new annotation, from 0.0 (fully transparent)
+        /// to 1.0 (fully opaque). If no opacity is set, the alpha channel of the
+        /// annotation's color is used.
+        #[inline]
+        pub fn opacity(mut self, opacity: f32) -> Self {
+            self.common.opacity = Some(opacity);
+
+            self
+        }
+
+        /// Sets the text displayed by a conforming PDF viewer for the new annotation,
+        /// usually in a popup window when the annotation is clicked.
+        #[inline]
+        pub fn contents(mut self, contents: &str) -> Self {
+            self.common.contents = Some(contents.to_string());
+
+            self
+        }
+
+        /// Sets the name of the author of the new annotation.
+        #[inline]
+        pub fn author(mut self, author: &str) -> Self {
+            self.common.author = Some(author.to_string());
+
+            self
+        }
+
+        /// Sets the date and time when the new annotation was last modified.
+        /// If no date is set, the time at which the annotation is created is used.
+        #[inline]
+        pub fn modification_date(mut self, date: DateTime<Utc>) -> Self {
+            self.common.modification_date = Some(date);
+
+            self
+        }
+    };
+}
+
+// Creates the setters shared by every text markup annotation builder. The containing struct
+// must have a field named quad_points of type Vec<PdfQuadPoints>.
+macro_rules! create_text_markup_builder_setters {
+    () => {
+        /// Adds the given [PdfQuadPoints] to the areas of the page marked up by the new
+        /// annotation. Call this function once for each line of marked up text.
+        #[inline]
+        pub fn add_quad_points(mut self, quad_points: PdfQuadPoints) -> Self {
+            self.quad_points.push(quad_points);
+
+            self
+        }
+
+        /// Adds the given [PdfRect] to the areas of the page marked up by the new annotation.
+        /// Call this function once for each line of marked up text.
+        #[inline]
+        pub fn add_rect(self, rect: PdfRect) -> Self {
+            self.add_quad_points(PdfQuadPoints::from_rect(rect))
+        }
+    };
+}
+
+/// The properties shared by every annotation builder.
+#[derive(Debug, Clone, Default)]
+struct PdfAnnotationBuilderCommon {
+    bounds: Option<PdfRect>,
+    color: Option<PdfColor>,
+    opacity: Option<f32>,
+    contents: Option<String>,
+    author: Option<String>,
+    modification_date: Option<DateTime<Utc>>,
+}
+
+impl PdfAnnotationBuilderCommon {
+    /// Returns the configured color, or the given default color if no color was configured,
+    /// with its alpha channel set to the configured opacity.
+    fn color_or(&self, default: PdfColor) -> PdfColor {
+        let color = self.color.unwrap_or(default);
+
+        match self.opacity {
+            Some(opacity) => color.with_alpha((opacity.clamp(0.0, 1.0) * 255.0).round() as u8),
+            None => color,
+        }
+    }
+
+    /// Returns the configured bounds, or an error if no bounds were configured.
+    fn required_bounds(&self) -> Result<PdfRect, PdfiumError> {
+        self.bounds.ok_or(PdfiumError::NoBoundsForAnnotation)
+    }
+
+    /// Positions and colors the given newly created annotation. The annotation's color
+    /// must be set before an appearance stream is added, since Pdfium does not allow
+    /// changing the color of an annotation that has an appearance stream.
+    fn apply_bounds_and_color<'a>(
+        &self,
+        annotation: &mut impl PdfPageAnnotationPrivate<'a>,
+        bounds: PdfRect,
+        color: PdfColor,
+    ) -> Result<(), PdfiumError> {
+        annotation.set_bounds(bounds)?;
+        annotation.set_stroke_color(color)
+    }
+
+    /// Sets the contents, author, and modification date of the given annotation. The
+    /// modification date is set last, since changing any other property of an annotation
+    /// resets its modification date to the current time.
+    fn apply_metadata<'a>(
+        &self,
+        annotation: &mut impl PdfPageAnnotationPrivate<'a>,
+    ) -> Result<(), PdfiumError> {
+        if let Some(contents) = self.contents.as_ref() {
+            annotation.set_contents(contents)?;
+        }
+
+        if let Some(author) = self.author.as_ref() {
+            annotation.set_creator(author)?;
+        }
+
+        if let Some(date) = self.modification_date {
+            annotation.set_modification_date(date)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Sets the border width of the given annotation.
+fn set_border_width<'a>(
+    annotation: &impl PdfPageAnnotationPrivate<'a>,
+    width: PdfPoints,
+) -> Result<(), PdfiumError> {
+    if annotation
+        .bindings()
+        .is_true(annotation.bindings().FPDFAnnot_SetBorder(
+            annotation.handle(),
+            0.0,
+            0.0,
+            width.value,
+        ))
+    {
+        Ok(())
+    } else {
+        Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ))
+    }
+}
+
+/// Sets the normal appearance stream of the given annotation to the given content stream.
+fn set_normal_appearance<'a>(
+    annotation: &impl PdfPageAnnotationPrivate<'a>,
+    content: &str,
+) -> Result<(), PdfiumError> {
+    if annotation
+        .bindings()
+        .is_true(annotation.bindings().FPDFAnnot_SetAP_str(
+            annotation.handle(),
+            PdfAppearanceMode::Normal.as_pdfium(),
+            content,
+        ))
+    {
+        Ok(())
+    } else {
+        Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ))
+    }
+}
+
+/// Creates a text markup annotation, marking up the given areas.
+fn create_text_markup_annotation<'a, T: PdfPageAnnotationPrivate<'a>>(
+    common: &PdfAnnotationBuilderCommon,
+    quad_points: &[PdfQuadPoints],
+    default_color: PdfColor,
+    create: impl FnOnce() -> Result<T, PdfiumError>,
+) -> Result<T, PdfiumError> {
+    let quad_points = if quad_points.is_empty() {
+        vec![PdfQuadPoints::from_rect(common.required_bounds()?)]
+    } else {
+        quad_points.to_vec()
+    };
+
+    let bounds = match common.bounds {
+        Some(bounds) => bounds,
+        None => bounds_of_points(
+            quad_points
+                .iter()
+                .flat_map(|quad| {
+                    [
+                        (quad.x1, quad.y1),
+                        (quad.x2, quad.y2),
+                        (quad.x3, quad.y3),
+                        (quad.x4, quad.y4),
+                    ]
+                })
+                .collect::<Vec<_>>()
+                .as_slice(),
+            PdfPoints::ZERO,
+        )?,
+    };
+
+    let color = common.color_or(default_color);
+
+    let mut annotation = create()?;
+
+    common.apply_bounds_and_color(&mut annotation, bounds, color)?;
+
+    for quad in quad_points.iter() {
+        annotation
+            .attachment_points_mut_impl()
+            .create_attachment_point_at_end(*quad)?;
+    }
+
+    generate_normal_appearance(&annotation)?;
+
+    common.apply_metadata(&mut annotation)?;
+
+    Ok(annotation)
+}
+
+/// Creates a fully configured [PdfPageHighlightAnnotation], marking up areas of the page
+/// with a translucent background color.
+///
+/// Add the areas to highlight using [PdfHighlightAnnotationBuilder::add_quad_points()] or
+/// [PdfHighlightAnnotationBuilder::add_rect()]. If no areas are added, the annotation's
+/// bounds are highlighted. The default color is yellow.
+#[derive(Debug, Clone, Default)]
+pub struct PdfHighlightAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    quad_points: Vec<PdfQuadPoints>,
+}
+
+impl PdfHighlightAnnotationBuilder {
+    /// Creates a new [PdfHighlightAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    create_annotation_builder_setters!();
+
+    create_text_markup_builder_setters!();
+
+    /// Creates a new [PdfPageHighlightAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageHighlightAnnotation<'a>, PdfiumError> {
+        create_text_markup_annotation(&self.common, &self.quad_points, PdfColor::YELLOW, || {
+            annotations.create_highlight_annotation()
+        })
+    }
+}
+
+/// Creates a fully configured [PdfPageUnderlineAnnotation], underlining areas of the page.
+///
+/// Add the areas to underline using [PdfUnderlineAnnotationBuilder::add_quad_points()] or
+/// [PdfUnderlineAnnotationBuilder::add_rect()]. If no areas are added, the annotation's
+/// bounds are underlined. The default color is blue.
+#[derive(Debug, Clone, Default)]
+pub struct PdfUnderlineAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    quad_points: Vec<PdfQuadPoints>,
+}
+
+impl PdfUnderlineAnnotationBuilder {
+    /// Creates a new [PdfUnderlineAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    create_annotation_builder_setters!();
+
+    create_text_markup_builder_setters!();
+
+    /// Creates a new [PdfPageUnderlineAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageUnderlineAnnotation<'a>, PdfiumError> {
+        create_text_markup_annotation(&self.common, &self.quad_points, PdfColor::BLUE, || {
+            annotations.create_underline_annotation()
+        })
+    }
+}
+
+/// Creates a fully configured [PdfPageStrikeoutAnnotation], striking through areas of the page.
+///
+/// Add the areas to strike through using [PdfStrikeoutAnnotationBuilder::add_quad_points()] or
+/// [PdfStrikeoutAnnotationBuilder::add_rect()]. If no areas are added, the annotation's
+/// bounds are struck through. The default color is red.
+#[derive(Debug, Clone, Default)]
+pub struct PdfStrikeoutAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    quad_points: Vec<PdfQuadPoints>,
+}
+
+impl PdfStrikeoutAnnotationBuilder {
+    /// Creates a new [PdfStrikeoutAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    create_annotation_builder_setters!();
+
+    create_text_markup_builder_setters!();
+
+    /// Creates a new [PdfPageStrikeoutAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageStrikeoutAnnotation<'a>, PdfiumError> {
+        create_text_markup_annotation(&self.common, &self.quad_points, PdfColor::RED, || {
+            annotations.create_strikeout_annotation()
+        })
+    }
+}
+
+/// Creates a fully configured [PdfPageSquigglyAnnotation], drawing a wavy line underneath
+/// areas of the page.
+///
+/// Add the areas to mark up using [PdfSquigglyAnnotationBuilder::add_quad_points()] or
+/// [PdfSquigglyAnnotationBuilder::add_rect()]. If no areas are added, the annotation's
+/// bounds are marked up. The default color is red.
+#[derive(Debug, Clone, Default)]
+pub struct PdfSquigglyAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    quad_points: Vec<PdfQuadPoints>,
+}
+
+impl PdfSquigglyAnnotationBuilder {
+    /// Creates a new [PdfSquigglyAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    create_annotation_builder_setters!();
+
+    create_text_markup_builder_setters!();
+
+    /// Creates a new [PdfPageSquigglyAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageSquigglyAnnotation<'a>, PdfiumError> {
+        create_text_markup_annotation(&self.common, &self.quad_points, PdfColor::RED, || {
+            annotations.create_squiggly_annotation()
+        })
+    }
+}
+
+/// Creates a fully configured [PdfPageInkAnnotation], drawing freehand strokes on the page.
+///
+/// Add each stroke using [PdfInkAnnotationBuilder::add_stroke()]. If no bounds are set,
+/// the annotation's bounds enclose all its strokes. The default color is black and the
+/// default line width is 1 point.
+#[derive(Debug, Clone)]
+pub struct PdfInkAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    strokes: Vec<Vec<(PdfPoints, PdfPoints)>>,
+    line_width: PdfPoints,
+}
+
+impl PdfInkAnnotationBuilder {
+    /// Creates a new [PdfInkAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        PdfInkAnnotationBuilder {
+            common: PdfAnnotationBuilderCommon::default(),
+            strokes: Vec::new(),
+            line_width: PdfPoints::new(1.0),
+        }
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Adds a stroke passing through the given points, expressed as (x, y) page coordinates,
+    /// to the new annotation.
+    #[inline]
+    pub fn add_stroke(mut self, points: &[(PdfPoints, PdfPoints)]) -> Self {
+        self.strokes.push(points.to_vec());
+
+        self
+    }
+
+    /// Sets the width of the strokes drawn by the new annotation.
+    #[inline]
+    pub fn line_width(mut self, width: PdfPoints) -> Self {
+        self.line_width = width;
+
+        self
+    }
+
+    /// Creates a new [PdfPageInkAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageInkAnnotation<'a>, PdfiumError> {
+        let bounds = match self.common.bounds {
+            Some(bounds) => bounds,
+            None => bounds_of_points(self.strokes.concat().as_slice(), self.line_width / 2.0)?,
+        };
+
+        let color = self.common.color_or(PdfColor::BLACK);
+
+        let mut annotation = annotations.create_ink_annotation()?;
+
+        self.common
+            .apply_bounds_and_color(&mut annotation, bounds, color)?;
+
+        set_border_width(&annotation, self.line_width)?;
+
+        for stroke in self.strokes.iter().filter(|stroke| !stroke.is_empty()) {
+            let points = stroke
+                .iter()
+                .map(|(x, y)| FS_POINTF {
+                    x: x.value,
+                    y: y.value,
+                })
+                .collect::<Vec<_>>();
+
+            if annotation.bindings().FPDFAnnot_AddInkStroke(
+                annotation.handle(),
+                points.as_ptr(),
+                points.len(),
+            ) < 0
+            {
+                return Err(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ));
+            }
+        }
+
+        generate_normal_appearance(&annotation)?;
+
+        self.common.apply_metadata(&mut annotation)?;
+
+        Ok(annotation)
+    }
+}
+
+impl Default for PdfInkAnnotationBuilder {
+    #[inline]
+    fn default() -> Self {
+        PdfInkAnnotationBuilder::new()
+    }
+}
+
+/// Creates a fully configured [PdfPageSquareAnnotation], drawing a rectangle on the page.
+///
+/// The rectangle fills the annotation's bounds, which must be set. The default color is red,
+/// the default line width is 1 point, and the rectangle is not filled unless a fill color is set.
+#[derive(Debug, Clone)]
+pub struct PdfSquareAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+}
+
+impl PdfSquareAnnotationBuilder {
+    /// Creates a new [PdfSquareAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        PdfSquareAnnotationBuilder {
+            common: PdfAnnotationBuilderCommon::default(),
+            fill_color: None,
+            line_width: PdfPoints::new(1.0),
+        }
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Sets the color used to fill the rectangle drawn by the new annotation.
+    #[inline]
+    pub fn fill_color(mut self, color: PdfColor) -> Self {
+        self.fill_color = Some(color);
+
+        self
+    }
+
+    /// Sets the width of the border of the rectangle drawn by the new annotation.
+    #[inline]
+    pub fn line_width(mut self, width: PdfPoints) -> Self {
+        self.line_width = width;
+
+        self
+    }
+
+    /// Creates a new [PdfPageSquareAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageSquareAnnotation<'a>, PdfiumError> {
+        create_shape_annotation(&self.common, self.fill_color, self.line_width, || {
+            annotations.create_square_annotation()
+        })
+    }
+}
+
+impl Default for PdfSquareAnnotationBuilder {
+    #[inline]
+    fn default() -> Self {
+        PdfSquareAnnotationBuilder::new()
+    }
+}
+
+/// Creates a fully configured [PdfPageCircleAnnotation], drawing an ellipse on the page.
+///
+/// The ellipse fills the annotation's bounds, which must be set. The default color is red,
+/// the default line width is 1 point, and the ellipse is not filled unless a fill color is set.
+#[derive(Debug, Clone)]
+pub struct PdfCircleAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+}
+
+impl PdfCircleAnnotationBuilder {
+    /// Creates a new [PdfCircleAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        PdfCircleAnnotationBuilder {
+            common: PdfAnnotationBuilderCommon::default(),
+            fill_color: None,
+            line_width: PdfPoints::new(1.0),
+        }
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Sets the color used to fill the ellipse drawn by the new annotation.
+    #[inline]
+    pub fn fill_color(mut self, color: PdfColor) -> Self {
+        self.fill_color = Some(color);
+
+        self
+    }
+
+    /// Sets the width of the border of the ellipse drawn by the new annotation.
+    #[inline]
+    pub fn line_width(mut self, width: PdfPoints) -> Self {
+        self.line_width = width;
+
+        self
+    }
+
+    /// Creates a new [PdfPageCircleAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageCircleAnnotation<'a>, PdfiumError> {
+        create_shape_annotation(&self.common, self.fill_color, self.line_width, || {
+            annotations.create_circle_annotation()
+        })
+    }
+}
+
+impl Default for PdfCircleAnnotationBuilder {
+    #[inline]
+    fn default() -> Self {
+        PdfCircleAnnotationBuilder::new()
+    }
+}
+
+/// Creates a square or circle annotation filling its bounds.
+fn create_shape_annotation<'a, T: PdfPageAnnotationPrivate<'a>>(
+    common: &PdfAnnotationBuilderCommon,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+    create: impl FnOnce() -> Result<T, PdfiumError>,
+) -> Result<T, PdfiumError> {
+    let bounds = common.required_bounds()?;
+
+    let color = common.color_or(PdfColor::RED);
+
+    let fill_color = fill_color.map(|fill_color| fill_color.with_alpha(color.alpha()));
+
+    let mut annotation = create()?;
+
+    common.apply_bounds_and_color(&mut annotation, bounds, color)?;
+
+    if let Some(fill_color) = fill_color {
+        annotation.set_fill_color(fill_color)?;
+    }
+
+    set_border_width(&annotation, line_width)?;
+
+    generate_normal_appearance(&annotation)?;
+
+    common.apply_metadata(&mut annotation)?;
+
+    Ok(annotation)
+}
+
+/// Creates a fully configured polygon or polyline annotation, drawing a closed polygon or an
+/// open path joining a series of vertices on the page.
+///
+/// Add the vertices using [PdfPolygonAnnotationBuilder::add_vertex()]. A polygon needs at least
+/// three vertices, and a polyline at least two. If no bounds are set, the annotation's bounds
+/// are the smallest rectangle enclosing its vertices, its border, and its line endings.
+/// The default color is red, the default line width is 1 point, and the polygon is not filled
+/// unless a fill color is set.
+///
+/// Pdfium cannot create polygon or polyline annotations, so the annotation is written directly
+/// into the document's file, and the document is reloaded. See
+/// [PdfPolygonAnnotationBuilder::create()].
+#[derive(Debug, Clone)]
+pub struct PdfPolygonAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    is_polyline: bool,
+    vertices: Vec<(PdfPoints, PdfPoints)>,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+    border_style: PdfAnnotationBorderStyle,
+    line_endings: (PdfAnnotationLineEnding, PdfAnnotationLineEnding),
+}
+
+impl PdfPolygonAnnotationBuilder {
+    /// Creates a new [PdfPolygonAnnotationBuilder] for a polygon annotation, with all settings
+    /// initialized with their default values.
+    #[inline]
+    pub fn new() -> Self {
+        PdfPolygonAnnotationBuilder {
+            common: PdfAnnotationBuilderCommon::default(),
+            is_polyline: false,
+            vertices: Vec::new(),
+            fill_color: None,
+            line_width: PdfPoints::new(1.0),
+            border_style: PdfAnnotationBorderStyle::Solid,
+            line_endings: (PdfAnnotationLineEnding::None, PdfAnnotationLineEnding::None),
+        }
+    }
+
+    /// Creates a new [PdfPolygonAnnotationBuilder] for a polyline annotation, with all
+    /// settings initialized with their default values.
+    #[inline]
+    pub fn polyline() -> Self {
+        PdfPolygonAnnotationBuilder {
+            is_polyline: true,
+            ..Self::new()
+        }
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Adds a vertex at the given (x, y) page coordinates to the end of the path drawn
+    /// by the new annotation.
+    #[inline]
+    pub fn add_vertex(mut self, x: PdfPoints, y: PdfPoints) -> Self {
+        self.vertices.push((x, y));
+
+        self
+    }
+
+    /// Sets the color used to fill the polygon drawn by the new annotation, or the closed
+    /// line endings drawn by a polyline annotation.
+    #[inline]
+    pub fn fill_color(mut self, color: PdfColor) -> Self {
+        self.fill_color = Some(color);
+
+        self
+    }
+
+    /// Sets the width of the lines drawn by the new annotation.
+    #[inline]
+    pub fn line_width(mut self, width: PdfPoints) -> Self {
+        self.line_width = width;
+
+        self
+    }
+
+    /// Sets the style in which the lines of the new annotation are drawn. Only solid and
+    /// dashed lines are drawn; other styles are drawn as solid lines.
+    #[inline]
+    pub fn border_style(mut self, style: PdfAnnotationBorderStyle) -> Self {
+        self.border_style = style;
+
+        self
+    }
+
+    /// Sets the line endings drawn at the start and end of the path drawn by a polyline
+    /// annotation. Line endings are ignored for polygon annotations.
+    #[inline]
+    pub fn line_endings(
+        mut self,
+        start: PdfAnnotationLineEnding,
+        end: PdfAnnotationLineEnding,
+    ) -> Self {
+        self.line_endings = (start, end);
+
+        self
+    }
+
+    /// Adds a new polygon or polyline annotation configured by this builder to the end of the
+    /// annotations of the page at the given index in the given [PdfDocument], returning the
+    /// index of the new annotation within the page's annotations.
+    ///
+    /// Pdfium cannot create these annotations, so this function updates the document in the
+    /// same way as `PdfDocument::set_xmp()`, preserving any existing signatures, and reloads
+    /// it. The new annotation is given an appearance stream, so that it displays consistently
+    /// in all PDF viewers.
+    ///
+    /// Returns [PdfiumError::NotEnoughVerticesForAnnotation] if too few vertices were added,
+    /// [PdfiumError::PageIndexOutOfBounds] if the document has no page at the given index,
+    /// or [PdfiumError::DocumentUpdateUnsupported] if the document is encrypted.
+    pub fn create(
+        &self,
+        document: &mut PdfDocument,
+        page_index: PdfPageIndex,
+    ) -> Result<PdfPageAnnotationIndex, PdfiumError> {
+        let (annotation, bounds, color, appearance) = self.annotation()?;
+
+        document.append_annotation(page_index, annotation, bounds, color, &appearance)
+    }
+
+    /// Returns the dictionary of the annotation configured by this builder, together with its
+    /// bounds, its color, and the content of its normal appearance stream.
+    fn annotation(&self) -> Result<(PdfSyntaxObject, PdfRect, PdfColor, String), PdfiumError> {
+        if self.vertices.len() < if self.is_polyline { 2 } else { 3 } {
+            return Err(PdfiumError::NotEnoughVerticesForAnnotation);
+        }
+
+        let color = self.common.color_or(PdfColor::RED);
+
+        let fill_color = self
+            .fill_color
+            .map(|fill_color| fill_color.with_alpha(color.alpha()));
+
+        let has_line_endings = self.is_polyline
+            && self.line_endings != (PdfAnnotationLineEnding::None, PdfAnnotationLineEnding::None);
+
+        let bounds = match self.common.bounds {
+            Some(bounds) => bounds,
+            None => {
+                let margin = self.line_width / 2.0
+                    + if self.is_polyline {
+                        line_ending_margin(self.line_width, self.line_endings)
+                    } else {
+                        PdfPoints::ZERO
+                    };
+
+                bounds_of_points(&self.vertices, margin)?
+            }
+        };
+
+        let content = if self.is_polyline {
+            open_path_appearance(
+                &self.vertices,
+                color,
+                fill_color,
+                self.line_width,
+                self.line_endings,
+            )
+        } else {
+            polygon_appearance(&self.vertices, color, fill_color, self.line_width, true)
+        };
+
+        let content = match self.border_style.dash_operator() {
+            Some(dash) => content.replacen("q\n", &format!("q\n{}", dash), 1),
+            None => content,
+        };
+
+        let color_array = |color: PdfColor| {
+            PdfSyntaxObject::Array(vec![
+                number(color.red() as f32 / 255.0),
+                number(color.green() as f32 / 255.0),
+                number(color.blue() as f32 / 255.0),
+            ])
+        };
+
+        let mut annotation = PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("Annot")),
+            (
+                "Subtype".to_string(),
+                PdfSyntaxObject::name(if self.is_polyline {
+                    "PolyLine"
+                } else {
+                    "Polygon"
+                }),
+            ),
+            (
+                "Rect".to_string(),
+                PdfSyntaxObject::Array(vec![
+                    number(bounds.left.value),
+                    number(bounds.bottom.value),
+                    number(bounds.right.value),
+                    number(bounds.top.value),
+                ]),
+            ),
+            (
+                "Vertices".to_string(),
+                PdfSyntaxObject::Array(
+                    self.vertices
+                        .iter()
+                        .flat_map(|(x, y)| [number(x.value), number(y.value)])
+                        .collect(),
+                ),
+            ),
+            (
+                "F".to_string(),
+                PdfSyntaxObject::Number(FPDF_ANNOT_FLAG_PRINT as f64),
+            ),
+            ("C".to_string(), color_array(color)),
+            (
+                "Border".to_string(),
+                PdfSyntaxObject::Array(vec![
+                    number(0.0),
+                    number(0.0),
+                    number(self.line_width.value),
+                ]),
+            ),
+            (
+                "BS".to_string(),
+                self.border_style.to_dictionary(self.line_width),
+            ),
+            (
+                "M".to_string(),
+                PdfSyntaxObject::text(&date_time_to_pdf_string(
+                    self.common.modification_date.unwrap_or_else(Utc::now),
+                )),
+            ),
+        ]);
+
+        if let Some(fill_color) = fill_color {
+            annotation.set("IC", color_array(fill_color));
+        }
+
+        if color.alpha() < 255 {
+            annotation.set("CA", number(color.alpha() as f32 / 255.0));
+        }
+
+        if has_line_endings {
+            annotation.set(
+                "LE",
+                PdfAnnotationLineEnding::to_array(self.line_endings.0, self.line_endings.1),
+            );
+        }
+
+        if let Some(contents) = self.common.contents.as_ref() {
+            annotation.set("Contents", PdfSyntaxObject::text(contents));
+        }
+
+        if let Some(author) = self.common.author.as_ref() {
+            annotation.set("T", PdfSyntaxObject::text(author));
+        }
+
+        Ok((annotation, bounds, color, content))
+    }
+}
+
+impl Default for PdfPolygonAnnotationBuilder {
+    #[inline]
+    fn default() -> Self {
+        PdfPolygonAnnotationBuilder::new()
+    }
+}
+
+/// Creates a fully configured redaction annotation, marking regions of the page whose content
+/// should be permanently removed when the redaction is applied using
+/// `PdfPage::apply_redactions()`.
+///
+/// Add the regions using [PdfRedactAnnotationBuilder::add_region()]. If no regions are added,
+/// the annotation's bounds mark the region instead. If no bounds are set, the annotation's
+/// bounds are the smallest rectangle enclosing its regions. Until the redaction is applied,
+/// each region is outlined in the annotation's color, which defaults to red.
+///
+/// Pdfium cannot create redaction annotations, so the annotation is written directly into
+/// the document's file, and the document is reloaded. See [PdfRedactAnnotationBuilder::create()].
+#[derive(Debug, Clone, Default)]
+pub struct PdfRedactAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    regions: Vec<PdfRect>,
+    fill_color: Option<PdfColor>,
+    overlay_text: Option<String>,
+}
+
+impl PdfRedactAnnotationBuilder {
+    /// Creates a new [PdfRedactAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Adds the given region of the page to the regions marked for redaction by the new
+    /// annotation. Call this function once for each line of redacted text.
+    #[inline]
+    pub fn add_region(mut self, region: PdfRect) -> Self {
+        self.regions.push(region);
+
+        self
+    }
+
+    /// Sets the color with which each redacted region is filled once the redaction is applied.
+    /// If no fill color is set, the redacted regions are left blank.
+    #[inline]
+    pub fn fill_color(mut self, color: PdfColor) -> Self {
+        self.fill_color = Some(color);
+
+        self
+    }
+
+    /// Sets the text drawn over each redacted region once the redaction is applied.
+    #[inline]
+    pub fn overlay_text(mut self, text: &str) -> Self {
+        self.overlay_text = Some(text.to_string());
+
+        self
+    }
+
+    /// Adds a new redaction annotation configured by this builder to the end of the
+    /// annotations of the page at the given index in the given [PdfDocument], returning the
+    /// index of the new annotation within the page's annotations.
+    ///
+    /// Pdfium cannot create redaction annotations, so this function updates the document in
+    /// the same way as [PdfPolygonAnnotationBuilder::create()], preserving any existing
+    /// signatures, and reloads it.
+    ///
+    /// Returns [PdfiumError::NoBoundsForAnnotation] if neither regions nor bounds were set,
+    /// [PdfiumError::PageIndexOutOfBounds] if the document has no page at the given index,
+    /// or [PdfiumError::DocumentUpdateUnsupported] if the document is encrypted.
+    pub fn create(
+        &self,
+        document: &mut PdfDocument,
+        page_index: PdfPageIndex,
+    ) -> Result<PdfPageAnnotationIndex, PdfiumError> {
+        let (annotation, bounds, color, appearance) = self.annotation()?;
+
+        document.append_annotation(page_index, annotation, bounds, color, &appearance)
+    }
+
+    /// Returns the dictionary of the annotation configured by this builder, together with its
+    /// bounds, its color, and the content of its normal appearance stream.
+    fn annotation(&self) -> Result<(PdfSyntaxObject, PdfRect, PdfColor, String), PdfiumError> {
+        let regions = if self.regions.is_empty() {
+            vec![self.common.required_bounds()?]
+        } else {
+            self.regions.clone()
+        };
+
+        let bounds = match self.common.bounds {
+            Some(bounds) => bounds,
+            None => bounds_of_points(
+                &regions
+                    .iter()
+                    .flat_map(|region| [(region.left, region.bottom), (region.right, region.top)])
+                    .collect::<Vec<_>>(),
+                PdfPoints::ZERO,
+            )?,
+        };
+
+        let color = self.common.color_or(PdfColor::RED);
+
+        let color_array = |color: PdfColor| {
+            PdfSyntaxObject::Array(vec![
+                number(color.red() as f32 / 255.0),
+                number(color.green() as f32 / 255.0),
+                number(color.blue() as f32 / 255.0),
+            ])
+        };
+
+        let mut annotation = PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("Annot")),
+            ("Subtype".to_string(), PdfSyntaxObject::name("Redact")),
+            (
+                "Rect".to_string(),
+                PdfSyntaxObject::Array(vec![
+                    number(bounds.left.value),
+                    number(bounds.bottom.value),
+                    number(bounds.right.value),
+                    number(bounds.top.value),
+                ]),
+            ),
+            (
+                "F".to_string(),
+                PdfSyntaxObject::Number(FPDF_ANNOT_FLAG_PRINT as f64),
+            ),
+            ("C".to_string(), color_array(color)),
+            (
+                "M".to_string(),
+                PdfSyntaxObject::text(&date_time_to_pdf_string(
+                    self.common.modification_date.unwrap_or_else(Utc::now),
+                )),
+            ),
+        ]);
+
+        if !self.regions.is_empty() {
+            annotation.set(
+                "QuadPoints",
+                PdfSyntaxObject::Array(
+                    self.regions
+                        .iter()
+                        .flat_map(|region| {
+                            let quad = PdfQuadPoints::from_rect(*region);
+
+                            [
+                                quad.x1, quad.y1, quad.x2, quad.y2, quad.x3, quad.y3, quad.x4,
+                                quad.y4,
+                            ]
+                        })
+                        .map(|value| number(value.value))
+                        .collect(),
+                ),
+            );
+        }
+
+        if let Some(fill_color) = self.fill_color {
+            annotation.set("IC", color_array(fill_color));
+        }
+
+        if let Some(text) = self.overlay_text.as_ref() {
+            annotation.set("OverlayText", PdfSyntaxObject::text(text));
+        }
+
+        if color.alpha() < 255 {
+            annotation.set("CA", number(color.alpha() as f32 / 255.0));
+        }
+
+        if let Some(contents) = self.common.contents.as_ref() {
+            annotation.set("Contents", PdfSyntaxObject::text(contents));
+        }
+
+        if let Some(author) = self.common.author.as_ref() {
+            annotation.set("T", PdfSyntaxObject::text(author));
+        }
+
+        Ok((
+            annotation,
+            bounds,
+            color,
+            redact_appearance(&regions, color),
+        ))
+    }
+}
+
+/// The position of the caption of a line annotation created by a [PdfLineAnnotationBuilder].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfLineCaptionPosition {
+    /// The caption is centered on the line, which is broken around it.
+    Inline,
+
+    /// The caption is drawn above the line.
+    Top,
+}
+
+impl PdfLineCaptionPosition {
+    /// Returns the PDF name identifying this position in a line annotation's `/CP` entry.
+    fn as_pdf_name(&self) -> &'static str {
+        match self {
+            PdfLineCaptionPosition::Inline => "Inline",
+            PdfLineCaptionPosition::Top => "Top",
+        }
+    }
+}
+
+/// Creates a fully configured line annotation, drawing a straight line between two points
+/// on the page, optionally with line endings such as arrowheads, leader lines, a caption,
+/// and a measurement scale. Together, these allow line annotations to be used as
+/// dimension lines by CAD-style measuring tools.
+///
+/// Set the line's end points using [PdfLineAnnotationBuilder::start()] and
+/// [PdfLineAnnotationBuilder::end()]. If no bounds are set, the annotation's bounds are the
+/// smallest rectangle enclosing its line, leader lines, line endings, and caption. The default
+/// color is red, the default line width is 1 point, and no line endings are drawn.
+///
+/// If a measurement scale is set using [PdfLineAnnotationBuilder::measure()] and no caption
+/// is set, the caption is the length of the line expressed in the scale's unit. The caption
+/// is recorded in the annotation's contents; it is only drawn in the generated appearance
+/// stream if a font is set using [PdfLineAnnotationBuilder::caption_font()], in which case
+/// its glyphs are drawn as paths, as for the [PdfFreeTextAnnotationBuilder].
+///
+/// Pdfium cannot create line annotations, so the annotation is written directly into the
+/// document's file, and the document is reloaded. See [PdfLineAnnotationBuilder::create()].
+#[derive(Debug, Clone)]
+pub struct PdfLineAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    start: Option<(PdfPoints, PdfPoints)>,
+    end: Option<(PdfPoints, PdfPoints)>,
+    fill_color: Option<PdfColor>,
+    line_width: PdfPoints,
+    border_style: PdfAnnotationBorderStyle,
+    line_endings: (PdfAnnotationLineEnding, PdfAnnotationLineEnding),
+    leader: PdfLineLeader,
+    caption: Option<String>,
+    caption_position: PdfLineCaptionPosition,
+    caption_font: Option<PdfFontToken>,
+    caption_font_size: PdfPoints,
+    measure: Option<PdfLineMeasure>,
+}
+
+impl PdfLineAnnotationBuilder {
+    /// Creates a new [PdfLineAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        PdfLineAnnotationBuilder {
+            common: PdfAnnotationBuilderCommon::default(),
+            start: None,
+            end: None,
+            fill_color: None,
+            line_width: PdfPoints::new(1.0),
+            border_style: PdfAnnotationBorderStyle::Solid,
+            line_endings: (PdfAnnotationLineEnding::None, PdfAnnotationLineEnding::None),
+            leader: PdfLineLeader::default(),
+            caption: None,
+            caption_position: PdfLineCaptionPosition::Inline,
+            caption_font: None,
+            caption_font_size: PdfPoints::new(10.0),
+            measure: None,
+        }
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Sets the start point of the new line, expressed as (x, y) page coordinates.
+    #[inline]
+    pub fn start(mut self, x: PdfPoints, y: PdfPoints) -> Self {
+        self.start = Some((x, y));
+
+        self
+    }
+
+    /// Sets the end point of the new line, expressed as (x, y) page coordinates.
+    #[inline]
+    pub fn end(mut self, x: PdfPoints, y: PdfPoints) -> Self {
+        self.end = Some((x, y));
+
+        self
+    }
+
+    /// Sets the color used to fill the closed line endings drawn by the new annotation.
+    #[inline]
+    pub fn fill_color(mut self, color: PdfColor) -> Self {
+        self.fill_color = Some(color);
+
+        self
+    }
+
+    /// Sets the width of the line drawn by the new annotation.
+    #[inline]
+    pub fn line_width(mut self, width: PdfPoints) -> Self {
+        self.line_width = width;
+
+        self
+    }
+
+    /// Sets the style in which the line of the new annotation is drawn. Only solid and
+    /// dashed lines are drawn; other styles are drawn as solid lines.
+    #[inline]
+    pub fn border_style(mut self, style: PdfAnnotationBorderStyle) -> Self {
+        self.border_style = style;
+
+        self
+    }
+
+    /// Sets the line endings drawn at the start and end of the new line.
+    #[inline]
+    pub fn line_endings(
+        mut self,
+        start: PdfAnnotationLineEnding,
+        end: PdfAnnotationLineEnding,
+    ) -> Self {
+        self.line_endings = (start, end);
+
+        self
+    }
+
+    /// Sets the leader lines drawn by the new annotation, extending perpendicularly from
+    /// each end point to the line itself, which is drawn parallel to the line joining the
+    /// end points. The leader lines have the given length, extend beyond the line by the
+    /// given extension, and start at the given offset from each end point. Positive lengths
+    /// extend clockwise, when traversing the line from its start point to its end point;
+    /// negative lengths extend anticlockwise.
+    #[inline]
+    pub fn leader_line(
+        mut self,
+        length: PdfPoints,
+        extension: PdfPoints,
+        offset: PdfPoints,
+    ) -> Self {
+        self.leader = PdfLineLeader {
+            length: length.value,
+            extension: extension.value,
+            offset: offset.value,
+        };
+
+        self
+    }
+
+    /// Sets the caption of the new line, replacing any text set using
+    /// [PdfLineAnnotationBuilder::contents()].
+    #[inline]
+    pub fn caption(mut self, caption: &str) -> Self {
+        self.caption = Some(caption.to_string());
+
+        self
+    }
+
+    /// Sets whether the caption of the new line is centered on the line or drawn above it.
+    /// The default position is [PdfLineCaptionPosition::Inline].
+    #[inline]
+    pub fn caption_position(mut self, position: PdfLineCaptionPosition) -> Self {
+        self.caption_position = position;
+
+        self
+    }
+
+    /// Sets the font used to draw the caption in the appearance stream generated for the
+    /// new annotation. Both built-in and embedded fonts can be used. If no font is set,
+    /// the caption is not drawn.
+    #[inline]
+    pub fn caption_font(mut self, font: impl ToPdfFontToken) -> Self {
+        self.caption_font = Some(font.token());
+
+        self
+    }
+
+    /// Sets the size of the font used to draw the caption. The default size is 10 points.
+    #[inline]
+    pub fn caption_font_size(mut self, size: PdfPoints) -> Self {
+        self.caption_font_size = size;
+
+        self
+    }
+
+    /// Sets the measurement scale of the new line, converting its length on the page into
+    /// a real-world length.
+    #[inline]
+    pub fn measure(mut self, measure: PdfLineMeasure) -> Self {
+        self.measure = Some(measure);
+
+        self
+    }
+
+    /// Adds a new line annotation configured by this builder to the end of the annotations
+    /// of the page at the given index in the given [PdfDocument], returning the index of the
+    /// new annotation within the page's annotations.
+    ///
+    /// Pdfium cannot create line annotations, so this function updates the document in the
+    /// same way as `PdfDocument::set_xmp()`, preserving any existing signatures, and reloads
+    /// it. The new annotation is given an appearance stream, so that it displays consistently
+    /// in all PDF viewers.
+    ///
+    /// Returns [PdfiumError::NotEnoughVerticesForAnnotation] if the line's start or end point
+    /// was not set, [PdfiumError::PageIndexOutOfBounds] if the document has no page at the
+    /// given index, or [PdfiumError::DocumentUpdateUnsupported] if the document is encrypted.
+    pub fn create(
+        &self,
+        document: &mut PdfDocument,
+        page_index: PdfPageIndex,
+    ) -> Result<PdfPageAnnotationIndex, PdfiumError> {
+        let (annotation, bounds, color, appearance) = self.annotation(document.bindings())?;
+
+        document.append_annotation(page_index, annotation, bounds, color, &appearance)
+    }
+
+    /// Returns the caption of the line configured by this builder, if any.
+    fn caption_text(
+        &self,
+        start: (PdfPoints, PdfPoints),
+        end: (PdfPoints, PdfPoints),
+    ) -> Option<String> {
+        self.caption.clone().or_else(|| {
+            self.measure.as_ref().map(|measure| {
+                measure.format_length(PdfPoints::new(
+                    ((end.0.value - start.0.value).powi(2) + (end.1.value - start.1.value).powi(2))
+                        .sqrt(),
+                ))
+            })
+        })
+    }
+
+    /// Returns the dictionary of the annotation configured by this builder, together with its
+    /// bounds, its color, and the content of its normal appearance stream.
+    fn annotation(
+        &self,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Result<(PdfSyntaxObject, PdfRect, PdfColor, String), PdfiumError> {
+        let (start, end) = match (self.start, self.end) {
+            (Some(start), Some(end)) => (start, end),
+            _ => return Err(PdfiumError::NotEnoughVerticesForAnnotation),
+        };
+
+        let color = self.common.color_or(PdfColor::RED);
+
+        let fill_color = self
+            .fill_color
+            .map(|fill_color| fill_color.with_alpha(color.alpha()));
+
+        let caption = self.caption_text(start, end);
+
+        let drawn_caption = match (caption.as_ref(), self.caption_font) {
+            (Some(text), Some(font)) => Some(PdfLineCaption {
+                bindings,
+                text: text.as_str(),
+                font: font.handle(),
+                font_size: self.caption_font_size,
+                is_inline: self.caption_position == PdfLineCaptionPosition::Inline,
+            }),
+            _ => None,
+        };
+
+        let bounds = match self.common.bounds {
+            Some(bounds) => bounds,
+            None => {
+                let mut margin =
+                    self.line_width / 2.0 + line_ending_margin(self.line_width, self.line_endings);
+
+                if drawn_caption.is_some() {
+                    // A caption drawn above the line extends beyond it by its font size,
+                    // plus a small gap.
+
+                    let caption_margin = self.caption_font_size + PdfPoints::new(4.0);
+
+                    if caption_margin > margin {
+                        margin = caption_margin;
+                    }
+                }
+
+                bounds_of_points(&self.leader.extent(start, end), margin)?
+            }
+        };
+
+        let content = line_annotation_appearance(
+            start,
+            end,
+            color,
+            fill_color,
+            self.line_width,
+            self.leader,
+            self.line_endings,
+            drawn_caption.as_ref(),
+        );
+
+        let content = match self.border_style.dash_operator() {
+            Some(dash) => content.replacen("q\n", &format!("q\n{}", dash), 1),
+            None => content,
+        };
+
+        let color_array = |color: PdfColor| {
+            PdfSyntaxObject::Array(vec![
+                number(color.red() as f32 / 255.0),
+                number(color.green() as f32 / 255.0),
+                number(color.blue() as f32 / 255.0),
+            ])
+        };
+
+        let mut annotation = PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("Annot")),
+            ("Subtype".to_string(), PdfSyntaxObject::name("Line")),
+            (
+                "Rect".to_string(),
+                PdfSyntaxObject::Array(vec![
+                    number(bounds.left.value),
+                    number(bounds.bottom.value),
+                    number(bounds.right.value),
+                    number(bounds.top.value),
+                ]),
+            ),
+            (
+                "L".to_string(),
+                PdfSyntaxObject::Array(vec![
+                    number(start.0.value),
+                    number(start.1.value),
+                    number(end.0.value),
+                    number(end.1.value),
+                ]),
+            ),
+            (
+                "F".to_string(),
+                PdfSyntaxObject::Number(FPDF_ANNOT_FLAG_PRINT as f64),
+            ),
+            ("C".to_string(), color_array(color)),
+            (
+                "Border".to_string(),
+                PdfSyntaxObject::Array(vec![
+                    number(0.0),
+                    number(0.0),
+                    number(self.line_width.value),
+                ]),
+            ),
+            (
+                "BS".to_string(),
+                self.border_style.to_dictionary(self.line_width),
+            ),
+            (
+                "M".to_string(),
+                PdfSyntaxObject::text(&date_time_to_pdf_string(
+                    self.common.modification_date.unwrap_or_else(Utc::now),
+                )),
+            ),
+        ]);
+
+        if let Some(fill_color) = fill_color {
+            annotation.set("IC", color_array(fill_color));
+        }
+
+        if color.alpha() < 255 {
+            annotation.set("CA", number(color.alpha() as f32 / 255.0));
+        }
+
+        if self.line_endings != (PdfAnnotationLineEnding::None, PdfAnnotationLineEnding::None) {
+            annotation.set(
+                "LE",
+                PdfAnnotationLineEnding::to_array(self.line_endings.0, self.line_endings.1),
+            );
+        }
+
+        if self.leader.length != 0.0 {
+            annotation.set("LL", number(self.leader.length));
+
+            if self.leader.extension != 0.0 {
+                annotation.set("LLE", number(self.leader.extension));
+            }
+
+            if self.leader.offset != 0.0 {
+                annotation.set("LLO", number(self.leader.offset));
+            }
+        }
+
+        match caption.as_ref() {
+            Some(caption) => {
+                annotation.set("Contents", PdfSyntaxObject::text(caption));
+                annotation.set("Cap", PdfSyntaxObject::Bool(true));
+                annotation.set(
+                    "CP",
+                    PdfSyntaxObject::name(self.caption_position.as_pdf_name()),
+                );
+            }
+            None => {
+                if let Some(contents) = self.common.contents.as_ref() {
+                    annotation.set("Contents", PdfSyntaxObject::text(contents));
+                }
+            }
+        }
+
+        if let Some(measure) = self.measure.as_ref() {
+            annotation.set("IT", PdfSyntaxObject::name("LineDimension"));
+            annotation.set("Measure", measure.to_dictionary());
+        }
+
+        if let Some(author) = self.common.author.as_ref() {
+            annotation.set("T", PdfSyntaxObject::text(author));
+        }
+
+        Ok((annotation, bounds, color, content))
+    }
+}
+
+impl Default for PdfLineAnnotationBuilder {
+    #[inline]
+    fn default() -> Self {
+        PdfLineAnnotationBuilder::new()
+    }
+}
+
+/// The horizontal alignment of the text displayed by a [PdfPageFreeTextAnnotation].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfFreeTextAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl PdfFreeTextAlignment {
+    /// Returns the value of the CSS `text-align` property matching this alignment.
+    fn as_css(&self) -> &'static str {
+        match self {
+            PdfFreeTextAlignment::Left => "left",
+            PdfFreeTextAlignment::Center => "center",
+            PdfFreeTextAlignment::Right => "right",
+        }
+    }
+}
+
+/// Creates a fully configured [PdfPageFreeTextAnnotation], displaying text directly
+/// on the page rather than in a popup window.
+///
+/// The text is set using [PdfFreeTextAnnotationBuilder::contents()] and is laid out
+/// within the annotation's bounds, which must be set. The annotation's color, if set,
+/// is used as the background color. The default text color is black, the default font
+/// size is 12 points, the default padding is 2 points, text is aligned to the left,
+/// and no border is drawn unless a border width is set.
+///
+/// The font, size, text color, and alignment are recorded in the annotation's default
+/// appearance and default style strings, from which PDF viewers generate the annotation's
+/// appearance. If a font is set using [PdfFreeTextAnnotationBuilder::font()], an appearance
+/// stream is also generated that draws the text in that font, wrapped to fit the annotation's
+/// bounds. Pdfium cannot add fonts to an annotation's appearance stream, so the text is drawn
+/// as the outlines of its glyphs. Pdfium itself does not draw free text annotations without
+/// an appearance stream.
+///
+/// Pdfium cannot record the annotation's alignment, padding, or callout line in the
+/// properties PDF viewers use to regenerate its appearance, so they are only displayed
+/// through the generated appearance stream.
+#[derive(Debug, Clone)]
+pub struct PdfFreeTextAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    font: Option<PdfFontToken>,
+    font_size: PdfPoints,
+    text_color: PdfColor,
+    border_width: PdfPoints,
+    alignment: PdfFreeTextAlignment,
+    padding: PdfPoints,
+    callout: Option<(PdfPoints, PdfPoints)>,
+}
+
+impl PdfFreeTextAnnotationBuilder {
+    /// Creates a new [PdfFreeTextAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        PdfFreeTextAnnotationBuilder {
+            common: PdfAnnotationBuilderCommon::default(),
+            font: None,
+            font_size: PdfPoints::new(12.0),
+            text_color: PdfColor::BLACK,
+            border_width: PdfPoints::ZERO,
+            alignment: PdfFreeTextAlignment::Left,
+            padding: PdfPoints::new(2.0),
+            callout: None,
+        }
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Sets the font used to draw the text in the appearance stream generated for the
+    /// new annotation. Both built-in and embedded fonts can be used. If no font is set,
+    /// no appearance stream is generated.
+    #[inline]
+    pub fn font(mut self, font: impl ToPdfFontToken) -> Self {
+        self.font = Some(font.token());
+
+        self
+    }
+
+    /// Sets the size of the text displayed by the new annotation.
+    #[inline]
+    pub fn font_size(mut self, size: PdfPoints) -> Self {
+        self.font_size = size;
+
+        self
+    }
+
+    /// Sets the color of the text displayed by the new annotation.
+    #[inline]
+    pub fn text_color(mut self, color: PdfColor) -> Self {
+        self.text_color = color;
+
+        self
+    }
+
+    /// Sets the width of the border drawn around the new annotation.
+    #[inline]
+    pub fn border_width(mut self, width: PdfPoints) -> Self {
+        self.border_width = width;
+
+        self
+    }
+
+    /// Sets the horizontal alignment of the text displayed by the new annotation.
+    #[inline]
+    pub fn alignment(mut self, alignment: PdfFreeTextAlignment) -> Self {
+        self.alignment = alignment;
+
+        self
+    }
+
+    /// Sets the space between the border of the new annotation and its text.
+    #[inline]
+    pub fn padding(mut self, padding: PdfPoints) -> Self {
+        self.padding = padding;
+
+        self
+    }
+
+    /// Draws a callout line with an arrowhead from the new annotation to the given point,
+    /// expressed in page coordinates. The annotation's bounds are expanded to enclose
+    /// the point; the text is laid out within the bounds set using
+    /// [PdfFreeTextAnnotationBuilder::bounds()].
+    #[inline]
+    pub fn callout(mut self, x: PdfPoints, y: PdfPoints) -> Self {
+        self.callout = Some((x, y));
+
+        self
+    }
+
+    /// Creates a new [PdfPageFreeTextAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageFreeTextAnnotation<'a>, PdfiumError> {
+        let text_box = self.common.required_bounds()?;
+
+        let bounds = match self.callout {
+            Some((x, y)) => {
+                // Leave room around the callout point for the arrowhead.
+
+                let arrow_size = PdfPoints::new(self.border_width.value.max(1.0) * 6.0);
+
+                bounds_of_points(
+                    &[
+                        (text_box.left, text_box.bottom),
+                        (text_box.right, text_box.top),
+                        (x - arrow_size, y - arrow_size),
+                        (x + arrow_size, y + arrow_size),
+                    ],
+                    PdfPoints::ZERO,
+                )?
+            }
+            None => text_box,
+        };
+
+        let contents = self.common.contents.as_deref().unwrap_or_default();
+
+        let bindings = annotations.bindings();
+
+        let mut annotation = annotations.create_free_text_annotation(contents)?;
+
+        annotation.set_bounds(bounds)?;
+
+        // The annotation's color must be set before its appearance stream is added.
+
+        let opacity_color = if self.common.color.is_some() {
+            let color = self.common.color_or(PdfColor::WHITE);
+
+            annotation.set_stroke_color(color)?;
+
+            color
+        } else {
+            PdfColor::WHITE
+        };
+
+        set_border_width(&annotation, self.border_width)?;
+
+        annotation.set_string_value(
+            "DA",
+            &format!(
+                "/Helv {} Tf {} rg",
+                format_number(self.font_size.value),
+                color_operands(self.text_color)
+            ),
+        )?;
+
+        annotation.set_string_value(
+            "DS",
+            &format!(
+                "font: Helvetica,sans-serif {}pt; text-align:{}; color:#{:02X}{:02X}{:02X}",
+                format_number(self.font_size.value),
+                self.alignment.as_css(),
+                self.text_color.red(),
+                self.text_color.green(),
+                self.text_color.blue()
+            ),
+        )?;
+
+        if let Some(font) = self.font {
+            let content = free_text_appearance(
+                bindings,
+                contents,
+                text_box,
+                self.callout,
+                opacity_color,
+                &PdfFreeTextLayout {
+                    font: font.handle(),
+                    font_size: self.font_size,
+                    text_color: self.text_color,
+                    background_color: self.common.color,
+                    border_width: self.border_width,
+                    alignment: self.alignment,
+                    padding: self.padding,
+                },
+            );
+
+            set_normal_appearance(&annotation, &content)?;
+        }
+
+        self.common.apply_metadata(&mut annotation)?;
+
+        Ok(annotation)
+    }
+}
+
+impl Default for PdfFreeTextAnnotationBuilder {
+    #[inline]
+    fn default() -> Self {
+        PdfFreeTextAnnotationBuilder::new()
+    }
+}
+
+/// The icon drawn by a [PdfPageFileAttachmentAnnotation] created by a
+/// [PdfFileAttachmentAnnotationBuilder].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfFileAttachmentIcon {
+    Paperclip,
+    PushPin,
+}
+
+impl PdfFileAttachmentIcon {
+    /// Returns the PDF name identifying this [PdfFileAttachmentIcon] in an annotation's
+    /// `/Name` entry.
+    pub(crate) fn as_pdf_name(&self) -> &'static str {
+        match self {
+            PdfFileAttachmentIcon::Paperclip => "Paperclip",
+            PdfFileAttachmentIcon::PushPin => "PushPin",
+        }
+    }
+}
+
+/// Creates a fully configured [PdfPageFileAttachmentAnnotation], embedding a file at a
+/// specific location on the page, displayed as an icon.
+///
+/// Set the embedded file using [PdfFileAttachmentAnnotationBuilder::file()], and describe it
+/// using [PdfFileAttachmentAnnotationBuilder::mime_type()] and
+/// [PdfFileAttachmentAnnotationBuilder::description()]. The annotation's own text can be set
+/// using [PdfFileAttachmentAnnotationBuilder::contents()]. The icon fills the annotation's
+/// bounds, which must be set. The default icon is a paperclip and the default color is blue.
+///
+/// The icon is drawn in the generated appearance stream and recorded in the annotation's
+/// `/Name` entry, so PDF viewers that regenerate the appearance draw the same icon.
+/// The icon name, MIME type, and description are written when the document is next saved.
+#[derive(Debug, Clone)]
+pub struct PdfFileAttachmentAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    file: Option<(String, Vec<u8>)>,
+    mime_type: Option<String>,
+    description: Option<String>,
+    icon: PdfFileAttachmentIcon,
+}
+
+impl PdfFileAttachmentAnnotationBuilder {
+    /// Creates a new [PdfFileAttachmentAnnotationBuilder] with all settings initialized with
+    /// their default values.
+    #[inline]
+    pub fn new() -> Self {
+        PdfFileAttachmentAnnotationBuilder {
+            common: PdfAnnotationBuilderCommon::default(),
+            file: None,
+            mime_type: None,
+            description: None,
+            icon: PdfFileAttachmentIcon::Paperclip,
+        }
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Sets the name and byte data of the file embedded in the new annotation.
+    #[inline]
+    pub fn file(mut self, name: &str, bytes: &[u8]) -> Self {
+        self.file = Some((name.to_string(), bytes.to_vec()));
+
+        self
+    }
+
+    /// Sets the MIME type of the file embedded in the new annotation, such as "text/plain".
+    #[inline]
+    pub fn mime_type(mut self, mime_type: &str) -> Self {
+        self.mime_type = Some(mime_type.to_string());
+
+        self
+    }
+
+    /// Sets the description of the file embedded in the new annotation.
+    #[inline]
+    pub fn description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+
+        self
+    }
+
+    /// Sets the icon drawn by the new annotation.
+    #[inline]
+    pub fn icon(mut self, icon: PdfFileAttachmentIcon) -> Self {
+        self.icon = icon;
+
+        self
+    }
+
+    /// Creates a new [PdfPageFileAttachmentAnnotation] in the given [PdfPageAnnotations]
+    /// collection configured by this builder, returning the newly created annotation.
+    ///
+    /// Returns [PdfiumError::NoAttachmentForAnnotation] if no file was set.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageFileAttachmentAnnotation<'a>, PdfiumError> {
+        let bounds = self.common.required_bounds()?;
+
+        let (name, bytes) = self
+            .file
+            .as_ref()
+            .ok_or(PdfiumError::NoAttachmentForAnnotation)?;
+
+        let color = self.common.color_or(PdfColor::BLUE);
+
+        let mut annotation = annotations.create_file_attachment_annotation(
+            name,
+            bytes,
+            self.mime_type.as_deref(),
+            self.description.as_deref(),
+        )?;
+
+        self.common
+            .apply_bounds_and_color(&mut annotation, bounds, color)?;
+
+        set_normal_appearance(
+            &annotation,
+            &file_attachment_appearance(bounds, color, self.icon),
+        )?;
+
+        PdfAnnotationDictionaryEdits::set(
+            &mut annotation,
+            "Name",
+            Some(PdfSyntaxObject::name(self.icon.as_pdf_name())),
+        )?;
+
+        self.common.apply_metadata(&mut annotation)?;
+
+        Ok(annotation)
+    }
+}
+
+impl Default for PdfFileAttachmentAnnotationBuilder {
+    #[inline]
+    fn default() -> Self {
+        PdfFileAttachmentAnnotationBuilder::new()
+    }
+}
+
+/// A standard rubber stamp, drawn by a [PdfStampAnnotationBuilder] as a label in a
+/// conventional color. Each template corresponds to one of the standard stamp names described
+/// in section 8.4.5 of The PDF Reference Manual, version 1.7, on page 635, which is recorded
+/// in the stamp's `/Name` entry.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfStampTemplate {
+    /// A green "APPROVED" stamp, marking a document as accepted.
+    Approved,
+
+    /// A red "NOT APPROVED" stamp, marking a document as rejected.
+    NotApproved,
+
+    /// A blue "DRAFT" stamp, marking a document as a work in progress.
+    Draft,
+
+    /// A green "FINAL" stamp, marking a document as complete.
+    Final,
+
+    /// A red "CONFIDENTIAL" stamp, marking a document as restricted to its intended readers.
+    Confidential,
+
+    /// A blue "FOR COMMENT" stamp, marking a document as circulated for review.
+    ForComment,
+}
+
+impl PdfStampTemplate {
+    /// Returns the standard stamp name identifying this template in a stamp annotation's
+    /// `/Name` entry.
+    pub(crate) fn as_pdf_name(&self) -> &'static str {
+        match self {
+            PdfStampTemplate::Approved => "Approved",
+            PdfStampTemplate::NotApproved => "NotApproved",
+            PdfStampTemplate::Draft => "Draft",
+            PdfStampTemplate::Final => "Final",
+            PdfStampTemplate::Confidential => "Confidential",
+            PdfStampTemplate::ForComment => "ForComment",
+        }
+    }
+
+    /// Returns the label drawn inside this stamp.
+    pub fn label(&self) -> &'static str {
+        match self {
+            PdfStampTemplate::Approved => "APPROVED",
+            PdfStampTemplate::NotApproved => "NOT APPROVED",
+            PdfStampTemplate::Draft => "DRAFT",
+            PdfStampTemplate::Final => "FINAL",
+            PdfStampTemplate::Confidential => "CONFIDENTIAL",
+            PdfStampTemplate::ForComment => "FOR COMMENT",
+        }
+    }
+
+    /// Returns the color in which this stamp is drawn.
+    pub fn color(&self) -> PdfColor {
+        match self {
+            PdfStampTemplate::Approved | PdfStampTemplate::Final => PdfColor::new(0, 128, 0, 255),
+            PdfStampTemplate::NotApproved | PdfStampTemplate::Confidential => PdfColor::RED,
+            PdfStampTemplate::Draft | PdfStampTemplate::ForComment => PdfColor::BLUE,
+        }
+    }
+}
+
+/// Creates a fully configured [PdfPageStampAnnotation], drawing a rubber stamp such as
+/// "APPROVED" or "DRAFT" on the page.
+///
+/// The stamp fills the annotation's bounds, which must be set. It is drawn as a bordered
+/// rectangle containing an optional image and an optional label, each scaled to fit the stamp
+/// and centered within it. Use [PdfStampAnnotationBuilder::template()] to draw one of the
+/// standard stamps. The default color is red, or the template's color if a template is used,
+/// and the default border width is 2 points.
+///
+/// The standard name of a template is written to the stamp's `/Name` entry when the document
+/// is next saved, so PDF viewers that regenerate the stamp's appearance draw the same stamp.
+#[derive(Debug, Clone)]
+pub struct PdfStampAnnotationBuilder {
+    common: PdfAnnotationBuilderCommon,
+    label: Option<(String, PdfFontToken)>,
+    template: Option<PdfStampTemplate>,
+    default_color: PdfColor,
+    border_width: PdfPoints,
+
+    #[cfg(feature = "image")]
+    image: Option<DynamicImage>,
+}
+
+impl PdfStampAnnotationBuilder {
+    /// Creates a new [PdfStampAnnotationBuilder] with all settings initialized with their
+    /// default values.
+    #[inline]
+    pub fn new() -> Self {
+        PdfStampAnnotationBuilder {
+            common: PdfAnnotationBuilderCommon::default(),
+            label: None,
+            template: None,
+            default_color: PdfColor::RED,
+            border_width: PdfPoints::new(2.0),
+
+            #[cfg(feature = "image")]
+            image: None,
+        }
+    }
+
+    create_annotation_builder_setters!();
+
+    /// Sets the label drawn inside the new stamp, using the given font.
+    #[inline]
+    pub fn label(mut self, label: &str, font: impl ToPdfFontToken) -> Self {
+        self.label = Some((label.to_string(), font.token()));
+
+        self
+    }
+
+    /// Draws the given standard stamp, using the given font for its label. The stamp's
+    /// color is used unless a color is set using [PdfStampAnnotationBuilder::color()].
+    #[inline]
+    pub fn template(mut self, template: PdfStampTemplate, font: impl ToPdfFontToken) -> Self {
+        self.template = Some(template);
+        self.default_color = template.color();
+
+        self.label(template.label(), font)
+    }
+
+    /// Draws the given image inside the new stamp, scaled to fit within the stamp's border
+    /// while preserving its aspect ratio. Any label is drawn over the image. Set a border
+    /// width of zero to draw the image alone.
+    ///
+    /// This function is only available when this crate's `image` feature is enabled.
+    #[cfg(feature = "image")]
+    #[inline]
+    pub fn image(mut self, image: &DynamicImage) -> Self {
+        self.image = Some(image.clone());
+
+        self
+    }
+
+    /// Sets the width of the border drawn around the new stamp. Set a width of zero
+    /// to draw no border.
+    #[inline]
+    pub fn border_width(mut self, width: PdfPoints) -> Self {
+        self.border_width = width;
+
+        self
+    }
+
+    /// Creates a new [PdfPageStampAnnotation] in the given [PdfPageAnnotations] collection
+    /// configured by this builder, returning the newly created annotation.
+    ///
+    /// Unlike other annotations, the appearance stream of a stamp is generated by Pdfium from
+    /// the page objects the stamp contains, so the stamp's label and border are available as
+    /// page objects through the returned annotation's `objects()` function.
+    pub fn create<'a>(
+        &self,
+        annotations: &mut PdfPageAnnotations<'a>,
+    ) -> Result<PdfPageStampAnnotation<'a>, PdfiumError> {
+        let bounds = self.common.required_bounds()?;
+
+        let color = self.common.color_or(self.default_color);
+
+        let document_handle = annotations.document_handle();
+
+        let bindings = annotations.bindings();
+
+        let mut annotation = annotations.create_stamp_annotation()?;
+
+        self.common
+            .apply_bounds_and_color(&mut annotation, bounds, color)?;
+
+        let half_border = self.border_width / 2.0;
+
+        let inner = PdfRect::new(
+            bounds.bottom + half_border,
+            bounds.left + half_border,
+            bounds.top - half_border,
+            bounds.right - half_border,
+        );
+
+        if self.border_width.value > 0.0 {
+            annotation.objects_mut_impl().create_path_object_rect(
+                inner,
+                Some(color),
+                Some(self.border_width),
+                None,
+            )?;
+        }
+
+        #[cfg(feature = "image")]
+        if let Some(image) = self.image.as_ref() {
+            // Fit the image within the stamp's border, preserving its aspect ratio.
+
+            let scale = (inner.width().value / image.width() as f32)
+                .min(inner.height().value / image.height() as f32);
+
+            let width = PdfPoints::new(image.width() as f32 * scale);
+
+            let height = PdfPoints::new(image.height() as f32 * scale);
+
+            annotation.objects_mut_impl().create_image_object(
+                (inner.left + inner.right - width) / 2.0,
+                (inner.bottom + inner.top - height) / 2.0,
+                image,
+                Some(width),
+                Some(height),
+            )?;
+        }
+
+        if let Some((label, font)) = self.label.as_ref() {
+            let mut object = PdfPageTextObject::new_from_handles(
+                document_handle,
+                label,
+                font.handle(),
+                inner.height() * 0.6,
+                bindings,
+            )?;
+
+            object.set_fill_color(color)?;
+
+            // Shrink the label to fit within the stamp, then center it.
+
+            let label_bounds = object.bounds()?;
+
+            let available_width = inner.width().value * 0.9;
+
+            if label_bounds.width().value > available_width {
+                let scale = available_width / label_bounds.width().value;
+
+                object.scale(scale, scale)?;
+            }
+
+            let label_bounds = object.bounds()?;
+
+            object.translate(
+                (inner.left + inner.right - label_bounds.left - label_bounds.right) / 2.0,
+                (inner.bottom + inner.top - label_bounds.bottom - label_bounds.top) / 2.0,
+            )?;
+
+            annotation.objects_mut_impl().add_text_object(object)?;
+        }
+
+        if let Some(template) = self.template {
+            PdfAnnotationDictionaryEdits::set(
+                &mut annotation,
+                "Name",
+                Some(PdfSyntaxObject::name(template.as_pdf_name())),
+            )?;
+        }
+
+        self.common.apply_metadata(&mut annotation)?;
+
+        Ok(annotation)
+    }
+}
+
+impl Default for PdfStampAnnotationBuilder {
+    #[inline]
+    fn default() -> Self {
+        PdfStampAnnotationBuilder::new()
+    }
+}
+
+/// Returns the smallest rectangle enclosing all the given points, expanded on all sides
+/// by the given margin, or an error if there are no points.
+fn bounds_of_points(
+    points: &[(PdfPoints, PdfPoints)],
+    margin: PdfPoints,
+) -> Result<PdfRect, PdfiumError> {
+    let (first_x, first_y) = *points.first().ok_or(PdfiumError::NoBoundsForAnnotation)?;
+
+    let (left, bottom, right, top) = points.iter().fold(
+        (first_x, first_y, first_x, first_y),
+        |(left, bottom, right, top), (x, y)| {
+            (
+                if *x < left { *x } else { left },
+                if *y < bottom { *y } else { bottom },
+                if *x > right { *x } else { right },
+                if *y > top { *y } else { top },
+            )
+        },
+    );
+
+    Ok(PdfRect::new(
+        bottom - margin,
+        left - margin,
+        top + margin,
+        right + margin,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::annotation::builders::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_create_annotations_from_builders() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let font = document.fonts_mut().helvetica_bold();
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let bounds = PdfRect::new_from_values(100.0, 100.0, 150.0, 300.0);
+
+        let highlight = PdfHighlightAnnotationBuilder::new()
+            .add_rect(bounds)
+            .opacity(0.5)
+            .contents("Check this")
+            .author("Reviewer")
+            .create(page.annotations_mut())?;
+
+        assert_eq!(highlight.bounds()?, bounds);
+        assert_eq!(highlight.contents(), Some("Check this".to_string()));
+        assert_eq!(highlight.creator(), Some("Reviewer".to_string()));
+
+        let ink = PdfInkAnnotationBuilder::new()
+            .add_stroke(&[
+                (PdfPoints::new(10.0), PdfPoints::new(10.0)),
+                (PdfPoints::new(20.0), PdfPoints::new(30.0)),
+            ])
+            .line_width(PdfPoints::new(2.0))
+            .create(page.annotations_mut())?;
+
+        assert_eq!(
+            ink.bounds()?,
+            PdfRect::new_from_values(9.0, 9.0, 31.0, 21.0)
+        );
+
+        PdfSquareAnnotationBuilder::new()
+            .bounds(bounds)
+            .fill_color(PdfColor::YELLOW)
+            .create(page.annotations_mut())?;
+
+        PdfCircleAnnotationBuilder::new()
+            .bounds(bounds)
+            .create(page.annotations_mut())?;
+
+        PdfFreeTextAnnotationBuilder::new()
+            .bounds(bounds)
+            .contents("Note")
+            .create(page.annotations_mut())?;
+
+        PdfFreeTextAnnotationBuilder::new()
+            .bounds(bounds)
+            .contents("A longer note that wraps onto several lines")
+            .font(font)
+            .alignment(PdfFreeTextAlignment::Center)
+            .callout(PdfPoints::new(300.0), PdfPoints::new(400.0))
+            .create(page.annotations_mut())?;
+
+        let stamp = PdfStampAnnotationBuilder::new()
+            .bounds(bounds)
+            .label("APPROVED", font)
+            .create(page.annotations_mut())?;
+
+        assert_eq!(stamp.objects().len(), 2);
+
+        let attachment = PdfFileAttachmentAnnotationBuilder::new()
+            .bounds(PdfRect::new_from_values(400.0, 100.0, 420.0, 120.0))
+            .file("notes.txt", b"Supporting notes")
+            .contents("Supporting notes for this page")
+            .icon(PdfFileAttachmentIcon::PushPin)
+            .create(page.annotations_mut())?;
+
+        assert_eq!(attachment.data()?, b"Supporting notes".to_vec());
+        assert_eq!(attachment.attachment().unwrap().name(), "notes.txt");
+
+        let stamp = PdfStampAnnotationBuilder::new()
+            .bounds(bounds)
+            .template(PdfStampTemplate::Confidential, font)
+            .create(page.annotations_mut())?;
+
+        assert_eq!(stamp.objects().len(), 2);
+        assert_eq!(
+            PdfAnnotationDictionaryEdits::read(&stamp, "Name")?,
+            Some(PdfSyntaxObject::name("Confidential"))
+        );
+
+        #[cfg(feature = "image")]
+        {
+            let image = image::DynamicImage::new_rgb8(40, 20);
+
+            let stamp = PdfStampAnnotationBuilder::new()
+                .bounds(bounds)
+                .image(&image)
+                .border_width(PdfPoints::ZERO)
+                .create(page.annotations_mut())?;
+
+            assert_eq!(stamp.objects().len(), 1);
+
+            let stamp = PdfPageStampAnnotation::from_image(page.annotations_mut(), bounds, &image)?;
+
+            assert_eq!(stamp.objects().len(), 1);
+            assert_eq!(stamp.bounds()?, bounds);
+        }
+
+        assert!(matches!(
+            PdfSquareAnnotationBuilder::new().create(page.annotations_mut()),
+            Err(PdfiumError::NoBoundsForAnnotation)
+        ));
+
+        assert_eq!(
+            page.annotations().len(),
+            if cfg!(feature = "image") { 11 } else { 9 }
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_file_attachment_annotations() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let mut attachment = PdfFileAttachmentAnnotationBuilder::new()
+            .bounds(PdfRect::new_from_values(400.0, 100.0, 420.0, 120.0))
+            .file("notes.csv", b"a,b\n1,2\n")
+            .mime_type("text/csv")
+            .description("Measurements")
+            .create(page.annotations_mut())?;
+
+        assert_eq!(attachment.mime_type()?, Some("text/csv".to_string()));
+        assert_eq!(attachment.description()?, Some("Measurements".to_string()));
+
+        let mut plain = page.annotations_mut().create_file_attachment_annotation(
+            "plain.txt",
+            b"Plain",
+            None,
+            None,
+        )?;
+
+        assert_eq!(plain.mime_type()?, None);
+        assert_eq!(plain.description()?, None);
+
+        attachment.set_description(Some("Revised measurements"))?;
+        plain.set_mime_type(Some("text/plain"))?;
+
+        drop(page);
+
+        let document = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        let page = document.pages().get(0)?;
+
+        let attachment = page.annotations().get(0)?;
+
+        let attachment = attachment.as_file_attachment_annotation().unwrap();
+
+        assert_eq!(attachment.data()?, b"a,b\n1,2\n".to_vec());
+        assert_eq!(attachment.mime_type()?, Some("text/csv".to_string()));
+        assert_eq!(
+            attachment.description()?,
+            Some("Revised measurements".to_string())
+        );
+        assert_eq!(
+            PdfAnnotationDictionaryEdits::read(attachment, "Name")?,
+            Some(PdfSyntaxObject::name("Paperclip"))
+        );
+
+        let plain = page.annotations().get(1)?;
+
+        let plain = plain.as_file_attachment_annotation().unwrap();
+
+        assert_eq!(plain.mime_type()?, Some("text/plain".to_string()));
+        assert_eq!(plain.description()?, None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_polygon_annotations() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let triangle = [
+            (PdfPoints::new(100.0), PdfPoints::new(100.0)),
+            (PdfPoints::new(200.0), PdfPoints::new(100.0)),
+            (PdfPoints::new(150.0), PdfPoints::new(180.0)),
+        ];
+
+        assert!(matches!(
+            PdfPolygonAnnotationBuilder::new()
+                .add_vertex(triangle[0].0, triangle[0].1)
+                .add_vertex(triangle[1].0, triangle[1].1)
+                .create(&mut document, 0),
+            Err(PdfiumError::NotEnoughVerticesForAnnotation)
+        ));
+
+        let polygon = triangle
+            .iter()
+            .fold(PdfPolygonAnnotationBuilder::new(), |builder, (x, y)| {
+                builder.add_vertex(*x, *y)
+            })
+            .fill_color(PdfColor::YELLOW)
+            .line_width(PdfPoints::new(2.0))
+            .border_style(PdfAnnotationBorderStyle::Dashed(vec![]))
+            .contents("Area of interest")
+            .create(&mut document, 0)?;
+
+        let polyline = PdfPolygonAnnotationBuilder::polyline()
+            .add_vertex(PdfPoints::new(300.0), PdfPoints::new(300.0))
+            .add_vertex(PdfPoints::new(400.0), PdfPoints::new(350.0))
+            .line_endings(
+                PdfAnnotationLineEnding::None,
+                PdfAnnotationLineEnding::ClosedArrow,
+            )
+            .create(&mut document, 0)?;
+
+        assert_eq!((polygon, polyline), (0, 1));
+
+        {
+            let page = document.pages().get(0)?;
+
+            let annotation = page.annotations().get(polygon)?;
+
+            assert_eq!(annotation.annotation_type(), PdfPageAnnotationType::Polygon);
+            assert_eq!(annotation.contents(), Some("Area of interest".to_string()));
+            assert_eq!(
+                annotation.bounds()?,
+                PdfRect::new_from_values(99.0, 99.0, 181.0, 201.0)
+            );
+
+            assert_eq!(
+                annotation.as_unsupported_annotation().unwrap().vertices(),
+                triangle.to_vec()
+            );
+
+            assert_eq!(
+                page.annotations().get(polyline)?.annotation_type(),
+                PdfPageAnnotationType::Polyline
+            );
+        }
+
+        // Vertices and line endings changed in Pdfium are written when the document is saved.
+
+        let moved = [
+            (PdfPoints::new(10.0), PdfPoints::new(10.0)),
+            (PdfPoints::new(50.0), PdfPoints::new(20.0)),
+        ];
+
+        {
+            let mut page = document.pages().get(0)?;
+
+            let mut annotation = page.annotations_mut().get(polyline)?;
+
+            let annotation = annotation.as_unsupported_annotation_mut().unwrap();
+
+            assert!(matches!(
+                annotation.set_vertices(&moved[..1]),
+                Err(PdfiumError::NotEnoughVerticesForAnnotation)
+            ));
+
+            annotation.set_line_endings(
+                PdfAnnotationLineEnding::Circle,
+                PdfAnnotationLineEnding::OpenArrow,
+            )?;
+
+            annotation.set_vertices(&moved)?;
+
+            assert_eq!(annotation.vertices(), moved.to_vec());
+            assert_eq!(
+                annotation.bounds()?,
+                PdfRect::new_from_values(3.5, 3.5, 26.5, 56.5)
+            );
+        }
+
+        let document = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        let page = document.pages().get(0)?;
+
+        assert_eq!(
+            page.annotations()
+                .get(polyline)?
+                .as_unsupported_annotation()
+                .unwrap()
+                .vertices(),
+            moved.to_vec()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_line_annotations() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        assert!(matches!(
+            PdfLineAnnotationBuilder::new()
+                .start(PdfPoints::new(100.0), PdfPoints::new(100.0))
+                .create(&mut document, 0),
+            Err(PdfiumError::NotEnoughVerticesForAnnotation)
+        ));
+
+        let font = document.fonts_mut().helvetica();
+
+        let index = PdfLineAnnotationBuilder::new()
+            .start(PdfPoints::new(100.0), PdfPoints::new(100.0))
+            .end(PdfPoints::new(244.0), PdfPoints::new(100.0))
+            .line_endings(
+                PdfAnnotationLineEnding::ClosedArrow,
+                PdfAnnotationLineEnding::ClosedArrow,
+            )
+            .leader_line(
+                PdfPoints::new(-20.0),
+                PdfPoints::new(5.0),
+                PdfPoints::new(2.0),
+            )
+            .measure(PdfLineMeasure::new("in", 1.0 / 72.0).ratio("1 in = 1 in"))
+            .caption_font(font)
+            .create(&mut document, 0)?;
+
+        {
+            let page = document.pages().get(0)?;
+
+            let annotation = page.annotations().get(index)?;
+
+            assert_eq!(annotation.annotation_type(), PdfPageAnnotationType::Line);
+            assert_eq!(annotation.contents(), Some("2.00 in".to_string()));
+
+            let line = annotation.as_unsupported_annotation().unwrap();
+
+            assert_eq!(
+                line.line(),
+                Some((
+                    (PdfPoints::new(100.0), PdfPoints::new(100.0)),
+                    (PdfPoints::new(244.0), PdfPoints::new(100.0))
+                ))
+            );
+            assert_eq!(line.leader_line_length(), Some(PdfPoints::new(-20.0)));
+            assert_eq!(line.leader_line_extension(), Some(PdfPoints::new(5.0)));
+            assert_eq!(line.leader_line_offset(), Some(PdfPoints::new(2.0)));
+        }
+
+        // End points and leader lines changed in Pdfium are written when the document is saved.
+
+        {
+            let mut page = document.pages().get(0)?;
+
+            let mut annotation = page.annotations_mut().get(index)?;
+
+            let line = annotation.as_unsupported_annotation_mut().unwrap();
+
+            line.set_line(
+                (PdfPoints::new(10.0), PdfPoints::new(10.0)),
+                (PdfPoints::new(10.0), PdfPoints::new(50.0)),
+            )?;
+
+            line.set_leader_line(PdfPoints::new(10.0), PdfPoints::ZERO, PdfPoints::ZERO)?;
+
+            assert_eq!(line.leader_line_length(), Some(PdfPoints::new(10.0)));
+        }
+
+        let document = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        let page = document.pages().get(0)?;
+
+        let annotation = page.annotations().get(index)?;
+
+        let line = annotation.as_unsupported_annotation().unwrap();
+
+        assert_eq!(
+            line.line(),
+            Some((
+                (PdfPoints::new(10.0), PdfPoints::new(10.0)),
+                (PdfPoints::new(10.0), PdfPoints::new(50.0))
+            ))
+        );
+        assert_eq!(line.leader_line_length(), Some(PdfPoints::new(10.0)));
+
+        Ok(())
+    }
+}
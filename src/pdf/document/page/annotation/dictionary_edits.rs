@@ -0,0 +1,830 @@
+//! Records changes to annotation dictionary entries that Pdfium cannot write itself, such as
+//! the vertices of a polygon annotation, and applies them to a document when it is saved.
+//! Changes can also be made to the file specification and embedded file stream of a file
+//! attachment annotation.
+//!
+//! Changes are recorded against the annotation's unique name, its `/NM` entry, so that they
+//! can be applied to the correct annotation however the page's annotations are rearranged
+//! before the document is saved. An annotation without a unique name is given one.
+
+use crate::bindgen::FPDF_DOCUMENT;
+use crate::error::PdfiumError;
+use crate::pdf::document::attachment::details::find_embedded_file;
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotations::PdfPageAnnotationIndex;
+use crate::pdf::document::page::index_cache::PdfPageIndexCache;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::save_document_to_bytes;
+use crate::pdf::document::syntax::{
+    decode_pdf_string, PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject,
+};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The changes recorded against the annotations of all open documents.
+static ANNOTATION_DICTIONARY_EDITS: Lazy<Mutex<PdfAnnotationDictionaryEdits>> =
+    Lazy::new(|| Mutex::new(PdfAnnotationDictionaryEdits::new()));
+
+/// A counter ensuring that every unique name assigned during a session is distinct.
+static NEXT_ANNOTATION_NAME: AtomicUsize = AtomicUsize::new(0);
+
+/// A single change to an entry in the dictionary of the annotation with the given unique name.
+#[derive(Debug, Clone, PartialEq)]
+struct PdfAnnotationDictionaryEdit {
+    name: String,
+    target: PdfAnnotationDictionaryEditTarget,
+    key: String,
+
+    // None removes the entry.
+    value: Option<PdfSyntaxObject>,
+
+    kind: PdfAnnotationDictionaryEditKind,
+}
+
+/// The dictionary containing the entry changed by a [PdfAnnotationDictionaryEdit].
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub(crate) enum PdfAnnotationDictionaryEditTarget {
+    /// The dictionary of the annotation itself.
+    Annotation,
+
+    /// The file specification dictionary referenced by the `/FS` entry of a file
+    /// attachment annotation.
+    FileSpecification,
+
+    /// The dictionary of the embedded file stream referenced by the `/EF` entry of the file
+    /// specification of a file attachment annotation. The stream's data is left unchanged.
+    EmbeddedFile,
+}
+
+/// The way in which the value of a [PdfAnnotationDictionaryEdit] is written to the file.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum PdfAnnotationDictionaryEditKind {
+    /// The value is written unchanged.
+    Value,
+
+    /// The value is a destination array whose first element is the zero-based index of the
+    /// destination page, replaced by a reference to that page when the edit is applied.
+    PageDestination,
+
+    /// The value is the unique name of another annotation, replaced by a reference to that
+    /// annotation when the edit is applied.
+    AnnotationReference,
+}
+
+pub(crate) struct PdfAnnotationDictionaryEdits {
+    edits_by_document: HashMap<FPDF_DOCUMENT, Vec<PdfAnnotationDictionaryEdit>>,
+}
+
+impl PdfAnnotationDictionaryEdits {
+    #[inline]
+    fn new() -> Self {
+        PdfAnnotationDictionaryEdits {
+            edits_by_document: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    fn lock() -> MutexGuard<'static, PdfAnnotationDictionaryEdits> {
+        ANNOTATION_DICTIONARY_EDITS.lock().unwrap()
+    }
+
+    /// Records that the entry with the given key in the dictionary of the given annotation
+    /// should be set to the given value, or removed if `None` is given, when the document
+    /// containing the annotation is next saved. Replaces any change previously recorded
+    /// for the same entry.
+    pub(crate) fn set<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &mut T,
+        key: &str,
+        value: Option<PdfSyntaxObject>,
+    ) -> Result<(), PdfiumError> {
+        Self::set_in(
+            annotation,
+            PdfAnnotationDictionaryEditTarget::Annotation,
+            key,
+            value,
+        )
+    }
+
+    /// Records that the entry with the given key in the given target dictionary of the given
+    /// annotation should be set to the given value, or removed if `None` is given, when the
+    /// document containing the annotation is next saved. If the annotation has no such
+    /// dictionary at that time, the entry is left unchanged.
+    pub(crate) fn set_in<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &mut T,
+        target: PdfAnnotationDictionaryEditTarget,
+        key: &str,
+        value: Option<PdfSyntaxObject>,
+    ) -> Result<(), PdfiumError> {
+        Self::record(
+            annotation,
+            target,
+            key,
+            value,
+            PdfAnnotationDictionaryEditKind::Value,
+        )
+    }
+
+    /// Records that the entry with the given key in the dictionary of the given annotation
+    /// should be set to a destination displaying the whole of the page at the given index
+    /// when the document containing the annotation is next saved. The page index is resolved
+    /// against the document's pages at that time; if there is no page at the given index,
+    /// the entry is left unchanged.
+    ///
+    /// The recorded change is returned from [PdfAnnotationDictionaryEdits::get()] as
+    /// a destination array whose first element is the page index.
+    pub(crate) fn set_page_destination<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &mut T,
+        key: &str,
+        page_index: PdfPageIndex,
+    ) -> Result<(), PdfiumError> {
+        Self::record(
+            annotation,
+            PdfAnnotationDictionaryEditTarget::Annotation,
+            key,
+            Some(PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Number(page_index as f64),
+                PdfSyntaxObject::name("Fit"),
+            ])),
+            PdfAnnotationDictionaryEditKind::PageDestination,
+        )
+    }
+
+    /// Records that the entry with the given key in the dictionary of the given annotation
+    /// should be set to a reference to the given target annotation when the document
+    /// containing both annotations is next saved. If the target annotation is no longer in
+    /// the document at that time, the entry is left unchanged.
+    ///
+    /// The target annotation is given a unique name if it does not already have one.
+    /// The recorded change can be retrieved using
+    /// [PdfAnnotationDictionaryEdits::get_annotation_reference()].
+    pub(crate) fn set_annotation_reference<
+        'a,
+        'b,
+        T: PdfPageAnnotationPrivate<'a> + ?Sized,
+        U: PdfPageAnnotationPrivate<'b> + ?Sized,
+    >(
+        annotation: &mut T,
+        key: &str,
+        target: &mut U,
+    ) -> Result<(), PdfiumError> {
+        let target = unique_name(target)?;
+
+        Self::record(
+            annotation,
+            PdfAnnotationDictionaryEditTarget::Annotation,
+            key,
+            Some(PdfSyntaxObject::text(&target)),
+            PdfAnnotationDictionaryEditKind::AnnotationReference,
+        )
+    }
+
+    /// Returns the change recorded for the entry with the given key in the dictionary of
+    /// the given annotation, if that entry is to be set to a reference to another annotation
+    /// or removed: `Some(Some(name))`, where `name` is the unique name of the referenced
+    /// annotation, or `Some(None)` if the entry is to be removed.
+    pub(crate) fn get_annotation_reference<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &T,
+        key: &str,
+    ) -> Option<Option<String>> {
+        let name = annotation.name_impl()?;
+
+        Self::lock()
+            .edits_by_document
+            .get(&annotation.document_handle())?
+            .iter()
+            .find(|edit| edit.matches(&name, PdfAnnotationDictionaryEditTarget::Annotation, key))
+            .and_then(|edit| match (edit.kind, edit.value.as_ref()) {
+                (_, None) => Some(None),
+                (
+                    PdfAnnotationDictionaryEditKind::AnnotationReference,
+                    Some(PdfSyntaxObject::String(target)),
+                ) => Some(Some(decode_pdf_string(target))),
+                _ => None,
+            })
+    }
+
+    fn record<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &mut T,
+        target: PdfAnnotationDictionaryEditTarget,
+        key: &str,
+        value: Option<PdfSyntaxObject>,
+        kind: PdfAnnotationDictionaryEditKind,
+    ) -> Result<(), PdfiumError> {
+        let name = unique_name(annotation)?;
+
+        let mut edits = Self::lock();
+
+        let edits = edits
+            .edits_by_document
+            .entry(annotation.document_handle())
+            .or_default();
+
+        let edit = PdfAnnotationDictionaryEdit {
+            name,
+            target,
+            key: key.to_string(),
+            value,
+            kind,
+        };
+
+        match edits
+            .iter_mut()
+            .find(|existing| existing.matches(&edit.name, edit.target, &edit.key))
+        {
+            Some(existing) => *existing = edit,
+            None => edits.push(edit),
+        }
+
+        Ok(())
+    }
+
+    /// Returns the change recorded for the entry with the given key in the dictionary of
+    /// the given annotation, if any: `Some(None)` if the entry is to be removed.
+    pub(crate) fn get<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &T,
+        key: &str,
+    ) -> Option<Option<PdfSyntaxObject>> {
+        Self::get_in(
+            annotation,
+            PdfAnnotationDictionaryEditTarget::Annotation,
+            key,
+        )
+    }
+
+    /// Returns the change recorded for the entry with the given key in the given target
+    /// dictionary of the given annotation, if any: `Some(None)` if the entry is to be removed.
+    pub(crate) fn get_in<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &T,
+        target: PdfAnnotationDictionaryEditTarget,
+        key: &str,
+    ) -> Option<Option<PdfSyntaxObject>> {
+        let name = annotation.name_impl()?;
+
+        Self::lock()
+            .edits_by_document
+            .get(&annotation.document_handle())?
+            .iter()
+            .find(|edit| edit.matches(&name, target, key))
+            .map(|edit| edit.value.clone())
+    }
+
+    /// Returns the value of the entry with the given key in the dictionary of the given
+    /// annotation, for entries Pdfium cannot read, such as booleans and arrays. A change
+    /// recorded for the entry takes precedence; otherwise, a copy of the annotation's document
+    /// is saved to memory and the entry is read from the copy. Returns `None` if the entry
+    /// does not exist or the copy cannot be read.
+    pub(crate) fn read<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &T,
+        key: &str,
+    ) -> Result<Option<PdfSyntaxObject>, PdfiumError> {
+        Self::read_in(
+            annotation,
+            PdfAnnotationDictionaryEditTarget::Annotation,
+            key,
+        )
+    }
+
+    /// Returns the value of the entry with the given key in the given target dictionary of
+    /// the given annotation, in the same way as [PdfAnnotationDictionaryEdits::read()].
+    pub(crate) fn read_in<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+        annotation: &T,
+        target: PdfAnnotationDictionaryEditTarget,
+        key: &str,
+    ) -> Result<Option<PdfSyntaxObject>, PdfiumError> {
+        if let Some(value) = Self::get_in(annotation, target, key) {
+            return Ok(value);
+        }
+
+        let page_handle = *annotation.objects_impl().get_page_handle();
+
+        let page_index =
+            PdfPageIndexCache::get_index_for_page(annotation.document_handle(), page_handle)
+                .ok_or(PdfiumError::SourcePageIndexNotInCache)?;
+
+        let annotation_index = annotation
+            .bindings()
+            .FPDFPage_GetAnnotIndex(page_handle, annotation.handle());
+
+        if annotation_index < 0 {
+            return Ok(None);
+        }
+
+        Ok(read_saved_entry(
+            &save_document_to_bytes(annotation.document_handle(), annotation.bindings())?,
+            page_index,
+            annotation_index as PdfPageAnnotationIndex,
+            target,
+            key,
+        ))
+    }
+
+    /// Returns `true` if changes have been recorded for any annotation in the given document.
+    #[inline]
+    pub(crate) fn is_modified(document: FPDF_DOCUMENT) -> bool {
+        Self::lock()
+            .edits_by_document
+            .get(&document)
+            .map_or(false, |edits| !edits.is_empty())
+    }
+
+    /// Applies the changes recorded for the annotations in the given document to the given
+    /// saved copy of that document, returning the updated file. Changes to annotations that
+    /// are no longer in the document are ignored. Returns
+    /// [PdfiumError::DocumentUpdateUnsupported] if the file is encrypted or its page tree
+    /// cannot be read.
+    pub(crate) fn write_to_file(
+        document: FPDF_DOCUMENT,
+        file: Vec<u8>,
+    ) -> Result<Vec<u8>, PdfiumError> {
+        let edits = match Self::lock().edits_by_document.get(&document) {
+            Some(edits) if !edits.is_empty() => edits.clone(),
+            _ => return Ok(file),
+        };
+
+        write_annotation_edits(file, &edits)
+    }
+
+    /// Discards the changes recorded for the annotations in the given document,
+    /// which is being closed.
+    #[inline]
+    pub(crate) fn close_document(document: FPDF_DOCUMENT) {
+        Self::lock().edits_by_document.remove(&document);
+    }
+}
+
+impl PdfAnnotationDictionaryEdit {
+    #[inline]
+    fn matches(&self, name: &str, target: PdfAnnotationDictionaryEditTarget, key: &str) -> bool {
+        self.name == name && self.target == target && self.key == key
+    }
+}
+
+unsafe impl Send for PdfAnnotationDictionaryEdits {}
+
+unsafe impl Sync for PdfAnnotationDictionaryEdits {}
+
+/// Returns the unique name of the given annotation, assigning it a new unique name
+/// if it does not already have one.
+pub(crate) fn unique_name<'a, T: PdfPageAnnotationPrivate<'a> + ?Sized>(
+    annotation: &mut T,
+) -> Result<String, PdfiumError> {
+    if let Some(name) = annotation.name_impl() {
+        return Ok(name);
+    }
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos());
+
+    let name = format!(
+        "pdfium-render-{:x}-{}",
+        timestamp,
+        NEXT_ANNOTATION_NAME.fetch_add(1, Ordering::Relaxed)
+    );
+
+    annotation.set_string_value("NM", &name)?;
+
+    Ok(name)
+}
+
+/// Returns the value of the entry with the given key in the given target dictionary of the
+/// annotation at the given index in the annotations of the page at the given index in the
+/// given file, or `None` if there is no such entry or the file cannot be read.
+fn read_saved_entry(
+    file: &[u8],
+    page_index: PdfPageIndex,
+    annotation_index: PdfPageAnnotationIndex,
+    target: PdfAnnotationDictionaryEditTarget,
+    key: &str,
+) -> Option<PdfSyntaxObject> {
+    let parsed = PdfSyntaxFile::parse(file)?;
+
+    let page = parsed.resolve(parsed.page_references()?.get(page_index as usize)?);
+
+    let annotation = match parsed.resolve(page.get("Annots")?) {
+        PdfSyntaxObject::Array(annotations) => parsed.resolve(annotations.get(annotation_index)?),
+        _ => return None,
+    };
+
+    let dictionary = match target {
+        PdfAnnotationDictionaryEditTarget::Annotation => annotation,
+        PdfAnnotationDictionaryEditTarget::FileSpecification => {
+            parsed.resolve(annotation.get("FS")?)
+        }
+        PdfAnnotationDictionaryEditTarget::EmbeddedFile => {
+            let specification = parsed.resolve(annotation.get("FS")?);
+
+            parsed.resolve(&PdfSyntaxObject::Reference(
+                find_embedded_file(&parsed, &specification)?,
+                0,
+            ))
+        }
+    };
+
+    Some(parsed.resolve(dictionary.get(key)?))
+}
+
+/// Sets the entry with the given key in the given dictionary to the given value, or removes
+/// it if `None` is given.
+fn apply_value(dictionary: &mut PdfSyntaxObject, key: &str, value: Option<&PdfSyntaxObject>) {
+    match value {
+        Some(value) => dictionary.set(key, value.clone()),
+        None => dictionary.remove(key),
+    }
+}
+
+/// Appends an incremental update to the given file applying the given changes to the
+/// dictionaries of the annotations with the given unique names.
+fn write_annotation_edits(
+    file: Vec<u8>,
+    edits: &[PdfAnnotationDictionaryEdit],
+) -> Result<Vec<u8>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if parsed
+        .trailer
+        .as_ref()
+        .map_or(true, |trailer| trailer.get("Encrypt").is_some())
+    {
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    let pages = parsed
+        .page_references()
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    // Only annotations stored as indirect objects can be updated. Pdfium always stores
+    // the annotations it creates as indirect objects.
+
+    let mut references_by_name = HashMap::new();
+
+    for page in pages.iter() {
+        let annotations = match parsed.resolve(page).get("Annots") {
+            Some(annotations) => parsed.resolve(annotations),
+            None => continue,
+        };
+
+        if let PdfSyntaxObject::Array(annotations) = annotations {
+            for annotation in annotations.iter() {
+                if let PdfSyntaxObject::Reference(..) = annotation {
+                    if let Some(PdfSyntaxObject::String(name)) =
+                        parsed.resolve(annotation).get("NM")
+                    {
+                        references_by_name
+                            .entry(decode_pdf_string(name))
+                            .or_insert_with(|| annotation.clone());
+                    }
+                }
+            }
+        }
+    }
+
+    let mut is_modified = false;
+
+    // Changes to embedded file streams are collected and written once all changes have been
+    // applied, since each stream must be rewritten together with its data.
+
+    let mut embedded_files = HashMap::new();
+
+    for edit in edits.iter() {
+        let number = match references_by_name.get(&edit.name) {
+            Some(PdfSyntaxObject::Reference(number, _)) => *number,
+            _ => continue,
+        };
+
+        let mut annotation = match update.get(number) {
+            Some(annotation) => annotation,
+            None => continue,
+        };
+
+        match edit.target {
+            PdfAnnotationDictionaryEditTarget::Annotation => {}
+            PdfAnnotationDictionaryEditTarget::FileSpecification => {
+                let is_applied = update
+                    .modify(number, &["FS"], &mut |specification| match specification {
+                        PdfSyntaxObject::Dictionary(_) => {
+                            apply_value(specification, &edit.key, edit.value.as_ref());
+
+                            Some(())
+                        }
+                        _ => None,
+                    })
+                    .is_some();
+
+                is_modified |= is_applied;
+
+                continue;
+            }
+            PdfAnnotationDictionaryEditTarget::EmbeddedFile => {
+                let stream = annotation
+                    .get("FS")
+                    .map(|specification| update.resolve(specification))
+                    .and_then(|specification| find_embedded_file(&parsed, &specification));
+
+                if let Some(stream) = stream {
+                    let dictionary = embedded_files
+                        .entry(stream)
+                        .or_insert_with(|| parsed.resolve(&PdfSyntaxObject::Reference(stream, 0)));
+
+                    apply_value(dictionary, &edit.key, edit.value.as_ref());
+                }
+
+                continue;
+            }
+        }
+
+        match edit.value.as_ref() {
+            Some(PdfSyntaxObject::Array(destination))
+                if edit.kind == PdfAnnotationDictionaryEditKind::PageDestination =>
+            {
+                let page = match destination.first() {
+                    Some(PdfSyntaxObject::Number(index)) => match pages.get(*index as usize) {
+                        Some(page) => page.clone(),
+                        None => continue,
+                    },
+                    _ => continue,
+                };
+
+                let mut destination = destination.clone();
+
+                destination[0] = page;
+
+                annotation.set(&edit.key, PdfSyntaxObject::Array(destination));
+            }
+            Some(PdfSyntaxObject::String(target))
+                if edit.kind == PdfAnnotationDictionaryEditKind::AnnotationReference =>
+            {
+                match references_by_name.get(&decode_pdf_string(target)) {
+                    Some(reference) => annotation.set(&edit.key, reference.clone()),
+                    None => continue,
+                }
+            }
+            value => apply_value(&mut annotation, &edit.key, value),
+        }
+
+        update.set(number, annotation);
+
+        is_modified = true;
+    }
+
+    for (number, dictionary) in embedded_files.into_iter() {
+        let data = match parsed
+            .objects
+            .get(&number)
+            .and_then(|stream| stream.stream_range.clone())
+            .and_then(|range| file.get(range))
+        {
+            Some(data) => data.to_vec(),
+            None => continue,
+        };
+
+        update.set_stream(number, dictionary, data);
+
+        is_modified = true;
+    }
+
+    if is_modified {
+        Ok(update.write(file))
+    } else {
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::annotation::dictionary_edits::*;
+
+    const ANNOTATED_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /Annots [4 0 R 5 0 R] >>\nendobj\n\
+        4 0 obj\n<< /Type /Annot /Subtype /Polygon /NM (first) /Vertices [0 0 10 0 10 10] >>\nendobj\n\
+        5 0 obj\n<< /Type /Annot /Subtype /Square /NM (second) /BS << /W 1 >> >>\nendobj\n\
+        xref\n0 6\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 6 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    const FILE_ATTACHMENT_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /Annots [4 0 R] >>\nendobj\n\
+        4 0 obj\n<< /Type /Annot /Subtype /FileAttachment /NM (attachment) /FS 5 0 R >>\nendobj\n\
+        5 0 obj\n<< /Type /Filespec /F (a.txt) /Desc (Old) /EF << /F 6 0 R >> >>\nendobj\n\
+        6 0 obj\n<< /Type /EmbeddedFile /Length 5 >>\nstream\nHello\nendstream\nendobj\n\
+        xref\n0 7\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 7 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    #[test]
+    fn test_write_annotation_edits() {
+        let edits = vec![
+            PdfAnnotationDictionaryEdit {
+                name: "first".to_string(),
+                target: PdfAnnotationDictionaryEditTarget::Annotation,
+                key: "Vertices".to_string(),
+                value: Some(PdfSyntaxObject::Array(vec![
+                    PdfSyntaxObject::Number(1.0),
+                    PdfSyntaxObject::Number(2.0),
+                ])),
+                kind: PdfAnnotationDictionaryEditKind::Value,
+            },
+            PdfAnnotationDictionaryEdit {
+                name: "second".to_string(),
+                target: PdfAnnotationDictionaryEditTarget::Annotation,
+                key: "BS".to_string(),
+                value: None,
+                kind: PdfAnnotationDictionaryEditKind::Value,
+            },
+            PdfAnnotationDictionaryEdit {
+                name: "second".to_string(),
+                target: PdfAnnotationDictionaryEditTarget::Annotation,
+                key: "Dest".to_string(),
+                value: Some(PdfSyntaxObject::Array(vec![
+                    PdfSyntaxObject::Number(0.0),
+                    PdfSyntaxObject::name("Fit"),
+                ])),
+                kind: PdfAnnotationDictionaryEditKind::PageDestination,
+            },
+            PdfAnnotationDictionaryEdit {
+                name: "first".to_string(),
+                target: PdfAnnotationDictionaryEditTarget::Annotation,
+                key: "IRT".to_string(),
+                value: Some(PdfSyntaxObject::text("second")),
+                kind: PdfAnnotationDictionaryEditKind::AnnotationReference,
+            },
+            PdfAnnotationDictionaryEdit {
+                name: "second".to_string(),
+                target: PdfAnnotationDictionaryEditTarget::Annotation,
+                key: "IRT".to_string(),
+                value: Some(PdfSyntaxObject::text("missing")),
+                kind: PdfAnnotationDictionaryEditKind::AnnotationReference,
+            },
+            PdfAnnotationDictionaryEdit {
+                name: "missing".to_string(),
+                target: PdfAnnotationDictionaryEditTarget::Annotation,
+                key: "BS".to_string(),
+                value: None,
+                kind: PdfAnnotationDictionaryEditKind::Value,
+            },
+        ];
+
+        let file = write_annotation_edits(ANNOTATED_PDF.to_vec(), &edits).unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        assert_eq!(
+            parsed
+                .resolve(&PdfSyntaxObject::Reference(4, 0))
+                .get("Vertices"),
+            Some(&PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Number(1.0),
+                PdfSyntaxObject::Number(2.0),
+            ]))
+        );
+
+        // Annotation references are resolved to a reference to the annotation with the
+        // given unique name, and are not applied if there is no such annotation.
+
+        assert_eq!(
+            parsed.resolve(&PdfSyntaxObject::Reference(4, 0)).get("IRT"),
+            Some(&PdfSyntaxObject::Reference(5, 0))
+        );
+
+        let second = parsed.resolve(&PdfSyntaxObject::Reference(5, 0));
+
+        assert_eq!(second.get("BS"), None);
+        assert_eq!(second.get("IRT"), None);
+
+        // Page destinations are resolved to a reference to the destination page.
+
+        assert_eq!(
+            second.get("Dest"),
+            Some(&PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Reference(3, 0),
+                PdfSyntaxObject::name("Fit"),
+            ]))
+        );
+        assert_eq!(
+            second.get("Subtype"),
+            Some(&PdfSyntaxObject::name("Square"))
+        );
+
+        // Entries are read from the dictionary of the annotation at the given index.
+        // Indirect values are resolved.
+
+        assert_eq!(
+            read_saved_entry(
+                &file,
+                0,
+                1,
+                PdfAnnotationDictionaryEditTarget::Annotation,
+                "IRT"
+            ),
+            None
+        );
+        assert_eq!(
+            read_saved_entry(
+                &file,
+                0,
+                0,
+                PdfAnnotationDictionaryEditTarget::Annotation,
+                "IRT"
+            )
+            .and_then(|parent| parent.get("NM").cloned()),
+            Some(PdfSyntaxObject::text("second"))
+        );
+        assert_eq!(
+            read_saved_entry(
+                &file,
+                0,
+                2,
+                PdfAnnotationDictionaryEditTarget::Annotation,
+                "NM"
+            ),
+            None
+        );
+        assert_eq!(
+            read_saved_entry(
+                &file,
+                1,
+                0,
+                PdfAnnotationDictionaryEditTarget::Annotation,
+                "NM"
+            ),
+            None
+        );
+
+        // A file is returned unchanged if none of its annotations are edited.
+
+        assert_eq!(
+            write_annotation_edits(ANNOTATED_PDF.to_vec(), &edits[5..]).unwrap(),
+            ANNOTATED_PDF
+        );
+    }
+
+    #[test]
+    fn test_write_file_attachment_edits() {
+        let edit = |target, key: &str, value| PdfAnnotationDictionaryEdit {
+            name: "attachment".to_string(),
+            target,
+            key: key.to_string(),
+            value,
+            kind: PdfAnnotationDictionaryEditKind::Value,
+        };
+
+        let edits = vec![
+            edit(
+                PdfAnnotationDictionaryEditTarget::FileSpecification,
+                "Desc",
+                Some(PdfSyntaxObject::text("Supporting notes")),
+            ),
+            edit(
+                PdfAnnotationDictionaryEditTarget::EmbeddedFile,
+                "Subtype",
+                Some(PdfSyntaxObject::name("text/plain")),
+            ),
+            edit(
+                PdfAnnotationDictionaryEditTarget::Annotation,
+                "Name",
+                Some(PdfSyntaxObject::name("Paperclip")),
+            ),
+        ];
+
+        let file = write_annotation_edits(FILE_ATTACHMENT_PDF.to_vec(), &edits).unwrap();
+
+        let read = |target, key| read_saved_entry(&file, 0, 0, target, key);
+
+        assert_eq!(
+            read(PdfAnnotationDictionaryEditTarget::FileSpecification, "Desc"),
+            Some(PdfSyntaxObject::text("Supporting notes"))
+        );
+        assert_eq!(
+            read(PdfAnnotationDictionaryEditTarget::EmbeddedFile, "Subtype"),
+            Some(PdfSyntaxObject::name("text/plain"))
+        );
+
+        // Entries with the same key in different dictionaries are kept apart.
+
+        assert_eq!(
+            read(PdfAnnotationDictionaryEditTarget::Annotation, "Subtype"),
+            Some(PdfSyntaxObject::name("FileAttachment"))
+        );
+        assert_eq!(
+            read(PdfAnnotationDictionaryEditTarget::Annotation, "Name"),
+            Some(PdfSyntaxObject::name("Paperclip"))
+        );
+
+        // The embedded file stream is rewritten together with its data.
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let stream = &parsed.objects[&6];
+
+        assert!(stream.is_stream);
+        assert_eq!(&file[stream.stream_range.clone().unwrap()], b"Hello");
+    }
+}
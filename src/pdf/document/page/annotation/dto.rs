@@ -0,0 +1,267 @@
+//! Defines the [PdfPageAnnotationDto] struct, a plain data representation of a single
+//! `PdfPageAnnotation` that can be serialized and deserialized using `serde`.
+//!
+//! This module is only available when this crate's `serde` feature is enabled.
+
+use crate::bindgen::{
+    FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color,
+    FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor, FS_POINTF,
+};
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::page::annotation::appearance::{generate_normal_appearance, ink_strokes};
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::{
+    PdfPageAnnotation, PdfPageAnnotationCommon, PdfPageAnnotationType,
+};
+use crate::pdf::document::xfdf::exported_color;
+use crate::pdf::quad_points::PdfQuadPoints;
+use crate::pdf::rect::PdfRect;
+use serde::{Deserialize, Serialize};
+
+/// A plain data representation of a single `PdfPageAnnotation`, suitable for serializing
+/// to JSON or any other format supported by `serde`, for example to synchronize annotations
+/// between a PDF viewer and a server.
+///
+/// Create a [PdfPageAnnotationDto] from an existing annotation using the
+/// `PdfPageAnnotation::to_dto()` function, and create a new annotation from a
+/// [PdfPageAnnotationDto] using the `PdfPageAnnotations::create_from_dto()` function.
+///
+/// Coordinates are measured in points from the bottom left corner of the page. Colors
+/// are hexadecimal ARGB quadruplets with a leading hash symbol, as accepted by
+/// `PdfColor::from_hex()`; the alpha channel carries the annotation's opacity. Dates are
+/// PDF date strings, for example `D:20240131120000Z`, as returned by
+/// `PdfPageAnnotationCommon::creation_date()`.
+///
+/// This struct is only available when this crate's `serde` feature is enabled.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PdfPageAnnotationDto {
+    /// The subtype of the annotation.
+    pub subtype: PdfPageAnnotationType,
+
+    /// The unique name of the annotation on its page, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// The bounding box of the annotation, as `[left, bottom, right, top]`.
+    pub rect: [f32; 4],
+
+    /// The attachment points of the annotation, one quadrilateral per marked up area, each
+    /// given as `[x1, y1, x2, y2, x3, y3, x4, y4]` in the vertex order used by [PdfQuadPoints].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub quads: Vec<[f32; 8]>,
+
+    /// The strokes of an ink annotation, each given as a list of `[x, y]` points.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ink_strokes: Vec<Vec<[f32; 2]>>,
+
+    /// The stroke color of the annotation, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stroke_color: Option<String>,
+
+    /// The interior fill color of the annotation, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fill_color: Option<String>,
+
+    /// The text displayed for the annotation, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contents: Option<String>,
+
+    /// The name of the author of the annotation, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// The date and time when the annotation was created, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub creation_date: Option<String>,
+
+    /// The date and time when the annotation was last modified, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modification_date: Option<String>,
+}
+
+impl PdfPageAnnotationDto {
+    /// Creates a new [PdfPageAnnotationDto] describing the given annotation.
+    pub(crate) fn from_annotation(annotation: &PdfPageAnnotation) -> Result<Self, PdfiumError> {
+        let bounds = annotation.bounds()?;
+
+        let quads = if annotation.has_attachment_points() {
+            annotation
+                .attachment_points()
+                .iter()
+                .map(|quad| {
+                    [
+                        quad.x1.value,
+                        quad.y1.value,
+                        quad.x2.value,
+                        quad.y2.value,
+                        quad.x3.value,
+                        quad.y3.value,
+                        quad.x4.value,
+                        quad.y4.value,
+                    ]
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let ink_strokes = if annotation.annotation_type() == PdfPageAnnotationType::Ink {
+            ink_strokes(annotation)
+                .iter()
+                .map(|stroke| stroke.iter().map(|(x, y)| [x.value, y.value]).collect())
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        Ok(PdfPageAnnotationDto {
+            subtype: annotation.annotation_type(),
+            name: annotation.name(),
+            rect: [
+                bounds.left.value,
+                bounds.bottom.value,
+                bounds.right.value,
+                bounds.top.value,
+            ],
+            quads,
+            ink_strokes,
+            stroke_color: exported_color(
+                annotation,
+                "C",
+                FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color,
+            )
+            .map(|color| color.to_hex_specification(true)),
+            fill_color: exported_color(
+                annotation,
+                "IC",
+                FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor,
+            )
+            .map(|color| color.to_hex_specification(true)),
+            contents: annotation.contents(),
+            author: annotation.creator(),
+            creation_date: annotation.creation_date(),
+            modification_date: annotation.modification_date(),
+        })
+    }
+
+    /// Applies the properties of this [PdfPageAnnotationDto] to the given newly created
+    /// annotation, then generates an appearance stream for it if possible.
+    pub(crate) fn apply<'a, T: PdfPageAnnotationPrivate<'a>>(
+        &self,
+        mut annotation: T,
+    ) -> Result<T, PdfiumError> {
+        let [left, bottom, right, top] = self.rect;
+
+        annotation.set_bounds(PdfRect::new_from_values(
+            bottom.min(top),
+            left.min(right),
+            bottom.max(top),
+            left.max(right),
+        ))?;
+
+        // Colors must be set before an appearance stream is generated, since Pdfium does not
+        // allow changing the color of an annotation that has an appearance stream.
+
+        if let Some(color) = self.stroke_color.as_deref() {
+            annotation.set_stroke_color(PdfColor::from_hex(color)?)?;
+        }
+
+        if let Some(color) = self.fill_color.as_deref() {
+            annotation.set_fill_color(PdfColor::from_hex(color)?)?;
+        }
+
+        if annotation.has_attachment_points() {
+            for quad in self.quads.iter() {
+                annotation
+                    .attachment_points_mut_impl()
+                    .create_attachment_point_at_end(PdfQuadPoints::new_from_values(
+                        quad[0], quad[1], quad[2], quad[3], quad[4], quad[5], quad[6], quad[7],
+                    ))?;
+            }
+        }
+
+        if self.subtype == PdfPageAnnotationType::Ink {
+            for stroke in self.ink_strokes.iter().filter(|stroke| !stroke.is_empty()) {
+                let points = stroke
+                    .iter()
+                    .map(|[x, y]| FS_POINTF { x: *x, y: *y })
+                    .collect::<Vec<_>>();
+
+                if annotation.bindings().FPDFAnnot_AddInkStroke(
+                    annotation.handle(),
+                    points.as_ptr(),
+                    points.len(),
+                ) < 0
+                {
+                    return Err(PdfiumError::PdfiumLibraryInternalError(
+                        PdfiumInternalError::Unknown,
+                    ));
+                }
+            }
+        }
+
+        if let Some(contents) = self.contents.as_deref() {
+            annotation.set_contents(contents)?;
+        }
+
+        if let Some(author) = self.author.as_deref() {
+            annotation.set_creator(author)?;
+        }
+
+        if let Some(name) = self.name.as_deref() {
+            annotation.set_string_value("NM", name)?;
+        }
+
+        if let Some(date) = self.creation_date.as_deref() {
+            annotation.set_string_value("CreationDate", date)?;
+        }
+
+        generate_normal_appearance(&annotation)?;
+
+        // The modification date is set last, since changing any other property of an annotation
+        // resets its modification date to the current time.
+
+        if let Some(date) = self.modification_date.as_deref() {
+            annotation.set_string_value("M", date)?;
+        }
+
+        Ok(annotation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_dto_round_trip() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let original = PdfHighlightAnnotationBuilder::new()
+            .add_rect(PdfRect::new_from_values(700.0, 50.0, 712.0, 150.0))
+            .color(PdfColor::GREEN)
+            .contents("Check this")
+            .author("Reviewer")
+            .create(page.annotations_mut())?;
+
+        let dto = PdfPageAnnotation::Highlight(original).to_dto()?;
+
+        assert_eq!(dto.subtype, PdfPageAnnotationType::Highlight);
+        assert_eq!(dto.quads.len(), 1);
+        assert_eq!(dto.author.as_deref(), Some("Reviewer"));
+
+        let copy = page.annotations_mut().create_from_dto(&dto)?;
+
+        assert_eq!(copy.to_dto()?, dto);
+
+        Ok(())
+    }
+}
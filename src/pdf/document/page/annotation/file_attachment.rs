@@ -0,0 +1,167 @@
+//! Defines the [PdfPageFileAttachmentAnnotation] struct, exposing functionality related to a single
+//! user annotation of type `PdfPageAnnotationType::FileAttachment`.
+
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_PAGE};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::document::attachment::PdfAttachment;
+use crate::pdf::document::page::annotation::attachment_points::PdfPageAnnotationAttachmentPoints;
+use crate::pdf::document::page::annotation::dictionary_edits::{
+    PdfAnnotationDictionaryEditTarget, PdfAnnotationDictionaryEdits,
+};
+use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::syntax::{decode_pdf_string, PdfSyntaxObject};
+
+/// A single `PdfPageAnnotation` of type `PdfPageAnnotationType::FileAttachment`.
+///
+/// A file attachment annotation embeds a file at a specific location on a page. Conforming
+/// PDF viewers usually display the annotation as an icon that opens the embedded file when
+/// double-clicked. Unlike the attachments in a document's `PdfAttachments` collection, files
+/// embedded in file attachment annotations are not listed in the document's name tree of
+/// embedded files.
+///
+/// Pdfium cannot read or write the file specification dictionary holding the embedded file's
+/// description, nor the embedded file stream holding its MIME type. Changes to either are
+/// applied when the document is saved, and both are otherwise read from a copy of the
+/// document saved to memory.
+pub struct PdfPageFileAttachmentAnnotation<'a> {
+    handle: FPDF_ANNOTATION,
+    document_handle: FPDF_DOCUMENT,
+    objects: PdfPageAnnotationObjects<'a>,
+    attachment_points: PdfPageAnnotationAttachmentPoints<'a>,
+    bindings: &'a dyn PdfiumLibraryBindings,
+}
+
+impl<'a> PdfPageFileAttachmentAnnotation<'a> {
+    pub(crate) fn from_pdfium(
+        document_handle: FPDF_DOCUMENT,
+        page_handle: FPDF_PAGE,
+        annotation_handle: FPDF_ANNOTATION,
+        bindings: &'a dyn PdfiumLibraryBindings,
+    ) -> Self {
+        PdfPageFileAttachmentAnnotation {
+            handle: annotation_handle,
+            document_handle,
+            objects: PdfPageAnnotationObjects::from_pdfium(
+                document_handle,
+                page_handle,
+                annotation_handle,
+                bindings,
+            ),
+            attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
+                annotation_handle,
+                bindings,
+            ),
+            bindings,
+        }
+    }
+
+    /// Returns the file embedded in this [PdfPageFileAttachmentAnnotation], if any.
+    pub fn attachment(&self) -> Option<PdfAttachment<'a>> {
+        let handle = self.bindings.FPDFAnnot_GetFileAttachment(self.handle);
+
+        if handle.is_null() {
+            None
+        } else {
+            Some(PdfAttachment::from_pdfium(
+                handle,
+                self.document_handle,
+                self.bindings,
+            ))
+        }
+    }
+
+    /// Returns the byte data of the file embedded in this [PdfPageFileAttachmentAnnotation].
+    ///
+    /// Returns [PdfiumError::NoAttachmentForAnnotation] if this annotation does not
+    /// embed a file.
+    pub fn data(&self) -> Result<Vec<u8>, PdfiumError> {
+        self.attachment()
+            .ok_or(PdfiumError::NoAttachmentForAnnotation)?
+            .save_to_bytes()
+    }
+
+    /// Returns the description of the file embedded in this [PdfPageFileAttachmentAnnotation],
+    /// if any.
+    pub fn description(&self) -> Result<Option<String>, PdfiumError> {
+        match PdfAnnotationDictionaryEdits::read_in(
+            self,
+            PdfAnnotationDictionaryEditTarget::FileSpecification,
+            "Desc",
+        )? {
+            Some(PdfSyntaxObject::String(description)) => Ok(Some(decode_pdf_string(&description))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Sets the description of the file embedded in this [PdfPageFileAttachmentAnnotation],
+    /// or removes it if `None` is given. The description is written to the file specification
+    /// dictionary when the document is next saved.
+    pub fn set_description(&mut self, description: Option<&str>) -> Result<(), PdfiumError> {
+        PdfAnnotationDictionaryEdits::set_in(
+            self,
+            PdfAnnotationDictionaryEditTarget::FileSpecification,
+            "Desc",
+            description.map(PdfSyntaxObject::text),
+        )
+    }
+
+    /// Returns the MIME type of the file embedded in this [PdfPageFileAttachmentAnnotation],
+    /// such as "text/plain", if any.
+    pub fn mime_type(&self) -> Result<Option<String>, PdfiumError> {
+        match PdfAnnotationDictionaryEdits::read_in(
+            self,
+            PdfAnnotationDictionaryEditTarget::EmbeddedFile,
+            "Subtype",
+        )? {
+            Some(PdfSyntaxObject::Name(mime_type)) => Ok(Some(mime_type)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Sets the MIME type of the file embedded in this [PdfPageFileAttachmentAnnotation],
+    /// or removes it if `None` is given. The MIME type is written to the embedded file stream
+    /// when the document is next saved.
+    pub fn set_mime_type(&mut self, mime_type: Option<&str>) -> Result<(), PdfiumError> {
+        PdfAnnotationDictionaryEdits::set_in(
+            self,
+            PdfAnnotationDictionaryEditTarget::EmbeddedFile,
+            "Subtype",
+            mime_type.map(PdfSyntaxObject::name),
+        )
+    }
+}
+
+impl<'a> PdfPageAnnotationPrivate<'a> for PdfPageFileAttachmentAnnotation<'a> {
+    #[inline]
+    fn handle(&self) -> FPDF_ANNOTATION {
+        self.handle
+    }
+
+    #[inline]
+    fn bindings(&self) -> &dyn PdfiumLibraryBindings {
+        self.bindings
+    }
+
+    #[inline]
+    fn objects_impl(&self) -> &PdfPageAnnotationObjects<'_> {
+        &self.objects
+    }
+
+    #[inline]
+    fn objects_mut_impl(&mut self) -> &mut PdfPageAnnotationObjects<'a> {
+        &mut self.objects
+    }
+
+    #[inline]
+    fn attachment_points_impl(&self) -> &PdfPageAnnotationAttachmentPoints<'_> {
+        &self.attachment_points
+    }
+
+    #[inline]
+    fn attachment_points_mut_impl(&mut self) -> &mut PdfPageAnnotationAttachmentPoints<'a> {
+        &mut self.attachment_points
+    }
+}
@@ -31,6 +31,7 @@ impl<'a> PdfPageFreeTextAnnotation<'a> {
                 bindings,
             ),
             attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
                 annotation_handle,
                 bindings,
             ),
@@ -0,0 +1,140 @@
+//! Defines the [PdfAnnotationLineEnding] enum, the shape drawn at the start or end of
+//! the open path of a line or polyline `PdfPageAnnotation`.
+
+use crate::pdf::document::syntax::PdfSyntaxObject;
+
+/// The shape drawn at the start or end of the open path of a line or polyline annotation,
+/// as described in section 8.4.5 of The PDF Reference Manual, version 1.7, on page 630.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfAnnotationLineEnding {
+    /// No line ending.
+    None,
+
+    /// A square centered on the end point of the line.
+    Square,
+
+    /// A circle centered on the end point of the line.
+    Circle,
+
+    /// A square centered on the end point of the line, rotated by 45 degrees.
+    Diamond,
+
+    /// Two short lines meeting at the end point of the line, forming an open arrowhead.
+    OpenArrow,
+
+    /// A triangle with its apex at the end point of the line, forming a closed arrowhead.
+    ClosedArrow,
+
+    /// A short line through the end point of the line, perpendicular to the line.
+    Butt,
+
+    /// Two short lines meeting at the end point of the line, forming an open arrowhead
+    /// pointing back along the line.
+    ReverseOpenArrow,
+
+    /// A triangle with its apex at the end point of the line, forming a closed arrowhead
+    /// pointing back along the line.
+    ReverseClosedArrow,
+
+    /// A short line through the end point of the line, slanted at 30 degrees from
+    /// the perpendicular.
+    Slash,
+}
+
+impl PdfAnnotationLineEnding {
+    /// Returns the PDF name identifying this [PdfAnnotationLineEnding] in an
+    /// annotation's `/LE` array.
+    pub(crate) fn as_pdf_name(&self) -> &'static str {
+        match self {
+            PdfAnnotationLineEnding::None => "None",
+            PdfAnnotationLineEnding::Square => "Square",
+            PdfAnnotationLineEnding::Circle => "Circle",
+            PdfAnnotationLineEnding::Diamond => "Diamond",
+            PdfAnnotationLineEnding::OpenArrow => "OpenArrow",
+            PdfAnnotationLineEnding::ClosedArrow => "ClosedArrow",
+            PdfAnnotationLineEnding::Butt => "Butt",
+            PdfAnnotationLineEnding::ReverseOpenArrow => "ROpenArrow",
+            PdfAnnotationLineEnding::ReverseClosedArrow => "RClosedArrow",
+            PdfAnnotationLineEnding::Slash => "Slash",
+        }
+    }
+
+    /// Returns the [PdfAnnotationLineEnding] identified by the given PDF name. Unrecognized
+    /// names are treated as [PdfAnnotationLineEnding::None], as the PDF specification requires.
+    pub(crate) fn from_pdf_name(name: &str) -> Self {
+        match name {
+            "Square" => PdfAnnotationLineEnding::Square,
+            "Circle" => PdfAnnotationLineEnding::Circle,
+            "Diamond" => PdfAnnotationLineEnding::Diamond,
+            "OpenArrow" => PdfAnnotationLineEnding::OpenArrow,
+            "ClosedArrow" => PdfAnnotationLineEnding::ClosedArrow,
+            "Butt" => PdfAnnotationLineEnding::Butt,
+            "ROpenArrow" => PdfAnnotationLineEnding::ReverseOpenArrow,
+            "RClosedArrow" => PdfAnnotationLineEnding::ReverseClosedArrow,
+            "Slash" => PdfAnnotationLineEnding::Slash,
+            _ => PdfAnnotationLineEnding::None,
+        }
+    }
+
+    /// Returns the `/LE` array holding the given start and end line endings.
+    pub(crate) fn to_array(start: Self, end: Self) -> PdfSyntaxObject {
+        PdfSyntaxObject::Array(vec![
+            PdfSyntaxObject::name(start.as_pdf_name()),
+            PdfSyntaxObject::name(end.as_pdf_name()),
+        ])
+    }
+
+    /// Returns the start and end line endings held in the given `/LE` array, if it is valid.
+    pub(crate) fn from_array(array: &PdfSyntaxObject) -> Option<(Self, Self)> {
+        match array {
+            PdfSyntaxObject::Array(names) => match names.as_slice() {
+                [PdfSyntaxObject::Name(start), PdfSyntaxObject::Name(end)] => {
+                    Some((Self::from_pdf_name(start), Self::from_pdf_name(end)))
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+}
+
+impl Default for PdfAnnotationLineEnding {
+    #[inline]
+    fn default() -> Self {
+        PdfAnnotationLineEnding::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::annotation::line_ending::*;
+
+    #[test]
+    fn test_line_ending_names() {
+        let array = PdfAnnotationLineEnding::to_array(
+            PdfAnnotationLineEnding::ReverseOpenArrow,
+            PdfAnnotationLineEnding::Slash,
+        );
+
+        assert_eq!(
+            array,
+            PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::name("ROpenArrow"),
+                PdfSyntaxObject::name("Slash"),
+            ])
+        );
+
+        assert_eq!(
+            PdfAnnotationLineEnding::from_array(&array),
+            Some((
+                PdfAnnotationLineEnding::ReverseOpenArrow,
+                PdfAnnotationLineEnding::Slash
+            ))
+        );
+
+        assert_eq!(
+            PdfAnnotationLineEnding::from_pdf_name("Unknown"),
+            PdfAnnotationLineEnding::None
+        );
+    }
+}
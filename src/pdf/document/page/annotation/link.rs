@@ -34,6 +34,7 @@ impl<'a> PdfPageLinkAnnotation<'a> {
                 bindings,
             ),
             attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
                 annotation_handle,
                 bindings,
             ),
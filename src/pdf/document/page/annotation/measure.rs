@@ -0,0 +1,151 @@
+//! Defines the [PdfLineMeasure] struct, the measurement scale of a line annotation
+//! used as a measuring tool.
+
+use crate::pdf::document::form::builder::number;
+use crate::pdf::document::syntax::PdfSyntaxObject;
+use crate::pdf::points::PdfPoints;
+
+/// The measurement scale of a line annotation, converting distances on the page into
+/// real-world lengths, as described in section 8.8 of The PDF Reference Manual, version 1.7,
+/// on page 741.
+///
+/// A scale is defined by the unit in which real-world lengths are expressed and the number
+/// of those units represented by a single point on the page. For instance, a drawing at a
+/// scale of 1 inch to 10 feet has a conversion factor of 10 / 72 feet per point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfLineMeasure {
+    unit: String,
+    conversion: f32,
+    ratio: Option<String>,
+    decimal_places: u32,
+}
+
+impl PdfLineMeasure {
+    /// Creates a new [PdfLineMeasure] expressing lengths in the given unit, such as "mm"
+    /// or "ft", with the given number of those units represented by a single point on
+    /// the page. Lengths are formatted with two decimal places.
+    #[inline]
+    pub fn new(unit: &str, conversion: f32) -> Self {
+        PdfLineMeasure {
+            unit: unit.to_string(),
+            conversion,
+            ratio: None,
+            decimal_places: 2,
+        }
+    }
+
+    /// Sets the text describing this scale to the user, such as "1 in = 10 ft". If no ratio
+    /// is set, a ratio expressing the length represented by a single point is used.
+    #[inline]
+    pub fn ratio(mut self, ratio: &str) -> Self {
+        self.ratio = Some(ratio.to_string());
+
+        self
+    }
+
+    /// Sets the number of decimal places with which lengths are formatted.
+    #[inline]
+    pub fn decimal_places(mut self, decimal_places: u32) -> Self {
+        self.decimal_places = decimal_places;
+
+        self
+    }
+
+    /// Returns the unit in which this scale expresses real-world lengths.
+    #[inline]
+    pub fn unit(&self) -> &str {
+        self.unit.as_str()
+    }
+
+    /// Returns the number of units represented by a single point on the page.
+    #[inline]
+    pub fn conversion(&self) -> f32 {
+        self.conversion
+    }
+
+    /// Returns the real-world length, expressed in this scale's unit, represented by the
+    /// given distance on the page.
+    #[inline]
+    pub fn length(&self, distance: PdfPoints) -> f32 {
+        distance.value * self.conversion
+    }
+
+    /// Returns the real-world length represented by the given distance on the page,
+    /// formatted with this scale's number of decimal places and followed by its unit,
+    /// such as "12.50 ft".
+    #[inline]
+    pub fn format_length(&self, distance: PdfPoints) -> String {
+        format!(
+            "{:.*} {}",
+            self.decimal_places as usize,
+            self.length(distance),
+            self.unit
+        )
+    }
+
+    /// Returns the `/Measure` dictionary describing this scale.
+    pub(crate) fn to_dictionary(&self) -> PdfSyntaxObject {
+        let ratio = match self.ratio.as_ref() {
+            Some(ratio) => ratio.clone(),
+            None => format!("1 pt = {} {}", self.conversion, self.unit),
+        };
+
+        let number_format = PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("NumberFormat")),
+            ("U".to_string(), PdfSyntaxObject::text(&self.unit)),
+            ("C".to_string(), number(self.conversion)),
+            (
+                "D".to_string(),
+                PdfSyntaxObject::Number(10f64.powi(self.decimal_places as i32)),
+            ),
+        ]);
+
+        PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("Measure")),
+            ("Subtype".to_string(), PdfSyntaxObject::name("RL")),
+            ("R".to_string(), PdfSyntaxObject::text(&ratio)),
+            ("X".to_string(), PdfSyntaxObject::Array(vec![number_format])),
+        ])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::annotation::measure::*;
+
+    #[test]
+    fn test_line_measure() {
+        let measure = PdfLineMeasure::new("ft", 10.0 / 72.0).ratio("1 in = 10 ft");
+
+        assert_eq!(measure.format_length(PdfPoints::new(90.0)), "12.50 ft");
+        assert_eq!(
+            measure
+                .clone()
+                .decimal_places(0)
+                .format_length(PdfPoints::new(100.0)),
+            "14 ft"
+        );
+
+        let dictionary = measure.to_dictionary();
+
+        assert_eq!(
+            dictionary.get("Subtype"),
+            Some(&PdfSyntaxObject::name("RL"))
+        );
+        assert_eq!(
+            dictionary.get("R"),
+            Some(&PdfSyntaxObject::text("1 in = 10 ft"))
+        );
+
+        let number_format = match dictionary.get("X") {
+            Some(PdfSyntaxObject::Array(formats)) => formats[0].clone(),
+            _ => panic!("measure has no number format"),
+        };
+
+        assert_eq!(number_format.get("U"), Some(&PdfSyntaxObject::text("ft")));
+        assert_eq!(
+            number_format.get("D"),
+            Some(&PdfSyntaxObject::Number(100.0))
+        );
+    }
+}
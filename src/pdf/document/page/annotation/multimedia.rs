@@ -0,0 +1,162 @@
+//! Defines the [PdfPageMultimediaAnnotation] struct, exposing functionality related to a single
+//! user annotation of type `PdfPageAnnotationType::Sound`, `PdfPageAnnotationType::Movie`,
+//! `PdfPageAnnotationType::Screen`, or `PdfPageAnnotationType::RichMedia`.
+
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_PAGE};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::annotation::attachment_points::PdfPageAnnotationAttachmentPoints;
+use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::PdfPageAnnotationType;
+
+#[cfg(feature = "image")]
+use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
+
+#[cfg(feature = "image")]
+use image::DynamicImage;
+
+/// A single `PdfPageAnnotation` of type `PdfPageAnnotationType::Sound`,
+/// `PdfPageAnnotationType::Movie`, `PdfPageAnnotationType::Screen`, or
+/// `PdfPageAnnotationType::RichMedia`.
+///
+/// Multimedia annotations play sound, video, or interactive content embedded in, or referenced
+/// by, the document. Pdfium does not support creating, editing, or playing multimedia
+/// annotations, and provides no access to the media streams and activation dictionaries
+/// they contain, so media payloads cannot be extracted. The annotations can still be
+/// enumerated, and their titles, descriptions, and poster images inspected.
+pub struct PdfPageMultimediaAnnotation<'a> {
+    annotation_type: PdfPageAnnotationType,
+    handle: FPDF_ANNOTATION,
+    objects: PdfPageAnnotationObjects<'a>,
+    attachment_points: PdfPageAnnotationAttachmentPoints<'a>,
+    bindings: &'a dyn PdfiumLibraryBindings,
+}
+
+impl<'a> PdfPageMultimediaAnnotation<'a> {
+    pub(crate) fn from_pdfium(
+        document_handle: FPDF_DOCUMENT,
+        page_handle: FPDF_PAGE,
+        annotation_handle: FPDF_ANNOTATION,
+        annotation_type: PdfPageAnnotationType,
+        bindings: &'a dyn PdfiumLibraryBindings,
+    ) -> Self {
+        PdfPageMultimediaAnnotation {
+            annotation_type,
+            handle: annotation_handle,
+            objects: PdfPageAnnotationObjects::from_pdfium(
+                document_handle,
+                page_handle,
+                annotation_handle,
+                bindings,
+            ),
+            attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
+                annotation_handle,
+                bindings,
+            ),
+            bindings,
+        }
+    }
+
+    /// Returns the annotation type of this [PdfPageMultimediaAnnotation]: one of
+    /// [PdfPageAnnotationType::Sound], [PdfPageAnnotationType::Movie],
+    /// [PdfPageAnnotationType::Screen], or [PdfPageAnnotationType::RichMedia].
+    #[inline]
+    pub fn get_type(&self) -> PdfPageAnnotationType {
+        self.annotation_type
+    }
+
+    /// Returns `true` if this [PdfPageMultimediaAnnotation] carries a media payload: the sound
+    /// stream of a sound annotation, the movie dictionary of a movie annotation, the action
+    /// that plays the media of a screen annotation, or the content dictionary of a rich
+    /// media annotation.
+    pub fn has_media(&self) -> bool {
+        let key = match self.annotation_type {
+            PdfPageAnnotationType::Sound => "Sound",
+            PdfPageAnnotationType::Movie => "Movie",
+            PdfPageAnnotationType::Screen => "A",
+            _ => "RichMediaContent",
+        };
+
+        self.has_key(key)
+    }
+
+    /// Returns `true` if this [PdfPageMultimediaAnnotation] defines how its media should be
+    /// activated and played: the activation dictionary of a movie annotation, the additional
+    /// actions of a screen annotation, or the settings dictionary of a rich media annotation.
+    /// Sound annotations have no activation parameters, so `false` is always returned
+    /// for them.
+    pub fn has_activation_parameters(&self) -> bool {
+        match self.annotation_type {
+            PdfPageAnnotationType::Sound => false,
+            PdfPageAnnotationType::Movie => self.has_key("A"),
+            PdfPageAnnotationType::Screen => self.has_key("AA"),
+            _ => self.has_key("RichMediaSettings"),
+        }
+    }
+
+    /// Returns the title of this [PdfPageMultimediaAnnotation], if it is a movie or screen
+    /// annotation and has a title. Sound and rich media annotations have no titles; their
+    /// author can be retrieved using the `PdfPageAnnotationCommon::author()` function.
+    #[inline]
+    pub fn title(&self) -> Option<String> {
+        match self.annotation_type {
+            PdfPageAnnotationType::Movie | PdfPageAnnotationType::Screen => {
+                self.get_string_value("T")
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the poster image displayed by conforming PDF viewers in place of the media of
+    /// this [PdfPageMultimediaAnnotation] when the media is not playing, if any.
+    ///
+    /// The poster image is taken from the first image object in the annotation's
+    /// appearance stream.
+    ///
+    /// This function is only available when this crate's `image` feature is enabled.
+    #[cfg(feature = "image")]
+    pub fn poster_image(&self) -> Option<DynamicImage> {
+        self.objects
+            .iter()
+            .find_map(|object| object.as_image_object()?.get_raw_image().ok())
+    }
+
+    #[inline]
+    fn has_key(&self, key: &str) -> bool {
+        self.bindings
+            .is_true(self.bindings.FPDFAnnot_HasKey(self.handle, key))
+    }
+}
+
+impl<'a> PdfPageAnnotationPrivate<'a> for PdfPageMultimediaAnnotation<'a> {
+    #[inline]
+    fn handle(&self) -> FPDF_ANNOTATION {
+        self.handle
+    }
+
+    #[inline]
+    fn bindings(&self) -> &dyn PdfiumLibraryBindings {
+        self.bindings
+    }
+
+    #[inline]
+    fn objects_impl(&self) -> &PdfPageAnnotationObjects<'_> {
+        &self.objects
+    }
+
+    #[inline]
+    fn objects_mut_impl(&mut self) -> &mut PdfPageAnnotationObjects<'a> {
+        &mut self.objects
+    }
+
+    #[inline]
+    fn attachment_points_impl(&self) -> &PdfPageAnnotationAttachmentPoints<'_> {
+        &self.attachment_points
+    }
+
+    #[inline]
+    fn attachment_points_mut_impl(&mut self) -> &mut PdfPageAnnotationAttachmentPoints<'a> {
+        &mut self.attachment_points
+    }
+}
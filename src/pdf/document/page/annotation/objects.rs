@@ -51,6 +51,13 @@ impl<'a> PdfPageAnnotationObjects<'a> {
         }
     }
 
+    /// Returns the internal `FPDF_PAGE` handle for the page containing
+    /// this [PdfPageAnnotationObjects] collection.
+    #[inline]
+    pub(crate) fn get_page_handle(&self) -> &FPDF_PAGE {
+        &self.page_handle
+    }
+
     /// Returns the internal `FPDF_ANNOTATION` handle for the [PdfPageAnnotation] containing
     /// this [PdfPageAnnotationObjects] collection.
     #[inline]
@@ -3,11 +3,19 @@
 
 use crate::bindgen::{FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
 use crate::pdf::document::page::annotation::attachment_points::PdfPageAnnotationAttachmentPoints;
+use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
 use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
 use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::{linked_annotation, PdfPageAnnotation};
+use crate::pdf::document::syntax::PdfSyntaxObject;
 
 /// A single `PdfPageAnnotation` of type `PdfPageAnnotationType::Popup`.
+///
+/// A popup annotation displays the text of its parent markup annotation in a separate
+/// window. Use the `PdfPageAnnotation::popup()` function to retrieve the popup annotation
+/// of a markup annotation, and the `PdfPageAnnotation::create_popup()` function to create one.
 pub struct PdfPagePopupAnnotation<'a> {
     handle: FPDF_ANNOTATION,
     objects: PdfPageAnnotationObjects<'a>,
@@ -31,12 +39,42 @@ impl<'a> PdfPagePopupAnnotation<'a> {
                 bindings,
             ),
             attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
                 annotation_handle,
                 bindings,
             ),
             bindings,
         }
     }
+
+    /// Returns the markup annotation whose text is displayed in this [PdfPagePopupAnnotation],
+    /// if any.
+    #[inline]
+    pub fn parent(&self) -> Option<PdfPageAnnotation<'_>> {
+        linked_annotation(self, "Parent")
+    }
+
+    /// Returns `true` if this [PdfPagePopupAnnotation] is initially open in conforming
+    /// PDF viewers.
+    ///
+    /// Pdfium cannot read the boolean `/Open` entry of a popup annotation, so unless it has
+    /// been set since the containing document was loaded, this function saves a copy of the
+    /// document to memory and reads it from the copy.
+    pub fn is_open(&self) -> Result<bool, PdfiumError> {
+        Ok(matches!(
+            PdfAnnotationDictionaryEdits::read(self, "Open")?,
+            Some(PdfSyntaxObject::Bool(true))
+        ))
+    }
+
+    /// Sets whether this [PdfPagePopupAnnotation] is initially open in conforming PDF viewers.
+    ///
+    /// Pdfium cannot write boolean values, so the change is written when the containing
+    /// `PdfDocument` is saved.
+    #[inline]
+    pub fn set_open(&mut self, is_open: bool) -> Result<(), PdfiumError> {
+        PdfAnnotationDictionaryEdits::set(self, "Open", Some(PdfSyntaxObject::Bool(is_open)))
+    }
 }
 
 impl<'a> PdfPageAnnotationPrivate<'a> for PdfPagePopupAnnotation<'a> {
@@ -70,3 +108,70 @@ impl<'a> PdfPageAnnotationPrivate<'a> for PdfPagePopupAnnotation<'a> {
         &mut self.attachment_points
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_create_popups() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/replies-test.pdf", None)?;
+
+        {
+            let page = document.pages().first()?;
+
+            let mut square = page.annotations().get(0)?;
+
+            let mut circle = page.annotations().get(4)?;
+
+            assert!(square.popup().is_none());
+
+            square.set_popup_open(true)?;
+
+            let popup = circle.create_popup()?;
+
+            assert!(!popup.is_open()?);
+            assert_eq!(
+                popup.parent().and_then(|parent| parent.name()).as_deref(),
+                Some("circle")
+            );
+
+            // The popup of the square annotation was created automatically.
+
+            assert_eq!(page.annotations().len(), 7);
+
+            let popup = square.popup().unwrap();
+
+            let popup = popup.as_popup_annotation().unwrap();
+
+            assert!(popup.is_open()?);
+            assert_eq!(
+                popup.parent().and_then(|parent| parent.name()).as_deref(),
+                Some("square")
+            );
+        }
+
+        let document = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        let page = document.pages().first()?;
+
+        for (index, name, is_open) in [(0, "square", true), (4, "circle", false)] {
+            let annotation = page.annotations().get(index)?;
+
+            let popup = annotation.popup().unwrap();
+
+            let popup = popup.as_popup_annotation().unwrap();
+
+            assert_eq!(popup.is_open()?, is_open);
+            assert_eq!(
+                popup.parent().and_then(|parent| parent.name()).as_deref(),
+                Some(name)
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -9,15 +9,25 @@ pub(crate) mod internal {
 
     use crate::bindgen::{
         FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color,
-        FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor, FPDF_ANNOTATION, FPDF_OBJECT_STRING,
-        FPDF_PAGEOBJECT, FPDF_WCHAR, FS_RECTF,
+        FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor, FPDF_ANNOTATION, FPDF_DOCUMENT,
+        FPDF_OBJECT_STRING, FPDF_PAGEOBJECT, FPDF_WCHAR, FS_RECTF,
     };
     use crate::bindings::PdfiumLibraryBindings;
     use crate::error::{PdfiumError, PdfiumInternalError};
+    use crate::pdf::appearance_mode::PdfAppearanceMode;
     use crate::pdf::color::PdfColor;
+    use crate::pdf::document::page::annotation::appearance::{
+        annotation_color, border_width, generate_normal_appearance,
+        generate_normal_appearance_with_border_style, has_normal_appearance,
+        is_appearance_generation_supported, number_value,
+    };
     use crate::pdf::document::page::annotation::attachment_points::PdfPageAnnotationAttachmentPoints;
+    use crate::pdf::document::page::annotation::border::PdfAnnotationBorderStyle;
+    use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
     use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
     use crate::pdf::document::page::annotation::{PdfPageAnnotationCommon, PdfPageAnnotationType};
+    use crate::pdf::document::page::generation::PdfContentGenerations;
+    use crate::pdf::document::page::objects::private::internal::PdfPageObjectsPrivate;
     use crate::pdf::points::PdfPoints;
     use crate::pdf::rect::PdfRect;
     use crate::utils::dates::date_time_to_pdf_string;
@@ -34,6 +44,22 @@ pub(crate) mod internal {
         /// Returns the [PdfiumLibraryBindings] used by this [PdfPageAnnotation].
         fn bindings(&self) -> &dyn PdfiumLibraryBindings;
 
+        /// Returns the internal `FPDF_DOCUMENT` handle of the document containing
+        /// this [PdfPageAnnotation].
+        #[inline]
+        fn document_handle(&self) -> FPDF_DOCUMENT {
+            self.objects_impl().document_handle()
+        }
+
+        /// Records that the page containing this [PdfPageAnnotation] may have changed,
+        /// advancing the page's content generation.
+        #[inline]
+        fn mark_page_content_changed(&self) {
+            PdfContentGenerations::increment_for_page_handle(
+                *self.objects_impl().get_page_handle(),
+            );
+        }
+
         /// Returns the [PdfPageAnnotationType] of this [PdfPageAnnotation].
         fn get_annotation_type(&self) -> PdfPageAnnotationType {
             PdfPageAnnotationType::from_pdfium(self.bindings().FPDFAnnot_GetSubtype(self.handle()))
@@ -121,6 +147,8 @@ pub(crate) mod internal {
                         .FPDFAnnot_SetStringValue_str(self.handle(), key, value),
                 )
             {
+                self.mark_page_content_changed();
+
                 Ok(())
             } else {
                 Err(PdfiumError::PdfiumLibraryInternalError(
@@ -234,6 +262,18 @@ pub(crate) mod internal {
             self.set_string_value("T", creator)
         }
 
+        /// Internal implementation of [PdfPageAnnotationCommon::subject()].
+        #[inline]
+        fn subject_impl(&self) -> Option<String> {
+            self.get_string_value("Subj")
+        }
+
+        /// Internal implementation of [PdfPageAnnotationCommon::set_subject()].
+        #[inline]
+        fn set_subject_impl(&mut self, subject: &str) -> Result<(), PdfiumError> {
+            self.set_string_value("Subj", subject)
+        }
+
         /// Internal implementation of [PdfPageAnnotationCommon::creation_date()].
         #[inline]
         fn creation_date_impl(&self) -> Option<String> {
@@ -258,6 +298,120 @@ pub(crate) mod internal {
             self.set_string_value("M", &date_time_to_pdf_string(date))
         }
 
+        /// Internal implementation of [PdfPageAnnotationCommon::opacity()].
+        #[inline]
+        fn opacity_impl(&self) -> f32 {
+            number_value(self, "CA").unwrap_or(1.0)
+        }
+
+        /// Internal implementation of [PdfPageAnnotationCommon::set_opacity()].
+        fn set_opacity_impl(&mut self, opacity: f32) -> Result<(), PdfiumError> {
+            // Pdfium has no function for setting an annotation's opacity directly, but sets it
+            // from the alpha channel of the annotation's color. Neither the color nor the opacity
+            // can be changed while the annotation has an appearance stream, so any existing
+            // appearance stream must be removed first and then regenerated.
+
+            let has_appearance = has_normal_appearance(self);
+
+            if has_appearance {
+                if !is_appearance_generation_supported(self.get_annotation_type()) {
+                    return Err(PdfiumError::PdfiumLibraryInternalError(
+                        PdfiumInternalError::Unknown,
+                    ));
+                }
+
+                if !self.bindings().is_true(self.bindings().FPDFAnnot_SetAP(
+                    self.handle(),
+                    PdfAppearanceMode::Normal.as_pdfium(),
+                    std::ptr::null(),
+                )) {
+                    return Err(PdfiumError::PdfiumLibraryInternalError(
+                        PdfiumInternalError::Unknown,
+                    ));
+                }
+            }
+
+            // Pdfium reports a default color if the annotation has none.
+
+            let color = annotation_color(self, FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color)
+                .ok_or(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ))?;
+
+            if !self.bindings().is_true(self.bindings().FPDFAnnot_SetColor(
+                self.handle(),
+                FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color,
+                color.red() as c_uint,
+                color.green() as c_uint,
+                color.blue() as c_uint,
+                (opacity.clamp(0.0, 1.0) * 255.0).round() as c_uint,
+            )) {
+                return Err(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ));
+            }
+
+            if has_appearance {
+                generate_normal_appearance(self)?;
+            }
+
+            self.mark_page_content_changed();
+
+            Ok(())
+        }
+
+        /// Internal implementation of [PdfPageAnnotationCommon::border_width()].
+        #[inline]
+        fn border_width_impl(&self) -> Option<PdfPoints> {
+            border_width(self)
+        }
+
+        /// Internal implementation of [PdfPageAnnotationCommon::border_style()].
+        fn border_style_impl(&self) -> Result<Option<PdfAnnotationBorderStyle>, PdfiumError> {
+            Ok(PdfAnnotationDictionaryEdits::read(self, "BS")?
+                .map(|dictionary| PdfAnnotationBorderStyle::from_dictionary(&dictionary)))
+        }
+
+        /// Internal implementation of [PdfPageAnnotationCommon::set_border()].
+        fn set_border_impl(
+            &mut self,
+            width: PdfPoints,
+            style: &PdfAnnotationBorderStyle,
+        ) -> Result<(), PdfiumError> {
+            let annotation_type = self.get_annotation_type();
+
+            let is_style_supported = is_appearance_generation_supported(annotation_type)
+                || annotation_type == PdfPageAnnotationType::Link;
+
+            if !self.bindings().is_true(self.bindings().FPDFAnnot_SetBorder(
+                self.handle(),
+                0.0,
+                0.0,
+                width.value,
+            )) {
+                return Err(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ));
+            }
+
+            PdfAnnotationDictionaryEdits::set(self, "BS", Some(style.to_dictionary(width)))?;
+
+            // Conforming PDF viewers draw the border from the /BS dictionary when they generate
+            // the annotation's appearance themselves, so an appearance stream is only needed
+            // for border styles other than solid, or to replace an existing appearance stream
+            // drawn with the previous border.
+
+            if is_style_supported
+                && (*style != PdfAnnotationBorderStyle::Solid || has_normal_appearance(self))
+            {
+                generate_normal_appearance_with_border_style(self, style)?;
+            }
+
+            self.mark_page_content_changed();
+
+            Ok(())
+        }
+
         /// Internal implementation of [PdfPageAnnotationCommon::is_markup_annotation()].
         #[inline]
         fn is_markup_annotation_impl(&self) -> bool {
@@ -332,6 +486,8 @@ pub(crate) mod internal {
                 fill_color.blue() as c_uint,
                 fill_color.alpha() as c_uint,
             )) {
+                self.mark_page_content_changed();
+
                 Ok(())
             } else {
                 // The FPDFAnnot_SetColor() function returns false if the annotation
@@ -349,6 +505,8 @@ pub(crate) mod internal {
                         fill_color.alpha() as c_uint,
                     ))
                 {
+                    self.mark_page_content_changed();
+
                     Ok(())
                 } else {
                     Err(PdfiumError::PdfiumLibraryInternalError(
@@ -414,6 +572,8 @@ pub(crate) mod internal {
                 stroke_color.blue() as c_uint,
                 stroke_color.alpha() as c_uint,
             )) {
+                self.mark_page_content_changed();
+
                 Ok(())
             } else {
                 // The FPDFAnnot_SetColor() function returns false if the annotation
@@ -431,6 +591,8 @@ pub(crate) mod internal {
                         stroke_color.alpha() as c_uint,
                     ))
                 {
+                    self.mark_page_content_changed();
+
                     Ok(())
                 } else {
                     Err(PdfiumError::PdfiumLibraryInternalError(
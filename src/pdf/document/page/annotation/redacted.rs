@@ -3,11 +3,27 @@
 
 use crate::bindgen::{FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::color::PdfColor;
 use crate::pdf::document::page::annotation::attachment_points::PdfPageAnnotationAttachmentPoints;
+use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
 use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
 use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::PdfPageAnnotationCommon;
+use crate::pdf::document::syntax::PdfSyntaxObject;
+use crate::pdf::rect::PdfRect;
 
 /// A single `PdfPageAnnotation` of type `PdfPageAnnotationType::Redacted`.
+///
+/// A redaction annotation marks content on a page for removal without removing it.
+/// The marked content is permanently removed when the redaction is applied using the
+/// `PdfPage::apply_redactions()` function. Before the redaction is applied, conforming
+/// PDF viewers outline the marked regions using the annotation's stroke color; once the
+/// redaction is applied, each region is filled with the annotation's fill color, if any,
+/// and labelled with the annotation's overlay text, if any.
+///
+/// Pdfium does not support creating redaction annotations; use a `PdfRedactAnnotationBuilder`
+/// to add them to a page.
 pub struct PdfPageRedactedAnnotation<'a> {
     handle: FPDF_ANNOTATION,
     objects: PdfPageAnnotationObjects<'a>,
@@ -31,12 +47,80 @@ impl<'a> PdfPageRedactedAnnotation<'a> {
                 bindings,
             ),
             attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
                 annotation_handle,
                 bindings,
             ),
             bindings,
         }
     }
+
+    /// Returns the overlay text that will be drawn over each redacted region once this
+    /// [PdfPageRedactedAnnotation] is applied, if any.
+    #[inline]
+    pub fn overlay_text(&self) -> Option<String> {
+        self.get_string_value("OverlayText")
+    }
+
+    /// Sets the overlay text that will be drawn over each redacted region once this
+    /// [PdfPageRedactedAnnotation] is applied.
+    #[inline]
+    pub fn set_overlay_text(&mut self, text: &str) -> Result<(), PdfiumError> {
+        self.set_string_value("OverlayText", text)
+    }
+
+    /// Returns the color with which each redacted region will be filled once this
+    /// [PdfPageRedactedAnnotation] is applied, or `None` if the regions will be left blank.
+    pub(crate) fn redaction_fill_color(&self) -> Option<PdfColor> {
+        if self
+            .bindings
+            .is_true(self.bindings.FPDFAnnot_HasKey(self.handle, "IC"))
+        {
+            self.fill_color().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the regions of the page marked for redaction by this
+    /// [PdfPageRedactedAnnotation]. Each attachment point of the annotation marks a single
+    /// region; if the annotation has no attachment points, its bounds mark the region instead.
+    ///
+    /// Pdfium only reads the attachment points of text markup and link annotations, so unless
+    /// attachment points have been added since the containing document was loaded, this
+    /// function saves a copy of the document to memory and reads them from the copy.
+    pub fn regions(&self) -> Result<Vec<PdfRect>, PdfiumError> {
+        if self.attachment_points.is_empty() {
+            return match PdfAnnotationDictionaryEdits::read(self, "QuadPoints")?
+                .as_ref()
+                .and_then(quad_point_regions)
+            {
+                Some(regions) => Ok(regions),
+                None => self.bounds().map(|bounds| vec![bounds]),
+            };
+        }
+
+        Ok(self
+            .attachment_points
+            .iter()
+            .map(|points| {
+                let xs = [points.x1, points.x2, points.x3, points.x4];
+
+                let ys = [points.y1, points.y2, points.y3, points.y4];
+
+                PdfRect::new(
+                    ys.iter()
+                        .fold(ys[0], |min, y| if *y < min { *y } else { min }),
+                    xs.iter()
+                        .fold(xs[0], |min, x| if *x < min { *x } else { min }),
+                    ys.iter()
+                        .fold(ys[0], |max, y| if *y > max { *y } else { max }),
+                    xs.iter()
+                        .fold(xs[0], |max, x| if *x > max { *x } else { max }),
+                )
+            })
+            .collect())
+    }
 }
 
 impl<'a> PdfPageAnnotationPrivate<'a> for PdfPageRedactedAnnotation<'a> {
@@ -70,3 +154,68 @@ impl<'a> PdfPageAnnotationPrivate<'a> for PdfPageRedactedAnnotation<'a> {
         &mut self.attachment_points
     }
 }
+
+/// Returns the regions marked by the given `/QuadPoints` array of a redaction annotation,
+/// or `None` if the array is empty or malformed. Each quadrilateral marks the smallest
+/// rectangle enclosing it.
+fn quad_point_regions(quad_points: &PdfSyntaxObject) -> Option<Vec<PdfRect>> {
+    let values = match quad_points {
+        PdfSyntaxObject::Array(values) => values
+            .iter()
+            .map(|value| match value {
+                PdfSyntaxObject::Number(value) => Some(*value as f32),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?,
+        _ => return None,
+    };
+
+    let regions = values
+        .chunks_exact(8)
+        .map(|quad| {
+            let xs = quad.iter().step_by(2);
+
+            let ys = quad.iter().skip(1).step_by(2);
+
+            PdfRect::new_from_values(
+                ys.clone().fold(f32::MAX, |min, y| min.min(*y)),
+                xs.clone().fold(f32::MAX, |min, x| min.min(*x)),
+                ys.fold(f32::MIN, |max, y| max.max(*y)),
+                xs.fold(f32::MIN, |max, x| max.max(*x)),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    (!regions.is_empty()).then(|| regions)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::annotation::redacted::*;
+
+    #[test]
+    fn test_quad_point_regions() {
+        let quad_points = PdfSyntaxObject::Array(
+            [
+                10, 90, 60, 90, 10, 80, 60, 80, 10, 50, 40, 50, 10, 40, 40, 40,
+            ]
+            .iter()
+            .map(|value| PdfSyntaxObject::Number(*value as f64))
+            .collect(),
+        );
+
+        assert_eq!(
+            quad_point_regions(&quad_points),
+            Some(vec![
+                PdfRect::new_from_values(80.0, 10.0, 90.0, 60.0),
+                PdfRect::new_from_values(40.0, 10.0, 50.0, 40.0),
+            ])
+        );
+
+        assert_eq!(
+            quad_point_regions(&PdfSyntaxObject::Array(Vec::new())),
+            None
+        );
+        assert_eq!(quad_point_regions(&PdfSyntaxObject::Null), None);
+    }
+}
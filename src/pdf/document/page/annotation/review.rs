@@ -0,0 +1,180 @@
+//! Defines the [PdfAnnotationReviewStatus] enum, the review status of a markup annotation
+//! as recorded by the replies in its comment thread.
+
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::{PdfPageAnnotation, PdfPageAnnotationCommon};
+
+/// The review status of a markup annotation, as set by a reviewer in a conforming PDF viewer.
+///
+/// Review statuses are defined by the "Review" state model in The PDF Reference Manual,
+/// sixth edition, section 8.4.5, on page 621. A reviewer sets the status of an annotation
+/// by adding a reply to it that carries the new status; the most recent such reply
+/// determines the annotation's current status.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfAnnotationReviewStatus {
+    /// No review status has been set, or the status has been reset.
+    None,
+
+    /// The reviewer agrees with the change proposed by the annotation.
+    Accepted,
+
+    /// The reviewer disagrees with the change proposed by the annotation.
+    Rejected,
+
+    /// The change proposed by the annotation has been withdrawn.
+    Cancelled,
+
+    /// The change proposed by the annotation has been made.
+    Completed,
+}
+
+impl PdfAnnotationReviewStatus {
+    pub(crate) fn from_state(state: &str) -> Option<Self> {
+        match state {
+            "None" => Some(PdfAnnotationReviewStatus::None),
+            "Accepted" => Some(PdfAnnotationReviewStatus::Accepted),
+            "Rejected" => Some(PdfAnnotationReviewStatus::Rejected),
+            "Cancelled" => Some(PdfAnnotationReviewStatus::Cancelled),
+            "Completed" => Some(PdfAnnotationReviewStatus::Completed),
+            _ => None,
+        }
+    }
+
+    /// Returns the `/State` value recording this [PdfAnnotationReviewStatus] in a reply.
+    pub(crate) fn as_state(&self) -> &'static str {
+        match self {
+            PdfAnnotationReviewStatus::None => "None",
+            PdfAnnotationReviewStatus::Accepted => "Accepted",
+            PdfAnnotationReviewStatus::Rejected => "Rejected",
+            PdfAnnotationReviewStatus::Cancelled => "Cancelled",
+            PdfAnnotationReviewStatus::Completed => "Completed",
+        }
+    }
+}
+
+/// Returns the review status set by the most recently modified of the given replies that
+/// carries a review status. Replies without a modification date are treated as older than
+/// those with one; of equally dated replies, the last one takes precedence.
+pub(crate) fn review_status_from_replies(
+    replies: &[PdfPageAnnotation],
+) -> PdfAnnotationReviewStatus {
+    replies
+        .iter()
+        .filter_map(|reply| {
+            // A state model of "Review" is assumed if none is given, unless the state
+            // belongs to the "Marked" state model.
+
+            if reply
+                .get_string_value("StateModel")
+                .map(|model| model != "Review")
+                .unwrap_or(false)
+            {
+                return None;
+            }
+
+            let status = PdfAnnotationReviewStatus::from_state(&reply.get_string_value("State")?)?;
+
+            Some((reply.modification_date_time(), status))
+        })
+        .fold(None, |latest, (date, status)| match latest {
+            Some((latest_date, _)) if latest_date > date => latest,
+            _ => Some((date, status)),
+        })
+        .map(|(_, status)| status)
+        .unwrap_or(PdfAnnotationReviewStatus::None)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_replies_and_review_status() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/replies-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let square = page.annotations().get(0)?;
+
+        let replies = square.replies();
+
+        assert_eq!(replies.len(), 3);
+        assert!(replies.iter().all(|reply| reply.is_reply()));
+        assert_eq!(replies[0].author().as_deref(), Some("Reviewer"));
+        assert_eq!(replies[0].contents().as_deref(), Some("Looks good"));
+        assert_eq!(
+            replies[0]
+                .in_reply_to()
+                .and_then(|parent| parent.name())
+                .as_deref(),
+            Some("square")
+        );
+
+        // The status set by the most recent reply takes precedence over the status set
+        // by an earlier reply.
+
+        assert_eq!(square.review_status(), PdfAnnotationReviewStatus::Accepted);
+        assert!(!square.is_reply());
+
+        let circle = page.annotations().get(4)?;
+
+        assert!(circle.replies().is_empty());
+        assert_eq!(circle.review_status(), PdfAnnotationReviewStatus::None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_add_reply_and_set_review_status() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/replies-test.pdf", None)?;
+
+        {
+            let page = document.pages().first()?;
+
+            let mut circle = page.annotations().get(4)?;
+
+            circle.add_reply("Reviewer", "Make it bigger")?;
+            circle.set_review_status("Reviewer", PdfAnnotationReviewStatus::Completed)?;
+
+            // Replies are visible before the document is saved.
+
+            let replies = circle.replies();
+
+            assert_eq!(replies.len(), 2);
+            assert!(replies[0].is_reply());
+            assert_eq!(replies[0].contents().as_deref(), Some("Make it bigger"));
+            assert_eq!(circle.review_status(), PdfAnnotationReviewStatus::Completed);
+
+            // Replies to other annotations are unaffected.
+
+            assert_eq!(page.annotations().get(0)?.replies().len(), 3);
+        }
+
+        let document = pdfium.load_pdf_from_byte_vec(document.save_to_bytes()?, None)?;
+
+        let page = document.pages().first()?;
+
+        let circle = page.annotations().get(4)?;
+
+        let replies = circle.replies();
+
+        assert_eq!(replies.len(), 2);
+        assert_eq!(replies[0].author().as_deref(), Some("Reviewer"));
+        assert_eq!(replies[0].contents().as_deref(), Some("Make it bigger"));
+        assert_eq!(
+            replies[0]
+                .in_reply_to()
+                .and_then(|parent| parent.name())
+                .as_deref(),
+            Some("circle")
+        );
+        assert_eq!(circle.review_status(), PdfAnnotationReviewStatus::Completed);
+
+        Ok(())
+    }
+}
@@ -7,6 +7,14 @@ use crate::pdf::document::page::annotation::attachment_points::PdfPageAnnotation
 use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
 use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
 
+#[cfg(feature = "image")]
+use {
+    crate::error::PdfiumError,
+    crate::pdf::document::page::annotation::builders::PdfStampAnnotationBuilder,
+    crate::pdf::document::page::annotations::PdfPageAnnotations, crate::pdf::points::PdfPoints,
+    crate::pdf::rect::PdfRect, image::DynamicImage,
+};
+
 /// A single `PdfPageAnnotation` of type `PdfPageAnnotationType::Stamp`.
 pub struct PdfPageStampAnnotation<'a> {
     handle: FPDF_ANNOTATION,
@@ -31,6 +39,7 @@ impl<'a> PdfPageStampAnnotation<'a> {
                 bindings,
             ),
             attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
                 annotation_handle,
                 bindings,
             ),
@@ -38,6 +47,26 @@ impl<'a> PdfPageStampAnnotation<'a> {
         }
     }
 
+    /// Creates a new [PdfPageStampAnnotation] in the given [PdfPageAnnotations] collection
+    /// displaying the given image, scaled to fit within the given bounds while preserving its
+    /// aspect ratio and centered within them. The stamp has no border. Use a
+    /// `PdfStampAnnotationBuilder` to combine an image with a border or a label.
+    ///
+    /// This function is only available when this crate's `image` feature is enabled.
+    #[cfg(feature = "image")]
+    #[inline]
+    pub fn from_image(
+        annotations: &mut PdfPageAnnotations<'a>,
+        bounds: PdfRect,
+        image: &DynamicImage,
+    ) -> Result<Self, PdfiumError> {
+        PdfStampAnnotationBuilder::new()
+            .bounds(bounds)
+            .image(image)
+            .border_width(PdfPoints::ZERO)
+            .create(annotations)
+    }
+
     /// Returns a mutable collection of all the page objects in this [PdfPageStampAnnotation].
     #[inline]
     pub fn objects_mut(&mut self) -> &mut PdfPageAnnotationObjects<'a> {
@@ -3,10 +3,21 @@
 
 use crate::bindgen::{FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::document::form::builder::number;
+use crate::pdf::document::page::annotation::appearance::{
+    generate_normal_appearance, line_ending_margin, line_endings, line_points, number_value,
+    vertices, PdfLineLeader,
+};
 use crate::pdf::document::page::annotation::attachment_points::PdfPageAnnotationAttachmentPoints;
+use crate::pdf::document::page::annotation::dictionary_edits::PdfAnnotationDictionaryEdits;
+use crate::pdf::document::page::annotation::line_ending::PdfAnnotationLineEnding;
 use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
 use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
 use crate::pdf::document::page::annotation::PdfPageAnnotationType;
+use crate::pdf::document::syntax::PdfSyntaxObject;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
 
 /// A single `PdfPageAnnotation` of any annotation type not supported by Pdfium.
 pub struct PdfPageUnsupportedAnnotation<'a> {
@@ -35,6 +46,7 @@ impl<'a> PdfPageUnsupportedAnnotation<'a> {
                 bindings,
             ),
             attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
                 annotation_handle,
                 bindings,
             ),
@@ -48,6 +60,247 @@ impl<'a> PdfPageUnsupportedAnnotation<'a> {
     pub fn get_type(&self) -> PdfPageAnnotationType {
         self.annotation_type
     }
+
+    /// Returns the vertices of this annotation, expressed as (x, y) page coordinates,
+    /// if this annotation is of type [PdfPageAnnotationType::Polygon] or
+    /// [PdfPageAnnotationType::Polyline]. Returns an empty list for all other annotation types.
+    ///
+    /// Polygon and polyline annotations can be created using a `PdfPolygonAnnotationBuilder`.
+    /// Their colors, border, and vertices can be changed, and their appearance streams
+    /// regenerated using the `PdfPageAnnotationCommon::generate_appearance_stream()` function.
+    #[inline]
+    pub fn vertices(&self) -> Vec<(PdfPoints, PdfPoints)> {
+        vertices(self)
+    }
+
+    /// Replaces the vertices of this polygon or polyline annotation with the given vertices,
+    /// expressed as (x, y) page coordinates, resizing the annotation's bounds to enclose them
+    /// and regenerating its appearance stream.
+    ///
+    /// Pdfium cannot write an annotation's vertices, so the new vertices are written
+    /// when the document containing this annotation is saved. Until then, they are reported
+    /// by [PdfPageUnsupportedAnnotation::vertices()] but not by Pdfium itself, so the
+    /// annotation is rendered using its regenerated appearance stream.
+    ///
+    /// Returns [PdfiumError::UnsupportedPdfAnnotationType] if this annotation is not a
+    /// polygon or polyline annotation, or [PdfiumError::NotEnoughVerticesForAnnotation]
+    /// if a polygon is given fewer than three vertices or a polyline fewer than two.
+    pub fn set_vertices(&mut self, vertices: &[(PdfPoints, PdfPoints)]) -> Result<(), PdfiumError> {
+        let minimum = match self.annotation_type {
+            PdfPageAnnotationType::Polygon => 3,
+            PdfPageAnnotationType::Polyline => 2,
+            _ => return Err(PdfiumError::UnsupportedPdfAnnotationType),
+        };
+
+        if vertices.len() < minimum {
+            return Err(PdfiumError::NotEnoughVerticesForAnnotation);
+        }
+
+        PdfAnnotationDictionaryEdits::set(
+            self,
+            "Vertices",
+            Some(PdfSyntaxObject::Array(
+                vertices
+                    .iter()
+                    .flat_map(|(x, y)| [number(x.value), number(y.value)])
+                    .collect(),
+            )),
+        )?;
+
+        let width = self.border_width_impl().unwrap_or(PdfPoints::new(1.0));
+
+        let margin = width / 2.0 + line_ending_margin(width, line_endings(self));
+
+        self.set_bounds_impl(enclosing_rect(vertices, margin))?;
+
+        generate_normal_appearance(self).map(|_| ())
+    }
+
+    /// Returns the line endings drawn at the start and end of the path of this polyline or
+    /// line annotation.
+    ///
+    /// Pdfium cannot read an annotation's line endings, so only line endings set using
+    /// [PdfPageUnsupportedAnnotation::set_line_endings()] since the document containing this
+    /// annotation was loaded are returned; otherwise, no line endings are reported.
+    #[inline]
+    pub fn line_endings(&self) -> (PdfAnnotationLineEnding, PdfAnnotationLineEnding) {
+        line_endings(self)
+    }
+
+    /// Sets the line endings drawn at the start and end of the path of this polyline or line
+    /// annotation, regenerating its appearance stream.
+    ///
+    /// Pdfium cannot write an annotation's line endings, so the new line endings are written
+    /// when the document containing this annotation is saved. Closed line endings are filled
+    /// with the annotation's fill color, if it has one.
+    ///
+    /// Returns [PdfiumError::UnsupportedPdfAnnotationType] if this annotation is not a
+    /// polyline or line annotation.
+    pub fn set_line_endings(
+        &mut self,
+        start: PdfAnnotationLineEnding,
+        end: PdfAnnotationLineEnding,
+    ) -> Result<(), PdfiumError> {
+        if self.annotation_type != PdfPageAnnotationType::Polyline
+            && self.annotation_type != PdfPageAnnotationType::Line
+        {
+            return Err(PdfiumError::UnsupportedPdfAnnotationType);
+        }
+
+        PdfAnnotationDictionaryEdits::set(
+            self,
+            "LE",
+            Some(PdfAnnotationLineEnding::to_array(start, end)),
+        )?;
+
+        if self.annotation_type == PdfPageAnnotationType::Line {
+            self.set_line_bounds()?;
+        }
+
+        generate_normal_appearance(self).map(|_| ())
+    }
+
+    /// Returns the start and end points of this annotation, expressed as (x, y) page
+    /// coordinates, if this annotation is of type [PdfPageAnnotationType::Line].
+    /// Returns `None` for all other annotation types.
+    ///
+    /// Line annotations can be created using the `PdfLineAnnotationBuilder`, and their end
+    /// points, line endings, and leader lines changed using the
+    /// [PdfPageUnsupportedAnnotation::set_line()],
+    /// [PdfPageUnsupportedAnnotation::set_line_endings()], and
+    /// [PdfPageUnsupportedAnnotation::set_leader_line()] functions. Regenerated appearance
+    /// streams include any leader lines and line endings, but not the annotation's caption.
+    /// A line annotation's caption, if any, is its `PdfPageAnnotationCommon::contents()`.
+    #[inline]
+    pub fn line(&self) -> Option<((PdfPoints, PdfPoints), (PdfPoints, PdfPoints))> {
+        line_points(self)
+    }
+
+    /// Returns the length of the leader lines extending perpendicularly from each end point
+    /// of this line annotation to the line itself, if any. Positive lengths extend clockwise,
+    /// when traversing the line from its start point to its end point; negative lengths
+    /// extend anticlockwise.
+    #[inline]
+    pub fn leader_line_length(&self) -> Option<PdfPoints> {
+        number_value(self, "LL").map(PdfPoints::new)
+    }
+
+    /// Returns the length by which the leader lines of this line annotation extend
+    /// beyond the line itself, if any.
+    #[inline]
+    pub fn leader_line_extension(&self) -> Option<PdfPoints> {
+        number_value(self, "LLE").map(PdfPoints::new)
+    }
+
+    /// Returns the length of the gap between each end point of this line annotation and
+    /// the start of its leader line, if any.
+    #[inline]
+    pub fn leader_line_offset(&self) -> Option<PdfPoints> {
+        number_value(self, "LLO").map(PdfPoints::new)
+    }
+
+    /// Moves the start and end points of this line annotation to the given (x, y) page
+    /// coordinates, updating its bounds and regenerating its appearance stream.
+    ///
+    /// Pdfium cannot write a line annotation's end points, so the new end points are written
+    /// when the document containing this annotation is saved.
+    ///
+    /// Returns [PdfiumError::UnsupportedPdfAnnotationType] if this annotation is not a
+    /// line annotation.
+    pub fn set_line(
+        &mut self,
+        start: (PdfPoints, PdfPoints),
+        end: (PdfPoints, PdfPoints),
+    ) -> Result<(), PdfiumError> {
+        if self.annotation_type != PdfPageAnnotationType::Line {
+            return Err(PdfiumError::UnsupportedPdfAnnotationType);
+        }
+
+        PdfAnnotationDictionaryEdits::set(
+            self,
+            "L",
+            Some(PdfSyntaxObject::Array(vec![
+                number(start.0.value),
+                number(start.1.value),
+                number(end.0.value),
+                number(end.1.value),
+            ])),
+        )?;
+
+        self.set_line_bounds()?;
+
+        generate_normal_appearance(self).map(|_| ())
+    }
+
+    /// Sets the length, extension, and offset of the leader lines of this line annotation,
+    /// updating its bounds and regenerating its appearance stream. A length of zero removes
+    /// the leader lines. See [PdfPageUnsupportedAnnotation::leader_line_length()],
+    /// [PdfPageUnsupportedAnnotation::leader_line_extension()], and
+    /// [PdfPageUnsupportedAnnotation::leader_line_offset()].
+    ///
+    /// Pdfium cannot write numeric annotation properties, so the new leader lines are written
+    /// when the document containing this annotation is saved.
+    ///
+    /// Returns [PdfiumError::UnsupportedPdfAnnotationType] if this annotation is not a
+    /// line annotation.
+    pub fn set_leader_line(
+        &mut self,
+        length: PdfPoints,
+        extension: PdfPoints,
+        offset: PdfPoints,
+    ) -> Result<(), PdfiumError> {
+        if self.annotation_type != PdfPageAnnotationType::Line {
+            return Err(PdfiumError::UnsupportedPdfAnnotationType);
+        }
+
+        PdfAnnotationDictionaryEdits::set(self, "LL", Some(number(length.value)))?;
+        PdfAnnotationDictionaryEdits::set(self, "LLE", Some(number(extension.value)))?;
+        PdfAnnotationDictionaryEdits::set(self, "LLO", Some(number(offset.value)))?;
+
+        self.set_line_bounds()?;
+
+        generate_normal_appearance(self).map(|_| ())
+    }
+
+    /// Sets the bounds of this line annotation to enclose its line, leader lines,
+    /// and line endings.
+    fn set_line_bounds(&mut self) -> Result<(), PdfiumError> {
+        let (start, end) = line_points(self).ok_or(PdfiumError::NoBoundsForAnnotation)?;
+
+        let leader = PdfLineLeader {
+            length: number_value(self, "LL").unwrap_or(0.0),
+            extension: number_value(self, "LLE").unwrap_or(0.0),
+            offset: number_value(self, "LLO").unwrap_or(0.0),
+        };
+
+        let width = self.border_width_impl().unwrap_or(PdfPoints::new(1.0));
+
+        let margin = width / 2.0 + line_ending_margin(width, line_endings(self));
+
+        self.set_bounds_impl(enclosing_rect(&leader.extent(start, end), margin))
+    }
+}
+
+/// Returns the smallest rectangle enclosing the given points, expanded by the given margin.
+fn enclosing_rect(points: &[(PdfPoints, PdfPoints)], margin: PdfPoints) -> PdfRect {
+    let (left, bottom, right, top) = points.iter().fold(
+        (f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+        |(left, bottom, right, top), (x, y)| {
+            (
+                left.min(x.value),
+                bottom.min(y.value),
+                right.max(x.value),
+                top.max(y.value),
+            )
+        },
+    );
+
+    PdfRect::new_from_values(
+        bottom - margin.value,
+        left - margin.value,
+        top + margin.value,
+        right + margin.value,
+    )
 }
 
 impl<'a> PdfPageAnnotationPrivate<'a> for PdfPageUnsupportedAnnotation<'a> {
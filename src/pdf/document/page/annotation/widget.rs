@@ -37,11 +37,12 @@ impl<'a> PdfPageWidgetAnnotation<'a> {
                 bindings,
             ),
             attachment_points: PdfPageAnnotationAttachmentPoints::from_pdfium(
+                page_handle,
                 annotation_handle,
                 bindings,
             ),
             form_field: form_handle.and_then(|form_handle| {
-                PdfFormField::from_pdfium(form_handle, annotation_handle, bindings)
+                PdfFormField::from_pdfium(form_handle, page_handle, annotation_handle, bindings)
             }),
             bindings,
         }
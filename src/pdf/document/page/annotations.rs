@@ -1,10 +1,21 @@
 //! Defines the [PdfPageAnnotations] struct, exposing functionality related to the
 //! annotations that have been added to a single `PdfPage`.
 
-use crate::bindgen::{FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_FORMHANDLE, FPDF_PAGE};
+use crate::bindgen::{
+    FPDF_ANNOTATION, FPDF_ANNOT_FLAG_HIDDEN, FPDF_DOCUMENT, FPDF_FORMHANDLE, FPDF_PAGE,
+};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::{PdfiumError, PdfiumInternalError};
 use crate::pdf::color::PdfColor;
+use crate::pdf::document::page::annotation::appearance::{
+    has_normal_appearance, is_appearance_generation_supported,
+};
+use crate::pdf::document::page::annotation::builders::{
+    PdfHighlightAnnotationBuilder, PdfSquigglyAnnotationBuilder, PdfStrikeoutAnnotationBuilder,
+    PdfUnderlineAnnotationBuilder,
+};
+use crate::pdf::document::page::annotation::circle::PdfPageCircleAnnotation;
+use crate::pdf::document::page::annotation::file_attachment::PdfPageFileAttachmentAnnotation;
 use crate::pdf::document::page::annotation::free_text::PdfPageFreeTextAnnotation;
 use crate::pdf::document::page::annotation::highlight::PdfPageHighlightAnnotation;
 use crate::pdf::document::page::annotation::ink::PdfPageInkAnnotation;
@@ -20,11 +31,19 @@ use crate::pdf::document::page::annotation::underline::PdfPageUnderlineAnnotatio
 use crate::pdf::document::page::annotation::{
     PdfPageAnnotation, PdfPageAnnotationCommon, PdfPageAnnotationType,
 };
+use crate::pdf::document::page::generation::PdfContentGenerations;
 use crate::pdf::document::page::object::{PdfPageObject, PdfPageObjectCommon};
+use crate::pdf::document::page::text::chars::PdfPageTextCharIndex;
+use crate::pdf::document::page::text::selection::quads_for_chars;
+use crate::pdf::points::PdfPoints;
 use crate::pdf::quad_points::PdfQuadPoints;
+use crate::pdf::rect::PdfRect;
 use chrono::prelude::*;
 use std::ops::Range;
-use std::os::raw::c_int;
+use std::os::raw::{c_int, c_ulong, c_void};
+
+#[cfg(feature = "serde")]
+use crate::pdf::document::page::annotation::dto::PdfPageAnnotationDto;
 
 pub type PdfPageAnnotationIndex = usize;
 
@@ -73,6 +92,13 @@ impl<'a> PdfPageAnnotations<'a> {
             do_regenerate_page_content_after_each_change;
     }
 
+    /// Returns the internal `FPDF_DOCUMENT` handle of the document containing this
+    /// [PdfPageAnnotations] collection.
+    #[inline]
+    pub(crate) fn document_handle(&self) -> FPDF_DOCUMENT {
+        self.document_handle
+    }
+
     /// Returns the [PdfiumLibraryBindings] used by this [PdfPageAnnotations] collection.
     #[inline]
     pub fn bindings(&self) -> &'a dyn PdfiumLibraryBindings {
@@ -148,6 +174,172 @@ impl<'a> PdfPageAnnotations<'a> {
         PdfPageAnnotationsIterator::new(self)
     }
 
+    /// Returns the topmost visible [PdfPageAnnotation] in this [PdfPageAnnotations] collection
+    /// whose bounds contain the given point, or `None` if no visible annotation covers the point.
+    ///
+    /// Annotations are drawn in z-order, so the topmost annotation at a point is the one
+    /// a user clicking at that point would expect to select. Annotations flagged as hidden
+    /// are ignored. Pdfium's own hit tests for link annotations and, if the containing
+    /// `PdfDocument` has an embedded form, form field widgets are consulted in addition
+    /// to annotation bounds.
+    #[inline]
+    pub fn annotation_at_point(&self, x: PdfPoints, y: PdfPoints) -> Option<PdfPageAnnotation<'a>> {
+        self.z_order_at_point(x, y)
+            .and_then(|index| self.get(index).ok())
+    }
+
+    /// Returns the z-order of the topmost visible annotation in this [PdfPageAnnotations]
+    /// collection whose bounds contain the given point, or `None` if no visible annotation
+    /// covers the point. The z-order of an annotation is its index in this collection.
+    pub fn z_order_at_point(&self, x: PdfPoints, y: PdfPoints) -> Option<PdfPageAnnotationIndex> {
+        let is_hit = |annotation: &PdfPageAnnotation| {
+            !is_hidden(annotation)
+                && annotation
+                    .bounds()
+                    .map(|bounds| bounds.contains(x, y))
+                    .unwrap_or(false)
+        };
+
+        // Pdfium can locate the topmost link and form field widget at the point directly.
+        // Pdfium caches the links on a page the first time they are hit tested, so a z-order
+        // returned by Pdfium is only accepted if it still refers to a visible annotation of
+        // the expected type that covers the point.
+
+        let link_z_order = self.bindings().FPDFLink_GetLinkZOrderAtPoint(
+            self.page_handle,
+            x.value as f64,
+            y.value as f64,
+        );
+
+        let field_z_order = match self.form_handle {
+            Some(form_handle) => self.bindings().FPDFPage_FormFieldZOrderAtPoint(
+                form_handle,
+                self.page_handle,
+                x.value as f64,
+                y.value as f64,
+            ),
+            None => -1,
+        };
+
+        let found = [
+            (link_z_order, PdfPageAnnotationType::Link),
+            (field_z_order, PdfPageAnnotationType::Widget),
+        ]
+        .iter()
+        .filter(|(z_order, annotation_type)| {
+            *z_order >= 0
+                && self
+                    .get(*z_order as PdfPageAnnotationIndex)
+                    .map(|annotation| {
+                        annotation.annotation_type() == *annotation_type && is_hit(&annotation)
+                    })
+                    .unwrap_or(false)
+        })
+        .map(|(z_order, _)| *z_order as PdfPageAnnotationIndex)
+        .max();
+
+        // Only annotations drawn above any link or form field found by Pdfium need to be
+        // tested against their bounds.
+
+        let start = found.map(|index| index + 1).unwrap_or(0);
+
+        (start..self.len())
+            .rev()
+            .find(|index| {
+                self.get(*index)
+                    .map(|annotation| is_hit(&annotation))
+                    .unwrap_or(false)
+            })
+            .or(found)
+    }
+
+    /// Returns all visible annotations in this [PdfPageAnnotations] collection whose bounds
+    /// contain the given point, ordered from topmost to bottommost; that is, in descending
+    /// z-order, so that the first annotation returned is the one that would receive a click
+    /// at the point.
+    pub fn annotations_at_point(&self, x: PdfPoints, y: PdfPoints) -> Vec<PdfPageAnnotation<'a>> {
+        self.hit_test(|bounds| bounds.contains(x, y))
+            .into_iter()
+            .rev()
+            .filter_map(|index| self.get(index).ok())
+            .collect()
+    }
+
+    /// Returns all visible annotations in this [PdfPageAnnotations] collection whose bounds
+    /// overlap the given rectangle, ordered from topmost to bottommost; that is, in descending
+    /// z-order, as for [PdfPageAnnotations::annotations_at_point()].
+    pub fn annotations_intersecting(&self, rect: PdfRect) -> Vec<PdfPageAnnotation<'a>> {
+        self.hit_test(|bounds| bounds.does_overlap(&rect))
+            .into_iter()
+            .rev()
+            .filter_map(|index| self.get(index).ok())
+            .collect()
+    }
+
+    /// Returns the z-orders, in ascending order, of all visible annotations in this
+    /// [PdfPageAnnotations] collection whose bounds satisfy the given predicate.
+    fn hit_test(&self, predicate: impl Fn(&PdfRect) -> bool) -> Vec<PdfPageAnnotationIndex> {
+        self.iter()
+            .enumerate()
+            .filter(|(_, annotation)| {
+                !is_hidden(annotation)
+                    && annotation
+                        .bounds()
+                        .map(|bounds| predicate(&bounds))
+                        .unwrap_or(false)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Performs the given edits on this [PdfPageAnnotations] collection, regenerating the
+    /// content of the containing `PdfPage` once after all edits are complete rather than after
+    /// each individual edit, returning the result of the edits.
+    ///
+    /// If the containing `PdfPage` has a content regeneration strategy of
+    /// `PdfPageContentRegenerationStrategy::AutomaticOnEveryChange`, every annotation created,
+    /// changed, or deleted triggers content regeneration on the page, making it expensive to
+    /// add many annotations at once. Performing the edits in a batch avoids this. Content
+    /// regeneration is triggered once the edits are complete even if one of them fails, since
+    /// the edits preceding the failure will already have been applied.
+    ///
+    /// ```
+    /// # use pdfium_render::prelude::*;
+    /// # fn highlight(page: &mut PdfPage, regions: &[PdfRect]) -> Result<(), PdfiumError> {
+    /// page.annotations_mut().batch(|annotations| {
+    ///     for region in regions {
+    ///         annotations
+    ///             .create_highlight_annotation()?
+    ///             .attachment_points_mut()
+    ///             .create_attachment_point_at_end(PdfQuadPoints::from_rect(*region))?;
+    ///     }
+    ///
+    ///     Ok(())
+    /// })
+    /// # }
+    /// ```
+    pub fn batch<T>(
+        &mut self,
+        edit: impl FnOnce(&mut PdfPageAnnotations<'a>) -> Result<T, PdfiumError>,
+    ) -> Result<T, PdfiumError> {
+        let do_regenerate_page_content_after_each_change =
+            self.do_regenerate_page_content_after_each_change;
+
+        if do_regenerate_page_content_after_each_change {
+            self.do_regenerate_page_content_after_each_change(false);
+        }
+
+        let result = edit(self);
+
+        if do_regenerate_page_content_after_each_change {
+            self.do_regenerate_page_content_after_each_change(true);
+
+            self.regenerate_content()?;
+        }
+
+        result
+    }
+
     // Regenerates the content of the containing [PdfPage] if necessary after this
     // [PdfPageAnnotations] collection has been mutated.
     fn regenerate_content(&self) -> Result<(), PdfiumError> {
@@ -206,6 +398,81 @@ impl<'a> PdfPageAnnotations<'a> {
         }
     }
 
+    /// Creates a new [PdfPageCircleAnnotation] annotation in this [PdfPageAnnotations] collection,
+    /// returning the newly created annotation.
+    ///
+    /// If the containing `PdfPage` has a content regeneration strategy of
+    /// `PdfPageContentRegenerationStrategy::AutomaticOnEveryChange` then content regeneration
+    /// will be triggered on the page.
+    #[inline]
+    pub fn create_circle_annotation(&mut self) -> Result<PdfPageCircleAnnotation<'a>, PdfiumError> {
+        self.create_annotation(
+            PdfPageAnnotationType::Circle,
+            PdfPageCircleAnnotation::from_pdfium,
+        )
+    }
+
+    /// Creates a new [PdfPageFileAttachmentAnnotation] embedding the given byte data as a file
+    /// with the given name in this [PdfPageAnnotations] collection, returning the newly
+    /// created annotation. The embedded file is given the MIME type and description given,
+    /// if any; both are written when the document is next saved.
+    ///
+    /// The new annotation has no appearance stream, so it is not visible on the page until its
+    /// bounds are set and an appearance stream is added. Use a `PdfFileAttachmentAnnotationBuilder`
+    /// to create a file attachment annotation that displays an icon.
+    ///
+    /// If the containing `PdfPage` has a content regeneration strategy of
+    /// `PdfPageContentRegenerationStrategy::AutomaticOnEveryChange` then content regeneration
+    /// will be triggered on the page.
+    pub fn create_file_attachment_annotation(
+        &mut self,
+        name: &str,
+        bytes: &[u8],
+        mime_type: Option<&str>,
+        description: Option<&str>,
+    ) -> Result<PdfPageFileAttachmentAnnotation<'a>, PdfiumError> {
+        let mut annotation = self.create_annotation(
+            PdfPageAnnotationType::FileAttachment,
+            PdfPageFileAttachmentAnnotation::from_pdfium,
+        )?;
+
+        // Embedding the file is a two step operation. First, we create the FPDF_ATTACHMENT
+        // handle using the given name. Then, we add the given byte data to the FPDF_ATTACHMENT.
+
+        let handle = self
+            .bindings()
+            .FPDFAnnot_AddFileAttachment_str(annotation.handle(), name);
+
+        if !handle.is_null()
+            && self
+                .bindings()
+                .is_true(self.bindings().FPDFAttachment_SetFile(
+                    handle,
+                    self.document_handle,
+                    bytes.as_ptr() as *const c_void,
+                    bytes.len() as c_ulong,
+                ))
+        {
+            if mime_type.is_some() {
+                annotation.set_mime_type(mime_type)?;
+            }
+
+            if description.is_some() {
+                annotation.set_description(description)?;
+            }
+
+            Ok(annotation)
+        } else {
+            // Remove the new annotation, since it embeds no file.
+
+            self.delete_annotation(PdfPageAnnotation::FileAttachment(annotation))?;
+
+            Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ))
+        }
+    }
+
     /// Creates a new [PdfPageFreeTextAnnotation] containing the given text in this
     /// [PdfPageAnnotations] collection, returning the newly created annotation.
     ///
@@ -558,6 +825,173 @@ impl<'a> PdfPageAnnotations<'a> {
         Ok(annotation)
     }
 
+    /// Creates a new text markup annotation of the given type marking up the characters in
+    /// the given range of the containing `PdfPage`'s `PdfPageText` collection, coloring it with
+    /// the given [PdfColor]. The annotation type must be one of
+    /// [PdfPageAnnotationType::Highlight], [PdfPageAnnotationType::Underline],
+    /// [PdfPageAnnotationType::Squiggly], or [PdfPageAnnotationType::Strikeout].
+    ///
+    /// One attachment point is added for each run of adjacent characters that share a line
+    /// and rotation angle, following the rotation of the text, and an appearance stream is
+    /// generated so the annotation displays consistently in all PDF viewers. The range of
+    /// characters in a search result can be retrieved using the
+    /// `PdfPageTextSegments::char_range()` function.
+    ///
+    /// Returns [PdfiumError::CharIndexOutOfBounds] if the range is empty or extends beyond
+    /// the last character on the page, or [PdfiumError::NoCharsInRange] if none of the
+    /// characters in the range occupy any area of the page.
+    ///
+    /// If the containing `PdfPage` has a content regeneration strategy of
+    /// `PdfPageContentRegenerationStrategy::AutomaticOnEveryChange` then content regeneration
+    /// will be triggered on the page.
+    pub fn create_markup_over_chars(
+        &mut self,
+        chars: Range<PdfPageTextCharIndex>,
+        annotation_type: PdfPageAnnotationType,
+        color: PdfColor,
+    ) -> Result<PdfPageAnnotation<'a>, PdfiumError> {
+        match annotation_type {
+            PdfPageAnnotationType::Highlight
+            | PdfPageAnnotationType::Underline
+            | PdfPageAnnotationType::Squiggly
+            | PdfPageAnnotationType::Strikeout => {}
+            _ => return Err(PdfiumError::UnsupportedPdfAnnotationType),
+        }
+
+        let text_page = self.bindings.FPDFText_LoadPage(self.page_handle);
+
+        if text_page.is_null() {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let len = self.bindings.FPDFText_CountChars(text_page).max(0) as PdfPageTextCharIndex;
+
+        let quads = if chars.is_empty() || chars.end > len {
+            None
+        } else {
+            Some(quads_for_chars(text_page, chars, self.bindings))
+        };
+
+        self.bindings.FPDFText_ClosePage(text_page);
+
+        let quads = quads.ok_or(PdfiumError::CharIndexOutOfBounds)?;
+
+        if quads.is_empty() {
+            // None of the characters in the range occupy any area of the page.
+
+            return Err(PdfiumError::NoCharsInRange);
+        }
+
+        match annotation_type {
+            PdfPageAnnotationType::Highlight => quads
+                .iter()
+                .fold(
+                    PdfHighlightAnnotationBuilder::new().color(color),
+                    |builder, quad| builder.add_quad_points(*quad),
+                )
+                .create(self)
+                .map(PdfPageAnnotation::Highlight),
+            PdfPageAnnotationType::Underline => quads
+                .iter()
+                .fold(
+                    PdfUnderlineAnnotationBuilder::new().color(color),
+                    |builder, quad| builder.add_quad_points(*quad),
+                )
+                .create(self)
+                .map(PdfPageAnnotation::Underline),
+            PdfPageAnnotationType::Squiggly => quads
+                .iter()
+                .fold(
+                    PdfSquigglyAnnotationBuilder::new().color(color),
+                    |builder, quad| builder.add_quad_points(*quad),
+                )
+                .create(self)
+                .map(PdfPageAnnotation::Squiggly),
+            _ => quads
+                .iter()
+                .fold(
+                    PdfStrikeoutAnnotationBuilder::new().color(color),
+                    |builder, quad| builder.add_quad_points(*quad),
+                )
+                .create(self)
+                .map(PdfPageAnnotation::Strikeout),
+        }
+    }
+
+    /// Creates a new annotation in this [PdfPageAnnotations] collection from the properties
+    /// in the given [PdfPageAnnotationDto], returning the newly created annotation. An
+    /// appearance stream is generated for the new annotation if its type supports it.
+    ///
+    /// Annotations of types [PdfPageAnnotationType::Circle], [PdfPageAnnotationType::FreeText],
+    /// [PdfPageAnnotationType::Highlight], [PdfPageAnnotationType::Ink],
+    /// [PdfPageAnnotationType::Popup], [PdfPageAnnotationType::Square],
+    /// [PdfPageAnnotationType::Squiggly], [PdfPageAnnotationType::Stamp],
+    /// [PdfPageAnnotationType::Strikeout], [PdfPageAnnotationType::Text], and
+    /// [PdfPageAnnotationType::Underline] can be created. Returns an error for any other type.
+    ///
+    /// If the containing `PdfPage` has a content regeneration strategy of
+    /// `PdfPageContentRegenerationStrategy::AutomaticOnEveryChange` then content regeneration
+    /// will be triggered on the page.
+    ///
+    /// This function is only available when this crate's `serde` feature is enabled.
+    #[cfg(feature = "serde")]
+    pub fn create_from_dto(
+        &mut self,
+        dto: &PdfPageAnnotationDto,
+    ) -> Result<PdfPageAnnotation<'a>, PdfiumError> {
+        let contents = dto.contents.as_deref().unwrap_or_default();
+
+        match dto.subtype {
+            PdfPageAnnotationType::Circle => self
+                .create_circle_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Circle),
+            PdfPageAnnotationType::FreeText => self
+                .create_free_text_annotation(contents)
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::FreeText),
+            PdfPageAnnotationType::Highlight => self
+                .create_highlight_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Highlight),
+            PdfPageAnnotationType::Ink => self
+                .create_ink_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Ink),
+            PdfPageAnnotationType::Popup => self
+                .create_popup_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Popup),
+            PdfPageAnnotationType::Square => self
+                .create_square_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Square),
+            PdfPageAnnotationType::Squiggly => self
+                .create_squiggly_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Squiggly),
+            PdfPageAnnotationType::Stamp => self
+                .create_stamp_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Stamp),
+            PdfPageAnnotationType::Strikeout => self
+                .create_strikeout_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Strikeout),
+            PdfPageAnnotationType::Text => self
+                .create_text_annotation(contents)
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Text),
+            PdfPageAnnotationType::Underline => self
+                .create_underline_annotation()
+                .and_then(|annotation| dto.apply(annotation))
+                .map(PdfPageAnnotation::Underline),
+            _ => Err(PdfiumError::UnsupportedPdfAnnotationType),
+        }
+    }
+
     /// Removes the given [PdfPageAnnotation] from this [PdfPageAnnotations] collection,
     /// consuming the [PdfPageAnnotation].
     ///
@@ -580,6 +1014,8 @@ impl<'a> PdfPageAnnotations<'a> {
             .bindings
             .is_true(self.bindings.FPDFPage_RemoveAnnot(self.page_handle, index))
         {
+            PdfContentGenerations::increment_for_page_handle(self.page_handle);
+
             self.regenerate_content()
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -587,6 +1023,45 @@ impl<'a> PdfPageAnnotations<'a> {
             ))
         }
     }
+
+    /// Generates a normal appearance stream for every annotation in this [PdfPageAnnotations]
+    /// collection that does not already have one, returning the number of appearance streams
+    /// generated. Annotations of types for which appearance streams cannot be generated are
+    /// skipped; see `PdfPageAnnotationCommon::generate_appearance_stream()` for the supported types.
+    ///
+    /// If the containing `PdfPage` has a content regeneration strategy of
+    /// `PdfPageContentRegenerationStrategy::AutomaticOnEveryChange` then content regeneration
+    /// will be triggered on the page.
+    pub fn generate_missing_appearance_streams(&mut self) -> Result<usize, PdfiumError> {
+        let mut count = 0;
+
+        for index in self.as_range() {
+            let mut annotation = self.get(index)?;
+
+            if is_appearance_generation_supported(annotation.annotation_type())
+                && !has_normal_appearance(&annotation)
+                && annotation.generate_appearance_stream()?
+            {
+                count += 1;
+            }
+        }
+
+        if count > 0 {
+            self.regenerate_content()?;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Returns `true` if the given annotation is flagged as hidden, and so should not be
+/// considered when hit testing.
+fn is_hidden(annotation: &PdfPageAnnotation) -> bool {
+    annotation
+        .bindings()
+        .FPDFAnnot_GetFlags(annotation.handle()) as u32
+        & FPDF_ANNOT_FLAG_HIDDEN
+        != 0
 }
 
 /// An iterator over all the [PdfPageAnnotation] objects in a [PdfPageAnnotations] collection.
@@ -616,3 +1091,201 @@ impl<'a> Iterator for PdfPageAnnotationsIterator<'a> {
         next.ok()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_annotation_hit_testing() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let mut lower = page.annotations_mut().create_square_annotation()?;
+
+        lower.set_bounds(PdfRect::new_from_values(100.0, 100.0, 200.0, 200.0))?;
+
+        let mut upper = page.annotations_mut().create_circle_annotation()?;
+
+        upper.set_bounds(PdfRect::new_from_values(150.0, 150.0, 250.0, 250.0))?;
+
+        let annotations = page.annotations();
+
+        // The circle overlaps the square and is drawn above it.
+
+        let hit = annotations
+            .annotation_at_point(PdfPoints::new(175.0), PdfPoints::new(175.0))
+            .unwrap();
+
+        assert_eq!(hit.annotation_type(), PdfPageAnnotationType::Circle);
+        assert_eq!(hit.z_order(), Some(1));
+
+        let hits = annotations.annotations_at_point(PdfPoints::new(175.0), PdfPoints::new(175.0));
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].annotation_type(), PdfPageAnnotationType::Circle);
+        assert_eq!(hits[1].annotation_type(), PdfPageAnnotationType::Square);
+
+        assert_eq!(
+            annotations.z_order_at_point(PdfPoints::new(120.0), PdfPoints::new(120.0)),
+            Some(0)
+        );
+
+        assert!(annotations
+            .annotation_at_point(PdfPoints::new(400.0), PdfPoints::new(400.0))
+            .is_none());
+
+        assert_eq!(
+            annotations
+                .annotations_intersecting(PdfRect::new_from_values(0.0, 0.0, 120.0, 120.0))
+                .len(),
+            1
+        );
+
+        // Both hit testing functions order their results from topmost to bottommost.
+
+        let hits =
+            annotations.annotations_intersecting(PdfRect::new_from_values(0.0, 0.0, 300.0, 300.0));
+
+        assert_eq!(hits.len(), 2);
+        assert_eq!(hits[0].annotation_type(), PdfPageAnnotationType::Circle);
+        assert_eq!(hits[1].annotation_type(), PdfPageAnnotationType::Square);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_create_markup_over_chars() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let font = document.fonts_mut().courier();
+
+        page.objects_mut().create_text_object(
+            PdfPoints::new(50.0),
+            PdfPoints::new(700.0),
+            "one two one two one",
+            font,
+            PdfPoints::new(10.0),
+        )?;
+
+        page.objects_mut().create_text_object(
+            PdfPoints::new(50.0),
+            PdfPoints::new(600.0),
+            "three",
+            font,
+            PdfPoints::new(10.0),
+        )?;
+
+        // Mark up the third search result.
+
+        let chars = {
+            let text = page.text()?;
+
+            let search = text.search("one", &PdfSearchOptions::new());
+
+            let chars = search
+                .iter(PdfSearchDirection::SearchForward)
+                .nth(2)
+                .unwrap()
+                .char_range();
+
+            chars
+        };
+
+        assert_eq!(chars, 16..19);
+
+        let annotation = page.annotations_mut().create_markup_over_chars(
+            chars,
+            PdfPageAnnotationType::Squiggly,
+            PdfColor::RED,
+        )?;
+
+        assert_eq!(
+            annotation.annotation_type(),
+            PdfPageAnnotationType::Squiggly
+        );
+        assert_eq!(annotation.attachment_points().len(), 1);
+
+        assert!(matches!(
+            page.annotations_mut().create_markup_over_chars(
+                0..3,
+                PdfPageAnnotationType::Square,
+                PdfColor::RED,
+            ),
+            Err(PdfiumError::UnsupportedPdfAnnotationType)
+        ));
+
+        assert!(matches!(
+            page.annotations_mut().create_markup_over_chars(
+                16..40,
+                PdfPageAnnotationType::Highlight,
+                PdfColor::YELLOW,
+            ),
+            Err(PdfiumError::CharIndexOutOfBounds)
+        ));
+
+        // The line break generated by Pdfium between the two text objects occupies no area.
+
+        assert!(matches!(
+            page.annotations_mut().create_markup_over_chars(
+                19..21,
+                PdfPageAnnotationType::Highlight,
+                PdfColor::YELLOW,
+            ),
+            Err(PdfiumError::NoCharsInRange)
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_batch() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        page.set_content_regeneration_strategy(
+            PdfPageContentRegenerationStrategy::AutomaticOnEveryChange,
+        );
+
+        let count = page.annotations_mut().batch(|annotations| {
+            for index in 0..20 {
+                let top = 800.0 - index as f32 * 20.0;
+
+                let mut annotation = annotations.create_square_annotation()?;
+
+                annotation.set_bounds(PdfRect::new_from_values(top - 10.0, 50.0, top, 150.0))?;
+            }
+
+            Ok(annotations.len())
+        })?;
+
+        assert_eq!(count, 20);
+        assert_eq!(page.annotations().len(), 20);
+
+        // Errors returned by the edits are passed on to the caller.
+
+        assert!(page
+            .annotations_mut()
+            .batch(|annotations| annotations.get(100).map(|_| ()))
+            .is_err());
+
+        Ok(())
+    }
+}
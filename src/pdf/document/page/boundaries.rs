@@ -4,6 +4,7 @@
 use crate::bindgen::{FPDF_BOOL, FPDF_PAGE, FS_RECTF};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::PdfiumError;
+use crate::pdf::document::page::generation::PdfContentGenerations;
 use crate::pdf::rect::PdfRect;
 use std::os::raw::c_float;
 
@@ -132,6 +133,8 @@ impl<'a> PdfPageBoundaries<'a> {
             rect.top.value,
         );
 
+        PdfContentGenerations::increment_for_page_handle(self.page_handle);
+
         Ok(())
     }
 
@@ -157,6 +160,8 @@ impl<'a> PdfPageBoundaries<'a> {
             rect.top.value,
         );
 
+        PdfContentGenerations::increment_for_page_handle(self.page_handle);
+
         Ok(())
     }
 
@@ -182,6 +187,8 @@ impl<'a> PdfPageBoundaries<'a> {
             rect.top.value,
         );
 
+        PdfContentGenerations::increment_for_page_handle(self.page_handle);
+
         Ok(())
     }
 
@@ -207,6 +214,8 @@ impl<'a> PdfPageBoundaries<'a> {
             rect.top.value,
         );
 
+        PdfContentGenerations::increment_for_page_handle(self.page_handle);
+
         Ok(())
     }
 
@@ -231,6 +240,8 @@ impl<'a> PdfPageBoundaries<'a> {
             rect.top.value,
         );
 
+        PdfContentGenerations::increment_for_page_handle(self.page_handle);
+
         Ok(())
     }
 
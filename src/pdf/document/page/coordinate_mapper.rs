@@ -0,0 +1,179 @@
+//! Defines the [PdfPageCoordinateMapper] struct, converting positions between the coordinate
+//! system of a `PdfPage`, measured in points, and the coordinate system of a bitmap into which
+//! that page has been rendered, measured in pixels.
+
+use crate::bindgen::FPDF_PAGE;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::bitmap::Pixels;
+use crate::pdf::document::page::render_config::PdfRenderConfig;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+use std::os::raw::{c_double, c_int};
+
+/// Converts positions between the coordinate system of a [PdfPage], measured in [PdfPoints]
+/// with its origin at the bottom left of the page, and the coordinate system of a bitmap
+/// into which the page has been rendered, measured in [Pixels] with its origin at the top left
+/// of the bitmap.
+///
+/// The pixel dimensions and rotation of the rendered page are calculated once, from the
+/// [PdfRenderConfig] used to render it, so a viewer can map mouse clicks to page positions
+/// (and page positions back to pixels, for example to draw selection highlights) without
+/// repeating that calculation for every conversion. Any custom transformation matrix set on the
+/// [PdfRenderConfig] is not taken into account.
+pub struct PdfPageCoordinateMapper<'a> {
+    page_handle: FPDF_PAGE,
+    bindings: &'a dyn PdfiumLibraryBindings,
+    origin_x: c_int,
+    origin_y: c_int,
+    width: c_int,
+    height: c_int,
+    rotate: c_int,
+}
+
+impl<'a> PdfPageCoordinateMapper<'a> {
+    /// Creates a new [PdfPageCoordinateMapper] for the given [PdfPage], as rendered
+    /// using the given [PdfRenderConfig].
+    pub fn new(page: &'a PdfPage, config: &PdfRenderConfig) -> Self {
+        let settings = config.apply_to_page(page);
+
+        PdfPageCoordinateMapper {
+            page_handle: page.page_handle(),
+            bindings: page.bindings(),
+            origin_x: 0,
+            origin_y: 0,
+            width: settings.width,
+            height: settings.height,
+            rotate: settings.rotate,
+        }
+    }
+
+    /// Sets the position of the top left corner of the rendered page in the device coordinate
+    /// system used by this [PdfPageCoordinateMapper]. This is useful when the rendered page is
+    /// drawn at an offset within a larger viewport, for example when the viewport is scrolled.
+    /// The default is `(0, 0)`.
+    #[inline]
+    pub fn set_origin(mut self, x: Pixels, y: Pixels) -> Self {
+        self.origin_x = x;
+        self.origin_y = y;
+
+        self
+    }
+
+    /// Returns the pixel width of the rendered page.
+    #[inline]
+    pub fn width(&self) -> Pixels {
+        self.width
+    }
+
+    /// Returns the pixel height of the rendered page.
+    #[inline]
+    pub fn height(&self) -> Pixels {
+        self.height
+    }
+
+    /// Converts the given device position, measured in [Pixels], to the equivalent position
+    /// on the page, measured in [PdfPoints]. Positions outside the rendered page are
+    /// converted to positions outside the page boundaries.
+    pub fn pixels_to_points(
+        &self,
+        x: Pixels,
+        y: Pixels,
+    ) -> Result<(PdfPoints, PdfPoints), PdfiumError> {
+        let mut page_x: c_double = 0.0;
+        let mut page_y: c_double = 0.0;
+
+        if self.bindings.is_true(self.bindings.FPDF_DeviceToPage(
+            self.page_handle,
+            self.origin_x,
+            self.origin_y,
+            self.width,
+            self.height,
+            self.rotate,
+            x,
+            y,
+            &mut page_x,
+            &mut page_y,
+        )) {
+            Ok((PdfPoints::new(page_x as f32), PdfPoints::new(page_y as f32)))
+        } else {
+            Err(PdfiumError::CoordinateConversionFunctionIndicatedError)
+        }
+    }
+
+    /// Converts the given page position, measured in [PdfPoints], to the equivalent
+    /// device position, measured in [Pixels].
+    pub fn points_to_pixels(
+        &self,
+        x: PdfPoints,
+        y: PdfPoints,
+    ) -> Result<(Pixels, Pixels), PdfiumError> {
+        let mut device_x: c_int = 0;
+        let mut device_y: c_int = 0;
+
+        if self.bindings.is_true(self.bindings.FPDF_PageToDevice(
+            self.page_handle,
+            self.origin_x,
+            self.origin_y,
+            self.width,
+            self.height,
+            self.rotate,
+            x.value.into(),
+            y.value.into(),
+            &mut device_x,
+            &mut device_y,
+        )) {
+            Ok((device_x as Pixels, device_y as Pixels))
+        } else {
+            Err(PdfiumError::CoordinateConversionFunctionIndicatedError)
+        }
+    }
+
+    /// Converts the given rectangle on the page, measured in [PdfPoints], to the equivalent
+    /// device rectangle, returned as `(left, top, right, bottom)` pixel coordinates.
+    /// The returned coordinates are normalized so that `left <= right` and `top <= bottom`,
+    /// irrespective of the rotation of the rendered page.
+    pub fn rect_to_pixels(
+        &self,
+        rect: PdfRect,
+    ) -> Result<(Pixels, Pixels, Pixels, Pixels), PdfiumError> {
+        let (x1, y1) = self.points_to_pixels(rect.left, rect.top)?;
+
+        let (x2, y2) = self.points_to_pixels(rect.right, rect.bottom)?;
+
+        Ok((x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_coordinate_mapper_round_trip() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let config = PdfRenderConfig::new()
+            .set_target_width(1000)
+            .rotate(PdfPageRenderRotation::Degrees90, false);
+
+        let mapper = page.coordinate_mapper(&config);
+
+        let (x, y) = mapper.pixels_to_points(250, 400)?;
+
+        let (round_trip_x, round_trip_y) = mapper.points_to_pixels(x, y)?;
+
+        // Allow for rounding to whole pixels.
+
+        assert!((round_trip_x - 250).abs() <= 1);
+        assert!((round_trip_y - 400).abs() <= 1);
+
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 //! Defines the [PdfFormField] enum, exposing functionality related to a single interactive
 //! form field in a [PdfForm].
 
+pub mod barcode;
 pub mod button;
 pub mod checkbox;
 pub mod combo;
@@ -16,11 +17,13 @@ pub mod unknown;
 use crate::bindgen::{
     FPDF_ANNOTATION, FPDF_FORMFIELD_CHECKBOX, FPDF_FORMFIELD_COMBOBOX, FPDF_FORMFIELD_LISTBOX,
     FPDF_FORMFIELD_PUSHBUTTON, FPDF_FORMFIELD_RADIOBUTTON, FPDF_FORMFIELD_SIGNATURE,
-    FPDF_FORMFIELD_TEXTFIELD, FPDF_FORMFIELD_UNKNOWN, FPDF_FORMHANDLE,
+    FPDF_FORMFIELD_TEXTFIELD, FPDF_FORMFIELD_UNKNOWN, FPDF_FORMHANDLE, FPDF_PAGE, FS_RECTF,
 };
 use crate::bindings::PdfiumLibraryBindings;
-use crate::error::PdfiumError;
+use crate::error::{PdfiumError, PdfiumInternalError};
 use crate::pdf::appearance_mode::PdfAppearanceMode;
+use crate::pdf::bitmap::{PdfBitmap, PdfBitmapFormat, Pixels};
+use crate::pdf::document::form::scripts::PdfFormFieldScripts;
 use crate::pdf::document::page::field::button::PdfFormPushButtonField;
 use crate::pdf::document::page::field::checkbox::PdfFormCheckboxField;
 use crate::pdf::document::page::field::combo::PdfFormComboBoxField;
@@ -30,7 +33,9 @@ use crate::pdf::document::page::field::radio::PdfFormRadioButtonField;
 use crate::pdf::document::page::field::signature::PdfFormSignatureField;
 use crate::pdf::document::page::field::text::PdfFormTextField;
 use crate::pdf::document::page::field::unknown::PdfFormUnknownField;
-use std::os::raw::c_int;
+use crate::pdf::document::page::render_config::PdfRenderConfig;
+use crate::pdf::points::PdfPoints;
+use std::os::raw::{c_double, c_int};
 
 #[cfg(doc)]
 use crate::pdf::document::form::PdfForm;
@@ -99,6 +104,7 @@ pub enum PdfFormField<'a> {
 impl<'a> PdfFormField<'a> {
     pub(crate) fn from_pdfium(
         form_handle: FPDF_FORMHANDLE,
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Option<Self> {
@@ -112,33 +118,61 @@ impl<'a> PdfFormField<'a> {
             PdfFormFieldType::from_pdfium(result).unwrap_or(PdfFormFieldType::Unknown);
 
         Some(match form_field_type {
-            PdfFormFieldType::PushButton => PdfFormField::PushButton(
-                PdfFormPushButtonField::from_pdfium(form_handle, annotation_handle, bindings),
-            ),
-            PdfFormFieldType::Checkbox => PdfFormField::Checkbox(
-                PdfFormCheckboxField::from_pdfium(form_handle, annotation_handle, bindings),
-            ),
-            PdfFormFieldType::RadioButton => PdfFormField::RadioButton(
-                PdfFormRadioButtonField::from_pdfium(form_handle, annotation_handle, bindings),
-            ),
-            PdfFormFieldType::ComboBox => PdfFormField::ComboBox(
-                PdfFormComboBoxField::from_pdfium(form_handle, annotation_handle, bindings),
-            ),
+            PdfFormFieldType::PushButton => {
+                PdfFormField::PushButton(PdfFormPushButtonField::from_pdfium(
+                    form_handle,
+                    page_handle,
+                    annotation_handle,
+                    bindings,
+                ))
+            }
+            PdfFormFieldType::Checkbox => {
+                PdfFormField::Checkbox(PdfFormCheckboxField::from_pdfium(
+                    form_handle,
+                    page_handle,
+                    annotation_handle,
+                    bindings,
+                ))
+            }
+            PdfFormFieldType::RadioButton => {
+                PdfFormField::RadioButton(PdfFormRadioButtonField::from_pdfium(
+                    form_handle,
+                    page_handle,
+                    annotation_handle,
+                    bindings,
+                ))
+            }
+            PdfFormFieldType::ComboBox => {
+                PdfFormField::ComboBox(PdfFormComboBoxField::from_pdfium(
+                    form_handle,
+                    page_handle,
+                    annotation_handle,
+                    bindings,
+                ))
+            }
             PdfFormFieldType::ListBox => PdfFormField::ListBox(PdfFormListBoxField::from_pdfium(
                 form_handle,
+                page_handle,
                 annotation_handle,
                 bindings,
             )),
             PdfFormFieldType::Text => PdfFormField::Text(PdfFormTextField::from_pdfium(
                 form_handle,
+                page_handle,
                 annotation_handle,
                 bindings,
             )),
-            PdfFormFieldType::Signature => PdfFormField::Signature(
-                PdfFormSignatureField::from_pdfium(form_handle, annotation_handle, bindings),
-            ),
+            PdfFormFieldType::Signature => {
+                PdfFormField::Signature(PdfFormSignatureField::from_pdfium(
+                    form_handle,
+                    page_handle,
+                    annotation_handle,
+                    bindings,
+                ))
+            }
             _ => PdfFormField::Unknown(PdfFormUnknownField::from_pdfium(
                 form_handle,
+                page_handle,
                 annotation_handle,
                 bindings,
             )),
@@ -282,6 +316,185 @@ impl<'a> PdfFormField<'a> {
             _ => None,
         }
     }
+
+    /// Renders the widget annotation of this [PdfFormField] into a new [PdfBitmap] sized
+    /// to fit the widget's bounds, using the rendering options configured in the
+    /// given [PdfRenderConfig].
+    ///
+    /// The widget is drawn by Pdfium's form fill environment exactly as an interactive viewer
+    /// would draw it, including any form field highlight colors configured with
+    /// [PdfRenderConfig::highlight_form_fields_of_type()] and the widget's current focus state. Drawing
+    /// is clipped tightly to the widget's bounds, so parts of neighbouring widgets may appear
+    /// at the edges of the bitmap, but the rest of the page's content is not rendered.
+    /// This is useful for implementing accessibility zoom or custom field magnifiers.
+    ///
+    /// The target sizes, scale factors, and maximum sizes configured in the given
+    /// [PdfRenderConfig] are applied to the widget's bounds rather than to the whole page;
+    /// for example, a target width of 400 pixels renders the widget 400 pixels wide.
+    /// Rotation settings are ignored; the widget is always rendered in the orientation in
+    /// which its page is displayed.
+    pub fn render_widget(&self, config: &PdfRenderConfig) -> Result<PdfBitmap<'a>, PdfiumError> {
+        let bindings = self.bindings_with_lifetime();
+
+        let form_handle = *self.form_handle();
+
+        let page_handle = *self.page_handle();
+
+        let mut rect = FS_RECTF {
+            left: 0.0,
+            top: 0.0,
+            right: 0.0,
+            bottom: 0.0,
+        };
+
+        if !bindings.is_true(bindings.FPDFAnnot_GetRect(*self.annotation_handle(), &mut rect)) {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let widget_width = (rect.right - rect.left).abs();
+
+        let widget_height = (rect.top - rect.bottom).abs();
+
+        // Rotating the page by a quarter turn swaps the widget's displayed width and height.
+
+        let (display_width, display_height) = if bindings.FPDFPage_GetRotation(page_handle) % 2 == 1
+        {
+            (widget_height, widget_width)
+        } else {
+            (widget_width, widget_height)
+        };
+
+        let mut settings = config
+            .apply_to_dimensions(
+                PdfPoints::new(display_width),
+                PdfPoints::new(display_height),
+                None,
+            )
+            .without_subpixel_reversal();
+
+        if settings.is_maximum_bitmap_bytes_exceeded {
+            return Err(PdfiumError::RenderBudgetExceeded);
+        }
+
+        if settings.width <= 0 || settings.height <= 0 {
+            return Err(PdfiumError::ZeroSizedRenderTarget);
+        }
+
+        settings.rotate = 0;
+
+        // Scale the whole page so that the widget is drawn at the requested size, then offset
+        // the page so that the top left corner of the widget lies at the bitmap's origin.
+        // Pdfium clips drawing to the bitmap's bounds.
+
+        let page_width = (bindings.FPDF_GetPageWidthF(page_handle) * settings.width as f32
+            / display_width)
+            .round() as c_int;
+
+        let page_height = (bindings.FPDF_GetPageHeightF(page_handle) * settings.height as f32
+            / display_height)
+            .round() as c_int;
+
+        let (mut left, mut top) = (c_int::MAX, c_int::MAX);
+
+        for (x, y) in [(rect.left, rect.top), (rect.right, rect.bottom)] {
+            let (mut device_x, mut device_y) = (0, 0);
+
+            bindings.FPDF_PageToDevice(
+                page_handle,
+                0,
+                0,
+                page_width,
+                page_height,
+                settings.rotate,
+                x as c_double,
+                y as c_double,
+                &mut device_x,
+                &mut device_y,
+            );
+
+            left = left.min(device_x);
+            top = top.min(device_y);
+        }
+
+        let mut bitmap = PdfBitmap::empty(
+            settings.width as Pixels,
+            settings.height as Pixels,
+            PdfBitmapFormat::from_pdfium(settings.format as u32)
+                .unwrap_or_else(|_| PdfBitmapFormat::default()),
+            bindings,
+        )?;
+
+        let bitmap_handle = *bitmap.handle();
+
+        if settings.do_clear_to_transparent {
+            if bitmap.format()? != PdfBitmapFormat::BGRA {
+                return Err(PdfiumError::TransparentBackgroundRequiresBitmapWithAlphaChannel);
+            }
+
+            let buffer_length = bindings.FPDFBitmap_GetStride(bitmap_handle) * settings.height;
+
+            bindings.FPDFBitmap_SetBuffer(bitmap_handle, &vec![0; buffer_length.max(0) as usize]);
+        } else if settings.do_clear_bitmap_before_rendering {
+            bindings.FPDFBitmap_FillRect(
+                bitmap_handle,
+                0,
+                0,
+                settings.width,
+                settings.height,
+                settings.clear_color,
+            );
+        }
+
+        if let Some(form_field_highlight) = settings.form_field_highlight.as_ref() {
+            for (form_field_type, (color, alpha)) in form_field_highlight.iter() {
+                bindings.FPDF_SetFormFieldHighlightColor(form_handle, *form_field_type, *color);
+
+                bindings.FPDF_SetFormFieldHighlightAlpha(form_handle, *alpha);
+            }
+        }
+
+        bindings.FPDF_FFLDraw(
+            form_handle,
+            bitmap_handle,
+            page_handle,
+            -left,
+            -top,
+            page_width,
+            page_height,
+            settings.rotate,
+            settings.render_flags,
+        );
+
+        bitmap.set_byte_order_from_render_settings(&settings);
+
+        if let Some(threshold) = settings.monochrome_threshold {
+            if settings.do_dither_monochrome {
+                bitmap.apply_dithering(threshold)?;
+            } else {
+                bitmap.apply_threshold(threshold)?;
+            }
+        }
+
+        Ok(bitmap)
+    }
+
+    /// Returns the [PdfiumLibraryBindings] used by this [PdfFormField], with the lifetime
+    /// of the bindings rather than of this [PdfFormField].
+    #[inline]
+    fn bindings_with_lifetime(&self) -> &'a dyn PdfiumLibraryBindings {
+        match self {
+            PdfFormField::PushButton(field) => field.bindings(),
+            PdfFormField::Checkbox(field) => field.bindings(),
+            PdfFormField::RadioButton(field) => field.bindings(),
+            PdfFormField::ComboBox(field) => field.bindings(),
+            PdfFormField::ListBox(field) => field.bindings(),
+            PdfFormField::Signature(field) => field.bindings(),
+            PdfFormField::Text(field) => field.bindings(),
+            PdfFormField::Unknown(field) => field.bindings(),
+        }
+    }
 }
 
 /// Functionality common to all [PdfFormField] objects, regardless of their [PdfFormFieldType].
@@ -295,6 +508,20 @@ pub trait PdfFormFieldCommon {
     /// Returns the value currently set for the given appearance mode for this [PdfFormField],
     /// if any.
     fn appearance_mode_value(&self, appearance_mode: PdfAppearanceMode) -> Option<String>;
+
+    /// Returns the JavaScript actions run by a viewer when the value of this [PdfFormField]
+    /// is typed, formatted, validated, or calculated.
+    fn scripts(&self) -> PdfFormFieldScripts;
+
+    /// Replaces the normal appearance stream of this [PdfFormField] with one generated by
+    /// Pdfium from the field's current value, so that the value is displayed correctly by
+    /// viewers that do not regenerate appearances themselves.
+    ///
+    /// Setting the value of a text field, combo box, or list box removes its appearance
+    /// stream; call this function afterwards to have Pdfium draw the new value, or instead
+    /// ask viewers to regenerate every field's appearance using
+    /// `PdfDocument::set_form_needs_appearances()`. Signature fields are left unchanged.
+    fn regenerate_appearance(&mut self) -> Result<(), PdfiumError>;
 }
 
 // Blanket implementation for all PdfFormFieldCommon types.
@@ -317,6 +544,20 @@ where
     fn appearance_mode_value(&self, appearance_mode: PdfAppearanceMode) -> Option<String> {
         self.appearance_mode_value_impl(appearance_mode)
     }
+
+    #[inline]
+    fn scripts(&self) -> PdfFormFieldScripts {
+        PdfFormFieldScripts::from_pdfium(
+            *self.form_handle(),
+            *self.annotation_handle(),
+            self.bindings(),
+        )
+    }
+
+    #[inline]
+    fn regenerate_appearance(&mut self) -> Result<(), PdfiumError> {
+        self.regenerate_appearance_impl()
+    }
 }
 
 impl<'a> PdfFormFieldPrivate<'a> for PdfFormField<'a> {
@@ -325,6 +566,11 @@ impl<'a> PdfFormFieldPrivate<'a> for PdfFormField<'a> {
         self.unwrap_as_trait().form_handle()
     }
 
+    #[inline]
+    fn page_handle(&self) -> &FPDF_PAGE {
+        self.unwrap_as_trait().page_handle()
+    }
+
     #[inline]
     fn annotation_handle(&self) -> &FPDF_ANNOTATION {
         self.unwrap_as_trait().annotation_handle()
@@ -391,3 +637,60 @@ impl<'a> From<PdfFormUnknownField<'a>> for PdfFormField<'a> {
         Self::Unknown(field)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_render_widget() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("test/form-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let mut annotation = page
+            .annotations()
+            .iter()
+            .find(|annotation| {
+                annotation
+                    .as_form_field()
+                    .and_then(|field| field.as_text_field())
+                    .is_some()
+            })
+            .unwrap();
+
+        let bounds = annotation.bounds()?;
+
+        annotation
+            .as_form_field_mut()
+            .and_then(|field| field.as_text_field_mut())
+            .unwrap()
+            .set_value("Hello")?;
+
+        let bitmap = annotation.as_form_field().unwrap().render_widget(
+            &PdfRenderConfig::new()
+                .set_target_width(200)
+                .set_clear_color(PdfColor::WHITE)
+                .highlight_all_form_fields(PdfColor::YELLOW),
+        )?;
+
+        // The bitmap is sized to the widget's bounds, not to the page.
+
+        let expected_height = 200.0 * bounds.height().value / bounds.width().value;
+
+        assert_eq!(bitmap.width(), 200);
+        assert!((bitmap.height() as f32 - expected_height).abs() <= 1.0);
+
+        // The widget's highlight and value must have been drawn over the white background.
+
+        assert!(bitmap
+            .as_rgba_bytes()
+            .chunks_exact(4)
+            .any(|pixel| pixel[..3] != [255, 255, 255]));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,153 @@
+//! Defines the [PdfFormBarcodeParameters] struct, exposing the symbology parameters of a
+//! barcode form field created by Adobe Acrobat.
+
+use crate::bindgen::FPDF_ANNOTATION;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::field::private::internal::get_text_value;
+use std::os::raw::c_float;
+
+#[cfg(doc)]
+use crate::pdf::document::page::field::text::PdfFormTextField;
+
+/// The symbology used to encode the value of a barcode form field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PdfFormBarcodeSymbology {
+    /// A stacked linear PDF417 barcode.
+    Pdf417,
+
+    /// A two-dimensional QR Code barcode.
+    QrCode,
+
+    /// A two-dimensional Data Matrix barcode.
+    DataMatrix,
+
+    /// A symbology not recognized by `pdfium-render`, identified by the given name.
+    Other(String),
+}
+
+impl PdfFormBarcodeSymbology {
+    #[inline]
+    pub(crate) fn from_pdf_name(name: &str) -> Self {
+        match name {
+            "PDF417" => PdfFormBarcodeSymbology::Pdf417,
+            "QRCode" => PdfFormBarcodeSymbology::QrCode,
+            "DataMatrix" => PdfFormBarcodeSymbology::DataMatrix,
+            _ => PdfFormBarcodeSymbology::Other(name.to_owned()),
+        }
+    }
+}
+
+/// The symbology parameters of a barcode form field.
+///
+/// Pdfium does not model barcode fields; Adobe Acrobat stores them as text fields whose
+/// `/PMD` (paper metadata) dictionary describes how the field's value should be encoded
+/// when the form is printed. Pdfium does not draw the barcode itself, so applications
+/// that need to display or decode it must do so using these parameters and the value
+/// returned by [PdfFormTextField::value()].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfFormBarcodeParameters {
+    symbology: PdfFormBarcodeSymbology,
+    module_width: Option<f32>,
+    module_height: Option<f32>,
+    error_correction_level: Option<u32>,
+    rows: Option<u32>,
+    columns: Option<u32>,
+    resolution: Option<f32>,
+}
+
+impl PdfFormBarcodeParameters {
+    /// Reads barcode parameters from the paper metadata dictionary with the given handle.
+    pub(crate) fn from_pdfium(
+        handle: FPDF_ANNOTATION,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Self {
+        let number = |key: &str| {
+            let mut value: c_float = 0.0;
+
+            if bindings.is_true(bindings.FPDFAnnot_GetNumberValue(handle, key, &mut value)) {
+                Some(value)
+            } else {
+                None
+            }
+        };
+
+        PdfFormBarcodeParameters {
+            symbology: PdfFormBarcodeSymbology::from_pdf_name(
+                &get_text_value(handle, "Symbology", bindings).unwrap_or_default(),
+            ),
+            module_width: number("XSymWidth"),
+            module_height: number("XSymHeight"),
+            error_correction_level: number("ECC").map(|value| value.max(0.0) as u32),
+            rows: number("nCodeWordRow").map(|value| value.max(0.0) as u32),
+            columns: number("nCodeWordCol").map(|value| value.max(0.0) as u32),
+            resolution: number("Resolution"),
+        }
+    }
+
+    /// Returns the symbology used to encode the field's value.
+    #[inline]
+    pub fn symbology(&self) -> &PdfFormBarcodeSymbology {
+        &self.symbology
+    }
+
+    /// Returns the width of a single module, or bar, of the barcode, in printer dots,
+    /// if specified.
+    #[inline]
+    pub fn module_width(&self) -> Option<f32> {
+        self.module_width
+    }
+
+    /// Returns the height of a single module, or bar, of the barcode, in printer dots,
+    /// if specified. For PDF417 barcodes this is usually a multiple of the module width.
+    #[inline]
+    pub fn module_height(&self) -> Option<f32> {
+        self.module_height
+    }
+
+    /// Returns the error correction level of the barcode, if specified. The meaning of
+    /// the level depends on the symbology.
+    #[inline]
+    pub fn error_correction_level(&self) -> Option<u32> {
+        self.error_correction_level
+    }
+
+    /// Returns the number of rows of code words in the barcode, if specified.
+    #[inline]
+    pub fn rows(&self) -> Option<u32> {
+        self.rows
+    }
+
+    /// Returns the number of columns of code words in the barcode, if specified.
+    #[inline]
+    pub fn columns(&self) -> Option<u32> {
+        self.columns
+    }
+
+    /// Returns the resolution, in dots per inch, of the printer the barcode was
+    /// designed for, if specified.
+    #[inline]
+    pub fn resolution(&self) -> Option<f32> {
+        self.resolution
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::field::barcode::*;
+
+    #[test]
+    fn test_symbology_from_pdf_name() {
+        assert_eq!(
+            PdfFormBarcodeSymbology::from_pdf_name("PDF417"),
+            PdfFormBarcodeSymbology::Pdf417
+        );
+        assert_eq!(
+            PdfFormBarcodeSymbology::from_pdf_name("QRCode"),
+            PdfFormBarcodeSymbology::QrCode
+        );
+        assert_eq!(
+            PdfFormBarcodeSymbology::from_pdf_name("Aztec"),
+            PdfFormBarcodeSymbology::Other("Aztec".to_owned())
+        );
+    }
+}
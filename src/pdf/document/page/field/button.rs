@@ -1,7 +1,7 @@
 //! Defines the [PdfFormPushButtonField] struct, exposing functionality related to a single
 //! form field of type `PdfFormFieldType::PushButton`.
 
-use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE};
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
 
@@ -14,6 +14,7 @@ use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
 /// `PdfForm::field_values()` function.
 pub struct PdfFormPushButtonField<'a> {
     form_handle: FPDF_FORMHANDLE,
+    page_handle: FPDF_PAGE,
     annotation_handle: FPDF_ANNOTATION,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
@@ -22,11 +23,13 @@ impl<'a> PdfFormPushButtonField<'a> {
     #[inline]
     pub(crate) fn from_pdfium(
         form_handle: FPDF_FORMHANDLE,
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
         PdfFormPushButtonField {
             form_handle,
+            page_handle,
             annotation_handle,
             bindings,
         }
@@ -45,6 +48,11 @@ impl<'a> PdfFormFieldPrivate<'a> for PdfFormPushButtonField<'a> {
         &self.form_handle
     }
 
+    #[inline]
+    fn page_handle(&self) -> &FPDF_PAGE {
+        &self.page_handle
+    }
+
     #[inline]
     fn annotation_handle(&self) -> &FPDF_ANNOTATION {
         &self.annotation_handle
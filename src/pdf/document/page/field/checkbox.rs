@@ -1,7 +1,7 @@
 //! Defines the [PdfFormCheckboxField] struct, exposing functionality related to a single
 //! form field of type [PdfFormFieldType::Checkbox].
 
-use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE};
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::PdfiumError;
 use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
@@ -22,19 +22,25 @@ use {
 /// [PdfForm::field_values()] function.
 pub struct PdfFormCheckboxField<'a> {
     form_handle: FPDF_FORMHANDLE,
+    page_handle: FPDF_PAGE,
     annotation_handle: FPDF_ANNOTATION,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
 
 impl<'a> PdfFormCheckboxField<'a> {
+    /// The appearance state name conventionally used for a checked checkbox.
+    const DEFAULT_ON_STATE_NAME: &'static str = "Yes";
+
     #[inline]
     pub(crate) fn from_pdfium(
         form_handle: FPDF_FORMHANDLE,
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
         PdfFormCheckboxField {
             form_handle,
+            page_handle,
             annotation_handle,
             bindings,
         }
@@ -67,6 +73,16 @@ impl<'a> PdfFormCheckboxField<'a> {
         self.value_impl()
     }
 
+    /// Returns the name of the appearance state this [PdfFormCheckboxField] takes when its
+    /// checkbox is checked, usually "Yes". This is also the value taken by the field when
+    /// the checkbox is checked. If the field defines an `/Opt` array of export values,
+    /// Pdfium returns the export value of the checkbox instead.
+    #[inline]
+    pub fn on_state_name(&self) -> String {
+        self.export_value_impl()
+            .unwrap_or_else(|| Self::DEFAULT_ON_STATE_NAME.to_string())
+    }
+
     /// Returns `true` if this [PdfFormCheckboxField] object has its checkbox checked.
     #[inline]
     pub fn is_checked(&self) -> Result<bool, PdfiumError> {
@@ -78,21 +94,43 @@ impl<'a> PdfFormCheckboxField<'a> {
         match self.is_checked_impl() {
             Ok(true) => Ok(true),
             Ok(false) => match self.group_value() {
-                Some(value) => Ok(value == "Yes"),
+                Some(value) => Ok(value == self.on_state_name()),
                 _ => Ok(false),
             },
             Err(err) => match self.group_value() {
-                Some(value) => Ok(value == "Yes"),
+                Some(value) => Ok(value == self.on_state_name()),
                 _ => Err(err),
             },
         }
     }
 
-    /// Checks or clears the checkbox of this [PdfFormCheckboxField] object.
+    /// Checks or clears the checkbox of this [PdfFormCheckboxField] object, setting both
+    /// the value and the appearance state of the checkbox.
+    ///
+    /// Only this widget is updated. To update every widget of a checkbox field displayed
+    /// by more than one widget, use `PdfFormNamedCheckboxField::check()` or
+    /// `PdfFormNamedCheckboxField::uncheck()` instead.
     #[inline]
     pub fn set_checked(&mut self, is_checked: bool) -> Result<(), PdfiumError> {
-        // *** may need to set the appearance stream as well?
-        self.set_value_impl(if is_checked { "Yes" } else { "Off" })
+        if is_checked {
+            let on_state_name = self.on_state_name();
+
+            self.set_checked_state_impl(on_state_name.as_str(), on_state_name.as_str())
+        } else {
+            self.set_checked_state_impl("Off", "Off")
+        }
+    }
+
+    /// Checks the checkbox of this [PdfFormCheckboxField] object.
+    #[inline]
+    pub fn check(&mut self) -> Result<(), PdfiumError> {
+        self.set_checked(true)
+    }
+
+    /// Clears the checkbox of this [PdfFormCheckboxField] object.
+    #[inline]
+    pub fn uncheck(&mut self) -> Result<(), PdfiumError> {
+        self.set_checked(false)
     }
 }
 
@@ -102,6 +140,11 @@ impl<'a> PdfFormFieldPrivate<'a> for PdfFormCheckboxField<'a> {
         &self.form_handle
     }
 
+    #[inline]
+    fn page_handle(&self) -> &FPDF_PAGE {
+        &self.page_handle
+    }
+
     #[inline]
     fn annotation_handle(&self) -> &FPDF_ANNOTATION {
         &self.annotation_handle
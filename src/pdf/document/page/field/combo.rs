@@ -1,7 +1,7 @@
 //! Defines the [PdfFormComboBoxField] struct, exposing functionality related to a single
 //! form field of type `PdfFormFieldType::ComboBox`.
 
-use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE};
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::pdf::document::page::field::options::PdfFormFieldOptions;
 use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
@@ -16,6 +16,7 @@ use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
 /// `PdfForm::field_values()` function.
 pub struct PdfFormComboBoxField<'a> {
     form_handle: FPDF_FORMHANDLE,
+    page_handle: FPDF_PAGE,
     annotation_handle: FPDF_ANNOTATION,
     options: PdfFormFieldOptions<'a>,
     bindings: &'a dyn PdfiumLibraryBindings,
@@ -25,11 +26,13 @@ impl<'a> PdfFormComboBoxField<'a> {
     #[inline]
     pub(crate) fn from_pdfium(
         form_handle: FPDF_FORMHANDLE,
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
         PdfFormComboBoxField {
             form_handle,
+            page_handle,
             annotation_handle,
             options: PdfFormFieldOptions::from_pdfium(form_handle, annotation_handle, bindings),
             bindings,
@@ -63,6 +66,11 @@ impl<'a> PdfFormFieldPrivate<'a> for PdfFormComboBoxField<'a> {
         &self.form_handle
     }
 
+    #[inline]
+    fn page_handle(&self) -> &FPDF_PAGE {
+        &self.page_handle
+    }
+
     #[inline]
     fn annotation_handle(&self) -> &FPDF_ANNOTATION {
         &self.annotation_handle
@@ -11,12 +11,14 @@ pub(crate) mod internal {
         FPDF_ANNOTATION, FPDF_ANNOT_FLAG_HIDDEN, FPDF_ANNOT_FLAG_INVISIBLE, FPDF_ANNOT_FLAG_LOCKED,
         FPDF_ANNOT_FLAG_NONE, FPDF_ANNOT_FLAG_NOROTATE, FPDF_ANNOT_FLAG_NOVIEW,
         FPDF_ANNOT_FLAG_NOZOOM, FPDF_ANNOT_FLAG_PRINT, FPDF_ANNOT_FLAG_READONLY,
-        FPDF_ANNOT_FLAG_TOGGLENOVIEW, FPDF_FORMHANDLE, FPDF_WCHAR,
+        FPDF_ANNOT_FLAG_TOGGLENOVIEW, FPDF_FORMFIELD_SIGNATURE, FPDF_FORMHANDLE, FPDF_PAGE,
+        FPDF_WCHAR,
     };
     use crate::bindings::PdfiumLibraryBindings;
-    use crate::error::PdfiumError;
+    use crate::error::{PdfiumError, PdfiumInternalError};
     use crate::pdf::appearance_mode::PdfAppearanceMode;
     use crate::pdf::document::page::field::PdfFormFieldCommon;
+    use crate::pdf::document::page::generation::PdfContentGenerations;
     use crate::utils::dates::date_time_to_pdf_string;
     use crate::utils::mem::create_byte_buffer;
     use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
@@ -44,6 +46,9 @@ pub(crate) mod internal {
         /// Returns the internal `FPDF_FORMHANDLE` handle for this [PdfFormField].
         fn form_handle(&self) -> &FPDF_FORMHANDLE;
 
+        /// Returns the internal `FPDF_PAGE` handle of the page containing this [PdfFormField].
+        fn page_handle(&self) -> &FPDF_PAGE;
+
         /// Returns the internal `FPDF_ANNOTATION` handle for this [PdfFormField].
         fn annotation_handle(&self) -> &FPDF_ANNOTATION;
 
@@ -150,6 +155,43 @@ pub(crate) mod internal {
                             value,
                         ))
                 })
+                .map(|_| PdfContentGenerations::increment_for_page_handle(*self.page_handle()))
+        }
+
+        /// Internal implementation of `set_checked()` function shared by on/off form field
+        /// widgets such as checkbox and radio button fields. Not exposed directly by
+        /// [PdfFormFieldCommon]. Sets the value of the widget to the given value and its
+        /// appearance state to the given state name, retaining the widget's appearance
+        /// streams so that the appearance matching the new state is displayed.
+        #[inline]
+        fn set_checked_state_impl(&mut self, value: &str, state: &str) -> Result<(), PdfiumError> {
+            // Pdfium can only write string values, whereas the value and appearance state
+            // of a checkable widget should be names. Pdfium itself, and most other viewers,
+            // accept strings in their place.
+
+            self.bindings()
+                .to_result(self.bindings().FPDFAnnot_SetStringValue_str(
+                    *self.annotation_handle(),
+                    "M",
+                    &date_time_to_pdf_string(Utc::now()),
+                ))
+                .and_then(|_| {
+                    self.bindings()
+                        .to_result(self.bindings().FPDFAnnot_SetStringValue_str(
+                            *self.annotation_handle(),
+                            "AS",
+                            state,
+                        ))
+                })
+                .and_then(|_| {
+                    self.bindings()
+                        .to_result(self.bindings().FPDFAnnot_SetStringValue_str(
+                            *self.annotation_handle(),
+                            "V",
+                            value,
+                        ))
+                })
+                .map(|_| PdfContentGenerations::increment_for_page_handle(*self.page_handle()))
         }
 
         /// Internal implementation of `export_value()` function shared by on/off form field widgets
@@ -190,6 +232,46 @@ pub(crate) mod internal {
             }
         }
 
+        /// Internal implementation of [PdfFormFieldCommon::regenerate_appearance()].
+        fn regenerate_appearance_impl(&mut self) -> Result<(), PdfiumError> {
+            // Pdfium never generates appearances for signature widgets.
+
+            if self
+                .bindings()
+                .FPDFAnnot_GetFormFieldType(*self.form_handle(), *self.annotation_handle())
+                == FPDF_FORMFIELD_SIGNATURE as c_int
+            {
+                return Ok(());
+            }
+
+            self.bindings().to_result(self.bindings().FPDFAnnot_SetAP(
+                *self.annotation_handle(),
+                PdfAppearanceMode::Normal as i32,
+                std::ptr::null(),
+            ))?;
+
+            // Focusing the widget loads its page into the form fill environment, at which
+            // point Pdfium generates appearance streams for any widgets on the page that
+            // lack them. Killing focus again leaves the field's value unchanged.
+
+            self.bindings()
+                .FORM_SetFocusedAnnot(*self.form_handle(), *self.annotation_handle());
+            self.bindings().FORM_ForceToKillFocus(*self.form_handle());
+
+            PdfContentGenerations::increment_for_page_handle(*self.page_handle());
+
+            if self
+                .appearance_mode_value_impl(PdfAppearanceMode::Normal)
+                .is_some()
+            {
+                Ok(())
+            } else {
+                Err(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ))
+            }
+        }
+
         /// Internal implementation of `is_checked()` function shared by checkable form field widgets
         /// such as radio buttons and checkboxes. Not exposed directly by [PdfFormFieldCommon].
         fn is_checked_impl(&self) -> Result<bool, PdfiumError> {
@@ -309,10 +391,99 @@ pub(crate) mod internal {
 
         #[inline]
         fn set_flags_impl(&mut self, flags: FpdfAnnotationFlags) -> bool {
-            self.bindings().is_true(
+            let result = self.bindings().is_true(
                 self.bindings()
                     .FPDFAnnot_SetFlags(*self.annotation_handle(), flags.bits() as c_int),
-            )
+            );
+
+            if result {
+                PdfContentGenerations::increment_for_page_handle(*self.page_handle());
+            }
+
+            result
+        }
+    }
+
+    /// The maximum depth of the field hierarchy searched for inheritable entries, guarding
+    /// against cyclic `/Parent` references in malformed documents.
+    const MAXIMUM_FIELD_HIERARCHY_DEPTH: usize = 32;
+
+    /// Calls the given function with a handle to the nearest dictionary containing the given
+    /// key, searching first the given widget annotation's dictionary and then the
+    /// dictionaries of its ancestor fields, returning the function's result, or `None` if
+    /// no dictionary in the hierarchy contains the key.
+    pub(crate) fn with_inheritable_key<T>(
+        annotation_handle: FPDF_ANNOTATION,
+        key: &str,
+        bindings: &dyn PdfiumLibraryBindings,
+        f: impl FnOnce(FPDF_ANNOTATION) -> T,
+    ) -> Option<T> {
+        if bindings.is_true(bindings.FPDFAnnot_HasKey(annotation_handle, key)) {
+            return Some(f(annotation_handle));
+        }
+
+        let mut opened = Vec::new();
+
+        let mut current = annotation_handle;
+
+        let mut result = None;
+
+        for _ in 0..MAXIMUM_FIELD_HIERARCHY_DEPTH {
+            current = bindings.FPDFAnnot_GetLinkedAnnot(current, "Parent");
+
+            if current.is_null() {
+                break;
+            }
+
+            opened.push(current);
+
+            if bindings.is_true(bindings.FPDFAnnot_HasKey(current, key)) {
+                result = Some(f(current));
+
+                break;
+            }
+        }
+
+        for handle in opened {
+            bindings.FPDFPage_CloseAnnot(handle);
         }
+
+        result
+    }
+
+    /// Returns the text of the string or text stream associated with the given key in the
+    /// dictionary with the given handle, if any.
+    pub(crate) fn get_text_value(
+        handle: FPDF_ANNOTATION,
+        key: &str,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Option<String> {
+        // Retrieving the value from Pdfium is a two-step operation. First, we call
+        // FPDFAnnot_GetStringValue() with a null buffer; this will retrieve the length of
+        // the value in bytes. If the length is zero, then the key is not present.
+
+        // If the length is non-zero, then we reserve a byte buffer of the given
+        // length and call FPDFAnnot_GetStringValue() again with a pointer to the buffer;
+        // this will write the value to the buffer in UTF16LE format. Pdfium decodes
+        // text streams as well as strings.
+
+        let buffer_length = bindings.FPDFAnnot_GetStringValue(handle, key, std::ptr::null_mut(), 0);
+
+        if buffer_length == 0 {
+            return None;
+        }
+
+        let mut buffer = create_byte_buffer(buffer_length as usize);
+
+        let result = bindings.FPDFAnnot_GetStringValue(
+            handle,
+            key,
+            buffer.as_mut_ptr() as *mut FPDF_WCHAR,
+            buffer_length,
+        );
+
+        debug_assert_eq!(result, buffer_length);
+
+        get_string_from_pdfium_utf16le_bytes(buffer)
     }
 }
@@ -1,7 +1,7 @@
 //! Defines the [PdfFormRadioButtonField] struct, exposing functionality related to a single
 //! form field of type [PdfFormFieldType::RadioButton].
 
-use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE};
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::PdfiumError;
 use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
@@ -22,6 +22,7 @@ use {
 /// [PdfForm::field_values()] function.
 pub struct PdfFormRadioButtonField<'a> {
     form_handle: FPDF_FORMHANDLE,
+    page_handle: FPDF_PAGE,
     annotation_handle: FPDF_ANNOTATION,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
@@ -30,11 +31,13 @@ impl<'a> PdfFormRadioButtonField<'a> {
     #[inline]
     pub(crate) fn from_pdfium(
         form_handle: FPDF_FORMHANDLE,
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
         PdfFormRadioButtonField {
             form_handle,
+            page_handle,
             annotation_handle,
             bindings,
         }
@@ -67,6 +70,15 @@ impl<'a> PdfFormRadioButtonField<'a> {
         self.value_impl()
     }
 
+    /// Returns the name of the appearance state this [PdfFormRadioButtonField] takes when its
+    /// radio button is selected, if any. This is also the value taken by the radio button
+    /// group when this radio button is selected. If the group defines an `/Opt` array of
+    /// export values, Pdfium returns the export value of this radio button instead.
+    #[inline]
+    pub fn on_state_name(&self) -> Option<String> {
+        self.export_value_impl()
+    }
+
     /// Returns `true` if this [PdfFormRadioButtonField] object has its radio button selected.
     #[inline]
     pub fn is_checked(&self) -> Result<bool, PdfiumError> {
@@ -86,11 +98,18 @@ impl<'a> PdfFormRadioButtonField<'a> {
         }
     }
 
-    /// Selects the radio button of this [PdfFormRadioButtonField] object.
+    /// Selects the radio button of this [PdfFormRadioButtonField] object, setting both the
+    /// value and the appearance state of the radio button.
+    ///
+    /// Only this widget is updated; other radio buttons in the same group are not cleared.
+    /// To select a radio button and clear the rest of its group, use
+    /// `PdfFormNamedRadioGroup::select()` instead.
     #[inline]
     pub fn set_checked(&mut self) -> Result<(), PdfiumError> {
-        match self.appearance_stream_impl() {
-            Some(appearance_stream) => self.set_value_impl(appearance_stream.as_str()),
+        match self.on_state_name() {
+            Some(on_state_name) => {
+                self.set_checked_state_impl(on_state_name.as_str(), on_state_name.as_str())
+            }
             None => Err(PdfiumError::FormFieldAppearanceStreamUndefined),
         }
     }
@@ -102,6 +121,11 @@ impl<'a> PdfFormFieldPrivate<'a> for PdfFormRadioButtonField<'a> {
         &self.form_handle
     }
 
+    #[inline]
+    fn page_handle(&self) -> &FPDF_PAGE {
+        &self.page_handle
+    }
+
     #[inline]
     fn annotation_handle(&self) -> &FPDF_ANNOTATION {
         &self.annotation_handle
@@ -1,7 +1,7 @@
 //! Defines the [PdfFormSignatureField] struct, exposing functionality related to a single
 //! form field of type `PdfFormFieldType::Signature`.
 
-use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE};
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
 
@@ -14,6 +14,7 @@ use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
 /// `PdfForm::field_values()` function.
 pub struct PdfFormSignatureField<'a> {
     form_handle: FPDF_FORMHANDLE,
+    page_handle: FPDF_PAGE,
     annotation_handle: FPDF_ANNOTATION,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
@@ -21,11 +22,13 @@ pub struct PdfFormSignatureField<'a> {
 impl<'a> PdfFormSignatureField<'a> {
     pub(crate) fn from_pdfium(
         form_handle: FPDF_FORMHANDLE,
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
         PdfFormSignatureField {
             form_handle,
+            page_handle,
             annotation_handle,
             bindings,
         }
@@ -44,6 +47,11 @@ impl<'a> PdfFormFieldPrivate<'a> for PdfFormSignatureField<'a> {
         &self.form_handle
     }
 
+    #[inline]
+    fn page_handle(&self) -> &FPDF_PAGE {
+        &self.page_handle
+    }
+
     #[inline]
     fn annotation_handle(&self) -> &FPDF_ANNOTATION {
         &self.annotation_handle
@@ -1,10 +1,13 @@
 //! Defines the [PdfFormTextField] struct, exposing functionality related to a single
 //! form field of type [PdfFormFieldType::Text].
 
-use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE};
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::PdfiumError;
-use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
+use crate::pdf::document::page::field::barcode::PdfFormBarcodeParameters;
+use crate::pdf::document::page::field::private::internal::{
+    get_text_value, with_inheritable_key, PdfFormFieldPrivate,
+};
 
 #[cfg(doc)]
 use {
@@ -13,6 +16,10 @@ use {
     crate::pdf::document::page::field::{PdfFormField, PdfFormFieldType},
 };
 
+/// The form field flag marking a text field whose value is formatted as rich text.
+/// Pdfium does not define a constant for this flag.
+pub(crate) const FORM_FIELD_FLAG_TEXT_RICH_TEXT: u32 = 1 << 25;
+
 /// A single [PdfFormField] of type [PdfFormFieldType::Text]. The form field object defines
 /// an interactive data entry widget that allows the user to enter data by typing.
 ///
@@ -22,6 +29,7 @@ use {
 /// [PdfForm::field_values()] function.
 pub struct PdfFormTextField<'a> {
     form_handle: FPDF_FORMHANDLE,
+    page_handle: FPDF_PAGE,
     annotation_handle: FPDF_ANNOTATION,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
@@ -30,11 +38,13 @@ impl<'a> PdfFormTextField<'a> {
     #[inline]
     pub(crate) fn from_pdfium(
         form_handle: FPDF_FORMHANDLE,
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
         PdfFormTextField {
             form_handle,
+            page_handle,
             annotation_handle,
             bindings,
         }
@@ -57,6 +67,57 @@ impl<'a> PdfFormTextField<'a> {
     pub fn set_value(&mut self, value: &str) -> Result<(), PdfiumError> {
         self.set_value_impl(value)
     }
+
+    /// Returns `true` if the value of this [PdfFormTextField] object is formatted as rich text.
+    ///
+    /// Pdfium displays and edits only the plain text value of rich text fields, returned
+    /// by [PdfFormTextField::value()]; the formatted value is available from
+    /// [PdfFormTextField::rich_text_value()].
+    #[inline]
+    pub fn is_rich_text(&self) -> bool {
+        self.bindings
+            .FPDFAnnot_GetFormFieldFlags(self.form_handle, self.annotation_handle) as u32
+            & FORM_FIELD_FLAG_TEXT_RICH_TEXT
+            != 0
+    }
+
+    /// Returns the rich text value of this [PdfFormTextField] object, if any, as the raw
+    /// XHTML markup stored in the field's `/RV` entry. The markup is returned whether it
+    /// is stored in a string or in a text stream.
+    #[inline]
+    pub fn rich_text_value(&self) -> Option<String> {
+        with_inheritable_key(self.annotation_handle, "RV", self.bindings, |handle| {
+            get_text_value(handle, "RV", self.bindings)
+        })
+        .flatten()
+    }
+
+    /// Returns `true` if this [PdfFormTextField] object is a barcode field created by
+    /// Adobe Acrobat.
+    #[inline]
+    pub fn is_barcode(&self) -> bool {
+        self.barcode().is_some()
+    }
+
+    /// Returns the symbology parameters of this [PdfFormTextField] object, if it is a barcode
+    /// field created by Adobe Acrobat. The value encoded by the barcode is returned by
+    /// [PdfFormTextField::value()].
+    pub fn barcode(&self) -> Option<PdfFormBarcodeParameters> {
+        with_inheritable_key(self.annotation_handle, "PMD", self.bindings, |handle| {
+            let metadata = self.bindings.FPDFAnnot_GetLinkedAnnot(handle, "PMD");
+
+            if metadata.is_null() {
+                return None;
+            }
+
+            let result = PdfFormBarcodeParameters::from_pdfium(metadata, self.bindings);
+
+            self.bindings.FPDFPage_CloseAnnot(metadata);
+
+            Some(result)
+        })
+        .flatten()
+    }
 }
 
 impl<'a> PdfFormFieldPrivate<'a> for PdfFormTextField<'a> {
@@ -65,6 +126,11 @@ impl<'a> PdfFormFieldPrivate<'a> for PdfFormTextField<'a> {
         &self.form_handle
     }
 
+    #[inline]
+    fn page_handle(&self) -> &FPDF_PAGE {
+        &self.page_handle
+    }
+
     #[inline]
     fn annotation_handle(&self) -> &FPDF_ANNOTATION {
         &self.annotation_handle
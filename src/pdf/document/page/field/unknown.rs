@@ -1,7 +1,7 @@
 //! Defines the [PdfFormUnknownField] struct, exposing functionality related to a single
 //! form field of type `PdfFormFieldType::Unknown`.
 //!
-use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE};
+use crate::bindgen::{FPDF_ANNOTATION, FPDF_FORMHANDLE, FPDF_PAGE};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
 
@@ -13,6 +13,7 @@ use crate::pdf::document::page::field::private::internal::PdfFormFieldPrivate;
 /// `PdfForm::field_values()` function.
 pub struct PdfFormUnknownField<'a> {
     form_handle: FPDF_FORMHANDLE,
+    page_handle: FPDF_PAGE,
     annotation_handle: FPDF_ANNOTATION,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
@@ -20,11 +21,13 @@ pub struct PdfFormUnknownField<'a> {
 impl<'a> PdfFormUnknownField<'a> {
     pub(crate) fn from_pdfium(
         form_handle: FPDF_FORMHANDLE,
+        page_handle: FPDF_PAGE,
         annotation_handle: FPDF_ANNOTATION,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
         PdfFormUnknownField {
             form_handle,
+            page_handle,
             annotation_handle,
             bindings,
         }
@@ -43,6 +46,11 @@ impl<'a> PdfFormFieldPrivate<'a> for PdfFormUnknownField<'a> {
         &self.form_handle
     }
 
+    #[inline]
+    fn page_handle(&self) -> &FPDF_PAGE {
+        &self.page_handle
+    }
+
     #[inline]
     fn annotation_handle(&self) -> &FPDF_ANNOTATION {
         &self.annotation_handle
@@ -0,0 +1,164 @@
+use crate::bindgen::{FPDF_DOCUMENT, FPDF_PAGE};
+use crate::pdf::document::page::index_cache::PdfPageIndexCache;
+use crate::pdf::document::pages::PdfPageIndex;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Content generation counters for all open documents and the pages within them.
+/// We keep track of these so that caches of rendered pages, such as [PdfRenderCache],
+/// can tell when a page may have changed since it was rendered.
+static CONTENT_GENERATIONS: Lazy<Mutex<PdfContentGenerations>> =
+    Lazy::new(|| Mutex::new(PdfContentGenerations::new()));
+
+/// Generations are allocated from a single counter shared by all documents, and every document
+/// is given a new generation when it is opened, so a generation is never reused, even if Pdfium
+/// reuses the handle of a closed document for a new document.
+pub(crate) struct PdfContentGenerations {
+    next: u64,
+    documents: HashMap<FPDF_DOCUMENT, u64>,
+    pages: HashMap<(FPDF_DOCUMENT, PdfPageIndex), u64>,
+}
+
+impl PdfContentGenerations {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            next: 1,
+            documents: HashMap::new(),
+            pages: HashMap::new(),
+        }
+    }
+
+    /// Returns the current content generation of the page at the given index in the
+    /// given document. The generation increases whenever the page, or the arrangement of
+    /// pages in the document, may have changed.
+    #[inline]
+    fn get(&self, document: FPDF_DOCUMENT, index: PdfPageIndex) -> u64 {
+        self.documents
+            .get(&document)
+            .copied()
+            .unwrap_or(0)
+            .max(self.pages.get(&(document, index)).copied().unwrap_or(0))
+    }
+
+    /// Allocates a new generation, greater than any generation allocated before it.
+    #[inline]
+    fn allocate(&mut self) -> u64 {
+        let result = self.next;
+
+        self.next += 1;
+
+        result
+    }
+
+    #[inline]
+    fn lock() -> MutexGuard<'static, PdfContentGenerations> {
+        CONTENT_GENERATIONS.lock().unwrap()
+    }
+
+    // The remaining methods in this implementation take care of thread-safe locking.
+    // These methods form the public API of the cache.
+
+    /// Returns the current content generation of the page at the given index in the
+    /// given document.
+    #[inline]
+    pub(crate) fn get_for_page(document: FPDF_DOCUMENT, index: PdfPageIndex) -> u64 {
+        Self::lock().get(document, index)
+    }
+
+    /// Records that the page at the given index in the given document may have changed.
+    #[inline]
+    pub(crate) fn increment_for_page(document: FPDF_DOCUMENT, index: PdfPageIndex) {
+        let mut generations = Self::lock();
+
+        let generation = generations.allocate();
+
+        generations.pages.insert((document, index), generation);
+    }
+
+    /// Records that the page with the given raw page handle may have changed. Changes to pages
+    /// that are not open in any document are ignored.
+    #[inline]
+    pub(crate) fn increment_for_page_handle(page: FPDF_PAGE) {
+        if let Some((document, index)) = PdfPageIndexCache::get_document_and_index_for_page(page) {
+            Self::increment_for_page(document, index);
+        }
+    }
+
+    /// Records that any page in the given document may have changed, for instance because
+    /// pages have been inserted, deleted, or moved.
+    #[inline]
+    pub(crate) fn increment_for_document(document: FPDF_DOCUMENT) {
+        let mut generations = Self::lock();
+
+        let generation = generations.allocate();
+
+        generations.documents.insert(document, generation);
+    }
+
+    /// Records that the given document has been opened. The document is given a new generation,
+    /// so that any page in it is considered to have changed since the rendering of any page in
+    /// a closed document that Pdfium opened using the same handle.
+    #[inline]
+    pub(crate) fn open_document(document: FPDF_DOCUMENT) {
+        Self::increment_for_document(document);
+    }
+
+    /// Records that the given document has been closed, discarding its generation and the
+    /// generations of all the pages within it.
+    pub(crate) fn close_document(document: FPDF_DOCUMENT) {
+        let mut generations = Self::lock();
+
+        generations.documents.remove(&document);
+        generations.pages.retain(|(key, _), _| *key != document);
+    }
+}
+
+unsafe impl Send for PdfContentGenerations {}
+
+unsafe impl Sync for PdfContentGenerations {}
+
+#[cfg(test)]
+mod tests {
+    use crate::bindgen::FPDF_DOCUMENT;
+    use crate::pdf::document::page::generation::PdfContentGenerations;
+
+    #[test]
+    fn test_close_document_discards_generations() {
+        // Fake document handles are never dereferenced by the generation counters.
+
+        let document = 0x5eed as FPDF_DOCUMENT;
+
+        PdfContentGenerations::open_document(document);
+
+        let opened = PdfContentGenerations::get_for_page(document, 0);
+
+        assert!(opened > 0);
+
+        PdfContentGenerations::increment_for_page(document, 0);
+
+        let changed = PdfContentGenerations::get_for_page(document, 0);
+
+        assert!(changed > opened);
+        assert_eq!(PdfContentGenerations::get_for_page(document, 1), opened);
+
+        PdfContentGenerations::close_document(document);
+
+        {
+            let generations = PdfContentGenerations::lock();
+
+            assert!(!generations.documents.contains_key(&document));
+            assert!(!generations.pages.keys().any(|(key, _)| *key == document));
+        }
+
+        // A new document opened with the same handle has a newer generation than any page
+        // of the closed document.
+
+        PdfContentGenerations::open_document(document);
+
+        assert!(PdfContentGenerations::get_for_page(document, 0) > changed);
+
+        PdfContentGenerations::close_document(document);
+    }
+}
@@ -87,6 +87,27 @@ impl PdfPageIndexCache {
         }
     }
 
+    /// Returns the raw document handle and current [PdfPageIndex] value for the given raw page
+    /// handle, if any.
+    #[inline]
+    fn find(&self, page: FPDF_PAGE) -> Option<(FPDF_DOCUMENT, PdfPageIndex)> {
+        self.pages_by_index
+            .iter()
+            .find(|((_, key), _)| *key == page)
+            .map(|((document, _), index)| (*document, *index))
+    }
+
+    /// Returns `true` if any page with an index in the given range is open in the given document.
+    #[inline]
+    fn contains_any(
+        &self,
+        document: FPDF_DOCUMENT,
+        index: PdfPageIndex,
+        count: PdfPageIndex,
+    ) -> bool {
+        (index..index + count).any(|index| self.indices_by_page.contains_key(&(document, index)))
+    }
+
     /// Adjusts all cached [PdfPageIndex] values for the given document as necessary to accommodate
     /// an insertion of the given number of pages at the given index position.
     #[inline]
@@ -165,6 +186,51 @@ impl PdfPageIndexCache {
         }
     }
 
+    /// Adjusts all cached [PdfPageIndex] values for the given document as necessary to accommodate
+    /// a rearrangement of its pages into the given order, where each element of the given order
+    /// is the previous index of the page now at that position.
+    #[cfg(any(
+        feature = "pdfium_6043",
+        feature = "pdfium_6084",
+        feature = "pdfium_6110",
+        feature = "pdfium_6124",
+        feature = "pdfium_6164",
+        feature = "pdfium_6259",
+        feature = "pdfium_6295",
+        feature = "pdfium_6337",
+        feature = "pdfium_6406",
+        feature = "pdfium_6490",
+        feature = "pdfium_6555",
+        feature = "pdfium_6569",
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    fn reorder(&mut self, document: FPDF_DOCUMENT, order: &[PdfPageIndex]) {
+        let pages = self
+            .pages_by_index
+            .iter()
+            .filter(|((page_document, _), _)| *page_document == document)
+            .map(|((_, page), index)| (*page, *index))
+            .collect::<Vec<_>>();
+
+        for (page, _) in pages.iter() {
+            self.remove(document, *page);
+        }
+
+        let positions = order
+            .iter()
+            .enumerate()
+            .map(|(position, index)| (*index, position as PdfPageIndex))
+            .collect::<HashMap<_, _>>();
+
+        for (page, index) in pages {
+            if let Some(position) = positions.get(&index) {
+                self.set(document, page, *position);
+            }
+        }
+    }
+
     #[inline]
     fn lock() -> MutexGuard<'static, PdfPageIndexCache> {
         PAGE_INDEX_CACHE.lock().unwrap()
@@ -192,12 +258,32 @@ impl PdfPageIndexCache {
         Self::lock().get(document, page)
     }
 
+    /// Returns the raw document handle and current [PdfPageIndex] value for the given raw
+    /// page handle, if any.
+    #[inline]
+    pub(crate) fn get_document_and_index_for_page(
+        page: FPDF_PAGE,
+    ) -> Option<(FPDF_DOCUMENT, PdfPageIndex)> {
+        Self::lock().find(page)
+    }
+
     /// Removes the cached [PdfPageIndex] value for the given raw document and page handles.
     #[inline]
     pub(crate) fn remove_index_for_page(document: FPDF_DOCUMENT, page: FPDF_PAGE) {
         Self::lock().remove(document, page)
     }
 
+    /// Returns `true` if a [PdfPage] is open for any of the given number of pages starting
+    /// at the given index position in the given document.
+    #[inline]
+    pub(crate) fn has_open_pages_at_index(
+        document: FPDF_DOCUMENT,
+        index: PdfPageIndex,
+        count: PdfPageIndex,
+    ) -> bool {
+        Self::lock().contains_any(document, index, count)
+    }
+
     /// Adjusts all cached [PdfPageIndex] values for the given document as necessary to accommodate
     /// an insertion of the given number of pages at the given index position.
     #[inline]
@@ -219,6 +305,31 @@ impl PdfPageIndexCache {
     ) {
         Self::lock().delete(document, index, count)
     }
+
+    /// Adjusts all cached [PdfPageIndex] values for the given document as necessary to accommodate
+    /// a rearrangement of its pages into the given order, where each element of the given order
+    /// is the previous index of the page now at that position.
+    #[cfg(any(
+        feature = "pdfium_6043",
+        feature = "pdfium_6084",
+        feature = "pdfium_6110",
+        feature = "pdfium_6124",
+        feature = "pdfium_6164",
+        feature = "pdfium_6259",
+        feature = "pdfium_6295",
+        feature = "pdfium_6337",
+        feature = "pdfium_6406",
+        feature = "pdfium_6490",
+        feature = "pdfium_6555",
+        feature = "pdfium_6569",
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    #[inline]
+    pub(crate) fn reorder_pages(document: FPDF_DOCUMENT, order: &[PdfPageIndex]) {
+        Self::lock().reorder(document, order)
+    }
 }
 
 unsafe impl Send for PdfPageIndexCache {}
@@ -0,0 +1,344 @@
+//! Internal functionality for listing the optional content groups, or layers, used on a page,
+//! and for changing the visibility of selected layers in a copy of a document, so that the
+//! document's pages can be rendered with those layers shown or hidden.
+
+use crate::bindgen::{FPDF_PAGE, FPDF_PAGEOBJECT, FPDF_PAGEOBJ_FORM};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::document::page::object::mark::optional_content_group_names;
+use crate::pdf::document::syntax::{
+    decode_pdf_string, PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject,
+};
+use std::os::raw::c_ulong;
+
+/// Appends the names of the optional content groups used by the page objects on the given
+/// page, including page objects nested inside form XObjects, to the given list.
+/// Each name is listed once.
+pub(crate) fn collect_page_layer_names(
+    page: FPDF_PAGE,
+    names: &mut Vec<String>,
+    bindings: &dyn PdfiumLibraryBindings,
+) {
+    for index in 0..bindings.FPDFPage_CountObjects(page) {
+        collect_object_layer_names(bindings.FPDFPage_GetObject(page, index), names, bindings);
+    }
+}
+
+fn collect_object_layer_names(
+    object: FPDF_PAGEOBJECT,
+    names: &mut Vec<String>,
+    bindings: &dyn PdfiumLibraryBindings,
+) {
+    if object.is_null() {
+        return;
+    }
+
+    for name in optional_content_group_names(object, bindings) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+
+    if bindings.FPDFPageObj_GetType(object) as u32 == FPDF_PAGEOBJ_FORM {
+        for index in 0..bindings.FPDFFormObj_CountObjects(object).max(0) {
+            collect_object_layer_names(
+                bindings.FPDFFormObj_GetObject(object, index as c_ulong),
+                names,
+                bindings,
+            );
+        }
+    }
+}
+
+/// Returns the names of the optional content groups, or layers, hidden by the document's
+/// default optional content configuration in the given file.
+///
+/// A layer is hidden if it is listed in the configuration's `/OFF` array, or if the
+/// configuration's `/BaseState` is `/OFF` and the layer is not listed in its `/ON` array.
+/// Usage applications in the configuration's `/AS` array are not evaluated.
+pub(crate) fn hidden_layer_names(file: &[u8]) -> Vec<String> {
+    let parsed = match PdfSyntaxFile::parse(file) {
+        Some(parsed) => parsed,
+        None => return Vec::new(),
+    };
+
+    let properties = match parsed
+        .trailer
+        .as_ref()
+        .and_then(|trailer| trailer.get("Root"))
+        .and_then(|root| {
+            parsed
+                .resolve(root)
+                .get("OCProperties")
+                .map(|properties| parsed.resolve(properties))
+        }) {
+        Some(properties) => properties,
+        None => return Vec::new(),
+    };
+
+    let groups = match properties.get("OCGs").map(|groups| parsed.resolve(groups)) {
+        Some(PdfSyntaxObject::Array(groups)) => groups,
+        _ => return Vec::new(),
+    };
+
+    let configuration = properties
+        .get("D")
+        .map(|configuration| parsed.resolve(configuration))
+        .unwrap_or(PdfSyntaxObject::Dictionary(Vec::new()));
+
+    let listed = |key: &str| match configuration.get(key).map(|groups| parsed.resolve(groups)) {
+        Some(PdfSyntaxObject::Array(groups)) => groups,
+        _ => Vec::new(),
+    };
+
+    let on = listed("ON");
+
+    let off = listed("OFF");
+
+    let is_base_state_off = configuration.get("BaseState") == Some(&PdfSyntaxObject::name("OFF"));
+
+    let mut names: Vec<String> = Vec::new();
+
+    for group in groups.iter() {
+        let is_hidden = if is_base_state_off {
+            !on.contains(group)
+        } else {
+            off.contains(group)
+        };
+
+        if !is_hidden {
+            continue;
+        }
+
+        if let Some(PdfSyntaxObject::String(name)) = parsed.resolve(group).get("Name") {
+            let name = decode_pdf_string(name);
+
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+    }
+
+    names
+}
+
+/// Returns a copy of the given file in which the document's default optional content
+/// configuration shows or hides each optional content group, or layer, named in the given
+/// list according to its paired visibility. Layers not in the list, and names not matching
+/// any of the document's layers, are left unchanged.
+///
+/// The named layers are removed from the configuration's `/ON` and `/OFF` arrays and from
+/// any usage application in its `/AS` array, so that their visibility is not overridden
+/// by the layers' usage dictionaries, and then added to `/ON` or `/OFF` as appropriate.
+pub(crate) fn apply_layer_visibility(
+    file: Vec<u8>,
+    visibility: &[(String, bool)],
+) -> Result<Vec<u8>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if update.trailer().get("Encrypt").is_some() {
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    let root = match update.trailer().get("Root") {
+        Some(PdfSyntaxObject::Reference(number, _)) => *number,
+        _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+    };
+
+    let properties = match parsed
+        .resolve(&PdfSyntaxObject::Reference(root, 0))
+        .get("OCProperties")
+    {
+        Some(properties) => parsed.resolve(properties),
+
+        // A document without optional content has no layers to show or hide.
+        None => return Ok(file),
+    };
+
+    let groups = match properties.get("OCGs").map(|groups| parsed.resolve(groups)) {
+        Some(PdfSyntaxObject::Array(groups)) => groups,
+        _ => return Ok(file),
+    };
+
+    let mut shown = Vec::new();
+
+    let mut hidden = Vec::new();
+
+    for group in groups.iter() {
+        let name = match parsed.resolve(group).get("Name") {
+            Some(PdfSyntaxObject::String(name)) => decode_pdf_string(name),
+            _ => continue,
+        };
+
+        if let Some((_, is_visible)) = visibility.iter().find(|(layer, _)| *layer == name) {
+            if *is_visible {
+                shown.push(group.clone());
+            } else {
+                hidden.push(group.clone());
+            }
+        }
+    }
+
+    if shown.is_empty() && hidden.is_empty() {
+        return Ok(file);
+    }
+
+    let configuration = properties
+        .get("D")
+        .map(|configuration| parsed.resolve(configuration))
+        .unwrap_or(PdfSyntaxObject::Dictionary(Vec::new()));
+
+    let is_unaffected = |group: &PdfSyntaxObject| !shown.contains(group) && !hidden.contains(group);
+
+    let groups_except_affected =
+        |key: &str| match configuration.get(key).map(|groups| parsed.resolve(groups)) {
+            Some(PdfSyntaxObject::Array(groups)) => groups
+                .into_iter()
+                .filter(|group| is_unaffected(group))
+                .collect::<Vec<_>>(),
+            _ => Vec::new(),
+        };
+
+    let mut on = groups_except_affected("ON");
+
+    on.extend(shown.iter().cloned());
+
+    let mut off = groups_except_affected("OFF");
+
+    off.extend(hidden.iter().cloned());
+
+    let applications = match configuration
+        .get("AS")
+        .map(|applications| parsed.resolve(applications))
+    {
+        Some(PdfSyntaxObject::Array(applications)) => Some(
+            applications
+                .iter()
+                .map(|application| {
+                    let mut application = parsed.resolve(application);
+
+                    if let Some(PdfSyntaxObject::Array(groups)) =
+                        application.get("OCGs").map(|groups| parsed.resolve(groups))
+                    {
+                        application.set(
+                            "OCGs",
+                            PdfSyntaxObject::Array(
+                                groups
+                                    .into_iter()
+                                    .filter(|group| is_unaffected(group))
+                                    .collect(),
+                            ),
+                        );
+                    }
+
+                    application
+                })
+                .collect::<Vec<_>>(),
+        ),
+        _ => None,
+    };
+
+    update
+        .modify(root, &["OCProperties", "D"], &mut |configuration| {
+            if *configuration == PdfSyntaxObject::Null {
+                *configuration = PdfSyntaxObject::Dictionary(Vec::new());
+            }
+
+            configuration.set("ON", PdfSyntaxObject::Array(on.clone()));
+            configuration.set("OFF", PdfSyntaxObject::Array(off.clone()));
+
+            if let Some(applications) = applications.as_ref() {
+                configuration.set("AS", PdfSyntaxObject::Array(applications.clone()));
+            }
+
+            Some(())
+        })
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    Ok(update.write(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::layers::*;
+
+    #[test]
+    fn test_hidden_layer_names() {
+        let file = std::fs::read("./test/layers-test.pdf").unwrap();
+
+        assert_eq!(hidden_layer_names(&file), vec!["Blue".to_string()]);
+
+        // Showing the Blue layer and hiding the Red layer is reflected in the hidden names.
+
+        let updated = apply_layer_visibility(
+            file,
+            &[("Blue".to_string(), true), ("Red".to_string(), false)],
+        )
+        .unwrap();
+
+        assert_eq!(hidden_layer_names(&updated), vec!["Red".to_string()]);
+
+        // A base state of /OFF hides every layer not listed in /ON.
+
+        let base_state_off = b"%PDF-1.7\n\
+            1 0 obj\n<< /Type /Catalog /Pages 2 0 R /OCProperties << /OCGs [4 0 R 5 0 R] \
+            /D << /BaseState /OFF /ON [4 0 R] >> >> >>\nendobj\n\
+            2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+            3 0 obj\n<< /Type /Page /Parent 2 0 R >>\nendobj\n\
+            4 0 obj\n<< /Type /OCG /Name (English) >>\nendobj\n\
+            5 0 obj\n<< /Type /OCG /Name (French) >>\nendobj\n\
+            xref\n0 6\n0000000000 65535 f\r\n\
+            trailer\n<< /Size 6 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+        assert_eq!(
+            hidden_layer_names(base_state_off),
+            vec!["French".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_apply_layer_visibility() {
+        let file = std::fs::read("./test/layers-test.pdf").unwrap();
+
+        // Names not matching any layer leave the file unchanged.
+
+        assert_eq!(
+            apply_layer_visibility(file.clone(), &[("No such layer".to_string(), false)]).unwrap(),
+            file
+        );
+
+        // The Blue layer is hidden by default; showing it moves it from /OFF to /ON.
+
+        let updated = apply_layer_visibility(
+            file,
+            &[("Blue".to_string(), true), ("Red".to_string(), false)],
+        )
+        .unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&updated).unwrap();
+
+        let configuration = parsed
+            .resolve(&PdfSyntaxObject::Reference(1, 0))
+            .get("OCProperties")
+            .and_then(|properties| properties.get("D"))
+            .cloned()
+            .unwrap();
+
+        assert_eq!(
+            configuration.get("ON"),
+            Some(&PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Reference(7, 0),
+                PdfSyntaxObject::Reference(6, 0),
+            ]))
+        );
+        assert_eq!(
+            configuration.get("OFF"),
+            Some(&PdfSyntaxObject::Array(vec![PdfSyntaxObject::Reference(
+                5, 0
+            )]))
+        );
+    }
+}
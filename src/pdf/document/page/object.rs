@@ -2,6 +2,7 @@
 
 pub(crate) mod group;
 pub(crate) mod image;
+pub(crate) mod mark;
 pub(crate) mod path;
 pub(crate) mod private; // Keep private so that the PdfPageObjectPrivate trait is not exposed.
 pub(crate) mod shading;
@@ -16,9 +17,10 @@ use crate::bindgen::{
     FPDF_PAGEOBJ_SHADING, FPDF_PAGEOBJ_TEXT, FPDF_PAGEOBJ_UNKNOWN,
 };
 use crate::bindings::PdfiumLibraryBindings;
-use crate::error::PdfiumError;
+use crate::error::{PdfiumError, PdfiumInternalError};
 use crate::pdf::color::PdfColor;
 use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
+use crate::pdf::document::page::index_cache::PdfPageIndexCache;
 use crate::pdf::document::page::object::image::PdfPageImageObject;
 use crate::pdf::document::page::object::path::PdfPagePathObject;
 use crate::pdf::document::page::object::private::internal::PdfPageObjectPrivate;
@@ -271,6 +273,89 @@ impl PdfPageObjectLineCap {
     }
 }
 
+/// The structure type of a marked content sequence containing a [PdfPageObject]. In a tagged
+/// document, each marked content sequence is bound to a structure element of the same type
+/// in the document's structure tree, which defines the logical structure and reading order
+/// of the document for assistive technologies.
+///
+/// A formal definition of these structure types can be found in Section 14.8.4 of
+/// the PDF Reference Manual, version 1.7, on page 604.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfPageObjectStructureTag {
+    /// A paragraph.
+    Paragraph,
+
+    /// A first-level heading.
+    H1,
+
+    /// A second-level heading.
+    H2,
+
+    /// A third-level heading.
+    H3,
+
+    /// A fourth-level heading.
+    H4,
+
+    /// A fifth-level heading.
+    H5,
+
+    /// A sixth-level heading.
+    H6,
+
+    /// A figure or other graphic.
+    Figure,
+
+    /// A table.
+    Table,
+
+    /// A row of cells in a table.
+    TableRow,
+
+    /// A header cell in a table.
+    TableHeaderCell,
+
+    /// A data cell in a table.
+    TableDataCell,
+
+    /// A list.
+    List,
+
+    /// A single item in a list.
+    ListItem,
+
+    /// A generic inline span of content.
+    Span,
+
+    /// Content that is not part of the logical content of the document, such as
+    /// running headers and footers, page numbers, or decorative graphics.
+    Artifact,
+}
+
+impl PdfPageObjectStructureTag {
+    /// Returns the name of this [PdfPageObjectStructureTag], as used in content marks.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PdfPageObjectStructureTag::Paragraph => "P",
+            PdfPageObjectStructureTag::H1 => "H1",
+            PdfPageObjectStructureTag::H2 => "H2",
+            PdfPageObjectStructureTag::H3 => "H3",
+            PdfPageObjectStructureTag::H4 => "H4",
+            PdfPageObjectStructureTag::H5 => "H5",
+            PdfPageObjectStructureTag::H6 => "H6",
+            PdfPageObjectStructureTag::Figure => "Figure",
+            PdfPageObjectStructureTag::Table => "Table",
+            PdfPageObjectStructureTag::TableRow => "TR",
+            PdfPageObjectStructureTag::TableHeaderCell => "TH",
+            PdfPageObjectStructureTag::TableDataCell => "TD",
+            PdfPageObjectStructureTag::List => "L",
+            PdfPageObjectStructureTag::ListItem => "LI",
+            PdfPageObjectStructureTag::Span => "Span",
+            PdfPageObjectStructureTag::Artifact => "Artifact",
+        }
+    }
+}
+
 /// A single renderable object on a `PdfPage`.
 pub enum PdfPageObject<'a> {
     /// A page object containing renderable text.
@@ -728,6 +813,37 @@ pub trait PdfPageObjectCommon<'a> {
     /// original object will not be transferred to the copy.
     fn is_copyable(&self) -> bool;
 
+    /// Returns the marked content ID of the marked content sequence containing this
+    /// [PdfPageObject], if any. In a tagged document, the marked content ID binds this
+    /// object to a structure element in the document's structure tree.
+    fn marked_content_id(&self) -> Option<i32>;
+
+    /// Wraps this [PdfPageObject] in a new marked content sequence of the given structure type,
+    /// optionally identified by the given marked content ID.
+    ///
+    /// Marked content IDs must be unique within a page. [PdfPageObjectStructureTag::Artifact]
+    /// sequences are not part of the document's logical structure and should not be given
+    /// a marked content ID.
+    ///
+    /// If a marked content ID is given, a structure element of the same type, bound to the
+    /// new sequence, is added to the given document's structure tree when the document is
+    /// saved. The structure tree is created if the document does not have one, and the
+    /// document is marked as tagged. New structure elements are appended to the top level
+    /// of the structure tree, in the order in which they were tagged. Pdfium cannot edit the
+    /// structure tree itself, so the elements are written in an incremental update appended
+    /// to the saved document.
+    ///
+    /// Changes to the content of the page take effect when the content of the containing
+    /// `PdfPage` is regenerated. Returns [PdfiumError::PageObjectNotAttachedToPage] if a
+    /// marked content ID is given but this object is not attached to a page of the
+    /// given document.
+    fn set_structure_tag(
+        &mut self,
+        document: &mut PdfDocument,
+        tag: PdfPageObjectStructureTag,
+        marked_content_id: Option<i32>,
+    ) -> Result<(), PdfiumError>;
+
     /// Attempts to copy this [PdfPageObject] by creating a new page object and copying across
     /// all the properties of this [PdfPageObject] to the new page object.
     ///
@@ -779,6 +895,8 @@ where
         self.bindings()
             .FPDFPageObj_SetBlendMode(self.get_object_handle(), blend_mode.as_pdfium());
 
+        self.mark_page_content_changed();
+
         Ok(())
     }
 
@@ -829,6 +947,8 @@ where
                 fill_color.alpha() as c_uint,
             ))
         {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure)
@@ -882,6 +1002,8 @@ where
                 stroke_color.alpha() as c_uint,
             ))
         {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure)
@@ -908,6 +1030,8 @@ where
             self.bindings()
                 .FPDFPageObj_SetStrokeWidth(self.get_object_handle(), stroke_width.value),
         ) {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure)
@@ -929,6 +1053,8 @@ where
             self.bindings()
                 .FPDFPageObj_SetLineJoin(self.get_object_handle(), line_join.as_pdfium() as c_int),
         ) {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure)
@@ -950,6 +1076,8 @@ where
             self.bindings()
                 .FPDFPageObj_SetLineCap(self.get_object_handle(), line_cap.as_pdfium() as c_int),
         ) {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure)
@@ -976,6 +1104,8 @@ where
             self.bindings()
                 .FPDFPageObj_SetDashPhase(self.get_object_handle(), dash_phase.value),
         ) {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure)
@@ -1019,6 +1149,8 @@ where
                 phase.value,
             ))
         {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumFunctionReturnValueIndicatedFailure)
@@ -1037,6 +1169,73 @@ where
     ) -> Result<PdfPageObject<'b>, PdfiumError> {
         self.try_copy_impl(document.handle(), document.bindings())
     }
+
+    #[inline]
+    fn marked_content_id(&self) -> Option<i32> {
+        match self
+            .bindings()
+            .FPDFPageObj_GetMarkedContentID(self.get_object_handle())
+        {
+            id if id >= 0 => Some(id),
+            _ => None,
+        }
+    }
+
+    fn set_structure_tag(
+        &mut self,
+        document: &mut PdfDocument,
+        tag: PdfPageObjectStructureTag,
+        marked_content_id: Option<i32>,
+    ) -> Result<(), PdfiumError> {
+        // Artifacts are not part of the logical structure, so are never bound to an element.
+
+        let page_index = match marked_content_id {
+            Some(_) if tag != PdfPageObjectStructureTag::Artifact => Some(
+                self.get_page_handle()
+                    .and_then(|page| PdfPageIndexCache::get_index_for_page(document.handle(), page))
+                    .ok_or(PdfiumError::PageObjectNotAttachedToPage)?,
+            ),
+            _ => None,
+        };
+
+        let mark = self
+            .bindings()
+            .FPDFPageObj_AddMark(self.get_object_handle(), tag.as_str());
+
+        if mark.is_null() {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        if let Some(marked_content_id) = marked_content_id {
+            if !self
+                .bindings()
+                .is_true(self.bindings().FPDFPageObjMark_SetIntParam(
+                    document.handle(),
+                    self.get_object_handle(),
+                    mark,
+                    "MCID",
+                    marked_content_id,
+                ))
+            {
+                return Err(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ));
+            }
+
+            if let Some(page_index) = page_index {
+                document.structure_tree_mut().bind(
+                    page_index,
+                    marked_content_id,
+                    tag.as_str(),
+                    None,
+                );
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl<'a> PdfPageObjectPrivate<'a> for PdfPageObject<'a> {
@@ -1230,6 +1429,53 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_set_structure_tag() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_start(PdfPagePaperSize::a4())?;
+
+        let font = document.fonts_mut().times_roman();
+
+        let mut object = page.objects_mut().create_text_object(
+            PdfPoints::ZERO,
+            PdfPoints::ZERO,
+            "A tagged heading",
+            font,
+            PdfPoints::new(10.0),
+        )?;
+
+        assert_eq!(object.marked_content_id(), None);
+
+        object.set_structure_tag(&mut document, PdfPageObjectStructureTag::H1, Some(0))?;
+
+        assert_eq!(object.marked_content_id(), Some(0));
+
+        page.regenerate_content()?;
+
+        // The structure element bound to the tagged sequence is written when the document
+        // is saved, and carried through a reload.
+
+        let bytes = document.save_to_bytes()?;
+
+        let text = String::from_utf8_lossy(&bytes);
+
+        assert!(text.contains("/StructTreeRoot"));
+        assert!(text.contains("/Type /StructElem /S /H1"));
+
+        let document = pdfium.load_pdf_from_byte_vec(bytes, None)?;
+
+        let object = document.pages().get(0)?.objects().get(0)?;
+
+        assert_eq!(object.marked_content_id(), Some(0));
+
+        Ok(())
+    }
+
     #[test]
     fn test_reset_matrix_to_identity() -> Result<(), PdfiumError> {
         let pdfium = test_bind_to_pdfium();
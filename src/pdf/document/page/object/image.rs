@@ -2,14 +2,18 @@
 //! object defining a bitmapped image.
 
 use crate::bindgen::{
-    fpdf_page_t__, FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_IMAGEOBJ_METADATA, FPDF_PAGE,
-    FPDF_PAGEOBJECT,
+    fpdf_page_t__, FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_IMAGEOBJ_METADATA, FPDF_OBJECT_NUMBER,
+    FPDF_OBJECT_TYPE, FPDF_PAGE, FPDF_PAGEOBJECT,
 };
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::{PdfiumError, PdfiumInternalError};
 use crate::pdf::bitmap::PdfBitmap;
 use crate::pdf::bitmap::Pixels;
 use crate::pdf::color_space::PdfColorSpace;
+use crate::pdf::document::page::index_cache::PdfPageIndexCache;
+use crate::pdf::document::page::object::mark::{
+    get_mark_name, get_mark_string_param, marks, next_marked_content_id,
+};
 use crate::pdf::document::page::object::private::internal::PdfPageObjectPrivate;
 use crate::pdf::document::page::object::PdfPageObject;
 use crate::pdf::document::PdfDocument;
@@ -578,6 +582,8 @@ impl<'a> PdfPageImageObject<'a> {
             self.object_handle,
             *bitmap.handle(),
         )) {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -651,6 +657,128 @@ impl<'a> PdfPageImageObject<'a> {
             .and_then(|metadata| PdfColorSpace::from_pdfium(metadata.colorspace as u32))
     }
 
+    /// Returns the alternate description of this [PdfPageImageObject], if any, as set in the
+    /// property list of the marked content sequence containing this object.
+    ///
+    /// Alternate descriptions set on a `Figure` structure element in the document's structure
+    /// tree, rather than on the marked content sequence itself, are not returned.
+    pub fn alt_text(&self) -> Option<String> {
+        marks(self.object_handle, self.bindings)
+            .find_map(|mark| get_mark_string_param(mark, "Alt", self.bindings))
+    }
+
+    /// Sets the alternate description of this [PdfPageImageObject] to the given text, so that
+    /// assistive technologies such as screen readers can describe the image to users.
+    ///
+    /// If this object is already part of a tagged marked content sequence, the alternate
+    /// description is added to, or replaces any existing alternate description in, the property
+    /// list of that sequence. Otherwise, a new `Figure` marked content sequence is created
+    /// around this object to carry the alternate description. A marked content ID not used
+    /// by any other object on the page is assigned to the sequence if it does not have one.
+    ///
+    /// The alternate description is also set on the structure element bound to the sequence
+    /// in the given document's structure tree, which is where assistive technologies look
+    /// for it. If no element is bound to the sequence, a new `Figure` structure element is
+    /// added; see `PdfPageObjectCommon::set_structure_tag()` for details of how the structure
+    /// tree is updated when the document is saved. Changes to the content of the page take
+    /// effect when the content of the containing `PdfPage` is regenerated.
+    ///
+    /// Returns [PdfiumError::PageObjectNotAttachedToPage] if this object is not attached to
+    /// a page of the given document.
+    pub fn set_alt_text(
+        &mut self,
+        document: &mut PdfDocument,
+        text: &str,
+    ) -> Result<(), PdfiumError> {
+        let (page, page_index) = self
+            .page_handle
+            .and_then(|page| {
+                PdfPageIndexCache::get_index_for_page(document.handle(), page)
+                    .map(|index| (page, index))
+            })
+            .ok_or(PdfiumError::PageObjectNotAttachedToPage)?;
+
+        let mark = match marks(self.object_handle, self.bindings).find(|mark| {
+            self.bindings
+                .FPDFPageObjMark_GetParamValueType(*mark, "MCID")
+                == FPDF_OBJECT_NUMBER as FPDF_OBJECT_TYPE
+                || get_mark_name(*mark, self.bindings).as_deref() == Some("Figure")
+        }) {
+            Some(mark) => mark,
+            None => self
+                .bindings
+                .FPDFPageObj_AddMark(self.object_handle, "Figure"),
+        };
+
+        if mark.is_null() {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let marked_content_id = match self
+            .bindings
+            .FPDFPageObj_GetMarkedContentID(self.object_handle)
+        {
+            id if id >= 0 => id,
+            _ => {
+                let id = next_marked_content_id(page, self.bindings);
+
+                if !self
+                    .bindings
+                    .is_true(self.bindings.FPDFPageObjMark_SetIntParam(
+                        document.handle(),
+                        self.object_handle,
+                        mark,
+                        "MCID",
+                        id,
+                    ))
+                {
+                    return Err(PdfiumError::PdfiumLibraryInternalError(
+                        PdfiumInternalError::Unknown,
+                    ));
+                }
+
+                id
+            }
+        };
+
+        // Pdfium writes string parameters as raw bytes. Non-ASCII text is prefixed with
+        // a byte order mark so it is interpreted as UTF-8 rather than PDFDocEncoding.
+
+        let value = if text.is_ascii() {
+            text.to_string()
+        } else {
+            format!("\u{FEFF}{}", text)
+        };
+
+        if !self
+            .bindings
+            .is_true(self.bindings.FPDFPageObjMark_SetStringParam(
+                document.handle(),
+                self.object_handle,
+                mark,
+                "Alt",
+                value.as_str(),
+            ))
+        {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let structure_type = get_mark_name(mark, self.bindings).unwrap_or_else(|| "Figure".into());
+
+        document.structure_tree_mut().set_alt_text(
+            page_index,
+            marked_content_id,
+            &structure_type,
+            text,
+        );
+
+        Ok(())
+    }
+
     /// Returns the collection of image filters currently applied to this [PdfPageImageObject].
     #[inline]
     pub fn filters(&self) -> PdfPageImageObjectFilters {
@@ -962,6 +1090,59 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_page_image_object_alt_text() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_start(PdfPagePaperSize::a4())?;
+
+        let object = PdfPageImageObject::new(&document, &image::DynamicImage::new_rgb8(10, 10))?;
+
+        let mut object = page.objects_mut().add_image_object(object)?;
+
+        let image = object.as_image_object_mut().unwrap();
+
+        assert_eq!(image.alt_text(), None);
+
+        image.set_alt_text(&mut document, "A red square")?;
+
+        assert_eq!(image.alt_text().as_deref(), Some("A red square"));
+        assert_eq!(image.marked_content_id(), Some(0));
+
+        // Setting the alt text again should replace the existing value.
+
+        image.set_alt_text(&mut document, "Un carré rouge")?;
+
+        assert_eq!(image.alt_text().as_deref(), Some("Un carré rouge"));
+
+        page.regenerate_content()?;
+
+        // The alt text is also written to a Figure structure element bound to the image.
+
+        let bytes = document.save_to_bytes()?;
+
+        let text = String::from_utf8_lossy(&bytes);
+
+        assert!(text.contains("/Type /StructElem /S /Figure"));
+        assert!(text.contains("/K 0 /Alt "));
+
+        // An image that is not on a page cannot be bound to a structure element.
+
+        let mut detached =
+            PdfPageImageObject::new(&document, &image::DynamicImage::new_rgb8(10, 10))?;
+
+        assert!(matches!(
+            detached.set_alt_text(&mut document, "Detached"),
+            Err(PdfiumError::PageObjectNotAttachedToPage)
+        ));
+
+        Ok(())
+    }
+
     fn compare_equality_of_byte_arrays(a: &[u8], b: &[u8]) -> bool {
         if a.len() != b.len() {
             return false;
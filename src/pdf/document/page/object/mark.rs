@@ -0,0 +1,114 @@
+//! Internal functionality for reading the content marks applied to a page object.
+
+use crate::bindgen::{FPDF_PAGE, FPDF_PAGEOBJECT, FPDF_PAGEOBJECTMARK};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::utils::mem::create_byte_buffer;
+use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use std::os::raw::{c_ulong, c_void};
+
+/// Returns an iterator over the handles of all content marks applied to the given page object.
+pub(crate) fn marks<'a>(
+    object: FPDF_PAGEOBJECT,
+    bindings: &'a dyn PdfiumLibraryBindings,
+) -> impl Iterator<Item = FPDF_PAGEOBJECTMARK> + 'a {
+    (0..bindings.FPDFPageObj_CountMarks(object).max(0))
+        .map(move |index| bindings.FPDFPageObj_GetMark(object, index as c_ulong))
+        .filter(|mark| !mark.is_null())
+}
+
+/// Returns a marked content ID not yet used by any page object on the given page.
+pub(crate) fn next_marked_content_id(page: FPDF_PAGE, bindings: &dyn PdfiumLibraryBindings) -> i32 {
+    (0..bindings.FPDFPage_CountObjects(page).max(0))
+        .map(|index| bindings.FPDFPage_GetObject(page, index))
+        .filter(|object| !object.is_null())
+        .map(|object| bindings.FPDFPageObj_GetMarkedContentID(object))
+        .max()
+        .map_or(0, |id| id.max(-1) + 1)
+}
+
+/// Returns the name of the given content mark.
+pub(crate) fn get_mark_name(
+    mark: FPDF_PAGEOBJECTMARK,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Option<String> {
+    // Retrieving the mark name from Pdfium is a two-step operation. First, we call
+    // FPDFPageObjMark_GetName() with a null buffer; this will retrieve the length of
+    // the name in bytes. If the length is zero, then there is no name.
+
+    // If the length is non-zero, then we reserve a byte buffer of the given
+    // length and call FPDFPageObjMark_GetName() again with a pointer to the buffer;
+    // this will write the name to the buffer in UTF16-LE format.
+
+    let mut buffer_length: c_ulong = 0;
+
+    if !bindings.is_true(bindings.FPDFPageObjMark_GetName(
+        mark,
+        std::ptr::null_mut(),
+        0,
+        &mut buffer_length,
+    )) || buffer_length == 0
+    {
+        return None;
+    }
+
+    let mut buffer = create_byte_buffer(buffer_length as usize);
+
+    if bindings.is_true(bindings.FPDFPageObjMark_GetName(
+        mark,
+        buffer.as_mut_ptr() as *mut c_void,
+        buffer_length,
+        &mut buffer_length,
+    )) {
+        get_string_from_pdfium_utf16le_bytes(buffer)
+    } else {
+        None
+    }
+}
+
+/// Returns the value of the string parameter with the given key in the given content mark.
+pub(crate) fn get_mark_string_param(
+    mark: FPDF_PAGEOBJECTMARK,
+    key: &str,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Option<String> {
+    let mut buffer_length: c_ulong = 0;
+
+    if !bindings.is_true(bindings.FPDFPageObjMark_GetParamStringValue(
+        mark,
+        key,
+        std::ptr::null_mut(),
+        0,
+        &mut buffer_length,
+    )) || buffer_length == 0
+    {
+        return None;
+    }
+
+    let mut buffer = create_byte_buffer(buffer_length as usize);
+
+    if bindings.is_true(bindings.FPDFPageObjMark_GetParamStringValue(
+        mark,
+        key,
+        buffer.as_mut_ptr() as *mut c_void,
+        buffer_length,
+        &mut buffer_length,
+    )) {
+        get_string_from_pdfium_utf16le_bytes(buffer)
+    } else {
+        None
+    }
+}
+
+/// Returns the names of the optional content groups, or layers, to which the given page
+/// object belongs, outermost first. Optional content is identified by content marks
+/// tagged `OC` whose properties dictionary is an optional content group with a `Name` entry;
+/// optional content membership dictionaries, which have no name, are not returned.
+pub(crate) fn optional_content_group_names(
+    object: FPDF_PAGEOBJECT,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Vec<String> {
+    marks(object, bindings)
+        .filter(|mark| get_mark_name(*mark, bindings).as_deref() == Some("OC"))
+        .filter_map(|mark| get_mark_string_param(mark, "Name", bindings))
+        .collect()
+}
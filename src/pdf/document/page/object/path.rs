@@ -630,6 +630,8 @@ impl<'a> PdfPagePathObject<'a> {
             self.current_point_x = x;
             self.current_point_y = y;
 
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -649,6 +651,8 @@ impl<'a> PdfPagePathObject<'a> {
             self.current_point_x = x;
             self.current_point_y = y;
 
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -681,6 +685,8 @@ impl<'a> PdfPagePathObject<'a> {
             self.current_point_x = x;
             self.current_point_y = y;
 
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -796,6 +802,8 @@ impl<'a> PdfPagePathObject<'a> {
             .bindings
             .is_true(self.bindings.FPDFPath_Close(self.object_handle))
         {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -862,6 +870,8 @@ impl<'a> PdfPagePathObject<'a> {
             fill_mode.as_pdfium() as c_int,
             self.bindings.bool_to_pdfium(do_stroke),
         )) {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
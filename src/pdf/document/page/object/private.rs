@@ -13,6 +13,7 @@ pub(crate) mod internal {
     use crate::bindings::PdfiumLibraryBindings;
     use crate::error::{PdfiumError, PdfiumInternalError};
     use crate::pdf::document::page::annotation::objects::PdfPageAnnotationObjects;
+    use crate::pdf::document::page::generation::PdfContentGenerations;
     use crate::pdf::document::page::object::{PdfPageObject, PdfPageObjectCommon};
     use crate::pdf::document::page::objects::PdfPageObjects;
     use crate::pdf::matrix::{PdfMatrix, PdfMatrixValue};
@@ -62,6 +63,15 @@ pub(crate) mod internal {
             self.get_page_handle().is_some() || self.get_annotation_handle().is_some()
         }
 
+        /// Records that the [PdfPage] containing this [PdfPageObject], if any, may have changed,
+        /// advancing the page's content generation.
+        #[inline]
+        fn mark_page_content_changed(&self) {
+            if let Some(page_handle) = self.get_page_handle() {
+                PdfContentGenerations::increment_for_page_handle(page_handle);
+            }
+        }
+
         /// Adds this [PdfPageObject] to the given [PdfPageObjects] collection.
         // We use inversion of control here so that PdfPageObjects doesn't need to care whether
         // the page object being added is a single object or a group.
@@ -76,6 +86,8 @@ pub(crate) mod internal {
 
             self.set_page_handle(page_handle);
 
+            self.mark_page_content_changed();
+
             Ok(())
         }
 
@@ -88,6 +100,8 @@ pub(crate) mod internal {
                     self.bindings()
                         .FPDFPage_RemoveObject(page_handle, self.get_object_handle()),
                 ) {
+                    self.mark_page_content_changed();
+
                     self.clear_page_handle();
 
                     Ok(())
@@ -235,6 +249,8 @@ pub(crate) mod internal {
                 f as c_double,
             );
 
+            self.mark_page_content_changed();
+
             Ok(())
         }
 
@@ -268,6 +284,8 @@ pub(crate) mod internal {
                 self.bindings()
                     .FPDFPageObj_SetMatrix(self.get_object_handle(), &matrix.as_pdfium()),
             ) {
+                self.mark_page_content_changed();
+
                 Ok(())
             } else {
                 Err(PdfiumError::PdfiumLibraryInternalError(
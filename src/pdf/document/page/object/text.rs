@@ -352,6 +352,8 @@ impl<'a> PdfPageTextObject<'a> {
             self.bindings()
                 .FPDFText_SetText_str(self.object_handle, text),
         ) {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -369,6 +371,8 @@ impl<'a> PdfPageTextObject<'a> {
             self.bindings()
                 .FPDFTextObj_SetTextRenderMode(self.object_handle, render_mode.as_pdfium()),
         ) {
+            self.mark_page_content_changed();
+
             Ok(())
         } else {
             Err(PdfiumError::PdfiumLibraryInternalError(
@@ -0,0 +1,382 @@
+//! Defines the [PdfPrintedPageNumber] struct, exposing functionality related to the page
+//! number printed in the content of a single `PdfPage`.
+
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+
+/// The numbering style of a [PdfPrintedPageNumber].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfPageNumberStyle {
+    /// Decimal arabic numerals, e.g. `1, 2, 3`.
+    Arabic,
+
+    /// Lowercase roman numerals, e.g. `i, ii, iii`. Commonly used for front matter.
+    LowercaseRoman,
+
+    /// Uppercase roman numerals, e.g. `I, II, III`.
+    UppercaseRoman,
+}
+
+/// A page number printed in the content of a `PdfPage`, as detected by
+/// `PdfPage::detect_printed_page_number()`.
+///
+/// The printed page number of a page need not match either its index in the containing
+/// `PdfDocument` or its page label, if any. This is often the case in scanned books,
+/// where front matter, blank pages, or missing pages cause the printed numbering to drift
+/// away from the document's logical page numbering.
+#[derive(Debug, Clone)]
+pub struct PdfPrintedPageNumber {
+    text: String,
+    value: u32,
+    style: PdfPageNumberStyle,
+    bounds: PdfRect,
+}
+
+impl PdfPrintedPageNumber {
+    /// Returns the text of the page number exactly as it appears on the page, including
+    /// any surrounding decoration such as `Page` or `- 12 -`.
+    #[inline]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Returns the numeric value of the page number. Roman numerals are converted
+    /// to their equivalent decimal value.
+    #[inline]
+    pub fn value(&self) -> u32 {
+        self.value
+    }
+
+    /// Returns the [PdfPageNumberStyle] in which the page number is printed.
+    #[inline]
+    pub fn style(&self) -> PdfPageNumberStyle {
+        self.style
+    }
+
+    /// Returns the bounding box of the text containing the page number.
+    #[inline]
+    pub fn bounds(&self) -> PdfRect {
+        self.bounds
+    }
+}
+
+/// The proportion of the page height, measured from both the top and bottom edges of the page,
+/// within which page numbers are expected to appear.
+const MARGIN_PROPORTION: f32 = 0.12;
+
+/// Selects the most likely printed page number from the given text runs, each given as
+/// a string and the bounding box of that string on a page of the given dimensions.
+pub(crate) fn detect_printed_page_number(
+    page_width: PdfPoints,
+    page_height: PdfPoints,
+    candidates: impl Iterator<Item = (String, PdfRect)>,
+) -> Option<PdfPrintedPageNumber> {
+    let page_width = page_width.value;
+
+    let page_height = page_height.value;
+
+    if page_width <= 0.0 || page_height <= 0.0 {
+        return None;
+    }
+
+    let margin = page_height * MARGIN_PROPORTION;
+
+    let mut best: Option<(f32, PdfPrintedPageNumber)> = None;
+
+    for (text, bounds) in candidates {
+        // Distance of the text from the nearest top or bottom edge of the page, expressed
+        // as a proportion of the margin. Text outside both margins cannot be a page number.
+
+        let (distance, is_footer) = if bounds.top.value <= margin {
+            (bounds.bottom.value.max(0.0), true)
+        } else if bounds.bottom.value >= page_height - margin {
+            ((page_height - bounds.top.value).max(0.0), false)
+        } else {
+            continue;
+        };
+
+        let (value, style, is_whole_text) = match parse_page_number(&text) {
+            Some((value, style)) => (value, style, true),
+            None => {
+                // A running header or footer may combine the page number with other text,
+                // e.g. "12 A Tale of Two Cities" or "Chapter One 13".
+
+                let tokens = text.split_whitespace().collect::<Vec<_>>();
+
+                if tokens.len() < 2 {
+                    continue;
+                }
+
+                match tokens
+                    .first()
+                    .and_then(|token| parse_numeral(token))
+                    .or_else(|| tokens.last().and_then(|token| parse_numeral(token)))
+                {
+                    Some((value, style)) => (value, style, false),
+                    None => continue,
+                }
+            }
+        };
+
+        // Score the candidate. Candidates closer to the edge of the page, in the footer,
+        // centered or aligned to an outside edge, consisting solely of the page number,
+        // and using arabic numerals are preferred.
+
+        let center_x = (bounds.left.value + bounds.right.value) / 2.0;
+
+        let horizontal = center_x / page_width;
+
+        let mut score = 1.0 - (distance / margin).min(1.0);
+
+        if is_footer {
+            score += 0.25;
+        }
+
+        if (horizontal - 0.5).abs() < 0.1 || !(0.2..=0.8).contains(&horizontal) {
+            score += 0.5;
+        }
+
+        if is_whole_text {
+            score += 1.0;
+        }
+
+        if style == PdfPageNumberStyle::Arabic {
+            score += 0.25;
+        }
+
+        if best
+            .as_ref()
+            .map(|(best_score, _)| score > *best_score)
+            .unwrap_or(true)
+        {
+            best = Some((
+                score,
+                PdfPrintedPageNumber {
+                    text: text.trim().to_string(),
+                    value,
+                    style,
+                    bounds,
+                },
+            ));
+        }
+    }
+
+    best.map(|(_, page_number)| page_number)
+}
+
+/// Parses text that consists solely of a page number, possibly with common decoration such
+/// as `Page 12`, `- 12 -`, `[xii]`, or `12 of 300`.
+pub(crate) fn parse_page_number(text: &str) -> Option<(u32, PdfPageNumberStyle)> {
+    let is_decoration = |c: char| {
+        c.is_whitespace() || matches!(c, '-' | '–' | '—' | '[' | ']' | '(' | ')' | '|' | '.')
+    };
+
+    let mut text = text.trim_matches(is_decoration);
+
+    // Strip any leading "Page", "Pg", or "P" prefix.
+
+    for prefix in ["page", "pg", "p"].iter() {
+        if text.len() > prefix.len()
+            && text.is_char_boundary(prefix.len())
+            && text[..prefix.len()].eq_ignore_ascii_case(prefix)
+        {
+            let rest = &text[prefix.len()..];
+
+            if rest.starts_with(|c: char| c.is_whitespace() || c == '.') {
+                text = rest.trim_matches(is_decoration);
+
+                break;
+            }
+        }
+    }
+
+    // Strip any trailing page count, as in "12 of 300" or "12 / 300".
+
+    let tokens = text.split_whitespace().collect::<Vec<_>>();
+
+    let text = match tokens.as_slice() {
+        [number] => number.split('/').next().unwrap_or(number).trim(),
+        [number, separator, total]
+            if (separator.eq_ignore_ascii_case("of") || *separator == "/")
+                && parse_numeral(total).is_some() =>
+        {
+            number
+        }
+        _ => return None,
+    };
+
+    parse_numeral(text)
+}
+
+/// Parses a single arabic or roman numeral.
+fn parse_numeral(text: &str) -> Option<(u32, PdfPageNumberStyle)> {
+    if text.is_empty() {
+        return None;
+    }
+
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        return if text.len() <= 5 {
+            text.parse::<u32>()
+                .ok()
+                .filter(|value| *value > 0)
+                .map(|value| (value, PdfPageNumberStyle::Arabic))
+        } else {
+            None
+        };
+    }
+
+    let style = if text.chars().all(|c| "ivxlcdm".contains(c)) {
+        PdfPageNumberStyle::LowercaseRoman
+    } else if text.chars().all(|c| "IVXLCDM".contains(c)) {
+        PdfPageNumberStyle::UppercaseRoman
+    } else {
+        return None;
+    };
+
+    let value = roman_to_decimal(&text.to_ascii_uppercase())?;
+
+    Some((value, style))
+}
+
+/// Converts a roman numeral in canonical form to its decimal value. Non-canonical numerals,
+/// such as `IIII` or `VX`, are rejected, since they are more likely to be ordinary text.
+fn roman_to_decimal(text: &str) -> Option<u32> {
+    let digit_value = |c: char| match c {
+        'I' => Some(1),
+        'V' => Some(5),
+        'X' => Some(10),
+        'L' => Some(50),
+        'C' => Some(100),
+        'D' => Some(500),
+        'M' => Some(1000),
+        _ => None,
+    };
+
+    let values = text
+        .chars()
+        .map(digit_value)
+        .collect::<Option<Vec<u32>>>()?;
+
+    let mut total = 0;
+
+    for (index, value) in values.iter().enumerate() {
+        match values.get(index + 1) {
+            Some(next) if next > value => total -= *value as i64,
+            _ => total += *value as i64,
+        }
+    }
+
+    if total <= 0 || total >= 4000 {
+        return None;
+    }
+
+    let total = total as u32;
+
+    if decimal_to_roman(total) == text {
+        Some(total)
+    } else {
+        None
+    }
+}
+
+/// Converts a decimal value between 1 and 3999 to an uppercase roman numeral in canonical form.
+fn decimal_to_roman(mut value: u32) -> String {
+    const NUMERALS: [(u32, &str); 13] = [
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let mut result = String::new();
+
+    for (numeral_value, numeral) in NUMERALS.iter() {
+        while value >= *numeral_value {
+            result.push_str(numeral);
+            value -= numeral_value;
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::page_number::*;
+
+    #[test]
+    fn test_parse_page_number() {
+        assert_eq!(
+            parse_page_number("12"),
+            Some((12, PdfPageNumberStyle::Arabic))
+        );
+        assert_eq!(
+            parse_page_number(" - 12 - "),
+            Some((12, PdfPageNumberStyle::Arabic))
+        );
+        assert_eq!(
+            parse_page_number("Page 7 of 300"),
+            Some((7, PdfPageNumberStyle::Arabic))
+        );
+        assert_eq!(
+            parse_page_number("[xiv]"),
+            Some((14, PdfPageNumberStyle::LowercaseRoman))
+        );
+        assert_eq!(
+            parse_page_number("MCMXCIX"),
+            Some((1999, PdfPageNumberStyle::UppercaseRoman))
+        );
+        assert_eq!(parse_page_number("IIII"), None);
+        assert_eq!(parse_page_number("Chapter"), None);
+        assert_eq!(parse_page_number("0"), None);
+        assert_eq!(parse_page_number("2024-01-31"), None);
+    }
+
+    #[test]
+    fn test_detect_printed_page_number() {
+        let width = PdfPoints::new(600.0);
+        let height = PdfPoints::new(800.0);
+
+        let candidates = vec![
+            (
+                "23 A Tale of Two Cities".to_string(),
+                PdfRect::new_from_values(770.0, 50.0, 780.0, 300.0),
+            ),
+            (
+                "It was the best of times, 1775".to_string(),
+                PdfRect::new_from_values(400.0, 50.0, 410.0, 550.0),
+            ),
+            (
+                "23".to_string(),
+                PdfRect::new_from_values(30.0, 295.0, 40.0, 305.0),
+            ),
+        ];
+
+        let detected = detect_printed_page_number(width, height, candidates.into_iter()).unwrap();
+
+        assert_eq!(detected.value(), 23);
+        assert_eq!(detected.text(), "23");
+        assert_eq!(detected.style(), PdfPageNumberStyle::Arabic);
+        assert_eq!(detected.bounds().bottom.value, 30.0);
+
+        assert!(detect_printed_page_number(
+            width,
+            height,
+            vec![(
+                "42".to_string(),
+                PdfRect::new_from_values(400.0, 295.0, 410.0, 305.0),
+            )]
+            .into_iter()
+        )
+        .is_none());
+    }
+}
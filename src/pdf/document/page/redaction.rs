@@ -0,0 +1,703 @@
+//! Internal functionality for redacting regions of a `PdfPage`, permanently removing the
+//! text and image content that lies within them rather than merely covering it up.
+
+use crate::bindgen::{
+    FPDF_ANNOTATION, FPDF_DOCUMENT, FPDF_FILLMODE_WINDING, FPDF_PAGE, FPDF_PAGEOBJECT,
+    FPDF_PAGEOBJ_FORM, FPDF_PAGEOBJ_IMAGE, FS_MATRIX, FS_RECTF,
+};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::bitmap::PdfBitmap;
+use crate::pdf::color::PdfColor;
+use crate::pdf::rect::PdfRect;
+use std::collections::HashMap;
+use std::os::raw::{c_double, c_float, c_int, c_uint, c_ulong};
+
+/// A single character in a text page, with the position needed to decide whether it lies
+/// within a redacted region.
+#[derive(Debug, Clone)]
+pub(crate) struct PdfRedactionChar {
+    pub(crate) char: char,
+    pub(crate) origin: (f32, f32),
+    pub(crate) bounds: Option<PdfRect>,
+}
+
+impl PdfRedactionChar {
+    /// Returns `true` if any part of this character lies within any of the given regions.
+    /// Characters without bounds, such as spaces, are redacted if their origin lies
+    /// within a region.
+    pub(crate) fn is_redacted(&self, regions: &[PdfRect]) -> bool {
+        regions.iter().any(|region| match self.bounds {
+            Some(bounds) => {
+                bounds.left.value < region.right.value
+                    && bounds.right.value > region.left.value
+                    && bounds.bottom.value < region.top.value
+                    && bounds.top.value > region.bottom.value
+            }
+            None => {
+                let (x, y) = self.origin;
+
+                x >= region.left.value
+                    && x <= region.right.value
+                    && y >= region.bottom.value
+                    && y <= region.top.value
+            }
+        })
+    }
+}
+
+/// Returns the runs of consecutive characters in the given text object that lie outside
+/// all the given regions, together with the origin of the first character in each run.
+/// Runs containing only whitespace are omitted.
+///
+/// Returns `None` if no character in the text object lies within any region, in which case
+/// the text object does not need to be changed.
+pub(crate) fn kept_runs(
+    chars: &[PdfRedactionChar],
+    regions: &[PdfRect],
+) -> Option<Vec<(String, (f32, f32))>> {
+    let mut runs: Vec<(String, (f32, f32))> = Vec::new();
+
+    let mut current: Option<(String, (f32, f32))> = None;
+
+    let mut is_changed = false;
+
+    for char in chars {
+        if char.is_redacted(regions) {
+            is_changed = true;
+
+            runs.extend(current.take());
+        } else {
+            current
+                .get_or_insert_with(|| (String::new(), char.origin))
+                .0
+                .push(char.char);
+        }
+    }
+
+    runs.extend(current);
+
+    if is_changed {
+        runs.retain(|(text, _)| !text.trim().is_empty());
+
+        Some(runs)
+    } else {
+        None
+    }
+}
+
+/// Permanently removes all text and image content within the given regions of the given
+/// page, then covers each region with a filled rectangle of the given color, if any.
+///
+/// * Text objects containing characters within a region are replaced by new text objects
+///   holding only the characters outside the regions. Text objects entirely within the
+///   regions are removed.
+/// * Pixels of image objects within a region are overwritten.
+/// * Form XObjects containing redacted text, or images overlapping a region, are removed
+///   in their entirety, since Pdfium cannot edit their content in place.
+/// * Annotations overlapping a region are removed.
+///
+/// The caller is responsible for regenerating the page's content stream afterwards.
+pub(crate) fn redact_page(
+    document: FPDF_DOCUMENT,
+    page: FPDF_PAGE,
+    regions: &[PdfRect],
+    fill_color: Option<PdfColor>,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<(), PdfiumError> {
+    let regions = regions
+        .iter()
+        .filter(|region| region.width().value > 0.0 && region.height().value > 0.0)
+        .copied()
+        .collect::<Vec<_>>();
+
+    if regions.is_empty() {
+        return Ok(());
+    }
+
+    // Collect the characters on the page before any text objects are changed; removing
+    // a text object invalidates all text pages loaded for the page.
+
+    let chars_by_object = chars_by_text_object(page, bindings)?;
+
+    let top_level_objects = (0..bindings.FPDFPage_CountObjects(page))
+        .map(|index| bindings.FPDFPage_GetObject(page, index))
+        .filter(|object| !object.is_null())
+        .collect::<Vec<_>>();
+
+    let mut redacted_nested_objects = Vec::new();
+
+    for (object, chars) in chars_by_object.iter() {
+        let runs = match kept_runs(chars, &regions) {
+            Some(runs) => runs,
+            None => continue,
+        };
+
+        if top_level_objects.contains(object) {
+            replace_text_object(document, page, *object, &runs, bindings)?;
+        } else {
+            redacted_nested_objects.push(*object);
+        }
+    }
+
+    for object in top_level_objects {
+        match bindings.FPDFPageObj_GetType(object) as u32 {
+            FPDF_PAGEOBJ_IMAGE if overlaps_any(object_bounds(object, bindings), &regions) => {
+                redact_image_object(object, &regions, bindings)?;
+            }
+            FPDF_PAGEOBJ_FORM
+                if form_contains(object, bindings, &|nested| {
+                    redacted_nested_objects.contains(&nested)
+                        || (bindings.FPDFPageObj_GetType(nested) == FPDF_PAGEOBJ_IMAGE as c_int
+                            && overlaps_any(object_bounds(nested, bindings), &regions))
+                }) =>
+            {
+                remove_object(page, object, bindings)?;
+            }
+            _ => {}
+        }
+    }
+
+    remove_annotations(page, &regions, bindings)?;
+
+    if let Some(color) = fill_color {
+        for region in regions.iter() {
+            add_fill_rect(page, region, color, bindings)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns every character on the given page that belongs to a text object, grouped by
+/// text object, in the order in which the characters are defined on the page.
+fn chars_by_text_object(
+    page: FPDF_PAGE,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<Vec<(FPDF_PAGEOBJECT, Vec<PdfRedactionChar>)>, PdfiumError> {
+    let text_page = bindings.FPDFText_LoadPage(page);
+
+    if text_page.is_null() {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    let mut result: Vec<(FPDF_PAGEOBJECT, Vec<PdfRedactionChar>)> = Vec::new();
+
+    let mut positions: HashMap<FPDF_PAGEOBJECT, usize> = HashMap::new();
+
+    for index in 0..bindings.FPDFText_CountChars(text_page) {
+        let object = bindings.FPDFText_GetTextObject(text_page, index);
+
+        let char = match char::from_u32(bindings.FPDFText_GetUnicode(text_page, index)) {
+            Some(char) if !object.is_null() => char,
+            _ => continue,
+        };
+
+        let mut x: c_double = 0.0;
+        let mut y: c_double = 0.0;
+
+        bindings.FPDFText_GetCharOrigin(text_page, index, &mut x, &mut y);
+
+        let mut left: c_double = 0.0;
+        let mut right: c_double = 0.0;
+        let mut bottom: c_double = 0.0;
+        let mut top: c_double = 0.0;
+
+        let bounds = if bindings.is_true(bindings.FPDFText_GetCharBox(
+            text_page,
+            index,
+            &mut left,
+            &mut right,
+            &mut bottom,
+            &mut top,
+        )) && right > left
+            && top > bottom
+        {
+            Some(PdfRect::new_from_values(
+                bottom as f32,
+                left as f32,
+                top as f32,
+                right as f32,
+            ))
+        } else {
+            None
+        };
+
+        let position = *positions.entry(object).or_insert_with(|| {
+            result.push((object, Vec::new()));
+
+            result.len() - 1
+        });
+
+        result[position].1.push(PdfRedactionChar {
+            char,
+            origin: (x as f32, y as f32),
+            bounds,
+        });
+    }
+
+    bindings.FPDFText_ClosePage(text_page);
+
+    Ok(result)
+}
+
+/// Replaces the given top-level text object with new text objects holding the given runs of
+/// text, each positioned at its given origin and sharing the font, font size, orientation,
+/// colors, and rendering mode of the original text object.
+fn replace_text_object(
+    document: FPDF_DOCUMENT,
+    page: FPDF_PAGE,
+    object: FPDF_PAGEOBJECT,
+    runs: &[(String, (f32, f32))],
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<(), PdfiumError> {
+    let font = bindings.FPDFTextObj_GetFont(object);
+
+    let mut font_size: c_float = 0.0;
+
+    let mut matrix = FS_MATRIX {
+        a: 0.0,
+        b: 0.0,
+        c: 0.0,
+        d: 0.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    if !runs.is_empty()
+        && (font.is_null()
+            || !bindings.is_true(bindings.FPDFTextObj_GetFontSize(object, &mut font_size))
+            || !bindings.is_true(bindings.FPDFPageObj_GetMatrix(object, &mut matrix)))
+    {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    let fill_color = object_color(object, bindings, true);
+
+    let stroke_color = object_color(object, bindings, false);
+
+    let render_mode = bindings.FPDFTextObj_GetTextRenderMode(object);
+
+    for (text, (x, y)) in runs {
+        let replacement = bindings.FPDFPageObj_CreateTextObj(document, font, font_size);
+
+        if replacement.is_null() {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let placement = FS_MATRIX {
+            e: *x,
+            f: *y,
+            ..matrix
+        };
+
+        if !bindings.is_true(bindings.FPDFText_SetText_str(replacement, text))
+            || !bindings.is_true(bindings.FPDFPageObj_SetMatrix(replacement, &placement))
+        {
+            bindings.FPDFPageObj_Destroy(replacement);
+
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        if let Some((r, g, b, a)) = fill_color {
+            bindings.FPDFPageObj_SetFillColor(replacement, r, g, b, a);
+        }
+
+        if let Some((r, g, b, a)) = stroke_color {
+            bindings.FPDFPageObj_SetStrokeColor(replacement, r, g, b, a);
+        }
+
+        bindings.FPDFTextObj_SetTextRenderMode(replacement, render_mode);
+
+        bindings.FPDFPage_InsertObject(page, replacement);
+    }
+
+    remove_object(page, object, bindings)
+}
+
+/// Returns the fill or stroke color of the given page object as red, green, blue, and
+/// alpha components, if known.
+fn object_color(
+    object: FPDF_PAGEOBJECT,
+    bindings: &dyn PdfiumLibraryBindings,
+    is_fill: bool,
+) -> Option<(c_uint, c_uint, c_uint, c_uint)> {
+    let mut r = 0;
+    let mut g = 0;
+    let mut b = 0;
+    let mut a = 0;
+
+    let result = if is_fill {
+        bindings.FPDFPageObj_GetFillColor(object, &mut r, &mut g, &mut b, &mut a)
+    } else {
+        bindings.FPDFPageObj_GetStrokeColor(object, &mut r, &mut g, &mut b, &mut a)
+    };
+
+    bindings.is_true(result).then(|| (r, g, b, a))
+}
+
+/// Overwrites the pixels of the given image object that lie within any of the given regions
+/// with black, replacing the image's data in the document.
+fn redact_image_object(
+    object: FPDF_PAGEOBJECT,
+    regions: &[PdfRect],
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<(), PdfiumError> {
+    let mut matrix = FS_MATRIX {
+        a: 0.0,
+        b: 0.0,
+        c: 0.0,
+        d: 0.0,
+        e: 0.0,
+        f: 0.0,
+    };
+
+    let handle = bindings.FPDFImageObj_GetBitmap(object);
+
+    if handle.is_null() || !bindings.is_true(bindings.FPDFPageObj_GetMatrix(object, &mut matrix)) {
+        if !handle.is_null() {
+            bindings.FPDFBitmap_Destroy(handle);
+        }
+
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    let mut bitmap = PdfBitmap::from_pdfium(handle, bindings);
+
+    let width = bitmap.width() as f32;
+
+    let height = bitmap.height() as f32;
+
+    // The image's matrix maps the unit square onto the page, with the first row of pixels
+    // at the top of the square.
+
+    let is_filled = bitmap.fill_pixels_where(PdfColor::BLACK, |column, row| {
+        let u = (column as f32 + 0.5) / width;
+
+        let v = 1.0 - (row as f32 + 0.5) / height;
+
+        let x = matrix.a * u + matrix.c * v + matrix.e;
+
+        let y = matrix.b * u + matrix.d * v + matrix.f;
+
+        regions.iter().any(|region| {
+            x >= region.left.value
+                && x <= region.right.value
+                && y >= region.bottom.value
+                && y <= region.top.value
+        })
+    })?;
+
+    if is_filled
+        && !bindings.is_true(bindings.FPDFImageObj_SetBitmap(
+            std::ptr::null_mut(),
+            0,
+            object,
+            *bitmap.handle(),
+        ))
+    {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns `true` if the given form object, or any form object nested within it, contains
+/// a page object matching the given predicate.
+fn form_contains(
+    form: FPDF_PAGEOBJECT,
+    bindings: &dyn PdfiumLibraryBindings,
+    predicate: &dyn Fn(FPDF_PAGEOBJECT) -> bool,
+) -> bool {
+    let count = bindings.FPDFFormObj_CountObjects(form).max(0) as c_ulong;
+
+    (0..count)
+        .map(|index| bindings.FPDFFormObj_GetObject(form, index))
+        .filter(|object| !object.is_null())
+        .any(|object| {
+            predicate(object)
+                || (bindings.FPDFPageObj_GetType(object) == FPDF_PAGEOBJ_FORM as c_int
+                    && form_contains(object, bindings, predicate))
+        })
+}
+
+/// Removes all annotations overlapping any of the given regions from the given page.
+fn remove_annotations(
+    page: FPDF_PAGE,
+    regions: &[PdfRect],
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<(), PdfiumError> {
+    for index in (0..bindings.FPDFPage_GetAnnotCount(page)).rev() {
+        let annotation: FPDF_ANNOTATION = bindings.FPDFPage_GetAnnot(page, index);
+
+        if annotation.is_null() {
+            continue;
+        }
+
+        let mut rect = FS_RECTF {
+            left: 0.0,
+            top: 0.0,
+            right: 0.0,
+            bottom: 0.0,
+        };
+
+        let bounds = bindings
+            .is_true(bindings.FPDFAnnot_GetRect(annotation, &mut rect))
+            .then(|| PdfRect::from_pdfium(rect));
+
+        bindings.FPDFPage_CloseAnnot(annotation);
+
+        if overlaps_any(bounds, regions)
+            && !bindings.is_true(bindings.FPDFPage_RemoveAnnot(page, index))
+        {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Adds a filled rectangle covering the given region to the given page.
+fn add_fill_rect(
+    page: FPDF_PAGE,
+    region: &PdfRect,
+    color: PdfColor,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<(), PdfiumError> {
+    let rect = bindings.FPDFPageObj_CreateNewRect(
+        region.left.value,
+        region.bottom.value,
+        region.width().value,
+        region.height().value,
+    );
+
+    if rect.is_null() {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    if !bindings.is_true(bindings.FPDFPageObj_SetFillColor(
+        rect,
+        color.red() as c_uint,
+        color.green() as c_uint,
+        color.blue() as c_uint,
+        color.alpha() as c_uint,
+    )) || !bindings.is_true(bindings.FPDFPath_SetDrawMode(
+        rect,
+        FPDF_FILLMODE_WINDING as c_int,
+        bindings.FALSE(),
+    )) {
+        bindings.FPDFPageObj_Destroy(rect);
+
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    bindings.FPDFPage_InsertObject(page, rect);
+
+    Ok(())
+}
+
+/// Adds the given overlay text to the given page, vertically centered within the given
+/// region and inset slightly from its left edge, in a standard font sized to fit the region's
+/// height. The text is drawn in white over dark fill colors, and in black otherwise.
+pub(crate) fn add_overlay_text(
+    document: FPDF_DOCUMENT,
+    page: FPDF_PAGE,
+    region: &PdfRect,
+    text: &str,
+    fill_color: Option<PdfColor>,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<(), PdfiumError> {
+    let font_size = overlay_font_size(region);
+
+    let object = bindings.FPDFPageObj_NewTextObj(document, "Helvetica", font_size);
+
+    if object.is_null() {
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    let text_color = match fill_color {
+        Some(color) if is_dark(color) => PdfColor::WHITE,
+        _ => PdfColor::BLACK,
+    };
+
+    if !bindings.is_true(bindings.FPDFText_SetText_str(object, text))
+        || !bindings.is_true(bindings.FPDFPageObj_SetFillColor(
+            object,
+            text_color.red() as c_uint,
+            text_color.green() as c_uint,
+            text_color.blue() as c_uint,
+            text_color.alpha() as c_uint,
+        ))
+    {
+        bindings.FPDFPageObj_Destroy(object);
+
+        return Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ));
+    }
+
+    // Text is positioned by its baseline, which lies roughly a fifth of the font size
+    // above the bottom of its descenders.
+
+    bindings.FPDFPageObj_Transform(
+        object,
+        1.0,
+        0.0,
+        0.0,
+        1.0,
+        (region.left.value + font_size * 0.25) as c_double,
+        (region.bottom.value + (region.height().value - font_size) / 2.0 + font_size * 0.2)
+            as c_double,
+    );
+
+    bindings.FPDFPage_InsertObject(page, object);
+
+    Ok(())
+}
+
+/// Returns the font size of overlay text drawn within the given region: three quarters of
+/// the region's height, up to a maximum of 12 points.
+#[inline]
+fn overlay_font_size(region: &PdfRect) -> c_float {
+    (region.height().value * 0.75).min(12.0)
+}
+
+/// Returns `true` if the given color is dark enough that text drawn over it should be light.
+#[inline]
+fn is_dark(color: PdfColor) -> bool {
+    // Perceived brightness, as per https://www.w3.org/TR/AERT/#color-contrast
+
+    (color.red() as u32 * 299 + color.green() as u32 * 587 + color.blue() as u32 * 114) / 1000 < 128
+}
+
+/// Removes the given top-level object from the given page and destroys it.
+fn remove_object(
+    page: FPDF_PAGE,
+    object: FPDF_PAGEOBJECT,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Result<(), PdfiumError> {
+    if bindings.is_true(bindings.FPDFPage_RemoveObject(page, object)) {
+        bindings.FPDFPageObj_Destroy(object);
+
+        Ok(())
+    } else {
+        Err(PdfiumError::PdfiumLibraryInternalError(
+            PdfiumInternalError::Unknown,
+        ))
+    }
+}
+
+/// Returns the bounding box of the given page object, if known.
+fn object_bounds(object: FPDF_PAGEOBJECT, bindings: &dyn PdfiumLibraryBindings) -> Option<PdfRect> {
+    let mut left = 0.0;
+    let mut bottom = 0.0;
+    let mut right = 0.0;
+    let mut top = 0.0;
+
+    bindings
+        .is_true(bindings.FPDFPageObj_GetBounds(
+            object,
+            &mut left,
+            &mut bottom,
+            &mut right,
+            &mut top,
+        ))
+        .then(|| PdfRect::new_from_values(bottom, left, top, right))
+}
+
+/// Returns `true` if the given bounds overlap any of the given regions.
+#[inline]
+fn overlaps_any(bounds: Option<PdfRect>, regions: &[PdfRect]) -> bool {
+    bounds
+        .map(|bounds| regions.iter().any(|region| bounds.does_overlap(region)))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::redaction::*;
+
+    #[test]
+    fn test_kept_runs() {
+        // "Call 555 0199 now", with characters five points wide on a single line.
+
+        let chars = "Call 555 0199 now"
+            .chars()
+            .enumerate()
+            .map(|(index, char)| {
+                let left = index as f32 * 5.0;
+
+                PdfRedactionChar {
+                    char,
+                    origin: (left, 100.0),
+                    bounds: (char != ' ')
+                        .then(|| PdfRect::new_from_values(100.0, left, 110.0, left + 5.0)),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // A region covering the phone number, and clipping the first digit only partially.
+
+        let regions = [PdfRect::new_from_values(95.0, 27.0, 115.0, 64.0)];
+
+        assert_eq!(
+            kept_runs(&chars, &regions),
+            Some(vec![
+                ("Call ".to_string(), (0.0, 100.0)),
+                (" now".to_string(), (65.0, 100.0)),
+            ])
+        );
+
+        // A region elsewhere on the page leaves the text object unchanged.
+
+        assert_eq!(
+            kept_runs(&chars, &[PdfRect::new_from_values(0.0, 0.0, 50.0, 50.0)]),
+            None
+        );
+
+        // A region covering every character removes the text object entirely.
+
+        assert_eq!(
+            kept_runs(&chars, &[PdfRect::new_from_values(0.0, 0.0, 200.0, 200.0)]),
+            Some(vec![])
+        );
+    }
+
+    #[test]
+    fn test_overlay_text_style() {
+        assert_eq!(
+            overlay_font_size(&PdfRect::new_from_values(100.0, 0.0, 108.0, 50.0)),
+            6.0
+        );
+
+        assert_eq!(
+            overlay_font_size(&PdfRect::new_from_values(100.0, 0.0, 200.0, 50.0)),
+            12.0
+        );
+
+        assert!(is_dark(PdfColor::BLACK));
+        assert!(is_dark(PdfColor::new(128, 0, 0, 255)));
+        assert!(!is_dark(PdfColor::WHITE));
+        assert!(!is_dark(PdfColor::new(255, 255, 0, 255)));
+    }
+}
@@ -12,15 +12,57 @@ use crate::error::PdfiumError;
 use crate::pdf::bitmap::{PdfBitmapFormat, Pixels};
 use crate::pdf::color::PdfColor;
 use crate::pdf::document::page::field::PdfFormFieldType;
+use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
 use crate::pdf::document::page::PdfPageOrientation::{Landscape, Portrait};
 use crate::pdf::document::page::{PdfPage, PdfPageOrientation, PdfPageRenderRotation};
 use crate::pdf::matrix::{PdfMatrix, PdfMatrixValue};
 use crate::pdf::points::PdfPoints;
+use crate::utils::hash::Fnv1a64;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::ops::ControlFlow;
 use std::os::raw::c_int;
+use std::sync::Arc;
 
 #[cfg(doc)]
 use crate::pdf::bitmap::PdfBitmap;
 
+/// The order of the red, green, and blue subpixels of each pixel on a display, used to
+/// control subpixel anti-aliasing of text using the [PdfRenderConfig::set_subpixel_order()]
+/// function.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfSubpixelOrder {
+    /// Subpixels are arranged horizontally in red, green, blue order. This is the layout
+    /// of most LCD displays.
+    Rgb,
+
+    /// Subpixels are arranged horizontally in blue, green, red order.
+    Bgr,
+
+    /// The display has no addressable subpixels, as is the case for e-ink displays, projectors,
+    /// and most OLED displays, or the rendered bitmap will be scaled, rotated, or printed.
+    None,
+}
+
+/// A callback registered using the [PdfRenderConfig::set_progress_callback()] function.
+#[derive(Clone)]
+pub(crate) struct PdfRenderProgressCallback(Arc<dyn Fn(f32) -> ControlFlow<()> + Send + Sync>);
+
+impl PdfRenderProgressCallback {
+    /// Reports the given estimated completion, from `0.0` to `1.0`, to this callback.
+    #[inline]
+    pub(crate) fn report(&self, progress: f32) -> ControlFlow<()> {
+        (self.0)(progress.clamp(0.0, 1.0))
+    }
+}
+
+impl Debug for PdfRenderProgressCallback {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str("PdfRenderProgressCallback")
+    }
+}
+
 // TODO: AJRC - 29/7/22 - remove deprecated PdfBitmapConfig struct in 0.9.0 as part of tracking issue
 // https://github.com/ajrcarey/pdfium-render/issues/36
 #[deprecated(
@@ -69,6 +111,7 @@ impl PdfBitmapConfig {
 /// automatically disables rendering of form data. If you must render form data while simultaneously
 /// applying transformations, consider using the [PdfPage::flatten()] function to flatten the
 /// form elements and form data into the containing page.
+#[derive(Debug, Clone)]
 pub struct PdfRenderConfig {
     target_width: Option<Pixels>,
     target_height: Option<Pixels>,
@@ -76,6 +119,8 @@ pub struct PdfRenderConfig {
     scale_height_factor: Option<f32>,
     maximum_width: Option<Pixels>,
     maximum_height: Option<Pixels>,
+    maximum_bitmap_bytes: Option<usize>,
+    do_downscale_to_maximum_bitmap_bytes: bool,
     portrait_rotation: PdfPageRenderRotation,
     portrait_rotation_do_rotate_constraints: bool,
     landscape_rotation: PdfPageRenderRotation,
@@ -83,24 +128,33 @@ pub struct PdfRenderConfig {
     format: PdfBitmapFormat,
     do_clear_bitmap_before_rendering: bool,
     clear_color: PdfColor,
+    do_render_transparent_background: bool,
+    progress_callback: Option<PdfRenderProgressCallback>,
+    do_collect_render_stats: bool,
+    layer_visibility: Vec<(String, bool)>,
+    monochrome_threshold: Option<u8>,
+    do_dither_monochrome: bool,
+    minimum_smooth_text_size: Option<f32>,
     do_render_form_data: bool,
     form_field_highlight: Option<Vec<(PdfFormFieldType, PdfColor)>>,
     transformation_matrix: PdfMatrix,
     clip_rect: Option<(Pixels, Pixels, Pixels, Pixels)>,
+    do_render_annotation_layer_only: bool,
 
     // The fields below set Pdfium's page rendering flags. Coverage for the
     // FPDF_DEBUG_INFO and FPDF_NO_CATCH flags is omitted since they are obsolete.
     do_set_flag_render_annotations: bool,     // Sets FPDF_ANNOT
     do_set_flag_use_lcd_text_rendering: bool, // Sets FPDF_LCD_TEXT
-    do_set_flag_no_native_text: bool,         // Sets FPDF_NO_NATIVETEXT
-    do_set_flag_grayscale: bool,              // Sets FPDF_GRAYSCALE
+    do_reverse_subpixel_order: bool,
+    do_set_flag_no_native_text: bool, // Sets FPDF_NO_NATIVETEXT
+    do_set_flag_grayscale: bool,      // Sets FPDF_GRAYSCALE
     do_set_flag_render_limited_image_cache: bool, // Sets FPDF_RENDER_LIMITEDIMAGECACHE
     do_set_flag_render_force_half_tone: bool, // Sets FPDF_RENDER_FORCEHALFTONE
-    do_set_flag_render_for_printing: bool,    // Sets FPDF_PRINTING
-    do_set_flag_render_no_smooth_text: bool,  // Sets FPDF_RENDER_NO_SMOOTHTEXT
+    do_set_flag_render_for_printing: bool, // Sets FPDF_PRINTING
+    do_set_flag_render_no_smooth_text: bool, // Sets FPDF_RENDER_NO_SMOOTHTEXT
     do_set_flag_render_no_smooth_image: bool, // Sets FPDF_RENDER_NO_SMOOTHIMAGE
-    do_set_flag_render_no_smooth_path: bool,  // Sets FPDF_RENDER_NO_SMOOTHPATH
-    do_set_flag_reverse_byte_order: bool,     // Sets FPDF_REVERSE_BYTE_ORDER
+    do_set_flag_render_no_smooth_path: bool, // Sets FPDF_RENDER_NO_SMOOTHPATH
+    do_set_flag_reverse_byte_order: bool, // Sets FPDF_REVERSE_BYTE_ORDER
     do_set_flag_convert_fill_to_stroke: bool, // Sets FPDF_CONVERT_FILL_TO_STROKE
 }
 
@@ -114,6 +168,8 @@ impl PdfRenderConfig {
             scale_height_factor: None,
             maximum_width: None,
             maximum_height: None,
+            maximum_bitmap_bytes: None,
+            do_downscale_to_maximum_bitmap_bytes: true,
             portrait_rotation: PdfPageRenderRotation::None,
             portrait_rotation_do_rotate_constraints: false,
             landscape_rotation: PdfPageRenderRotation::None,
@@ -121,12 +177,21 @@ impl PdfRenderConfig {
             format: PdfBitmapFormat::default(),
             do_clear_bitmap_before_rendering: true,
             clear_color: PdfColor::WHITE,
+            do_render_transparent_background: false,
+            progress_callback: None,
+            do_collect_render_stats: false,
+            layer_visibility: Vec::new(),
+            monochrome_threshold: None,
+            do_dither_monochrome: false,
+            minimum_smooth_text_size: None,
             do_render_form_data: true,
             form_field_highlight: None,
             transformation_matrix: PdfMatrix::IDENTITY,
             clip_rect: None,
+            do_render_annotation_layer_only: false,
             do_set_flag_render_annotations: true,
             do_set_flag_use_lcd_text_rendering: false,
+            do_reverse_subpixel_order: false,
             do_set_flag_no_native_text: false,
             do_set_flag_grayscale: false,
             do_set_flag_render_limited_image_cache: false,
@@ -168,6 +233,34 @@ impl PdfRenderConfig {
             .render_form_data(false)
     }
 
+    /// Creates a new [PdfRenderConfig] object with settings suitable for one-bit output
+    /// devices such as fax machines, thermal receipt printers, and label printers.
+    ///
+    /// * The source [PdfPage] will be rendered at the given resolution, in dots per inch.
+    /// * Pdfium will render in grayscale, at print quality, using halftone image stretching.
+    /// * Text and vector paths will not be anti-aliased.
+    /// * Annotations and user-filled form field data will be rendered.
+    /// * Every pixel in the rendered bitmap will be set to either pure black or pure white
+    ///   using Floyd-Steinberg dithering around a threshold of 128, so that images and shading
+    ///   remain legible. See [PdfRenderConfig::set_monochrome_threshold()] and
+    ///   [PdfRenderConfig::set_monochrome_dithering()].
+    ///
+    /// These settings can be selectively overridden by later function calls.
+    #[inline]
+    pub fn for_monochrome_printer(dpi: f32) -> Self {
+        PdfRenderConfig::new()
+            .scale_page_by_factor(dpi / 72.0)
+            .use_grayscale_rendering(true)
+            .use_print_quality(true)
+            .force_half_tone(true)
+            .set_text_smoothing(false)
+            .set_path_smoothing(false)
+            .render_annotations(true)
+            .render_form_data(true)
+            .set_monochrome_threshold(Some(128))
+            .set_monochrome_dithering(true)
+    }
+
     /// Converts the width and height of a [PdfPage] from points to pixels, scaling each
     /// dimension to the given target pixel sizes. The aspect ratio of the source page
     /// will not be maintained.
@@ -267,6 +360,37 @@ impl PdfRenderConfig {
         self
     }
 
+    /// Specifies that the memory required by the bitmap into which the [PdfPage] is rendered
+    /// will not exceed the given number of bytes.
+    ///
+    /// By default, if the pixel dimensions calculated from the other settings in this
+    /// [PdfRenderConfig] would require a larger bitmap, the scale of the rendered page is reduced
+    /// until the bitmap fits within the budget. The aspect ratio of the rendered page is maintained.
+    /// Use the [PdfRenderConfig::downscale_to_maximum_bitmap_bytes()] function to return
+    /// a [PdfiumError::RenderBudgetExceeded] error instead.
+    ///
+    /// The budget applies only when rendering allocates a new bitmap, such as when calling
+    /// [PdfPage::render_with_config()]; it is ignored when rendering into an existing bitmap.
+    #[inline]
+    pub fn set_maximum_bitmap_bytes(mut self, bytes: usize) -> Self {
+        self.maximum_bitmap_bytes = Some(bytes);
+
+        self
+    }
+
+    /// Controls whether the scale of the rendered [PdfPage] should be automatically reduced
+    /// if the bitmap it would be rendered into would exceed the memory budget set by
+    /// the [PdfRenderConfig::set_maximum_bitmap_bytes()] function. If set to `false`, rendering
+    /// will instead fail with a [PdfiumError::RenderBudgetExceeded] error.
+    ///
+    /// By default, the scale is reduced automatically.
+    #[inline]
+    pub fn downscale_to_maximum_bitmap_bytes(mut self, do_downscale: bool) -> Self {
+        self.do_downscale_to_maximum_bitmap_bytes = do_downscale;
+
+        self
+    }
+
     /// Applies the given clockwise rotation setting to the [PdfPage] during rendering, irrespective
     /// of its orientation. If the given flag is set to `true` then any maximum
     /// constraint on the final pixel width set by a call to [PdfRenderConfig::set_maximum_width()]
@@ -379,6 +503,126 @@ impl PdfRenderConfig {
         self
     }
 
+    /// Controls whether the [PdfPage] should be rendered onto a fully transparent background,
+    /// so that every pixel not covered by page content has an alpha value of zero.
+    /// The default is `false`.
+    ///
+    /// The alpha handling of Pdfium's `FPDFBitmap_FillRect()` function, normally used to clear
+    /// the destination bitmap, differs between Pdfium versions; some versions blend a
+    /// semi-transparent clear color into the existing pixels rather than replacing them.
+    /// When this setting is `true`, the destination bitmap's buffer is instead zeroed directly,
+    /// irrespective of the [PdfRenderConfig::clear_before_rendering()] and
+    /// [PdfRenderConfig::set_clear_color()] settings.
+    ///
+    /// Setting this to `true` also forces the [PdfBitmapFormat::BGRA] pixel format, since
+    /// transparency requires an alpha channel. Use the [PdfBitmap::composite_over()] function
+    /// to place the rendered page over a solid background color afterwards.
+    #[inline]
+    pub fn render_transparent_background(mut self, do_render: bool) -> Self {
+        self.do_render_transparent_background = do_render;
+
+        if do_render {
+            self.format = PdfBitmapFormat::BGRA;
+        }
+
+        self
+    }
+
+    /// Controls which optional content groups, or layers, are rendered. Each entry in the given
+    /// map pairs the name of a layer with its visibility; layers mapped to `false` are hidden,
+    /// and layers mapped to `true` are shown, even if the document's default layer configuration
+    /// hides them. Layers not in the map are rendered according to the document's default layer
+    /// configuration. Use the `PdfDocument::layers()` function to list the layers used in a
+    /// document.
+    ///
+    /// Pdfium does not allow the visibility of layers to be changed directly, so the page is
+    /// instead rendered from a copy of its document whose default layer configuration has been
+    /// changed, leaving the document itself unchanged. Pdfium then applies the visibility of
+    /// each layer to all content in that layer, including content nested inside form XObjects.
+    /// Since the copy is made by saving the document, any changes to the page's objects that
+    /// have not yet been written to the page's content stream are not rendered, and interactive
+    /// form fields are rendered from their appearance streams.
+    #[inline]
+    pub fn set_layer_visibility(mut self, visibility: HashMap<String, bool>) -> Self {
+        let mut visibility = visibility.into_iter().collect::<Vec<_>>();
+
+        // Sort the layers so that equivalent configurations are hashed identically.
+
+        visibility.sort();
+
+        self.layer_visibility = visibility;
+
+        self
+    }
+
+    /// Registers a callback that is invoked repeatedly while the [PdfPage] is being rendered,
+    /// with an estimate of the fraction of rendering completed, from `0.0` to `1.0`. This is useful
+    /// for showing a progress bar while rendering very large or complex pages. Returning
+    /// `ControlFlow::Break(())` from the callback cancels rendering; the rendering function
+    /// then returns a [PdfiumError::RenderCancelled] error, and the contents of the destination
+    /// bitmap are undefined.
+    ///
+    /// Progress is reported from Pdfium's progressive rendering loop. Pdfium does not itself
+    /// measure progress, so the estimate is based on the number of page objects rendered so far
+    /// and may not advance evenly. The callback is always invoked with `1.0` once rendering
+    /// completes successfully.
+    ///
+    /// Pdfium's progressive rendering loop does not support custom transformation matrices.
+    /// If a transformation is applied, or if form data rendering is disabled, the callback is
+    /// invoked only once, after rendering has completed, and rendering cannot be cancelled.
+    /// Rendering cannot be cancelled when compiling to WASM, since Pdfium cannot call back
+    /// into Rust code to pause rendering.
+    #[inline]
+    pub fn set_progress_callback<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(f32) -> ControlFlow<()> + Send + Sync + 'static,
+    {
+        self.progress_callback = Some(PdfRenderProgressCallback(Arc::new(callback)));
+
+        self
+    }
+
+    /// Controls whether a breakdown of the work done while rendering each page should be
+    /// collected and attached to the rendered bitmap, where it can be retrieved using the
+    /// [PdfBitmap::render_stats()] function. The default is `false`.
+    ///
+    /// Collecting render statistics adds a small timing overhead to each render. A bitmap
+    /// returned from a `PdfRenderCache` without re-rendering the page retains the render
+    /// statistics of its original rendering.
+    #[inline]
+    pub fn collect_render_stats(mut self, do_collect: bool) -> Self {
+        self.do_collect_render_stats = do_collect;
+
+        self
+    }
+
+    /// Converts every pixel in the rendered bitmap to either pure black or pure white after
+    /// rendering, by comparing the luminance of each pixel against the given threshold.
+    /// Pixels darker than the threshold become black; all other pixels become white.
+    /// Pass `None` to disable the conversion. The default is `None`.
+    ///
+    /// See [PdfBitmap::apply_threshold()] for more information.
+    #[inline]
+    pub fn set_monochrome_threshold(mut self, threshold: Option<u8>) -> Self {
+        self.monochrome_threshold = threshold;
+
+        self
+    }
+
+    /// Controls whether the conversion to pure black and pure white enabled by
+    /// [PdfRenderConfig::set_monochrome_threshold()] should use Floyd-Steinberg error diffusion,
+    /// reproducing areas of gray as patterns of black and white pixels, rather than comparing
+    /// each pixel against the threshold independently. Has no effect unless a monochrome
+    /// threshold is set. The default is `false`.
+    ///
+    /// See [PdfBitmap::apply_dithering()] for more information.
+    #[inline]
+    pub fn set_monochrome_dithering(mut self, do_dither: bool) -> Self {
+        self.do_dither_monochrome = do_dither;
+
+        self
+    }
+
     /// Controls whether form data widgets and user-supplied form data should be included
     /// during rendering of the [PdfPage]. The default is `true`.
     ///
@@ -401,13 +645,93 @@ impl PdfRenderConfig {
         self
     }
 
+    /// Controls whether only the annotation and form field layer of the [PdfPage] should be
+    /// rendered, leaving every other pixel in the destination bitmap fully transparent.
+    /// The default is `false`.
+    ///
+    /// This allows the static page content to be rendered and cached once (by rendering with
+    /// `PdfRenderConfig::render_annotations(false)` and `PdfRenderConfig::render_form_data(false)`),
+    /// with the frequently-changing annotation layer re-rendered separately and composited
+    /// on top as necessary.
+    ///
+    /// Pdfium cannot render annotations without also rendering the page content beneath them,
+    /// so the annotations are rendered from a temporary copy of the page from which all page
+    /// objects have been removed. Semi-transparent annotations therefore remain
+    /// semi-transparent in the resulting layer, and can be composited over the static
+    /// page content.
+    ///
+    /// Setting this to `true` also enables rendering of annotations and forces the
+    /// [PdfBitmapFormat::BGRA] pixel format, since the layer requires an alpha channel.
+    #[inline]
+    pub fn render_annotation_layer_only(mut self, do_render: bool) -> Self {
+        self.do_render_annotation_layer_only = do_render;
+
+        if do_render {
+            self.do_set_flag_render_annotations = true;
+            self.format = PdfBitmapFormat::BGRA;
+        }
+
+        self
+    }
+
     /// Controls whether text rendering should be optimized for LCD display.
     /// The default is `false`.
     /// Has no effect if anti-aliasing of text has been disabled by a call to
     /// `PdfRenderConfig::set_text_smoothing(false)`.
+    ///
+    /// Sets Pdfium's `FPDF_LCD_TEXT` render flag. Text is anti-aliased using subpixel
+    /// rendering, giving sharper glyph edges on LCD displays at the cost of colored
+    /// fringes around glyphs when the rendered bitmap is scaled, printed, or viewed on a
+    /// display with a different subpixel layout.
     #[inline]
     pub fn use_lcd_text_rendering(mut self, do_set_flag: bool) -> Self {
         self.do_set_flag_use_lcd_text_rendering = do_set_flag;
+        self.do_reverse_subpixel_order = false;
+
+        self
+    }
+
+    /// Sets the subpixel layout of the display the rendered bitmap will be shown on, enabling
+    /// or disabling subpixel anti-aliasing of text accordingly. The default is
+    /// [PdfSubpixelOrder::None], which anti-aliases text using shades of gray only.
+    ///
+    /// Pdfium can only render subpixel anti-aliased text for displays with [PdfSubpixelOrder::Rgb]
+    /// subpixels. For displays with [PdfSubpixelOrder::Bgr] subpixels, the page is rendered a
+    /// second time with text anti-aliased using shades of gray only, and the red and blue
+    /// subpixel contributions of the first rendering, relative to the second, are swapped.
+    /// The colors of the page content itself are unchanged. Rendering for
+    /// [PdfSubpixelOrder::Bgr] subpixels therefore takes roughly twice as long. When rendering
+    /// to a Skia canvas, to a printer, or of a single form field widget, where the rendered
+    /// output cannot be processed in this way, text is anti-aliased using shades of gray only
+    /// for [PdfSubpixelOrder::Bgr] subpixels.
+    ///
+    /// Has no effect if anti-aliasing of text has been disabled by a call to
+    /// `PdfRenderConfig::set_text_smoothing(false)`.
+    ///
+    /// This is a more descriptive alternative to the
+    /// [PdfRenderConfig::use_lcd_text_rendering()] function; whichever is called last
+    /// takes precedence.
+    #[inline]
+    pub fn set_subpixel_order(mut self, order: PdfSubpixelOrder) -> Self {
+        self.do_set_flag_use_lcd_text_rendering = order != PdfSubpixelOrder::None;
+        self.do_reverse_subpixel_order = order == PdfSubpixelOrder::Bgr;
+
+        self
+    }
+
+    /// Disables anti-aliasing of text on any page whose text would mostly be rendered smaller
+    /// than the given size, in pixels. Pass `None` to anti-alias text irrespective of its size.
+    /// The default is `None`.
+    ///
+    /// Small anti-aliased text can look blurred, particularly on low-resolution and e-ink
+    /// displays. Pdfium can only enable or disable anti-aliasing of text for an entire page,
+    /// so the decision is made for each page by comparing the median rendered font size of
+    /// the top-level text objects on the page against the given size. Has no effect if
+    /// anti-aliasing of text has been disabled by a call to
+    /// `PdfRenderConfig::set_text_smoothing(false)`.
+    #[inline]
+    pub fn set_minimum_smooth_text_size(mut self, pixels: Option<f32>) -> Self {
+        self.minimum_smooth_text_size = pixels;
 
         self
     }
@@ -416,6 +740,9 @@ impl PdfRenderConfig {
     /// The alternative is for Pdfium to render all text internally, which may give more
     /// consistent rendering results across platforms but may also be slower.
     /// The default is `false`.
+    ///
+    /// Sets Pdfium's `FPDF_NO_NATIVETEXT` render flag. Glyph shapes, weights, and hinting
+    /// may differ slightly from text drawn by the platform's own text rasterizer.
     #[inline]
     pub fn disable_native_text_rendering(mut self, do_set_flag: bool) -> Self {
         self.do_set_flag_no_native_text = do_set_flag;
@@ -435,6 +762,9 @@ impl PdfRenderConfig {
     /// Controls whether Pdfium should limit its image cache size during rendering.
     /// A smaller cache size may result in lower memory usage at the cost of slower rendering.
     /// The default is `false`.
+    ///
+    /// Sets Pdfium's `FPDF_RENDER_LIMITEDIMAGECACHE` render flag. This setting has no
+    /// visual effect on the rendered output.
     #[inline]
     pub fn limit_render_image_cache_size(mut self, do_set_flag: bool) -> Self {
         self.do_set_flag_render_limited_image_cache = do_set_flag;
@@ -445,6 +775,9 @@ impl PdfRenderConfig {
     /// Controls whether Pdfium should always use halftone for image stretching.
     /// Halftone image stretching is often higher quality than linear image stretching
     /// but is much slower. The default is `false`.
+    ///
+    /// Sets Pdfium's `FPDF_RENDER_FORCEHALFTONE` render flag. Downscaled images show fewer
+    /// aliasing artifacts, such as moiré patterns and jagged edges.
     #[inline]
     pub fn force_half_tone(mut self, do_set_flag: bool) -> Self {
         self.do_set_flag_render_force_half_tone = do_set_flag;
@@ -468,8 +801,12 @@ impl PdfRenderConfig {
     /// Controls whether rendered text should be anti-aliased.
     /// The default is `true`.
     /// The enabling of LCD-optimized text rendering via a call to
-    /// `PdfiumBitmapConfig::use_lcd_text_rendering(true)` has no effect if this flag
+    /// `PdfRenderConfig::use_lcd_text_rendering(true)` has no effect if this flag
     /// is set to `false`.
+    ///
+    /// Setting this flag to `false` sets Pdfium's `FPDF_RENDER_NO_SMOOTHTEXT` render flag.
+    /// Glyph edges are rendered as hard, stair-stepped pixel boundaries, which can be
+    /// useful when rendering for OCR or for pixel-exact comparisons.
     #[inline]
     pub fn set_text_smoothing(mut self, do_set_flag: bool) -> Self {
         self.do_set_flag_render_no_smooth_text = !do_set_flag;
@@ -479,6 +816,10 @@ impl PdfRenderConfig {
 
     /// Controls whether rendered images should be anti-aliased.
     /// The default is `true`.
+    ///
+    /// Setting this flag to `false` sets Pdfium's `FPDF_RENDER_NO_SMOOTHIMAGE` render flag.
+    /// Scaled images are resampled using nearest-neighbour interpolation, giving a blocky
+    /// appearance when enlarged and visible aliasing when reduced.
     #[inline]
     pub fn set_image_smoothing(mut self, do_set_flag: bool) -> Self {
         self.do_set_flag_render_no_smooth_image = !do_set_flag;
@@ -488,6 +829,9 @@ impl PdfRenderConfig {
 
     /// Controls whether rendered vector paths should be anti-aliased.
     /// The default is `true`.
+    ///
+    /// Setting this flag to `false` sets Pdfium's `FPDF_RENDER_NO_SMOOTHPATH` render flag.
+    /// Lines, curves, and shape outlines are rendered with hard, stair-stepped edges.
     #[inline]
     pub fn set_path_smoothing(mut self, do_set_flag: bool) -> Self {
         self.do_set_flag_render_no_smooth_path = !do_set_flag;
@@ -502,6 +846,11 @@ impl PdfRenderConfig {
     /// There should generally be no need to change this flag unless you want to do raw
     /// image processing and specifically need the pixel data returned by the
     /// [PdfBitmap::as_raw_bytes()] function to be in BGR8 format.
+    ///
+    /// Sets Pdfium's `FPDF_REVERSE_BYTE_ORDER` render flag. Changing this flag does not change
+    /// the pixel data returned by [PdfBitmap::as_rgba_bytes()], since `pdfium-render`
+    /// takes the byte order into account when converting pixel data, but it does swap the
+    /// red and blue channels of the raw pixel data.
     #[inline]
     pub fn set_reverse_byte_order(mut self, do_set_flag: bool) -> Self {
         self.do_set_flag_reverse_byte_order = do_set_flag;
@@ -656,10 +1005,18 @@ impl PdfRenderConfig {
     /// based on the configuration of this [PdfRenderConfig].
     #[inline]
     pub(crate) fn apply_to_page(&self, page: &PdfPage) -> PdfRenderSettings {
-        let source_width = page.width();
-
-        let source_height = page.height();
+        self.apply_to_dimensions(page.width(), page.height(), Some(page))
+    }
 
+    /// Computes the pixel dimensions and rotation settings for a source area of the given
+    /// width and height based on the configuration of this [PdfRenderConfig]. The page
+    /// containing the source area, if given, is used to apply the minimum smooth text size.
+    pub(crate) fn apply_to_dimensions(
+        &self,
+        source_width: PdfPoints,
+        source_height: PdfPoints,
+        page: Option<&PdfPage>,
+    ) -> PdfRenderSettings {
         let source_orientation =
             PdfPageOrientation::from_width_and_height(source_width, source_height);
 
@@ -792,7 +1149,11 @@ impl PdfRenderConfig {
             render_flags |= FPDF_PRINTING;
         }
 
-        if self.do_set_flag_render_no_smooth_text {
+        if self.do_set_flag_render_no_smooth_text
+            || page.map_or(false, |page| {
+                self.is_text_below_minimum_smooth_size(page, width_scale.min(height_scale))
+            })
+        {
             render_flags |= FPDF_RENDER_NO_SMOOTHTEXT;
         }
 
@@ -812,9 +1173,50 @@ impl PdfRenderConfig {
             render_flags |= FPDF_CONVERT_FILL_TO_STROKE;
         }
 
-        let output_width = (source_width.value * width_scale).round() as c_int;
+        let mut output_width = (source_width.value * width_scale).round() as c_int;
+
+        let mut output_height = (source_height.value * height_scale).round() as c_int;
+
+        // Apply the constraint on maximum bitmap memory usage, if any.
+
+        let mut is_maximum_bitmap_bytes_exceeded = false;
+
+        if let Some(maximum) = self.maximum_bitmap_bytes {
+            let required = bitmap_bytes_required(output_width, output_height, self.format);
+
+            if required > maximum {
+                if self.do_downscale_to_maximum_bitmap_bytes {
+                    // Memory usage grows with the square of the scale, so reducing both
+                    // dimensions by the square root of the excess should be sufficient.
+                    // Rounding and row padding may still leave the bitmap slightly too large,
+                    // so continue shrinking it in small steps until it fits.
+
+                    let mut factor = (maximum as f32 / required as f32).sqrt();
+
+                    loop {
+                        output_width =
+                            ((source_width.value * width_scale * factor).round() as c_int).max(1);
+
+                        output_height =
+                            ((source_height.value * height_scale * factor).round() as c_int).max(1);
 
-        let output_height = (source_height.value * height_scale).round() as c_int;
+                        if bitmap_bytes_required(output_width, output_height, self.format)
+                            <= maximum
+                            || (output_width == 1 && output_height == 1)
+                        {
+                            break;
+                        }
+
+                        factor *= 0.99;
+                    }
+
+                    width_scale *= factor;
+                    height_scale *= factor;
+                } else {
+                    is_maximum_bitmap_bytes_exceeded = true;
+                }
+            }
+        }
 
         // Pages can be rendered either _with_ transformation matrices and clipping
         // but _without_ form data, or _with_ form data but _without_ transformation matrices
@@ -854,7 +1256,16 @@ impl PdfRenderConfig {
             rotate: target_rotation.as_pdfium(),
             do_clear_bitmap_before_rendering: self.do_clear_bitmap_before_rendering,
             clear_color: self.clear_color.as_pdfium_color(),
+            do_clear_to_transparent: self.do_render_transparent_background,
+            progress_callback: self.progress_callback.clone(),
+            do_collect_render_stats: self.do_collect_render_stats,
+            layer_visibility: self.layer_visibility.clone(),
+            monochrome_threshold: self.monochrome_threshold,
+            do_dither_monochrome: self.do_dither_monochrome,
+            do_reverse_subpixel_order: self.do_set_flag_use_lcd_text_rendering
+                && self.do_reverse_subpixel_order,
             do_render_form_data: self.do_render_form_data,
+            do_render_annotation_layer_only: self.do_render_annotation_layer_only,
             form_field_highlight: if !self.do_render_form_data
                 || self.form_field_highlight.is_none()
             {
@@ -894,7 +1305,38 @@ impl PdfRenderConfig {
             },
             render_flags: render_flags as c_int,
             is_reversed_byte_order_flag_set: self.do_set_flag_reverse_byte_order,
+            is_maximum_bitmap_bytes_exceeded,
+        }
+    }
+}
+
+impl PdfRenderConfig {
+    /// Returns `true` if a minimum size for anti-aliased text has been set, and the median
+    /// font size of the top-level text objects on the given page would be rendered smaller
+    /// than that size at the given scale.
+    fn is_text_below_minimum_smooth_size(&self, page: &PdfPage, scale: f32) -> bool {
+        let minimum = match self.minimum_smooth_text_size {
+            Some(minimum) => minimum,
+            None => return false,
+        };
+
+        let mut sizes = page
+            .objects()
+            .iter()
+            .filter_map(|object| {
+                object
+                    .as_text_object()
+                    .map(|text| text.scaled_font_size().value.abs())
+            })
+            .collect::<Vec<_>>();
+
+        if sizes.is_empty() {
+            return false;
         }
+
+        sizes.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+        sizes[sizes.len() / 2] * scale < minimum
     }
 }
 
@@ -915,10 +1357,123 @@ pub(crate) struct PdfRenderSettings {
     pub(crate) rotate: c_int,
     pub(crate) do_clear_bitmap_before_rendering: bool,
     pub(crate) clear_color: FPDF_DWORD,
+    pub(crate) do_clear_to_transparent: bool,
+    pub(crate) progress_callback: Option<PdfRenderProgressCallback>,
+    pub(crate) do_collect_render_stats: bool,
+    pub(crate) layer_visibility: Vec<(String, bool)>,
+    pub(crate) monochrome_threshold: Option<u8>,
+    pub(crate) do_dither_monochrome: bool,
+    pub(crate) do_reverse_subpixel_order: bool,
     pub(crate) do_render_form_data: bool,
+    pub(crate) do_render_annotation_layer_only: bool,
     pub(crate) form_field_highlight: Option<Vec<(c_int, (FPDF_DWORD, u8))>>,
     pub(crate) matrix: FS_MATRIX,
     pub(crate) clipping: FS_RECTF,
     pub(crate) render_flags: c_int,
     pub(crate) is_reversed_byte_order_flag_set: bool,
+    pub(crate) is_maximum_bitmap_bytes_exceeded: bool,
+}
+
+impl PdfRenderSettings {
+    /// Returns these settings with subpixel anti-aliasing of text replaced by anti-aliasing
+    /// in shades of gray if the subpixel order of the rendering must be reversed, for use
+    /// when rendering to a target whose pixels cannot be post-processed.
+    pub(crate) fn without_subpixel_reversal(mut self) -> Self {
+        if self.do_reverse_subpixel_order {
+            self.render_flags &= !(FPDF_LCD_TEXT as c_int);
+            self.do_reverse_subpixel_order = false;
+        }
+
+        self
+    }
+
+    /// Returns a hash of every setting that affects the rendered output. Two sets of
+    /// settings with the same key render a page identically.
+    pub(crate) fn cache_key(&self) -> u64 {
+        let mut hasher = Fnv1a64::new();
+
+        for value in [
+            self.width,
+            self.height,
+            self.format,
+            self.rotate,
+            self.render_flags,
+        ] {
+            hasher.write(&value.to_le_bytes());
+        }
+
+        hasher.write(&[
+            self.do_clear_bitmap_before_rendering as u8,
+            self.do_clear_to_transparent as u8,
+            self.do_render_form_data as u8,
+            self.do_render_annotation_layer_only as u8,
+            self.is_reversed_byte_order_flag_set as u8,
+        ]);
+
+        hasher.write(&self.clear_color.to_le_bytes());
+
+        for value in [
+            self.matrix.a,
+            self.matrix.b,
+            self.matrix.c,
+            self.matrix.d,
+            self.matrix.e,
+            self.matrix.f,
+            self.clipping.left,
+            self.clipping.top,
+            self.clipping.right,
+            self.clipping.bottom,
+        ] {
+            hasher.write(&value.to_bits().to_le_bytes());
+        }
+
+        for (field_type, (color, alpha)) in self.form_field_highlight.iter().flatten() {
+            hasher.write(&field_type.to_le_bytes());
+            hasher.write(&color.to_le_bytes());
+            hasher.write(&[*alpha]);
+        }
+
+        for (layer, is_visible) in self.layer_visibility.iter() {
+            hasher.write(layer.as_bytes());
+            hasher.write(&[0, *is_visible as u8]);
+        }
+
+        hasher.write(&[self.do_reverse_subpixel_order as u8]);
+
+        match self.monochrome_threshold {
+            Some(threshold) => hasher.write(&[1, threshold, self.do_dither_monochrome as u8]),
+            None => hasher.write(&[0]),
+        }
+
+        hasher.finish()
+    }
+}
+
+/// Returns the number of bytes Pdfium will allocate for a bitmap of the given pixel dimensions
+/// and format. Pdfium pads each row of pixel data to a multiple of four bytes.
+pub(crate) fn bitmap_bytes_required(width: c_int, height: c_int, format: PdfBitmapFormat) -> usize {
+    let bytes_per_pixel = match format {
+        PdfBitmapFormat::Gray => 1,
+        PdfBitmapFormat::BGR => 3,
+        #[allow(deprecated)]
+        PdfBitmapFormat::BRGx | PdfBitmapFormat::BGRx | PdfBitmapFormat::BGRA => 4,
+    };
+
+    let stride = (width.max(0) as usize * bytes_per_pixel + 3) / 4 * 4;
+
+    stride * height.max(0) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::bitmap::PdfBitmapFormat;
+    use crate::pdf::document::page::render_config::bitmap_bytes_required;
+
+    #[test]
+    fn test_bitmap_bytes_required() {
+        assert_eq!(bitmap_bytes_required(10, 10, PdfBitmapFormat::BGRA), 400);
+        assert_eq!(bitmap_bytes_required(10, 10, PdfBitmapFormat::BGR), 320);
+        assert_eq!(bitmap_bytes_required(10, 10, PdfBitmapFormat::Gray), 120);
+        assert_eq!(bitmap_bytes_required(0, 10, PdfBitmapFormat::BGRA), 0);
+    }
 }
@@ -0,0 +1,87 @@
+//! Defines the [PdfRenderStats] struct, a breakdown of the work done while rendering
+//! a single `PdfPage`.
+
+use std::time::Duration;
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::time::Instant;
+
+/// A breakdown of the work done while rendering a single `PdfPage`, collected when
+/// rendering with `PdfRenderConfig::collect_render_stats(true)` and retrieved from the
+/// rendered bitmap using the `PdfBitmap::render_stats()` function.
+///
+/// Timings are measured using the system's monotonic clock. The clock is not available
+/// when compiling to WASM, so all durations are reported as zero on that platform.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PdfRenderStats {
+    pub(crate) pdfium_duration: Duration,
+    pub(crate) conversion_duration: Duration,
+    pub(crate) bytes_allocated: usize,
+    pub(crate) progressive_segments: usize,
+}
+
+impl PdfRenderStats {
+    /// Returns the time spent inside Pdfium's rendering functions, including the
+    /// rendering of any form data and any time spent in a progress callback.
+    #[inline]
+    pub fn pdfium_duration(&self) -> Duration {
+        self.pdfium_duration
+    }
+
+    /// Returns the time spent outside Pdfium's rendering functions, post-processing the
+    /// rendered pixel data; for instance, hiding and restoring optional content layers,
+    /// extracting the annotation layer, or applying a monochrome threshold.
+    #[inline]
+    pub fn conversion_duration(&self) -> Duration {
+        self.conversion_duration
+    }
+
+    /// Returns the total time spent rendering the page.
+    #[inline]
+    pub fn total_duration(&self) -> Duration {
+        self.pdfium_duration + self.conversion_duration
+    }
+
+    /// Returns the number of bytes allocated for bitmap buffers while rendering the page,
+    /// including the buffer of the returned bitmap if it was newly created and any scratch
+    /// bitmaps used during rendering. Allocations made internally by Pdfium are not included.
+    #[inline]
+    pub fn bytes_allocated(&self) -> usize {
+        self.bytes_allocated
+    }
+
+    /// Returns the number of segments in which Pdfium rendered the page. Pages rendered with
+    /// a progress callback are rendered progressively, one batch of page objects per segment;
+    /// otherwise, each rendering pass is a single segment.
+    #[inline]
+    pub fn progressive_segments(&self) -> usize {
+        self.progressive_segments
+    }
+}
+
+/// Measures elapsed time on platforms with a monotonic clock.
+pub(crate) struct PdfRenderTimer {
+    #[cfg(not(target_arch = "wasm32"))]
+    start: Instant,
+}
+
+impl PdfRenderTimer {
+    #[inline]
+    pub(crate) fn start() -> Self {
+        PdfRenderTimer {
+            #[cfg(not(target_arch = "wasm32"))]
+            start: Instant::now(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn elapsed(&self) -> Duration {
+        #[cfg(not(target_arch = "wasm32"))]
+        let result = self.start.elapsed();
+
+        #[cfg(target_arch = "wasm32")]
+        let result = Duration::from_secs(0);
+
+        result
+    }
+}
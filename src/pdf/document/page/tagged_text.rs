@@ -0,0 +1,473 @@
+//! Defines the [PdfTaggedTextNode] struct, a single element in the tree of structured text
+//! returned by the `PdfPage::tagged_text()` function.
+
+use crate::bindgen::{FPDF_PAGE, FPDF_STRUCTELEMENT, FPDF_TEXTPAGE};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::text::reading_order::{
+    chars_with_marked_content_ids, normalize_whitespace,
+};
+use crate::utils::mem::create_byte_buffer;
+use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use std::collections::HashMap;
+use std::os::raw::{c_int, c_ulong, c_void};
+
+/// The structure types defined by the PDF specification for inline-level structure elements.
+/// Text in these elements flows within the text of their parent element.
+const INLINE_ELEMENT_TYPES: [&str; 12] = [
+    "Span",
+    "Quote",
+    "Note",
+    "Reference",
+    "BibEntry",
+    "Code",
+    "Link",
+    "Annot",
+    "Ruby",
+    "Warichu",
+    "Em",
+    "Strong",
+];
+
+/// A single structure element in the structure tree of a tagged `PdfPage`, together with
+/// the text it marks, as returned by the `PdfPage::tagged_text()` function.
+///
+/// Each node carries the structure type of its element, such as `H1` for a top-level
+/// heading, `P` for a paragraph, or `TD` for a table cell, and the child elements nested
+/// within it, in the logical order defined by the document's author.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTaggedTextNode {
+    element_type: String,
+    title: Option<String>,
+    alt_text: Option<String>,
+    actual_text: Option<String>,
+    marked_content_ids: Vec<c_int>,
+    text: String,
+    children: Vec<PdfTaggedTextNode>,
+    contents: Vec<PdfTaggedTextContent>,
+}
+
+/// A single item in the content of a [PdfTaggedTextNode], in logical order.
+#[derive(Debug, Clone, PartialEq)]
+enum PdfTaggedTextContent {
+    /// Page content with the given marked content ID, and the text it contains.
+    MarkedContent(c_int, String),
+
+    /// The child node at the given index.
+    Child(usize),
+}
+
+impl PdfTaggedTextNode {
+    /// Returns the nodes for the top-level elements in the structure tree of the given page,
+    /// with the text of each node taken from the characters in the given text page.
+    ///
+    /// Returns an empty list if the page has no structure tree.
+    pub(crate) fn from_pdfium(
+        page: FPDF_PAGE,
+        text_page: FPDF_TEXTPAGE,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Vec<Self> {
+        let tree = bindings.FPDF_StructTree_GetForPage(page);
+
+        if tree.is_null() {
+            return Vec::new();
+        }
+
+        let mut nodes = (0..bindings.FPDF_StructTree_CountChildren(tree))
+            .map(|index| bindings.FPDF_StructTree_GetChildAtIndex(tree, index))
+            .filter(|element| !element.is_null())
+            .map(|element| Self::from_element(element, bindings))
+            .collect::<Vec<_>>();
+
+        bindings.FPDF_StructTree_Close(tree);
+
+        let text_by_id =
+            text_by_marked_content_id(&chars_with_marked_content_ids(text_page, bindings));
+
+        for node in nodes.iter_mut() {
+            node.assign_text(&text_by_id);
+        }
+
+        nodes
+    }
+
+    /// Creates a new [PdfTaggedTextNode] for the given structure element and all its
+    /// descendants. Text is assigned separately by the [PdfTaggedTextNode::assign_text()]
+    /// function.
+    fn from_element(element: FPDF_STRUCTELEMENT, bindings: &dyn PdfiumLibraryBindings) -> Self {
+        let marked_content_ids = (0..bindings.FPDF_StructElement_GetMarkedContentIdCount(element))
+            .map(|index| bindings.FPDF_StructElement_GetMarkedContentIdAtIndex(element, index))
+            .filter(|id| *id >= 0)
+            .collect::<Vec<_>>();
+
+        let mut children = Vec::new();
+
+        let mut contents = Vec::new();
+
+        for index in 0..bindings.FPDF_StructElement_CountChildren(element) {
+            let child = bindings.FPDF_StructElement_GetChildAtIndex(element, index);
+
+            if !child.is_null() {
+                contents.push(PdfTaggedTextContent::Child(children.len()));
+                children.push(Self::from_element(child, bindings));
+            } else if let Some(id) = child_marked_content_id(element, index, bindings) {
+                contents.push(PdfTaggedTextContent::MarkedContent(id, String::new()));
+            }
+        }
+
+        // Marked content whose position among the element's children could not be
+        // determined is placed before the element's children.
+
+        for id in marked_content_ids.iter().rev() {
+            if !contents.iter().any(
+                |content| matches!(content, PdfTaggedTextContent::MarkedContent(other, _) if other == id),
+            ) {
+                contents.insert(0, PdfTaggedTextContent::MarkedContent(*id, String::new()));
+            }
+        }
+
+        PdfTaggedTextNode {
+            element_type: struct_element_string(|buffer, length| {
+                bindings.FPDF_StructElement_GetType(element, buffer, length)
+            })
+            .unwrap_or_default(),
+            title: struct_element_string(|buffer, length| {
+                bindings.FPDF_StructElement_GetTitle(element, buffer, length)
+            }),
+            alt_text: struct_element_string(|buffer, length| {
+                bindings.FPDF_StructElement_GetAltText(element, buffer, length)
+            }),
+            actual_text: struct_element_string(|buffer, length| {
+                bindings.FPDF_StructElement_GetActualText(element, buffer, length)
+            }),
+            marked_content_ids,
+            text: String::new(),
+            children,
+            contents,
+        }
+    }
+
+    /// Sets the text of this [PdfTaggedTextNode] and all its descendants from the given text,
+    /// keyed by marked content ID.
+    fn assign_text(&mut self, text_by_id: &HashMap<c_int, String>) {
+        for content in self.contents.iter_mut() {
+            if let PdfTaggedTextContent::MarkedContent(id, text) = content {
+                *text = text_by_id
+                    .get(id)
+                    .map(|text| normalize_whitespace(text))
+                    .unwrap_or_default();
+            }
+        }
+
+        self.text = self
+            .contents
+            .iter()
+            .filter_map(|content| match content {
+                PdfTaggedTextContent::MarkedContent(_, text) if !text.is_empty() => {
+                    Some(text.as_str())
+                }
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        for child in self.children.iter_mut() {
+            child.assign_text(text_by_id);
+        }
+    }
+
+    /// Returns the structure type of this [PdfTaggedTextNode], such as `H1`, `P`, `Table`,
+    /// or `TD`. Documents may define their own structure types; these are returned as-is,
+    /// without being mapped to standard structure types.
+    #[inline]
+    pub fn element_type(&self) -> &str {
+        self.element_type.as_str()
+    }
+
+    /// Returns the title of this [PdfTaggedTextNode], if any.
+    #[inline]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Returns the alternate description of this [PdfTaggedTextNode], if any. Alternate
+    /// descriptions are typically provided for figures and formulae.
+    #[inline]
+    pub fn alt_text(&self) -> Option<&str> {
+        self.alt_text.as_deref()
+    }
+
+    /// Returns the replacement text of this [PdfTaggedTextNode], if any. Where present,
+    /// the replacement text supersedes the text marked by this node and its descendants.
+    #[inline]
+    pub fn actual_text(&self) -> Option<&str> {
+        self.actual_text.as_deref()
+    }
+
+    /// Returns the marked content IDs of the page content directly marked by this
+    /// [PdfTaggedTextNode], excluding content marked by its descendants.
+    #[inline]
+    pub fn marked_content_ids(&self) -> &[c_int] {
+        self.marked_content_ids.as_slice()
+    }
+
+    /// Returns the text directly marked by this [PdfTaggedTextNode], excluding the text of
+    /// its descendants, with runs of whitespace collapsed into single spaces.
+    #[inline]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Returns the child nodes of this [PdfTaggedTextNode], in logical order.
+    #[inline]
+    pub fn children(&self) -> &[PdfTaggedTextNode] {
+        self.children.as_slice()
+    }
+
+    /// Returns `true` if this [PdfTaggedTextNode] is a heading, i.e. if its structure type
+    /// is `H` or one of `H1` to `H6`.
+    pub fn is_heading(&self) -> bool {
+        matches!(
+            self.element_type.as_str(),
+            "H" | "H1" | "H2" | "H3" | "H4" | "H5" | "H6"
+        )
+    }
+
+    /// Returns `true` if this [PdfTaggedTextNode] is an inline-level element, such as a `Span`
+    /// or a `Link`, whose text flows within the text of its parent.
+    pub fn is_inline(&self) -> bool {
+        INLINE_ELEMENT_TYPES.contains(&self.element_type.as_str())
+    }
+
+    /// Returns the text of this [PdfTaggedTextNode] and all its descendants, in logical order.
+    ///
+    /// The text of inline-level children is separated from the surrounding text by spaces;
+    /// the text of all other children is separated by line breaks. Where a node has
+    /// replacement text, that text is used in place of the text of the node and its descendants.
+    pub fn all_text(&self) -> String {
+        if let Some(actual_text) = self.actual_text.as_ref() {
+            return actual_text.clone();
+        }
+
+        let mut result = String::new();
+
+        let mut is_previous_inline = true;
+
+        for content in self.contents.iter() {
+            let (text, is_inline) = match content {
+                PdfTaggedTextContent::MarkedContent(_, text) => (text.clone(), true),
+                PdfTaggedTextContent::Child(index) => {
+                    let child = &self.children[*index];
+
+                    (child.all_text(), child.is_inline())
+                }
+            };
+
+            if text.is_empty() {
+                continue;
+            }
+
+            if !result.is_empty() {
+                result.push(if is_inline && is_previous_inline {
+                    ' '
+                } else {
+                    '\n'
+                });
+            }
+
+            result.push_str(&text);
+
+            is_previous_inline = is_inline;
+        }
+
+        result
+    }
+}
+
+/// Returns the marked content ID of the child at the given index of the given structure
+/// element, or `None` if the child is not marked content on the current page.
+#[cfg(any(
+    feature = "pdfium_6084",
+    feature = "pdfium_6110",
+    feature = "pdfium_6124",
+    feature = "pdfium_6164",
+    feature = "pdfium_6259",
+    feature = "pdfium_6295",
+    feature = "pdfium_6337",
+    feature = "pdfium_6406",
+    feature = "pdfium_6490",
+    feature = "pdfium_6555",
+    feature = "pdfium_6569",
+    feature = "pdfium_6611",
+    feature = "pdfium_6666",
+    feature = "pdfium_future"
+))]
+#[inline]
+fn child_marked_content_id(
+    element: FPDF_STRUCTELEMENT,
+    index: c_int,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Option<c_int> {
+    let id = bindings.FPDF_StructElement_GetChildMarkedContentID(element, index);
+
+    (id >= 0).then(|| id)
+}
+
+/// Returns the marked content ID of the child at the given index of the given structure
+/// element. Pdfium versions before 6084 cannot retrieve the marked content IDs of
+/// individual children, so this always returns `None`.
+#[cfg(not(any(
+    feature = "pdfium_6084",
+    feature = "pdfium_6110",
+    feature = "pdfium_6124",
+    feature = "pdfium_6164",
+    feature = "pdfium_6259",
+    feature = "pdfium_6295",
+    feature = "pdfium_6337",
+    feature = "pdfium_6406",
+    feature = "pdfium_6490",
+    feature = "pdfium_6555",
+    feature = "pdfium_6569",
+    feature = "pdfium_6611",
+    feature = "pdfium_6666",
+    feature = "pdfium_future"
+)))]
+#[inline]
+fn child_marked_content_id(
+    _element: FPDF_STRUCTELEMENT,
+    _index: c_int,
+    _bindings: &dyn PdfiumLibraryBindings,
+) -> Option<c_int> {
+    None
+}
+
+/// Groups the given characters by marked content ID, ignoring characters that are not
+/// marked as content.
+fn text_by_marked_content_id(chars: &[(c_int, char)]) -> HashMap<c_int, String> {
+    let mut result: HashMap<c_int, String> = HashMap::new();
+
+    for (id, char) in chars.iter().filter(|(id, _)| *id >= 0) {
+        result.entry(*id).or_default().push(*char);
+    }
+
+    result
+}
+
+/// Retrieves a string property of a structure element using the given Pdfium function,
+/// returning `None` if the property is not set or is empty.
+fn struct_element_string(get: impl Fn(*mut c_void, c_ulong) -> c_ulong) -> Option<String> {
+    // Retrieving the string from Pdfium is a two-step operation. First, we call
+    // the Pdfium function with a null buffer; this will retrieve the length of
+    // the string in bytes. If the length is zero, then the property is not set.
+
+    // If the length is non-zero, then we reserve a byte buffer of the given
+    // length and call the function again with a pointer to the buffer;
+    // this will write the string into the buffer.
+
+    let buffer_length = get(std::ptr::null_mut(), 0);
+
+    if buffer_length == 0 {
+        return None;
+    }
+
+    let mut buffer = create_byte_buffer(buffer_length as usize);
+
+    let result = get(buffer.as_mut_ptr() as *mut c_void, buffer_length);
+
+    debug_assert_eq!(result, buffer_length);
+
+    get_string_from_pdfium_utf16le_bytes(buffer).filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::tagged_text::*;
+
+    fn node(
+        element_type: &str,
+        marked_content_ids: Vec<c_int>,
+        children: Vec<PdfTaggedTextNode>,
+    ) -> PdfTaggedTextNode {
+        let contents = marked_content_ids
+            .iter()
+            .map(|id| PdfTaggedTextContent::MarkedContent(*id, String::new()))
+            .chain((0..children.len()).map(PdfTaggedTextContent::Child))
+            .collect();
+
+        PdfTaggedTextNode {
+            element_type: element_type.to_string(),
+            title: None,
+            alt_text: None,
+            actual_text: None,
+            marked_content_ids,
+            text: String::new(),
+            children,
+            contents,
+        }
+    }
+
+    #[test]
+    fn test_assign_text() {
+        // A heading and a paragraph containing a link, followed by a table, defined in the
+        // content stream in a different order than their logical order.
+
+        let chars = [
+            (3, "Cell 1"),
+            (-1, "Page 1"),
+            (1, "See the  "),
+            (4, "Cell 2"),
+            (0, "Title"),
+            (2, "manual"),
+            (5, "for details."),
+        ]
+        .iter()
+        .flat_map(|(id, text)| text.chars().map(move |char| (*id, char)))
+        .collect::<Vec<_>>();
+
+        let mut document = node(
+            "Document",
+            vec![],
+            vec![
+                node("H1", vec![0], vec![]),
+                node("P", vec![1, 5], vec![node("Link", vec![2], vec![])]),
+                node(
+                    "Table",
+                    vec![],
+                    vec![node(
+                        "TR",
+                        vec![],
+                        vec![node("TD", vec![3], vec![]), node("TD", vec![4], vec![])],
+                    )],
+                ),
+            ],
+        );
+
+        // The link sits in the middle of the paragraph's text.
+
+        document.children[1].contents = vec![
+            PdfTaggedTextContent::MarkedContent(1, String::new()),
+            PdfTaggedTextContent::Child(0),
+            PdfTaggedTextContent::MarkedContent(5, String::new()),
+        ];
+
+        document.assign_text(&text_by_marked_content_id(&chars));
+
+        assert!(document.children()[0].is_heading());
+        assert_eq!(document.children()[0].text(), "Title");
+        assert_eq!(document.children()[1].text(), "See the for details.");
+        assert_eq!(
+            document.children()[1].all_text(),
+            "See the manual for details."
+        );
+        assert_eq!(
+            document.all_text(),
+            "Title\nSee the manual for details.\nCell 1\nCell 2"
+        );
+
+        document.children[1].actual_text = Some("See the handbook.".to_string());
+
+        assert_eq!(
+            document.all_text(),
+            "Title\nSee the handbook.\nCell 1\nCell 2"
+        );
+    }
+}
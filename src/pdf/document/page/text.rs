@@ -3,23 +3,46 @@
 
 pub mod char;
 pub mod chars;
+pub mod extraction;
+pub mod index_map;
+pub mod layout;
+pub(crate) mod optional_content;
+pub(crate) mod reading_order;
+pub mod region;
 pub mod search;
 pub mod segment;
 pub mod segments;
+pub mod selection;
+pub mod style_runs;
 
 use crate::bindgen::{FPDF_TEXTPAGE, FPDF_WCHAR, FPDF_WIDESTRING};
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::PdfiumError;
 use crate::pdf::document::page::annotation::PdfPageAnnotation;
 use crate::pdf::document::page::annotation::PdfPageAnnotationCommon;
+use crate::pdf::document::page::layers::hidden_layer_names;
 use crate::pdf::document::page::object::private::internal::PdfPageObjectPrivate;
 use crate::pdf::document::page::object::text::PdfPageTextObject;
 use crate::pdf::document::page::object::PdfPageObjectCommon;
 use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
 use crate::pdf::document::page::text::chars::{PdfPageTextCharIndex, PdfPageTextChars};
+use crate::pdf::document::page::text::extraction::PdfTextExtractionOptions;
+use crate::pdf::document::page::text::index_map::PdfPageTextIndexMap;
+use crate::pdf::document::page::text::layout::PdfPageTextLayout;
+use crate::pdf::document::page::text::optional_content::chars_with_optional_content_groups;
+use crate::pdf::document::page::text::reading_order::{
+    chars_with_marked_content_ids, structure_tree_marked_content_ids, text_in_geometric_order,
+    text_in_structure_order,
+};
+use crate::pdf::document::page::text::region::PdfTextRegion;
 use crate::pdf::document::page::text::search::{PdfPageTextSearch, PdfSearchOptions};
 use crate::pdf::document::page::text::segments::PdfPageTextSegments;
+use crate::pdf::document::page::text::selection::PdfTextSelection;
+use crate::pdf::document::page::text::style_runs::{
+    group_chars_into_style_runs, PdfStyledChar, PdfTextStyle, PdfTextStyleRun,
+};
 use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::save_document_to_bytes;
 use crate::pdf::points::PdfPoints;
 use crate::pdf::rect::PdfRect;
 use crate::utils::mem::{create_byte_buffer, create_sized_buffer};
@@ -41,7 +64,8 @@ use std::ptr::null_mut;
 ///
 /// In complex custom layouts, the order in which characters are defined in the document
 /// and the order in which they appear visually during rendering (and thus the order in
-/// which they are read by a user) may not necessarily match.
+/// which they are read by a user) may not necessarily match. Use the
+/// [PdfPageText::all_in_reading_order()] function to return all characters in reading order.
 ///
 /// [PdfPageText] implements both the [ToString] and the [Display] traits.
 pub struct PdfPageText<'a> {
@@ -255,6 +279,43 @@ impl<'a> PdfPageText<'a> {
         }
     }
 
+    /// Selects all characters between the given anchor and focus character indices, inclusive,
+    /// returning a [PdfTextSelection] describing the selected text and the areas of the
+    /// containing [PdfPage] it occupies. The anchor may come before or after the focus.
+    /// Indices beyond the end of this [PdfPageText] collection are clamped to its last character.
+    pub fn select(
+        &self,
+        anchor: PdfPageTextCharIndex,
+        focus: PdfPageTextCharIndex,
+    ) -> PdfTextSelection {
+        let len = self.len().max(0) as PdfPageTextCharIndex;
+
+        let chars = if len == 0 {
+            0..0
+        } else {
+            anchor.min(focus).min(len - 1)..anchor.max(focus).min(len - 1) + 1
+        };
+
+        PdfTextSelection::from_pdfium(self, chars)
+    }
+
+    /// Selects all characters between the characters nearest to the given anchor and focus
+    /// positions on the containing [PdfPage], inclusive, as when a user drags the mouse across
+    /// the page. Returns `None` if either position is further than the given tolerance
+    /// from the nearest character.
+    pub fn select_between_points(
+        &self,
+        anchor: (PdfPoints, PdfPoints),
+        focus: (PdfPoints, PdfPoints),
+        tolerance: PdfPoints,
+    ) -> Option<PdfTextSelection> {
+        let char_near = |(x, y): (PdfPoints, PdfPoints)| {
+            Self::get_char_index_near_point(self.handle, x, tolerance, y, tolerance, self.bindings)
+        };
+
+        Some(self.select(char_near(anchor)?, char_near(focus)?))
+    }
+
     /// Returns the character near to the given x and y positions on the containing [PdfPage],
     /// if any. The returned character will be no further from the given positions than the given
     /// tolerance values.
@@ -282,13 +343,166 @@ impl<'a> PdfPageText<'a> {
     /// Returns all characters that lie within the containing [PdfPage], in the order in which
     /// they are defined in the document, concatenated into a single string.
     ///
+    /// Characters in optional content groups, or layers, that are hidden by the document's
+    /// default layer configuration are omitted, so that text not visible when the page is
+    /// displayed does not pollute the extracted text. Use the [PdfPageText::inside_rect()]
+    /// function with the page bounds to extract the text of every layer.
+    ///
     /// In complex custom layouts, the order in which characters are defined in the document
     /// and the order in which they appear visually during rendering (and thus the order in
     /// which they are read by a user) may not necessarily match.
     pub fn all(&self) -> String {
+        let chars = chars_with_optional_content_groups(self.handle, self.bindings);
+
+        let layers = chars.group_names();
+
+        if !layers.is_empty() {
+            let hidden = self.hidden_layers();
+
+            let excluded = layers
+                .iter()
+                .filter(|layer| hidden.contains(layer))
+                .map(|layer| layer.as_str())
+                .collect::<Vec<_>>();
+
+            if !excluded.is_empty() {
+                return chars.text_excluding_groups(&excluded);
+            }
+        }
+
         self.inside_rect(self.page.page_size())
     }
 
+    /// Returns the names of the optional content groups, or layers, hidden by the default
+    /// layer configuration of the document containing this [PdfPageText].
+    ///
+    /// Pdfium does not expose the visibility state of a document's layers, so the layer
+    /// configuration is read from a copy of the document saved to bytes.
+    fn hidden_layers(&self) -> Vec<String> {
+        save_document_to_bytes(self.page.document_handle(), self.bindings)
+            .map(|file| hidden_layer_names(&file))
+            .unwrap_or_default()
+    }
+
+    /// Returns all characters that lie within the containing [PdfPage], in the order in which
+    /// they are defined in the document, concatenated into a single string, applying the given
+    /// [PdfTextExtractionOptions].
+    ///
+    /// The options control whether spaces and line breaks generated by Pdfium are included
+    /// and, when the `unicode` crate feature is enabled, whether the text is converted to
+    /// Unicode Normalization Form C and whether right-to-left text is reordered from visual
+    /// order into logical order.
+    #[inline]
+    pub fn all_with_options(&self, options: &PdfTextExtractionOptions) -> String {
+        options.text_from_chars(&self.chars())
+    }
+
+    /// Returns all characters in the containing [PdfPage] concatenated into a single string,
+    /// in the order in which they would be read by a user.
+    ///
+    /// If the containing [PdfPage] is tagged, the reading order is taken from the document's
+    /// structure tree, which correctly orders text in multi-column and other complex layouts.
+    /// Text marked as an artifact, such as running headers and footers, is omitted.
+    /// Text from separate structure elements is separated by line breaks.
+    ///
+    /// If the containing [PdfPage] is not tagged, text is ordered geometrically instead:
+    /// from top to bottom, then from left to right within each line. Geometric ordering
+    /// does not attempt to detect columns.
+    pub fn all_in_reading_order(&self) -> String {
+        match structure_tree_marked_content_ids(self.page.page_handle(), self.bindings) {
+            Some(elements) => text_in_structure_order(
+                &elements,
+                &chars_with_marked_content_ids(self.handle, self.bindings),
+            ),
+            None => text_in_geometric_order(
+                self.segments()
+                    .iter()
+                    .map(|segment| (segment.text(), segment.bounds()))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Extracts all characters in the containing [PdfPage], returning a [PdfPageTextIndexMap]
+    /// that maps byte offsets into the extracted text to character indices and back again.
+    #[inline]
+    pub fn index_map(&self) -> PdfPageTextIndexMap {
+        PdfPageTextIndexMap::from_pdfium(self.handle, self.bindings)
+    }
+
+    /// Groups all characters in the containing [PdfPage] into words, lines, and paragraphs,
+    /// each with its own bounding box, suitable for highlighting and indexing individual words.
+    ///
+    /// See [PdfPageTextLayout] for details of how words, lines, and paragraphs are detected.
+    #[inline]
+    pub fn layout(&self) -> PdfPageTextLayout {
+        PdfPageTextLayout::from_pdfium(self.handle, self.bindings)
+    }
+
+    /// Groups all characters in the containing [PdfPage] into runs of consecutive characters
+    /// that share the same font name, font weight, font size, fill color, and text rendering
+    /// mode, in the order in which they are defined in the document. Spaces and line breaks
+    /// generated by Pdfium are added to the run that precedes them.
+    ///
+    /// Style runs are useful for exporting text with basic formatting, for instance as HTML
+    /// or Markdown, and for detecting formatting changes when comparing documents.
+    pub fn style_runs(&self) -> Vec<PdfTextStyleRun> {
+        group_chars_into_style_runs(
+            self.chars()
+                .iter()
+                .map(|char| {
+                    let is_generated = self
+                        .bindings
+                        .FPDFText_IsGenerated(self.handle, char.index() as c_int)
+                        == 1;
+
+                    PdfStyledChar {
+                        index: char.index(),
+                        text: char.unicode_string().unwrap_or_default(),
+                        style: (!is_generated).then(|| PdfTextStyle::from_char(&char)),
+                        bounds: char
+                            .tight_bounds()
+                            .ok()
+                            .filter(|bounds| bounds.width().value > 0.0),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Returns the names of the optional content groups, or layers, that contain text in the
+    /// containing [PdfPage], in the order in which they are first used.
+    ///
+    /// Pass any of the returned names to the [PdfPageText::all_excluding_layers()] function
+    /// to omit text in those layers when extracting text.
+    #[inline]
+    pub fn layers(&self) -> Vec<String> {
+        chars_with_optional_content_groups(self.handle, self.bindings).group_names()
+    }
+
+    /// Returns all characters in the containing [PdfPage], in the order in which they are
+    /// defined in the document, concatenated into a single string, omitting any character
+    /// that belongs to one of the given optional content groups, or layers, or to a layer
+    /// hidden by the document's default layer configuration. This is useful for preventing
+    /// text in visible but unwanted layers, such as alternate-language translations or
+    /// watermarks, from polluting the extracted text of CAD drawings and multi-language
+    /// documents.
+    ///
+    /// Use the [PdfPageText::layers()] function to list the layers used on the page.
+    /// Layer membership is determined from the marked content enclosing each text object.
+    /// Text inside a form XObject that is itself marked as optional content, rather than
+    /// marked within the form's own content stream, is not recognized as belonging to a layer.
+    pub fn all_excluding_layers(&self, layers: &[&str]) -> String {
+        let hidden = self.hidden_layers();
+
+        let mut excluded = layers.to_vec();
+
+        excluded.extend(hidden.iter().map(|layer| layer.as_str()));
+
+        chars_with_optional_content_groups(self.handle, self.bindings)
+            .text_excluding_groups(&excluded)
+    }
+
     /// Returns all characters that lie within the bounds of the given [PdfRect] in the
     /// containing [PdfPage], in the order in which they are defined in the document,
     /// concatenated into a single string.
@@ -401,6 +615,42 @@ impl<'a> PdfPageText<'a> {
         Ok(self.inside_rect(bounds))
     }
 
+    /// Returns all characters that lie within the given [PdfTextRegion] in the containing
+    /// [PdfPage], in the order in which they are defined in the document, concatenated into
+    /// a single string. Spaces and line breaks generated by Pdfium are included only where
+    /// they separate two characters within the region.
+    ///
+    /// A [PdfTextRegion::Rect] region returns the same result as the
+    /// [PdfPageText::inside_rect()] function.
+    pub fn text_in_region(&self, region: &PdfTextRegion) -> String {
+        match region {
+            PdfTextRegion::Rect(rect) => self.inside_rect(*rect),
+            region => region.text_in_text_page(self.handle, self.bindings),
+        }
+    }
+
+    /// Returns the text marked by the given [PdfPageAnnotation] in the containing [PdfPage].
+    ///
+    /// For annotations with attachment points, such as highlight, underline, squiggly, and
+    /// strikeout annotations, only the text covered by the attachment points' quadrilaterals
+    /// is returned. This is usually much more precise than the text within the annotation's
+    /// bounding box, as returned by the [PdfPageText::for_annotation()] function, since
+    /// a highlight spanning several lines has a bounding box that covers the whole of every
+    /// line it touches. For annotations without attachment points, the text within the
+    /// annotation's bounding box is returned.
+    pub fn text_under_annotation(
+        &self,
+        annotation: &PdfPageAnnotation,
+    ) -> Result<String, PdfiumError> {
+        let quads = annotation.attachment_points().iter().collect::<Vec<_>>();
+
+        if quads.is_empty() {
+            self.for_annotation(annotation)
+        } else {
+            Ok(self.text_in_region(&PdfTextRegion::Quads(quads)))
+        }
+    }
+
     /// Starts a search for the given text string, returning a new [PdfPageTextSearch]
     /// object that can be used to step through the search results.
     #[inline]
@@ -450,6 +700,39 @@ mod tests {
     use crate::prelude::*;
     use crate::utils::test::test_bind_to_pdfium;
 
+    #[test]
+    fn test_all_skips_hidden_layers() -> Result<(), PdfiumError> {
+        // The test file has two layers: English, containing "Hello", and French,
+        // containing "Bonjour" but hidden by default.
+
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/layers-text-test.pdf", None)?;
+
+        let page = document.pages().first()?;
+
+        let text = page.text()?;
+
+        assert_eq!(
+            text.layers(),
+            vec!["English".to_string(), "French".to_string()]
+        );
+
+        let all = text.all();
+
+        assert!(all.contains("Hello"));
+        assert!(!all.contains("Bonjour"));
+
+        let page_size = page.page_size();
+
+        assert!(text.inside_rect(page_size).contains("Bonjour"));
+
+        assert!(!text.all_excluding_layers(&["English"]).contains("Hello"));
+        assert!(!text.all_excluding_layers(&["English"]).contains("Bonjour"));
+
+        Ok(())
+    }
+
     #[test]
     fn test_overlapping_chars_results() -> Result<(), PdfiumError> {
         // Test to make sure the result of the .chars_for_object() function returns the
@@ -528,4 +811,174 @@ mod tests {
             Ok(false)
         }
     }
+
+    #[test]
+    fn test_layout() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_start(PdfPagePaperSize::a4())?;
+
+        let font = document.fonts_mut().courier();
+
+        for (y, text) in [
+            (700.0, "Hello world"),
+            (688.0, "second line"),
+            (600.0, "Footer"),
+        ] {
+            page.objects_mut().create_text_object(
+                PdfPoints::new(50.0),
+                PdfPoints::new(y),
+                text,
+                font,
+                PdfPoints::new(10.0),
+            )?;
+        }
+
+        let layout = page.text()?.layout();
+
+        assert_eq!(layout.text(), "Hello world\nsecond line\n\nFooter");
+
+        let world = layout.words().nth(1).unwrap();
+
+        assert_eq!(world.text(), "world");
+        assert!(world.bounds().left.value > 50.0);
+        assert!(world
+            .bounds()
+            .does_overlap(&layout.lines().next().unwrap().bounds()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_select() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_start(PdfPagePaperSize::a4())?;
+
+        let font = document.fonts_mut().courier();
+
+        page.objects_mut().create_text_object(
+            PdfPoints::new(50.0),
+            PdfPoints::new(700.0),
+            "Hello world",
+            font,
+            PdfPoints::new(10.0),
+        )?;
+
+        let text = page.text()?;
+
+        // The anchor may follow the focus.
+
+        let selection = text.select(10, 6);
+
+        assert_eq!(selection.text(), "world");
+        assert_eq!(selection.char_range(), 6..11);
+        assert_eq!(selection.rects().len(), 1);
+        assert_eq!(selection.quads().len(), 1);
+
+        let chars = text.chars();
+
+        let hello = chars.get(0)?.loose_bounds()?;
+
+        let world = chars.get(10)?.loose_bounds()?;
+
+        let selection = text
+            .select_between_points(
+                (
+                    hello.left + PdfPoints::new(1.0),
+                    hello.bottom + PdfPoints::new(1.0),
+                ),
+                (
+                    world.left + PdfPoints::new(1.0),
+                    world.bottom + PdfPoints::new(1.0),
+                ),
+                PdfPoints::new(1.0),
+            )
+            .unwrap();
+
+        assert_eq!(selection.text(), "Hello world");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_style_runs() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_start(PdfPagePaperSize::a4())?;
+
+        let regular = document.fonts_mut().helvetica();
+
+        let bold = document.fonts_mut().helvetica_bold();
+
+        for (x, text, font) in [(50.0, "Heading", bold), (150.0, "body", regular)] {
+            page.objects_mut().create_text_object(
+                PdfPoints::new(x),
+                PdfPoints::new(700.0),
+                text,
+                font,
+                PdfPoints::new(10.0),
+            )?;
+        }
+
+        let runs = page.text()?.style_runs();
+
+        assert_eq!(runs.len(), 2);
+        assert!(runs[0].text().starts_with("Heading"));
+        assert_eq!(runs[1].text(), "body");
+        assert_ne!(runs[0].style().font_name(), runs[1].style().font_name());
+        assert!(runs[0].bounds().right.value <= runs[1].bounds().left.value);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_in_region() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_start(PdfPagePaperSize::a4())?;
+
+        let font = document.fonts_mut().courier();
+
+        page.objects_mut().create_text_object(
+            PdfPoints::new(50.0),
+            PdfPoints::new(700.0),
+            "Hello world",
+            font,
+            PdfPoints::new(10.0),
+        )?;
+
+        let text = page.text()?;
+
+        // A quad covering only the second word.
+
+        let world = text.chars().get(6)?.loose_bounds()?;
+
+        let quad = PdfQuadPoints::from_rect(PdfRect::new(
+            world.bottom,
+            world.left,
+            world.top,
+            PdfPoints::new(200.0),
+        ));
+
+        assert_eq!(text.text_in_region(&quad.into()), "world");
+
+        Ok(())
+    }
 }
@@ -78,6 +78,16 @@ impl<'a> PdfPageTextChar<'a> {
         self.unicode_char().map(|char| char.to_string())
     }
 
+    /// Returns `true` if this character was generated by Pdfium during text extraction rather
+    /// than defined in the document, such as a space or line break inserted between words
+    /// or lines that are positioned apart on the page without any separating character.
+    #[inline]
+    pub fn is_generated(&self) -> bool {
+        self.bindings
+            .FPDFText_IsGenerated(self.text_page_handle, self.index)
+            == 1
+    }
+
     /// Returns the effective size of this character when rendered, taking into account both the
     /// font size applied to the character as well as any vertical scale factor applied
     /// to the character's transformation matrix.
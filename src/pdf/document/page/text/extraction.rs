@@ -0,0 +1,186 @@
+//! Defines the [PdfTextExtractionOptions] struct, configuring how the characters on a
+//! `PdfPage` are converted into a string by `PdfPageText::all_with_options()`.
+
+use crate::pdf::document::page::text::chars::PdfPageTextChars;
+
+#[cfg(feature = "unicode")]
+use unicode_bidi::BidiInfo;
+
+#[cfg(feature = "unicode")]
+use unicode_normalization::UnicodeNormalization;
+
+/// Configures the options that should be applied when extracting text from a page using
+/// the `PdfPageText::all_with_options()` function.
+///
+/// By default, text is extracted exactly as Pdfium returns it: characters appear in the
+/// order in which they are defined in the document, without normalization, and include
+/// the spaces and line breaks Pdfium generates between words and lines. Unicode normalization
+/// and bidirectional reordering are only available when the `unicode` crate feature is enabled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PdfTextExtractionOptions {
+    include_generated_chars: bool,
+
+    #[cfg(feature = "unicode")]
+    normalize_nfc: bool,
+
+    #[cfg(feature = "unicode")]
+    reorder_bidi: bool,
+}
+
+impl PdfTextExtractionOptions {
+    /// Creates a new [PdfTextExtractionOptions] object with all settings initialized with
+    /// their default values.
+    pub fn new() -> Self {
+        PdfTextExtractionOptions {
+            include_generated_chars: true,
+
+            #[cfg(feature = "unicode")]
+            normalize_nfc: false,
+
+            #[cfg(feature = "unicode")]
+            reorder_bidi: false,
+        }
+    }
+
+    /// Controls whether spaces and line breaks generated by Pdfium should be included in
+    /// the extracted text. Pdfium generates these characters between words and lines that
+    /// are positioned apart on the page without any separating character in the document.
+    /// Excluding them returns only the characters actually defined in the document, which
+    /// may run words and lines together. The default is `true`.
+    #[inline]
+    pub fn include_generated_chars(mut self, do_include: bool) -> Self {
+        self.include_generated_chars = do_include;
+
+        self
+    }
+
+    /// Controls whether the extracted text should be converted to Unicode Normalization
+    /// Form C, composing base characters and combining marks into single precomposed
+    /// characters wherever possible. The default is `false`.
+    #[cfg(feature = "unicode")]
+    #[inline]
+    pub fn normalize_nfc(mut self, do_normalize: bool) -> Self {
+        self.normalize_nfc = do_normalize;
+
+        self
+    }
+
+    /// Controls whether each line of extracted text containing right-to-left characters,
+    /// such as Arabic or Hebrew, should be reordered from the visual order in which the
+    /// characters are usually laid out on the page into logical reading order, using the
+    /// Unicode Bidirectional Algorithm. Lines containing only left-to-right characters
+    /// are unaffected. The default is `false`.
+    ///
+    /// Documents that already define right-to-left text in logical order, as many tagged
+    /// documents do, should not be reordered.
+    #[cfg(feature = "unicode")]
+    #[inline]
+    pub fn reorder_bidi(mut self, do_reorder: bool) -> Self {
+        self.reorder_bidi = do_reorder;
+
+        self
+    }
+
+    /// Concatenates the given characters into a single string, applying these options.
+    pub(crate) fn text_from_chars(&self, chars: &PdfPageTextChars) -> String {
+        let text = chars
+            .iter()
+            .filter(|char| self.include_generated_chars || !char.is_generated())
+            .filter_map(|char| char.unicode_char())
+            .collect::<String>();
+
+        self.apply(text)
+    }
+
+    /// Applies the normalization and reordering configured in these options to the given text.
+    #[cfg(feature = "unicode")]
+    pub(crate) fn apply(&self, text: String) -> String {
+        let text = if self.reorder_bidi {
+            reorder_visual_to_logical(&text)
+        } else {
+            text
+        };
+
+        if self.normalize_nfc {
+            text.nfc().collect()
+        } else {
+            text
+        }
+    }
+
+    /// Applies the normalization and reordering configured in these options to the given text.
+    #[cfg(not(feature = "unicode"))]
+    #[inline]
+    pub(crate) fn apply(&self, text: String) -> String {
+        text
+    }
+}
+
+impl Default for PdfTextExtractionOptions {
+    #[inline]
+    fn default() -> Self {
+        PdfTextExtractionOptions::new()
+    }
+}
+
+/// Reorders each line of the given text from visual order into logical order. Reordering a
+/// line using the Unicode Bidirectional Algorithm reverses each right-to-left run while leaving
+/// embedded left-to-right runs such as numbers intact, so applying the algorithm to visually
+/// ordered text restores its logical order.
+#[cfg(feature = "unicode")]
+fn reorder_visual_to_logical(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for line in text.split_inclusive('\n') {
+        // Line breaks are kept in place at the end of each line, rather than being reordered
+        // along with the line's content.
+
+        let content = line.trim_end_matches(['\r', '\n']);
+
+        let info = BidiInfo::new(content, None);
+
+        if info.has_rtl() {
+            for paragraph in info.paragraphs.iter() {
+                result.push_str(&info.reorder_line(paragraph, paragraph.range.clone()));
+            }
+        } else {
+            result.push_str(content);
+        }
+
+        result.push_str(&line[content.len()..]);
+    }
+
+    result
+}
+
+#[cfg(test)]
+#[cfg(feature = "unicode")]
+mod tests {
+    use crate::pdf::document::page::text::extraction::*;
+
+    #[test]
+    fn test_apply() {
+        let options = PdfTextExtractionOptions::new()
+            .normalize_nfc(true)
+            .reorder_bidi(true);
+
+        // Decomposed combining marks are composed.
+
+        assert_eq!(options.apply("Cafe\u{301}".to_string()), "Caf\u{e9}");
+
+        // Visually ordered Hebrew is reversed into logical order, line by line,
+        // leaving left-to-right text untouched.
+
+        assert_eq!(
+            options.apply("\u{5dd}\u{5d5}\u{5dc}\u{5e9}\r\nHello".to_string()),
+            "\u{5e9}\u{5dc}\u{5d5}\u{5dd}\r\nHello"
+        );
+
+        // Default options leave text unchanged.
+
+        assert_eq!(
+            PdfTextExtractionOptions::new().apply("Cafe\u{301}".to_string()),
+            "Cafe\u{301}"
+        );
+    }
+}
@@ -0,0 +1,239 @@
+//! Defines the [PdfPageTextIndexMap] struct, a mapping between byte offsets into the text
+//! extracted from a `PdfPageText` collection and the indices of Pdfium's characters.
+
+use crate::bindgen::FPDF_TEXTPAGE;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::text::chars::PdfPageTextCharIndex;
+use crate::utils::mem::create_sized_buffer;
+use std::ops::Range;
+use std::os::raw::c_int;
+
+/// A mapping between byte offsets into the UTF-8 text extracted from a `PdfPageText`
+/// collection and the indices of the `PdfPageTextChar` characters that text was extracted
+/// from, as returned by the `PdfPageText::index_map()` function.
+///
+/// Pdfium indexes extracted text in UTF-16 code units, and its list of characters includes
+/// characters, such as inferred spaces and line breaks, that may not be present in the
+/// extracted text. Applications that store byte offsets into extracted text, for instance
+/// in a search index, can use a [PdfPageTextIndexMap] to map those offsets back to character
+/// indices, and from there to the characters' positions on the page, without having to
+/// account for either difference themselves.
+///
+/// All byte offsets are offsets into the text returned by the [PdfPageTextIndexMap::text()]
+/// function, which may differ slightly from the text returned by other text extraction
+/// functions, such as `PdfPageText::all()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfPageTextIndexMap {
+    text: String,
+
+    // The byte offset and UTF-16 text index at which each Rust char in the text starts,
+    // followed by a final entry for the end of the text.
+    boundaries: Vec<(usize, usize)>,
+
+    // The character index for each UTF-16 text index, or -1 if there is none.
+    text_to_char: Vec<c_int>,
+
+    // The UTF-16 text index for each character index, or -1 if there is none.
+    char_to_text: Vec<c_int>,
+}
+
+impl PdfPageTextIndexMap {
+    /// Creates a new [PdfPageTextIndexMap] from the characters in the given text page.
+    pub(crate) fn from_pdfium(
+        text_page: FPDF_TEXTPAGE,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Self {
+        let count = bindings.FPDFText_CountChars(text_page).max(0);
+
+        // FPDFText_GetText() requires space for a trailing terminator.
+
+        let mut buffer = create_sized_buffer::<u16>(count as usize + 1);
+
+        let written = bindings.FPDFText_GetText(text_page, 0, count, buffer.as_mut_ptr());
+
+        buffer.truncate((written - 1).max(0) as usize);
+
+        while buffer.last() == Some(&0) {
+            buffer.pop();
+        }
+
+        let text_to_char = (0..buffer.len() as c_int)
+            .map(|index| bindings.FPDFText_GetCharIndexFromTextIndex(text_page, index))
+            .collect();
+
+        let char_to_text = (0..count)
+            .map(|index| bindings.FPDFText_GetTextIndexFromCharIndex(text_page, index))
+            .collect();
+
+        Self::from_parts(&buffer, text_to_char, char_to_text)
+    }
+
+    /// Creates a new [PdfPageTextIndexMap] from the given UTF-16 text and the mappings
+    /// between its text indices and character indices.
+    pub(crate) fn from_parts(
+        utf16: &[u16],
+        text_to_char: Vec<c_int>,
+        char_to_text: Vec<c_int>,
+    ) -> Self {
+        let mut text = String::with_capacity(utf16.len());
+
+        let mut boundaries = Vec::with_capacity(utf16.len() + 1);
+
+        let mut text_index = 0;
+
+        for decoded in char::decode_utf16(utf16.iter().copied()) {
+            boundaries.push((text.len(), text_index));
+
+            // Unpaired surrogates are replaced so that every text index remains addressable.
+
+            let char = match decoded {
+                Ok(char) => {
+                    text_index += char.len_utf16();
+
+                    char
+                }
+                Err(_) => {
+                    text_index += 1;
+
+                    char::REPLACEMENT_CHARACTER
+                }
+            };
+
+            text.push(char);
+        }
+
+        boundaries.push((text.len(), text_index));
+
+        PdfPageTextIndexMap {
+            text,
+            boundaries,
+            text_to_char,
+            char_to_text,
+        }
+    }
+
+    /// Returns the text mapped by this [PdfPageTextIndexMap].
+    #[inline]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Returns the index of the character from which the text at the given byte offset was
+    /// extracted. Offsets that fall inside a multi-byte UTF-8 sequence are treated as
+    /// referring to the start of that sequence. Returns `None` if the offset is beyond the
+    /// end of the text, or if Pdfium cannot map the offset to a character.
+    pub fn char_index_from_byte_offset(&self, offset: usize) -> Option<PdfPageTextCharIndex> {
+        if offset >= self.text.len() {
+            return None;
+        }
+
+        let text_index = self.boundaries[self.boundary_containing(offset)].1;
+
+        self.text_to_char
+            .get(text_index)
+            .filter(|index| **index >= 0)
+            .map(|index| *index as PdfPageTextCharIndex)
+    }
+
+    /// Returns the byte offset of the text extracted from the character at the given index.
+    /// Returns `None` if the character is not present in the text, or if the index is out
+    /// of range.
+    pub fn byte_offset_from_char_index(&self, index: PdfPageTextCharIndex) -> Option<usize> {
+        let text_index = *self.char_to_text.get(index).filter(|index| **index >= 0)? as usize;
+
+        // A text index in the middle of a surrogate pair maps to the start of the pair.
+
+        let position = self
+            .boundaries
+            .partition_point(|(_, start)| *start <= text_index)
+            .checked_sub(1)?;
+
+        self.boundaries
+            .get(position)
+            .filter(|_| position + 1 < self.boundaries.len())
+            .map(|(offset, _)| *offset)
+    }
+
+    /// Returns the range of indices of the characters from which the text in the given range
+    /// of byte offsets was extracted, or `None` if the range is empty or cannot be mapped.
+    pub fn char_range_from_byte_range(
+        &self,
+        range: Range<usize>,
+    ) -> Option<Range<PdfPageTextCharIndex>> {
+        if range.start >= range.end {
+            return None;
+        }
+
+        let start = self.char_index_from_byte_offset(range.start)?;
+
+        let end = self.char_index_from_byte_offset(range.end - 1)?;
+
+        Some(start.min(end)..start.max(end) + 1)
+    }
+
+    /// Returns the range of byte offsets of the text extracted from the characters in the
+    /// given range of indices, or `None` if none of the characters are present in the text.
+    /// Characters at either end of the range that are not present in the text are ignored.
+    pub fn byte_range_from_char_range(
+        &self,
+        range: Range<PdfPageTextCharIndex>,
+    ) -> Option<Range<usize>> {
+        let start = range
+            .clone()
+            .find_map(|index| self.byte_offset_from_char_index(index))?;
+
+        let last = range
+            .rev()
+            .find_map(|index| self.byte_offset_from_char_index(index))?;
+
+        let end = self.boundaries[self.boundary_containing(last) + 1].0;
+
+        Some(start.min(last)..end.max(start))
+    }
+
+    /// Returns the position in the boundaries list of the Rust char containing the given
+    /// byte offset.
+    #[inline]
+    fn boundary_containing(&self, offset: usize) -> usize {
+        self.boundaries
+            .partition_point(|(start, _)| *start <= offset)
+            .saturating_sub(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::text::index_map::*;
+
+    #[test]
+    fn test_index_map_with_multi_byte_text() {
+        // "é😀a", where Pdfium's character list holds an inferred space between the emoji
+        // and the "a" that is not present in the extracted text.
+
+        let utf16 = "é😀a".encode_utf16().collect::<Vec<_>>();
+
+        assert_eq!(utf16.len(), 4);
+
+        let map = PdfPageTextIndexMap::from_parts(&utf16, vec![0, 1, 1, 3], vec![0, 1, -1, 3]);
+
+        assert_eq!(map.text(), "é😀a");
+
+        // "é" occupies bytes 0..2, the emoji bytes 2..6, and "a" byte 6.
+
+        assert_eq!(map.char_index_from_byte_offset(0), Some(0));
+        assert_eq!(map.char_index_from_byte_offset(1), Some(0));
+        assert_eq!(map.char_index_from_byte_offset(4), Some(1));
+        assert_eq!(map.char_index_from_byte_offset(6), Some(3));
+        assert_eq!(map.char_index_from_byte_offset(7), None);
+
+        assert_eq!(map.byte_offset_from_char_index(1), Some(2));
+        assert_eq!(map.byte_offset_from_char_index(2), None);
+        assert_eq!(map.byte_offset_from_char_index(3), Some(6));
+
+        assert_eq!(map.char_range_from_byte_range(2..7), Some(1..4));
+        assert_eq!(map.byte_range_from_char_range(1..2), Some(2..6));
+        assert_eq!(map.byte_range_from_char_range(0..4), Some(0..7));
+        assert_eq!(map.byte_range_from_char_range(1..3), Some(2..6));
+        assert_eq!(map.byte_range_from_char_range(2..3), None);
+    }
+}
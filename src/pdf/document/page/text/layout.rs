@@ -0,0 +1,459 @@
+//! Defines the [PdfPageTextLayout] struct, the result of grouping the characters in a
+//! `PdfPageText` collection into words, lines, and paragraphs.
+
+use crate::bindgen::FPDF_TEXTPAGE;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::text::chars::PdfPageTextCharIndex;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::os::raw::c_int;
+
+/// The horizontal gap between two characters on the same line, as a multiple of the font size,
+/// beyond which the characters are considered to be part of separate words even if they are
+/// not separated by whitespace.
+const WORD_GAP: f32 = 0.3;
+
+/// The vertical distance between the baselines of two consecutive characters, as a multiple
+/// of the font size, beyond which the characters are considered to be on separate lines
+/// even if they are not separated by a line break.
+const LINE_BASELINE_SHIFT: f32 = 0.5;
+
+/// The vertical distance between the baselines of two consecutive lines, as a multiple of the
+/// larger font size of the two lines, beyond which the lines are considered to be part of
+/// separate paragraphs. Body text is usually set with baselines 1.2 times the font size apart.
+const PARAGRAPH_LINE_SPACING: f32 = 1.5;
+
+/// A single word in a [PdfPageTextLayout]: a run of consecutive non-whitespace characters
+/// on the same line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextWord {
+    text: String,
+    bounds: PdfRect,
+    chars: Range<PdfPageTextCharIndex>,
+}
+
+impl PdfTextWord {
+    /// Returns the text of this [PdfTextWord].
+    #[inline]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Returns the bounding box of this [PdfTextWord], the union of the tight bounding
+    /// boxes of its characters. This is suitable for highlighting the word.
+    #[inline]
+    pub fn bounds(&self) -> PdfRect {
+        self.bounds
+    }
+
+    /// Returns the range of indices of the characters in this [PdfTextWord] within the
+    /// containing `PdfPageText` collection, for use with the `PdfPageText::chars()` function.
+    #[inline]
+    pub fn char_range(&self) -> Range<PdfPageTextCharIndex> {
+        self.chars.clone()
+    }
+}
+
+/// A single line of text in a [PdfPageTextLayout]: a sequence of [PdfTextWord] words sharing
+/// the same baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextLine {
+    words: Vec<PdfTextWord>,
+    bounds: PdfRect,
+    baseline: PdfPoints,
+    font_size: f32,
+}
+
+impl PdfTextLine {
+    /// Returns the words in this [PdfTextLine], in the order in which they are read.
+    #[inline]
+    pub fn words(&self) -> &[PdfTextWord] {
+        self.words.as_slice()
+    }
+
+    /// Returns the bounding box of this [PdfTextLine], the union of the bounding boxes
+    /// of its words.
+    #[inline]
+    pub fn bounds(&self) -> PdfRect {
+        self.bounds
+    }
+
+    /// Returns the vertical position of the baseline of this [PdfTextLine], taken from the
+    /// origin of its first character.
+    #[inline]
+    pub fn baseline(&self) -> PdfPoints {
+        self.baseline
+    }
+
+    /// Returns the text of this [PdfTextLine], with words separated by single spaces.
+    pub fn text(&self) -> String {
+        self.words
+            .iter()
+            .map(|word| word.text())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+/// A single paragraph in a [PdfPageTextLayout]: a sequence of consecutive [PdfTextLine] lines
+/// that are not separated by a noticeably larger vertical gap than usual.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextParagraph {
+    lines: Vec<PdfTextLine>,
+    bounds: PdfRect,
+}
+
+impl PdfTextParagraph {
+    /// Returns the lines in this [PdfTextParagraph], in the order in which they are read.
+    #[inline]
+    pub fn lines(&self) -> &[PdfTextLine] {
+        self.lines.as_slice()
+    }
+
+    /// Returns the bounding box of this [PdfTextParagraph], the union of the bounding boxes
+    /// of its lines.
+    #[inline]
+    pub fn bounds(&self) -> PdfRect {
+        self.bounds
+    }
+
+    /// Returns the text of this [PdfTextParagraph], with lines separated by line breaks.
+    pub fn text(&self) -> String {
+        self.lines
+            .iter()
+            .map(|line| line.text())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The characters in a `PdfPageText` collection grouped into words, lines, and paragraphs,
+/// as returned by the `PdfPageText::layout()` function.
+///
+/// Lines are ordered in the order in which Pdfium extracts them from the page, which usually
+/// follows the order in which the page's text is read, including column by column in
+/// multi-column layouts. Words within each line are ordered from left to right.
+///
+/// Layout analysis assumes horizontal, left-to-right text. A new word begins after any
+/// whitespace, or wherever the horizontal gap between two characters is large relative to
+/// the font size. A new line begins after any line break, or wherever the baseline shifts.
+/// A new paragraph begins wherever the distance between the baselines of two lines is large
+/// relative to the font size, or wherever a line starts above or entirely beside the line
+/// before it.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PdfPageTextLayout {
+    paragraphs: Vec<PdfTextParagraph>,
+}
+
+impl PdfPageTextLayout {
+    /// Analyzes the layout of all the characters in the given text page.
+    pub(crate) fn from_pdfium(
+        text_page: FPDF_TEXTPAGE,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Self {
+        let chars = (0..bindings.FPDFText_CountChars(text_page))
+            .filter_map(|index| PdfLayoutChar::from_pdfium(text_page, index, bindings))
+            .collect::<Vec<_>>();
+
+        Self::from_chars(&chars)
+    }
+
+    /// Groups the given characters, in text page order, into words, lines, and paragraphs.
+    pub(crate) fn from_chars(chars: &[PdfLayoutChar]) -> Self {
+        PdfPageTextLayout {
+            paragraphs: group_lines_into_paragraphs(group_chars_into_lines(chars)),
+        }
+    }
+
+    /// Returns the paragraphs in this [PdfPageTextLayout], in the order in which they are read.
+    #[inline]
+    pub fn paragraphs(&self) -> &[PdfTextParagraph] {
+        self.paragraphs.as_slice()
+    }
+
+    /// Returns an iterator over every line in this [PdfPageTextLayout], in the order in which
+    /// they are read.
+    #[inline]
+    pub fn lines(&self) -> impl Iterator<Item = &PdfTextLine> {
+        self.paragraphs
+            .iter()
+            .flat_map(|paragraph| paragraph.lines.iter())
+    }
+
+    /// Returns an iterator over every word in this [PdfPageTextLayout], in the order in which
+    /// they are read.
+    #[inline]
+    pub fn words(&self) -> impl Iterator<Item = &PdfTextWord> {
+        self.lines().flat_map(|line| line.words.iter())
+    }
+
+    /// Returns `true` if this [PdfPageTextLayout] contains no words.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.paragraphs.is_empty()
+    }
+
+    /// Returns the text in this [PdfPageTextLayout] in reading order, with lines separated
+    /// by line breaks and paragraphs separated by blank lines.
+    pub fn text(&self) -> String {
+        self.paragraphs
+            .iter()
+            .map(|paragraph| paragraph.text())
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+/// A single character in a text page, with the geometry needed for layout analysis.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PdfLayoutChar {
+    pub(crate) index: PdfPageTextCharIndex,
+    pub(crate) char: char,
+    pub(crate) bounds: PdfRect,
+    pub(crate) baseline: f32,
+    pub(crate) font_size: f32,
+}
+
+impl PdfLayoutChar {
+    fn from_pdfium(
+        text_page: FPDF_TEXTPAGE,
+        index: c_int,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Option<Self> {
+        let char = char::from_u32(bindings.FPDFText_GetUnicode(text_page, index))?;
+
+        let mut left = 0.0;
+        let mut right = 0.0;
+        let mut bottom = 0.0;
+        let mut top = 0.0;
+
+        // Characters generated by Pdfium, such as inferred spaces and line breaks,
+        // may not have a bounding box. Only whitespace is generated, so this is harmless.
+
+        let _ = bindings.FPDFText_GetCharBox(
+            text_page,
+            index,
+            &mut left,
+            &mut right,
+            &mut bottom,
+            &mut top,
+        );
+
+        let mut x = 0.0;
+        let mut y = 0.0;
+
+        let _ = bindings.FPDFText_GetCharOrigin(text_page, index, &mut x, &mut y);
+
+        Some(PdfLayoutChar {
+            index: index as PdfPageTextCharIndex,
+            char,
+            bounds: PdfRect::new_from_values(bottom as f32, left as f32, top as f32, right as f32),
+            baseline: y as f32,
+            font_size: bindings.FPDFText_GetFontSize(text_page, index) as f32,
+        })
+    }
+}
+
+/// Groups the given characters into words and lines.
+fn group_chars_into_lines(chars: &[PdfLayoutChar]) -> Vec<PdfTextLine> {
+    let mut lines = Vec::new();
+
+    let mut line = PdfLineBuilder::default();
+
+    let mut word: Option<PdfTextWord> = None;
+
+    let mut previous: Option<&PdfLayoutChar> = None;
+
+    for char in chars {
+        if char.char.is_whitespace() {
+            line.words.extend(word.take());
+
+            if char.char == '\n' || char.char == '\r' {
+                lines.extend(std::mem::take(&mut line).build());
+            }
+
+            continue;
+        }
+
+        if let Some(previous) = previous {
+            let size = previous.font_size.max(char.font_size).max(1.0);
+
+            if (char.baseline - previous.baseline).abs() > size * LINE_BASELINE_SHIFT {
+                line.words.extend(word.take());
+                lines.extend(std::mem::take(&mut line).build());
+            } else {
+                let gap = char.bounds.left.value - previous.bounds.right.value;
+
+                if gap > size * WORD_GAP || char.bounds.right.value < previous.bounds.left.value {
+                    line.words.extend(word.take());
+                }
+            }
+        }
+
+        if line.baseline.is_none() {
+            line.baseline = Some(char.baseline);
+        }
+
+        line.font_size = line.font_size.max(char.font_size);
+
+        match word.as_mut() {
+            Some(word) => {
+                word.text.push(char.char);
+                word.bounds = word.bounds.union(&char.bounds);
+                word.chars.end = char.index + 1;
+            }
+            None => {
+                word = Some(PdfTextWord {
+                    text: char.char.to_string(),
+                    bounds: char.bounds,
+                    chars: char.index..char.index + 1,
+                });
+            }
+        }
+
+        previous = Some(char);
+    }
+
+    line.words.extend(word);
+    lines.extend(line.build());
+
+    lines
+}
+
+/// The words of a [PdfTextLine] under construction.
+#[derive(Default)]
+struct PdfLineBuilder {
+    words: Vec<PdfTextWord>,
+    baseline: Option<f32>,
+    font_size: f32,
+}
+
+impl PdfLineBuilder {
+    /// Creates a new line from the words collected so far, ordering the words from
+    /// left to right. Returns `None` if no words have been collected.
+    fn build(mut self) -> Option<PdfTextLine> {
+        let first = self.words.first()?;
+
+        let bounds = self
+            .words
+            .iter()
+            .skip(1)
+            .fold(first.bounds, |bounds, word| bounds.union(&word.bounds));
+
+        self.words.sort_by(|a, b| {
+            a.bounds
+                .left
+                .value
+                .partial_cmp(&b.bounds.left.value)
+                .unwrap_or(Ordering::Equal)
+        });
+
+        Some(PdfTextLine {
+            words: self.words,
+            bounds,
+            baseline: PdfPoints::new(self.baseline.unwrap_or(bounds.bottom.value)),
+            font_size: self.font_size,
+        })
+    }
+}
+
+/// Groups the given lines into paragraphs.
+fn group_lines_into_paragraphs(lines: Vec<PdfTextLine>) -> Vec<PdfTextParagraph> {
+    let mut paragraphs: Vec<PdfTextParagraph> = Vec::new();
+
+    for line in lines {
+        if let Some(paragraph) = paragraphs.last_mut() {
+            let previous = &paragraph.lines[paragraph.lines.len() - 1];
+
+            let size = previous.font_size.max(line.font_size).max(1.0);
+
+            let spacing = previous.baseline.value - line.baseline.value;
+
+            let is_horizontally_overlapping = line.bounds.left.value <= previous.bounds.right.value
+                && line.bounds.right.value >= previous.bounds.left.value;
+
+            if spacing > 0.0
+                && spacing <= size * PARAGRAPH_LINE_SPACING
+                && is_horizontally_overlapping
+            {
+                paragraph.bounds = paragraph.bounds.union(&line.bounds);
+                paragraph.lines.push(line);
+
+                continue;
+            }
+        }
+
+        paragraphs.push(PdfTextParagraph {
+            bounds: line.bounds,
+            lines: vec![line],
+        });
+    }
+
+    paragraphs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::text::layout::*;
+
+    /// Lays out the given text as a sequence of characters in a monospaced font of size 10,
+    /// each 6 points wide, starting at the given position.
+    fn chars_at(text: &str, first_index: usize, left: f32, baseline: f32) -> Vec<PdfLayoutChar> {
+        text.chars()
+            .enumerate()
+            .map(|(offset, char)| {
+                let left = left + offset as f32 * 6.0;
+
+                PdfLayoutChar {
+                    index: first_index + offset,
+                    char,
+                    bounds: PdfRect::new_from_values(baseline, left, baseline + 7.0, left + 5.0),
+                    baseline,
+                    font_size: 10.0,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_layout_words_lines_and_paragraphs() {
+        let mut chars = chars_at("Hello world\r\n", 0, 50.0, 700.0);
+
+        chars.extend(chars_at("second line", 13, 50.0, 688.0));
+
+        // No explicit line break, but the baseline shifts.
+
+        chars.extend(chars_at("New paragraph", 24, 50.0, 650.0));
+
+        let layout = PdfPageTextLayout::from_chars(&chars);
+
+        assert_eq!(layout.paragraphs().len(), 2);
+        assert_eq!(layout.lines().count(), 3);
+        assert_eq!(layout.text(), "Hello world\nsecond line\n\nNew paragraph");
+
+        let world = layout.words().nth(1).unwrap();
+
+        assert_eq!(world.text(), "world");
+        assert_eq!(world.char_range(), 6..11);
+        assert_eq!(
+            world.bounds(),
+            PdfRect::new_from_values(700.0, 86.0, 707.0, 115.0)
+        );
+
+        assert_eq!(layout.lines().nth(1).unwrap().baseline().value, 688.0);
+    }
+
+    #[test]
+    fn test_layout_splits_words_on_large_gaps() {
+        let mut chars = chars_at("Name", 0, 50.0, 700.0);
+
+        chars.extend(chars_at("Value", 4, 200.0, 700.0));
+
+        let layout = PdfPageTextLayout::from_chars(&chars);
+
+        assert_eq!(layout.lines().count(), 1);
+        assert_eq!(layout.text(), "Name Value");
+    }
+}
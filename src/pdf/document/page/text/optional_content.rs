@@ -0,0 +1,123 @@
+//! Internal functionality for filtering the characters in a `PdfPageText` collection by the
+//! optional content groups, or layers, to which they belong.
+
+use crate::bindgen::{FPDF_PAGEOBJECT, FPDF_TEXTPAGE};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::object::mark::optional_content_group_names;
+use std::collections::HashMap;
+
+/// The characters in a text page, each paired with an index into a table of the distinct
+/// sets of optional content group names to which those characters belong.
+pub(crate) struct CharsWithOptionalContentGroups {
+    pub(crate) group_sets: Vec<Vec<String>>,
+    pub(crate) chars: Vec<(usize, char)>,
+}
+
+/// Returns every character in the given text page, in content stream order, paired with the
+/// optional content groups of the page object that defines it. Characters generated by Pdfium,
+/// such as inferred spaces and line breaks, inherit the optional content groups of the
+/// preceding character.
+pub(crate) fn chars_with_optional_content_groups(
+    text_page: FPDF_TEXTPAGE,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> CharsWithOptionalContentGroups {
+    let mut group_sets: Vec<Vec<String>> = vec![Vec::new()];
+
+    let mut group_set_by_object: HashMap<FPDF_PAGEOBJECT, usize> = HashMap::new();
+
+    let mut chars = Vec::new();
+
+    let mut current = 0;
+
+    for index in 0..bindings.FPDFText_CountChars(text_page) {
+        let object = bindings.FPDFText_GetTextObject(text_page, index);
+
+        if !object.is_null() {
+            current = *group_set_by_object.entry(object).or_insert_with(|| {
+                let names = optional_content_group_names(object, bindings);
+
+                match group_sets.iter().position(|existing| *existing == names) {
+                    Some(position) => position,
+                    None => {
+                        group_sets.push(names);
+
+                        group_sets.len() - 1
+                    }
+                }
+            });
+        }
+
+        if let Some(char) = char::from_u32(bindings.FPDFText_GetUnicode(text_page, index)) {
+            chars.push((current, char));
+        }
+    }
+
+    CharsWithOptionalContentGroups { group_sets, chars }
+}
+
+impl CharsWithOptionalContentGroups {
+    /// Returns the distinct names of all optional content groups used by these characters,
+    /// in the order in which they are first used.
+    pub(crate) fn group_names(&self) -> Vec<String> {
+        let mut result: Vec<String> = Vec::new();
+
+        for (set, _) in self.chars.iter() {
+            for name in self.group_sets[*set].iter() {
+                if !result.contains(name) {
+                    result.push(name.clone());
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Concatenates these characters into a single string, omitting every character that
+    /// belongs to any of the given optional content groups.
+    pub(crate) fn text_excluding_groups(&self, excluded: &[&str]) -> String {
+        let is_excluded = self
+            .group_sets
+            .iter()
+            .map(|names| names.iter().any(|name| excluded.contains(&name.as_str())))
+            .collect::<Vec<_>>();
+
+        self.chars
+            .iter()
+            .filter(|(set, _)| !is_excluded[*set])
+            .map(|(_, char)| *char)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::text::optional_content::CharsWithOptionalContentGroups;
+
+    #[test]
+    fn test_text_excluding_groups() {
+        let chars = CharsWithOptionalContentGroups {
+            group_sets: vec![
+                vec![],
+                vec!["English".to_string()],
+                vec!["Annotations".to_string(), "French".to_string()],
+            ],
+            chars: "Title Hello Bonjour"
+                .chars()
+                .enumerate()
+                .map(|(index, char)| match index {
+                    0..=5 => (0, char),
+                    6..=11 => (1, char),
+                    _ => (2, char),
+                })
+                .collect(),
+        };
+
+        assert_eq!(
+            chars.group_names(),
+            vec!["English", "Annotations", "French"]
+        );
+
+        assert_eq!(chars.text_excluding_groups(&["French"]), "Title Hello ");
+        assert_eq!(chars.text_excluding_groups(&[]), "Title Hello Bonjour");
+    }
+}
@@ -0,0 +1,257 @@
+//! Internal functionality for ordering the characters in a `PdfPageText` collection in the
+//! order in which they would be read by a user, rather than the order in which they
+//! are defined in the page's content stream.
+
+use crate::bindgen::{FPDF_PAGE, FPDF_STRUCTELEMENT, FPDF_TEXTPAGE};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::rect::PdfRect;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::os::raw::c_int;
+
+/// Returns the marked content IDs referenced by the structure tree of the given page, grouped by
+/// the structure element that references them, in the logical order defined by the structure tree.
+///
+/// Returns `None` if the page has no structure tree or if the structure tree does not
+/// reference any marked content, i.e. if the page is not tagged.
+pub(crate) fn structure_tree_marked_content_ids(
+    page: FPDF_PAGE,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Option<Vec<Vec<c_int>>> {
+    let tree = bindings.FPDF_StructTree_GetForPage(page);
+
+    if tree.is_null() {
+        return None;
+    }
+
+    let mut result = Vec::new();
+
+    for index in 0..bindings.FPDF_StructTree_CountChildren(tree) {
+        let element = bindings.FPDF_StructTree_GetChildAtIndex(tree, index);
+
+        if !element.is_null() {
+            collect_marked_content_ids(element, bindings, &mut result);
+        }
+    }
+
+    bindings.FPDF_StructTree_Close(tree);
+
+    if result.is_empty() {
+        None
+    } else {
+        Some(result)
+    }
+}
+
+/// Walks the given structure element and all its descendants depth-first, appending the
+/// marked content IDs referenced by each element to the given result.
+fn collect_marked_content_ids(
+    element: FPDF_STRUCTELEMENT,
+    bindings: &dyn PdfiumLibraryBindings,
+    result: &mut Vec<Vec<c_int>>,
+) {
+    let ids = (0..bindings.FPDF_StructElement_GetMarkedContentIdCount(element))
+        .map(|index| bindings.FPDF_StructElement_GetMarkedContentIdAtIndex(element, index))
+        .filter(|id| *id >= 0)
+        .collect::<Vec<_>>();
+
+    if !ids.is_empty() {
+        result.push(ids);
+    }
+
+    for index in 0..bindings.FPDF_StructElement_CountChildren(element) {
+        let child = bindings.FPDF_StructElement_GetChildAtIndex(element, index);
+
+        if !child.is_null() {
+            collect_marked_content_ids(child, bindings, result);
+        }
+    }
+}
+
+/// Returns every character in the given text page, in content stream order, paired with the
+/// marked content ID of the page object that defines it. Characters generated by Pdfium,
+/// such as inferred spaces and line breaks, inherit the marked content ID of the
+/// preceding character.
+pub(crate) fn chars_with_marked_content_ids(
+    text_page: FPDF_TEXTPAGE,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Vec<(c_int, char)> {
+    let mut result = Vec::new();
+
+    let mut current_id = -1;
+
+    for index in 0..bindings.FPDFText_CountChars(text_page) {
+        let object = bindings.FPDFText_GetTextObject(text_page, index);
+
+        if !object.is_null() {
+            current_id = bindings.FPDFPageObj_GetMarkedContentID(object);
+        }
+
+        if let Some(char) = char::from_u32(bindings.FPDFText_GetUnicode(text_page, index)) {
+            result.push((current_id, char));
+        }
+    }
+
+    result
+}
+
+/// Assembles the given characters into a single string, following the order of the given
+/// marked content IDs. Each inner list of IDs corresponds to a single structure element;
+/// text within a structure element is joined by spaces, and text from separate structure
+/// elements is separated by line breaks.
+///
+/// Characters not marked as content, such as artifacts, are omitted. Characters whose
+/// marked content IDs are not referenced by the structure tree are appended at the end,
+/// in content stream order.
+pub(crate) fn text_in_structure_order(elements: &[Vec<c_int>], chars: &[(c_int, char)]) -> String {
+    let mut text_by_id: HashMap<c_int, String> = HashMap::new();
+
+    let mut unreferenced_ids = Vec::new();
+
+    for (id, char) in chars.iter() {
+        if *id < 0 {
+            continue;
+        }
+
+        text_by_id
+            .entry(*id)
+            .or_insert_with(|| {
+                if !elements.iter().any(|ids| ids.contains(id)) {
+                    unreferenced_ids.push(*id);
+                }
+
+                String::new()
+            })
+            .push(*char);
+    }
+
+    let mut lines = Vec::new();
+
+    for ids in elements
+        .iter()
+        .map(|ids| ids.as_slice())
+        .chain(unreferenced_ids.iter().map(std::slice::from_ref))
+    {
+        let line = ids
+            .iter()
+            .filter_map(|id| text_by_id.remove(id))
+            .map(|text| normalize_whitespace(&text))
+            .filter(|text| !text.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        if !line.is_empty() {
+            lines.push(line);
+        }
+    }
+
+    lines.join("\n")
+}
+
+/// Assembles the given text runs into a single string, ordering them geometrically: top to
+/// bottom, then left to right within each line. Text runs whose vertical midpoints fall within
+/// the vertical extent of the first run on a line are considered part of that line.
+pub(crate) fn text_in_geometric_order(runs: Vec<(String, PdfRect)>) -> String {
+    let mut runs = runs
+        .into_iter()
+        .map(|(text, bounds)| (normalize_whitespace(&text), bounds))
+        .filter(|(text, _)| !text.is_empty())
+        .collect::<Vec<_>>();
+
+    runs.sort_by(|(_, a), (_, b)| {
+        b.top
+            .value
+            .partial_cmp(&a.top.value)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let mut lines: Vec<(PdfRect, Vec<(String, PdfRect)>)> = Vec::new();
+
+    for (text, bounds) in runs {
+        let middle = (bounds.top.value + bounds.bottom.value) / 2.0;
+
+        match lines.last_mut() {
+            Some((line_bounds, line))
+                if middle <= line_bounds.top.value && middle >= line_bounds.bottom.value =>
+            {
+                line.push((text, bounds));
+            }
+            _ => lines.push((bounds, vec![(text, bounds)])),
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(_, mut line)| {
+            line.sort_by(|(_, a), (_, b)| {
+                a.left
+                    .value
+                    .partial_cmp(&b.left.value)
+                    .unwrap_or(Ordering::Equal)
+            });
+
+            line.into_iter()
+                .map(|(text, _)| text)
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Collapses all runs of whitespace in the given text into single spaces, trimming
+/// leading and trailing whitespace.
+pub(crate) fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::text::reading_order::*;
+
+    #[test]
+    fn test_text_in_structure_order() {
+        // Two columns, defined in the content stream in an interleaved order.
+
+        let chars = "Left1 Right1\r\nLeft2 Right2"
+            .chars()
+            .enumerate()
+            .map(|(index, char)| {
+                let id = match index {
+                    0..=5 => 0,
+                    6..=13 => 2,
+                    14..=19 => 1,
+                    _ => 3,
+                };
+
+                (id, char)
+            })
+            .chain("Footer".chars().map(|char| (-1, char)))
+            .chain(" Extra".chars().map(|char| (7, char)))
+            .collect::<Vec<_>>();
+
+        let text = text_in_structure_order(&[vec![0, 1], vec![2, 3]], &chars);
+
+        assert_eq!(text, "Left1 Left2\nRight1 Right2\nExtra");
+    }
+
+    #[test]
+    fn test_text_in_geometric_order() {
+        let runs = vec![
+            (
+                "second line".to_string(),
+                PdfRect::new_from_values(680.0, 50.0, 690.0, 200.0),
+            ),
+            (
+                "right".to_string(),
+                PdfRect::new_from_values(700.0, 300.0, 712.0, 400.0),
+            ),
+            (
+                "left".to_string(),
+                PdfRect::new_from_values(701.0, 50.0, 711.0, 200.0),
+            ),
+        ];
+
+        assert_eq!(text_in_geometric_order(runs), "left right\nsecond line");
+    }
+}
@@ -0,0 +1,235 @@
+//! Defines the [PdfTextRegion] enum, an area of a `PdfPage` from which text can be extracted
+//! using the `PdfPageText::text_in_region()` function.
+
+use crate::bindgen::FPDF_TEXTPAGE;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::quad_points::PdfQuadPoints;
+use crate::pdf::rect::PdfRect;
+use std::cmp::Ordering;
+use std::os::raw::c_int;
+
+/// An area of a `PdfPage` from which text can be extracted using the
+/// `PdfPageText::text_in_region()` function.
+#[derive(Debug, Clone)]
+pub enum PdfTextRegion {
+    /// An axis-aligned rectangle. Characters are selected by Pdfium, in the same way as
+    /// by the `PdfPageText::inside_rect()` function.
+    Rect(PdfRect),
+
+    /// One or more quadrilaterals, such as the attachment points of a highlight annotation.
+    /// A character is selected if the center of its bounding box lies within any of the
+    /// quadrilaterals. The vertices of each quadrilateral may be given in any order.
+    Quads(Vec<PdfQuadPoints>),
+
+    /// A closed polygon, which need not be convex, with vertices given in order around its
+    /// edge. A character is selected if the center of its bounding box lies within the polygon.
+    Polygon(Vec<(PdfPoints, PdfPoints)>),
+}
+
+impl PdfTextRegion {
+    /// Returns the polygons outlining this [PdfTextRegion], as sequences of vertices in order
+    /// around their edges.
+    pub(crate) fn polygons(&self) -> Vec<Vec<(f32, f32)>> {
+        match self {
+            PdfTextRegion::Rect(rect) => vec![vec![
+                (rect.left.value, rect.bottom.value),
+                (rect.right.value, rect.bottom.value),
+                (rect.right.value, rect.top.value),
+                (rect.left.value, rect.top.value),
+            ]],
+            PdfTextRegion::Quads(quads) => quads.iter().map(quad_polygon).collect(),
+            PdfTextRegion::Polygon(points) => {
+                vec![points.iter().map(|(x, y)| (x.value, y.value)).collect()]
+            }
+        }
+    }
+
+    /// Returns the text of the characters in the given text page whose centers lie within
+    /// this [PdfTextRegion], in the order in which they are defined in the document.
+    pub(crate) fn text_in_text_page(
+        &self,
+        text_page: FPDF_TEXTPAGE,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> String {
+        let polygons = self.polygons();
+
+        let chars = (0..bindings.FPDFText_CountChars(text_page))
+            .filter_map(|index| {
+                let char = char::from_u32(bindings.FPDFText_GetUnicode(text_page, index))?;
+
+                Some((char, char_center(text_page, index, bindings)))
+            })
+            .collect::<Vec<_>>();
+
+        text_in_polygons(&chars, &polygons)
+    }
+}
+
+impl From<PdfRect> for PdfTextRegion {
+    #[inline]
+    fn from(rect: PdfRect) -> Self {
+        PdfTextRegion::Rect(rect)
+    }
+}
+
+impl From<PdfQuadPoints> for PdfTextRegion {
+    #[inline]
+    fn from(quad: PdfQuadPoints) -> Self {
+        PdfTextRegion::Quads(vec![quad])
+    }
+}
+
+/// Returns the center of the bounding box of the character at the given index, or `None`
+/// if the character has no bounding box, as is the case for spaces and line breaks
+/// generated by Pdfium.
+fn char_center(
+    text_page: FPDF_TEXTPAGE,
+    index: c_int,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Option<(f32, f32)> {
+    if bindings.FPDFText_IsGenerated(text_page, index) == 1 {
+        return None;
+    }
+
+    let mut left = 0.0;
+    let mut right = 0.0;
+    let mut bottom = 0.0;
+    let mut top = 0.0;
+
+    if !bindings.is_true(bindings.FPDFText_GetCharBox(
+        text_page,
+        index,
+        &mut left,
+        &mut right,
+        &mut bottom,
+        &mut top,
+    )) {
+        return None;
+    }
+
+    if right <= left || top <= bottom {
+        return None;
+    }
+
+    Some((((left + right) / 2.0) as f32, ((bottom + top) / 2.0) as f32))
+}
+
+/// Returns the vertices of the given quadrilateral in order around its edge. Quad points
+/// are stored in different vertex orders by different PDF producers, so the vertices are
+/// sorted by their angle around the quadrilateral's center.
+fn quad_polygon(quad: &PdfQuadPoints) -> Vec<(f32, f32)> {
+    let mut points = vec![
+        (quad.x1.value, quad.y1.value),
+        (quad.x2.value, quad.y2.value),
+        (quad.x3.value, quad.y3.value),
+        (quad.x4.value, quad.y4.value),
+    ];
+
+    let center_x = points.iter().map(|(x, _)| x).sum::<f32>() / 4.0;
+
+    let center_y = points.iter().map(|(_, y)| y).sum::<f32>() / 4.0;
+
+    let angle = |(x, y): &(f32, f32)| (y - center_y).atan2(x - center_x);
+
+    points.sort_by(|a, b| angle(a).partial_cmp(&angle(b)).unwrap_or(Ordering::Equal));
+
+    points
+}
+
+/// Returns `true` if the given point lies within the given polygon, using the even-odd rule.
+pub(crate) fn is_point_in_polygon((x, y): (f32, f32), polygon: &[(f32, f32)]) -> bool {
+    let mut is_inside = false;
+
+    for (index, (x1, y1)) in polygon.iter().enumerate() {
+        let (x2, y2) = polygon[(index + 1) % polygon.len()];
+
+        if (*y1 > y) != (y2 > y) && x < x1 + (y - y1) * (x2 - x1) / (y2 - y1) {
+            is_inside = !is_inside;
+        }
+    }
+
+    is_inside
+}
+
+/// Returns the text of the given characters, in order, whose centers lie within any of the
+/// given polygons. Characters without a center, such as generated spaces and line breaks,
+/// are included only when they separate two selected characters.
+pub(crate) fn text_in_polygons(
+    chars: &[(char, Option<(f32, f32)>)],
+    polygons: &[Vec<(f32, f32)>],
+) -> String {
+    let mut result = String::new();
+
+    let mut pending = String::new();
+
+    for (char, center) in chars {
+        match center {
+            Some(center) => {
+                if polygons
+                    .iter()
+                    .any(|polygon| is_point_in_polygon(*center, polygon))
+                {
+                    if !result.is_empty() {
+                        result.push_str(&pending);
+                    }
+
+                    result.push(*char);
+                }
+
+                pending.clear();
+            }
+            None => pending.push(*char),
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::text::region::*;
+
+    #[test]
+    fn test_quad_vertex_order() {
+        // The same rotated square, given in counter-clockwise and in top-left, top-right,
+        // bottom-left, bottom-right order.
+
+        let counter_clockwise =
+            PdfQuadPoints::new_from_values(5.0, 0.0, 10.0, 5.0, 5.0, 10.0, 0.0, 5.0);
+
+        let z_order = PdfQuadPoints::new_from_values(0.0, 5.0, 5.0, 10.0, 5.0, 0.0, 10.0, 5.0);
+
+        for quad in [counter_clockwise, z_order] {
+            let polygon = quad_polygon(&quad);
+
+            assert!(is_point_in_polygon((5.0, 5.0), &polygon));
+            assert!(is_point_in_polygon((7.0, 4.0), &polygon));
+            assert!(!is_point_in_polygon((1.0, 1.0), &polygon));
+        }
+    }
+
+    #[test]
+    fn test_text_in_polygons() {
+        let chars = [
+            ('a', Some((1.0, 1.0))),
+            (' ', None),
+            ('b', Some((3.0, 1.0))),
+            ('\r', None),
+            ('\n', None),
+            ('c', Some((1.0, 5.0))),
+            (' ', None),
+            ('d', Some((9.0, 5.0))),
+        ];
+
+        // Two quads covering "b" on the first line and "c" on the second.
+
+        let polygons = PdfTextRegion::Quads(vec![
+            PdfQuadPoints::new_from_values(2.0, 2.0, 4.0, 2.0, 2.0, 0.0, 4.0, 0.0),
+            PdfQuadPoints::new_from_values(0.0, 6.0, 2.0, 6.0, 0.0, 4.0, 2.0, 4.0),
+        ])
+        .polygons();
+
+        assert_eq!(text_in_polygons(&chars, &polygons), "b\r\nc");
+    }
+}
@@ -4,6 +4,7 @@
 use crate::bindgen::FS_RECTF;
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::PdfiumError;
+use crate::pdf::document::page::text::chars::PdfPageTextCharIndex;
 use crate::pdf::document::page::text::segment::PdfPageTextSegment;
 use crate::pdf::document::page::text::PdfPageText;
 use crate::pdf::rect::PdfRect;
@@ -120,6 +121,16 @@ impl<'a> PdfPageTextSegments<'a> {
         .map(|rect| PdfPageTextSegment::from_pdfium(self.text, rect))
     }
 
+    /// Returns the range of indices of the characters spanned by this [PdfPageTextSegments]
+    /// collection within the containing `PdfPageText` collection. For a search result, this is
+    /// the range of characters matching the search target.
+    #[inline]
+    pub fn char_range(&self) -> Range<PdfPageTextCharIndex> {
+        let start = self.start.max(0) as PdfPageTextCharIndex;
+
+        start..start + self.characters.max(0) as PdfPageTextCharIndex
+    }
+
     /// Returns an iterator over all the text segments in this [PdfPageTextSegments] collection.
     ///
     /// Pdfium automatically merges smaller text boxes into larger text segments if all
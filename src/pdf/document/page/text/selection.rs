@@ -0,0 +1,424 @@
+//! Defines the [PdfTextSelection] struct, a contiguous range of characters selected from
+//! a `PdfPageText` collection, along with the areas of the page the selection occupies.
+
+use crate::bindgen::{FPDF_TEXTPAGE, FS_RECTF};
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::text::chars::PdfPageTextCharIndex;
+use crate::pdf::document::page::text::PdfPageText;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::quad_points::PdfQuadPoints;
+use crate::pdf::rect::PdfRect;
+use crate::utils::mem::create_sized_buffer;
+use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use bytemuck::cast_slice;
+use std::f32::consts::{FRAC_PI_2, PI};
+use std::ops::Range;
+use std::os::raw::c_int;
+
+/// The largest difference in rotation angle, in radians, between two consecutive characters
+/// for them to be highlighted by the same quadrilateral.
+const ANGLE_TOLERANCE: f32 = 0.01;
+
+/// The largest gap between two consecutive characters on the same line, as a multiple of the
+/// line height, for them to be highlighted by the same quadrilateral.
+const RUN_GAP: f32 = 2.0;
+
+/// A contiguous range of characters selected from a `PdfPageText` collection, as returned
+/// by the `PdfPageText::select()` and `PdfPageText::select_between_points()` functions.
+///
+/// Characters are selected in the order in which they are stored in the text page. For
+/// right-to-left scripts, this is the logical reading order rather than the visual order,
+/// so a selection made by dragging across right-to-left text covers the same characters
+/// a user would expect, irrespective of the direction of the drag.
+///
+/// A [PdfTextSelection] owns its data and remains valid after the `PdfPageText` it was
+/// created from is dropped.
+#[derive(Debug, Clone)]
+pub struct PdfTextSelection {
+    chars: Range<PdfPageTextCharIndex>,
+    text: String,
+    rects: Vec<PdfRect>,
+    quads: Vec<PdfQuadPoints>,
+}
+
+impl PdfTextSelection {
+    /// Creates a new [PdfTextSelection] covering the characters in the given range.
+    pub(crate) fn from_pdfium(text: &PdfPageText, chars: Range<PdfPageTextCharIndex>) -> Self {
+        if chars.is_empty() {
+            return PdfTextSelection {
+                chars,
+                text: String::new(),
+                rects: Vec::new(),
+                quads: Vec::new(),
+            };
+        }
+
+        let handle = *text.handle();
+
+        let bindings = text.bindings();
+
+        PdfTextSelection {
+            text: selected_text(handle, &chars, bindings),
+            rects: text
+                .segments_subset(chars.start, chars.end - chars.start)
+                .iter()
+                .map(|segment| segment.bounds())
+                .collect(),
+            quads: quads_for_chars(handle, chars.clone(), bindings),
+            chars,
+        }
+    }
+
+    /// Returns the range of indices of the selected characters within the containing
+    /// `PdfPageText` collection, for use with the `PdfPageText::chars()` function.
+    #[inline]
+    pub fn char_range(&self) -> Range<PdfPageTextCharIndex> {
+        self.chars.clone()
+    }
+
+    /// Returns `true` if this [PdfTextSelection] contains no characters.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// Returns the selected text, including any spaces and line breaks generated by Pdfium
+    /// between the selected characters.
+    #[inline]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Returns the axis-aligned rectangles occupied by the selected characters. Pdfium merges
+    /// the bounding boxes of adjacent characters that share a line and text style into a
+    /// single rectangle.
+    ///
+    /// Rotated characters are bounded by their axis-aligned bounding boxes, which may be
+    /// considerably larger than the characters themselves. Use the [PdfTextSelection::quads()]
+    /// function to highlight rotated text precisely.
+    #[inline]
+    pub fn rects(&self) -> &[PdfRect] {
+        self.rects.as_slice()
+    }
+
+    /// Returns the quadrilaterals occupied by the selected characters, one for each run of
+    /// adjacent characters that share a line and rotation angle. Each quadrilateral follows
+    /// the rotation of the text it covers, with its vertices ordered as for
+    /// `PdfQuadPoints::from_rect()`: top left, top right, bottom left, bottom right,
+    /// relative to the text. The quadrilaterals can be passed directly to the
+    /// `PdfPageAnnotationAttachmentPoints` of a highlight annotation.
+    #[inline]
+    pub fn quads(&self) -> &[PdfQuadPoints] {
+        self.quads.as_slice()
+    }
+}
+
+/// A single selected character, with the geometry needed to highlight it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PdfSelectionChar {
+    pub(crate) angle: f32,
+    pub(crate) origin: (f32, f32),
+    pub(crate) bounds: PdfRect,
+}
+
+impl PdfSelectionChar {
+    /// Returns the geometry of the character at the given index, or `None` if the character
+    /// has no area, as is the case for spaces and line breaks generated by Pdfium.
+    fn from_pdfium(
+        text_page: FPDF_TEXTPAGE,
+        index: c_int,
+        bindings: &dyn PdfiumLibraryBindings,
+    ) -> Option<Self> {
+        let mut bounds = FS_RECTF {
+            left: 0.0,
+            top: 0.0,
+            right: 0.0,
+            bottom: 0.0,
+        };
+
+        if !bindings.is_true(bindings.FPDFText_GetLooseCharBox(text_page, index, &mut bounds)) {
+            return None;
+        }
+
+        let angle = bindings.FPDFText_GetCharAngle(text_page, index);
+
+        if angle < 0.0 {
+            return None;
+        }
+
+        let mut x = 0.0;
+        let mut y = 0.0;
+
+        let _ = bindings.FPDFText_GetCharOrigin(text_page, index, &mut x, &mut y);
+
+        let char = PdfSelectionChar {
+            angle,
+            origin: (x as f32, y as f32),
+            bounds: PdfRect::new_from_values(bounds.bottom, bounds.left, bounds.top, bounds.right),
+        };
+
+        if char.bounds.width().value > 0.0 && char.bounds.height().value > 0.0 {
+            Some(char)
+        } else {
+            None
+        }
+    }
+
+    /// Returns this character's box as ranges along, and perpendicular to, a baseline
+    /// rotated counter-clockwise by the given angle.
+    fn extents(&self, angle: f32) -> (Range<f32>, Range<f32>) {
+        let (sin, cos) = angle.sin_cos();
+
+        let PdfRect {
+            bottom,
+            left,
+            top,
+            right,
+        } = self.bounds;
+
+        let (bottom, left, top, right) = (bottom.value, left.value, top.value, right.value);
+
+        if is_axis_aligned(angle) {
+            // The character's box coincides with its axis-aligned bounding box.
+
+            let corners = [(left, bottom), (right, bottom), (right, top), (left, top)];
+
+            let along = corners.iter().map(|(x, y)| x * cos + y * sin);
+
+            let across = corners.iter().map(|(x, y)| y * cos - x * sin);
+
+            return (min_max(along), min_max(across));
+        }
+
+        // Pdfium only reports the axis-aligned bounding box of a rotated character.
+        // Recover the width and height of the character's own box from the size of
+        // its bounding box, falling back to the character's origin when the angle is
+        // too close to 45 degrees for the sizes to be told apart.
+
+        let (sin_abs, cos_abs) = (sin.abs(), cos.abs());
+
+        let (bounds_width, bounds_height) = (right - left, top - bottom);
+
+        let (centre_x, centre_y) = ((left + right) / 2.0, (bottom + top) / 2.0);
+
+        let centre_along = centre_x * cos + centre_y * sin;
+
+        let centre_across = centre_y * cos - centre_x * sin;
+
+        let determinant = cos_abs * cos_abs - sin_abs * sin_abs;
+
+        let (width, height) = if determinant.abs() >= 0.25 {
+            (
+                (bounds_width * cos_abs - bounds_height * sin_abs) / determinant,
+                (bounds_height * cos_abs - bounds_width * sin_abs) / determinant,
+            )
+        } else {
+            let origin_along = self.origin.0 * cos + self.origin.1 * sin;
+
+            let width = 2.0 * (centre_along - origin_along).abs();
+
+            (width, (bounds_width - width * cos_abs) / sin_abs)
+        };
+
+        let (half_width, half_height) = (width.max(0.0) / 2.0, height.max(0.0) / 2.0);
+
+        (
+            centre_along - half_width..centre_along + half_width,
+            centre_across - half_height..centre_across + half_height,
+        )
+    }
+}
+
+/// Returns the quadrilaterals covering the characters in the given range of the given
+/// text page, merging adjacent characters that share a line and rotation angle.
+pub(crate) fn quads_for_chars(
+    text_page: FPDF_TEXTPAGE,
+    chars: Range<PdfPageTextCharIndex>,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> Vec<PdfQuadPoints> {
+    let chars = chars
+        .filter_map(|index| PdfSelectionChar::from_pdfium(text_page, index as c_int, bindings))
+        .collect::<Vec<_>>();
+
+    selection_quads(&chars)
+}
+
+/// Returns the quadrilaterals covering the given characters, in text page order, merging
+/// adjacent characters that share a line and rotation angle.
+pub(crate) fn selection_quads(chars: &[PdfSelectionChar]) -> Vec<PdfQuadPoints> {
+    let mut quads = Vec::new();
+
+    // The run currently being built: its angle, and its extents along and across its baseline.
+
+    let mut run: Option<(f32, Range<f32>, Range<f32>)> = None;
+
+    for char in chars {
+        if let Some((angle, along, across)) = run.as_mut() {
+            if angle_difference(*angle, char.angle) <= ANGLE_TOLERANCE {
+                let (char_along, char_across) = char.extents(*angle);
+
+                let centre_across = (char_across.start + char_across.end) / 2.0;
+
+                let gap = (char_along.start - along.end).max(along.start - char_along.end);
+
+                let line_height = across.end - across.start;
+
+                if across.contains(&centre_across) && gap <= line_height * RUN_GAP {
+                    along.start = along.start.min(char_along.start);
+                    along.end = along.end.max(char_along.end);
+                    across.start = across.start.min(char_across.start);
+                    across.end = across.end.max(char_across.end);
+
+                    continue;
+                }
+            }
+        }
+
+        quads.extend(
+            run.take()
+                .map(|(angle, along, across)| quad(angle, along, across)),
+        );
+
+        let (along, across) = char.extents(char.angle);
+
+        run = Some((char.angle, along, across));
+    }
+
+    quads.extend(run.map(|(angle, along, across)| quad(angle, along, across)));
+
+    quads
+}
+
+/// Returns the quadrilateral with the given extents along, and perpendicular to, a baseline
+/// rotated counter-clockwise by the given angle.
+fn quad(angle: f32, along: Range<f32>, across: Range<f32>) -> PdfQuadPoints {
+    let (sin, cos) = angle.sin_cos();
+
+    let point = |along: f32, across: f32| {
+        (
+            PdfPoints::new(along * cos - across * sin),
+            PdfPoints::new(along * sin + across * cos),
+        )
+    };
+
+    let (x1, y1) = point(along.start, across.end);
+    let (x2, y2) = point(along.end, across.end);
+    let (x3, y3) = point(along.start, across.start);
+    let (x4, y4) = point(along.end, across.start);
+
+    PdfQuadPoints::new(x1, y1, x2, y2, x3, y3, x4, y4)
+}
+
+/// Returns `true` if the given angle, in radians, is a multiple of 90 degrees.
+#[inline]
+fn is_axis_aligned(angle: f32) -> bool {
+    let remainder = angle.rem_euclid(FRAC_PI_2);
+
+    remainder <= ANGLE_TOLERANCE || FRAC_PI_2 - remainder <= ANGLE_TOLERANCE
+}
+
+/// Returns the absolute difference between the given angles, in radians, taking
+/// wrap-around at a full turn into account.
+#[inline]
+fn angle_difference(a: f32, b: f32) -> f32 {
+    let difference = (a - b).rem_euclid(2.0 * PI);
+
+    difference.min(2.0 * PI - difference)
+}
+
+/// Returns the range spanned by the given values.
+#[inline]
+fn min_max(values: impl Iterator<Item = f32>) -> Range<f32> {
+    values.fold(f32::MAX..f32::MIN, |range, value| {
+        range.start.min(value)..range.end.max(value)
+    })
+}
+
+/// Returns the text of the characters in the given range.
+fn selected_text(
+    text_page: FPDF_TEXTPAGE,
+    chars: &Range<PdfPageTextCharIndex>,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> String {
+    let count = (chars.end - chars.start) as c_int;
+
+    // FPDFText_GetText() requires space for a trailing terminator.
+
+    let mut buffer = create_sized_buffer::<u16>(count as usize + 1);
+
+    let written =
+        bindings.FPDFText_GetText(text_page, chars.start as c_int, count, buffer.as_mut_ptr());
+
+    if written <= 0 {
+        return String::new();
+    }
+
+    buffer.truncate(written as usize);
+
+    get_string_from_pdfium_utf16le_bytes(cast_slice(buffer.as_slice()).to_vec()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::text::selection::*;
+
+    fn assert_points_eq(actual: (PdfPoints, PdfPoints), expected: (f32, f32)) {
+        assert!(
+            (actual.0.value - expected.0).abs() < 0.01
+                && (actual.1.value - expected.1).abs() < 0.01,
+            "{:?} != {:?}",
+            (actual.0.value, actual.1.value),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_selection_quads_merges_runs() {
+        let char = |left: f32, bottom: f32| PdfSelectionChar {
+            angle: 0.0,
+            origin: (left, bottom + 2.0),
+            bounds: PdfRect::new_from_values(bottom, left, bottom + 10.0, left + 5.0),
+        };
+
+        // Two characters on one line, then one character on the next line.
+
+        let quads = selection_quads(&[char(100.0, 700.0), char(105.0, 700.0), char(100.0, 688.0)]);
+
+        assert_eq!(quads.len(), 2);
+
+        assert_points_eq((quads[0].x1, quads[0].y1), (100.0, 710.0));
+        assert_points_eq((quads[0].x4, quads[0].y4), (110.0, 700.0));
+        assert_points_eq((quads[1].x1, quads[1].y1), (100.0, 698.0));
+    }
+
+    #[test]
+    fn test_selection_quads_follow_rotation() {
+        // A 10 x 4 character rotated 30 degrees counter-clockwise about its origin at (0, 0).
+
+        let angle = 30.0_f32.to_radians();
+
+        let (sin, cos) = angle.sin_cos();
+
+        let corners = [(0.0, 0.0), (10.0, 0.0), (10.0, 4.0), (0.0, 4.0)]
+            .iter()
+            .map(|(u, v)| (u * cos - v * sin, u * sin + v * cos))
+            .collect::<Vec<_>>();
+
+        let xs = min_max(corners.iter().map(|(x, _)| *x));
+
+        let ys = min_max(corners.iter().map(|(_, y)| *y));
+
+        let quads = selection_quads(&[PdfSelectionChar {
+            angle,
+            origin: (0.0, 0.0),
+            bounds: PdfRect::new_from_values(ys.start, xs.start, ys.end, xs.end),
+        }]);
+
+        assert_eq!(quads.len(), 1);
+
+        assert_points_eq((quads[0].x3, quads[0].y3), corners[0]);
+        assert_points_eq((quads[0].x4, quads[0].y4), corners[1]);
+        assert_points_eq((quads[0].x2, quads[0].y2), corners[2]);
+        assert_points_eq((quads[0].x1, quads[0].y1), corners[3]);
+    }
+}
@@ -0,0 +1,209 @@
+//! Defines the [PdfTextStyleRun] struct, a run of consecutive characters in a `PdfPageText`
+//! collection that share the same [PdfTextStyle].
+
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::page::object::text::PdfPageTextRenderMode;
+use crate::pdf::document::page::text::char::PdfPageTextChar;
+use crate::pdf::document::page::text::chars::PdfPageTextCharIndex;
+use crate::pdf::font::PdfFontWeight;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+use std::ops::Range;
+
+/// The visual style applied to a character in a `PdfPageText` collection.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextStyle {
+    font_name: String,
+    font_weight: Option<PdfFontWeight>,
+    font_size: PdfPoints,
+    fill_color: Option<PdfColor>,
+    render_mode: Option<PdfPageTextRenderMode>,
+}
+
+impl PdfTextStyle {
+    /// Returns the style applied to the given character.
+    pub(crate) fn from_char(char: &PdfPageTextChar) -> Self {
+        PdfTextStyle {
+            font_name: char.font_name(),
+            font_weight: char.font_weight(),
+            font_size: char.scaled_font_size(),
+            fill_color: char.fill_color().ok(),
+            render_mode: char.render_mode().ok(),
+        }
+    }
+
+    /// Returns the name of the font in this [PdfTextStyle].
+    #[inline]
+    pub fn font_name(&self) -> &str {
+        self.font_name.as_str()
+    }
+
+    /// Returns the weight of the font in this [PdfTextStyle], if known.
+    ///
+    /// Pdfium may not reliably return the correct value of this property for built-in fonts.
+    #[inline]
+    pub fn font_weight(&self) -> Option<PdfFontWeight> {
+        self.font_weight
+    }
+
+    /// Returns the font size in this [PdfTextStyle], taking any vertical scaling applied to
+    /// the characters into account.
+    #[inline]
+    pub fn font_size(&self) -> PdfPoints {
+        self.font_size
+    }
+
+    /// Returns the fill color in this [PdfTextStyle], if known.
+    #[inline]
+    pub fn fill_color(&self) -> Option<PdfColor> {
+        self.fill_color
+    }
+
+    /// Returns the text rendering mode in this [PdfTextStyle], if known.
+    #[inline]
+    pub fn render_mode(&self) -> Option<PdfPageTextRenderMode> {
+        self.render_mode
+    }
+}
+
+/// A run of consecutive characters in a `PdfPageText` collection that share the same
+/// [PdfTextStyle], as returned by the `PdfPageText::style_runs()` function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextStyleRun {
+    style: PdfTextStyle,
+    text: String,
+    bounds: PdfRect,
+    chars: Range<PdfPageTextCharIndex>,
+}
+
+impl PdfTextStyleRun {
+    /// Returns the [PdfTextStyle] shared by all characters in this [PdfTextStyleRun].
+    #[inline]
+    pub fn style(&self) -> &PdfTextStyle {
+        &self.style
+    }
+
+    /// Returns the text of this [PdfTextStyleRun], including any spaces and line breaks
+    /// generated by Pdfium following its characters. Concatenating the text of every run
+    /// on a page reproduces the page's text.
+    #[inline]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Returns the bounding box of this [PdfTextStyleRun], the union of the tight bounding
+    /// boxes of its characters. A run that spans several lines has a bounding box covering
+    /// all those lines.
+    #[inline]
+    pub fn bounds(&self) -> PdfRect {
+        self.bounds
+    }
+
+    /// Returns the range of indices of the characters in this [PdfTextStyleRun] within the
+    /// containing `PdfPageText` collection, for use with the `PdfPageText::chars()` function.
+    #[inline]
+    pub fn char_range(&self) -> Range<PdfPageTextCharIndex> {
+        self.chars.clone()
+    }
+}
+
+/// A single character in a text page, with the style needed to group it into runs.
+/// Characters generated by Pdfium, such as inferred spaces and line breaks, have no style.
+#[derive(Debug, Clone)]
+pub(crate) struct PdfStyledChar {
+    pub(crate) index: PdfPageTextCharIndex,
+    pub(crate) text: String,
+    pub(crate) style: Option<PdfTextStyle>,
+    pub(crate) bounds: Option<PdfRect>,
+}
+
+/// Groups the given characters, in text page order, into runs of consecutive characters
+/// sharing the same style. Characters without a style are added to the run that precedes them.
+pub(crate) fn group_chars_into_style_runs(chars: Vec<PdfStyledChar>) -> Vec<PdfTextStyleRun> {
+    let mut runs: Vec<PdfTextStyleRun> = Vec::new();
+
+    for char in chars {
+        match (runs.last_mut(), char.style) {
+            (Some(run), None) => {
+                run.text.push_str(&char.text);
+                run.chars.end = char.index + 1;
+            }
+            (Some(run), Some(style)) if run.style == style => {
+                run.text.push_str(&char.text);
+                run.chars.end = char.index + 1;
+
+                if let Some(bounds) = char.bounds {
+                    run.bounds = if run.bounds == PdfRect::ZERO {
+                        bounds
+                    } else {
+                        run.bounds.union(&bounds)
+                    };
+                }
+            }
+            (_, Some(style)) => runs.push(PdfTextStyleRun {
+                style,
+                text: char.text,
+                bounds: char.bounds.unwrap_or(PdfRect::ZERO),
+                chars: char.index..char.index + 1,
+            }),
+            (None, None) => {
+                // Generated characters preceding the first styled character have no run to join.
+            }
+        }
+    }
+
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page::text::style_runs::*;
+
+    #[test]
+    fn test_group_chars_into_style_runs() {
+        let style = |font_name: &str, font_size: f32| PdfTextStyle {
+            font_name: font_name.to_string(),
+            font_weight: None,
+            font_size: PdfPoints::new(font_size),
+            fill_color: Some(PdfColor::BLACK),
+            render_mode: Some(PdfPageTextRenderMode::FilledUnstroked),
+        };
+
+        let chars = [
+            ("A", Some(style("Helvetica-Bold", 14.0))),
+            ("\r\n", None),
+            ("b", Some(style("Helvetica", 10.0))),
+            (" ", None),
+            ("c", Some(style("Helvetica", 10.0))),
+            ("d", Some(style("Helvetica", 12.0))),
+        ]
+        .iter()
+        .enumerate()
+        .map(|(index, (text, style))| PdfStyledChar {
+            index: index as PdfPageTextCharIndex,
+            text: text.to_string(),
+            style: style.clone(),
+            bounds: Some(PdfRect::new_from_values(
+                0.0,
+                index as f32,
+                1.0,
+                index as f32 + 1.0,
+            )),
+        })
+        .collect::<Vec<_>>();
+
+        let runs = group_chars_into_style_runs(chars);
+
+        assert_eq!(
+            runs.iter().map(|run| run.text()).collect::<Vec<_>>(),
+            vec!["A\r\n", "b c", "d"]
+        );
+
+        assert_eq!(runs[0].char_range(), 0..2);
+        assert_eq!(runs[1].style().font_name(), "Helvetica");
+        assert_eq!(
+            runs[1].bounds(),
+            PdfRect::new_from_values(0.0, 2.0, 1.0, 5.0)
+        );
+    }
+}
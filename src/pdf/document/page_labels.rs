@@ -0,0 +1,562 @@
+//! Defines the [PdfPageLabelRange] struct, which assigns labels to a range of pages in a
+//! `PdfDocument`, and the [PdfPageLabelStyle] enum, which controls how the numeric portion
+//! of each label is written. Page labels are the logical page numbers displayed by PDF
+//! viewers in place of page indices, such as "iv" for a page in the front matter of a book.
+//!
+//! Pdfium can read the label of a single page but cannot write labels, so the document
+//! catalog's `/PageLabels` number tree is read and written at the syntax level.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{
+    decode_pdf_string, PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject,
+};
+use std::ops::RangeInclusive;
+
+/// The maximum depth of the `/PageLabels` number tree followed when reading page labels.
+const MAXIMUM_NUMBER_TREE_DEPTH: usize = 32;
+
+/// The numbering style used for the numeric portion of the labels in a [PdfPageLabelRange].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfPageLabelStyle {
+    /// Labels consist of the range's prefix alone, without a numeric portion.
+    None,
+
+    /// Decimal arabic numerals: 1, 2, 3, ...
+    Decimal,
+
+    /// Uppercase roman numerals: I, II, III, ...
+    UppercaseRoman,
+
+    /// Lowercase roman numerals: i, ii, iii, ...
+    LowercaseRoman,
+
+    /// Uppercase letters: A to Z for the first 26 pages, AA to ZZ for the next 26, and so on.
+    UppercaseLetters,
+
+    /// Lowercase letters: a to z for the first 26 pages, aa to zz for the next 26, and so on.
+    LowercaseLetters,
+}
+
+impl PdfPageLabelStyle {
+    #[inline]
+    pub(crate) fn from_pdf_name(name: &str) -> Option<Self> {
+        match name {
+            "D" => Some(PdfPageLabelStyle::Decimal),
+            "R" => Some(PdfPageLabelStyle::UppercaseRoman),
+            "r" => Some(PdfPageLabelStyle::LowercaseRoman),
+            "A" => Some(PdfPageLabelStyle::UppercaseLetters),
+            "a" => Some(PdfPageLabelStyle::LowercaseLetters),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn as_pdf_name(&self) -> Option<&'static str> {
+        match self {
+            PdfPageLabelStyle::None => None,
+            PdfPageLabelStyle::Decimal => Some("D"),
+            PdfPageLabelStyle::UppercaseRoman => Some("R"),
+            PdfPageLabelStyle::LowercaseRoman => Some("r"),
+            PdfPageLabelStyle::UppercaseLetters => Some("A"),
+            PdfPageLabelStyle::LowercaseLetters => Some("a"),
+        }
+    }
+
+    /// Returns the numeric portion of a label in this style for the given page number.
+    fn format(&self, number: u32) -> String {
+        match self {
+            PdfPageLabelStyle::None => String::new(),
+            PdfPageLabelStyle::Decimal => number.to_string(),
+            PdfPageLabelStyle::UppercaseRoman => to_roman(number),
+            PdfPageLabelStyle::LowercaseRoman => to_roman(number).to_lowercase(),
+            PdfPageLabelStyle::UppercaseLetters => to_letters(number),
+            PdfPageLabelStyle::LowercaseLetters => to_letters(number).to_lowercase(),
+        }
+    }
+}
+
+/// Assigns labels to the pages of a `PdfDocument` starting at a given page index and
+/// continuing until the start of the next range, or the end of the document.
+///
+/// Each label consists of an optional prefix followed by a page number written in the
+/// range's [PdfPageLabelStyle]. Numbering starts at 1 for the first page in the range unless
+/// a different first number is set, so a document can number its front matter "i, ii, iii"
+/// and restart at "1" for its first chapter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfPageLabelRange {
+    start_index: PdfPageIndex,
+    style: PdfPageLabelStyle,
+    prefix: Option<String>,
+    first_number: u32,
+}
+
+impl PdfPageLabelRange {
+    /// Creates a new [PdfPageLabelRange] starting at the given page index, numbering pages
+    /// in the given style from 1 with no prefix.
+    #[inline]
+    pub fn new(start_index: PdfPageIndex, style: PdfPageLabelStyle) -> Self {
+        PdfPageLabelRange {
+            start_index,
+            style,
+            prefix: None,
+            first_number: 1,
+        }
+    }
+
+    /// Sets the prefix written before the numeric portion of each label in this range,
+    /// for example "A-" to label the pages of an appendix "A-1, A-2, A-3".
+    #[inline]
+    pub fn set_prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_owned());
+
+        self
+    }
+
+    /// Sets the page number of the first page in this range. The default is 1.
+    #[inline]
+    pub fn set_first_number(mut self, number: u32) -> Self {
+        self.first_number = number.max(1);
+
+        self
+    }
+
+    /// Returns the index of the first page in this range.
+    #[inline]
+    pub fn start_index(&self) -> PdfPageIndex {
+        self.start_index
+    }
+
+    /// Returns the numbering style of the labels in this range.
+    #[inline]
+    pub fn style(&self) -> PdfPageLabelStyle {
+        self.style
+    }
+
+    /// Returns the prefix written before the numeric portion of each label in this range,
+    /// if any.
+    #[inline]
+    pub fn prefix(&self) -> Option<&str> {
+        self.prefix.as_deref()
+    }
+
+    /// Returns the page number of the first page in this range.
+    #[inline]
+    pub fn first_number(&self) -> u32 {
+        self.first_number
+    }
+
+    /// Returns the label this range assigns to the page at the given index, or `None` if
+    /// the page comes before the start of this range. The end of the range is determined
+    /// by the start of the next range, so it is not taken into account.
+    pub fn label(&self, index: PdfPageIndex) -> Option<String> {
+        if index < self.start_index {
+            return None;
+        }
+
+        let number = self
+            .first_number
+            .saturating_add((index - self.start_index) as u32);
+
+        Some(format!(
+            "{}{}",
+            self.prefix.as_deref().unwrap_or(""),
+            self.style.format(number)
+        ))
+    }
+
+    /// Returns a copy of this range starting at the given page index, with its first
+    /// number adjusted by the given amount.
+    fn moved(&self, start_index: PdfPageIndex, skipped: u32) -> Self {
+        PdfPageLabelRange {
+            start_index,
+            style: self.style,
+            prefix: self.prefix.clone(),
+            first_number: self.first_number.saturating_add(skipped),
+        }
+    }
+}
+
+/// Returns the given number written in roman numerals. Thousands beyond the first are
+/// written as repeated Ms.
+fn to_roman(number: u32) -> String {
+    const NUMERALS: &[(u32, &str)] = &[
+        (1000, "M"),
+        (900, "CM"),
+        (500, "D"),
+        (400, "CD"),
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+
+    let mut result = String::new();
+
+    let mut remaining = number;
+
+    for (value, numeral) in NUMERALS.iter() {
+        while remaining >= *value {
+            result.push_str(numeral);
+            remaining -= value;
+        }
+    }
+
+    result
+}
+
+/// Returns the given number written as uppercase letters: A to Z for 1 to 26, AA to ZZ
+/// for 27 to 52, and so on.
+fn to_letters(number: u32) -> String {
+    if number == 0 {
+        return String::new();
+    }
+
+    let letter = (b'A' + ((number - 1) % 26) as u8) as char;
+
+    std::iter::repeat(letter)
+        .take(((number - 1) / 26 + 1) as usize)
+        .collect()
+}
+
+/// Returns [PdfiumError::InvalidPageLabelRanges] if the given ranges do not start at the first
+/// page or are not in strictly increasing order of start index, or
+/// [PdfiumError::PageIndexOutOfBounds] if any range starts past the last of the given number
+/// of pages. An empty list of ranges is valid, and removes all page labels.
+pub(crate) fn validate_page_label_ranges(
+    ranges: &[PdfPageLabelRange],
+    page_count: PdfPageIndex,
+) -> Result<(), PdfiumError> {
+    match ranges.first() {
+        None => return Ok(()),
+        Some(first) if first.start_index != 0 => return Err(PdfiumError::InvalidPageLabelRanges),
+        _ => {}
+    }
+
+    if ranges
+        .windows(2)
+        .any(|pair| pair[0].start_index >= pair[1].start_index)
+    {
+        return Err(PdfiumError::InvalidPageLabelRanges);
+    }
+
+    if ranges.iter().any(|range| range.start_index >= page_count) {
+        return Err(PdfiumError::PageIndexOutOfBounds);
+    }
+
+    Ok(())
+}
+
+/// Returns the given ranges with their start indices shifted by the given offset, as needed
+/// when the pages they label are inserted after the given number of other pages.
+pub(crate) fn offset_page_labels(
+    ranges: &[PdfPageLabelRange],
+    offset: PdfPageIndex,
+) -> Vec<PdfPageLabelRange> {
+    ranges
+        .iter()
+        .map(|range| range.moved(range.start_index + offset, 0))
+        .collect()
+}
+
+/// Returns the ranges labelling the pages in the given range of page indices, renumbered so
+/// that the first page in the given range has index 0, as needed when those pages are
+/// copied into a new document. Each page keeps the label it had before.
+pub(crate) fn slice_page_labels(
+    ranges: &[PdfPageLabelRange],
+    pages: RangeInclusive<PdfPageIndex>,
+) -> Vec<PdfPageLabelRange> {
+    let first = *pages.start();
+
+    let mut sliced = Vec::new();
+
+    // The range containing the first page, if any, is moved to start at that page.
+
+    if let Some(containing) = ranges.iter().rev().find(|range| range.start_index <= first) {
+        sliced.push(containing.moved(0, (first - containing.start_index) as u32));
+    }
+
+    sliced.extend(
+        ranges
+            .iter()
+            .filter(|range| range.start_index > first && pages.contains(&range.start_index))
+            .map(|range| range.moved(range.start_index - first, 0)),
+    );
+
+    sliced
+}
+
+/// Returns the page label ranges in the `/PageLabels` number tree of the document catalog
+/// of the given PDF file, in order of start index.
+///
+/// Returns [PdfiumError::PageLabelsUnreadable] if the file cannot be parsed or is encrypted.
+pub(crate) fn read_page_labels(file: &[u8]) -> Result<Vec<PdfPageLabelRange>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(file).ok_or(PdfiumError::PageLabelsUnreadable)?;
+
+    let trailer = parsed
+        .trailer
+        .as_ref()
+        .ok_or(PdfiumError::PageLabelsUnreadable)?;
+
+    if trailer.get("Encrypt").is_some() {
+        return Err(PdfiumError::PageLabelsUnreadable);
+    }
+
+    let catalog = parsed.resolve(
+        trailer
+            .get("Root")
+            .ok_or(PdfiumError::PageLabelsUnreadable)?,
+    );
+
+    let mut ranges = Vec::new();
+
+    if let Some(tree) = catalog.get("PageLabels") {
+        collect_number_tree(&parsed, &parsed.resolve(tree), &mut ranges, 0);
+    }
+
+    ranges.sort_by_key(|range| range.start_index);
+    ranges.dedup_by_key(|range| range.start_index);
+
+    Ok(ranges)
+}
+
+/// Appends the page label ranges in the given number tree node and its descendants to the
+/// given list. Entries that are not valid page label dictionaries are skipped.
+fn collect_number_tree(
+    file: &PdfSyntaxFile,
+    node: &PdfSyntaxObject,
+    ranges: &mut Vec<PdfPageLabelRange>,
+    depth: usize,
+) {
+    if depth > MAXIMUM_NUMBER_TREE_DEPTH {
+        return;
+    }
+
+    if let Some(PdfSyntaxObject::Array(nums)) = node.get("Nums").map(|nums| file.resolve(nums)) {
+        for pair in nums.chunks_exact(2) {
+            let start_index = match &pair[0] {
+                PdfSyntaxObject::Number(key) if *key >= 0.0 && *key <= PdfPageIndex::MAX as f64 => {
+                    *key as PdfPageIndex
+                }
+                _ => continue,
+            };
+
+            let label = file.resolve(&pair[1]);
+
+            let style = match label.get("S").map(|style| file.resolve(style)) {
+                Some(PdfSyntaxObject::Name(name)) => {
+                    PdfPageLabelStyle::from_pdf_name(&name).unwrap_or(PdfPageLabelStyle::None)
+                }
+                _ => PdfPageLabelStyle::None,
+            };
+
+            let mut range = PdfPageLabelRange::new(start_index, style);
+
+            if let Some(PdfSyntaxObject::String(prefix)) =
+                label.get("P").map(|prefix| file.resolve(prefix))
+            {
+                range = range.set_prefix(&decode_pdf_string(&prefix));
+            }
+
+            if let Some(PdfSyntaxObject::Number(first_number)) = label
+                .get("St")
+                .map(|first_number| file.resolve(first_number))
+            {
+                if first_number >= 1.0 && first_number <= u32::MAX as f64 {
+                    range = range.set_first_number(first_number as u32);
+                }
+            }
+
+            ranges.push(range);
+        }
+    }
+
+    if let Some(PdfSyntaxObject::Array(kids)) = node.get("Kids").map(|kids| file.resolve(kids)) {
+        for kid in kids.iter() {
+            collect_number_tree(file, &file.resolve(kid), ranges, depth + 1);
+        }
+    }
+}
+
+/// Replaces the `/PageLabels` number tree of the document catalog of the given PDF file with
+/// one containing the given ranges, or removes it if no ranges are given, returning the
+/// updated file. The ranges should already have been validated.
+pub(crate) fn write_page_labels(
+    file: Vec<u8>,
+    ranges: &[PdfPageLabelRange],
+) -> Result<Vec<u8>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let root = match update.trailer().get("Root") {
+        Some(PdfSyntaxObject::Reference(number, _))
+            if update.trailer().get("Encrypt").is_none() =>
+        {
+            *number
+        }
+        _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+    };
+
+    let mut catalog = update
+        .get(root)
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if ranges.is_empty() {
+        catalog.remove("PageLabels");
+    } else {
+        let mut nums = Vec::with_capacity(ranges.len() * 2);
+
+        for range in ranges.iter() {
+            let mut label = PdfSyntaxObject::Dictionary(vec![]);
+
+            if let Some(style) = range.style.as_pdf_name() {
+                label.set("S", PdfSyntaxObject::name(style));
+            }
+
+            if let Some(prefix) = range.prefix.as_deref() {
+                label.set("P", PdfSyntaxObject::text(prefix));
+            }
+
+            if range.first_number != 1 {
+                label.set("St", PdfSyntaxObject::Number(range.first_number as f64));
+            }
+
+            nums.push(PdfSyntaxObject::Number(range.start_index as f64));
+            nums.push(label);
+        }
+
+        let tree = update.add(PdfSyntaxObject::Dictionary(vec![(
+            "Nums".to_owned(),
+            PdfSyntaxObject::Array(nums),
+        )]));
+
+        catalog.set("PageLabels", tree);
+    }
+
+    update.set(root, catalog);
+
+    Ok(update.write(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::page_labels::*;
+
+    const PAGE_LABELS_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R /PageLabels 3 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n\
+        3 0 obj\n<< /Kids [4 0 R 5 0 R] >>\nendobj\n\
+        4 0 obj\n<< /Nums [0 << /S /r >>] /Limits [0 0] >>\nendobj\n\
+        5 0 obj\n<< /Nums [4 << /S /D >> 9 << /S /A /P (App-) /St 3 >>] /Limits [4 9] >>\nendobj\n\
+        xref\n0 6\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 6 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    fn expected_ranges() -> Vec<PdfPageLabelRange> {
+        vec![
+            PdfPageLabelRange::new(0, PdfPageLabelStyle::LowercaseRoman),
+            PdfPageLabelRange::new(4, PdfPageLabelStyle::Decimal),
+            PdfPageLabelRange::new(9, PdfPageLabelStyle::UppercaseLetters)
+                .set_prefix("App-")
+                .set_first_number(3),
+        ]
+    }
+
+    #[test]
+    fn test_page_label_formatting() {
+        let ranges = expected_ranges();
+
+        assert_eq!(ranges[0].label(3), Some("iv".to_owned()));
+        assert_eq!(ranges[1].label(4), Some("1".to_owned()));
+        assert_eq!(ranges[1].label(3), None);
+        assert_eq!(ranges[2].label(9), Some("App-C".to_owned()));
+        assert_eq!(ranges[2].label(33), Some("App-AA".to_owned()));
+
+        assert_eq!(to_roman(1994), "MCMXCIV");
+        assert_eq!(to_letters(53), "AAA");
+        assert_eq!(
+            PdfPageLabelRange::new(0, PdfPageLabelStyle::None)
+                .set_prefix("Cover")
+                .label(0),
+            Some("Cover".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_read_and_write_page_labels() {
+        assert_eq!(
+            read_page_labels(PAGE_LABELS_PDF).unwrap(),
+            expected_ranges()
+        );
+
+        let ranges = vec![
+            PdfPageLabelRange::new(0, PdfPageLabelStyle::UppercaseRoman).set_first_number(2),
+            PdfPageLabelRange::new(2, PdfPageLabelStyle::None).set_prefix("Index"),
+        ];
+
+        let file = write_page_labels(PAGE_LABELS_PDF.to_vec(), &ranges).unwrap();
+
+        assert_eq!(read_page_labels(&file).unwrap(), ranges);
+
+        let file = write_page_labels(file, &[]).unwrap();
+
+        assert!(read_page_labels(&file).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_validate_page_label_ranges() {
+        assert!(validate_page_label_ranges(&expected_ranges(), 10).is_ok());
+        assert!(validate_page_label_ranges(&[], 0).is_ok());
+
+        assert!(matches!(
+            validate_page_label_ranges(&expected_ranges(), 9),
+            Err(PdfiumError::PageIndexOutOfBounds)
+        ));
+        assert!(matches!(
+            validate_page_label_ranges(&expected_ranges()[1..], 10),
+            Err(PdfiumError::InvalidPageLabelRanges)
+        ));
+        assert!(matches!(
+            validate_page_label_ranges(
+                &[
+                    PdfPageLabelRange::new(0, PdfPageLabelStyle::Decimal),
+                    PdfPageLabelRange::new(0, PdfPageLabelStyle::Decimal),
+                ],
+                10
+            ),
+            Err(PdfiumError::InvalidPageLabelRanges)
+        ));
+    }
+
+    #[test]
+    fn test_slice_and_offset_page_labels() {
+        let ranges = expected_ranges();
+
+        // Pages 2 to 5 are labelled iii, iv, 1, 2.
+
+        assert_eq!(
+            slice_page_labels(&ranges, 2..=5),
+            vec![
+                PdfPageLabelRange::new(0, PdfPageLabelStyle::LowercaseRoman).set_first_number(3),
+                PdfPageLabelRange::new(2, PdfPageLabelStyle::Decimal),
+            ]
+        );
+
+        assert_eq!(
+            slice_page_labels(&ranges, 9..=9),
+            vec![
+                PdfPageLabelRange::new(0, PdfPageLabelStyle::UppercaseLetters)
+                    .set_prefix("App-")
+                    .set_first_number(3)
+            ]
+        );
+
+        assert_eq!(offset_page_labels(&ranges[..1], 7)[0].start_index(), 7);
+    }
+}
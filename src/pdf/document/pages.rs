@@ -8,10 +8,13 @@ use crate::bindgen::{
 };
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::page::generation::PdfContentGenerations;
 use crate::pdf::document::page::index_cache::PdfPageIndexCache;
 use crate::pdf::document::page::object::group::PdfPageGroupObject;
 use crate::pdf::document::page::size::PdfPagePaperSize;
-use crate::pdf::document::page::{PdfPage, PdfPageContentRegenerationStrategy};
+use crate::pdf::document::page::{
+    PdfPage, PdfPageContentRegenerationStrategy, PdfPageRenderRotation,
+};
 use crate::pdf::document::PdfDocument;
 use crate::pdf::points::PdfPoints;
 use crate::pdf::rect::PdfRect;
@@ -20,6 +23,25 @@ use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
 use std::ops::{Range, RangeInclusive};
 use std::os::raw::{c_double, c_int, c_void};
 
+#[cfg(any(
+    feature = "pdfium_6043",
+    feature = "pdfium_6084",
+    feature = "pdfium_6110",
+    feature = "pdfium_6124",
+    feature = "pdfium_6164",
+    feature = "pdfium_6259",
+    feature = "pdfium_6295",
+    feature = "pdfium_6337",
+    feature = "pdfium_6406",
+    feature = "pdfium_6490",
+    feature = "pdfium_6555",
+    feature = "pdfium_6569",
+    feature = "pdfium_6611",
+    feature = "pdfium_6666",
+    feature = "pdfium_future"
+))]
+use std::os::raw::c_ulong;
+
 /// The zero-based index of a single [PdfPage] inside its containing [PdfPages] collection.
 pub type PdfPageIndex = u16;
 
@@ -98,6 +120,13 @@ impl<'a> PdfPages<'a> {
         self.bindings
     }
 
+    /// Returns the internal `FPDF_DOCUMENT` handle of the document containing this
+    /// [PdfPages] collection.
+    #[inline]
+    pub(crate) fn document_handle(&self) -> FPDF_DOCUMENT {
+        self.document_handle
+    }
+
     /// Returns the number of pages in this [PdfPages] collection.
     pub fn len(&self) -> PdfPageIndex {
         self.bindings.FPDF_GetPageCount(self.document_handle) as PdfPageIndex
@@ -248,6 +277,8 @@ impl<'a> PdfPages<'a> {
         );
 
         if let Ok(page) = result.as_ref() {
+            PdfContentGenerations::increment_for_document(self.document_handle);
+
             PdfPageIndexCache::insert_pages_at_index(self.document_handle, index, 1);
             PdfPageIndexCache::set_index_for_page(self.document_handle, page.page_handle(), index);
         }
@@ -276,6 +307,8 @@ impl<'a> PdfPages<'a> {
         self.bindings
             .FPDFPage_Delete(self.document_handle, index as c_int);
 
+        PdfContentGenerations::increment_for_document(self.document_handle);
+
         PdfPageIndexCache::delete_pages_at_index(self.document_handle, index, 1);
 
         Ok(())
@@ -303,6 +336,139 @@ impl<'a> PdfPages<'a> {
         Ok(())
     }
 
+    /// Rearranges the pages in this [PdfPages] collection into the given order in a single
+    /// operation. Each element of the given order is the current index of the page that should
+    /// be moved to that position; for example, an order of `[2, 0, 1]` moves the last page of a
+    /// three-page document to the front.
+    ///
+    /// Returns [PdfiumError::PageIndexOutOfBounds] if the given order contains an index
+    /// past the last page in this collection, or [PdfiumError::InvalidPageOrder] if it does
+    /// not contain every page index exactly once. The pages are left unchanged if the given
+    /// order is invalid.
+    #[cfg(any(
+        feature = "pdfium_6043",
+        feature = "pdfium_6084",
+        feature = "pdfium_6110",
+        feature = "pdfium_6124",
+        feature = "pdfium_6164",
+        feature = "pdfium_6259",
+        feature = "pdfium_6295",
+        feature = "pdfium_6337",
+        feature = "pdfium_6406",
+        feature = "pdfium_6490",
+        feature = "pdfium_6555",
+        feature = "pdfium_6569",
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    pub fn reorder(&mut self, new_order: &[PdfPageIndex]) -> Result<(), PdfiumError> {
+        let len = self.len();
+
+        if new_order.len() != len as usize {
+            return Err(PdfiumError::InvalidPageOrder);
+        }
+
+        let mut is_listed = vec![false; len as usize];
+
+        for index in new_order.iter() {
+            match is_listed.get_mut(*index as usize) {
+                Some(true) => return Err(PdfiumError::InvalidPageOrder),
+                Some(is_listed) => *is_listed = true,
+                None => return Err(PdfiumError::PageIndexOutOfBounds),
+            }
+        }
+
+        // Moving every page to the start of the document, in the new order, rearranges
+        // all pages in one call to Pdfium.
+
+        let indices = new_order
+            .iter()
+            .map(|index| *index as c_int)
+            .collect::<Vec<_>>();
+
+        if self.bindings.is_true(self.bindings.FPDF_MovePages(
+            self.document_handle,
+            indices.as_ptr(),
+            indices.len() as c_ulong,
+            0,
+        )) {
+            PdfContentGenerations::increment_for_document(self.document_handle);
+
+            PdfPageIndexCache::reorder_pages(self.document_handle, new_order);
+
+            Ok(())
+        } else {
+            Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ))
+        }
+    }
+
+    /// Sets the intrinsic rotation of every page in the given range of page indices
+    /// in this [PdfPages] collection to the given [PdfPageRenderRotation].
+    ///
+    /// Returns [PdfiumError::PageIndexOutOfBounds] if the range is empty or extends past
+    /// the last page in this collection, in which case no pages are rotated.
+    pub fn rotate_range(
+        &mut self,
+        range: RangeInclusive<PdfPageIndex>,
+        rotation: PdfPageRenderRotation,
+    ) -> Result<(), PdfiumError> {
+        self.validate_range(&range)?;
+
+        for index in range {
+            self.get(index)?.set_rotation(rotation);
+        }
+
+        Ok(())
+    }
+
+    /// Deletes every page in the given range of page indices from this [PdfPages] collection,
+    /// shuffling up all following pages.
+    ///
+    /// Returns [PdfiumError::PageIndexOutOfBounds] if the range is empty or extends past
+    /// the last page in this collection, or [PdfiumError::PageInUse] if a [PdfPage] referring
+    /// to any page in the range is still open, in which case no pages are deleted. Drop any
+    /// such [PdfPage] before calling this function, or delete a single open page using
+    /// [PdfPage::delete()]. Open pages outside the range remain valid.
+    pub fn delete_range(&mut self, range: RangeInclusive<PdfPageIndex>) -> Result<(), PdfiumError> {
+        self.validate_range(&range)?;
+
+        // Deleting a page invalidates the FPDF_PAGE handle of any PdfPage referring to it,
+        // so open pages in the range must be closed first.
+
+        let count = range.end() - range.start() + 1;
+
+        if PdfPageIndexCache::has_open_pages_at_index(self.document_handle, *range.start(), count) {
+            return Err(PdfiumError::PageInUse);
+        }
+
+        // Deleting pages from the end of the range first means the indices of the
+        // remaining pages in the range are unaffected by each deletion.
+
+        for index in range.clone().rev() {
+            self.bindings
+                .FPDFPage_Delete(self.document_handle, index as c_int);
+        }
+
+        PdfContentGenerations::increment_for_document(self.document_handle);
+
+        PdfPageIndexCache::delete_pages_at_index(self.document_handle, *range.start(), count);
+
+        Ok(())
+    }
+
+    /// Returns [PdfiumError::PageIndexOutOfBounds] if the given range of page indices is empty
+    /// or extends past the last page in this [PdfPages] collection.
+    fn validate_range(&self, range: &RangeInclusive<PdfPageIndex>) -> Result<(), PdfiumError> {
+        if range.is_empty() || *range.end() >= self.len() {
+            Err(PdfiumError::PageIndexOutOfBounds)
+        } else {
+            Ok(())
+        }
+    }
+
     /// Copies a single page with the given source page index from the given
     /// source [PdfDocument], inserting it at the given destination page index
     /// in this [PdfPages] collection.
@@ -361,6 +527,8 @@ impl<'a> PdfPages<'a> {
         )) {
             let destination_page_count_after_import = bindings.FPDF_GetPageCount(destination);
 
+            PdfContentGenerations::increment_for_document(destination);
+
             PdfPageIndexCache::insert_pages_at_index(
                 destination,
                 destination_page_index,
@@ -416,6 +584,8 @@ impl<'a> PdfPages<'a> {
                 destination_page_index as c_int,
             ),
         ) {
+            PdfContentGenerations::increment_for_document(destination);
+
             PdfPageIndexCache::insert_pages_at_index(
                 destination,
                 destination_page_index,
@@ -461,7 +631,7 @@ impl<'a> PdfPages<'a> {
         rows_per_page: u8,
         columns_per_row: u8,
         size: PdfPagePaperSize,
-    ) -> Result<PdfDocument, PdfiumError> {
+    ) -> Result<PdfDocument<'a>, PdfiumError> {
         let handle = self.bindings.FPDF_ImportNPagesToOne(
             self.document_handle,
             size.width().value,
@@ -479,6 +649,47 @@ impl<'a> PdfPages<'a> {
         }
     }
 
+    /// Returns the label of the page at the given index in this [PdfPages] collection, if any.
+    /// Page labels are the logical page numbers displayed by PDF viewers, such as "iv" for
+    /// a page in the front matter of a book, or "A-3" for a page in an appendix.
+    ///
+    /// Labels are assigned to ranges of pages using `PdfDocument::set_page_label_ranges()`.
+    pub fn label(&self, index: PdfPageIndex) -> Option<String> {
+        // Retrieving the label text from Pdfium is a two-step operation. First, we call
+        // FPDF_GetPageLabel() with a null buffer; this will retrieve the length of
+        // the label text in bytes. If the length is zero, then there is no such tag.
+
+        // If the length is non-zero, then we reserve a byte buffer of the given
+        // length and call FPDF_GetPageLabel() again with a pointer to the buffer;
+        // this will write the label text to the buffer in UTF16LE format.
+
+        let buffer_length = self.bindings.FPDF_GetPageLabel(
+            self.document_handle,
+            index as c_int,
+            std::ptr::null_mut(),
+            0,
+        );
+
+        if buffer_length == 0 {
+            // The label is not present.
+
+            None
+        } else {
+            let mut buffer = create_byte_buffer(buffer_length as usize);
+
+            let result = self.bindings.FPDF_GetPageLabel(
+                self.document_handle,
+                index as c_int,
+                buffer.as_mut_ptr() as *mut c_void,
+                buffer_length,
+            );
+
+            debug_assert_eq!(result, buffer_length);
+
+            get_string_from_pdfium_utf16le_bytes(buffer)
+        }
+    }
+
     /// Returns a [PdfPage] from the given `FPDF_PAGE` handle, if possible.
     pub(crate) fn pdfium_page_handle_to_result(
         &self,
@@ -496,43 +707,10 @@ impl<'a> PdfPages<'a> {
             // page index as an immutable property of the PdfPage; instead, we look up the label now.
 
             // (Pdfium does not currently include an FPDF_SetPageLabel() function, so the label
-            // _will_ be an immutable property of the PdfPage for its entire lifetime.)
-
-            let label = {
-                // Retrieving the label text from Pdfium is a two-step operation. First, we call
-                // FPDF_GetPageLabel() with a null buffer; this will retrieve the length of
-                // the label text in bytes. If the length is zero, then there is no such tag.
-
-                // If the length is non-zero, then we reserve a byte buffer of the given
-                // length and call FPDF_GetPageLabel() again with a pointer to the buffer;
-                // this will write the label text to the buffer in UTF16LE format.
-
-                let buffer_length = self.bindings.FPDF_GetPageLabel(
-                    self.document_handle,
-                    index as c_int,
-                    std::ptr::null_mut(),
-                    0,
-                );
-
-                if buffer_length == 0 {
-                    // The label is not present.
-
-                    None
-                } else {
-                    let mut buffer = create_byte_buffer(buffer_length as usize);
+            // _will_ be an immutable property of the PdfPage for its entire lifetime. Labels
+            // written using PdfDocument::set_page_label_ranges() reload the document.)
 
-                    let result = self.bindings.FPDF_GetPageLabel(
-                        self.document_handle,
-                        index as c_int,
-                        buffer.as_mut_ptr() as *mut c_void,
-                        buffer_length,
-                    );
-
-                    debug_assert_eq!(result, buffer_length);
-
-                    get_string_from_pdfium_utf16le_bytes(buffer)
-                }
-            };
+            let label = self.label(index);
 
             Ok(PdfPage::from_pdfium(
                 self.document_handle,
@@ -655,6 +833,7 @@ impl<'a> Iterator for PdfPagesIterator<'a> {
 
 #[cfg(test)]
 mod tests {
+    use crate::pdf::document::page::index_cache::PdfPageIndexCache;
     use crate::prelude::*;
     use crate::utils::test::test_bind_to_pdfium;
 
@@ -698,6 +877,108 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(any(
+        feature = "pdfium_6043",
+        feature = "pdfium_6084",
+        feature = "pdfium_6110",
+        feature = "pdfium_6124",
+        feature = "pdfium_6164",
+        feature = "pdfium_6259",
+        feature = "pdfium_6295",
+        feature = "pdfium_6337",
+        feature = "pdfium_6406",
+        feature = "pdfium_6490",
+        feature = "pdfium_6555",
+        feature = "pdfium_6569",
+        feature = "pdfium_6611",
+        feature = "pdfium_6666",
+        feature = "pdfium_future"
+    ))]
+    fn test_batch_page_operations() -> Result<(), PdfiumError> {
+        // Pages are told apart by their widths.
+
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        for width in [100.0, 200.0, 300.0, 400.0] {
+            document
+                .pages_mut()
+                .create_page_at_end(PdfPagePaperSize::Custom(
+                    PdfPoints::new(width),
+                    PdfPoints::new(500.0),
+                ))?;
+        }
+
+        let widths = |document: &PdfDocument| -> Result<Vec<f32>, PdfiumError> {
+            Ok(document
+                .pages()
+                .page_sizes()?
+                .iter()
+                .map(|size| size.width().value)
+                .collect())
+        };
+
+        document.pages_mut().reorder(&[3, 0, 2, 1])?;
+
+        assert_eq!(widths(&document)?, vec![400.0, 100.0, 300.0, 200.0]);
+
+        assert!(matches!(
+            document.pages_mut().reorder(&[0, 0, 1, 2]),
+            Err(PdfiumError::InvalidPageOrder)
+        ));
+        assert!(matches!(
+            document.pages_mut().reorder(&[0, 1, 2, 4]),
+            Err(PdfiumError::PageIndexOutOfBounds)
+        ));
+
+        document
+            .pages_mut()
+            .rotate_range(1..=2, PdfPageRenderRotation::Degrees90)?;
+
+        assert_eq!(
+            document.pages().get(0)?.rotation()?,
+            PdfPageRenderRotation::None
+        );
+        assert_eq!(
+            document.pages().get(2)?.rotation()?,
+            PdfPageRenderRotation::Degrees90
+        );
+
+        assert!(matches!(
+            document.pages_mut().delete_range(3..=4),
+            Err(PdfiumError::PageIndexOutOfBounds)
+        ));
+
+        {
+            let open = document.pages().get(2)?;
+
+            let following = document.pages().get(3)?;
+
+            assert!(matches!(
+                document.pages_mut().delete_range(1..=2),
+                Err(PdfiumError::PageInUse)
+            ));
+
+            drop(open);
+
+            document.pages_mut().delete_range(1..=2)?;
+
+            // Open pages after the range remain valid, and follow their new index.
+
+            assert_eq!(
+                PdfPageIndexCache::get_index_for_page(document.handle(), following.page_handle()),
+                Some(1)
+            );
+            assert_eq!(following.width().value, 200.0);
+        }
+
+        assert_eq!(widths(&document)?, vec![400.0, 200.0]);
+
+        Ok(())
+    }
+
     const fn expected_page_0_size() -> PdfRect {
         PdfRect::new_from_values(0.0, 0.0, 841.8897, 595.3039)
     }
@@ -0,0 +1,202 @@
+//! Defines the [PdfPrintJob] struct, exposing functionality related to printing a range
+//! of pages in a `PdfPages` collection to a Windows device context.
+
+use crate::bindgen::{
+    FPDF_PRINTMODE_EMF, FPDF_PRINTMODE_EMF_IMAGE_MASKS, FPDF_PRINTMODE_POSTSCRIPT2,
+    FPDF_PRINTMODE_POSTSCRIPT2_PASSTHROUGH, FPDF_PRINTMODE_POSTSCRIPT3,
+    FPDF_PRINTMODE_POSTSCRIPT3_PASSTHROUGH, FPDF_PRINTMODE_POSTSCRIPT3_TYPE42,
+    FPDF_PRINTMODE_POSTSCRIPT3_TYPE42_PASSTHROUGH, FPDF_PRINTMODE_TEXTONLY,
+};
+use crate::error::PdfiumError;
+use crate::pdf::bitmap::Pixels;
+use crate::pdf::document::page::render_config::PdfRenderConfig;
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use std::ops::Range;
+use std::os::raw::c_int;
+use windows::Win32::Graphics::Gdi::HDC;
+
+/// The output mode Pdfium should use when rendering pages to a Windows printer device context.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PdfPrintMode {
+    /// Outputs EMF. This is the default.
+    Emf,
+
+    /// Outputs text only, for character stream devices.
+    TextOnly,
+
+    /// Outputs level 2 PostScript into EMF as a series of GDI comments.
+    PostScript2,
+
+    /// Outputs level 3 PostScript into EMF as a series of GDI comments.
+    PostScript3,
+
+    /// Outputs level 2 PostScript via `ExtEscape()` in `PASSTHROUGH` mode.
+    PostScript2Passthrough,
+
+    /// Outputs level 3 PostScript via `ExtEscape()` in `PASSTHROUGH` mode.
+    PostScript3Passthrough,
+
+    /// Outputs EMF, with more efficient processing of documents containing image masks.
+    EmfImageMasks,
+
+    /// Outputs level 3 PostScript with embedded Type 42 fonts, when applicable,
+    /// into EMF as a series of GDI comments.
+    PostScript3Type42,
+
+    /// Outputs level 3 PostScript with embedded Type 42 fonts, when applicable,
+    /// via `ExtEscape()` in `PASSTHROUGH` mode.
+    PostScript3Type42Passthrough,
+}
+
+impl PdfPrintMode {
+    #[inline]
+    pub(crate) fn as_pdfium(&self) -> u32 {
+        match self {
+            PdfPrintMode::Emf => FPDF_PRINTMODE_EMF,
+            PdfPrintMode::TextOnly => FPDF_PRINTMODE_TEXTONLY,
+            PdfPrintMode::PostScript2 => FPDF_PRINTMODE_POSTSCRIPT2,
+            PdfPrintMode::PostScript3 => FPDF_PRINTMODE_POSTSCRIPT3,
+            PdfPrintMode::PostScript2Passthrough => FPDF_PRINTMODE_POSTSCRIPT2_PASSTHROUGH,
+            PdfPrintMode::PostScript3Passthrough => FPDF_PRINTMODE_POSTSCRIPT3_PASSTHROUGH,
+            PdfPrintMode::EmfImageMasks => FPDF_PRINTMODE_EMF_IMAGE_MASKS,
+            PdfPrintMode::PostScript3Type42 => FPDF_PRINTMODE_POSTSCRIPT3_TYPE42,
+            PdfPrintMode::PostScript3Type42Passthrough => {
+                FPDF_PRINTMODE_POSTSCRIPT3_TYPE42_PASSTHROUGH
+            }
+        }
+    }
+}
+
+impl Default for PdfPrintMode {
+    #[inline]
+    fn default() -> Self {
+        PdfPrintMode::Emf
+    }
+}
+
+/// Prints a range of pages in a `PdfPages` collection to a Windows device context,
+/// typically a printer device context obtained from a print dialog.
+///
+/// Pixel dimensions, rotation, and rendering flags for each page are calculated from
+/// the [PdfRenderConfig] assigned to the print job using the [PdfPrintJob::set_render_config()]
+/// function; set the target width and height of the render configuration to the printable
+/// area of the device, in device pixels. By default, pages are scaled to their natural size
+/// in points, rendered with print quality enabled.
+///
+/// Windows requires each printed page to be bracketed by calls to `StartPage()` and `EndPage()`.
+/// Either make these calls yourself around each call to [PdfPrintJob::print_page()],
+/// or use the [PdfPrintJob::print()] function to make them using callbacks.
+///
+/// This struct is only available when the `pdfium_use_win32` crate feature is enabled.
+pub struct PdfPrintJob<'a> {
+    pages: &'a PdfPages<'a>,
+    range: Range<PdfPageIndex>,
+    mode: PdfPrintMode,
+    config: PdfRenderConfig,
+    origin: (Pixels, Pixels),
+}
+
+impl<'a> PdfPrintJob<'a> {
+    /// Creates a new [PdfPrintJob] that will print every page in the given `PdfPages` collection.
+    #[inline]
+    pub fn new(pages: &'a PdfPages<'a>) -> Self {
+        PdfPrintJob {
+            pages,
+            range: 0..pages.len(),
+            mode: PdfPrintMode::default(),
+            config: PdfRenderConfig::new().use_print_quality(true),
+            origin: (0, 0),
+        }
+    }
+
+    /// Restricts this [PdfPrintJob] to the pages in the given range of zero-based page indices.
+    /// Page indices outside the containing `PdfPages` collection are ignored.
+    #[inline]
+    pub fn set_page_range(mut self, range: Range<PdfPageIndex>) -> Self {
+        self.range = range.start.min(self.pages.len())..range.end.min(self.pages.len());
+
+        self
+    }
+
+    /// Sets the [PdfPrintMode] Pdfium should use when printing. The default is [PdfPrintMode::Emf].
+    #[inline]
+    pub fn set_print_mode(mut self, mode: PdfPrintMode) -> Self {
+        self.mode = mode;
+
+        self
+    }
+
+    /// Sets the [PdfRenderConfig] used to calculate the pixel dimensions, rotation, and
+    /// rendering flags of each printed page. Custom transformation matrices and clipping
+    /// rectangles are not supported when printing and will be ignored.
+    #[inline]
+    pub fn set_render_config(mut self, config: PdfRenderConfig) -> Self {
+        self.config = config;
+
+        self
+    }
+
+    /// Sets the position of the top left corner of each printed page, in device pixels.
+    /// The default is `(0, 0)`.
+    #[inline]
+    pub fn set_origin(mut self, x: Pixels, y: Pixels) -> Self {
+        self.origin = (x, y);
+
+        self
+    }
+
+    /// Returns the zero-based indices of the pages that will be printed by this [PdfPrintJob].
+    #[inline]
+    pub fn page_range(&self) -> Range<PdfPageIndex> {
+        self.range.clone()
+    }
+
+    /// Renders the page at the given zero-based index to the given device context, using the
+    /// print mode and render configuration of this [PdfPrintJob].
+    pub fn print_page(&self, dc: HDC, index: PdfPageIndex) -> Result<(), PdfiumError> {
+        let bindings = self.pages.bindings();
+
+        let page = self.pages.get(index)?;
+
+        let settings = self.config.apply_to_page(&page).without_subpixel_reversal();
+
+        bindings.FPDF_SetPrintMode(self.mode.as_pdfium() as c_int);
+
+        bindings.FPDF_RenderPage(
+            dc,
+            page.page_handle(),
+            self.origin.0,
+            self.origin.1,
+            settings.width,
+            settings.height,
+            settings.rotate,
+            settings.render_flags,
+        );
+
+        Ok(())
+    }
+
+    /// Prints every page in the page range of this [PdfPrintJob] to the given device context.
+    ///
+    /// The given callbacks are invoked immediately before and immediately after each page
+    /// is rendered, and should call `StartPage()` and `EndPage()` respectively on the
+    /// device context. Printing stops at the first error returned by either callback.
+    pub fn print<S, E>(
+        &self,
+        dc: HDC,
+        mut start_page: S,
+        mut end_page: E,
+    ) -> Result<(), PdfiumError>
+    where
+        S: FnMut(PdfPageIndex) -> Result<(), PdfiumError>,
+        E: FnMut(PdfPageIndex) -> Result<(), PdfiumError>,
+    {
+        for index in self.page_range() {
+            start_page(index)?;
+            self.print_page(dc, index)?;
+            end_page(index)?;
+        }
+
+        Ok(())
+    }
+}
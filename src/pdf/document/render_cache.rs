@@ -0,0 +1,264 @@
+//! Defines the [PdfRenderCache] struct, an opt-in cache of rendered pages with a memory budget.
+
+use crate::bindgen::FPDF_DOCUMENT;
+use crate::error::PdfiumError;
+use crate::pdf::bitmap::PdfBitmap;
+use crate::pdf::document::page::index_cache::PdfPageIndexCache;
+use crate::pdf::document::page::render_config::PdfRenderConfig;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::document::pages::PdfPageIndex;
+
+/// A cache of rendered pages and page tiles, keyed by page and by every render setting that
+/// affects the rendered output, such as the scale, rotation, and clipping rectangle.
+///
+/// Cached bitmaps are evicted in least recently used order once the total size of their
+/// bitmap buffers exceeds the cache's memory budget. A cached bitmap is discarded and the page
+/// rendered again if the page's content generation has changed since the bitmap was rendered,
+/// for instance because page objects or annotations have been added, removed, or edited;
+/// see `PdfPage::content_generation()` for details of which changes are tracked.
+///
+/// Caching is opt-in: pages rendered using `PdfPage::render_with_config()` are never cached.
+pub struct PdfRenderCache<'a> {
+    maximum_bytes: usize,
+    used_bytes: usize,
+
+    // Entries are kept in order of use, least recently used first.
+    entries: Vec<PdfRenderCacheEntry<'a>>,
+}
+
+struct PdfRenderCacheEntry<'a> {
+    document: FPDF_DOCUMENT,
+    page_index: PdfPageIndex,
+    settings_key: u64,
+    generation: u64,
+    bytes: usize,
+    bitmap: PdfBitmap<'a>,
+}
+
+impl<'a> PdfRenderCache<'a> {
+    /// Creates a new, empty [PdfRenderCache] that holds rendered bitmaps totalling at most
+    /// the given number of bytes.
+    #[inline]
+    pub fn new(maximum_bytes: usize) -> Self {
+        PdfRenderCache {
+            maximum_bytes,
+            used_bytes: 0,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Returns the given [PdfPage] rendered using the given [PdfRenderConfig], rendering it
+    /// only if no up-to-date rendering with equivalent settings is already cached.
+    ///
+    /// A newly rendered bitmap is always added to the cache, evicting least recently used
+    /// bitmaps as necessary to stay within the cache's memory budget. A bitmap larger than
+    /// the entire budget is held only until the next bitmap is added.
+    ///
+    /// Any progress callback set on the given [PdfRenderConfig] is only invoked if the
+    /// page is actually rendered.
+    pub fn render(
+        &mut self,
+        page: &PdfPage<'a>,
+        config: &PdfRenderConfig,
+    ) -> Result<&PdfBitmap<'a>, PdfiumError> {
+        let document = page.document_handle();
+
+        let page_index = PdfPageIndexCache::get_index_for_page(document, page.page_handle())
+            .ok_or(PdfiumError::SourcePageIndexNotInCache)?;
+
+        let settings_key = config.apply_to_page(page).cache_key();
+
+        let generation = page.content_generation();
+
+        let mut is_cached = false;
+
+        if let Some(position) = self.entries.iter().position(|entry| {
+            entry.document == document
+                && entry.page_index == page_index
+                && entry.settings_key == settings_key
+        }) {
+            let entry = self.entries.remove(position);
+
+            if entry.generation == generation {
+                // Move the entry to the most recently used position.
+
+                self.entries.push(entry);
+
+                is_cached = true;
+            } else {
+                // The page may have changed since the entry was rendered.
+
+                self.used_bytes -= entry.bytes;
+            }
+        }
+
+        if !is_cached {
+            let bitmap = page.render_with_config(config)?;
+
+            let bytes = bitmap.as_raw_bytes().len();
+
+            while !self.entries.is_empty() && self.used_bytes + bytes > self.maximum_bytes {
+                let evicted = self.entries.remove(0);
+
+                self.used_bytes -= evicted.bytes;
+            }
+
+            self.used_bytes += bytes;
+
+            self.entries.push(PdfRenderCacheEntry {
+                document,
+                page_index,
+                settings_key,
+                generation,
+                bytes,
+                bitmap,
+            });
+        }
+
+        match self.entries.last() {
+            Some(entry) => Ok(&entry.bitmap),
+            None => unreachable!(),
+        }
+    }
+
+    /// Discards all cached renderings of the given [PdfPage].
+    pub fn invalidate_page(&mut self, page: &PdfPage) {
+        let document = page.document_handle();
+
+        if let Some(page_index) =
+            PdfPageIndexCache::get_index_for_page(document, page.page_handle())
+        {
+            self.retain(|entry| entry.document != document || entry.page_index != page_index);
+        }
+    }
+
+    /// Discards all cached renderings.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.used_bytes = 0;
+    }
+
+    /// Returns the number of rendered bitmaps in this [PdfRenderCache].
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns `true` if this [PdfRenderCache] contains no rendered bitmaps.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the total size, in bytes, of the bitmap buffers in this [PdfRenderCache].
+    #[inline]
+    pub fn used_bytes(&self) -> usize {
+        self.used_bytes
+    }
+
+    /// Returns the memory budget of this [PdfRenderCache], in bytes.
+    #[inline]
+    pub fn maximum_bytes(&self) -> usize {
+        self.maximum_bytes
+    }
+
+    /// Sets the memory budget of this [PdfRenderCache], in bytes, immediately evicting
+    /// least recently used bitmaps as necessary to stay within the new budget.
+    pub fn set_maximum_bytes(&mut self, maximum_bytes: usize) {
+        self.maximum_bytes = maximum_bytes;
+
+        while self.used_bytes > self.maximum_bytes && !self.entries.is_empty() {
+            let evicted = self.entries.remove(0);
+
+            self.used_bytes -= evicted.bytes;
+        }
+    }
+
+    fn retain(&mut self, f: impl Fn(&PdfRenderCacheEntry<'a>) -> bool) {
+        self.entries.retain(|entry| f(entry));
+        self.used_bytes = self.entries.iter().map(|entry| entry.bytes).sum();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_render_cache() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        let mut page = document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let config = PdfRenderConfig::new().set_target_width(100);
+
+        let mut cache = PdfRenderCache::new(usize::MAX);
+
+        let blank = cache.render(&page, &config)?.content_hash();
+
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.render(&page, &config)?.content_hash(), blank);
+        assert_eq!(cache.len(), 1);
+
+        // A different scale is cached separately.
+
+        cache.render(&page, &config.clone().set_target_width(50))?;
+
+        assert_eq!(cache.len(), 2);
+
+        // Mutable access to the page's objects does not invalidate its cached renderings
+        // unless the objects are changed.
+
+        let generation = page.content_generation();
+
+        page.objects_mut();
+        page.annotations_mut();
+
+        assert_eq!(page.content_generation(), generation);
+
+        // Editing the page invalidates its cached renderings.
+
+        let mut rect = page.objects_mut().create_path_object_rect(
+            PdfRect::new_from_values(100.0, 100.0, 300.0, 300.0),
+            None,
+            None,
+            Some(PdfColor::BLACK),
+        )?;
+
+        assert!(page.content_generation() > generation);
+
+        let black = cache.render(&page, &config)?.content_hash();
+
+        assert_ne!(black, blank);
+
+        // So does editing an object already on the page.
+
+        let generation = page.content_generation();
+
+        rect.set_fill_color(PdfColor::RED)?;
+
+        assert!(page.content_generation() > generation);
+        assert_ne!(cache.render(&page, &config)?.content_hash(), black);
+
+        // A budget too small for both renderings evicts the least recently used one.
+
+        let used = cache.used_bytes();
+
+        cache.set_maximum_bytes(used - 1);
+
+        assert_eq!(cache.len(), 1);
+
+        cache.clear();
+
+        assert!(cache.is_empty());
+        assert_eq!(cache.used_bytes(), 0);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,164 @@
+//! Defines the [PdfRenderIterator] struct, used to render every page in a `PdfDocument`
+//! lazily, one page at a time, reusing a single bitmap, using the `PdfDocument::render_iter()`
+//! function.
+
+use crate::error::PdfiumError;
+use crate::pdf::bitmap::{PdfBitmap, PdfBitmapFormat, Pixels};
+use crate::pdf::document::page::render_config::PdfRenderConfig;
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+
+/// Renders every page in a `PdfDocument`, in page order, one page at a time, as returned by
+/// the `PdfDocument::render_iter()` function.
+///
+/// Each page is rendered only when requested, and the bitmap backing the previous page is
+/// reused wherever possible, so memory usage is bounded by the size of the largest rendered
+/// page rather than by the number of pages in the document. A new bitmap is allocated only
+/// when a page renders at different pixel dimensions or in a different pixel format to the
+/// page before it.
+///
+/// Because each page is rendered into the bitmap used by the previous page, the returned
+/// bitmap is borrowed from this [PdfRenderIterator] and must be released before the next page
+/// can be rendered. For this reason, [PdfRenderIterator] does not implement the `Iterator`
+/// trait. Use a `while let` loop instead:
+///
+/// ```ignore
+/// let config = PdfRenderConfig::new().set_target_width(1000);
+///
+/// let mut pages = document.render_iter(&config);
+///
+/// while let Some(result) = pages.render_next() {
+///     let (index, bitmap) = result?;
+///
+///     // ... encode or display the bitmap before rendering the next page
+/// }
+/// ```
+pub struct PdfRenderIterator<'a, 'b> {
+    pages: &'b PdfPages<'a>,
+    config: &'b PdfRenderConfig,
+    next_index: PdfPageIndex,
+    bitmap: Option<PdfBitmap<'a>>,
+}
+
+impl<'a, 'b> PdfRenderIterator<'a, 'b> {
+    #[inline]
+    pub(crate) fn new(pages: &'b PdfPages<'a>, config: &'b PdfRenderConfig) -> Self {
+        PdfRenderIterator {
+            pages,
+            config,
+            next_index: 0,
+            bitmap: None,
+        }
+    }
+
+    /// Renders the next page in the document, returning its page index together with
+    /// the rendered bitmap. Returns `None` once every page has been rendered.
+    ///
+    /// An error rendering one page does not prevent the following pages from being rendered.
+    pub fn render_next(&mut self) -> Option<Result<(PdfPageIndex, &PdfBitmap<'a>), PdfiumError>> {
+        if self.next_index >= self.pages.len() {
+            return None;
+        }
+
+        let index = self.next_index;
+
+        self.next_index += 1;
+
+        Some(self.render(index).map(|bitmap| (index, bitmap)))
+    }
+
+    /// Returns the number of pages that have not yet been rendered.
+    #[inline]
+    pub fn remaining(&self) -> usize {
+        self.pages.len().saturating_sub(self.next_index) as usize
+    }
+
+    fn render(&mut self, index: PdfPageIndex) -> Result<&PdfBitmap<'a>, PdfiumError> {
+        let page = self.pages.get(index)?;
+
+        let settings = self.config.apply_to_page(&page);
+
+        if settings.is_maximum_bitmap_bytes_exceeded {
+            return Err(PdfiumError::RenderBudgetExceeded);
+        }
+
+        let width = settings.width as Pixels;
+
+        let height = settings.height as Pixels;
+
+        let format = PdfBitmapFormat::from_pdfium(settings.format as u32)
+            .unwrap_or_else(|_| PdfBitmapFormat::default());
+
+        let is_reusable = match self.bitmap.as_ref() {
+            Some(bitmap) => {
+                bitmap.width() == width
+                    && bitmap.height() == height
+                    && bitmap.format().ok() == Some(format)
+            }
+            None => false,
+        };
+
+        if !is_reusable {
+            // Release the previous bitmap before allocating its replacement, so that
+            // at most one bitmap is held in memory at a time.
+
+            self.bitmap = None;
+            self.bitmap = Some(PdfBitmap::empty(width, height, format, page.bindings())?);
+        }
+
+        match self.bitmap.as_mut() {
+            Some(bitmap) => {
+                page.render_into_bitmap_with_settings(bitmap, settings)?;
+
+                if !is_reusable {
+                    bitmap.record_buffer_allocation_in_render_stats();
+                }
+
+                Ok(bitmap)
+            }
+            None => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_render_iter() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let config = PdfRenderConfig::new().set_target_width(200);
+
+        let expected = document
+            .pages()
+            .iter()
+            .map(|page| {
+                page.render_with_config(&config)
+                    .map(|bitmap| bitmap.content_hash())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut pages = document.render_iter(&config);
+
+        assert_eq!(pages.remaining(), expected.len());
+
+        let mut actual = Vec::new();
+
+        while let Some(result) = pages.render_next() {
+            let (index, bitmap) = result?;
+
+            assert_eq!(index as usize, actual.len());
+
+            actual.push(bitmap.content_hash());
+        }
+
+        assert_eq!(actual, expected);
+        assert_eq!(pages.remaining(), 0);
+
+        Ok(())
+    }
+}
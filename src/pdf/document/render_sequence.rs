@@ -0,0 +1,269 @@
+//! Defines the [PdfRenderSequenceConfig] struct, used to render every page in a `PdfDocument`
+//! as a sequence of identically sized video frames using the `PdfDocument::render_sequence()`
+//! function.
+
+use crate::error::PdfiumError;
+use crate::pdf::bitmap::{PdfBitmap, PdfBitmapFormat, Pixels};
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::page::render_config::PdfRenderConfig;
+use crate::pdf::document::pages::PdfPages;
+use crate::pdf::matrix::{PdfMatrix, PdfMatrixValue};
+use crate::pdf::rect::PdfRect;
+use std::io::Write;
+
+/// Controls how a page is fitted into a video frame whose aspect ratio differs from
+/// the aspect ratio of the page.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PdfFrameFit {
+    /// The page is scaled to fit entirely within the frame, centered, with any unused
+    /// area of the frame filled with the background color. This is the default.
+    Letterbox,
+
+    /// The page is scaled to fill the entire frame, centered, with any part of the page
+    /// that falls outside the frame cropped.
+    Crop,
+
+    /// The page is scaled to fill the entire frame exactly, distorting its aspect ratio.
+    Stretch,
+}
+
+impl Default for PdfFrameFit {
+    #[inline]
+    fn default() -> Self {
+        PdfFrameFit::Letterbox
+    }
+}
+
+/// Configures the size, cadence, and fitting policy of the frames rendered by the
+/// `PdfDocument::render_sequence()` function.
+///
+/// Every page is rendered into a frame of exactly the same pixel dimensions, making the
+/// output suitable for encoding directly as a video or animated flipbook. Each frame is
+/// written as raw, tightly packed RGBA pixel data, eight bits per channel, with no padding
+/// between rows or frames; for example, the output can be piped into
+/// `ffmpeg -f rawvideo -pix_fmt rgba -s <width>x<height> -r <frame rate> -i - <output file>`.
+#[derive(Debug, Clone)]
+pub struct PdfRenderSequenceConfig {
+    width: Pixels,
+    height: Pixels,
+    frames_per_page: usize,
+    fit: PdfFrameFit,
+    background_color: PdfColor,
+    render_config: PdfRenderConfig,
+}
+
+impl PdfRenderSequenceConfig {
+    /// Creates a new [PdfRenderSequenceConfig] that renders every page into a frame with the
+    /// given pixel dimensions, letterboxed against a black background, one frame per page.
+    #[inline]
+    pub fn new(width: Pixels, height: Pixels) -> Self {
+        PdfRenderSequenceConfig {
+            width,
+            height,
+            frames_per_page: 1,
+            fit: PdfFrameFit::default(),
+            background_color: PdfColor::BLACK,
+            render_config: PdfRenderConfig::new(),
+        }
+    }
+
+    /// Sets the number of identical frames written for each page. At a frame rate of 30 frames
+    /// per second, a value of 90 holds each page on screen for three seconds. Values less than
+    /// one are treated as one. The default is 1.
+    #[inline]
+    pub fn set_frames_per_page(mut self, frames: usize) -> Self {
+        self.frames_per_page = frames.max(1);
+
+        self
+    }
+
+    /// Sets the [PdfFrameFit] policy used to fit each page into a frame.
+    /// The default is [PdfFrameFit::Letterbox].
+    #[inline]
+    pub fn set_fit(mut self, fit: PdfFrameFit) -> Self {
+        self.fit = fit;
+
+        self
+    }
+
+    /// Sets the color used to fill any area of a frame not covered by a page.
+    /// The default is [PdfColor::BLACK].
+    #[inline]
+    pub fn set_background_color(mut self, color: PdfColor) -> Self {
+        self.background_color = color;
+
+        self
+    }
+
+    /// Sets the [PdfRenderConfig] from which rendering flags, such as anti-aliasing and
+    /// annotation rendering settings, are taken. Its size, rotation, format, clear color,
+    /// transformation, and clipping settings are ignored, and form data is not rendered.
+    #[inline]
+    pub fn set_render_config(mut self, config: PdfRenderConfig) -> Self {
+        self.render_config = config;
+
+        self
+    }
+
+    /// Returns the pixel width of each frame.
+    #[inline]
+    pub fn width(&self) -> Pixels {
+        self.width
+    }
+
+    /// Returns the pixel height of each frame.
+    #[inline]
+    pub fn height(&self) -> Pixels {
+        self.height
+    }
+
+    /// Returns the number of identical frames written for each page.
+    #[inline]
+    pub fn frames_per_page(&self) -> usize {
+        self.frames_per_page
+    }
+
+    /// Returns the number of bytes written for each frame.
+    #[inline]
+    pub fn frame_size_in_bytes(&self) -> usize {
+        PdfBitmap::bytes_required_for_size(self.width, self.height)
+    }
+
+    /// Renders every page in the given [PdfPages] collection, in page order, writing
+    /// [PdfRenderSequenceConfig::frames_per_page()] frames for each page to the given writer.
+    /// Returns the total number of frames written.
+    pub(crate) fn render<W: Write>(
+        &self,
+        pages: &PdfPages,
+        mut sink: W,
+    ) -> Result<usize, PdfiumError> {
+        let config = self
+            .render_config
+            .clone()
+            .set_format(PdfBitmapFormat::BGRA)
+            .set_reverse_byte_order(true)
+            .clear_before_rendering(true)
+            .set_clear_color(self.background_color);
+
+        let mut bitmap = PdfBitmap::empty(
+            self.width,
+            self.height,
+            PdfBitmapFormat::BGRA,
+            pages.bindings(),
+        )?;
+
+        let clip = PdfRect::new_from_values(0.0, 0.0, self.height as f32, self.width as f32);
+
+        let mut frames = 0;
+
+        for page in pages.iter() {
+            let (scale_x, scale_y, offset_x, offset_y) = frame_placement(
+                page.width().value,
+                page.height().value,
+                self.width as f32,
+                self.height as f32,
+                self.fit,
+            );
+
+            page.render_into_bitmap_with_matrix(
+                &mut bitmap,
+                PdfMatrix::new(scale_x, 0.0, 0.0, scale_y, offset_x, offset_y),
+                clip,
+                &config,
+            )?;
+
+            let bytes = bitmap.as_rgba_bytes();
+
+            for _ in 0..self.frames_per_page {
+                sink.write_all(&bytes).map_err(PdfiumError::IoError)?;
+
+                frames += 1;
+            }
+        }
+
+        sink.flush().map_err(PdfiumError::IoError)?;
+
+        Ok(frames)
+    }
+}
+
+/// Returns the horizontal and vertical scale factors and offsets, in pixels, that place
+/// a page of the given size into a frame of the given size using the given [PdfFrameFit] policy.
+pub(crate) fn frame_placement(
+    page_width: f32,
+    page_height: f32,
+    frame_width: f32,
+    frame_height: f32,
+    fit: PdfFrameFit,
+) -> (
+    PdfMatrixValue,
+    PdfMatrixValue,
+    PdfMatrixValue,
+    PdfMatrixValue,
+) {
+    if page_width <= 0.0 || page_height <= 0.0 {
+        return (1.0, 1.0, 0.0, 0.0);
+    }
+
+    let scale_x = frame_width / page_width;
+
+    let scale_y = frame_height / page_height;
+
+    let (scale_x, scale_y) = match fit {
+        PdfFrameFit::Letterbox => (scale_x.min(scale_y), scale_x.min(scale_y)),
+        PdfFrameFit::Crop => (scale_x.max(scale_y), scale_x.max(scale_y)),
+        PdfFrameFit::Stretch => (scale_x, scale_y),
+    };
+
+    (
+        scale_x,
+        scale_y,
+        ((frame_width - page_width * scale_x) / 2.0).round(),
+        ((frame_height - page_height * scale_y) / 2.0).round(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::render_sequence::frame_placement;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_frame_placement() {
+        // A portrait page placed in a landscape frame.
+
+        assert_eq!(
+            frame_placement(100.0, 200.0, 400.0, 200.0, PdfFrameFit::Letterbox),
+            (1.0, 1.0, 150.0, 0.0)
+        );
+
+        assert_eq!(
+            frame_placement(100.0, 200.0, 400.0, 200.0, PdfFrameFit::Crop),
+            (4.0, 4.0, 0.0, -300.0)
+        );
+
+        assert_eq!(
+            frame_placement(100.0, 200.0, 400.0, 200.0, PdfFrameFit::Stretch),
+            (4.0, 1.0, 0.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_render_sequence() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let document = pdfium.load_pdf_from_file("./test/export-test.pdf", None)?;
+
+        let config = PdfRenderSequenceConfig::new(320, 180).set_frames_per_page(2);
+
+        let mut output = Vec::new();
+
+        let frames = document.render_sequence(&config, &mut output)?;
+
+        assert_eq!(frames, document.pages().len() as usize * 2);
+        assert_eq!(output.len(), frames * config.frame_size_in_bytes());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,652 @@
+//! Defines the [PdfDocumentSearch] struct, an iterator over the results of searching for
+//! a string in every page of a `PdfDocument`, as returned by the `PdfDocument::search()`,
+//! `PdfDocument::search_regex()`, and `PdfDocument::search_fuzzy()` functions.
+
+use crate::bindgen::FPDF_DOCUMENT;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::pdf::document::page::generation::PdfContentGenerations;
+use crate::pdf::document::page::text::chars::PdfPageTextCharIndex;
+use crate::pdf::document::page::text::search::PdfSearchOptions;
+use crate::pdf::document::page::text::PdfPageText;
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use crate::pdf::rect::PdfRect;
+use once_cell::sync::Lazy;
+use std::collections::{HashMap, VecDeque};
+use std::ops::Range;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+#[cfg(feature = "regex")]
+use regex::Regex;
+
+/// A single match found by a [PdfDocumentSearch].
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfSearchResult {
+    page_index: PdfPageIndex,
+    char_range: Range<PdfPageTextCharIndex>,
+    bounding_rects: Vec<PdfRect>,
+}
+
+impl PdfSearchResult {
+    /// Returns the index of the page containing this [PdfSearchResult].
+    #[inline]
+    pub fn page_index(&self) -> PdfPageIndex {
+        self.page_index
+    }
+
+    /// Returns the range of indices of the matched characters within the text of the page
+    /// containing this [PdfSearchResult], for use with the `PdfPageText::chars()` function.
+    #[inline]
+    pub fn char_range(&self) -> Range<PdfPageTextCharIndex> {
+        self.char_range.clone()
+    }
+
+    /// Returns the rectangular areas of the page occupied by the matched characters,
+    /// suitable for highlighting this [PdfSearchResult]. A match spanning several lines,
+    /// or several differently styled spans of text, occupies several rectangles.
+    #[inline]
+    pub fn bounding_rects(&self) -> &[PdfRect] {
+        self.bounding_rects.as_slice()
+    }
+}
+
+/// An iterator over the results of searching for a string in every page of a `PdfDocument`,
+/// in page order, as returned by the `PdfDocument::search()`, `PdfDocument::search_regex()`,
+/// and `PdfDocument::search_fuzzy()` functions.
+///
+/// Pages are searched lazily, one page at a time, as results are requested. Each page is
+/// loaded, searched, and closed again before any of its results are returned, so no page or
+/// Pdfium search handle is held open between calls to `next()`. Pages that cannot be loaded
+/// are skipped.
+///
+/// The normalized text of each page searched by a regular expression or fuzzy search is
+/// cached until the page changes or the document is closed, so later searches of the same
+/// document need not extract it again. A page whose cached text contains no matches is
+/// not loaded at all.
+pub struct PdfDocumentSearch<'a, 'b> {
+    pages: &'b PdfPages<'a>,
+    matcher: PdfSearchMatcher,
+    next_page_index: PdfPageIndex,
+    results: VecDeque<PdfSearchResult>,
+}
+
+/// The kinds of search supported by a [PdfDocumentSearch].
+enum PdfSearchMatcher {
+    /// Pdfium's own text search.
+    Text {
+        text: String,
+        options: PdfSearchOptions,
+    },
+
+    /// A regular expression, matched against the normalized text of each page.
+    #[cfg(feature = "regex")]
+    Regex(Regex),
+
+    /// An approximate match, allowing up to the given number of edits, against the
+    /// normalized, lowercased text of each page.
+    Fuzzy { text: Vec<char>, max_edits: usize },
+}
+
+impl<'a, 'b> PdfDocumentSearch<'a, 'b> {
+    #[inline]
+    pub(crate) fn new(pages: &'b PdfPages<'a>, text: &str, options: &PdfSearchOptions) -> Self {
+        Self::with_matcher(
+            pages,
+            PdfSearchMatcher::Text {
+                text: text.to_string(),
+                options: *options,
+            },
+        )
+    }
+
+    #[cfg(feature = "regex")]
+    #[inline]
+    pub(crate) fn new_regex(pages: &'b PdfPages<'a>, pattern: &Regex) -> Self {
+        Self::with_matcher(pages, PdfSearchMatcher::Regex(pattern.clone()))
+    }
+
+    #[inline]
+    pub(crate) fn new_fuzzy(pages: &'b PdfPages<'a>, text: &str, max_edits: usize) -> Self {
+        Self::with_matcher(
+            pages,
+            PdfSearchMatcher::Fuzzy {
+                text: text.chars().map(fold_case).collect(),
+                max_edits,
+            },
+        )
+    }
+
+    #[inline]
+    fn with_matcher(pages: &'b PdfPages<'a>, matcher: PdfSearchMatcher) -> Self {
+        PdfDocumentSearch {
+            pages,
+            matcher,
+            next_page_index: 0,
+            results: VecDeque::new(),
+        }
+    }
+
+    /// Returns `true` if the search cannot match anything, so no pages need be searched.
+    fn is_empty(&self) -> bool {
+        match &self.matcher {
+            PdfSearchMatcher::Text { text, .. } => text.is_empty(),
+            #[cfg(feature = "regex")]
+            PdfSearchMatcher::Regex(_) => false,
+            PdfSearchMatcher::Fuzzy { text, max_edits } => text.len() <= *max_edits,
+        }
+    }
+
+    /// Returns `true` if this search matches against the normalized text of each page,
+    /// rather than using Pdfium's own text search.
+    #[inline]
+    fn uses_normalized_text(&self) -> bool {
+        !matches!(self.matcher, PdfSearchMatcher::Text { .. })
+    }
+
+    /// Returns the ranges of page characters matched by this search in the given normalized
+    /// page text. Always returns no ranges for Pdfium's own text search.
+    fn find_in_normalized_text(
+        &self,
+        normalized: &PdfNormalizedText,
+    ) -> Vec<Range<PdfPageTextCharIndex>> {
+        match &self.matcher {
+            PdfSearchMatcher::Text { .. } => Vec::new(),
+            #[cfg(feature = "regex")]
+            PdfSearchMatcher::Regex(regex) => {
+                let haystack = normalized
+                    .chars
+                    .iter()
+                    .map(|(char, _)| *char)
+                    .collect::<String>();
+
+                let offsets = haystack
+                    .char_indices()
+                    .map(|(offset, _)| offset)
+                    .collect::<Vec<_>>();
+
+                regex
+                    .find_iter(&haystack)
+                    .filter(|found| !found.as_str().is_empty())
+                    .filter_map(|found| {
+                        let start = offsets.partition_point(|offset| *offset < found.start());
+
+                        let end = offsets.partition_point(|offset| *offset < found.end());
+
+                        normalized.char_range(start..end)
+                    })
+                    .collect()
+            }
+            PdfSearchMatcher::Fuzzy {
+                text: needle,
+                max_edits,
+            } => {
+                let haystack = normalized
+                    .chars
+                    .iter()
+                    .map(|(char, _)| fold_case(*char))
+                    .collect::<Vec<_>>();
+
+                find_approximate_matches(&haystack, needle, *max_edits)
+                    .into_iter()
+                    .filter_map(|range| normalized.char_range(range))
+                    .collect()
+            }
+        }
+    }
+
+    /// Searches the page at the given index, queueing all results found on the page.
+    fn search_page(&mut self, index: PdfPageIndex) {
+        let document = self.pages.document_handle();
+
+        let generation = PdfContentGenerations::get_for_page(document, index);
+
+        let mut ranges = None;
+
+        if self.uses_normalized_text() {
+            if let Some(normalized) = PdfNormalizedTextCache::get(document, index, generation) {
+                let found = self.find_in_normalized_text(&normalized);
+
+                if found.is_empty() {
+                    // There is nothing on this page to measure, so it need not be loaded.
+
+                    return;
+                }
+
+                ranges = Some(found);
+            }
+        }
+
+        let page = match self.pages.get(index) {
+            Ok(page) => page,
+            Err(_) => return,
+        };
+
+        let text = match page.text() {
+            Ok(text) => text,
+            Err(_) => return,
+        };
+
+        let ranges = match (ranges, &self.matcher) {
+            (Some(ranges), _) => ranges,
+            (
+                None,
+                PdfSearchMatcher::Text {
+                    text: needle,
+                    options,
+                },
+            ) => {
+                let search = text.search(needle, options);
+
+                let mut ranges = Vec::new();
+
+                while let Some(segments) = search.find_next() {
+                    ranges.push(segments.char_range());
+                }
+
+                ranges
+            }
+            (None, _) => {
+                let normalized = Arc::new(PdfNormalizedText::from_page_text(&text));
+
+                PdfNormalizedTextCache::set(document, index, generation, normalized.clone());
+
+                self.find_in_normalized_text(&normalized)
+            }
+        };
+
+        for range in ranges {
+            let segments = text.segments_subset(range.start, range.end - range.start);
+
+            self.results.push_back(PdfSearchResult {
+                page_index: index,
+                char_range: range,
+                bounding_rects: segments.iter().map(|segment| segment.bounds()).collect(),
+            });
+        }
+    }
+}
+
+impl<'a, 'b> Iterator for PdfDocumentSearch<'a, 'b> {
+    type Item = PdfSearchResult;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.results.is_empty() {
+            if self.is_empty() || self.next_page_index >= self.pages.len() {
+                return None;
+            }
+
+            let index = self.next_page_index;
+
+            self.next_page_index += 1;
+
+            self.search_page(index);
+        }
+
+        self.results.pop_front()
+    }
+}
+
+/// The text of a single page, normalized for pattern matching: whitespace, including line
+/// breaks, is collapsed into single spaces, and a hyphen followed by a line break is removed
+/// together with the line break if it falls inside a word, with letters on both sides, so
+/// that words broken across lines can be matched. Other hyphens, such as those in numeric
+/// ranges or at the start of list items, are kept. Each normalized character is paired
+/// with the index of the page character it was derived from.
+struct PdfNormalizedText {
+    chars: Vec<(char, PdfPageTextCharIndex)>,
+}
+
+impl PdfNormalizedText {
+    fn from_page_text(text: &PdfPageText) -> Self {
+        let handle = *text.handle();
+
+        let bindings = text.bindings();
+
+        let count = bindings.FPDFText_CountChars(handle).max(0) as PdfPageTextCharIndex;
+
+        let page_chars = (0..count)
+            .map(|index| {
+                char::from_u32(bindings.FPDFText_GetUnicode(handle, index as i32))
+                    .unwrap_or(char::REPLACEMENT_CHARACTER)
+            })
+            .collect::<Vec<_>>();
+
+        Self::from_chars(&page_chars, |index| is_pdfium_hyphen(text, index, bindings))
+    }
+
+    /// Normalizes the given page characters. The given function returns `true` if Pdfium
+    /// reports the character at the given index as a hyphen.
+    fn from_chars(page_chars: &[char], is_hyphen: impl Fn(PdfPageTextCharIndex) -> bool) -> Self {
+        let mut chars: Vec<(char, PdfPageTextCharIndex)> = Vec::with_capacity(page_chars.len());
+
+        let mut index = 0;
+
+        while index < page_chars.len() {
+            let char = page_chars[index];
+
+            let mut next = index + 1;
+
+            while matches!(page_chars.get(next), Some('\r' | '\n')) {
+                next += 1;
+            }
+
+            let is_inside_word = next > index + 1
+                && index > 0
+                && page_chars[index - 1].is_alphabetic()
+                && matches!(page_chars.get(next), Some(char) if char.is_alphabetic());
+
+            if is_inside_word && (matches!(char, '-' | '\u{00AD}' | '\u{2010}') || is_hyphen(index))
+            {
+                // Skip the hyphen and the line break following it, joining the two halves
+                // of the broken word.
+
+                index = next;
+
+                continue;
+            }
+
+            if char.is_whitespace() {
+                if !chars.is_empty() && !matches!(chars.last(), Some((' ', _))) {
+                    chars.push((' ', index));
+                }
+            } else {
+                chars.push((char, index));
+            }
+
+            index += 1;
+        }
+
+        PdfNormalizedText { chars }
+    }
+
+    /// Returns the range of page character indices spanned by the normalized characters
+    /// at the given positions, or `None` if the given range is empty.
+    fn char_range(&self, positions: Range<usize>) -> Option<Range<PdfPageTextCharIndex>> {
+        if positions.start >= positions.end || positions.end > self.chars.len() {
+            return None;
+        }
+
+        Some(self.chars[positions.start].1..self.chars[positions.end - 1].1 + 1)
+    }
+}
+
+/// Normalized page text for all open documents. We keep this so that repeated regular
+/// expression and fuzzy searches of a document need not extract the text of every page afresh.
+static NORMALIZED_TEXT_CACHE: Lazy<Mutex<PdfNormalizedTextCache>> =
+    Lazy::new(|| Mutex::new(PdfNormalizedTextCache::new()));
+
+/// Each cached text is stored along with the content generation of its page at the time the
+/// text was extracted, and is only returned while the page remains at that generation.
+pub(crate) struct PdfNormalizedTextCache {
+    pages: HashMap<(FPDF_DOCUMENT, PdfPageIndex), (u64, Arc<PdfNormalizedText>)>,
+}
+
+impl PdfNormalizedTextCache {
+    #[inline]
+    fn new() -> Self {
+        Self {
+            pages: HashMap::new(),
+        }
+    }
+
+    #[inline]
+    fn lock() -> MutexGuard<'static, PdfNormalizedTextCache> {
+        NORMALIZED_TEXT_CACHE.lock().unwrap()
+    }
+
+    // The remaining methods in this implementation take care of thread-safe locking.
+    // These methods form the public API of the cache.
+
+    /// Returns the cached normalized text of the page at the given index in the given
+    /// document, if the page has not changed since the text was extracted at the given
+    /// content generation.
+    #[inline]
+    fn get(
+        document: FPDF_DOCUMENT,
+        index: PdfPageIndex,
+        generation: u64,
+    ) -> Option<Arc<PdfNormalizedText>> {
+        match Self::lock().pages.get(&(document, index)) {
+            Some((cached, text)) if *cached == generation => Some(text.clone()),
+            _ => None,
+        }
+    }
+
+    /// Caches the given normalized text of the page at the given index in the given document,
+    /// extracted at the given content generation.
+    #[inline]
+    fn set(
+        document: FPDF_DOCUMENT,
+        index: PdfPageIndex,
+        generation: u64,
+        text: Arc<PdfNormalizedText>,
+    ) {
+        Self::lock()
+            .pages
+            .insert((document, index), (generation, text));
+    }
+
+    /// Discards the cached text of all the pages in the given document.
+    pub(crate) fn close_document(document: FPDF_DOCUMENT) {
+        Self::lock().pages.retain(|(key, _), _| *key != document);
+    }
+}
+
+unsafe impl Send for PdfNormalizedTextCache {}
+
+unsafe impl Sync for PdfNormalizedTextCache {}
+
+/// Returns `true` if Pdfium reports the character at the given index as a hyphen.
+#[cfg(any(
+    feature = "pdfium_6015",
+    feature = "pdfium_6043",
+    feature = "pdfium_6084",
+    feature = "pdfium_6110",
+    feature = "pdfium_6124",
+    feature = "pdfium_6164",
+    feature = "pdfium_6259",
+    feature = "pdfium_6295",
+    feature = "pdfium_6337",
+    feature = "pdfium_6406",
+    feature = "pdfium_6490",
+    feature = "pdfium_6555",
+    feature = "pdfium_6569",
+    feature = "pdfium_6611",
+    feature = "pdfium_6666",
+    feature = "pdfium_future"
+))]
+#[inline]
+fn is_pdfium_hyphen(
+    text: &PdfPageText,
+    index: PdfPageTextCharIndex,
+    bindings: &dyn PdfiumLibraryBindings,
+) -> bool {
+    bindings.FPDFText_IsHyphen(*text.handle(), index as i32) == 1
+}
+
+/// Returns `true` if Pdfium reports the character at the given index as a hyphen.
+/// Always returns `false`, since FPDFText_IsHyphen() is not available in this Pdfium release.
+#[cfg(not(any(
+    feature = "pdfium_6015",
+    feature = "pdfium_6043",
+    feature = "pdfium_6084",
+    feature = "pdfium_6110",
+    feature = "pdfium_6124",
+    feature = "pdfium_6164",
+    feature = "pdfium_6259",
+    feature = "pdfium_6295",
+    feature = "pdfium_6337",
+    feature = "pdfium_6406",
+    feature = "pdfium_6490",
+    feature = "pdfium_6555",
+    feature = "pdfium_6569",
+    feature = "pdfium_6611",
+    feature = "pdfium_6666",
+    feature = "pdfium_future"
+)))]
+#[inline]
+fn is_pdfium_hyphen(
+    _text: &PdfPageText,
+    _index: PdfPageTextCharIndex,
+    _bindings: &dyn PdfiumLibraryBindings,
+) -> bool {
+    false
+}
+
+/// Folds the case of the given character for case-insensitive comparison.
+#[inline]
+fn fold_case(char: char) -> char {
+    char.to_lowercase().next().unwrap_or(char)
+}
+
+/// Returns the positions of non-overlapping substrings of the given text that can be turned
+/// into the given pattern with at most the given number of single-character insertions,
+/// deletions, and substitutions, using Sellers' algorithm. Where several candidate matches
+/// overlap, the candidate needing the fewest edits is returned.
+pub(crate) fn find_approximate_matches(
+    text: &[char],
+    pattern: &[char],
+    max_edits: usize,
+) -> Vec<Range<usize>> {
+    if pattern.len() <= max_edits {
+        return Vec::new();
+    }
+
+    // Each cell holds the lowest number of edits needed to match the pattern prefix
+    // ending at that row against a substring of the text ending at the current column,
+    // together with the position in the text at which that substring starts.
+
+    let mut previous = (0..=pattern.len()).map(|row| (row, 0)).collect::<Vec<_>>();
+
+    let mut current = previous.clone();
+
+    let mut results: Vec<(Range<usize>, usize)> = Vec::new();
+
+    for (column, char) in text.iter().enumerate() {
+        current[0] = (0, column + 1);
+
+        for row in 1..=pattern.len() {
+            let substitution = (
+                previous[row - 1].0 + if pattern[row - 1] == *char { 0 } else { 1 },
+                previous[row - 1].1,
+            );
+
+            let deletion = (current[row - 1].0 + 1, current[row - 1].1);
+
+            let insertion = (previous[row].0 + 1, previous[row].1);
+
+            current[row] = [deletion, insertion]
+                .iter()
+                .fold(substitution, |best, candidate| {
+                    if candidate.0 < best.0 {
+                        *candidate
+                    } else {
+                        best
+                    }
+                });
+        }
+
+        let (edits, start) = current[pattern.len()];
+
+        if edits <= max_edits {
+            let candidate = start..column + 1;
+
+            match results.last_mut() {
+                Some((range, best)) if candidate.start < range.end => {
+                    if edits < *best {
+                        *range = candidate;
+                        *best = edits;
+                    }
+                }
+                _ => results.push((candidate, edits)),
+            }
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    results.into_iter().map(|(range, _)| range).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::search::*;
+
+    #[test]
+    fn test_normalized_text_joins_hyphenated_words() {
+        let page_chars = "A cross-\r\nline  match".chars().collect::<Vec<_>>();
+
+        let normalized = PdfNormalizedText::from_chars(&page_chars, |_| false);
+
+        let text = normalized
+            .chars
+            .iter()
+            .map(|(char, _)| *char)
+            .collect::<String>();
+
+        assert_eq!(text, "A crossline match");
+
+        // The match spans the hyphen and line break in the page text.
+
+        assert_eq!(normalized.char_range(2..11), Some(2..14));
+    }
+
+    #[test]
+    fn test_normalized_text_keeps_hyphens_outside_words() {
+        let normalize = |text: &str| {
+            let page_chars = text.chars().collect::<Vec<_>>();
+
+            PdfNormalizedText::from_chars(&page_chars, |_| false)
+                .chars
+                .iter()
+                .map(|(char, _)| *char)
+                .collect::<String>()
+        };
+
+        assert_eq!(normalize("pages 3-\r\n4"), "pages 3- 4");
+        assert_eq!(normalize("items:\r\n-\r\nnext"), "items: - next");
+        assert_eq!(normalize("well -\r\nknown"), "well - known");
+        assert_eq!(normalize("well-known"), "well-known");
+        assert_eq!(normalize("co-\n\nop"), "coop");
+    }
+
+    #[test]
+    fn test_normalized_text_cache() {
+        // Fake document handles are never dereferenced by the cache.
+
+        let document = 0x7e47 as FPDF_DOCUMENT;
+
+        let text = Arc::new(PdfNormalizedText::from_chars(&['a'], |_| false));
+
+        PdfNormalizedTextCache::set(document, 0, 3, text);
+
+        assert!(PdfNormalizedTextCache::get(document, 0, 3).is_some());
+        assert!(PdfNormalizedTextCache::get(document, 0, 4).is_none());
+        assert!(PdfNormalizedTextCache::get(document, 1, 3).is_none());
+
+        PdfNormalizedTextCache::close_document(document);
+
+        assert!(PdfNormalizedTextCache::get(document, 0, 3).is_none());
+    }
+
+    #[test]
+    fn test_find_approximate_matches() {
+        let text = "the quick brown fox jumps".chars().collect::<Vec<_>>();
+
+        let pattern = |text: &str| text.chars().collect::<Vec<_>>();
+
+        assert_eq!(
+            find_approximate_matches(&text, &pattern("brown"), 0),
+            vec![10..15]
+        );
+        assert_eq!(
+            find_approximate_matches(&text, &pattern("brwn"), 1),
+            vec![10..15]
+        );
+        assert_eq!(
+            find_approximate_matches(&text, &pattern("fax"), 1),
+            vec![16..19]
+        );
+        assert!(find_approximate_matches(&text, &pattern("zebra"), 1).is_empty());
+        assert!(find_approximate_matches(&text, &pattern("ab"), 2).is_empty());
+    }
+}
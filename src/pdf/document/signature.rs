@@ -1,16 +1,62 @@
 //! Defines the [PdfSignature] struct, exposing functionality related to a single
 //! digital signature in a `PdfSignatures` collection.
 
+#[cfg(feature = "pkcs7")]
+pub mod pkcs7;
+
 use crate::bindgen::FPDF_SIGNATURE;
 use crate::bindings::PdfiumLibraryBindings;
-use crate::utils::mem::create_byte_buffer;
+use crate::pdf::document::signatures::PdfSignatureSourceFile;
+use crate::utils::dates::date_time_from_pdf_string;
+use crate::utils::mem::{create_byte_buffer, create_sized_buffer};
 use crate::utils::utf16le::get_string_from_pdfium_utf16le_bytes;
+use chrono::{DateTime, Utc};
+use std::convert::TryFrom;
 use std::ffi::CString;
-use std::os::raw::{c_char, c_void};
+use std::ops::Range;
+use std::os::raw::{c_char, c_int, c_void};
+
+#[cfg(feature = "pkcs7")]
+use crate::error::PdfiumError;
+
+#[cfg(feature = "pkcs7")]
+use crate::pdf::document::signature::pkcs7::{PdfSignatureCmsInfo, PdfSignatureIntegrity};
+
+/// The changes to a `PdfDocument` permitted by a certifying signature without invalidating
+/// the signature, as set by the `/P` entry of the signature's DocMDP transform parameters.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfSignatureDocMdpPermission {
+    /// No changes to the document are permitted.
+    NoChanges,
+
+    /// Filling in forms, instantiating page templates, and signing are permitted.
+    FormFilling,
+
+    /// Filling in forms, instantiating page templates, signing, and creating, deleting,
+    /// and modifying annotations are permitted.
+    FormFillingAndAnnotations,
+}
+
+impl PdfSignatureDocMdpPermission {
+    #[inline]
+    pub(crate) fn from_pdfium(permission: u32) -> Option<Self> {
+        match permission {
+            1 => Some(PdfSignatureDocMdpPermission::NoChanges),
+            2 => Some(PdfSignatureDocMdpPermission::FormFilling),
+            3 => Some(PdfSignatureDocMdpPermission::FormFillingAndAnnotations),
+            _ => None,
+        }
+    }
+}
 
 /// A single digital signature in a `PdfDocument`.
 pub struct PdfSignature<'a> {
     handle: FPDF_SIGNATURE,
+
+    #[cfg_attr(not(feature = "pkcs7"), allow(dead_code))]
+    // This field is only used when the pkcs7 crate feature is enabled.
+    source_file: Option<PdfSignatureSourceFile<'a>>,
+
     bindings: &'a dyn PdfiumLibraryBindings,
 }
 
@@ -18,9 +64,14 @@ impl<'a> PdfSignature<'a> {
     #[inline]
     pub(crate) fn from_pdfium(
         handle: FPDF_SIGNATURE,
+        source_file: Option<PdfSignatureSourceFile<'a>>,
         bindings: &'a dyn PdfiumLibraryBindings,
     ) -> Self {
-        PdfSignature { handle, bindings }
+        PdfSignature {
+            handle,
+            source_file,
+            bindings,
+        }
     }
 
     /// Returns the [PdfiumLibraryBindings] used by this [PdfSignature].
@@ -142,4 +193,274 @@ impl<'a> PdfSignature<'a> {
             None
         }
     }
+
+    /// Returns the date of signing specified by the creator of this [PdfSignature], if any,
+    /// parsed from the value returned by [PdfSignature::signing_date()].
+    ///
+    /// This value should only be used if the date of signing is not encoded into the digital signature itself.
+    #[inline]
+    pub fn signing_time(&self) -> Option<DateTime<Utc>> {
+        self.signing_date()
+            .and_then(|date| date_time_from_pdf_string(&date))
+    }
+
+    /// Returns the byte ranges of the document file covered by this [PdfSignature], as given
+    /// by the `/ByteRange` entry of the signature dictionary. The ranges are usually the whole
+    /// of the file as it was when it was signed, excluding the signature's own contents.
+    pub fn byte_ranges(&self) -> Vec<Range<usize>> {
+        // Retrieving the byte range from Pdfium is a two-step operation. First, we call
+        // FPDFSignatureObj_GetByteRange() with a null buffer; this will retrieve the number
+        // of integers in the byte range. If the number is zero, then there is no byte range
+        // associated with this signature.
+
+        // If the number is non-zero, then we reserve a buffer of the given length and call
+        // FPDFSignatureObj_GetByteRange() again with a pointer to the buffer; this will write
+        // pairs of (starting byte offset, length in bytes) integers to the buffer.
+
+        let buffer_length =
+            self.bindings()
+                .FPDFSignatureObj_GetByteRange(self.handle, std::ptr::null_mut(), 0);
+
+        if buffer_length == 0 {
+            // There is no byte range given for this signature.
+
+            return Vec::new();
+        }
+
+        let mut buffer = create_sized_buffer::<c_int>(buffer_length as usize);
+
+        let result = self.bindings().FPDFSignatureObj_GetByteRange(
+            self.handle,
+            buffer.as_mut_ptr(),
+            buffer_length,
+        );
+
+        assert_eq!(result, buffer_length);
+
+        byte_ranges_from_pairs(&buffer)
+    }
+
+    /// Returns the encoding of the contents of this [PdfSignature], as given by the
+    /// `/SubFilter` entry of the signature dictionary, if any. Common values include
+    /// `adbe.pkcs7.detached`, `adbe.pkcs7.sha1`, `adbe.x509.rsa_sha1`, and
+    /// `ETSI.CAdES.detached`.
+    pub fn sub_filter(&self) -> Option<String> {
+        // Retrieving the sub-filter from Pdfium is a two-step operation. First, we call
+        // FPDFSignatureObj_GetSubFilter() with a null buffer; this will retrieve the length of
+        // the encoding name in bytes. If the length is zero, then there is no sub-filter
+        // associated with this signature.
+
+        // If the length is non-zero, then we reserve a byte buffer of the given
+        // length and call FPDFSignatureObj_GetSubFilter() again with a pointer to the buffer;
+        // this will write the encoding name to the buffer as an array of 7-bit ASCII characters.
+
+        let buffer_length =
+            self.bindings()
+                .FPDFSignatureObj_GetSubFilter(self.handle, std::ptr::null_mut(), 0);
+
+        if buffer_length == 0 {
+            // There is no sub-filter given for this signature.
+
+            return None;
+        }
+
+        let mut buffer = create_byte_buffer(buffer_length as usize);
+
+        let result = self.bindings().FPDFSignatureObj_GetSubFilter(
+            self.handle,
+            buffer.as_mut_ptr() as *mut c_char,
+            buffer_length,
+        );
+
+        assert_eq!(result, buffer_length);
+
+        if let Ok(result) = CString::from_vec_with_nul(buffer) {
+            result.into_string().ok()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the changes to the document permitted by this [PdfSignature] without
+    /// invalidating it, if this is a certifying signature with DocMDP transform parameters.
+    /// Returns `None` for ordinary approval signatures.
+    #[inline]
+    pub fn doc_mdp_permission(&self) -> Option<PdfSignatureDocMdpPermission> {
+        PdfSignatureDocMdpPermission::from_pdfium(
+            self.bindings()
+                .FPDFSignatureObj_GetDocMDPPermission(self.handle),
+        )
+    }
+
+    /// Parses the PKCS#7 (CMS) contents of this [PdfSignature], returning details of the
+    /// signer, the certificates embedded in the signature, and the message digest.
+    ///
+    /// Returns [PdfiumError::InvalidSignatureContents] if the contents are not a PKCS#7
+    /// `signedData` structure, as is the case for signatures using the `adbe.x509.rsa_sha1`
+    /// sub-filter, whose contents are a PKCS#1 binary.
+    ///
+    /// This function is only available when the `pkcs7` crate feature is enabled.
+    #[cfg(feature = "pkcs7")]
+    #[inline]
+    pub fn cms_info(&self) -> Result<PdfSignatureCmsInfo, PdfiumError> {
+        PdfSignatureCmsInfo::from_bytes(&self.bytes())
+    }
+
+    /// Checks that the content of the document covered by this [PdfSignature] has not changed
+    /// since it was signed, by computing the digest of the byte ranges returned by
+    /// [PdfSignature::byte_ranges()] and comparing it against the message digest recorded
+    /// in the signature's PKCS#7 contents.
+    ///
+    /// The document's content is read from the bytes the document was loaded from, if they
+    /// were retained: that is, if the document was opened using
+    /// `Pdfium::load_pdf_from_byte_slice()` or `Pdfium::load_pdf_from_byte_vec()`. Documents
+    /// opened from files or readers do not retain their bytes; for these, the complete file
+    /// as it was loaded must be given. Given bytes are always used in preference to the
+    /// retained bytes. Since Pdfium rewrites the whole file when a `PdfDocument` is saved,
+    /// the bytes of a saved copy of the document cannot be checked.
+    ///
+    /// Only the integrity of the signed content is checked. No attempt is made to check that
+    /// the signature was produced by the signer's certificate, or that the certificate can be
+    /// trusted.
+    ///
+    /// Returns [PdfiumError::SignedFileUnavailable] if no bytes are given and the document's
+    /// bytes were not retained, [PdfiumError::InvalidSignatureContents] if the contents of this
+    /// [PdfSignature] cannot be parsed, or [PdfiumError::SignatureDigestUnavailable] if the
+    /// signature does not record a message digest computed using a recognized digest algorithm.
+    ///
+    /// This function is only available when the `pkcs7` crate feature is enabled.
+    #[cfg(feature = "pkcs7")]
+    pub fn verify_document_integrity(
+        &self,
+        file: Option<&[u8]>,
+    ) -> Result<PdfSignatureIntegrity, PdfiumError> {
+        let file = match file {
+            Some(file) => file,
+            None => self
+                .source_file
+                .as_ref()
+                .map(|source_file| source_file.as_bytes())
+                .ok_or(PdfiumError::SignedFileUnavailable)?,
+        };
+
+        PdfSignatureIntegrity::verify(file, &self.byte_ranges(), &self.cms_info()?)
+    }
+}
+
+/// Converts the given pairs of (starting byte offset, length in bytes) integers into byte
+/// ranges. Pairs with a negative offset or length, or whose end cannot be represented,
+/// are skipped.
+fn byte_ranges_from_pairs(pairs: &[c_int]) -> Vec<Range<usize>> {
+    pairs
+        .chunks_exact(2)
+        .filter_map(|pair| {
+            let start = usize::try_from(pair[0]).ok()?;
+
+            let length = usize::try_from(pair[1]).ok()?;
+
+            start.checked_add(length).map(|end| start..end)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::signature::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_byte_ranges_from_pairs() {
+        assert_eq!(
+            byte_ranges_from_pairs(&[0, 100, 164, 36]),
+            vec![0..100, 164..200]
+        );
+
+        // Negative values and unpaired integers are skipped.
+
+        assert_eq!(
+            byte_ranges_from_pairs(&[-1, 10, 20, -5, 30, 5, 40]),
+            vec![30..35]
+        );
+
+        assert_eq!(
+            byte_ranges_from_pairs(&[c_int::MAX, c_int::MAX]),
+            vec![c_int::MAX as usize..c_int::MAX as usize * 2]
+        );
+    }
+
+    #[test]
+    fn test_signature_byte_ranges() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let file = std::fs::read("./test/signatures-test.pdf").unwrap();
+
+        let document = pdfium.load_pdf_from_byte_slice(&file, None)?;
+
+        assert_eq!(document.signatures().len(), 3);
+
+        let mut byte_ranges = document
+            .signatures()
+            .iter()
+            .map(|signature| signature.byte_ranges())
+            .collect::<Vec<_>>();
+
+        byte_ranges.sort_by_key(|ranges| ranges.last().map(|range| range.end));
+
+        // Each signature covers the whole of the file as it was when it was signed, excluding
+        // its own contents. The last signature covers the whole of the final file.
+
+        assert_eq!(
+            byte_ranges,
+            vec![
+                vec![0..210013, 210277..218067],
+                vec![0..247803, 248067..255473],
+                vec![0..285210, 285474..292691],
+            ]
+        );
+        assert_eq!(byte_ranges[2][1].end, file.len());
+
+        // None of the signatures is a certifying signature.
+
+        for signature in document.signatures().iter() {
+            assert_eq!(signature.doc_mdp_permission(), None);
+        }
+
+        Ok(())
+    }
+
+    #[cfg(feature = "pkcs7")]
+    #[test]
+    fn test_verify_document_integrity_uses_source_file() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let file = std::fs::read("./test/signatures-test.pdf").unwrap();
+
+        // Documents loaded from bytes retain them, so no bytes need to be given.
+
+        for document in [
+            pdfium.load_pdf_from_byte_slice(&file, None)?,
+            pdfium.load_pdf_from_byte_vec(file.clone(), None)?,
+        ] {
+            for signature in document.signatures().iter() {
+                assert_eq!(
+                    format!("{:?}", signature.verify_document_integrity(None)),
+                    format!("{:?}", signature.verify_document_integrity(Some(&file)))
+                );
+            }
+        }
+
+        // Documents loaded from files do not.
+
+        let document = pdfium.load_pdf_from_file("./test/signatures-test.pdf", None)?;
+
+        let signature = document.signatures().get(0)?;
+
+        assert!(matches!(
+            signature.verify_document_integrity(None),
+            Err(PdfiumError::SignedFileUnavailable)
+        ));
+
+        Ok(())
+    }
 }
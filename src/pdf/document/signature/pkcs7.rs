@@ -0,0 +1,820 @@
+//! Defines the [PdfSignatureCmsInfo] struct, exposing the signer, certificates, and digest
+//! details parsed from the PKCS#7 (CMS) contents of a single `PdfSignature`, and the
+//! [PdfSignatureIntegrity] enum, the result of checking that the signed content of a
+//! document is unchanged.
+
+use crate::error::PdfiumError;
+use chrono::{DateTime, NaiveDate, TimeZone, Utc};
+use md5::Md5;
+use sha1::Sha1;
+use sha2::{Digest, Sha224, Sha256, Sha384, Sha512};
+use std::ops::Range;
+use yasna::models::ObjectIdentifier;
+use yasna::{ASN1Error, ASN1ErrorKind, ASN1Result, BERReader, Tag};
+
+/// The PKCS#7 `signedData` content type.
+const OID_SIGNED_DATA: &[u64] = &[1, 2, 840, 113549, 1, 7, 2];
+
+/// The PKCS#9 `messageDigest` signed attribute.
+const OID_MESSAGE_DIGEST: &[u64] = &[1, 2, 840, 113549, 1, 9, 4];
+
+/// The PKCS#9 `signingTime` signed attribute.
+const OID_SIGNING_TIME: &[u64] = &[1, 2, 840, 113549, 1, 9, 5];
+
+/// The X.520 `commonName` attribute type.
+const OID_COMMON_NAME: &[u64] = &[2, 5, 4, 3];
+
+/// The maximum number of trailing zero bytes that may belong to the PKCS#7 data itself
+/// rather than to the padding that fills the remainder of a signature's `/Contents` entry.
+/// Data in the indefinite-length form of BER ends with two zero bytes for each level of
+/// nesting still open.
+const MAXIMUM_TRAILING_ZERO_BYTES: usize = 64;
+
+/// The algorithm used to compute the message digest of a signed document.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PdfSignatureDigestAlgorithm {
+    Md5,
+    Sha1,
+    Sha224,
+    Sha256,
+    Sha384,
+    Sha512,
+
+    /// An algorithm not recognized by `pdfium-render`, given by its object identifier
+    /// in dotted decimal form, such as `1.2.840.113549.2.5`.
+    Other(String),
+}
+
+impl PdfSignatureDigestAlgorithm {
+    #[inline]
+    fn from_oid(oid: &ObjectIdentifier) -> Self {
+        match oid.components().as_slice() {
+            [1, 2, 840, 113549, 2, 5] => PdfSignatureDigestAlgorithm::Md5,
+            [1, 3, 14, 3, 2, 26] => PdfSignatureDigestAlgorithm::Sha1,
+            [2, 16, 840, 1, 101, 3, 4, 2, 4] => PdfSignatureDigestAlgorithm::Sha224,
+            [2, 16, 840, 1, 101, 3, 4, 2, 1] => PdfSignatureDigestAlgorithm::Sha256,
+            [2, 16, 840, 1, 101, 3, 4, 2, 2] => PdfSignatureDigestAlgorithm::Sha384,
+            [2, 16, 840, 1, 101, 3, 4, 2, 3] => PdfSignatureDigestAlgorithm::Sha512,
+            _ => PdfSignatureDigestAlgorithm::Other(oid.to_string()),
+        }
+    }
+
+    /// Returns the digest of the concatenation of the given byte slices computed using this
+    /// [PdfSignatureDigestAlgorithm], or `None` if the algorithm is not recognized.
+    fn digest(&self, parts: &[&[u8]]) -> Option<Vec<u8>> {
+        fn digest_with<D: Digest>(parts: &[&[u8]]) -> Vec<u8> {
+            let mut digest = D::new();
+
+            for part in parts.iter() {
+                digest.update(part);
+            }
+
+            digest.finalize().to_vec()
+        }
+
+        match self {
+            PdfSignatureDigestAlgorithm::Md5 => Some(digest_with::<Md5>(parts)),
+            PdfSignatureDigestAlgorithm::Sha1 => Some(digest_with::<Sha1>(parts)),
+            PdfSignatureDigestAlgorithm::Sha224 => Some(digest_with::<Sha224>(parts)),
+            PdfSignatureDigestAlgorithm::Sha256 => Some(digest_with::<Sha256>(parts)),
+            PdfSignatureDigestAlgorithm::Sha384 => Some(digest_with::<Sha384>(parts)),
+            PdfSignatureDigestAlgorithm::Sha512 => Some(digest_with::<Sha512>(parts)),
+            PdfSignatureDigestAlgorithm::Other(_) => None,
+        }
+    }
+}
+
+/// The result of checking that the content of a `PdfDocument` covered by a `PdfSignature`
+/// has not changed since it was signed, as returned by the
+/// `PdfSignature::verify_document_integrity()` function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PdfSignatureIntegrity {
+    /// The signed content is unchanged, and the signature covers the whole file other than
+    /// the signature's own contents.
+    Intact,
+
+    /// The signed content is unchanged, but the given byte ranges of the file are not covered
+    /// by the signature. These are usually incremental updates appended to the file after it
+    /// was signed, which may add further signatures or fill in form fields, but may also
+    /// change how the signed content is displayed.
+    UnsignedRangesPresent(Vec<Range<usize>>),
+
+    /// The signed content has changed since it was signed, or the file is shorter than
+    /// the signed byte ranges.
+    ModifiedAfterSigning,
+}
+
+impl PdfSignatureIntegrity {
+    /// Checks the given byte ranges of the given file against the message digest recorded
+    /// in the given PKCS#7 contents of the signature covering those ranges.
+    pub(crate) fn verify(
+        file: &[u8],
+        ranges: &[Range<usize>],
+        info: &PdfSignatureCmsInfo,
+    ) -> Result<Self, PdfiumError> {
+        let message_digest = info
+            .message_digest()
+            .ok_or(PdfiumError::SignatureDigestUnavailable)?;
+
+        if ranges.is_empty() {
+            return Err(PdfiumError::SignatureDigestUnavailable);
+        }
+
+        if ranges
+            .iter()
+            .any(|range| range.start > range.end || range.end > file.len())
+        {
+            return Ok(PdfSignatureIntegrity::ModifiedAfterSigning);
+        }
+
+        let parts = ranges
+            .iter()
+            .map(|range| &file[range.clone()])
+            .collect::<Vec<_>>();
+
+        let digest = info
+            .digest_algorithm()
+            .digest(&parts)
+            .ok_or(PdfiumError::SignatureDigestUnavailable)?;
+
+        if digest != message_digest {
+            return Ok(PdfSignatureIntegrity::ModifiedAfterSigning);
+        }
+
+        // Any part of the file not covered by the signed byte ranges, other than the
+        // hexadecimal string holding the signature's own contents, is unsigned.
+
+        let mut sorted = ranges.to_vec();
+
+        sorted.sort_by_key(|range| range.start);
+
+        let mut gaps = Vec::new();
+
+        let mut position = 0;
+
+        for range in sorted.iter() {
+            if range.start > position {
+                gaps.push(position..range.start);
+            }
+
+            position = position.max(range.end);
+        }
+
+        if position < file.len() {
+            gaps.push(position..file.len());
+        }
+
+        let unsigned = gaps
+            .into_iter()
+            .filter(|gap| !is_hex_string(&file[gap.clone()]))
+            .collect::<Vec<_>>();
+
+        Ok(if unsigned.is_empty() {
+            PdfSignatureIntegrity::Intact
+        } else {
+            PdfSignatureIntegrity::UnsignedRangesPresent(unsigned)
+        })
+    }
+}
+
+/// Returns `true` if the given bytes are a PDF hexadecimal string, as used to hold the
+/// contents of a signature.
+#[inline]
+fn is_hex_string(bytes: &[u8]) -> bool {
+    bytes.len() >= 2
+        && bytes[0] == b'<'
+        && bytes[bytes.len() - 1] == b'>'
+        && bytes[1..bytes.len() - 1]
+            .iter()
+            .all(|byte| byte.is_ascii_hexdigit() || byte.is_ascii_whitespace())
+}
+
+/// A single X.509 certificate embedded in the PKCS#7 contents of a `PdfSignature`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfSignatureCertificate {
+    subject_common_name: Option<String>,
+    issuer_common_name: Option<String>,
+    serial_number: Vec<u8>,
+    subject: Vec<u8>,
+    issuer: Vec<u8>,
+    der: Vec<u8>,
+}
+
+impl PdfSignatureCertificate {
+    /// Parses the given DER-encoded X.509 certificate.
+    fn from_der(der: Vec<u8>) -> ASN1Result<Self> {
+        let (serial_number, issuer, subject) = yasna::parse_ber(&der, |reader| {
+            reader.read_sequence(|reader| {
+                let result = reader.next().read_sequence(|reader| {
+                    // The version is optional, and tagged when present.
+
+                    reader.read_optional(|reader| {
+                        reader.read_tagged(Tag::context(0), |reader| reader.read_der())
+                    })?;
+
+                    let serial_number = reader.next().read_bigint_bytes()?.0;
+
+                    let _signature_algorithm = reader.next().read_der()?;
+
+                    let issuer = reader.next().read_der()?;
+
+                    let _validity = reader.next().read_der()?;
+
+                    let subject = reader.next().read_der()?;
+
+                    skip_remaining(reader)?;
+
+                    Ok((serial_number, issuer, subject))
+                })?;
+
+                skip_remaining(reader)?;
+
+                Ok(result)
+            })
+        })?;
+
+        Ok(PdfSignatureCertificate {
+            subject_common_name: common_name(&subject),
+            issuer_common_name: common_name(&issuer),
+            serial_number,
+            subject,
+            issuer,
+            der,
+        })
+    }
+
+    /// Returns the common name (CN) of the subject of this [PdfSignatureCertificate], if any.
+    #[inline]
+    pub fn subject_common_name(&self) -> Option<&str> {
+        self.subject_common_name.as_deref()
+    }
+
+    /// Returns the common name (CN) of the issuer of this [PdfSignatureCertificate], if any.
+    #[inline]
+    pub fn issuer_common_name(&self) -> Option<&str> {
+        self.issuer_common_name.as_deref()
+    }
+
+    /// Returns the big-endian serial number of this [PdfSignatureCertificate].
+    #[inline]
+    pub fn serial_number(&self) -> &[u8] {
+        self.serial_number.as_slice()
+    }
+
+    /// Returns the DER encoding of this [PdfSignatureCertificate], suitable for passing
+    /// to an X.509 library for trust chain validation.
+    #[inline]
+    pub fn der(&self) -> &[u8] {
+        self.der.as_slice()
+    }
+
+    /// Returns `true` if this [PdfSignatureCertificate] was issued by the given certificate.
+    /// Only the names of the two certificates are compared.
+    #[inline]
+    fn is_issued_by(&self, issuer: &PdfSignatureCertificate) -> bool {
+        self.issuer == issuer.subject
+    }
+}
+
+/// The details of the signer, certificates, and message digest parsed from the PKCS#7 (CMS)
+/// contents of a `PdfSignature`, as returned by the `PdfSignature::cms_info()` function.
+///
+/// No attempt is made to check that the signature was produced by the signer's certificate,
+/// or that the certificates can be trusted. The DER encoding of each certificate can be
+/// passed to an X.509 library to perform that validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfSignatureCmsInfo {
+    digest_algorithm: PdfSignatureDigestAlgorithm,
+    message_digest: Option<Vec<u8>>,
+    signing_time: Option<DateTime<Utc>>,
+    certificates: Vec<PdfSignatureCertificate>,
+    signer_index: Option<usize>,
+}
+
+impl PdfSignatureCmsInfo {
+    /// Parses the given PKCS#7 (CMS) `signedData` structure, as retrieved from the
+    /// `/Contents` entry of a signature dictionary. Any zero bytes padding the
+    /// structure are ignored.
+    pub(crate) fn from_bytes(bytes: &[u8]) -> Result<Self, PdfiumError> {
+        let length = bytes
+            .iter()
+            .rposition(|byte| *byte != 0)
+            .map_or(0, |index| index + 1);
+
+        (length..=bytes.len().min(length + MAXIMUM_TRAILING_ZERO_BYTES))
+            .find_map(|end| yasna::parse_ber(&bytes[..end], read_content_info).ok())
+            .ok_or(PdfiumError::InvalidSignatureContents)
+    }
+
+    /// Returns the algorithm used to compute the message digest of the signed document.
+    #[inline]
+    pub fn digest_algorithm(&self) -> &PdfSignatureDigestAlgorithm {
+        &self.digest_algorithm
+    }
+
+    /// Returns the message digest of the signed byte ranges of the document, as recorded
+    /// by the signer, if the signature includes signed attributes.
+    #[inline]
+    pub fn message_digest(&self) -> Option<&[u8]> {
+        self.message_digest.as_deref()
+    }
+
+    /// Returns the time of signing recorded by the signer in the signed attributes of
+    /// the signature, if any. Where available, this should be preferred over the time
+    /// returned by `PdfSignature::signing_time()`.
+    #[inline]
+    pub fn signing_time(&self) -> Option<DateTime<Utc>> {
+        self.signing_time
+    }
+
+    /// Returns the common name (CN) of the signer, taken from the subject of the
+    /// signer's certificate, if the certificate is embedded in the signature.
+    #[inline]
+    pub fn signer_common_name(&self) -> Option<&str> {
+        self.signer_certificate()
+            .and_then(|certificate| certificate.subject_common_name())
+    }
+
+    /// Returns the signer's certificate, if it is embedded in the signature.
+    #[inline]
+    pub fn signer_certificate(&self) -> Option<&PdfSignatureCertificate> {
+        self.signer_index.map(|index| &self.certificates[index])
+    }
+
+    /// Returns every certificate embedded in the signature. The signer's certificate, if
+    /// present, comes first, followed by its chain of issuing certificates in order,
+    /// followed by any other certificates in the order in which they are embedded.
+    #[inline]
+    pub fn certificates(&self) -> &[PdfSignatureCertificate] {
+        self.certificates.as_slice()
+    }
+}
+
+/// Reads a PKCS#7 `ContentInfo` structure containing `signedData`.
+fn read_content_info(reader: BERReader) -> ASN1Result<PdfSignatureCmsInfo> {
+    reader.read_sequence(|reader| {
+        if reader.next().read_oid()?.components().as_slice() != OID_SIGNED_DATA {
+            return Err(ASN1Error::new(ASN1ErrorKind::Invalid));
+        }
+
+        reader.next().read_tagged(Tag::context(0), read_signed_data)
+    })
+}
+
+/// Reads a PKCS#7 `SignedData` structure, taking the details of its first signer.
+fn read_signed_data(reader: BERReader) -> ASN1Result<PdfSignatureCmsInfo> {
+    reader.read_sequence(|reader| {
+        let _version = reader.next().read_der()?;
+
+        let _digest_algorithms = reader.next().read_der()?;
+
+        let _encapsulated_content_info = reader.next().read_der()?;
+
+        let certificates = reader
+            .read_optional(|reader| {
+                reader.read_tagged_implicit(Tag::context(0), |reader| {
+                    reader.collect_set_of(|reader| reader.read_der())
+                })
+            })?
+            .unwrap_or_default()
+            .into_iter()
+            // Certificate choices other than X.509 certificates are skipped.
+            .filter_map(|der| PdfSignatureCertificate::from_der(der).ok())
+            .collect::<Vec<_>>();
+
+        if reader.next().lookahead_tag()? == Tag::context(1) {
+            let _revocation_information = reader.next().read_der()?;
+        }
+
+        let mut signers = reader
+            .next()
+            .collect_set_of(|reader| reader.read_der())?
+            .into_iter();
+
+        let signer = signers
+            .next()
+            .ok_or_else(|| ASN1Error::new(ASN1ErrorKind::Invalid))?;
+
+        let (digest_algorithm, message_digest, signing_time, issuer, serial_number) =
+            yasna::parse_ber(&signer, read_signer_info)?;
+
+        let signer_index = certificates.iter().position(|certificate| {
+            issuer.as_ref().map_or(false, |issuer| {
+                certificate.issuer == *issuer && certificate.serial_number == serial_number
+            })
+        });
+
+        let certificates = match signer_index {
+            Some(index) => order_certificate_chain(certificates, index),
+            None => certificates,
+        };
+
+        Ok(PdfSignatureCmsInfo {
+            digest_algorithm,
+            message_digest,
+            signing_time,
+            certificates,
+            signer_index: signer_index.map(|_| 0),
+        })
+    })
+}
+
+/// The digest algorithm, message digest, signing time, and signer certificate issuer and
+/// serial number read from a PKCS#7 `SignerInfo` structure.
+type PdfSignerInfo = (
+    PdfSignatureDigestAlgorithm,
+    Option<Vec<u8>>,
+    Option<DateTime<Utc>>,
+    Option<Vec<u8>>,
+    Vec<u8>,
+);
+
+/// Reads a PKCS#7 `SignerInfo` structure.
+fn read_signer_info(reader: BERReader) -> ASN1Result<PdfSignerInfo> {
+    reader.read_sequence(|reader| {
+        let _version = reader.next().read_der()?;
+
+        // The signer's certificate is identified either by its issuer and serial number,
+        // or by a tagged subject key identifier, which is not supported.
+
+        let identifier = reader.next().read_der()?;
+
+        let (issuer, serial_number) = yasna::parse_ber(&identifier, |reader| {
+            reader.read_sequence(|reader| {
+                let issuer = reader.next().read_der()?;
+
+                let serial_number = reader.next().read_bigint_bytes()?.0;
+
+                Ok((Some(issuer), serial_number))
+            })
+        })
+        .unwrap_or((None, Vec::new()));
+
+        let digest_algorithm = reader.next().read_sequence(|reader| {
+            let oid = reader.next().read_oid()?;
+
+            skip_remaining(reader)?;
+
+            Ok(PdfSignatureDigestAlgorithm::from_oid(&oid))
+        })?;
+
+        let mut message_digest = None;
+
+        let mut signing_time = None;
+
+        reader.read_optional(|reader| {
+            reader.read_tagged_implicit(Tag::context(0), |reader| {
+                reader.read_set_of(|reader| {
+                    reader.read_sequence(|reader| {
+                        let oid = reader.next().read_oid()?;
+
+                        let value = reader.next().collect_set_of(|reader| reader.read_der())?;
+
+                        let value = match value.first() {
+                            Some(value) => value,
+                            None => return Ok(()),
+                        };
+
+                        if oid.components().as_slice() == OID_MESSAGE_DIGEST {
+                            message_digest =
+                                yasna::parse_ber(value, |reader| reader.read_bytes()).ok();
+                        } else if oid.components().as_slice() == OID_SIGNING_TIME {
+                            signing_time =
+                                yasna::parse_ber(value, |reader| reader.read_tagged_der())
+                                    .ok()
+                                    .and_then(|time| parse_asn1_time(time.tag(), time.value()));
+                        }
+
+                        Ok(())
+                    })
+                })
+            })
+        })?;
+
+        skip_remaining(reader)?;
+
+        Ok((
+            digest_algorithm,
+            message_digest,
+            signing_time,
+            issuer,
+            serial_number,
+        ))
+    })
+}
+
+/// Skips any remaining elements in a sequence.
+#[inline]
+fn skip_remaining(reader: &mut yasna::BERReaderSeq) -> ASN1Result<()> {
+    while reader.read_optional(|reader| reader.read_der())?.is_some() {}
+
+    Ok(())
+}
+
+/// Reorders the given certificates so that the certificate at the given index comes first,
+/// followed by its chain of issuing certificates, followed by all other certificates in
+/// their original order.
+fn order_certificate_chain(
+    mut certificates: Vec<PdfSignatureCertificate>,
+    index: usize,
+) -> Vec<PdfSignatureCertificate> {
+    let mut result = vec![certificates.remove(index)];
+
+    while let Some(last) = result.last() {
+        if last.issuer == last.subject {
+            // The chain ends with a self-signed root certificate.
+
+            break;
+        }
+
+        match certificates
+            .iter()
+            .position(|certificate| last.is_issued_by(certificate))
+        {
+            Some(issuer) => result.push(certificates.remove(issuer)),
+            None => break,
+        }
+    }
+
+    result.extend(certificates);
+
+    result
+}
+
+/// Returns the first common name (CN) in the given DER-encoded X.501 name, if any.
+fn common_name(name: &[u8]) -> Option<String> {
+    yasna::parse_ber(name, |reader| {
+        let mut result = None;
+
+        reader.read_sequence_of(|reader| {
+            reader.read_set_of(|reader| {
+                reader.read_sequence(|reader| {
+                    let oid = reader.next().read_oid()?;
+
+                    let value = reader.next().read_tagged_der()?;
+
+                    if result.is_none() && oid.components().as_slice() == OID_COMMON_NAME {
+                        result = decode_directory_string(value.tag(), value.value());
+                    }
+
+                    Ok(())
+                })
+            })
+        })?;
+
+        Ok(result)
+    })
+    .ok()
+    .flatten()
+}
+
+/// Decodes the value of an X.520 `DirectoryString`.
+fn decode_directory_string(tag: Tag, value: &[u8]) -> Option<String> {
+    match tag {
+        // BMPString, encoded in UTF-16BE.
+        yasna::tags::TAG_BMPSTRING => {
+            let units = value
+                .chunks_exact(2)
+                .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+                .collect::<Vec<_>>();
+
+            String::from_utf16(&units).ok()
+        }
+        // UniversalString, encoded in UTF-32BE.
+        yasna::tags::TAG_UNIVERSALSTRING => value
+            .chunks_exact(4)
+            .map(|quad| char::from_u32(u32::from_be_bytes([quad[0], quad[1], quad[2], quad[3]])))
+            .collect(),
+        // TeletexString is treated as Latin-1, as most encoders do.
+        yasna::tags::TAG_TELETEXSTRING => Some(value.iter().map(|byte| *byte as char).collect()),
+        // UTF8String, PrintableString, IA5String, and similar.
+        _ => String::from_utf8(value.to_vec()).ok(),
+    }
+}
+
+/// Parses the value of an ASN.1 `UTCTime` or `GeneralizedTime` in the forms permitted
+/// by RFC 5280, `YYMMDDHHMMSSZ` and `YYYYMMDDHHMMSSZ` respectively.
+fn parse_asn1_time(tag: Tag, value: &[u8]) -> Option<DateTime<Utc>> {
+    let value = std::str::from_utf8(value).ok()?.strip_suffix('Z')?;
+
+    let (year, rest) = match tag {
+        yasna::tags::TAG_UTCTIME => {
+            let year = value.get(0..2)?.parse::<i32>().ok()?;
+
+            // Two-digit years from 50 onwards are in the twentieth century.
+
+            (
+                if year >= 50 { 1900 + year } else { 2000 + year },
+                &value[2..],
+            )
+        }
+        yasna::tags::TAG_GENERALIZEDTIME => (value.get(0..4)?.parse::<i32>().ok()?, &value[4..]),
+        _ => return None,
+    };
+
+    let field = |index: usize| rest.get(index * 2..index * 2 + 2)?.parse::<u32>().ok();
+
+    let date_time = NaiveDate::from_ymd_opt(year, field(0)?, field(1)?)?.and_hms_opt(
+        field(2)?,
+        field(3)?,
+        field(4).unwrap_or(0),
+    )?;
+
+    Some(Utc.from_utc_datetime(&date_time))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use yasna::models::ObjectIdentifier;
+    use yasna::DERWriter;
+
+    fn write_name(writer: DERWriter, common_name: &str) {
+        writer.write_sequence_of(|writer| {
+            writer.next().write_set_of(|writer| {
+                writer.next().write_sequence(|writer| {
+                    writer
+                        .next()
+                        .write_oid(&ObjectIdentifier::from_slice(OID_COMMON_NAME));
+                    writer.next().write_utf8_string(common_name);
+                })
+            })
+        })
+    }
+
+    fn certificate(subject: &str, issuer: &str, serial_number: i64) -> Vec<u8> {
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer.next().write_sequence(|writer| {
+                    writer.next().write_tagged(Tag::context(0), |writer| {
+                        writer.write_i64(2);
+                    });
+                    writer.next().write_i64(serial_number);
+                    writer.next().write_sequence(|writer| {
+                        writer.next().write_oid(&ObjectIdentifier::from_slice(&[
+                            1, 2, 840, 113549, 1, 1, 11,
+                        ]));
+                    });
+                    write_name(writer.next(), issuer);
+                    writer.next().write_sequence(|_| {});
+                    write_name(writer.next(), subject);
+                    writer.next().write_sequence(|_| {});
+                });
+                writer.next().write_sequence(|_| {});
+                writer.next().write_bitvec_bytes(&[0], 8);
+            })
+        })
+    }
+
+    fn signed_data() -> Vec<u8> {
+        let certificates = [
+            certificate("Root CA", "Root CA", 1),
+            certificate("Jane Signer", "Intermediate CA", 3),
+            certificate("Intermediate CA", "Root CA", 2),
+        ];
+
+        yasna::construct_der(|writer| {
+            writer.write_sequence(|writer| {
+                writer
+                    .next()
+                    .write_oid(&ObjectIdentifier::from_slice(OID_SIGNED_DATA));
+                writer.next().write_tagged(Tag::context(0), |writer| {
+                    writer.write_sequence(|writer| {
+                        writer.next().write_i64(1);
+                        writer.next().write_set(|_| {});
+                        writer.next().write_sequence(|writer| {
+                            writer
+                                .next()
+                                .write_oid(&ObjectIdentifier::from_slice(&[1, 2, 840, 113549, 1, 7, 1]));
+                        });
+                        writer.next().write_tagged_implicit(Tag::context(0), |writer| {
+                            writer.write_set_of(|writer| {
+                                for certificate in certificates.iter() {
+                                    writer.next().write_der(certificate);
+                                }
+                            })
+                        });
+                        writer.next().write_set_of(|writer| {
+                            writer.next().write_sequence(|writer| {
+                                writer.next().write_i64(1);
+                                writer.next().write_sequence(|writer| {
+                                    write_name(writer.next(), "Intermediate CA");
+                                    writer.next().write_i64(3);
+                                });
+                                writer.next().write_sequence(|writer| {
+                                    writer.next().write_oid(&ObjectIdentifier::from_slice(&[
+                                        2, 16, 840, 1, 101, 3, 4, 2, 1,
+                                    ]));
+                                    writer.next().write_null();
+                                });
+                                writer.next().write_tagged_implicit(Tag::context(0), |writer| {
+                                    writer.write_set_of(|writer| {
+                                        writer.next().write_sequence(|writer| {
+                                            writer.next().write_oid(
+                                                &ObjectIdentifier::from_slice(OID_SIGNING_TIME),
+                                            );
+                                            writer.next().write_set(|writer| {
+                                                writer.next().write_tagged_der(
+                                                    &yasna::models::TaggedDerValue::from_tag_and_bytes(
+                                                        yasna::tags::TAG_UTCTIME,
+                                                        b"240131123000Z".to_vec(),
+                                                    ),
+                                                );
+                                            });
+                                        });
+                                        writer.next().write_sequence(|writer| {
+                                            writer.next().write_oid(
+                                                &ObjectIdentifier::from_slice(OID_MESSAGE_DIGEST),
+                                            );
+                                            writer.next().write_set(|writer| {
+                                                writer.next().write_bytes(&[1, 2, 3, 4]);
+                                            });
+                                        });
+                                    })
+                                });
+                                writer.next().write_sequence(|writer| {
+                                    writer.next().write_oid(&ObjectIdentifier::from_slice(&[
+                                        1, 2, 840, 113549, 1, 1, 1,
+                                    ]));
+                                });
+                                writer.next().write_bytes(&[0; 16]);
+                            });
+                        });
+                    });
+                });
+            })
+        })
+    }
+
+    #[test]
+    fn test_cms_info_from_bytes() {
+        let mut contents = signed_data();
+
+        // Signature contents are padded with zero bytes to fill the space reserved for them.
+
+        contents.extend_from_slice(&[0; 100]);
+
+        let info = PdfSignatureCmsInfo::from_bytes(&contents).unwrap();
+
+        assert_eq!(
+            info.digest_algorithm(),
+            &PdfSignatureDigestAlgorithm::Sha256
+        );
+        assert_eq!(info.message_digest(), Some([1, 2, 3, 4].as_slice()));
+        assert_eq!(
+            info.signing_time().map(|time| time.to_rfc3339()),
+            Some("2024-01-31T12:30:00+00:00".to_string())
+        );
+        assert_eq!(info.signer_common_name(), Some("Jane Signer"));
+        assert_eq!(
+            info.certificates()
+                .iter()
+                .map(|certificate| certificate.subject_common_name().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["Jane Signer", "Intermediate CA", "Root CA"]
+        );
+        assert_eq!(info.certificates()[0].serial_number(), &[3]);
+    }
+
+    #[test]
+    fn test_cms_info_rejects_pkcs1_contents() {
+        let contents = yasna::construct_der(|writer| writer.write_bytes(&[0; 128]));
+
+        assert!(PdfSignatureCmsInfo::from_bytes(&contents).is_err());
+    }
+
+    #[test]
+    fn test_verify_document_integrity() {
+        let mut file = b"%PDF-1.7 <</Contents <00ff00ff> /ByteRange [...]>> %%EOF".to_vec();
+
+        let ranges = [0..21, 31..file.len()];
+
+        let mut info = PdfSignatureCmsInfo::from_bytes(&signed_data()).unwrap();
+
+        info.message_digest =
+            PdfSignatureDigestAlgorithm::Sha256.digest(&[&file[0..21], &file[31..]]);
+
+        assert_eq!(
+            PdfSignatureIntegrity::verify(&file, &ranges, &info).unwrap(),
+            PdfSignatureIntegrity::Intact
+        );
+
+        let signed_length = file.len();
+
+        file.extend_from_slice(b"\n1 0 obj\n<<>>\nendobj\n");
+
+        let update = signed_length..file.len();
+
+        assert_eq!(
+            PdfSignatureIntegrity::verify(&file, &ranges, &info).unwrap(),
+            PdfSignatureIntegrity::UnsignedRangesPresent(vec![update])
+        );
+
+        file[1] = b'X';
+
+        assert_eq!(
+            PdfSignatureIntegrity::verify(&file, &ranges, &info).unwrap(),
+            PdfSignatureIntegrity::ModifiedAfterSigning
+        );
+    }
+}
@@ -7,12 +7,38 @@ use crate::error::{PdfiumError, PdfiumInternalError};
 use crate::pdf::document::signature::PdfSignature;
 use std::ops::{Range, RangeInclusive};
 use std::os::raw::c_int;
+use std::sync::Arc;
 
 pub type PdfSignatureIndex = u16;
 
+/// The complete file from which a `PdfDocument` was loaded, retained so that the content
+/// covered by its signatures can be checked.
+#[derive(Clone)]
+#[cfg_attr(not(feature = "pkcs7"), allow(dead_code))]
+// The file is only read when the pkcs7 crate feature is enabled.
+pub(crate) enum PdfSignatureSourceFile<'a> {
+    /// The byte slice passed to `Pdfium::load_pdf_from_byte_slice()`.
+    Borrowed(&'a [u8]),
+
+    /// A byte buffer owned by the `PdfDocument`, shared with the signatures read from it.
+    Owned(Arc<Vec<u8>>),
+}
+
+impl<'a> PdfSignatureSourceFile<'a> {
+    #[cfg_attr(not(feature = "pkcs7"), allow(dead_code))]
+    #[inline]
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        match self {
+            PdfSignatureSourceFile::Borrowed(bytes) => bytes,
+            PdfSignatureSourceFile::Owned(bytes) => bytes.as_slice(),
+        }
+    }
+}
+
 /// The collection of [PdfSignature] objects inside a `PdfDocument`.
 pub struct PdfSignatures<'a> {
     document_handle: FPDF_DOCUMENT,
+    source_file: Option<PdfSignatureSourceFile<'a>>,
     bindings: &'a dyn PdfiumLibraryBindings,
 }
 
@@ -24,10 +50,17 @@ impl<'a> PdfSignatures<'a> {
     ) -> Self {
         PdfSignatures {
             document_handle,
+            source_file: None,
             bindings,
         }
     }
 
+    /// Sets the complete file from which the containing `PdfDocument` was loaded.
+    #[inline]
+    pub(crate) fn set_source_file(&mut self, source_file: PdfSignatureSourceFile<'a>) {
+        self.source_file = Some(source_file);
+    }
+
     /// Returns the [PdfiumLibraryBindings] used by this [PdfSignatures] collection.
     #[inline]
     pub fn bindings(&self) -> &'a dyn PdfiumLibraryBindings {
@@ -77,7 +110,11 @@ impl<'a> PdfSignatures<'a> {
                 PdfiumInternalError::Unknown,
             ))
         } else {
-            Ok(PdfSignature::from_pdfium(handle, self.bindings()))
+            Ok(PdfSignature::from_pdfium(
+                handle,
+                self.source_file.clone(),
+                self.bindings(),
+            ))
         }
     }
 
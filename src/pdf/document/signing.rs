@@ -0,0 +1,610 @@
+//! Defines the [PdfSignatureFieldBuilder] struct, used to prepare a `PdfDocument` for signing
+//! by an external signing service, and the [PdfPreparedSignature] struct, holding the
+//! prepared file and the byte ranges of the file to be signed.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::form::builder::{append_to_array, new_widget, number};
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use crate::pdf::document::PdfDocument;
+use crate::pdf::rect::PdfRect;
+use crate::utils::dates::date_time_to_pdf_string;
+use chrono::{DateTime, Utc};
+use std::ops::Range;
+
+/// The default number of bytes reserved for the signature in a [PdfPreparedSignature].
+const DEFAULT_CONTENTS_CAPACITY: usize = 8192;
+
+/// The value written to each entry of the `/ByteRange` array of a new signature before
+/// the actual byte ranges are known. The placeholder is wide enough to hold the offset
+/// of any byte in a file smaller than 10 GB.
+const BYTE_RANGE_PLACEHOLDER: f64 = 9_999_999_999.0;
+
+/// The `/SigFlags` entry of a form containing signature fields: `SignaturesExist` (bit 1)
+/// and `AppendOnly` (bit 2).
+const SIGNATURE_FLAGS: u32 = 3;
+
+/// The encoding used for the signature written into a [PdfPreparedSignature], as recorded
+/// in the `/SubFilter` entry of the signature dictionary.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PdfSignatureSubFilter {
+    /// A detached PKCS#7 (CMS) `signedData` structure, as described in section 12.8.3.3 of
+    /// the PDF 2.0 specification. This is the encoding most widely supported by PDF viewers.
+    Pkcs7Detached,
+
+    /// A detached CAdES signature, as required for PAdES signatures, described in section
+    /// 12.8.3.4 of the PDF 2.0 specification.
+    CadesDetached,
+}
+
+impl PdfSignatureSubFilter {
+    #[inline]
+    pub(crate) fn as_pdf_name(&self) -> &'static str {
+        match self {
+            PdfSignatureSubFilter::Pkcs7Detached => "adbe.pkcs7.detached",
+            PdfSignatureSubFilter::CadesDetached => "ETSI.CAdES.detached",
+        }
+    }
+}
+
+/// Describes a new, unsigned signature field to be added to a `PdfDocument` by the
+/// `PdfDocument::prepare_signature()` function.
+///
+/// By default the signature is invisible, is placed on the first page in the document,
+/// uses the [PdfSignatureSubFilter::Pkcs7Detached] encoding, and reserves space for
+/// a signature of up to 8192 bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfSignatureFieldBuilder {
+    name: String,
+    page_index: PdfPageIndex,
+    bounds: Option<PdfRect>,
+    reason: Option<String>,
+    location: Option<String>,
+    contact_info: Option<String>,
+    signer_name: Option<String>,
+    signing_time: Option<DateTime<Utc>>,
+    sub_filter: PdfSignatureSubFilter,
+    contents_capacity: usize,
+}
+
+impl PdfSignatureFieldBuilder {
+    /// Creates a new [PdfSignatureFieldBuilder] for a signature field with the given name.
+    /// The name must not be empty, must not contain a period, and must not be the name of
+    /// an existing field in the document.
+    #[inline]
+    pub fn new(name: &str) -> Self {
+        PdfSignatureFieldBuilder {
+            name: name.to_string(),
+            page_index: 0,
+            bounds: None,
+            reason: None,
+            location: None,
+            contact_info: None,
+            signer_name: None,
+            signing_time: None,
+            sub_filter: PdfSignatureSubFilter::Pkcs7Detached,
+            contents_capacity: DEFAULT_CONTENTS_CAPACITY,
+        }
+    }
+
+    /// Sets the zero-based index of the page holding the new signature field. The default
+    /// is the first page in the document.
+    #[inline]
+    pub fn page_index(mut self, index: PdfPageIndex) -> Self {
+        self.page_index = index;
+
+        self
+    }
+
+    /// Sets the bounding box of the new signature field on its page, making the signature
+    /// visible. The field is initially drawn empty. By default the signature is invisible.
+    #[inline]
+    pub fn bounds(mut self, bounds: PdfRect) -> Self {
+        self.bounds = Some(bounds);
+
+        self
+    }
+
+    /// Sets the reason for signing recorded in the signature.
+    #[inline]
+    pub fn reason(mut self, reason: &str) -> Self {
+        self.reason = Some(reason.to_string());
+
+        self
+    }
+
+    /// Sets the location of signing recorded in the signature.
+    #[inline]
+    pub fn location(mut self, location: &str) -> Self {
+        self.location = Some(location.to_string());
+
+        self
+    }
+
+    /// Sets the contact information of the signer recorded in the signature.
+    #[inline]
+    pub fn contact_info(mut self, contact_info: &str) -> Self {
+        self.contact_info = Some(contact_info.to_string());
+
+        self
+    }
+
+    /// Sets the name of the signer recorded in the signature. Viewers usually prefer the
+    /// name given in the signer's certificate.
+    #[inline]
+    pub fn signer_name(mut self, name: &str) -> Self {
+        self.signer_name = Some(name.to_string());
+
+        self
+    }
+
+    /// Sets the time of signing recorded in the signature. By default no time is recorded,
+    /// and viewers use the signing time given in the signature itself, if any.
+    #[inline]
+    pub fn signing_time(mut self, time: DateTime<Utc>) -> Self {
+        self.signing_time = Some(time);
+
+        self
+    }
+
+    /// Sets the encoding of the signature. The default is
+    /// [PdfSignatureSubFilter::Pkcs7Detached].
+    #[inline]
+    pub fn sub_filter(mut self, sub_filter: PdfSignatureSubFilter) -> Self {
+        self.sub_filter = sub_filter;
+
+        self
+    }
+
+    /// Sets the maximum length, in bytes, of the signature that can be written into the
+    /// prepared file. The space is reserved in the file whether or not it is used, so it
+    /// should be large enough for the signer's certificate chain and any timestamp or
+    /// revocation information embedded in the signature. The default is 8192 bytes.
+    #[inline]
+    pub fn contents_capacity(mut self, capacity: usize) -> Self {
+        self.contents_capacity = capacity;
+
+        self
+    }
+
+    /// Prepares the given `PdfDocument` for signing, as configured by this builder.
+    ///
+    /// See `PdfDocument::prepare_signature()` for details of how the document is prepared.
+    #[inline]
+    pub fn prepare(&self, document: &PdfDocument) -> Result<PdfPreparedSignature, PdfiumError> {
+        document.prepare_signature(self)
+    }
+
+    /// Returns the signature dictionary described by this builder, with placeholder
+    /// `/ByteRange` and `/Contents` entries.
+    fn signature_dictionary(&self) -> PdfSyntaxObject {
+        let mut entries = vec![
+            ("Type".to_string(), PdfSyntaxObject::name("Sig")),
+            ("Filter".to_string(), PdfSyntaxObject::name("Adobe.PPKLite")),
+            (
+                "SubFilter".to_string(),
+                PdfSyntaxObject::name(self.sub_filter.as_pdf_name()),
+            ),
+        ];
+
+        for (key, value) in [
+            ("Name", &self.signer_name),
+            ("Reason", &self.reason),
+            ("Location", &self.location),
+            ("ContactInfo", &self.contact_info),
+        ] {
+            if let Some(value) = value {
+                entries.push((key.to_string(), PdfSyntaxObject::text(value)));
+            }
+        }
+
+        if let Some(time) = self.signing_time {
+            entries.push((
+                "M".to_string(),
+                PdfSyntaxObject::text(&date_time_to_pdf_string(time)),
+            ));
+        }
+
+        // The /ByteRange entry must immediately precede the /Contents entry, so that both
+        // placeholders can be found in the written file.
+
+        entries.push((
+            "ByteRange".to_string(),
+            PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Number(0.0),
+                PdfSyntaxObject::Number(BYTE_RANGE_PLACEHOLDER),
+                PdfSyntaxObject::Number(BYTE_RANGE_PLACEHOLDER),
+                PdfSyntaxObject::Number(BYTE_RANGE_PLACEHOLDER),
+            ]),
+        ));
+
+        // Non-ASCII bytes are written as a hexadecimal string, reserving two characters
+        // in the file for each byte of the signature.
+
+        entries.push((
+            "Contents".to_string(),
+            PdfSyntaxObject::String(vec![0xFF; self.contents_capacity]),
+        ));
+
+        PdfSyntaxObject::Dictionary(entries)
+    }
+}
+
+/// A `PdfDocument` saved with a new, unsigned signature field, ready to be signed by an
+/// external signing service, as returned by the `PdfDocument::prepare_signature()` function.
+///
+/// The signature must be computed over the bytes given by
+/// [PdfPreparedSignature::signed_content()], which cover the whole file other than the
+/// space reserved for the signature itself, and then written into the file using
+/// [PdfPreparedSignature::into_signed_bytes()].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfPreparedSignature {
+    bytes: Vec<u8>,
+    contents: Range<usize>,
+}
+
+impl PdfPreparedSignature {
+    /// Returns the prepared file, with the space reserved for the signature filled with zeros.
+    #[inline]
+    pub fn bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+
+    /// Returns the two byte ranges of the prepared file covered by the signature, as recorded
+    /// in the `/ByteRange` entry of the signature dictionary. The ranges cover the whole file
+    /// other than the hexadecimal string reserved for the signature.
+    #[inline]
+    pub fn byte_ranges(&self) -> [Range<usize>; 2] {
+        [0..self.contents.start, self.contents.end..self.bytes.len()]
+    }
+
+    /// Returns the two portions of the prepared file covered by the signature, in order.
+    /// A digest computed over the concatenation of these portions is the message digest
+    /// to be signed.
+    #[inline]
+    pub fn signed_parts(&self) -> [&[u8]; 2] {
+        let [first, second] = self.byte_ranges();
+
+        [&self.bytes[first], &self.bytes[second]]
+    }
+
+    /// Returns the concatenation of the portions of the prepared file covered by the
+    /// signature. This is the content to be passed to signing services that compute the
+    /// message digest themselves.
+    #[inline]
+    pub fn signed_content(&self) -> Vec<u8> {
+        self.signed_parts().concat()
+    }
+
+    /// Returns the maximum length, in bytes, of the signature that can be written into
+    /// the prepared file.
+    #[inline]
+    pub fn contents_capacity(&self) -> usize {
+        // The reserved hexadecimal string is delimited by angle brackets.
+
+        (self.contents.len() - 2) / 2
+    }
+
+    /// Writes the given DER-encoded signature into the space reserved for it in the prepared
+    /// file, returning the signed file. The signature is padded with zeros to fill the
+    /// reserved space.
+    ///
+    /// Returns [PdfiumError::SignatureContentsTooLarge] if the signature is longer than
+    /// [PdfPreparedSignature::contents_capacity()].
+    pub fn into_signed_bytes(mut self, signature: &[u8]) -> Result<Vec<u8>, PdfiumError> {
+        if signature.len() > self.contents_capacity() {
+            return Err(PdfiumError::SignatureContentsTooLarge);
+        }
+
+        let hex = signature
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<String>();
+
+        let start = self.contents.start + 1;
+
+        self.bytes[start..start + hex.len()].copy_from_slice(hex.as_bytes());
+
+        Ok(self.bytes)
+    }
+}
+
+/// Adds the unsigned signature field described by the given builder to the given PDF file,
+/// which must contain the given number of pages and the fields with the given fully
+/// qualified names. The field is added in an incremental update appended to the end of
+/// the file, and the `/ByteRange` entry of its signature dictionary is filled in.
+pub(crate) fn append_signature_field(
+    file: Vec<u8>,
+    field: &PdfSignatureFieldBuilder,
+    page_count: PdfPageIndex,
+    existing_field_names: &[String],
+) -> Result<PdfPreparedSignature, PdfiumError> {
+    if field.name.is_empty()
+        || field.name.contains('.')
+        || field.contents_capacity == 0
+        || existing_field_names
+            .iter()
+            .any(|name| name.split('.').next() == Some(field.name.as_str()))
+    {
+        return Err(PdfiumError::InvalidFormFieldDefinition);
+    }
+
+    if field.page_index >= page_count {
+        return Err(PdfiumError::PageIndexOutOfBounds);
+    }
+
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let root = match update.trailer().get("Root") {
+        Some(PdfSyntaxObject::Reference(number, _))
+            if update.trailer().get("Encrypt").is_none() =>
+        {
+            *number
+        }
+        _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+    };
+
+    let pages = parsed
+        .page_references()
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    if pages.len() != page_count as usize {
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    let page = &pages[field.page_index as usize];
+
+    let page_number = match page {
+        PdfSyntaxObject::Reference(number, _) => *number,
+        _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+    };
+
+    let signature = update.add(field.signature_dictionary());
+
+    // An invisible signature has an empty bounding box. Every widget is given an appearance
+    // stream, as required by PDF/A.
+
+    let bounds = field.bounds.unwrap_or(PdfRect::ZERO);
+
+    let appearance = update.add_stream(
+        PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_string(), PdfSyntaxObject::name("XObject")),
+            ("Subtype".to_string(), PdfSyntaxObject::name("Form")),
+            (
+                "BBox".to_string(),
+                PdfSyntaxObject::Array(vec![
+                    number(0.0),
+                    number(0.0),
+                    number(bounds.width().value),
+                    number(bounds.height().value),
+                ]),
+            ),
+        ]),
+        Vec::new(),
+    );
+
+    let mut widget = new_widget(page, &bounds);
+
+    widget.set("FT", PdfSyntaxObject::name("Sig"));
+    widget.set("T", PdfSyntaxObject::text(&field.name));
+    widget.set("V", signature);
+    widget.set(
+        "AP",
+        PdfSyntaxObject::Dictionary(vec![("N".to_string(), appearance)]),
+    );
+
+    let widget = update.add(widget);
+
+    update
+        .modify(page_number, &["Annots"], &mut |annotations| {
+            append_to_array(annotations, std::slice::from_ref(&widget))
+        })
+        .and_then(|_| {
+            update.modify(root, &["AcroForm", "Fields"], &mut |fields| {
+                append_to_array(fields, std::slice::from_ref(&widget))
+            })
+        })
+        .and_then(|_| {
+            update.modify(root, &["AcroForm", "SigFlags"], &mut |flags| {
+                let existing = match flags {
+                    PdfSyntaxObject::Number(value) => *value as u32,
+                    _ => 0,
+                };
+
+                *flags = PdfSyntaxObject::Number((existing | SIGNATURE_FLAGS) as f64);
+
+                Some(())
+            })
+        })
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let original_length = file.len();
+
+    fill_byte_range(update.write(file), original_length)
+}
+
+/// Locates the placeholder `/ByteRange` and `/Contents` entries written after the given
+/// offset in the given file, replaces the placeholder byte ranges with the actual byte
+/// ranges covered by the signature, and fills the space reserved for the signature
+/// with zeros.
+fn fill_byte_range(mut bytes: Vec<u8>, offset: usize) -> Result<PdfPreparedSignature, PdfiumError> {
+    let mut placeholder = b"/ByteRange ".to_vec();
+
+    PdfSyntaxObject::Array(vec![
+        PdfSyntaxObject::Number(0.0),
+        PdfSyntaxObject::Number(BYTE_RANGE_PLACEHOLDER),
+        PdfSyntaxObject::Number(BYTE_RANGE_PLACEHOLDER),
+        PdfSyntaxObject::Number(BYTE_RANGE_PLACEHOLDER),
+    ])
+    .write_to(&mut placeholder);
+
+    let byte_range_length = placeholder.len() - b"/ByteRange ".len();
+
+    placeholder.extend_from_slice(b" /Contents <");
+
+    let position = bytes[offset..]
+        .windows(placeholder.len())
+        .position(|window| window == placeholder.as_slice())
+        .map(|position| position + offset)
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let contents_start = position + placeholder.len() - 1;
+
+    let contents_end = bytes[contents_start..]
+        .iter()
+        .position(|byte| *byte == b'>')
+        .map(|position| contents_start + position + 1)
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let byte_range = format!(
+        "[0 {} {} {}]",
+        contents_start,
+        contents_end,
+        bytes.len() - contents_end
+    );
+
+    if byte_range.len() > byte_range_length {
+        return Err(PdfiumError::DocumentUpdateUnsupported);
+    }
+
+    // The actual byte ranges are padded with spaces to the width of the placeholder,
+    // so that no other content in the file moves.
+
+    let byte_range_start = position + b"/ByteRange ".len();
+
+    bytes[byte_range_start..byte_range_start + byte_range_length]
+        .copy_from_slice(format!("{:width$}", byte_range, width = byte_range_length).as_bytes());
+
+    for byte in bytes[contents_start + 1..contents_end - 1].iter_mut() {
+        *byte = b'0';
+    }
+
+    Ok(PdfPreparedSignature {
+        bytes,
+        contents: contents_start..contents_end,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::signing::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    const MINIMAL_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] >>\nendobj\n\
+        xref\n0 4\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 4 /Root 1 0 R >>\nstartxref\n200\n%%EOF\n";
+
+    #[test]
+    fn test_append_signature_field() {
+        let field = PdfSignatureFieldBuilder::new("Signature1")
+            .reason("Approval")
+            .contents_capacity(16);
+
+        let prepared = append_signature_field(MINIMAL_PDF.to_vec(), &field, 1, &[]).unwrap();
+
+        assert_eq!(prepared.contents_capacity(), 16);
+        assert!(prepared.bytes().starts_with(MINIMAL_PDF));
+
+        let [first, second] = prepared.byte_ranges();
+
+        assert_eq!(first.start, 0);
+        assert_eq!(second.end, prepared.bytes().len());
+        assert_eq!(
+            &prepared.bytes()[first.end..second.start],
+            format!("<{}>", "0".repeat(32)).as_bytes()
+        );
+
+        let file = PdfSyntaxFile::parse(prepared.bytes()).unwrap();
+
+        let catalog = file.resolve(&PdfSyntaxObject::Reference(1, 0));
+
+        let form = file.resolve(catalog.get("AcroForm").unwrap());
+
+        assert_eq!(form.get("SigFlags"), Some(&PdfSyntaxObject::Number(3.0)));
+
+        let widget = match form.get("Fields") {
+            Some(PdfSyntaxObject::Array(fields)) if fields.len() == 1 => file.resolve(&fields[0]),
+            _ => panic!("missing signature field"),
+        };
+
+        assert_eq!(widget.get("FT"), Some(&PdfSyntaxObject::name("Sig")));
+
+        let signature = file.resolve(widget.get("V").unwrap());
+
+        assert_eq!(
+            signature.get("ByteRange"),
+            Some(&PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Number(0.0),
+                PdfSyntaxObject::Number(first.end as f64),
+                PdfSyntaxObject::Number(second.start as f64),
+                PdfSyntaxObject::Number(second.len() as f64),
+            ]))
+        );
+        assert_eq!(
+            signature.get("Reason"),
+            Some(&PdfSyntaxObject::text("Approval"))
+        );
+
+        let content = prepared.signed_content();
+
+        let signed = prepared.clone().into_signed_bytes(&[0xAB; 16]).unwrap();
+
+        assert_eq!(signed.len(), prepared.bytes().len());
+        assert_eq!(
+            &signed[first.end..second.start],
+            format!("<{}>", "AB".repeat(16)).as_bytes()
+        );
+        assert_eq!([&signed[first], &signed[second]].concat(), content);
+
+        assert!(matches!(
+            prepared.into_signed_bytes(&[0; 17]),
+            Err(PdfiumError::SignatureContentsTooLarge)
+        ));
+        assert!(matches!(
+            append_signature_field(MINIMAL_PDF.to_vec(), &field, 1, &["Signature1".to_string()]),
+            Err(PdfiumError::InvalidFormFieldDefinition)
+        ));
+        assert!(matches!(
+            append_signature_field(MINIMAL_PDF.to_vec(), &field.page_index(1), 1, &[]),
+            Err(PdfiumError::PageIndexOutOfBounds)
+        ));
+    }
+
+    #[test]
+    fn test_prepare_signature() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let prepared = PdfSignatureFieldBuilder::new("Signature1")
+            .bounds(PdfRect::new_from_values(50.0, 50.0, 100.0, 250.0))
+            .prepare(&document)?;
+
+        let signed = prepared.into_signed_bytes(&[0x30, 0x00])?;
+
+        let document = pdfium.load_pdf_from_byte_vec(signed, None)?;
+
+        assert_eq!(document.signatures().len(), 1);
+
+        let signature = document.signatures().get(0)?;
+
+        assert_eq!(
+            signature.sub_filter().as_deref(),
+            Some("adbe.pkcs7.detached")
+        );
+        assert_eq!(signature.byte_ranges().len(), 2);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,431 @@
+//! Writes the structure elements bound to marked content sequences tagged by
+//! `PdfPageObjectCommon::set_structure_tag()` and `PdfPageImageObject::set_alt_text()`
+//! into a document's structure tree, which Pdfium cannot edit itself.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use std::collections::BTreeMap;
+
+/// A structure element to be bound to the marked content sequence with the given marked
+/// content ID on the page at the given index when the document is saved.
+#[derive(Debug, Clone, PartialEq)]
+struct PdfStructureElementEdit {
+    page_index: PdfPageIndex,
+    marked_content_id: i32,
+    structure_type: String,
+    alt_text: Option<String>,
+}
+
+/// The structure elements added to a document since it was loaded, written into the
+/// document's structure tree each time the document is saved.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PdfStructureTreeEdits {
+    edits: Vec<PdfStructureElementEdit>,
+}
+
+impl PdfStructureTreeEdits {
+    #[inline]
+    pub(crate) fn new() -> Self {
+        PdfStructureTreeEdits { edits: Vec::new() }
+    }
+
+    /// Binds a structure element of the given type to the marked content sequence with the
+    /// given marked content ID on the page at the given index, replacing any element
+    /// previously bound to the same sequence by this collection. If the document's structure
+    /// tree already binds an element to the sequence, that element is kept, and only its
+    /// alternate description is updated.
+    pub(crate) fn bind(
+        &mut self,
+        page_index: PdfPageIndex,
+        marked_content_id: i32,
+        structure_type: &str,
+        alt_text: Option<&str>,
+    ) {
+        let edit = PdfStructureElementEdit {
+            page_index,
+            marked_content_id,
+            structure_type: structure_type.to_string(),
+            alt_text: alt_text.map(|text| text.to_string()),
+        };
+
+        match self.edits.iter_mut().find(|existing| {
+            existing.page_index == page_index && existing.marked_content_id == marked_content_id
+        }) {
+            Some(existing) => *existing = edit,
+            None => self.edits.push(edit),
+        }
+    }
+
+    /// Sets the alternate description of the structure element bound to the marked content
+    /// sequence with the given marked content ID on the page at the given index, binding a
+    /// new element of the given type to the sequence if necessary.
+    pub(crate) fn set_alt_text(
+        &mut self,
+        page_index: PdfPageIndex,
+        marked_content_id: i32,
+        structure_type: &str,
+        alt_text: &str,
+    ) {
+        match self.edits.iter_mut().find(|existing| {
+            existing.page_index == page_index && existing.marked_content_id == marked_content_id
+        }) {
+            Some(existing) => existing.alt_text = Some(alt_text.to_string()),
+            None => self.bind(
+                page_index,
+                marked_content_id,
+                structure_type,
+                Some(alt_text),
+            ),
+        }
+    }
+
+    /// Returns `true` if any structure elements have been added since the document was loaded.
+    #[inline]
+    pub(crate) fn is_modified(&self) -> bool {
+        !self.edits.is_empty()
+    }
+
+    /// Writes the added structure elements into the structure tree of the given PDF file,
+    /// creating the structure tree if the file does not have one, and returns the updated file.
+    /// Returns [PdfiumError::DocumentUpdateUnsupported] if the file is encrypted, or if its
+    /// structure tree uses a parent tree with more than one level, which is not supported.
+    pub(crate) fn write_to_file(&self, file: Vec<u8>) -> Result<Vec<u8>, PdfiumError> {
+        if self.edits.is_empty() {
+            return Ok(file);
+        }
+
+        let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let pages = parsed
+            .page_references()
+            .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let mut update =
+            PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let root = match update.trailer().get("Root") {
+            Some(PdfSyntaxObject::Reference(number, _))
+                if update.trailer().get("Encrypt").is_none() =>
+            {
+                *number
+            }
+            _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+        };
+
+        let mut catalog = update
+            .get(root)
+            .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+        let (tree_reference, mut tree) = match catalog.get("StructTreeRoot") {
+            Some(PdfSyntaxObject::Reference(number, generation)) => (
+                PdfSyntaxObject::Reference(*number, *generation),
+                update
+                    .get(*number)
+                    .ok_or(PdfiumError::DocumentUpdateUnsupported)?,
+            ),
+            None => (
+                update.reserve(),
+                PdfSyntaxObject::Dictionary(vec![(
+                    "Type".to_string(),
+                    PdfSyntaxObject::name("StructTreeRoot"),
+                )]),
+            ),
+            _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+        };
+
+        // The parent tree maps the value of each page's /StructParents entry to an array
+        // holding the structure element bound to each marked content ID on that page.
+
+        let parent_tree_number = match tree.get("ParentTree") {
+            Some(PdfSyntaxObject::Reference(number, _)) => Some(*number),
+            _ => None,
+        };
+
+        let mut parent_tree = match tree.get("ParentTree") {
+            Some(parent_tree) => update.resolve(parent_tree),
+            None => PdfSyntaxObject::Dictionary(Vec::new()),
+        };
+
+        if parent_tree.get("Kids").is_some() {
+            return Err(PdfiumError::DocumentUpdateUnsupported);
+        }
+
+        let mut nums = match parent_tree.get("Nums").map(|nums| update.resolve(nums)) {
+            Some(PdfSyntaxObject::Array(nums)) => nums,
+            None => Vec::new(),
+            _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+        };
+
+        let mut next_key = nums
+            .iter()
+            .step_by(2)
+            .filter_map(|key| match key {
+                PdfSyntaxObject::Number(key) => Some(*key as i64 + 1),
+                _ => None,
+            })
+            .chain(match tree.get("ParentTreeNextKey") {
+                Some(PdfSyntaxObject::Number(key)) => Some(*key as i64),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        let mut kids = match tree.get("K").map(|kids| update.resolve(kids)) {
+            Some(PdfSyntaxObject::Array(kids)) => kids,
+            Some(PdfSyntaxObject::Null) | None => Vec::new(),
+            Some(_) => vec![tree.get("K").cloned().unwrap_or(PdfSyntaxObject::Null)],
+        };
+
+        let mut edits_by_page = BTreeMap::new();
+
+        for edit in self.edits.iter() {
+            edits_by_page
+                .entry(edit.page_index)
+                .or_insert_with(Vec::new)
+                .push(edit);
+        }
+
+        for (page_index, edits) in edits_by_page {
+            let (page_number, page_reference) = match pages.get(page_index as usize) {
+                Some(PdfSyntaxObject::Reference(number, generation)) => {
+                    (*number, PdfSyntaxObject::Reference(*number, *generation))
+                }
+                _ => return Err(PdfiumError::PageIndexOutOfBounds),
+            };
+
+            let mut page = update
+                .get(page_number)
+                .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+            let key = match page.get("StructParents") {
+                Some(PdfSyntaxObject::Number(key)) => *key as i64,
+                _ => {
+                    let key = next_key;
+
+                    next_key += 1;
+
+                    page.set("StructParents", PdfSyntaxObject::Number(key as f64));
+
+                    update.set(page_number, page);
+
+                    key
+                }
+            };
+
+            let position = nums
+                .iter()
+                .step_by(2)
+                .position(|existing| *existing == PdfSyntaxObject::Number(key as f64))
+                .map(|position| position * 2 + 1);
+
+            let array_number = match position.map(|position| &nums[position]) {
+                Some(PdfSyntaxObject::Reference(number, _)) => Some(*number),
+                _ => None,
+            };
+
+            let mut elements = match position.map(|position| update.resolve(&nums[position])) {
+                Some(PdfSyntaxObject::Array(elements)) => elements,
+                _ => Vec::new(),
+            };
+
+            for edit in edits {
+                if edit.marked_content_id < 0 {
+                    continue;
+                }
+
+                let index = edit.marked_content_id as usize;
+
+                if elements.len() <= index {
+                    elements.resize(index + 1, PdfSyntaxObject::Null);
+                }
+
+                match &elements[index] {
+                    PdfSyntaxObject::Reference(number, _) if update.get(*number).is_some() => {
+                        // An element is already bound to this marked content sequence.
+
+                        if let Some(alt_text) = edit.alt_text.as_ref() {
+                            let number = *number;
+
+                            let mut element = update
+                                .get(number)
+                                .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+                            element.set("Alt", PdfSyntaxObject::text(alt_text));
+
+                            update.set(number, element);
+                        }
+                    }
+                    _ => {
+                        let mut element = PdfSyntaxObject::Dictionary(vec![
+                            ("Type".to_string(), PdfSyntaxObject::name("StructElem")),
+                            ("S".to_string(), PdfSyntaxObject::name(&edit.structure_type)),
+                            ("P".to_string(), tree_reference.clone()),
+                            ("Pg".to_string(), page_reference.clone()),
+                            (
+                                "K".to_string(),
+                                PdfSyntaxObject::Number(edit.marked_content_id as f64),
+                            ),
+                        ]);
+
+                        if let Some(alt_text) = edit.alt_text.as_ref() {
+                            element.set("Alt", PdfSyntaxObject::text(alt_text));
+                        }
+
+                        let reference = update.add(element);
+
+                        kids.push(reference.clone());
+
+                        elements[index] = reference;
+                    }
+                }
+            }
+
+            match (array_number, position) {
+                (Some(number), _) => update.set(number, PdfSyntaxObject::Array(elements)),
+                (None, Some(position)) => {
+                    nums[position] = update.add(PdfSyntaxObject::Array(elements));
+                }
+                (None, None) => {
+                    nums.push(PdfSyntaxObject::Number(key as f64));
+                    nums.push(update.add(PdfSyntaxObject::Array(elements)));
+                }
+            }
+        }
+
+        parent_tree.set("Nums", PdfSyntaxObject::Array(nums));
+
+        match parent_tree_number {
+            Some(number) => update.set(number, parent_tree),
+            None => tree.set("ParentTree", parent_tree),
+        }
+
+        tree.set(
+            "ParentTreeNextKey",
+            PdfSyntaxObject::Number(next_key as f64),
+        );
+
+        tree.set("K", PdfSyntaxObject::Array(kids));
+
+        if let PdfSyntaxObject::Reference(number, _) = tree_reference {
+            update.set(number, tree);
+        }
+
+        catalog.set("StructTreeRoot", tree_reference);
+
+        let mut mark_info = match catalog.get("MarkInfo") {
+            Some(mark_info) => update.resolve(mark_info),
+            None => PdfSyntaxObject::Dictionary(Vec::new()),
+        };
+
+        mark_info.set("Marked", PdfSyntaxObject::Bool(true));
+
+        catalog.set("MarkInfo", mark_info);
+
+        update.set(root, catalog);
+
+        Ok(update.write(file))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::structure_tree::*;
+
+    const TAGGED_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R /StructTreeRoot 5 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R 4 0 R] /Count 2 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /StructParents 0 >>\nendobj\n\
+        4 0 obj\n<< /Type /Page /Parent 2 0 R >>\nendobj\n\
+        5 0 obj\n<< /Type /StructTreeRoot /K 6 0 R /ParentTree << /Nums [0 7 0 R] >> /ParentTreeNextKey 1 >>\nendobj\n\
+        6 0 obj\n<< /Type /StructElem /S /Figure /P 5 0 R /Pg 3 0 R /K 0 >>\nendobj\n\
+        7 0 obj\n[6 0 R]\nendobj\n\
+        xref\n0 8\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 8 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    #[test]
+    fn test_write_structure_elements() {
+        let mut edits = PdfStructureTreeEdits::new();
+
+        assert!(!edits.is_modified());
+
+        // Alternate text for a sequence already bound to an element updates that element.
+
+        edits.set_alt_text(0, 0, "Figure", "A chart");
+
+        // New sequences on both a page that is already in the parent tree and
+        // a page that is not are bound to new elements.
+
+        edits.bind(0, 1, "P", None);
+        edits.bind(1, 0, "H1", None);
+
+        assert!(edits.is_modified());
+
+        let file = edits.write_to_file(TAGGED_PDF.to_vec()).unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        let catalog = parsed.resolve(&PdfSyntaxObject::Reference(1, 0));
+
+        assert_eq!(
+            catalog.get("MarkInfo").and_then(|info| info.get("Marked")),
+            Some(&PdfSyntaxObject::Bool(true))
+        );
+
+        let tree = parsed.resolve(catalog.get("StructTreeRoot").unwrap());
+
+        let figure = parsed.resolve(&PdfSyntaxObject::Reference(6, 0));
+
+        assert_eq!(figure.get("Alt"), Some(&PdfSyntaxObject::text("A chart")));
+
+        let kids = match tree.get("K") {
+            Some(PdfSyntaxObject::Array(kids)) => kids.clone(),
+            _ => panic!("expected an array of structure elements"),
+        };
+
+        assert_eq!(kids.len(), 3);
+
+        let types = kids
+            .iter()
+            .map(|kid| parsed.resolve(kid).get("S").cloned())
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            types,
+            vec![
+                Some(PdfSyntaxObject::name("Figure")),
+                Some(PdfSyntaxObject::name("P")),
+                Some(PdfSyntaxObject::name("H1")),
+            ]
+        );
+
+        let second_page = parsed.resolve(&PdfSyntaxObject::Reference(4, 0));
+
+        assert_eq!(
+            second_page.get("StructParents"),
+            Some(&PdfSyntaxObject::Number(1.0))
+        );
+
+        assert_eq!(
+            tree.get("ParentTreeNextKey"),
+            Some(&PdfSyntaxObject::Number(2.0))
+        );
+
+        let nums = match tree.get("ParentTree").and_then(|tree| tree.get("Nums")) {
+            Some(PdfSyntaxObject::Array(nums)) => nums.clone(),
+            _ => panic!("expected a flat parent tree"),
+        };
+
+        assert_eq!(nums.len(), 4);
+
+        assert_eq!(
+            parsed.resolve(&nums[1]),
+            PdfSyntaxObject::Array(vec![PdfSyntaxObject::Reference(6, 0), kids[1].clone()])
+        );
+
+        assert_eq!(
+            parsed.resolve(&nums[3]),
+            PdfSyntaxObject::Array(vec![kids[2].clone()])
+        );
+    }
+}
@@ -0,0 +1,1208 @@
+//! Reads and writes the object syntax shared by PDF and FDF files, as described in section 3.2
+//! of The PDF Reference Manual, version 1.7. This is used for the few tasks Pdfium cannot
+//! perform itself, such as reading FDF documents and appending incremental updates that add
+//! new form fields to a document.
+
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+
+/// The maximum depth of nested arrays and dictionaries, and the maximum length of chains of
+/// references, followed when parsing a file. The limit guards against exhausting the stack
+/// when parsing malicious input.
+const MAXIMUM_NESTING_DEPTH: usize = 64;
+
+/// A single object in a parsed PDF or FDF file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum PdfSyntaxObject {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(Vec<u8>),
+    Name(String),
+    Array(Vec<PdfSyntaxObject>),
+    Dictionary(Vec<(String, PdfSyntaxObject)>),
+
+    /// A reference to an indirect object, given by its object number and generation number.
+    Reference(u32, u16),
+}
+
+impl PdfSyntaxObject {
+    /// Creates a new string object containing the given text.
+    #[inline]
+    pub(crate) fn text(text: &str) -> Self {
+        PdfSyntaxObject::String(encode_pdf_text(text))
+    }
+
+    /// Creates a new name object containing the given name.
+    #[inline]
+    pub(crate) fn name(name: &str) -> Self {
+        PdfSyntaxObject::Name(name.to_string())
+    }
+
+    /// Returns the value of the given key, if this object is a dictionary containing that key.
+    pub(crate) fn get(&self, key: &str) -> Option<&PdfSyntaxObject> {
+        match self {
+            PdfSyntaxObject::Dictionary(entries) => entries
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Sets the value of the given key, if this object is a dictionary, replacing any
+    /// existing value of that key.
+    pub(crate) fn set(&mut self, key: &str, value: PdfSyntaxObject) {
+        if let Some(entry) = self.entry_mut(key) {
+            *entry = value;
+        }
+    }
+
+    /// Removes the given key, if this object is a dictionary containing that key.
+    pub(crate) fn remove(&mut self, key: &str) {
+        if let PdfSyntaxObject::Dictionary(entries) = self {
+            entries.retain(|(name, _)| name != key);
+        }
+    }
+
+    /// Returns a mutable reference to the value of the given key, if this object is a
+    /// dictionary. A null value is added for the key if the dictionary does not contain it.
+    fn entry_mut(&mut self, key: &str) -> Option<&mut PdfSyntaxObject> {
+        match self {
+            PdfSyntaxObject::Dictionary(entries) => {
+                let index = match entries.iter().position(|(name, _)| name == key) {
+                    Some(index) => index,
+                    None => {
+                        entries.push((key.to_string(), PdfSyntaxObject::Null));
+
+                        entries.len() - 1
+                    }
+                };
+
+                Some(&mut entries[index].1)
+            }
+            _ => None,
+        }
+    }
+
+    /// Writes this object to the given buffer in PDF syntax.
+    pub(crate) fn write_to(&self, output: &mut Vec<u8>) {
+        match self {
+            PdfSyntaxObject::Null => output.extend_from_slice(b"null"),
+            PdfSyntaxObject::Bool(value) => {
+                output.extend_from_slice(if *value { b"true" } else { b"false" })
+            }
+            PdfSyntaxObject::Number(value) => {
+                // Rust never formats floating point values using exponential notation,
+                // which PDF syntax does not support.
+
+                output.extend_from_slice(format!("{}", value).as_bytes())
+            }
+            PdfSyntaxObject::String(bytes) => {
+                output.extend_from_slice(format_pdf_string(bytes).as_bytes())
+            }
+            PdfSyntaxObject::Name(name) => {
+                output.extend_from_slice(format_pdf_name(name).as_bytes())
+            }
+            PdfSyntaxObject::Array(items) => {
+                output.push(b'[');
+
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        output.push(b' ');
+                    }
+
+                    item.write_to(output);
+                }
+
+                output.push(b']');
+            }
+            PdfSyntaxObject::Dictionary(entries) => {
+                output.extend_from_slice(b"<<");
+
+                for (key, value) in entries.iter() {
+                    output.push(b' ');
+                    output.extend_from_slice(format_pdf_name(key).as_bytes());
+                    output.push(b' ');
+                    value.write_to(output);
+                }
+
+                output.extend_from_slice(b" >>");
+            }
+            PdfSyntaxObject::Reference(number, generation) => {
+                output.extend_from_slice(format!("{} {} R", number, generation).as_bytes())
+            }
+        }
+    }
+}
+
+/// Encodes the given text for use in a PDF string, as PDFDocEncoding if the text is entirely
+/// ASCII and as UTF-16BE with a leading byte order mark otherwise.
+pub(crate) fn encode_pdf_text(text: &str) -> Vec<u8> {
+    if text.is_ascii() {
+        text.as_bytes().to_vec()
+    } else {
+        let mut bytes = vec![0xFE, 0xFF];
+
+        for unit in text.encode_utf16() {
+            bytes.extend_from_slice(&unit.to_be_bytes());
+        }
+
+        bytes
+    }
+}
+
+/// Formats the given bytes as a PDF string. ASCII strings are written as literal strings;
+/// any other strings are written as hexadecimal strings.
+pub(crate) fn format_pdf_string(bytes: &[u8]) -> String {
+    if bytes.is_ascii() {
+        let mut result = String::with_capacity(bytes.len() + 2);
+
+        result.push('(');
+
+        for byte in bytes.iter() {
+            match byte {
+                b'(' => result.push_str("\\("),
+                b')' => result.push_str("\\)"),
+                b'\\' => result.push_str("\\\\"),
+                b'\n' => result.push_str("\\n"),
+                b'\r' => result.push_str("\\r"),
+                b'\t' => result.push_str("\\t"),
+                byte if byte.is_ascii_control() => result.push_str(&format!("\\{:03o}", byte)),
+                byte => result.push(*byte as char),
+            }
+        }
+
+        result.push(')');
+
+        result
+    } else {
+        let mut result = String::with_capacity(bytes.len() * 2 + 2);
+
+        result.push('<');
+
+        for byte in bytes.iter() {
+            result.push_str(&format!("{:02X}", byte));
+        }
+
+        result.push('>');
+
+        result
+    }
+}
+
+/// Formats the given text as a PDF name, escaping any bytes that may not appear
+/// literally in a name.
+pub(crate) fn format_pdf_name(name: &str) -> String {
+    let mut result = String::with_capacity(name.len() + 1);
+
+    result.push('/');
+
+    for byte in name.bytes() {
+        if (0x21..=0x7E).contains(&byte) && !is_pdf_delimiter(byte) && byte != b'#' {
+            result.push(byte as char);
+        } else {
+            result.push_str(&format!("#{:02X}", byte));
+        }
+    }
+
+    result
+}
+
+/// Decodes the given PDF string. Strings starting with a UTF-16BE or UTF-8 byte order mark
+/// are decoded accordingly; all other strings are decoded as Latin-1, which matches
+/// PDFDocEncoding for all commonly used characters.
+pub(crate) fn decode_pdf_string(bytes: &[u8]) -> String {
+    if let Some(bytes) = bytes.strip_prefix(&[0xFE, 0xFF]) {
+        let units = bytes
+            .chunks(2)
+            .map(|pair| u16::from_be_bytes([pair[0], *pair.get(1).unwrap_or(&0)]))
+            .collect::<Vec<_>>();
+
+        String::from_utf16_lossy(&units)
+    } else if let Some(bytes) = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]) {
+        String::from_utf8_lossy(bytes).into_owned()
+    } else {
+        bytes.iter().map(|byte| *byte as char).collect()
+    }
+}
+
+#[inline]
+fn is_pdf_whitespace(byte: u8) -> bool {
+    matches!(byte, 0 | b'\t' | b'\n' | 0x0C | b'\r' | b' ')
+}
+
+#[inline]
+fn is_pdf_delimiter(byte: u8) -> bool {
+    matches!(
+        byte,
+        b'(' | b')' | b'<' | b'>' | b'[' | b']' | b'{' | b'}' | b'/' | b'%'
+    )
+}
+
+/// A single indirect object in a parsed PDF or FDF file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PdfIndirectObject {
+    /// The generation number of the object.
+    pub(crate) generation: u16,
+
+    /// The value of the object. For a stream, this is the stream dictionary.
+    pub(crate) object: PdfSyntaxObject,
+
+    /// `true` if the object is a stream.
+    pub(crate) is_stream: bool,
+
+    /// The byte range of the stream's data within the parsed file, if the object is a stream.
+    /// The data itself is not retained.
+    pub(crate) stream_range: Option<Range<usize>>,
+}
+
+/// The indirect objects and trailer of a parsed PDF or FDF file.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct PdfSyntaxFile {
+    /// The indirect objects defined in the file, keyed by object number.
+    pub(crate) objects: HashMap<u32, PdfIndirectObject>,
+
+    /// The last trailer dictionary in the file, if any.
+    pub(crate) trailer: Option<PdfSyntaxObject>,
+
+    /// The byte offset of the last cross-reference section in the file, if any.
+    pub(crate) start_xref: Option<usize>,
+}
+
+impl PdfSyntaxFile {
+    /// Parses the given PDF or FDF file by reading each of its top-level objects in turn.
+    ///
+    /// Cross-reference tables are not consulted, so objects stored in object streams are
+    /// not read, and files whose cross-reference information is stored only in
+    /// cross-reference streams have no trailer. Later definitions of an object, such as
+    /// those in incremental updates, replace earlier definitions.
+    pub(crate) fn parse(input: &[u8]) -> Option<Self> {
+        let mut parser = PdfSyntaxParser::new(input);
+
+        let mut objects = HashMap::new();
+
+        let mut trailer = None;
+
+        let mut start_xref = None;
+
+        loop {
+            parser.skip_whitespace();
+
+            if parser.position == input.len() {
+                break;
+            }
+
+            match parser.parse_keyword()? {
+                b"trailer" => trailer = Some(parser.parse_object(0)?),
+                b"xref" => {
+                    parser.skip_past(b"trailer")?;
+                    parser.position -= b"trailer".len();
+                }
+                b"startxref" => {
+                    parser.skip_whitespace();
+
+                    start_xref = std::str::from_utf8(parser.parse_keyword()?)
+                        .ok()?
+                        .parse::<usize>()
+                        .ok();
+                }
+                number => {
+                    let number = std::str::from_utf8(number).ok()?.parse::<u32>().ok()?;
+
+                    parser.skip_whitespace();
+
+                    let generation = std::str::from_utf8(parser.parse_keyword()?)
+                        .ok()?
+                        .parse::<u16>()
+                        .ok()?;
+
+                    parser.skip_whitespace();
+
+                    if parser.parse_keyword()? != b"obj" {
+                        return None;
+                    }
+
+                    let object = parser.parse_object(0)?;
+
+                    parser.skip_whitespace();
+
+                    let mut keyword = parser.parse_keyword()?;
+
+                    let is_stream = keyword == b"stream";
+
+                    let mut stream_range = None;
+
+                    if is_stream {
+                        stream_range = Some(parser.skip_stream_data(object.get("Length"))?);
+                        parser.skip_whitespace();
+
+                        keyword = parser.parse_keyword()?;
+                    }
+
+                    if keyword != b"endobj" {
+                        return None;
+                    }
+
+                    objects.insert(
+                        number,
+                        PdfIndirectObject {
+                            generation,
+                            object,
+                            is_stream,
+                            stream_range,
+                        },
+                    );
+                }
+            }
+        }
+
+        Some(PdfSyntaxFile {
+            objects,
+            trailer,
+            start_xref,
+        })
+    }
+
+    /// Returns the given object, following any chain of references. References to objects
+    /// that do not exist resolve to null.
+    pub(crate) fn resolve(&self, object: &PdfSyntaxObject) -> PdfSyntaxObject {
+        resolve_with(object, |number| {
+            self.objects
+                .get(&number)
+                .map(|indirect| indirect.object.clone())
+        })
+    }
+
+    /// Returns references to the page dictionaries of this file, in page order, or `None`
+    /// if the page tree cannot be followed.
+    pub(crate) fn page_references(&self) -> Option<Vec<PdfSyntaxObject>> {
+        let catalog = self.resolve(self.trailer.as_ref()?.get("Root")?);
+
+        let mut pages = Vec::new();
+
+        self.collect_pages(catalog.get("Pages")?, 0, &mut pages)?;
+
+        Some(pages)
+    }
+
+    /// Appends references to the pages in the page tree rooted at the given node to
+    /// the given list, in page order.
+    fn collect_pages(
+        &self,
+        node: &PdfSyntaxObject,
+        depth: usize,
+        pages: &mut Vec<PdfSyntaxObject>,
+    ) -> Option<()> {
+        if depth > MAXIMUM_NESTING_DEPTH {
+            return None;
+        }
+
+        match self.resolve(node).get("Kids") {
+            Some(kids) => match self.resolve(kids) {
+                PdfSyntaxObject::Array(kids) => {
+                    for kid in kids.iter() {
+                        self.collect_pages(kid, depth + 1, pages)?;
+                    }
+
+                    Some(())
+                }
+                _ => None,
+            },
+            None => match node {
+                PdfSyntaxObject::Reference(..) => {
+                    pages.push(node.clone());
+
+                    Some(())
+                }
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Returns the given object, following any chain of references using the given function
+/// to look up indirect objects. Gives up after a fixed number of steps in case of a
+/// reference cycle.
+fn resolve_with(
+    object: &PdfSyntaxObject,
+    lookup: impl Fn(u32) -> Option<PdfSyntaxObject>,
+) -> PdfSyntaxObject {
+    let mut object = object.clone();
+
+    for _ in 0..MAXIMUM_NESTING_DEPTH {
+        match object {
+            PdfSyntaxObject::Reference(number, _) => {
+                object = lookup(number).unwrap_or(PdfSyntaxObject::Null)
+            }
+            _ => break,
+        }
+    }
+
+    object
+}
+
+/// A minimal parser for the object syntax of PDF and FDF files.
+pub(crate) struct PdfSyntaxParser<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl<'a> PdfSyntaxParser<'a> {
+    #[inline]
+    pub(crate) fn new(input: &'a [u8]) -> Self {
+        PdfSyntaxParser { input, position: 0 }
+    }
+
+    #[inline]
+    fn peek(&self) -> Option<u8> {
+        self.input.get(self.position).copied()
+    }
+
+    #[inline]
+    fn rest(&self) -> &'a [u8] {
+        &self.input[self.position..]
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(byte) = self.peek() {
+            if is_pdf_whitespace(byte) {
+                self.position += 1;
+            } else if byte == b'%' {
+                while let Some(byte) = self.peek() {
+                    if byte == b'\n' || byte == b'\r' {
+                        break;
+                    }
+
+                    self.position += 1;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Moves past the next occurrence of the given bytes.
+    fn skip_past(&mut self, bytes: &[u8]) -> Option<()> {
+        let offset = self
+            .rest()
+            .windows(bytes.len())
+            .position(|window| window == bytes)?;
+
+        self.position += offset + bytes.len();
+
+        Some(())
+    }
+
+    /// Moves past the data of a stream, immediately following the `stream` keyword, and
+    /// the `endstream` keyword that ends it. The given length is used to skip the data if it
+    /// is a direct number that correctly locates the `endstream` keyword; otherwise, the data
+    /// is assumed to end at the next occurrence of the `endstream` keyword. Returns the byte
+    /// range of the data.
+    fn skip_stream_data(&mut self, length: Option<&PdfSyntaxObject>) -> Option<Range<usize>> {
+        if self.rest().starts_with(b"\r\n") {
+            self.position += 2;
+        } else if self.peek() == Some(b'\n') {
+            self.position += 1;
+        }
+
+        let start = self.position;
+
+        if let Some(PdfSyntaxObject::Number(length)) = length {
+            self.position = self.position.saturating_add(*length as usize);
+
+            if self.position <= self.input.len() {
+                let end = self.position;
+
+                self.skip_whitespace();
+
+                if self.rest().starts_with(b"endstream") {
+                    self.position += b"endstream".len();
+
+                    return Some(start..end);
+                }
+            }
+
+            self.position = start;
+        }
+
+        // The stream's length is missing or wrong, so its data is taken to end at the
+        // end-of-line marker preceding the endstream keyword.
+
+        self.skip_past(b"endstream")?;
+
+        let mut end = self.position - b"endstream".len();
+
+        if self.input[start..end].ends_with(b"\r\n") {
+            end -= 2;
+        } else if self.input[start..end].ends_with(b"\n") || self.input[start..end].ends_with(b"\r")
+        {
+            end -= 1;
+        }
+
+        Some(start..end)
+    }
+
+    /// Parses a run of regular characters, such as a keyword or a number.
+    fn parse_keyword(&mut self) -> Option<&'a [u8]> {
+        let rest = self.rest();
+
+        let length = rest
+            .iter()
+            .position(|byte| is_pdf_whitespace(*byte) || is_pdf_delimiter(*byte))
+            .unwrap_or(rest.len());
+
+        if length == 0 {
+            return None;
+        }
+
+        self.position += length;
+
+        Some(&rest[..length])
+    }
+
+    pub(crate) fn parse_object(&mut self, depth: usize) -> Option<PdfSyntaxObject> {
+        if depth > MAXIMUM_NESTING_DEPTH {
+            return None;
+        }
+
+        self.skip_whitespace();
+
+        match self.peek()? {
+            b'<' if self.rest().starts_with(b"<<") => {
+                self.position += 2;
+
+                let mut entries = Vec::new();
+
+                loop {
+                    self.skip_whitespace();
+
+                    if self.rest().starts_with(b">>") {
+                        self.position += 2;
+
+                        return Some(PdfSyntaxObject::Dictionary(entries));
+                    }
+
+                    let key = self.parse_name()?;
+
+                    let value = self.parse_object(depth + 1)?;
+
+                    entries.push((key, value));
+                }
+            }
+            b'<' => self.parse_hex_string().map(PdfSyntaxObject::String),
+            b'(' => self.parse_literal_string().map(PdfSyntaxObject::String),
+            b'/' => self.parse_name().map(PdfSyntaxObject::Name),
+            b'[' => {
+                self.position += 1;
+
+                let mut items = Vec::new();
+
+                loop {
+                    self.skip_whitespace();
+
+                    if self.peek()? == b']' {
+                        self.position += 1;
+
+                        return Some(PdfSyntaxObject::Array(items));
+                    }
+
+                    items.push(self.parse_object(depth + 1)?);
+                }
+            }
+            _ => match self.parse_keyword()? {
+                b"true" => Some(PdfSyntaxObject::Bool(true)),
+                b"false" => Some(PdfSyntaxObject::Bool(false)),
+                b"null" => Some(PdfSyntaxObject::Null),
+                number => {
+                    let number = std::str::from_utf8(number).ok()?;
+
+                    // An integer followed by a generation number and the keyword R
+                    // is a reference to an indirect object.
+
+                    if let Ok(object_number) = number.parse::<u32>() {
+                        let position = self.position;
+
+                        self.skip_whitespace();
+
+                        if let Some(generation) = self
+                            .parse_keyword()
+                            .and_then(|generation| std::str::from_utf8(generation).ok())
+                            .and_then(|generation| generation.parse::<u16>().ok())
+                        {
+                            self.skip_whitespace();
+
+                            if self.parse_keyword() == Some(b"R") {
+                                return Some(PdfSyntaxObject::Reference(object_number, generation));
+                            }
+                        }
+
+                        self.position = position;
+                    }
+
+                    number.parse::<f64>().ok().map(PdfSyntaxObject::Number)
+                }
+            },
+        }
+    }
+
+    pub(crate) fn parse_name(&mut self) -> Option<String> {
+        if self.peek()? != b'/' {
+            return None;
+        }
+
+        self.position += 1;
+
+        let rest = self.rest();
+
+        let length = rest
+            .iter()
+            .position(|byte| is_pdf_whitespace(*byte) || is_pdf_delimiter(*byte))
+            .unwrap_or(rest.len());
+
+        self.position += length;
+
+        let mut bytes = Vec::with_capacity(length);
+
+        let mut index = 0;
+
+        while index < length {
+            if rest[index] == b'#' && index + 2 < length {
+                let hex = std::str::from_utf8(rest.get(index + 1..index + 3)?).ok()?;
+
+                bytes.push(u8::from_str_radix(hex, 16).ok()?);
+
+                index += 3;
+            } else {
+                bytes.push(rest[index]);
+
+                index += 1;
+            }
+        }
+
+        Some(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    fn parse_hex_string(&mut self) -> Option<Vec<u8>> {
+        self.position += 1;
+
+        let end = self.rest().iter().position(|byte| *byte == b'>')?;
+
+        let mut digits = self.rest()[..end]
+            .iter()
+            .filter(|byte| !is_pdf_whitespace(**byte))
+            .map(|byte| (*byte as char).to_digit(16))
+            .collect::<Option<Vec<_>>>()?;
+
+        self.position += end + 1;
+
+        // A final missing digit is assumed to be zero.
+
+        if digits.len() % 2 == 1 {
+            digits.push(0);
+        }
+
+        Some(
+            digits
+                .chunks(2)
+                .map(|pair| (pair[0] * 16 + pair[1]) as u8)
+                .collect(),
+        )
+    }
+
+    fn parse_literal_string(&mut self) -> Option<Vec<u8>> {
+        self.position += 1;
+
+        let mut result = Vec::new();
+
+        let mut depth = 0;
+
+        loop {
+            let byte = self.peek()?;
+
+            self.position += 1;
+
+            match byte {
+                b'(' => {
+                    depth += 1;
+                    result.push(byte);
+                }
+                b')' if depth == 0 => return Some(result),
+                b')' => {
+                    depth -= 1;
+                    result.push(byte);
+                }
+                b'\\' => {
+                    let escaped = self.peek()?;
+
+                    self.position += 1;
+
+                    match escaped {
+                        b'n' => result.push(b'\n'),
+                        b'r' => result.push(b'\r'),
+                        b't' => result.push(b'\t'),
+                        b'b' => result.push(0x08),
+                        b'f' => result.push(0x0C),
+                        b'0'..=b'7' => {
+                            // Up to three octal digits.
+
+                            let mut value = (escaped - b'0') as u32;
+
+                            for _ in 0..2 {
+                                match self.peek() {
+                                    Some(digit @ b'0'..=b'7') => {
+                                        value = value * 8 + (digit - b'0') as u32;
+                                        self.position += 1;
+                                    }
+                                    _ => break,
+                                }
+                            }
+
+                            result.push(value as u8);
+                        }
+                        b'\r' => {
+                            // A backslash at the end of a line continues the string on
+                            // the next line.
+
+                            if self.peek() == Some(b'\n') {
+                                self.position += 1;
+                            }
+                        }
+                        b'\n' => {}
+                        escaped => result.push(escaped),
+                    }
+                }
+                byte => result.push(byte),
+            }
+        }
+    }
+}
+
+/// A new or modified indirect object in a [PdfIncrementalUpdate].
+#[derive(Debug, Clone)]
+struct PdfUpdatedObject {
+    generation: u16,
+    object: PdfSyntaxObject,
+    stream_data: Option<Vec<u8>>,
+}
+
+/// Builds an incremental update to a parsed PDF file, as described in section 3.4.5 of
+/// The PDF Reference Manual, version 1.7. The update adds new and modified objects to the
+/// end of the file, leaving the existing content of the file unchanged.
+pub(crate) struct PdfIncrementalUpdate<'a> {
+    file: &'a PdfSyntaxFile,
+    trailer: PdfSyntaxObject,
+    start_xref: usize,
+    next_object_number: u32,
+    objects: BTreeMap<u32, PdfUpdatedObject>,
+}
+
+impl<'a> PdfIncrementalUpdate<'a> {
+    /// Creates a new, empty [PdfIncrementalUpdate] to the given file. Returns `None` if the
+    /// file does not have a trailer dictionary and a cross-reference section.
+    pub(crate) fn new(file: &'a PdfSyntaxFile) -> Option<Self> {
+        let trailer = file.trailer.clone()?;
+
+        let next_object_number = match trailer.get("Size") {
+            Some(PdfSyntaxObject::Number(size)) => *size as u32,
+            _ => return None,
+        }
+        .max(file.objects.keys().max().map_or(1, |number| number + 1));
+
+        Some(PdfIncrementalUpdate {
+            file,
+            trailer,
+            start_xref: file.start_xref?,
+            next_object_number,
+            objects: BTreeMap::new(),
+        })
+    }
+
+    /// Returns the trailer dictionary of the file being updated.
+    #[inline]
+    pub(crate) fn trailer(&self) -> &PdfSyntaxObject {
+        &self.trailer
+    }
+
+    /// Sets the given entry in the trailer dictionary written at the end of this update.
+    #[inline]
+    pub(crate) fn set_trailer_entry(&mut self, key: &str, value: PdfSyntaxObject) {
+        self.trailer.set(key, value);
+    }
+
+    /// Returns the current value of the indirect object with the given object number,
+    /// taking into account any changes made by this update. Returns `None` if the object
+    /// does not exist or is a stream.
+    pub(crate) fn get(&self, number: u32) -> Option<PdfSyntaxObject> {
+        match self.objects.get(&number) {
+            Some(updated) if updated.stream_data.is_none() => Some(updated.object.clone()),
+            Some(_) => None,
+            None => self
+                .file
+                .objects
+                .get(&number)
+                .filter(|indirect| !indirect.is_stream)
+                .map(|indirect| indirect.object.clone()),
+        }
+    }
+
+    /// Returns the given object, following any chain of references, taking into account any
+    /// changes made by this update.
+    pub(crate) fn resolve(&self, object: &PdfSyntaxObject) -> PdfSyntaxObject {
+        resolve_with(object, |number| self.get(number))
+    }
+
+    /// Reserves an object number for a new indirect object, returning a reference to it.
+    /// The value of the object must be set using [PdfIncrementalUpdate::set()].
+    pub(crate) fn reserve(&mut self) -> PdfSyntaxObject {
+        let number = self.next_object_number;
+
+        self.next_object_number += 1;
+
+        self.objects.insert(
+            number,
+            PdfUpdatedObject {
+                generation: 0,
+                object: PdfSyntaxObject::Null,
+                stream_data: None,
+            },
+        );
+
+        PdfSyntaxObject::Reference(number, 0)
+    }
+
+    /// Adds the given object to the file as a new indirect object, returning a reference to it.
+    pub(crate) fn add(&mut self, object: PdfSyntaxObject) -> PdfSyntaxObject {
+        let reference = self.reserve();
+
+        if let PdfSyntaxObject::Reference(number, _) = reference {
+            self.set(number, object);
+        }
+
+        reference
+    }
+
+    /// Adds a new stream with the given dictionary and data to the file, returning a
+    /// reference to it. The data is not compressed.
+    pub(crate) fn add_stream(
+        &mut self,
+        dictionary: PdfSyntaxObject,
+        data: Vec<u8>,
+    ) -> PdfSyntaxObject {
+        let reference = self.reserve();
+
+        if let PdfSyntaxObject::Reference(number, _) = reference {
+            self.set_stream(number, dictionary, data);
+        }
+
+        reference
+    }
+
+    /// Sets the indirect object with the given object number to a stream with the given
+    /// dictionary and data, replacing its existing value if it exists. The data is written
+    /// as given, so the dictionary must describe any filters already applied to it.
+    pub(crate) fn set_stream(
+        &mut self,
+        number: u32,
+        mut dictionary: PdfSyntaxObject,
+        data: Vec<u8>,
+    ) {
+        dictionary.set("Length", PdfSyntaxObject::Number(data.len() as f64));
+
+        let generation = self
+            .file
+            .objects
+            .get(&number)
+            .map_or(0, |indirect| indirect.generation);
+
+        self.objects.insert(
+            number,
+            PdfUpdatedObject {
+                generation,
+                object: dictionary,
+                stream_data: Some(data),
+            },
+        );
+    }
+
+    /// Sets the value of the indirect object with the given object number, replacing its
+    /// existing value if it exists.
+    pub(crate) fn set(&mut self, number: u32, object: PdfSyntaxObject) {
+        let generation = self
+            .file
+            .objects
+            .get(&number)
+            .map_or(0, |indirect| indirect.generation);
+
+        self.objects.insert(
+            number,
+            PdfUpdatedObject {
+                generation,
+                object,
+                stream_data: None,
+            },
+        );
+    }
+
+    /// Applies the given function to the value reached by following the given path of
+    /// dictionary keys from the indirect object with the given object number, updating
+    /// whichever indirect objects contain that value. References along the path are followed;
+    /// missing dictionaries along the path are created. The function receives a null value
+    /// if the last key in the path is missing.
+    ///
+    /// Returns `None` if the path cannot be followed or the function returns `None`, in
+    /// which case the update may have been partially applied.
+    pub(crate) fn modify(
+        &mut self,
+        number: u32,
+        path: &[&str],
+        f: &mut dyn FnMut(&mut PdfSyntaxObject) -> Option<()>,
+    ) -> Option<()> {
+        let mut object = self.get(number)?;
+
+        self.modify_within(&mut object, path, 0, f)?;
+
+        self.set(number, object);
+
+        Some(())
+    }
+
+    fn modify_within(
+        &mut self,
+        object: &mut PdfSyntaxObject,
+        path: &[&str],
+        depth: usize,
+        f: &mut dyn FnMut(&mut PdfSyntaxObject) -> Option<()>,
+    ) -> Option<()> {
+        if depth > MAXIMUM_NESTING_DEPTH {
+            return None;
+        }
+
+        match path.split_first() {
+            None => f(object),
+            Some((key, rest)) => {
+                let entry = object.entry_mut(key)?;
+
+                match entry {
+                    PdfSyntaxObject::Reference(number, _) => {
+                        let number = *number;
+
+                        let mut referenced = self.get(number)?;
+
+                        self.modify_within(&mut referenced, rest, depth + 1, f)?;
+
+                        self.set(number, referenced);
+
+                        Some(())
+                    }
+                    _ => {
+                        if *entry == PdfSyntaxObject::Null && !rest.is_empty() {
+                            *entry = PdfSyntaxObject::Dictionary(Vec::new());
+                        }
+
+                        self.modify_within(entry, rest, depth + 1, f)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Appends this incremental update to the given original file content, returning
+    /// the updated file.
+    pub(crate) fn write(self, mut output: Vec<u8>) -> Vec<u8> {
+        if !output.ends_with(b"\n") && !output.ends_with(b"\r") {
+            output.push(b'\n');
+        }
+
+        let update = self.write_at(output.len());
+
+        output.extend_from_slice(&update);
+
+        output
+    }
+
+    /// Returns the bytes of this incremental update, ready to be appended at the given
+    /// byte offset to a file whose content ends with a line break. Only the update itself
+    /// is returned, so the original file content need not be held in memory.
+    pub(crate) fn write_at(self, offset: usize) -> Vec<u8> {
+        let mut output = Vec::new();
+
+        let mut offsets = Vec::with_capacity(self.objects.len());
+
+        for (number, updated) in self.objects.iter() {
+            offsets.push((*number, updated.generation, offset + output.len()));
+
+            output.extend_from_slice(format!("{} {} obj\n", number, updated.generation).as_bytes());
+
+            updated.object.write_to(&mut output);
+
+            if let Some(data) = updated.stream_data.as_ref() {
+                output.extend_from_slice(b"\nstream\n");
+                output.extend_from_slice(data);
+                output.extend_from_slice(b"\nendstream");
+            }
+
+            output.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = offset + output.len();
+
+        output.extend_from_slice(b"xref\n");
+
+        // Each subsection of the cross-reference table covers a run of consecutive
+        // object numbers.
+
+        let mut start = 0;
+
+        while start < offsets.len() {
+            let mut end = start + 1;
+
+            while end < offsets.len() && offsets[end].0 == offsets[end - 1].0 + 1 {
+                end += 1;
+            }
+
+            output.extend_from_slice(format!("{} {}\n", offsets[start].0, end - start).as_bytes());
+
+            for (_, generation, offset) in offsets[start..end].iter() {
+                output.extend_from_slice(
+                    format!("{:010} {:05} n\r\n", offset, generation).as_bytes(),
+                );
+            }
+
+            start = end;
+        }
+
+        let mut trailer = self.trailer;
+
+        trailer.remove("XRefStm");
+        trailer.set(
+            "Size",
+            PdfSyntaxObject::Number(self.next_object_number as f64),
+        );
+        trailer.set("Prev", PdfSyntaxObject::Number(self.start_xref as f64));
+
+        output.extend_from_slice(b"trailer\n");
+        trailer.write_to(&mut output);
+        output.extend_from_slice(format!("\nstartxref\n{}\n%%EOF\n", xref_offset).as_bytes());
+
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::syntax::*;
+
+    const MINIMAL_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [3 0 R] /Count 1 >>\nendobj\n\
+        3 0 obj\n<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] /Contents 4 0 R >>\nendobj\n\
+        4 0 obj\n<< /Length 9 >>\nstream\nendstream\nendstream\nendobj\n\
+        xref\n0 5\n0000000000 65535 f\r\n0000000009 00000 n\r\n\
+        trailer\n<< /Size 5 /Root 1 0 R >>\nstartxref\n300\n%%EOF\n";
+
+    #[test]
+    fn test_parse_and_write_objects() {
+        let mut parser =
+            PdfSyntaxParser::new(b"<< /A [1 -2.5 (x\\)y) <FEFF00E9> /N#20m 3 0 R] /B true >>");
+
+        let object = parser.parse_object(0).unwrap();
+
+        assert_eq!(
+            object.get("A"),
+            Some(&PdfSyntaxObject::Array(vec![
+                PdfSyntaxObject::Number(1.0),
+                PdfSyntaxObject::Number(-2.5),
+                PdfSyntaxObject::String(b"x)y".to_vec()),
+                PdfSyntaxObject::text("é"),
+                PdfSyntaxObject::name("N m"),
+                PdfSyntaxObject::Reference(3, 0),
+            ]))
+        );
+
+        let mut output = Vec::new();
+
+        object.write_to(&mut output);
+
+        assert_eq!(
+            output,
+            b"<< /A [1 -2.5 (x\\)y) <FEFF00E9> /N#20m 3 0 R] /B true >>".to_vec()
+        );
+        assert_eq!(format_pdf_string(b"a(b)\\c\n"), "(a\\(b\\)\\\\c\\n)");
+        assert_eq!(decode_pdf_string(&[0xFE, 0xFF, 0x00, 0xE9]), "é");
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        let file = PdfSyntaxFile::parse(MINIMAL_PDF).unwrap();
+
+        assert_eq!(file.objects.len(), 4);
+        assert!(file.objects[&4].is_stream);
+        assert_eq!(
+            &MINIMAL_PDF[file.objects[&4].stream_range.clone().unwrap()],
+            b"endstream"
+        );
+        assert_eq!(file.start_xref, Some(300));
+
+        let mut update = PdfIncrementalUpdate::new(&file).unwrap();
+
+        let annotation = update.add(PdfSyntaxObject::Dictionary(vec![(
+            "Type".to_string(),
+            PdfSyntaxObject::name("Annot"),
+        )]));
+
+        update
+            .modify(3, &["Annots"], &mut |annotations| {
+                *annotations = PdfSyntaxObject::Array(vec![annotation.clone()]);
+
+                Some(())
+            })
+            .unwrap();
+
+        update
+            .modify(1, &["AcroForm", "Fields"], &mut |fields| {
+                *fields = PdfSyntaxObject::Array(Vec::new());
+
+                Some(())
+            })
+            .unwrap();
+
+        assert!(update.modify(4, &["Filter"], &mut |_| Some(())).is_none());
+
+        let output = update.write(MINIMAL_PDF.to_vec());
+
+        assert!(output.starts_with(MINIMAL_PDF));
+
+        let updated = PdfSyntaxFile::parse(&output).unwrap();
+
+        assert_eq!(updated.objects.len(), 5);
+        assert_eq!(
+            updated.objects[&3].object.get("Annots"),
+            Some(&PdfSyntaxObject::Array(vec![PdfSyntaxObject::Reference(
+                5, 0
+            )]))
+        );
+        assert_eq!(
+            updated
+                .resolve(&PdfSyntaxObject::Reference(1, 0))
+                .get("AcroForm"),
+            Some(&PdfSyntaxObject::Dictionary(vec![(
+                "Fields".to_string(),
+                PdfSyntaxObject::Array(Vec::new())
+            )]))
+        );
+
+        let trailer = updated.trailer.unwrap();
+
+        assert_eq!(trailer.get("Size"), Some(&PdfSyntaxObject::Number(6.0)));
+        assert_eq!(trailer.get("Prev"), Some(&PdfSyntaxObject::Number(300.0)));
+        assert_eq!(
+            updated.start_xref,
+            output
+                .windows(6)
+                .rposition(|window| window == b"\nxref\n")
+                .map(|position| position + 1)
+        );
+    }
+}
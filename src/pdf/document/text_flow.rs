@@ -0,0 +1,718 @@
+//! Defines the [PdfTextFlow] struct, used to lay out a long stream of styled text across
+//! successive pages in a `PdfDocument` using the `PdfDocument::flow_text()` function.
+
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
+use crate::pdf::document::fonts::{PdfFontToken, ToPdfFontToken};
+use crate::pdf::document::page::objects::common::{PdfPageObjectIndex, PdfPageObjectsCommon};
+use crate::pdf::document::page::size::PdfPagePaperSize;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::document::PdfDocument;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+use std::os::raw::{c_float, c_uint};
+
+/// A single span of consistently styled text in a [PdfTextFlowParagraph].
+#[derive(Debug, Clone, PartialEq)]
+struct PdfTextFlowRun {
+    text: String,
+    font: PdfFontToken,
+    font_size: PdfPoints,
+}
+
+/// A single paragraph of styled text in a [PdfTextFlow].
+///
+/// A paragraph is made up of one or more runs of text, each with its own font and font size.
+/// Runs are joined together without any separator, so a word may be split across runs
+/// with different styling. A newline character in the text of a run forces a line break.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PdfTextFlowParagraph {
+    runs: Vec<PdfTextFlowRun>,
+    is_kept_with_next: bool,
+}
+
+impl PdfTextFlowParagraph {
+    /// Creates a new, empty [PdfTextFlowParagraph].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a run of text, styled using the given font and font size, to this paragraph.
+    #[inline]
+    pub fn push_run(
+        mut self,
+        text: impl ToString,
+        font: impl ToPdfFontToken,
+        font_size: PdfPoints,
+    ) -> Self {
+        self.runs.push(PdfTextFlowRun {
+            text: text.to_string(),
+            font: font.token(),
+            font_size,
+        });
+
+        self
+    }
+
+    /// Controls whether this paragraph must be placed on the same page as the start of the
+    /// paragraph that follows it. This is typically used for headings, so that a heading is
+    /// never left stranded at the bottom of a page. The default is `false`.
+    #[inline]
+    pub fn keep_with_next(mut self, do_keep: bool) -> Self {
+        self.is_kept_with_next = do_keep;
+
+        self
+    }
+
+    /// Returns `true` if this paragraph must be placed on the same page as the start of the
+    /// paragraph that follows it.
+    #[inline]
+    pub fn is_kept_with_next(&self) -> bool {
+        self.is_kept_with_next
+    }
+}
+
+/// The page objects created on a single page by the `PdfDocument::flow_text()` function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfTextFlowPage {
+    page_index: PdfPageIndex,
+    objects: Vec<PdfPageObjectIndex>,
+}
+
+impl PdfTextFlowPage {
+    /// Returns the zero-based index of the page on which the objects were created.
+    #[inline]
+    pub fn page_index(&self) -> PdfPageIndex {
+        self.page_index
+    }
+
+    /// Returns the indices, in the `PdfPageObjects` collection of the page, of the text
+    /// objects created on the page, in reading order. The objects can be retrieved and
+    /// further styled, for example by changing their fill color.
+    #[inline]
+    pub fn objects(&self) -> &[PdfPageObjectIndex] {
+        self.objects.as_slice()
+    }
+}
+
+/// A stream of styled paragraphs that can be laid out across successive pages in a
+/// `PdfDocument` using the `PdfDocument::flow_text()` function.
+///
+/// Text is word-wrapped to the width of a frame rectangle, and each page is filled to the
+/// bottom of the frame before the flow continues onto the next page. Page breaks are placed
+/// so as to avoid leaving too few lines of a paragraph alone at the bottom of a page (orphans)
+/// or at the top of the next page (widows), and so that paragraphs marked with
+/// [PdfTextFlowParagraph::keep_with_next()] are never separated from the paragraph following
+/// them. These rules are relaxed only when a paragraph cannot be placed any other way.
+///
+/// Each line is created as one text object for each differently styled run of text it contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTextFlow {
+    paragraphs: Vec<PdfTextFlowParagraph>,
+    widow_lines: usize,
+    orphan_lines: usize,
+    line_spacing: f32,
+    paragraph_spacing: PdfPoints,
+}
+
+impl PdfTextFlow {
+    /// Creates a new, empty [PdfTextFlow] with widow and orphan control of two lines,
+    /// line spacing of 1.2 times the font size, and no additional spacing between paragraphs.
+    #[inline]
+    pub fn new() -> Self {
+        PdfTextFlow {
+            paragraphs: Vec::new(),
+            widow_lines: 2,
+            orphan_lines: 2,
+            line_spacing: 1.2,
+            paragraph_spacing: PdfPoints::ZERO,
+        }
+    }
+
+    /// Appends the given paragraph to this [PdfTextFlow].
+    #[inline]
+    pub fn push_paragraph(mut self, paragraph: PdfTextFlowParagraph) -> Self {
+        self.paragraphs.push(paragraph);
+
+        self
+    }
+
+    /// Sets the minimum number of lines of a paragraph that may be carried over onto the top
+    /// of a new page. A value of one disables widow control. The default is 2.
+    #[inline]
+    pub fn set_widow_lines(mut self, lines: usize) -> Self {
+        self.widow_lines = lines.max(1);
+
+        self
+    }
+
+    /// Sets the minimum number of lines of a paragraph that may be left at the bottom of a page
+    /// before a page break. A value of one disables orphan control. The default is 2.
+    #[inline]
+    pub fn set_orphan_lines(mut self, lines: usize) -> Self {
+        self.orphan_lines = lines.max(1);
+
+        self
+    }
+
+    /// Sets the height of each line as a multiple of the largest font size on that line.
+    /// The default is 1.2.
+    #[inline]
+    pub fn set_line_spacing(mut self, spacing: f32) -> Self {
+        self.line_spacing = spacing;
+
+        self
+    }
+
+    /// Sets the additional vertical space placed between paragraphs on the same page.
+    /// The default is zero.
+    #[inline]
+    pub fn set_paragraph_spacing(mut self, spacing: PdfPoints) -> Self {
+        self.paragraph_spacing = spacing;
+
+        self
+    }
+
+    /// Lays out this [PdfTextFlow] inside the given frame, starting on the page at the
+    /// given index and inserting new pages of the same size directly after it as needed.
+    pub(crate) fn flow(
+        &self,
+        document: &mut PdfDocument,
+        first_page_index: PdfPageIndex,
+        frame: PdfRect,
+    ) -> Result<Vec<PdfTextFlowPage>, PdfiumError> {
+        let page_size = document.pages().page_size(first_page_index)?;
+
+        let bindings = document.bindings();
+
+        let paragraphs = self
+            .paragraphs
+            .iter()
+            .map(|paragraph| {
+                let lines = break_lines(
+                    &paragraph.runs,
+                    frame.width().value,
+                    self.line_spacing,
+                    |run, text| measure(bindings, run, text),
+                );
+
+                (lines, paragraph.is_kept_with_next)
+            })
+            .collect::<Vec<_>>();
+
+        let blocks = paragraphs
+            .iter()
+            .map(|(lines, is_kept_with_next)| FlowBlock {
+                line_heights: lines.iter().map(|line| line.height).collect(),
+                is_kept_with_next: *is_kept_with_next,
+            })
+            .collect::<Vec<_>>();
+
+        let placements = paginate(
+            &blocks,
+            frame.height().value,
+            self.paragraph_spacing.value,
+            self.widow_lines,
+            self.orphan_lines,
+        );
+
+        let mut result = Vec::with_capacity(placements.len());
+
+        for (offset, lines) in placements.iter().enumerate() {
+            let page_index = first_page_index + offset as PdfPageIndex;
+
+            let mut page = if offset == 0 {
+                document.pages().get(page_index)?
+            } else {
+                document.pages_mut().create_page_at_index(
+                    PdfPagePaperSize::Custom(page_size.width(), page_size.height()),
+                    page_index,
+                )?
+            };
+
+            let mut objects = Vec::new();
+
+            for placement in lines.iter() {
+                let line = &paragraphs[placement.block].0[placement.line];
+
+                let baseline = frame.top.value - placement.top - line.font_size;
+
+                for segment in line.segments.iter() {
+                    let run = &self.paragraphs[placement.block].runs[segment.run];
+
+                    page.objects_mut().create_text_object(
+                        PdfPoints::new(frame.left.value + segment.left),
+                        PdfPoints::new(baseline),
+                        segment.text.as_str(),
+                        run.font,
+                        run.font_size,
+                    )?;
+
+                    objects.push(page.objects().len() - 1);
+                }
+            }
+
+            result.push(PdfTextFlowPage {
+                page_index,
+                objects,
+            });
+        }
+
+        Ok(result)
+    }
+}
+
+impl Default for PdfTextFlow {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns the width of the given text when drawn in the font and font size of the given run.
+fn measure(bindings: &dyn PdfiumLibraryBindings, run: &PdfTextFlowRun, text: &str) -> f32 {
+    text.chars()
+        .map(|char| {
+            let mut width: c_float = 0.0;
+
+            if bindings.is_true(bindings.FPDFFont_GetGlyphWidth(
+                run.font.handle(),
+                char as c_uint,
+                run.font_size.value as c_float,
+                &mut width,
+            )) {
+                width
+            } else {
+                0.0
+            }
+        })
+        .sum()
+}
+
+/// A span of text on a single line, drawn using the styling of a single run.
+#[derive(Debug, Clone, PartialEq)]
+struct FlowSegment {
+    run: usize,
+    text: String,
+    left: f32,
+}
+
+/// A single line of a paragraph.
+#[derive(Debug, Clone, PartialEq, Default)]
+struct FlowLine {
+    segments: Vec<FlowSegment>,
+    width: f32,
+    font_size: f32,
+    height: f32,
+}
+
+impl FlowLine {
+    fn push(&mut self, run: usize, text: &str, width: f32, font_size: f32, line_spacing: f32) {
+        match self.segments.last_mut() {
+            Some(segment) if segment.run == run => segment.text.push_str(text),
+            _ => self.segments.push(FlowSegment {
+                run,
+                text: text.to_string(),
+                left: self.width,
+            }),
+        }
+
+        self.width += width;
+        self.font_size = self.font_size.max(font_size);
+        self.height = self.font_size * line_spacing;
+    }
+}
+
+/// Word-wraps the given runs into lines no wider than the given width, measuring text using
+/// the given function. A word wider than the given width is placed on a line of its own.
+fn break_lines<F>(
+    runs: &[PdfTextFlowRun],
+    maximum_width: f32,
+    line_spacing: f32,
+    measure: F,
+) -> Vec<FlowLine>
+where
+    F: Fn(&PdfTextFlowRun, &str) -> f32,
+{
+    // Split the runs into words, each made up of one or more pieces of differently styled text.
+    // Each word records the run of the space preceding it, if any, and whether it
+    // must start a new line.
+
+    struct Word {
+        pieces: Vec<(usize, String)>,
+        space: Option<usize>,
+        is_line_start: bool,
+    }
+
+    let mut words: Vec<Word> = Vec::new();
+
+    let mut current = Word {
+        pieces: Vec::new(),
+        space: None,
+        is_line_start: false,
+    };
+
+    for (index, run) in runs.iter().enumerate() {
+        for char in run.text.chars() {
+            if char.is_whitespace() {
+                if !current.pieces.is_empty() {
+                    words.push(std::mem::replace(
+                        &mut current,
+                        Word {
+                            pieces: Vec::new(),
+                            space: None,
+                            is_line_start: false,
+                        },
+                    ));
+                }
+
+                if char == '\n' {
+                    current.is_line_start = true;
+                    current.space = None;
+                } else if !current.is_line_start {
+                    current.space = Some(index);
+                }
+            } else {
+                match current.pieces.last_mut() {
+                    Some((run, text)) if *run == index => text.push(char),
+                    _ => current.pieces.push((index, char.to_string())),
+                }
+            }
+        }
+    }
+
+    if !current.pieces.is_empty() {
+        words.push(current);
+    }
+
+    let mut lines: Vec<FlowLine> = Vec::new();
+
+    let mut line = FlowLine::default();
+
+    for word in words.iter() {
+        let word_width: f32 = word
+            .pieces
+            .iter()
+            .map(|(run, text)| measure(&runs[*run], text))
+            .sum();
+
+        let space_width = match word.space {
+            Some(run) if !line.segments.is_empty() => measure(&runs[run], " "),
+            _ => 0.0,
+        };
+
+        if !line.segments.is_empty()
+            && (word.is_line_start || line.width + space_width + word_width > maximum_width)
+        {
+            lines.push(std::mem::take(&mut line));
+        } else if let Some(run) = word.space.filter(|_| !line.segments.is_empty()) {
+            line.push(
+                run,
+                " ",
+                space_width,
+                runs[run].font_size.value,
+                line_spacing,
+            );
+        }
+
+        for (run, text) in word.pieces.iter() {
+            line.push(
+                *run,
+                text,
+                measure(&runs[*run], text),
+                runs[*run].font_size.value,
+                line_spacing,
+            );
+        }
+    }
+
+    if !line.segments.is_empty() {
+        lines.push(line);
+    }
+
+    lines
+}
+
+/// The line heights of a single paragraph, as used when dividing paragraphs between pages.
+struct FlowBlock {
+    line_heights: Vec<f32>,
+    is_kept_with_next: bool,
+}
+
+/// The position of a single line on a page, measured downwards from the top of the frame.
+#[derive(Debug, Copy, Clone, PartialEq)]
+struct FlowPlacement {
+    block: usize,
+    line: usize,
+    top: f32,
+}
+
+/// Divides the lines of the given paragraphs between pages with frames of the given height,
+/// honouring the given widow and orphan limits and each paragraph's keep-with-next setting
+/// where possible. Returns the placement of every line on every page.
+fn paginate(
+    blocks: &[FlowBlock],
+    frame_height: f32,
+    paragraph_spacing: f32,
+    widow_lines: usize,
+    orphan_lines: usize,
+) -> Vec<Vec<FlowPlacement>> {
+    let mut pages = vec![Vec::new()];
+
+    let mut top = 0.0;
+
+    for (index, block) in blocks.iter().enumerate() {
+        let count = block.line_heights.len();
+
+        let mut start = 0;
+
+        while start < count {
+            let is_page_empty = pages.last().map(|page| page.is_empty()).unwrap_or(true);
+
+            let gap = if start == 0 && !is_page_empty {
+                paragraph_spacing
+            } else {
+                0.0
+            };
+
+            let available = frame_height - top - gap;
+
+            let heights = &block.line_heights[start..];
+
+            let fit = lines_fitting(heights, available);
+
+            let remaining = count - start;
+
+            let take = if fit >= remaining {
+                // The rest of the paragraph fits on this page. If it must be kept with the next
+                // paragraph, check that the first lines of that paragraph fit here too.
+
+                let is_next_separated = block.is_kept_with_next
+                    && blocks.get(index + 1).map_or(false, |next| {
+                        let next_lines = next.line_heights.len().min(orphan_lines);
+
+                        let needed = heights.iter().sum::<f32>()
+                            + paragraph_spacing
+                            + next.line_heights[..next_lines].iter().sum::<f32>();
+
+                        needed > available
+                    });
+
+                if is_next_separated && start == 0 && !is_page_empty {
+                    0
+                } else {
+                    remaining
+                }
+            } else {
+                let mut take = fit;
+
+                if remaining - take < widow_lines {
+                    take = remaining.saturating_sub(widow_lines);
+                }
+
+                if start == 0 && take < orphan_lines {
+                    take = 0;
+                }
+
+                if take == 0 && is_page_empty {
+                    // The paragraph cannot be placed without breaking the rules, even on an
+                    // empty page, so place as much as will fit.
+
+                    fit.max(1)
+                } else {
+                    take
+                }
+            };
+
+            if take > 0 {
+                if start == 0 {
+                    top += gap;
+                }
+
+                let page = pages.last_mut().unwrap();
+
+                for line in start..start + take {
+                    page.push(FlowPlacement {
+                        block: index,
+                        line,
+                        top,
+                    });
+
+                    top += block.line_heights[line];
+                }
+
+                start += take;
+            }
+
+            if start < count {
+                pages.push(Vec::new());
+
+                top = 0.0;
+            }
+        }
+    }
+
+    pages
+}
+
+/// Returns the number of lines with the given heights that fit within the given height.
+fn lines_fitting(heights: &[f32], available: f32) -> usize {
+    let mut total = 0.0;
+
+    heights
+        .iter()
+        .take_while(|height| {
+            total += **height;
+
+            total <= available
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::text_flow::{break_lines, paginate, FlowBlock, PdfTextFlowRun};
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    fn block(lines: usize, is_kept_with_next: bool) -> FlowBlock {
+        FlowBlock {
+            line_heights: vec![10.0; lines],
+            is_kept_with_next,
+        }
+    }
+
+    fn lines_per_page(blocks: &[FlowBlock], widows: usize, orphans: usize) -> Vec<Vec<usize>> {
+        // Returns the number of lines of each paragraph placed on each page.
+
+        paginate(blocks, 50.0, 0.0, widows, orphans)
+            .iter()
+            .map(|page| {
+                (0..blocks.len())
+                    .map(|index| page.iter().filter(|line| line.block == index).count())
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_break_lines() {
+        let runs = vec![
+            PdfTextFlowRun {
+                text: "The quick brown fox jumps ov".to_string(),
+                font: PdfFontToken::from_pdfium(std::ptr::null_mut()),
+                font_size: PdfPoints::new(10.0),
+            },
+            PdfTextFlowRun {
+                text: "er the\nlazy dog".to_string(),
+                font: PdfFontToken::from_pdfium(std::ptr::null_mut()),
+                font_size: PdfPoints::new(12.0),
+            },
+        ];
+
+        let lines = break_lines(&runs, 100.0, 1.5, |_, text| {
+            text.chars().count() as f32 * 5.0
+        });
+
+        let text = lines
+            .iter()
+            .map(|line| {
+                line.segments
+                    .iter()
+                    .map(|segment| segment.text.as_str())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            text,
+            vec!["The quick brown fox", "jumps over the", "lazy dog"]
+        );
+
+        // The word split across two runs is drawn as two segments.
+
+        assert_eq!(lines[1].segments.len(), 2);
+        assert_eq!(lines[1].segments[1].left, 40.0);
+        assert_eq!(lines[1].height, 18.0);
+        assert_eq!(lines[0].height, 15.0);
+    }
+
+    #[test]
+    fn test_paginate_widows_and_orphans() {
+        // Five lines fit on each page.
+
+        assert_eq!(
+            lines_per_page(&[block(3, false), block(3, false)], 1, 1),
+            vec![vec![3, 2], vec![0, 1]]
+        );
+
+        // Widow control pulls a second line onto the next page.
+
+        assert_eq!(
+            lines_per_page(&[block(3, false), block(3, false)], 2, 1),
+            vec![vec![3, 1], vec![0, 2]]
+        );
+
+        // Orphan control moves the whole paragraph onto the next page.
+
+        assert_eq!(
+            lines_per_page(&[block(4, false), block(3, false)], 1, 2),
+            vec![vec![4, 0], vec![0, 3]]
+        );
+    }
+
+    #[test]
+    fn test_paginate_keep_with_next() {
+        assert_eq!(
+            lines_per_page(&[block(3, false), block(1, true), block(4, false)], 2, 2),
+            vec![vec![3, 0, 0], vec![0, 1, 4]]
+        );
+
+        // A paragraph longer than a page is split regardless.
+
+        assert_eq!(
+            lines_per_page(&[block(12, false)], 2, 2),
+            vec![vec![5], vec![5], vec![2]]
+        );
+    }
+
+    #[test]
+    fn test_flow_text() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut document = pdfium.create_new_pdf()?;
+
+        document
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        let font = document.fonts_mut().helvetica();
+
+        let mut flow = PdfTextFlow::new().set_paragraph_spacing(PdfPoints::new(6.0));
+
+        for _ in 0..40 {
+            flow = flow.push_paragraph(PdfTextFlowParagraph::new().push_run(
+                "Lorem ipsum dolor sit amet, consectetur adipiscing elit, sed do eiusmod tempor.",
+                font,
+                PdfPoints::new(12.0),
+            ));
+        }
+
+        let frame = PdfRect::new_from_values(72.0, 72.0, 770.0, 523.0);
+
+        let pages = document.flow_text(&flow, 0, frame)?;
+
+        assert!(pages.len() > 1);
+        assert_eq!(document.pages().len() as usize, pages.len());
+
+        for page in pages.iter() {
+            assert_eq!(
+                document.pages().get(page.page_index())?.objects().len(),
+                page.objects().len()
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,434 @@
+//! Defines the [PdfTextIndex] struct, a compact, self-contained record of the text on every
+//! page in a `PdfDocument` and the position of every character, suitable for storing
+//! alongside a search index so that search hits can be highlighted without reopening the
+//! original document.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::page::text::PdfPageText;
+use crate::pdf::document::pages::PdfPageIndex;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+use std::convert::TryFrom;
+
+/// The magic bytes at the start of the encoded form of a [PdfTextIndex].
+const MAGIC: &[u8; 4] = b"PTI1";
+
+/// Character positions are stored in tenths of a point.
+const QUANTIZATION: f32 = 10.0;
+
+/// The text on a single page in a `PdfDocument`, together with the bounding box of
+/// every character in that text.
+///
+/// Character offsets used by this struct count Unicode scalar values (Rust `char`s),
+/// not bytes. Use `str::char_indices()` to convert byte offsets returned by a search
+/// backend into character offsets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfPageTextIndex {
+    page_index: PdfPageIndex,
+    text: String,
+    bounds: Vec<PdfRect>,
+}
+
+impl PdfPageTextIndex {
+    /// Creates a new [PdfPageTextIndex] from the characters in the given [PdfPageText].
+    pub(crate) fn from_page_text(page_index: PdfPageIndex, text: &PdfPageText) -> Self {
+        let mut result = PdfPageTextIndex {
+            page_index,
+            text: String::new(),
+            bounds: Vec::new(),
+        };
+
+        for char in text.chars().iter() {
+            // Characters without a valid Unicode representation are replaced so that
+            // every character on the page still occupies exactly one character offset.
+
+            result
+                .text
+                .push(char.unicode_char().unwrap_or(char::REPLACEMENT_CHARACTER));
+
+            result
+                .bounds
+                .push(char.loose_bounds().unwrap_or(PdfRect::ZERO));
+        }
+
+        result
+    }
+
+    /// Returns the zero-based index of the page described by this [PdfPageTextIndex].
+    #[inline]
+    pub fn page_index(&self) -> PdfPageIndex {
+        self.page_index
+    }
+
+    /// Returns the full text of the page described by this [PdfPageTextIndex].
+    #[inline]
+    pub fn text(&self) -> &str {
+        self.text.as_str()
+    }
+
+    /// Returns the number of characters in the text of this [PdfPageTextIndex].
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bounds.len()
+    }
+
+    /// Returns `true` if the page described by this [PdfPageTextIndex] contains no text.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bounds.is_empty()
+    }
+
+    /// Returns the bounding box of the character at the given character offset, or `None`
+    /// if the offset is out of range or the character has no position on the page,
+    /// as is the case for line breaks generated by Pdfium.
+    pub fn char_bounds(&self, offset: usize) -> Option<PdfRect> {
+        self.bounds
+            .get(offset)
+            .copied()
+            .filter(|bounds| !is_empty_rect(bounds))
+    }
+
+    /// Returns the rectangles that should be highlighted to show the given number of
+    /// characters starting at the given character offset. The bounding boxes of adjacent
+    /// characters on the same line are merged, so a match that fits on a single line
+    /// results in a single rectangle.
+    pub fn highlight_rects(&self, offset: usize, count: usize) -> Vec<PdfRect> {
+        let mut result: Vec<PdfRect> = Vec::new();
+
+        let end = offset.saturating_add(count).min(self.bounds.len());
+
+        for bounds in self.bounds[offset.min(end)..end].iter() {
+            if is_empty_rect(bounds) {
+                continue;
+            }
+
+            let middle = (bounds.top.value + bounds.bottom.value) / 2.0;
+
+            match result.last_mut() {
+                Some(line)
+                    if middle <= line.top.value
+                        && middle >= line.bottom.value
+                        && bounds.left.value >= line.left.value =>
+                {
+                    line.top = PdfPoints::new(line.top.value.max(bounds.top.value));
+                    line.bottom = PdfPoints::new(line.bottom.value.min(bounds.bottom.value));
+                    line.right = PdfPoints::new(line.right.value.max(bounds.right.value));
+                }
+                _ => result.push(*bounds),
+            }
+        }
+
+        result
+    }
+
+    fn encode(&self, output: &mut Vec<u8>) {
+        write_varint(output, self.page_index as u64);
+        write_varint(output, self.text.len() as u64);
+        output.extend_from_slice(self.text.as_bytes());
+        write_varint(output, self.bounds.len() as u64);
+
+        // Each character's position is stored relative to the position of the preceding
+        // character, so characters running along a line encode to only a few bytes each.
+
+        let mut previous_left = 0;
+
+        let mut previous_bottom = 0;
+
+        for bounds in self.bounds.iter() {
+            let left = quantize(bounds.left);
+
+            let bottom = quantize(bounds.bottom);
+
+            write_varint(output, zigzag(left - previous_left));
+            write_varint(output, zigzag(bottom - previous_bottom));
+            write_varint(output, zigzag(quantize(bounds.right) - left));
+            write_varint(output, zigzag(quantize(bounds.top) - bottom));
+
+            previous_left = left;
+            previous_bottom = bottom;
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> Result<Self, PdfiumError> {
+        let page_index = PdfPageIndex::try_from(read_varint(input)?)
+            .map_err(|_| PdfiumError::InvalidTextIndexData)?;
+
+        let text_length = read_length(input)?;
+
+        if input.len() < text_length {
+            return Err(PdfiumError::InvalidTextIndexData);
+        }
+
+        let text = std::str::from_utf8(&input[..text_length])
+            .map_err(|_| PdfiumError::InvalidTextIndexData)?
+            .to_string();
+
+        *input = &input[text_length..];
+
+        let count = read_length(input)?;
+
+        // Each character's bounds are encoded in at least four bytes.
+
+        let minimum_length = count
+            .checked_mul(4)
+            .ok_or(PdfiumError::InvalidTextIndexData)?;
+
+        if count != text.chars().count() || input.len() < minimum_length {
+            return Err(PdfiumError::InvalidTextIndexData);
+        }
+
+        let mut bounds = Vec::with_capacity(count);
+
+        let mut left: i64 = 0;
+
+        let mut bottom: i64 = 0;
+
+        for _ in 0..count {
+            left = read_offset_from(input, left)?;
+            bottom = read_offset_from(input, bottom)?;
+
+            let right = read_offset_from(input, left)?;
+
+            let top = read_offset_from(input, bottom)?;
+
+            bounds.push(PdfRect::new(
+                dequantize(bottom),
+                dequantize(left),
+                dequantize(top),
+                dequantize(right),
+            ));
+        }
+
+        Ok(PdfPageTextIndex {
+            page_index,
+            text,
+            bounds,
+        })
+    }
+}
+
+/// The text and character positions of every page in a `PdfDocument`, as returned by the
+/// `PdfDocument::build_text_index()` function.
+///
+/// A search backend can index the text of each page, store the compact encoding of this
+/// [PdfTextIndex] returned by the [PdfTextIndex::to_bytes()] function, and later use
+/// [PdfTextIndex::from_bytes()] and [PdfPageTextIndex::highlight_rects()] to return
+/// highlight coordinates for a search hit without reopening the document.
+/// Character positions are stored to a precision of one tenth of a point.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PdfTextIndex {
+    pages: Vec<PdfPageTextIndex>,
+}
+
+impl PdfTextIndex {
+    #[inline]
+    pub(crate) fn from_pages(pages: Vec<PdfPageTextIndex>) -> Self {
+        PdfTextIndex { pages }
+    }
+
+    /// Returns the [PdfPageTextIndex] for every page in this [PdfTextIndex], in page order.
+    #[inline]
+    pub fn pages(&self) -> &[PdfPageTextIndex] {
+        self.pages.as_slice()
+    }
+
+    /// Returns the [PdfPageTextIndex] for the page at the given zero-based index, if any.
+    #[inline]
+    pub fn page(&self, index: PdfPageIndex) -> Option<&PdfPageTextIndex> {
+        self.pages.iter().find(|page| page.page_index == index)
+    }
+
+    /// Returns a compact binary encoding of this [PdfTextIndex]. The original [PdfTextIndex]
+    /// can be recovered by passing the encoded bytes to [PdfTextIndex::from_bytes()].
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut output = Vec::new();
+
+        output.extend_from_slice(MAGIC);
+        write_varint(&mut output, self.pages.len() as u64);
+
+        for page in self.pages.iter() {
+            page.encode(&mut output);
+        }
+
+        output
+    }
+
+    /// Decodes a [PdfTextIndex] from bytes previously returned by [PdfTextIndex::to_bytes()].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, PdfiumError> {
+        if !bytes.starts_with(MAGIC) {
+            return Err(PdfiumError::InvalidTextIndexData);
+        }
+
+        let mut input = &bytes[MAGIC.len()..];
+
+        let count = read_length(&mut input)?;
+
+        let mut pages = Vec::new();
+
+        for _ in 0..count {
+            pages.push(PdfPageTextIndex::decode(&mut input)?);
+        }
+
+        if input.is_empty() {
+            Ok(PdfTextIndex { pages })
+        } else {
+            Err(PdfiumError::InvalidTextIndexData)
+        }
+    }
+}
+
+#[inline]
+fn is_empty_rect(rect: &PdfRect) -> bool {
+    rect.right.value <= rect.left.value || rect.top.value <= rect.bottom.value
+}
+
+#[inline]
+fn quantize(value: PdfPoints) -> i64 {
+    (value.value * QUANTIZATION).round() as i64
+}
+
+#[inline]
+fn dequantize(value: i64) -> PdfPoints {
+    PdfPoints::new(value as f32 / QUANTIZATION)
+}
+
+#[inline]
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+#[inline]
+fn unzigzag(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+
+    output.push(value as u8);
+}
+
+/// Reads a zigzag-encoded offset from the given input and adds it to the given value,
+/// returning an error if the result overflows.
+fn read_offset_from(input: &mut &[u8], value: i64) -> Result<i64, PdfiumError> {
+    value
+        .checked_add(unzigzag(read_varint(input)?))
+        .ok_or(PdfiumError::InvalidTextIndexData)
+}
+
+/// Reads a length or count from the given input, returning an error if it does not fit
+/// in a `usize`.
+fn read_length(input: &mut &[u8]) -> Result<usize, PdfiumError> {
+    usize::try_from(read_varint(input)?).map_err(|_| PdfiumError::InvalidTextIndexData)
+}
+
+fn read_varint(input: &mut &[u8]) -> Result<u64, PdfiumError> {
+    let mut result = 0u64;
+
+    for shift in (0..64).step_by(7) {
+        let (byte, rest) = input
+            .split_first()
+            .ok_or(PdfiumError::InvalidTextIndexData)?;
+
+        *input = rest;
+
+        result |= ((byte & 0x7f) as u64) << shift;
+
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+    }
+
+    Err(PdfiumError::InvalidTextIndexData)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::PdfiumError;
+    use crate::pdf::document::text_index::{PdfPageTextIndex, PdfTextIndex, MAGIC};
+    use crate::pdf::rect::PdfRect;
+
+    fn test_page() -> PdfPageTextIndex {
+        PdfPageTextIndex {
+            page_index: 3,
+            text: "Hi\r\nyou".to_string(),
+            bounds: vec![
+                PdfRect::new_from_values(700.0, 50.0, 712.0, 58.5),
+                PdfRect::new_from_values(700.0, 58.5, 712.0, 62.0),
+                PdfRect::ZERO,
+                PdfRect::ZERO,
+                PdfRect::new_from_values(680.0, 50.0, 692.0, 57.0),
+                PdfRect::new_from_values(680.0, 57.0, 692.0, 64.0),
+                PdfRect::new_from_values(680.0, 64.0, 692.0, 71.0),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_text_index_round_trip() -> Result<(), PdfiumError> {
+        let index = PdfTextIndex::from_pages(vec![test_page()]);
+
+        let bytes = index.to_bytes();
+
+        assert_eq!(PdfTextIndex::from_bytes(&bytes)?, index);
+        assert!(PdfTextIndex::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_text_index_from_corrupt_bytes() {
+        // A single page containing the text "ab", whose character positions are encoded as
+        // offsets of i64::MAX that overflow when summed.
+
+        let mut bytes = MAGIC.to_vec();
+
+        bytes.extend_from_slice(&[1, 0, 2, b'a', b'b', 2]);
+
+        for _ in 0..2 * 4 {
+            bytes.extend_from_slice(&[0xfe; 9]);
+            bytes.push(0x01);
+        }
+
+        assert!(matches!(
+            PdfTextIndex::from_bytes(&bytes),
+            Err(PdfiumError::InvalidTextIndexData)
+        ));
+
+        // A character count too large to be backed by the remaining input is rejected.
+
+        let mut bytes = MAGIC.to_vec();
+
+        bytes.extend_from_slice(&[1, 0, 0]);
+        bytes.extend_from_slice(&[0xff; 9]);
+        bytes.push(0x01);
+
+        assert!(matches!(
+            PdfTextIndex::from_bytes(&bytes),
+            Err(PdfiumError::InvalidTextIndexData)
+        ));
+    }
+
+    #[test]
+    fn test_highlight_rects() {
+        let page = test_page();
+
+        assert_eq!(page.char_bounds(2), None);
+
+        assert_eq!(
+            page.highlight_rects(0, 7),
+            vec![
+                PdfRect::new_from_values(700.0, 50.0, 712.0, 62.0),
+                PdfRect::new_from_values(680.0, 50.0, 692.0, 71.0),
+            ]
+        );
+
+        assert_eq!(
+            page.highlight_rects(5, 100),
+            vec![PdfRect::new_from_values(680.0, 57.0, 692.0, 71.0)]
+        );
+    }
+}
@@ -0,0 +1,415 @@
+//! Defines the [PdfXfaPackets] struct, a collection of all the [PdfXfaPacket] objects
+//! making up the XFA form embedded in a `PdfDocument`.
+//!
+//! This module is only available when the `pdfium_enable_xfa` crate feature is enabled.
+
+use crate::bindgen::FPDF_DOCUMENT;
+use crate::bindings::PdfiumLibraryBindings;
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use crate::utils::mem::create_byte_buffer;
+use std::ffi::{c_void, CString};
+use std::ops::{Range, RangeInclusive};
+use std::os::raw::{c_int, c_ulong};
+
+pub type PdfXfaPacketIndex = usize;
+
+/// A single packet of the XFA form embedded in a `PdfDocument`, such as the `template`
+/// packet describing the form's layout or the `datasets` packet holding the form's data.
+///
+/// Each packet holds a fragment of an XML Data Package (XDP) document; the concatenation
+/// of every packet in a [PdfXfaPackets] collection, in order, is the complete XDP document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PdfXfaPacket {
+    name: String,
+    content: Vec<u8>,
+}
+
+impl PdfXfaPacket {
+    /// Returns the name of this [PdfXfaPacket], such as `template`, `datasets`, or `config`.
+    /// The name is empty if the XFA form is stored as a single XDP document rather than as
+    /// separate packets.
+    #[inline]
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    /// Returns the XML content of this [PdfXfaPacket] as raw bytes.
+    #[inline]
+    pub fn content(&self) -> &[u8] {
+        self.content.as_slice()
+    }
+
+    /// Returns the XML content of this [PdfXfaPacket] as text, or `None` if the content
+    /// is not valid UTF-8.
+    #[inline]
+    pub fn content_as_str(&self) -> Option<&str> {
+        std::str::from_utf8(self.content.as_slice()).ok()
+    }
+}
+
+/// The collection of [PdfXfaPacket] objects making up the XFA form embedded in a
+/// `PdfDocument`. The collection is empty if the document does not contain an XFA form.
+pub struct PdfXfaPackets<'a> {
+    document_handle: FPDF_DOCUMENT,
+    bindings: &'a dyn PdfiumLibraryBindings,
+}
+
+impl<'a> PdfXfaPackets<'a> {
+    #[inline]
+    pub(crate) fn from_pdfium(
+        document_handle: FPDF_DOCUMENT,
+        bindings: &'a dyn PdfiumLibraryBindings,
+    ) -> Self {
+        PdfXfaPackets {
+            document_handle,
+            bindings,
+        }
+    }
+
+    /// Returns the [PdfiumLibraryBindings] used by this [PdfXfaPackets] collection.
+    #[inline]
+    pub fn bindings(&self) -> &'a dyn PdfiumLibraryBindings {
+        self.bindings
+    }
+
+    /// Returns the number of packets in this [PdfXfaPackets] collection.
+    pub fn len(&self) -> PdfXfaPacketIndex {
+        self.bindings()
+            .FPDF_GetXFAPacketCount(self.document_handle)
+            .max(0) as PdfXfaPacketIndex
+    }
+
+    /// Returns `true` if this [PdfXfaPackets] collection is empty, indicating that the
+    /// document does not contain an XFA form.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns a Range from `0..(number of packets)` for this [PdfXfaPackets] collection.
+    #[inline]
+    pub fn as_range(&self) -> Range<PdfXfaPacketIndex> {
+        0..self.len()
+    }
+
+    /// Returns an inclusive Range from `0..=(number of packets - 1)`
+    /// for this [PdfXfaPackets] collection.
+    #[inline]
+    pub fn as_range_inclusive(&self) -> RangeInclusive<PdfXfaPacketIndex> {
+        if self.is_empty() {
+            0..=0
+        } else {
+            0..=(self.len() - 1)
+        }
+    }
+
+    /// Returns a single [PdfXfaPacket] from this [PdfXfaPackets] collection.
+    pub fn get(&self, index: PdfXfaPacketIndex) -> Result<PdfXfaPacket, PdfiumError> {
+        if index >= self.len() {
+            return Err(PdfiumError::XfaPacketIndexOutOfBounds);
+        }
+
+        let index = index as c_int;
+
+        // Retrieving the packet name from Pdfium is a two-step operation. First, we call
+        // FPDF_GetXFAPacketName() with a null buffer; this will retrieve the length of
+        // the name in bytes, including the trailing null terminator.
+
+        // We then reserve a byte buffer of the given length and call FPDF_GetXFAPacketName()
+        // again with a pointer to the buffer; this will write the name to the buffer
+        // as a null-terminated string.
+
+        let name_length = self.bindings().FPDF_GetXFAPacketName(
+            self.document_handle,
+            index,
+            std::ptr::null_mut(),
+            0,
+        );
+
+        if name_length == 0 {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let mut name_buffer = create_byte_buffer(name_length as usize);
+
+        self.bindings().FPDF_GetXFAPacketName(
+            self.document_handle,
+            index,
+            name_buffer.as_mut_ptr() as *mut c_void,
+            name_length,
+        );
+
+        let name = CString::from_vec_with_nul(name_buffer)
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        // Retrieving the packet content follows the same two-step pattern, except that
+        // the length of the content is returned through an output parameter.
+
+        let mut content_length: c_ulong = 0;
+
+        if !self
+            .bindings()
+            .is_true(self.bindings().FPDF_GetXFAPacketContent(
+                self.document_handle,
+                index,
+                std::ptr::null_mut(),
+                0,
+                &mut content_length,
+            ))
+        {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        let mut content = create_byte_buffer(content_length as usize);
+
+        let mut written_length: c_ulong = 0;
+
+        if content_length > 0
+            && !self
+                .bindings()
+                .is_true(self.bindings().FPDF_GetXFAPacketContent(
+                    self.document_handle,
+                    index,
+                    content.as_mut_ptr() as *mut c_void,
+                    content_length,
+                    &mut written_length,
+                ))
+        {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+
+        content.truncate(written_length.min(content_length) as usize);
+
+        Ok(PdfXfaPacket { name, content })
+    }
+
+    /// Returns the first [PdfXfaPacket] in this [PdfXfaPackets] collection with the given
+    /// name, if any.
+    pub fn get_by_name(&self, name: &str) -> Option<PdfXfaPacket> {
+        self.iter().find(|packet| packet.name() == name)
+    }
+
+    /// Returns the `template` packet, describing the layout and behaviour of the XFA form,
+    /// if any.
+    #[inline]
+    pub fn template(&self) -> Option<PdfXfaPacket> {
+        self.get_by_name("template")
+    }
+
+    /// Returns the `datasets` packet, holding the data entered into the XFA form, if any.
+    #[inline]
+    pub fn datasets(&self) -> Option<PdfXfaPacket> {
+        self.get_by_name("datasets")
+    }
+
+    /// Returns the complete XML Data Package (XDP) document describing the XFA form,
+    /// formed by concatenating the content of every packet in this [PdfXfaPackets]
+    /// collection in order. Returns an empty buffer if the document does not contain
+    /// an XFA form.
+    pub fn xdp(&self) -> Vec<u8> {
+        self.iter()
+            .flat_map(|packet| packet.content.into_iter())
+            .collect()
+    }
+
+    /// Returns an iterator over all the packets in this [PdfXfaPackets] collection.
+    #[inline]
+    pub fn iter(&self) -> PdfXfaPacketsIterator<'_> {
+        PdfXfaPacketsIterator::new(self)
+    }
+}
+
+/// An iterator over all the [PdfXfaPacket] objects in a [PdfXfaPackets] collection.
+pub struct PdfXfaPacketsIterator<'a> {
+    packets: &'a PdfXfaPackets<'a>,
+    next_index: PdfXfaPacketIndex,
+}
+
+impl<'a> PdfXfaPacketsIterator<'a> {
+    #[inline]
+    pub(crate) fn new(packets: &'a PdfXfaPackets<'a>) -> Self {
+        PdfXfaPacketsIterator {
+            packets,
+            next_index: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for PdfXfaPacketsIterator<'a> {
+    type Item = PdfXfaPacket;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.packets.get(self.next_index);
+
+        self.next_index += 1;
+
+        next.ok()
+    }
+}
+
+/// Replaces the content of the `datasets` packet of the XFA form in the given PDF file with
+/// the given XML, returning the updated file. A `datasets` packet is added before the
+/// closing `postamble` packet if the form does not have one. The new packet is added in an
+/// incremental update appended to the end of the file.
+///
+/// Returns [PdfiumError::XfaPacketsUnavailable] if the file does not contain an XFA form
+/// stored as separate packets.
+pub(crate) fn replace_xfa_datasets(file: Vec<u8>, datasets: &[u8]) -> Result<Vec<u8>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let root = match update.trailer().get("Root") {
+        Some(PdfSyntaxObject::Reference(number, _))
+            if update.trailer().get("Encrypt").is_none() =>
+        {
+            *number
+        }
+        _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+    };
+
+    let form = update.resolve(
+        update
+            .resolve(&PdfSyntaxObject::Reference(root, 0))
+            .get("AcroForm")
+            .unwrap_or(&PdfSyntaxObject::Null),
+    );
+
+    // An XFA form stored as a single stream, rather than as an array of named packets,
+    // would need its XML to be rewritten, which is not supported.
+
+    if !matches!(
+        update.resolve(form.get("XFA").unwrap_or(&PdfSyntaxObject::Null)),
+        PdfSyntaxObject::Array(_)
+    ) {
+        return Err(PdfiumError::XfaPacketsUnavailable);
+    }
+
+    let stream = update.add_stream(PdfSyntaxObject::Dictionary(Vec::new()), datasets.to_vec());
+
+    update
+        .modify(root, &["AcroForm", "XFA"], &mut |xfa| match xfa {
+            PdfSyntaxObject::Array(packets) => {
+                // The array alternates between packet names and references to the streams
+                // holding each packet.
+
+                let position = packets
+                    .chunks(2)
+                    .position(|pair| pair[0] == PdfSyntaxObject::text("datasets"));
+
+                match position {
+                    Some(position) if position * 2 + 1 < packets.len() => {
+                        packets[position * 2 + 1] = stream.clone();
+                    }
+                    _ => {
+                        let position = packets
+                            .chunks(2)
+                            .position(|pair| pair[0] == PdfSyntaxObject::text("postamble"))
+                            .map_or(packets.len(), |position| position * 2);
+
+                        packets.splice(
+                            position..position,
+                            [PdfSyntaxObject::text("datasets"), stream.clone()],
+                        );
+                    }
+                }
+
+                Some(())
+            }
+            _ => None,
+        })
+        .ok_or(PdfiumError::XfaPacketsUnavailable)?;
+
+    Ok(update.write(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::xfa::*;
+
+    fn test_file(xfa: &str) -> Vec<u8> {
+        let mut file = b"%PDF-1.7\n".to_vec();
+
+        let mut offsets = Vec::new();
+
+        for (index, object) in [
+            "<< /Type /Catalog /Pages 2 0 R /AcroForm << /Fields [] /XFA 3 0 R >> >>",
+            "<< /Type /Pages /Kids [] /Count 0 >>",
+            xfa,
+        ]
+        .iter()
+        .enumerate()
+        {
+            offsets.push(file.len());
+            file.extend_from_slice(format!("{} 0 obj\n{}\nendobj\n", index + 1, object).as_bytes());
+        }
+
+        let start_xref = file.len();
+
+        file.extend_from_slice(b"xref\n0 4\n0000000000 65535 f \n");
+
+        for offset in offsets {
+            file.extend_from_slice(format!("{:010} 00000 n \n", offset).as_bytes());
+        }
+
+        file.extend_from_slice(
+            format!(
+                "trailer\n<< /Size 7 /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+                start_xref
+            )
+            .as_bytes(),
+        );
+
+        file
+    }
+
+    #[test]
+    fn test_replace_xfa_datasets() {
+        let file = replace_xfa_datasets(
+            test_file("[(preamble) 4 0 R (template) 5 0 R (postamble) 6 0 R]"),
+            b"<xfa:datasets/>",
+        )
+        .unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        match &parsed.objects[&3].object {
+            PdfSyntaxObject::Array(packets) => {
+                assert_eq!(packets.len(), 8);
+                assert_eq!(packets[4], PdfSyntaxObject::text("datasets"));
+                assert_eq!(packets[6], PdfSyntaxObject::text("postamble"));
+                assert!(parsed.objects[&7].is_stream);
+                assert_eq!(packets[5], PdfSyntaxObject::Reference(7, 0));
+            }
+            _ => panic!("missing XFA packets"),
+        }
+
+        // An existing datasets packet is replaced.
+
+        let file = replace_xfa_datasets(file, b"<xfa:datasets></xfa:datasets>").unwrap();
+
+        let parsed = PdfSyntaxFile::parse(&file).unwrap();
+
+        match &parsed.objects[&3].object {
+            PdfSyntaxObject::Array(packets) => {
+                assert_eq!(packets.len(), 8);
+                assert_eq!(packets[5], PdfSyntaxObject::Reference(8, 0));
+            }
+            _ => panic!("missing XFA packets"),
+        }
+
+        assert!(matches!(
+            replace_xfa_datasets(test_file("<< /Length 0 >>"), b"<xfa:datasets/>"),
+            Err(PdfiumError::XfaPacketsUnavailable)
+        ));
+    }
+}
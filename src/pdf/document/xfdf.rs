@@ -0,0 +1,579 @@
+//! Imports and exports the markup annotations in a `PdfDocument` as XFDF, the XML-based
+//! annotation exchange format defined in ISO 19444-1 and used by web-based PDF viewers
+//! such as PDF.js, via the `PdfDocument::export_annotations_xfdf()` and
+//! `PdfDocument::import_annotations_xfdf()` functions.
+
+use crate::bindgen::{
+    FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color,
+    FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor, FPDFANNOT_COLORTYPE, FS_POINTF,
+};
+use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::color::PdfColor;
+use crate::pdf::document::page::annotation::appearance::{
+    annotation_color, border_width, format_number, generate_normal_appearance, ink_strokes,
+};
+use crate::pdf::document::page::annotation::private::internal::PdfPageAnnotationPrivate;
+use crate::pdf::document::page::annotation::{
+    PdfPageAnnotation, PdfPageAnnotationCommon, PdfPageAnnotationType,
+};
+use crate::pdf::document::page::annotations::PdfPageAnnotations;
+use crate::pdf::document::page::object::{PdfPageObject, PdfPageObjectCommon};
+use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
+use crate::pdf::document::pages::{PdfPageIndex, PdfPages};
+use crate::pdf::document::xml::{escape_xml, parse_xml, XmlElement};
+use crate::pdf::quad_points::PdfQuadPoints;
+use crate::pdf::rect::PdfRect;
+use std::os::raw::c_float;
+
+/// The XML namespace of an XFDF document.
+const XFDF_NAMESPACE: &str = "http://ns.adobe.com/xfdf/";
+
+/// Returns the XFDF element name used for annotations of the given type, or `None` if
+/// annotations of that type are not imported or exported.
+fn element_name(annotation_type: PdfPageAnnotationType) -> Option<&'static str> {
+    match annotation_type {
+        PdfPageAnnotationType::Text => Some("text"),
+        PdfPageAnnotationType::FreeText => Some("freetext"),
+        PdfPageAnnotationType::Highlight => Some("highlight"),
+        PdfPageAnnotationType::Underline => Some("underline"),
+        PdfPageAnnotationType::Strikeout => Some("strikeout"),
+        PdfPageAnnotationType::Squiggly => Some("squiggly"),
+        PdfPageAnnotationType::Square => Some("square"),
+        PdfPageAnnotationType::Circle => Some("circle"),
+        PdfPageAnnotationType::Ink => Some("ink"),
+        _ => None,
+    }
+}
+
+/// Returns the annotation type described by the XFDF element with the given name, or `None`
+/// if annotations of that type are not imported.
+fn annotation_type_from_element_name(name: &str) -> Option<PdfPageAnnotationType> {
+    [
+        PdfPageAnnotationType::Text,
+        PdfPageAnnotationType::FreeText,
+        PdfPageAnnotationType::Highlight,
+        PdfPageAnnotationType::Underline,
+        PdfPageAnnotationType::Strikeout,
+        PdfPageAnnotationType::Squiggly,
+        PdfPageAnnotationType::Square,
+        PdfPageAnnotationType::Circle,
+        PdfPageAnnotationType::Ink,
+    ]
+    .iter()
+    .copied()
+    .find(|annotation_type| element_name(*annotation_type) == Some(name))
+}
+
+/// Returns the markup annotations on every page in the given collection as an XFDF document.
+pub(crate) fn export_annotations(pages: &PdfPages) -> String {
+    let mut xfdf = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<xfdf xmlns=\"{}\" xml:space=\"preserve\">\n<annots>\n",
+        XFDF_NAMESPACE
+    );
+
+    for (page_index, page) in pages.iter().enumerate() {
+        for annotation in page.annotations().iter() {
+            if let Some(element) = export_annotation(page_index as PdfPageIndex, &annotation) {
+                xfdf.push_str(&element);
+            }
+        }
+    }
+
+    xfdf.push_str("</annots>\n</xfdf>\n");
+
+    xfdf
+}
+
+/// Returns the given annotation as an XFDF element, or `None` if annotations of its type
+/// are not exported.
+fn export_annotation(page_index: PdfPageIndex, annotation: &PdfPageAnnotation) -> Option<String> {
+    let annotation_type = annotation.annotation_type();
+
+    let name = element_name(annotation_type)?;
+
+    let bounds = annotation.bounds().ok()?;
+
+    let mut attributes = vec![
+        ("page", page_index.to_string()),
+        (
+            "rect",
+            format_numbers(&[
+                bounds.left.value,
+                bounds.bottom.value,
+                bounds.right.value,
+                bounds.top.value,
+            ]),
+        ),
+    ];
+
+    if let Some(color) = exported_color(
+        annotation,
+        "C",
+        FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_Color,
+    ) {
+        attributes.push(("color", color.to_hex_specification(false)));
+    }
+
+    if let Some(color) = exported_color(
+        annotation,
+        "IC",
+        FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor,
+    ) {
+        attributes.push(("interior-color", color.to_hex_specification(false)));
+    }
+
+    let mut opacity: c_float = 1.0;
+
+    if annotation
+        .bindings()
+        .is_true(annotation.bindings().FPDFAnnot_GetNumberValue(
+            annotation.handle(),
+            "CA",
+            &mut opacity,
+        ))
+    {
+        attributes.push(("opacity", format_number(opacity)));
+    }
+
+    if let Some(width) = border_width(annotation) {
+        attributes.push(("width", format_number(width.value)));
+    }
+
+    if annotation.has_attachment_points() && !annotation.attachment_points().is_empty() {
+        attributes.push((
+            "coords",
+            format_numbers(
+                &annotation
+                    .attachment_points()
+                    .iter()
+                    .flat_map(|quad| {
+                        [
+                            quad.x1.value,
+                            quad.y1.value,
+                            quad.x2.value,
+                            quad.y2.value,
+                            quad.x3.value,
+                            quad.y3.value,
+                            quad.x4.value,
+                            quad.y4.value,
+                        ]
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+        ));
+    }
+
+    for (attribute, value) in [
+        ("name", annotation.name()),
+        ("title", annotation.creator()),
+        ("subject", annotation.get_string_value("Subj")),
+        ("creationdate", annotation.creation_date()),
+        ("date", annotation.modification_date()),
+    ] {
+        if let Some(value) = value.filter(|value| !value.is_empty()) {
+            attributes.push((attribute, value));
+        }
+    }
+
+    let mut children = String::new();
+
+    if let Some(contents) = annotation.contents().filter(|value| !value.is_empty()) {
+        children.push_str(&format!("<contents>{}</contents>\n", escape_xml(&contents)));
+    }
+
+    if let Some(appearance) = annotation.get_string_value("DA") {
+        children.push_str(&format!(
+            "<defaultappearance>{}</defaultappearance>\n",
+            escape_xml(&appearance)
+        ));
+    }
+
+    if annotation_type == PdfPageAnnotationType::Ink {
+        children.push_str("<inklist>\n");
+
+        for stroke in ink_strokes(annotation) {
+            children.push_str(&format!(
+                "<gesture>{}</gesture>\n",
+                stroke
+                    .iter()
+                    .map(|(x, y)| format!("{},{}", format_number(x.value), format_number(y.value)))
+                    .collect::<Vec<_>>()
+                    .join(";")
+            ));
+        }
+
+        children.push_str("</inklist>\n");
+    }
+
+    let attributes = attributes
+        .iter()
+        .map(|(attribute, value)| format!(" {}=\"{}\"", attribute, escape_xml(value)))
+        .collect::<String>();
+
+    Some(if children.is_empty() {
+        format!("<{}{}/>\n", name, attributes)
+    } else {
+        format!("<{}{}>\n{}</{}>\n", name, attributes, children, name)
+    })
+}
+
+/// Returns the color of the given type set in the given annotation's dictionary under the
+/// given key, or `None` if no color is set.
+pub(crate) fn exported_color(
+    annotation: &PdfPageAnnotation,
+    key: &str,
+    color_type: FPDFANNOT_COLORTYPE,
+) -> Option<PdfColor> {
+    if !annotation.bindings().is_true(
+        annotation
+            .bindings()
+            .FPDFAnnot_HasKey(annotation.handle(), key),
+    ) {
+        return None;
+    }
+
+    if let Some(color) = annotation_color(annotation, color_type) {
+        return Some(color);
+    }
+
+    // Pdfium does not report the colors of an annotation that has an appearance stream,
+    // so take the color from the paths drawn by the appearance stream instead.
+
+    let is_filled = color_type == FPDFANNOT_COLORTYPE_FPDFANNOT_COLORTYPE_InteriorColor
+        || annotation.annotation_type() == PdfPageAnnotationType::Highlight;
+
+    annotation
+        .objects()
+        .iter()
+        .find_map(|object| match &object {
+            PdfPageObject::Path(path) => {
+                if is_filled {
+                    path.fill_color().ok()
+                } else {
+                    path.stroke_color().ok()
+                }
+            }
+            _ => None,
+        })
+}
+
+/// Imports the markup annotations in the given XFDF document into the pages in the given
+/// collection, returning the number of annotations imported.
+pub(crate) fn import_annotations(pages: &PdfPages, xfdf: &str) -> Result<usize, PdfiumError> {
+    let root = parse_xml(xfdf).ok_or(PdfiumError::InvalidXfdfData)?;
+
+    if root.local_name() != "xfdf" {
+        return Err(PdfiumError::InvalidXfdfData);
+    }
+
+    let annots = match root.child("annots") {
+        Some(annots) => annots,
+        None => return Ok(0),
+    };
+
+    let mut count = 0;
+
+    for element in annots.children.iter() {
+        let annotation_type = match annotation_type_from_element_name(element.local_name()) {
+            Some(annotation_type) => annotation_type,
+            None => continue, // Annotations of other types are skipped.
+        };
+
+        let page_index = element
+            .attribute("page")
+            .and_then(|page| page.trim().parse::<PdfPageIndex>().ok())
+            .ok_or(PdfiumError::InvalidXfdfData)?;
+
+        let mut page = pages.get(page_index)?;
+
+        import_annotation(page.annotations_mut(), annotation_type, element)?;
+
+        count += 1;
+    }
+
+    Ok(count)
+}
+
+/// Creates a new annotation of the given type in the given collection, configured by
+/// the given XFDF element.
+fn import_annotation(
+    annotations: &mut PdfPageAnnotations,
+    annotation_type: PdfPageAnnotationType,
+    element: &XmlElement,
+) -> Result<(), PdfiumError> {
+    let contents = element
+        .child("contents")
+        .map(|contents| contents.text.as_str())
+        .unwrap_or_default();
+
+    match annotation_type {
+        PdfPageAnnotationType::Text => {
+            configure_annotation(&mut annotations.create_text_annotation(contents)?, element)
+        }
+        PdfPageAnnotationType::FreeText => configure_annotation(
+            &mut annotations.create_free_text_annotation(contents)?,
+            element,
+        ),
+        PdfPageAnnotationType::Highlight => {
+            configure_annotation(&mut annotations.create_highlight_annotation()?, element)
+        }
+        PdfPageAnnotationType::Underline => {
+            configure_annotation(&mut annotations.create_underline_annotation()?, element)
+        }
+        PdfPageAnnotationType::Strikeout => {
+            configure_annotation(&mut annotations.create_strikeout_annotation()?, element)
+        }
+        PdfPageAnnotationType::Squiggly => {
+            configure_annotation(&mut annotations.create_squiggly_annotation()?, element)
+        }
+        PdfPageAnnotationType::Square => {
+            configure_annotation(&mut annotations.create_square_annotation()?, element)
+        }
+        PdfPageAnnotationType::Circle => {
+            configure_annotation(&mut annotations.create_circle_annotation()?, element)
+        }
+        PdfPageAnnotationType::Ink => {
+            configure_annotation(&mut annotations.create_ink_annotation()?, element)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Applies the properties in the given XFDF element to the given newly created annotation,
+/// then generates an appearance stream for it.
+fn configure_annotation<'a>(
+    annotation: &mut impl PdfPageAnnotationPrivate<'a>,
+    element: &XmlElement,
+) -> Result<(), PdfiumError> {
+    let rect = element
+        .attribute("rect")
+        .and_then(parse_numbers)
+        .filter(|rect| rect.len() == 4)
+        .ok_or(PdfiumError::InvalidXfdfData)?;
+
+    annotation.set_bounds(PdfRect::new_from_values(
+        rect[1].min(rect[3]),
+        rect[0].min(rect[2]),
+        rect[1].max(rect[3]),
+        rect[0].max(rect[2]),
+    ))?;
+
+    let opacity = element
+        .attribute("opacity")
+        .and_then(|opacity| opacity.trim().parse::<f32>().ok())
+        .map(|opacity| (opacity.clamp(0.0, 1.0) * 255.0).round() as u8);
+
+    if let Some(color) = element.attribute("color").and_then(parse_color) {
+        annotation.set_stroke_color(color.with_alpha(opacity.unwrap_or(255)))?;
+    }
+
+    if let Some(color) = element.attribute("interior-color").and_then(parse_color) {
+        annotation.set_fill_color(color.with_alpha(opacity.unwrap_or(255)))?;
+    }
+
+    if let Some(width) = element
+        .attribute("width")
+        .and_then(|width| width.trim().parse::<f32>().ok())
+    {
+        if !annotation
+            .bindings()
+            .is_true(annotation.bindings().FPDFAnnot_SetBorder(
+                annotation.handle(),
+                0.0,
+                0.0,
+                width,
+            ))
+        {
+            return Err(PdfiumError::PdfiumLibraryInternalError(
+                PdfiumInternalError::Unknown,
+            ));
+        }
+    }
+
+    if annotation.has_attachment_points() {
+        if let Some(coords) = element.attribute("coords").and_then(parse_numbers) {
+            for quad in coords.chunks_exact(8) {
+                annotation
+                    .attachment_points_mut_impl()
+                    .create_attachment_point_at_end(PdfQuadPoints::new_from_values(
+                        quad[0], quad[1], quad[2], quad[3], quad[4], quad[5], quad[6], quad[7],
+                    ))?;
+            }
+        }
+    }
+
+    if let Some(ink_list) = element.child("inklist") {
+        for gesture in ink_list
+            .children
+            .iter()
+            .filter(|child| child.local_name() == "gesture")
+        {
+            let points = parse_numbers(&gesture.text)
+                .ok_or(PdfiumError::InvalidXfdfData)?
+                .chunks_exact(2)
+                .map(|point| FS_POINTF {
+                    x: point[0],
+                    y: point[1],
+                })
+                .collect::<Vec<_>>();
+
+            if !points.is_empty()
+                && annotation.bindings().FPDFAnnot_AddInkStroke(
+                    annotation.handle(),
+                    points.as_ptr(),
+                    points.len(),
+                ) < 0
+            {
+                return Err(PdfiumError::PdfiumLibraryInternalError(
+                    PdfiumInternalError::Unknown,
+                ));
+            }
+        }
+    }
+
+    if let Some(contents) = element.child("contents") {
+        annotation.set_contents(&contents.text)?;
+    }
+
+    if let Some(appearance) = element.child("defaultappearance") {
+        annotation.set_string_value("DA", &appearance.text)?;
+    }
+
+    for (attribute, key) in [
+        ("name", "NM"),
+        ("title", "T"),
+        ("subject", "Subj"),
+        ("creationdate", "CreationDate"),
+    ] {
+        if let Some(value) = element.attribute(attribute) {
+            annotation.set_string_value(key, value)?;
+        }
+    }
+
+    generate_normal_appearance(annotation)?;
+
+    // The modification date is set last, since changing any other property of an annotation
+    // resets its modification date to the current time.
+
+    if let Some(date) = element.attribute("date") {
+        annotation.set_string_value("M", date)?;
+    }
+
+    Ok(())
+}
+
+/// Formats the given values as a comma-separated list of numbers.
+fn format_numbers(values: &[f32]) -> String {
+    values
+        .iter()
+        .map(|value| format_number(*value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Parses a list of numbers separated by commas, semicolons, or whitespace.
+fn parse_numbers(value: &str) -> Option<Vec<f32>> {
+    value
+        .split(|c: char| c == ',' || c == ';' || c.is_whitespace())
+        .filter(|number| !number.is_empty())
+        .map(|number| number.parse::<f32>().ok())
+        .collect()
+}
+
+/// Parses an XFDF color of the form `#RRGGBB`.
+fn parse_color(value: &str) -> Option<PdfColor> {
+    let hex = value.trim().strip_prefix('#')?;
+
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+
+    Some(PdfColor::new(
+        component(0)?,
+        component(2)?,
+        component(4)?,
+        255,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::xfdf::*;
+    use crate::prelude::*;
+    use crate::utils::test::test_bind_to_pdfium;
+
+    #[test]
+    fn test_colors_and_numbers() {
+        assert_eq!(
+            parse_color("#ff8000"),
+            Some(PdfColor::new(255, 128, 0, 255))
+        );
+        assert_eq!(parse_color("ff8000"), None);
+        assert_eq!(format_numbers(&[1.0, 2.5, -3.25]), "1,2.5,-3.25");
+        assert_eq!(parse_numbers("1,2;3 4"), Some(vec![1.0, 2.0, 3.0, 4.0]));
+        assert_eq!(parse_numbers("1,x"), None);
+    }
+
+    #[test]
+    fn test_xfdf_round_trip() -> Result<(), PdfiumError> {
+        let pdfium = test_bind_to_pdfium();
+
+        let mut source = pdfium.create_new_pdf()?;
+
+        let mut page = source
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        PdfHighlightAnnotationBuilder::new()
+            .add_rect(PdfRect::new_from_values(100.0, 100.0, 114.0, 300.0))
+            .color(PdfColor::GREEN)
+            .contents("Check <this> & that")
+            .author("Reviewer")
+            .create(page.annotations_mut())?;
+
+        PdfInkAnnotationBuilder::new()
+            .add_stroke(&[
+                (PdfPoints::new(10.0), PdfPoints::new(10.0)),
+                (PdfPoints::new(20.0), PdfPoints::new(30.0)),
+            ])
+            .create(page.annotations_mut())?;
+
+        let xfdf = source.export_annotations_xfdf();
+
+        assert!(xfdf.contains("<highlight page=\"0\""));
+        assert!(xfdf.contains("title=\"Reviewer\""));
+        assert!(xfdf.contains("<gesture>10,10;20,30</gesture>"));
+
+        let mut target = pdfium.create_new_pdf()?;
+
+        target
+            .pages_mut()
+            .create_page_at_end(PdfPagePaperSize::a4())?;
+
+        assert_eq!(target.import_annotations_xfdf(&xfdf)?, 2);
+
+        let page = target.pages().first()?;
+
+        let highlight = page.annotations().first()?;
+
+        assert_eq!(
+            highlight.annotation_type(),
+            PdfPageAnnotationType::Highlight
+        );
+        assert_eq!(
+            highlight.contents(),
+            Some("Check <this> & that".to_string())
+        );
+        assert_eq!(highlight.creator(), Some("Reviewer".to_string()));
+        assert_eq!(highlight.attachment_points().len(), 1);
+
+        assert!(matches!(
+            target.import_annotations_xfdf("<xfdf><annots>"),
+            Err(PdfiumError::InvalidXfdfData)
+        ));
+
+        Ok(())
+    }
+}
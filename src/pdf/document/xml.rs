@@ -0,0 +1,324 @@
+//! Reads and writes the simple XML documents embedded in or exchanged with PDF files,
+//! such as XFDF annotation data and XMP metadata packets.
+
+/// The maximum depth of nested elements accepted when parsing an XML document. The documents
+/// read by this module are never deeply nested; the limit guards against exhausting the stack
+/// when parsing malicious input.
+const MAXIMUM_ELEMENT_DEPTH: usize = 64;
+
+/// Escapes the given text for use in XML element content or a quoted attribute value.
+pub(crate) fn escape_xml(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            '\r' => result.push_str("&#13;"),
+            '\n' => result.push_str("&#10;"),
+            '\t' => result.push_str("&#9;"),
+            c => result.push(c),
+        }
+    }
+
+    result
+}
+
+/// A single element in a parsed XML document.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct XmlElement {
+    pub(crate) name: String,
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) children: Vec<XmlElement>,
+    pub(crate) text: String,
+}
+
+impl XmlElement {
+    /// Returns the name of this element without any namespace prefix.
+    pub(crate) fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    /// Returns the value of the attribute with the given name, if any.
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| local_name(attribute) == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the first child element with the given name, if any.
+    pub(crate) fn child(&self, name: &str) -> Option<&XmlElement> {
+        self.children
+            .iter()
+            .find(|child| child.local_name() == name)
+    }
+}
+
+/// Returns the given XML name without any namespace prefix.
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+/// Parses the given XML document, returning its root element, or `None` if the document
+/// is not well-formed. Document type declarations are skipped rather than processed.
+pub(crate) fn parse_xml(input: &str) -> Option<XmlElement> {
+    let mut parser = XmlParser { input, position: 0 };
+
+    parser.skip_prolog()?;
+
+    let root = parser.parse_element(0)?;
+
+    parser.skip_prolog()?;
+
+    if parser.position == input.len() {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+/// A minimal non-validating XML parser, sufficient for reading XFDF documents and
+/// XMP metadata packets.
+struct XmlParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl<'a> XmlParser<'a> {
+    #[inline]
+    fn rest(&self) -> &'a str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Advances past the next occurrence of the given terminator.
+    fn skip_past(&mut self, terminator: &str) -> Option<()> {
+        let offset = self.rest().find(terminator)?;
+
+        self.position += offset + terminator.len();
+
+        Some(())
+    }
+
+    /// Skips any whitespace, processing instructions, comments, and document type declarations.
+    fn skip_prolog(&mut self) -> Option<()> {
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<!") {
+                self.skip_past(">")?;
+            } else {
+                return Some(());
+            }
+        }
+    }
+
+    fn parse_name(&mut self) -> Option<String> {
+        let rest = self.rest();
+
+        let length = rest
+            .find(|c: char| c.is_whitespace() || c == '/' || c == '>' || c == '=')
+            .unwrap_or(rest.len());
+
+        if length == 0 {
+            return None;
+        }
+
+        self.position += length;
+
+        Some(rest[..length].to_string())
+    }
+
+    fn parse_element(&mut self, depth: usize) -> Option<XmlElement> {
+        if depth > MAXIMUM_ELEMENT_DEPTH || !self.rest().starts_with('<') {
+            return None;
+        }
+
+        self.position += 1;
+
+        let mut element = XmlElement {
+            name: self.parse_name()?,
+            ..XmlElement::default()
+        };
+
+        // Attributes.
+
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+
+                return Some(element);
+            }
+
+            if self.rest().starts_with('>') {
+                self.position += 1;
+
+                break;
+            }
+
+            let name = self.parse_name()?;
+
+            self.skip_whitespace();
+
+            if !self.rest().starts_with('=') {
+                return None;
+            }
+
+            self.position += 1;
+
+            self.skip_whitespace();
+
+            let quote = self
+                .rest()
+                .chars()
+                .next()
+                .filter(|c| *c == '"' || *c == '\'')?;
+
+            self.position += 1;
+
+            let length = self.rest().find(quote)?;
+
+            let value = decode_entities(&self.rest()[..length])?;
+
+            self.position += length + 1;
+
+            element.attributes.push((name, value));
+        }
+
+        // Content.
+
+        loop {
+            let rest = self.rest();
+
+            if rest.starts_with("</") {
+                self.position += 2;
+
+                if self.parse_name()? != element.name {
+                    return None;
+                }
+
+                self.skip_whitespace();
+
+                if !self.rest().starts_with('>') {
+                    return None;
+                }
+
+                self.position += 1;
+
+                return Some(element);
+            } else if rest.starts_with("<![CDATA[") {
+                self.position += "<![CDATA[".len();
+
+                let length = self.rest().find("]]>")?;
+
+                element.text.push_str(&self.rest()[..length]);
+
+                self.position += length + "]]>".len();
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with('<') {
+                element.children.push(self.parse_element(depth + 1)?);
+            } else if rest.is_empty() {
+                return None;
+            } else {
+                let length = rest.find('<').unwrap_or(rest.len());
+
+                element.text.push_str(&decode_entities(&rest[..length])?);
+
+                self.position += length;
+            }
+        }
+    }
+}
+
+/// Replaces the predefined XML entities and numeric character references in the given text
+/// with the characters they represent, or returns `None` if the text contains a malformed
+/// or unknown entity.
+fn decode_entities(text: &str) -> Option<String> {
+    let mut result = String::with_capacity(text.len());
+
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+
+        let end = rest[start..].find(';')? + start;
+
+        let entity = &rest[start + 1..end];
+
+        result.push(match entity {
+            "lt" => '<',
+            "gt" => '>',
+            "amp" => '&',
+            "quot" => '"',
+            "apos" => '\'',
+            _ => {
+                let code = if let Some(hex) = entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                {
+                    u32::from_str_radix(hex, 16).ok()?
+                } else {
+                    entity.strip_prefix('#')?.parse::<u32>().ok()?
+                };
+
+                char::from_u32(code)?
+            }
+        });
+
+        rest = &rest[end + 1..];
+    }
+
+    result.push_str(rest);
+
+    Some(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::xml::*;
+
+    #[test]
+    fn test_parse_xml() {
+        let root = parse_xml(
+            "<?xml version=\"1.0\"?>\n<!-- comment -->\n<xfdf xmlns=\"http://ns.adobe.com/xfdf/\">\
+            <annots><highlight page='0' title=\"A &amp; B\"><contents>x &lt; y&#10;z</contents>\
+            </highlight><square page=\"1\"/></annots></xfdf>\n",
+        )
+        .unwrap();
+
+        let annots = root.child("annots").unwrap();
+
+        assert_eq!(annots.children.len(), 2);
+        assert_eq!(annots.children[0].attribute("title"), Some("A & B"));
+        assert_eq!(
+            annots.children[0].child("contents").unwrap().text,
+            "x < y\nz"
+        );
+        assert_eq!(annots.children[1].attribute("page"), Some("1"));
+
+        assert_eq!(parse_xml("<xfdf><annots></xfdf>"), None);
+        assert_eq!(parse_xml("<xfdf>&bogus;</xfdf>"), None);
+        assert_eq!(
+            decode_entities(&escape_xml("<\"a\" & 'b'>\n")).unwrap(),
+            "<\"a\" & 'b'>\n"
+        );
+    }
+}
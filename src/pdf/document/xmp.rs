@@ -0,0 +1,456 @@
+//! Defines the [PdfXmpMetadata] struct, a typed view of the common Dublin Core and PDF/A
+//! identification properties in the XMP metadata packet of a `PdfDocument`.
+
+use crate::error::PdfiumError;
+use crate::pdf::document::syntax::{PdfIncrementalUpdate, PdfSyntaxFile, PdfSyntaxObject};
+use crate::pdf::document::xml::{escape_xml, parse_xml, XmlElement};
+
+#[cfg(doc)]
+use crate::pdf::document::PdfDocument;
+
+/// The common Dublin Core and PDF/A identification properties in an XMP metadata packet,
+/// as returned by [PdfDocument::xmp_metadata()].
+///
+/// Properties are read from the `dc:` and `pdfaid:` namespace prefixes conventionally used
+/// by PDF producers, whether they are written as elements or as attributes of an
+/// `rdf:Description` element. Only the properties exposed by this struct are retained;
+/// a packet written using [PdfXmpMetadata::to_xml()] contains only those properties.
+/// Applications that need to preserve other properties should edit the raw packet returned
+/// by [PdfDocument::xmp()] instead.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PdfXmpMetadata {
+    title: Option<String>,
+    creators: Vec<String>,
+    description: Option<String>,
+    subjects: Vec<String>,
+    pdfa_part: Option<u32>,
+    pdfa_conformance: Option<String>,
+}
+
+impl PdfXmpMetadata {
+    /// Creates a new, empty [PdfXmpMetadata].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reads the common Dublin Core and PDF/A identification properties from the given
+    /// XMP metadata packet. Properties that are missing or cannot be read are left empty,
+    /// as are all properties if the packet is not well-formed XML.
+    pub fn from_xml(xml: &str) -> Self {
+        let root = match parse_xml(xml) {
+            Some(root) => root,
+            None => return Self::default(),
+        };
+
+        let text = |name: &str| {
+            read_property(&root, name).and_then(|values| {
+                values
+                    .iter()
+                    .find(|(is_default, _)| *is_default)
+                    .or_else(|| values.first())
+                    .map(|(_, value)| value.clone())
+            })
+        };
+
+        let list = |name: &str| {
+            read_property(&root, name)
+                .map(|values| values.into_iter().map(|(_, value)| value).collect())
+                .unwrap_or_default()
+        };
+
+        PdfXmpMetadata {
+            title: text("dc:title"),
+            creators: list("dc:creator"),
+            description: text("dc:description"),
+            subjects: list("dc:subject"),
+            pdfa_part: text("pdfaid:part").and_then(|part| part.trim().parse().ok()),
+            pdfa_conformance: text("pdfaid:conformance").map(|value| value.trim().to_owned()),
+        }
+    }
+
+    /// Returns the `dc:title` property, the title of the document.
+    #[inline]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    /// Sets the `dc:title` property, the title of the document.
+    #[inline]
+    pub fn set_title(&mut self, title: Option<&str>) {
+        self.title = title.map(|title| title.to_owned());
+    }
+
+    /// Returns the `dc:creator` property, the authors of the document.
+    #[inline]
+    pub fn creators(&self) -> &[String] {
+        self.creators.as_slice()
+    }
+
+    /// Sets the `dc:creator` property, the authors of the document.
+    #[inline]
+    pub fn set_creators(&mut self, creators: &[&str]) {
+        self.creators = creators.iter().map(|creator| creator.to_string()).collect();
+    }
+
+    /// Returns the `dc:description` property, a textual description of the document.
+    #[inline]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    /// Sets the `dc:description` property, a textual description of the document.
+    #[inline]
+    pub fn set_description(&mut self, description: Option<&str>) {
+        self.description = description.map(|description| description.to_owned());
+    }
+
+    /// Returns the `dc:subject` property, the keywords describing the document.
+    #[inline]
+    pub fn subjects(&self) -> &[String] {
+        self.subjects.as_slice()
+    }
+
+    /// Sets the `dc:subject` property, the keywords describing the document.
+    #[inline]
+    pub fn set_subjects(&mut self, subjects: &[&str]) {
+        self.subjects = subjects.iter().map(|subject| subject.to_string()).collect();
+    }
+
+    /// Returns the `pdfaid:part` property, the part of the PDF/A standard to which the
+    /// document claims to conform; for example, `2` for PDF/A-2.
+    #[inline]
+    pub fn pdfa_part(&self) -> Option<u32> {
+        self.pdfa_part
+    }
+
+    /// Returns the `pdfaid:conformance` property, the PDF/A conformance level claimed by
+    /// the document; for example, `B` for PDF/A-2b.
+    #[inline]
+    pub fn pdfa_conformance(&self) -> Option<&str> {
+        self.pdfa_conformance.as_deref()
+    }
+
+    /// Sets the `pdfaid:part` and `pdfaid:conformance` properties, identifying the document
+    /// as conforming to the given part and conformance level of the PDF/A standard; for
+    /// example, part `2` and conformance level `B` for PDF/A-2b.
+    ///
+    /// Setting these properties only claims conformance; it does not make the document
+    /// conform to the standard.
+    #[inline]
+    pub fn set_pdfa_identification(&mut self, part: u32, conformance: &str) {
+        self.pdfa_part = Some(part);
+        self.pdfa_conformance = Some(conformance.to_owned());
+    }
+
+    /// Removes the `pdfaid:part` and `pdfaid:conformance` properties.
+    #[inline]
+    pub fn clear_pdfa_identification(&mut self) {
+        self.pdfa_part = None;
+        self.pdfa_conformance = None;
+    }
+
+    /// Returns a complete XMP metadata packet containing the properties in this
+    /// [PdfXmpMetadata].
+    pub fn to_xml(&self) -> String {
+        let mut properties = String::new();
+
+        let mut push_list = |name: &str, container: &str, items: Vec<&String>, lang: bool| {
+            if items.is_empty() {
+                return;
+            }
+
+            properties.push_str(&format!("   <{}>\n    <rdf:{}>\n", name, container));
+
+            for item in items {
+                properties.push_str(&format!(
+                    "     <rdf:li{}>{}</rdf:li>\n",
+                    if lang { " xml:lang=\"x-default\"" } else { "" },
+                    escape_xml(item)
+                ));
+            }
+
+            properties.push_str(&format!("    </rdf:{}>\n   </{}>\n", container, name));
+        };
+
+        push_list("dc:title", "Alt", self.title.iter().collect(), true);
+        push_list("dc:creator", "Seq", self.creators.iter().collect(), false);
+        push_list(
+            "dc:description",
+            "Alt",
+            self.description.iter().collect(),
+            true,
+        );
+        push_list("dc:subject", "Bag", self.subjects.iter().collect(), false);
+
+        if let Some(part) = self.pdfa_part {
+            properties.push_str(&format!("   <pdfaid:part>{}</pdfaid:part>\n", part));
+        }
+
+        if let Some(conformance) = self.pdfa_conformance.as_ref() {
+            properties.push_str(&format!(
+                "   <pdfaid:conformance>{}</pdfaid:conformance>\n",
+                escape_xml(conformance)
+            ));
+        }
+
+        format!(
+            "<?xpacket begin=\"\u{FEFF}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n\
+            <x:xmpmeta xmlns:x=\"adobe:ns:meta/\">\n \
+            <rdf:RDF xmlns:rdf=\"http://www.w3.org/1999/02/22-rdf-syntax-ns#\">\n  \
+            <rdf:Description rdf:about=\"\" \
+            xmlns:dc=\"http://purl.org/dc/elements/1.1/\" \
+            xmlns:pdfaid=\"http://www.aiim.org/pdfa/ns/id/\">\n\
+            {}  \
+            </rdf:Description>\n \
+            </rdf:RDF>\n\
+            </x:xmpmeta>\n\
+            <?xpacket end=\"w\"?>",
+            properties
+        )
+    }
+}
+
+/// Reads the values of the property with the given qualified name from the given parsed
+/// XMP packet. Each value is paired with `true` if it is the default language alternative
+/// of a language alternative array. Returns `None` if the property is not present.
+fn read_property(element: &XmlElement, name: &str) -> Option<Vec<(bool, String)>> {
+    // Simple properties may be written as attributes of an rdf:Description element.
+
+    if let Some((_, value)) = element
+        .attributes
+        .iter()
+        .find(|(attribute, _)| attribute == name)
+    {
+        return Some(vec![(true, value.clone())]);
+    }
+
+    for child in element.children.iter() {
+        if child.name == name {
+            return Some(property_values(child));
+        }
+
+        if let Some(values) = read_property(child, name) {
+            return Some(values);
+        }
+    }
+
+    None
+}
+
+/// Returns the values of the given property element, whose content is either text or
+/// an `rdf:Alt`, `rdf:Seq`, or `rdf:Bag` array of `rdf:li` items.
+fn property_values(property: &XmlElement) -> Vec<(bool, String)> {
+    let array = property
+        .children
+        .iter()
+        .find(|child| matches!(child.name.as_str(), "rdf:Alt" | "rdf:Seq" | "rdf:Bag"));
+
+    match array {
+        Some(array) => array
+            .children
+            .iter()
+            .filter(|item| item.name == "rdf:li")
+            .map(|item| {
+                let is_default = item
+                    .attributes
+                    .iter()
+                    .any(|(name, value)| name == "xml:lang" && value == "x-default");
+
+                (is_default, item.text.trim().to_owned())
+            })
+            .collect(),
+        None => vec![(true, property.text.trim().to_owned())],
+    }
+}
+
+/// Returns the XMP metadata packet referenced by the document catalog of the given PDF file,
+/// or `None` if the document has no XMP metadata.
+///
+/// Returns [PdfiumError::XmpMetadataUnreadable] if the file cannot be parsed, or if the
+/// metadata stream is compressed, encrypted, or not valid UTF-8.
+pub(crate) fn read_xmp(file: &[u8]) -> Result<Option<String>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(file).ok_or(PdfiumError::XmpMetadataUnreadable)?;
+
+    let trailer = parsed
+        .trailer
+        .as_ref()
+        .ok_or(PdfiumError::XmpMetadataUnreadable)?;
+
+    let catalog = parsed.resolve(
+        trailer
+            .get("Root")
+            .ok_or(PdfiumError::XmpMetadataUnreadable)?,
+    );
+
+    let number = match catalog.get("Metadata") {
+        Some(PdfSyntaxObject::Reference(number, _)) => *number,
+        Some(_) => return Err(PdfiumError::XmpMetadataUnreadable),
+        None => return Ok(None),
+    };
+
+    let stream = match parsed.objects.get(&number) {
+        Some(stream) => stream,
+        None => return Ok(None),
+    };
+
+    let has_filter = match stream.object.get("Filter") {
+        None | Some(PdfSyntaxObject::Null) => false,
+        Some(PdfSyntaxObject::Array(filters)) => !filters.is_empty(),
+        Some(_) => true,
+    };
+
+    if has_filter || trailer.get("Encrypt").is_some() {
+        return Err(PdfiumError::XmpMetadataUnreadable);
+    }
+
+    let data = stream
+        .stream_range
+        .clone()
+        .and_then(|range| file.get(range))
+        .ok_or(PdfiumError::XmpMetadataUnreadable)?;
+
+    let data = data.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(data);
+
+    String::from_utf8(data.to_vec())
+        .map(Some)
+        .map_err(|_| PdfiumError::XmpMetadataUnreadable)
+}
+
+/// Replaces the XMP metadata packet referenced by the document catalog of the given PDF file
+/// with the given packet, returning the updated file. The new metadata stream is added in an
+/// incremental update appended to the end of the file.
+pub(crate) fn write_xmp(file: Vec<u8>, xml: &str) -> Result<Vec<u8>, PdfiumError> {
+    let parsed = PdfSyntaxFile::parse(&file).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let mut update =
+        PdfIncrementalUpdate::new(&parsed).ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    let root = match update.trailer().get("Root") {
+        Some(PdfSyntaxObject::Reference(number, _))
+            if update.trailer().get("Encrypt").is_none() =>
+        {
+            *number
+        }
+        _ => return Err(PdfiumError::DocumentUpdateUnsupported),
+    };
+
+    let stream = update.add_stream(
+        PdfSyntaxObject::Dictionary(vec![
+            ("Type".to_owned(), PdfSyntaxObject::name("Metadata")),
+            ("Subtype".to_owned(), PdfSyntaxObject::name("XML")),
+        ]),
+        xml.as_bytes().to_vec(),
+    );
+
+    // The existing metadata stream, if any, is replaced rather than modified, so the catalog
+    // entry is set directly instead of following the reference to the stream.
+
+    let mut catalog = update
+        .get(root)
+        .ok_or(PdfiumError::DocumentUpdateUnsupported)?;
+
+    catalog.set("Metadata", stream);
+
+    update.set(root, catalog);
+
+    Ok(update.write(file))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::document::xmp::*;
+
+    const MINIMAL_PDF: &[u8] = b"%PDF-1.7\n\
+        1 0 obj\n<< /Type /Catalog /Pages 2 0 R >>\nendobj\n\
+        2 0 obj\n<< /Type /Pages /Kids [] /Count 0 >>\nendobj\n\
+        xref\n0 3\n0000000000 65535 f\r\n\
+        trailer\n<< /Size 3 /Root 1 0 R >>\nstartxref\n100\n%%EOF\n";
+
+    #[test]
+    fn test_xmp_metadata_from_xml() {
+        let xml = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+            <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+              <rdf:Description rdf:about="" pdfaid:part="3" pdfaid:conformance="U"/>
+              <rdf:Description rdf:about="">
+                <dc:title><rdf:Alt>
+                  <rdf:li xml:lang="fr">Rapport</rdf:li>
+                  <rdf:li xml:lang="x-default">Report &amp; summary</rdf:li>
+                </rdf:Alt></dc:title>
+                <dc:creator><rdf:Seq><rdf:li>Alice</rdf:li><rdf:li>Bob</rdf:li></rdf:Seq></dc:creator>
+                <dc:subject><rdf:Bag><rdf:li>finance</rdf:li></rdf:Bag></dc:subject>
+              </rdf:Description>
+            </rdf:RDF>
+          </x:xmpmeta>"#;
+
+        let metadata = PdfXmpMetadata::from_xml(xml);
+
+        assert_eq!(metadata.title(), Some("Report & summary"));
+        assert_eq!(metadata.creators(), &["Alice".to_owned(), "Bob".to_owned()]);
+        assert_eq!(metadata.description(), None);
+        assert_eq!(metadata.subjects(), &["finance".to_owned()]);
+        assert_eq!(metadata.pdfa_part(), Some(3));
+        assert_eq!(metadata.pdfa_conformance(), Some("U"));
+
+        // Writing the properties and reading them back gives the same properties.
+
+        let mut metadata = metadata;
+
+        metadata.set_pdfa_identification(2, "B");
+        metadata.set_description(Some("Quarterly <figures>"));
+
+        assert_eq!(PdfXmpMetadata::from_xml(&metadata.to_xml()), metadata);
+    }
+
+    #[test]
+    fn test_xmp_metadata_from_xml_with_attributes_and_cdata() {
+        // Property names that appear in attribute values, in CDATA sections, or as the
+        // prefix of a longer element name are not mistaken for properties.
+
+        let xml = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+          <x:xmpmeta xmlns:x="adobe:ns:meta/">
+            <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+              <rdf:Description rdf:about="" xmp:Label='dc:title="Wrong"'>
+                <dc:titles>Wrong</dc:titles>
+                <dc:description><rdf:Alt>
+                  <rdf:li xml:lang="x-default"><![CDATA[Contains <dc:title> & more]]></rdf:li>
+                </rdf:Alt></dc:description>
+                <dc:title>Right</dc:title>
+              </rdf:Description>
+            </rdf:RDF>
+          </x:xmpmeta>
+          <?xpacket end="w"?>"#;
+
+        let metadata = PdfXmpMetadata::from_xml(xml);
+
+        assert_eq!(metadata.title(), Some("Right"));
+        assert_eq!(metadata.description(), Some("Contains <dc:title> & more"));
+
+        assert_eq!(
+            PdfXmpMetadata::from_xml("<x:xmpmeta><dc:title>Unclosed</x:xmpmeta>"),
+            PdfXmpMetadata::new()
+        );
+    }
+
+    #[test]
+    fn test_read_and_write_xmp() {
+        assert_eq!(read_xmp(MINIMAL_PDF).unwrap(), None);
+
+        let mut metadata = PdfXmpMetadata::new();
+
+        metadata.set_title(Some("Résumé"));
+        metadata.set_pdfa_identification(1, "B");
+
+        let file = write_xmp(MINIMAL_PDF.to_vec(), &metadata.to_xml()).unwrap();
+
+        let xml = read_xmp(&file).unwrap().unwrap();
+
+        assert_eq!(xml, metadata.to_xml());
+
+        // Replacing the packet again references the newer stream.
+
+        let file = write_xmp(file, "<x:xmpmeta/>").unwrap();
+
+        assert_eq!(read_xmp(&file).unwrap(), Some("<x:xmpmeta/>".to_owned()));
+    }
+}
@@ -1,10 +1,12 @@
 //! Defines the [PdfLink] struct, exposing functionality related to a single link contained
 //! within a `PdfPage`, a `PdfPageAnnotation`, or a `PdfBookmark`.
 
-use crate::bindgen::{FPDF_DOCUMENT, FPDF_LINK};
+use crate::bindgen::{FPDF_DOCUMENT, FPDF_LINK, FS_RECTF};
 use crate::bindings::PdfiumLibraryBindings;
+use crate::error::PdfiumError;
 use crate::pdf::action::PdfAction;
 use crate::pdf::destination::PdfDestination;
+use crate::pdf::rect::PdfRect;
 
 pub struct PdfLink<'a> {
     handle: FPDF_LINK,
@@ -53,6 +55,21 @@ impl<'a> PdfLink<'a> {
         }
     }
 
+    /// Returns the bounding box of the link annotation containing this [PdfLink]
+    /// on its containing page.
+    pub fn rect(&self) -> Result<PdfRect, PdfiumError> {
+        let mut rect = FS_RECTF {
+            left: 0.0,
+            top: 0.0,
+            right: 0.0,
+            bottom: 0.0,
+        };
+
+        let result = self.bindings.FPDFLink_GetAnnotRect(self.handle, &mut rect);
+
+        PdfRect::from_pdfium_as_result(result, rect, self.bindings)
+    }
+
     /// Returns the [PdfDestination] associated with this [PdfLink], if any.
     ///
     /// The destination specifies the page and region, if any, that will be the target
@@ -150,6 +150,17 @@ impl PdfRect {
             && self.bottom < other.top
     }
 
+    /// Returns the smallest rectangle containing both this [PdfRect] and the given rectangle.
+    #[inline]
+    pub(crate) fn union(&self, other: &PdfRect) -> PdfRect {
+        PdfRect::new_from_values(
+            self.bottom.value.min(other.bottom.value),
+            self.left.value.min(other.left.value),
+            self.top.value.max(other.top.value),
+            self.right.value.max(other.right.value),
+        )
+    }
+
     /// Returns the result of applying the given [PdfMatrix] to each corner point of this [PdfRect].
     #[inline]
     pub fn transform(&self, matrix: PdfMatrix) -> PdfRect {
@@ -247,6 +258,21 @@ mod tests {
             .does_overlap(&PdfRect::new_from_values(5.0, 8.0, 10.0, 10.0)));
     }
 
+    #[test]
+    fn test_rect_union() {
+        assert_eq!(
+            PdfRect::new_from_values(2.0, 2.0, 7.0, 7.0)
+                .union(&PdfRect::new_from_values(5.0, 4.0, 10.0, 10.0)),
+            PdfRect::new_from_values(2.0, 2.0, 10.0, 10.0)
+        );
+
+        assert_eq!(
+            PdfRect::new_from_values(3.0, 3.0, 9.0, 9.0)
+                .union(&PdfRect::new_from_values(2.0, 2.0, 10.0, 10.0)),
+            PdfRect::new_from_values(2.0, 2.0, 10.0, 10.0)
+        );
+    }
+
     #[test]
     fn test_transform_rect() {
         let delta_x = PdfPoints::new(50.0);
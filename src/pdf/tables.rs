@@ -0,0 +1,849 @@
+//! Defines the [PdfTable] struct, a table of text detected in the content of a `PdfPage`
+//! by the `PdfPage::tables()` function, and the [PdfTableConfig] struct, which controls
+//! how tables are detected.
+//!
+//! Table detection is heuristic. Two kinds of tables are detected:
+//!
+//! * Ruled tables, whose rows and columns are separated by horizontal and vertical lines
+//!   drawn as path objects. The grid formed by the lines defines the table's cells.
+//! * Unruled tables, whose columns are separated only by whitespace. Consecutive lines of
+//!   text that are split into the same columns by wide horizontal gaps form the table's rows.
+//!
+//! Cells spanning several rows or columns, tables without text, and tables drawn as images
+//! are not detected.
+
+use crate::pdf::document::page::object::PdfPageObjectCommon;
+use crate::pdf::document::page::objects::common::PdfPageObjectsCommon;
+use crate::pdf::document::page::PdfPage;
+use crate::pdf::path::segments::PdfPathSegments;
+use crate::pdf::points::PdfPoints;
+use crate::pdf::rect::PdfRect;
+use std::cmp::Ordering;
+
+/// Configures how tables are detected by the `PdfPage::tables_with_config()` function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTableConfig {
+    minimum_rows: usize,
+    minimum_columns: usize,
+    minimum_column_gap: Option<PdfPoints>,
+    ruling_tolerance: PdfPoints,
+    detect_unruled_tables: bool,
+}
+
+impl PdfTableConfig {
+    /// Creates a new [PdfTableConfig] with default settings: tables must have at least
+    /// two rows and two columns, and both ruled and unruled tables are detected.
+    #[inline]
+    pub fn new() -> Self {
+        PdfTableConfig {
+            minimum_rows: 2,
+            minimum_columns: 2,
+            minimum_column_gap: None,
+            ruling_tolerance: PdfPoints::new(2.0),
+            detect_unruled_tables: true,
+        }
+    }
+
+    /// Sets the minimum number of rows a detected table must have. The default is 2.
+    #[inline]
+    pub fn set_minimum_rows(mut self, rows: usize) -> Self {
+        self.minimum_rows = rows.max(1);
+
+        self
+    }
+
+    /// Sets the minimum number of columns a detected table must have. The default is 2.
+    #[inline]
+    pub fn set_minimum_columns(mut self, columns: usize) -> Self {
+        self.minimum_columns = columns.max(1);
+
+        self
+    }
+
+    /// Sets the minimum horizontal gap between two words on the same line for them to be
+    /// considered part of separate columns in an unruled table. By default, the gap must be
+    /// at least the height of the tallest word on the line.
+    #[inline]
+    pub fn set_minimum_column_gap(mut self, gap: PdfPoints) -> Self {
+        self.minimum_column_gap = Some(gap);
+
+        self
+    }
+
+    /// Sets the maximum thickness of a path object for it to be considered a ruling line,
+    /// and the maximum distance between two ruling lines for them to be considered to
+    /// meet. The default is 2 points.
+    #[inline]
+    pub fn set_ruling_tolerance(mut self, tolerance: PdfPoints) -> Self {
+        self.ruling_tolerance = tolerance;
+
+        self
+    }
+
+    /// Controls whether tables whose columns are separated only by whitespace should be
+    /// detected. The default is `true`.
+    #[inline]
+    pub fn detect_unruled_tables(mut self, detect: bool) -> Self {
+        self.detect_unruled_tables = detect;
+
+        self
+    }
+}
+
+impl Default for PdfTableConfig {
+    #[inline]
+    fn default() -> Self {
+        PdfTableConfig::new()
+    }
+}
+
+/// A table of text detected in the content of a `PdfPage`, as returned by the
+/// `PdfPage::tables()` function.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PdfTable {
+    cells: Vec<Vec<String>>,
+    bounds: PdfRect,
+    is_ruled: bool,
+}
+
+impl PdfTable {
+    /// Returns the text of the cells in this [PdfTable], row by row from top to bottom,
+    /// with the cells in each row ordered from left to right. Every row has the same
+    /// number of cells; cells without text are empty strings.
+    #[inline]
+    pub fn cells(&self) -> &[Vec<String>] {
+        self.cells.as_slice()
+    }
+
+    /// Returns the text of the cell at the given zero-based row and column indices in this
+    /// [PdfTable], or `None` if either index is out of range.
+    #[inline]
+    pub fn cell(&self, row: usize, column: usize) -> Option<&str> {
+        self.cells
+            .get(row)
+            .and_then(|cells| cells.get(column))
+            .map(|text| text.as_str())
+    }
+
+    /// Returns the number of rows in this [PdfTable].
+    #[inline]
+    pub fn row_count(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns the number of columns in this [PdfTable].
+    #[inline]
+    pub fn column_count(&self) -> usize {
+        self.cells.first().map(|row| row.len()).unwrap_or(0)
+    }
+
+    /// Returns the bounding box of this [PdfTable] on its page.
+    #[inline]
+    pub fn bounds(&self) -> PdfRect {
+        self.bounds
+    }
+
+    /// Returns `true` if this [PdfTable] was detected from the ruling lines separating its
+    /// cells, or `false` if it was detected from the whitespace separating its columns.
+    #[inline]
+    pub fn is_ruled(&self) -> bool {
+        self.is_ruled
+    }
+}
+
+/// A single word of text on a page.
+#[derive(Debug, Clone)]
+pub(crate) struct PdfTableWord {
+    pub(crate) text: String,
+    pub(crate) bounds: PdfRect,
+}
+
+impl PdfTableWord {
+    #[inline]
+    fn center(&self) -> (f32, f32) {
+        (
+            (self.bounds.left.value + self.bounds.right.value) / 2.0,
+            (self.bounds.bottom.value + self.bounds.top.value) / 2.0,
+        )
+    }
+}
+
+/// A horizontal or vertical line drawn on a page, which may separate the cells of a table.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) struct PdfTableRuling {
+    pub(crate) is_horizontal: bool,
+
+    // The y coordinate of a horizontal line, or the x coordinate of a vertical line.
+    pub(crate) position: f32,
+
+    // The extent of the line along its length.
+    pub(crate) start: f32,
+    pub(crate) end: f32,
+}
+
+impl PdfTableRuling {
+    /// Returns `true` if this ruling crosses or touches the given ruling, allowing
+    /// for the given tolerance.
+    #[inline]
+    fn meets(&self, other: &PdfTableRuling, tolerance: f32) -> bool {
+        self.is_horizontal != other.is_horizontal
+            && other.position >= self.start - tolerance
+            && other.position <= self.end + tolerance
+            && self.position >= other.start - tolerance
+            && self.position <= other.end + tolerance
+    }
+}
+
+/// Detects the tables in the given page.
+pub(crate) fn tables_in_page(page: &PdfPage, config: &PdfTableConfig) -> Vec<PdfTable> {
+    let words = match page.text() {
+        Ok(text) => words_in_chars(
+            text.chars()
+                .iter()
+                .map(|char| (char.unicode_char(), char.loose_bounds().ok())),
+        ),
+        Err(_) => return Vec::new(),
+    };
+
+    detect_tables(words, &rulings_in_page(page, config), config)
+}
+
+/// Groups the given characters, in text page order, into words. Words are separated by
+/// whitespace, by characters without bounds, and by horizontal gaps between characters
+/// on the same line.
+pub(crate) fn words_in_chars(
+    chars: impl Iterator<Item = (Option<char>, Option<PdfRect>)>,
+) -> Vec<PdfTableWord> {
+    let mut words = Vec::new();
+
+    let mut current: Option<PdfTableWord> = None;
+
+    for (char, bounds) in chars {
+        let (char, bounds) = match (char, bounds) {
+            (Some(char), Some(bounds)) if !char.is_whitespace() && bounds.width().value > 0.0 => {
+                (char, bounds)
+            }
+            _ => {
+                words.extend(current.take());
+
+                continue;
+            }
+        };
+
+        if let Some(word) = current.as_mut() {
+            let height = word.bounds.height().value.max(bounds.height().value);
+
+            let middle = (bounds.bottom.value + bounds.top.value) / 2.0;
+
+            let is_on_same_line =
+                middle >= word.bounds.bottom.value && middle <= word.bounds.top.value;
+
+            let gap = bounds.left.value - word.bounds.right.value;
+
+            if is_on_same_line && gap <= height * 0.25 && gap >= -height {
+                word.text.push(char);
+                word.bounds = word.bounds.union(&bounds);
+
+                continue;
+            }
+
+            words.extend(current.take());
+        }
+
+        current = Some(PdfTableWord {
+            text: char.to_string(),
+            bounds,
+        });
+    }
+
+    words.extend(current);
+
+    words
+}
+
+/// Returns the horizontal and vertical lines drawn by the top-level path objects in the
+/// given page. Thin path objects are treated as single lines; rectangles are treated as
+/// four lines along their edges.
+fn rulings_in_page(page: &PdfPage, config: &PdfTableConfig) -> Vec<PdfTableRuling> {
+    let tolerance = config.ruling_tolerance.value;
+
+    let mut rulings = Vec::new();
+
+    for object in page.objects().iter() {
+        let path = match object.as_path_object() {
+            Some(path) => path,
+            None => continue,
+        };
+
+        let bounds = match path.bounds() {
+            Ok(bounds) => bounds,
+            Err(_) => continue,
+        };
+
+        let width = bounds.width().value;
+
+        let height = bounds.height().value;
+
+        let horizontal = |position: f32| PdfTableRuling {
+            is_horizontal: true,
+            position,
+            start: bounds.left.value,
+            end: bounds.right.value,
+        };
+
+        let vertical = |position: f32| PdfTableRuling {
+            is_horizontal: false,
+            position,
+            start: bounds.bottom.value,
+            end: bounds.top.value,
+        };
+
+        if height <= tolerance && width > tolerance {
+            rulings.push(horizontal((bounds.bottom.value + bounds.top.value) / 2.0));
+        } else if width <= tolerance && height > tolerance {
+            rulings.push(vertical((bounds.left.value + bounds.right.value) / 2.0));
+        } else if width > tolerance && height > tolerance && path.segments().len() <= 6 {
+            // A closed rectangle is drawn with at most six segments.
+
+            rulings.push(horizontal(bounds.bottom.value));
+            rulings.push(horizontal(bounds.top.value));
+            rulings.push(vertical(bounds.left.value));
+            rulings.push(vertical(bounds.right.value));
+        }
+    }
+
+    rulings
+}
+
+/// Detects tables among the given words, using the given rulings to detect ruled tables.
+/// Words that are not part of a ruled table are considered for unruled tables.
+/// Tables are returned from the top of the page to the bottom.
+pub(crate) fn detect_tables(
+    words: Vec<PdfTableWord>,
+    rulings: &[PdfTableRuling],
+    config: &PdfTableConfig,
+) -> Vec<PdfTable> {
+    let mut words = lines_in_words(words)
+        .into_iter()
+        .flatten()
+        .map(Some)
+        .collect::<Vec<_>>();
+
+    let mut tables = Vec::new();
+
+    for (xs, ys) in grids_in_rulings(rulings, config.ruling_tolerance.value) {
+        let rows = ys.len() - 1;
+
+        let columns = xs.len() - 1;
+
+        if rows < config.minimum_rows || columns < config.minimum_columns {
+            continue;
+        }
+
+        let mut cells = vec![vec![String::new(); columns]; rows];
+
+        let mut is_empty = true;
+
+        for slot in words.iter_mut() {
+            let (x, y) = match slot.as_ref() {
+                Some(word) => word.center(),
+                None => continue,
+            };
+
+            // Columns are ordered from left to right; rows from top to bottom.
+
+            let column = xs.windows(2).position(|xs| x >= xs[0] && x < xs[1]);
+
+            let row = ys.windows(2).position(|ys| y <= ys[0] && y > ys[1]);
+
+            if let (Some(row), Some(column), Some(word)) = (row, column, slot.take()) {
+                push_word(&mut cells[row][column], &word.text);
+
+                is_empty = false;
+            }
+        }
+
+        if !is_empty {
+            tables.push(PdfTable {
+                cells,
+                bounds: PdfRect::new_from_values(ys[ys.len() - 1], xs[0], ys[0], xs[xs.len() - 1]),
+                is_ruled: true,
+            });
+        }
+    }
+
+    if config.detect_unruled_tables {
+        tables.extend(unruled_tables_in_lines(
+            lines_in_words(words.into_iter().flatten().collect()),
+            config,
+        ));
+    }
+
+    tables.sort_by(|a, b| {
+        b.bounds
+            .top
+            .value
+            .partial_cmp(&a.bounds.top.value)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    tables
+}
+
+/// Groups the given rulings into grids of connected lines, returning the x coordinates of
+/// the vertical lines in each grid from left to right and the y coordinates of its
+/// horizontal lines from top to bottom. Only grids with at least two vertical and two
+/// horizontal lines are returned.
+pub(crate) fn grids_in_rulings(
+    rulings: &[PdfTableRuling],
+    tolerance: f32,
+) -> Vec<(Vec<f32>, Vec<f32>)> {
+    // Assign each ruling to a group of connected rulings, merging groups as connections
+    // between them are found.
+
+    let mut groups = (0..rulings.len()).collect::<Vec<_>>();
+
+    fn root(groups: &mut [usize], mut index: usize) -> usize {
+        while groups[index] != index {
+            groups[index] = groups[groups[index]];
+            index = groups[index];
+        }
+
+        index
+    }
+
+    for (index, ruling) in rulings.iter().enumerate() {
+        for (other_index, other) in rulings.iter().enumerate().skip(index + 1) {
+            if ruling.meets(other, tolerance) {
+                let a = root(&mut groups, index);
+                let b = root(&mut groups, other_index);
+
+                groups[a] = b;
+            }
+        }
+    }
+
+    let mut grids: Vec<(usize, Vec<f32>, Vec<f32>)> = Vec::new();
+
+    for (index, ruling) in rulings.iter().enumerate() {
+        let group = root(&mut groups, index);
+
+        let position = match grids.iter().position(|(other, _, _)| *other == group) {
+            Some(position) => position,
+            None => {
+                grids.push((group, Vec::new(), Vec::new()));
+
+                grids.len() - 1
+            }
+        };
+
+        if ruling.is_horizontal {
+            grids[position].2.push(ruling.position);
+        } else {
+            grids[position].1.push(ruling.position);
+        }
+    }
+
+    grids
+        .into_iter()
+        .map(|(_, xs, ys)| {
+            let xs = merge_positions(xs, tolerance);
+
+            let mut ys = merge_positions(ys, tolerance);
+
+            ys.reverse();
+
+            (xs, ys)
+        })
+        .filter(|(xs, ys)| xs.len() >= 2 && ys.len() >= 2)
+        .collect()
+}
+
+/// Sorts the given positions in ascending order, merging positions that lie within the
+/// given tolerance of one another.
+fn merge_positions(mut positions: Vec<f32>, tolerance: f32) -> Vec<f32> {
+    positions.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+
+    let mut result: Vec<f32> = Vec::new();
+
+    for position in positions {
+        match result.last() {
+            Some(last) if position - last <= tolerance => {}
+            _ => result.push(position),
+        }
+    }
+
+    result
+}
+
+/// Groups the given words into lines, ordered from top to bottom, with the words in each
+/// line ordered from left to right. Words whose vertical midpoints fall within the vertical
+/// extent of the first word on a line are considered part of that line.
+fn lines_in_words(mut words: Vec<PdfTableWord>) -> Vec<Vec<PdfTableWord>> {
+    words.sort_by(|a, b| {
+        b.bounds
+            .top
+            .value
+            .partial_cmp(&a.bounds.top.value)
+            .unwrap_or(Ordering::Equal)
+    });
+
+    let mut lines: Vec<(PdfRect, Vec<PdfTableWord>)> = Vec::new();
+
+    for word in words {
+        let (_, middle) = word.center();
+
+        match lines.last_mut() {
+            Some((bounds, line)) if middle <= bounds.top.value && middle >= bounds.bottom.value => {
+                line.push(word);
+            }
+            _ => lines.push((word.bounds, vec![word])),
+        }
+    }
+
+    lines
+        .into_iter()
+        .map(|(_, mut line)| {
+            line.sort_by(|a, b| {
+                a.bounds
+                    .left
+                    .value
+                    .partial_cmp(&b.bounds.left.value)
+                    .unwrap_or(Ordering::Equal)
+            });
+
+            line
+        })
+        .collect()
+}
+
+/// A single line of text, split into cells at wide horizontal gaps between its words.
+struct PdfTableLine {
+    bounds: PdfRect,
+
+    // The horizontal extent and text of each cell, from left to right.
+    cells: Vec<(f32, f32, String)>,
+}
+
+/// Detects unruled tables among the given lines of words. Consecutive lines that are each
+/// split into at least the minimum number of cells, and whose cells line up in the same
+/// columns, form a table.
+fn unruled_tables_in_lines(
+    lines: Vec<Vec<PdfTableWord>>,
+    config: &PdfTableConfig,
+) -> Vec<PdfTable> {
+    let mut tables = Vec::new();
+
+    let mut block: Vec<PdfTableLine> = Vec::new();
+
+    let mut columns: Vec<(f32, f32)> = Vec::new();
+
+    for words in lines {
+        let line = split_line_into_cells(words, config);
+
+        let is_joined = if line.cells.len() < config.minimum_columns.max(2) {
+            false
+        } else if let Some(previous) = block.last() {
+            let gap = previous.bounds.bottom.value - line.bounds.top.value;
+
+            let is_adjacent = gap
+                <= previous
+                    .bounds
+                    .height()
+                    .value
+                    .max(line.bounds.height().value);
+
+            let joined_columns = merge_columns(&columns, &line);
+
+            if is_adjacent
+                && joined_columns.len() >= config.minimum_columns
+                && block
+                    .iter()
+                    .chain(std::iter::once(&line))
+                    .all(|line| has_one_cell_per_column(line, &joined_columns))
+            {
+                columns = joined_columns;
+
+                true
+            } else {
+                false
+            }
+        } else {
+            false
+        };
+
+        if !is_joined {
+            tables.extend(table_from_block(&block, &columns, config));
+
+            block.clear();
+            columns.clear();
+
+            if line.cells.len() >= config.minimum_columns.max(2) {
+                columns = merge_columns(&columns, &line);
+            } else {
+                continue;
+            }
+        }
+
+        block.push(line);
+    }
+
+    tables.extend(table_from_block(&block, &columns, config));
+
+    tables
+}
+
+/// Splits the given line of words, ordered from left to right, into cells at horizontal
+/// gaps of at least the minimum column gap.
+fn split_line_into_cells(words: Vec<PdfTableWord>, config: &PdfTableConfig) -> PdfTableLine {
+    let minimum_gap = config
+        .minimum_column_gap
+        .map(|gap| gap.value)
+        .unwrap_or_else(|| {
+            words
+                .iter()
+                .map(|word| word.bounds.height().value)
+                .fold(0.0, f32::max)
+        });
+
+    let mut bounds: Option<PdfRect> = None;
+
+    let mut cells: Vec<(f32, f32, String)> = Vec::new();
+
+    for word in words {
+        bounds = Some(match bounds {
+            Some(bounds) => bounds.union(&word.bounds),
+            None => word.bounds,
+        });
+
+        match cells.last_mut() {
+            Some((_, right, text)) if word.bounds.left.value - *right < minimum_gap => {
+                *right = right.max(word.bounds.right.value);
+
+                push_word(text, &word.text);
+            }
+            _ => cells.push((word.bounds.left.value, word.bounds.right.value, word.text)),
+        }
+    }
+
+    PdfTableLine {
+        bounds: bounds.unwrap_or(PdfRect::ZERO),
+        cells,
+    }
+}
+
+/// Returns the horizontal extents of the columns formed by the given columns together with
+/// the cells in the given line, merging extents that overlap.
+fn merge_columns(columns: &[(f32, f32)], line: &PdfTableLine) -> Vec<(f32, f32)> {
+    let mut extents = columns
+        .iter()
+        .copied()
+        .chain(line.cells.iter().map(|(left, right, _)| (*left, *right)))
+        .collect::<Vec<_>>();
+
+    extents.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+
+    let mut result: Vec<(f32, f32)> = Vec::new();
+
+    for (left, right) in extents {
+        match result.last_mut() {
+            Some((_, last_right)) if left <= *last_right => *last_right = last_right.max(right),
+            _ => result.push((left, right)),
+        }
+    }
+
+    result
+}
+
+/// Returns `true` if no two cells in the given line fall in the same column.
+fn has_one_cell_per_column(line: &PdfTableLine, columns: &[(f32, f32)]) -> bool {
+    let mut previous = None;
+
+    for (left, _, _) in line.cells.iter() {
+        let column = column_containing(columns, *left);
+
+        if column.is_none() || column == previous {
+            return false;
+        }
+
+        previous = column;
+    }
+
+    true
+}
+
+/// Returns the index of the column containing the given x coordinate.
+#[inline]
+fn column_containing(columns: &[(f32, f32)], x: f32) -> Option<usize> {
+    columns
+        .iter()
+        .position(|(left, right)| x >= *left && x <= *right)
+}
+
+/// Creates a table from the given block of lines and the columns they form, if the block
+/// is large enough to be considered a table.
+fn table_from_block(
+    block: &[PdfTableLine],
+    columns: &[(f32, f32)],
+    config: &PdfTableConfig,
+) -> Option<PdfTable> {
+    if block.len() < config.minimum_rows || columns.len() < config.minimum_columns {
+        return None;
+    }
+
+    let cells = block
+        .iter()
+        .map(|line| {
+            let mut row = vec![String::new(); columns.len()];
+
+            for (left, _, text) in line.cells.iter() {
+                if let Some(column) = column_containing(columns, *left) {
+                    row[column] = text.clone();
+                }
+            }
+
+            row
+        })
+        .collect();
+
+    let bounds = block
+        .iter()
+        .skip(1)
+        .fold(block[0].bounds, |bounds, line| bounds.union(&line.bounds));
+
+    Some(PdfTable {
+        cells,
+        bounds,
+        is_ruled: false,
+    })
+}
+
+/// Appends the given word to the given cell text, separated by a space.
+#[inline]
+fn push_word(text: &mut String, word: &str) {
+    if !text.is_empty() {
+        text.push(' ');
+    }
+
+    text.push_str(word);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::pdf::tables::*;
+
+    /// Returns words for the given lines of text, laid out in a fixed-pitch font with
+    /// characters 5 points wide and lines 10 points high, starting at the top of the page.
+    fn words(lines: &[&str]) -> Vec<PdfTableWord> {
+        let chars = lines.iter().enumerate().flat_map(|(row, line)| {
+            let top = 800.0 - row as f32 * 12.0;
+
+            line.chars()
+                .enumerate()
+                .map(move |(column, char)| {
+                    let left = column as f32 * 5.0;
+
+                    (
+                        Some(char),
+                        Some(PdfRect::new_from_values(top - 10.0, left, top, left + 5.0)),
+                    )
+                })
+                .chain(std::iter::once((Some('\n'), None)))
+        });
+
+        words_in_chars(chars)
+    }
+
+    #[test]
+    fn test_unruled_table() {
+        let words = words(&[
+            "Quarterly results",
+            "Region     Sales     Growth",
+            "North      1,200     4%",
+            "South        950",
+            "East       2,100     6%",
+            "West and central   3,250     2%",
+        ]);
+
+        let tables = detect_tables(words, &[], &PdfTableConfig::new());
+
+        assert_eq!(tables.len(), 1);
+
+        let table = &tables[0];
+
+        assert!(!table.is_ruled());
+        assert_eq!(table.row_count(), 4);
+        assert_eq!(table.column_count(), 3);
+        assert_eq!(
+            table.cells()[0],
+            vec![
+                "Region".to_string(),
+                "Sales".to_string(),
+                "Growth".to_string()
+            ]
+        );
+        assert_eq!(table.cell(2, 1), Some("950"));
+        assert_eq!(table.cell(2, 2), Some(""));
+    }
+
+    #[test]
+    fn test_ruled_table() {
+        // A two-by-two grid drawn with three horizontal and three vertical lines, with a
+        // cell holding two words on separate lines.
+
+        let horizontal = |position: f32| PdfTableRuling {
+            is_horizontal: true,
+            position,
+            start: 0.0,
+            end: 200.0,
+        };
+
+        let vertical = |position: f32| PdfTableRuling {
+            is_horizontal: false,
+            position,
+            start: 740.0,
+            end: 802.0,
+        };
+
+        let rulings = [
+            horizontal(802.0),
+            horizontal(789.0),
+            horizontal(740.5),
+            vertical(0.0),
+            vertical(100.0),
+            vertical(200.0),
+            // A separate underline elsewhere on the page, which is not part of the grid.
+            PdfTableRuling {
+                is_horizontal: true,
+                position: 500.0,
+                start: 0.0,
+                end: 100.0,
+            },
+        ];
+
+        let words = words(&[
+            "Name                Value",
+            "Alpha",
+            "Beta                7",
+        ]);
+
+        let tables = detect_tables(words, &rulings, &PdfTableConfig::new());
+
+        assert_eq!(tables.len(), 1);
+
+        let table = &tables[0];
+
+        assert!(table.is_ruled());
+        assert_eq!(
+            table.cells(),
+            &[
+                vec!["Name".to_string(), "Value".to_string()],
+                vec!["Alpha Beta".to_string(), "7".to_string()],
+            ]
+        );
+        assert_eq!(
+            table.bounds(),
+            PdfRect::new_from_values(740.5, 0.0, 802.0, 200.0)
+        );
+    }
+}
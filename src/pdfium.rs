@@ -2,8 +2,10 @@
 
 use crate::bindings::PdfiumLibraryBindings;
 use crate::error::{PdfiumError, PdfiumInternalError};
+use crate::pdf::document::merge::merge_documents;
 use crate::pdf::document::{PdfDocument, PdfDocumentVersion};
 use std::fmt::{Debug, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[cfg(all(not(target_arch = "wasm32"), not(feature = "static")))]
 use {
@@ -41,6 +43,10 @@ use crate::bindings::thread_safe::ThreadSafePdfiumBindings;
 #[cfg(doc)]
 struct Blob;
 
+/// The number of [Pdfium] instances alive in the process. Pdfium's library state is
+/// process-wide, so the library must not be closed while any instance is still using it.
+static PDFIUM_INSTANCES: AtomicUsize = AtomicUsize::new(0);
+
 /// A high-level idiomatic Rust wrapper around Pdfium, the C++ PDF library used by
 /// the Google Chromium project.
 pub struct Pdfium {
@@ -147,10 +153,17 @@ impl Pdfium {
     }
 
     /// Creates a new [Pdfium] instance from the given external Pdfium library bindings.
+    ///
+    /// Pdfium's library state is shared by the whole process, so the external library is
+    /// only closed once every [Pdfium] instance in the process has been dropped.
     #[inline]
     pub fn new(bindings: Box<dyn PdfiumLibraryBindings>) -> Self {
+        // Initializing an already initialized library has no effect.
+
         bindings.FPDF_InitLibrary();
 
+        PDFIUM_INSTANCES.fetch_add(1, Ordering::SeqCst);
+
         Self { bindings }
     }
 
@@ -202,6 +215,11 @@ impl Pdfium {
             self.bindings.FPDF_LoadMemDocument64(bytes, password),
             self.bindings(),
         )
+        .map(|mut document| {
+            document.set_source_byte_slice(bytes);
+
+            document
+        })
     }
 
     /// Attempts to open a [PdfDocument] from the given owned byte buffer.
@@ -266,7 +284,11 @@ impl Pdfium {
     ///
     /// Because Pdfium must know the total content length in advance prior to loading
     /// any portion of it, the given reader must implement the `Seek` trait as well as
-    /// the `Read` trait.
+    /// the `Read` trait. Any such reader can be used, including files, memory-mapped regions
+    /// wrapped in a `std::io::Cursor`, entries in uncompressed archives, and network-backed
+    /// readers that fetch byte ranges on demand. Readers that return fewer bytes than requested
+    /// from a single read are supported. If the reader returns an error while the document
+    /// is being opened, that error is returned as [PdfiumError::IoError].
     ///
     /// If the document is password protected, the given password will be used
     /// to unlock it.
@@ -290,13 +312,22 @@ impl Pdfium {
         reader: R,
         password: Option<&'a str>,
     ) -> Result<PdfDocument<'a>, PdfiumError> {
-        let mut reader = get_pdfium_file_accessor_from_reader(reader);
+        let mut reader =
+            get_pdfium_file_accessor_from_reader(reader).map_err(PdfiumError::IoError)?;
 
         Pdfium::pdfium_document_handle_to_result(
             self.bindings
                 .FPDF_LoadCustomDocument(reader.as_fpdf_file_access_mut_ptr(), password),
             self.bindings(),
         )
+        .map_err(|err| {
+            // If loading failed because the reader failed, return the reader's error.
+
+            match reader.take_error() {
+                Some(err) => PdfiumError::IoError(err),
+                None => err,
+            }
+        })
         .map(|mut document| {
             // Give the newly-created document ownership of the reader, so that Pdfium can continue
             // to read from it on an as-needed basis throughout the lifetime of the document.
@@ -385,6 +416,23 @@ impl Pdfium {
         })
     }
 
+    /// Creates a new [PdfDocument] containing copies of the pages of the given documents,
+    /// in the order given.
+    ///
+    /// The new document also receives copies of the viewer preferences of the first document,
+    /// of the bookmarks of every document, retargeted to the copied pages, and of every
+    /// attachment in each document's `PdfAttachments` collection. Attachments whose names
+    /// are already used by an attachment copied from an earlier document are renamed by
+    /// adding a numeric suffix, for instance from `data.csv` to `data (2).csv`.
+    ///
+    /// Page labels are carried across, so each copied page keeps the label it had in its
+    /// source document. If only some documents label their pages, pages from the others are
+    /// labelled with their page numbers in the new document.
+    #[inline]
+    pub fn merge(&self, documents: &[&PdfDocument]) -> Result<PdfDocument, PdfiumError> {
+        merge_documents(self.bindings(), documents)
+    }
+
     /// Returns a [PdfDocument] from the given `FPDF_DOCUMENT` handle, if possible.
     pub(crate) fn pdfium_document_handle_to_result(
         handle: crate::bindgen::FPDF_DOCUMENT,
@@ -425,10 +473,13 @@ impl Pdfium {
 }
 
 impl Drop for Pdfium {
-    /// Closes the external Pdfium library, releasing held memory.
+    /// Closes the external Pdfium library, releasing held memory, if no other [Pdfium]
+    /// instance in the process is still using it.
     #[inline]
     fn drop(&mut self) {
-        self.bindings.FPDF_DestroyLibrary();
+        if PDFIUM_INSTANCES.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.bindings.FPDF_DestroyLibrary();
+        }
     }
 }
 
@@ -153,6 +153,160 @@ pub(crate) mod dates {
 
         format!("D:{}{}", date_part, timezone_part)
     }
+
+    /// Converts a [DateTime] to an RFC 3339 timestamp in UTC, to the nearest second,
+    /// such as "2026-10-14T11:44:05Z".
+    #[inline]
+    pub(crate) fn date_time_to_rfc3339_string<T: TimeZone>(date: DateTime<T>) -> String {
+        date.with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// Parses a formatted PDF date string, as defined in The PDF Reference Manual, sixth edition,
+    /// section 3.8.3, on page 160, into a [DateTime]. All fields after the year are optional;
+    /// omitted fields take their default values as described in the PDF Reference.
+    ///
+    /// Returns `None` if the given string is not a valid PDF date string.
+    pub(crate) fn date_time_from_pdf_string(date: &str) -> Option<DateTime<Utc>> {
+        let date = date.trim();
+
+        let date = date.strip_prefix("D:").unwrap_or(date);
+
+        let digits = date
+            .chars()
+            .take_while(|c| c.is_ascii_digit())
+            .collect::<String>();
+
+        if digits.len() < 4 {
+            return None;
+        }
+
+        let field = |start: usize, len: usize, default: u32| -> Option<u32> {
+            match digits.get(start..start + len) {
+                Some(value) => value.parse::<u32>().ok(),
+                None => Some(default),
+            }
+        };
+
+        let year = digits.get(0..4)?.parse::<i32>().ok()?;
+        let month = field(4, 2, 1)?;
+        let day = field(6, 2, 1)?;
+        let hour = field(8, 2, 0)?;
+        let minute = field(10, 2, 0)?;
+        let second = field(12, 2, 0)?;
+
+        let naive = NaiveDate::from_ymd_opt(year, month, day)?.and_hms_opt(hour, minute, second)?;
+
+        // The timezone designator, if any, follows immediately after the date digits.
+
+        let timezone = date[digits.len()..].replace('\'', "");
+
+        let offset_seconds = match timezone.chars().next() {
+            Some(sign @ '+') | Some(sign @ '-') => {
+                let hours = timezone.get(1..3).and_then(|h| h.parse::<i32>().ok())?;
+
+                let minutes = timezone
+                    .get(3..5)
+                    .and_then(|m| m.parse::<i32>().ok())
+                    .unwrap_or(0);
+
+                let seconds = hours * 3600 + minutes * 60;
+
+                if sign == '-' {
+                    -seconds
+                } else {
+                    seconds
+                }
+            }
+            _ => 0, // Either 'Z' or no timezone information; both are treated as UTC.
+        };
+
+        FixedOffset::east_opt(offset_seconds)?
+            .from_local_datetime(&naive)
+            .single()
+            .map(|date| date.with_timezone(&Utc))
+    }
+}
+
+pub(crate) mod json {
+    /// Returns the given string as a quoted JSON string literal, escaping any characters
+    /// that cannot appear unescaped inside a JSON string.
+    pub(crate) fn json_string(value: &str) -> String {
+        let mut result = String::with_capacity(value.len() + 2);
+
+        result.push('"');
+
+        for c in value.chars() {
+            match c {
+                '"' => result.push_str("\\\""),
+                '\\' => result.push_str("\\\\"),
+                '\n' => result.push_str("\\n"),
+                '\r' => result.push_str("\\r"),
+                '\t' => result.push_str("\\t"),
+                c if (c as u32) < 0x20 => result.push_str(&format!("\\u{:04x}", c as u32)),
+                c => result.push(c),
+            }
+        }
+
+        result.push('"');
+
+        result
+    }
+}
+
+pub(crate) mod hash {
+    /// A minimal implementation of the 64-bit FNV-1a hash function. Unlike the hashers provided
+    /// by the standard library, the output of FNV-1a is fully specified, so hashes computed
+    /// with it are stable across Rust releases, platforms, and program runs.
+    pub(crate) struct Fnv1a64 {
+        state: u64,
+    }
+
+    impl Fnv1a64 {
+        const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+
+        const PRIME: u64 = 0x100000001b3;
+
+        #[inline]
+        pub(crate) fn new() -> Self {
+            Fnv1a64 {
+                state: Self::OFFSET_BASIS,
+            }
+        }
+
+        #[inline]
+        pub(crate) fn write(&mut self, bytes: &[u8]) {
+            for byte in bytes {
+                self.state ^= *byte as u64;
+                self.state = self.state.wrapping_mul(Self::PRIME);
+            }
+        }
+
+        #[inline]
+        pub(crate) fn finish(&self) -> u64 {
+            self.state
+        }
+    }
+
+    /// Computes the CRC-32 checksum of the given bytes, as used by the PNG and Zip file formats.
+    #[cfg(any(feature = "image", test))]
+    pub(crate) fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = 0xffffffffu32;
+
+        for byte in bytes {
+            crc ^= *byte as u32;
+
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xedb88320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+
+        !crc
+    }
 }
 
 pub(crate) mod mem {
@@ -237,6 +391,7 @@ pub(crate) mod utf16le {
 
 pub(crate) mod files {
     use crate::bindgen::{FPDF_FILEACCESS, FPDF_FILEWRITE};
+    use std::convert::TryFrom;
     use std::io::{Read, Seek, SeekFrom, Write};
     use std::ops::Deref;
     use std::os::raw::{c_int, c_uchar, c_ulong, c_void};
@@ -272,19 +427,26 @@ pub(crate) mod files {
     ///
     /// Because Pdfium must know the total content length in advance prior to loading
     /// any portion of it, the given reader must implement the `Seek` trait as well as
-    /// the `Read` trait.
+    /// the `Read` trait. An error is returned if the content length cannot be determined,
+    /// or is too large to be passed to Pdfium on this platform.
     #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
     // This function is never used when compiling to WASM.
     pub(crate) fn get_pdfium_file_accessor_from_reader<'a, R: Read + Seek + 'a>(
         mut reader: R,
-    ) -> Box<FpdfFileAccessExt<'a>> {
-        let content_length = reader.seek(SeekFrom::End(0)).unwrap_or(0) as c_ulong;
+    ) -> std::io::Result<Box<FpdfFileAccessExt<'a>>> {
+        let content_length = c_ulong::try_from(reader.seek(SeekFrom::End(0))?).map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                "content length exceeds the maximum supported by Pdfium",
+            )
+        })?;
 
         let mut result = Box::new(FpdfFileAccessExt {
             content_length,
             get_block: Some(read_block_from_callback),
             file_access_ptr: null_mut(), // We'll set this value in just a moment.
             reader: Box::new(reader),
+            error: None,
         });
 
         // Update the struct with a pointer to its own memory location. This pointer will
@@ -295,7 +457,7 @@ pub(crate) mod files {
 
         result.as_mut().file_access_ptr = file_access_ptr as *mut FpdfFileAccessExt;
 
-        result
+        Ok(result)
     }
 
     trait PdfiumDocumentReader: Read + Seek {
@@ -322,6 +484,7 @@ pub(crate) mod files {
         >,
         file_access_ptr: *mut FpdfFileAccessExt<'a>,
         reader: Box<dyn PdfiumDocumentReader + 'a>, // Type-erased equivalent of <R: Read + Seek>
+        error: Option<std::io::Error>, // The first error returned by the reader, if any
     }
 
     impl<'a> FpdfFileAccessExt<'a> {
@@ -334,6 +497,15 @@ pub(crate) mod files {
         pub(crate) fn as_fpdf_file_access_mut_ptr(&mut self) -> &mut FPDF_FILEACCESS {
             unsafe { &mut *(self as *mut FpdfFileAccessExt as *mut FPDF_FILEACCESS) }
         }
+
+        /// Returns the first error returned by the underlying Rust reader, if any. Pdfium
+        /// only learns that a read failed, not why, so the error is retained here instead.
+        #[cfg_attr(target_arch = "wasm32", allow(dead_code))]
+        // This function is never used when compiling to WASM.
+        #[inline]
+        pub(crate) fn take_error(&mut self) -> Option<std::io::Error> {
+            self.error.take()
+        }
     }
 
     // The callback function invoked by Pdfium.
@@ -344,30 +516,46 @@ pub(crate) mod files {
         size: c_ulong,
     ) -> c_int {
         unsafe {
-            let reader = (*file_access_ptr).reader.as_mut();
+            let file_access = &mut *file_access_ptr;
+
+            // Pdfium expects the whole block to be filled, but readers such as network streams
+            // and decompressors may return fewer bytes than requested from a single read,
+            // so keep reading until the block is full.
 
             #[allow(clippy::unnecessary_cast)]
             // c_ulong isn't guaranteed to be u64 on all platforms
-            let result = match reader.seek(SeekFrom::Start(position as u64)) {
-                Ok(_) => reader
-                    .read(slice::from_raw_parts_mut(buf, size as usize))
-                    .unwrap_or(0),
-                Err(_) => 0,
-            };
-
-            result as c_int
+            let result = file_access
+                .reader
+                .seek(SeekFrom::Start(position as u64))
+                .and_then(|_| {
+                    file_access
+                        .reader
+                        .read_exact(slice::from_raw_parts_mut(buf, size as usize))
+                });
+
+            match result {
+                Ok(()) => 1,
+                Err(err) => {
+                    if file_access.error.is_none() {
+                        file_access.error = Some(err);
+                    }
+
+                    0
+                }
+            }
         }
     }
 
     /// Returns a wrapped Pdfium `FPDF_FILEWRITE` struct that uses the given writer as an
     /// output source for Pdfium's file writing callback function.
-    pub(crate) fn get_pdfium_file_writer_from_writer<W: Write + 'static>(
-        writer: &mut W,
-    ) -> FpdfFileWriteExt {
+    pub(crate) fn get_pdfium_file_writer_from_writer<'a, W: Write + 'a>(
+        writer: &'a mut W,
+    ) -> FpdfFileWriteExt<'a> {
         FpdfFileWriteExt {
             version: 1,
             write_block: Some(write_block_from_callback),
             writer,
+            error: None,
         }
     }
 
@@ -396,6 +584,7 @@ pub(crate) mod files {
             ) -> c_int,
         >,
         writer: &'a mut dyn PdfiumDocumentWriter, // Type-erased equivalent of <W: Write>
+        error: Option<std::io::Error>,            // The first error returned by the writer, if any
     }
 
     impl<'a> FpdfFileWriteExt<'a> {
@@ -411,6 +600,14 @@ pub(crate) mod files {
         pub(crate) fn flush(&mut self) -> std::io::Result<()> {
             self.writer.flush()
         }
+
+        /// Returns the first error returned by the underlying Rust writer, if any. Pdfium
+        /// abandons a save operation as soon as a write fails, discarding the reason for
+        /// the failure, so the error is retained here instead.
+        #[inline]
+        pub(crate) fn take_error(&mut self) -> Option<std::io::Error> {
+            self.error.take()
+        }
     }
 
     // The callback function invoked by Pdfium.
@@ -420,12 +617,20 @@ pub(crate) mod files {
         size: c_ulong,
     ) -> c_int {
         let result = unsafe {
-            match (*file_write_ext_ptr)
+            let file_write_ext = &mut *file_write_ext_ptr;
+
+            match file_write_ext
                 .writer
                 .write_all(slice::from_raw_parts(buf as *const u8, size as usize))
             {
                 Ok(()) => 1,
-                Err(_) => 0,
+                Err(err) => {
+                    if file_write_ext.error.is_none() {
+                        file_write_ext.error = Some(err);
+                    }
+
+                    0
+                }
             }
         };
 
@@ -460,6 +665,9 @@ pub(crate) mod test {
 #[cfg(test)]
 mod tests {
     use crate::utils::dates::*;
+    use crate::utils::files::*;
+    use crate::utils::hash::*;
+    use crate::utils::json::*;
     use crate::utils::pixels::*;
     use chrono::prelude::*;
 
@@ -596,4 +804,151 @@ mod tests {
             "D:19981223195200-08'00'"
         )
     }
+
+    #[test]
+    fn test_date_time_from_pdf_date_string() {
+        assert_eq!(
+            date_time_from_pdf_string("D:19981223195200Z00'00'"),
+            Some(Utc.with_ymd_and_hms(1998, 12, 23, 19, 52, 00).unwrap())
+        );
+
+        assert_eq!(
+            date_time_from_pdf_string("D:19981223195200-08'00'"),
+            Some(Utc.with_ymd_and_hms(1998, 12, 24, 3, 52, 00).unwrap())
+        );
+
+        assert_eq!(
+            date_time_from_pdf_string("D:1998"),
+            Some(Utc.with_ymd_and_hms(1998, 1, 1, 0, 0, 0).unwrap())
+        );
+
+        assert_eq!(
+            date_time_from_pdf_string("D:20240101090000+05'30'"),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 3, 30, 0).unwrap())
+        );
+
+        assert_eq!(
+            date_time_from_pdf_string("  20240102  "),
+            Some(Utc.with_ymd_and_hms(2024, 1, 2, 0, 0, 0).unwrap())
+        );
+
+        assert_eq!(
+            date_time_from_pdf_string("D:202403151230"),
+            Some(Utc.with_ymd_and_hms(2024, 3, 15, 12, 30, 0).unwrap())
+        );
+
+        assert_eq!(date_time_from_pdf_string("D:19981399"), None);
+        assert_eq!(date_time_from_pdf_string("D:20240101250000"), None);
+        assert_eq!(date_time_from_pdf_string("D:199"), None);
+        assert_eq!(date_time_from_pdf_string("not a date"), None);
+    }
+
+    #[test]
+    fn test_date_time_to_rfc3339_string() {
+        assert_eq!(
+            date_time_to_rfc3339_string(Utc.with_ymd_and_hms(2000, 2, 29, 0, 0, 0).unwrap()),
+            "2000-02-29T00:00:00Z"
+        );
+
+        assert_eq!(
+            date_time_to_rfc3339_string(
+                FixedOffset::west_opt(8 * 3600)
+                    .unwrap()
+                    .with_ymd_and_hms(2026, 10, 14, 3, 44, 5)
+                    .unwrap()
+            ),
+            "2026-10-14T11:44:05Z"
+        );
+    }
+
+    // Tests of JSON serialization functions.
+
+    #[test]
+    fn test_json_string() {
+        assert_eq!(json_string("plain"), "\"plain\"");
+        assert_eq!(json_string("say \"hi\"\n\\"), "\"say \\\"hi\\\"\\n\\\\\"");
+        assert_eq!(json_string("\u{1}"), "\"\\u0001\"");
+        assert_eq!(json_string("a\tb\rc\u{1f}"), "\"a\\tb\\rc\\u001f\"");
+        assert_eq!(json_string("café / \u{7f}"), "\"café / \u{7f}\"");
+        assert_eq!(json_string(""), "\"\"");
+    }
+
+    // Tests of hashing functions.
+
+    #[test]
+    fn test_fnv1a64() {
+        let hasher = Fnv1a64::new();
+
+        assert_eq!(hasher.finish(), 0xcbf29ce484222325);
+
+        let mut hasher = Fnv1a64::new();
+
+        hasher.write(b"a");
+
+        assert_eq!(hasher.finish(), 0xaf63dc4c8601ec8c);
+
+        let mut hasher = Fnv1a64::new();
+
+        hasher.write(b"foo");
+        hasher.write(b"bar");
+
+        assert_eq!(hasher.finish(), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    }
+
+    // Tests of file access functions.
+
+    /// A reader that returns at most three bytes from each call to read().
+    struct ShortReader(std::io::Cursor<Vec<u8>>);
+
+    impl std::io::Read for ShortReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = buf.len().min(3);
+
+            self.0.read(&mut buf[..len])
+        }
+    }
+
+    impl std::io::Seek for ShortReader {
+        fn seek(&mut self, pos: std::io::SeekFrom) -> std::io::Result<u64> {
+            self.0.seek(pos)
+        }
+    }
+
+    #[test]
+    fn test_file_accessor_fills_block_from_short_reads() {
+        let mut accessor = get_pdfium_file_accessor_from_reader(ShortReader(std::io::Cursor::new(
+            b"0123456789".to_vec(),
+        )))
+        .unwrap();
+
+        assert_eq!(accessor.as_fpdf_file_access_mut_ptr().m_FileLen, 10);
+
+        let mut buf = [0u8; 8];
+
+        let ptr: *mut FpdfFileAccessExt = accessor.as_mut();
+
+        assert_eq!(
+            read_block_from_callback(ptr, 1, buf.as_mut_ptr(), buf.len() as _),
+            1
+        );
+        assert_eq!(&buf, b"12345678");
+        assert!(accessor.take_error().is_none());
+
+        // Reading beyond the end of the content fails, and the error is retained.
+
+        assert_eq!(
+            read_block_from_callback(ptr, 5, buf.as_mut_ptr(), buf.len() as _),
+            0
+        );
+        assert_eq!(
+            accessor.take_error().map(|err| err.kind()),
+            Some(std::io::ErrorKind::UnexpectedEof)
+        );
+    }
 }